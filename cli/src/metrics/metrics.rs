@@ -183,7 +183,16 @@ impl Metrics {
 
     async fn sample_metrics(self: &Arc<Self>, rpc: Arc<dyn RpcApi>) -> Result<()> {
         if let Ok(metrics) = rpc.get_metrics(true, true).await {
-            let GetMetricsResponse { server_time: _, consensus_metrics, process_metrics } = metrics;
+            let GetMetricsResponse {
+                server_time: _,
+                consensus_metrics,
+                process_metrics,
+                lifetime_metrics: _,
+                method_metrics: _,
+                p2p_message_metrics: _,
+                extended_consensus_metrics: _,
+                listener_metrics: _,
+            } = metrics;
 
             let mut data = self.data.lock().unwrap();
             let data = data.as_mut().unwrap();
@@ -0,0 +1,37 @@
+use crate::imports::*;
+
+#[derive(Default, Handler)]
+#[help("Show the balance of the selected account, or of all active accounts with 'balance all'")]
+pub struct Balance;
+
+impl Balance {
+    async fn main(self: Arc<Self>, ctx: &Arc<dyn Context>, argv: Vec<String>, _cmd: &str) -> Result<()> {
+        let ctx = ctx.clone().downcast_arc::<KaspaCli>()?;
+
+        let accounts = match argv.first().map(String::as_str) {
+            None => vec![ctx.wallet().account()?],
+            Some("all") => ctx.wallet().active_accounts().inner().values().cloned().collect::<Vec<_>>(),
+            Some(v) => {
+                tprintln!(ctx, "unknown command: '{v}'");
+                tprintln!(ctx, "usage: balance [all]");
+                return Ok(());
+            }
+        };
+
+        tprintln!(ctx);
+        for account in accounts {
+            tprintln!(ctx, "• {}: {}", style(account.name_with_id()).blue(), account.balance_as_strings(None)?);
+        }
+        tprintln!(ctx);
+
+        if !ctx.wallet().is_connected() {
+            tprintln!(ctx, "{}", style("Wallet is not connected to the network").magenta());
+            tprintln!(ctx);
+        } else if !ctx.wallet().is_synced() {
+            tprintln!(ctx, "{}", style("Kaspa node is currently syncing").magenta());
+            tprintln!(ctx);
+        }
+
+        Ok(())
+    }
+}
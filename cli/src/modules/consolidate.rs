@@ -0,0 +1,82 @@
+use crate::imports::*;
+use kaspa_wallet_core::runtime::{ConsolidationSettings, Consolidator};
+
+#[derive(Default, Handler)]
+#[help("Merges the dust UTXOs of the account into its change address")]
+pub struct Consolidate {
+    consolidator: Mutex<Option<Consolidator>>,
+}
+
+impl Consolidate {
+    async fn main(self: Arc<Self>, ctx: &Arc<dyn Context>, mut argv: Vec<String>, _cmd: &str) -> Result<()> {
+        let ctx = ctx.clone().downcast_arc::<KaspaCli>()?;
+
+        if argv.is_empty() {
+            return self.display_help(ctx, argv).await;
+        }
+
+        let action = argv.remove(0);
+        match action.as_str() {
+            "now" => {
+                let account = ctx.wallet().account()?;
+                let (wallet_secret, payment_secret) = ctx.ask_wallet_secret(Some(&account)).await?;
+                let abortable = Abortable::default();
+                let settings = ConsolidationSettings::default();
+                match account.consolidate(&settings, wallet_secret, payment_secret, &abortable, None).await? {
+                    Some((summary, _ids)) => tprintln!(ctx, "Consolidation: {summary}"),
+                    None => tprintln!(ctx, "Nothing to consolidate"),
+                }
+            }
+            "start" => {
+                if self.consolidator().is_some_and(|consolidator| consolidator.is_running()) {
+                    tprintln!(ctx, "Consolidation is already running");
+                    return Ok(());
+                }
+
+                let account = ctx.wallet().account()?;
+                let (wallet_secret, payment_secret) = ctx.ask_wallet_secret(Some(&account)).await?;
+                let consolidator = Consolidator::new(account.clone(), ConsolidationSettings::default());
+                consolidator.start(wallet_secret, payment_secret).await?;
+                self.consolidator.lock().unwrap().replace(consolidator);
+                tprintln!(ctx, "Consolidation of account {} started", account.name_or_id());
+            }
+            "stop" => {
+                if let Some(consolidator) = self.consolidator.lock().unwrap().take() {
+                    consolidator.stop().await?;
+                }
+                tprintln!(ctx, "Consolidation stopped");
+            }
+            "status" => {
+                if self.consolidator().is_some_and(|consolidator| consolidator.is_running()) {
+                    tprintln!(ctx, "Consolidation is running");
+                } else {
+                    tprintln!(ctx, "Consolidation is not running");
+                }
+            }
+            v => {
+                tprintln!(ctx, "unknown command: '{v}'\r\n");
+                return self.display_help(ctx, argv).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn consolidator(&self) -> Option<Consolidator> {
+        self.consolidator.lock().unwrap().clone()
+    }
+
+    async fn display_help(self: Arc<Self>, ctx: Arc<KaspaCli>, _argv: Vec<String>) -> Result<()> {
+        ctx.term().help(
+            &[
+                ("now", "Consolidate the dust UTXOs of the selected account once"),
+                ("start", "Periodically consolidate the dust UTXOs of the selected account"),
+                ("stop", "Stop the periodic consolidation"),
+                ("status", "Get the status of the periodic consolidation"),
+            ],
+            None,
+        )?;
+
+        Ok(())
+    }
+}
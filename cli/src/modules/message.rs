@@ -1,6 +1,5 @@
 use kaspa_addresses::Version;
-use kaspa_bip32::secp256k1::XOnlyPublicKey;
-use kaspa_wallet_core::message::{sign_message, verify_message, PersonalMessage};
+use kaspa_wallet_core::message::{sign_message, verify_message_with_address, PersonalMessage};
 
 use crate::imports::*;
 
@@ -103,13 +102,11 @@ impl Message {
             return Err(Error::custom("Address not supported for message signing. Only supports PubKey addresses"));
         }
 
-        let pubkey = XOnlyPublicKey::from_slice(&kaspa_address.payload[0..32]).unwrap();
-
         let mut signature_hex = [0u8; 64];
         faster_hex::hex_decode(signature.as_bytes(), &mut signature_hex)?;
 
         let pm = PersonalMessage(message);
-        let verify_result = verify_message(&pm, &signature_hex.to_vec(), &pubkey);
+        let verify_result = verify_message_with_address(&pm, &signature_hex.to_vec(), &kaspa_address);
 
         match verify_result {
             Ok(()) => {
@@ -2,9 +2,11 @@ use crate::imports::*;
 
 pub mod account;
 pub mod address;
+pub mod balance;
 pub mod broadcast;
 pub mod close;
 pub mod connect;
+pub mod consolidate;
 #[path = "create-unsigned-tx.rs"]
 pub mod create_unsigned_tx;
 pub mod details;
@@ -52,8 +54,38 @@ pub fn register_handlers(cli: &Arc<KaspaCli>) -> Result<()> {
         cli,
         cli.handlers(),
         [
-            account, address, close, connect, details, disconnect, estimate, exit, export, guide, help, history, rpc, list, miner,
-            message, monitor, mute, network, node, open, ping, reload, select, send, server, settings, sweep, track, transfer,
+            account,
+            address,
+            balance,
+            close,
+            connect,
+            consolidate,
+            details,
+            disconnect,
+            estimate,
+            exit,
+            export,
+            guide,
+            help,
+            history,
+            rpc,
+            list,
+            miner,
+            message,
+            monitor,
+            mute,
+            network,
+            node,
+            open,
+            ping,
+            reload,
+            select,
+            send,
+            server,
+            settings,
+            sweep,
+            track,
+            transfer,
             wallet,
             // halt,
             // theme,  start, stop
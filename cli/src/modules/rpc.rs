@@ -81,7 +81,7 @@ impl Rpc {
                 self.println(&ctx, result);
             }
             RpcApiOps::GetConnectedPeerInfo => {
-                let result = rpc.get_connected_peer_info_call(GetConnectedPeerInfoRequest {}).await?;
+                let result = rpc.get_connected_peer_info_call(GetConnectedPeerInfoRequest { include_traffic: false }).await?;
                 self.println(&ctx, result);
             }
             // RpcApiOps::AddPeer => {
@@ -129,10 +129,16 @@ impl Rpc {
                 let result = rpc.shutdown_call(ShutdownRequest {}).await?;
                 self.println(&ctx, result);
             }
-            // RpcApiOps::GetHeaders => {
-            //     let result = rpc.get_headers_call(GetHeadersRequest {  }).await?;
-            //     self.println(&ctx, result);
-            // }
+            RpcApiOps::GetHeaders => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Missing start hash argument"));
+                }
+                let start_hash = RpcHash::from_hex(argv.remove(0).as_str())?;
+                let limit = if argv.is_empty() { 100 } else { argv.remove(0).parse::<u64>()? };
+                let is_ascending = argv.is_empty() || argv.remove(0) != "desc";
+                let result = rpc.get_headers_call(GetHeadersRequest { start_hash, limit, is_ascending }).await?;
+                self.println(&ctx, result);
+            }
             RpcApiOps::GetUtxosByAddresses => {
                 if argv.is_empty() {
                     return Err(Error::custom("Please specify at least one address"));
@@ -224,6 +230,144 @@ impl Rpc {
                     }
                 }
             }
+            RpcApiOps::GetTransaction => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Please specify a transaction id"));
+                }
+                let transaction_id = RpcTransactionId::from_hex(argv.remove(0).as_str())?;
+                let include_proof = argv.first().map(|s| s == "proof").unwrap_or(false);
+                let result = rpc.get_transaction_call(GetTransactionRequest { transaction_id, include_proof }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetHealth => {
+                let result = rpc.get_health_call(GetHealthRequest {}).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetBlockValidationTrace => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Missing block hash argument"));
+                }
+                let hash = RpcHash::from_hex(argv.remove(0).as_str())?;
+                let result = rpc.get_block_validation_trace_call(GetBlockValidationTraceRequest { hash }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetBlockDagTimingInfo => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Missing block hash argument"));
+                }
+                let hash = RpcHash::from_hex(argv.remove(0).as_str())?;
+                let result = rpc.get_block_dag_timing_info_call(GetBlockDagTimingInfoRequest { hash }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetUtxoSetChunk => {
+                let cursor = if argv.is_empty() { None } else { Some(argv.remove(0)) };
+                let result = rpc.get_utxo_set_chunk_call(GetUtxoSetChunkRequest { cursor }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetStateDigests => {
+                let count = if argv.is_empty() { 10 } else { argv.remove(0).parse::<u32>()? };
+                let result = rpc.get_state_digests_call(GetStateDigestsRequest { count }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetDagBlocksAround => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Missing block hash argument"));
+                }
+                let hash = RpcHash::from_hex(argv.remove(0).as_str())?;
+                let radius = if argv.is_empty() { 2 } else { argv.remove(0).parse::<u32>()? };
+                let result = rpc.get_dag_blocks_around_call(GetDagBlocksAroundRequest { hash, radius }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetBlockRelations => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Missing block hash argument"));
+                }
+                let hash = RpcHash::from_hex(argv.remove(0).as_str())?;
+                let result = rpc.get_block_relations_call(GetBlockRelationsRequest { hash }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetTransactionAcceptanceProof => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Missing transaction id argument"));
+                }
+                let transaction_id = RpcTransactionId::from_hex(argv.remove(0).as_str())?;
+                let accepting_block_hash = if argv.is_empty() { None } else { Some(RpcHash::from_hex(argv.remove(0).as_str())?) };
+                let result = rpc
+                    .get_transaction_acceptance_proof_call(GetTransactionAcceptanceProofRequest {
+                        transaction_id,
+                        accepting_block_hash,
+                    })
+                    .await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::InvalidateBlock => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Missing block hash argument"));
+                }
+                let hash = RpcHash::from_hex(argv.remove(0).as_str())?;
+                let result = rpc.invalidate_block_call(InvalidateBlockRequest { hash }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::ReconsiderBlock => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Missing block hash argument"));
+                }
+                let hash = RpcHash::from_hex(argv.remove(0).as_str())?;
+                let result = rpc.reconsider_block_call(ReconsiderBlockRequest { hash }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetNetworkStats => {
+                let result = rpc.get_network_stats_call(GetNetworkStatsRequest {}).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetMempoolFeeHistogram => {
+                let bucket_bounds = argv.iter().map(|s| s.parse::<f64>()).collect::<std::result::Result<Vec<_>, _>>()?;
+                let result = rpc.get_mempool_fee_histogram_call(GetMempoolFeeHistogramRequest::new(bucket_bounds)).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetUtxoReturnAddress => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Please specify a transaction id"));
+                }
+                let transaction_id = RpcTransactionId::from_hex(argv.remove(0).as_str())?;
+                let result = rpc.get_utxo_return_address_call(GetUtxoReturnAddressRequest { transaction_id }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetSpendingTransaction => {
+                if argv.len() < 2 {
+                    return Err(Error::custom("Please specify a transaction id and an output index"));
+                }
+                let transaction_id = RpcTransactionId::from_hex(argv.remove(0).as_str())?;
+                let index = argv.remove(0).parse::<u32>()?;
+                let outpoint = RpcTransactionOutpoint::new(transaction_id, index);
+                let result = rpc.get_spending_transaction_call(GetSpendingTransactionRequest { outpoint }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::StartBlockProduction => {
+                if argv.len() < 2 {
+                    return Err(Error::custom("Please specify a pay address and a rate in blocks per second"));
+                }
+                let pay_address = Address::try_from(argv.remove(0).as_str())?;
+                let blocks_per_second = argv.remove(0).parse::<f64>()?;
+                let result = rpc.start_block_production_call(StartBlockProductionRequest { pay_address, blocks_per_second }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::StopBlockProduction => {
+                let result = rpc.stop_block_production_call(StopBlockProductionRequest {}).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::ResetLifetimeMetrics => {
+                let result = rpc.reset_lifetime_metrics_call(ResetLifetimeMetricsRequest {}).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetSystemInfo => {
+                let result = rpc.get_system_info_call(GetSystemInfoRequest {}).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetRelayPolicy => {
+                let result = rpc.get_relay_policy_call(GetRelayPolicyRequest {}).await?;
+                self.println(&ctx, result);
+            }
             _ => {
                 tprintln!(ctx, "rpc method exists but is not supported by the cli: '{op_str}'\r\n");
                 return Ok(());
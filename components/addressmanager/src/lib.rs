@@ -1,10 +1,17 @@
+mod natpmp;
 mod port_mapping_extender;
 mod stores;
 extern crate self as address_manager;
 
-use std::{collections::HashSet, iter, net::SocketAddr, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    iter,
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
 
-use address_manager::port_mapping_extender::Extender;
+use address_manager::port_mapping_extender::{Extender, PortMappingGateway};
 use igd_next::{
     self as igd, aio::tokio::Tokio, AddAnyPortError, AddPortError, Gateway, GetExternalIpError, GetGenericPortMappingEntryError,
     SearchError,
@@ -16,17 +23,27 @@ use itertools::{
 use kaspa_consensus_core::config::Config;
 use kaspa_core::{debug, info, task::tick::TickService, time::unix_now, warn};
 use kaspa_database::prelude::{StoreResultExtensions, DB};
-use kaspa_utils::networking::IpAddress;
+use kaspa_database::registry::DatabaseStorePrefixes;
+use kaspa_utils::networking::{IpAddress, PeerId, PrefixBucket};
 use local_ip_address::list_afinet_netifas;
+use natpmp::NatPmpError;
 use parking_lot::Mutex;
 use stores::banned_address_store::{BannedAddressesStore, BannedAddressesStoreReader, ConnectionBanTimestamp, DbBannedAddressesStore};
+use stores::node_identity_store::{DbNodeIdentityStore, NodeIdentityStore, NodeIdentityStoreReader};
 use thiserror::Error;
+use uuid::Uuid;
 
 pub use stores::NetAddress;
 
 const MAX_ADDRESSES: usize = 4096;
 const MAX_CONNECTION_FAILED_COUNT: u64 = 3;
 
+/// The maximum number of distinct external address candidates tracked from peer observations
+const MAX_EXTERNAL_ADDRESS_CANDIDATES: usize = 32;
+/// The minimal number of distinct network groups which must have observed an external address candidate
+/// before it is advertised. Requiring agreement across groups prevents a single peer from dictating our address
+const MIN_EXTERNAL_ADDRESS_AGREEMENT: usize = 2;
+
 const UPNP_DEADLINE_SEC: u64 = 2 * 60;
 const UPNP_EXTEND_PERIOD: u64 = UPNP_DEADLINE_SEC / 2;
 
@@ -56,14 +73,21 @@ pub struct AddressManager {
     address_store: address_store_with_cache::Store,
     config: Arc<Config>,
     local_net_addresses: Vec<NetAddress>,
+    /// External ip candidates reported by peers, along with the network groups of the reporting peers
+    external_address_observations: HashMap<IpAddress, HashSet<PrefixBucket>>,
+    node_id: PeerId,
+    system_id: PeerId,
 }
 
 impl AddressManager {
     pub fn new(config: Arc<Config>, db: Arc<DB>, tick_service: Arc<TickService>) -> (Arc<Mutex<Self>>, Option<Extender>) {
         let mut instance = Self {
             banned_address_store: DbBannedAddressesStore::new(db.clone(), MAX_ADDRESSES as u64),
-            address_store: address_store_with_cache::new(db),
+            address_store: address_store_with_cache::new(db.clone()),
             local_net_addresses: Vec::new(),
+            external_address_observations: HashMap::new(),
+            node_id: Self::load_or_create_id(db.clone(), DatabaseStorePrefixes::NodeIdentity, "node id"),
+            system_id: Self::load_or_create_id(db, DatabaseStorePrefixes::SystemIdentity, "system id"),
             config,
         };
 
@@ -72,28 +96,65 @@ impl AddressManager {
         (Arc::new(Mutex::new(instance)), extender)
     }
 
+    /// Loads the id persisted under `prefix`, or generates and persists a new one on first run
+    fn load_or_create_id(db: Arc<DB>, prefix: DatabaseStorePrefixes, name: &str) -> PeerId {
+        let mut store = DbNodeIdentityStore::new(db, prefix);
+        match store.get().unwrap_option() {
+            Some(id) => id,
+            None => {
+                let id = PeerId::new(Uuid::new_v4());
+                store.set(id).unwrap();
+                info!("Generated a new {} {}", name, id);
+                id
+            }
+        }
+    }
+
+    /// The persistent id identifying this node to its peers
+    pub fn node_id(&self) -> PeerId {
+        self.node_id
+    }
+
+    /// The persistent id identifying this node installation to RPC clients, e.g., for fleet inventory. Unrelated to
+    /// the node id, so that it cannot be matched with the identity advertised to peers
+    pub fn system_id(&self) -> PeerId {
+        self.system_id
+    }
+
     fn init_local_addresses(&mut self, tick_service: Arc<TickService>) -> Option<Extender> {
         self.local_net_addresses = self.local_addresses().collect();
 
         let extender = if self.local_net_addresses.is_empty() && !self.config.disable_upnp {
-            let (net_address, ExtendHelper { gateway, local_addr, external_port }) = match self.upnp() {
+            let (net_address, gateway, local_addr, external_port) = match self.upnp() {
+                Ok(Some((net_address, ExtendHelper { gateway, local_addr, external_port }))) => {
+                    let gateway: igd_next::aio::Gateway<Tokio> = igd_next::aio::Gateway {
+                        addr: gateway.addr,
+                        root_url: gateway.root_url,
+                        control_url: gateway.control_url,
+                        control_schema_url: gateway.control_schema_url,
+                        control_schema: gateway.control_schema,
+                        provider: Tokio,
+                    };
+                    (net_address, PortMappingGateway::Upnp(gateway), local_addr, external_port)
+                }
+                Ok(None) => return None,
                 Err(err) => {
                     warn!("[UPnP] Error adding port mapping: {err}");
-                    return None;
+                    // Many home routers which do not speak UPnP support NAT-PMP instead
+                    match self.nat_pmp() {
+                        Ok(Some((net_address, gateway, local_addr))) => {
+                            (net_address, PortMappingGateway::NatPmp(gateway), local_addr, net_address.port)
+                        }
+                        Ok(None) => return None,
+                        Err(err) => {
+                            warn!("[NAT-PMP] Error adding port mapping: {err}");
+                            return None;
+                        }
+                    }
                 }
-                Ok(None) => return None,
-                Ok(Some((net_address, extend_helper))) => (net_address, extend_helper),
             };
             self.local_net_addresses.push(net_address);
 
-            let gateway: igd_next::aio::Gateway<Tokio> = igd_next::aio::Gateway {
-                addr: gateway.addr,
-                root_url: gateway.root_url,
-                control_url: gateway.control_url,
-                control_schema_url: gateway.control_schema_url,
-                control_schema: gateway.control_schema,
-                provider: Tokio,
-            };
             Some(Extender::new(
                 tick_service,
                 Duration::from_secs(UPNP_EXTEND_PERIOD),
@@ -241,9 +302,38 @@ impl AddressManager {
         }
     }
 
+    /// Maps the P2P listen port via NAT-PMP. Returns the mapped external address, the gateway and the mapped local address
+    fn nat_pmp(&self) -> Result<Option<(NetAddress, Ipv4Addr, SocketAddr)>, NatPmpError> {
+        info!("[NAT-PMP] Attempting to register a port mapping... (to disable run the node with --disable-upnp)");
+        let gateway = natpmp::default_gateway().ok_or(NatPmpError::NoGateway)?;
+        let ip = IpAddress::new(natpmp::external_address(gateway)?.into());
+        if !ip.is_publicly_routable() {
+            info!("[NAT-PMP] Non-publicly routable external ip from gateway {} not added to store", ip);
+            return Ok(None);
+        }
+        info!("[NAT-PMP] Got external ip from gateway {gateway}: {ip}");
+
+        let normalized_p2p_listen_address = self.config.p2p_listen_address.normalize(self.config.default_p2p_port());
+        // NAT-PMP maps ports of the requesting host, so the local ip is only informative and the listen address is
+        // kept if it cannot be resolved
+        let local_addr = match normalized_p2p_listen_address.ip.is_unspecified() {
+            true => match local_ip_address::local_ip() {
+                Ok(local_ip) => SocketAddr::new(local_ip, normalized_p2p_listen_address.port),
+                Err(err) => {
+                    warn!("[NAT-PMP] Failed resolving the local ip: {err}");
+                    normalized_p2p_listen_address.into()
+                }
+            },
+            false => normalized_p2p_listen_address.into(),
+        };
+        let mapping = natpmp::map_tcp_port(gateway, local_addr.port(), local_addr.port(), UPNP_DEADLINE_SEC as u32)?;
+        info!("[NAT-PMP] Added port mapping to external port: {ip}:{}", mapping.external_port);
+        Ok(Some((NetAddress { ip, port: mapping.external_port }, gateway, local_addr)))
+    }
+
     pub fn best_local_address(&mut self) -> Option<NetAddress> {
         if self.local_net_addresses.is_empty() {
-            None
+            self.best_observed_external_address()
         } else {
             // TODO: Add logic for finding the best as a function of a peer remote address.
             // for now, returning the first one
@@ -251,6 +341,32 @@ impl AddressManager {
         }
     }
 
+    /// Records that the peer at `observer` observed our address as `observed_ip`
+    pub fn add_external_address_observation(&mut self, observed_ip: IpAddress, observer: IpAddress) {
+        if !observed_ip.is_publicly_routable() || !observer.is_publicly_routable() {
+            return;
+        }
+        if !self.external_address_observations.contains_key(&observed_ip)
+            && self.external_address_observations.len() >= MAX_EXTERNAL_ADDRESS_CANDIDATES
+        {
+            return;
+        }
+        let observers = self.external_address_observations.entry(observed_ip).or_default();
+        if observers.insert(observer.prefix_bucket()) && observers.len() == MIN_EXTERNAL_ADDRESS_AGREEMENT {
+            info!("External address {} confirmed by peer observations", observed_ip);
+        }
+    }
+
+    /// Returns the external ip observed by peers from the largest number of distinct network groups, if
+    /// sufficiently agreed upon, coupled with our P2P listen port
+    fn best_observed_external_address(&self) -> Option<NetAddress> {
+        self.external_address_observations
+            .iter()
+            .filter(|(_, observers)| observers.len() >= MIN_EXTERNAL_ADDRESS_AGREEMENT)
+            .max_by_key(|(_, observers)| observers.len())
+            .map(|(&ip, _)| NetAddress::new(ip, self.config.p2p_listen_address.normalize(self.config.default_p2p_port()).port))
+    }
+
     pub fn add_address(&mut self, address: NetAddress) {
         if address.ip.is_loopback() || address.ip.is_unspecified() {
             debug!("[Address manager] skipping local address {}", address.ip);
@@ -294,6 +410,38 @@ impl AddressManager {
         self.address_store.iterate_prioritized_random_addresses(exceptions)
     }
 
+    /// Iterates addresses for outbound connection attempts, excluding `active_outbound`.
+    ///
+    /// Unless disabled by config, the iteration is biased toward distinct network groups (/16 for ipv4, /64 for ipv6):
+    /// addresses belonging to a group already used by an active outbound peer or by a previously yielded address are
+    /// deferred until all other addresses were exhausted. Grouping by ASN is not supported since no ASN mapping is available.
+    ///
+    /// Addresses which cannot be dialed under the proxy configuration are excluded (see [`Self::is_dialable`]).
+    pub fn iterate_outbound_candidate_addresses(
+        &self,
+        active_outbound: HashSet<NetAddress>,
+    ) -> impl ExactSizeIterator<Item = NetAddress> {
+        let used_groups = active_outbound.iter().map(|addr| addr.prefix_bucket()).collect();
+        let mut exceptions = active_outbound;
+        exceptions.extend(self.address_store.iterate_addresses().filter(|addr| !self.is_dialable(addr)));
+        let iter = self.address_store.iterate_prioritized_random_addresses(exceptions);
+        if self.config.disable_netgroup_diversity {
+            Right(iter)
+        } else {
+            Left(address_store_with_cache::NetGroupDiverseIterator::new(iter, used_groups))
+        }
+    }
+
+    /// Returns whether an outbound connection to `address` is possible: onion addresses can only be reached through
+    /// a proxy, while clearnet addresses are not dialed at all if clearnet is disabled
+    pub fn is_dialable(&self, address: &NetAddress) -> bool {
+        if address.ip.is_onion() {
+            self.config.p2p_proxy.is_some()
+        } else {
+            !self.config.disable_clearnet
+        }
+    }
+
     pub fn ban(&mut self, ip: IpAddress) {
         self.banned_address_store.set(ip.into(), ConnectionBanTimestamp(unix_now())).unwrap();
         self.address_store.remove_by_ip(ip.into());
@@ -331,7 +479,7 @@ mod address_store_with_cache {
     // Since we need operations such as iterating all addresses, count, etc, we keep an easy to use copy of the database addresses.
     // We don't expect it to be expensive since we limit the number of saved addresses.
     use std::{
-        collections::{HashMap, HashSet},
+        collections::{HashMap, HashSet, VecDeque},
         net::IpAddr,
         sync::Arc,
     };
@@ -508,6 +656,42 @@ mod address_store_with_cache {
 
     impl ExactSizeIterator for RandomWeightedIterator {}
 
+    /// Wraps an address iterator and defers addresses whose prefix bucket was already used, so that
+    /// addresses from distinct network groups are yielded first. Deferred addresses are yielded
+    /// in their original order once the inner iterator is exhausted.
+    pub struct NetGroupDiverseIterator<I: ExactSizeIterator<Item = NetAddress>> {
+        inner: I,
+        used_groups: HashSet<PrefixBucket>,
+        deferred: VecDeque<NetAddress>,
+    }
+
+    impl<I: ExactSizeIterator<Item = NetAddress>> NetGroupDiverseIterator<I> {
+        pub fn new(inner: I, used_groups: HashSet<PrefixBucket>) -> Self {
+            Self { inner, used_groups, deferred: VecDeque::new() }
+        }
+    }
+
+    impl<I: ExactSizeIterator<Item = NetAddress>> Iterator for NetGroupDiverseIterator<I> {
+        type Item = NetAddress;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            for address in self.inner.by_ref() {
+                if self.used_groups.insert(address.prefix_bucket()) {
+                    return Some(address);
+                }
+                self.deferred.push_back(address);
+            }
+            self.deferred.pop_front()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let remaining = self.inner.len() + self.deferred.len();
+            (remaining, Some(remaining))
+        }
+    }
+
+    impl<I: ExactSizeIterator<Item = NetAddress>> ExactSizeIterator for NetGroupDiverseIterator<I> {}
+
     #[cfg(test)]
     mod tests {
         use std::str::FromStr;
@@ -535,6 +719,86 @@ mod address_store_with_cache {
             assert_eq!(iter.count(), 0);
         }
 
+        #[test]
+        fn test_external_address_observations() {
+            let db = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+            let mut config = Config::new(SIMNET_PARAMS);
+            config.disable_upnp = true;
+            let default_port = config.default_p2p_port();
+            let (am, _) = AddressManager::new(Arc::new(config), db.1, Arc::new(TickService::default()));
+            let mut am = am.lock();
+            // Make sure the test does not depend on the interfaces of the host running it
+            am.local_net_addresses.clear();
+
+            let external = IpAddress::from_str("1.2.3.4").unwrap();
+            let other = IpAddress::from_str("5.6.7.8").unwrap();
+            am.add_external_address_observation(external, IpAddress::from_str("20.0.0.1").unwrap());
+            // Observations from the same network group count once
+            am.add_external_address_observation(external, IpAddress::from_str("20.0.0.2").unwrap());
+            assert_eq!(am.best_local_address(), None);
+
+            // Non routable observations are ignored
+            am.add_external_address_observation(external, IpAddress::from_str("127.0.0.1").unwrap());
+            am.add_external_address_observation(IpAddress::from_str("192.168.0.1").unwrap(), IpAddress::from_str("30.0.0.1").unwrap());
+            assert_eq!(am.best_local_address(), None);
+
+            am.add_external_address_observation(external, IpAddress::from_str("21.0.0.1").unwrap());
+            am.add_external_address_observation(other, IpAddress::from_str("22.0.0.1").unwrap());
+            am.add_external_address_observation(other, IpAddress::from_str("23.0.0.1").unwrap());
+            am.add_external_address_observation(other, IpAddress::from_str("24.0.0.1").unwrap());
+            assert_eq!(am.best_local_address(), Some(NetAddress::new(other, default_port)));
+        }
+
+        #[test]
+        fn test_net_group_diverse_iterator() {
+            let address = |s: &str| NetAddress::new(IpAddress::from_str(s).unwrap(), 16111);
+            let addresses = vec![
+                address("10.1.0.1"),
+                address("10.1.0.2"),
+                address("10.2.0.1"),
+                address("10.3.0.1"),
+                address("10.2.0.2"),
+                address("10.4.0.1"),
+            ];
+            let used_groups = HashSet::from([address("10.4.1.1").prefix_bucket()]);
+            let iter = NetGroupDiverseIterator::new(addresses.into_iter(), used_groups);
+            assert_eq!(iter.len(), 6);
+            assert_eq!(
+                iter.collect_vec(),
+                vec![
+                    address("10.1.0.1"),
+                    address("10.2.0.1"),
+                    address("10.3.0.1"),
+                    address("10.1.0.2"),
+                    address("10.2.0.2"),
+                    address("10.4.0.1"),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_outbound_candidates_dialability() {
+            let clearnet = NetAddress::new(IpAddress::from_str("1.2.3.4").unwrap(), 16111);
+            let onion = NetAddress::new(IpAddress::from_str("fd87:d87e:eb43:744:208d:5408:63e3:3b4e").unwrap(), 16111);
+            let candidates = |p2p_proxy: Option<NetAddress>, disable_clearnet: bool| {
+                let db = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+                let mut config = Config::new(SIMNET_PARAMS);
+                config.disable_upnp = true;
+                config.p2p_proxy = p2p_proxy;
+                config.disable_clearnet = disable_clearnet;
+                let (am, _) = AddressManager::new(Arc::new(config), db.1, Arc::new(TickService::default()));
+                let mut am = am.lock();
+                am.add_address(clearnet);
+                am.add_address(onion);
+                am.iterate_outbound_candidate_addresses(HashSet::new()).collect::<HashSet<_>>()
+            };
+
+            let proxy = Some(NetAddress::from_str("127.0.0.1:9050").unwrap());
+            assert_eq!(candidates(None, false), HashSet::from([clearnet]));
+            assert_eq!(candidates(proxy, false), HashSet::from([clearnet, onion]));
+            assert_eq!(candidates(proxy, true), HashSet::from([onion]));
+        }
+
         #[test]
         fn test_network_distribution_weighting() {
             kaspa_core::log::try_init_logger("info");
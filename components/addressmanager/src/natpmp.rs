@@ -0,0 +1,126 @@
+//! A minimal NAT-PMP (RFC 6886) client, used as a port mapping fallback for gateways which do not support UPnP
+
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddrV4, UdpSocket},
+    time::Duration,
+};
+use thiserror::Error;
+
+/// The port NAT-PMP gateways listen to
+const NATPMP_PORT: u16 = 5351;
+const NATPMP_VERSION: u8 = 0;
+
+const OP_EXTERNAL_ADDRESS: u8 = 0;
+const OP_MAP_TCP: u8 = 2;
+/// Responses carry the request opcode plus 128
+const OP_RESPONSE_OFFSET: u8 = 128;
+
+/// The RFC specifies an initial timeout of 250ms which is doubled on every retry. We limit
+/// the number of attempts so that an unresponsive gateway does not stall the node startup
+const INITIAL_TIMEOUT_MILLIS: u64 = 250;
+const MAX_ATTEMPTS: u32 = 4;
+
+#[derive(Error, Debug)]
+pub enum NatPmpError {
+    #[error("default gateway could not be determined")]
+    NoGateway,
+
+    #[error("gateway did not respond")]
+    NoResponse,
+
+    #[error("malformed response from gateway")]
+    MalformedResponse,
+
+    #[error("gateway responded with result code {0}")]
+    ResultCode(u16),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// A port mapping acquired from a NAT-PMP gateway
+#[derive(Debug, Clone, Copy)]
+pub struct NatPmpMapping {
+    pub internal_port: u16,
+    pub external_port: u16,
+    pub lifetime_sec: u32,
+}
+
+/// Returns the default IPv4 gateway of this host, if it can be determined
+#[cfg(target_os = "linux")]
+pub fn default_gateway() -> Option<Ipv4Addr> {
+    // Each route line holds the interface, destination and gateway, where the addresses are little-endian hex
+    let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace().skip(1);
+        let (destination, gateway) = (fields.next()?, fields.next()?);
+        if destination != "00000000" {
+            return None;
+        }
+        let gateway = u32::from_str_radix(gateway, 16).ok()?;
+        Some(Ipv4Addr::from(gateway.swap_bytes())).filter(|ip| !ip.is_unspecified())
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn default_gateway() -> Option<Ipv4Addr> {
+    None
+}
+
+/// Queries the external address of `gateway`
+pub fn external_address(gateway: Ipv4Addr) -> Result<Ipv4Addr, NatPmpError> {
+    let response = request(gateway, &[NATPMP_VERSION, OP_EXTERNAL_ADDRESS], OP_EXTERNAL_ADDRESS, 12)?;
+    Ok(Ipv4Addr::new(response[8], response[9], response[10], response[11]))
+}
+
+/// Requests `gateway` to map `internal_port` for a duration of `lifetime_sec`. The gateway is free to
+/// assign an external port other than `suggested_external_port`. A zero lifetime removes the mapping
+pub fn map_tcp_port(
+    gateway: Ipv4Addr,
+    internal_port: u16,
+    suggested_external_port: u16,
+    lifetime_sec: u32,
+) -> Result<NatPmpMapping, NatPmpError> {
+    let mut message = [0u8; 12];
+    message[0] = NATPMP_VERSION;
+    message[1] = OP_MAP_TCP;
+    message[4..6].copy_from_slice(&internal_port.to_be_bytes());
+    message[6..8].copy_from_slice(&suggested_external_port.to_be_bytes());
+    message[8..12].copy_from_slice(&lifetime_sec.to_be_bytes());
+
+    let response = request(gateway, &message, OP_MAP_TCP, 16)?;
+    Ok(NatPmpMapping {
+        internal_port: u16::from_be_bytes([response[8], response[9]]),
+        external_port: u16::from_be_bytes([response[10], response[11]]),
+        lifetime_sec: u32::from_be_bytes([response[12], response[13], response[14], response[15]]),
+    })
+}
+
+fn request(gateway: Ipv4Addr, message: &[u8], opcode: u8, response_len: usize) -> Result<Vec<u8>, NatPmpError> {
+    let socket = UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.connect(SocketAddrV4::new(gateway, NATPMP_PORT))?;
+
+    let mut buf = [0u8; 16];
+    let mut timeout = Duration::from_millis(INITIAL_TIMEOUT_MILLIS);
+    for _ in 0..MAX_ATTEMPTS {
+        socket.send(message)?;
+        socket.set_read_timeout(Some(timeout))?;
+        match socket.recv(&mut buf) {
+            Ok(len) => {
+                // A valid response echoes the request opcode
+                if len < response_len || buf[0] != NATPMP_VERSION || buf[1] != opcode + OP_RESPONSE_OFFSET {
+                    return Err(NatPmpError::MalformedResponse);
+                }
+                let result_code = u16::from_be_bytes([buf[2], buf[3]]);
+                if result_code != 0 {
+                    return Err(NatPmpError::ResultCode(result_code));
+                }
+                return Ok(buf[..response_len].to_vec());
+            }
+            Err(err) if matches!(err.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => timeout *= 2,
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Err(NatPmpError::NoResponse)
+}
@@ -7,17 +7,27 @@ use kaspa_core::{
     },
     trace, warn,
 };
-use std::{net::SocketAddr, sync::Arc, time::Duration};
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
 
-use crate::UPNP_REGISTRATION_NAME;
+use crate::{natpmp, UPNP_REGISTRATION_NAME};
 
 pub const SERVICE_NAME: &str = "port-mapping-extender";
 
+/// The gateway holding the port mapping, along with the protocol used to register it
+pub enum PortMappingGateway {
+    Upnp(igd_next::aio::Gateway<Tokio>),
+    NatPmp(Ipv4Addr),
+}
+
 pub struct Extender {
     tick_service: Arc<TickService>,
     fetch_interval: Duration,
     deadline_sec: u64,
-    gateway: igd_next::aio::Gateway<Tokio>,
+    gateway: PortMappingGateway,
     external_port: u16,
     local_addr: SocketAddr,
 }
@@ -27,7 +37,7 @@ impl Extender {
         tick_service: Arc<TickService>,
         fetch_interval: Duration,
         deadline_sec: u64,
-        gateway: igd_next::aio::Gateway<Tokio>,
+        gateway: PortMappingGateway,
         external_port: u16,
         local_addr: SocketAddr,
     ) -> Self {
@@ -38,20 +48,31 @@ impl Extender {
 impl Extender {
     pub async fn worker(&self) -> Result<(), AddPortError> {
         while let TickReason::Wakeup = self.tick_service.tick(self.fetch_interval).await {
-            if let Err(e) = self
-                .gateway
-                .add_port(
-                    igd_next::PortMappingProtocol::TCP,
-                    self.external_port,
-                    self.local_addr,
-                    self.deadline_sec as u32,
-                    UPNP_REGISTRATION_NAME,
-                )
-                .await
-            {
-                warn!("[UPnP] Extend external ip mapping err: {e:?}");
-            } else {
-                debug!("[UPnP] Extend external ip mapping");
+            match &self.gateway {
+                PortMappingGateway::Upnp(gateway) => {
+                    if let Err(e) = gateway
+                        .add_port(
+                            igd_next::PortMappingProtocol::TCP,
+                            self.external_port,
+                            self.local_addr,
+                            self.deadline_sec as u32,
+                            UPNP_REGISTRATION_NAME,
+                        )
+                        .await
+                    {
+                        warn!("[UPnP] Extend external ip mapping err: {e:?}");
+                    } else {
+                        debug!("[UPnP] Extend external ip mapping");
+                    }
+                }
+                PortMappingGateway::NatPmp(gateway) => match self.nat_pmp_map(*gateway, self.deadline_sec as u32).await {
+                    // The gateway may reassign the external port, in which case the advertised address is stale
+                    Ok(mapping) if mapping.external_port != self.external_port => {
+                        warn!("[NAT-PMP] Gateway reassigned external port {} to {}", self.external_port, mapping.external_port)
+                    }
+                    Ok(_) => debug!("[NAT-PMP] Extend external ip mapping"),
+                    Err(e) => warn!("[NAT-PMP] Extend external ip mapping err: {e}"),
+                },
             }
         }
         // Let the system print final logs before exiting
@@ -61,6 +82,16 @@ impl Extender {
     }
 }
 
+impl Extender {
+    async fn nat_pmp_map(&self, gateway: Ipv4Addr, lifetime_sec: u32) -> Result<natpmp::NatPmpMapping, natpmp::NatPmpError> {
+        let (internal_port, external_port) = (self.local_addr.port(), self.external_port);
+        // The NAT-PMP client uses blocking sockets
+        tokio::task::spawn_blocking(move || natpmp::map_tcp_port(gateway, internal_port, external_port, lifetime_sec))
+            .await
+            .expect("NAT-PMP request task panicked")
+    }
+}
+
 impl AsyncService for Extender {
     fn ident(self: Arc<Self>) -> &'static str {
         SERVICE_NAME
@@ -81,10 +112,22 @@ impl AsyncService for Extender {
 
     fn stop(self: Arc<Self>) -> AsyncServiceFuture {
         Box::pin(async move {
-            if let Err(err) = self.gateway.remove_port(igd_next::PortMappingProtocol::TCP, self.external_port).await {
-                warn!("[UPnP] Remove port mapping err: {err:?}");
-            } else {
-                info!("[UPnP] Successfully removed port mapping, external port: {}", self.external_port);
+            match &self.gateway {
+                PortMappingGateway::Upnp(gateway) => {
+                    if let Err(err) = gateway.remove_port(igd_next::PortMappingProtocol::TCP, self.external_port).await {
+                        warn!("[UPnP] Remove port mapping err: {err:?}");
+                    } else {
+                        info!("[UPnP] Successfully removed port mapping, external port: {}", self.external_port);
+                    }
+                }
+                // A zero lifetime request deletes the mapping
+                PortMappingGateway::NatPmp(gateway) => {
+                    if let Err(err) = self.nat_pmp_map(*gateway, 0).await {
+                        warn!("[NAT-PMP] Remove port mapping err: {err}");
+                    } else {
+                        info!("[NAT-PMP] Successfully removed port mapping, external port: {}", self.external_port);
+                    }
+                }
             }
             trace!("{} stopped", SERVICE_NAME);
             Ok(())
@@ -0,0 +1,41 @@
+use kaspa_database::{
+    prelude::{CachedDbItem, DirectDbWriter, StoreResult, DB},
+    registry::DatabaseStorePrefixes,
+};
+use kaspa_utils::networking::PeerId;
+use std::sync::Arc;
+
+pub trait NodeIdentityStoreReader {
+    fn get(&self) -> StoreResult<PeerId>;
+}
+
+pub trait NodeIdentityStore: NodeIdentityStoreReader {
+    fn set(&mut self, node_id: PeerId) -> StoreResult<()>;
+}
+
+/// Persists an id generated on first run so that it remains stable across restarts: the node id advertised to peers
+/// during the handshake under [`DatabaseStorePrefixes::NodeIdentity`], or the system id reported to RPC clients under
+/// [`DatabaseStorePrefixes::SystemIdentity`]
+#[derive(Clone)]
+pub struct DbNodeIdentityStore {
+    db: Arc<DB>,
+    access: CachedDbItem<PeerId>,
+}
+
+impl DbNodeIdentityStore {
+    pub fn new(db: Arc<DB>, prefix: DatabaseStorePrefixes) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbItem::new(db, prefix.into()) }
+    }
+}
+
+impl NodeIdentityStoreReader for DbNodeIdentityStore {
+    fn get(&self) -> StoreResult<PeerId> {
+        self.access.read()
+    }
+}
+
+impl NodeIdentityStore for DbNodeIdentityStore {
+    fn set(&mut self, node_id: PeerId) -> StoreResult<()> {
+        self.access.write(DirectDbWriter::new(&self.db), &node_id)
+    }
+}
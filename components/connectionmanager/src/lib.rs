@@ -2,19 +2,26 @@ use std::{
     cmp::min,
     collections::{HashMap, HashSet},
     net::{IpAddr, SocketAddr, ToSocketAddrs},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     time::{Duration, SystemTime},
 };
 
 use duration_string::DurationString;
 use futures_util::future::join_all;
+use ipnet::IpNet;
 use itertools::Itertools;
 use kaspa_addressmanager::{AddressManager, NetAddress};
-use kaspa_core::{debug, info, warn};
+use kaspa_core::{debug, info, time::unix_now, warn};
 use kaspa_p2p_lib::{common::ProtocolError, ConnectionError, Peer};
-use kaspa_utils::triggers::SingleTrigger;
+use kaspa_utils::{
+    backoff::{AddressBackoff, BackoffPolicy},
+    triggers::SingleTrigger,
+};
 use parking_lot::Mutex as ParkingLotMutex;
-use rand::{seq::SliceRandom, thread_rng};
+use rand::{random, seq::SliceRandom, thread_rng};
 use tokio::{
     select,
     sync::{
@@ -24,14 +31,27 @@ use tokio::{
     time::{interval, MissedTickBehavior},
 };
 
+/// Retry delays of failing permanent connection requests: 30 seconds doubling up to 8 minutes, retrying forever
+const CONNECTION_REQUEST_BACKOFF: BackoffPolicy = BackoffPolicy::new(Duration::from_secs(30), 2, Duration::from_secs(480), 0.2, None);
+
+/// Retry delays of failing outbound candidate addresses, which are put on cooldown after a few consecutive failures
+const OUTBOUND_BACKOFF: BackoffPolicy = BackoffPolicy::new(Duration::from_secs(30), 2, Duration::from_secs(300), 0.2, Some(3));
+const OUTBOUND_COOLDOWN: Duration = Duration::from_secs(30 * 60);
+
 pub struct ConnectionManager {
     p2p_adaptor: Arc<kaspa_p2p_lib::Adaptor>,
-    outbound_target: usize,
-    inbound_limit: usize,
+    outbound_target: AtomicUsize,
+    inbound_limit: AtomicUsize,
+    /// Networks of trusted peers (e.g., mining pools) which may connect even when the inbound limit is reached
+    whitelist: Vec<IpNet>,
+    /// The number of inbound slots reserved for whitelisted peers on top of the inbound limit
+    reserved_inbound: usize,
     dns_seeders: &'static [&'static str],
     default_port: u16,
     address_manager: Arc<ParkingLotMutex<AddressManager>>,
     connection_requests: TokioMutex<HashMap<SocketAddr, ConnectionRequest>>,
+    /// Failures of outbound candidate addresses, deferring further attempts to them
+    outbound_backoff: ParkingLotMutex<AddressBackoff<NetAddress>>,
     force_next_iteration: UnboundedSender<()>,
     shutdown_signal: SingleTrigger,
 }
@@ -54,6 +74,8 @@ impl ConnectionManager {
         p2p_adaptor: Arc<kaspa_p2p_lib::Adaptor>,
         outbound_target: usize,
         inbound_limit: usize,
+        whitelist: Vec<IpNet>,
+        reserved_inbound: usize,
         dns_seeders: &'static [&'static str],
         default_port: u16,
         address_manager: Arc<ParkingLotMutex<AddressManager>>,
@@ -61,10 +83,13 @@ impl ConnectionManager {
         let (tx, rx) = unbounded_channel::<()>();
         let manager = Arc::new(Self {
             p2p_adaptor,
-            outbound_target,
-            inbound_limit,
+            outbound_target: AtomicUsize::new(outbound_target),
+            inbound_limit: AtomicUsize::new(inbound_limit),
+            whitelist,
+            reserved_inbound,
             address_manager,
             connection_requests: Default::default(),
+            outbound_backoff: ParkingLotMutex::new(AddressBackoff::new(OUTBOUND_BACKOFF, OUTBOUND_COOLDOWN)),
             force_next_iteration: tx,
             shutdown_signal: SingleTrigger::new(),
             dns_seeders,
@@ -103,6 +128,22 @@ impl ConnectionManager {
         self.handle_inbound_connections(&peer_by_address).await;
     }
 
+    /// Updates the outbound connections target and the inbound connections limit. The new values are applied
+    /// immediately, i.e., missing outbound connections are requested and excess inbound peers are disconnected
+    pub fn set_connection_targets(&self, outbound_target: usize, inbound_limit: usize) {
+        self.outbound_target.store(outbound_target, Ordering::Relaxed);
+        self.inbound_limit.store(inbound_limit, Ordering::Relaxed);
+        let _ = self.force_next_iteration.send(());
+    }
+
+    pub fn outbound_target(&self) -> usize {
+        self.outbound_target.load(Ordering::Relaxed)
+    }
+
+    pub fn inbound_limit(&self) -> usize {
+        self.inbound_limit.load(Ordering::Relaxed)
+    }
+
     pub async fn add_connection_request(&self, address: SocketAddr, is_permanent: bool) {
         // If the request already exists, it resets the attempts count and overrides the `is_permanent` setting.
         self.connection_requests.lock().await.insert(address, ConnectionRequest::new(is_permanent));
@@ -130,8 +171,7 @@ impl ConnectionManager {
                 if self.p2p_adaptor.connect_peer(address.to_string()).await.is_err() {
                     debug!("Failed connecting to peer request {}", address);
                     if request.is_permanent {
-                        const MAX_ACCOUNTABLE_ATTEMPTS: u32 = 4;
-                        let retry_duration = Duration::from_secs(30u64 * 2u64.pow(min(request.attempts, MAX_ACCOUNTABLE_ATTEMPTS)));
+                        let retry_duration = CONNECTION_REQUEST_BACKOFF.delay(request.attempts + 1, random());
                         debug!("Will retry peer request {} in {}", address, DurationString::from(retry_duration));
                         new_requests.insert(
                             address,
@@ -157,12 +197,15 @@ impl ConnectionManager {
     async fn handle_outbound_connections(self: &Arc<Self>, peer_by_address: &HashMap<SocketAddr, Peer>) {
         let active_outbound: HashSet<kaspa_addressmanager::NetAddress> =
             peer_by_address.values().filter(|peer| peer.is_outbound()).map(|peer| peer.net_address().into()).collect();
-        if active_outbound.len() >= self.outbound_target {
+        let outbound_target = self.outbound_target();
+        if active_outbound.len() >= outbound_target {
             return;
         }
 
-        let mut missing_connections = self.outbound_target - active_outbound.len();
-        let mut addr_iter = self.address_manager.lock().iterate_prioritized_random_addresses(active_outbound);
+        let mut missing_connections = outbound_target - active_outbound.len();
+        let now = unix_now();
+        self.outbound_backoff.lock().prune(now);
+        let mut addr_iter = self.address_manager.lock().iterate_outbound_candidate_addresses(active_outbound);
 
         let mut progressing = true;
         let mut connecting = true;
@@ -173,7 +216,8 @@ impl ConnectionManager {
             let mut addrs_to_connect = Vec::with_capacity(missing_connections);
             let mut jobs = Vec::with_capacity(missing_connections);
             for _ in 0..missing_connections {
-                let Some(net_addr) = addr_iter.next() else {
+                // Addresses which failed recently are skipped until their retry delay is over
+                let Some(net_addr) = addr_iter.by_ref().find(|addr| self.outbound_backoff.lock().can_attempt(addr, now)) else {
                     connecting = false;
                     break;
                 };
@@ -187,16 +231,16 @@ impl ConnectionManager {
                 // Log only if progress was made
                 info!(
                     "Connection manager: has {}/{} outgoing P2P connections, trying to obtain {} additional connections...",
-                    self.outbound_target - missing_connections,
-                    self.outbound_target,
+                    outbound_target - missing_connections,
+                    outbound_target,
                     jobs.len(),
                 );
                 progressing = false;
             } else {
                 debug!(
                     "Connection manager: outgoing: {}/{} , connecting: {}, iterator: {}",
-                    self.outbound_target - missing_connections,
-                    self.outbound_target,
+                    outbound_target - missing_connections,
+                    outbound_target,
                     jobs.len(),
                     addr_iter.len(),
                 );
@@ -205,6 +249,7 @@ impl ConnectionManager {
             for (res, net_addr) in (join_all(jobs).await).into_iter().zip(addrs_to_connect) {
                 match res {
                     Ok(_) => {
+                        self.outbound_backoff.lock().on_success(&net_addr);
                         self.address_manager.lock().mark_connection_success(net_addr);
                         missing_connections -= 1;
                         progressing = true;
@@ -215,6 +260,7 @@ impl ConnectionManager {
                     }
                     Err(err) => {
                         debug!("Failed connecting to {:?}, err: {}", net_addr, err);
+                        self.outbound_backoff.lock().on_failure(net_addr, unix_now(), random());
                         self.address_manager.lock().mark_connection_failure(net_addr);
                     }
                 }
@@ -231,21 +277,53 @@ impl ConnectionManager {
         }
     }
 
+    /// Disconnects inbound peers in excess of the inbound limit. Whitelisted peers first occupy the reserved slots and
+    /// only the remaining ones count toward the general limit, so excess peers are chosen among the non-whitelisted
+    /// peers first.
     async fn handle_inbound_connections(self: &Arc<Self>, peer_by_address: &HashMap<SocketAddr, Peer>) {
-        let active_inbound = peer_by_address.values().filter(|peer| !peer.is_outbound()).collect_vec();
-        let active_inbound_len = active_inbound.len();
-        if self.inbound_limit >= active_inbound_len {
+        let (whitelisted, general): (Vec<_>, Vec<_>) =
+            peer_by_address.values().filter(|peer| !peer.is_outbound()).partition(|peer| self.is_whitelisted(peer.net_address().ip()));
+        let occupied = general.len() + whitelisted.len().saturating_sub(self.reserved_inbound);
+        let inbound_limit = self.inbound_limit();
+        if inbound_limit >= occupied {
             return;
         }
 
-        let mut futures = Vec::with_capacity(active_inbound_len - self.inbound_limit);
-        for peer in active_inbound.choose_multiple(&mut thread_rng(), active_inbound_len - self.inbound_limit) {
+        let excess = occupied - inbound_limit;
+        let excess_general = min(excess, general.len());
+        let mut rng = thread_rng();
+        let mut to_disconnect = general.choose_multiple(&mut rng, excess_general).collect_vec();
+        to_disconnect.extend(whitelisted.choose_multiple(&mut rng, excess - excess_general));
+
+        let mut futures = Vec::with_capacity(excess);
+        for peer in to_disconnect {
             debug!("Disconnecting from {} because we're above the inbound limit", peer.net_address());
             futures.push(self.p2p_adaptor.terminate(peer.key()));
         }
         join_all(futures).await;
     }
 
+    /// Disconnects all outbound peers which were not explicitly requested and immediately dials fresh peers
+    /// in their place. Returns the number of disconnected peers
+    pub async fn rotate_outbound_peers(&self) -> usize {
+        let requests = self.connection_requests.lock().await;
+        let peers = self
+            .p2p_adaptor
+            .active_peers()
+            .into_iter()
+            .filter(|peer| peer.is_outbound() && !requests.contains_key(&peer.net_address()))
+            .collect_vec();
+        drop(requests);
+        join_all(peers.iter().map(|peer| self.p2p_adaptor.terminate(peer.key()))).await;
+        let _ = self.force_next_iteration.send(());
+        peers.len()
+    }
+
+    /// Returns whether the given IP belongs to a whitelisted network
+    pub fn is_whitelisted(&self, ip: IpAddr) -> bool {
+        self.whitelist.iter().any(|net| net.contains(&ip))
+    }
+
     fn dns_seed(self: &Arc<Self>, mut min_addresses_to_fetch: usize) {
         let shuffled_dns_seeders = self.dns_seeders.choose_multiple(&mut thread_rng(), self.dns_seeders.len());
         for &seeder in shuffled_dns_seeders {
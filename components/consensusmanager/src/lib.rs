@@ -1,8 +1,22 @@
 use itertools::Itertools;
 use kaspa_consensus_core::api::{ConsensusApi, DynConsensus};
-use kaspa_core::{core::Core, debug, service::Service};
+use kaspa_core::{
+    core::Core,
+    debug, error,
+    service::{Service, ServiceHealth},
+    signals::Shutdown,
+};
+use kaspa_utils::triggers::{Listener, SingleTrigger};
 use parking_lot::RwLock;
-use std::{collections::VecDeque, ops::Deref, sync::Arc, thread::JoinHandle};
+use std::{
+    collections::VecDeque,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+};
 
 mod session;
 
@@ -111,6 +125,9 @@ impl ManagerInner {
 pub struct ConsensusManager {
     factory: Arc<dyn ConsensusFactory>,
     inner: RwLock<ManagerInner>,
+    /// Triggered once the active consensus was initialized and its workers were started
+    ready: SingleTrigger,
+    stopped: AtomicBool,
 }
 
 impl ConsensusManager {
@@ -118,17 +135,27 @@ impl ConsensusManager {
 
     pub fn new(factory: Arc<dyn ConsensusFactory>) -> Self {
         let (consensus, ctl) = factory.new_active_consensus();
-        Self { factory, inner: RwLock::new(ManagerInner::new(consensus, ctl)) }
+        Self { factory, inner: RwLock::new(ManagerInner::new(consensus, ctl)), ready: Default::default(), stopped: Default::default() }
     }
 
     /// Creates a consensus manager with a fixed consensus. Will panic if staging API is used. To be
     /// used for test purposes only.
     pub fn from_consensus<T: ConsensusApi + ConsensusCtl + 'static>(consensus: Arc<T>) -> Self {
         let (consensus, ctl) = (consensus.clone() as DynConsensus, consensus as DynConsensusCtl);
-        Self {
+        let manager = Self {
             factory: Arc::new(MockFactory),
             inner: RwLock::new(ManagerInner::new(ConsensusInstance::new(SessionLock::new(), consensus), ctl)),
-        }
+            ready: Default::default(),
+            stopped: Default::default(),
+        };
+        // The fixed consensus is managed by the caller
+        manager.ready.trigger.trigger();
+        manager
+    }
+
+    /// Returns a listener which completes once the active consensus is ready to process and serve data
+    pub fn ready_listener(&self) -> Listener {
+        self.ready.listener.clone()
     }
 
     pub fn consensus(&self) -> ConsensusInstance {
@@ -144,14 +171,20 @@ impl ConsensusManager {
         self.inner.write().consensus_reset_handlers.push(handler);
     }
 
-    fn worker(&self) {
+    fn worker(&self, core: Arc<Core>) {
         let handles = self.inner.read().current.ctl.clone().start();
         self.inner.write().handles.extend(handles);
+        self.ready.trigger.trigger();
         // If current consensus is switched, this loop will join the replaced handles, and will switch to waiting for the new ones
         let mut g = self.inner.write();
         while let Some(handle) = g.handles.pop_front() {
             drop(g);
-            handle.join().unwrap();
+            let name = handle.thread().name().unwrap_or_default().to_owned();
+            if handle.join().is_err() {
+                // A consensus processor gave up, so the node can no longer make progress
+                error!("[Consensus manager] consensus thread {} terminated abnormally, shutting down the node", name);
+                core.shutdown();
+            }
             g = self.inner.write();
         }
 
@@ -175,13 +208,24 @@ impl Service for ConsensusManager {
         Self::IDENT
     }
 
-    fn start(self: Arc<Self>, _core: Arc<Core>) -> Vec<JoinHandle<()>> {
-        vec![std::thread::spawn(move || self.worker())]
+    fn start(self: Arc<Self>, core: Arc<Core>) -> Vec<JoinHandle<()>> {
+        vec![std::thread::spawn(move || self.worker(core))]
     }
 
     fn stop(self: Arc<Self>) {
+        self.stopped.store(true, Ordering::SeqCst);
         self.inner.read().current.ctl.clone().stop();
     }
+
+    fn health(self: Arc<Self>) -> ServiceHealth {
+        if self.stopped.load(Ordering::SeqCst) {
+            ServiceHealth::Stopped
+        } else if self.ready.listener.is_triggered() {
+            ServiceHealth::Ready
+        } else {
+            ServiceHealth::Starting
+        }
+    }
 }
 
 pub struct StagingConsensus {
@@ -3,17 +3,27 @@
 //! We use newtypes in order to simplify changing the underlying lock in the future
 
 use kaspa_consensus_core::{
-    acceptance_data::AcceptanceData,
+    acceptance_data::{AcceptanceData, MergesetBlockUtxoDiff},
+    acceptance_proof::TransactionAcceptanceProof,
     api::{BlockValidationFutures, ConsensusApi, DynConsensus},
-    block::Block,
+    block::{Block, BlockOrigin},
     block_count::BlockCount,
+    blockhash::BlockHashes,
     blockstatus::BlockStatus,
     daa_score_timestamp::DaaScoreTimestamp,
-    errors::consensus::ConsensusResult,
+    dag_timing::BlockDagTimingInfo,
+    dag_view::DagBlockInfo,
+    errors::{block::BlockProcessResult, consensus::ConsensusResult},
     header::Header,
+    integrity::IntegrityReport,
+    network_stats::NetworkStats,
     pruning::{PruningPointProof, PruningPointTrustedData, PruningPointsList},
+    state_digest::StateDigest,
+    storage_stats::StorageStats,
     trusted::{ExternalGhostdagData, TrustedBlock},
-    tx::{Transaction, TransactionOutpoint, UtxoEntry},
+    tx::{Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
+    utxo::export::{UtxoSetChunk, UtxoSetCursor},
+    validation_trace::BlockValidationTrace,
     BlockHashSet, BlueWorkType, ChainPath, Hash,
 };
 use kaspa_utils::sync::rwlock::*;
@@ -149,7 +159,9 @@ impl ConsensusSessionOwned {
         F: FnOnce(&dyn ConsensusApi) -> R + Send + 'static,
         R: Send + 'static,
     {
-        spawn_blocking(move || f(self.consensus.as_ref())).await.unwrap()
+        // Consensus calls are traced as children of the span of the calling task, if any (see `kaspa_rpc_core::trace`)
+        let span = tracing::trace_span!("consensus");
+        spawn_blocking(move || span.in_scope(|| f(self.consensus.as_ref()))).await.unwrap()
     }
 }
 
@@ -158,10 +170,19 @@ impl ConsensusSessionOwned {
         self.consensus.validate_and_insert_block(block)
     }
 
+    pub fn validate_and_insert_block_with_origin(&self, block: Block, origin: BlockOrigin) -> BlockValidationFutures {
+        self.consensus.validate_and_insert_block_with_origin(block, origin)
+    }
+
     pub fn validate_and_insert_trusted_block(&self, tb: TrustedBlock) -> BlockValidationFutures {
         self.consensus.validate_and_insert_trusted_block(tb)
     }
 
+    pub fn precheck_block(&self, block: &Block) -> BlockProcessResult<()> {
+        // This method only performs fast in-memory checks so no need for an async wrapper
+        self.consensus.precheck_block(block)
+    }
+
     pub fn calculate_transaction_mass(&self, transaction: &Transaction) -> u64 {
         // This method performs pure calculations so no need for an async wrapper
         self.consensus.calculate_transaction_mass(transaction)
@@ -304,6 +325,10 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(move |c| c.get_block_even_if_header_only(hash)).await
     }
 
+    pub async fn async_get_archived_block(&self, hash: Hash) -> ConsensusResult<Block> {
+        self.clone().spawn_blocking(move |c| c.get_archived_block(hash)).await
+    }
+
     pub async fn async_get_ghostdag_data(&self, hash: Hash) -> ConsensusResult<ExternalGhostdagData> {
         self.clone().spawn_blocking(move |c| c.get_ghostdag_data(hash)).await
     }
@@ -312,7 +337,7 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(move |c| c.get_block_children(hash)).await
     }
 
-    pub async fn async_get_block_parents(&self, hash: Hash) -> Option<Arc<Vec<Hash>>> {
+    pub async fn async_get_block_parents(&self, hash: Hash) -> Option<BlockHashes> {
         self.clone().spawn_blocking(move |c| c.get_block_parents(hash)).await
     }
 
@@ -324,6 +349,10 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(move |c| c.get_block_acceptance_data(hash)).await
     }
 
+    pub async fn async_get_chain_block_mergeset_utxo_diffs(&self, hash: Hash) -> ConsensusResult<Vec<MergesetBlockUtxoDiff>> {
+        self.clone().spawn_blocking(move |c| c.get_chain_block_mergeset_utxo_diffs(hash)).await
+    }
+
     /// Returns acceptance data for a set of blocks belonging to the selected parent chain.
     ///
     /// See `self::get_virtual_chain`
@@ -351,6 +380,81 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(move |c| c.get_missing_block_body_hashes(high)).await
     }
 
+    pub async fn async_verify_chain_integrity(&self, high: Hash, max_chain_blocks: usize) -> ConsensusResult<IntegrityReport> {
+        self.clone().spawn_blocking(move |c| c.verify_chain_integrity(high, max_chain_blocks)).await
+    }
+
+    pub async fn async_quarantine_blocks(&self, hashes: Vec<Hash>) -> usize {
+        self.clone().spawn_blocking(move |c| c.quarantine_blocks(&hashes)).await
+    }
+
+    pub async fn async_get_quarantined_blocks(&self) -> Vec<Hash> {
+        self.clone().spawn_blocking(|c| c.get_quarantined_blocks()).await
+    }
+
+    pub async fn async_repair_quarantined_block(&self, block: Block) -> ConsensusResult<()> {
+        self.clone().spawn_blocking(move |c| c.repair_quarantined_block(block)).await
+    }
+
+    pub async fn async_invalidate_block(&self, hash: Hash) -> ConsensusResult<()> {
+        self.clone().spawn_blocking(move |c| c.invalidate_block(hash)).await
+    }
+
+    pub async fn async_reconsider_block(&self, hash: Hash) -> ConsensusResult<()> {
+        self.clone().spawn_blocking(move |c| c.reconsider_block(hash)).await
+    }
+
+    pub async fn async_get_block_validation_trace(&self, hash: Hash) -> Option<BlockValidationTrace> {
+        self.clone().spawn_blocking(move |c| c.get_block_validation_trace(hash)).await
+    }
+
+    pub async fn async_get_pipeline_backlog(&self) -> usize {
+        self.clone().spawn_blocking(|c| c.get_pipeline_backlog()).await
+    }
+
+    pub async fn async_get_network_stats(&self) -> NetworkStats {
+        self.clone().spawn_blocking(|c| c.get_network_stats()).await
+    }
+
+    pub async fn async_get_storage_stats(&self) -> StorageStats {
+        self.clone().spawn_blocking(|c| c.get_storage_stats()).await
+    }
+
+    pub async fn async_get_block_dag_timing_info(&self, hash: Hash) -> ConsensusResult<BlockDagTimingInfo> {
+        self.clone().spawn_blocking(move |c| c.get_block_dag_timing_info(hash)).await
+    }
+
+    pub async fn async_get_state_digests(&self, count: usize) -> Vec<StateDigest> {
+        self.clone().spawn_blocking(move |c| c.get_state_digests(count)).await
+    }
+
+    pub async fn async_get_dag_blocks_around(&self, hash: Hash, radius: u32, max_blocks: usize) -> ConsensusResult<Vec<DagBlockInfo>> {
+        self.clone().spawn_blocking(move |c| c.get_dag_blocks_around(hash, radius, max_blocks)).await
+    }
+
+    pub async fn async_get_chain_headers(&self, start: Hash, limit: usize, is_ascending: bool) -> ConsensusResult<Vec<Arc<Header>>> {
+        self.clone().spawn_blocking(move |c| c.get_chain_headers(start, limit, is_ascending)).await
+    }
+
+    pub async fn async_get_transaction_acceptance_proof(
+        &self,
+        transaction_id: TransactionId,
+        accepting_block_hash: Hash,
+        max_chain_headers: usize,
+    ) -> ConsensusResult<TransactionAcceptanceProof> {
+        self.clone()
+            .spawn_blocking(move |c| c.get_transaction_acceptance_proof(transaction_id, accepting_block_hash, max_chain_headers))
+            .await
+    }
+
+    pub async fn async_get_virtual_utxo_set_chunk(
+        &self,
+        cursor: Option<UtxoSetCursor>,
+        chunk_size: usize,
+    ) -> ConsensusResult<UtxoSetChunk> {
+        self.clone().spawn_blocking(move |c| c.get_virtual_utxo_set_chunk(cursor, chunk_size)).await
+    }
+
     pub async fn async_pruning_point(&self) -> Hash {
         self.clone().spawn_blocking(|c| c.pruning_point()).await
     }
@@ -1,7 +1,7 @@
 use kaspa_hashes::Hash;
 use serde::{Deserialize, Serialize};
 
-use crate::tx::TransactionId;
+use crate::{tx::TransactionId, utxo::utxo_diff::UtxoDiff};
 
 pub type AcceptanceData = Vec<MergesetBlockAcceptanceData>;
 
@@ -16,3 +16,12 @@ pub struct AcceptedTxEntry {
     pub transaction_id: TransactionId,
     pub index_within_block: u32,
 }
+
+/// The UTXO contribution of a block of the mergeset of a chain block, i.e. the UTXO diff applied by the transactions
+/// it got accepted by the chain block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergesetBlockUtxoDiff {
+    pub block_hash: Hash,
+    pub is_blue: bool,
+    pub utxo_diff: UtxoDiff,
+}
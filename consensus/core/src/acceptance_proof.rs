@@ -0,0 +1,177 @@
+//!
+//! Compact proofs of transaction acceptance, verifiable by light clients tracking the selected chain headers.
+//!
+
+use crate::{hashing, header::Header, tx::TransactionId};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use kaspa_hashes::Hash;
+use kaspa_merkle::verify_merkle_branch;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AcceptanceProofError {
+    #[error("the acceptance proof contains no chain headers")]
+    EmptyChainSegment,
+
+    #[error("header {0} does not match its content")]
+    HeaderHashMismatch(Hash),
+
+    #[error("the merkle branch does not prove the acceptance of transaction {0} by block {1}")]
+    InvalidMerkleBranch(TransactionId, Hash),
+
+    #[error("block {0} is not the selected parent of block {1}")]
+    NotSelectedParent(Hash, Hash),
+
+    #[error("the acceptance proof lacks the header of parent {0} of block {1}")]
+    MissingParentHeader(Hash, Hash),
+}
+
+/// A proof that a transaction was accepted by a selected chain block.
+///
+/// The merkle branch proves the transaction ID against the accepted ID merkle root of the accepting block header,
+/// and the chain segment links this header to later chain blocks through their selected parents, i.e. the direct
+/// parents with the highest blue work (see `GhostdagManager::find_selected_parent`). The proof only establishes the
+/// acceptance once the last block of the segment is found on the selected chain tracked by the verifier, e.g.
+/// through the `GetHeaders` RPC.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionAcceptanceProof {
+    pub transaction_id: TransactionId,
+    /// The position of the transaction ID among the sorted IDs accepted by the accepting block
+    pub accepted_id_index: u32,
+    /// Merkle branch from the transaction ID up to the accepted ID merkle root of the accepting block
+    pub accepted_id_merkle_branch: Vec<Hash>,
+    /// Headers of consecutive selected chain blocks, starting at the accepting block
+    pub chain_headers: Vec<Header>,
+    /// Headers of the direct parents of the chain blocks following the accepting block, excluding chain blocks, which
+    /// prove that each chain block is the selected parent of the next one
+    pub parent_headers: Vec<Header>,
+}
+
+impl TransactionAcceptanceProof {
+    pub fn accepting_block_hash(&self) -> Option<Hash> {
+        self.chain_headers.first().map(|header| header.hash)
+    }
+
+    /// Verifies the consistency of the proof and returns the hash of the last block of its chain segment
+    pub fn verify(&self) -> Result<Hash, AcceptanceProofError> {
+        let accepting_header = self.chain_headers.first().ok_or(AcceptanceProofError::EmptyChainSegment)?;
+        // Cached hashes come from the prover, so they are recomputed
+        let mut headers = self.chain_headers.iter().chain(self.parent_headers.iter());
+        if let Some(header) = headers.find(|header| hashing::header::hash(header) != header.hash) {
+            return Err(AcceptanceProofError::HeaderHashMismatch(header.hash));
+        }
+        if !verify_merkle_branch(
+            self.transaction_id,
+            self.accepted_id_index as usize,
+            &self.accepted_id_merkle_branch,
+            accepting_header.accepted_id_merkle_root,
+        ) {
+            return Err(AcceptanceProofError::InvalidMerkleBranch(self.transaction_id, accepting_header.hash));
+        }
+        // A direct parent is not necessarily a chain block, so each link is checked to be the selected parent
+        let headers: HashMap<Hash, &Header> =
+            self.chain_headers.iter().chain(self.parent_headers.iter()).map(|header| (header.hash, header)).collect();
+        for pair in self.chain_headers.windows(2) {
+            let (selected_parent, block) = (&pair[0], &pair[1]);
+            if !block.direct_parents().contains(&selected_parent.hash) {
+                return Err(AcceptanceProofError::NotSelectedParent(selected_parent.hash, block.hash));
+            }
+            for parent in block.direct_parents() {
+                let parent = headers.get(parent).ok_or(AcceptanceProofError::MissingParentHeader(*parent, block.hash))?;
+                if (parent.blue_work, parent.hash) > (selected_parent.blue_work, selected_parent.hash) {
+                    return Err(AcceptanceProofError::NotSelectedParent(selected_parent.hash, block.hash));
+                }
+            }
+        }
+        Ok(self.chain_headers.last().unwrap().hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlueWorkType;
+    use kaspa_merkle::{calc_merkle_root, create_merkle_branch};
+
+    fn header(parents: Vec<Hash>, accepted_id_merkle_root: Hash, blue_score: u64) -> Header {
+        header_with_blue_work(parents, accepted_id_merkle_root, blue_score, blue_score)
+    }
+
+    fn header_with_blue_work(parents: Vec<Hash>, accepted_id_merkle_root: Hash, blue_score: u64, blue_work: u64) -> Header {
+        Header::new_finalized(
+            0,
+            vec![parents],
+            Default::default(),
+            accepted_id_merkle_root,
+            Default::default(),
+            blue_score,
+            0,
+            0,
+            blue_score,
+            BlueWorkType::from_u64(blue_work),
+            blue_score,
+            Default::default(),
+        )
+    }
+
+    #[test]
+    fn test_acceptance_proof() {
+        let accepted_ids = (1..=5u64).map(Hash::from_u64_word).collect::<Vec<_>>();
+        let accepting_header = header(vec![Hash::from_u64_word(100)], calc_merkle_root(accepted_ids.iter().copied()), 10);
+        let sibling_header = header(vec![Hash::from_u64_word(100)], Default::default(), 9);
+        let next_header = header(vec![sibling_header.hash, accepting_header.hash], Default::default(), 11);
+        let proof = TransactionAcceptanceProof {
+            transaction_id: accepted_ids[3],
+            accepted_id_index: 3,
+            accepted_id_merkle_branch: create_merkle_branch(accepted_ids.iter().copied(), 3).unwrap(),
+            chain_headers: vec![accepting_header.clone(), next_header.clone()],
+            parent_headers: vec![sibling_header.clone()],
+        };
+        assert_eq!(proof.accepting_block_hash(), Some(accepting_header.hash));
+        assert_eq!(proof.verify(), Ok(next_header.hash));
+
+        let mut wrong_transaction = proof.clone();
+        wrong_transaction.transaction_id = Hash::from_u64_word(6);
+        assert_eq!(wrong_transaction.verify(), Err(AcceptanceProofError::InvalidMerkleBranch(6.into(), accepting_header.hash)));
+
+        let mut broken_segment = proof.clone();
+        broken_segment.chain_headers.reverse();
+        assert_eq!(broken_segment.verify(), Err(AcceptanceProofError::NotSelectedParent(next_header.hash, accepting_header.hash)));
+
+        let mut missing_parent = proof.clone();
+        missing_parent.parent_headers.clear();
+        assert_eq!(missing_parent.verify(), Err(AcceptanceProofError::MissingParentHeader(sibling_header.hash, next_header.hash)));
+
+        let mut forged_parent = proof.clone();
+        forged_parent.parent_headers[0].blue_work = BlueWorkType::from_u64(100);
+        assert_eq!(forged_parent.verify(), Err(AcceptanceProofError::HeaderHashMismatch(sibling_header.hash)));
+
+        let mut forged_header = proof.clone();
+        forged_header.chain_headers[1].blue_score += 1;
+        assert_eq!(forged_header.verify(), Err(AcceptanceProofError::HeaderHashMismatch(next_header.hash)));
+
+        let mut empty_segment = proof;
+        empty_segment.chain_headers.clear();
+        assert_eq!(empty_segment.verify(), Err(AcceptanceProofError::EmptyChainSegment));
+    }
+
+    #[test]
+    fn test_acceptance_proof_through_non_selected_parent() {
+        // The accepting block is a direct parent of the next block, whose selected parent is however a heavier sibling
+        let accepted_ids = (1..=5u64).map(Hash::from_u64_word).collect::<Vec<_>>();
+        let accepting_header = header(vec![Hash::from_u64_word(100)], calc_merkle_root(accepted_ids.iter().copied()), 10);
+        let selected_header = header_with_blue_work(vec![Hash::from_u64_word(100)], Default::default(), 10, 20);
+        let next_header = header(vec![selected_header.hash, accepting_header.hash], Default::default(), 21);
+        let proof = TransactionAcceptanceProof {
+            transaction_id: accepted_ids[0],
+            accepted_id_index: 0,
+            accepted_id_merkle_branch: create_merkle_branch(accepted_ids.iter().copied(), 0).unwrap(),
+            chain_headers: vec![accepting_header.clone(), next_header.clone()],
+            parent_headers: vec![selected_header],
+        };
+        assert_eq!(proof.verify(), Err(AcceptanceProofError::NotSelectedParent(accepting_header.hash, next_header.hash)));
+    }
+}
@@ -3,12 +3,16 @@ use kaspa_muhash::MuHash;
 use std::sync::Arc;
 
 use crate::{
-    acceptance_data::AcceptanceData,
-    block::{Block, BlockTemplate, TemplateBuildMode, TemplateTransactionSelector, VirtualStateApproxId},
+    acceptance_data::{AcceptanceData, MergesetBlockUtxoDiff},
+    acceptance_proof::TransactionAcceptanceProof,
+    block::{Block, BlockOrigin, BlockTemplate, TemplateBuildMode, TemplateTransactionSelector, VirtualStateApproxId},
     block_count::BlockCount,
+    blockhash::BlockHashes,
     blockstatus::BlockStatus,
     coinbase::MinerData,
     daa_score_timestamp::DaaScoreTimestamp,
+    dag_timing::BlockDagTimingInfo,
+    dag_view::DagBlockInfo,
     errors::{
         block::{BlockProcessResult, RuleError},
         coinbase::CoinbaseResult,
@@ -17,9 +21,15 @@ use crate::{
         tx::TxResult,
     },
     header::Header,
+    integrity::IntegrityReport,
+    network_stats::NetworkStats,
     pruning::{PruningPointProof, PruningPointTrustedData, PruningPointsList},
+    state_digest::StateDigest,
+    storage_stats::StorageStats,
     trusted::{ExternalGhostdagData, TrustedBlock},
-    tx::{MutableTransaction, Transaction, TransactionOutpoint, UtxoEntry},
+    tx::{MutableTransaction, Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
+    utxo::export::{UtxoSetChunk, UtxoSetCursor},
+    validation_trace::BlockValidationTrace,
     BlockHashSet, BlueWorkType, ChainPath,
 };
 use kaspa_hashes::Hash;
@@ -52,6 +62,17 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Same as [`Self::validate_and_insert_block`], tagging the block with the given origin throughout the pipeline
+    fn validate_and_insert_block_with_origin(&self, block: Block, origin: BlockOrigin) -> BlockValidationFutures {
+        unimplemented!()
+    }
+
+    /// Synchronously runs fast pre-validation checks (version, timestamp, PoW and parents existence) on the
+    /// block header, without queuing the block for processing
+    fn precheck_block(&self, block: &Block) -> BlockProcessResult<()> {
+        unimplemented!()
+    }
+
     fn validate_and_insert_trusted_block(&self, tb: TrustedBlock) -> BlockValidationFutures {
         unimplemented!()
     }
@@ -154,6 +175,12 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns up to `chunk_size` entries of the virtual UTXO set following `cursor`. A `None` cursor pins a new
+    /// snapshot of the set, which the cursors of the returned chunks keep reading from
+    fn get_virtual_utxo_set_chunk(&self, cursor: Option<UtxoSetCursor>, chunk_size: usize) -> ConsensusResult<UtxoSetChunk> {
+        unimplemented!()
+    }
+
     fn get_tips(&self) -> Vec<Hash> {
         unimplemented!()
     }
@@ -182,6 +209,11 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Discards the pruning point UTXOs appended so far, so that an import can be restarted from another source
+    fn clear_imported_pruning_point_utxos(&self) {
+        unimplemented!()
+    }
+
     fn is_chain_ancestor_of(&self, low: Hash, high: Hash) -> ConsensusResult<bool> {
         unimplemented!()
     }
@@ -238,6 +270,16 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the transactions of each of the blocks `hashes`, in order, read from the block bodies with a single batched read
+    fn get_blocks_transactions(&self, hashes: &[Hash]) -> ConsensusResult<Vec<Arc<Vec<Transaction>>>> {
+        unimplemented!()
+    }
+
+    /// Returns a pruned block out of the era archive the node exports pruned block bodies to, if configured
+    fn get_archived_block(&self, hash: Hash) -> ConsensusResult<Block> {
+        unimplemented!()
+    }
+
     fn get_ghostdag_data(&self, hash: Hash) -> ConsensusResult<ExternalGhostdagData> {
         unimplemented!()
     }
@@ -246,7 +288,7 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
-    fn get_block_parents(&self, hash: Hash) -> Option<Arc<Vec<Hash>>> {
+    fn get_block_parents(&self, hash: Hash) -> Option<BlockHashes> {
         unimplemented!()
     }
 
@@ -265,6 +307,12 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the UTXO diff contributed by each block of the mergeset of the chain block `hash`, in consensus
+    /// order. Together they compose the UTXO diff of the chain block.
+    fn get_chain_block_mergeset_utxo_diffs(&self, hash: Hash) -> ConsensusResult<Vec<MergesetBlockUtxoDiff>> {
+        unimplemented!()
+    }
+
     fn is_chain_block(&self, hash: Hash) -> ConsensusResult<bool> {
         unimplemented!()
     }
@@ -283,6 +331,110 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Verifies cross-store invariants of up to `max_chain_blocks` selected chain blocks, starting at `high` and
+    /// going down to the pruning point, along with their mergesets
+    fn verify_chain_integrity(&self, high: Hash, max_chain_blocks: usize) -> ConsensusResult<IntegrityReport> {
+        unimplemented!()
+    }
+
+    /// Marks blocks whose stored data was found corrupted or missing for re-download from peers.
+    /// Returns the number of newly quarantined blocks
+    fn quarantine_blocks(&self, hashes: &[Hash]) -> usize {
+        unimplemented!()
+    }
+
+    /// Returns the quarantined blocks which are still awaiting re-download
+    fn get_quarantined_blocks(&self) -> Vec<Hash> {
+        unimplemented!()
+    }
+
+    /// Restores the body of a quarantined block from a re-downloaded copy and releases it from quarantine
+    fn repair_quarantined_block(&self, block: Block) -> ConsensusResult<()> {
+        unimplemented!()
+    }
+
+    /// Clears the invalid marks of blocks which were rejected for violating the rule with code `rule_code` (see
+    /// [`crate::errors::block::RuleError::code`]) and resubmits them for validation. Returns the resubmitted hashes
+    fn revalidate_invalid_blocks(&self, rule_code: u16) -> Vec<Hash> {
+        unimplemented!()
+    }
+
+    /// Clears the invalid marks of blocks which were rejected by the validation logic of version `version` (see
+    /// [`crate::constants::VALIDATION_VERSION`]) and resubmits them for validation. Returns the resubmitted hashes.
+    /// Blocks accepted by that version are left as is
+    fn revalidate_blocks_by_version(&self, version: u16) -> Vec<Hash> {
+        unimplemented!()
+    }
+
+    /// Disqualifies the block along with its selected chain descendants from the virtual chain and re-resolves
+    /// the virtual. Meant for emergency response to consensus incidents
+    fn invalidate_block(&self, hash: Hash) -> ConsensusResult<()> {
+        unimplemented!()
+    }
+
+    /// Reverts a previous [`ConsensusApi::invalidate_block`] and re-resolves the virtual
+    fn reconsider_block(&self, hash: Hash) -> ConsensusResult<()> {
+        unimplemented!()
+    }
+
+    /// Returns the rules evaluated while validating the block along with their timings. Traces are only recorded when
+    /// validation tracing is enabled and are kept for recently validated blocks only
+    fn get_block_validation_trace(&self, hash: Hash) -> Option<BlockValidationTrace> {
+        unimplemented!()
+    }
+
+    /// Returns the approximate number of submitted blocks which did not complete the processing pipeline yet
+    fn get_pipeline_backlog(&self) -> usize {
+        unimplemented!()
+    }
+
+    /// Returns rolling statistics (block inter-arrival intervals, parent counts and mergeset coloring) over the
+    /// most recently processed headers
+    fn get_network_stats(&self) -> NetworkStats {
+        unimplemented!()
+    }
+
+    /// Returns statistics of the storage engine backing the consensus stores (write amplification, compaction
+    /// backlog and write stalls)
+    fn get_storage_stats(&self) -> StorageStats {
+        unimplemented!()
+    }
+
+    /// Returns the difficulty window boundaries, past median time and expected bits consensus computes for the block
+    fn get_block_dag_timing_info(&self, hash: Hash) -> ConsensusResult<BlockDagTimingInfo> {
+        unimplemented!()
+    }
+
+    /// Returns the state digests of the `count` most recent checkpoint chain blocks above the pruning point,
+    /// the most recent first. At most [`MAX_STATE_DIGEST_CHAIN_WALK`](crate::state_digest::MAX_STATE_DIGEST_CHAIN_WALK)
+    /// chain blocks are walked, so fewer digests might be returned
+    fn get_state_digests(&self, count: usize) -> Vec<StateDigest> {
+        unimplemented!()
+    }
+
+    /// Returns the blocks reachable from `hash` by following at most `radius` parent or child edges, closest first
+    /// and truncated to `max_blocks`. Blocks below the pruning point are not traversed
+    fn get_dag_blocks_around(&self, hash: Hash, radius: u32, max_blocks: usize) -> ConsensusResult<Vec<DagBlockInfo>> {
+        unimplemented!()
+    }
+
+    /// Returns at most `limit` headers of consecutive selected chain blocks starting at the chain block `start`,
+    /// going either up to the sink or down to the pruning point
+    fn get_chain_headers(&self, start: Hash, limit: usize, is_ascending: bool) -> ConsensusResult<Vec<Arc<Header>>> {
+        unimplemented!()
+    }
+
+    /// Builds a proof that `transaction_id` was accepted by the chain block `accepting_block_hash`, whose chain
+    /// segment spans at most `max_chain_headers` chain blocks starting at the accepting block
+    fn get_transaction_acceptance_proof(
+        &self,
+        transaction_id: TransactionId,
+        accepting_block_hash: Hash,
+        max_chain_headers: usize,
+    ) -> ConsensusResult<TransactionAcceptanceProof> {
+        unimplemented!()
+    }
+
     fn pruning_point(&self) -> Hash {
         unimplemented!()
     }
@@ -85,6 +85,31 @@ pub trait TemplateTransactionSelector {
     fn is_successful(&self) -> bool;
 }
 
+/// The origin of a block submitted for validation, allowing origin-aware policies throughout the pipeline
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlockOrigin {
+    /// The block was inserted locally, e.g., by tests, simulations or consensus maintenance operations
+    #[default]
+    Local,
+    /// The block was submitted via RPC, usually by a miner
+    Rpc,
+    /// The block was relayed by a peer
+    Relay,
+    /// The block was downloaded from a peer during IBD
+    Ibd,
+}
+
+impl std::fmt::Display for BlockOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockOrigin::Local => f.write_str("local"),
+            BlockOrigin::Rpc => f.write_str("submit block"),
+            BlockOrigin::Relay => f.write_str("relay"),
+            BlockOrigin::Ibd => f.write_str("IBD"),
+        }
+    }
+}
+
 /// Block template build mode
 #[derive(Clone, Copy, Debug)]
 pub enum TemplateBuildMode {
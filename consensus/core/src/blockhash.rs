@@ -1,8 +1,14 @@
 use crate::{BlockHashSet, HashMapCustomHasher};
 use kaspa_hashes::{Hash, HASH_SIZE};
+use smallvec::SmallVec;
 use std::sync::Arc;
 
-pub type BlockHashes = Arc<Vec<Hash>>;
+/// The number of hashes a [`BlockHashVec`] holds without a heap allocation of its own. Parent lists and mergesets
+/// are short in the common case, in which the list is allocated along with its `Arc` in a single allocation
+pub const BLOCK_HASHES_INLINE_CAPACITY: usize = 2;
+
+pub type BlockHashVec = SmallVec<[Hash; BLOCK_HASHES_INLINE_CAPACITY]>;
+pub type BlockHashes = Arc<BlockHashVec>;
 
 /// `blockhash::NONE` is a hash which is used in rare cases as the `None` block hash
 pub const NONE: Hash = Hash::from_bytes([0u8; HASH_SIZE]);
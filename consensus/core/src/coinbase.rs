@@ -1,6 +1,10 @@
 use crate::tx::{ScriptPublicKey, Transaction};
 use serde::{Deserialize, Serialize};
 
+/// The length of the coinbase payload fields preceding the miner data: blue score, subsidy,
+/// script public key version and script public key length
+pub const COINBASE_PAYLOAD_HEADER_LEN: usize = 8 + 8 + 2 + 1;
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct MinerData<T: AsRef<[u8]> = Vec<u8>> {
     pub script_public_key: ScriptPublicKey,
@@ -11,6 +15,11 @@ impl<T: AsRef<[u8]>> MinerData<T> {
     pub fn new(script_public_key: ScriptPublicKey, extra_data: T) -> Self {
         Self { script_public_key, extra_data }
     }
+
+    /// The length of a coinbase payload embedding this miner data
+    pub fn payload_len(&self) -> usize {
+        COINBASE_PAYLOAD_HEADER_LEN + self.script_public_key.script().len() + self.extra_data.as_ref().len()
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
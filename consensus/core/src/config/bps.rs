@@ -120,6 +120,16 @@ impl<const BPS: u64> Bps<BPS> {
     pub const fn pre_deflationary_phase_base_subsidy() -> u64 {
         50000000000 / BPS
     }
+
+    /// Returns the max block mass. Above 10 BPS the mass of each block is scaled down such that the
+    /// overall mass throughput of the network remains that of a 10 BPS network
+    pub const fn max_block_mass() -> u64 {
+        if BPS <= 10 {
+            500_000
+        } else {
+            500_000 * 10 / BPS
+        }
+    }
 }
 
 #[cfg(test)]
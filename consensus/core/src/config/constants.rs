@@ -127,6 +127,8 @@ pub mod perf {
     const BASELINE_BLOCK_DATA_CACHE_SIZE: u64 = 200;
     const BASELINE_BLOCK_WINDOW_CACHE_SIZE: u64 = 2000;
     const BASELINE_UTXOSET_CACHE_SIZE: u64 = 10_000;
+    const BASELINE_BLOCK_PIPELINE_CAPACITY: usize = 2000;
+    const BASELINE_BLOCK_PIPELINE_MAX_BYTES: u64 = 256 * 1024 * 1024; // 256MB
 
     #[derive(Clone, Debug)]
     pub struct PerfParams {
@@ -160,6 +162,28 @@ pub mod perf {
         /// Defaults to 0 which indicates using system default
         /// which is typically the number of logical CPU cores
         pub virtual_processor_num_threads: usize,
+
+        /// Pin the threads of the validation pools to consecutive CPU cores, the virtual processor
+        /// pool taking the cores following those of the block processors pool
+        pub pin_worker_threads: bool,
+
+        //
+        // Block pipeline
+        //
+        /// The maximal number of blocks admitted for processing and not yet fully processed, above which
+        /// submitted blocks are parked until blocks in flight are released (0 for no limit)
+        pub block_pipeline_capacity: usize,
+
+        /// The maximal total estimated size in bytes of the blocks in the pipeline, bounding memory
+        /// usage when processing large blocks (0 for no limit)
+        pub block_pipeline_max_bytes: u64,
+
+        //
+        // Storage
+        //
+        /// Iterator read-ahead size in bytes for stores which are scanned sequentially
+        /// (the virtual and pruning point UTXO sets). 0 indicates using the RocksDB default
+        pub scan_readahead_size: usize,
     }
 
     pub const PERF_PARAMS: PerfParams = PerfParams {
@@ -170,14 +194,27 @@ pub mod perf {
         block_window_cache_size: BASELINE_BLOCK_WINDOW_CACHE_SIZE,
         block_processors_num_threads: 0,
         virtual_processor_num_threads: 0,
+        pin_worker_threads: false,
+        block_pipeline_capacity: BASELINE_BLOCK_PIPELINE_CAPACITY,
+        block_pipeline_max_bytes: BASELINE_BLOCK_PIPELINE_MAX_BYTES,
+        scan_readahead_size: 2 * 1024 * 1024, // 2MB
     };
 
     impl PerfParams {
         pub fn adjust_to_consensus_params(&mut self, consensus_params: &Params) {
             self.block_data_cache_size *= consensus_params.bps().clamp(1, 10); // Allow caching up to 10x over the baseline
+            self.block_pipeline_capacity *= consensus_params.bps().clamp(1, 10) as usize; // Headers are small, the byte cap bounds full blocks
             self.block_window_cache_size = calculate_difficulty_window_cache_size(consensus_params);
             self.headers_cache_size = calculate_headers_cache_size(consensus_params);
         }
+
+        /// Splits a budget of `num_threads` validation threads between the block processors pool and the virtual
+        /// processor pool, so that the two pools together do not oversubscribe the cores dedicated to validation.
+        /// Each pool gets at least one thread
+        pub fn set_validation_threads(&mut self, num_threads: usize) {
+            self.virtual_processor_num_threads = (num_threads / 2).max(1);
+            self.block_processors_num_threads = num_threads.saturating_sub(self.virtual_processor_num_threads).max(1);
+        }
     }
 
     /// Bounds the cache size according to the "memory budget" (represented in bytes) and the approximate size of each unit in bytes
@@ -211,9 +248,70 @@ pub mod perf {
     }
 }
 
+pub mod decoding {
+    //!
+    //! A module for network independent upper bounds on the sizes of consensus objects decoded from untrusted
+    //! sources (P2P peers and RPC clients).
+    //!
+    //! The protobuf conversions reject larger objects before converting them into consensus objects. Note that the
+    //! protobuf wire message is fully decoded by then, its size being bounded only by the max message size of the
+    //! transport, so the bounds spare the conversion and validation of oversized objects rather than the decoding of
+    //! the wire message. The Borsh decoder of `ScriptPublicKey` rejects oversized scripts by their length prefix,
+    //! before reading them, while the other Borsh decoded collections are bounded by the size of the input.
+    //!
+    //! The bounds are implied by consensus rules, so that they never reject an object consensus could accept.
+    //! Every byte of a non-coinbase transaction is charged at least one unit of mass, hence the sizes of scripts,
+    //! payloads and the numbers of transactions, inputs and outputs are all bounded by the max block mass.
+    //!
+
+    use crate::BlockLevel;
+
+    /// Upper bound on the max block mass of all networks
+    pub const MAX_BLOCK_MASS: usize = 500_000;
+
+    /// The estimated serialized size of a transaction with no inputs, outputs and payload
+    const MIN_TRANSACTION_SIZE: usize = 94;
+
+    /// The estimated serialized size of an input with an empty signature script
+    const MIN_TRANSACTION_INPUT_SIZE: usize = 52;
+
+    /// The estimated serialized size of an output with an empty script public key
+    const MIN_TRANSACTION_OUTPUT_SIZE: usize = 18;
+
+    /// Max number of parent levels of a header, i.e., one for each block level
+    pub const MAX_HEADER_PARENT_LEVELS: usize = BlockLevel::MAX as usize + 1;
+
+    /// Max number of parents of a header across all levels. Direct parents are limited by the `u8` max block
+    /// parents param, while the parents of higher levels are usually way fewer
+    pub const MAX_HEADER_PARENTS: usize = MAX_HEADER_PARENT_LEVELS * u8::MAX as usize;
+
+    /// Max number of transactions in a block, including the coinbase transaction
+    pub const MAX_BLOCK_TRANSACTIONS: usize = MAX_BLOCK_MASS / MIN_TRANSACTION_SIZE + 1;
+
+    /// Max number of inputs of a transaction
+    pub const MAX_TRANSACTION_INPUTS: usize = MAX_BLOCK_MASS / MIN_TRANSACTION_INPUT_SIZE;
+
+    /// Max number of outputs of a transaction
+    pub const MAX_TRANSACTION_OUTPUTS: usize = MAX_BLOCK_MASS / MIN_TRANSACTION_OUTPUT_SIZE;
+
+    /// Max length of a signature script or of a script public key
+    pub const MAX_SCRIPT_LEN: usize = MAX_BLOCK_MASS;
+
+    /// Max length of a transaction payload
+    pub const MAX_PAYLOAD_LEN: usize = MAX_BLOCK_MASS;
+}
+
 #[cfg(test)]
 mod tests {
     use super::consensus::{MAX_DIFFICULTY_TARGET, MAX_DIFFICULTY_TARGET_AS_F64};
+    use super::decoding::MAX_BLOCK_MASS;
+    use super::perf::PERF_PARAMS;
+    use crate::{
+        config::params::{DEVNET_PARAMS, MAINNET_PARAMS, SIMNET_PARAMS, TESTNET11_PARAMS, TESTNET_PARAMS},
+        mass::transaction_estimated_serialized_size,
+        subnets::SUBNETWORK_ID_NATIVE,
+        tx::{ScriptPublicKey, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput},
+    };
     use kaspa_math::Uint256;
 
     #[test]
@@ -221,4 +319,33 @@ mod tests {
         assert_eq!(MAX_DIFFICULTY_TARGET, Uint256::from_u64(1).wrapping_shl(255) - 1.into());
         assert_eq!(MAX_DIFFICULTY_TARGET_AS_F64, MAX_DIFFICULTY_TARGET.as_f64());
     }
+
+    #[test]
+    fn test_decoding_limits() {
+        for params in [MAINNET_PARAMS, TESTNET_PARAMS, TESTNET11_PARAMS, SIMNET_PARAMS, DEVNET_PARAMS] {
+            assert!(params.max_block_mass as usize <= MAX_BLOCK_MASS, "{}", params.net);
+            assert!(params.mass_per_tx_byte >= 1, "{}", params.net);
+        }
+
+        // The min sizes the limits are derived from
+        let mut tx = Transaction::new(0, vec![], vec![], 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+        assert_eq!(transaction_estimated_serialized_size(&tx), 94);
+        tx.inputs.push(TransactionInput::new(TransactionOutpoint::new(1.into(), 0), vec![], 0, 0));
+        assert_eq!(transaction_estimated_serialized_size(&tx), 94 + 52);
+        tx.outputs.push(TransactionOutput::new(0, ScriptPublicKey::default()));
+        assert_eq!(transaction_estimated_serialized_size(&tx), 94 + 52 + 18);
+    }
+
+    #[test]
+    fn test_set_validation_threads() {
+        let threads = |num_threads| {
+            let mut perf_params = PERF_PARAMS;
+            perf_params.set_validation_threads(num_threads);
+            (perf_params.block_processors_num_threads, perf_params.virtual_processor_num_threads)
+        };
+        assert_eq!(threads(1), (1, 1));
+        assert_eq!(threads(2), (1, 1));
+        assert_eq!(threads(7), (4, 3));
+        assert_eq!(threads(16), (8, 8));
+    }
 }
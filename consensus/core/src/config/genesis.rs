@@ -1,6 +1,13 @@
-use crate::{block::Block, header::Header, subnets::SUBNETWORK_ID_COINBASE, tx::Transaction};
+use crate::{
+    block::Block,
+    header::Header,
+    muhash::MuHashExtensions,
+    subnets::SUBNETWORK_ID_COINBASE,
+    tx::{ScriptPublicKey, ScriptPublicKeyVersion, ScriptVec, Transaction, TransactionOutpoint, UtxoEntry},
+    utxo::utxo_collection::UtxoCollection,
+};
 use kaspa_hashes::{Hash, ZERO_HASH};
-use kaspa_muhash::EMPTY_MUHASH;
+use kaspa_muhash::{MuHash, EMPTY_MUHASH};
 
 /// The constants uniquely representing the genesis block
 #[derive(Clone, Debug)]
@@ -20,6 +27,46 @@ impl GenesisBlock {
     pub fn build_genesis_transactions(&self) -> Vec<Transaction> {
         vec![Transaction::new(0, Vec::new(), Vec::new(), 0, SUBNETWORK_ID_COINBASE, 0, self.coinbase_payload.to_vec())]
     }
+
+    /// Commits the genesis to `utxo_set` as its initial UTXO set, which changes the genesis hash
+    pub fn commit_to_utxo_set(&mut self, utxo_set: &UtxoCollection) {
+        let mut multiset = MuHash::new();
+        for (outpoint, entry) in utxo_set.iter() {
+            multiset.add_utxo(outpoint, entry);
+        }
+        self.utxo_commitment = multiset.finalize();
+        self.hash = Header::from(&*self).hash;
+    }
+}
+
+/// An amount allocated to a script public key in the genesis UTXO set, so that a development network starts with funded
+/// accounts. Allocations are static data in order to be part of the params
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GenesisAllocation {
+    pub script_public_key_version: ScriptPublicKeyVersion,
+    pub script: &'static [u8],
+    pub amount: u64,
+}
+
+impl GenesisAllocation {
+    pub fn script_public_key(&self) -> ScriptPublicKey {
+        ScriptPublicKey::new(self.script_public_key_version, ScriptVec::from_slice(self.script))
+    }
+}
+
+/// Returns the UTXOs materializing `allocations` in the genesis UTXO set. Allocations are outputs of the zero
+/// transaction id, which is not the id of any actual transaction
+pub fn genesis_allocation_utxos(allocations: &[GenesisAllocation]) -> UtxoCollection {
+    allocations
+        .iter()
+        .enumerate()
+        .map(|(index, allocation)| {
+            (
+                TransactionOutpoint::new(ZERO_HASH, index as u32),
+                UtxoEntry::new(allocation.amount, allocation.script_public_key(), 0, false),
+            )
+        })
+        .collect()
 }
 
 impl From<&GenesisBlock> for Header {
@@ -222,6 +269,27 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_genesis_allocations() {
+        let allocations = [
+            GenesisAllocation { script_public_key_version: 0, script: &[0x51], amount: 100 },
+            GenesisAllocation { script_public_key_version: 0, script: &[0x52], amount: 200 },
+        ];
+        let utxo_set = genesis_allocation_utxos(&allocations);
+        assert_eq!(utxo_set.len(), 2);
+        let entry = &utxo_set[&TransactionOutpoint::new(ZERO_HASH, 1)];
+        assert_eq!((entry.amount, entry.script_public_key.script(), entry.block_daa_score), (200, &[0x52][..], 0));
+
+        // Committing to the allocations changes the genesis, while committing to an empty set keeps the original one
+        let mut genesis = DEVNET_GENESIS;
+        genesis.commit_to_utxo_set(&utxo_set);
+        assert_ne!(genesis.utxo_commitment, EMPTY_MUHASH);
+        assert_eq!(genesis.hash, Block::from(&genesis).hash());
+        assert_ne!(genesis.hash, DEVNET_GENESIS.hash);
+        genesis.commit_to_utxo_set(&UtxoCollection::new());
+        assert_eq!(genesis.hash, DEVNET_GENESIS.hash);
+    }
+
     #[test]
     fn gen_testnet11_genesis() {
         let bps = Testnet11Bps::bps();
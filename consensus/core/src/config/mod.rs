@@ -5,12 +5,17 @@ pub mod params;
 
 use kaspa_utils::networking::{ContextualNetAddress, NetAddress};
 
-#[cfg(feature = "devnet-prealloc")]
-use crate::utxo::utxo_collection::UtxoCollection;
 #[cfg(feature = "devnet-prealloc")]
 use std::sync::Arc;
 
-use std::ops::Deref;
+use crate::{state_digest::DEFAULT_STATE_DIGEST_INTERVAL, utxo::utxo_collection::UtxoCollection};
+use std::{ops::Deref, path::PathBuf};
+
+/// The default stale tip timeout in seconds, i.e., the detection is disabled unless configured
+pub const DEFAULT_STALE_TIP_TIMEOUT: u64 = 0;
+
+/// The default block processing budget in milliseconds
+pub const DEFAULT_BLOCK_PROCESSING_BUDGET: u64 = 1000;
 
 use {
     constants::perf::{PerfParams, PERF_PARAMS},
@@ -30,6 +35,8 @@ pub struct Config {
     //
     // Additional consensus configuration arguments which are not consensus sensitive
     //
+    /// Indicates whether a new consensus is rooted at genesis. Otherwise, the consensus is rooted at the pruning point
+    /// (and its trusted anticone) later imported through the pruning point proof, and genesis is never processed
     pub process_genesis: bool,
 
     /// Indicates whether this node is an archival node
@@ -42,6 +49,9 @@ pub struct Config {
     /// Enable the UTXO index
     pub utxoindex: bool,
 
+    /// Enable the transaction index
+    pub txindex: bool,
+
     /// Enable RPC commands which affect the state of the node
     pub unsafe_rpc: bool,
 
@@ -64,6 +74,61 @@ pub struct Config {
     #[cfg(feature = "devnet-prealloc")]
     pub initial_utxo_set: Arc<UtxoCollection>,
     pub disable_upnp: bool,
+
+    /// Disable biasing outbound peer selection toward distinct network groups (useful for private topologies)
+    pub disable_netgroup_diversity: bool,
+
+    /// A SOCKS5 proxy (e.g., Tor) through which outbound P2P connections are dialed
+    pub p2p_proxy: Option<NetAddress>,
+
+    /// Only dial onion addresses through the proxy and only accept inbound connections relayed by a local onion
+    /// service (i.e., from loopback), never connecting to clearnet peers
+    pub disable_clearnet: bool,
+
+    /// The time in seconds without the sink timestamp advancing after which outbound peers are rotated if other
+    /// peers are ahead of the node (0 disables the detection)
+    pub stale_tip_timeout: u64,
+
+    /// Relay blocks to supporting peers in compact form (short transaction ids rebuilt from the mempool)
+    pub compact_block_relay: bool,
+
+    /// Run as a header-only light node, skipping block bodies and UTXO tracking altogether
+    pub headers_only: bool,
+
+    /// A local pruning point UTXO set snapshot to import during IBD instead of downloading the set from the syncer
+    pub utxo_snapshot_path: Option<PathBuf>,
+
+    /// A directory to which the bodies of pruned blocks are exported as era files before being deleted from the database
+    pub pruned_archive_dir: Option<PathBuf>,
+
+    /// Record the rules evaluated while validating recent blocks along with their timings
+    pub trace_validation: bool,
+
+    /// The time in milliseconds above which the processing of a block by any pipeline stage (header, body or UTXO
+    /// validation of chain blocks) is logged along with its dependency structure (0 disables the logging)
+    pub block_processing_budget: u64,
+
+    /// The blue score interval between consecutive state digest checkpoints
+    pub state_digest_interval: u64,
+
+    /// Tips whose DAA score lags the selected parent of virtual by more than this threshold are treated as stale
+    /// branches and are not merged by virtual (0 disables the policy)
+    pub stale_branch_daa_threshold: u64,
+
+    /// Blocks submitted over RPC whose DAA score lags the virtual DAA score by more than this threshold are rejected
+    /// as mined over a stale template. If undefined, the duration of the difficulty window in blocks is used
+    pub stale_template_daa_threshold: Option<u64>,
+
+    /// The number of blocks pending in the processing pipeline above which RPC requests for bulk data (blocks with
+    /// transactions, UTXO sets) are rejected (0 disables the policy)
+    pub rpc_shed_pipeline_threshold: u64,
+
+    /// The maximal number of RPC requests for bulk data served concurrently, further requests being rejected (0 for no limit)
+    pub rpc_max_heavy_requests: u64,
+
+    /// The number of most recent blocks whose GHOSTDAG, relations and reachability data is loaded into the caches
+    /// on startup (0 disables the warmup)
+    pub cache_warmup_blocks: u64,
 }
 
 impl Config {
@@ -79,6 +144,7 @@ impl Config {
             is_archival: false,
             enable_sanity_checks: false,
             utxoindex: false,
+            txindex: false,
             unsafe_rpc: false,
             enable_unsynced_mining: false,
             enable_mainnet_mining: false,
@@ -90,12 +156,46 @@ impl Config {
             #[cfg(feature = "devnet-prealloc")]
             initial_utxo_set: Default::default(),
             disable_upnp: false,
+            disable_netgroup_diversity: false,
+            p2p_proxy: None,
+            disable_clearnet: false,
+            stale_tip_timeout: DEFAULT_STALE_TIP_TIMEOUT,
+            compact_block_relay: false,
+            headers_only: false,
+            utxo_snapshot_path: None,
+            pruned_archive_dir: None,
+            trace_validation: false,
+            block_processing_budget: DEFAULT_BLOCK_PROCESSING_BUDGET,
+            state_digest_interval: DEFAULT_STATE_DIGEST_INTERVAL,
+            stale_branch_daa_threshold: 0,
+            stale_template_daa_threshold: None,
+            rpc_shed_pipeline_threshold: 0,
+            rpc_max_heavy_requests: 0,
+            cache_warmup_blocks: 0,
         }
     }
 
     pub fn to_builder(&self) -> ConfigBuilder {
         ConfigBuilder { config: self.clone() }
     }
+
+    /// Returns the UTXO set of genesis: the params genesis allocations along with any preallocated UTXOs
+    pub fn genesis_utxo_set(&self) -> UtxoCollection {
+        #[allow(unused_mut)]
+        let mut utxo_set = genesis::genesis_allocation_utxos(self.params.genesis_allocations);
+        #[cfg(feature = "devnet-prealloc")]
+        utxo_set.extend(self.initial_utxo_set.iter().map(|(outpoint, entry)| (*outpoint, entry.clone())));
+        utxo_set
+    }
+
+    /// Commits the genesis to its UTXO set. Genesis is left untouched when the set is empty, which is the case for all
+    /// public networks
+    pub fn commit_genesis_to_utxo_set(&mut self) {
+        let utxo_set = self.genesis_utxo_set();
+        if !utxo_set.is_empty() {
+            self.params.genesis.commit_to_utxo_set(&utxo_set);
+        }
+    }
 }
 
 impl AsRef<Params> for Config {
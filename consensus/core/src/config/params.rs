@@ -1,9 +1,11 @@
 pub use super::{
     bps::{Bps, Testnet11Bps},
     constants::consensus::*,
-    genesis::{GenesisBlock, DEVNET_GENESIS, GENESIS, SIMNET_GENESIS, TESTNET11_GENESIS, TESTNET_GENESIS},
+    genesis::{GenesisAllocation, GenesisBlock, DEVNET_GENESIS, GENESIS, SIMNET_GENESIS, TESTNET11_GENESIS, TESTNET_GENESIS},
 };
 use crate::{
+    config::bps::calculate_ghostdag_k,
+    constants::MAX_SCRIPT_PUBLIC_KEY_VERSION,
     network::{NetworkId, NetworkType},
     BlockLevel, KType,
 };
@@ -22,6 +24,8 @@ pub struct Params {
     pub dns_seeders: &'static [&'static str],
     pub net: NetworkId,
     pub genesis: GenesisBlock,
+    /// Amounts allocated at genesis, committed to by the genesis UTXO commitment (see [`crate::config::Config::commit_genesis_to_utxo_set`])
+    pub genesis_allocations: &'static [GenesisAllocation],
     pub ghostdag_k: KType,
 
     /// Legacy timestamp deviation tolerance (in seconds)
@@ -66,6 +70,11 @@ pub struct Params {
     pub finality_depth: u64,
     pub pruning_depth: u64,
     pub coinbase_payload_script_public_key_max_len: u8,
+
+    /// The highest script version allowed for the script public key of block templates. This is a mining policy rather
+    /// than a consensus rule, coinbase payloads with higher versions remaining valid
+    pub coinbase_payload_script_public_key_max_version: u16,
+
     pub max_coinbase_payload_len: usize,
     pub max_tx_inputs: usize,
     pub max_tx_outputs: usize,
@@ -84,6 +93,14 @@ pub struct Params {
     pub skip_proof_of_work: bool,
     pub max_block_level: BlockLevel,
     pub pruning_proof_m: u64,
+
+    /// Whether the mempool enforces the standardness policy of scripts (see `kaspa_txscript::policy`) on the
+    /// transactions it admits. Consensus script rules are enforced regardless
+    pub enforce_script_policy: bool,
+
+    /// Whether the script engine executes the experimental transaction introspection opcodes (see
+    /// `kaspa_txscript::opcodes::introspection`). These are not part of the consensus of any public network
+    pub introspection_opcodes: bool,
 }
 
 fn unix_now() -> u64 {
@@ -91,6 +108,72 @@ fn unix_now() -> u64 {
 }
 
 impl Params {
+    /// Returns hash-rate independent params for building large DAGs quickly in tests and simulations.
+    /// Proof of work is skipped, the difficulty and median time windows are tiny and the BPS dependent
+    /// constants are derived from `bps`. The simnet genesis is used, so the DAG is rooted at a fixed
+    /// hash and timestamp. Pair with `TestConsensus::with_simulated_clock` for a difficulty independent of the building rate
+    pub fn for_testing(bps: u64) -> Self {
+        assert!(bps > 0 && 1000 % bps == 0, "target_time_per_block is in milliseconds hence BPS must divide 1000 with no remainder");
+        let k = calculate_ghostdag_k(2.0 * NETWORK_DELAY_BOUND as f64 * bps as f64, GHOSTDAG_TAIL_DELTA).min(KType::MAX as u64);
+        let mut params = Params {
+            dns_seeders: &[],
+            ghostdag_k: k as KType,
+            target_time_per_block: 1000 / bps,
+            sampling_activation_daa_score: 0,
+            past_median_time_sample_rate: 1,
+            past_median_time_sampled_window_size: 11,
+            new_timestamp_deviation_tolerance: 6,
+            difficulty_sample_rate: 1,
+            sampled_difficulty_window_size: 16,
+            min_difficulty_window_len: MIN_DIFFICULTY_WINDOW_LEN,
+            max_block_parents: u8::max((k / 2).min(u8::MAX as u64) as u8, 10),
+            mergeset_size_limit: k * 10,
+            merge_depth: bps * 60,
+            finality_depth: bps * 120,
+            coinbase_maturity: 10,
+            skip_proof_of_work: true,
+            ..SIMNET_PARAMS
+        };
+        params.pruning_depth = params.finality_depth
+            + params.merge_depth * 2
+            + 4 * params.mergeset_size_limit * params.ghostdag_k as u64
+            + 2 * params.ghostdag_k as u64
+            + 2;
+        params
+    }
+
+    /// Returns a copy of these params with all BPS dependent constants (GHOSTDAG K, depths, sample rates,
+    /// subsidy and mass limits) derived from `BPS`
+    pub const fn with_bps<const BPS: u64>(self) -> Self {
+        Params {
+            ghostdag_k: Bps::<BPS>::ghostdag_k(),
+            // Rounded down (rather than rejected as in `Bps::target_time_per_block`) for BPS values not dividing 1000
+            target_time_per_block: 1000 / BPS,
+            past_median_time_sample_rate: Bps::<BPS>::past_median_time_sample_rate(),
+            difficulty_sample_rate: Bps::<BPS>::difficulty_adjustment_sample_rate(),
+            max_block_parents: Bps::<BPS>::max_block_parents(),
+            mergeset_size_limit: Bps::<BPS>::mergeset_size_limit(),
+            merge_depth: Bps::<BPS>::merge_depth_bound(),
+            finality_depth: Bps::<BPS>::finality_depth(),
+            pruning_depth: Bps::<BPS>::pruning_depth(),
+            pruning_proof_m: Bps::<BPS>::pruning_proof_m(),
+            deflationary_phase_daa_score: Bps::<BPS>::deflationary_phase_daa_score(),
+            pre_deflationary_phase_base_subsidy: Bps::<BPS>::pre_deflationary_phase_base_subsidy(),
+            coinbase_maturity: Bps::<BPS>::coinbase_maturity(),
+            max_block_mass: Bps::<BPS>::max_block_mass(),
+            ..self
+        }
+    }
+
+    /// Returns the high-throughput simnet preset for the given BPS, if one exists
+    pub fn high_bps_preset(bps: u64) -> Option<Self> {
+        match bps {
+            10 => Some(SIMNET_10BPS_PARAMS),
+            32 => Some(SIMNET_32BPS_PARAMS),
+            _ => None,
+        }
+    }
+
     /// Returns the size of the full blocks window that is inspected to calculate the past median time (legacy)
     #[inline]
     #[must_use]
@@ -238,6 +321,11 @@ impl Params {
         self.net.default_rpc_port()
     }
 
+    /// The magic number identifying the network in the P2P handshake
+    pub fn net_magic(&self) -> u32 {
+        self.net.magic()
+    }
+
     pub fn finality_duration(&self) -> u64 {
         self.target_time_per_block * self.finality_depth
     }
@@ -293,6 +381,7 @@ pub const MAINNET_PARAMS: Params = Params {
     ],
     net: NetworkId::new(NetworkType::Mainnet),
     genesis: GENESIS,
+    genesis_allocations: &[],
     ghostdag_k: LEGACY_DEFAULT_GHOSTDAG_K,
     legacy_timestamp_deviation_tolerance: LEGACY_TIMESTAMP_DEVIATION_TOLERANCE,
     new_timestamp_deviation_tolerance: NEW_TIMESTAMP_DEVIATION_TOLERANCE,
@@ -312,6 +401,7 @@ pub const MAINNET_PARAMS: Params = Params {
     finality_depth: 86400,
     pruning_depth: 185798,
     coinbase_payload_script_public_key_max_len: 150,
+    coinbase_payload_script_public_key_max_version: MAX_SCRIPT_PUBLIC_KEY_VERSION,
     max_coinbase_payload_len: 204,
 
     // This is technically a soft fork from the Go implementation since kaspad's consensus doesn't
@@ -340,6 +430,8 @@ pub const MAINNET_PARAMS: Params = Params {
     skip_proof_of_work: false,
     max_block_level: 225,
     pruning_proof_m: 1000,
+    enforce_script_policy: true,
+    introspection_opcodes: false,
 };
 
 pub const TESTNET_PARAMS: Params = Params {
@@ -349,6 +441,7 @@ pub const TESTNET_PARAMS: Params = Params {
     ],
     net: NetworkId::with_suffix(NetworkType::Testnet, 10),
     genesis: TESTNET_GENESIS,
+    genesis_allocations: &[],
     ghostdag_k: LEGACY_DEFAULT_GHOSTDAG_K,
     legacy_timestamp_deviation_tolerance: LEGACY_TIMESTAMP_DEVIATION_TOLERANCE,
     new_timestamp_deviation_tolerance: NEW_TIMESTAMP_DEVIATION_TOLERANCE,
@@ -368,6 +461,7 @@ pub const TESTNET_PARAMS: Params = Params {
     finality_depth: 86400,
     pruning_depth: 185798,
     coinbase_payload_script_public_key_max_len: 150,
+    coinbase_payload_script_public_key_max_version: MAX_SCRIPT_PUBLIC_KEY_VERSION,
     max_coinbase_payload_len: 204,
 
     // This is technically a soft fork from the Go implementation since kaspad's consensus doesn't
@@ -396,6 +490,8 @@ pub const TESTNET_PARAMS: Params = Params {
     skip_proof_of_work: false,
     max_block_level: 250,
     pruning_proof_m: 1000,
+    enforce_script_policy: true,
+    introspection_opcodes: false,
 };
 
 pub const TESTNET11_PARAMS: Params = Params {
@@ -405,6 +501,7 @@ pub const TESTNET11_PARAMS: Params = Params {
     ],
     net: NetworkId::with_suffix(NetworkType::Testnet, 11),
     genesis: TESTNET11_GENESIS,
+    genesis_allocations: &[],
     legacy_timestamp_deviation_tolerance: LEGACY_TIMESTAMP_DEVIATION_TOLERANCE,
     new_timestamp_deviation_tolerance: NEW_TIMESTAMP_DEVIATION_TOLERANCE,
     past_median_time_sampled_window_size: MEDIAN_TIME_SAMPLED_WINDOW_SIZE,
@@ -428,11 +525,14 @@ pub const TESTNET11_PARAMS: Params = Params {
     finality_depth: Testnet11Bps::finality_depth(),
     pruning_depth: Testnet11Bps::pruning_depth(),
     pruning_proof_m: Testnet11Bps::pruning_proof_m(),
+    enforce_script_policy: true,
+    introspection_opcodes: false,
     deflationary_phase_daa_score: Testnet11Bps::deflationary_phase_daa_score(),
     pre_deflationary_phase_base_subsidy: Testnet11Bps::pre_deflationary_phase_base_subsidy(),
     coinbase_maturity: Testnet11Bps::coinbase_maturity(),
 
     coinbase_payload_script_public_key_max_len: 150,
+    coinbase_payload_script_public_key_max_version: MAX_SCRIPT_PUBLIC_KEY_VERSION,
     max_coinbase_payload_len: 204,
 
     // This is technically a soft fork from the Go implementation since kaspad's consensus doesn't
@@ -457,6 +557,7 @@ pub const SIMNET_PARAMS: Params = Params {
     dns_seeders: &[],
     net: NetworkId::new(NetworkType::Simnet),
     genesis: SIMNET_GENESIS,
+    genesis_allocations: &[],
     legacy_timestamp_deviation_tolerance: LEGACY_TIMESTAMP_DEVIATION_TOLERANCE,
     new_timestamp_deviation_tolerance: NEW_TIMESTAMP_DEVIATION_TOLERANCE,
     past_median_time_sampled_window_size: MEDIAN_TIME_SAMPLED_WINDOW_SIZE,
@@ -481,11 +582,14 @@ pub const SIMNET_PARAMS: Params = Params {
     finality_depth: Testnet11Bps::finality_depth(),
     pruning_depth: Testnet11Bps::pruning_depth(),
     pruning_proof_m: Testnet11Bps::pruning_proof_m(),
+    enforce_script_policy: false,
+    introspection_opcodes: false,
     deflationary_phase_daa_score: Testnet11Bps::deflationary_phase_daa_score(),
     pre_deflationary_phase_base_subsidy: Testnet11Bps::pre_deflationary_phase_base_subsidy(),
     coinbase_maturity: Testnet11Bps::coinbase_maturity(),
 
     coinbase_payload_script_public_key_max_len: 150,
+    coinbase_payload_script_public_key_max_version: MAX_SCRIPT_PUBLIC_KEY_VERSION,
     max_coinbase_payload_len: 204,
 
     // This is technically a soft fork from the Go implementation since kaspad's consensus doesn't
@@ -510,6 +614,7 @@ pub const DEVNET_PARAMS: Params = Params {
     dns_seeders: &[],
     net: NetworkId::new(NetworkType::Devnet),
     genesis: DEVNET_GENESIS,
+    genesis_allocations: &[],
     ghostdag_k: LEGACY_DEFAULT_GHOSTDAG_K,
     legacy_timestamp_deviation_tolerance: LEGACY_TIMESTAMP_DEVIATION_TOLERANCE,
     new_timestamp_deviation_tolerance: NEW_TIMESTAMP_DEVIATION_TOLERANCE,
@@ -529,6 +634,7 @@ pub const DEVNET_PARAMS: Params = Params {
     finality_depth: 86400,
     pruning_depth: 185798,
     coinbase_payload_script_public_key_max_len: 150,
+    coinbase_payload_script_public_key_max_version: MAX_SCRIPT_PUBLIC_KEY_VERSION,
     max_coinbase_payload_len: 204,
 
     // This is technically a soft fork from the Go implementation since kaspad's consensus doesn't
@@ -557,4 +663,38 @@ pub const DEVNET_PARAMS: Params = Params {
     skip_proof_of_work: false,
     max_block_level: 250,
     pruning_proof_m: 1000,
+    enforce_script_policy: false,
+    introspection_opcodes: true,
 };
+
+/// High-throughput simnet preset for studying 10 BPS networks
+pub const SIMNET_10BPS_PARAMS: Params = SIMNET_PARAMS.with_bps::<10>();
+
+/// High-throughput simnet preset for studying 32 BPS networks
+pub const SIMNET_32BPS_PARAMS: Params = SIMNET_PARAMS.with_bps::<32>();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_high_bps_presets() {
+        for (bps, params) in [(10, SIMNET_10BPS_PARAMS), (32, SIMNET_32BPS_PARAMS)] {
+            assert_eq!(params.bps(), bps);
+            assert_eq!(
+                params.ghostdag_k as u64,
+                calculate_ghostdag_k(2.0 * NETWORK_DELAY_BOUND as f64 * bps as f64, GHOSTDAG_TAIL_DELTA)
+            );
+            // Blocks must be prunable only once their anticone is final
+            assert!(
+                params.pruning_depth
+                    > params.finality_depth + params.merge_depth + 4 * params.mergeset_size_limit * params.ghostdag_k as u64
+            );
+            assert!(params.merge_depth < params.finality_depth);
+            // The mass throughput per second is bounded by that of a 10 BPS network
+            assert!(params.max_block_mass * bps <= 10 * 500_000);
+            assert_eq!(params.net, SIMNET_PARAMS.net);
+        }
+        assert!(Params::high_bps_preset(5).is_none());
+    }
+}
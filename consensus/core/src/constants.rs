@@ -1,6 +1,12 @@
 /// BLOCK_VERSION represents the current block version
 pub const BLOCK_VERSION: u16 = 1;
 
+/// VALIDATION_VERSION identifies the version of the block validation logic and is stamped on every block status
+/// written, so that blocks rejected by a version later found to be buggy can be selectively revalidated. Blocks
+/// accepted by such a version are not revalidated, since undoing their effect on the UTXO state requires a resync.
+/// It must be bumped along with any fix to the validation logic
+pub const VALIDATION_VERSION: u16 = 1;
+
 /// TX_VERSION is the current latest supported transaction version.
 pub const TX_VERSION: u16 = 0;
 
@@ -0,0 +1,33 @@
+use kaspa_hashes::Hash;
+
+/// The timing and difficulty data consensus derives for a block, used to diagnose `bad-diff-bits` and
+/// timestamp rejections reported between implementations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockDagTimingInfo {
+    pub hash: Hash,
+    pub timestamp: u64,
+    pub daa_score: u64,
+    /// The bits found in the block header
+    pub bits: u32,
+    /// The bits consensus expects the header to carry, as computed from the difficulty window
+    pub expected_bits: u32,
+    pub past_median_time: u64,
+    pub difficulty_window: DifficultyWindowInfo,
+}
+
+/// The boundaries of the difficulty window of a block. Windows are ordered by blue work, so the boundaries are
+/// the blocks with the lowest and the highest blue work in the window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DifficultyWindowInfo {
+    /// The number of blocks in the window
+    pub size: u64,
+    pub sample_rate: u64,
+    /// The block with the lowest blue work in the window, or `None` if the window is empty
+    pub lowest_block: Option<Hash>,
+    /// The block with the highest blue work in the window, or `None` if the window is empty
+    pub highest_block: Option<Hash>,
+    /// The minimal timestamp among the window blocks, or zero if the window is empty
+    pub min_timestamp: u64,
+    /// The maximal timestamp among the window blocks, or zero if the window is empty
+    pub max_timestamp: u64,
+}
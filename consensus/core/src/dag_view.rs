@@ -0,0 +1,18 @@
+use kaspa_hashes::Hash;
+
+/// A block of a DAG neighborhood along with its relations and GHOSTDAG coloring, as needed to draw the DAG
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DagBlockInfo {
+    pub hash: Hash,
+    /// The number of parent or child edges separating the block from the block the neighborhood is centered on
+    pub distance: u32,
+    pub timestamp: u64,
+    pub daa_score: u64,
+    pub blue_score: u64,
+    pub parents: Vec<Hash>,
+    pub children: Vec<Hash>,
+    pub selected_parent: Hash,
+    pub mergeset_blues: Vec<Hash>,
+    pub mergeset_reds: Vec<Hash>,
+    pub is_chain_block: bool,
+}
@@ -97,6 +97,15 @@ pub enum RuleError {
     #[error("coinbase blue score of {0} is not the expected value of {1}")]
     BadCoinbasePayloadBlueScore(u64, u64),
 
+    #[error("coinbase payload length is {0} while the maximum allowed length is {1}")]
+    CoinbasePayloadLenAboveMax(usize, usize),
+
+    #[error("coinbase payload script public key length is {0} while the maximum allowed length is {1}")]
+    CoinbasePayloadScriptPublicKeyLenAboveMax(usize, u8),
+
+    #[error("coinbase payload script public key version is {0} while the maximum allowed version is {1}")]
+    CoinbasePayloadScriptPublicKeyVersionAboveMax(u16, u16),
+
     #[error("transaction in isolation validation failed for tx {0}: {1}")]
     TxInIsolationValidationFailed(TransactionId, TxRuleError),
 
@@ -144,6 +153,136 @@ pub enum RuleError {
 
     #[error("DAA window data has only {0} entries")]
     InsufficientDaaWindowSize(usize),
+
+    #[error("trusted block {0} is not part of the trusted set applied along with the pruning point proof")]
+    TrustedBlockNotInAppliedSet(Hash),
+
+    #[error("block processing panicked: {0}")]
+    ProcessingPanicked(String),
+}
+
+/// The action to be taken against a peer which relayed a block violating a consensus rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BanSeverity {
+    /// The violation does not imply misbehavior (e.g. the block is an orphan or local data is missing)
+    None,
+    /// The violation is relative to the local state of the DAG, hence the peer might be honest but is not useful
+    Disconnect,
+    /// The block is provably invalid regardless of the local state
+    Ban,
+}
+
+impl RuleError {
+    /// Maps a coinbase payload error to the rule error reporting it. Violations of the payload limits set by the params
+    /// are reported distinctly, pointing at a misconfigured miner
+    pub fn from_coinbase_payload_error(err: CoinbaseError) -> Self {
+        match err {
+            CoinbaseError::PayloadLenAboveMax(len, max) => Self::CoinbasePayloadLenAboveMax(len, max),
+            CoinbaseError::PayloadScriptPublicKeyLenAboveMax(len, max) => Self::CoinbasePayloadScriptPublicKeyLenAboveMax(len, max),
+            CoinbaseError::PayloadScriptPublicKeyVersionAboveMax(version, max) => {
+                Self::CoinbasePayloadScriptPublicKeyVersionAboveMax(version, max)
+            }
+            err => Self::BadCoinbasePayload(err),
+        }
+    }
+
+    /// A stable numeric code identifying the violated rule. Codes are grouped by validation stage:
+    /// 1xx for header rules, 2xx for body rules and 3xx for rules validated in UTXO context.
+    /// Codes are never reassigned, new rules get new codes
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::WrongBlockVersion(_) => 101,
+            Self::TimeTooFarIntoTheFuture(_, _) => 102,
+            Self::NoParents => 103,
+            Self::TooManyParents(_, _) => 104,
+            Self::OriginParent => 105,
+            Self::InvalidParentsRelation(_, _) => 106,
+            Self::InvalidParent(_) => 107,
+            Self::MissingParents(_) => 108,
+            Self::PruningViolation(_) => 109,
+            Self::UnexpectedHeaderDaaScore(_, _) => 110,
+            Self::UnexpectedHeaderBlueScore(_, _) => 111,
+            Self::UnexpectedHeaderBlueWork(_, _) => 112,
+            Self::UnexpectedDifficulty(_, _) => 113,
+            Self::TimeTooOld(_, _) => 114,
+            Self::KnownInvalid => 115,
+            Self::MergeSetTooBig(_, _) => 116,
+            Self::ViolatingBoundedMergeDepth => 117,
+            Self::InvalidPoW => 118,
+            Self::WrongHeaderPruningPoint(_, _) => 119,
+            Self::UnexpectedIndirectParents(_, _) => 120,
+            Self::InsufficientDaaWindowSize(_) => 121,
+            Self::TrustedBlockNotInAppliedSet(_) => 122,
+            Self::ProcessingPanicked(_) => 123,
+
+            Self::BadMerkleRoot(_, _) => 201,
+            Self::NoTransactions => 202,
+            Self::FirstTxNotCoinbase => 203,
+            Self::MultipleCoinbases(_) => 204,
+            Self::BadCoinbasePayload(_) => 205,
+            Self::BadCoinbasePayloadBlueScore(_, _) => 206,
+            Self::TxInIsolationValidationFailed(_, _) => 207,
+            Self::ExceedsMassLimit(_) => 208,
+            Self::DoubleSpendInSameBlock(_) => 209,
+            Self::ChainedTransaction(_) => 210,
+            Self::TxInContextFailed(_, _) => 211,
+            Self::DuplicateTransactions(_) => 212,
+            Self::CoinbasePayloadLenAboveMax(_, _) => 213,
+            Self::CoinbasePayloadScriptPublicKeyLenAboveMax(_, _) => 214,
+            Self::CoinbasePayloadScriptPublicKeyVersionAboveMax(_, _) => 215,
+
+            Self::WrongSubsidy(_, _) => 301,
+            Self::BadUTXOCommitment(_, _, _) => 302,
+            Self::BadAcceptedIDMerkleRoot(_, _, _) => 303,
+            Self::BadCoinbaseTransaction => 304,
+            Self::InvalidTransactionsInUtxoContext(_, _) => 305,
+            Self::InvalidTransactionsInNewBlock(_) => 306,
+        }
+    }
+
+    /// The code of the transaction rule violated, if the block was rejected due to an invalid transaction
+    pub fn tx_rule_code(&self) -> Option<u16> {
+        match self {
+            Self::TxInIsolationValidationFailed(_, err) | Self::TxInContextFailed(_, err) => Some(err.code()),
+            _ => None,
+        }
+    }
+
+    pub fn ban_severity(&self) -> BanSeverity {
+        match self {
+            Self::MissingParents(_)
+            | Self::TimeTooFarIntoTheFuture(_, _)
+            | Self::InsufficientDaaWindowSize(_)
+            | Self::TrustedBlockNotInAppliedSet(_)
+            | Self::ProcessingPanicked(_)
+            | Self::InvalidTransactionsInNewBlock(_) => BanSeverity::None,
+            Self::PruningViolation(_) | Self::ViolatingBoundedMergeDepth => BanSeverity::Disconnect,
+            _ => BanSeverity::Ban,
+        }
+    }
 }
 
 pub type BlockProcessResult<T> = std::result::Result<T, RuleError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_error_codes() {
+        // Codes are part of the RPC and P2P interfaces, so they must never change
+        assert_eq!(RuleError::WrongBlockVersion(2).code(), 101);
+        assert_eq!(RuleError::BadMerkleRoot(Hash::default(), Hash::default()).code(), 201);
+        assert_eq!(RuleError::WrongSubsidy(1, 2).code(), 301);
+
+        // Blocks rejected due to an invalid transaction carry the code of the transaction rule
+        let err = RuleError::TxInContextFailed(TransactionId::default(), TxRuleError::NoTxInputs);
+        assert_eq!(err.code(), 211);
+        assert_eq!(err.tx_rule_code(), Some(1001));
+        assert_eq!(RuleError::WrongSubsidy(1, 2).tx_rule_code(), None);
+
+        assert_eq!(err.ban_severity(), BanSeverity::Ban);
+        assert_eq!(RuleError::MissingParents(vec![]).ban_severity(), BanSeverity::None);
+        assert_eq!(RuleError::ViolatingBoundedMergeDepth.ban_severity(), BanSeverity::Disconnect);
+    }
+}
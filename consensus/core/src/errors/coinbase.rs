@@ -11,6 +11,9 @@ pub enum CoinbaseError {
     #[error("coinbase payload script public key length is {0} while the maximum allowed length is {1}")]
     PayloadScriptPublicKeyLenAboveMax(usize, u8),
 
+    #[error("coinbase payload script public key version is {0} while the maximum allowed version is {1}")]
+    PayloadScriptPublicKeyVersionAboveMax(u16, u16),
+
     #[error("coinbase payload length is {0} bytes but it needs to be at least {1} bytes long in order to accommodate the script public key")]
     PayloadCantContainScriptPublicKey(usize, usize),
 }
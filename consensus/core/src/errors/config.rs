@@ -8,6 +8,27 @@ pub enum ConfigError {
     #[error("Configuration: --logdir and --nologfiles cannot be used together")]
     MixedLogDirAndNoLogFiles,
 
+    #[error("Configuration: --headers-only cannot be used together with --utxoindex or --txindex")]
+    MixedHeadersOnlyAndIndexes,
+
+    #[error("Configuration: --export-state and --import-state cannot be used together")]
+    MixedExportAndImportState,
+
+    #[error("Configuration: --journal-listen requires --journal")]
+    JournalListenWithoutJournal,
+
+    #[error("Configuration: --coinbase-tag is {0} bytes long while at most {1} bytes are allowed")]
+    CoinbaseTagTooLong(usize, usize),
+
+    #[error("Configuration: --produce-blocks-address is only supported on devnet and simnet")]
+    BlockProductionOnNonDevnet,
+
+    #[error("Configuration: --produce-blocks-address {0} is not a valid address of the configured network")]
+    InvalidBlockProductionAddress(String),
+
+    #[error("Configuration: --produce-blocks-rate {0} is not within (0, {1}] blocks per second")]
+    InvalidBlockProductionRate(f64, f64),
+
     #[cfg(feature = "devnet-prealloc")]
     #[error("Cannot preallocate UTXOs on any network except devnet")]
     PreallocUtxosOnNonDevnet,
@@ -15,6 +36,15 @@ pub enum ConfigError {
     #[cfg(feature = "devnet-prealloc")]
     #[error("--num-prealloc-utxos has to appear with --prealloc-address and vice versa")]
     MissingPreallocNumOrAddress,
+
+    #[error("Cannot allocate genesis UTXOs on any network except devnet and simnet")]
+    GenesisAllocationOnNonDevnet,
+
+    #[error("Configuration: --genesis-allocation address {0} does not belong to the configured network")]
+    InvalidGenesisAllocationAddress(String),
+
+    #[error("Configuration: genesis allocations and preallocated UTXOs exceed the maximal supply")]
+    GenesisAllocationsExceedMaxSupply,
 }
 
 pub type ConfigResult<T> = std::result::Result<T, ConfigError>;
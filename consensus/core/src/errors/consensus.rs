@@ -1,6 +1,8 @@
 use kaspa_hashes::Hash;
 use thiserror::Error;
 
+use crate::tx::TransactionId;
+
 use super::{difficulty::DifficultyError, sync::SyncManagerError, traversal::TraversalError};
 
 #[derive(Error, Debug, Clone)]
@@ -17,6 +19,12 @@ pub enum ConsensusError {
     #[error("some data is missing for block {0}")]
     MissingData(Hash),
 
+    #[error("block {0} is not quarantined")]
+    BlockNotQuarantined(Hash),
+
+    #[error("transactions of block {0} do not match its header merkle root")]
+    BlockTransactionsMismatch(Hash),
+
     #[error("got unexpected pruning point")]
     UnexpectedPruningPoint,
 
@@ -32,6 +40,24 @@ pub enum ConsensusError {
     #[error("difficulty error: {0}")]
     DifficultyError(#[from] DifficultyError),
 
+    #[error("UTXO set snapshot {0} is unknown or expired")]
+    UtxoSetSnapshotNotFound(u64),
+
+    #[error("cannot pin more than {0} UTXO set snapshots concurrently")]
+    TooManyUtxoSetSnapshots(usize),
+
+    #[error("block {0} is not on the selected chain")]
+    NotChainBlock(Hash),
+
+    #[error("transaction {0} is not accepted by block {1}")]
+    TransactionNotAccepted(TransactionId, Hash),
+
+    #[error("block {0} is not in the future of the finality point hence cannot be invalidated")]
+    InvalidationBeyondFinality(Hash),
+
+    #[error("block {0} was not invalidated")]
+    BlockNotInvalidated(Hash),
+
     #[error("{0}")]
     General(&'static str),
 }
@@ -42,6 +42,18 @@ pub enum PruningImportError {
     #[error("got header-only trusted block {0} which is not in pruning point past according to available reachability")]
     PruningPointPastMissingReachability(Hash),
 
+    #[error("the trusted set is missing the pruning point {0}")]
+    TrustedSetMissingPruningPoint(Hash),
+
+    #[error("the ghostdag data of trusted block {0} doesn't match the blue score and blue work of its header")]
+    TrustedBlockGhostdagMismatch(Hash),
+
+    #[error("the ghostdag selected parent {1} of trusted block {0} is not one of its direct parents")]
+    TrustedBlockSelectedParentNotAParent(Hash, Hash),
+
+    #[error("the ghostdag mergeset of trusted block {0} is malformed")]
+    TrustedBlockMalformedMergeset(Hash),
+
     #[error("new pruning point has an invalid transaction {0}: {1}")]
     NewPruningPointTxError(Hash, TxRuleError),
 
@@ -84,4 +84,37 @@ pub enum TxRuleError {
     WrongSigOpCount(usize, u64, u64),
 }
 
+impl TxRuleError {
+    /// A stable numeric code identifying the violated transaction rule. Codes are never reassigned, new rules get new codes
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::NoTxInputs => 1001,
+            Self::TxDuplicateInputs => 1002,
+            Self::TxHasGas => 1003,
+            Self::NonCoinbaseTxHasPayload => 1004,
+            Self::UnknownTxVersion(_) => 1005,
+            Self::TooManyInputs(_, _) => 1006,
+            Self::TooManyOutputs(_, _) => 1007,
+            Self::TooBigSignatureScript(_, _) => 1008,
+            Self::TooBigScriptPublicKey(_, _) => 1009,
+            Self::NotFinalized(_) => 1010,
+            Self::CoinbaseHasInputs(_) => 1011,
+            Self::CoinbaseTooManyOutputs(_, _) => 1012,
+            Self::CoinbaseScriptPublicKeyTooLong(_) => 1013,
+            Self::ImmatureCoinbaseSpend(_, _, _, _, _) => 1014,
+            Self::InputAmountOverflow => 1015,
+            Self::InputAmountTooHigh => 1016,
+            Self::TxOutZero(_) => 1017,
+            Self::TxOutTooHigh(_) => 1018,
+            Self::OutputsValueOverflow => 1019,
+            Self::TotalTxOutTooHigh => 1020,
+            Self::SpendTooHigh(_, _) => 1021,
+            Self::SequenceLockConditionsAreNotMet => 1022,
+            Self::MissingTxOutpoints => 1023,
+            Self::SignatureInvalid(_) => 1024,
+            Self::WrongSigOpCount(_, _, _) => 1025,
+        }
+    }
+}
+
 pub type TxResult<T> = std::result::Result<T, TxRuleError>;
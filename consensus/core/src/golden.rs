@@ -0,0 +1,138 @@
+//!
+//! Golden vectors pinning the consensus hashing domains (header hashes, hash merkle roots, transaction ids and
+//! hashes, and signature hashes) to the values computed by the Go implementation of kaspad.
+//!
+//! The vectors are recorded in `testdata/go_vectors.json`, each entry naming a sample which is rebuilt here from the
+//! same inputs the Go implementation was given. A failing entry means the Rust hashing diverged from the network,
+//! and must never be fixed by updating the recorded value.
+//!
+
+use crate::{
+    block::Block,
+    config::genesis::{GenesisBlock, DEVNET_GENESIS, GENESIS, SIMNET_GENESIS, TESTNET_GENESIS},
+    hashing::{
+        self,
+        sighash::{calc_schnorr_signature_hash, SigHashReusedValues},
+        sighash_type::SigHashType,
+    },
+    header::Header,
+    merkle::calc_hash_merkle_root,
+    subnets::{self, SubnetworkId, SUBNETWORK_ID_NATIVE},
+    tx::{
+        scriptvec, PopulatedTransaction, ScriptPublicKey, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput,
+        UtxoEntry,
+    },
+};
+use kaspa_hashes::Hash;
+use serde::Deserialize;
+use std::str::FromStr;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum Kind {
+    HeaderHash,
+    HashMerkleRoot,
+    TransactionId,
+    TransactionHash,
+    SigHash,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoVector {
+    name: String,
+    kind: Kind,
+    #[serde(default)]
+    hash_type: u8,
+    #[serde(default)]
+    input_index: usize,
+    expected: String,
+}
+
+fn genesis(name: &str) -> GenesisBlock {
+    match name {
+        "mainnet-genesis" => GENESIS,
+        "testnet-genesis" => TESTNET_GENESIS,
+        "simnet-genesis" => SIMNET_GENESIS,
+        // The Rust devnet genesis only differs by its (lower) difficulty bits
+        "devnet-genesis" => GenesisBlock { bits: 525264379, ..DEVNET_GENESIS },
+        name => panic!("no genesis sample is defined for the Go vector {name}"),
+    }
+}
+
+fn transaction(name: &str) -> Transaction {
+    let inputs = vec![TransactionInput::new(TransactionOutpoint::new(Hash::from_u64_word(0), 2), vec![1, 2], 7, 5)];
+    let outputs = vec![TransactionOutput::new(1564, ScriptPublicKey::new(7, scriptvec![1, 2, 3, 4, 5]))];
+    let spending_inputs = vec![TransactionInput::new(
+        TransactionOutpoint::new(Hash::from_str("59b3d6dc6cdc660c389c3fdb5704c48c598d279cdf1bab54182db586a4c95dd5").unwrap(), 2),
+        vec![1, 2],
+        7,
+        5,
+    )];
+    match name {
+        "empty" => Transaction::new(0, vec![], vec![], 0, SubnetworkId::from_byte(0), 0, vec![]),
+        "one-input" => Transaction::new(1, inputs, vec![], 0, SubnetworkId::from_byte(0), 0, vec![]),
+        "one-input-one-output" => Transaction::new(1, inputs, outputs, 0, SubnetworkId::from_byte(0), 0, vec![]),
+        "lock-time-and-gas" => Transaction::new(2, inputs, outputs, 54, SubnetworkId::from_byte(0), 3, vec![]),
+        "coinbase-subnetwork" => Transaction::new(2, spending_inputs, outputs, 54, subnets::SUBNETWORK_ID_COINBASE, 3, vec![]),
+        "registry-subnetwork" => Transaction::new(2, spending_inputs, outputs, 54, subnets::SUBNETWORK_ID_REGISTRY, 3, vec![]),
+        name => panic!("no transaction sample is defined for the Go vector {name}"),
+    }
+}
+
+/// Returns the transaction signed over along with the UTXO entries it spends
+fn signed_transaction(name: &str) -> (Transaction, Vec<UtxoEntry>) {
+    let prev_tx_id = Hash::from_str("880eb9819a31821d9d2399e2f35e2433b72637e393d71ecc9b8d0250f49153c3").unwrap();
+    let script_public_key = |hex: &str| {
+        let mut bytes = vec![0u8; hex.len() / 2];
+        faster_hex::hex_decode(hex.as_bytes(), &mut bytes).unwrap();
+        ScriptPublicKey::new(0, bytes.into())
+    };
+    let script_public_key_1 = script_public_key("208325613d2eeaf7176ac6c670b13c0043156c427438ed72d74b7800862ad884e8ac");
+    let script_public_key_2 = script_public_key("20fcef4c106cf11135bbd70f02a726a92162d2fb8b22f0469126f800862ad884e8ac");
+
+    let mut tx = Transaction::new(
+        0,
+        (0..3).map(|i| TransactionInput::new(TransactionOutpoint::new(prev_tx_id, i), vec![], i as u64, 0)).collect(),
+        vec![TransactionOutput::new(300, script_public_key_2.clone()), TransactionOutput::new(300, script_public_key_1.clone())],
+        1615462089000,
+        SUBNETWORK_ID_NATIVE,
+        0,
+        vec![],
+    );
+    let entries = vec![
+        UtxoEntry::new(100, script_public_key_1, 0, false),
+        UtxoEntry::new(200, script_public_key_2.clone(), 0, false),
+        UtxoEntry::new(300, script_public_key_2, 0, false),
+    ];
+    match name {
+        "native" => {}
+        "subnetwork" => {
+            tx.subnetwork_id = SubnetworkId::from_bytes([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            tx.gas = 250;
+            tx.payload = vec![10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20];
+        }
+        name => panic!("no signed transaction sample is defined for the Go vector {name}"),
+    }
+    (tx, entries)
+}
+
+#[test]
+fn test_go_vectors() {
+    let vectors: Vec<GoVector> = serde_json::from_str(include_str!("../testdata/go_vectors.json")).unwrap();
+    for vector in vectors {
+        let computed = match vector.kind {
+            Kind::HeaderHash => Header::from(&genesis(&vector.name)).hash,
+            Kind::HashMerkleRoot => calc_hash_merkle_root(Block::from(&genesis(&vector.name)).transactions.iter()),
+            Kind::TransactionId => transaction(&vector.name).id(),
+            Kind::TransactionHash => hashing::tx::hash(&transaction(&vector.name)),
+            Kind::SigHash => {
+                let (tx, entries) = signed_transaction(&vector.name);
+                let hash_type = SigHashType::from_u8(vector.hash_type).unwrap();
+                let populated = PopulatedTransaction::new(&tx, entries);
+                calc_schnorr_signature_hash(&populated, vector.input_index, hash_type, &mut SigHashReusedValues::new())
+            }
+        };
+        assert_eq!(computed.to_string(), vector.expected, "{:?} of {} diverged from the Go implementation", vector.kind, vector.name);
+    }
+}
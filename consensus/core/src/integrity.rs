@@ -0,0 +1,67 @@
+use kaspa_hashes::Hash;
+use std::fmt::Display;
+
+/// A cross-store invariant found broken by the consensus integrity verification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityViolationKind {
+    /// The block has no status although it is referenced by a valid chain block
+    MissingStatus,
+    /// The block is marked invalid although it is referenced by a valid chain block
+    InvalidStatus,
+    MissingHeader,
+    MissingGhostdagData,
+    /// The blue score or blue work in the block header do not match its GHOSTDAG data
+    GhostdagHeaderMismatch,
+    MissingReachabilityData,
+    /// The selected parent of the chain block is not its chain ancestor in terms of reachability
+    ReachabilityMismatch,
+    /// The block status indicates a body which is missing from the store
+    MissingBody,
+    /// The stored UTXO multiset of the chain block does not match its header UTXO commitment
+    UtxoCommitmentMismatch,
+}
+
+impl IntegrityViolationKind {
+    /// Whether the violated data can be restored by re-downloading it from peers, as opposed to data derived locally
+    pub fn is_redownloadable(&self) -> bool {
+        matches!(self, Self::MissingBody)
+    }
+}
+
+impl Display for IntegrityViolationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::MissingStatus => "missing block status",
+            Self::InvalidStatus => "block referenced by the chain is marked invalid",
+            Self::MissingHeader => "missing header",
+            Self::MissingGhostdagData => "missing GHOSTDAG data",
+            Self::GhostdagHeaderMismatch => "header blue score/work do not match the GHOSTDAG data",
+            Self::MissingReachabilityData => "missing reachability data",
+            Self::ReachabilityMismatch => "selected parent is not a reachability chain ancestor",
+            Self::MissingBody => "missing block body",
+            Self::UtxoCommitmentMismatch => "UTXO multiset does not match the header UTXO commitment",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegrityViolation {
+    pub hash: Hash,
+    pub kind: IntegrityViolationKind,
+}
+
+impl IntegrityViolation {
+    pub fn new(hash: Hash, kind: IntegrityViolationKind) -> Self {
+        Self { hash, kind }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct IntegrityReport {
+    /// The number of blocks verified, including the mergesets of the verified chain blocks
+    pub checked_blocks: u64,
+    pub violations: Vec<IntegrityViolation>,
+    /// The chain block to continue the verification from, or `None` if the pruning point was reached
+    pub next: Option<Hash>,
+}
@@ -8,6 +8,7 @@ use std::hash::{BuildHasher, Hasher};
 pub use kaspa_hashes::Hash;
 
 pub mod acceptance_data;
+pub mod acceptance_proof;
 pub mod api;
 pub mod block;
 pub mod block_count;
@@ -17,19 +18,28 @@ pub mod coinbase;
 pub mod config;
 pub mod constants;
 pub mod daa_score_timestamp;
+pub mod dag_timing;
+pub mod dag_view;
 pub mod errors;
+#[cfg(test)]
+mod golden;
 pub mod hashing;
 pub mod header;
+pub mod integrity;
 pub mod mass;
 pub mod merkle;
 pub mod muhash;
 pub mod network;
+pub mod network_stats;
 pub mod pruning;
 pub mod sign;
+pub mod state_digest;
+pub mod storage_stats;
 pub mod subnets;
 pub mod trusted;
 pub mod tx;
 pub mod utxo;
+pub mod validation_trace;
 
 /// Integer type for accumulated PoW of blue blocks. We expect no more than
 /// 2^128 work in a single block (btc has ~2^80), and no more than 2^64
@@ -1,11 +1,16 @@
 use crate::{hashing, tx::Transaction};
 use kaspa_hashes::Hash;
-use kaspa_merkle::calc_merkle_root;
+use kaspa_merkle::{calc_merkle_root, create_merkle_branch};
 
 pub fn calc_hash_merkle_root<'a>(txs: impl ExactSizeIterator<Item = &'a Transaction>) -> Hash {
     calc_merkle_root(txs.map(hashing::tx::hash))
 }
 
+/// Creates the merkle branch proving the inclusion of the transaction at `index` against the hash merkle root of `txs`
+pub fn create_hash_merkle_branch<'a>(txs: impl ExactSizeIterator<Item = &'a Transaction>, index: usize) -> Option<Vec<Hash>> {
+    create_merkle_branch(txs.map(hashing::tx::hash), index)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::merkle::calc_hash_merkle_root;
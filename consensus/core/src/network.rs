@@ -27,6 +27,16 @@ u8_try_from! {
 }
 
 impl NetworkType {
+    /// The magic number identifying the network in the P2P handshake, matching the values used by the Go implementation
+    pub fn magic(&self) -> u32 {
+        match self {
+            NetworkType::Mainnet => 0x3ddcf71d,
+            NetworkType::Testnet => 0xddb8af8f,
+            NetworkType::Simnet => 0x374dcf1c,
+            NetworkType::Devnet => 0x732d87e1,
+        }
+    }
+
     pub fn default_rpc_port(&self) -> u16 {
         match self {
             NetworkType::Mainnet => 16110,
@@ -199,6 +209,11 @@ impl NetworkId {
         self.suffix
     }
 
+    /// The magic number of the network type, offset by the suffix so that testnets of different suffixes are told apart
+    pub fn magic(&self) -> u32 {
+        self.network_type.magic().wrapping_add(self.suffix.unwrap_or_default())
+    }
+
     pub fn default_p2p_port(&self) -> u16 {
         // We define the P2P port on the [`networkId`] type in order to adapt testnet ports according to testnet suffix,
         // hence avoiding repeatedly failing P2P handshakes between nodes on different networks. RPC does not have
@@ -428,6 +443,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_network_magics_and_ports() {
+        // Nodes of distinct networks must neither share a magic nor a default P2P port
+        let networks = NetworkId::iter().collect::<Vec<_>>();
+        for (i, a) in networks.iter().enumerate() {
+            for b in networks.iter().skip(i + 1) {
+                assert_ne!(a.magic(), b.magic(), "{a} and {b} share a magic");
+                assert_ne!(a.default_p2p_port(), b.default_p2p_port(), "{a} and {b} share a default P2P port");
+            }
+        }
+        assert_eq!(NetworkId::new(NetworkType::Mainnet).magic(), 0x3ddcf71d);
+    }
+
     #[test]
     fn test_network_id_parse() {
         struct Test {
@@ -0,0 +1,36 @@
+/// Rolling statistics over the most recently processed block headers, describing the shape of the DAG as observed by the node
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkStats {
+    /// The number of blocks the statistics are computed over
+    pub window_size: u64,
+    /// Statistics of the intervals (in milliseconds) between the timestamps of the window blocks, in timestamp order
+    pub inter_arrival: InterArrivalStats,
+    /// The average number of direct parents of the window blocks
+    pub average_parent_count: f64,
+    /// The number of blocks merged as blue by the window blocks, selected parents included
+    pub mergeset_blues: u64,
+    /// The number of blocks merged as red by the window blocks
+    pub mergeset_reds: u64,
+}
+
+impl NetworkStats {
+    /// The ratio of blue blocks among the merged blocks, or zero if the window is empty
+    pub fn blue_ratio(&self) -> f64 {
+        let merged = self.mergeset_blues + self.mergeset_reds;
+        if merged == 0 {
+            0.0
+        } else {
+            self.mergeset_blues as f64 / merged as f64
+        }
+    }
+}
+
+/// The distribution of block inter-arrival intervals, all values in milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InterArrivalStats {
+    pub mean: u64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub max: u64,
+}
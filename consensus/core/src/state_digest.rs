@@ -0,0 +1,51 @@
+use kaspa_hashes::{Hash, HasherBase, StateDigestHash};
+
+/// The default blue score interval between consecutive state digest checkpoints
+pub const DEFAULT_STATE_DIGEST_INTERVAL: u64 = 1000;
+
+/// The maximal number of chain blocks walked when looking up state digests, bounding the cost of a lookup regardless
+/// of the interval and of the requested count
+pub const MAX_STATE_DIGEST_CHAIN_WALK: usize = 100_000;
+
+/// A digest of the consensus state at a checkpoint chain block. Checkpoints are the chain blocks whose blue score
+/// crosses a multiple of the digest interval, so nodes following the same chain compute digests of the same blocks
+/// and can detect a fork by comparing them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateDigest {
+    pub hash: Hash,
+    pub blue_score: u64,
+    pub daa_score: u64,
+    pub utxo_commitment: Hash,
+    /// The hash of the block hash, DAA score and UTXO commitment
+    pub digest: Hash,
+}
+
+impl StateDigest {
+    pub fn new(hash: Hash, blue_score: u64, daa_score: u64, utxo_commitment: Hash) -> Self {
+        let mut hasher = StateDigestHash::new();
+        hasher.update(hash).update(daa_score.to_le_bytes()).update(utxo_commitment);
+        Self { hash, blue_score, daa_score, utxo_commitment, digest: hasher.finalize() }
+    }
+
+    /// Whether a chain block of `blue_score` whose selected parent has `selected_parent_blue_score` is a checkpoint
+    pub fn is_checkpoint(blue_score: u64, selected_parent_blue_score: u64, interval: u64) -> bool {
+        blue_score / interval > selected_parent_blue_score / interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoints() {
+        assert!(StateDigest::is_checkpoint(1000, 999, 1000));
+        assert!(StateDigest::is_checkpoint(1003, 998, 1000));
+        assert!(!StateDigest::is_checkpoint(1003, 1000, 1000));
+        assert!(!StateDigest::is_checkpoint(999, 990, 1000));
+
+        let digest = StateDigest::new(1.into(), 1000, 1010, 2.into());
+        assert_eq!(digest, StateDigest::new(1.into(), 1000, 1010, 2.into()));
+        assert_ne!(digest.digest, StateDigest::new(1.into(), 1000, 1010, 3.into()).digest);
+    }
+}
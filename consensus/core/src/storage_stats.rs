@@ -0,0 +1,16 @@
+/// Statistics of the storage engine backing the consensus stores, exposing compaction load and write stalls
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StorageStats {
+    /// The bytes written by compactions (including memtable flushes) per byte written by consensus
+    pub write_amplification: f64,
+    /// Estimated total bytes pending compaction
+    pub pending_compaction_bytes: u64,
+    /// The number of currently running compactions
+    pub running_compactions: u64,
+    /// The cumulative time writes were stalled since the database was opened
+    pub stall_seconds: f64,
+    /// The current delayed write rate in bytes per second (0 if writes are not being slowed down)
+    pub delayed_write_rate: u64,
+    /// Whether writes are currently stopped
+    pub is_write_stopped: bool,
+}
@@ -1,3 +1,4 @@
+use crate::config::constants::decoding::MAX_SCRIPT_LEN;
 use alloc::borrow::Cow;
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use core::fmt::Formatter;
@@ -339,8 +340,17 @@ impl BorshSerialize for ScriptPublicKey {
 
 impl BorshDeserialize for ScriptPublicKey {
     fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let version = borsh::BorshDeserialize::deserialize(buf)?;
+        // Peek the length prefix of the script in order to reject oversized scripts before allocating for them
+        let len: u32 = borsh::BorshDeserialize::deserialize(&mut &buf[..])?;
+        if len as usize > MAX_SCRIPT_LEN {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("script public key of size {len} exceeds the decoding limit of {MAX_SCRIPT_LEN}"),
+            ));
+        }
         // Deserialize into vec first since we have no custom smallvec support
-        Ok(Self::from_vec(borsh::BorshDeserialize::deserialize(buf)?, borsh::BorshDeserialize::deserialize(buf)?))
+        Ok(Self::from_vec(version, borsh::BorshDeserialize::deserialize(buf)?))
     }
 }
 
@@ -412,6 +422,11 @@ mod tests {
         let bin = spk.try_to_vec().unwrap();
         let spk2: ScriptPublicKey = BorshDeserialize::try_from_slice(&bin).unwrap();
         assert_eq!(spk, spk2);
+
+        // Oversized scripts are rejected by their length prefix alone
+        let mut bin = 0u16.try_to_vec().unwrap();
+        bin.extend((MAX_SCRIPT_LEN as u32 + 1).to_le_bytes());
+        assert!(ScriptPublicKey::try_from_slice(&bin).is_err());
     }
 
     use wasm_bindgen_test::wasm_bindgen_test;
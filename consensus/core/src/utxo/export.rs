@@ -0,0 +1,81 @@
+//!
+//! Paginated export of the virtual UTXO set. The first chunk request pins a snapshot of the set and every
+//! following request, identified by the cursor returned with the previous chunk, reads from that same snapshot,
+//! so that the exported set is consistent regardless of the blocks processed meanwhile.
+//!
+
+use crate::tx::{TransactionOutpoint, UtxoEntry};
+use kaspa_hashes::Hash;
+use std::{fmt::Display, str::FromStr};
+use thiserror::Error;
+
+/// The position of an export within a pinned snapshot of the virtual UTXO set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UtxoSetCursor {
+    pub snapshot_id: u64,
+    /// The last exported outpoint, `None` if nothing was exported yet
+    pub last_outpoint: Option<TransactionOutpoint>,
+}
+
+impl UtxoSetCursor {
+    pub fn new(snapshot_id: u64, last_outpoint: Option<TransactionOutpoint>) -> Self {
+        Self { snapshot_id, last_outpoint }
+    }
+}
+
+/// Formats as `<snapshot id>` or `<snapshot id>:<transaction id>:<index>`
+impl Display for UtxoSetCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.last_outpoint {
+            Some(outpoint) => write!(f, "{}:{}:{}", self.snapshot_id, outpoint.transaction_id, outpoint.index),
+            None => write!(f, "{}", self.snapshot_id),
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone)]
+#[error("invalid UTXO set cursor '{0}'")]
+pub struct InvalidUtxoSetCursor(pub String);
+
+impl FromStr for UtxoSetCursor {
+    type Err = InvalidUtxoSetCursor;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidUtxoSetCursor(s.to_string());
+        let mut parts = s.split(':');
+        let snapshot_id = parts.next().and_then(|id| id.parse().ok()).ok_or_else(invalid)?;
+        let last_outpoint = match (parts.next(), parts.next(), parts.next()) {
+            (None, _, _) => None,
+            (Some(transaction_id), Some(index), None) => Some(TransactionOutpoint::new(
+                Hash::from_str(transaction_id).map_err(|_| invalid())?,
+                index.parse().map_err(|_| invalid())?,
+            )),
+            _ => return Err(invalid()),
+        };
+        Ok(Self { snapshot_id, last_outpoint })
+    }
+}
+
+pub struct UtxoSetChunk {
+    pub entries: Vec<(TransactionOutpoint, UtxoEntry)>,
+    /// The cursor to request the next chunk with, `None` if this chunk completes the export
+    pub next_cursor: Option<UtxoSetCursor>,
+    /// The virtual DAA score at the time the snapshot was pinned
+    pub snapshot_daa_score: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_roundtrip() {
+        let cursors = [UtxoSetCursor::new(0, None), UtxoSetCursor::new(7, Some(TransactionOutpoint::new(3.into(), 12)))];
+        for cursor in cursors {
+            assert_eq!(cursor.to_string().parse::<UtxoSetCursor>().unwrap(), cursor);
+        }
+        for invalid in ["", "x", "1:", "1:abc:0", "1:0000000000000000000000000000000000000000000000000000000000000003", "1:a:b:c"] {
+            assert!(invalid.parse::<UtxoSetCursor>().is_err(), "{invalid}");
+        }
+    }
+}
@@ -1,3 +1,5 @@
+pub mod export;
+pub mod snapshot;
 pub mod utxo_collection;
 pub mod utxo_diff;
 pub mod utxo_error;
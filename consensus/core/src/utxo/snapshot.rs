@@ -0,0 +1,176 @@
+//!
+//! A file format for pruning point UTXO set snapshots, allowing to bootstrap a node from a local file rather
+//! than downloading the UTXO set from peers. The snapshot is not trusted blindly: the imported set is committed
+//! to a multiset which consensus verifies against the UTXO commitment of the pruning point header.
+//!
+//! Layout: `magic | version (u16 LE) | pruning point hash | chunk* | terminator`, where each chunk is a u32 LE byte
+//! length followed by a borsh-serialized vector of (outpoint, entry) pairs, and the terminator is a zero length.
+//!
+
+use crate::{
+    api::ConsensusApi,
+    tx::{TransactionOutpoint, UtxoEntry},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use kaspa_hashes::{Hash, HASH_SIZE};
+use std::io::{self, Read, Write};
+
+pub const UTXO_SNAPSHOT_MAGIC: [u8; 8] = *b"KASUTXOS";
+pub const UTXO_SNAPSHOT_VERSION: u16 = 1;
+
+/// Upper bound on the serialized size of a single chunk, protecting the reader from allocating arbitrary amounts of memory
+const MAX_CHUNK_BYTES: usize = 64 * 1024 * 1024;
+
+pub type UtxoSnapshotChunk = Vec<(TransactionOutpoint, UtxoEntry)>;
+
+pub struct UtxoSnapshotWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> UtxoSnapshotWriter<W> {
+    /// Creates a writer for a snapshot of the UTXO set of `pruning_point` and writes the snapshot header
+    pub fn new(mut writer: W, pruning_point: Hash) -> io::Result<Self> {
+        writer.write_all(&UTXO_SNAPSHOT_MAGIC)?;
+        writer.write_all(&UTXO_SNAPSHOT_VERSION.to_le_bytes())?;
+        writer.write_all(&pruning_point.as_bytes())?;
+        Ok(Self { writer })
+    }
+
+    pub fn write_chunk(&mut self, chunk: &[(TransactionOutpoint, UtxoEntry)]) -> io::Result<()> {
+        if chunk.is_empty() {
+            // An empty chunk would be interpreted as the terminator
+            return Ok(());
+        }
+        let bytes = chunk.try_to_vec()?;
+        if bytes.len() > MAX_CHUNK_BYTES {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "snapshot chunk is too large"));
+        }
+        self.writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        self.writer.write_all(&bytes)
+    }
+
+    /// Writes the terminator and returns the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.write_all(&0u32.to_le_bytes())?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+pub struct UtxoSnapshotReader<R: Read> {
+    reader: R,
+    pruning_point: Hash,
+    done: bool,
+}
+
+impl<R: Read> UtxoSnapshotReader<R> {
+    /// Creates a reader over `reader`, reading and validating the snapshot header
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != UTXO_SNAPSHOT_MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a UTXO snapshot file"));
+        }
+        let mut version = [0u8; 2];
+        reader.read_exact(&mut version)?;
+        let version = u16::from_le_bytes(version);
+        if version != UTXO_SNAPSHOT_VERSION {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("unsupported UTXO snapshot version {version}")));
+        }
+        let mut pruning_point = [0u8; HASH_SIZE];
+        reader.read_exact(&mut pruning_point)?;
+        Ok(Self { reader, pruning_point: Hash::from_bytes(pruning_point), done: false })
+    }
+
+    /// The pruning point whose UTXO set this snapshot claims to hold
+    pub fn pruning_point(&self) -> Hash {
+        self.pruning_point
+    }
+
+    /// Returns the next chunk of UTXOs, or `None` once the terminator was reached
+    pub fn next_chunk(&mut self) -> io::Result<Option<UtxoSnapshotChunk>> {
+        if self.done {
+            return Ok(None);
+        }
+        let mut len = [0u8; 4];
+        self.reader.read_exact(&mut len)?;
+        let len = u32::from_le_bytes(len) as usize;
+        if len == 0 {
+            self.done = true;
+            return Ok(None);
+        }
+        if len > MAX_CHUNK_BYTES {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "snapshot chunk is too large"));
+        }
+        let mut bytes = vec![0u8; len];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(Some(UtxoSnapshotChunk::try_from_slice(&bytes)?))
+    }
+}
+
+/// Writes a snapshot of the UTXO set of the current pruning point of `consensus`. Returns the pruning point along with
+/// the number of exported UTXOs
+pub fn export_pruning_point_utxo_set<W: Write>(consensus: &dyn ConsensusApi, writer: W, chunk_size: usize) -> io::Result<(Hash, u64)> {
+    let pruning_point = consensus.pruning_point();
+    let mut writer = UtxoSnapshotWriter::new(writer, pruning_point)?;
+    let mut from_outpoint = None;
+    let mut count = 0;
+    loop {
+        let chunk = consensus
+            .get_pruning_point_utxos(pruning_point, from_outpoint, chunk_size, from_outpoint.is_some())
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+        writer.write_chunk(&chunk)?;
+        count += chunk.len() as u64;
+        if chunk.len() < chunk_size {
+            break;
+        }
+        from_outpoint = chunk.last().map(|(outpoint, _)| *outpoint);
+    }
+    writer.finish()?;
+    Ok((pruning_point, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx::{ScriptPublicKey, TransactionId};
+
+    #[test]
+    fn test_utxo_snapshot_roundtrip() {
+        let pruning_point = Hash::from_u64_word(7);
+        let chunks: Vec<UtxoSnapshotChunk> = (0..3u64)
+            .map(|i| {
+                (0..4u64)
+                    .map(|j| {
+                        let outpoint = TransactionOutpoint::new(TransactionId::from_u64_word(i * 10 + j), j as u32);
+                        (outpoint, UtxoEntry::new(i * j, ScriptPublicKey::default(), i, j == 0))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut writer = UtxoSnapshotWriter::new(Vec::new(), pruning_point).unwrap();
+        for chunk in chunks.iter() {
+            writer.write_chunk(chunk).unwrap();
+        }
+        writer.write_chunk(&[]).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let mut reader = UtxoSnapshotReader::new(bytes.as_slice()).unwrap();
+        assert_eq!(reader.pruning_point(), pruning_point);
+        let mut read = Vec::new();
+        while let Some(chunk) = reader.next_chunk().unwrap() {
+            read.push(chunk);
+        }
+        assert_eq!(read, chunks);
+        assert!(reader.next_chunk().unwrap().is_none());
+
+        // A truncated file is reported rather than silently accepted as complete
+        let mut reader = UtxoSnapshotReader::new(&bytes[..bytes.len() - 8]).unwrap();
+        assert!(std::iter::from_fn(|| reader.next_chunk().transpose()).any(|r| r.is_err()));
+
+        let mut corrupted = bytes.clone();
+        corrupted[0] ^= 1;
+        assert!(UtxoSnapshotReader::new(corrupted.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,24 @@
+use kaspa_hashes::Hash;
+
+/// The evaluation of a single consensus rule during block validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleTrace {
+    /// The name of the validation step, e.g. `check_block_mass`
+    pub rule: String,
+    pub duration_micros: u64,
+    /// Whether the rule passed. Validation stops at the first failing rule, so it can only be the last one
+    pub passed: bool,
+}
+
+/// The rules evaluated while validating a block, in evaluation order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockValidationTrace {
+    pub hash: Hash,
+    pub rules: Vec<RuleTrace>,
+}
+
+impl BlockValidationTrace {
+    pub fn total_duration_micros(&self) -> u64 {
+        self.rules.iter().map(|rule| rule.duration_micros).sum()
+    }
+}
@@ -0,0 +1,24 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum JournalError {
+    #[error("journal i/o error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("the file is not a consensus journal")]
+    InvalidMagic,
+
+    #[error("unsupported journal format version {0}")]
+    UnsupportedVersion(u16),
+
+    #[error("unknown journal event kind {0}")]
+    UnknownEventKind(u8),
+
+    #[error("truncated journal record")]
+    Truncated,
+
+    #[error("malformed journal record: {0}")]
+    MalformedRecord(&'static str),
+}
+
+pub type JournalResult<T> = std::result::Result<T, JournalError>;
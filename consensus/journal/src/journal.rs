@@ -0,0 +1,187 @@
+use crate::{
+    errors::{JournalError, JournalResult},
+    record::{check_header, encode_header, read_record_body, JournalEvent, JournalRecord, JOURNAL_HEADER_LEN},
+};
+use kaspa_core::warn;
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufReader, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+/// An append-only journal file of consensus events
+pub struct Journal {
+    path: PathBuf,
+    file: File,
+    /// The length of the file up to the end of the last complete record
+    len: u64,
+    next_sequence: u64,
+}
+
+impl Journal {
+    /// Opens the journal at `path`, creating it if missing. A trailing record left incomplete by an interrupted
+    /// write is discarded, so that appending resumes right after the last complete record
+    pub fn open(path: impl AsRef<Path>) -> JournalResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(&path)?;
+        if file.metadata()?.len() == 0 {
+            file.write_all(&encode_header())?;
+            file.sync_all()?;
+            return Ok(Self { path, file, len: JOURNAL_HEADER_LEN, next_sequence: 0 });
+        }
+
+        let mut reader = BufReader::new(&file);
+        check_header(&mut reader)?;
+        let mut valid_len = JOURNAL_HEADER_LEN;
+        let mut next_sequence = 0;
+        loop {
+            match read_record_body(&mut reader) {
+                Ok(Some(body)) => {
+                    next_sequence = JournalRecord::peek_sequence(&body)? + 1;
+                    valid_len += 4 + body.len() as u64;
+                }
+                Ok(None) => break,
+                Err(JournalError::Truncated) => {
+                    warn!("Discarding an incomplete trailing record of the consensus journal {}", path.display());
+                    file.set_len(valid_len)?;
+                    break;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        file.seek(SeekFrom::Start(valid_len))?;
+        Ok(Self { path, file, len: valid_len, next_sequence })
+    }
+
+    /// Appends `event` under the next sequence number and returns the written record. The record is not durable
+    /// until the next [`sync`](Self::sync). A record failing to be written is cut off, so that the journal ends
+    /// with the last complete record
+    pub fn append(&mut self, event: JournalEvent) -> JournalResult<JournalRecord> {
+        let record = JournalRecord::new(self.next_sequence, event);
+        let encoded = record.encode();
+        if let Err(err) = self.file.write_all(&encoded) {
+            let _ = self.file.set_len(self.len).and_then(|_| self.file.seek(SeekFrom::Start(self.len)));
+            return Err(err.into());
+        }
+        self.len += encoded.len() as u64;
+        self.next_sequence += 1;
+        Ok(record)
+    }
+
+    /// Syncs the appended records to disk, to be called at commit points (i.e., once all the records of an event
+    /// were appended)
+    pub fn sync(&self) -> JournalResult<()> {
+        Ok(self.file.sync_data()?)
+    }
+
+    pub fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Reads the records of a journal file in sequence order
+pub struct JournalReader {
+    reader: BufReader<File>,
+    from_sequence: u64,
+}
+
+impl JournalReader {
+    /// Opens the journal at `path` for reading the records whose sequence number is at least `from_sequence`
+    pub fn open(path: impl AsRef<Path>, from_sequence: u64) -> JournalResult<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        check_header(&mut reader)?;
+        Ok(Self { reader, from_sequence })
+    }
+}
+
+impl Iterator for JournalReader {
+    type Item = JournalResult<JournalRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let body = match read_record_body(&mut self.reader) {
+                Ok(Some(body)) => body,
+                // The journal might be in the middle of appending the last record
+                Ok(None) | Err(JournalError::Truncated) => return None,
+                Err(err) => return Some(Err(err)),
+            };
+            match JournalRecord::peek_sequence(&body) {
+                Ok(sequence) if sequence < self.from_sequence => continue,
+                Ok(_) => return Some(JournalRecord::decode(&body)),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_added(n: u64) -> JournalEvent {
+        JournalEvent::BlockAdded { hash: n.into(), daa_score: n, blue_score: n, timestamp: n, transaction_count: 1 }
+    }
+
+    #[test]
+    fn test_append_reopen_and_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal");
+
+        let mut journal = Journal::open(&path).unwrap();
+        for n in 0..5 {
+            assert_eq!(journal.append(block_added(n)).unwrap().sequence, n);
+        }
+        journal.sync().unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), journal.len);
+        drop(journal);
+
+        // Appending resumes at the next sequence number after reopening
+        let mut journal = Journal::open(&path).unwrap();
+        assert_eq!(journal.next_sequence(), 5);
+        journal.append(JournalEvent::ChainChanged { removed: vec![], added: vec![4.into()] }).unwrap();
+
+        let records: Vec<_> = JournalReader::open(&path, 0).unwrap().collect::<JournalResult<_>>().unwrap();
+        assert_eq!(records.len(), 6);
+        assert!(records.iter().enumerate().all(|(i, record)| record.sequence == i as u64));
+
+        // Replaying from an offset skips the earlier records
+        let records: Vec<_> = JournalReader::open(&path, 3).unwrap().collect::<JournalResult<_>>().unwrap();
+        assert_eq!(records.iter().map(|record| record.sequence).collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert_eq!(records[0].event, block_added(3));
+        assert_eq!(JournalReader::open(&path, 6).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_incomplete_record_recovery() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal");
+
+        let mut journal = Journal::open(&path).unwrap();
+        journal.append(block_added(0)).unwrap();
+        journal.append(block_added(1)).unwrap();
+        drop(journal);
+
+        // Simulate a crash in the middle of writing a record
+        let len = std::fs::metadata(&path).unwrap().len();
+        OpenOptions::new().write(true).open(&path).unwrap().set_len(len - 10).unwrap();
+        assert_eq!(JournalReader::open(&path, 0).unwrap().count(), 1);
+
+        let mut journal = Journal::open(&path).unwrap();
+        assert_eq!(journal.next_sequence(), 1);
+        journal.append(block_added(2)).unwrap();
+        let records: Vec<_> = JournalReader::open(&path, 0).unwrap().collect::<JournalResult<_>>().unwrap();
+        assert_eq!(records, vec![JournalRecord::new(0, block_added(0)), JournalRecord::new(1, block_added(2))]);
+    }
+
+    #[test]
+    fn test_invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal");
+        std::fs::write(&path, b"not a journal").unwrap();
+        assert!(matches!(Journal::open(&path), Err(JournalError::InvalidMagic)));
+    }
+}
@@ -0,0 +1,15 @@
+//!
+//! An optional append-only journal of consensus events (blocks added, virtual chain changes and accepted
+//! transactions) written in a stable binary format, so that external stream processors can consume the events
+//! without RPC subscriptions and resume from any sequence number.
+//!
+//! See [`record`] for the format specification.
+//!
+
+pub mod errors;
+pub mod journal;
+pub mod record;
+pub mod server;
+pub mod service;
+
+const IDENT: &str = "consensus-journal";
@@ -0,0 +1,321 @@
+//!
+//! The journal binary format. All integers are little-endian and hashes are their 32 raw bytes.
+//!
+//! A journal file starts with a header made of the magic bytes `KJNL` followed by a `u16` format version,
+//! and continues with a sequence of records. The socket server streams records using the very same encoding,
+//! without the file header. Each record is laid out as:
+//!
+//! | field    | type            | description                                          |
+//! |----------|-----------------|------------------------------------------------------|
+//! | length   | `u32`           | the number of bytes following this field             |
+//! | sequence | `u64`           | consecutive record number, starting at zero          |
+//! | kind     | `u8`            | the event kind                                       |
+//! | payload  | `[u8; length-9]`| the event, encoded according to its kind (see below) |
+//!
+//! Event payloads, by kind:
+//!
+//! - `1` block added: `hash`, `daa_score: u64`, `blue_score: u64`, `timestamp: u64`, `transaction_count: u32`
+//! - `2` chain changed: `removed_count: u32`, `removed: [hash]`, `added_count: u32`, `added: [hash]`
+//! - `3` transactions accepted: `accepting_block: hash`, `count: u32`, `transaction_ids: [hash]`
+//!
+//! New event kinds may be added in future versions while existing layouts never change, so readers should
+//! skip records of unknown kinds.
+//!
+
+use crate::errors::{JournalError, JournalResult};
+use kaspa_hashes::{Hash, HASH_SIZE};
+use std::io::{ErrorKind, Read};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+pub const JOURNAL_MAGIC: [u8; 4] = *b"KJNL";
+pub const JOURNAL_VERSION: u16 = 1;
+pub const JOURNAL_HEADER_LEN: u64 = 6;
+
+const BLOCK_ADDED_KIND: u8 = 1;
+const CHAIN_CHANGED_KIND: u8 = 2;
+const TRANSACTIONS_ACCEPTED_KIND: u8 = 3;
+
+/// The length of the sequence and kind fields of a record
+const RECORD_PREFIX_LEN: usize = 9;
+
+/// Records larger than this are considered corrupted rather than allocated
+const MAX_RECORD_LEN: u32 = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JournalEvent {
+    BlockAdded {
+        hash: Hash,
+        daa_score: u64,
+        blue_score: u64,
+        timestamp: u64,
+        transaction_count: u32,
+    },
+    /// The virtual selected chain changed, with the removed and added chain blocks in the order reported by consensus
+    ChainChanged {
+        removed: Vec<Hash>,
+        added: Vec<Hash>,
+    },
+    /// The transactions accepted by a newly added chain block, in acceptance order
+    TransactionsAccepted {
+        accepting_block: Hash,
+        transaction_ids: Vec<Hash>,
+    },
+}
+
+impl JournalEvent {
+    fn kind(&self) -> u8 {
+        match self {
+            JournalEvent::BlockAdded { .. } => BLOCK_ADDED_KIND,
+            JournalEvent::ChainChanged { .. } => CHAIN_CHANGED_KIND,
+            JournalEvent::TransactionsAccepted { .. } => TRANSACTIONS_ACCEPTED_KIND,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalRecord {
+    pub sequence: u64,
+    pub event: JournalEvent,
+}
+
+impl JournalRecord {
+    pub fn new(sequence: u64, event: JournalEvent) -> Self {
+        Self { sequence, event }
+    }
+
+    /// Encodes the record, length prefix included
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![0u8; 4];
+        buf.extend_from_slice(&self.sequence.to_le_bytes());
+        buf.push(self.event.kind());
+        match &self.event {
+            JournalEvent::BlockAdded { hash, daa_score, blue_score, timestamp, transaction_count } => {
+                buf.extend_from_slice(&hash.as_bytes());
+                buf.extend_from_slice(&daa_score.to_le_bytes());
+                buf.extend_from_slice(&blue_score.to_le_bytes());
+                buf.extend_from_slice(&timestamp.to_le_bytes());
+                buf.extend_from_slice(&transaction_count.to_le_bytes());
+            }
+            JournalEvent::ChainChanged { removed, added } => {
+                encode_hashes(&mut buf, removed);
+                encode_hashes(&mut buf, added);
+            }
+            JournalEvent::TransactionsAccepted { accepting_block, transaction_ids } => {
+                buf.extend_from_slice(&accepting_block.as_bytes());
+                encode_hashes(&mut buf, transaction_ids);
+            }
+        }
+        let len = (buf.len() - 4) as u32;
+        buf[..4].copy_from_slice(&len.to_le_bytes());
+        buf
+    }
+
+    /// Decodes a record from its bytes, length prefix excluded
+    pub fn decode(body: &[u8]) -> JournalResult<Self> {
+        let mut cursor = Cursor(body);
+        let sequence = cursor.u64()?;
+        let kind = cursor.u8()?;
+        let event = match kind {
+            BLOCK_ADDED_KIND => JournalEvent::BlockAdded {
+                hash: cursor.hash()?,
+                daa_score: cursor.u64()?,
+                blue_score: cursor.u64()?,
+                timestamp: cursor.u64()?,
+                transaction_count: cursor.u32()?,
+            },
+            CHAIN_CHANGED_KIND => JournalEvent::ChainChanged { removed: cursor.hashes()?, added: cursor.hashes()? },
+            TRANSACTIONS_ACCEPTED_KIND => {
+                JournalEvent::TransactionsAccepted { accepting_block: cursor.hash()?, transaction_ids: cursor.hashes()? }
+            }
+            kind => return Err(JournalError::UnknownEventKind(kind)),
+        };
+        if !cursor.0.is_empty() {
+            return Err(JournalError::MalformedRecord("trailing bytes"));
+        }
+        Ok(Self { sequence, event })
+    }
+
+    /// Reads the sequence number of an encoded record body without decoding the event
+    pub fn peek_sequence(body: &[u8]) -> JournalResult<u64> {
+        Cursor(body).u64()
+    }
+}
+
+/// Reads the body of the next record. Returns `None` at a clean end of input and [`JournalError::Truncated`]
+/// if the input ends in the middle of a record
+pub fn read_record_body(reader: &mut impl Read) -> JournalResult<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    match reader.read(&mut len[..1])? {
+        0 => return Ok(None),
+        _ => read_exact_or_truncated(reader, &mut len[1..])?,
+    }
+    let mut body = vec![0u8; checked_record_len(u32::from_le_bytes(len))?];
+    read_exact_or_truncated(reader, &mut body)?;
+    Ok(Some(body))
+}
+
+/// The async counterpart of [`read_record_body`]
+pub async fn read_record_body_async(reader: &mut (impl AsyncRead + Unpin)) -> JournalResult<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    match reader.read(&mut len[..1]).await? {
+        0 => return Ok(None),
+        _ => truncated_on_eof(reader.read_exact(&mut len[1..]).await.map(|_| ()))?,
+    }
+    let mut body = vec![0u8; checked_record_len(u32::from_le_bytes(len))?];
+    truncated_on_eof(reader.read_exact(&mut body).await.map(|_| ()))?;
+    Ok(Some(body))
+}
+
+pub(crate) fn encode_header() -> [u8; JOURNAL_HEADER_LEN as usize] {
+    let mut header = [0u8; JOURNAL_HEADER_LEN as usize];
+    header[..4].copy_from_slice(&JOURNAL_MAGIC);
+    header[4..].copy_from_slice(&JOURNAL_VERSION.to_le_bytes());
+    header
+}
+
+pub(crate) fn check_header(reader: &mut impl Read) -> JournalResult<()> {
+    let mut header = [0u8; JOURNAL_HEADER_LEN as usize];
+    read_exact_or_truncated(reader, &mut header)?;
+    if header[..4] != JOURNAL_MAGIC {
+        return Err(JournalError::InvalidMagic);
+    }
+    match u16::from_le_bytes([header[4], header[5]]) {
+        JOURNAL_VERSION => Ok(()),
+        version => Err(JournalError::UnsupportedVersion(version)),
+    }
+}
+
+fn checked_record_len(len: u32) -> JournalResult<usize> {
+    if !(RECORD_PREFIX_LEN as u32..=MAX_RECORD_LEN).contains(&len) {
+        return Err(JournalError::MalformedRecord("invalid record length"));
+    }
+    Ok(len as usize)
+}
+
+fn read_exact_or_truncated(reader: &mut impl Read, buf: &mut [u8]) -> JournalResult<()> {
+    truncated_on_eof(reader.read_exact(buf))
+}
+
+fn truncated_on_eof(result: std::io::Result<()>) -> JournalResult<()> {
+    match result {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => Err(JournalError::Truncated),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn encode_hashes(buf: &mut Vec<u8>, hashes: &[Hash]) {
+    buf.extend_from_slice(&(hashes.len() as u32).to_le_bytes());
+    hashes.iter().for_each(|hash| buf.extend_from_slice(&hash.as_bytes()));
+}
+
+struct Cursor<'a>(&'a [u8]);
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> JournalResult<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(JournalError::MalformedRecord("unexpected end of record"));
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> JournalResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> JournalResult<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> JournalResult<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn hash(&mut self) -> JournalResult<Hash> {
+        Ok(Hash::from_slice(self.take(HASH_SIZE)?))
+    }
+
+    fn hashes(&mut self) -> JournalResult<Vec<Hash>> {
+        let count = self.u32()? as usize;
+        if self.0.len() < count * HASH_SIZE {
+            return Err(JournalError::MalformedRecord("unexpected end of record"));
+        }
+        (0..count).map(|_| self.hash()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_roundtrip() {
+        let records = [
+            JournalRecord::new(
+                0,
+                JournalEvent::BlockAdded { hash: 1.into(), daa_score: 2, blue_score: 3, timestamp: 4, transaction_count: 5 },
+            ),
+            JournalRecord::new(1, JournalEvent::ChainChanged { removed: vec![6.into()], added: vec![7.into(), 8.into()] }),
+            JournalRecord::new(2, JournalEvent::ChainChanged { removed: vec![], added: vec![] }),
+            JournalRecord::new(
+                u64::MAX,
+                JournalEvent::TransactionsAccepted { accepting_block: 9.into(), transaction_ids: vec![10.into()] },
+            ),
+        ];
+        let stream: Vec<u8> = records.iter().flat_map(|record| record.encode()).collect();
+        let mut reader = stream.as_slice();
+        for record in records.iter() {
+            let body = read_record_body(&mut reader).unwrap().unwrap();
+            assert_eq!(JournalRecord::peek_sequence(&body).unwrap(), record.sequence);
+            assert_eq!(&JournalRecord::decode(&body).unwrap(), record);
+        }
+        assert!(read_record_body(&mut reader).unwrap().is_none());
+
+        // A stream cut in the middle of a record is reported as truncated
+        let mut reader = &stream[..stream.len() - 1];
+        for _ in 0..records.len() - 1 {
+            read_record_body(&mut reader).unwrap().unwrap();
+        }
+        assert!(matches!(read_record_body(&mut reader), Err(JournalError::Truncated)));
+    }
+
+    #[test]
+    fn test_block_added_layout() {
+        // The layout is part of the public format and must never change
+        let record = JournalRecord::new(
+            7,
+            JournalEvent::BlockAdded {
+                hash: Hash::from_bytes([0xaa; 32]),
+                daa_score: 1,
+                blue_score: 2,
+                timestamp: 3,
+                transaction_count: 4,
+            },
+        );
+        let mut expected = vec![];
+        expected.extend_from_slice(&69u32.to_le_bytes());
+        expected.extend_from_slice(&7u64.to_le_bytes());
+        expected.push(1);
+        expected.extend_from_slice(&[0xaa; 32]);
+        expected.extend_from_slice(&1u64.to_le_bytes());
+        expected.extend_from_slice(&2u64.to_le_bytes());
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        expected.extend_from_slice(&4u32.to_le_bytes());
+        assert_eq!(record.encode(), expected);
+    }
+
+    #[test]
+    fn test_malformed_records() {
+        let mut body =
+            JournalRecord::new(0, JournalEvent::ChainChanged { removed: vec![], added: vec![1.into()] }).encode().split_off(4);
+        body[8] = 42;
+        assert!(matches!(JournalRecord::decode(&body), Err(JournalError::UnknownEventKind(42))));
+        body[8] = CHAIN_CHANGED_KIND;
+        body.push(0);
+        assert!(matches!(JournalRecord::decode(&body), Err(JournalError::MalformedRecord(_))));
+        body.truncate(body.len() - 2);
+        assert!(matches!(JournalRecord::decode(&body), Err(JournalError::MalformedRecord(_))));
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{
+    errors::{JournalError, JournalResult},
+    record::{read_record_body_async, JournalRecord, JOURNAL_HEADER_LEN},
+};
+use kaspa_core::{debug, warn};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::broadcast::{self, error::RecvError},
+};
+use triggered::Listener;
+
+/// The number of live records buffered for each connected client. A client lagging further behind is
+/// disconnected and expected to reconnect from its last received sequence number
+const LIVE_RECORDS_CAPACITY: usize = 4096;
+
+/// A record as appended to the journal, already encoded for the wire
+#[derive(Debug)]
+pub struct LiveRecord {
+    pub sequence: u64,
+    pub bytes: Vec<u8>,
+}
+
+impl From<&JournalRecord> for LiveRecord {
+    fn from(record: &JournalRecord) -> Self {
+        Self { sequence: record.sequence, bytes: record.encode() }
+    }
+}
+
+/// Streams the journal over TCP.
+///
+/// A client connects and sends the sequence number to start from as a little-endian `u64`. The server then
+/// replays the journal records from that sequence number on and keeps streaming new records as they are
+/// appended, using the record encoding of the journal file.
+pub struct JournalServer {
+    path: PathBuf,
+    live: broadcast::Sender<Arc<LiveRecord>>,
+}
+
+impl JournalServer {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, live: broadcast::channel(LIVE_RECORDS_CAPACITY).0 }
+    }
+
+    /// Publishes a record just appended to the journal to the connected clients
+    pub fn publish(&self, record: &JournalRecord) {
+        if self.live.receiver_count() > 0 {
+            // An error only means all clients disconnected meanwhile
+            let _ = self.live.send(Arc::new(record.into()));
+        }
+    }
+
+    /// Accepts clients on `address` until `shutdown` is triggered
+    pub async fn serve(self: Arc<Self>, address: SocketAddr, shutdown: Listener) -> JournalResult<()> {
+        let listener = TcpListener::bind(address).await?;
+        debug!("Consensus journal server listening on {}", address);
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let (stream, peer) = accepted?;
+                    // Subscribe before replaying so that no record appended during the replay is missed
+                    let live = self.live.subscribe();
+                    let path = self.path.clone();
+                    let shutdown = shutdown.clone();
+                    tokio::spawn(async move {
+                        tokio::select! {
+                            result = serve_client(path, stream, live) => {
+                                if let Err(err) = result {
+                                    debug!("Consensus journal client {} disconnected: {}", peer, err);
+                                }
+                            }
+                            _ = shutdown => {}
+                        }
+                    });
+                }
+                _ = shutdown.clone() => return Ok(()),
+            }
+        }
+    }
+}
+
+async fn serve_client(path: PathBuf, mut stream: TcpStream, mut live: broadcast::Receiver<Arc<LiveRecord>>) -> JournalResult<()> {
+    let mut from_sequence = [0u8; 8];
+    stream.read_exact(&mut from_sequence).await?;
+    let mut next_sequence = u64::from_le_bytes(from_sequence);
+
+    // Replay the records already written to the journal. The header was validated when the journal was opened
+    let mut file = BufReader::new(File::open(&path).await?);
+    file.read_exact(&mut [0u8; JOURNAL_HEADER_LEN as usize]).await?;
+    loop {
+        match read_record_body_async(&mut file).await {
+            Ok(Some(body)) => {
+                let sequence = JournalRecord::peek_sequence(&body)?;
+                if sequence >= next_sequence {
+                    stream.write_all(&(body.len() as u32).to_le_bytes()).await?;
+                    stream.write_all(&body).await?;
+                    next_sequence = sequence + 1;
+                }
+            }
+            // A truncated record is being appended and will be received live
+            Ok(None) | Err(JournalError::Truncated) => break,
+            Err(err) => return Err(err),
+        }
+    }
+
+    // Records appended during the replay are received both ways, so skip those already sent
+    loop {
+        match live.recv().await {
+            Ok(record) => {
+                if record.sequence >= next_sequence {
+                    stream.write_all(&record.bytes).await?;
+                    next_sequence = record.sequence + 1;
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("Disconnecting a consensus journal client lagging {} records behind", skipped);
+                return Ok(());
+            }
+            Err(RecvError::Closed) => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{journal::Journal, record::JournalEvent};
+    use kaspa_utils::triggers::SingleTrigger;
+    use std::time::Duration;
+
+    fn block_added(n: u64) -> JournalEvent {
+        JournalEvent::BlockAdded { hash: n.into(), daa_score: n, blue_score: n, timestamp: n, transaction_count: 1 }
+    }
+
+    #[tokio::test]
+    async fn test_replay_and_live_stream() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal");
+        let mut journal = Journal::open(&path).unwrap();
+        for n in 0..3 {
+            journal.append(block_added(n)).unwrap();
+        }
+
+        let address: SocketAddr = {
+            // Reserve a free port
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap()
+        };
+        let server = Arc::new(JournalServer::new(path));
+        let shutdown = SingleTrigger::new();
+        tokio::spawn(server.clone().serve(address, shutdown.listener.clone()));
+
+        let mut stream = loop {
+            match TcpStream::connect(address).await {
+                Ok(stream) => break stream,
+                Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        };
+        stream.write_all(&1u64.to_le_bytes()).await.unwrap();
+        for n in 1..3 {
+            let body = read_record_body_async(&mut stream).await.unwrap().unwrap();
+            assert_eq!(JournalRecord::decode(&body).unwrap(), JournalRecord::new(n, block_added(n)));
+        }
+
+        let record = journal.append(block_added(3)).unwrap();
+        server.publish(&record);
+        let body = read_record_body_async(&mut stream).await.unwrap().unwrap();
+        assert_eq!(JournalRecord::decode(&body).unwrap(), record);
+
+        shutdown.trigger.trigger();
+    }
+}
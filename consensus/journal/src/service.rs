@@ -0,0 +1,156 @@
+use crate::{
+    errors::JournalResult,
+    journal::Journal,
+    record::{JournalEvent, JournalRecord},
+    server::JournalServer,
+    IDENT,
+};
+use kaspa_consensus_notify::{
+    connection::ConsensusChannelConnection, notification::Notification as ConsensusNotification, notifier::ConsensusNotifier,
+};
+use kaspa_core::{
+    error,
+    task::service::{AsyncService, AsyncServiceFuture},
+    trace,
+};
+use kaspa_notify::{
+    connection::ChannelType,
+    scope::{BlockAddedScope, Scope, VirtualChainChangedScope},
+};
+use kaspa_utils::{channel::Channel, triggers::SingleTrigger};
+use parking_lot::Mutex;
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+
+const JOURNAL_SERVICE: &str = IDENT;
+
+/// Writes the blocks added, virtual chain changes and accepted transactions reported by consensus to the
+/// journal, optionally streaming them to TCP clients
+pub struct JournalService {
+    journal: Mutex<Journal>,
+    server: Option<(Arc<JournalServer>, SocketAddr)>,
+    consensus_notify_channel: Channel<ConsensusNotification>,
+    shutdown: SingleTrigger,
+}
+
+impl JournalService {
+    pub fn new(consensus_notifier: &Arc<ConsensusNotifier>, path: PathBuf, listen: Option<SocketAddr>) -> JournalResult<Self> {
+        let journal = Journal::open(&path)?;
+
+        let consensus_notify_channel = Channel::<ConsensusNotification>::default();
+        let consensus_notify_listener_id = consensus_notifier
+            .register_new_listener(ConsensusChannelConnection::new(consensus_notify_channel.sender(), ChannelType::Closable));
+        consensus_notifier
+            .try_start_notify(consensus_notify_listener_id, Scope::BlockAdded(BlockAddedScope {}))
+            .expect("the subscription always succeeds");
+        consensus_notifier
+            .try_start_notify(consensus_notify_listener_id, Scope::VirtualChainChanged(VirtualChainChangedScope::new(true)))
+            .expect("the subscription always succeeds");
+
+        let server = listen.map(|address| (Arc::new(JournalServer::new(path)), address));
+        Ok(Self { journal: Mutex::new(journal), server, consensus_notify_channel, shutdown: SingleTrigger::default() })
+    }
+
+    fn process_notification(&self, notification: ConsensusNotification) -> JournalResult<()> {
+        let events = match notification {
+            ConsensusNotification::BlockAdded(notification) => {
+                let header = &notification.block.header;
+                vec![JournalEvent::BlockAdded {
+                    hash: header.hash,
+                    daa_score: header.daa_score,
+                    blue_score: header.blue_score,
+                    timestamp: header.timestamp,
+                    transaction_count: notification.block.transactions.len() as u32,
+                }]
+            }
+            ConsensusNotification::VirtualChainChanged(notification) => {
+                let mut events = vec![JournalEvent::ChainChanged {
+                    removed: notification.removed_chain_block_hashes.to_vec(),
+                    added: notification.added_chain_block_hashes.to_vec(),
+                }];
+                for (accepting_block, acceptance_data) in
+                    notification.added_chain_block_hashes.iter().zip(notification.added_chain_blocks_acceptance_data.iter())
+                {
+                    let transaction_ids = acceptance_data
+                        .iter()
+                        .flat_map(|mergeset_block| mergeset_block.accepted_transactions.iter().map(|entry| entry.transaction_id))
+                        .collect();
+                    events.push(JournalEvent::TransactionsAccepted { accepting_block: *accepting_block, transaction_ids });
+                }
+                events
+            }
+            _ => return Ok(()),
+        };
+        self.commit(events)
+    }
+
+    /// Appends the events of a single notification and syncs them to disk, only then streaming them to the clients
+    /// so that they never receive records which could be lost
+    fn commit(&self, events: Vec<JournalEvent>) -> JournalResult<()> {
+        let records = {
+            let mut journal = self.journal.lock();
+            let records = events.into_iter().map(|event| journal.append(event)).collect::<JournalResult<Vec<JournalRecord>>>()?;
+            journal.sync()?;
+            records
+        };
+        if let Some((server, _)) = self.server.as_ref() {
+            records.iter().for_each(|record| server.publish(record));
+        }
+        Ok(())
+    }
+}
+
+impl AsyncService for JournalService {
+    fn ident(self: Arc<Self>) -> &'static str {
+        JOURNAL_SERVICE
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} starting", JOURNAL_SERVICE);
+
+        // Prepare a shutdown signal receiver
+        let shutdown_signal = self.shutdown.listener.clone();
+
+        Box::pin(async move {
+            if let Some((server, address)) = self.server.clone() {
+                let shutdown_signal = shutdown_signal.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = server.serve(address, shutdown_signal).await {
+                        error!("Consensus journal server on {} failed: {}", address, err);
+                    }
+                });
+            }
+
+            // A failure to write the journal does not affect the node: journaling is disabled, while notifications
+            // keep being drained so that the consensus notifier never blocks on this listener
+            let mut failed = false;
+            loop {
+                tokio::select! {
+                    notification = self.consensus_notify_channel.recv() => {
+                        let Ok(notification) = notification else { break };
+                        if failed {
+                            continue;
+                        }
+                        if let Err(err) = self.process_notification(notification) {
+                            error!("Error while writing to the consensus journal, journaling is disabled until the node restarts: {}", err);
+                            failed = true;
+                        }
+                    }
+                    _ = shutdown_signal.clone() => break,
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", JOURNAL_SERVICE);
+        self.shutdown.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            trace!("{} stopped", JOURNAL_SERVICE);
+            Ok(())
+        })
+    }
+}
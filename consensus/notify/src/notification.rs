@@ -1,16 +1,16 @@
 use derive_more::Display;
-use kaspa_consensus_core::{acceptance_data::AcceptanceData, block::Block, utxo::utxo_diff::UtxoDiff};
+use kaspa_consensus_core::{acceptance_data::AcceptanceData, block::Block, tx::ScriptPublicKey, utxo::utxo_diff::UtxoDiff};
 use kaspa_hashes::Hash;
 use kaspa_notify::{
     events::EventType,
     full_featured,
     notification::Notification as NotificationTrait,
     subscription::{
-        single::{OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
+        single::{AcceptedTransactionsSubscription, OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
         Subscription,
     },
 };
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 full_featured! {
 #[derive(Clone, Debug, Display)]
@@ -41,6 +41,9 @@ pub enum Notification {
 
     #[display(fmt = "NewBlockTemplate notification")]
     NewBlockTemplate(NewBlockTemplateNotification),
+
+    #[display(fmt = "AcceptedTransactions notification: {} added chain blocks", "_0.added_chain_block_hashes.len()")]
+    AcceptedTransactions(AcceptedTransactionsNotification),
 }
 }
 
@@ -58,11 +61,15 @@ impl NotificationTrait for Notification {
                 // If the subscription excludes accepted transaction ids and the notification includes some
                 // then we must re-create the object and drop the ids, otherwise we can clone it as is.
                 if let Notification::VirtualChainChanged(ref payload) = self {
-                    if !subscription.include_accepted_transaction_ids() && !payload.added_chain_blocks_acceptance_data.is_empty() {
+                    if !subscription.include_accepted_transaction_ids()
+                        && (!payload.added_chain_blocks_acceptance_data.is_empty()
+                            || !payload.removed_chain_blocks_acceptance_data.is_empty())
+                    {
                         return Some(Notification::VirtualChainChanged(VirtualChainChangedNotification {
                             removed_chain_block_hashes: payload.removed_chain_block_hashes.clone(),
                             added_chain_block_hashes: payload.added_chain_block_hashes.clone(),
                             added_chain_blocks_acceptance_data: Arc::new(vec![]),
+                            removed_chain_blocks_acceptance_data: Arc::new(vec![]),
                         }));
                     }
                 }
@@ -78,6 +85,20 @@ impl NotificationTrait for Notification {
         Some(self.clone())
     }
 
+    fn apply_accepted_transactions_subscription(&self, subscription: &AcceptedTransactionsSubscription) -> Option<Self> {
+        // The transactions are only known by the RPC service, which applies the subscription addresses. The script public
+        // keys subscribed to are attached though so the RPC service only fetches and converts the transactions of interest.
+        match self {
+            Notification::AcceptedTransactions(payload) if !subscription.to_all() => {
+                Some(Notification::AcceptedTransactions(AcceptedTransactionsNotification {
+                    script_public_keys: Some(Arc::new(subscription.script_public_keys().cloned().collect())),
+                    ..payload.clone()
+                }))
+            }
+            _ => Some(self.clone()),
+        }
+    }
+
     fn event_type(&self) -> EventType {
         self.into()
     }
@@ -99,14 +120,23 @@ pub struct VirtualChainChangedNotification {
     pub added_chain_block_hashes: Arc<Vec<Hash>>,
     pub removed_chain_block_hashes: Arc<Vec<Hash>>,
     pub added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+    /// The acceptance diff of the removed chain blocks, i.e. the acceptance data of the transactions they no
+    /// longer accept, allowing consumers to roll back a reorg without querying consensus
+    pub removed_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
 }
 impl VirtualChainChangedNotification {
     pub fn new(
         added_chain_block_hashes: Arc<Vec<Hash>>,
         removed_chain_block_hashes: Arc<Vec<Hash>>,
         added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+        removed_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
     ) -> Self {
-        Self { added_chain_block_hashes, removed_chain_block_hashes, added_chain_blocks_acceptance_data }
+        Self {
+            added_chain_block_hashes,
+            removed_chain_block_hashes,
+            added_chain_blocks_acceptance_data,
+            removed_chain_blocks_acceptance_data,
+        }
     }
 }
 
@@ -172,3 +202,19 @@ pub struct PruningPointUtxoSetOverrideNotification {}
 
 #[derive(Debug, Clone)]
 pub struct NewBlockTemplateNotification {}
+
+/// The transactions accepted by the chain blocks added to the selected chain, reported by their acceptance data
+#[derive(Debug, Clone)]
+pub struct AcceptedTransactionsNotification {
+    pub added_chain_block_hashes: Arc<Vec<Hash>>,
+    pub added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+    /// The script public keys subscribed to, restricting the transactions of interest to the ones paying to any of them,
+    /// or `None` if all transactions are of interest
+    pub script_public_keys: Option<Arc<HashSet<ScriptPublicKey>>>,
+}
+
+impl AcceptedTransactionsNotification {
+    pub fn new(added_chain_block_hashes: Arc<Vec<Hash>>, added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>) -> Self {
+        Self { added_chain_block_hashes, added_chain_blocks_acceptance_data, script_public_keys: None }
+    }
+}
@@ -24,6 +24,8 @@ pub struct NotifyService {
 }
 
 impl NotifyService {
+    pub const IDENT: &'static str = NOTIFY_SERVICE;
+
     pub fn new(root: Arc<ConsensusNotificationRoot>, notification_receiver: Receiver<Notification>) -> Self {
         let root_events: EventSwitches = EVENT_TYPE_ARRAY[..].into();
         let collector = Arc::new(ConsensusCollector::new(NOTIFY_SERVICE, notification_receiver, Arc::new(ConsensusConverter::new())));
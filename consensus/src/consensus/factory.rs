@@ -1,5 +1,4 @@
-#[cfg(feature = "devnet-prealloc")]
-use super::utxo_set_override::{set_genesis_utxo_commitment_from_config, set_initial_utxo_set};
+use super::utxo_set_override::set_initial_utxo_set;
 use super::{ctl::Ctl, Consensus};
 use crate::{model::stores::U64Key, pipeline::ProcessingCounters};
 use itertools::Itertools;
@@ -8,7 +7,10 @@ use kaspa_consensus_notify::root::ConsensusNotificationRoot;
 use kaspa_consensusmanager::{ConsensusFactory, ConsensusInstance, DynConsensusCtl, SessionLock};
 use kaspa_core::{debug, time::unix_now, warn};
 use kaspa_database::{
-    prelude::{BatchDbWriter, CachedDbAccess, CachedDbItem, DirectDbWriter, StoreError, StoreResult, StoreResultExtensions, DB},
+    prelude::{
+        BatchDbWriter, CachedDbAccess, CachedDbItem, DirectDbWriter, DurabilityProfile, StoreError, StoreResult,
+        StoreResultExtensions, DB,
+    },
     registry::DatabaseStorePrefixes,
 };
 
@@ -232,6 +234,7 @@ pub struct Factory {
     counters: Arc<ProcessingCounters>,
     tx_script_cache_counters: Arc<TxScriptCacheCounters>,
     fd_budget: i32,
+    durability: DurabilityProfile,
 }
 
 impl Factory {
@@ -244,11 +247,11 @@ impl Factory {
         counters: Arc<ProcessingCounters>,
         tx_script_cache_counters: Arc<TxScriptCacheCounters>,
         fd_budget: i32,
+        durability: DurabilityProfile,
     ) -> Self {
         assert!(fd_budget > 0, "fd_budget has to be positive");
         let mut config = config.clone();
-        #[cfg(feature = "devnet-prealloc")]
-        set_genesis_utxo_commitment_from_config(&mut config);
+        config.commit_genesis_to_utxo_set();
         config.process_genesis = false;
         let management_store = Arc::new(RwLock::new(MultiConsensusManagementStore::new(management_db)));
         management_store.write().set_is_archival_node(config.is_archival);
@@ -261,6 +264,7 @@ impl Factory {
             counters,
             tx_script_cache_counters,
             fd_budget,
+            durability,
         };
         factory.delete_inactive_consensus_entries();
         factory
@@ -291,6 +295,7 @@ impl ConsensusFactory for Factory {
             .with_db_path(dir)
             .with_parallelism(self.db_parallelism)
             .with_files_limit(self.fd_budget / 2) // active and staging consensuses should have equal budgets
+            .with_durability(self.durability)
             .build()
             .unwrap();
 
@@ -308,8 +313,10 @@ impl ConsensusFactory for Factory {
         // We write the new active entry only once the instance was created successfully.
         // This way we can safely avoid processing genesis in future process runs
         if is_new_consensus {
-            #[cfg(feature = "devnet-prealloc")]
-            set_initial_utxo_set(&self.config.initial_utxo_set, consensus.clone(), self.config.params.genesis.hash);
+            let genesis_utxo_set = self.config.genesis_utxo_set();
+            if !genesis_utxo_set.is_empty() {
+                set_initial_utxo_set(&genesis_utxo_set, consensus.clone(), self.config.params.genesis.hash);
+            }
             self.management_store.write().save_new_active_consensus(entry).unwrap();
         }
 
@@ -325,6 +332,7 @@ impl ConsensusFactory for Factory {
             .with_db_path(dir)
             .with_parallelism(self.db_parallelism)
             .with_files_limit(self.fd_budget / 2) // active and staging consensuses should have equal budgets
+            .with_durability(self.durability)
             .build()
             .unwrap();
 
@@ -0,0 +1,244 @@
+use super::Consensus;
+use crate::model::{
+    services::reachability::ReachabilityService,
+    stores::{
+        block_transactions::BlockTransactionsStore,
+        ghostdag::{GhostdagData, GhostdagStoreReader},
+        headers::HeaderStoreReader,
+        pruning::PruningStoreReader,
+        quarantine::{QuarantineStore, QuarantineStoreReader},
+        reachability::ReachabilityStoreReader,
+        statuses::StatusesStoreReader,
+        utxo_multisets::UtxoMultisetsStoreReader,
+    },
+};
+use kaspa_consensus_core::{
+    block::Block,
+    blockstatus::BlockStatus,
+    errors::consensus::{ConsensusError, ConsensusResult},
+    integrity::{IntegrityReport, IntegrityViolation, IntegrityViolationKind},
+    merkle::calc_hash_merkle_root,
+};
+use kaspa_core::{info, warn};
+use kaspa_database::prelude::StoreResultExtensions;
+use kaspa_hashes::Hash;
+use std::sync::Arc;
+
+impl Consensus {
+    /// Walks the selected chain down from `high` verifying the consistency of statuses, headers, GHOSTDAG data,
+    /// reachability data, bodies and UTXO commitments. Assumes the pruning lock is held by the caller
+    pub(super) fn verify_chain_integrity_impl(&self, high: Hash, max_chain_blocks: usize) -> IntegrityReport {
+        let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
+        let mut report = IntegrityReport::default();
+        let mut current = high;
+        for _ in 0..max_chain_blocks {
+            let Some(ghostdag) = self.verify_block_integrity(current, &mut report) else {
+                // The chain cannot be followed any further
+                return report;
+            };
+            if current == pruning_point {
+                return report;
+            }
+
+            for hash in ghostdag.unordered_mergeset_without_selected_parent() {
+                self.verify_block_integrity(hash, &mut report);
+            }
+            self.verify_chain_block_integrity(current, &ghostdag, &mut report);
+
+            current = ghostdag.selected_parent;
+        }
+        report.next = Some(current);
+        report
+    }
+
+    /// Verifies the invariants of a single block and returns its GHOSTDAG data if found
+    fn verify_block_integrity(&self, hash: Hash, report: &mut IntegrityReport) -> Option<Arc<GhostdagData>> {
+        report.checked_blocks += 1;
+        let mut violation = |kind| report.violations.push(IntegrityViolation::new(hash, kind));
+
+        let status = self.statuses_store.read().get(hash).unwrap_option();
+        match status {
+            None => violation(IntegrityViolationKind::MissingStatus),
+            Some(BlockStatus::StatusInvalid) => violation(IntegrityViolationKind::InvalidStatus),
+            Some(status) if status.has_block_body() && !self.block_transactions_store.has(hash).unwrap() => {
+                violation(IntegrityViolationKind::MissingBody)
+            }
+            _ => {}
+        }
+
+        let header = self.headers_store.get_header(hash).unwrap_option();
+        if header.is_none() {
+            violation(IntegrityViolationKind::MissingHeader);
+        }
+        if !self.reachability_store.read().has(hash).unwrap() {
+            violation(IntegrityViolationKind::MissingReachabilityData);
+        }
+
+        let ghostdag = self.ghostdag_primary_store.get_data(hash).unwrap_option();
+        match (&ghostdag, &header) {
+            (None, _) => violation(IntegrityViolationKind::MissingGhostdagData),
+            (Some(ghostdag), Some(header)) if ghostdag.blue_score != header.blue_score || ghostdag.blue_work != header.blue_work => {
+                violation(IntegrityViolationKind::GhostdagHeaderMismatch)
+            }
+            _ => {}
+        }
+        ghostdag
+    }
+
+    /// Verifies the invariants specific to selected chain blocks
+    fn verify_chain_block_integrity(&self, hash: Hash, ghostdag: &GhostdagData, report: &mut IntegrityReport) {
+        let has_reachability_data = {
+            let reachability_store = self.reachability_store.read();
+            reachability_store.has(hash).unwrap() && reachability_store.has(ghostdag.selected_parent).unwrap()
+        };
+        if has_reachability_data && !self.services.reachability_service.is_chain_ancestor_of(ghostdag.selected_parent, hash) {
+            report.violations.push(IntegrityViolation::new(hash, IntegrityViolationKind::ReachabilityMismatch));
+        }
+
+        // Multisets are only kept for UTXO-validated chain blocks which were not pruned yet
+        if let (Some(mut multiset), Some(header)) =
+            (self.utxo_multisets_store.get(hash).unwrap_option(), self.headers_store.get_header(hash).unwrap_option())
+        {
+            if multiset.finalize() != header.utxo_commitment {
+                report.violations.push(IntegrityViolation::new(hash, IntegrityViolationKind::UtxoCommitmentMismatch));
+            }
+        }
+    }
+}
+
+impl Consensus {
+    /// Marks the given blocks for body re-download and returns the number of newly quarantined blocks
+    pub(super) fn quarantine_blocks_impl(&self, hashes: &[Hash]) -> usize {
+        let added = self.quarantine_store.write().insert_many(hashes).unwrap();
+        if added > 0 {
+            warn!("Quarantined {} blocks with corrupted data for re-download from peers", added);
+        }
+        added
+    }
+
+    /// Returns the quarantined blocks which still require a repair. Blocks which no longer have a body
+    /// (e.g. since they were pruned in the meantime) are released from quarantine
+    pub(super) fn get_quarantined_blocks_impl(&self) -> Vec<Hash> {
+        let quarantined = self.quarantine_store.read().get().unwrap();
+        let (pending, stale): (Vec<_>, Vec<_>) = {
+            let statuses_store = self.statuses_store.read();
+            quarantined
+                .iter()
+                .copied()
+                .partition(|&hash| statuses_store.get(hash).unwrap_option().is_some_and(|status| status.has_block_body()))
+        };
+        if !stale.is_empty() {
+            let mut quarantine_store = self.quarantine_store.write();
+            for hash in stale {
+                quarantine_store.remove(hash).unwrap();
+            }
+        }
+        pending
+    }
+
+    /// Rewrites the body of a quarantined block. The block is not revalidated: its header hash commits to the
+    /// merkle root, so matching the root guarantees the transactions are identical to the originally validated ones
+    pub(super) fn repair_quarantined_block_impl(&self, block: Block) -> ConsensusResult<()> {
+        let hash = block.hash();
+        if !self.quarantine_store.read().get().unwrap().contains(&hash) {
+            return Err(ConsensusError::BlockNotQuarantined(hash));
+        }
+        if block.is_header_only() || calc_hash_merkle_root(block.transactions.iter()) != block.header.hash_merkle_root {
+            return Err(ConsensusError::BlockTransactionsMismatch(hash));
+        }
+
+        // Delete first in case a corrupted record is still present
+        self.block_transactions_store.delete(hash).unwrap();
+        self.block_transactions_store.insert(hash, block.transactions).unwrap();
+        self.quarantine_store.write().remove(hash).unwrap();
+        info!("Repaired the body of quarantined block {}", hash);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::ConfigBuilder,
+        consensus::test_consensus::TestConsensus,
+        model::stores::{block_transactions::BlockTransactionsStore, statuses::StatusesStore},
+    };
+    use kaspa_consensus_core::{
+        api::ConsensusApi, block::Block, blockstatus::BlockStatus, config::params::DEVNET_PARAMS, errors::consensus::ConsensusError,
+        integrity::IntegrityViolationKind,
+    };
+    use kaspa_hashes::Hash;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_verify_chain_integrity() {
+        let config = ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work().build();
+        let consensus = TestConsensus::new(&config);
+        let wait_handles = consensus.init();
+
+        let mut parent = config.genesis.hash;
+        for i in 1..=5u64 {
+            let hash = Hash::from_u64_word(i);
+            consensus.add_utxo_valid_block_with_parents(hash, vec![parent], vec![]).await.unwrap();
+            parent = hash;
+        }
+
+        // Verification in batches continues from where the previous batch stopped, down to the pruning point
+        let report = consensus.verify_chain_integrity(parent, 3).unwrap();
+        assert!(report.violations.is_empty(), "{:?}", report.violations);
+        assert_eq!(report.checked_blocks, 3);
+        assert_eq!(report.next, Some(Hash::from_u64_word(2)));
+        let report = consensus.verify_chain_integrity(report.next.unwrap(), 3).unwrap();
+        assert!(report.violations.is_empty(), "{:?}", report.violations);
+        assert_eq!(report.checked_blocks, 3);
+        assert_eq!(report.next, None);
+
+        let corrupted = Hash::from_u64_word(3);
+        consensus.statuses_store.write().set(corrupted, BlockStatus::StatusInvalid).unwrap();
+        let report = consensus.verify_chain_integrity(parent, 10).unwrap();
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.violations[0].hash, corrupted);
+        assert_eq!(report.violations[0].kind, IntegrityViolationKind::InvalidStatus);
+
+        consensus.shutdown(wait_handles);
+    }
+
+    #[tokio::test]
+    async fn test_quarantine_and_repair() {
+        let config = ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work().build();
+        let consensus = TestConsensus::new(&config);
+        let wait_handles = consensus.init();
+
+        let mut parent = config.genesis.hash;
+        for i in 1..=3u64 {
+            let hash = Hash::from_u64_word(i);
+            consensus.add_utxo_valid_block_with_parents(hash, vec![parent], vec![]).await.unwrap();
+            parent = hash;
+        }
+
+        // Reading a block whose body record vanished quarantines it
+        let corrupted = Hash::from_u64_word(2);
+        let block = consensus.get_block(corrupted).unwrap();
+        consensus.block_transactions_store.delete(corrupted).unwrap();
+        assert!(consensus.get_block(corrupted).is_err());
+        assert_eq!(consensus.get_quarantined_blocks(), vec![corrupted]);
+
+        let report = consensus.verify_chain_integrity(parent, 10).unwrap();
+        assert_eq!(report.violations.len(), 1);
+        assert!(report.violations[0].kind.is_redownloadable());
+        assert_eq!(consensus.quarantine_blocks(&[corrupted]), 0);
+
+        // A body not matching the header merkle root is rejected
+        let mut transactions = (*block.transactions).clone();
+        transactions.push(transactions[0].clone());
+        let tampered = Block::from_arcs(block.header.clone(), Arc::new(transactions));
+        assert!(matches!(consensus.repair_quarantined_block(tampered), Err(ConsensusError::BlockTransactionsMismatch(_))));
+
+        consensus.repair_quarantined_block(block.clone()).unwrap();
+        assert!(consensus.get_quarantined_blocks().is_empty());
+        assert_eq!(consensus.get_block(corrupted).unwrap().transactions, block.transactions);
+        assert!(matches!(consensus.repair_quarantined_block(block), Err(ConsensusError::BlockNotQuarantined(_))));
+
+        consensus.shutdown(wait_handles);
+    }
+}
@@ -0,0 +1,169 @@
+use super::Consensus;
+use crate::model::{
+    services::reachability::ReachabilityService,
+    stores::{
+        manual_invalidations::{ManualInvalidationsStore, ManualInvalidationsStoreReader},
+        pruning::PruningStoreReader,
+        relations::RelationsStoreReader,
+        statuses::{StatusesStore, StatusesStoreReader},
+        utxo_diffs::UtxoDiffsStoreReader,
+        virtual_state::VirtualStateStoreReader,
+    },
+};
+use kaspa_consensus_core::{
+    api::ConsensusApi,
+    blockstatus::BlockStatus::{StatusDisqualifiedFromChain, StatusInvalid, StatusUTXOPendingVerification, StatusUTXOValid},
+    errors::consensus::{ConsensusError, ConsensusResult},
+    BlockHashSet,
+};
+use kaspa_core::warn;
+use kaspa_database::prelude::StoreResultExtensions;
+use kaspa_hashes::Hash;
+use std::collections::VecDeque;
+
+impl Consensus {
+    /// Disqualifies `hash` along with its selected chain descendants from the virtual chain and re-resolves the
+    /// virtual accordingly. The block remains invalidated, including for descendants arriving later, until reconsidered
+    pub(super) fn invalidate_block_impl(&self, hash: Hash) -> ConsensusResult<()> {
+        {
+            // Prevents the virtual processor from concurrently updating statuses. Locks are acquired in the same
+            // order as when resolving the virtual
+            let virtual_read = self.virtual_stores.upgradable_read();
+            let _prune_guard = self.pruning_lock.blocking_read();
+            match self.statuses_store.read().get(hash).unwrap_option() {
+                Some(StatusInvalid) => return Err(ConsensusError::InvalidBlock(hash)),
+                Some(status) if status.has_block_body() => {}
+                _ => return Err(ConsensusError::BlockNotFound(hash)),
+            }
+
+            // Reorging below the finality point is not possible
+            let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
+            let finality_point =
+                self.virtual_processor.virtual_finality_point(&virtual_read.state.get().unwrap().ghostdag_data, pruning_point);
+            if hash == finality_point || !self.services.reachability_service.is_dag_ancestor_of(finality_point, hash) {
+                return Err(ConsensusError::InvalidationBeyondFinality(hash));
+            }
+
+            self.manual_invalidations_store.write().insert(hash).unwrap();
+            let mut statuses_store = self.statuses_store.write();
+            for current in self.selected_chain_future(hash, &Default::default()) {
+                if statuses_store.get(current).unwrap().has_block_body() {
+                    statuses_store.set(current, StatusDisqualifiedFromChain).unwrap();
+                }
+            }
+        }
+
+        self.virtual_processor.resolve_virtual(true);
+        warn!("Block {} was invalidated by the node operator, the virtual was resolved to sink {}", hash, self.get_sink());
+        Ok(())
+    }
+
+    /// Reverts a previous invalidation of `hash` and re-resolves the virtual accordingly. Blocks disqualified for
+    /// other reasons are disqualified again once their UTXO state is verified
+    pub(super) fn reconsider_block_impl(&self, hash: Hash) -> ConsensusResult<()> {
+        {
+            let _virtual_read = self.virtual_stores.upgradable_read();
+            let _prune_guard = self.pruning_lock.blocking_read();
+            let mut manual_invalidations_store = self.manual_invalidations_store.write();
+            if !manual_invalidations_store.remove(hash).unwrap() {
+                return Err(ConsensusError::BlockNotInvalidated(hash));
+            }
+
+            // Statuses are kept if the block is still disqualified by an invalidated chain ancestor, and
+            // subtrees of invalidated chain descendants are skipped
+            let invalidated = manual_invalidations_store.get().unwrap();
+            let reachability_service = &self.services.reachability_service;
+            if !invalidated.iter().any(|&other| reachability_service.is_chain_ancestor_of(other, hash)) {
+                let mut statuses_store = self.statuses_store.write();
+                for current in self.selected_chain_future(hash, &invalidated) {
+                    if statuses_store.get(current).unwrap() != StatusDisqualifiedFromChain {
+                        continue;
+                    }
+                    // Blocks with a calculated UTXO state were disqualified only due to the invalidation
+                    let status = if self.utxo_diffs_store.get(current).unwrap_option().is_some() {
+                        StatusUTXOValid
+                    } else {
+                        StatusUTXOPendingVerification
+                    };
+                    statuses_store.set(current, status).unwrap();
+                }
+            }
+        }
+
+        self.virtual_processor.resolve_virtual(true);
+        warn!("Block {} was reconsidered by the node operator, the virtual was resolved to sink {}", hash, self.get_sink());
+        Ok(())
+    }
+
+    /// Returns `root` along with all blocks having it on their selected chain, skipping the subtrees of blocks in `excluded`
+    fn selected_chain_future(&self, root: Hash, excluded: &BlockHashSet) -> Vec<Hash> {
+        let relations_service = &self.services.relations_service;
+        let reachability_service = &self.services.reachability_service;
+        let mut result = vec![root];
+        let mut queue = VecDeque::from([root]);
+        let mut visited = BlockHashSet::from_iter([root]);
+        while let Some(current) = queue.pop_front() {
+            let children = relations_service.get_children(current).unwrap().read().iter().copied().collect::<Vec<_>>();
+            for child in children {
+                if !excluded.contains(&child) && reachability_service.is_chain_ancestor_of(root, child) && visited.insert(child) {
+                    result.push(child);
+                    queue.push_back(child);
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{config::ConfigBuilder, consensus::test_consensus::TestConsensus};
+    use kaspa_consensus_core::{
+        api::ConsensusApi, blockstatus::BlockStatus, coinbase::MinerData, config::params::DEVNET_PARAMS,
+        errors::consensus::ConsensusError, tx::ScriptPublicKey,
+    };
+    use kaspa_hashes::Hash;
+
+    #[tokio::test]
+    async fn test_invalidate_and_reconsider_block() {
+        let config = ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work().build();
+        let consensus = TestConsensus::new(&config);
+        let wait_handles = consensus.init();
+
+        // Build a chain 1 <- 2 <- 3 along with a competing block 4 on genesis
+        let mut parent = config.genesis.hash;
+        for i in 1..=3u64 {
+            let hash = Hash::from_u64_word(i);
+            consensus.add_utxo_valid_block_with_parents(hash, vec![parent], vec![]).await.unwrap();
+            parent = hash;
+        }
+        consensus.add_utxo_valid_block_with_parents(4.into(), vec![config.genesis.hash], vec![]).await.unwrap();
+        assert_eq!(consensus.get_sink(), 3.into());
+        let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![]), vec![]);
+        let block = consensus.build_utxo_valid_block_with_parents(5.into(), vec![3.into()], miner_data, vec![]).to_immutable();
+
+        consensus.invalidate_block(2.into()).unwrap();
+        assert!([Hash::from(1), Hash::from(4)].contains(&consensus.get_sink()));
+        for hash in [2, 3] {
+            assert_eq!(consensus.get_block_status(hash.into()), Some(BlockStatus::StatusDisqualifiedFromChain));
+        }
+        assert_eq!(consensus.get_block_status(1.into()), Some(BlockStatus::StatusUTXOValid));
+
+        // Invalidations are kept for descendants arriving later
+        let status = consensus.validate_and_insert_block(block).virtual_state_task.await.unwrap();
+        assert_eq!(status, BlockStatus::StatusDisqualifiedFromChain);
+        assert_ne!(consensus.get_sink(), 5.into());
+
+        consensus.reconsider_block(2.into()).unwrap();
+        assert_eq!(consensus.get_sink(), 5.into());
+        for hash in [2, 3, 5] {
+            assert_eq!(consensus.get_block_status(hash.into()), Some(BlockStatus::StatusUTXOValid));
+        }
+
+        assert!(matches!(consensus.reconsider_block(2.into()), Err(ConsensusError::BlockNotInvalidated(_))));
+        assert!(matches!(consensus.invalidate_block(config.genesis.hash), Err(ConsensusError::InvalidationBeyondFinality(_))));
+        assert!(matches!(consensus.invalidate_block(6.into()), Err(ConsensusError::BlockNotFound(_))));
+
+        consensus.shutdown(wait_handles);
+    }
+}
@@ -1,10 +1,15 @@
 pub mod ctl;
 pub mod factory;
+mod integrity;
+mod invalidation;
+mod revalidation;
+mod roots;
 pub mod services;
 pub mod storage;
 pub mod test_consensus;
+mod warmup;
 
-#[cfg(feature = "devnet-prealloc")]
+mod utxo_set_export;
 mod utxo_set_override;
 
 use crate::{
@@ -18,11 +23,13 @@ use crate::{
             ghostdag::{GhostdagData, GhostdagStoreReader},
             headers::{CompactHeaderData, HeaderStoreReader},
             headers_selected_tip::HeadersSelectedTipStoreReader,
+            invalid_blocks::{InvalidBlocksStore, InvalidBlocksStoreReader},
             past_pruning_points::PastPruningPointsStoreReader,
             pruning::PruningStoreReader,
             relations::RelationsStoreReader,
             statuses::StatusesStoreReader,
             tips::TipsStoreReader,
+            utxo_diffs::UtxoDiffsStoreReader,
             utxo_set::{UtxoSetStore, UtxoSetStoreReader},
             virtual_state::VirtualStateStoreReader,
             DB,
@@ -30,23 +37,32 @@ use crate::{
     },
     pipeline::{
         body_processor::BlockBodyProcessor,
+        budget::PipelineBudget,
         deps_manager::{BlockProcessingMessage, BlockResultSender, BlockTask, VirtualStateProcessingMessage},
         header_processor::HeaderProcessor,
+        network_stats::NetworkStatsStore,
         pruning_processor::processor::{PruningProcessingMessage, PruningProcessor},
+        tracer::ValidationTracer,
         virtual_processor::{errors::PruningImportResult, VirtualStateProcessor},
         ProcessingCounters,
     },
-    processes::window::{WindowManager, WindowType},
+    processes::{
+        era_archive::EraReader,
+        window::{WindowManager, WindowType},
+    },
 };
 use kaspa_consensus_core::{
-    acceptance_data::AcceptanceData,
+    acceptance_data::{AcceptanceData, MergesetBlockUtxoDiff},
+    acceptance_proof::TransactionAcceptanceProof,
     api::{BlockValidationFutures, ConsensusApi},
-    block::{Block, BlockTemplate, TemplateBuildMode, TemplateTransactionSelector, VirtualStateApproxId},
+    block::{Block, BlockOrigin, BlockTemplate, TemplateBuildMode, TemplateTransactionSelector, VirtualStateApproxId},
     block_count::BlockCount,
-    blockhash::BlockHashExtensions,
+    blockhash::{BlockHashExtensions, BlockHashes},
     blockstatus::BlockStatus,
     coinbase::MinerData,
     daa_score_timestamp::DaaScoreTimestamp,
+    dag_timing::{BlockDagTimingInfo, DifficultyWindowInfo},
+    dag_view::DagBlockInfo,
     errors::{
         coinbase::CoinbaseResult,
         consensus::{ConsensusError, ConsensusResult},
@@ -54,11 +70,20 @@ use kaspa_consensus_core::{
     },
     errors::{difficulty::DifficultyError, pruning::PruningImportError},
     header::Header,
+    integrity::IntegrityReport,
     muhash::MuHashExtensions,
     network::NetworkType,
+    network_stats::NetworkStats,
     pruning::{PruningPointProof, PruningPointTrustedData, PruningPointsList},
+    state_digest::{StateDigest, MAX_STATE_DIGEST_CHAIN_WALK},
+    storage_stats::StorageStats,
     trusted::{ExternalGhostdagData, TrustedBlock},
-    tx::{MutableTransaction, Transaction, TransactionOutpoint, UtxoEntry},
+    tx::{MutableTransaction, PopulatedTransaction, Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
+    utxo::{
+        export::{UtxoSetChunk, UtxoSetCursor},
+        utxo_diff::UtxoDiff,
+    },
+    validation_trace::BlockValidationTrace,
     BlockHashSet, BlueWorkType, ChainPath,
 };
 use kaspa_consensus_notify::root::ConsensusNotificationRoot;
@@ -68,22 +93,26 @@ use crossbeam_channel::{
 };
 use itertools::Itertools;
 use kaspa_consensusmanager::{SessionLock, SessionReadGuard};
+use kaspa_core::warn;
 
 use kaspa_database::prelude::StoreResultExtensions;
 use kaspa_hashes::Hash;
 use kaspa_muhash::MuHash;
 use kaspa_txscript::caches::TxScriptCacheCounters;
+use kaspa_utils::affinity;
 
 use std::thread::{self, JoinHandle};
 use std::{
+    collections::{HashMap, VecDeque},
     future::Future,
     iter::once,
     ops::Deref,
     sync::{atomic::Ordering, Arc},
+    time::Duration,
 };
 use tokio::sync::oneshot;
 
-use self::{services::ConsensusServices, storage::ConsensusStorage};
+use self::{services::ConsensusServices, storage::ConsensusStorage, utxo_set_export::PinnedUtxoSetSnapshots};
 
 use crate::model::stores::selected_chain::SelectedChainStoreReader;
 
@@ -95,6 +124,7 @@ pub struct Consensus {
 
     // Channels
     block_sender: CrossbeamSender<BlockProcessingMessage>,
+    pipeline_budget: Arc<PipelineBudget>,
 
     // Processors
     pub(super) header_processor: Arc<HeaderProcessor>,
@@ -117,6 +147,18 @@ pub struct Consensus {
     // Counters
     counters: Arc<ProcessingCounters>,
 
+    // Validation tracing
+    tracer: Arc<ValidationTracer>,
+
+    // Rolling network statistics
+    network_stats: Arc<NetworkStatsStore>,
+
+    // UTXO set snapshots pinned by ongoing exports
+    utxo_set_snapshots: PinnedUtxoSetSnapshots,
+
+    // Reader of the era files pruned block bodies are exported to
+    era_reader: Option<EraReader>,
+
     // Config
     config: Arc<Config>,
 
@@ -180,7 +222,7 @@ impl Consensus {
 
         // Pool for header and body processors
         let block_processors_pool = Arc::new(
-            rayon::ThreadPoolBuilder::new()
+            Self::pool_builder(perf_params.pin_worker_threads, 0)
                 .num_threads(perf_params.block_processors_num_threads)
                 .thread_name(|i| format!("block-pool-{i}"))
                 .build()
@@ -189,8 +231,9 @@ impl Consensus {
         // We need a dedicated thread-pool for the virtual processor to avoid possible deadlocks probably caused by the
         // combined usage of `par_iter` (in virtual processor) and `rayon::spawn` (in header/body processors).
         // See for instance https://github.com/rayon-rs/rayon/issues/690
+        // When pinned, its threads take the cores following those of the block processors pool
         let virtual_pool = Arc::new(
-            rayon::ThreadPoolBuilder::new()
+            Self::pool_builder(perf_params.pin_worker_threads, block_processors_pool.current_num_threads())
                 .num_threads(perf_params.virtual_processor_num_threads)
                 .thread_name(|i| format!("virtual-pool-{i}"))
                 .build()
@@ -201,6 +244,10 @@ impl Consensus {
         // Pipeline processors
         //
 
+        let tracer = Arc::new(ValidationTracer::new(config.trace_validation));
+        let processing_budget = (config.block_processing_budget > 0).then(|| Duration::from_millis(config.block_processing_budget));
+        let network_stats = Arc::new(NetworkStatsStore::default());
+
         let header_processor = Arc::new(HeaderProcessor::new(
             receiver,
             body_sender,
@@ -211,6 +258,9 @@ impl Consensus {
             &services,
             pruning_lock.clone(),
             counters.clone(),
+            tracer.clone(),
+            network_stats.clone(),
+            processing_budget,
         ));
 
         let body_processor = Arc::new(BlockBodyProcessor::new(
@@ -223,6 +273,7 @@ impl Consensus {
             storage.headers_store.clone(),
             storage.block_transactions_store.clone(),
             storage.body_tips_store.clone(),
+            storage.invalid_blocks_store.clone(),
             services.reachability_service.clone(),
             services.coinbase_manager.clone(),
             services.mass_calculator.clone(),
@@ -233,6 +284,8 @@ impl Consensus {
             pruning_lock.clone(),
             notification_root.clone(),
             counters.clone(),
+            tracer.clone(),
+            processing_budget,
         ));
 
         let virtual_processor = Arc::new(VirtualStateProcessor::new(
@@ -247,26 +300,18 @@ impl Consensus {
             pruning_lock.clone(),
             notification_root.clone(),
             counters.clone(),
+            tracer.clone(),
+            processing_budget,
+            (config.stale_branch_daa_threshold > 0).then_some(config.stale_branch_daa_threshold),
         ));
 
         let pruning_processor =
             Arc::new(PruningProcessor::new(pruning_receiver, db.clone(), &storage, &services, pruning_lock.clone(), config.clone()));
 
-        // Ensure the relations stores are initialized
-        header_processor.init();
-        // Ensure that some pruning point is registered
-        virtual_processor.init();
-
-        // Ensure that genesis was processed
-        if config.process_genesis {
-            header_processor.process_genesis();
-            body_processor.process_genesis();
-            virtual_processor.process_genesis();
-        }
-
-        Self {
+        let consensus = Self {
             db,
             block_sender: sender,
+            pipeline_budget: PipelineBudget::new(config.perf.block_pipeline_capacity, config.perf.block_pipeline_max_bytes),
             header_processor,
             body_processor,
             virtual_processor,
@@ -276,9 +321,39 @@ impl Consensus {
             pruning_lock,
             notification_root,
             counters,
+            tracer,
+            network_stats,
+            utxo_set_snapshots: Default::default(),
+            era_reader: config.pruned_archive_dir.clone().map(EraReader::new),
             config,
             creation_timestamp,
+        };
+
+        // Ensure the DAG roots are initialized
+        consensus.init_roots();
+
+        // Resume skipping the WAL (fast-sync durability profile) if the node is still in the midst of IBD
+        let has_virtual_state = consensus.virtual_stores.read().state.get().unwrap_option().is_some();
+        consensus.db.set_bulk_sync(!has_virtual_state || !consensus.is_nearly_synced());
+        if has_virtual_state && consensus.config.cache_warmup_blocks > 0 {
+            consensus.warm_up_caches(consensus.config.cache_warmup_blocks as usize);
         }
+        consensus
+    }
+
+    /// Returns a thread-pool builder which optionally pins the i'th pool thread to the CPU core `first_core + i`
+    /// (wrapping around the available cores)
+    fn pool_builder(pin_worker_threads: bool, first_core: usize) -> rayon::ThreadPoolBuilder {
+        let builder = rayon::ThreadPoolBuilder::new();
+        if !pin_worker_threads {
+            return builder;
+        }
+        let num_cores = thread::available_parallelism().map_or(1, |n| n.get());
+        builder.start_handler(move |i| {
+            if !affinity::pin_current_thread((first_core + i) % num_cores) {
+                warn!("Failed pinning validation thread {} to a CPU core", i);
+            }
+        })
     }
 
     pub fn run_processors(&self) -> Vec<JoinHandle<()>> {
@@ -305,13 +380,47 @@ impl Consensus {
         &self,
         task: BlockTask,
     ) -> (impl Future<Output = BlockProcessResult<BlockStatus>>, impl Future<Output = BlockProcessResult<BlockStatus>>) {
-        let (btx, brx): (BlockResultSender, _) = oneshot::channel();
-        let (vtx, vrx): (BlockResultSender, _) = oneshot::channel();
-        self.block_sender.send(BlockProcessingMessage::Process(task, btx, vtx)).unwrap();
+        let (btx, brx) = oneshot::channel();
+        let (vtx, vrx) = oneshot::channel();
+        let (btx, vtx) = (BlockResultSender::new(btx), BlockResultSender::new(vtx));
         self.counters.blocks_submitted.fetch_add(1, Ordering::Relaxed);
+        self.counters.count_origin(task.origin());
+        match self.check_known_invalid_ancestry(&task) {
+            Ok(()) => {
+                // Sent right away if admitted into the pipeline budget, otherwise once enough blocks in flight are
+                // released. The permit is released along with the final (virtual) result
+                let block = task.block().clone();
+                let block_sender = self.block_sender.clone();
+                self.pipeline_budget.admit(&block, move |permit| {
+                    block_sender.send(BlockProcessingMessage::Process(task, btx, vtx.with_permit(permit))).unwrap()
+                });
+            }
+            Err(err) => {
+                btx.send(Err(err.clone()));
+                vtx.send(Err(err));
+            }
+        }
         (async { brx.await.unwrap() }, async { vrx.await.unwrap() })
     }
 
+    /// Rejects ordinary blocks which are, or directly descend from, recently invalidated blocks without
+    /// entering the processing pipeline. Rejected descendants are remembered as well, so that chains of
+    /// invalid blocks are cut short at submission time
+    fn check_known_invalid_ancestry(&self, task: &BlockTask) -> BlockProcessResult<()> {
+        if !task.is_ordinary() {
+            return Ok(());
+        }
+        let header = &task.block().header;
+        if self.invalid_blocks_store.is_known_invalid(header.hash) {
+            return Err(RuleError::KnownInvalid);
+        }
+        if let Some(&parent) = header.direct_parents().iter().find(|&&parent| self.invalid_blocks_store.is_known_invalid(parent)) {
+            self.invalid_blocks_store.mark_known_invalid(header.hash);
+            return Err(RuleError::InvalidParent(parent));
+        }
+        Ok(())
+    }
+
     pub fn body_tips(&self) -> Arc<BlockHashSet> {
         self.body_tips_store.read().get().unwrap()
     }
@@ -333,6 +442,7 @@ impl Consensus {
     }
 
     pub fn signal_exit(&self) {
+        self.utxo_set_snapshots.clear();
         self.block_sender.send(BlockProcessingMessage::Exit).unwrap();
     }
 
@@ -356,6 +466,21 @@ impl Consensus {
         }
     }
 
+    /// Returns whether `hash` is a block of the selected chain. Membership is tested in O(1) through the chain index of
+    /// the selected chain store, resorting to reachability only for blocks below the pruning point, where the index is pruned
+    fn is_selected_chain_block(&self, hash: Hash) -> bool {
+        if self.storage.selected_chain_store.read().is_chain_block(hash).unwrap() {
+            return true;
+        }
+        // All chain blocks above the pruning point are indexed
+        let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
+        let pruning_point_blue_score = self.ghostdag_primary_store.get_blue_score(pruning_point).unwrap();
+        match self.ghostdag_primary_store.get_blue_score(hash).unwrap_option() {
+            Some(blue_score) if blue_score > pruning_point_blue_score => false,
+            _ => self.services.reachability_service.is_chain_ancestor_of(hash, self.get_sink()),
+        }
+    }
+
     fn estimate_network_hashes_per_second_impl(&self, ghostdag_data: &GhostdagData, window_size: usize) -> ConsensusResult<u64> {
         let window = match self.services.window_manager.block_window(ghostdag_data, WindowType::VaryingWindow(window_size)) {
             Ok(w) => w,
@@ -387,10 +512,18 @@ impl ConsensusApi for Consensus {
     }
 
     fn validate_and_insert_block(&self, block: Block) -> BlockValidationFutures {
-        let (block_task, virtual_state_task) = self.validate_and_insert_block_impl(BlockTask::Ordinary { block });
+        self.validate_and_insert_block_with_origin(block, BlockOrigin::Local)
+    }
+
+    fn validate_and_insert_block_with_origin(&self, block: Block, origin: BlockOrigin) -> BlockValidationFutures {
+        let (block_task, virtual_state_task) = self.validate_and_insert_block_impl(BlockTask::Ordinary { block, origin });
         BlockValidationFutures { block_task: Box::pin(block_task), virtual_state_task: Box::pin(virtual_state_task) }
     }
 
+    fn precheck_block(&self, block: &Block) -> BlockProcessResult<()> {
+        self.header_processor.precheck_header(&block.header)
+    }
+
     fn validate_and_insert_trusted_block(&self, tb: TrustedBlock) -> BlockValidationFutures {
         let (block_task, virtual_state_task) = self.validate_and_insert_block_impl(BlockTask::Trusted { block: tb.block });
         BlockValidationFutures { block_task: Box::pin(block_task), virtual_state_task: Box::pin(virtual_state_task) }
@@ -492,7 +625,33 @@ impl ConsensusApi for Consensus {
         // won't later need to remove it from the result.
         let _guard = self.pruning_lock.blocking_read();
         self.validate_block_exists(hash)?;
-        Ok(self.services.dag_traversal_manager.calculate_chain_path(hash, self.get_sink()))
+
+        // Walk down the selected parents of `hash` until reaching a block indexed in the selected chain, which is the
+        // common chain ancestor of `hash` and the sink. Blocks below the pruning point aren't indexed anymore, in which
+        // case we fall back to reachability queries.
+        let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
+        let pruning_point_blue_score = self.ghostdag_primary_store.get_blue_score(pruning_point).unwrap();
+        let sc_read = self.storage.selected_chain_store.read();
+        let mut removed = Vec::new();
+        let mut current = hash;
+        let common_index = loop {
+            if let Some(index) = sc_read.get_by_hash(current).unwrap_option() {
+                break index;
+            }
+            match self.ghostdag_primary_store.get_data(current).unwrap_option() {
+                Some(ghostdag_data) if ghostdag_data.blue_score > pruning_point_blue_score => {
+                    removed.push(current);
+                    current = ghostdag_data.selected_parent;
+                }
+                _ => {
+                    drop(sc_read);
+                    return Ok(self.services.dag_traversal_manager.calculate_chain_path(hash, self.get_sink()));
+                }
+            }
+        };
+        let tip_index = sc_read.get_tip().unwrap().0;
+        let added = (common_index + 1..=tip_index).map(|index| sc_read.get_by_index(index).unwrap()).collect();
+        Ok(ChainPath { added, removed })
     }
 
     /// Returns a Vec of header samples since genesis
@@ -577,6 +736,20 @@ impl ConsensusApi for Consensus {
         iter.map(|item| item.unwrap()).collect()
     }
 
+    fn get_virtual_utxo_set_chunk(&self, cursor: Option<UtxoSetCursor>, chunk_size: usize) -> ConsensusResult<UtxoSetChunk> {
+        let cursor = match cursor {
+            Some(cursor) => cursor,
+            None => {
+                // Pin the snapshot while holding the virtual stores lock so it captures a complete virtual state
+                let virtual_stores = self.virtual_stores.read();
+                let daa_score = virtual_stores.state.get().unwrap().daa_score;
+                let snapshot_id = self.utxo_set_snapshots.pin(self.db.clone(), virtual_stores.utxo_set.clone(), daa_score)?;
+                UtxoSetCursor::new(snapshot_id, None)
+            }
+        };
+        self.utxo_set_snapshots.read_chunk(cursor, chunk_size)
+    }
+
     fn get_tips(&self) -> Vec<Hash> {
         self.body_tips().iter().copied().collect_vec()
     }
@@ -633,6 +806,10 @@ impl ConsensusApi for Consensus {
         self.virtual_processor.import_pruning_point_utxo_set(new_pruning_point, imported_utxo_multiset)
     }
 
+    fn clear_imported_pruning_point_utxos(&self) {
+        self.pruning_utxoset_stores.write().utxo_set.clear().unwrap();
+    }
+
     fn validate_pruning_points(&self) -> ConsensusResult<()> {
         let hst = self.storage.headers_selected_tip_store.read().get().unwrap().hash;
         let pp_info = self.pruning_point_store.read().get().unwrap();
@@ -729,10 +906,22 @@ impl ConsensusApi for Consensus {
             return Err(ConsensusError::BlockNotFound(hash));
         }
 
-        Ok(Block {
-            header: self.headers_store.get_header(hash).unwrap_option().ok_or(ConsensusError::BlockNotFound(hash))?,
-            transactions: self.block_transactions_store.get(hash).unwrap_option().ok_or(ConsensusError::BlockNotFound(hash))?,
-        })
+        let header = self.headers_store.get_header(hash).unwrap_option().ok_or(ConsensusError::BlockNotFound(hash))?;
+        let transactions = match self.block_transactions_store.get(hash) {
+            Ok(transactions) => transactions,
+            Err(err) => {
+                // The status indicates a body, hence the record is missing or corrupted and should be re-downloaded
+                warn!("Quarantining block {} due to an unreadable body: {}", hash, err);
+                self.quarantine_blocks_impl(&[hash]);
+                return Err(ConsensusError::BlockNotFound(hash));
+            }
+        };
+        Ok(Block { header, transactions })
+    }
+
+    fn get_blocks_transactions(&self, hashes: &[Hash]) -> ConsensusResult<Vec<Arc<Vec<Transaction>>>> {
+        let transactions = self.block_transactions_store.multi_get(hashes).unwrap();
+        hashes.iter().zip(transactions).map(|(&hash, transactions)| transactions.ok_or(ConsensusError::BlockNotFound(hash))).collect()
     }
 
     fn get_block_even_if_header_only(&self, hash: Hash) -> ConsensusResult<Block> {
@@ -749,6 +938,19 @@ impl ConsensusApi for Consensus {
         })
     }
 
+    fn get_archived_block(&self, hash: Hash) -> ConsensusResult<Block> {
+        let Some(era_reader) = self.era_reader.as_ref() else {
+            return Err(ConsensusError::BlockNotFound(hash));
+        };
+        match era_reader.get_block(hash) {
+            Ok(block) => block.ok_or(ConsensusError::BlockNotFound(hash)),
+            Err(err) => {
+                warn!("Failed reading archived block {}: {}", hash, err);
+                Err(ConsensusError::BlockNotFound(hash))
+            }
+        }
+    }
+
     fn get_ghostdag_data(&self, hash: Hash) -> ConsensusResult<ExternalGhostdagData> {
         match self.get_block_status(hash) {
             None => return Err(ConsensusError::HeaderNotFound(hash)),
@@ -767,7 +969,7 @@ impl ConsensusApi for Consensus {
             .map(|children| children.read().iter().copied().collect_vec())
     }
 
-    fn get_block_parents(&self, hash: Hash) -> Option<Arc<Vec<Hash>>> {
+    fn get_block_parents(&self, hash: Hash) -> Option<BlockHashes> {
         self.services.relations_service.get_parents(hash).unwrap_option()
     }
 
@@ -780,15 +982,70 @@ impl ConsensusApi for Consensus {
     }
 
     fn get_blocks_acceptance_data(&self, hashes: &[Hash]) -> ConsensusResult<Vec<Arc<AcceptanceData>>> {
-        hashes
-            .iter()
-            .copied()
-            .map(|hash| self.acceptance_data_store.get(hash).unwrap_option().ok_or(ConsensusError::MissingData(hash)))
-            .collect::<ConsensusResult<Vec<_>>>()
+        let acceptance_data = self.acceptance_data_store.multi_get(hashes).unwrap();
+        hashes.iter().zip(acceptance_data).map(|(&hash, data)| data.ok_or(ConsensusError::MissingData(hash))).collect()
+    }
+
+    fn get_chain_block_mergeset_utxo_diffs(&self, hash: Hash) -> ConsensusResult<Vec<MergesetBlockUtxoDiff>> {
+        // We need consistency between the selected chain, acceptance data and UTXO diff reads
+        let _guard = self.pruning_lock.blocking_read();
+        self.validate_block_exists(hash)?;
+        if !self.storage.selected_chain_store.read().is_chain_block(hash).unwrap() {
+            return Err(ConsensusError::NotChainBlock(hash));
+        }
+
+        let acceptance_data = self.acceptance_data_store.get(hash).unwrap_option().ok_or(ConsensusError::MissingData(hash))?;
+        // The UTXO diff of the chain block holds the entries spent out of the selected parent state
+        let utxo_diff = self.utxo_diffs_store.get(hash).unwrap_option().ok_or(ConsensusError::MissingData(hash))?;
+        let mergeset_blues = self.ghostdag_primary_store.get_mergeset_blues(hash).unwrap();
+        let pov_daa_score = self.headers_store.get_daa_score(hash).unwrap();
+
+        // Replay the accepted transactions in consensus order (see `calculate_utxo_state`), tracking the entries
+        // created along the way since a transaction may spend an output created by a previous mergeset block
+        let mut created: HashMap<TransactionOutpoint, UtxoEntry> = HashMap::new();
+        let mut mergeset_utxo_diffs = Vec::with_capacity(acceptance_data.len());
+        for mergeset_block in acceptance_data.iter() {
+            let transactions = self
+                .block_transactions_store
+                .get(mergeset_block.block_hash)
+                .unwrap_option()
+                .ok_or(ConsensusError::MissingData(mergeset_block.block_hash))?;
+            let mut block_utxo_diff = UtxoDiff::default();
+            for accepted in mergeset_block.accepted_transactions.iter() {
+                let tx = transactions
+                    .get(accepted.index_within_block as usize)
+                    .ok_or(ConsensusError::MissingData(mergeset_block.block_hash))?;
+                let entries = tx
+                    .inputs
+                    .iter()
+                    .map(|input| {
+                        created
+                            .remove(&input.previous_outpoint)
+                            .or_else(|| utxo_diff.remove.get(&input.previous_outpoint).cloned())
+                            .ok_or(ConsensusError::MissingData(hash))
+                    })
+                    .collect::<ConsensusResult<Vec<_>>>()?;
+                let populated_tx = PopulatedTransaction::new(tx, entries);
+                block_utxo_diff.add_transaction(&populated_tx, pov_daa_score).map_err(|_| ConsensusError::MissingData(hash))?;
+                let tx_id = tx.id();
+                created.extend(tx.outputs.iter().enumerate().map(|(i, output)| {
+                    let entry = UtxoEntry::new(output.value, output.script_public_key.clone(), pov_daa_score, tx.is_coinbase());
+                    (TransactionOutpoint::new(tx_id, i as u32), entry)
+                }));
+            }
+            mergeset_utxo_diffs.push(MergesetBlockUtxoDiff {
+                block_hash: mergeset_block.block_hash,
+                is_blue: mergeset_blues.contains(&mergeset_block.block_hash),
+                utxo_diff: block_utxo_diff,
+            });
+        }
+        Ok(mergeset_utxo_diffs)
     }
 
     fn is_chain_block(&self, hash: Hash) -> ConsensusResult<bool> {
-        self.is_chain_ancestor_of(hash, self.get_sink())
+        let _guard = self.pruning_lock.blocking_read();
+        self.validate_block_exists(hash)?;
+        Ok(self.is_selected_chain_block(hash))
     }
 
     fn get_missing_block_body_hashes(&self, high: Hash) -> ConsensusResult<Vec<Hash>> {
@@ -797,6 +1054,276 @@ impl ConsensusApi for Consensus {
         Ok(self.services.sync_manager.get_missing_block_body_hashes(high)?)
     }
 
+    fn verify_chain_integrity(&self, high: Hash, max_chain_blocks: usize) -> ConsensusResult<IntegrityReport> {
+        let _guard = self.pruning_lock.blocking_read();
+        self.validate_block_exists(high)?;
+        Ok(self.verify_chain_integrity_impl(high, max_chain_blocks))
+    }
+
+    fn quarantine_blocks(&self, hashes: &[Hash]) -> usize {
+        self.quarantine_blocks_impl(hashes)
+    }
+
+    fn get_quarantined_blocks(&self) -> Vec<Hash> {
+        let _guard = self.pruning_lock.blocking_read();
+        self.get_quarantined_blocks_impl()
+    }
+
+    fn repair_quarantined_block(&self, block: Block) -> ConsensusResult<()> {
+        let _guard = self.pruning_lock.blocking_read();
+        self.repair_quarantined_block_impl(block)
+    }
+
+    fn revalidate_invalid_blocks(&self, rule_code: u16) -> Vec<Hash> {
+        let _guard = self.pruning_lock.blocking_read();
+        self.revalidate_invalid_blocks_impl(rule_code)
+    }
+
+    fn revalidate_blocks_by_version(&self, version: u16) -> Vec<Hash> {
+        let _guard = self.pruning_lock.blocking_read();
+        self.revalidate_blocks_by_version_impl(version)
+    }
+
+    fn invalidate_block(&self, hash: Hash) -> ConsensusResult<()> {
+        self.invalidate_block_impl(hash)
+    }
+
+    fn reconsider_block(&self, hash: Hash) -> ConsensusResult<()> {
+        self.reconsider_block_impl(hash)
+    }
+
+    fn get_block_validation_trace(&self, hash: Hash) -> Option<BlockValidationTrace> {
+        self.tracer.get(hash)
+    }
+
+    fn get_pipeline_backlog(&self) -> usize {
+        self.header_processor.backlog() + self.body_processor.backlog() + self.virtual_processor.backlog()
+    }
+
+    fn get_network_stats(&self) -> NetworkStats {
+        self.network_stats.stats()
+    }
+
+    fn get_storage_stats(&self) -> StorageStats {
+        let stats = self.db.stats();
+        StorageStats {
+            write_amplification: stats.write_amplification,
+            pending_compaction_bytes: stats.pending_compaction_bytes,
+            running_compactions: stats.running_compactions,
+            stall_seconds: stats.stall_seconds,
+            delayed_write_rate: stats.delayed_write_rate,
+            is_write_stopped: stats.is_write_stopped,
+        }
+    }
+
+    fn get_block_dag_timing_info(&self, hash: Hash) -> ConsensusResult<BlockDagTimingInfo> {
+        let _guard = self.pruning_lock.blocking_read();
+        self.validate_block_exists(hash)?;
+        let header = self.get_header(hash)?;
+        let ghostdag_data = self.ghostdag_primary_store.get_data(hash).unwrap_option().ok_or(ConsensusError::MissingData(hash))?;
+        let window_manager = &self.services.window_manager;
+        let daa_window = window_manager.block_daa_window(&ghostdag_data).map_err(|_| ConsensusError::MissingData(hash))?;
+        let expected_bits = window_manager.calculate_difficulty_bits(&ghostdag_data, &daa_window);
+        let (past_median_time, _) =
+            window_manager.calc_past_median_time(&ghostdag_data).map_err(|_| ConsensusError::MissingData(hash))?;
+
+        let (lowest_block, highest_block) = match daa_window.window.iter().map(|block| &block.0).minmax().into_option() {
+            Some((lowest, highest)) => (Some(lowest.hash), Some(highest.hash)),
+            None => (None, None),
+        };
+        let (min_timestamp, max_timestamp) = daa_window
+            .window
+            .iter()
+            .map(|block| self.headers_store.get_timestamp(block.0.hash).unwrap())
+            .minmax()
+            .into_option()
+            .unwrap_or_default();
+
+        Ok(BlockDagTimingInfo {
+            hash,
+            timestamp: header.timestamp,
+            daa_score: header.daa_score,
+            bits: header.bits,
+            expected_bits,
+            past_median_time,
+            difficulty_window: DifficultyWindowInfo {
+                size: daa_window.window.len() as u64,
+                sample_rate: window_manager.sample_rate(&ghostdag_data, WindowType::SampledDifficultyWindow),
+                lowest_block,
+                highest_block,
+                min_timestamp,
+                max_timestamp,
+            },
+        })
+    }
+
+    fn get_state_digests(&self, count: usize) -> Vec<StateDigest> {
+        // The number of chain blocks walked under a single acquisition of the pruning lock
+        const BATCH_SIZE: usize = 1000;
+
+        let interval = self.config.state_digest_interval;
+        let mut digests = Vec::with_capacity(count);
+        let (mut current, mut blue_score) = {
+            let _guard = self.pruning_lock.blocking_read();
+            let sink = self.get_sink();
+            (sink, self.headers_store.get_blue_score(sink).unwrap())
+        };
+        let mut walked = 0;
+        while digests.len() < count && walked < MAX_STATE_DIGEST_CHAIN_WALK {
+            // The lock is released between batches so that a long walk does not hold off pruning
+            let _guard = self.pruning_lock.blocking_read();
+            let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
+            for _ in 0..BATCH_SIZE.min(MAX_STATE_DIGEST_CHAIN_WALK - walked) {
+                if digests.len() == count || current == pruning_point {
+                    return digests;
+                }
+                // The rest of the walk might have been pruned while the lock was released
+                let Some(selected_parent) = self.ghostdag_primary_store.get_selected_parent(current).unwrap_option() else {
+                    return digests;
+                };
+                let Some(selected_parent_blue_score) = self.headers_store.get_blue_score(selected_parent).unwrap_option() else {
+                    return digests;
+                };
+                if StateDigest::is_checkpoint(blue_score, selected_parent_blue_score, interval) {
+                    let header = self.headers_store.get_header(current).unwrap();
+                    digests.push(StateDigest::new(current, blue_score, header.daa_score, header.utxo_commitment));
+                }
+                (current, blue_score) = (selected_parent, selected_parent_blue_score);
+                walked += 1;
+            }
+        }
+        digests
+    }
+
+    fn get_dag_blocks_around(&self, hash: Hash, radius: u32, max_blocks: usize) -> ConsensusResult<Vec<DagBlockInfo>> {
+        let _guard = self.pruning_lock.blocking_read();
+        self.validate_block_exists(hash)?;
+
+        // Breadth-first traversal, so that truncating to `max_blocks` keeps the closest blocks
+        let mut blocks = Vec::new();
+        let mut visited = BlockHashSet::from_iter([hash]);
+        let mut queue = VecDeque::from([(hash, 0)]);
+        while let Some((current, distance)) = queue.pop_front() {
+            if blocks.len() == max_blocks {
+                break;
+            }
+            // Blocks lacking GHOSTDAG data (e.g. pruned ones) are skipped
+            let Some(ghostdag_data) = self.ghostdag_primary_store.get_data(current).unwrap_option() else { continue };
+            let header = self.headers_store.get_header(current).unwrap();
+            let parents = self.get_block_parents(current).map(|parents| parents.to_vec()).unwrap_or_default();
+            let children = self.get_block_children(current).unwrap_or_default();
+            if distance < radius {
+                for &neighbor in parents.iter().chain(children.iter()) {
+                    if visited.insert(neighbor) {
+                        queue.push_back((neighbor, distance + 1));
+                    }
+                }
+            }
+            blocks.push(DagBlockInfo {
+                hash: current,
+                distance,
+                timestamp: header.timestamp,
+                daa_score: header.daa_score,
+                blue_score: ghostdag_data.blue_score,
+                parents,
+                children,
+                selected_parent: ghostdag_data.selected_parent,
+                mergeset_blues: ghostdag_data.mergeset_blues.to_vec(),
+                mergeset_reds: ghostdag_data.mergeset_reds.to_vec(),
+                is_chain_block: self.is_selected_chain_block(current),
+            });
+        }
+        Ok(blocks)
+    }
+
+    fn get_chain_headers(&self, start: Hash, limit: usize, is_ascending: bool) -> ConsensusResult<Vec<Arc<Header>>> {
+        // We need consistency between the pruning point, selected chain and header store reads
+        let _guard = self.pruning_lock.blocking_read();
+        self.validate_block_exists(start)?;
+
+        let sc_read = self.storage.selected_chain_store.read();
+        let start_index = sc_read.get_by_hash(start).unwrap_option().ok_or(ConsensusError::NotChainBlock(start))?;
+        let indices: Vec<u64> = if is_ascending {
+            let high_index = sc_read.get_tip().unwrap().0;
+            (start_index..=high_index).take(limit).collect()
+        } else {
+            // The selected chain store is pruned below the pruning point
+            let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
+            let low_index = sc_read.get_by_hash(pruning_point).unwrap_option().unwrap_or(start_index).min(start_index);
+            (low_index..=start_index).rev().take(limit).collect()
+        };
+        Ok(indices
+            .into_iter()
+            .map(|index| self.headers_store.get_header(sc_read.get_by_index(index).expect("store lock is acquired")).unwrap())
+            .collect())
+    }
+
+    fn get_transaction_acceptance_proof(
+        &self,
+        transaction_id: TransactionId,
+        accepting_block_hash: Hash,
+        max_chain_headers: usize,
+    ) -> ConsensusResult<TransactionAcceptanceProof> {
+        // We need consistency between the selected chain, acceptance data and header store reads
+        let _guard = self.pruning_lock.blocking_read();
+        self.validate_block_exists(accepting_block_hash)?;
+
+        let sc_read = self.storage.selected_chain_store.read();
+        let accepting_index =
+            sc_read.get_by_hash(accepting_block_hash).unwrap_option().ok_or(ConsensusError::NotChainBlock(accepting_block_hash))?;
+        let acceptance_data = self
+            .acceptance_data_store
+            .get(accepting_block_hash)
+            .unwrap_option()
+            .ok_or(ConsensusError::MissingData(accepting_block_hash))?;
+
+        // The accepted ID merkle root is built over the sorted accepted IDs (see `calculate_utxo_state`)
+        let mut accepted_ids = acceptance_data
+            .iter()
+            .flat_map(|mergeset_block| mergeset_block.accepted_transactions.iter().map(|entry| entry.transaction_id))
+            .collect_vec();
+        accepted_ids.sort();
+        let accepted_id_index = accepted_ids
+            .binary_search(&transaction_id)
+            .map_err(|_| ConsensusError::TransactionNotAccepted(transaction_id, accepting_block_hash))?;
+        let accepted_id_merkle_branch = kaspa_merkle::create_merkle_branch(accepted_ids.into_iter(), accepted_id_index)
+            .expect("the index is within the accepted IDs");
+
+        let high_index = sc_read.get_tip().unwrap().0;
+        let chain_headers = (accepting_index..=high_index)
+            .take(max_chain_headers.max(1))
+            .map(|index| {
+                let hash = sc_read.get_by_index(index).expect("store lock is acquired");
+                self.headers_store.get_header(hash).unwrap().as_ref().clone()
+            })
+            .collect_vec();
+
+        // The non-chain parents of the chain blocks let the verifier check that each link goes through the selected parent
+        let chain_hashes = chain_headers.iter().map(|header| header.hash).collect::<BlockHashSet>();
+        let parent_headers = chain_headers
+            .iter()
+            .skip(1)
+            .flat_map(|header| header.direct_parents().iter().copied())
+            .filter(|parent| !chain_hashes.contains(parent))
+            .unique()
+            .map(|parent| {
+                self.headers_store
+                    .get_header(parent)
+                    .unwrap_option()
+                    .map(|header| header.as_ref().clone())
+                    .ok_or(ConsensusError::MissingData(parent))
+            })
+            .collect::<ConsensusResult<Vec<_>>>()?;
+
+        Ok(TransactionAcceptanceProof {
+            transaction_id,
+            accepted_id_index: accepted_id_index as u32,
+            accepted_id_merkle_branch,
+            chain_headers,
+            parent_headers,
+        })
+    }
+
     fn pruning_point(&self) -> Hash {
         self.pruning_point_store.read().pruning_point().unwrap()
     }
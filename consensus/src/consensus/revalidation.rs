@@ -0,0 +1,179 @@
+use super::Consensus;
+use crate::{
+    model::stores::{
+        invalid_blocks::{InvalidBlockRecord, InvalidBlocksStore},
+        statuses::{StatusesStore, StatusesStoreReader},
+    },
+    pipeline::deps_manager::BlockTask,
+};
+use itertools::Itertools;
+use kaspa_consensus_core::{block::BlockOrigin, blockstatus::BlockStatus};
+use kaspa_core::info;
+use kaspa_database::prelude::StoreResultExtensions;
+use kaspa_hashes::Hash;
+use std::sync::Arc;
+
+impl Consensus {
+    /// Clears the `StatusInvalid` marks of blocks which were rejected for violating the rule with code `rule_code`
+    /// and resubmits them for processing. Meant for recovering, without a resync, from a validation bug which wrongly
+    /// rejected blocks. Returns the hashes of the resubmitted blocks. Assumes the pruning lock is held by the caller
+    pub(super) fn revalidate_invalid_blocks_impl(&self, rule_code: u16) -> Vec<Hash> {
+        let records =
+            self.invalid_blocks_store.iterator().map(|r| r.unwrap()).filter(|(_, record)| record.rule_code == rule_code).collect_vec();
+        let hashes = self.resubmit_invalid_blocks(records);
+        if !hashes.is_empty() {
+            info!("Resubmitted {} blocks marked invalid by rule {} for processing", hashes.len(), rule_code);
+        }
+        hashes
+    }
+
+    /// Same as [`Self::revalidate_invalid_blocks_impl`], only for blocks which were rejected by the validation logic of
+    /// version `version` (see `VALIDATION_VERSION`), regardless of the violated rule
+    pub(super) fn revalidate_blocks_by_version_impl(&self, version: u16) -> Vec<Hash> {
+        let records = {
+            let statuses_store = self.statuses_store.read();
+            self.invalid_blocks_store
+                .iterator()
+                .map(|r| r.unwrap())
+                .filter(|(hash, _)| statuses_store.validation_version(*hash).unwrap() == version)
+                .collect_vec()
+        };
+        let hashes = self.resubmit_invalid_blocks(records);
+        if !hashes.is_empty() {
+            info!("Resubmitted {} blocks marked invalid by validation version {} for processing", hashes.len(), version);
+        }
+        hashes
+    }
+
+    /// Clears the invalid marks of the blocks of `records` and resubmits them for processing
+    fn resubmit_invalid_blocks(&self, mut records: Vec<(Hash, Arc<InvalidBlockRecord>)>) -> Vec<Hash> {
+        {
+            let mut statuses_store = self.statuses_store.write();
+            records.retain(|(hash, _)| {
+                self.invalid_blocks_store.delete(*hash).unwrap();
+                if statuses_store.get(*hash).unwrap_option() != Some(BlockStatus::StatusInvalid) {
+                    return false;
+                }
+                // Blocks rejected by a body rule already have a valid header, while blocks rejected by
+                // a header rule are forgotten altogether
+                if self.headers_store.has(*hash).unwrap() {
+                    statuses_store.set(*hash, BlockStatus::StatusHeaderOnly).unwrap();
+                } else {
+                    statuses_store.delete(*hash).unwrap();
+                }
+                true
+            });
+        }
+
+        // Parents always have a lower blue work than their children, so blocks are resubmitted in topological order
+        records.sort_by_key(|(_, record)| record.header.blue_work);
+        for (_, record) in records.iter() {
+            // The processing results are not awaited, and are reported as usual by the pipeline
+            let _ = self.validate_and_insert_block_impl(BlockTask::Ordinary { block: record.to_block(), origin: BlockOrigin::Local });
+        }
+        records.into_iter().map(|(hash, _)| hash).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::ConfigBuilder,
+        consensus::test_consensus::TestConsensus,
+        errors::RuleError,
+        model::stores::{
+            invalid_blocks::{InvalidBlockRecord, InvalidBlocksStore, InvalidBlocksStoreReader},
+            statuses::{StatusesStore, StatusesStoreReader},
+        },
+    };
+    use kaspa_consensus_core::{
+        api::ConsensusApi, blockstatus::BlockStatus, coinbase::MinerData, config::params::DEVNET_PARAMS,
+        constants::VALIDATION_VERSION, tx::ScriptPublicKey,
+    };
+    use kaspa_hashes::Hash;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_revalidate_invalid_blocks() {
+        let config = ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work().build();
+        let consensus = TestConsensus::new(&config);
+        let wait_handles = consensus.init();
+
+        let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![]), vec![]);
+        let hash = Hash::from_u64_word(1);
+        let block = consensus.build_utxo_valid_block_with_parents(hash, vec![config.genesis.hash], miner_data, vec![]).to_immutable();
+
+        // Simulate a block which was wrongly rejected for an unexpected difficulty
+        let rule_code = RuleError::UnexpectedDifficulty(0, 0).code();
+        consensus.invalid_blocks_store.insert(hash, Arc::new(InvalidBlockRecord::new(rule_code, &block))).unwrap();
+        consensus.statuses_store.write().set(hash, BlockStatus::StatusInvalid).unwrap();
+        let result = consensus.validate_and_insert_block(block.clone()).virtual_state_task.await;
+        assert!(matches!(result, Err(RuleError::KnownInvalid)));
+
+        // Only blocks rejected by the given rule are revalidated, and only once
+        assert!(consensus.revalidate_invalid_blocks(RuleError::InvalidPoW.code()).is_empty());
+        assert_eq!(consensus.revalidate_invalid_blocks(rule_code), vec![hash]);
+        assert!(consensus.revalidate_invalid_blocks(rule_code).is_empty());
+
+        // A resubmission completes along with the revalidation
+        let status = consensus.validate_and_insert_block(block).virtual_state_task.await.unwrap();
+        assert_eq!(status, BlockStatus::StatusUTXOValid);
+
+        consensus.shutdown(wait_handles);
+    }
+    #[tokio::test]
+    async fn test_revalidate_blocks_by_version() {
+        let config = ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work().build();
+        let consensus = TestConsensus::new(&config);
+        let wait_handles = consensus.init();
+
+        let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![]), vec![]);
+        let valid_hash = Hash::from_u64_word(1);
+        let valid_block = consensus
+            .build_utxo_valid_block_with_parents(valid_hash, vec![config.genesis.hash], miner_data.clone(), vec![])
+            .to_immutable();
+        let status = consensus.validate_and_insert_block(valid_block).virtual_state_task.await.unwrap();
+        assert_eq!(status, BlockStatus::StatusUTXOValid);
+        assert_eq!(consensus.statuses_store.read().validation_version(valid_hash).unwrap(), VALIDATION_VERSION);
+        // Blocks whose status was never written report version 0
+        assert_eq!(consensus.statuses_store.read().validation_version(Hash::from_u64_word(100)).unwrap(), 0);
+
+        // Simulate a block which was wrongly rejected by the current validation logic
+        let hash = Hash::from_u64_word(2);
+        let block = consensus.build_utxo_valid_block_with_parents(hash, vec![valid_hash], miner_data, vec![]).to_immutable();
+        let rule_code = RuleError::UnexpectedDifficulty(0, 0).code();
+        consensus.invalid_blocks_store.insert(hash, Arc::new(InvalidBlockRecord::new(rule_code, &block))).unwrap();
+        consensus.statuses_store.write().set(hash, BlockStatus::StatusInvalid).unwrap();
+
+        // Only invalid blocks stamped by the given version are revalidated
+        assert!(consensus.revalidate_blocks_by_version(VALIDATION_VERSION + 1).is_empty());
+        assert_eq!(consensus.revalidate_blocks_by_version(VALIDATION_VERSION), vec![hash]);
+        assert!(consensus.revalidate_blocks_by_version(VALIDATION_VERSION).is_empty());
+
+        let status = consensus.validate_and_insert_block(block).virtual_state_task.await.unwrap();
+        assert_eq!(status, BlockStatus::StatusUTXOValid);
+
+        consensus.shutdown(wait_handles);
+    }
+
+    #[tokio::test]
+    async fn test_prune_invalid_blocks() {
+        let config = ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work().build();
+        let consensus = TestConsensus::new(&config);
+        let wait_handles = consensus.init();
+
+        let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![]), vec![]);
+        let hash = Hash::from_u64_word(1);
+        let block = consensus.build_utxo_valid_block_with_parents(hash, vec![config.genesis.hash], miner_data, vec![]).to_immutable();
+        let rule_code = RuleError::UnexpectedDifficulty(0, 0).code();
+        consensus.invalid_blocks_store.insert(hash, Arc::new(InvalidBlockRecord::new(rule_code, &block))).unwrap();
+
+        // Only records of blocks not above the given DAA score are pruned
+        assert_eq!(consensus.invalid_blocks_store.prune_below(block.header.daa_score - 1).unwrap(), 0);
+        assert!(consensus.invalid_blocks_store.get(hash).is_ok());
+        assert_eq!(consensus.invalid_blocks_store.prune_below(block.header.daa_score).unwrap(), 1);
+        assert!(consensus.invalid_blocks_store.get(hash).is_err());
+
+        consensus.shutdown(wait_handles);
+    }
+}
@@ -0,0 +1,48 @@
+use super::Consensus;
+
+impl Consensus {
+    /// Initializes the roots of the DAG if this is a new consensus, and is a no-op otherwise. With `config.process_genesis`
+    /// the DAG is rooted at genesis, which is registered as pruning point and processed as the first block. Otherwise, the
+    /// DAG is rooted at an imported pruning point: the pruning point-related stores are left empty, and it is up to
+    /// `apply_pruning_proof` and `import_pruning_points` to register the pruning point and its trusted anticone as roots.
+    /// Registering genesis in this mode would leave a stale history root and pruning UTXO set position behind
+    pub(super) fn init_roots(&self) {
+        // The relations of all levels are rooted at the virtual genesis (ORIGIN) in both modes
+        self.header_processor.init();
+
+        if self.config.process_genesis {
+            self.virtual_processor.init_genesis_roots();
+            self.header_processor.process_genesis();
+            self.body_processor.process_genesis();
+            self.virtual_processor.process_genesis();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::ConfigBuilder,
+        consensus::test_consensus::TestConsensus,
+        model::stores::{pruning::PruningStoreReader, relations::RelationsStoreReader},
+    };
+    use kaspa_consensus_core::{blockhash::ORIGIN, config::params::DEVNET_PARAMS};
+    use kaspa_database::prelude::StoreResultExtensions;
+
+    #[test]
+    fn test_init_roots() {
+        let config = ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work().build();
+        let consensus = TestConsensus::new(&config);
+        assert_eq!(consensus.pruning_point_store.read().pruning_point().unwrap(), config.genesis.hash);
+        assert_eq!(consensus.pruning_point_store.read().history_root().unwrap(), config.genesis.hash);
+        assert!(consensus.headers_store.has(config.genesis.hash).unwrap());
+
+        // A consensus awaiting a pruning point import has no roots other than ORIGIN, and genesis is never processed
+        let config = ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work().skip_adding_genesis().build();
+        let consensus = TestConsensus::new(&config);
+        assert!(consensus.relations_stores.read()[0].has(ORIGIN).unwrap());
+        assert!(consensus.pruning_point_store.read().pruning_point().unwrap_option().is_none());
+        assert!(consensus.pruning_point_store.read().history_root().unwrap_option().is_none());
+        assert!(!consensus.headers_store.has(config.genesis.hash).unwrap());
+    }
+}
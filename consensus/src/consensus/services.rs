@@ -133,6 +133,7 @@ impl ConsensusServices {
 
         let coinbase_manager = CoinbaseManager::new(
             params.coinbase_payload_script_public_key_max_len,
+            params.coinbase_payload_script_public_key_max_version,
             params.max_coinbase_payload_len,
             params.deflationary_phase_daa_score,
             params.pre_deflationary_phase_base_subsidy,
@@ -150,6 +151,7 @@ impl ConsensusServices {
             params.ghostdag_k,
             params.coinbase_payload_script_public_key_max_len,
             params.coinbase_maturity,
+            params.introspection_opcodes,
             tx_script_cache_counters,
         );
 
@@ -9,9 +9,12 @@ use crate::{
         ghostdag::{DbGhostdagStore, GhostdagData},
         headers::DbHeadersStore,
         headers_selected_tip::DbHeadersSelectedTipStore,
+        invalid_blocks::DbInvalidBlocksStore,
+        manual_invalidations::DbManualInvalidationsStore,
         past_pruning_points::DbPastPruningPointsStore,
         pruning::DbPruningStore,
         pruning_utxoset::PruningUtxosetStores,
+        quarantine::DbQuarantineStore,
         reachability::{DbReachabilityStore, ReachabilityData},
         relations::DbRelationsStore,
         selected_chain::DbSelectedChainStore,
@@ -28,6 +31,7 @@ use crate::{
 use itertools::Itertools;
 
 use kaspa_consensus_core::{blockstatus::BlockStatus, config::constants::perf, BlockHashSet};
+use kaspa_database::prelude::ScanOptions;
 use kaspa_database::registry::DatabaseStorePrefixes;
 use kaspa_hashes::Hash;
 use parking_lot::RwLock;
@@ -49,6 +53,8 @@ pub struct ConsensusStorage {
     pub pruning_utxoset_stores: Arc<RwLock<PruningUtxosetStores>>,
     pub virtual_stores: Arc<RwLock<VirtualStores>>,
     pub selected_chain_store: Arc<RwLock<DbSelectedChainStore>>,
+    pub quarantine_store: Arc<RwLock<DbQuarantineStore>>,
+    pub manual_invalidations_store: Arc<RwLock<DbManualInvalidationsStore>>,
 
     // Append-only stores
     pub ghostdag_stores: Arc<Vec<Arc<DbGhostdagStore>>>,
@@ -58,6 +64,7 @@ pub struct ConsensusStorage {
     pub past_pruning_points_store: Arc<DbPastPruningPointsStore>,
     pub daa_excluded_store: Arc<DbDaaStore>,
     pub depth_store: Arc<DbDepthStore>,
+    pub invalid_blocks_store: Arc<DbInvalidBlocksStore>,
 
     // Utxo-related stores
     pub utxo_diffs_store: Arc<DbUtxoDiffsStore>,
@@ -140,8 +147,10 @@ impl ConsensusStorage {
         // Pruning
         let pruning_point_store = Arc::new(RwLock::new(DbPruningStore::new(db.clone())));
         let past_pruning_points_store = Arc::new(DbPastPruningPointsStore::new(db.clone(), 4));
+        // The UTXO sets are scanned in full when moving the pruning point and when importing or exporting a UTXO set
+        let scan_options = ScanOptions::sequential(perf_params.scan_readahead_size);
         let pruning_utxoset_stores =
-            Arc::new(RwLock::new(PruningUtxosetStores::new(db.clone(), noise(perf_params.utxo_set_cache_size))));
+            Arc::new(RwLock::new(PruningUtxosetStores::new(db.clone(), noise(perf_params.utxo_set_cache_size), scan_options)));
 
         // Txs
         let block_transactions_store = Arc::new(DbBlockTransactionsStore::new(db.clone(), noise(perf_params.block_data_cache_size)));
@@ -153,12 +162,18 @@ impl ConsensusStorage {
         let headers_selected_tip_store = Arc::new(RwLock::new(DbHeadersSelectedTipStore::new(db.clone())));
         let body_tips_store = Arc::new(RwLock::new(DbTipsStore::new(db.clone())));
 
+        // Quarantine
+        let quarantine_store = Arc::new(RwLock::new(DbQuarantineStore::new(db.clone())));
+        let invalid_blocks_store = Arc::new(DbInvalidBlocksStore::new(db.clone(), 16));
+        let manual_invalidations_store = Arc::new(RwLock::new(DbManualInvalidationsStore::new(db.clone())));
+
         // Block windows
         let block_window_cache_for_difficulty = Arc::new(BlockWindowCacheStore::new(noise(perf_params.block_window_cache_size)));
         let block_window_cache_for_past_median_time = Arc::new(BlockWindowCacheStore::new(noise(perf_params.block_window_cache_size)));
 
         // Virtual stores
-        let virtual_stores = Arc::new(RwLock::new(VirtualStores::new(db.clone(), noise(perf_params.utxo_set_cache_size))));
+        let virtual_stores =
+            Arc::new(RwLock::new(VirtualStores::new(db.clone(), noise(perf_params.utxo_set_cache_size), scan_options)));
 
         // Ensure that reachability stores are initialized
         reachability::init(reachability_store.write().deref_mut()).unwrap();
@@ -180,6 +195,9 @@ impl ConsensusStorage {
             pruning_utxoset_stores,
             virtual_stores,
             selected_chain_store,
+            quarantine_store,
+            invalid_blocks_store,
+            manual_invalidations_store,
             acceptance_data_store,
             past_pruning_points_store,
             daa_excluded_store,
@@ -26,8 +26,12 @@ use crate::{
     model::{
         services::reachability::MTReachabilityService,
         stores::{
-            ghostdag::DbGhostdagStore, headers::HeaderStoreReader, pruning::PruningStoreReader, reachability::DbReachabilityStore,
-            virtual_state::VirtualStores, DB,
+            ghostdag::{DbGhostdagStore, GhostdagData},
+            headers::HeaderStoreReader,
+            pruning::PruningStoreReader,
+            reachability::DbReachabilityStore,
+            virtual_state::VirtualStores,
+            DB,
         },
     },
     params::Params,
@@ -43,6 +47,8 @@ pub struct TestConsensus {
     consensus: Arc<Consensus>,
     block_builder: TestBlockBuilder,
     db_lifetime: DbLifetime,
+    /// Whether UTXO valid blocks are timestamped by a simulated clock rather than by the wall clock
+    simulated_clock: bool,
 }
 
 impl TestConsensus {
@@ -62,7 +68,7 @@ impl TestConsensus {
         ));
         let block_builder = TestBlockBuilder::new(consensus.virtual_processor.clone());
 
-        Self { params: config.params.clone(), consensus, block_builder, db_lifetime: Default::default() }
+        Self { params: config.params.clone(), consensus, block_builder, db_lifetime: Default::default(), simulated_clock: false }
     }
 
     /// Creates a test consensus instance based on `config` with a temp DB and the provided `notification_sender`
@@ -82,7 +88,7 @@ impl TestConsensus {
         ));
         let block_builder = TestBlockBuilder::new(consensus.virtual_processor.clone());
 
-        Self { consensus, block_builder, params: config.params.clone(), db_lifetime }
+        Self { consensus, block_builder, params: config.params.clone(), db_lifetime, simulated_clock: false }
     }
 
     /// Creates a test consensus instance based on `config` with a temp DB and no notifier
@@ -103,7 +109,15 @@ impl TestConsensus {
         ));
         let block_builder = TestBlockBuilder::new(consensus.virtual_processor.clone());
 
-        Self { consensus, block_builder, params: config.params.clone(), db_lifetime }
+        Self { consensus, block_builder, params: config.params.clone(), db_lifetime, simulated_clock: false }
+    }
+
+    /// Timestamps the UTXO valid blocks built from now on by a simulated clock which advances by the target time per
+    /// block with each unit of DAA score, so that the difficulty remains constant regardless of the rate at which
+    /// blocks are built (see [`Params::for_testing`])
+    pub fn with_simulated_clock(mut self) -> Self {
+        self.simulated_clock = true;
+        self
     }
 
     /// Clone the inner consensus Arc. For general usage of the underlying consensus simply deref
@@ -155,11 +169,23 @@ impl TestConsensus {
         miner_data: MinerData,
         txs: Vec<Transaction>,
     ) -> MutableBlock {
+        let ghostdag_data = self.simulated_clock.then(|| self.consensus.services.ghostdag_primary_manager.ghostdag(&parents));
         let mut template = self.block_builder.build_block_template_with_parents(parents, miner_data, txs).unwrap();
         template.block.header.hash = hash;
+        if let Some(ghostdag_data) = ghostdag_data {
+            template.block.header.timestamp = self.simulated_timestamp(template.block.header.daa_score, &ghostdag_data);
+        }
         template.block
     }
 
+    /// Returns the timestamp of the simulated clock (see [`Self::with_simulated_clock`]) at `daa_score`
+    fn simulated_timestamp(&self, daa_score: u64, ghostdag_data: &GhostdagData) -> u64 {
+        let past_median_time = self.consensus.services.window_manager.calc_past_median_time(ghostdag_data).unwrap().0;
+        let simulated_time = self.params.genesis.timestamp
+            + daa_score.saturating_sub(self.params.genesis.daa_score) * self.params.target_time_per_block;
+        simulated_time.max(past_median_time + 1)
+    }
+
     pub fn build_block_with_parents_and_transactions(
         &self,
         hash: Hash,
@@ -0,0 +1,105 @@
+use crate::model::stores::utxo_set::DbUtxoSetStore;
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
+use kaspa_consensus_core::{
+    errors::consensus::{ConsensusError, ConsensusResult},
+    tx::{TransactionOutpoint, UtxoEntry},
+    utxo::export::{UtxoSetChunk, UtxoSetCursor},
+};
+use kaspa_core::debug;
+use kaspa_database::prelude::DB;
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// A pinned snapshot prevents RocksDB from reclaiming the space of the entries deleted since, so the number of
+/// concurrent exports is bounded and an abandoned export is released after some idle time
+const MAX_PINNED_SNAPSHOTS: usize = 4;
+const SNAPSHOT_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+type ChunkRequest = (Option<TransactionOutpoint>, usize, Sender<Vec<(TransactionOutpoint, UtxoEntry)>>);
+
+struct PinnedSnapshot {
+    requests: Sender<ChunkRequest>,
+    handle: JoinHandle<()>,
+    daa_score: u64,
+}
+
+/// The snapshots of the virtual UTXO set pinned by ongoing exports.
+///
+/// A RocksDB snapshot borrows the DB, so each one is owned by a dedicated thread serving the chunk requests
+/// of its export until the export completes, expires or consensus exits.
+#[derive(Default)]
+pub(super) struct PinnedUtxoSetSnapshots {
+    snapshots: Mutex<HashMap<u64, PinnedSnapshot>>,
+    next_id: AtomicU64,
+}
+
+impl PinnedUtxoSetSnapshots {
+    /// Pins a snapshot of the current DB state and returns its id. The caller must hold the virtual stores lock
+    /// so that the snapshot reflects a complete virtual state, whose DAA score is `daa_score`
+    pub(super) fn pin(&self, db: Arc<DB>, utxo_set: DbUtxoSetStore, daa_score: u64) -> ConsensusResult<u64> {
+        let mut snapshots = self.snapshots.lock();
+        snapshots.retain(|_, snapshot| !snapshot.handle.is_finished());
+        if snapshots.len() >= MAX_PINNED_SNAPSHOTS {
+            return Err(ConsensusError::TooManyUtxoSetSnapshots(MAX_PINNED_SNAPSHOTS));
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (requests_sender, requests_receiver) = unbounded();
+        let (pinned_sender, pinned_receiver) = bounded(1);
+        let handle = thread::Builder::new()
+            .name(format!("utxo-set-snapshot-{id}"))
+            .spawn(move || serve_snapshot(db, utxo_set, requests_receiver, pinned_sender))
+            .expect("failed to spawn a UTXO set snapshot thread");
+        pinned_receiver.recv().expect("the snapshot thread signals once the snapshot is pinned");
+        snapshots.insert(id, PinnedSnapshot { requests: requests_sender, handle, daa_score });
+        debug!("Pinned UTXO set snapshot {} at DAA score {}", id, daa_score);
+        Ok(id)
+    }
+
+    /// Reads the chunk following `cursor` from its pinned snapshot, releasing the snapshot once exhausted
+    pub(super) fn read_chunk(&self, cursor: UtxoSetCursor, chunk_size: usize) -> ConsensusResult<UtxoSetChunk> {
+        let not_found = ConsensusError::UtxoSetSnapshotNotFound(cursor.snapshot_id);
+        let (requests, daa_score) = match self.snapshots.lock().get(&cursor.snapshot_id) {
+            Some(snapshot) => (snapshot.requests.clone(), snapshot.daa_score),
+            None => return Err(not_found),
+        };
+        let (reply_sender, reply_receiver) = bounded(1);
+        requests.send((cursor.last_outpoint, chunk_size, reply_sender)).map_err(|_| not_found.clone())?;
+        let entries = reply_receiver.recv().map_err(|_| not_found)?;
+
+        let next_cursor = match entries.last() {
+            Some((outpoint, _)) if entries.len() == chunk_size => Some(UtxoSetCursor::new(cursor.snapshot_id, Some(*outpoint))),
+            _ => {
+                self.snapshots.lock().remove(&cursor.snapshot_id);
+                debug!("Released UTXO set snapshot {}", cursor.snapshot_id);
+                None
+            }
+        };
+        Ok(UtxoSetChunk { entries, next_cursor, snapshot_daa_score: daa_score })
+    }
+
+    /// Releases all pinned snapshots
+    pub(super) fn clear(&self) {
+        self.snapshots.lock().clear();
+    }
+}
+
+fn serve_snapshot(db: Arc<DB>, utxo_set: DbUtxoSetStore, requests: Receiver<ChunkRequest>, pinned: Sender<()>) {
+    let snapshot = db.snapshot();
+    pinned.send(()).unwrap();
+    // Exits when the snapshot is released or after being idle for too long
+    while let Ok((last_outpoint, chunk_size, reply)) = requests.recv_timeout(SNAPSHOT_IDLE_TIMEOUT) {
+        let skip_first = last_outpoint.is_some();
+        let entries = utxo_set.seek_iterator_at(&snapshot, last_outpoint, chunk_size, skip_first).map(|item| item.unwrap()).collect();
+        // The requester may have given up meanwhile
+        let _ = reply.send(entries);
+    }
+}
@@ -0,0 +1,68 @@
+use super::Consensus;
+use kaspa_core::info;
+use kaspa_hashes::Hash;
+use std::{cmp::Reverse, collections::BinaryHeap, time::Instant};
+
+/// The number of blocks whose data is loaded by a single batched read
+const WARMUP_CHUNK_SIZE: usize = 1000;
+
+impl Consensus {
+    /// Pre-warms the GHOSTDAG, relations and reachability caches with the data of the `num_blocks` most recent
+    /// blocks, so that validation right after a restart does not pay for cold caches. Returns the number of blocks
+    /// loaded. Blocks loaded beyond the cache capacities evict each other, hence `num_blocks` should not exceed them
+    pub(super) fn warm_up_caches(&self, num_blocks: usize) -> usize {
+        let start = Instant::now();
+        let hashes = self.recent_blocks(num_blocks);
+        for chunk in hashes.chunks(WARMUP_CHUNK_SIZE) {
+            self.ghostdag_primary_store.prefetch(chunk).unwrap();
+            self.relations_stores.read()[0].prefetch(chunk).unwrap();
+            self.reachability_store.read().prefetch(chunk).unwrap();
+        }
+        info!("Warmed up the consensus caches with {} recent blocks in {:?}", hashes.len(), start.elapsed());
+        hashes.len()
+    }
+
+    /// Returns the `num_blocks` blocks of highest blue score, ordered by ascending blue score. The blocks are found
+    /// by a sequential scan of the compact GHOSTDAG store, which is far cheaper than walking the DAG with random reads
+    fn recent_blocks(&self, num_blocks: usize) -> Vec<Hash> {
+        // A min-heap of the highest blue score blocks seen so far
+        let mut recent = BinaryHeap::with_capacity(num_blocks + 1);
+        for (hash, data) in self.ghostdag_primary_store.compact_iterator().map(|r| r.unwrap()) {
+            recent.push(Reverse((data.blue_score, hash)));
+            if recent.len() > num_blocks {
+                recent.pop();
+            }
+        }
+        // Sorting the reversed entries in ascending order yields descending blue scores
+        recent.into_sorted_vec().into_iter().rev().map(|Reverse((_, hash))| hash).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{config::ConfigBuilder, consensus::test_consensus::TestConsensus};
+    use kaspa_consensus_core::config::params::DEVNET_PARAMS;
+    use kaspa_hashes::Hash;
+
+    #[tokio::test]
+    async fn test_warm_up_caches() {
+        let config = ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work().build();
+        let consensus = TestConsensus::new(&config);
+        let wait_handles = consensus.init();
+
+        let mut parent = config.genesis.hash;
+        for i in 1..=10u64 {
+            let hash = Hash::from_u64_word(i);
+            consensus.add_block_with_parents(hash, vec![parent]).await.unwrap();
+            parent = hash;
+        }
+
+        let expected = (8..=10u64).map(Hash::from_u64_word).collect::<Vec<_>>();
+        assert_eq!(consensus.recent_blocks(3), expected);
+        // Genesis is included once all blocks are requested
+        assert_eq!(consensus.recent_blocks(100).len(), 11);
+        assert_eq!(consensus.warm_up_caches(3), 3);
+
+        consensus.shutdown(wait_handles);
+    }
+}
@@ -0,0 +1,248 @@
+//!
+//! A deterministic generator of random DAG topologies, for benchmarks, fuzzers and simulations which must be
+//! comparable across runs (and across PRs).
+//!
+//! Blocks are mined at Poisson distributed intervals and become visible to the network after a random propagation
+//! delay, each block pointing at the tips visible when it was mined. The width of the resulting DAG is therefore
+//! governed by the block rate times the mean delay. The generated topology is fully determined by the profile and
+//! the seed, since sampling uses a portable RNG and no other source of randomness.
+//!
+
+use kaspa_hashes::Hash;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, BinaryHeap, HashMap},
+};
+
+/// The distribution of block propagation delays, in milliseconds
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DelayDistribution {
+    Constant(u64),
+    /// Uniform over the inclusive range
+    Uniform(u64, u64),
+    /// Exponential with the given mean
+    Exponential(f64),
+}
+
+impl DelayDistribution {
+    fn sample(&self, rng: &mut ChaCha8Rng) -> u64 {
+        match *self {
+            DelayDistribution::Constant(delay) => delay,
+            DelayDistribution::Uniform(min, max) => rng.gen_range(min..=max),
+            DelayDistribution::Exponential(mean) => sample_exponential(rng, mean) as u64,
+        }
+    }
+}
+
+/// The parameters shaping the topology of a generated DAG
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DagProfile {
+    /// Mean number of blocks mined per second
+    pub bps: f64,
+    /// The delay after which a mined block is visible to all miners
+    pub delay: DelayDistribution,
+    /// The maximum number of parents of a block. Excess tips are left to be merged by later blocks, preferring the
+    /// most recently mined ones as parents
+    pub max_parents: usize,
+    /// The probability of a block to be delivered before one of its parents, i.e., as an orphan. Only affects
+    /// [`GeneratedDag::delivery_order`]
+    pub orphan_rate: f64,
+}
+
+impl DagProfile {
+    /// A DAG with rare parallel blocks, resembling the 1 BPS mainnet
+    pub const NARROW: DagProfile =
+        DagProfile { bps: 1.0, delay: DelayDistribution::Exponential(500.0), max_parents: 10, orphan_rate: 0.0 };
+
+    /// A 10 BPS DAG with a typical anticone of a few blocks
+    pub const WIDE: DagProfile =
+        DagProfile { bps: 10.0, delay: DelayDistribution::Uniform(200, 1500), max_parents: 10, orphan_rate: 0.0 };
+
+    /// A 32 BPS DAG with long delays, where blocks mostly have the maximal number of parents and tips remain unmerged
+    pub const STRESSED: DagProfile =
+        DagProfile { bps: 32.0, delay: DelayDistribution::Exponential(2000.0), max_parents: 16, orphan_rate: 0.01 };
+
+    pub fn with_orphan_rate(self, orphan_rate: f64) -> Self {
+        Self { orphan_rate, ..self }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GeneratedBlock {
+    pub hash: Hash,
+    pub parents: Vec<Hash>,
+    /// The time the block was mined at, in milliseconds since the genesis timestamp
+    pub timestamp: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct GeneratedDag {
+    pub genesis: Hash,
+    /// The generated blocks (excluding genesis) in the order they were mined, which is a topological order
+    pub blocks: Vec<GeneratedBlock>,
+    delivery_order: Vec<usize>,
+}
+
+impl GeneratedDag {
+    /// Returns the blocks in the order they are delivered to a node, in which some blocks might precede their parents
+    /// (see [`DagProfile::orphan_rate`])
+    pub fn delivery_order(&self) -> impl Iterator<Item = &GeneratedBlock> + '_ {
+        self.delivery_order.iter().map(|&index| &self.blocks[index])
+    }
+
+    /// Returns the hashes of the blocks which are not parents of any other block
+    pub fn tips(&self) -> Vec<Hash> {
+        if self.blocks.is_empty() {
+            return vec![self.genesis];
+        }
+        let mut tips: BTreeSet<Hash> = self.blocks.iter().map(|block| block.hash).collect();
+        for block in self.blocks.iter() {
+            for parent in block.parents.iter() {
+                tips.remove(parent);
+            }
+        }
+        tips.into_iter().collect()
+    }
+}
+
+pub struct DagGenerator {
+    profile: DagProfile,
+    rng: ChaCha8Rng,
+}
+
+impl DagGenerator {
+    pub fn new(profile: DagProfile, seed: u64) -> Self {
+        Self { profile, rng: ChaCha8Rng::seed_from_u64(seed) }
+    }
+
+    /// Generates a DAG of `num_blocks` blocks on top of `genesis`. Block hashes are synthetic and unique per index
+    pub fn generate(&mut self, genesis: Hash, num_blocks: usize) -> GeneratedDag {
+        let hashes: Vec<Hash> = (0..num_blocks as u64).map(|index| Hash::from_u64_word(index + 1)).collect();
+        let mut blocks = Vec::with_capacity(num_blocks);
+        let mut parent_indices: Vec<Vec<Option<usize>>> = Vec::with_capacity(num_blocks);
+
+        // Indices of visible blocks which are not yet referenced by another visible block, where `None` is genesis
+        let mut tips: BTreeSet<Option<usize>> = BTreeSet::from([None]);
+        // Mined blocks ordered by the time they become visible
+        let mut propagating = BinaryHeap::new();
+        let mut time = 0u64;
+        for index in 0..num_blocks {
+            time += sample_exponential(&mut self.rng, 1000.0 / self.profile.bps) as u64;
+            while let Some(&Reverse((visible_time, visible))) = propagating.peek() {
+                if visible_time > time {
+                    break;
+                }
+                propagating.pop();
+                for parent in parent_indices[visible].iter() {
+                    tips.remove(parent);
+                }
+                tips.insert(Some(visible));
+            }
+
+            let parents = tips.iter().rev().take(self.profile.max_parents).copied().collect::<Vec<_>>();
+            blocks.push(GeneratedBlock {
+                hash: hashes[index],
+                parents: parents.iter().map(|parent| parent.map_or(genesis, |parent| hashes[parent])).collect(),
+                timestamp: time,
+            });
+            parent_indices.push(parents);
+            propagating.push(Reverse((time + self.profile.delay.sample(&mut self.rng), index)));
+        }
+
+        let delivery_order = self.delivery_order(&blocks);
+        GeneratedDag { genesis, blocks, delivery_order }
+    }
+
+    /// Postpones the delivery of a parent of each orphan block to right after it
+    fn delivery_order(&mut self, blocks: &[GeneratedBlock]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..blocks.len()).collect();
+        if self.profile.orphan_rate <= 0.0 {
+            return order;
+        }
+        // Synthetic hashes are unsuitable for the `BlockHasher`
+        let indices: HashMap<Hash, usize> = blocks.iter().enumerate().map(|(index, block)| (block.hash, index)).collect();
+        for (index, block) in blocks.iter().enumerate() {
+            if !self.rng.gen_bool(self.profile.orphan_rate) {
+                continue;
+            }
+            let Some(&parent) = indices.get(&block.parents[0]) else {
+                continue; // The parent is genesis, which is never delivered
+            };
+            let parent_position = order.iter().position(|&i| i == parent).unwrap();
+            let position = order.iter().position(|&i| i == index).unwrap();
+            if parent_position < position {
+                order.remove(parent_position);
+                order.insert(position, parent);
+            }
+        }
+        order
+    }
+}
+
+/// Samples an exponential distribution by inverse transform sampling
+fn sample_exponential(rng: &mut ChaCha8Rng, mean: f64) -> f64 {
+    -mean * (1.0 - rng.gen::<f64>()).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::ConfigBuilder, consensus::test_consensus::TestConsensus};
+    use kaspa_consensus_core::{api::ConsensusApi, config::params::DEVNET_PARAMS};
+
+    #[test]
+    fn test_dag_generator() {
+        let genesis = Hash::from_u64_word(u64::MAX);
+        let dag = DagGenerator::new(DagProfile::WIDE, 7).generate(genesis, 1000);
+        assert_eq!(dag.blocks.len(), 1000);
+
+        // The same seed yields the same topology
+        let again = DagGenerator::new(DagProfile::WIDE, 7).generate(genesis, 1000);
+        assert_eq!(dag.blocks, again.blocks);
+        assert_ne!(dag.blocks, DagGenerator::new(DagProfile::WIDE, 8).generate(genesis, 1000).blocks);
+
+        // Blocks are in topological order and respect the profile
+        for (index, block) in dag.blocks.iter().enumerate() {
+            assert!(!block.parents.is_empty() && block.parents.len() <= DagProfile::WIDE.max_parents);
+            assert!(block.parents.iter().all(|parent| *parent == genesis || dag.blocks[..index].iter().any(|b| b.hash == *parent)));
+        }
+        assert!(dag.blocks.iter().any(|block| block.parents.len() > 1), "a 10 BPS DAG is expected to have parallel blocks");
+        assert!(dag.delivery_order().eq(dag.blocks.iter()));
+        assert!(!dag.tips().is_empty());
+
+        let dag = DagGenerator::new(DagProfile::WIDE.with_orphan_rate(0.1), 7).generate(genesis, 1000);
+        let delivered: Vec<_> = dag.delivery_order().collect();
+        assert_eq!(delivered.len(), dag.blocks.len());
+        let orphans = delivered
+            .iter()
+            .enumerate()
+            .filter(|(position, block)| {
+                block.parents.iter().any(|parent| *parent != genesis && !delivered[..*position].iter().any(|b| b.hash == *parent))
+            })
+            .count();
+        assert!(orphans > 0);
+    }
+
+    #[tokio::test]
+    async fn test_generated_dag_processing() {
+        let config = ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work().build();
+        for (profile, num_blocks) in [(DagProfile::NARROW, 200), (DagProfile::WIDE, 500)] {
+            let consensus = TestConsensus::new(&config);
+            let wait_handles = consensus.init();
+
+            let dag = DagGenerator::new(profile, 7).generate(config.genesis.hash, num_blocks);
+            for block in dag.delivery_order() {
+                consensus.add_block_with_parents(block.hash, block.parents.clone()).await.unwrap();
+            }
+
+            // Consensus ends up with the tips of the generated topology
+            let mut tips = consensus.get_tips();
+            tips.sort();
+            assert_eq!(tips, dag.tips());
+
+            consensus.shutdown(wait_handles);
+        }
+    }
+}
@@ -5,6 +5,7 @@
 pub mod config;
 pub mod consensus;
 pub mod constants;
+pub mod dag_generator;
 pub mod errors;
 pub mod model;
 pub mod params;
@@ -23,6 +23,10 @@ impl<T: RelationsStoreReader> RelationsStoreReader for MTRelationsService<T> {
         self.store.read()[self.level].get_parents(hash)
     }
 
+    fn get_parents_many(&self, hashes: &[Hash]) -> Result<Vec<kaspa_consensus_core::blockhash::BlockHashes>, StoreError> {
+        self.store.read()[self.level].get_parents_many(hashes)
+    }
+
     fn get_children(&self, hash: Hash) -> StoreResult<ReadLock<BlockHashSet>> {
         self.store.read()[self.level].get_children(hash)
     }
@@ -10,6 +10,8 @@ use std::sync::Arc;
 
 pub trait AcceptanceDataStoreReader {
     fn get(&self, hash: Hash) -> Result<Arc<AcceptanceData>, StoreError>;
+    /// Returns the acceptance data of each of `hashes`, in order
+    fn get_many(&self, hashes: &[Hash]) -> Result<Vec<Arc<AcceptanceData>>, StoreError>;
 }
 
 pub trait AcceptanceDataStore: AcceptanceDataStoreReader {
@@ -44,12 +46,21 @@ impl DbAcceptanceDataStore {
     pub fn delete_batch(&self, batch: &mut WriteBatch, hash: Hash) -> Result<(), StoreError> {
         self.access.delete(BatchDbWriter::new(batch), hash)
     }
+
+    /// Returns the acceptance data of each of `hashes`, in order, or `None` for hashes with no acceptance data
+    pub fn multi_get(&self, hashes: &[Hash]) -> Result<Vec<Option<Arc<AcceptanceData>>>, StoreError> {
+        self.access.multi_get(hashes.iter().copied())
+    }
 }
 
 impl AcceptanceDataStoreReader for DbAcceptanceDataStore {
     fn get(&self, hash: Hash) -> Result<Arc<AcceptanceData>, StoreError> {
         self.access.read(hash)
     }
+
+    fn get_many(&self, hashes: &[Hash]) -> Result<Vec<Arc<AcceptanceData>>, StoreError> {
+        self.access.read_many(hashes.iter().copied())
+    }
 }
 
 impl AcceptanceDataStore for DbAcceptanceDataStore {
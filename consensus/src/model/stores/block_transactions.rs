@@ -49,6 +49,11 @@ impl DbBlockTransactionsStore {
     pub fn delete_batch(&self, batch: &mut WriteBatch, hash: Hash) -> Result<(), StoreError> {
         self.access.delete(BatchDbWriter::new(batch), hash)
     }
+
+    /// Returns the transactions of each of `hashes`, in order, or `None` for hashes with no stored body
+    pub fn multi_get(&self, hashes: &[Hash]) -> Result<Vec<Option<Arc<Vec<Transaction>>>>, StoreError> {
+        self.access.multi_get(hashes.iter().copied())
+    }
 }
 
 impl BlockTransactionsStoreReader for DbBlockTransactionsStore {
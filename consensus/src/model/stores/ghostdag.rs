@@ -1,12 +1,15 @@
 use crate::processes::ghostdag::ordering::SortableBlock;
 use kaspa_consensus_core::trusted::ExternalGhostdagData;
-use kaspa_consensus_core::{blockhash::BlockHashes, BlueWorkType};
+use kaspa_consensus_core::{
+    blockhash::{BlockHashVec, BlockHashes},
+    BlueWorkType,
+};
 use kaspa_consensus_core::{BlockHashMap, BlockHasher, BlockLevel, HashMapCustomHasher};
 use kaspa_database::prelude::StoreError;
 use kaspa_database::prelude::DB;
 use kaspa_database::prelude::{BatchDbWriter, CachedDbAccess, DbKey};
 use kaspa_database::registry::{DatabaseStorePrefixes, SEPARATOR};
-use kaspa_hashes::Hash;
+use kaspa_hashes::{Hash, HASH_SIZE};
 
 use itertools::EitherOrBoth::{Both, Left, Right};
 use itertools::Itertools;
@@ -28,6 +31,25 @@ pub struct GhostdagData {
     pub blues_anticone_sizes: HashKTypeMap,
 }
 
+/// A mergeset block tagged by its GHOSTDAG coloring
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColoredBlock {
+    Blue(Hash),
+    Red(Hash),
+}
+
+impl ColoredBlock {
+    pub fn hash(&self) -> Hash {
+        match self {
+            ColoredBlock::Blue(hash) | ColoredBlock::Red(hash) => *hash,
+        }
+    }
+
+    pub fn is_blue(&self) -> bool {
+        matches!(self, ColoredBlock::Blue(_))
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Copy)]
 pub struct CompactGhostdagData {
     pub blue_score: u64,
@@ -47,8 +69,8 @@ impl From<ExternalGhostdagData> for GhostdagData {
             blue_score: value.blue_score,
             blue_work: value.blue_work,
             selected_parent: value.selected_parent,
-            mergeset_blues: Arc::new(value.mergeset_blues),
-            mergeset_reds: Arc::new(value.mergeset_reds),
+            mergeset_blues: Arc::new(value.mergeset_blues.into()),
+            mergeset_reds: Arc::new(value.mergeset_reds.into()),
             blues_anticone_sizes: Arc::new(value.blues_anticone_sizes),
         }
     }
@@ -60,8 +82,8 @@ impl From<&GhostdagData> for ExternalGhostdagData {
             blue_score: value.blue_score,
             blue_work: value.blue_work,
             selected_parent: value.selected_parent,
-            mergeset_blues: (*value.mergeset_blues).clone(),
-            mergeset_reds: (*value.mergeset_reds).clone(),
+            mergeset_blues: value.mergeset_blues.to_vec(),
+            mergeset_reds: value.mergeset_reds.to_vec(),
             blues_anticone_sizes: (*value.blues_anticone_sizes).clone(),
         }
     }
@@ -80,7 +102,8 @@ impl GhostdagData {
     }
 
     pub fn new_with_selected_parent(selected_parent: Hash, k: KType) -> Self {
-        let mut mergeset_blues: Vec<Hash> = Vec::with_capacity((k + 1) as usize);
+        // Mergesets are short in the common case, so the blues are not preallocated in order to be kept inline
+        let mut mergeset_blues = BlockHashVec::new();
         let mut blues_anticone_sizes: BlockHashMap<KType> = BlockHashMap::with_capacity(k as usize);
         mergeset_blues.push(selected_parent);
         blues_anticone_sizes.insert(selected_parent, 0);
@@ -174,6 +197,13 @@ impl GhostdagData {
         self.ascending_mergeset_without_selected_parent(store).map(|s| s.hash)
     }
 
+    /// Returns an iterator to the mergeset ordered by coloring -- starting with the selected parent, followed by
+    /// the remaining blues and then by the reds, each in ascending blue work order. This is the order by which
+    /// mergeset rewards are paid by the coinbase transaction
+    pub fn color_ordered_mergeset(&self) -> impl Iterator<Item = ColoredBlock> + '_ {
+        self.mergeset_blues.iter().copied().map(ColoredBlock::Blue).chain(self.mergeset_reds.iter().copied().map(ColoredBlock::Red))
+    }
+
     /// Returns an iterator to the mergeset with no specified order (including the selected parent)
     pub fn unordered_mergeset(&self) -> impl Iterator<Item = Hash> + '_ {
         self.mergeset_blues.iter().cloned().chain(self.mergeset_reds.iter().cloned())
@@ -281,6 +311,23 @@ impl DbGhostdagStore {
         self.compact_access.delete(BatchDbWriter::new(batch), hash)?;
         self.access.delete(BatchDbWriter::new(batch), hash)
     }
+
+    /// Iterates over the compact data of all blocks, in key order
+    pub fn compact_iterator(&self) -> impl Iterator<Item = Result<(Hash, CompactGhostdagData), StoreError>> + '_ {
+        self.compact_access.iterator().map(|iter_result| match iter_result {
+            Ok((key_bytes, data)) => match <[u8; HASH_SIZE]>::try_from(&key_bytes[..]) {
+                Ok(hash_bytes) => Ok((Hash::from_bytes(hash_bytes), data)),
+                Err(e) => Err(StoreError::DataInconsistency(e.to_string())),
+            },
+            Err(e) => Err(StoreError::DataInconsistency(e.to_string())),
+        })
+    }
+
+    /// Loads the full and compact data of `hashes` into the cache
+    pub fn prefetch(&self, hashes: &[Hash]) -> Result<(), StoreError> {
+        self.access.prefetch(hashes.iter().copied())?;
+        self.compact_access.prefetch(hashes.iter().copied())
+    }
 }
 
 impl GhostdagStoreReader for DbGhostdagStore {
@@ -523,5 +570,15 @@ mod tests {
 
         let expected = BlockHashSet::from_iter([1.into(), 4.into(), 2.into(), 5.into(), 3.into(), 6.into()]);
         assert_eq!(expected, data.unordered_mergeset().collect::<BlockHashSet>());
+
+        let expected = vec![
+            ColoredBlock::Blue(1.into()),
+            ColoredBlock::Blue(2.into()),
+            ColoredBlock::Blue(3.into()),
+            ColoredBlock::Red(4.into()),
+            ColoredBlock::Red(5.into()),
+            ColoredBlock::Red(6.into()),
+        ];
+        assert_eq!(expected, data.color_ordered_mergeset().collect::<Vec<_>>());
     }
 }
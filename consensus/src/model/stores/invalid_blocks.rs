@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use kaspa_consensus_core::{block::Block, header::Header, tx::Transaction, BlockHasher};
+use kaspa_database::prelude::Cache;
+use kaspa_database::prelude::StoreError;
+use kaspa_database::prelude::DB;
+use kaspa_database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter};
+use kaspa_database::registry::DatabaseStorePrefixes;
+use kaspa_hashes::{Hash, HASH_SIZE};
+use rocksdb::WriteBatch;
+use serde::{Deserialize, Serialize};
+
+/// The record of a block which was marked with `StatusInvalid`, kept so that the block can be revalidated
+/// if the violated rule turns out to have been wrongly enforced. Records are dropped once the block falls
+/// below the pruning point, see [`DbInvalidBlocksStore::prune_below`]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InvalidBlockRecord {
+    /// The code of the violated rule, see `RuleError::code`
+    pub rule_code: u16,
+    pub header: Arc<Header>,
+    /// The transactions of the block. Empty if the block was submitted with its header only
+    pub transactions: Arc<Vec<Transaction>>,
+}
+
+impl InvalidBlockRecord {
+    pub fn new(rule_code: u16, block: &Block) -> Self {
+        Self { rule_code, header: block.header.clone(), transactions: block.transactions.clone() }
+    }
+
+    pub fn to_block(&self) -> Block {
+        Block::from_arcs(self.header.clone(), self.transactions.clone())
+    }
+}
+
+pub trait InvalidBlocksStoreReader {
+    fn get(&self, hash: Hash) -> Result<Arc<InvalidBlockRecord>, StoreError>;
+
+    /// Returns whether `hash` was recently found invalid, either by validation or as a descendant of
+    /// a known-invalid block. Only an in-memory set is queried, so the call is cheap but not exhaustive
+    fn is_known_invalid(&self, hash: Hash) -> bool;
+}
+
+pub trait InvalidBlocksStore: InvalidBlocksStoreReader {
+    fn insert(&self, hash: Hash, record: Arc<InvalidBlockRecord>) -> Result<(), StoreError>;
+    fn delete(&self, hash: Hash) -> Result<(), StoreError>;
+
+    /// Remembers `hash` as known-invalid without persisting a record, used for descendants of known-invalid blocks
+    fn mark_known_invalid(&self, hash: Hash);
+}
+
+/// The number of recently invalidated hashes kept in memory for short-circuiting their descendants
+const KNOWN_INVALID_CACHE_SIZE: u64 = 4096;
+
+/// A DB + cache implementation of `InvalidBlocksStore` trait, with concurrency support.
+#[derive(Clone)]
+pub struct DbInvalidBlocksStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<Hash, Arc<InvalidBlockRecord>, BlockHasher>,
+    known_invalid: Cache<Hash, (), BlockHasher>,
+}
+
+impl DbInvalidBlocksStore {
+    pub fn new(db: Arc<DB>, cache_size: u64) -> Self {
+        Self {
+            db: Arc::clone(&db),
+            access: CachedDbAccess::new(db, cache_size, DatabaseStorePrefixes::InvalidBlocks.into()),
+            known_invalid: Cache::new(KNOWN_INVALID_CACHE_SIZE),
+        }
+    }
+
+    pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
+        Self { known_invalid: self.known_invalid.clone(), ..Self::new(Arc::clone(&self.db), cache_size) }
+    }
+
+    /// Iterates over all records
+    pub fn iterator(&self) -> impl Iterator<Item = Result<(Hash, Arc<InvalidBlockRecord>), StoreError>> + '_ {
+        self.access.iterator().map(|iter_result| match iter_result {
+            Ok((key_bytes, record)) => match <[u8; HASH_SIZE]>::try_from(&key_bytes[..]) {
+                Ok(hash_bytes) => Ok((Hash::from_bytes(hash_bytes), record)),
+                Err(e) => Err(StoreError::DataInconsistency(e.to_string())),
+            },
+            Err(e) => Err(StoreError::DataInconsistency(e.to_string())),
+        })
+    }
+
+    /// Deletes the records of the blocks whose DAA score is not above `daa_score`, returning their number. Used with
+    /// the DAA score of the pruning point, since such blocks cannot be in its future and thus cannot be revalidated
+    pub fn prune_below(&self, daa_score: u64) -> Result<usize, StoreError> {
+        let mut batch = WriteBatch::default();
+        let mut count = 0;
+        for entry in self.iterator() {
+            let (hash, record) = entry?;
+            if record.header.daa_score <= daa_score {
+                self.access.delete(BatchDbWriter::new(&mut batch), hash)?;
+                count += 1;
+            }
+        }
+        self.db.write(batch)?;
+        Ok(count)
+    }
+}
+
+impl InvalidBlocksStoreReader for DbInvalidBlocksStore {
+    fn get(&self, hash: Hash) -> Result<Arc<InvalidBlockRecord>, StoreError> {
+        self.access.read(hash)
+    }
+
+    fn is_known_invalid(&self, hash: Hash) -> bool {
+        self.known_invalid.contains_key(&hash)
+    }
+}
+
+impl InvalidBlocksStore for DbInvalidBlocksStore {
+    fn insert(&self, hash: Hash, record: Arc<InvalidBlockRecord>) -> Result<(), StoreError> {
+        self.access.write(DirectDbWriter::new(&self.db), hash, record)?;
+        self.known_invalid.insert(hash, ());
+        Ok(())
+    }
+
+    fn delete(&self, hash: Hash) -> Result<(), StoreError> {
+        // Descendants marked due to this block cannot be told apart, hence the in-memory set is reset altogether
+        self.known_invalid.remove_all();
+        self.access.delete(DirectDbWriter::new(&self.db), hash)
+    }
+
+    fn mark_known_invalid(&self, hash: Hash) {
+        self.known_invalid.insert(hash, ());
+    }
+}
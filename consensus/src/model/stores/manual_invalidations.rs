@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use kaspa_consensus_core::BlockHashSet;
+use kaspa_database::prelude::StoreResult;
+use kaspa_database::prelude::StoreResultExtensions;
+use kaspa_database::prelude::DB;
+use kaspa_database::prelude::{CachedDbItem, DirectDbWriter};
+use kaspa_database::registry::DatabaseStorePrefixes;
+use kaspa_hashes::Hash;
+
+/// Reader API for `ManualInvalidationsStore`.
+pub trait ManualInvalidationsStoreReader {
+    fn get(&self) -> StoreResult<Arc<BlockHashSet>>;
+}
+
+/// Holds blocks which were invalidated by the node operator, disqualifying them along with their selected chain
+/// descendants from the virtual chain until reconsidered
+pub trait ManualInvalidationsStore: ManualInvalidationsStoreReader {
+    fn insert(&mut self, hash: Hash) -> StoreResult<()>;
+    /// Removes the block and returns whether it was present
+    fn remove(&mut self, hash: Hash) -> StoreResult<bool>;
+}
+
+/// A DB + cache implementation of `ManualInvalidationsStore` trait
+#[derive(Clone)]
+pub struct DbManualInvalidationsStore {
+    db: Arc<DB>,
+    access: CachedDbItem<Arc<BlockHashSet>>,
+}
+
+impl DbManualInvalidationsStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbItem::new(db, DatabaseStorePrefixes::ManualInvalidations.into()) }
+    }
+
+    pub fn clone_with_new_cache(&self) -> Self {
+        Self::new(Arc::clone(&self.db))
+    }
+}
+
+impl ManualInvalidationsStoreReader for DbManualInvalidationsStore {
+    fn get(&self) -> StoreResult<Arc<BlockHashSet>> {
+        Ok(self.access.read().unwrap_option().unwrap_or_default())
+    }
+}
+
+impl ManualInvalidationsStore for DbManualInvalidationsStore {
+    fn insert(&mut self, hash: Hash) -> StoreResult<()> {
+        let mut invalidated = self.get()?;
+        if Arc::make_mut(&mut invalidated).insert(hash) {
+            self.access.write(DirectDbWriter::new(&self.db), &invalidated)?;
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, hash: Hash) -> StoreResult<bool> {
+        let mut invalidated = self.get()?;
+        let removed = Arc::make_mut(&mut invalidated).remove(&hash);
+        if removed {
+            self.access.write(DirectDbWriter::new(&self.db), &invalidated)?;
+        }
+        Ok(removed)
+    }
+}
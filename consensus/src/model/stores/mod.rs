@@ -11,9 +11,12 @@ pub mod depth;
 pub mod ghostdag;
 pub mod headers;
 pub mod headers_selected_tip;
+pub mod invalid_blocks;
+pub mod manual_invalidations;
 pub mod past_pruning_points;
 pub mod pruning;
 pub mod pruning_utxoset;
+pub mod quarantine;
 pub mod reachability;
 pub mod relations;
 pub mod statuses;
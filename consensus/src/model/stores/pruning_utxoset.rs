@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use kaspa_database::prelude::StoreResult;
 use kaspa_database::prelude::DB;
-use kaspa_database::prelude::{BatchDbWriter, CachedDbItem};
+use kaspa_database::prelude::{BatchDbWriter, CachedDbItem, ScanOptions};
 use kaspa_database::registry::DatabaseStorePrefixes;
 use kaspa_hashes::Hash;
 use rocksdb::WriteBatch;
@@ -16,9 +16,9 @@ pub struct PruningUtxosetStores {
 }
 
 impl PruningUtxosetStores {
-    pub fn new(db: Arc<DB>, utxoset_cache_size: u64) -> Self {
+    pub fn new(db: Arc<DB>, utxoset_cache_size: u64, scan_options: ScanOptions) -> Self {
         Self {
-            utxo_set: DbUtxoSetStore::new(db.clone(), utxoset_cache_size, DatabaseStorePrefixes::PruningUtxoset.into()),
+            utxo_set: DbUtxoSetStore::new(db.clone(), utxoset_cache_size, DatabaseStorePrefixes::PruningUtxoset.into(), scan_options),
             utxoset_position_access: CachedDbItem::new(db, DatabaseStorePrefixes::PruningUtxosetPosition.into()),
         }
     }
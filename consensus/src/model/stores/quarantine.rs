@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use kaspa_consensus_core::BlockHashSet;
+use kaspa_database::prelude::StoreResult;
+use kaspa_database::prelude::StoreResultExtensions;
+use kaspa_database::prelude::DB;
+use kaspa_database::prelude::{CachedDbItem, DirectDbWriter};
+use kaspa_database::registry::DatabaseStorePrefixes;
+use kaspa_hashes::Hash;
+
+/// Reader API for `QuarantineStore`.
+pub trait QuarantineStoreReader {
+    fn get(&self) -> StoreResult<Arc<BlockHashSet>>;
+}
+
+/// Holds blocks whose stored data was found corrupted or missing and should be re-downloaded from peers
+pub trait QuarantineStore: QuarantineStoreReader {
+    /// Adds the given blocks to the quarantine and returns the number of newly quarantined blocks
+    fn insert_many(&mut self, hashes: &[Hash]) -> StoreResult<usize>;
+    fn remove(&mut self, hash: Hash) -> StoreResult<()>;
+}
+
+/// A DB + cache implementation of `QuarantineStore` trait
+#[derive(Clone)]
+pub struct DbQuarantineStore {
+    db: Arc<DB>,
+    access: CachedDbItem<Arc<BlockHashSet>>,
+}
+
+impl DbQuarantineStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbItem::new(db, DatabaseStorePrefixes::QuarantinedBlocks.into()) }
+    }
+
+    pub fn clone_with_new_cache(&self) -> Self {
+        Self::new(Arc::clone(&self.db))
+    }
+}
+
+impl QuarantineStoreReader for DbQuarantineStore {
+    fn get(&self) -> StoreResult<Arc<BlockHashSet>> {
+        Ok(self.access.read().unwrap_option().unwrap_or_default())
+    }
+}
+
+impl QuarantineStore for DbQuarantineStore {
+    fn insert_many(&mut self, hashes: &[Hash]) -> StoreResult<usize> {
+        let mut quarantined = self.get()?;
+        let set = Arc::make_mut(&mut quarantined);
+        let prev_len = set.len();
+        set.extend(hashes.iter().copied());
+        let added = set.len() - prev_len;
+        if added > 0 {
+            self.access.write(DirectDbWriter::new(&self.db), &quarantined)?;
+        }
+        Ok(added)
+    }
+
+    fn remove(&mut self, hash: Hash) -> StoreResult<()> {
+        let mut quarantined = self.get()?;
+        if Arc::make_mut(&mut quarantined).remove(&hash) {
+            self.access.write(DirectDbWriter::new(&self.db), &quarantined)?;
+        }
+        Ok(())
+    }
+}
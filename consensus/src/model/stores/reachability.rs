@@ -1,8 +1,5 @@
 use crate::processes::reachability::interval::Interval;
-use kaspa_consensus_core::{
-    blockhash::{self, BlockHashes},
-    BlockHashMap, BlockHashSet, BlockHasher, BlockLevel, HashMapCustomHasher,
-};
+use kaspa_consensus_core::{blockhash, BlockHashMap, BlockHashSet, BlockHasher, BlockLevel, HashMapCustomHasher};
 use kaspa_database::{
     prelude::{BatchDbWriter, CachedDbAccess, CachedDbItem, DbKey, DirectDbWriter, StoreError, DB},
     registry::{DatabaseStorePrefixes, SEPARATOR},
@@ -15,13 +12,16 @@ use rocksdb::WriteBatch;
 use serde::{Deserialize, Serialize};
 use std::{collections::hash_map::Entry::Vacant, iter::once, sync::Arc};
 
+/// Reachability lists are kept sorted and are updated in place, so unlike parents and mergesets they are plain vectors
+pub type ReachabilityHashes = Arc<Vec<Hash>>;
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ReachabilityData {
-    pub children: BlockHashes,
+    pub children: ReachabilityHashes,
     pub parent: Hash,
     pub interval: Interval,
     pub height: u64,
-    pub future_covering_set: BlockHashes,
+    pub future_covering_set: ReachabilityHashes,
 }
 
 impl ReachabilityData {
@@ -37,8 +37,8 @@ pub trait ReachabilityStoreReader {
     /// Returns the reachability *tree* parent of `hash`
     fn get_parent(&self, hash: Hash) -> Result<Hash, StoreError>;
     /// Returns the reachability *tree* children of `hash`
-    fn get_children(&self, hash: Hash) -> Result<BlockHashes, StoreError>;
-    fn get_future_covering_set(&self, hash: Hash) -> Result<BlockHashes, StoreError>;
+    fn get_children(&self, hash: Hash) -> Result<ReachabilityHashes, StoreError>;
+    fn get_future_covering_set(&self, hash: Hash) -> Result<ReachabilityHashes, StoreError>;
     /// Returns the counts of entries in the store. To be used for tests only
     fn count(&self) -> Result<usize, StoreError>;
 }
@@ -93,6 +93,11 @@ impl DbReachabilityStore {
     pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
         Self::with_prefix_end(Arc::clone(&self.db), cache_size, self.prefix_end)
     }
+
+    /// Loads the reachability data of `hashes` into the cache
+    pub fn prefetch(&self, hashes: &[Hash]) -> Result<(), StoreError> {
+        self.access.prefetch(hashes.iter().copied())
+    }
 }
 
 impl ReachabilityStore for DbReachabilityStore {
@@ -194,11 +199,11 @@ impl ReachabilityStoreReader for DbReachabilityStore {
         Ok(self.access.read(hash)?.parent)
     }
 
-    fn get_children(&self, hash: Hash) -> Result<BlockHashes, StoreError> {
+    fn get_children(&self, hash: Hash) -> Result<ReachabilityHashes, StoreError> {
         Ok(Arc::clone(&self.access.read(hash)?.children))
     }
 
-    fn get_future_covering_set(&self, hash: Hash) -> Result<BlockHashes, StoreError> {
+    fn get_future_covering_set(&self, hash: Hash) -> Result<ReachabilityHashes, StoreError> {
         Ok(Arc::clone(&self.access.read(hash)?.future_covering_set))
     }
 
@@ -398,19 +403,19 @@ impl ReachabilityStoreReader for StagingReachabilityStore<'_> {
         }
     }
 
-    fn get_children(&self, hash: Hash) -> Result<BlockHashes, StoreError> {
+    fn get_children(&self, hash: Hash) -> Result<ReachabilityHashes, StoreError> {
         self.check_not_in_deletions(hash)?;
         if let Some(data) = self.staging_writes.get(&hash) {
-            Ok(BlockHashes::clone(&data.children))
+            Ok(ReachabilityHashes::clone(&data.children))
         } else {
             self.store_read.get_children(hash)
         }
     }
 
-    fn get_future_covering_set(&self, hash: Hash) -> Result<BlockHashes, StoreError> {
+    fn get_future_covering_set(&self, hash: Hash) -> Result<ReachabilityHashes, StoreError> {
         self.check_not_in_deletions(hash)?;
         if let Some(data) = self.staging_writes.get(&hash) {
-            Ok(BlockHashes::clone(&data.future_covering_set))
+            Ok(ReachabilityHashes::clone(&data.future_covering_set))
         } else {
             self.store_read.get_future_covering_set(hash)
         }
@@ -549,11 +554,11 @@ impl ReachabilityStoreReader for MemoryReachabilityStore {
         Ok(self.get_data(hash)?.parent)
     }
 
-    fn get_children(&self, hash: Hash) -> Result<BlockHashes, StoreError> {
+    fn get_children(&self, hash: Hash) -> Result<ReachabilityHashes, StoreError> {
         Ok(Arc::clone(&self.get_data(hash)?.children))
     }
 
-    fn get_future_covering_set(&self, hash: Hash) -> Result<BlockHashes, StoreError> {
+    fn get_future_covering_set(&self, hash: Hash) -> Result<ReachabilityHashes, StoreError> {
         Ok(Arc::clone(&self.get_data(hash)?.future_covering_set))
     }
 
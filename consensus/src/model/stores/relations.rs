@@ -1,6 +1,9 @@
 use itertools::Itertools;
 use kaspa_consensus_core::BlockHashSet;
-use kaspa_consensus_core::{blockhash::BlockHashes, BlockHashMap, BlockHasher, BlockLevel};
+use kaspa_consensus_core::{
+    blockhash::{BlockHashVec, BlockHashes},
+    BlockHashMap, BlockHasher, BlockLevel,
+};
 use kaspa_database::prelude::{BatchDbWriter, DbWriter};
 use kaspa_database::prelude::{CachedDbAccess, DbKey, DirectDbWriter};
 use kaspa_database::prelude::{DirectWriter, MemoryWriter};
@@ -22,6 +25,12 @@ pub trait RelationsStoreReader {
     fn get_children(&self, hash: Hash) -> StoreResult<ReadLock<BlockHashSet>>;
     fn has(&self, hash: Hash) -> Result<bool, StoreError>;
 
+    /// Returns the parents of each of `hashes`, in order. Implementations backed by a DB may override this
+    /// in order to batch the reads
+    fn get_parents_many(&self, hashes: &[Hash]) -> Result<Vec<BlockHashes>, StoreError> {
+        hashes.iter().map(|&hash| self.get_parents(hash)).collect()
+    }
+
     /// Returns the counts of entries in parents/children stores. To be used for tests only
     fn counts(&self) -> Result<(usize, usize), StoreError>;
 }
@@ -39,7 +48,7 @@ pub trait RelationsStore: RelationsStoreReader {
 #[derive(Clone)]
 pub struct DbRelationsStore {
     db: Arc<DB>,
-    parents_access: CachedDbAccess<Hash, Arc<Vec<Hash>>, BlockHasher>,
+    parents_access: CachedDbAccess<Hash, BlockHashes, BlockHasher>,
     children_store: DbChildrenStore,
 }
 
@@ -68,6 +77,15 @@ impl DbRelationsStore {
     pub(crate) fn delete_children(&self, writer: impl DbWriter, parent: Hash) -> Result<(), StoreError> {
         self.children_store.delete_children(writer, parent)
     }
+
+    /// Loads the parents and children of `hashes` into the cache
+    pub fn prefetch(&self, hashes: &[Hash]) -> Result<(), StoreError> {
+        self.parents_access.prefetch(hashes.iter().copied())?;
+        for &hash in hashes {
+            self.children_store.get(hash)?;
+        }
+        Ok(())
+    }
 }
 
 impl RelationsStoreReader for DbRelationsStore {
@@ -75,6 +93,10 @@ impl RelationsStoreReader for DbRelationsStore {
         self.parents_access.read(hash)
     }
 
+    fn get_parents_many(&self, hashes: &[Hash]) -> Result<Vec<BlockHashes>, StoreError> {
+        self.parents_access.read_many(hashes.iter().copied())
+    }
+
     fn get_children(&self, hash: Hash) -> StoreResult<ReadLock<BlockHashSet>> {
         if !self.parents_access.has(hash)? {
             // Children store is iterator based so it might just be empty, hence we check
@@ -332,19 +354,19 @@ impl MemoryRelationsStore {
 impl ChildrenStore for MemoryRelationsStore {
     fn insert_child(&mut self, _writer: impl DbWriter, parent: Hash, child: Hash) -> Result<(), StoreError> {
         let mut children = match self.children_map.get(&parent) {
-            Some(children) => children.iter().copied().collect_vec(),
-            None => vec![],
+            Some(children) => children.iter().copied().collect(),
+            None => BlockHashVec::new(),
         };
 
         children.push(child);
-        self.children_map.insert(parent, children.into());
+        self.children_map.insert(parent, BlockHashes::new(children));
         Ok(())
     }
 
     fn delete_child(&mut self, _writer: impl DbWriter, parent: Hash, child: Hash) -> Result<(), StoreError> {
         let mut children = match self.children_map.get(&parent) {
-            Some(children) => children.iter().copied().collect_vec(),
-            None => vec![],
+            Some(children) => children.iter().copied().collect(),
+            None => BlockHashVec::new(),
         };
 
         let Some((to_remove_idx, _)) = children.iter().find_position(|current| **current == child) else {
@@ -352,7 +374,7 @@ impl ChildrenStore for MemoryRelationsStore {
         };
 
         children.remove(to_remove_idx);
-        self.children_map.insert(parent, children.into());
+        self.children_map.insert(parent, BlockHashes::new(children));
         Ok(())
     }
 }
@@ -18,6 +18,8 @@ pub trait SelectedChainStoreReader {
     fn get_by_hash(&self, hash: Hash) -> StoreResult<u64>;
     fn get_by_index(&self, index: u64) -> StoreResult<Hash>;
     fn get_tip(&self) -> StoreResult<(u64, Hash)>;
+    /// Returns whether `hash` is indexed as a block of the selected chain. Note that the chain is pruned below the pruning point
+    fn is_chain_block(&self, hash: Hash) -> StoreResult<bool>;
 }
 
 /// Write API for `SelectedChainStore`. The set function is deliberately `mut`
@@ -75,6 +77,10 @@ impl SelectedChainStoreReader for DbSelectedChainStore {
         let hash = self.access_hash_by_index.read(idx.into())?;
         Ok((idx, hash))
     }
+
+    fn is_chain_block(&self, hash: Hash) -> StoreResult<bool> {
+        self.access_index_by_hash.has(hash)
+    }
 }
 
 impl SelectedChainStore for DbSelectedChainStore {
@@ -1,18 +1,22 @@
-use kaspa_consensus_core::{blockstatus::BlockStatus, BlockHasher};
+use kaspa_consensus_core::{blockstatus::BlockStatus, constants::VALIDATION_VERSION, BlockHasher};
 use kaspa_database::registry::DatabaseStorePrefixes;
 use parking_lot::{RwLock, RwLockWriteGuard};
 use rocksdb::WriteBatch;
 use std::sync::Arc;
 
 use kaspa_database::prelude::DB;
-use kaspa_database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter};
-use kaspa_database::prelude::{StoreError, StoreResult};
+use kaspa_database::prelude::{BatchDbWriter, CachedDbAccess, DbWriter};
+use kaspa_database::prelude::{StoreError, StoreResult, StoreResultExtensions};
 use kaspa_hashes::Hash;
 
 /// Reader API for `StatusesStore`.
 pub trait StatusesStoreReader {
     fn get(&self, hash: Hash) -> StoreResult<BlockStatus>;
     fn has(&self, hash: Hash) -> StoreResult<bool>;
+
+    /// Returns the version of the validation logic which last set the status of `hash` (see `VALIDATION_VERSION`),
+    /// or 0 if the status was set before versions were stamped
+    fn validation_version(&self, hash: Hash) -> StoreResult<u16>;
 }
 
 /// Write API for `StatusesStore`. The set function is deliberately `mut`
@@ -23,16 +27,22 @@ pub trait StatusesStore: StatusesStoreReader {
     fn delete(&self, hash: Hash) -> Result<(), StoreError>;
 }
 
-/// A DB + cache implementation of `StatusesStore` trait, with concurrent readers support.
+/// A DB + cache implementation of `StatusesStore` trait, with concurrent readers support. Every status written is
+/// stamped with the current `VALIDATION_VERSION`
 #[derive(Clone)]
 pub struct DbStatusesStore {
     db: Arc<DB>,
     access: CachedDbAccess<Hash, BlockStatus, BlockHasher>,
+    versions_access: CachedDbAccess<Hash, u16, BlockHasher>,
 }
 
 impl DbStatusesStore {
     pub fn new(db: Arc<DB>, cache_size: u64) -> Self {
-        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_size, DatabaseStorePrefixes::Statuses.into()) }
+        Self {
+            db: Arc::clone(&db),
+            access: CachedDbAccess::new(db.clone(), cache_size, DatabaseStorePrefixes::Statuses.into()),
+            versions_access: CachedDbAccess::new(db, cache_size, DatabaseStorePrefixes::ValidationVersions.into()),
+        }
     }
 
     pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
@@ -40,12 +50,18 @@ impl DbStatusesStore {
     }
 
     pub fn set_batch(&mut self, batch: &mut WriteBatch, hash: Hash, status: BlockStatus) -> StoreResult<()> {
-        self.access.write(BatchDbWriter::new(batch), hash, status)
+        self.write_stamped(BatchDbWriter::new(batch), hash, status)
     }
 
     pub fn delete_batch(&self, batch: &mut WriteBatch, hash: Hash) -> Result<(), StoreError> {
+        self.versions_access.delete(BatchDbWriter::new(batch), hash)?;
         self.access.delete(BatchDbWriter::new(batch), hash)
     }
+
+    fn write_stamped(&self, mut writer: impl DbWriter, hash: Hash, status: BlockStatus) -> StoreResult<()> {
+        self.versions_access.write(&mut writer, hash, VALIDATION_VERSION)?;
+        self.access.write(writer, hash, status)
+    }
 }
 
 pub trait StatusesStoreBatchExtensions {
@@ -65,7 +81,7 @@ impl StatusesStoreBatchExtensions for Arc<RwLock<DbStatusesStore>> {
         status: BlockStatus,
     ) -> Result<RwLockWriteGuard<DbStatusesStore>, StoreError> {
         let write_guard = self.write();
-        write_guard.access.write(BatchDbWriter::new(batch), hash, status)?;
+        write_guard.write_stamped(BatchDbWriter::new(batch), hash, status)?;
         Ok(write_guard)
     }
 }
@@ -78,14 +94,24 @@ impl StatusesStoreReader for DbStatusesStore {
     fn has(&self, hash: Hash) -> StoreResult<bool> {
         self.access.has(hash)
     }
+
+    fn validation_version(&self, hash: Hash) -> StoreResult<u16> {
+        Ok(self.versions_access.read(hash).unwrap_option().unwrap_or(0))
+    }
 }
 
 impl StatusesStore for DbStatusesStore {
     fn set(&mut self, hash: Hash, status: BlockStatus) -> StoreResult<()> {
-        self.access.write(DirectDbWriter::new(&self.db), hash, status)
+        let mut batch = WriteBatch::default();
+        self.write_stamped(BatchDbWriter::new(&mut batch), hash, status)?;
+        self.db.write(batch)?;
+        Ok(())
     }
 
     fn delete(&self, hash: Hash) -> Result<(), StoreError> {
-        self.access.delete(DirectDbWriter::new(&self.db), hash)
+        let mut batch = WriteBatch::default();
+        self.delete_batch(&mut batch, hash)?;
+        self.db.write(batch)?;
+        Ok(())
     }
 }
@@ -8,9 +8,9 @@ use kaspa_consensus_core::{
 use kaspa_database::prelude::StoreError;
 use kaspa_database::prelude::StoreResultExtensions;
 use kaspa_database::prelude::DB;
-use kaspa_database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter};
+use kaspa_database::prelude::{BatchDbWriter, CachedDbAccess, DirectDbWriter, ScanOptions};
 use kaspa_hashes::Hash;
-use rocksdb::WriteBatch;
+use rocksdb::{SnapshotWithThreadMode, WriteBatch};
 use std::{error::Error, fmt::Display, sync::Arc};
 
 type UtxoCollectionIterator<'a> = Box<dyn Iterator<Item = Result<(TransactionOutpoint, UtxoEntry), Box<dyn Error>>> + 'a>;
@@ -93,15 +93,21 @@ pub struct DbUtxoSetStore {
     db: Arc<DB>,
     prefix: Vec<u8>,
     access: CachedDbAccess<UtxoKey, Arc<UtxoEntry>>,
+    scan_options: ScanOptions,
 }
 
 impl DbUtxoSetStore {
-    pub fn new(db: Arc<DB>, cache_size: u64, prefix: Vec<u8>) -> Self {
-        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_size, prefix.clone()), prefix }
+    pub fn new(db: Arc<DB>, cache_size: u64, prefix: Vec<u8>, scan_options: ScanOptions) -> Self {
+        Self {
+            db: Arc::clone(&db),
+            access: CachedDbAccess::new(db, cache_size, prefix.clone()).with_scan_options(scan_options),
+            prefix,
+            scan_options,
+        }
     }
 
     pub fn clone_with_new_cache(&self, cache_size: u64) -> Self {
-        Self::new(Arc::clone(&self.db), cache_size, self.prefix.clone())
+        Self::new(Arc::clone(&self.db), cache_size, self.prefix.clone(), self.scan_options)
     }
 
     /// See comment at [`UtxoSetStore::write_diff`]
@@ -112,6 +118,12 @@ impl DbUtxoSetStore {
         Ok(())
     }
 
+    /// Loads the entries of `outpoints` into the cache with a single batched DB read, so that following point
+    /// reads of these outpoints avoid individual DB lookups
+    pub fn prefetch(&self, outpoints: impl IntoIterator<Item = TransactionOutpoint>) -> Result<(), StoreError> {
+        self.access.prefetch(outpoints.into_iter().map(UtxoKey::from))
+    }
+
     pub fn iterator(&self) -> impl Iterator<Item = Result<(TransactionOutpoint, Arc<UtxoEntry>), Box<dyn Error>>> + '_ {
         self.access.iterator().map(|iter_result| match iter_result {
             Ok((key_bytes, utxo_entry)) => match UtxoKey::try_from(key_bytes.as_ref()) {
@@ -125,6 +137,22 @@ impl DbUtxoSetStore {
         })
     }
 
+    /// Same as [`UtxoSetStoreReader::seek_iterator`] but reading the UTXO set as it was when `snapshot` was taken
+    pub fn seek_iterator_at<'a>(
+        &'a self,
+        snapshot: &'a SnapshotWithThreadMode<'a, DB>,
+        from_outpoint: Option<TransactionOutpoint>,
+        limit: usize,
+        skip_first: bool,
+    ) -> UtxoCollectionIterator<'a> {
+        let seek_key = from_outpoint.map(UtxoKey::from);
+        Box::new(self.access.seek_iterator_at(snapshot, None, seek_key, limit, skip_first).map(|res| {
+            let (key, entry) = res?;
+            let outpoint: TransactionOutpoint = UtxoKey::try_from(key.as_ref()).unwrap().into();
+            Ok((outpoint, UtxoEntry::clone(&entry)))
+        }))
+    }
+
     /// Clear the store completely in DB and cache
     pub fn clear(&mut self) -> Result<(), StoreError> {
         let writer = DirectDbWriter::new(&self.db);
@@ -5,7 +5,7 @@ use kaspa_consensus_core::{
     utxo::utxo_diff::UtxoDiff, BlockHashMap, BlockHashSet, HashMapCustomHasher,
 };
 use kaspa_database::prelude::StoreResult;
-use kaspa_database::prelude::{BatchDbWriter, CachedDbItem, DirectDbWriter};
+use kaspa_database::prelude::{BatchDbWriter, CachedDbItem, DirectDbWriter, ScanOptions};
 use kaspa_database::prelude::{StoreError, DB};
 use kaspa_database::registry::DatabaseStorePrefixes;
 use kaspa_hashes::Hash;
@@ -84,10 +84,10 @@ pub struct VirtualStores {
 }
 
 impl VirtualStores {
-    pub fn new(db: Arc<DB>, utxoset_cache_size: u64) -> Self {
+    pub fn new(db: Arc<DB>, utxoset_cache_size: u64, scan_options: ScanOptions) -> Self {
         Self {
             state: DbVirtualStateStore::new(db.clone()),
-            utxo_set: DbUtxoSetStore::new(db, utxoset_cache_size, DatabaseStorePrefixes::VirtualUtxoset.into()),
+            utxo_set: DbUtxoSetStore::new(db, utxoset_cache_size, DatabaseStorePrefixes::VirtualUtxoset.into(), scan_options),
         }
     }
 }
@@ -12,10 +12,11 @@ use std::sync::Arc;
 
 impl BlockBodyProcessor {
     pub fn validate_body_in_context(self: &Arc<Self>, block: &Block) -> BlockProcessResult<()> {
-        self.check_parent_bodies_exist(block)?;
-        self.check_coinbase_blue_score_and_subsidy(block)?;
-        self.check_block_transactions_in_context(block)?;
-        self.check_block_is_not_pruned(block)
+        self.tracer.trace(block.hash(), "check_parent_bodies_exist", || self.check_parent_bodies_exist(block))?;
+        self.tracer
+            .trace(block.hash(), "check_coinbase_blue_score_and_subsidy", || self.check_coinbase_blue_score_and_subsidy(block))?;
+        self.tracer.trace(block.hash(), "check_block_transactions_in_context", || self.check_block_transactions_in_context(block))?;
+        self.tracer.trace(block.hash(), "check_block_is_not_pruned", || self.check_block_is_not_pruned(block))
     }
 
     fn check_block_is_not_pruned(self: &Arc<Self>, _block: &Block) -> BlockProcessResult<()> {
@@ -75,7 +76,7 @@ impl BlockBodyProcessor {
 
                 Ok(())
             }
-            Err(e) => Err(RuleError::BadCoinbasePayload(e)),
+            Err(e) => Err(RuleError::from_coinbase_payload_error(e)),
         }
     }
 }
@@ -158,6 +159,17 @@ mod tests {
             );
         }
 
+        {
+            let mut block = consensus.build_block_with_parents_and_transactions(16.into(), vec![3.into()], vec![]);
+            block.transactions[0].payload.extend([0; 256]);
+            block.header.hash_merkle_root = calc_hash_merkle_root(block.transactions.iter());
+
+            assert_match!(
+                consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task.await,
+                Err(RuleError::CoinbasePayloadLenAboveMax(_, 204))
+            );
+        }
+
         let valid_block_child = consensus.build_block_with_parents_and_transactions(6.into(), vec![3.into()], vec![]);
         consensus.validate_and_insert_block(valid_block_child.clone().to_immutable()).virtual_state_task.await.unwrap();
         {
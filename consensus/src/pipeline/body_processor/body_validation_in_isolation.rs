@@ -6,14 +6,14 @@ use kaspa_consensus_core::{block::Block, merkle::calc_hash_merkle_root, tx::Tran
 
 impl BlockBodyProcessor {
     pub fn validate_body_in_isolation(self: &Arc<Self>, block: &Block) -> BlockProcessResult<u64> {
-        Self::check_has_transactions(block)?;
-        Self::check_hash_merkle_root(block)?;
-        Self::check_only_one_coinbase(block)?;
-        self.check_transactions_in_isolation(block)?;
-        let mass = self.check_block_mass(block)?;
-        self.check_duplicate_transactions(block)?;
-        self.check_block_double_spends(block)?;
-        self.check_no_chained_transactions(block)?;
+        self.tracer.trace(block.hash(), "check_has_transactions", || Self::check_has_transactions(block))?;
+        self.tracer.trace(block.hash(), "check_hash_merkle_root", || Self::check_hash_merkle_root(block))?;
+        self.tracer.trace(block.hash(), "check_only_one_coinbase", || Self::check_only_one_coinbase(block))?;
+        self.tracer.trace(block.hash(), "check_transactions_in_isolation", || self.check_transactions_in_isolation(block))?;
+        let mass = self.tracer.trace(block.hash(), "check_block_mass", || self.check_block_mass(block))?;
+        self.tracer.trace(block.hash(), "check_duplicate_transactions", || self.check_duplicate_transactions(block))?;
+        self.tracer.trace(block.hash(), "check_block_double_spends", || self.check_block_double_spends(block))?;
+        self.tracer.trace(block.hash(), "check_no_chained_transactions", || self.check_no_chained_transactions(block))?;
         Ok(mass)
     }
 
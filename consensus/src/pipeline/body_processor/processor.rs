@@ -7,6 +7,7 @@ use crate::{
             block_transactions::DbBlockTransactionsStore,
             ghostdag::DbGhostdagStore,
             headers::DbHeadersStore,
+            invalid_blocks::{DbInvalidBlocksStore, InvalidBlockRecord, InvalidBlocksStore},
             reachability::DbReachabilityStore,
             statuses::{DbStatusesStore, StatusesStore, StatusesStoreBatchExtensions, StatusesStoreReader},
             tips::{DbTipsStore, TipsStore},
@@ -15,6 +16,8 @@ use crate::{
     },
     pipeline::{
         deps_manager::{BlockProcessingMessage, BlockTaskDependencyManager, TaskId, VirtualStateProcessingMessage},
+        exceeded_budget,
+        tracer::ValidationTracer,
         ProcessingCounters,
     },
     processes::{coinbase::CoinbaseManager, mass::MassCalculator, transaction_validator::TransactionValidator},
@@ -31,12 +34,17 @@ use kaspa_consensus_notify::{
     root::ConsensusNotificationRoot,
 };
 use kaspa_consensusmanager::SessionLock;
+use kaspa_core::warn;
 use kaspa_hashes::Hash;
 use kaspa_notify::notifier::Notify;
+use kaspa_utils::fail_point;
 use parking_lot::RwLock;
 use rayon::ThreadPool;
 use rocksdb::WriteBatch;
-use std::sync::{atomic::Ordering, Arc};
+use std::{
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
+};
 
 pub struct BlockBodyProcessor {
     // Channels
@@ -59,6 +67,7 @@ pub struct BlockBodyProcessor {
     pub(super) headers_store: Arc<DbHeadersStore>,
     pub(super) block_transactions_store: Arc<DbBlockTransactionsStore>,
     pub(super) body_tips_store: Arc<RwLock<DbTipsStore>>,
+    pub(super) invalid_blocks_store: Arc<DbInvalidBlocksStore>,
 
     // Managers and services
     pub(super) reachability_service: MTReachabilityService<DbReachabilityStore>,
@@ -78,6 +87,12 @@ pub struct BlockBodyProcessor {
 
     // Counters
     counters: Arc<ProcessingCounters>,
+
+    // Validation tracing
+    pub(super) tracer: Arc<ValidationTracer>,
+
+    /// Body processing exceeding this budget is logged along with the dependency structure of the block
+    processing_budget: Option<Duration>,
 }
 
 impl BlockBodyProcessor {
@@ -93,6 +108,7 @@ impl BlockBodyProcessor {
         headers_store: Arc<DbHeadersStore>,
         block_transactions_store: Arc<DbBlockTransactionsStore>,
         body_tips_store: Arc<RwLock<DbTipsStore>>,
+        invalid_blocks_store: Arc<DbInvalidBlocksStore>,
 
         reachability_service: MTReachabilityService<DbReachabilityStore>,
         coinbase_manager: CoinbaseManager,
@@ -104,6 +120,8 @@ impl BlockBodyProcessor {
         pruning_lock: SessionLock,
         notification_root: Arc<ConsensusNotificationRoot>,
         counters: Arc<ProcessingCounters>,
+        tracer: Arc<ValidationTracer>,
+        processing_budget: Option<Duration>,
     ) -> Self {
         Self {
             receiver,
@@ -116,6 +134,7 @@ impl BlockBodyProcessor {
             headers_store,
             block_transactions_store,
             body_tips_store,
+            invalid_blocks_store,
             coinbase_manager,
             mass_calculator,
             transaction_validator,
@@ -126,9 +145,16 @@ impl BlockBodyProcessor {
             task_manager: BlockTaskDependencyManager::new(),
             notification_root,
             counters,
+            tracer,
+            processing_budget,
         }
     }
 
+    /// Returns the number of blocks received by this processor which did not complete its processing yet
+    pub fn backlog(&self) -> usize {
+        self.receiver.len() + self.task_manager.pending_count()
+    }
+
     pub fn worker(self: &Arc<BlockBodyProcessor>) {
         while let Ok(msg) = self.receiver.recv() {
             match msg {
@@ -156,10 +182,9 @@ impl BlockBodyProcessor {
             let res = self.process_body(task.block(), task.is_trusted());
 
             let dependent_tasks = self.task_manager.end(task, |task, block_result_transmitter, virtual_state_result_transmitter| {
-                let _ = block_result_transmitter.send(res.clone());
+                block_result_transmitter.send(res.clone());
                 if res.is_err() || !task.requires_virtual_processing() {
-                    // We don't care if receivers were dropped
-                    let _ = virtual_state_result_transmitter.send(res.clone());
+                    virtual_state_result_transmitter.send(res.clone());
                 } else {
                     self.sender.send(VirtualStateProcessingMessage::Process(task, virtual_state_result_transmitter)).unwrap();
                 }
@@ -182,6 +207,7 @@ impl BlockBodyProcessor {
             _ => panic!("unexpected block status {status:?}"),
         }
 
+        let start = Instant::now();
         let mass = match self.validate_body(block, is_trusted) {
             Ok(mass) => mass,
             Err(e) => {
@@ -197,6 +223,7 @@ impl BlockBodyProcessor {
                 // not the block as a whole, so we shouldn't mark it as invalid.
                 // TODO: implement the last part.
                 if !matches!(e, RuleError::BadMerkleRoot(_, _) | RuleError::MissingParents(_)) {
+                    self.invalid_blocks_store.insert(block.hash(), Arc::new(InvalidBlockRecord::new(e.code(), block))).unwrap();
                     self.statuses_store.write().set(block.hash(), BlockStatus::StatusInvalid).unwrap();
                 }
                 return Err(e);
@@ -204,6 +231,7 @@ impl BlockBodyProcessor {
         };
 
         self.commit_body(block.hash(), block.header.direct_parents(), block.transactions.clone());
+        self.check_processing_budget(block, mass, start.elapsed());
 
         // Send a BlockAdded notification
         self.notification_root
@@ -217,6 +245,25 @@ impl BlockBodyProcessor {
         Ok(BlockStatus::StatusUTXOPendingVerification)
     }
 
+    /// Logs the dependency structure of a block whose body processing exceeded the budget, in order to
+    /// correlate latency spikes with DAG shapes
+    fn check_processing_budget(&self, block: &Block, mass: u64, elapsed: Duration) {
+        let Some(budget) = exceeded_budget(self.processing_budget, elapsed) else {
+            return;
+        };
+        warn!(
+            "Body processing of block {} took {} ms (budget: {} ms): {} parents, {} transactions with {} inputs and {} outputs, mass of {}",
+            block.hash(),
+            elapsed.as_millis(),
+            budget.as_millis(),
+            block.header.direct_parents().len(),
+            block.transactions.len(),
+            block.transactions.iter().map(|tx| tx.inputs.len()).sum::<usize>(),
+            block.transactions.iter().map(|tx| tx.outputs.len()).sum::<usize>(),
+            mass
+        );
+    }
+
     fn validate_body(self: &Arc<BlockBodyProcessor>, block: &Block, is_trusted: bool) -> BlockProcessResult<u64> {
         let mass = self.validate_body_in_isolation(block)?;
         if !is_trusted {
@@ -237,6 +284,7 @@ impl BlockBodyProcessor {
         let statuses_write_guard =
             self.statuses_store.set_batch(&mut batch, hash, BlockStatus::StatusUTXOPendingVerification).unwrap();
 
+        fail_point!("body-processor-commit");
         self.db.write(batch).unwrap();
 
         // Calling the drops explicitly after the batch is written in order to avoid possible errors.
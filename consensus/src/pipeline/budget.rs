@@ -0,0 +1,167 @@
+use kaspa_consensus_core::{block::Block, header::Header, mass::transaction_estimated_serialized_size};
+use kaspa_hashes::HASH_SIZE;
+use parking_lot::Mutex;
+use std::{collections::VecDeque, mem::size_of, sync::Arc};
+
+/// Dispatches an admitted block to the processing pipeline
+type Dispatch = Box<dyn FnOnce(PipelinePermit) + Send>;
+
+#[derive(Default)]
+struct InFlight {
+    blocks: usize,
+    bytes: u64,
+    /// Blocks submitted while the pipeline is over budget, in submission order, along with their estimated size
+    parked: VecDeque<(u64, Dispatch)>,
+}
+
+/// Bounds the number of blocks admitted to the processing pipeline and not yet fully processed, as well as their
+/// total estimated size, so that a fast producer (e.g., IBD of full blocks) cannot accumulate an unbounded amount of
+/// blocks in the processing stages. Blocks are admitted at submission, and blocks submitted while the pipeline is over
+/// budget are parked and dispatched in submission order as the blocks in flight are released, so submitters are never
+/// blocked and a block is never dispatched before a block submitted earlier. A block is always admitted into an empty
+/// pipeline, hence a block larger than the byte cap is still processed
+pub struct PipelineBudget {
+    /// The maximal number of blocks in flight (0 for no limit)
+    max_blocks: usize,
+    /// The maximal estimated size of the blocks in flight (0 for no limit)
+    max_bytes: u64,
+    in_flight: Mutex<InFlight>,
+}
+
+impl PipelineBudget {
+    pub fn new(max_blocks: usize, max_bytes: u64) -> Arc<Self> {
+        Arc::new(Self { max_blocks, max_bytes, in_flight: Default::default() })
+    }
+
+    /// Admits `block` into the budget and calls `dispatch` with a permit accounting for it until dropped. The block is
+    /// dispatched right away if it fits within the budget and no block submitted earlier is parked, otherwise it is
+    /// parked until enough blocks in flight are released
+    pub fn admit(self: &Arc<Self>, block: &Block, dispatch: impl FnOnce(PipelinePermit) + Send + 'static) {
+        let bytes = estimated_block_size(block);
+        let mut in_flight = self.in_flight.lock();
+        if !in_flight.parked.is_empty() || !self.fits(&in_flight, bytes) {
+            in_flight.parked.push_back((bytes, Box::new(dispatch)));
+            return;
+        }
+        let permit = self.account(&mut in_flight, bytes);
+        drop(in_flight);
+        dispatch(permit);
+    }
+
+    fn fits(&self, in_flight: &InFlight, bytes: u64) -> bool {
+        in_flight.blocks == 0
+            || ((self.max_blocks == 0 || in_flight.blocks < self.max_blocks)
+                && (self.max_bytes == 0 || in_flight.bytes + bytes <= self.max_bytes))
+    }
+
+    fn account(self: &Arc<Self>, in_flight: &mut InFlight, bytes: u64) -> PipelinePermit {
+        in_flight.blocks += 1;
+        in_flight.bytes += bytes;
+        PipelinePermit { budget: self.clone(), bytes }
+    }
+
+    /// Returns the number of blocks in flight and their total estimated size
+    pub fn in_flight(&self) -> (usize, u64) {
+        let in_flight = self.in_flight.lock();
+        (in_flight.blocks, in_flight.bytes)
+    }
+
+    /// Returns the number of blocks parked until the pipeline has room for them
+    pub fn parked(&self) -> usize {
+        self.in_flight.lock().parked.len()
+    }
+
+    fn release(self: &Arc<Self>, bytes: u64) {
+        let mut in_flight = self.in_flight.lock();
+        in_flight.blocks -= 1;
+        in_flight.bytes -= bytes;
+        let mut admitted = Vec::new();
+        while in_flight.parked.front().is_some_and(|&(bytes, _)| self.fits(&in_flight, bytes)) {
+            let (bytes, dispatch) = in_flight.parked.pop_front().unwrap();
+            admitted.push((self.account(&mut in_flight, bytes), dispatch));
+        }
+        // Dispatch outside the lock, since a dispatch may in turn release a permit
+        drop(in_flight);
+        for (permit, dispatch) in admitted {
+            dispatch(permit);
+        }
+    }
+}
+
+/// Accounts for a block in the [`PipelineBudget`] for as long as it is alive
+pub struct PipelinePermit {
+    budget: Arc<PipelineBudget>,
+    bytes: u64,
+}
+
+impl Drop for PipelinePermit {
+    fn drop(&mut self) {
+        self.budget.release(self.bytes);
+    }
+}
+
+/// Estimates the memory held by `block`, where transactions are accounted by their estimated serialized size
+fn estimated_block_size(block: &Block) -> u64 {
+    let header_size = size_of::<Header>() + block.header.parents_by_level.iter().map(|level| level.len() * HASH_SIZE).sum::<usize>();
+    header_size as u64 + block.transactions.iter().map(transaction_estimated_serialized_size).sum::<u64>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::{
+        subnets::SUBNETWORK_ID_NATIVE,
+        tx::{Transaction, TransactionInput, TransactionOutpoint},
+    };
+    use kaspa_hashes::Hash;
+    use std::sync::mpsc;
+
+    fn block_with_payload(payload_size: usize) -> Block {
+        let input = TransactionInput::new(TransactionOutpoint::new(Hash::from_u64_word(1), 0), vec![], 0, 0);
+        let tx = Transaction::new(0, vec![input], vec![], 0, SUBNETWORK_ID_NATIVE, 0, vec![0; payload_size]);
+        Block::new(Header::from_precomputed_hash(Hash::from_u64_word(1), vec![]), vec![tx])
+    }
+
+    #[test]
+    fn test_pipeline_budget() {
+        let small = block_with_payload(0);
+        let large = block_with_payload(10_000);
+        let budget = PipelineBudget::new(3, estimated_block_size(&large) + estimated_block_size(&small));
+        let (sender, receiver) = mpsc::channel();
+        let submit = |block: &Block, id: u32| {
+            let sender = sender.clone();
+            budget.admit(block, move |permit| sender.send((id, permit)).unwrap());
+        };
+
+        // Blocks are dispatched right away while both caps hold
+        submit(&small, 0);
+        submit(&small, 1);
+        submit(&small, 2);
+        let permits: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(permits.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(budget.in_flight(), (3, 3 * estimated_block_size(&small)));
+        drop(permits);
+        assert_eq!(budget.in_flight(), (0, 0));
+
+        // Blocks exceeding a cap are parked, and so are the blocks submitted after them, even if they fit
+        submit(&large, 0);
+        submit(&small, 1);
+        submit(&large, 2);
+        submit(&small, 3);
+        let (_, large_permit) = receiver.try_recv().unwrap();
+        let (_, small_permit) = receiver.try_recv().unwrap();
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(budget.parked(), 2);
+
+        // Parked blocks are dispatched in submission order as the blocks in flight are released
+        drop(large_permit);
+        let permits: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(permits.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![2]);
+        drop(small_permit);
+        let late: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(late.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(budget.parked(), 0);
+        drop((permits, late));
+        assert_eq!(budget.in_flight(), (0, 0));
+    }
+}
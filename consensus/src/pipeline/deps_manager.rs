@@ -1,14 +1,52 @@
+use super::budget::PipelinePermit;
 use crate::errors::BlockProcessResult;
-use kaspa_consensus_core::{block::Block, blockstatus::BlockStatus};
+use kaspa_consensus_core::{
+    block::{Block, BlockOrigin},
+    blockstatus::BlockStatus,
+    tx::Transaction,
+};
 use kaspa_hashes::Hash;
 use parking_lot::{Condvar, Mutex};
-use std::collections::{
-    hash_map::Entry::{Occupied, Vacant},
-    HashMap, VecDeque,
+use std::{
+    collections::{
+        hash_map::Entry::{Occupied, Vacant},
+        HashMap, VecDeque,
+    },
+    sync::Arc,
 };
 use tokio::sync::oneshot;
 
-pub type BlockResultSender = oneshot::Sender<BlockProcessResult<BlockStatus>>;
+/// Transmits the processing result of a block task to the async callers awaiting it. Duplicate submissions
+/// of a pending block are coalesced into the pending task, hence a single task might have several callers
+pub struct BlockResultSender {
+    senders: Vec<oneshot::Sender<BlockProcessResult<BlockStatus>>>,
+    /// Pipeline budget permits of the submissions, released once the result is sent (or the sender is dropped)
+    permits: Vec<PipelinePermit>,
+}
+
+impl BlockResultSender {
+    pub fn new(sender: oneshot::Sender<BlockProcessResult<BlockStatus>>) -> Self {
+        Self { senders: vec![sender], permits: Vec::new() }
+    }
+
+    /// Holds `permit` until the result is sent
+    pub fn with_permit(mut self, permit: PipelinePermit) -> Self {
+        self.permits.push(permit);
+        self
+    }
+
+    fn merge(&mut self, other: BlockResultSender) {
+        self.senders.extend(other.senders);
+        self.permits.extend(other.permits);
+    }
+
+    pub fn send(self, result: BlockProcessResult<BlockStatus>) {
+        for sender in self.senders {
+            // We don't care if receivers were dropped
+            let _ = sender.send(result.clone());
+        }
+    }
+}
 
 pub enum BlockProcessingMessage {
     Exit,
@@ -42,7 +80,7 @@ impl VirtualStateProcessingMessage {
 
 pub enum BlockTask {
     /// Ordinary block processing task, requiring full validation. The block might be header-only
-    Ordinary { block: Block },
+    Ordinary { block: Block, origin: BlockOrigin },
 
     /// Trusted block processing task, only requiring partial validation.
     /// Trusted blocks arrive as part of the pruning proof; the block might be header-only.
@@ -52,11 +90,19 @@ pub enum BlockTask {
 impl BlockTask {
     pub fn block(&self) -> &Block {
         match self {
-            BlockTask::Ordinary { block } => block,
+            BlockTask::Ordinary { block, .. } => block,
             BlockTask::Trusted { block } => block,
         }
     }
 
+    pub fn origin(&self) -> BlockOrigin {
+        match self {
+            BlockTask::Ordinary { origin, .. } => *origin,
+            // Trusted blocks are only received as part of IBD
+            BlockTask::Trusted { .. } => BlockOrigin::Ibd,
+        }
+    }
+
     pub fn is_ordinary(&self) -> bool {
         matches!(self, BlockTask::Ordinary { .. })
     }
@@ -71,11 +117,28 @@ impl BlockTask {
     }
 }
 
+/// Tasks of the same block and of the same kind produce the same processing result, provided their bodies are identical
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct BlockTaskKind {
+    trusted: bool,
+    header_only: bool,
+}
+
+impl From<&BlockTask> for BlockTaskKind {
+    fn from(task: &BlockTask) -> Self {
+        Self { trusted: task.is_trusted(), header_only: task.block().is_header_only() }
+    }
+}
+
 /// An internal struct used to manage a block processing task
 struct BlockTaskInternal {
     // The externally accepted block task
     task: Option<BlockTask>,
 
+    // The kind and the transactions of the task, kept since the task itself is taken once processing begins
+    kind: BlockTaskKind,
+    transactions: Arc<Vec<Transaction>>,
+
     // A list of channel senders for transmitting the processing result of this task to the async callers
     block_result_transmitter: BlockResultSender,
     virtual_state_result_transmitter: BlockResultSender,
@@ -83,7 +146,20 @@ struct BlockTaskInternal {
 
 impl BlockTaskInternal {
     fn new(task: BlockTask, block_result_transmitter: BlockResultSender, virtual_state_result_transmitter: BlockResultSender) -> Self {
-        Self { task: Some(task), block_result_transmitter, virtual_state_result_transmitter }
+        Self {
+            kind: BlockTaskKind::from(&task),
+            transactions: task.block().transactions.clone(),
+            task: Some(task),
+            block_result_transmitter,
+            virtual_state_result_transmitter,
+        }
+    }
+
+    /// Returns whether `task` is bound to produce the same processing result as this task. Tasks of the same hash
+    /// might still carry different bodies, e.g. a body with a malformed transaction relayed by a malicious peer
+    fn is_duplicate(&self, task: &BlockTask) -> bool {
+        let transactions = &task.block().transactions;
+        self.kind == BlockTaskKind::from(task) && (Arc::ptr_eq(&self.transactions, transactions) || self.transactions == *transactions)
     }
 }
 
@@ -143,6 +219,15 @@ impl TaskQueue {
         }
     }
 
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut BlockTaskInternal> {
+        let (single, many) = match self {
+            TaskQueue::Empty => (None, None),
+            TaskQueue::Single(t) => (Some(t), None),
+            TaskQueue::Many(q) => (None, Some(q.iter_mut())),
+        };
+        single.into_iter().chain(many.into_iter().flatten())
+    }
+
     fn is_empty(&self) -> bool {
         match self {
             TaskQueue::Empty => true,
@@ -183,8 +268,11 @@ impl BlockTaskDependencyManager {
     /// Registers the `(task, result_transmitter)` pair as a pending task. If a task with the same
     /// hash is already pending and has a corresponding internal task group, the task group is updated
     /// with the additional task and the function returns `None` indicating that the task shall
-    /// not be queued for processing yet. The function is expected to be called by a single worker
-    /// controlling the reception of block processing tasks.
+    /// not be queued for processing yet. If the group already holds a task of the same kind and with the
+    /// same transactions (possibly being processed), the duplicate is coalesced into it and its callers
+    /// receive the same result.
+    /// The function is expected to be called by a single worker controlling the reception of block
+    /// processing tasks.
     pub fn register(
         &self,
         task: BlockTask,
@@ -202,10 +290,14 @@ impl BlockTaskDependencyManager {
                 )));
                 Some(hash)
             }
-            e => {
-                e.and_modify(|g| {
-                    g.tasks.push_back(BlockTaskInternal::new(task, block_result_transmitter, virtual_state_result_transmitter));
-                });
+            Occupied(mut e) => {
+                let group = e.get_mut();
+                if let Some(existing) = group.tasks.iter_mut().find(|t| t.is_duplicate(&task)) {
+                    existing.block_result_transmitter.merge(block_result_transmitter);
+                    existing.virtual_state_result_transmitter.merge(virtual_state_result_transmitter);
+                } else {
+                    group.tasks.push_back(BlockTaskInternal::new(task, block_result_transmitter, virtual_state_result_transmitter));
+                }
                 None
             }
         }
@@ -264,6 +356,11 @@ impl BlockTaskDependencyManager {
         next_tasks
     }
 
+    /// Returns the number of blocks registered and not yet ended, whether queued, waiting for a parent or being processed
+    pub fn pending_count(&self) -> usize {
+        self.pending.lock().len()
+    }
+
     /// Wait until all pending tasks are completed and workers are idle.
     pub fn wait_for_idle(&self) {
         let mut pending = self.pending.lock();
@@ -272,3 +369,72 @@ impl BlockTaskDependencyManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::blockstatus::BlockStatus::StatusUTXOPendingVerification;
+
+    fn register(
+        manager: &BlockTaskDependencyManager,
+        block: Block,
+    ) -> (Option<TaskId>, [oneshot::Receiver<BlockProcessResult<BlockStatus>>; 2]) {
+        let (btx, brx) = oneshot::channel();
+        let (vtx, vrx) = oneshot::channel();
+        let task = BlockTask::Ordinary { block, origin: BlockOrigin::Local };
+        (manager.register(task, BlockResultSender::new(btx), BlockResultSender::new(vtx)), [brx, vrx])
+    }
+
+    #[test]
+    fn test_duplicate_tasks_coalescing() {
+        let manager = BlockTaskDependencyManager::new();
+        let block = Block::from_precomputed_hash(1.into(), vec![]);
+        let (task_id, first_receivers) = register(&manager, block.clone());
+        let task = manager.try_begin(task_id.unwrap()).unwrap();
+
+        // A duplicate arriving while the task is processed is coalesced into it
+        let (duplicate_id, duplicate_receivers) = register(&manager, block.clone());
+        assert_eq!(duplicate_id, None);
+
+        // A trusted task of the same block is queued separately
+        let (btx, _brx) = oneshot::channel();
+        let (vtx, _vrx) = oneshot::channel();
+        let trusted_task = BlockTask::Trusted { block: block.clone() };
+        assert_eq!(manager.register(trusted_task, BlockResultSender::new(btx), BlockResultSender::new(vtx)), None);
+
+        let next_tasks = manager.end(task, |_, block_result_transmitter, virtual_state_result_transmitter| {
+            block_result_transmitter.send(Ok(StatusUTXOPendingVerification));
+            virtual_state_result_transmitter.send(Ok(StatusUTXOPendingVerification));
+        });
+        assert_eq!(next_tasks, vec![block.hash()]);
+        for mut receiver in first_receivers.into_iter().chain(duplicate_receivers) {
+            assert!(matches!(receiver.try_recv().unwrap(), Ok(StatusUTXOPendingVerification)));
+        }
+
+        assert!(manager.try_begin(block.hash()).unwrap().is_trusted());
+    }
+
+    #[test]
+    fn test_duplicate_tasks_with_different_bodies() {
+        let manager = BlockTaskDependencyManager::new();
+        let header = Block::from_precomputed_hash(1.into(), vec![]).header;
+        let block = Block::from_arcs(header.clone(), Arc::new(vec![Transaction::default()]));
+        let (task_id, _first_receivers) = register(&manager, block.clone());
+        let task = manager.try_begin(task_id.unwrap()).unwrap();
+
+        // An equal body is coalesced even if not shared
+        let equal_body = Block::from_arcs(header.clone(), Arc::new(vec![Transaction::default()]));
+        assert_eq!(register(&manager, equal_body).0, None);
+
+        // A different body of the same hash is queued separately rather than inheriting the result
+        let other_body = Block::from_arcs(header, Arc::new(vec![Transaction { lock_time: 1, ..Default::default() }]));
+        assert_eq!(register(&manager, other_body).0, None);
+
+        let next_tasks = manager.end(task, |_, block_result_transmitter, virtual_state_result_transmitter| {
+            block_result_transmitter.send(Ok(StatusUTXOPendingVerification));
+            virtual_state_result_transmitter.send(Ok(StatusUTXOPendingVerification));
+        });
+        assert_eq!(next_tasks, vec![block.hash()]);
+        assert_eq!(manager.try_begin(block.hash()).unwrap().block().transactions[0].lock_time, 1);
+    }
+}
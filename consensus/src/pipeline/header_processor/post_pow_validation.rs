@@ -8,13 +8,13 @@ use std::collections::HashSet;
 
 impl HeaderProcessor {
     pub fn post_pow_validation(&self, ctx: &mut HeaderProcessingContext, header: &Header) -> BlockProcessResult<()> {
-        self.check_blue_score(ctx, header)?;
-        self.check_blue_work(ctx, header)?;
-        self.check_median_timestamp(ctx, header)?;
-        self.check_merge_size_limit(ctx)?;
-        self.check_bounded_merge_depth(ctx)?;
-        self.check_pruning_point(ctx, header)?;
-        self.check_indirect_parents(ctx, header)
+        self.tracer.trace(header.hash, "check_blue_score", || self.check_blue_score(ctx, header))?;
+        self.tracer.trace(header.hash, "check_blue_work", || self.check_blue_work(ctx, header))?;
+        self.tracer.trace(header.hash, "check_median_timestamp", || self.check_median_timestamp(ctx, header))?;
+        self.tracer.trace(header.hash, "check_merge_size_limit", || self.check_merge_size_limit(ctx))?;
+        self.tracer.trace(header.hash, "check_bounded_merge_depth", || self.check_bounded_merge_depth(ctx))?;
+        self.tracer.trace(header.hash, "check_pruning_point", || self.check_pruning_point(ctx, header))?;
+        self.tracer.trace(header.hash, "check_indirect_parents", || self.check_indirect_parents(ctx, header))
     }
 
     pub fn check_median_timestamp(&self, ctx: &mut HeaderProcessingContext, header: &Header) -> BlockProcessResult<()> {
@@ -15,19 +15,30 @@ impl HeaderProcessor {
     /// Validates the header in isolation including pow check against header declared bits.
     /// Returns the block level as computed from pow state or a rule error if such was encountered
     pub(super) fn validate_header_in_isolation(&self, header: &Header) -> BlockProcessResult<BlockLevel> {
-        self.check_header_version(header)?;
-        self.check_block_timestamp_in_isolation(header)?;
-        self.check_parents_limit(header)?;
-        Self::check_parents_not_origin(header)?;
-        self.check_pow_and_calc_block_level(header)
+        self.tracer.trace(header.hash, "check_header_version", || self.check_header_version(header))?;
+        self.tracer.trace(header.hash, "check_block_timestamp_in_isolation", || self.check_block_timestamp_in_isolation(header))?;
+        self.tracer.trace(header.hash, "check_parents_limit", || self.check_parents_limit(header))?;
+        self.tracer.trace(header.hash, "check_parents_not_origin", || Self::check_parents_not_origin(header))?;
+        self.tracer.trace(header.hash, "check_pow_and_calc_block_level", || self.check_pow_and_calc_block_level(header))
     }
 
     pub(super) fn validate_parent_relations(&self, header: &Header) -> BlockProcessResult<()> {
-        self.check_parents_exist(header)?;
-        self.check_parents_incest(header)?;
+        self.tracer.trace(header.hash, "check_parents_exist", || self.check_parents_exist(header))?;
+        self.tracer.trace(header.hash, "check_parents_incest", || self.check_parents_incest(header))?;
         Ok(())
     }
 
+    /// Runs the cheap checks of [`Self::validate_header_in_isolation`] and the parents existence check out of the
+    /// processing pipeline, allowing block submitters to be rejected before their block is queued
+    pub(crate) fn precheck_header(&self, header: &Header) -> BlockProcessResult<()> {
+        self.check_header_version(header)?;
+        self.check_block_timestamp_in_isolation(header)?;
+        self.check_parents_limit(header)?;
+        Self::check_parents_not_origin(header)?;
+        self.check_pow_and_calc_block_level(header)?;
+        self.check_parents_exist(header)
+    }
+
     fn check_header_version(&self, header: &Header) -> BlockProcessResult<()> {
         if header.version != constants::BLOCK_VERSION {
             return Err(RuleError::WrongBlockVersion(header.version));
@@ -6,8 +6,8 @@ use kaspa_consensus_core::header::Header;
 
 impl HeaderProcessor {
     pub(super) fn pre_pow_validation(&self, ctx: &mut HeaderProcessingContext, header: &Header) -> BlockProcessResult<()> {
-        self.check_pruning_violation(ctx)?;
-        self.check_difficulty_and_daa_score(ctx, header)?;
+        self.tracer.trace(header.hash, "check_pruning_violation", || self.check_pruning_violation(ctx))?;
+        self.tracer.trace(header.hash, "check_difficulty_and_daa_score", || self.check_difficulty_and_daa_score(ctx, header))?;
         Ok(())
     }
 
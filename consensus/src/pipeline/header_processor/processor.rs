@@ -16,6 +16,7 @@ use crate::{
             ghostdag::{DbGhostdagStore, GhostdagData, GhostdagStoreReader},
             headers::DbHeadersStore,
             headers_selected_tip::{DbHeadersSelectedTipStore, HeadersSelectedTipStoreReader},
+            invalid_blocks::{DbInvalidBlocksStore, InvalidBlockRecord, InvalidBlocksStore},
             pruning::{DbPruningStore, PruningPointInfo, PruningStoreReader},
             reachability::{DbReachabilityStore, StagingReachabilityStore},
             relations::{DbRelationsStore, RelationsStoreReader},
@@ -24,28 +25,47 @@ use crate::{
         },
     },
     params::Params,
-    pipeline::deps_manager::{BlockProcessingMessage, BlockTask, BlockTaskDependencyManager, TaskId},
-    processes::{ghostdag::ordering::SortableBlock, reachability::inquirer as reachability, relations::RelationsStoreExtensions},
+    pipeline::deps_manager::{BlockProcessingMessage, BlockResultSender, BlockTask, BlockTaskDependencyManager, TaskId},
+    processes::{
+        ghostdag::ordering::SortableBlock, pruning_proof::PruningProofManager, reachability::inquirer as reachability,
+        relations::RelationsStoreExtensions,
+    },
 };
 use crossbeam_channel::{Receiver, Sender};
 use itertools::Itertools;
 use kaspa_consensus_core::{
-    blockhash::{BlockHashes, ORIGIN},
-    blockstatus::BlockStatus::{self, StatusHeaderOnly, StatusInvalid},
+    block::{Block, BlockOrigin},
+    blockhash::{BlockHashVec, BlockHashes, ORIGIN},
+    blockstatus::BlockStatus::{
+        self, StatusDisqualifiedFromChain, StatusHeaderOnly, StatusInvalid, StatusUTXOPendingVerification, StatusUTXOValid,
+    },
     config::genesis::GenesisBlock,
     header::Header,
     BlockHashSet, BlockLevel,
 };
 use kaspa_consensusmanager::SessionLock;
+use kaspa_core::{
+    error,
+    panic::{catch_recoverable_panic, panic_message},
+    warn,
+};
 use kaspa_database::prelude::{StoreResultEmptyTuple, StoreResultExtensions};
 use kaspa_hashes::Hash;
-use kaspa_utils::vec::VecExtensions;
+use kaspa_utils::fail_point;
 use parking_lot::RwLock;
 use rayon::ThreadPool;
 use rocksdb::WriteBatch;
-use std::sync::{atomic::Ordering, Arc};
+use smallvec::smallvec;
+use std::{
+    panic,
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
+};
+
+use super::super::{exceeded_budget, network_stats::NetworkStatsStore, tracer::ValidationTracer, ProcessingCounters};
 
-use super::super::ProcessingCounters;
+/// The number of times the header processor worker is restarted after dying unexpectedly before the failure is escalated
+pub const MAX_WORKER_RESTARTS: usize = 3;
 
 pub struct HeaderProcessingContext {
     pub hash: Hash,
@@ -103,6 +123,30 @@ impl HeaderProcessingContext {
     }
 }
 
+/// The dependency structure of a block which determines the cost of processing its header
+struct DependencyShape {
+    parents: usize,
+    mergeset_blues: usize,
+    mergeset_reds: usize,
+    mergeset_non_daa: usize,
+    difficulty_window: usize,
+    past_median_time_window: usize,
+}
+
+impl DependencyShape {
+    fn new(ctx: &HeaderProcessingContext) -> Self {
+        let ghostdag_data = ctx.ghostdag_data();
+        Self {
+            parents: ctx.header.direct_parents().len(),
+            mergeset_blues: ghostdag_data.mergeset_blues.len(),
+            mergeset_reds: ghostdag_data.mergeset_reds.len(),
+            mergeset_non_daa: ctx.mergeset_non_daa.as_ref().map_or(0, |set| set.len()),
+            difficulty_window: ctx.block_window_for_difficulty.as_ref().map_or(0, |window| window.len()),
+            past_median_time_window: ctx.block_window_for_past_median_time.as_ref().map_or(0, |window| window.len()),
+        }
+    }
+}
+
 pub struct HeaderProcessor {
     // Channels
     receiver: Receiver<BlockProcessingMessage>,
@@ -119,6 +163,8 @@ pub struct HeaderProcessor {
     pub(super) mergeset_size_limit: u64,
     pub(super) skip_proof_of_work: bool,
     pub(super) max_block_level: BlockLevel,
+    /// Header processing exceeding this budget is logged along with the dependency structure of the block
+    processing_budget: Option<Duration>,
 
     // DB
     db: Arc<DB>,
@@ -136,6 +182,7 @@ pub struct HeaderProcessor {
     pub(super) headers_store: Arc<DbHeadersStore>,
     pub(super) headers_selected_tip_store: Arc<RwLock<DbHeadersSelectedTipStore>>,
     pub(super) depth_store: Arc<DbDepthStore>,
+    pub(super) invalid_blocks_store: Arc<DbInvalidBlocksStore>,
 
     // Managers and services
     pub(super) ghostdag_managers: Arc<Vec<DbGhostdagManager>>,
@@ -145,6 +192,7 @@ pub struct HeaderProcessor {
     pub(super) reachability_service: MTReachabilityService<DbReachabilityStore>,
     pub(super) pruning_point_manager: DbPruningPointManager,
     pub(super) parents_manager: DbParentsManager,
+    pruning_proof_manager: Arc<PruningProofManager>,
 
     // Pruning lock
     pruning_lock: SessionLock,
@@ -154,6 +202,12 @@ pub struct HeaderProcessor {
 
     // Counters
     counters: Arc<ProcessingCounters>,
+
+    // Validation tracing
+    pub(super) tracer: Arc<ValidationTracer>,
+
+    // Rolling network statistics
+    network_stats: Arc<NetworkStatsStore>,
 }
 
 impl HeaderProcessor {
@@ -167,6 +221,9 @@ impl HeaderProcessor {
         services: &Arc<ConsensusServices>,
         pruning_lock: SessionLock,
         counters: Arc<ProcessingCounters>,
+        tracer: Arc<ValidationTracer>,
+        network_stats: Arc<NetworkStatsStore>,
+        processing_budget: Option<Duration>,
     ) -> Self {
         Self {
             receiver,
@@ -184,6 +241,7 @@ impl HeaderProcessor {
             daa_excluded_store: storage.daa_excluded_store.clone(),
             headers_store: storage.headers_store.clone(),
             depth_store: storage.depth_store.clone(),
+            invalid_blocks_store: storage.invalid_blocks_store.clone(),
             headers_selected_tip_store: storage.headers_selected_tip_store.clone(),
             block_window_cache_for_difficulty: storage.block_window_cache_for_difficulty.clone(),
             block_window_cache_for_past_median_time: storage.block_window_cache_for_past_median_time.clone(),
@@ -195,10 +253,13 @@ impl HeaderProcessor {
             depth_manager: services.depth_manager.clone(),
             pruning_point_manager: services.pruning_point_manager.clone(),
             parents_manager: services.parents_manager.clone(),
+            pruning_proof_manager: services.pruning_proof_manager.clone(),
 
             task_manager: BlockTaskDependencyManager::new(),
             pruning_lock,
             counters,
+            tracer,
+            network_stats,
             // TODO (HF): make sure to also pass `new_timestamp_deviation_tolerance` and use according to HF activation score
             timestamp_deviation_tolerance: params.timestamp_deviation_tolerance(0),
             target_time_per_block: params.target_time_per_block,
@@ -206,16 +267,69 @@ impl HeaderProcessor {
             mergeset_size_limit: params.mergeset_size_limit,
             skip_proof_of_work: params.skip_proof_of_work,
             max_block_level: params.max_block_level,
+            processing_budget,
         }
     }
 
+    /// Returns the number of blocks received by this processor which did not complete its processing yet
+    pub fn backlog(&self) -> usize {
+        self.receiver.len() + self.task_manager.pending_count()
+    }
+
+    /// Runs the message loop, restarting it whenever it panics. Panics of the loop are caught rather than exiting the
+    /// process (see [`catch_recoverable_panic`]), and headers are committed atomically, so a restarted loop resumes from
+    /// consistent store state, while blocks already dispatched to the thread-pool proceed regardless of the loop. Panics
+    /// processing a block on the thread-pool are caught as well, failing that block alone (see `queue_block`).
+    ///
+    /// Once [`MAX_WORKER_RESTARTS`] restarts are exhausted, the exit signal is passed on so that the following processors
+    /// wind down, and the failure is propagated to the thread joining this worker in order to be escalated
     pub fn worker(self: &Arc<HeaderProcessor>) {
-        while let Ok(msg) = self.receiver.recv() {
+        let mut restarts = 0;
+        let failure = loop {
+            match catch_recoverable_panic(|| self.message_loop()) {
+                Ok(()) => break None,
+                Err(payload) => {
+                    let reason = panic_message(&*payload);
+                    if restarts == MAX_WORKER_RESTARTS {
+                        break Some(format!("the header processor worker died {} times, last: {}", restarts + 1, reason));
+                    }
+                    restarts += 1;
+                    warn!("The header processor worker died ({}), restarting it ({}/{})", reason, restarts, MAX_WORKER_RESTARTS);
+                }
+            }
+        };
+
+        // Wait until all workers are idle before exiting
+        self.task_manager.wait_for_idle();
+
+        // Pass the exit signal on to the following processor
+        self.body_sender.send(BlockProcessingMessage::Exit).unwrap();
+
+        if let Some(failure) = failure {
+            error!("{}, the consensus pipeline is stopping", failure);
+            // Resuming the unwind does not invoke the panic hook, leaving the escalation to the joining thread
+            panic::resume_unwind(Box::new(failure));
+        }
+    }
+
+    /// Dispatches incoming blocks to the thread-pool until receiving the exit signal
+    fn message_loop(self: &Arc<HeaderProcessor>) {
+        loop {
+            fail_point!("header-processor-worker");
+            let Ok(msg) = self.receiver.recv() else {
+                return;
+            };
             match msg {
                 BlockProcessingMessage::Exit => {
-                    break;
+                    return;
                 }
                 BlockProcessingMessage::Process(task, block_result_transmitter, virtual_state_result_transmitter) => {
+                    if let Some(result) = self.known_block_result(&task) {
+                        self.counters.known_block_counts.fetch_add(1, Ordering::Relaxed);
+                        block_result_transmitter.send(result.clone());
+                        virtual_state_result_transmitter.send(result);
+                        continue;
+                    }
                     if let Some(task_id) = self.task_manager.register(task, block_result_transmitter, virtual_state_result_transmitter)
                     {
                         let processor = self.clone();
@@ -226,34 +340,42 @@ impl HeaderProcessor {
                 }
             };
         }
+    }
 
-        // Wait until all workers are idle before exiting
-        self.task_manager.wait_for_idle();
-
-        // Pass the exit signal on to the following processor
-        self.body_sender.send(BlockProcessingMessage::Exit).unwrap();
+    /// Returns the processing result of a block which was already processed to the extent required by `task`, so that
+    /// resubmissions (e.g., blocks overlapping between relay and IBD) are resolved without passing through the pipeline.
+    /// Blocks pending UTXO verification are not resolved since their virtual processing might still be in progress
+    fn known_block_result(&self, task: &BlockTask) -> Option<BlockProcessResult<BlockStatus>> {
+        let status = self.statuses_store.read().get(task.block().hash()).unwrap_option()?;
+        match status {
+            StatusInvalid => Some(Err(RuleError::KnownInvalid)),
+            _ if task.block().is_header_only() => Some(Ok(status)),
+            StatusUTXOValid | StatusDisqualifiedFromChain => Some(Ok(status)),
+            StatusHeaderOnly | StatusUTXOPendingVerification => None,
+        }
     }
 
     fn queue_block(self: &Arc<HeaderProcessor>, task_id: TaskId) {
         if let Some(task) = self.task_manager.try_begin(task_id) {
-            let res = self.process_header(&task);
-
-            let dependent_tasks = self.task_manager.end(
-                task,
-                |task,
-                 block_result_transmitter: tokio::sync::oneshot::Sender<Result<BlockStatus, RuleError>>,
-                 virtual_state_result_transmitter| {
+            // A panic fails the block rather than exiting the process, while the task is still ended below so that
+            // its callers and dependent tasks do not hang. Headers are committed atomically, so no partial state is left
+            let res = catch_recoverable_panic(|| self.process_header(&task)).unwrap_or_else(|payload| {
+                let reason = panic_message(&*payload);
+                error!("Header processing of block {} panicked: {}", task.block().hash(), reason);
+                Err(RuleError::ProcessingPanicked(reason))
+            });
+
+            let dependent_tasks =
+                self.task_manager.end(task, |task, block_result_transmitter: BlockResultSender, virtual_state_result_transmitter| {
                     if res.is_err() || task.block().is_header_only() {
-                        // We don't care if receivers were dropped
-                        let _ = block_result_transmitter.send(res.clone());
-                        let _ = virtual_state_result_transmitter.send(res.clone());
+                        block_result_transmitter.send(res.clone());
+                        virtual_state_result_transmitter.send(res.clone());
                     } else {
                         self.body_sender
                             .send(BlockProcessingMessage::Process(task, block_result_transmitter, virtual_state_result_transmitter))
                             .unwrap();
                     }
-                },
-            );
+                });
 
             for dep in dependent_tasks {
                 let processor = self.clone();
@@ -276,8 +398,12 @@ impl HeaderProcessor {
         // Validate the header depending on task type
         match task {
             BlockTask::Ordinary { .. } => {
-                let ctx = self.validate_header(header)?;
+                let start = Instant::now();
+                let ctx = self.validate_header(task.block())?;
+                let shape = DependencyShape::new(&ctx);
                 self.commit_header(ctx, header);
+                self.network_stats.record(header.timestamp, shape.parents, shape.mergeset_blues, shape.mergeset_reds);
+                self.check_processing_budget(header.hash, task.origin(), start.elapsed(), shape);
             }
             BlockTask::Trusted { .. } => {
                 let ctx = self.validate_trusted_header(header)?;
@@ -292,14 +418,37 @@ impl HeaderProcessor {
         Ok(StatusHeaderOnly)
     }
 
+    /// Logs the dependency structure of a block whose header processing exceeded the budget, in order to
+    /// correlate latency spikes with DAG shapes
+    fn check_processing_budget(&self, hash: Hash, origin: BlockOrigin, elapsed: Duration, shape: DependencyShape) {
+        let Some(budget) = exceeded_budget(self.processing_budget, elapsed) else {
+            return;
+        };
+        warn!(
+            "Header processing of block {} (via {}) took {} ms (budget: {} ms): {} parents, mergeset of {} blues and {} reds ({} non-DAA), difficulty window of {} blocks, past median time window of {} blocks",
+            hash,
+            origin,
+            elapsed.as_millis(),
+            budget.as_millis(),
+            shape.parents,
+            shape.mergeset_blues,
+            shape.mergeset_reds,
+            shape.mergeset_non_daa,
+            shape.difficulty_window,
+            shape.past_median_time_window
+        );
+    }
+
     /// Runs full ordinary header validation
-    fn validate_header(&self, header: &Arc<Header>) -> BlockProcessResult<HeaderProcessingContext> {
+    fn validate_header(&self, block: &Block) -> BlockProcessResult<HeaderProcessingContext> {
+        let header = &block.header;
         let block_level = self.validate_header_in_isolation(header)?;
         self.validate_parent_relations(header)?;
         let mut ctx = self.build_processing_context(header, block_level);
         self.ghostdag(&mut ctx);
         self.pre_pow_validation(&mut ctx, header)?;
         if let Err(e) = self.post_pow_validation(&mut ctx, header) {
+            self.invalid_blocks_store.insert(ctx.hash, Arc::new(InvalidBlockRecord::new(e.code(), block))).unwrap();
             self.statuses_store.write().set(ctx.hash, StatusInvalid).unwrap();
             return Err(e);
         }
@@ -307,7 +456,12 @@ impl HeaderProcessor {
     }
 
     // Runs partial header validation for trusted blocks (currently validates only header-in-isolation and computes GHOSTDAG).
+    // Parent availability is not required since trusted blocks are only accepted as part of the trusted set already applied
+    // along with the pruning point proof, whose GHOSTDAG data was validated against the proof (see `apply_proof`)
     fn validate_trusted_header(&self, header: &Arc<Header>) -> BlockProcessResult<HeaderProcessingContext> {
+        if !self.pruning_proof_manager.is_in_applied_trusted_set(header.hash) {
+            return Err(RuleError::TrustedBlockNotInAppliedSet(header.hash));
+        }
         // TODO: For now we skip most validations for trusted blocks, but in the future we should
         // employ some validations to avoid spam etc.
         let block_level = self.validate_header_in_isolation(header)?;
@@ -327,21 +481,23 @@ impl HeaderProcessor {
     }
 
     /// Collects the known parents for all block levels
-    fn collect_known_parents(&self, header: &Header, block_level: BlockLevel) -> Vec<Arc<Vec<Hash>>> {
+    fn collect_known_parents(&self, header: &Header, block_level: BlockLevel) -> Vec<BlockHashes> {
         let relations_read = self.relations_stores.read();
         (0..=block_level)
             .map(|level| {
-                Arc::new(
-                    self.parents_manager
-                        .parents_at_level(header, level)
-                        .iter()
-                        .copied()
-                        .filter(|parent| relations_read[level as usize].has(*parent).unwrap())
-                        .collect_vec()
-                        // This kicks-in only for trusted blocks or for level > 0. If an ordinary block is 
-                        // missing direct parents it will fail validation.
-                        .push_if_empty(ORIGIN),
-                )
+                let mut parents: BlockHashVec = self
+                    .parents_manager
+                    .parents_at_level(header, level)
+                    .iter()
+                    .copied()
+                    .filter(|parent| relations_read[level as usize].has(*parent).unwrap())
+                    .collect();
+                // This kicks-in only for trusted blocks or for level > 0. If an ordinary block is
+                // missing direct parents it will fail validation.
+                if parents.is_empty() {
+                    parents.push(ORIGIN);
+                }
+                BlockHashes::new(parents)
             })
             .collect_vec()
     }
@@ -432,6 +588,7 @@ impl HeaderProcessor {
         let reachability_write = staging.commit(&mut batch).unwrap();
 
         // Flush the batch to the DB
+        fail_point!("header-processor-commit");
         self.db.write(batch).unwrap();
 
         // Calling the drops explicitly after the batch is written in order to avoid possible errors.
@@ -482,7 +639,7 @@ impl HeaderProcessor {
             genesis_header.clone(),
             self.max_block_level,
             PruningPointInfo::from_genesis(self.genesis.hash),
-            (0..=self.max_block_level).map(|_| BlockHashes::new(vec![ORIGIN])).collect(),
+            (0..=self.max_block_level).map(|_| BlockHashes::new(smallvec![ORIGIN])).collect(),
         );
         ctx.ghostdag_data =
             Some(self.ghostdag_managers.iter().map(|manager_by_level| Arc::new(manager_by_level.genesis_ghostdag_data())).collect());
@@ -501,7 +658,7 @@ impl HeaderProcessor {
         let mut batch = WriteBatch::default();
         let mut relations_write = self.relations_stores.write();
         (0..=self.max_block_level)
-            .for_each(|level| relations_write[level as usize].insert_batch(&mut batch, ORIGIN, BlockHashes::new(vec![])).unwrap());
+            .for_each(|level| relations_write[level as usize].insert_batch(&mut batch, ORIGIN, Default::default()).unwrap());
         let mut hst_write = self.headers_selected_tip_store.write();
         hst_write.set_batch(&mut batch, SortableBlock::new(ORIGIN, 0.into())).unwrap();
         self.db.write(batch).unwrap();
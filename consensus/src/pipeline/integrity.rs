@@ -0,0 +1,137 @@
+use itertools::Itertools;
+use kaspa_consensusmanager::ConsensusManager;
+use kaspa_core::{
+    debug, info,
+    task::{
+        service::{AsyncService, AsyncServiceFuture},
+        tick::{TickReason, TickService},
+    },
+    trace, warn,
+};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+const INTEGRITY_MONITOR: &str = "integrity-monitor";
+
+/// The interval between consecutive verification rounds
+const VERIFICATION_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// The number of recent selected chain blocks (along with their mergesets) verified in each round
+const RECENT_CHAIN_BLOCKS: usize = 2000;
+
+/// The number of chain blocks verified within a single consensus session. Keeping batches small
+/// makes sure the verification does not hold the pruning lock for long
+const CHAIN_BLOCKS_PER_SESSION: usize = 50;
+
+#[derive(Default)]
+pub struct IntegrityCounters {
+    pub rounds: AtomicU64,
+    pub checked_blocks: AtomicU64,
+    pub violations: AtomicU64,
+}
+
+/// A low-priority background task periodically verifying the consistency of recently processed consensus
+/// data, so that DB corruption is reported before it results in a consensus failure
+pub struct IntegrityMonitor {
+    consensus_manager: Arc<ConsensusManager>,
+    counters: Arc<IntegrityCounters>,
+    tick_service: Arc<TickService>,
+}
+
+impl IntegrityMonitor {
+    pub fn new(consensus_manager: Arc<ConsensusManager>, counters: Arc<IntegrityCounters>, tick_service: Arc<TickService>) -> Self {
+        Self { consensus_manager, counters, tick_service }
+    }
+
+    pub async fn worker(self: &Arc<IntegrityMonitor>) {
+        loop {
+            if let TickReason::Shutdown = self.tick_service.tick(VERIFICATION_INTERVAL).await {
+                break;
+            }
+            self.verify_recent_blocks().await;
+        }
+
+        trace!("{} thread exiting", INTEGRITY_MONITOR);
+    }
+
+    async fn verify_recent_blocks(&self) {
+        let consensus = self.consensus_manager.consensus();
+        let mut high = consensus.unguarded_session().async_get_sink().await;
+        let (mut checked_blocks, mut violations) = (0, 0);
+        let mut remaining = RECENT_CHAIN_BLOCKS;
+        while remaining > 0 {
+            let batch = remaining.min(CHAIN_BLOCKS_PER_SESSION);
+            let session = consensus.session().await;
+            let report = match session.async_verify_chain_integrity(high, batch).await {
+                Ok(report) => report,
+                Err(err) => {
+                    // The chain might have been pruned or reorged in between sessions, the next round will start over
+                    debug!("Integrity verification stopped at block {}: {}", high, err);
+                    break;
+                }
+            };
+
+            for violation in report.violations.iter() {
+                warn!("Integrity violation at block {}: {}", violation.hash, violation.kind);
+            }
+            // Data received from peers is quarantined for re-download rather than requiring a full resync
+            let redownloadable = report
+                .violations
+                .iter()
+                .filter(|violation| violation.kind.is_redownloadable())
+                .map(|violation| violation.hash)
+                .collect_vec();
+            if !redownloadable.is_empty() {
+                session.async_quarantine_blocks(redownloadable).await;
+            }
+            drop(session);
+
+            checked_blocks += report.checked_blocks;
+            violations += report.violations.len() as u64;
+            match report.next {
+                Some(next) => high = next,
+                None => break,
+            }
+            remaining -= batch;
+            tokio::task::yield_now().await;
+        }
+
+        self.counters.rounds.fetch_add(1, Ordering::Relaxed);
+        self.counters.checked_blocks.fetch_add(checked_blocks, Ordering::Relaxed);
+        self.counters.violations.fetch_add(violations, Ordering::Relaxed);
+        if violations > 0 {
+            warn!("Integrity verification of {} recent blocks found {} discrepancies", checked_blocks, violations);
+        } else {
+            info!("Integrity verification of {} recent blocks found no discrepancies", checked_blocks);
+        }
+    }
+}
+
+impl AsyncService for IntegrityMonitor {
+    fn ident(self: Arc<Self>) -> &'static str {
+        INTEGRITY_MONITOR
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            self.worker().await;
+            Ok(())
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", INTEGRITY_MONITOR);
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            trace!("{} stopped", INTEGRITY_MONITOR);
+            Ok(())
+        })
+    }
+}
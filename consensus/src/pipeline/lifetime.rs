@@ -0,0 +1,195 @@
+use super::{ProcessingCounters, ProcessingCountersSnapshot};
+use kaspa_core::{
+    task::{
+        service::{AsyncService, AsyncServiceFuture},
+        tick::{TickReason, TickService},
+    },
+    time::unix_now,
+    trace, warn,
+};
+use kaspa_database::prelude::{CachedDbItem, DirectDbWriter, StoreResult, StoreResultExtensions, DB};
+use kaspa_database::registry::DatabaseStorePrefixes;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+const LIFETIME_METRICS_MONITOR: &str = "lifetime-metrics-monitor";
+
+/// The interval between consecutive writes of the lifetime metrics, bounding the amount of counts lost on a crash
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The number of most recent runs of the node kept in the lifetime metrics
+pub const MAX_RECENT_RUNS: usize = 16;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Unix time in milliseconds
+    pub start_time: u64,
+    pub uptime_seconds: u64,
+}
+
+/// Cumulative counters of the node surviving restarts
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LifetimeMetrics {
+    /// Unix time in milliseconds the metrics are accumulated since, i.e. of the first start or of the last reset
+    pub since: u64,
+    pub blocks_processed: u64,
+    pub txs_processed: u64,
+    pub uptime_seconds: u64,
+    pub starts: u64,
+    /// The most recent runs of the node by ascending start time, the last being the current run
+    pub recent_runs: Vec<RunRecord>,
+}
+
+impl LifetimeMetrics {
+    fn new_run(mut self, start_time: u64) -> Self {
+        self.starts += 1;
+        self.recent_runs.push(RunRecord { start_time, uptime_seconds: 0 });
+        if self.recent_runs.len() > MAX_RECENT_RUNS {
+            self.recent_runs.drain(..self.recent_runs.len() - MAX_RECENT_RUNS);
+        }
+        self
+    }
+}
+
+/// The persisted metrics along with the process counters and time they were last (re)based on
+struct Baseline {
+    metrics: LifetimeMetrics,
+    counters: ProcessingCountersSnapshot,
+    instant: Instant,
+}
+
+/// Accumulates the processing counters and the uptime of the node into [`LifetimeMetrics`] persisted in the meta DB,
+/// which, unlike the consensus DB, outlives consensus resets
+pub struct LifetimeMetricsMonitor {
+    db: Arc<DB>,
+    access: Mutex<CachedDbItem<LifetimeMetrics>>,
+    baseline: Mutex<Baseline>,
+    counters: Arc<ProcessingCounters>,
+    tick_service: Arc<TickService>,
+}
+
+impl LifetimeMetricsMonitor {
+    /// Loads the metrics persisted in `meta_db` and records a new run of the node
+    pub fn new(meta_db: Arc<DB>, counters: Arc<ProcessingCounters>, tick_service: Arc<TickService>) -> StoreResult<Self> {
+        let mut access = CachedDbItem::new(meta_db.clone(), DatabaseStorePrefixes::LifetimeMetrics.into());
+        let now = unix_now();
+        let metrics = access.read().unwrap_option().unwrap_or(LifetimeMetrics { since: now, ..Default::default() }).new_run(now);
+        access.write(DirectDbWriter::new(&meta_db), &metrics)?;
+        let baseline = Baseline { metrics, counters: counters.snapshot(), instant: Instant::now() };
+        Ok(Self { db: meta_db, access: Mutex::new(access), baseline: Mutex::new(baseline), counters, tick_service })
+    }
+
+    /// Returns the lifetime metrics up to now
+    pub fn metrics(&self) -> LifetimeMetrics {
+        let baseline = self.baseline.lock();
+        let counts = &self.counters.snapshot() - &baseline.counters;
+        let uptime_seconds = baseline.instant.elapsed().as_secs();
+        let mut metrics = baseline.metrics.clone();
+        metrics.blocks_processed += counts.header_counts;
+        metrics.txs_processed += counts.txs_counts;
+        metrics.uptime_seconds += uptime_seconds;
+        if let Some(run) = metrics.recent_runs.last_mut() {
+            run.uptime_seconds += uptime_seconds;
+        }
+        metrics
+    }
+
+    /// Persists the lifetime metrics up to now
+    pub fn flush(&self) -> StoreResult<()> {
+        let metrics = self.metrics();
+        self.access.lock().write(DirectDbWriter::new(&self.db), &metrics)
+    }
+
+    /// Discards the accumulated metrics and the run history, accumulating anew from now on as if the node just started
+    pub fn reset(&self) -> StoreResult<()> {
+        let mut baseline = self.baseline.lock();
+        let now = unix_now();
+        let metrics = LifetimeMetrics { since: now, ..Default::default() }.new_run(now);
+        self.access.lock().write(DirectDbWriter::new(&self.db), &metrics)?;
+        *baseline = Baseline { metrics, counters: self.counters.snapshot(), instant: Instant::now() };
+        Ok(())
+    }
+
+    pub async fn worker(self: &Arc<LifetimeMetricsMonitor>) {
+        loop {
+            let reason = self.tick_service.tick(FLUSH_INTERVAL).await;
+            if let Err(err) = self.flush() {
+                warn!("Failed persisting the lifetime metrics: {}", err);
+            }
+            if let TickReason::Shutdown = reason {
+                break;
+            }
+        }
+
+        trace!("{} thread exiting", LIFETIME_METRICS_MONITOR);
+    }
+}
+
+impl AsyncService for LifetimeMetricsMonitor {
+    fn ident(self: Arc<Self>) -> &'static str {
+        LIFETIME_METRICS_MONITOR
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            self.worker().await;
+            Ok(())
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", LIFETIME_METRICS_MONITOR);
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            trace!("{} stopped", LIFETIME_METRICS_MONITOR);
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_database::{create_temp_db, prelude::ConnBuilder};
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn test_lifetime_metrics() {
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let tick_service = Arc::new(TickService::default());
+
+        let counters = Arc::new(ProcessingCounters::default());
+        let monitor = LifetimeMetricsMonitor::new(db.clone(), counters.clone(), tick_service.clone()).unwrap();
+        counters.header_counts.fetch_add(10, Ordering::Relaxed);
+        counters.txs_counts.fetch_add(25, Ordering::Relaxed);
+        monitor.flush().unwrap();
+        let since = monitor.metrics().since;
+
+        // A restart resets the process counters while the lifetime metrics carry on
+        let counters = Arc::new(ProcessingCounters::default());
+        let monitor = LifetimeMetricsMonitor::new(db.clone(), counters.clone(), tick_service.clone()).unwrap();
+        counters.header_counts.fetch_add(5, Ordering::Relaxed);
+        let metrics = monitor.metrics();
+        assert_eq!((metrics.blocks_processed, metrics.txs_processed), (15, 25));
+        assert_eq!((metrics.since, metrics.starts, metrics.recent_runs.len()), (since, 2, 2));
+
+        monitor.reset().unwrap();
+        counters.header_counts.fetch_add(1, Ordering::Relaxed);
+        let metrics = monitor.metrics();
+        assert_eq!((metrics.blocks_processed, metrics.txs_processed, metrics.starts, metrics.recent_runs.len()), (1, 0, 1, 1));
+
+        // Only the most recent runs are kept
+        for _ in 0..MAX_RECENT_RUNS + 5 {
+            LifetimeMetricsMonitor::new(db.clone(), Default::default(), tick_service.clone()).unwrap();
+        }
+        let metrics = LifetimeMetricsMonitor::new(db, Default::default(), tick_service).unwrap().metrics();
+        assert_eq!(metrics.starts, MAX_RECENT_RUNS as u64 + 7);
+        assert_eq!(metrics.recent_runs.len(), MAX_RECENT_RUNS);
+    }
+}
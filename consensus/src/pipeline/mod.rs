@@ -1,33 +1,77 @@
 pub mod body_processor;
+pub mod budget;
 pub mod deps_manager;
 pub mod header_processor;
+pub mod integrity;
+pub mod lifetime;
 pub mod monitor;
+pub mod network_stats;
 pub mod pruning_processor;
+pub mod state_digest;
+pub mod tracer;
 pub mod virtual_processor;
 
-use std::sync::atomic::{AtomicU64, Ordering};
+use kaspa_consensus_core::block::BlockOrigin;
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// Returns the processing budget if the processing of a block by some pipeline stage took longer, in which case
+/// the stage logs the dependency structure of the block in order to correlate latency spikes with DAG shapes
+pub(crate) fn exceeded_budget(budget: Option<Duration>, elapsed: Duration) -> Option<Duration> {
+    budget.filter(|&budget| elapsed > budget)
+}
 
 #[derive(Default)]
 pub struct ProcessingCounters {
     pub blocks_submitted: AtomicU64,
+    pub rpc_blocks_submitted: AtomicU64,
+    pub relay_blocks_submitted: AtomicU64,
+    pub ibd_blocks_submitted: AtomicU64,
     pub header_counts: AtomicU64,
+    /// Submitted blocks resolved upfront since they were already processed
+    pub known_block_counts: AtomicU64,
     pub dep_counts: AtomicU64,
     pub body_counts: AtomicU64,
     pub txs_counts: AtomicU64,
     pub chain_block_counts: AtomicU64,
     pub mass_counts: AtomicU64,
+    /// The current number of body tips, a gauge rather than a cumulative counter
+    pub tips_count: AtomicU64,
 }
 
 impl ProcessingCounters {
+    /// Counts a submitted block by its origin. Locally inserted blocks are only counted by `blocks_submitted`
+    pub fn count_origin(&self, origin: BlockOrigin) {
+        match origin {
+            BlockOrigin::Local => {}
+            BlockOrigin::Rpc => {
+                self.rpc_blocks_submitted.fetch_add(1, Ordering::Relaxed);
+            }
+            BlockOrigin::Relay => {
+                self.relay_blocks_submitted.fetch_add(1, Ordering::Relaxed);
+            }
+            BlockOrigin::Ibd => {
+                self.ibd_blocks_submitted.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
     pub fn snapshot(&self) -> ProcessingCountersSnapshot {
         ProcessingCountersSnapshot {
             blocks_submitted: self.blocks_submitted.load(Ordering::Relaxed),
+            rpc_blocks_submitted: self.rpc_blocks_submitted.load(Ordering::Relaxed),
+            relay_blocks_submitted: self.relay_blocks_submitted.load(Ordering::Relaxed),
+            ibd_blocks_submitted: self.ibd_blocks_submitted.load(Ordering::Relaxed),
             header_counts: self.header_counts.load(Ordering::Relaxed),
+            known_block_counts: self.known_block_counts.load(Ordering::Relaxed),
             dep_counts: self.dep_counts.load(Ordering::Relaxed),
             body_counts: self.body_counts.load(Ordering::Relaxed),
             txs_counts: self.txs_counts.load(Ordering::Relaxed),
             chain_block_counts: self.chain_block_counts.load(Ordering::Relaxed),
             mass_counts: self.mass_counts.load(Ordering::Relaxed),
+            tips_count: self.tips_count.load(Ordering::Relaxed),
         }
     }
 }
@@ -35,12 +79,17 @@ impl ProcessingCounters {
 #[derive(Debug, PartialEq, Eq)]
 pub struct ProcessingCountersSnapshot {
     pub blocks_submitted: u64,
+    pub rpc_blocks_submitted: u64,
+    pub relay_blocks_submitted: u64,
+    pub ibd_blocks_submitted: u64,
     pub header_counts: u64,
+    pub known_block_counts: u64,
     pub dep_counts: u64,
     pub body_counts: u64,
     pub txs_counts: u64,
     pub chain_block_counts: u64,
     pub mass_counts: u64,
+    pub tips_count: u64,
 }
 
 impl core::ops::Sub for &ProcessingCountersSnapshot {
@@ -49,12 +98,18 @@ impl core::ops::Sub for &ProcessingCountersSnapshot {
     fn sub(self, rhs: Self) -> Self::Output {
         Self::Output {
             blocks_submitted: self.blocks_submitted.checked_sub(rhs.blocks_submitted).unwrap_or_default(),
+            rpc_blocks_submitted: self.rpc_blocks_submitted.checked_sub(rhs.rpc_blocks_submitted).unwrap_or_default(),
+            relay_blocks_submitted: self.relay_blocks_submitted.checked_sub(rhs.relay_blocks_submitted).unwrap_or_default(),
+            ibd_blocks_submitted: self.ibd_blocks_submitted.checked_sub(rhs.ibd_blocks_submitted).unwrap_or_default(),
             header_counts: self.header_counts.checked_sub(rhs.header_counts).unwrap_or_default(),
+            known_block_counts: self.known_block_counts.checked_sub(rhs.known_block_counts).unwrap_or_default(),
             dep_counts: self.dep_counts.checked_sub(rhs.dep_counts).unwrap_or_default(),
             body_counts: self.body_counts.checked_sub(rhs.body_counts).unwrap_or_default(),
             txs_counts: self.txs_counts.checked_sub(rhs.txs_counts).unwrap_or_default(),
             chain_block_counts: self.chain_block_counts.checked_sub(rhs.chain_block_counts).unwrap_or_default(),
             mass_counts: self.mass_counts.checked_sub(rhs.mass_counts).unwrap_or_default(),
+            // A gauge, hence the current value is kept
+            tips_count: self.tips_count,
         }
     }
 }
@@ -0,0 +1,107 @@
+use kaspa_consensus_core::network_stats::{InterArrivalStats, NetworkStats};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+
+/// The number of most recently processed headers the network statistics are computed over
+pub const NETWORK_STATS_WINDOW_SIZE: usize = 2000;
+
+#[derive(Clone, Copy)]
+struct BlockSample {
+    timestamp: u64,
+    parents: u64,
+    mergeset_blues: u64,
+    mergeset_reds: u64,
+}
+
+/// Keeps a rolling window of per-block samples recorded by the header processor, from which [`NetworkStats`] are derived on demand
+pub struct NetworkStatsStore {
+    window_size: usize,
+    samples: Mutex<VecDeque<BlockSample>>,
+}
+
+impl NetworkStatsStore {
+    pub fn new(window_size: usize) -> Self {
+        Self { window_size, samples: Mutex::new(VecDeque::with_capacity(window_size)) }
+    }
+
+    pub fn record(&self, timestamp: u64, parents: usize, mergeset_blues: usize, mergeset_reds: usize) {
+        let sample = BlockSample {
+            timestamp,
+            parents: parents as u64,
+            mergeset_blues: mergeset_blues as u64,
+            mergeset_reds: mergeset_reds as u64,
+        };
+        let mut samples = self.samples.lock();
+        if samples.len() == self.window_size {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+
+    pub fn stats(&self) -> NetworkStats {
+        let samples = self.samples.lock().iter().copied().collect::<Vec<_>>();
+        if samples.is_empty() {
+            return NetworkStats::default();
+        }
+
+        // Headers are processed in topological rather than in timestamp order, so intervals are measured between sorted timestamps
+        let mut timestamps = samples.iter().map(|sample| sample.timestamp).collect::<Vec<_>>();
+        timestamps.sort_unstable();
+        let mut intervals = timestamps.windows(2).map(|pair| pair[1] - pair[0]).collect::<Vec<_>>();
+        intervals.sort_unstable();
+
+        NetworkStats {
+            window_size: samples.len() as u64,
+            inter_arrival: inter_arrival_stats(&intervals),
+            average_parent_count: samples.iter().map(|sample| sample.parents).sum::<u64>() as f64 / samples.len() as f64,
+            mergeset_blues: samples.iter().map(|sample| sample.mergeset_blues).sum(),
+            mergeset_reds: samples.iter().map(|sample| sample.mergeset_reds).sum(),
+        }
+    }
+}
+
+impl Default for NetworkStatsStore {
+    fn default() -> Self {
+        Self::new(NETWORK_STATS_WINDOW_SIZE)
+    }
+}
+
+/// Computes the distribution of sorted `intervals`, percentiles being nearest-rank
+fn inter_arrival_stats(intervals: &[u64]) -> InterArrivalStats {
+    if intervals.is_empty() {
+        return InterArrivalStats::default();
+    }
+    let percentile = |p: usize| intervals[(intervals.len() * p).div_ceil(100).max(1) - 1];
+    InterArrivalStats {
+        mean: intervals.iter().sum::<u64>() / intervals.len() as u64,
+        p50: percentile(50),
+        p90: percentile(90),
+        p99: percentile(99),
+        max: *intervals.last().unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_stats_store() {
+        let store = NetworkStatsStore::new(4);
+        assert_eq!(store.stats(), NetworkStats::default());
+
+        // The first sample falls out of the window
+        store.record(0, 5, 5, 5);
+        store.record(3000, 1, 1, 0);
+        store.record(1000, 2, 2, 1);
+        store.record(2000, 3, 3, 0);
+        store.record(7000, 2, 2, 1);
+
+        let stats = store.stats();
+        assert_eq!(stats.window_size, 4);
+        assert_eq!(stats.inter_arrival, InterArrivalStats { mean: 2000, p50: 1000, p90: 4000, p99: 4000, max: 4000 });
+        assert_eq!(stats.average_parent_count, 2.0);
+        assert_eq!((stats.mergeset_blues, stats.mergeset_reds), (8, 2));
+        assert_eq!(stats.blue_ratio(), 0.8);
+    }
+}
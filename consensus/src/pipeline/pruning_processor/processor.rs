@@ -8,6 +8,7 @@ use crate::{
     model::{
         services::reachability::{MTReachabilityService, ReachabilityService},
         stores::{
+            block_transactions::BlockTransactionsStoreReader,
             ghostdag::{CompactGhostdagData, GhostdagStoreReader},
             headers::HeaderStoreReader,
             past_pruning_points::PastPruningPointsStoreReader,
@@ -21,7 +22,7 @@ use crate::{
             virtual_state::VirtualStateStoreReader,
         },
     },
-    processes::{pruning_proof::PruningProofManager, reachability::inquirer as reachability, relations},
+    processes::{era_archive::EraWriter, pruning_proof::PruningProofManager, reachability::inquirer as reachability, relations},
 };
 use crossbeam_channel::Receiver as CrossbeamReceiver;
 use itertools::Itertools;
@@ -37,18 +38,45 @@ use kaspa_consensus_core::{
 use kaspa_consensusmanager::SessionLock;
 use kaspa_core::{debug, info, warn};
 use kaspa_database::prelude::{BatchDbWriter, MemoryWriter, StoreResultExtensions, DB};
+use kaspa_database::registry::DatabaseStorePrefixes;
 use kaspa_hashes::Hash;
 use kaspa_muhash::MuHash;
-use kaspa_utils::iter::IterExtensions;
-use parking_lot::RwLockUpgradableReadGuard;
+use kaspa_utils::{fail_point, iter::IterExtensions};
+use parking_lot::{Mutex, RwLockUpgradableReadGuard};
 use rocksdb::WriteBatch;
 use std::{
     collections::VecDeque,
     ops::Deref,
     sync::Arc,
+    thread::{self, JoinHandle},
     time::{Duration, Instant},
 };
 
+/// The stores whose entries are deleted when pruning the past of the pruning point
+const PRUNED_STORES: [DatabaseStorePrefixes; 18] = [
+    DatabaseStorePrefixes::UtxoMultisets,
+    DatabaseStorePrefixes::UtxoDiffs,
+    DatabaseStorePrefixes::AcceptanceData,
+    DatabaseStorePrefixes::BlockTransactions,
+    DatabaseStorePrefixes::Headers,
+    DatabaseStorePrefixes::HeadersCompact,
+    DatabaseStorePrefixes::Ghostdag,
+    DatabaseStorePrefixes::GhostdagCompact,
+    DatabaseStorePrefixes::RelationsParents,
+    DatabaseStorePrefixes::RelationsChildren,
+    DatabaseStorePrefixes::Reachability,
+    DatabaseStorePrefixes::ReachabilityRelations,
+    DatabaseStorePrefixes::NonDaaMergeset,
+    DatabaseStorePrefixes::BlockDepth,
+    DatabaseStorePrefixes::Statuses,
+    DatabaseStorePrefixes::ChainHashByIndex,
+    DatabaseStorePrefixes::ChainIndexByHash,
+    DatabaseStorePrefixes::InvalidBlocks,
+];
+
+/// The minimal interval between two compactions of the pruned stores
+const PRUNED_STORES_COMPACTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 pub enum PruningProcessingMessage {
     Exit,
     Process { sink_ghostdag_data: CompactGhostdagData },
@@ -74,6 +102,9 @@ pub struct PruningProcessor {
     // Pruning lock
     pruning_lock: SessionLock,
 
+    // The start time and thread of the last background compaction of the pruned stores
+    compaction: Mutex<Option<(Instant, JoinHandle<()>)>>,
+
     // Config
     config: Arc<Config>,
 }
@@ -104,6 +135,7 @@ impl PruningProcessor {
             pruning_point_manager: services.pruning_point_manager.clone(),
             pruning_proof_manager: services.pruning_proof_manager.clone(),
             pruning_lock,
+            compaction: Mutex::new(None),
             config,
         }
     }
@@ -121,6 +153,11 @@ impl PruningProcessor {
         while let Ok(PruningProcessingMessage::Process { sink_ghostdag_data }) = self.receiver.recv() {
             self.advance_pruning_point_and_candidate_if_possible(sink_ghostdag_data);
         }
+
+        // Wait for a running compaction, so that the DB is not in use once the processor exits
+        if let Some((_, handle)) = self.compaction.lock().take() {
+            handle.join().unwrap();
+        }
     }
 
     fn recover_pruning_workflows_if_needed(&self) {
@@ -191,6 +228,34 @@ impl PruningProcessor {
         }
     }
 
+    /// Deletes do not shrink the DB on their own, so the ranges of the pruned stores are compacted. The compaction runs
+    /// on a background thread in order not to delay pruning, and is skipped if the previous one is still running or
+    /// started less than [`PRUNED_STORES_COMPACTION_INTERVAL`] ago, in which case the following one covers these deletes
+    fn compact_pruned_stores(&self) {
+        let mut compaction = self.compaction.lock();
+        if let Some((started, handle)) = compaction.as_ref() {
+            if !handle.is_finished() || started.elapsed() < PRUNED_STORES_COMPACTION_INTERVAL {
+                return;
+            }
+        }
+
+        let db = self.db.clone();
+        let spawned = thread::Builder::new().name("pruning-compaction".to_string()).spawn(move || {
+            let report = db.compact_stores(&PRUNED_STORES);
+            info!(
+                "Header and Block pruning: compacted pruned stores in {:.1}s, reclaimed {:.2} MB (SST files size {:.2} MB -> {:.2} MB)",
+                report.elapsed.as_secs_f64(),
+                report.reclaimed_bytes() as f64 / 1_000_000.0,
+                report.size_before as f64 / 1_000_000.0,
+                report.size_after as f64 / 1_000_000.0
+            );
+        });
+        match spawned {
+            Ok(handle) => *compaction = Some((Instant::now(), handle)),
+            Err(err) => warn!("Header and Block pruning: failed spawning the compaction of the pruned stores: {}", err),
+        }
+    }
+
     fn advance_pruning_utxoset(&self, utxoset_position: Hash, new_pruning_point: Hash) {
         let mut pruning_utxoset_write = self.pruning_utxoset_stores.write();
         for chain_block in self.reachability_service.forward_chain_iterator(utxoset_position, new_pruning_point, true).skip(1) {
@@ -198,6 +263,7 @@ impl PruningProcessor {
             let mut batch = WriteBatch::default();
             pruning_utxoset_write.utxo_set.write_diff_batch(&mut batch, utxo_diff.as_ref()).unwrap();
             pruning_utxoset_write.set_utxoset_position(&mut batch, chain_block).unwrap();
+            fail_point!("pruning-processor-advance-utxoset");
             self.db.write(batch).unwrap();
         }
         drop(pruning_utxoset_write);
@@ -249,6 +315,27 @@ impl PruningProcessor {
             .collect();
         let keep_headers: BlockHashSet = self.past_pruning_points();
 
+        // If configured, open the era to which pruned block bodies are exported prior to their deletion. The era is
+        // identified by the pruning point index, so a prune resumed after a restart appends to the same era files
+        let mut era_writer = match self.config.pruned_archive_dir.as_ref() {
+            Some(dir) => {
+                let era = self.pruning_point_store.read().get().unwrap().index;
+                match EraWriter::open(dir, era, new_pruning_point) {
+                    Ok(writer) => Some(writer),
+                    Err(err) => {
+                        warn!(
+                            "Header and Block pruning: failed opening era {} in {}: {} -- postponing data pruning",
+                            era,
+                            dir.display(),
+                            err
+                        );
+                        return;
+                    }
+                }
+            }
+            None => None,
+        };
+
         info!("Header and Block pruning: waiting for consensus write permissions...");
 
         let mut prune_guard = self.pruning_lock.blocking_write();
@@ -347,6 +434,17 @@ impl PruningProcessor {
             self.block_window_cache_for_past_median_time.remove(&current);
 
             if !keep_blocks.contains(&current) {
+                if let Some(writer) = era_writer.as_mut() {
+                    if let Some(transactions) = self.block_transactions_store.get(current).unwrap_option() {
+                        let header = self.headers_store.get_header(current).unwrap();
+                        if let Err(err) = writer.append(&header, &transactions) {
+                            // Leave the history root untouched so that pruning resumes (and retries the export) on restart
+                            warn!("Header and Block pruning: failed archiving block {}: {} -- postponing data pruning", current, err);
+                            return;
+                        }
+                    }
+                }
+
                 let mut batch = WriteBatch::default();
                 let mut level_relations_write = self.relations_stores.write();
                 let mut reachability_relations_write = self.reachability_relations_store.write();
@@ -404,6 +502,7 @@ impl PruningProcessor {
                 staging_relations.commit(&mut batch).unwrap();
 
                 // Flush the batch to the DB
+                fail_point!("pruning-processor-prune");
                 self.db.write(batch).unwrap();
 
                 // Calling the drops explicitly after the batch is written in order to avoid possible errors.
@@ -419,7 +518,18 @@ impl PruningProcessor {
         drop(reachability_read);
         drop(prune_guard);
 
-        info!("Header and Block pruning completed: traversed: {}, pruned {}", traversed, counter);
+        if let Some(Err(err)) = era_writer.map(|writer| writer.finish()) {
+            warn!("Header and Block pruning: failed syncing the era files of pruning point {}: {}", new_pruning_point, err);
+        }
+
+        // Invalid blocks below the new pruning point can no longer be revalidated, hence their records are dropped
+        let pruning_point_daa_score = self.headers_store.get_daa_score(new_pruning_point).unwrap();
+        let pruned_invalid_blocks = self.invalid_blocks_store.prune_below(pruning_point_daa_score).unwrap();
+
+        info!(
+            "Header and Block pruning completed: traversed: {}, pruned {}, invalid block records pruned {}",
+            traversed, counter, pruned_invalid_blocks
+        );
         info!(
             "Header and Block pruning stats: proof size: {}, pruning point and anticone: {}, unique headers in proof and windows: {}, pruning points in history: {}",
             proof.iter().map(|l| l.len()).sum::<usize>(),
@@ -428,6 +538,10 @@ impl PruningProcessor {
             keep_headers.len()
         );
 
+        if traversed > 0 {
+            self.compact_pruned_stores();
+        }
+
         if self.config.enable_sanity_checks {
             self.assert_proof_rebuilding(proof, new_pruning_point);
             self.assert_data_rebuilding(data, new_pruning_point);
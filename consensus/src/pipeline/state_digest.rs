@@ -0,0 +1,73 @@
+use kaspa_consensusmanager::ConsensusManager;
+use kaspa_core::{
+    info,
+    task::{
+        service::{AsyncService, AsyncServiceFuture},
+        tick::{TickReason, TickService},
+    },
+    trace,
+};
+use std::{sync::Arc, time::Duration};
+
+const STATE_DIGEST_MONITOR: &str = "state-digest-monitor";
+
+/// The interval between consecutive lookups of a new checkpoint
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Logs the state digest of every new checkpoint chain block, so operators can compare the logs of
+/// several nodes to detect a fork
+pub struct StateDigestMonitor {
+    consensus_manager: Arc<ConsensusManager>,
+    tick_service: Arc<TickService>,
+}
+
+impl StateDigestMonitor {
+    pub fn new(consensus_manager: Arc<ConsensusManager>, tick_service: Arc<TickService>) -> Self {
+        Self { consensus_manager, tick_service }
+    }
+
+    pub async fn worker(self: &Arc<StateDigestMonitor>) {
+        let mut last_logged = None;
+        loop {
+            if let TickReason::Shutdown = self.tick_service.tick(CHECK_INTERVAL).await {
+                break;
+            }
+
+            let session = self.consensus_manager.consensus().unguarded_session();
+            let Some(digest) = session.async_get_state_digests(1).await.pop() else { continue };
+            if last_logged != Some(digest.hash) {
+                info!(
+                    "State digest {} at checkpoint chain block {} (blue score {}, DAA score {}, UTXO commitment {})",
+                    digest.digest, digest.hash, digest.blue_score, digest.daa_score, digest.utxo_commitment
+                );
+                last_logged = Some(digest.hash);
+            }
+        }
+
+        trace!("{} thread exiting", STATE_DIGEST_MONITOR);
+    }
+}
+
+impl AsyncService for StateDigestMonitor {
+    fn ident(self: Arc<Self>) -> &'static str {
+        STATE_DIGEST_MONITOR
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            self.worker().await;
+            Ok(())
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", STATE_DIGEST_MONITOR);
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            trace!("{} stopped", STATE_DIGEST_MONITOR);
+            Ok(())
+        })
+    }
+}
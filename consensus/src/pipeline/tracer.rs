@@ -0,0 +1,75 @@
+use indexmap::IndexMap;
+use kaspa_consensus_core::validation_trace::{BlockValidationTrace, RuleTrace};
+use kaspa_hashes::Hash;
+use parking_lot::Mutex;
+use std::time::Instant;
+
+/// The maximal number of recently validated blocks for which traces are retained
+const MAX_TRACED_BLOCKS: usize = 4096;
+
+/// Records which rules were evaluated for each validated block and how long each evaluation took.
+/// When disabled, tracing reduces to a direct call of the traced rule
+pub struct ValidationTracer {
+    enabled: bool,
+    traces: Mutex<IndexMap<Hash, Vec<RuleTrace>>>,
+}
+
+impl ValidationTracer {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled, traces: Default::default() }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Evaluates `rule` for block `hash` and records the evaluation if tracing is enabled
+    pub fn trace<T, E>(&self, hash: Hash, rule: &'static str, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        if !self.enabled {
+            return f();
+        }
+        let start = Instant::now();
+        let res = f();
+        let trace = RuleTrace { rule: rule.to_owned(), duration_micros: start.elapsed().as_micros() as u64, passed: res.is_ok() };
+
+        let mut traces = self.traces.lock();
+        if !traces.contains_key(&hash) && traces.len() >= MAX_TRACED_BLOCKS {
+            traces.shift_remove_index(0);
+        }
+        traces.entry(hash).or_default().push(trace);
+        res
+    }
+
+    pub fn get(&self, hash: Hash) -> Option<BlockValidationTrace> {
+        self.traces.lock().get(&hash).map(|rules| BlockValidationTrace { hash, rules: rules.clone() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_tracer() {
+        let tracer = ValidationTracer::new(true);
+        let hash = Hash::from_u64_word(1);
+        assert_eq!(tracer.trace(hash, "first", || Ok::<_, ()>(1)), Ok(1));
+        assert_eq!(tracer.trace(hash, "second", || Err::<(), _>(2)), Err(2));
+        let trace = tracer.get(hash).unwrap();
+        assert_eq!(
+            trace.rules.iter().map(|r| (r.rule.as_str(), r.passed)).collect::<Vec<_>>(),
+            vec![("first", true), ("second", false)]
+        );
+
+        // Only the most recent blocks are retained
+        for i in 2..=MAX_TRACED_BLOCKS as u64 + 1 {
+            tracer.trace(Hash::from_u64_word(i), "rule", || Ok::<_, ()>(())).unwrap();
+        }
+        assert!(tracer.get(hash).is_none());
+        assert!(tracer.get(Hash::from_u64_word(2)).is_some());
+
+        let disabled = ValidationTracer::new(false);
+        disabled.trace(hash, "rule", || Ok::<_, ()>(())).unwrap();
+        assert!(disabled.get(hash).is_none());
+    }
+}
@@ -36,8 +36,8 @@ use crate::{
     },
     params::Params,
     pipeline::{
-        deps_manager::VirtualStateProcessingMessage, pruning_processor::processor::PruningProcessingMessage,
-        virtual_processor::utxo_validation::UtxoProcessingContext, ProcessingCounters,
+        deps_manager::VirtualStateProcessingMessage, exceeded_budget, pruning_processor::processor::PruningProcessingMessage,
+        tracer::ValidationTracer, virtual_processor::utxo_validation::UtxoProcessingContext, ProcessingCounters,
     },
     processes::{
         coinbase::CoinbaseManager,
@@ -48,7 +48,7 @@ use crate::{
 };
 use kaspa_consensus_core::{
     acceptance_data::AcceptanceData,
-    block::{BlockTemplate, MutableBlock, TemplateBuildMode, TemplateTransactionSelector},
+    block::{BlockOrigin, BlockTemplate, MutableBlock, TemplateBuildMode, TemplateTransactionSelector},
     blockstatus::BlockStatus::{StatusDisqualifiedFromChain, StatusUTXOValid},
     coinbase::MinerData,
     config::genesis::GenesisBlock,
@@ -64,8 +64,8 @@ use kaspa_consensus_core::{
 };
 use kaspa_consensus_notify::{
     notification::{
-        NewBlockTemplateNotification, Notification, SinkBlueScoreChangedNotification, UtxosChangedNotification,
-        VirtualChainChangedNotification, VirtualDaaScoreChangedNotification,
+        AcceptedTransactionsNotification, NewBlockTemplateNotification, Notification, SinkBlueScoreChangedNotification,
+        UtxosChangedNotification, VirtualChainChangedNotification, VirtualDaaScoreChangedNotification,
     },
     root::ConsensusNotificationRoot,
 };
@@ -78,7 +78,7 @@ use kaspa_notify::notifier::Notify;
 
 use crossbeam_channel::{Receiver as CrossbeamReceiver, Sender as CrossbeamSender};
 use itertools::Itertools;
-use kaspa_utils::binary_heap::BinaryHeapExtensions;
+use kaspa_utils::{binary_heap::BinaryHeapExtensions, fail_point};
 use parking_lot::{RwLock, RwLockUpgradableReadGuard};
 use rand::seq::SliceRandom;
 use rayon::{
@@ -91,6 +91,7 @@ use std::{
     collections::{BinaryHeap, HashMap, VecDeque},
     ops::Deref,
     sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
 };
 
 use super::errors::{PruningImportError, PruningImportResult};
@@ -112,6 +113,9 @@ pub struct VirtualStateProcessor {
     pub(super) max_block_parents: u8,
     pub(super) mergeset_size_limit: u64,
     pub(super) pruning_depth: u64,
+    /// Tips lagging the selected parent by more than this DAA score difference are not merged
+    stale_branch_daa_threshold: Option<u64>,
+    params: Params,
 
     // Stores
     pub(super) statuses_store: Arc<RwLock<DbStatusesStore>>,
@@ -152,6 +156,12 @@ pub struct VirtualStateProcessor {
 
     // Counters
     counters: Arc<ProcessingCounters>,
+
+    // Validation tracing
+    tracer: Arc<ValidationTracer>,
+
+    /// UTXO processing of a chain block exceeding this budget is logged along with the dependency structure of the block
+    processing_budget: Option<Duration>,
 }
 
 impl VirtualStateProcessor {
@@ -168,6 +178,9 @@ impl VirtualStateProcessor {
         pruning_lock: SessionLock,
         notification_root: Arc<ConsensusNotificationRoot>,
         counters: Arc<ProcessingCounters>,
+        tracer: Arc<ValidationTracer>,
+        processing_budget: Option<Duration>,
+        stale_branch_daa_threshold: Option<u64>,
     ) -> Self {
         Self {
             receiver,
@@ -179,6 +192,8 @@ impl VirtualStateProcessor {
             max_block_parents: params.max_block_parents,
             mergeset_size_limit: params.mergeset_size_limit,
             pruning_depth: params.pruning_depth,
+            stale_branch_daa_threshold,
+            params: params.clone(),
 
             db,
             statuses_store: storage.statuses_store.clone(),
@@ -211,9 +226,16 @@ impl VirtualStateProcessor {
             pruning_lock,
             notification_root,
             counters,
+            tracer,
+            processing_budget,
         }
     }
 
+    /// Returns the number of processed blocks queued for virtual state resolution
+    pub fn backlog(&self) -> usize {
+        self.receiver.len()
+    }
+
     pub fn worker(self: &Arc<Self>) {
         'outer: while let Ok(msg) = self.receiver.recv() {
             if msg.is_exit_message() {
@@ -227,15 +249,19 @@ impl VirtualStateProcessor {
             let messages: Vec<VirtualStateProcessingMessage> = std::iter::once(msg).chain(self.receiver.try_iter()).collect();
             trace!("virtual processor received {} tasks", messages.len());
 
-            self.resolve_virtual();
+            // Blocks synced during IBD do not make current block templates stale for miners, since the node is
+            // not expected to be mining, hence new templates are only signaled following non-IBD blocks
+            let notify_new_template = messages
+                .iter()
+                .any(|msg| matches!(msg, VirtualStateProcessingMessage::Process(task, _) if task.origin() != BlockOrigin::Ibd));
+            self.resolve_virtual(notify_new_template);
 
             let statuses_read = self.statuses_store.read();
             for msg in messages {
                 match msg {
                     VirtualStateProcessingMessage::Exit => break 'outer,
                     VirtualStateProcessingMessage::Process(task, virtual_state_result_transmitter) => {
-                        // We don't care if receivers were dropped
-                        let _ = virtual_state_result_transmitter.send(Ok(statuses_read.get(task.block().hash()).unwrap()));
+                        virtual_state_result_transmitter.send(Ok(statuses_read.get(task.block().hash()).unwrap()));
                     }
                 };
             }
@@ -245,7 +271,7 @@ impl VirtualStateProcessor {
         self.pruning_sender.send(PruningProcessingMessage::Exit).unwrap();
     }
 
-    fn resolve_virtual(self: &Arc<Self>) {
+    pub(crate) fn resolve_virtual(self: &Arc<Self>, notify_new_template: bool) {
         let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
         let virtual_read = self.virtual_stores.upgradable_read();
         let prev_state = virtual_read.state.get().unwrap();
@@ -260,15 +286,10 @@ impl VirtualStateProcessor {
         //         bound (merge depth <= finality depth).
         // (both claims are true by induction for any block in their past as well)
         let prune_guard = self.pruning_lock.blocking_read();
-        let tips = self
-            .body_tips_store
-            .read()
-            .get()
-            .unwrap()
-            .iter()
-            .copied()
-            .filter(|&h| self.reachability_service.is_dag_ancestor_of(finality_point, h))
-            .collect_vec();
+        let body_tips = self.body_tips_store.read().get().unwrap();
+        self.counters.tips_count.store(body_tips.len() as u64, Ordering::Relaxed);
+        let tips =
+            body_tips.iter().copied().filter(|&h| self.reachability_service.is_dag_ancestor_of(finality_point, h)).collect_vec();
         drop(prune_guard);
         let prev_sink = prev_state.ghostdag_data.selected_parent;
         let mut accumulated_diff = prev_state.utxo_diff.clone().to_reversed();
@@ -291,6 +312,10 @@ impl VirtualStateProcessor {
             )
             .expect("all possible rule errors are unexpected here");
 
+        // Report IBD to the DB, which skips the WAL throughout under the fast-sync durability profile
+        let sink_compact = self.headers_store.get_compact_header_data(new_sink).unwrap();
+        self.db.set_bulk_sync(!self.params.is_nearly_synced(sink_compact.timestamp, sink_compact.daa_score));
+
         // Update the pruning processor about the virtual state change
         let sink_ghostdag_data = self.ghostdag_primary_store.get_compact_data(new_sink).unwrap();
         // Empty the channel before sending the new message. If pruning processor is busy, this step makes sure
@@ -301,9 +326,11 @@ impl VirtualStateProcessor {
         // Emit notifications
         let accumulated_diff = Arc::new(accumulated_diff);
         let virtual_parents = Arc::new(new_virtual_state.parents.clone());
-        self.notification_root
-            .notify(Notification::NewBlockTemplate(NewBlockTemplateNotification {}))
-            .expect("expecting an open unbounded channel");
+        if notify_new_template {
+            self.notification_root
+                .notify(Notification::NewBlockTemplate(NewBlockTemplateNotification {}))
+                .expect("expecting an open unbounded channel");
+        }
         self.notification_root
             .notify(Notification::UtxosChanged(UtxosChangedNotification::new(accumulated_diff, virtual_parents)))
             .expect("expecting an open unbounded channel");
@@ -314,13 +341,23 @@ impl VirtualStateProcessor {
             .notify(Notification::VirtualDaaScoreChanged(VirtualDaaScoreChangedNotification::new(new_virtual_state.daa_score)))
             .expect("expecting an open unbounded channel");
         // TODO: Fetch acceptance data only if there's a subscriber for the below notification.
-        let added_chain_blocks_acceptance_data =
-            chain_path.added.iter().copied().map(|added| self.acceptance_data_store.get(added).unwrap()).collect_vec();
+        let added_chain_blocks_acceptance_data = self.acceptance_data_store.get_many(&chain_path.added).unwrap();
+        // The acceptance data of removed chain blocks is fetched now, before pruning might delete it
+        let removed_chain_blocks_acceptance_data = self.acceptance_data_store.get_many(&chain_path.removed).unwrap();
+        let added_chain_block_hashes: Arc<Vec<Hash>> = chain_path.added.into();
+        let added_chain_blocks_acceptance_data = Arc::new(added_chain_blocks_acceptance_data);
         self.notification_root
             .notify(Notification::VirtualChainChanged(VirtualChainChangedNotification::new(
-                chain_path.added.into(),
+                added_chain_block_hashes.clone(),
                 chain_path.removed.into(),
-                Arc::new(added_chain_blocks_acceptance_data),
+                added_chain_blocks_acceptance_data.clone(),
+                Arc::new(removed_chain_blocks_acceptance_data),
+            )))
+            .expect("expecting an open unbounded channel");
+        self.notification_root
+            .notify(Notification::AcceptedTransactions(AcceptedTransactionsNotification::new(
+                added_chain_block_hashes,
+                added_chain_blocks_acceptance_data,
             )))
             .expect("expecting an open unbounded channel");
     }
@@ -377,28 +414,38 @@ impl VirtualStateProcessor {
                 continue;
             }
 
+            if self.statuses_store.read().get(current).unwrap() == StatusDisqualifiedFromChain {
+                // Current block is already known to be disqualified. Note that a block might be disqualified even
+                // if its UTXO state was calculated, as is the case for blocks invalidated by the node operator
+                continue;
+            }
+
             match self.utxo_diffs_store.get(current) {
                 Ok(mergeset_diff) => {
                     diff.with_diff_in_place(mergeset_diff.deref()).unwrap();
                     diff_point = current;
                 }
                 Err(StoreError::KeyNotFound(_)) => {
-                    if self.statuses_store.read().get(current).unwrap() == StatusDisqualifiedFromChain {
-                        // Current block is already known to be disqualified
-                        continue;
-                    }
-
                     let header = self.headers_store.get_header(current).unwrap();
                     let mergeset_data = self.ghostdag_primary_store.get_data(current).unwrap();
                     let pov_daa_score = header.daa_score;
 
+                    let start = Instant::now();
                     let selected_parent_multiset_hash = self.utxo_multisets_store.get(selected_parent).unwrap();
+                    // The block itself is included since its transactions are validated against its own UTXO view
+                    self.prefetch_utxos(&stores.utxo_set, std::iter::once(current).chain(mergeset_data.unordered_mergeset()));
                     let selected_parent_utxo_view = (&stores.utxo_set).compose(&*diff);
 
                     let mut ctx = UtxoProcessingContext::new(mergeset_data.into(), selected_parent_multiset_hash);
 
-                    self.calculate_utxo_state(&mut ctx, &selected_parent_utxo_view, pov_daa_score);
-                    let res = self.verify_expected_utxo_state(&mut ctx, &selected_parent_utxo_view, &header);
+                    let _: Result<_, std::convert::Infallible> = self.tracer.trace(current, "calculate_utxo_state", || {
+                        self.calculate_utxo_state(&mut ctx, &selected_parent_utxo_view, pov_daa_score);
+                        Ok(())
+                    });
+                    let res = self.tracer.trace(current, "verify_expected_utxo_state", || {
+                        self.verify_expected_utxo_state(&mut ctx, &selected_parent_utxo_view, &header)
+                    });
+                    self.check_processing_budget(current, &ctx, start.elapsed());
 
                     if let Err(rule_error) = res {
                         info!("Block {} is disqualified from virtual chain: {}", current, rule_error);
@@ -425,12 +472,30 @@ impl VirtualStateProcessor {
         diff_point
     }
 
+    /// Logs the dependency structure of a chain block whose UTXO processing exceeded the budget, in order to
+    /// correlate latency spikes with DAG shapes
+    fn check_processing_budget(&self, hash: Hash, ctx: &UtxoProcessingContext, elapsed: Duration) {
+        let Some(budget) = exceeded_budget(self.processing_budget, elapsed) else {
+            return;
+        };
+        warn!(
+            "UTXO processing of chain block {} took {} ms (budget: {} ms): mergeset of {} blues and {} reds, {} accepted transactions",
+            hash,
+            elapsed.as_millis(),
+            budget.as_millis(),
+            ctx.ghostdag_data.mergeset_blues.len(),
+            ctx.ghostdag_data.mergeset_reds.len(),
+            ctx.accepted_tx_ids.len()
+        );
+    }
+
     fn commit_utxo_state(&self, current: Hash, mergeset_diff: UtxoDiff, multiset: MuHash, acceptance_data: AcceptanceData) {
         let mut batch = WriteBatch::default();
         self.utxo_diffs_store.insert_batch(&mut batch, current, Arc::new(mergeset_diff)).unwrap();
         self.utxo_multisets_store.insert_batch(&mut batch, current, multiset).unwrap();
         self.acceptance_data_store.insert_batch(&mut batch, current, Arc::new(acceptance_data)).unwrap();
         let write_guard = self.statuses_store.set_batch(&mut batch, current, StatusUTXOValid).unwrap();
+        fail_point!("virtual-processor-commit-utxo-state");
         self.db.write(batch).unwrap();
         // Calling the drops explicitly after the batch is written in order to avoid possible errors.
         drop(write_guard);
@@ -464,6 +529,7 @@ impl VirtualStateProcessor {
         selected_parent_multiset: MuHash,
         accumulated_diff: &mut UtxoDiff,
     ) -> Result<Arc<VirtualState>, RuleError> {
+        self.prefetch_utxos(&virtual_stores.utxo_set, virtual_ghostdag_data.unordered_mergeset());
         let selected_parent_utxo_view = (&virtual_stores.utxo_set).compose(&*accumulated_diff);
         let mut ctx = UtxoProcessingContext::new((&virtual_ghostdag_data).into(), selected_parent_multiset);
 
@@ -514,6 +580,7 @@ impl VirtualStateProcessor {
         selected_chain_write.apply_changes(&mut batch, chain_path).unwrap();
 
         // Flush the batch changes
+        fail_point!("virtual-processor-commit-virtual-state");
         self.db.write(batch).unwrap();
 
         // Calling the drops explicitly after the batch is written in order to avoid possible errors.
@@ -530,7 +597,9 @@ impl VirtualStateProcessor {
     }
 
     /// Searches for the next valid sink block (SINK = Virtual selected parent). The search is performed
-    /// in the inclusive past of `tips`.
+    /// in the inclusive past of `tips`. Candidates are considered by descending blue work, where equal
+    /// blue work is broken by the higher hash (see [`SortableBlock`]), so the search is deterministic
+    /// regardless of the order in which the tips arrived.
     /// The provided `diff` is assumed to initially hold the UTXO diff of `prev_sink` from virtual.
     /// The function returns with `diff` being the diff of the new sink from previous virtual.
     /// In addition to the found sink the function also returns a queue of additional virtual
@@ -544,8 +613,6 @@ impl VirtualStateProcessor {
         finality_point: Hash,
         pruning_point: Hash,
     ) -> (Hash, VecDeque<Hash>) {
-        // TODO: tests
-
         let mut heap = tips
             .into_iter()
             .map(|block| SortableBlock { hash: block, blue_work: self.ghostdag_primary_store.get_blue_work(block).unwrap() })
@@ -560,7 +627,10 @@ impl VirtualStateProcessor {
         // (and it can't be in the future by induction)
         loop {
             let candidate = heap.pop().expect("valid sink must exist").hash;
-            if self.reachability_service.is_chain_ancestor_of(finality_point, candidate) {
+            if self.statuses_store.read().get(candidate).unwrap() == StatusDisqualifiedFromChain {
+                // Checked explicitly since the candidate might be the current diff point (e.g., a sink invalidated by the node operator)
+                debug!("Block candidate {} is disqualified from chain and is ignored from Virtual chain.", candidate)
+            } else if self.reachability_service.is_chain_ancestor_of(finality_point, candidate) {
                 diff_point = self.calculate_utxo_state_relatively(stores, diff, diff_point, candidate);
                 if diff_point == candidate {
                     // This indicates that candidate has valid UTXO state and that `diff` represents its diff from virtual
@@ -620,6 +690,13 @@ impl VirtualStateProcessor {
         let _prune_guard = self.pruning_lock.blocking_read();
         let max_block_parents = self.max_block_parents as usize;
 
+        // Tips lagging too far behind the selected parent are treated as stale branches and are left unmerged, so
+        // that a tip set which ballooned due to network issues does not keep inflating the mergesets of new blocks
+        if let Some(threshold) = self.stale_branch_daa_threshold {
+            let min_daa_score = self.headers_store.get_daa_score(selected_parent).unwrap().saturating_sub(threshold);
+            candidates.retain(|&candidate| self.headers_store.get_daa_score(candidate).unwrap() >= min_daa_score);
+        }
+
         // Prioritize half the blocks with highest blue work and pick the rest randomly to ensure diversity between nodes
         if candidates.len() > max_block_parents / 2 {
             // `make_contiguous` should be a no op since the deque was just built
@@ -668,19 +745,25 @@ impl VirtualStateProcessor {
         let mut visited: BlockHashSet = queue.iter().copied().collect();
         let mut mergeset_increase = 1u64; // Starts with 1 to count for the candidate itself
 
-        while let Some(current) = queue.pop_front() {
-            if self.reachability_service.is_dag_ancestor_of_any(current, &mut selected_parents.iter().copied()) {
-                continue;
-            }
-            mergeset_increase += 1;
-            if mergeset_increase > budget {
-                return MergesetIncreaseResult::Rejected { new_candidate: current };
+        // The traversal is performed level by level so that the parents of each level are read in a single batch
+        while !queue.is_empty() {
+            let mut level = Vec::with_capacity(queue.len());
+            while let Some(current) = queue.pop_front() {
+                if self.reachability_service.is_dag_ancestor_of_any(current, &mut selected_parents.iter().copied()) {
+                    continue;
+                }
+                mergeset_increase += 1;
+                if mergeset_increase > budget {
+                    return MergesetIncreaseResult::Rejected { new_candidate: current };
+                }
+                level.push(current);
             }
 
-            let current_parents = self.relations_service.get_parents(current).unwrap();
-            for &parent in current_parents.iter() {
-                if visited.insert(parent) {
-                    queue.push_back(parent);
+            for current_parents in self.relations_service.get_parents_many(&level).unwrap() {
+                for &parent in current_parents.iter() {
+                    if visited.insert(parent) {
+                        queue.push_back(parent);
+                    }
                 }
             }
         }
@@ -913,6 +996,7 @@ impl VirtualStateProcessor {
         let pruning_info = self.pruning_point_store.read().get().unwrap();
         let header_pruning_point =
             self.pruning_point_manager.expected_header_pruning_point(virtual_state.ghostdag_data.to_compact(), pruning_info);
+        self.coinbase_manager.validate_template_miner_data(&miner_data).map_err(RuleError::from_coinbase_payload_error)?;
         let coinbase = self
             .coinbase_manager
             .expected_coinbase_transaction(
@@ -958,8 +1042,9 @@ impl VirtualStateProcessor {
         ))
     }
 
-    /// Make sure pruning point-related stores are initialized
-    pub fn init(self: &Arc<Self>) {
+    /// Registers genesis as the pruning point, history root and pruning UTXO set position, unless some pruning point
+    /// is already registered
+    pub fn init_genesis_roots(self: &Arc<Self>) {
         let pruning_point_read = self.pruning_point_store.upgradable_read();
         if pruning_point_read.pruning_point().unwrap_option().is_none() {
             let mut pruning_point_write = RwLockUpgradableReadGuard::upgrade(pruning_point_read);
@@ -976,7 +1061,7 @@ impl VirtualStateProcessor {
     }
 
     /// Initializes UTXO state of genesis and points virtual at genesis.
-    /// Note that pruning point-related stores are initialized by `init`
+    /// Note that pruning point-related stores are initialized by `init_genesis_roots`
     pub fn process_genesis(self: &Arc<Self>) {
         // Write the UTXO state of genesis
         self.commit_utxo_state(self.genesis.hash, UtxoDiff::default(), MuHash::new(), AcceptanceData::default());
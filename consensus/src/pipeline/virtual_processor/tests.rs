@@ -1,4 +1,7 @@
-use crate::{consensus::test_consensus::TestConsensus, model::services::reachability::ReachabilityService};
+use crate::{
+    consensus::test_consensus::TestConsensus,
+    model::{services::reachability::ReachabilityService, stores::ghostdag::GhostdagStoreReader},
+};
 use kaspa_consensus_core::{
     api::ConsensusApi,
     block::{Block, BlockTemplate, MutableBlock, TemplateBuildMode, TemplateTransactionSelector},
@@ -296,6 +299,87 @@ async fn double_search_disqualified_test() {
     ctx.assert_tips_num(1);
 }
 
+#[tokio::test]
+async fn equal_work_sink_race_test() {
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let mut ctx = TestContext::new(TestConsensus::new(&config));
+    let mut ctx_reversed = TestContext::new(TestConsensus::new(&config));
+
+    // Build racing tips over the same parents, hence with equal blue work
+    ctx.build_block_template_row(0..5);
+    let blocks = ctx.current_templates.drain(..).map(|t| t.block.to_immutable()).collect::<Vec<_>>();
+    let blue_work = |ctx: &TestContext, hash| ctx.consensus.ghostdag_store().get_blue_work(hash).unwrap();
+
+    // Whatever the arrival order, the sink is the tip with the highest hash at any point
+    let mut max_hash = blockhash::NONE;
+    for block in blocks.iter().cloned() {
+        max_hash = max_hash.max(block.hash());
+        ctx.validate_and_insert_block(block).await;
+        assert_eq!(ctx.consensus.get_sink(), max_hash);
+    }
+    for block in blocks.iter().rev().cloned() {
+        ctx_reversed.validate_and_insert_block(block).await;
+    }
+    assert!(blocks.iter().all(|block| blue_work(&ctx, block.hash()) == blue_work(&ctx, max_hash)));
+    assert_eq!(ctx_reversed.consensus.get_sink(), max_hash);
+    assert_eq!(ctx.consensus.get_sink_timestamp(), blocks.iter().find(|block| block.hash() == max_hash).unwrap().header.timestamp);
+
+    // Higher blue work takes precedence over the tie-break, so a low-hash child of the lowest tip becomes the sink
+    let lowest = blocks.iter().map(|block| block.hash()).min().unwrap();
+    let child = Hash::from_u64_word(1);
+    for ctx in [&ctx, &ctx_reversed] {
+        ctx.consensus.add_utxo_valid_block_with_parents(child, vec![lowest], vec![]).await.unwrap();
+        assert!(blue_work(ctx, child) > blue_work(ctx, max_hash));
+        assert_eq!(ctx.consensus.get_sink(), child);
+    }
+}
+
+#[tokio::test]
+async fn stale_branch_exclusion_test() {
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().apply_args(|cfg| cfg.stale_branch_daa_threshold = 2).build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    // A side tip on genesis falls behind a chain of 4 blocks
+    let stale_tip = Hash::from_u64_word(100);
+    consensus.add_utxo_valid_block_with_parents(stale_tip, vec![config.genesis.hash], vec![]).await.unwrap();
+    assert!(consensus.get_virtual_parents().contains(&stale_tip));
+    for i in 1..=4u64 {
+        let parent = if i == 1 { config.genesis.hash } else { Hash::from_u64_word(i - 1) };
+        consensus.add_utxo_valid_block_with_parents(Hash::from_u64_word(i), vec![parent], vec![]).await.unwrap();
+    }
+
+    // The stale tip is still a tip, but is no longer merged by virtual
+    assert_eq!(consensus.get_virtual_parents(), BlockHashSet::from_iter([Hash::from_u64_word(4)]));
+    assert_eq!(consensus.processing_counters().snapshot().tips_count, 2);
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn known_block_resubmission_test() {
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    for i in 1..=3u64 {
+        let parent = if i == 1 { config.genesis.hash } else { Hash::from_u64_word(i - 1) };
+        consensus.add_utxo_valid_block_with_parents(Hash::from_u64_word(i), vec![parent], vec![]).await.unwrap();
+    }
+    assert_eq!(consensus.processing_counters().snapshot().known_block_counts, 0);
+
+    // Resubmitting processed blocks, in full or header-only, resolves them upfront with their status
+    let block = consensus.get_block(Hash::from_u64_word(3)).unwrap();
+    let status = consensus.validate_and_insert_block(block.clone()).virtual_state_task.await.unwrap();
+    assert_eq!(status, BlockStatus::StatusUTXOValid);
+    let header_only = Block::from_header_arc(block.header.clone());
+    let status = consensus.validate_and_insert_block(header_only).virtual_state_task.await.unwrap();
+    assert_eq!(status, BlockStatus::StatusUTXOValid);
+    assert_eq!(consensus.processing_counters().snapshot().known_block_counts, 2);
+
+    consensus.shutdown(wait_handles);
+}
+
 fn new_miner_data() -> MinerData {
     let secp = secp256k1::Secp256k1::new();
     let mut rng = rand::thread_rng();
@@ -4,7 +4,9 @@ use crate::{
         BlockProcessResult,
         RuleError::{BadAcceptedIDMerkleRoot, BadCoinbaseTransaction, BadUTXOCommitment, InvalidTransactionsInUtxoContext},
     },
-    model::stores::{block_transactions::BlockTransactionsStoreReader, daa::DaaStoreReader, ghostdag::GhostdagData},
+    model::stores::{
+        block_transactions::BlockTransactionsStoreReader, daa::DaaStoreReader, ghostdag::GhostdagData, utxo_set::DbUtxoSetStore,
+    },
     processes::transaction_validator::{
         errors::{TxResult, TxRuleError},
         transaction_validator_populated::TxValidationFlags,
@@ -61,6 +63,19 @@ impl<'a> UtxoProcessingContext<'a> {
 }
 
 impl VirtualStateProcessor {
+    /// Prefetches the UTXO entries spent by the transactions of `blocks` with a single batched read, so that the
+    /// following contextual validation of these transactions is served from the UTXO set cache rather than by
+    /// per-input DB reads
+    pub(super) fn prefetch_utxos(&self, utxo_set: &DbUtxoSetStore, blocks: impl Iterator<Item = Hash>) {
+        let mut outpoints = Vec::new();
+        for block in blocks {
+            let txs = self.block_transactions_store.get(block).unwrap();
+            // Skip the coinbase transaction which has no inputs
+            outpoints.extend(txs.iter().skip(1).flat_map(|tx| tx.inputs.iter().map(|input| input.previous_outpoint)));
+        }
+        utxo_set.prefetch(outpoints).unwrap();
+    }
+
     /// Calculates UTXO state and transaction acceptance data relative to the selected parent state
     pub(super) fn calculate_utxo_state<V: UtxoView + Sync>(
         &self,
@@ -76,6 +91,7 @@ impl VirtualStateProcessor {
         let validated_coinbase_id = validated_coinbase.id();
         ctx.accepted_tx_ids.push(validated_coinbase_id);
 
+        // Blocks are accepted in consensus order, which also determines the order of the accepted IDs merkle tree
         for (i, (merged_block, txs)) in once((ctx.selected_parent(), selected_parent_transactions))
             .chain(
                 ctx.ghostdag_data
@@ -7,7 +7,10 @@ use kaspa_consensus_core::{
 };
 use std::{convert::TryInto, mem::size_of};
 
-use crate::{constants, model::stores::ghostdag::GhostdagData};
+use crate::{
+    constants,
+    model::stores::ghostdag::{ColoredBlock, GhostdagData},
+};
 
 const LENGTH_OF_BLUE_SCORE: usize = size_of::<u64>();
 const LENGTH_OF_SUBSIDY: usize = size_of::<u64>();
@@ -27,6 +30,7 @@ pub type SubsidyByMonthTable = [u64; SUBSIDY_BY_MONTH_TABLE_SIZE];
 #[derive(Clone)]
 pub struct CoinbaseManager {
     coinbase_payload_script_public_key_max_len: u8,
+    coinbase_payload_script_public_key_max_version: u16,
     max_coinbase_payload_len: usize,
     deflationary_phase_daa_score: u64,
     pre_deflationary_phase_base_subsidy: u64,
@@ -60,6 +64,7 @@ impl<'a> PayloadParser<'a> {
 impl CoinbaseManager {
     pub fn new(
         coinbase_payload_script_public_key_max_len: u8,
+        coinbase_payload_script_public_key_max_version: u16,
         max_coinbase_payload_len: usize,
         deflationary_phase_daa_score: u64,
         pre_deflationary_phase_base_subsidy: u64,
@@ -75,6 +80,7 @@ impl CoinbaseManager {
         let subsidy_by_month_table: SubsidyByMonthTable = core::array::from_fn(|i| (SUBSIDY_BY_MONTH_TABLE[i] + bps - 1) / bps);
         Self {
             coinbase_payload_script_public_key_max_len,
+            coinbase_payload_script_public_key_max_version,
             max_coinbase_payload_len,
             deflationary_phase_daa_score,
             pre_deflationary_phase_base_subsidy,
@@ -101,21 +107,20 @@ impl CoinbaseManager {
         let mut outputs = Vec::with_capacity(ghostdag_data.mergeset_blues.len() + 1); // + 1 for possible red reward
 
         // Add an output for each mergeset blue block (∩ DAA window), paying to the script reported by the block.
-        // Note that combinatorically it is nearly impossible for a blue block to be non-DAA
-        for blue in ghostdag_data.mergeset_blues.iter().filter(|h| !mergeset_non_daa.contains(h)) {
-            let reward_data = mergeset_rewards.get(blue).unwrap();
-            if reward_data.subsidy + reward_data.total_fees > 0 {
-                outputs
-                    .push(TransactionOutput::new(reward_data.subsidy + reward_data.total_fees, reward_data.script_public_key.clone()));
-            }
-        }
-
-        // Collect all rewards from mergeset reds ∩ DAA window and create a
-        // single output rewarding all to the current block (the "merging" block)
+        // Note that combinatorically it is nearly impossible for a blue block to be non-DAA.
+        // Rewards from mergeset reds ∩ DAA window are collected into a single output rewarding
+        // all to the current block (the "merging" block)
         let mut red_reward = 0u64;
-        for red in ghostdag_data.mergeset_reds.iter().filter(|h| !mergeset_non_daa.contains(h)) {
-            let reward_data = mergeset_rewards.get(red).unwrap();
-            red_reward += reward_data.subsidy + reward_data.total_fees;
+        for block in ghostdag_data.color_ordered_mergeset().filter(|b| !mergeset_non_daa.contains(&b.hash())) {
+            let reward_data = mergeset_rewards.get(&block.hash()).unwrap();
+            let reward = reward_data.subsidy + reward_data.total_fees;
+            match block {
+                ColoredBlock::Blue(_) if reward > 0 => {
+                    outputs.push(TransactionOutput::new(reward, reward_data.script_public_key.clone()));
+                }
+                ColoredBlock::Blue(_) => {}
+                ColoredBlock::Red(_) => red_reward += reward,
+            }
         }
         if red_reward > 0 {
             outputs.push(TransactionOutput::new(red_reward, miner_data.script_public_key.clone()));
@@ -132,13 +137,8 @@ impl CoinbaseManager {
     }
 
     pub fn serialize_coinbase_payload<T: AsRef<[u8]>>(&self, data: &CoinbaseData<T>) -> CoinbaseResult<Vec<u8>> {
+        self.validate_miner_data(&data.miner_data)?;
         let script_pub_key_len = data.miner_data.script_public_key.script().len();
-        if script_pub_key_len > self.coinbase_payload_script_public_key_max_len as usize {
-            return Err(CoinbaseError::PayloadScriptPublicKeyLenAboveMax(
-                script_pub_key_len,
-                self.coinbase_payload_script_public_key_max_len,
-            ));
-        }
         let payload: Vec<u8> = data.blue_score.to_le_bytes().iter().copied()                    // Blue score                   (u64)
             .chain(data.subsidy.to_le_bytes().iter().copied())                                  // Subsidy                      (u64)
             .chain(data.miner_data.script_public_key.version().to_le_bytes().iter().copied())   // Script public key version    (u16)
@@ -151,13 +151,8 @@ impl CoinbaseManager {
     }
 
     pub fn modify_coinbase_payload<T: AsRef<[u8]>>(&self, mut payload: Vec<u8>, miner_data: &MinerData<T>) -> CoinbaseResult<Vec<u8>> {
+        self.validate_template_miner_data(miner_data)?;
         let script_pub_key_len = miner_data.script_public_key.script().len();
-        if script_pub_key_len > self.coinbase_payload_script_public_key_max_len as usize {
-            return Err(CoinbaseError::PayloadScriptPublicKeyLenAboveMax(
-                script_pub_key_len,
-                self.coinbase_payload_script_public_key_max_len,
-            ));
-        }
 
         // Keep only blue score and subsidy. Note that truncate does not modify capacity, so
         // the usual case where the payloads are the same size will not trigger a reallocation
@@ -172,6 +167,41 @@ impl CoinbaseManager {
         Ok(payload)
     }
 
+    /// Validates that `miner_data` fits the coinbase payload limits enforced by consensus
+    fn validate_miner_data<T: AsRef<[u8]>>(&self, miner_data: &MinerData<T>) -> CoinbaseResult<()> {
+        let script_pub_key_len = miner_data.script_public_key.script().len();
+        if script_pub_key_len > self.coinbase_payload_script_public_key_max_len as usize {
+            return Err(CoinbaseError::PayloadScriptPublicKeyLenAboveMax(
+                script_pub_key_len,
+                self.coinbase_payload_script_public_key_max_len,
+            ));
+        }
+
+        let payload_len = MIN_PAYLOAD_LENGTH + script_pub_key_len + miner_data.extra_data.as_ref().len();
+        if payload_len > self.max_coinbase_payload_len {
+            return Err(CoinbaseError::PayloadLenAboveMax(payload_len, self.max_coinbase_payload_len));
+        }
+
+        Ok(())
+    }
+
+    /// Validates that `miner_data` can be used for building a block template, so that a misconfigured miner (e.g. with a
+    /// pool tag too long) is reported when building the template rather than by the validation of the block.
+    ///
+    /// On top of the consensus limits, the script version of the miner must not exceed the max version set by the params.
+    /// This is a template policy only: blocks paying to a higher script version remain valid, as they always were
+    pub fn validate_template_miner_data<T: AsRef<[u8]>>(&self, miner_data: &MinerData<T>) -> CoinbaseResult<()> {
+        self.validate_miner_data(miner_data)?;
+        let script_pub_key_version = miner_data.script_public_key.version();
+        if script_pub_key_version > self.coinbase_payload_script_public_key_max_version {
+            return Err(CoinbaseError::PayloadScriptPublicKeyVersionAboveMax(
+                script_pub_key_version,
+                self.coinbase_payload_script_public_key_max_version,
+            ));
+        }
+        Ok(())
+    }
+
     pub fn deserialize_coinbase_payload<'a>(&self, payload: &'a [u8]) -> CoinbaseResult<CoinbaseData<&'a [u8]>> {
         if payload.len() < MIN_PAYLOAD_LENGTH {
             return Err(CoinbaseError::PayloadLenBelowMin(payload.len(), MIN_PAYLOAD_LENGTH));
@@ -475,9 +505,34 @@ mod tests {
         assert_eq!(data2, deserialized_data);
     }
 
+    #[test]
+    fn payload_limits_test() {
+        let cbm = create_manager(&MAINNET_PARAMS);
+        let script_public_key = ScriptPublicKey::new(0, ScriptVec::from_slice(&[33u8; 34]));
+
+        // A pool tag exceeding the maximum payload length
+        let miner_data = MinerData { script_public_key: script_public_key.clone(), extra_data: vec![0u8; 160] };
+        let data = CoinbaseData { blue_score: 56, subsidy: 44000000000, miner_data };
+        assert!(matches!(cbm.serialize_coinbase_payload(&data), Err(CoinbaseError::PayloadLenAboveMax(213, 204))));
+
+        // A script public key version above the maximum is rejected for templates only, payloads carrying it remaining valid
+        let miner_data = MinerData {
+            script_public_key: ScriptPublicKey::new(1, ScriptVec::from_slice(script_public_key.script())),
+            extra_data: Vec::<u8>::new(),
+        };
+        assert!(matches!(
+            cbm.validate_template_miner_data(&miner_data),
+            Err(CoinbaseError::PayloadScriptPublicKeyVersionAboveMax(1, 0))
+        ));
+        let data = CoinbaseData { blue_score: 56, subsidy: 44000000000, miner_data };
+        let payload = cbm.serialize_coinbase_payload(&data).unwrap();
+        assert_eq!(cbm.deserialize_coinbase_payload(&payload).unwrap().miner_data.script_public_key.version(), 1);
+    }
+
     fn create_manager(params: &Params) -> CoinbaseManager {
         CoinbaseManager::new(
             params.coinbase_payload_script_public_key_max_len,
+            params.coinbase_payload_script_public_key_max_version,
             params.max_coinbase_payload_len,
             params.deflationary_phase_daa_score,
             params.pre_deflationary_phase_base_subsidy,
@@ -487,6 +542,6 @@ mod tests {
 
     /// Return a CoinbaseManager with legacy golang 1 BPS properties
     fn create_legacy_manager() -> CoinbaseManager {
-        CoinbaseManager::new(150, 204, 15778800 - 259200, 50000000000, 1000)
+        CoinbaseManager::new(150, 0, 204, 15778800 - 259200, 50000000000, 1000)
     }
 }
@@ -0,0 +1,387 @@
+//! Era files hold the bodies of blocks pruned from the consensus database, so that full history can be retained
+//! outside of the hot DB.
+//!
+//! Each pruning point movement produces a single era, stored as a pair of append-only files in the archive directory:
+//! - `<era>-<pruning point>.era`: a sequence of records, each being a little-endian `u32` length followed by the
+//!   bincode serialization of the archived block (header and transactions)
+//! - `<era>-<pruning point>.idx`: a sequence of fixed-size entries, each being the block hash followed by the
+//!   little-endian `u64` offset of its record within the era file
+//!
+//! Records are only ever appended, so an era resumed after an interrupted prune may contain a block more than once;
+//! the last index entry of a hash is the authoritative one. Once an era is complete, its index is rewritten as
+//! `<era>-<pruning point>.sidx`, holding the authoritative entry of each block sorted by hash, which readers binary
+//! search instead of loading the indexes to memory.
+
+use kaspa_consensus_core::{block::Block, header::Header, tx::Transaction, BlockHashMap};
+use kaspa_hashes::Hash;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+pub const ERA_FILE_EXTENSION: &str = "era";
+pub const ERA_INDEX_FILE_EXTENSION: &str = "idx";
+pub const ERA_SORTED_INDEX_FILE_EXTENSION: &str = "sidx";
+
+/// The size of a single index file entry: block hash and record offset
+pub const ERA_INDEX_ENTRY_SIZE: usize = 32 + 8;
+
+/// The minimal interval between consecutive scans of the archive directory for new or completed eras
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Serialize)]
+struct ArchivedBlockRef<'a> {
+    header: &'a Header,
+    transactions: &'a [Transaction],
+}
+
+/// The owned counterpart of [`ArchivedBlockRef`], sharing its serialization layout
+#[derive(Deserialize)]
+struct ArchivedBlock {
+    header: Header,
+    transactions: Vec<Transaction>,
+}
+
+/// Returns the common path (without extension) of the era files of `era`, which ended at `pruning_point`
+pub fn era_file_stem(dir: &Path, era: u64, pruning_point: Hash) -> PathBuf {
+    dir.join(format!("{:08}-{}", era, pruning_point))
+}
+
+/// Appends pruned blocks to the era files of a single pruning point movement
+pub struct EraWriter {
+    stem: PathBuf,
+    data: BufWriter<File>,
+    index: BufWriter<File>,
+    offset: u64,
+}
+
+impl EraWriter {
+    /// Opens (or resumes) the era files of `era` within `dir`, creating the directory if needed. The directory is
+    /// synced once the files are created, so that their entries survive a crash along with their synced content
+    pub fn open(dir: &Path, era: u64, pruning_point: Hash) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let stem = era_file_stem(dir, era, pruning_point);
+        let data = OpenOptions::new().create(true).append(true).open(stem.with_extension(ERA_FILE_EXTENSION))?;
+        let index = OpenOptions::new().create(true).append(true).open(stem.with_extension(ERA_INDEX_FILE_EXTENSION))?;
+        sync_dir(dir)?;
+        let offset = data.metadata()?.len();
+        Ok(Self { stem, data: BufWriter::new(data), index: BufWriter::new(index), offset })
+    }
+
+    /// Appends a block to the era. Both files are synced to disk before returning, so that the block survives a crash
+    /// once the caller deletes it from the database. The record is synced before its index entry, so an entry never
+    /// points at a lost record
+    pub fn append(&mut self, header: &Header, transactions: &[Transaction]) -> io::Result<()> {
+        let record = bincode::serialize(&ArchivedBlockRef { header, transactions })
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let len = u32::try_from(record.len()).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        self.data.write_all(&len.to_le_bytes())?;
+        self.data.write_all(&record)?;
+        self.data.flush()?;
+        self.data.get_ref().sync_data()?;
+
+        self.index.write_all(&header.hash.as_bytes())?;
+        self.index.write_all(&self.offset.to_le_bytes())?;
+        self.index.flush()?;
+        self.index.get_ref().sync_data()?;
+
+        self.offset += 4 + record.len() as u64;
+        Ok(())
+    }
+
+    /// Flushes and syncs both era files to disk, then writes the sorted index of the complete era
+    pub fn finish(mut self) -> io::Result<()> {
+        self.data.flush()?;
+        self.index.flush()?;
+        self.data.get_ref().sync_all()?;
+        self.index.get_ref().sync_all()?;
+        write_sorted_index(&self.stem)
+    }
+}
+
+/// Rewrites the index of the era at `stem` as its sorted index, keeping the last entry of each block
+fn write_sorted_index(stem: &Path) -> io::Result<()> {
+    let bytes = fs::read(stem.with_extension(ERA_INDEX_FILE_EXTENSION))?;
+    let mut entries = BlockHashMap::with_capacity(bytes.len() / ERA_INDEX_ENTRY_SIZE);
+    for entry in bytes.chunks_exact(ERA_INDEX_ENTRY_SIZE) {
+        entries.insert(Hash::from_slice(&entry[..32]), &entry[32..]);
+    }
+    let mut entries = entries.into_iter().collect::<Vec<_>>();
+    entries.sort_unstable_by_key(|(hash, _)| hash.as_bytes());
+
+    // Write to a temporary file first, so that a sorted index is never observed partially written
+    let temp_path = stem.with_extension(format!("{ERA_SORTED_INDEX_FILE_EXTENSION}.tmp"));
+    let mut writer = BufWriter::new(File::create(&temp_path)?);
+    for (hash, offset) in entries {
+        writer.write_all(&hash.as_bytes())?;
+        writer.write_all(offset)?;
+    }
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+    fs::rename(temp_path, stem.with_extension(ERA_SORTED_INDEX_FILE_EXTENSION))?;
+    match stem.parent() {
+        Some(dir) => sync_dir(dir),
+        None => Ok(()),
+    }
+}
+
+/// Syncs the entries of `dir` to disk, making the files created or renamed within it durable. Directories cannot be
+/// opened for syncing on Windows, where the file system persists entries on its own
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+/// The files of an era found in the archive directory
+struct EraFiles {
+    data: PathBuf,
+    index: PathBuf,
+    /// Whether `index` is the sorted index of a complete era, or the index of an era still being written
+    sorted: bool,
+}
+
+impl EraFiles {
+    /// Returns the offset of the record of `hash` within the era file, if archived in this era
+    fn find(&self, hash: Hash) -> io::Result<Option<u64>> {
+        let file = match File::open(&self.index) {
+            Ok(file) => file,
+            // The era may have been completed since the directory was scanned, its index being sorted in the meantime
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        match self.sorted {
+            true => Self::binary_search(file, hash),
+            false => Self::scan(file, hash),
+        }
+    }
+
+    fn binary_search(mut file: File, hash: Hash) -> io::Result<Option<u64>> {
+        let target = hash.as_bytes();
+        let mut entry = [0u8; ERA_INDEX_ENTRY_SIZE];
+        let (mut low, mut high) = (0u64, file.metadata()?.len() / ERA_INDEX_ENTRY_SIZE as u64);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            file.seek(SeekFrom::Start(mid * ERA_INDEX_ENTRY_SIZE as u64))?;
+            file.read_exact(&mut entry)?;
+            match entry[..32].cmp(&target[..]) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return Ok(Some(u64::from_le_bytes(entry[32..].try_into().unwrap()))),
+            }
+        }
+        Ok(None)
+    }
+
+    fn scan(file: File, hash: Hash) -> io::Result<Option<u64>> {
+        let target = hash.as_bytes();
+        let mut reader = BufReader::new(file);
+        let mut entry = [0u8; ERA_INDEX_ENTRY_SIZE];
+        let mut offset = None;
+        loop {
+            match reader.read_exact(&mut entry) {
+                Ok(()) if entry[..32] == target => offset = Some(u64::from_le_bytes(entry[32..].try_into().unwrap())),
+                Ok(()) => {}
+                // Also ignores a trailing partial entry, which might still be in the process of being written
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(offset),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct EraListing {
+    /// The eras found in the archive directory, latest first
+    eras: Arc<Vec<EraFiles>>,
+    last_refresh: Option<Instant>,
+}
+
+/// Serves archived blocks out of the era files of an archive directory.
+///
+/// Lookups binary search the sorted indexes of the complete eras and scan the index of an era still being written,
+/// so memory use does not grow with the archive. The eras are listed again periodically, picking up the eras
+/// created or completed after the reader.
+pub struct EraReader {
+    dir: PathBuf,
+    listing: Mutex<EraListing>,
+}
+
+impl EraReader {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir, listing: Default::default() }
+    }
+
+    /// Returns the archived block with `hash`, if any
+    pub fn get_block(&self, hash: Hash) -> io::Result<Option<Block>> {
+        // Eras are searched latest first, since a later record of a block takes precedence
+        let mut location = None;
+        for era in self.eras()?.iter() {
+            if let Some(offset) = era.find(hash)? {
+                location = Some((era.data.clone(), offset));
+                break;
+            }
+        }
+        let Some((path, offset)) = location else {
+            return Ok(None);
+        };
+
+        let mut file = File::open(&path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut len = [0u8; 4];
+        file.read_exact(&mut len)?;
+        let mut record = vec![0u8; u32::from_le_bytes(len) as usize];
+        file.read_exact(&mut record)?;
+        let block: ArchivedBlock = bincode::deserialize(&record).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if block.header.hash != hash {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("era record at {}:{} holds block {} rather than {}", path.display(), offset, block.header.hash, hash),
+            ));
+        }
+        Ok(Some(Block::new(block.header, block.transactions)))
+    }
+
+    /// Returns the eras of the archive directory, listing them again if the previous listing is outdated. The directory
+    /// is read without holding the lock, so concurrent lookups are not blocked by the file system
+    fn eras(&self) -> io::Result<Arc<Vec<EraFiles>>> {
+        {
+            let listing = self.listing.lock();
+            if listing.last_refresh.is_some_and(|t| t.elapsed() < REFRESH_INTERVAL) {
+                return Ok(listing.eras.clone());
+            }
+        }
+        let eras = Arc::new(self.list_eras()?);
+        let mut listing = self.listing.lock();
+        listing.eras = eras.clone();
+        listing.last_refresh = Some(Instant::now());
+        Ok(eras)
+    }
+
+    fn list_eras(&self) -> io::Result<Vec<EraFiles>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err),
+        };
+        let mut index_paths = entries
+            .map(|entry| entry.map(|entry| entry.path()))
+            .filter(|path| path.as_ref().map_or(true, |path| path.extension().is_some_and(|ext| ext == ERA_INDEX_FILE_EXTENSION)))
+            .collect::<io::Result<Vec<_>>>()?;
+        // Era file names start with the zero-padded era, so sorting the paths in reverse lists the latest eras first
+        index_paths.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(index_paths
+            .into_iter()
+            .map(|index| {
+                let sorted_index = index.with_extension(ERA_SORTED_INDEX_FILE_EXTENSION);
+                let data = index.with_extension(ERA_FILE_EXTENSION);
+                match sorted_index.exists() {
+                    true => EraFiles { data, index: sorted_index, sorted: true },
+                    false => EraFiles { data, index, sorted: false },
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::subnets::SUBNETWORK_ID_NATIVE;
+
+    #[test]
+    fn test_era_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let reader = EraReader::new(dir.path().to_path_buf());
+
+        let blocks = (0..3u64)
+            .map(|i| {
+                let mut header = Header::from_precomputed_hash(i.into(), vec![]);
+                header.daa_score = i;
+                let transactions = vec![Transaction::new(0, vec![], vec![], i, SUBNETWORK_ID_NATIVE, 0, vec![i as u8])];
+                (header, transactions)
+            })
+            .collect::<Vec<_>>();
+
+        let mut writer = EraWriter::open(dir.path(), 1, 10.into()).unwrap();
+        for (header, transactions) in blocks.iter() {
+            writer.append(header, transactions).unwrap();
+        }
+        writer.finish().unwrap();
+
+        for (header, transactions) in blocks.iter() {
+            let block = reader.get_block(header.hash).unwrap().unwrap();
+            assert_eq!(block.header.daa_score, header.daa_score);
+            assert_eq!(block.transactions.as_ref(), transactions);
+        }
+        assert!(reader.get_block(100.into()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_era_append_is_durable() {
+        let dir = tempfile::tempdir().unwrap();
+        // The archive directory is created on open, including missing parents
+        let archive = dir.path().join("archive").join("eras");
+        let mut writer = EraWriter::open(&archive, 2, 20.into()).unwrap();
+        let stem = era_file_stem(&archive, 2, 20.into());
+        assert!(stem.with_extension(ERA_FILE_EXTENSION).exists() && stem.with_extension(ERA_INDEX_FILE_EXTENSION).exists());
+
+        // Each appended block is on disk once append returns, before the era is finished
+        let reader = EraReader::new(archive.clone());
+        for i in 0..3u64 {
+            let header = Header::from_precomputed_hash(i.into(), vec![]);
+            let transactions = vec![Transaction::new(0, vec![], vec![], i, SUBNETWORK_ID_NATIVE, 0, vec![i as u8])];
+            writer.append(&header, &transactions).unwrap();
+            assert_eq!(fs::metadata(stem.with_extension(ERA_FILE_EXTENSION)).unwrap().len(), writer.offset);
+            assert_eq!(
+                fs::metadata(stem.with_extension(ERA_INDEX_FILE_EXTENSION)).unwrap().len(),
+                (i + 1) * ERA_INDEX_ENTRY_SIZE as u64
+            );
+            assert_eq!(reader.get_block(header.hash).unwrap().unwrap().transactions.as_ref(), &transactions);
+        }
+    }
+
+    #[test]
+    fn test_era_lookup_before_and_after_completion() {
+        let dir = tempfile::tempdir().unwrap();
+        let block = |i: u64, tag: u8| {
+            let mut header = Header::from_precomputed_hash(i.into(), vec![]);
+            header.daa_score = i;
+            (header, vec![Transaction::new(0, vec![], vec![], i, SUBNETWORK_ID_NATIVE, 0, vec![tag])])
+        };
+
+        // An interrupted prune archived block 1, which the resumed prune archives again along with more blocks
+        let mut writer = EraWriter::open(dir.path(), 1, 10.into()).unwrap();
+        let (header, transactions) = block(1, 0);
+        writer.append(&header, &transactions).unwrap();
+        drop(writer);
+        let mut writer = EraWriter::open(dir.path(), 1, 10.into()).unwrap();
+        let blocks = (1..50u64).map(|i| block(i, 1)).collect::<Vec<_>>();
+        for (header, transactions) in blocks.iter() {
+            writer.append(header, transactions).unwrap();
+        }
+
+        // The era is still being written, so its index is scanned
+        let reader = EraReader::new(dir.path().to_path_buf());
+        assert_eq!(reader.get_block(1.into()).unwrap().unwrap().transactions[0].payload, vec![1]);
+        assert!(reader.get_block(100.into()).unwrap().is_none());
+
+        // Once complete, the sorted index is binary searched
+        writer.finish().unwrap();
+        assert!(era_file_stem(dir.path(), 1, 10.into()).with_extension(ERA_SORTED_INDEX_FILE_EXTENSION).exists());
+        let reader = EraReader::new(dir.path().to_path_buf());
+        for (header, transactions) in blocks.iter() {
+            let block = reader.get_block(header.hash).unwrap().unwrap();
+            assert_eq!(block.transactions.as_ref(), transactions);
+        }
+        assert!(reader.get_block(0.into()).unwrap().is_none());
+        assert!(reader.get_block(100.into()).unwrap().is_none());
+    }
+}
@@ -1,22 +1,20 @@
 use std::sync::Arc;
 
 use kaspa_consensus_core::{
-    blockhash::{self, BlockHashExtensions, BlockHashes},
+    blockhash::{self, BlockHashExtensions},
     BlockHashMap, BlueWorkType, HashMapCustomHasher,
 };
 use kaspa_hashes::Hash;
+use kaspa_math::work::calc_work;
 use kaspa_utils::refs::Refs;
 
-use crate::{
-    model::{
-        services::reachability::ReachabilityService,
-        stores::{
-            ghostdag::{GhostdagData, GhostdagStoreReader, HashKTypeMap, KType},
-            headers::HeaderStoreReader,
-            relations::RelationsStoreReader,
-        },
+use crate::model::{
+    services::reachability::ReachabilityService,
+    stores::{
+        ghostdag::{GhostdagData, GhostdagStoreReader, HashKTypeMap, KType},
+        headers::HeaderStoreReader,
+        relations::RelationsStoreReader,
     },
-    processes::difficulty::calc_work,
 };
 
 use super::ordering::*;
@@ -48,8 +46,8 @@ impl<T: GhostdagStoreReader, S: RelationsStoreReader, U: ReachabilityService, V:
             0,
             Default::default(),
             blockhash::ORIGIN,
-            BlockHashes::new(Vec::new()),
-            BlockHashes::new(Vec::new()),
+            Default::default(),
+            Default::default(),
             HashKTypeMap::new(BlockHashMap::new()),
         )
     }
@@ -59,8 +57,8 @@ impl<T: GhostdagStoreReader, S: RelationsStoreReader, U: ReachabilityService, V:
             0,
             Default::default(),
             0.into(),
-            BlockHashes::new(Vec::new()),
-            BlockHashes::new(Vec::new()),
+            Default::default(),
+            Default::default(),
             HashKTypeMap::new(BlockHashMap::new()),
         ))
     }
@@ -120,9 +118,15 @@ impl<T: GhostdagStoreReader, S: RelationsStoreReader, U: ReachabilityService, V:
             .iter()
             .cloned()
             .map(|hash| if hash.is_origin() { 0.into() } else { calc_work(self.headers_store.get_bits(hash).unwrap()) })
-            .sum();
+            .try_fold(BlueWorkType::ZERO, |sum, work| sum.checked_add(work))
+            .expect("added blue work should not overflow");
 
-        let blue_work = self.ghostdag_store.get_blue_work(selected_parent).unwrap() + added_blue_work;
+        let blue_work = self
+            .ghostdag_store
+            .get_blue_work(selected_parent)
+            .unwrap()
+            .checked_add(added_blue_work)
+            .expect("blue work should not exceed 2^192");
         new_block_data.finalize_score_and_work(blue_score, blue_work);
 
         new_block_data
@@ -1,6 +1,7 @@
 pub mod block_depth;
 pub mod coinbase;
 pub mod difficulty;
+pub mod era_archive;
 pub mod ghostdag;
 pub mod mass;
 pub mod parents_builder;
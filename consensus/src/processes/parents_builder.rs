@@ -207,6 +207,7 @@ mod tests {
     use kaspa_database::prelude::{ReadLock, StoreError, StoreResult};
     use kaspa_hashes::Hash;
     use parking_lot::RwLock;
+    use smallvec::smallvec;
 
     struct HeaderStoreMock {
         map: RwLock<BlockHashMap<HeaderWithBlockLevel>>,
@@ -480,8 +481,9 @@ mod tests {
         }
 
         let reachability_service = MTReachabilityService::new(Arc::new(RwLock::new(reachability_store)));
-        let relations_store =
-            Arc::new(RwLock::new(vec![RelationsStoreMock { children: BlockHashes::new(vec![pruning_point, pp_anticone_block]) }]));
+        let relations_store = Arc::new(RwLock::new(vec![RelationsStoreMock {
+            children: BlockHashes::new(smallvec![pruning_point, pp_anticone_block]),
+        }]));
         let relations_service = MTRelationsService::new(relations_store, 0);
         let parents_manager = ParentsManager::new(250, genesis_hash, headers_store, reachability_service, relations_service);
 
@@ -583,7 +585,7 @@ mod tests {
         }
 
         let reachability_service = MTReachabilityService::new(Arc::new(RwLock::new(reachability_store)));
-        let relations_store = Arc::new(RwLock::new(vec![RelationsStoreMock { children: BlockHashes::new(vec![pruning_point]) }]));
+        let relations_store = Arc::new(RwLock::new(vec![RelationsStoreMock { children: BlockHashes::new(smallvec![pruning_point]) }]));
         let relations_service = MTRelationsService::new(relations_store, 0);
         let parents_manager = ParentsManager::new(250, genesis_hash, headers_store, reachability_service, relations_service);
 
@@ -110,6 +110,10 @@ pub struct PruningProofManager {
     cached_proof: Mutex<Option<CachedPruningPointData<PruningPointProof>>>,
     cached_anticone: Mutex<Option<CachedPruningPointData<PruningPointTrustedData>>>,
 
+    /// The hashes of the proof headers and trusted blocks applied by the last call to `apply_proof`, i.e., the
+    /// blocks which can later be inserted as trusted blocks
+    applied_trusted_set: RwLock<BlockHashSet>,
+
     max_block_level: BlockLevel,
     genesis_hash: Hash,
     pruning_proof_m: u64,
@@ -157,6 +161,8 @@ impl PruningProofManager {
             cached_proof: Mutex::new(None),
             cached_anticone: Mutex::new(None),
 
+            applied_trusted_set: Default::default(),
+
             max_block_level,
             genesis_hash,
             pruning_proof_m,
@@ -196,6 +202,8 @@ impl PruningProofManager {
         let pruning_point_header = proof[0].last().unwrap().clone();
         let pruning_point = pruning_point_header.hash;
 
+        validate_trusted_set(pruning_point, trusted_set, self.ghostdag_k)?;
+
         let proof_zero_set = BlockHashSet::from_iter(proof[0].iter().map(|header| header.hash));
         let mut trusted_gd_map: BlockHashMap<GhostdagData> = BlockHashMap::new();
         for tb in trusted_set.iter() {
@@ -208,6 +216,7 @@ impl PruningProofManager {
         }
 
         proof[0].sort_by(|a, b| a.blue_work.cmp(&b.blue_work));
+        *self.applied_trusted_set.write() = BlockHashSet::from_iter(proof[0].iter().map(|header| header.hash));
         self.populate_reachability_and_headers(&proof);
 
         {
@@ -224,14 +233,15 @@ impl PruningProofManager {
             trace!("Applying level {} from the pruning point proof", level);
             self.ghostdag_stores[level].insert(ORIGIN, self.ghostdag_managers[level].origin_ghostdag_data()).unwrap();
             for header in headers.iter() {
-                let parents = Arc::new(
+                let parents = BlockHashes::new(
                     self.parents_manager
                         .parents_at_level(header, level as BlockLevel)
                         .iter()
                         .copied()
                         .filter(|parent| self.ghostdag_stores[level].has(*parent).unwrap())
                         .collect_vec()
-                        .push_if_empty(ORIGIN),
+                        .push_if_empty(ORIGIN)
+                        .into(),
                 );
 
                 self.relations_stores.write()[level].insert(header.hash, parents.clone()).unwrap();
@@ -279,6 +289,12 @@ impl PruningProofManager {
         Ok(())
     }
 
+    /// Returns whether `hash` was applied along with the pruning point proof, either as a proof header or as part of
+    /// the trusted set, which is required for inserting it as a trusted block
+    pub fn is_in_applied_trusted_set(&self, hash: Hash) -> bool {
+        self.applied_trusted_set.read().contains(&hash)
+    }
+
     fn estimate_proof_unique_size(&self, proof: &PruningPointProof) -> usize {
         let approx_history_size = proof[0][0].daa_score;
         let approx_unique_full_levels = f64::log2(approx_history_size as f64 / self.pruning_proof_m as f64).max(0f64) as usize;
@@ -347,7 +363,7 @@ impl PruningProofManager {
                 reachability_parents.push(parent);
             }
             let reachability_parents_hashes =
-                BlockHashes::new(reachability_parents.iter().map(|parent| parent.hash).collect_vec().push_if_empty(ORIGIN));
+                BlockHashes::new(reachability_parents.iter().map(|parent| parent.hash).collect_vec().push_if_empty(ORIGIN).into());
             let selected_parent = reachability_parents.iter().max().map(|parent| parent.hash).unwrap_or(ORIGIN);
 
             // Prepare batch
@@ -423,7 +439,7 @@ impl PruningProofManager {
             for level in 0..=self.max_block_level {
                 let level = level as usize;
                 reachability::init(reachability_stores[level].write().deref_mut()).unwrap();
-                relations_stores[level].insert_batch(&mut batch, ORIGIN, BlockHashes::new(vec![])).unwrap();
+                relations_stores[level].insert_batch(&mut batch, ORIGIN, Default::default()).unwrap();
                 ghostdag_stores[level].insert(ORIGIN, self.ghostdag_managers[level].origin_ghostdag_data()).unwrap();
             }
 
@@ -456,7 +472,7 @@ impl PruningProofManager {
                     return Err(PruningImportError::PruningProofHeaderWithNoKnownParents(header.hash, level));
                 }
 
-                let parents: BlockHashes = parents.push_if_empty(ORIGIN).into();
+                let parents = BlockHashes::new(parents.push_if_empty(ORIGIN).into());
 
                 if relations_stores[level_idx].has(header.hash).unwrap() {
                     return Err(PruningImportError::PruningProofDuplicateHeaderAtLevel(header.hash, level));
@@ -872,3 +888,93 @@ impl PruningProofManager {
         }
     }
 }
+
+/// Validates that the externally supplied GHOSTDAG data of the trusted set is consistent with the proof and with the
+/// headers it is attached to, before it is written to the stores as is. Note that the trusted set might be pruned of
+/// mergeset blocks which are not part of it (see `TrustedDataPackage::build_trusted_subdag`), in which case the selected
+/// parent is replaced by ORIGIN, so only the relations which survive such pruning can be strictly verified
+fn validate_trusted_set(pruning_point: Hash, trusted_set: &[TrustedBlock], ghostdag_k: KType) -> PruningImportResult<()> {
+    if !trusted_set.iter().any(|tb| tb.block.hash() == pruning_point) {
+        return Err(PruningImportError::TrustedSetMissingPruningPoint(pruning_point));
+    }
+
+    for tb in trusted_set.iter() {
+        let (hash, header, gd) = (tb.block.hash(), &tb.block.header, &tb.ghostdag);
+
+        // Blue score and blue work are committed to by the header, hence by the PoW mined over it
+        if gd.blue_score != header.blue_score || gd.blue_work != header.blue_work {
+            return Err(PruningImportError::TrustedBlockGhostdagMismatch(hash));
+        }
+
+        if !gd.selected_parent.is_origin() {
+            if !header.direct_parents().contains(&gd.selected_parent) {
+                return Err(PruningImportError::TrustedBlockSelectedParentNotAParent(hash, gd.selected_parent));
+            }
+            if gd.mergeset_blues.first() != Some(&gd.selected_parent) {
+                return Err(PruningImportError::TrustedBlockMalformedMergeset(hash));
+            }
+        }
+
+        let blues = BlockHashSet::from_iter(gd.mergeset_blues.iter().copied());
+        if blues.len() != gd.mergeset_blues.len()
+            || blues.len() > ghostdag_k as usize + 1
+            || blues.iter().any(|blue| gd.blues_anticone_sizes.get(blue).is_none())
+            || gd.blues_anticone_sizes.values().any(|&size| size > ghostdag_k)
+            || BlockHashSet::from_iter(gd.mergeset_reds.iter().copied()).len() != gd.mergeset_reds.len()
+            || gd.mergeset_reds.iter().any(|red| blues.contains(red))
+        {
+            return Err(PruningImportError::TrustedBlockMalformedMergeset(hash));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::{block::Block, trusted::ExternalGhostdagData};
+
+    fn trusted_block(hash: u64, parents: &[u64], selected_parent: u64, reds: &[u64]) -> TrustedBlock {
+        let header = Header::from_precomputed_hash(hash.into(), parents.iter().copied().map(Hash::from).collect());
+        let selected_parent = if selected_parent == 0 { ORIGIN } else { selected_parent.into() };
+        let mergeset_blues = if selected_parent.is_origin() { vec![] } else { vec![selected_parent] };
+        let ghostdag = ExternalGhostdagData {
+            blue_score: header.blue_score,
+            blue_work: header.blue_work,
+            selected_parent,
+            blues_anticone_sizes: mergeset_blues.iter().map(|&blue| (blue, 0)).collect(),
+            mergeset_blues,
+            mergeset_reds: reds.iter().copied().map(Hash::from).collect(),
+        };
+        TrustedBlock::new(Block::from_header(header), ghostdag)
+    }
+
+    #[test]
+    fn test_validate_trusted_set() {
+        let k = 18;
+        let valid = || vec![trusted_block(1, &[], 0, &[]), trusted_block(2, &[1], 1, &[]), trusted_block(3, &[1, 2], 2, &[1])];
+        let pruning_point = 2.into();
+        assert!(validate_trusted_set(pruning_point, &valid(), k).is_ok());
+
+        assert!(matches!(validate_trusted_set(10.into(), &valid(), k), Err(PruningImportError::TrustedSetMissingPruningPoint(_))));
+
+        let mut set = valid();
+        set[1].ghostdag.blue_score += 1;
+        assert!(matches!(validate_trusted_set(pruning_point, &set, k), Err(PruningImportError::TrustedBlockGhostdagMismatch(_))));
+
+        let set = vec![trusted_block(1, &[], 0, &[]), trusted_block(2, &[1], 3, &[])];
+        assert!(matches!(
+            validate_trusted_set(pruning_point, &set, k),
+            Err(PruningImportError::TrustedBlockSelectedParentNotAParent(_, _))
+        ));
+
+        // A block both blue and red in the same mergeset
+        let set = vec![trusted_block(1, &[], 0, &[]), trusted_block(2, &[1], 1, &[1])];
+        assert!(matches!(validate_trusted_set(pruning_point, &set, k), Err(PruningImportError::TrustedBlockMalformedMergeset(_))));
+
+        let mut set = valid();
+        set[1].ghostdag.blues_anticone_sizes.clear();
+        assert!(matches!(validate_trusted_set(pruning_point, &set, k), Err(PruningImportError::TrustedBlockMalformedMergeset(_))));
+    }
+}
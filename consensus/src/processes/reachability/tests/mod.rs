@@ -29,6 +29,8 @@ use thiserror::Error;
 
 #[cfg(test)]
 pub mod gen;
+#[cfg(test)]
+pub mod scenario;
 
 /// A struct with fluent API to streamline reachability store building
 pub struct StoreBuilder<'a, T: ReachabilityStore + ?Sized> {
@@ -133,7 +135,7 @@ impl<'a, T: ReachabilityStore + ?Sized, S: RelationsStore + ChildrenStore + ?Siz
         let mergeset = unordered_mergeset_without_selected_parent(self.relations, self.reachability, selected_parent, &block.parents);
         add_block(self.reachability, block.hash, selected_parent, &mut mergeset.iter().cloned()).unwrap();
         hint_virtual_selected_parent(self.reachability, block.hash).unwrap();
-        self.relations.insert(block.hash, BlockHashes::new(block.parents)).unwrap();
+        self.relations.insert(block.hash, BlockHashes::new(block.parents.into())).unwrap();
         self
     }
 
@@ -0,0 +1,182 @@
+//!
+//! A small DSL for encoding reachability scenarios over named blocks
+//!
+//! ```ignore
+//! let dag = DagScenario::parse("G; A: G; B: G; C: A B");
+//! dag.assert_past("A", "C").assert_anticone("A", "B").assert_chain_ancestor("G", "C").validate();
+//! ```
+//!
+use super::{super::inquirer::*, *};
+use crate::model::stores::{reachability::MemoryReachabilityStore, relations::MemoryRelationsStore};
+use kaspa_consensus_core::blockhash::ORIGIN;
+use std::collections::HashMap;
+
+/// Builds reachability and relations state in memory out of named blocks, and asserts ancestry queries by name.
+/// Blocks are added in declaration order, and a block declared with no parents is a child of origin
+pub struct DagScenario {
+    reachability: MemoryReachabilityStore,
+    relations: MemoryRelationsStore,
+    /// The hash of each block name, assigned incrementally
+    hashes: HashMap<String, Hash>,
+    /// Block names in declaration order
+    names: Vec<String>,
+}
+
+impl DagScenario {
+    pub fn new() -> Self {
+        Self::with_capacity(Interval::maximal())
+    }
+
+    /// Creates a scenario whose origin has the given interval `capacity`. Small capacities make interval
+    /// exhaustion and reindexing edge cases reachable with a handful of blocks
+    pub fn with_capacity(capacity: Interval) -> Self {
+        let mut reachability = MemoryReachabilityStore::new();
+        let mut relations = MemoryRelationsStore::new();
+        init_with_params(&mut reachability, ORIGIN, capacity).unwrap();
+        relations_init(&mut relations);
+        Self { reachability, relations, hashes: HashMap::new(), names: Vec::new() }
+    }
+
+    /// Builds a scenario out of `spec`, a list of blocks separated by semicolons or new lines. Each block is given
+    /// as `name: parent parent ...`, or as a bare `name` for a child of origin
+    pub fn parse(spec: &str) -> Self {
+        let mut scenario = Self::new();
+        scenario.extend(spec);
+        scenario
+    }
+
+    /// Adds the blocks of `spec` to the scenario, see [`DagScenario::parse`]
+    pub fn extend(&mut self, spec: &str) -> &mut Self {
+        for entry in spec.split([';', '\n']).map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (name, parents) = entry.split_once(':').unwrap_or((entry, ""));
+            self.block(name.trim(), &parents.split_whitespace().collect_vec());
+        }
+        self
+    }
+
+    /// Adds the block `name` with the given `parents`, which must have been added already
+    pub fn block(&mut self, name: &str, parents: &[&str]) -> &mut Self {
+        assert!(!self.hashes.contains_key(name), "block {name} is declared twice");
+        let parents = match parents.is_empty() {
+            true => vec![ORIGIN],
+            false => parents.iter().map(|parent| self.hash(parent)).collect(),
+        };
+        let hash = Hash::from_u64_word(self.names.len() as u64 + 1);
+        self.hashes.insert(name.to_owned(), hash);
+        self.names.push(name.to_owned());
+        DagBuilder::new(&mut self.reachability, &mut self.relations).add_block(DagBlock::new(hash, parents));
+        self
+    }
+
+    /// Deletes the block `name` from the reachability and relations state
+    pub fn delete(&mut self, name: &str) -> &mut Self {
+        let hash = self.hash(name);
+        DagBuilder::new(&mut self.reachability, &mut self.relations).delete_block(hash);
+        self.hashes.remove(name);
+        self.names.retain(|n| n != name);
+        self
+    }
+
+    pub fn hash(&self, name: &str) -> Hash {
+        *self.hashes.get(name).unwrap_or_else(|| panic!("unknown block {name}"))
+    }
+
+    pub fn is_past(&self, block: &str, other: &str) -> bool {
+        is_dag_ancestor_of(&self.reachability, self.hash(block), self.hash(other)).unwrap()
+    }
+
+    pub fn is_chain_ancestor(&self, block: &str, other: &str) -> bool {
+        is_chain_ancestor_of(&self.reachability, self.hash(block), self.hash(other)).unwrap()
+    }
+
+    /// Asserts that `block` is in the (strict) past of `other`
+    pub fn assert_past(&self, block: &str, other: &str) -> &Self {
+        assert!(block != other && self.is_past(block, other), "expected {block} to be in the past of {other}");
+        assert!(!self.is_past(other, block), "expected {other} not to be in the past of {block}");
+        self
+    }
+
+    /// Asserts that `block` and `other` are in the anticone of each other
+    pub fn assert_anticone(&self, block: &str, other: &str) -> &Self {
+        assert!(!self.is_past(block, other) && !self.is_past(other, block), "expected {block} and {other} to be in anticone");
+        self
+    }
+
+    /// Asserts that `block` is in the selected chain of `other`, a block being a chain ancestor of itself
+    pub fn assert_chain_ancestor(&self, block: &str, other: &str) -> &Self {
+        assert!(self.is_chain_ancestor(block, other), "expected {block} to be a chain ancestor of {other}");
+        self
+    }
+
+    /// Asserts that `block` is not in the selected chain of `other`
+    pub fn assert_not_chain_ancestor(&self, block: &str, other: &str) -> &Self {
+        assert!(!self.is_chain_ancestor(block, other), "expected {block} not to be a chain ancestor of {other}");
+        self
+    }
+
+    /// Validates the tree intervals and the relations, and asserts the answers of the reachability oracle for all
+    /// pairs of blocks against explicit DAG and chain traversals
+    pub fn validate(&self) -> &Self {
+        self.reachability.validate_intervals(ORIGIN).unwrap();
+        validate_relations(&self.relations).unwrap();
+        let hashes = self.names.iter().map(|name| self.hash(name)).collect_vec();
+        build_transitive_closure(&self.relations, &self.reachability, &hashes);
+        build_chain_closure(&self.reachability, &hashes);
+        self
+    }
+}
+
+impl Default for DagScenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dag_scenario() {
+        let mut dag = DagScenario::parse(
+            "
+            G
+            A: G; B: G
+            C: A
+            D: C B
+            E: B
+            F: D E
+            ",
+        );
+        dag.assert_past("G", "F")
+            .assert_past("A", "D")
+            .assert_past("B", "D")
+            .assert_past("E", "F")
+            .assert_anticone("A", "B")
+            .assert_anticone("D", "E")
+            .assert_chain_ancestor("G", "F")
+            .assert_chain_ancestor("F", "F")
+            .assert_not_chain_ancestor("B", "D")
+            .validate();
+
+        dag.delete("F").extend("H: D; I: H E").assert_past("B", "I").assert_anticone("H", "E").validate();
+    }
+
+    #[test]
+    fn test_dag_scenario_reindexing() {
+        // A tight origin capacity exhausts the intervals of a long chain and of a wide fan-out, forcing reindexing
+        let mut dag = DagScenario::with_capacity(Interval::new(1, 128));
+        dag.block("G", &[]);
+        let mut tip = "G".to_owned();
+        for i in 0..30 {
+            let name = format!("C{i}");
+            dag.block(&name, &[&tip]);
+            tip = name;
+        }
+        for i in 0..30 {
+            dag.block(&format!("S{i}"), &["C10"]);
+        }
+        dag.block("M", &[&tip, "S29"]);
+        dag.assert_past("S29", "M").assert_anticone("S0", "M").assert_chain_ancestor("C29", "M").assert_not_chain_ancestor("S29", "M");
+        dag.validate();
+    }
+}
@@ -15,7 +15,7 @@ use rocksdb::WriteBatch;
 /// Initializes this relations store with an `origin` root
 pub fn init<S: RelationsStore + ChildrenStore + ?Sized>(relations: &mut S) {
     if !relations.has(ORIGIN).unwrap() {
-        relations.insert(ORIGIN, BlockHashes::new(vec![])).unwrap();
+        relations.insert(ORIGIN, Default::default()).unwrap();
     }
 }
 
@@ -135,7 +135,7 @@ pub trait RelationsStoreExtensions: RelationsStore + ChildrenStore {
         let replaced_index =
             parents.iter().copied().position(|h| h == replaced_parent).expect("callers must ensure replaced is a parent");
         parents.swap_remove(replaced_index);
-        parents.extend(replace_with);
+        parents.extend_from_slice(replace_with);
         self.set_parents(&mut writer, hash, BlockHashes::new(parents))?;
 
         for parent in replace_with.iter().cloned() {
@@ -155,7 +155,7 @@ mod tests {
     use kaspa_core::assert_match;
     use kaspa_database::prelude::ConnBuilder;
     use kaspa_database::{create_temp_db, prelude::MemoryWriter};
-    use std::sync::Arc;
+    use smallvec::smallvec;
 
     #[test]
     fn test_delete_level_relations_zero_cache() {
@@ -163,8 +163,8 @@ mod tests {
         let cache_size = 0;
         let mut relations = DbRelationsStore::new(db.clone(), 0, cache_size);
         relations.insert(ORIGIN, Default::default()).unwrap();
-        relations.insert(1.into(), Arc::new(vec![ORIGIN])).unwrap();
-        relations.insert(2.into(), Arc::new(vec![1.into()])).unwrap();
+        relations.insert(1.into(), BlockHashes::new(smallvec![ORIGIN])).unwrap();
+        relations.insert(2.into(), BlockHashes::new(smallvec![1.into()])).unwrap();
 
         assert_eq!(relations.get_parents(ORIGIN).unwrap().as_slice(), []);
         assert_eq!(
@@ -21,6 +21,7 @@ pub struct TransactionValidator {
     ghostdag_k: ghostdag::KType,
     coinbase_payload_script_public_key_max_len: u8,
     coinbase_maturity: u64,
+    introspection_opcodes: bool,
     sig_cache: Cache<SigCacheKey, bool>,
 }
 
@@ -33,6 +34,7 @@ impl TransactionValidator {
         ghostdag_k: ghostdag::KType,
         coinbase_payload_script_public_key_max_len: u8,
         coinbase_maturity: u64,
+        introspection_opcodes: bool,
         counters: Arc<TxScriptCacheCounters>,
     ) -> Self {
         Self {
@@ -43,6 +45,7 @@ impl TransactionValidator {
             ghostdag_k,
             coinbase_payload_script_public_key_max_len,
             coinbase_maturity,
+            introspection_opcodes,
             sig_cache: Cache::with_counters(10_000, counters),
         }
     }
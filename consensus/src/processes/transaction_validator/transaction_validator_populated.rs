@@ -124,7 +124,8 @@ impl TransactionValidator {
         let mut reused_values = SigHashReusedValues::new();
         for (i, (input, entry)) in tx.populated_inputs().enumerate() {
             let mut engine = TxScriptEngine::from_transaction_input(tx, input, i, entry, &mut reused_values, &self.sig_cache)
-                .map_err(TxRuleError::SignatureInvalid)?;
+                .map_err(TxRuleError::SignatureInvalid)?
+                .with_introspection_opcodes(self.introspection_opcodes);
             engine.execute().map_err(TxRuleError::SignatureInvalid)?;
         }
 
@@ -161,6 +162,7 @@ mod tests {
             params.ghostdag_k,
             params.coinbase_payload_script_public_key_max_len,
             params.coinbase_maturity,
+            params.introspection_opcodes,
             Default::default(),
         );
 
@@ -222,6 +224,7 @@ mod tests {
             params.ghostdag_k,
             params.coinbase_payload_script_public_key_max_len,
             params.coinbase_maturity,
+            params.introspection_opcodes,
             Default::default(),
         );
 
@@ -284,6 +287,7 @@ mod tests {
             params.ghostdag_k,
             params.coinbase_payload_script_public_key_max_len,
             params.coinbase_maturity,
+            params.introspection_opcodes,
             Default::default(),
         );
 
@@ -346,6 +350,7 @@ mod tests {
             params.ghostdag_k,
             params.coinbase_payload_script_public_key_max_len,
             params.coinbase_maturity,
+            params.introspection_opcodes,
             Default::default(),
         );
 
@@ -409,6 +414,7 @@ mod tests {
             params.ghostdag_k,
             params.coinbase_payload_script_public_key_max_len,
             params.coinbase_maturity,
+            params.introspection_opcodes,
             Default::default(),
         );
 
@@ -472,6 +478,7 @@ mod tests {
             params.ghostdag_k,
             params.coinbase_payload_script_public_key_max_len,
             params.coinbase_maturity,
+            params.introspection_opcodes,
             Default::default(),
         );
 
@@ -535,6 +542,7 @@ mod tests {
             params.ghostdag_k,
             params.coinbase_payload_script_public_key_max_len,
             params.coinbase_maturity,
+            params.introspection_opcodes,
             Default::default(),
         );
 
@@ -589,6 +597,7 @@ mod tests {
             params.ghostdag_k,
             params.coinbase_payload_script_public_key_max_len,
             params.coinbase_maturity,
+            params.introspection_opcodes,
             Default::default(),
         );
 
@@ -173,6 +173,7 @@ mod tests {
             params.ghostdag_k,
             params.coinbase_payload_script_public_key_max_len,
             params.coinbase_maturity,
+            params.introspection_opcodes,
             Default::default(),
         );
 
@@ -0,0 +1,13 @@
+use crate::imports::*;
+use crate::result::Result;
+use kaspa_consensus_core::acceptance_proof::TransactionAcceptanceProof;
+use serde_wasm_bindgen::from_value;
+
+/// `verifyTransactionAcceptanceProof()` verifies a proof returned by the `getTransactionAcceptanceProof()` RPC method
+/// and returns the hash of the last chain block of the proof. The acceptance is proven once this block is found on
+/// the selected chain tracked by the caller (see the `getHeaders()` RPC method).
+#[wasm_bindgen(js_name = "verifyTransactionAcceptanceProof")]
+pub fn verify_transaction_acceptance_proof(proof: JsValue) -> Result<String> {
+    let proof: TransactionAcceptanceProof = from_value(proof)?;
+    Ok(proof.verify()?.to_string())
+}
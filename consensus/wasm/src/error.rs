@@ -39,6 +39,9 @@ pub enum Error {
 
     #[error(transparent)]
     NetworkTypeError(#[from] kaspa_consensus_core::network::NetworkTypeError),
+
+    #[error(transparent)]
+    AcceptanceProofError(#[from] kaspa_consensus_core::acceptance_proof::AcceptanceProofError),
 }
 
 // unsafe impl Send for Error {}
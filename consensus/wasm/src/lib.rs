@@ -1,3 +1,4 @@
+pub mod acceptance_proof;
 pub mod error;
 mod imports;
 pub mod input;
@@ -12,6 +13,7 @@ pub mod txscript;
 pub mod utils;
 pub mod utxo;
 
+pub use acceptance_proof::*;
 pub use input::*;
 pub use keypair::*;
 pub use outpoint::*;
@@ -0,0 +1,18 @@
+use std::process::Command;
+
+fn main() {
+    // Embed the commit the node is built from, if built out of a git checkout
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty());
+    if let Some(git_hash) = git_hash {
+        println!("cargo:rustc-env=KASPAD_GIT_HASH={git_hash}");
+    }
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/refs/heads");
+}
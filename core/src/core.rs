@@ -1,12 +1,31 @@
-use crate::service::Service;
+use crate::service::{dependency_order, Service, ServiceHealth};
 use crate::signals::Shutdown;
-use crate::trace;
+use crate::{trace, warn};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The interval at which the readiness of the dependencies of a service is polled before starting it
+const READINESS_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+struct BoundService {
+    service: Arc<dyn Service>,
+    /// Dependencies declared when binding the service, on top of those declared by the service itself
+    dependencies: Vec<&'static str>,
+}
+
+impl BoundService {
+    fn dependencies(&self) -> Vec<&'static str> {
+        let mut dependencies = self.service.clone().dependencies();
+        dependencies.extend(self.dependencies.iter().copied());
+        dependencies
+    }
+}
 
 pub struct Core {
     pub keep_running: AtomicBool,
-    services: Mutex<Vec<Arc<dyn Service>>>,
+    started: AtomicBool,
+    services: Mutex<Vec<BoundService>>,
 }
 
 impl Default for Core {
@@ -17,18 +36,26 @@ impl Default for Core {
 
 impl Core {
     pub fn new() -> Core {
-        Core { keep_running: AtomicBool::new(true), services: Mutex::new(Vec::new()) }
+        Core { keep_running: AtomicBool::new(true), started: AtomicBool::new(false), services: Mutex::new(Vec::new()) }
     }
 
     pub fn bind<T>(&self, service: Arc<T>)
     where
         T: Service,
     {
-        self.services.lock().unwrap().push(service);
+        self.bind_with_dependencies(service, &[]);
+    }
+
+    /// Binds `service`, which is started only once the services identified by `dependencies` are ready
+    pub fn bind_with_dependencies<T>(&self, service: Arc<T>, dependencies: &[&'static str])
+    where
+        T: Service,
+    {
+        self.services.lock().unwrap().push(BoundService { service, dependencies: dependencies.to_vec() });
     }
 
     pub fn find(&self, ident: &'static str) -> Option<Arc<dyn Service>> {
-        self.services.lock().unwrap().iter().find(|s| (*s).clone().ident() == ident).cloned()
+        self.services.lock().unwrap().iter().map(|s| &s.service).find(|s| (*s).clone().ident() == ident).cloned()
     }
 
     /// Starts all services and blocks waiting to join them. For performing other operations in between
@@ -37,16 +64,78 @@ impl Core {
         self.join(self.start());
     }
 
-    /// Start all services and return `std::thread` join handles
+    /// Start all services in dependency order and return `std::thread` join handles. Each service is started once
+    /// all its dependencies report being ready, so this call blocks until the dependencies are ready. Services left
+    /// when the core is shut down in the meantime are not started
     pub fn start(self: &Arc<Core>) -> Vec<std::thread::JoinHandle<()>> {
         let mut workers = Vec::new();
-        for service in self.services.lock().unwrap().iter() {
-            workers.append(&mut service.clone().start(self.clone()));
+        for (service, dependencies) in self.ordered_services() {
+            if !self.wait_for_dependencies(service.clone().ident(), &dependencies) {
+                break;
+            }
+            workers.append(&mut service.start(self.clone()));
         }
         trace!("core is starting {} workers", workers.len());
+        self.started.store(true, Ordering::SeqCst);
         workers
     }
 
+    /// Returns the bound services along with their dependencies, sorted so that each service follows its
+    /// dependencies, and otherwise by binding order. Panics if a dependency is not bound or if dependencies are cyclic
+    fn ordered_services(&self) -> Vec<(Arc<dyn Service>, Vec<&'static str>)> {
+        let services =
+            self.services.lock().unwrap().iter().map(|bound| (bound.service.clone(), bound.dependencies())).collect::<Vec<_>>();
+        let idents =
+            services.iter().map(|(service, dependencies)| (service.clone().ident(), dependencies.clone())).collect::<Vec<_>>();
+        dependency_order(&idents).into_iter().map(|i| services[i].clone()).collect()
+    }
+
+    /// Blocks until all `dependencies` of the service `ident` are ready. Returns `false` if the core is shut down
+    /// in the meantime, shutting it down if a dependency stopped before becoming ready
+    fn wait_for_dependencies(self: &Arc<Core>, ident: &'static str, dependencies: &[&'static str]) -> bool {
+        let dependencies = dependencies.iter().filter_map(|&dependency| self.find(dependency)).collect::<Vec<_>>();
+        loop {
+            if !self.keep_running.load(Ordering::SeqCst) {
+                return false;
+            }
+            let health = ServiceHealth::aggregate(dependencies.iter().map(|dependency| dependency.clone().health()));
+            match health {
+                health if health.is_ready() => return true,
+                ServiceHealth::Stopped => {
+                    warn!("A dependency of service {} stopped before becoming ready, shutting down", ident);
+                    self.shutdown();
+                    return false;
+                }
+                _ => std::thread::sleep(READINESS_POLL_INTERVAL),
+            }
+        }
+    }
+
+    /// Returns the health of each bound service and of its sub-services. Services are reported as `Starting`
+    /// until the core has started them and as `Stopped` once the core is shutting down
+    pub fn health_report(&self) -> Vec<(&'static str, ServiceHealth)> {
+        let state = if !self.keep_running.load(Ordering::SeqCst) {
+            Some(ServiceHealth::Stopped)
+        } else if !self.started.load(Ordering::SeqCst) {
+            Some(ServiceHealth::Starting)
+        } else {
+            None
+        };
+        let report = self.services.lock().unwrap().iter().flat_map(|bound| bound.service.clone().health_report()).collect::<Vec<_>>();
+        match state {
+            Some(state) => report.into_iter().map(|(ident, _)| (ident, state)).collect(),
+            None => report,
+        }
+    }
+
+    /// Returns the aggregated health of all bound services, to be used as the readiness state of the node
+    pub fn health(&self) -> ServiceHealth {
+        if !self.keep_running.load(Ordering::SeqCst) {
+            return ServiceHealth::Stopped;
+        }
+        ServiceHealth::aggregate(self.health_report().into_iter().map(|(_, health)| health))
+    }
+
     /// Join workers previously returned from `start`
     pub fn join(&self, workers: Vec<std::thread::JoinHandle<()>>) {
         for worker in workers {
@@ -66,6 +155,7 @@ impl Core {
 }
 
 impl Shutdown for Core {
+    /// Stops all services in reverse dependency order, so that each service is stopped before its dependencies
     fn shutdown(self: &Arc<Core>) {
         let keep_running = self.keep_running.load(Ordering::SeqCst);
         if !keep_running {
@@ -75,14 +165,86 @@ impl Shutdown for Core {
         trace!("signaling core shutdown...");
         self.keep_running.store(false, Ordering::SeqCst);
 
-        {
-            for service in self.services.lock().unwrap().iter() {
-                let ident = service.clone().ident();
-                trace!("shutting down: {}", ident);
-                service.clone().stop();
-            }
+        for (service, _) in self.ordered_services().into_iter().rev() {
+            let ident = service.clone().ident();
+            trace!("shutting down: {}", ident);
+            service.stop();
         }
 
         trace!("core is shutting down...");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::JoinHandle;
+
+    /// A service becoming ready after a delay and recording its lifecycle events
+    struct MockService {
+        ident: &'static str,
+        ready_after: Duration,
+        ready: Arc<AtomicBool>,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MockService {
+        fn new(ident: &'static str, ready_after: Duration, events: &Arc<Mutex<Vec<String>>>) -> Arc<Self> {
+            Arc::new(Self { ident, ready_after, ready: Default::default(), events: events.clone() })
+        }
+    }
+
+    impl Service for MockService {
+        fn ident(self: Arc<Self>) -> &'static str {
+            self.ident
+        }
+
+        fn start(self: Arc<Self>, _core: Arc<Core>) -> Vec<JoinHandle<()>> {
+            self.events.lock().unwrap().push(format!("start {}", self.ident));
+            vec![std::thread::spawn(move || {
+                std::thread::sleep(self.ready_after);
+                self.ready.store(true, Ordering::SeqCst);
+            })]
+        }
+
+        fn stop(self: Arc<Self>) {
+            self.events.lock().unwrap().push(format!("stop {}", self.ident));
+        }
+
+        fn health(self: Arc<Self>) -> ServiceHealth {
+            match self.ready.load(Ordering::SeqCst) {
+                true => ServiceHealth::Ready,
+                false => ServiceHealth::Starting,
+            }
+        }
+    }
+
+    #[test]
+    fn test_ordered_startup() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let core = Arc::new(Core::new());
+        // Bound in reverse order of their dependencies
+        core.bind_with_dependencies(MockService::new("rpc", Duration::ZERO, &events), &["consensus", "index"]);
+        core.bind_with_dependencies(MockService::new("index", Duration::ZERO, &events), &["consensus"]);
+        core.bind(MockService::new("consensus", Duration::from_millis(100), &events));
+
+        let workers = core.start();
+        // Each service was started only once its dependencies were ready
+        assert_eq!(*events.lock().unwrap(), vec!["start consensus", "start index", "start rpc"]);
+        assert!(core.find("consensus").unwrap().health().is_ready());
+
+        core.shutdown();
+        core.join(workers);
+        assert_eq!(events.lock().unwrap()[3..], ["stop rpc", "stop index", "stop consensus"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cyclic dependencies")]
+    fn test_cyclic_dependencies() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let core = Arc::new(Core::new());
+        core.bind_with_dependencies(MockService::new("a", Duration::ZERO, &events), &["b"]);
+        core.bind_with_dependencies(MockService::new("b", Duration::ZERO, &events), &["a"]);
+        core.start();
+    }
+}
@@ -5,3 +5,8 @@ pub fn version() -> &'static str {
 pub fn name() -> &'static str {
     "kaspad"
 }
+
+/// The abbreviated hash of the commit the node was built from, if built out of a git checkout
+pub fn git_hash() -> Option<&'static str> {
+    option_env!("KASPAD_GIT_HASH")
+}
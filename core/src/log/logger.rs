@@ -76,6 +76,19 @@ impl Builder {
         self.parse_specs(expression)
     }
 
+    /// Checks that all the specs of `expression` are valid, whereas parsing skips invalid specs
+    pub fn validate_expression(expression: &str) -> Result<(), LogError> {
+        for spec in expression.split(',').map(|x| x.trim()).filter(|x| !x.is_empty()) {
+            let mut parts = spec.split('=');
+            match (parts.next(), parts.next().map(|x| x.trim()), parts.next()) {
+                (Some(_), None | Some(""), None) => {}
+                (Some(_), Some(level), None) if level.parse::<LevelFilter>().is_ok() => {}
+                _ => return Err(LogError::ParseLoggerSpecError(spec.to_string())),
+            }
+        }
+        Ok(())
+    }
+
     fn parse_specs(&mut self, expression: &str) -> &mut Self {
         for spec in expression.split(',').map(|x| x.trim()) {
             if spec.is_empty() {
@@ -37,8 +37,12 @@ cfg_if::cfg_if! {
     }
 }
 
+/// The handle of the logger initialized by [`init_logger`] along with its log directory, kept for reloading its filters
 #[cfg(not(target_arch = "wasm32"))]
-pub fn init_logger(log_dir: Option<&str>, filters: &str) {
+static LOGGER: std::sync::Mutex<Option<(log4rs::Handle, Option<String>)>> = std::sync::Mutex::new(None);
+
+#[cfg(not(target_arch = "wasm32"))]
+fn build_logger_config(log_dir: Option<&str>, filters: &str) -> log4rs::Config {
     use crate::log::appender::AppenderSpec;
     use log4rs::{config::Root, Config};
     use std::iter::once;
@@ -56,7 +60,7 @@ pub fn init_logger(log_dir: Option<&str>, filters: &str) {
         log_dir.map(|x| AppenderSpec::roller(ERR_LOG_FILE_APPENDER, Some(LevelFilter::Warn), x, ERR_LOG_FILE_NAME));
     let appenders = once(&mut stdout_appender).chain(&mut file_appender).chain(&mut err_file_appender).map(|x| x.appender());
 
-    let config = Config::builder()
+    Config::builder()
         .appenders(appenders)
         .loggers(loggers.items())
         .build(
@@ -64,11 +68,33 @@ pub fn init_logger(log_dir: Option<&str>, filters: &str) {
                 .appenders(once(&stdout_appender).chain(&file_appender).chain(&err_file_appender).map(|x| x.name))
                 .build(loggers.root_level()),
         )
-        .unwrap();
+        .unwrap()
+}
 
-    let _handle = log4rs::init_config(config).unwrap();
+#[cfg(not(target_arch = "wasm32"))]
+pub fn init_logger(log_dir: Option<&str>, filters: &str) {
+    let handle = log4rs::init_config(build_logger_config(log_dir, filters)).unwrap();
+    *LOGGER.lock().unwrap() = Some((handle, log_dir.map(String::from)));
 
-    set_log_level(level);
+    set_log_level(LevelFilter::Info);
+}
+
+/// Replaces the filters of the logger initialized by [`init_logger`] while keeping its appenders. Returns
+/// `false` if no such logger was initialized
+#[cfg(not(target_arch = "wasm32"))]
+pub fn reload_logger_filters(filters: &str) -> bool {
+    let logger = LOGGER.lock().unwrap();
+    let Some((handle, log_dir)) = logger.as_ref() else {
+        return false;
+    };
+    handle.set_config(build_logger_config(log_dir.as_deref(), filters));
+    true
+}
+
+/// Checks that all the specs of the logger filters are valid, since invalid specs are otherwise skipped
+#[cfg(not(target_arch = "wasm32"))]
+pub fn validate_logger_filters(filters: &str) -> Result<(), String> {
+    logger::Builder::validate_expression(filters).map_err(|err| err.to_string())
 }
 
 /// Tries to init the global logger, but does not panic if it was already setup.
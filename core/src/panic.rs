@@ -1,13 +1,46 @@
-use std::{panic, process};
+use std::{
+    any::Any,
+    cell::Cell,
+    panic::{self, AssertUnwindSafe},
+    process, thread,
+};
 
-/// Configures the panic hook to exit the program on every panic
+thread_local! {
+    /// Whether panics on the current thread are caught by [`catch_recoverable_panic`] rather than exiting the program
+    static RECOVERABLE_PANICS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Configures the panic hook to exit the program on every panic, except for panics raised within
+/// [`catch_recoverable_panic`]
 pub fn configure_panic() {
     let default_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
         // Invoke the default hook and exit the process
         default_hook(panic_info);
+        if RECOVERABLE_PANICS.with(Cell::get) {
+            return;
+        }
         println!("Exiting...");
         // TODO: setup a wait time and fold the log system properly
         process::exit(1);
     }));
 }
+
+/// Runs `f`, returning the payload of any panic it raises on the current thread instead of exiting the program. Only meant
+/// for code able to recover from its own panics, since panics on other threads (e.g. of a thread-pool `f` dispatches to)
+/// still exit the program
+pub fn catch_recoverable_panic<R>(f: impl FnOnce() -> R) -> thread::Result<R> {
+    let outer = RECOVERABLE_PANICS.with(|recoverable| recoverable.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    RECOVERABLE_PANICS.with(|recoverable| recoverable.set(outer));
+    result
+}
+
+/// Returns the message of a panic from its payload, or an empty string if the payload is not a message
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_default()
+}
@@ -1,9 +1,122 @@
 use crate::core::Core;
 use intertrait::CastFromSync;
-use std::{sync::Arc, thread::JoinHandle};
+use std::{collections::HashSet, fmt::Display, sync::Arc, thread::JoinHandle};
+
+/// The health state of a service as reported to readiness probes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceHealth {
+    /// The service was started but cannot serve requests yet
+    Starting,
+    /// The service is fully operational
+    Ready,
+    /// The service is operational with limited functionality
+    Degraded,
+    /// The service was stopped or is shutting down
+    Stopped,
+}
+
+impl ServiceHealth {
+    /// Whether the service is able to serve requests, possibly with limited functionality
+    pub fn is_ready(self) -> bool {
+        matches!(self, Self::Ready | Self::Degraded)
+    }
+
+    /// Returns the aggregated health of several services, which is the least healthy state among them
+    pub fn aggregate<I: IntoIterator<Item = ServiceHealth>>(iter: I) -> Self {
+        iter.into_iter().max_by_key(|health| health.severity()).unwrap_or(Self::Ready)
+    }
+
+    fn severity(self) -> u8 {
+        match self {
+            Self::Ready => 0,
+            Self::Degraded => 1,
+            Self::Starting => 2,
+            Self::Stopped => 3,
+        }
+    }
+}
+
+impl Display for ServiceHealth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Starting => "starting",
+            Self::Ready => "ready",
+            Self::Degraded => "degraded",
+            Self::Stopped => "stopped",
+        };
+        write!(f, "{}", s)
+    }
+}
 
 pub trait Service: CastFromSync {
     fn ident(self: Arc<Self>) -> &'static str;
     fn start(self: Arc<Self>, core: Arc<Core>) -> Vec<JoinHandle<()>>;
     fn stop(self: Arc<Self>);
+
+    /// The health of a started service. Services which are operational once started need not override it
+    fn health(self: Arc<Self>) -> ServiceHealth {
+        ServiceHealth::Ready
+    }
+
+    /// The idents of the services which must be ready before this service is started. The [`Core`] starts services
+    /// in dependency order and stops them in reverse order
+    fn dependencies(self: Arc<Self>) -> Vec<&'static str> {
+        vec![]
+    }
+
+    /// The health of the service along with any sub-services it manages, identified by name
+    fn health_report(self: Arc<Self>) -> Vec<(&'static str, ServiceHealth)> {
+        vec![(self.clone().ident(), self.health())]
+    }
+}
+
+/// Returns the positions of `services`, given as idents along with the idents they depend on, sorted so that each
+/// service follows its dependencies, and otherwise by registration order. A dependency covers all the services
+/// sharing its ident. Panics if a dependency is not registered or if dependencies are cyclic
+pub(crate) fn dependency_order(services: &[(&'static str, Vec<&'static str>)]) -> Vec<usize> {
+    for (ident, dependencies) in services.iter() {
+        if let Some(unknown) = dependencies.iter().find(|&&dependency| !services.iter().any(|(other, _)| *other == dependency)) {
+            panic!("service {ident} depends on {unknown} which is not registered");
+        }
+    }
+
+    let mut ordered = Vec::with_capacity(services.len());
+    let mut placed = HashSet::with_capacity(services.len());
+    while ordered.len() < services.len() {
+        // Place the first service by registration order whose dependencies were all placed
+        let is_placed =
+            |dependency: &&'static str| services.iter().enumerate().all(|(i, (ident, _))| ident != dependency || placed.contains(&i));
+        let Some(next) = (0..services.len()).find(|i| !placed.contains(i) && services[*i].1.iter().all(is_placed)) else {
+            let cyclic = (0..services.len()).filter(|i| !placed.contains(i)).map(|i| services[i].0).collect::<Vec<_>>();
+            panic!("cyclic dependencies between the services {cyclic:?}");
+        };
+        placed.insert(next);
+        ordered.push(next);
+    }
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_aggregation() {
+        use ServiceHealth::*;
+        assert_eq!(ServiceHealth::aggregate([]), Ready);
+        assert_eq!(ServiceHealth::aggregate([Ready, Degraded, Ready]), Degraded);
+        assert_eq!(ServiceHealth::aggregate([Degraded, Starting]), Starting);
+        assert_eq!(ServiceHealth::aggregate([Starting, Stopped, Ready]), Stopped);
+        assert!(Degraded.is_ready() && !Starting.is_ready());
+    }
+
+    #[test]
+    fn test_dependency_order() {
+        let services = [("rpc", vec!["index", "notify"]), ("wrpc", vec!["rpc"]), ("index", vec!["notify"]), ("notify", vec![])];
+        assert_eq!(dependency_order(&services), vec![3, 2, 0, 1]);
+
+        // A dependency covers all the services sharing its ident
+        let services = [("client", vec!["server"]), ("server", vec![]), ("server", vec!["backend"]), ("backend", vec![])];
+        assert_eq!(dependency_order(&services), vec![1, 3, 2, 0]);
+    }
 }
@@ -1,20 +1,32 @@
 use crate::{signals::Shutdown, task::service::AsyncServiceResult};
 use futures_util::future::{select_all, try_join_all};
 use kaspa_core::core::Core;
-use kaspa_core::service::Service;
+use kaspa_core::service::{dependency_order, Service, ServiceHealth};
 use kaspa_core::task::service::AsyncService;
-use kaspa_core::trace;
+use kaspa_core::{trace, warn};
 use std::{
     sync::{Arc, Mutex},
     thread::{self, JoinHandle as ThreadJoinHandle},
+    time::Duration,
 };
 use tokio::task::JoinHandle as TaskJoinHandle;
 
+/// The interval at which the readiness of the dependencies of an async service is polled before starting it
+const READINESS_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+struct RegisteredService {
+    service: Arc<dyn AsyncService>,
+    /// The idents of the services which must be ready before this service is started
+    dependencies: Vec<&'static str>,
+}
+
 /// AsyncRuntime registers async services and provides
 /// a tokio Runtime to run them.
 pub struct AsyncRuntime {
     threads: usize,
-    services: Mutex<Vec<Arc<dyn AsyncService>>>,
+    services: Mutex<Vec<RegisteredService>>,
+    /// `Starting` until all services were spawned, and `Stopped` once any of them returned
+    lifecycle: Mutex<ServiceHealth>,
 }
 
 impl Default for AsyncRuntime {
@@ -29,15 +41,57 @@ impl AsyncRuntime {
 
     pub fn new(threads: usize) -> Self {
         trace!("Creating the async-runtime service");
-        Self { threads, services: Mutex::new(Vec::new()) }
+        Self { threads, services: Mutex::new(Vec::new()), lifecycle: Mutex::new(ServiceHealth::Starting) }
     }
 
     pub fn register<T>(&self, service: Arc<T>)
+    where
+        T: AsyncService,
+    {
+        self.register_with_dependencies(service, &[]);
+    }
+
+    /// Registers `service`, which is started only once the services identified by `dependencies` are ready
+    /// and is stopped before them
+    pub fn register_with_dependencies<T>(&self, service: Arc<T>, dependencies: &[&'static str])
     where
         T: AsyncService,
     {
         trace!("async-runtime registering service {}", service.clone().ident());
-        self.services.lock().unwrap().push(service);
+        self.services.lock().unwrap().push(RegisteredService { service, dependencies: dependencies.to_vec() });
+    }
+
+    /// Returns the registered services along with their dependencies, sorted so that each service follows its
+    /// dependencies, and otherwise by registration order. Panics if a dependency is not registered or if
+    /// dependencies are cyclic
+    fn ordered_services(&self) -> Vec<(Arc<dyn AsyncService>, Vec<&'static str>)> {
+        let services = self.services.lock().unwrap();
+        let idents = services.iter().map(|s| (s.service.clone().ident(), s.dependencies.clone())).collect::<Vec<_>>();
+        dependency_order(&idents).into_iter().map(|i| (services[i].service.clone(), services[i].dependencies.clone())).collect()
+    }
+
+    /// Waits until the started services identified by `dependencies` are ready. Returns `false` if any of them
+    /// returned in the meantime
+    async fn wait_for_dependencies(
+        started: &[(Arc<dyn AsyncService>, Vec<&'static str>)],
+        futures: &[TaskJoinHandle<AsyncServiceResult<()>>],
+        dependencies: &[&'static str],
+    ) -> bool {
+        let dependencies = started
+            .iter()
+            .zip(futures.iter())
+            .filter(|((service, _), _)| dependencies.contains(&service.clone().ident()))
+            .map(|((service, _), future)| (service, future))
+            .collect::<Vec<_>>();
+        loop {
+            if dependencies.iter().any(|(_, future)| future.is_finished()) {
+                return false;
+            }
+            if dependencies.iter().all(|(service, _)| (*service).clone().health().is_ready()) {
+                return true;
+            }
+            tokio::time::sleep(READINESS_POLL_INTERVAL).await;
+        }
     }
 
     pub fn init(self: Arc<AsyncRuntime>, core: Arc<Core>) -> Vec<ThreadJoinHandle<()>> {
@@ -67,20 +121,25 @@ impl AsyncRuntime {
             rt_handle.spawn(std::future::ready(()));
         });
 
-        // Start all async services
+        // Start all async services in dependency order, each once its dependencies are ready. Services left when
+        // a started service returns in the meantime are not started
         // All services futures are spawned as tokio tasks to enable parallelism
         trace!("async-runtime worker starting");
-        let futures = self
-            .services
-            .lock()
-            .unwrap()
-            .iter()
-            .map(|x| tokio::spawn(x.clone().start()))
-            .collect::<Vec<TaskJoinHandle<AsyncServiceResult<()>>>>();
+        let services = self.ordered_services();
+        let mut futures: Vec<TaskJoinHandle<AsyncServiceResult<()>>> = Vec::with_capacity(services.len());
+        for (service, dependencies) in services.iter() {
+            if !Self::wait_for_dependencies(&services[..futures.len()], &futures, dependencies).await {
+                trace!("async-runtime worker had a dependency of service {} returning", service.clone().ident());
+                break;
+            }
+            futures.push(tokio::spawn(service.clone().start()));
+        }
+        let started = &services[..futures.len()];
+        *self.lifecycle.lock().unwrap() = ServiceHealth::Ready;
 
         // wait for at least one service to return
         let (result, idx, remaining_futures) = select_all(futures).await;
-        trace!("async-runtime worker had service {} returning", self.services.lock().unwrap()[idx].clone().ident());
+        trace!("async-runtime worker had service {} returning", started[idx].0.clone().ident());
         // if at least one service yields an error, initiate global shutdown
         // this will cause signal_exit() to be executed externally (by Core invoking `stop()`)
         match result {
@@ -91,20 +150,18 @@ impl AsyncRuntime {
             _ => {}
         }
 
+        *self.lifecycle.lock().unwrap() = ServiceHealth::Stopped;
+
         // wait for remaining services to finish
         trace!("async-runtime worker joining remaining {} services", remaining_futures.len());
         try_join_all(remaining_futures).await.unwrap();
 
-        // Stop all async services
-        // All services futures are spawned as tokio tasks to enable parallelism
-        let futures = self
-            .services
-            .lock()
-            .unwrap()
-            .iter()
-            .map(|x| tokio::spawn(x.clone().stop()))
-            .collect::<Vec<TaskJoinHandle<AsyncServiceResult<()>>>>();
-        try_join_all(futures).await.unwrap();
+        // Stop the started async services in reverse dependency order, so that each service is stopped before its dependencies
+        for (service, _) in started.iter().rev() {
+            if let Err(err) = tokio::spawn(service.clone().stop()).await.unwrap() {
+                warn!("async service {} failed to stop: {}", service.clone().ident(), err);
+            }
+        }
 
         // Drop all services and cleanup
         self.services.lock().unwrap().clear();
@@ -114,8 +171,8 @@ impl AsyncRuntime {
 
     pub fn signal_exit(self: Arc<AsyncRuntime>) {
         trace!("Sending an exit signal to all async-runtime services");
-        for service in self.services.lock().unwrap().iter() {
-            service.clone().signal_exit();
+        for (service, _) in self.ordered_services().into_iter().rev() {
+            service.signal_exit();
         }
     }
 }
@@ -132,4 +189,24 @@ impl Service for AsyncRuntime {
     fn stop(self: Arc<AsyncRuntime>) {
         self.signal_exit()
     }
+
+    /// The aggregated health of all async services once the runtime is running
+    fn health(self: Arc<AsyncRuntime>) -> ServiceHealth {
+        ServiceHealth::aggregate(self.health_report().into_iter().map(|(_, health)| health))
+    }
+
+    /// Reports each async service individually
+    fn health_report(self: Arc<AsyncRuntime>) -> Vec<(&'static str, ServiceHealth)> {
+        let lifecycle = *self.lifecycle.lock().unwrap();
+        self.services
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|registered| {
+                let service = &registered.service;
+                let health = if lifecycle == ServiceHealth::Ready { service.clone().health() } else { lifecycle };
+                (service.clone().ident(), health)
+            })
+            .collect()
+    }
 }
@@ -1,3 +1,4 @@
+use crate::service::ServiceHealth;
 use futures_util::future::BoxFuture;
 use intertrait::CastFromSync;
 use std::sync::Arc;
@@ -18,4 +19,9 @@ pub trait AsyncService: CastFromSync {
     fn start(self: Arc<Self>) -> AsyncServiceFuture;
     fn signal_exit(self: Arc<Self>);
     fn stop(self: Arc<Self>) -> AsyncServiceFuture;
+
+    /// The health of a started service. Services which are operational once started need not override it
+    fn health(self: Arc<Self>) -> ServiceHealth {
+        ServiceHealth::Ready
+    }
 }
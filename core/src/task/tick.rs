@@ -19,6 +19,8 @@ pub struct TickService {
 }
 
 impl TickService {
+    pub const IDENT: &'static str = TICK;
+
     pub fn new() -> Self {
         let (shutdown, monitor) = trigger();
         Self { shutdown_trigger: shutdown, shutdown_listener: monitor }
@@ -22,8 +22,60 @@ pub fn calc_merkle_root(hashes: impl ExactSizeIterator<Item = Hash>) -> Hash {
     merkles.last().unwrap().unwrap()
 }
 
+/// Returns the merkle branch proving the inclusion of the hash at `index` within the merkle tree built by
+/// [`calc_merkle_root`], i.e., the sibling hashes along the path from the leaf up to (excluding) the root.
+/// Returns `None` if `index` is out of range.
+pub fn create_merkle_branch(hashes: impl ExactSizeIterator<Item = Hash>, index: usize) -> Option<Vec<Hash>> {
+    let len = hashes.len();
+    if index >= len {
+        return None;
+    }
+    let mut level: Vec<Option<Hash>> = hashes.map(Some).collect();
+    level.resize(len.next_power_of_two(), None);
+    let mut index = index;
+    let mut branch = Vec::with_capacity(level.len().trailing_zeros() as usize);
+    while level.len() > 1 {
+        branch.push(level[index ^ 1].unwrap_or(ZERO_HASH));
+        level = level.chunks(2).map(|pair| pair[0].map(|left| merkle_hash(left, pair[1].unwrap_or(ZERO_HASH)))).collect();
+        index /= 2;
+    }
+    Some(branch)
+}
+
+/// Verifies that `leaf` is located at `index` within the merkle tree whose root is `root`, using a branch created by [`create_merkle_branch`]
+pub fn verify_merkle_branch(leaf: Hash, index: usize, branch: &[Hash], root: Hash) -> bool {
+    let mut index = index;
+    let computed_root = branch.iter().fold(leaf, |current, &sibling| {
+        let parent = if index % 2 == 0 { merkle_hash(current, sibling) } else { merkle_hash(sibling, current) };
+        index /= 2;
+        parent
+    });
+    index == 0 && computed_root == root
+}
+
 fn merkle_hash(left: Hash, right: Hash) -> Hash {
     let mut hasher = MerkleBranchHash::new();
     hasher.update(left).update(right);
     hasher.finalize()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merkle_branch() {
+        for len in 1..=17u64 {
+            let hashes = (1..=len).map(Hash::from_u64_word).collect::<Vec<_>>();
+            let root = calc_merkle_root(hashes.iter().copied());
+            for (index, &leaf) in hashes.iter().enumerate() {
+                let branch = create_merkle_branch(hashes.iter().copied(), index).unwrap();
+                assert!(verify_merkle_branch(leaf, index, &branch, root), "len {len}, index {index}");
+                // The branch must not prove the leaf at any other position
+                assert!(!verify_merkle_branch(leaf, index ^ 1, &branch, root));
+                assert!(!verify_merkle_branch(Hash::from_u64_word(len + 1), index, &branch, root));
+            }
+            assert!(create_merkle_branch(hashes.iter().copied(), len as usize).is_none());
+        }
+    }
+}
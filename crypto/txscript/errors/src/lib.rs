@@ -43,6 +43,8 @@ pub enum TxScriptError {
     NotMinimalData(String),
     #[error("opcode not supported on current source: {0}")]
     InvalidSource(String),
+    #[error("introspected {0} index {1} is out of range (the transaction has {2})")]
+    InvalidIntrospectionIndex(&'static str, i32, usize),
     #[error("Unsatisfied lock time: {0}")]
     UnsatisfiedLockTime(String),
     #[error("Number too big: {0}")]
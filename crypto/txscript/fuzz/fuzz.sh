@@ -0,0 +1,7 @@
+#!/bin/sh -ex
+rustc --version
+cargo install cargo-fuzz
+
+# Verify the reference oracle against the kaspad script test vectors before fuzzing against it
+(cd .. && cargo test --release -- reference)
+cargo fuzz run script_engine --debug-assertions --release -- -use_counters=1 -use_value_profile=1 "$@" ../../../../rusty-kaspa-corpus/txscript/script_engine/
@@ -0,0 +1,13 @@
+#![no_main]
+use kaspa_txscript::reference;
+use libfuzzer_sys::fuzz_target;
+
+// Differential fuzzing of the script engine against the kaspad reference model. The first byte of the input
+// determines the length of the signature script, and the remaining bytes form the script public key
+fuzz_target!(|data: &[u8]| {
+    let Some((&sig_script_len, scripts)) = data.split_first() else {
+        return;
+    };
+    let (sig_script, script_public_key) = scripts.split_at((sig_script_len as usize).min(scripts.len()));
+    reference::assert_differential(sig_script, script_public_key);
+});
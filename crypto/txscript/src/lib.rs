@@ -4,6 +4,9 @@ extern crate core;
 pub mod caches;
 mod data_stack;
 pub mod opcodes;
+pub mod policy;
+#[cfg(any(test, fuzzing))]
+pub mod reference;
 pub mod script_builder;
 pub mod script_class;
 pub mod standard;
@@ -80,6 +83,9 @@ pub struct TxScriptEngine<'a, T: VerifiableTransaction> {
     cond_stack: Vec<OpCond>, // Following if stacks, and whether it is running
 
     num_ops: i32,
+
+    // Whether undefined opcodes are executed as the experimental introspection opcodes, see `opcodes::introspection`
+    introspection_opcodes: bool,
 }
 
 fn parse_script<T: VerifiableTransaction>(
@@ -152,6 +158,7 @@ impl<'a, T: VerifiableTransaction> TxScriptEngine<'a, T> {
             sig_cache,
             cond_stack: vec![],
             num_ops: 0,
+            introspection_opcodes: false,
         }
     }
 
@@ -176,6 +183,7 @@ impl<'a, T: VerifiableTransaction> TxScriptEngine<'a, T> {
                 sig_cache,
                 cond_stack: Default::default(),
                 num_ops: 0,
+                introspection_opcodes: false,
             }),
             false => Err(TxScriptError::InvalidIndex(input_idx, tx.tx().inputs.len())),
         }
@@ -190,9 +198,17 @@ impl<'a, T: VerifiableTransaction> TxScriptEngine<'a, T> {
             sig_cache,
             cond_stack: Default::default(),
             num_ops: 0,
+            introspection_opcodes: false,
         }
     }
 
+    /// Sets whether the experimental transaction introspection opcodes are executed instead of failing as undefined
+    /// opcodes
+    pub fn with_introspection_opcodes(mut self, enabled: bool) -> Self {
+        self.introspection_opcodes = enabled;
+        self
+    }
+
     #[inline]
     pub fn is_executing(&self) -> bool {
         return self.cond_stack.is_empty() || *self.cond_stack.last().expect("Checked not empty") == OpCond::True;
@@ -1054,43 +1070,7 @@ mod bitcoind_tests {
             match result {
                 Ok(_) => vec!["OK"],
                 Err(ue) => match ue {
-                    UnifiedError::TxScriptError(e) => match e {
-                        TxScriptError::NumberTooBig(_) => vec!["UNKNOWN_ERROR"],
-                        TxScriptError::PubKeyFormat => vec!["PUBKEYFORMAT"],
-                        TxScriptError::EvalFalse => vec!["EVAL_FALSE"],
-                        TxScriptError::EmptyStack => {
-                            vec!["EMPTY_STACK", "EVAL_FALSE", "UNBALANCED_CONDITIONAL", "INVALID_ALTSTACK_OPERATION"]
-                        }
-                        TxScriptError::NullFail => vec!["NULLFAIL"],
-                        TxScriptError::SigLength(_) => vec!["NULLFAIL"],
-                        //SIG_HIGH_S
-                        TxScriptError::InvalidSigHashType(_) => vec!["SIG_HASHTYPE"],
-                        TxScriptError::SignatureScriptNotPushOnly => vec!["SIG_PUSHONLY"],
-                        TxScriptError::CleanStack(_) => vec!["CLEANSTACK"],
-                        TxScriptError::OpcodeReserved(_) => vec!["BAD_OPCODE"],
-                        TxScriptError::MalformedPush(_, _) => vec!["BAD_OPCODE"],
-                        TxScriptError::InvalidOpcode(_) => vec!["BAD_OPCODE"],
-                        TxScriptError::ErrUnbalancedConditional => vec!["UNBALANCED_CONDITIONAL"],
-                        TxScriptError::InvalidState(s) if s == "condition stack empty" => vec!["UNBALANCED_CONDITIONAL"],
-                        //ErrInvalidStackOperation
-                        TxScriptError::EarlyReturn => vec!["OP_RETURN"],
-                        TxScriptError::VerifyError => vec!["VERIFY", "EQUALVERIFY"],
-                        TxScriptError::InvalidStackOperation(_, _) => vec!["INVALID_STACK_OPERATION", "INVALID_ALTSTACK_OPERATION"],
-                        TxScriptError::InvalidState(s) if s == "pick at an invalid location" => vec!["INVALID_STACK_OPERATION"],
-                        TxScriptError::InvalidState(s) if s == "roll at an invalid location" => vec!["INVALID_STACK_OPERATION"],
-                        TxScriptError::OpcodeDisabled(_) => vec!["DISABLED_OPCODE"],
-                        TxScriptError::ElementTooBig(_, _) => vec!["PUSH_SIZE"],
-                        TxScriptError::TooManyOperations(_) => vec!["OP_COUNT"],
-                        TxScriptError::StackSizeExceeded(_, _) => vec!["STACK_SIZE"],
-                        TxScriptError::InvalidPubKeyCount(_) => vec!["PUBKEY_COUNT"],
-                        TxScriptError::InvalidSignatureCount(_) => vec!["SIG_COUNT"],
-                        TxScriptError::NotMinimalData(_) => vec!["MINIMALDATA", "UNKNOWN_ERROR"],
-                        //ErrNegativeLockTime
-                        TxScriptError::UnsatisfiedLockTime(_) => vec!["UNSATISFIED_LOCKTIME"],
-                        TxScriptError::InvalidState(s) if s == "expected boolean" => vec!["MINIMALIF"],
-                        TxScriptError::ScriptSize(_, _) => vec!["SCRIPT_SIZE"],
-                        _ => vec![],
-                    },
+                    UnifiedError::TxScriptError(e) => reference::engine_result_names(&Err(e)).to_vec(),
                     UnifiedError::ScriptBuilderError(e) => match e {
                         ScriptBuilderError::ElementExceedsMaxSize(_) => vec!["PUSH_SIZE"],
                         _ => vec![],
@@ -1120,4 +1100,37 @@ mod bitcoind_tests {
             panic!("{}/{} json tests failed", had_errors, total_tests)
         }
     }
+
+    #[test]
+    fn test_reference_model() {
+        let file = File::open(Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data").join("script_tests.json"))
+            .expect("Could not find test file");
+        let tests: Vec<JsonTestRow> = serde_json::from_reader(BufReader::new(file)).expect("Failed Parsing {:?}");
+        let mut modeled_tests = 0;
+        for row in tests {
+            let (sig_script, script_pub_key, expected_result) = match row {
+                JsonTestRow::Test(sig_script, script_pub_key, _, expected_result)
+                | JsonTestRow::TestWithComment(sig_script, script_pub_key, _, expected_result, _) => {
+                    (sig_script, script_pub_key, expected_result)
+                }
+                JsonTestRow::Comment(_) => continue,
+            };
+            let (Ok(sig_script), Ok(script_pub_key)) =
+                (opcodes::parse_short_form(sig_script.clone()), opcodes::parse_short_form(script_pub_key.clone()))
+            else {
+                continue;
+            };
+            let Some(outcome) = reference::execute(&sig_script, &script_pub_key) else {
+                continue;
+            };
+            let names: &[&str] = match outcome.result {
+                Ok(()) => &["OK"],
+                Err(code) => code.result_names(),
+            };
+            assert!(names.contains(&expected_result.as_str()), "reference model expected {expected_result}, got {outcome:?}");
+            reference::assert_differential(&sig_script, &script_pub_key);
+            modeled_tests += 1;
+        }
+        assert!(modeled_tests > 0);
+    }
 }
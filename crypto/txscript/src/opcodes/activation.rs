@@ -0,0 +1,85 @@
+use super::codes;
+
+/// The consensus status of an opcode, determining how the script engine treats it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpcodeActivation {
+    /// Executed according to its definition
+    Active,
+    /// Fails the script wherever it appears, including within unexecuted branches
+    Disabled,
+    /// The conditional opcodes OP_VERIF and OP_VERNOTIF, which fail the script wherever they appear
+    AlwaysIllegal,
+    /// Fails the script when executed, but is allowed within unexecuted branches
+    Reserved,
+    /// Undefined opcodes (including the pseudo opcodes used by script templates), failing the script when executed
+    /// but allowed within unexecuted branches
+    Undefined,
+}
+
+impl OpcodeActivation {
+    pub const fn is_active(self) -> bool {
+        matches!(self, OpcodeActivation::Active)
+    }
+
+    /// Returns whether the opcode fails the script even when not executed
+    pub const fn fails_unexecuted(self) -> bool {
+        matches!(self, OpcodeActivation::Disabled | OpcodeActivation::AlwaysIllegal)
+    }
+}
+
+const fn activation(opcode: u8) -> OpcodeActivation {
+    match opcode {
+        codes::OpCat
+        | codes::OpSubStr
+        | codes::OpLeft
+        | codes::OpRight
+        | codes::OpInvert
+        | codes::OpAnd
+        | codes::OpOr
+        | codes::OpXor
+        | codes::Op2Mul
+        | codes::Op2Div
+        | codes::OpMul
+        | codes::OpDiv
+        | codes::OpMod
+        | codes::OpLShift
+        | codes::OpRShift => OpcodeActivation::Disabled,
+        codes::OpVerIf | codes::OpVerNotIf => OpcodeActivation::AlwaysIllegal,
+        codes::OpReserved | codes::OpVer | codes::OpReserved1 | codes::OpReserved2 => OpcodeActivation::Reserved,
+        0xa6..=0xa7 | 0xb2..=0xff => OpcodeActivation::Undefined,
+        _ => OpcodeActivation::Active,
+    }
+}
+
+/// The consensus status of every opcode, indexed by opcode value
+pub const OPCODE_ACTIVATIONS: [OpcodeActivation; 256] = {
+    let mut table = [OpcodeActivation::Active; 256];
+    let mut opcode = 0;
+    while opcode < table.len() {
+        table[opcode] = activation(opcode as u8);
+        opcode += 1;
+    }
+    table
+};
+
+/// Returns the consensus status of `opcode`
+pub const fn opcode_activation(opcode: u8) -> OpcodeActivation {
+    OPCODE_ACTIVATIONS[opcode as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opcode_activations() {
+        assert_eq!(opcode_activation(codes::OpCheckSig), OpcodeActivation::Active);
+        assert_eq!(opcode_activation(codes::OpCheckSequenceVerify), OpcodeActivation::Active);
+        assert_eq!(opcode_activation(codes::OpCat), OpcodeActivation::Disabled);
+        assert_eq!(opcode_activation(codes::OpVerNotIf), OpcodeActivation::AlwaysIllegal);
+        assert_eq!(opcode_activation(codes::OpReserved), OpcodeActivation::Reserved);
+        assert_eq!(opcode_activation(0xa6), OpcodeActivation::Undefined);
+        assert_eq!(opcode_activation(codes::OpPubKeyHash), OpcodeActivation::Undefined);
+        assert_eq!(OPCODE_ACTIVATIONS.iter().filter(|activation| activation.fails_unexecuted()).count(), 17);
+    }
+}
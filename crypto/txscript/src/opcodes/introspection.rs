@@ -0,0 +1,186 @@
+//!
+//! Experimental transaction introspection opcodes, allowing a script to inspect the transaction spending it, as
+//! needed for prototyping covenant designs.
+//!
+//! These opcodes occupy the undefined opcodes `0xb2..=0xc3` and are **not** part of the consensus of any public
+//! network: they are executed only by engines created
+//! [`with_introspection_opcodes`](crate::TxScriptEngine::with_introspection_opcodes), which consensus does on
+//! networks whose params enable `introspection_opcodes` (devnet), the params being the single switch so that all
+//! nodes of a network agree on them. Their definitions might change at any time.
+//!
+//! Opcodes reading a specific input or output pop its index off the stack. Counts, indices and amounts are pushed
+//! as numbers, while the lock time, gas and sequences are pushed as 8 little-endian bytes (as read by
+//! OP_CHECKLOCKTIMEVERIFY and OP_CHECKSEQUENCEVERIFY). Script public keys are pushed as their big-endian version
+//! followed by the script.
+//!
+
+use super::{push_data, push_number, OpCodeResult};
+use crate::{data_stack::DataStack, ScriptSource, TxScriptEngine, TxScriptError, MAX_SCRIPT_ELEMENT_SIZE};
+use kaspa_consensus_core::tx::{ScriptPublicKey, VerifiableTransaction};
+
+pub mod codes {
+    #![allow(non_upper_case_globals)]
+
+    pub const OpTxVersion: u8 = 0xb2;
+    pub const OpTxInputCount: u8 = 0xb3;
+    pub const OpTxOutputCount: u8 = 0xb4;
+    pub const OpTxLockTime: u8 = 0xb5;
+    pub const OpTxSubnetId: u8 = 0xb6;
+    pub const OpTxGas: u8 = 0xb7;
+    pub const OpTxPayload: u8 = 0xb8;
+    /// The index of the input being verified
+    pub const OpTxInputIndex: u8 = 0xb9;
+    pub const OpOutpointTxId: u8 = 0xba;
+    pub const OpOutpointIndex: u8 = 0xbb;
+    pub const OpTxInputScriptSig: u8 = 0xbc;
+    pub const OpTxInputSeq: u8 = 0xbd;
+    pub const OpTxInputAmount: u8 = 0xbe;
+    pub const OpTxInputSpk: u8 = 0xbf;
+    pub const OpTxInputBlockDaaScore: u8 = 0xc0;
+    pub const OpTxInputIsCoinbase: u8 = 0xc1;
+    pub const OpTxOutputAmount: u8 = 0xc2;
+    pub const OpTxOutputSpk: u8 = 0xc3;
+}
+
+pub const fn is_introspection_opcode(opcode: u8) -> bool {
+    matches!(opcode, codes::OpTxVersion..=codes::OpTxOutputSpk)
+}
+
+pub(super) fn execute<T: VerifiableTransaction>(opcode: u8, vm: &mut TxScriptEngine<T>) -> OpCodeResult {
+    let ScriptSource::TxInput { tx, id, .. } = vm.script_source else {
+        return Err(TxScriptError::InvalidSource("introspection opcodes only apply to transaction inputs".to_string()));
+    };
+    let inputs = &tx.tx().inputs;
+    let outputs = &tx.tx().outputs;
+    match opcode {
+        codes::OpTxVersion => push_number(tx.tx().version as i64, vm),
+        codes::OpTxInputCount => push_number(inputs.len() as i64, vm),
+        codes::OpTxOutputCount => push_number(outputs.len() as i64, vm),
+        codes::OpTxLockTime => push_data(tx.tx().lock_time.to_le_bytes().to_vec(), vm),
+        codes::OpTxSubnetId => push_data(AsRef::<[u8]>::as_ref(&tx.tx().subnetwork_id).to_vec(), vm),
+        codes::OpTxGas => push_data(tx.tx().gas.to_le_bytes().to_vec(), vm),
+        codes::OpTxPayload => push_element(tx.tx().payload.clone(), vm),
+        codes::OpTxInputIndex => push_number(id as i64, vm),
+        codes::OpOutpointTxId => {
+            let index = pop_index(vm, "input", inputs.len())?;
+            push_data(inputs[index].previous_outpoint.transaction_id.as_bytes().to_vec(), vm)
+        }
+        codes::OpOutpointIndex => {
+            let index = pop_index(vm, "input", inputs.len())?;
+            push_number(inputs[index].previous_outpoint.index as i64, vm)
+        }
+        codes::OpTxInputScriptSig => {
+            let index = pop_index(vm, "input", inputs.len())?;
+            push_element(inputs[index].signature_script.clone(), vm)
+        }
+        codes::OpTxInputSeq => {
+            let index = pop_index(vm, "input", inputs.len())?;
+            push_data(inputs[index].sequence.to_le_bytes().to_vec(), vm)
+        }
+        codes::OpTxInputAmount => {
+            let index = pop_index(vm, "input", inputs.len())?;
+            push_number(tx.populated_input(index).1.amount as i64, vm)
+        }
+        codes::OpTxInputSpk => {
+            let index = pop_index(vm, "input", inputs.len())?;
+            push_element(serialize_script_public_key(&tx.populated_input(index).1.script_public_key), vm)
+        }
+        codes::OpTxInputBlockDaaScore => {
+            let index = pop_index(vm, "input", inputs.len())?;
+            push_number(tx.populated_input(index).1.block_daa_score as i64, vm)
+        }
+        codes::OpTxInputIsCoinbase => {
+            let index = pop_index(vm, "input", inputs.len())?;
+            vm.dstack.push_item(tx.populated_input(index).1.is_coinbase);
+            Ok(())
+        }
+        codes::OpTxOutputAmount => {
+            let index = pop_index(vm, "output", outputs.len())?;
+            push_number(outputs[index].value as i64, vm)
+        }
+        codes::OpTxOutputSpk => {
+            let index = pop_index(vm, "output", outputs.len())?;
+            push_element(serialize_script_public_key(&outputs[index].script_public_key), vm)
+        }
+        _ => Err(TxScriptError::InvalidOpcode(format!("{opcode:#04x}"))),
+    }
+}
+
+/// Pops an index into the `count` inputs or outputs of the transaction
+fn pop_index<T: VerifiableTransaction>(vm: &mut TxScriptEngine<T>, kind: &'static str, count: usize) -> Result<usize, TxScriptError> {
+    let [index]: [i32; 1] = vm.dstack.pop_items()?;
+    usize::try_from(index).ok().filter(|&index| index < count).ok_or(TxScriptError::InvalidIntrospectionIndex(kind, index, count))
+}
+
+/// Pushes transaction data of variable length, which is subject to the usual element size limit
+fn push_element<T: VerifiableTransaction>(data: Vec<u8>, vm: &mut TxScriptEngine<T>) -> OpCodeResult {
+    if data.len() > MAX_SCRIPT_ELEMENT_SIZE {
+        return Err(TxScriptError::ElementTooBig(data.len(), MAX_SCRIPT_ELEMENT_SIZE));
+    }
+    push_data(data, vm)
+}
+
+fn serialize_script_public_key(script_public_key: &ScriptPublicKey) -> Vec<u8> {
+    [script_public_key.version().to_be_bytes().as_slice(), script_public_key.script()].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{caches::Cache, opcodes::codes::*, script_builder::ScriptBuilder};
+    use kaspa_consensus_core::{
+        hashing::sighash::SigHashReusedValues,
+        subnets::SUBNETWORK_ID_NATIVE,
+        tx::{PopulatedTransaction, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry},
+    };
+    use kaspa_hashes::Hash;
+
+    fn execute_script(script: &[u8], introspection_opcodes: bool) -> Result<(), TxScriptError> {
+        let covenant = ScriptPublicKey::from_vec(0, script.to_vec());
+        let tx = Transaction::new(
+            0,
+            vec![
+                TransactionInput::new(TransactionOutpoint::new(Hash::from_u64_word(1), 0), vec![], 0, 0),
+                TransactionInput::new(TransactionOutpoint::new(Hash::from_u64_word(2), 3), vec![], 0, 0),
+            ],
+            vec![TransactionOutput::new(1600, covenant.clone())],
+            0,
+            SUBNETWORK_ID_NATIVE,
+            0,
+            vec![],
+        );
+        let entries = vec![UtxoEntry::new(1000, covenant.clone(), 0, false), UtxoEntry::new(600, covenant, 0, false)];
+        let populated = PopulatedTransaction::new(&tx, entries);
+        let (input, entry) = populated.populated_input(0);
+        let sig_cache = Cache::new(10_000);
+        let mut reused_values = SigHashReusedValues::new();
+        TxScriptEngine::from_transaction_input(&populated, input, 0, entry, &mut reused_values, &sig_cache)?
+            .with_introspection_opcodes(introspection_opcodes)
+            .execute()
+    }
+
+    #[test]
+    fn test_introspection_opcodes() {
+        // Requires the transaction to have two inputs and to recreate the covenant with the sum of their amounts
+        let script = ScriptBuilder::new()
+            .add_op(codes::OpTxInputCount)
+            .unwrap()
+            .add_op(Op2)
+            .unwrap()
+            .add_op(OpEqualVerify)
+            .unwrap()
+            .add_ops(&[Op0, codes::OpTxInputSpk, Op0, codes::OpTxOutputSpk, OpEqualVerify])
+            .unwrap()
+            .add_ops(&[Op0, codes::OpTxInputAmount, Op1, codes::OpTxInputAmount, OpAdd, Op0, codes::OpTxOutputAmount, OpEqual])
+            .unwrap()
+            .drain();
+        assert_eq!(execute_script(&script, true), Ok(()));
+        assert!(matches!(execute_script(&script, false), Err(TxScriptError::InvalidOpcode(_))));
+
+        let script = ScriptBuilder::new().add_ops(&[Op1, codes::OpOutpointIndex, Op3, OpEqual]).unwrap().drain();
+        assert_eq!(execute_script(&script, true), Ok(()));
+
+        let script = ScriptBuilder::new().add_ops(&[Op2, codes::OpTxInputAmount]).unwrap().drain();
+        assert_eq!(execute_script(&script, true), Err(TxScriptError::InvalidIntrospectionIndex("input", 2, 2)));
+    }
+}
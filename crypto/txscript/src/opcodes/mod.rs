@@ -2,12 +2,15 @@ use core::mem::size_of;
 
 #[macro_use]
 mod macros;
+pub mod activation;
+pub mod introspection;
 
 use crate::data_stack::{DataStack, OpcodeData};
 use crate::{
     ScriptSource, TxScriptEngine, TxScriptError, LOCK_TIME_THRESHOLD, MAX_TX_IN_SEQUENCE_NUM, NO_COST_OPCODE,
     SEQUENCE_LOCK_TIME_DISABLED, SEQUENCE_LOCK_TIME_MASK,
 };
+use activation::{opcode_activation, OpcodeActivation};
 use blake2b_simd::Params;
 use core::cmp::{max, min};
 use kaspa_consensus_core::hashing::sighash_type::SigHashType;
@@ -104,28 +107,11 @@ impl<const CODE: u8> OpCodeMetadata for OpCode<CODE> {
     }
 
     fn is_disabled(&self) -> bool {
-        matches!(
-            CODE,
-            codes::OpCat
-                | codes::OpSubStr
-                | codes::OpLeft
-                | codes::OpRight
-                | codes::OpInvert
-                | codes::OpAnd
-                | codes::OpOr
-                | codes::OpXor
-                | codes::Op2Mul
-                | codes::Op2Div
-                | codes::OpMul
-                | codes::OpDiv
-                | codes::OpMod
-                | codes::OpLShift
-                | codes::OpRShift
-        )
+        opcode_activation(CODE) == OpcodeActivation::Disabled
     }
 
     fn always_illegal(&self) -> bool {
-        matches!(CODE, codes::OpVerIf | codes::OpVerNotIf)
+        opcode_activation(CODE) == OpcodeActivation::AlwaysIllegal
     }
 
     fn is_push_opcode(&self) -> bool {
@@ -206,6 +192,14 @@ fn push_number<T: VerifiableTransaction>(number: i64, vm: &mut TxScriptEngine<T>
     Ok(())
 }
 
+/// Fails on an undefined opcode, unless it is an experimental introspection opcode and these are enabled
+fn undefined_opcode<T: VerifiableTransaction, const CODE: u8>(opcode: &OpCode<CODE>, vm: &mut TxScriptEngine<T>) -> OpCodeResult {
+    if vm.introspection_opcodes && introspection::is_introspection_opcode(CODE) {
+        return introspection::execute(CODE, vm);
+    }
+    Err(TxScriptError::InvalidOpcode(format!("{opcode:?}")))
+}
+
 /*
 The following is the implementation and metadata of all opcodes. Each opcode has unique
 number (and template system makes it impossible to use two opcodes), length specification,
@@ -877,24 +871,24 @@ opcode_list! {
     }
 
     // Undefined opcodes.
-    opcode OpUnknown178<0xb2, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown179<0xb3, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown180<0xb4, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown181<0xb5, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown182<0xb6, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown183<0xb7, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown184<0xb8, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown185<0xb9, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown186<0xba, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown187<0xbb, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown188<0xbc, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown189<0xbd, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown190<0xbe, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown191<0xbf, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown192<0xc0, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown193<0xc1, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown194<0xc2, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
-    opcode OpUnknown195<0xc3, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
+    opcode OpUnknown178<0xb2, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown179<0xb3, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown180<0xb4, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown181<0xb5, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown182<0xb6, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown183<0xb7, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown184<0xb8, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown185<0xb9, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown186<0xba, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown187<0xbb, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown188<0xbc, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown189<0xbd, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown190<0xbe, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown191<0xbf, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown192<0xc0, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown193<0xc1, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown194<0xc2, 1>(self, vm) undefined_opcode(self, vm)
+    opcode OpUnknown195<0xc3, 1>(self, vm) undefined_opcode(self, vm)
     opcode OpUnknown196<0xc4, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
     opcode OpUnknown197<0xc5, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
     opcode OpUnknown198<0xc6, 1>(self, vm) Err(TxScriptError::InvalidOpcode(format!("{self:?}")))
@@ -0,0 +1,91 @@
+//!
+//! Standardness policy of scripts, i.e., script rules which are not part of consensus and are only enforced by the
+//! mempool on the transactions it admits (and therefore relays and mines).
+//!
+//! Consensus script rules are enforced by [`TxScriptEngine`](crate::TxScriptEngine) according to the
+//! [opcode activation table](crate::opcodes::activation). Note that disabled opcodes and non push-only signature
+//! scripts are rejected by consensus in Kaspa, hence they are not a matter of policy.
+//!
+//! The policy keeps opcodes which are not active (reserved and undefined opcodes) out of the scripts executed by
+//! standard transactions, including within unexecuted branches where consensus allows them. This keeps these
+//! opcodes available for future activation, without standard transactions depending on their current meaning.
+//!
+
+use crate::{
+    opcodes::activation::{opcode_activation, OpcodeActivation},
+    parse_script,
+    script_class::ScriptClass,
+};
+use kaspa_consensus_core::tx::{PopulatedTransaction, ScriptPublicKey};
+use thiserror::Error;
+
+#[derive(Error, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ScriptPolicyError {
+    #[error("script public key contains the opcode {0:#04x} which is {1:?}")]
+    ScriptPublicKeyOpcode(u8, OpcodeActivation),
+
+    #[error("redeem script contains the opcode {0:#04x} which is {1:?}")]
+    RedeemScriptOpcode(u8, OpcodeActivation),
+}
+pub type ScriptPolicyResult<T> = std::result::Result<T, ScriptPolicyError>;
+
+/// Checks that the scripts executed when spending an output locked by `script_public_key` with `signature_script`
+/// conform to the standardness policy. Malformed scripts are left for consensus to reject
+pub fn check_input_scripts_policy(signature_script: &[u8], script_public_key: &ScriptPublicKey) -> ScriptPolicyResult<()> {
+    if let Some((opcode, activation)) = find_non_active_opcode(script_public_key.script()) {
+        return Err(ScriptPolicyError::ScriptPublicKeyOpcode(opcode, activation));
+    }
+
+    if ScriptClass::is_pay_to_script_hash(script_public_key.script()) {
+        // The redeem script is the last data push of the signature script
+        if let Some(Ok(redeem_script)) = parse_script::<PopulatedTransaction>(signature_script).last() {
+            if let Some((opcode, activation)) = find_non_active_opcode(redeem_script.get_data()) {
+                return Err(ScriptPolicyError::RedeemScriptOpcode(opcode, activation));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn find_non_active_opcode(script: &[u8]) -> Option<(u8, OpcodeActivation)> {
+    parse_script::<PopulatedTransaction>(script)
+        .map_while(Result::ok)
+        .map(|opcode| (opcode.value(), opcode_activation(opcode.value())))
+        .find(|(_, activation)| !activation.is_active())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        opcodes::codes::{OpCheckSig, OpData32, OpEndIf, OpFalse, OpIf, OpReserved, OpTrue},
+        pay_to_script_hash_script, pay_to_script_hash_signature_script,
+    };
+    use std::iter::once;
+
+    #[test]
+    fn test_check_input_scripts_policy() {
+        let pay_to_pub_key = ScriptPublicKey::from_vec(0, once(OpData32).chain([7u8; 32]).chain(once(OpCheckSig)).collect());
+        assert_eq!(check_input_scripts_policy(&[0x40; 65], &pay_to_pub_key), Ok(()));
+
+        // Consensus accepts reserved and undefined opcodes within unexecuted branches, while the policy does not
+        let unexecuted_reserved = vec![OpFalse, OpIf, OpReserved, OpEndIf, OpTrue];
+        assert_eq!(
+            check_input_scripts_policy(&[], &ScriptPublicKey::from_vec(0, unexecuted_reserved.clone())),
+            Err(ScriptPolicyError::ScriptPublicKeyOpcode(OpReserved, OpcodeActivation::Reserved))
+        );
+
+        let pay_to_script_hash = pay_to_script_hash_script(&unexecuted_reserved);
+        let signature_script = pay_to_script_hash_signature_script(unexecuted_reserved, vec![]).unwrap();
+        assert_eq!(
+            check_input_scripts_policy(&signature_script, &pay_to_script_hash),
+            Err(ScriptPolicyError::RedeemScriptOpcode(OpReserved, OpcodeActivation::Reserved))
+        );
+
+        let undefined = vec![OpFalse, OpIf, 0xb2, OpEndIf, OpTrue];
+        assert_eq!(
+            check_input_scripts_policy(&[], &ScriptPublicKey::from_vec(0, undefined)),
+            Err(ScriptPolicyError::ScriptPublicKeyOpcode(0xb2, OpcodeActivation::Undefined))
+        );
+    }
+}
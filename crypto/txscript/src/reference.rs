@@ -0,0 +1,604 @@
+//!
+//! A reference model of the kaspad (Go) script engine, serving as the oracle for differential testing and fuzzing of
+//! [`TxScriptEngine`].
+//!
+//! The model is a straightforward interpreter written after the Go engine, independently of the opcode
+//! implementations of this crate. It covers the deterministic part of the instruction set (data pushes, flow
+//! control, stack, splice, bitwise and numeric opcodes, as well as all disabled, reserved and invalid opcodes), and
+//! declines scripts using signature, hashing or lock time opcodes.
+//!
+//! Outcomes are compared by the result names of the script test vectors (`test-data/script_tests.json`), which
+//! define which error codes of either engine are considered equivalent.
+//!
+
+use crate::{
+    caches::Cache, opcodes::codes, TxScriptEngine, MAX_OPS_PER_SCRIPT, MAX_SCRIPTS_SIZE, MAX_SCRIPT_ELEMENT_SIZE, MAX_STACK_SIZE,
+    MAX_TX_IN_SEQUENCE_NUM,
+};
+use kaspa_consensus_core::{
+    hashing::sighash::SigHashReusedValues,
+    tx::{
+        PopulatedTransaction, ScriptPublicKey, Transaction, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput,
+        UtxoEntry,
+    },
+};
+use kaspa_txscript_errors::TxScriptError;
+
+/// The maximal length of a numeric operand
+const MAX_NUM_LEN: usize = 4;
+
+/// The error codes of the kaspad script engine which the reference model can produce
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCode {
+    EvalFalse,
+    EmptyStack,
+    CleanStack,
+    ScriptTooBig,
+    MalformedPush,
+    NotPushOnly,
+    DisabledOpcode,
+    ReservedOpcode,
+    TooManyOperations,
+    ElementTooBig,
+    MinimalData,
+    NumberTooBig,
+    StackOverflow,
+    InvalidStackOperation,
+    UnbalancedConditional,
+    MinimalIf,
+    EarlyReturn,
+    Verify,
+    EqualVerify,
+    NumEqualVerify,
+}
+
+impl ErrorCode {
+    /// Returns the script test result names this error code satisfies, as mapped by the kaspad reference tests
+    pub fn result_names(self) -> &'static [&'static str] {
+        match self {
+            ErrorCode::EvalFalse | ErrorCode::EmptyStack => &["EVAL_FALSE"],
+            ErrorCode::CleanStack => &["CLEANSTACK"],
+            ErrorCode::ScriptTooBig => &["SCRIPT_SIZE"],
+            ErrorCode::MalformedPush | ErrorCode::ReservedOpcode => &["BAD_OPCODE"],
+            ErrorCode::NotPushOnly => &["SIG_PUSHONLY"],
+            ErrorCode::DisabledOpcode => &["DISABLED_OPCODE"],
+            ErrorCode::TooManyOperations => &["OP_COUNT"],
+            ErrorCode::ElementTooBig => &["PUSH_SIZE"],
+            ErrorCode::MinimalData => &["MINIMALDATA", "UNKNOWN_ERROR"],
+            ErrorCode::NumberTooBig => &["UNKNOWN_ERROR"],
+            ErrorCode::StackOverflow => &["STACK_SIZE"],
+            ErrorCode::InvalidStackOperation => &["INVALID_STACK_OPERATION", "INVALID_ALTSTACK_OPERATION", "UNBALANCED_CONDITIONAL"],
+            ErrorCode::UnbalancedConditional => &["UNBALANCED_CONDITIONAL"],
+            ErrorCode::MinimalIf => &["MINIMALIF"],
+            ErrorCode::EarlyReturn => &["OP_RETURN"],
+            ErrorCode::Verify | ErrorCode::NumEqualVerify => &["VERIFY"],
+            ErrorCode::EqualVerify => &["EQUALVERIFY"],
+        }
+    }
+}
+
+/// Returns the script test result names satisfied by an outcome of [`TxScriptEngine::execute`]
+pub fn engine_result_names(result: &Result<(), TxScriptError>) -> &'static [&'static str] {
+    let Err(err) = result else {
+        return &["OK"];
+    };
+    match err {
+        TxScriptError::NumberTooBig(_) => &["UNKNOWN_ERROR"],
+        TxScriptError::PubKeyFormat => &["PUBKEYFORMAT"],
+        TxScriptError::EvalFalse => &["EVAL_FALSE"],
+        TxScriptError::EmptyStack => &["EMPTY_STACK", "EVAL_FALSE", "UNBALANCED_CONDITIONAL", "INVALID_ALTSTACK_OPERATION"],
+        TxScriptError::NullFail => &["NULLFAIL"],
+        TxScriptError::SigLength(_) => &["NULLFAIL"],
+        //SIG_HIGH_S
+        TxScriptError::InvalidSigHashType(_) => &["SIG_HASHTYPE"],
+        TxScriptError::SignatureScriptNotPushOnly => &["SIG_PUSHONLY"],
+        TxScriptError::CleanStack(_) => &["CLEANSTACK"],
+        TxScriptError::OpcodeReserved(_) => &["BAD_OPCODE"],
+        TxScriptError::MalformedPush(_, _) => &["BAD_OPCODE"],
+        TxScriptError::InvalidOpcode(_) => &["BAD_OPCODE"],
+        TxScriptError::ErrUnbalancedConditional => &["UNBALANCED_CONDITIONAL"],
+        TxScriptError::InvalidState(s) if s == "condition stack empty" => &["UNBALANCED_CONDITIONAL"],
+        //ErrInvalidStackOperation
+        TxScriptError::EarlyReturn => &["OP_RETURN"],
+        TxScriptError::VerifyError => &["VERIFY", "EQUALVERIFY"],
+        TxScriptError::InvalidStackOperation(_, _) => &["INVALID_STACK_OPERATION", "INVALID_ALTSTACK_OPERATION"],
+        TxScriptError::InvalidState(s) if s == "pick at an invalid location" => &["INVALID_STACK_OPERATION"],
+        TxScriptError::InvalidState(s) if s == "roll at an invalid location" => &["INVALID_STACK_OPERATION"],
+        TxScriptError::OpcodeDisabled(_) => &["DISABLED_OPCODE"],
+        TxScriptError::ElementTooBig(_, _) => &["PUSH_SIZE"],
+        TxScriptError::TooManyOperations(_) => &["OP_COUNT"],
+        TxScriptError::StackSizeExceeded(_, _) => &["STACK_SIZE"],
+        TxScriptError::InvalidPubKeyCount(_) => &["PUBKEY_COUNT"],
+        TxScriptError::InvalidSignatureCount(_) => &["SIG_COUNT"],
+        TxScriptError::NotMinimalData(_) => &["MINIMALDATA", "UNKNOWN_ERROR"],
+        //ErrNegativeLockTime
+        TxScriptError::UnsatisfiedLockTime(_) => &["UNSATISFIED_LOCKTIME"],
+        TxScriptError::InvalidState(s) if s == "expected boolean" => &["MINIMALIF"],
+        TxScriptError::ScriptSize(_, _) => &["SCRIPT_SIZE"],
+        _ => &[],
+    }
+}
+
+/// The outcome of executing a script pair in the reference model
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    pub result: Result<(), ErrorCode>,
+
+    /// Whether the error was raised by a check kaspad performs on the whole scripts before executing them (parsing
+    /// and the push-only signature script rule). [`TxScriptEngine`] performs these checks lazily while executing,
+    /// so an earlier opcode might fail first with a different error code, while the script is rejected either way
+    pub is_pre_execution: bool,
+}
+
+impl Outcome {
+    fn executed(result: Result<(), ErrorCode>) -> Self {
+        Self { result, is_pre_execution: false }
+    }
+
+    fn pre_execution(code: ErrorCode) -> Self {
+        Self { result: Err(code), is_pre_execution: true }
+    }
+
+    /// Returns whether `result` of [`TxScriptEngine::execute`] agrees with this outcome: scripts must be accepted or
+    /// rejected alike, and errors must satisfy a common result name (unless raised before execution)
+    pub fn agrees_with(&self, result: &Result<(), TxScriptError>) -> bool {
+        match (self.result, result) {
+            (Ok(()), Ok(())) => true,
+            (Err(_), Err(_)) if self.is_pre_execution => true,
+            (Err(code), Err(_)) => code.result_names().iter().any(|name| engine_result_names(result).contains(name)),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Cond {
+    True,
+    False,
+    Skip,
+}
+
+struct ParsedOp {
+    opcode: u8,
+    data: Vec<u8>,
+}
+
+fn parse(script: &[u8]) -> Result<Vec<ParsedOp>, ErrorCode> {
+    let mut ops = Vec::new();
+    let mut rest = script;
+    while let Some((&opcode, tail)) = rest.split_first() {
+        let (len_size, len) = match opcode {
+            codes::OpData1..=codes::OpData75 => (0, opcode as usize),
+            codes::OpPushData1 => (1, 0),
+            codes::OpPushData2 => (2, 0),
+            codes::OpPushData4 => (4, 0),
+            _ => (0, 0),
+        };
+        if tail.len() < len_size {
+            return Err(ErrorCode::MalformedPush);
+        }
+        let (len_bytes, tail) = tail.split_at(len_size);
+        let len = len_bytes.iter().rev().fold(len, |len, &byte| (len << 8) | byte as usize);
+        if tail.len() < len {
+            return Err(ErrorCode::MalformedPush);
+        }
+        let (data, tail) = tail.split_at(len);
+        ops.push(ParsedOp { opcode, data: data.to_vec() });
+        rest = tail;
+    }
+    Ok(ops)
+}
+
+fn is_modeled(opcode: u8) -> bool {
+    !(codes::OpSHA256..=codes::OpCheckSequenceVerify).contains(&opcode)
+}
+
+fn is_disabled(opcode: u8) -> bool {
+    matches!(
+        opcode,
+        codes::OpCat
+            | codes::OpSubStr
+            | codes::OpLeft
+            | codes::OpRight
+            | codes::OpInvert
+            | codes::OpAnd
+            | codes::OpOr
+            | codes::OpXor
+            | codes::Op2Mul
+            | codes::Op2Div
+            | codes::OpMul
+            | codes::OpDiv
+            | codes::OpMod
+            | codes::OpLShift
+            | codes::OpRShift
+    )
+}
+
+fn encode_num(num: i64) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut abs = num.unsigned_abs();
+    while abs > 0 {
+        bytes.push(abs as u8);
+        abs >>= 8;
+    }
+    // The most significant bit holds the sign, requiring an extra byte if already in use by the magnitude
+    match bytes.last().copied() {
+        Some(last) if last & 0x80 != 0 => bytes.push(if num < 0 { 0x80 } else { 0 }),
+        Some(_) if num < 0 => *bytes.last_mut().unwrap() |= 0x80,
+        _ => {}
+    }
+    bytes
+}
+
+fn decode_num(bytes: &[u8]) -> Result<i64, ErrorCode> {
+    if bytes.len() > MAX_NUM_LEN {
+        return Err(ErrorCode::NumberTooBig);
+    }
+    let Some(&last) = bytes.last() else {
+        return Ok(0);
+    };
+    if last & 0x7f == 0 && (bytes.len() == 1 || bytes[bytes.len() - 2] & 0x80 == 0) {
+        return Err(ErrorCode::MinimalData);
+    }
+    let magnitude = bytes.iter().rev().fold(0i64, |acc, &byte| (acc << 8) | byte as i64) & !(0x80 << (8 * (bytes.len() - 1)));
+    Ok(if last & 0x80 != 0 { -magnitude } else { magnitude })
+}
+
+fn as_bool(bytes: &[u8]) -> bool {
+    match bytes.split_last() {
+        Some((&last, rest)) => last & 0x7f != 0 || rest.iter().any(|&byte| byte != 0),
+        None => false,
+    }
+}
+
+fn check_minimal_push(op: &ParsedOp) -> Result<(), ErrorCode> {
+    let expected = match op.data.as_slice() {
+        [] => codes::OpFalse,
+        &[value] if (1..=16).contains(&value) => codes::OpTrue + value - 1,
+        [0x81] => codes::Op1Negate,
+        data if data.len() <= 75 => data.len() as u8,
+        data if data.len() <= u8::MAX as usize => codes::OpPushData1,
+        data if data.len() <= u16::MAX as usize => codes::OpPushData2,
+        _ => codes::OpPushData4,
+    };
+    match op.opcode == expected {
+        true => Ok(()),
+        false => Err(ErrorCode::MinimalData),
+    }
+}
+
+#[derive(Default)]
+struct Machine {
+    dstack: Vec<Vec<u8>>,
+    astack: Vec<Vec<u8>>,
+    cond_stack: Vec<Cond>,
+    num_ops: i32,
+}
+
+impl Machine {
+    fn is_executing(&self) -> bool {
+        self.cond_stack.last().map_or(true, |cond| *cond == Cond::True)
+    }
+
+    fn pop(&mut self) -> Result<Vec<u8>, ErrorCode> {
+        self.dstack.pop().ok_or(ErrorCode::InvalidStackOperation)
+    }
+
+    fn pop_num(&mut self) -> Result<i64, ErrorCode> {
+        decode_num(&self.pop()?)
+    }
+
+    fn pop_bool(&mut self) -> Result<bool, ErrorCode> {
+        Ok(as_bool(&self.pop()?))
+    }
+
+    fn peek(&self, depth: usize) -> Result<Vec<u8>, ErrorCode> {
+        self.dstack.iter().rev().nth(depth).cloned().ok_or(ErrorCode::InvalidStackOperation)
+    }
+
+    fn push_num(&mut self, num: i64) {
+        self.dstack.push(encode_num(num));
+    }
+
+    fn push_bool(&mut self, value: bool) {
+        self.dstack.push(if value { vec![1] } else { vec![] });
+    }
+
+    /// Removes and returns the `count` items found at `depth` from the top, keeping their order
+    fn remove_at(&mut self, depth: usize, count: usize) -> Result<Vec<Vec<u8>>, ErrorCode> {
+        if self.dstack.len() < depth + count {
+            return Err(ErrorCode::InvalidStackOperation);
+        }
+        let start = self.dstack.len() - depth - count;
+        Ok(self.dstack.drain(start..start + count).collect())
+    }
+
+    /// Pushes copies of the `count` items found at `depth` from the top, keeping their order
+    fn copy_to_top(&mut self, depth: usize, count: usize) -> Result<(), ErrorCode> {
+        if self.dstack.len() < depth + count {
+            return Err(ErrorCode::InvalidStackOperation);
+        }
+        let start = self.dstack.len() - depth - count;
+        self.dstack.extend_from_within(start..start + count);
+        Ok(())
+    }
+
+    fn pop_if_cond(&mut self) -> Result<bool, ErrorCode> {
+        match self.pop()?.as_slice() {
+            [] => Ok(false),
+            [1] => Ok(true),
+            _ => Err(ErrorCode::MinimalIf),
+        }
+    }
+
+    fn execute_script(&mut self, ops: &[ParsedOp]) -> Result<(), ErrorCode> {
+        for op in ops {
+            self.execute_op(op)?;
+            if self.dstack.len() + self.astack.len() > MAX_STACK_SIZE {
+                return Err(ErrorCode::StackOverflow);
+            }
+        }
+        if !self.cond_stack.is_empty() {
+            return Err(ErrorCode::UnbalancedConditional);
+        }
+        self.astack.clear();
+        self.num_ops = 0;
+        Ok(())
+    }
+
+    fn execute_op(&mut self, op: &ParsedOp) -> Result<(), ErrorCode> {
+        if is_disabled(op.opcode) {
+            return Err(ErrorCode::DisabledOpcode);
+        }
+        if op.opcode == codes::OpVerIf || op.opcode == codes::OpVerNotIf {
+            return Err(ErrorCode::ReservedOpcode);
+        }
+        if op.opcode > codes::Op16 {
+            self.num_ops += 1;
+            if self.num_ops > MAX_OPS_PER_SCRIPT {
+                return Err(ErrorCode::TooManyOperations);
+            }
+        } else if op.data.len() > MAX_SCRIPT_ELEMENT_SIZE {
+            return Err(ErrorCode::ElementTooBig);
+        }
+
+        let is_conditional = (codes::OpIf..=codes::OpEndIf).contains(&op.opcode);
+        if !self.is_executing() && !is_conditional {
+            return Ok(());
+        }
+        if (codes::OpData1..=codes::OpPushData4).contains(&op.opcode) {
+            check_minimal_push(op)?;
+        }
+
+        match op.opcode {
+            codes::OpFalse..=codes::OpPushData4 => self.dstack.push(op.data.clone()),
+            codes::Op1Negate => self.push_num(-1),
+            codes::OpTrue..=codes::Op16 => self.push_num((op.opcode - codes::OpTrue + 1) as i64),
+            codes::OpNop => {}
+            codes::OpIf | codes::OpNotIf => {
+                let cond = match self.is_executing() {
+                    true => match self.pop_if_cond()? == (op.opcode == codes::OpIf) {
+                        true => Cond::True,
+                        false => Cond::False,
+                    },
+                    false => Cond::Skip,
+                };
+                self.cond_stack.push(cond);
+            }
+            codes::OpElse => {
+                let cond = self.cond_stack.last_mut().ok_or(ErrorCode::UnbalancedConditional)?;
+                *cond = match cond {
+                    Cond::True => Cond::False,
+                    Cond::False => Cond::True,
+                    Cond::Skip => Cond::Skip,
+                };
+            }
+            codes::OpEndIf => {
+                self.cond_stack.pop().ok_or(ErrorCode::UnbalancedConditional)?;
+            }
+            codes::OpVerify => {
+                if !self.pop_bool()? {
+                    return Err(ErrorCode::Verify);
+                }
+            }
+            codes::OpReturn => return Err(ErrorCode::EarlyReturn),
+            codes::OpToAltStack => {
+                let item = self.pop()?;
+                self.astack.push(item);
+            }
+            codes::OpFromAltStack => {
+                let item = self.astack.pop().ok_or(ErrorCode::InvalidStackOperation)?;
+                self.dstack.push(item);
+            }
+            codes::Op2Drop => {
+                self.remove_at(0, 2)?;
+            }
+            codes::Op2Dup => self.copy_to_top(0, 2)?,
+            codes::Op3Dup => self.copy_to_top(0, 3)?,
+            codes::Op2Over => self.copy_to_top(2, 2)?,
+            codes::Op2Rot => {
+                let items = self.remove_at(4, 2)?;
+                self.dstack.extend(items);
+            }
+            codes::Op2Swap => {
+                let items = self.remove_at(2, 2)?;
+                self.dstack.extend(items);
+            }
+            codes::OpIfDup => {
+                let top = self.peek(0)?;
+                if as_bool(&top) {
+                    self.dstack.push(top);
+                }
+            }
+            codes::OpDepth => self.push_num(self.dstack.len() as i64),
+            codes::OpDrop => {
+                self.pop()?;
+            }
+            codes::OpDup => self.copy_to_top(0, 1)?,
+            codes::OpNip => {
+                self.remove_at(1, 1)?;
+            }
+            codes::OpOver => self.copy_to_top(1, 1)?,
+            codes::OpPick | codes::OpRoll => {
+                let depth = self.pop_num()?;
+                if depth < 0 || depth as usize >= self.dstack.len() {
+                    return Err(ErrorCode::InvalidStackOperation);
+                }
+                match op.opcode == codes::OpPick {
+                    true => self.copy_to_top(depth as usize, 1)?,
+                    false => {
+                        let items = self.remove_at(depth as usize, 1)?;
+                        self.dstack.extend(items);
+                    }
+                }
+            }
+            codes::OpRot => {
+                let items = self.remove_at(2, 1)?;
+                self.dstack.extend(items);
+            }
+            codes::OpSwap => {
+                let items = self.remove_at(1, 1)?;
+                self.dstack.extend(items);
+            }
+            codes::OpTuck => {
+                let [second, top]: [Vec<u8>; 2] = self.remove_at(0, 2)?.try_into().unwrap();
+                self.dstack.extend([top.clone(), second, top]);
+            }
+            codes::OpSize => {
+                let top = self.peek(0)?;
+                self.push_num(top.len() as i64);
+            }
+            codes::OpEqual | codes::OpEqualVerify => {
+                let (a, b) = (self.pop()?, self.pop()?);
+                match op.opcode == codes::OpEqual {
+                    true => self.push_bool(a == b),
+                    false if a != b => return Err(ErrorCode::EqualVerify),
+                    false => {}
+                }
+            }
+            codes::Op1Add | codes::Op1Sub | codes::OpNegate | codes::OpAbs | codes::OpNot | codes::Op0NotEqual => {
+                let m = self.pop_num()?;
+                match op.opcode {
+                    codes::Op1Add => self.push_num(m + 1),
+                    codes::Op1Sub => self.push_num(m - 1),
+                    codes::OpNegate => self.push_num(-m),
+                    codes::OpAbs => self.push_num(m.abs()),
+                    codes::OpNot => self.push_bool(m == 0),
+                    _ => self.push_bool(m != 0),
+                }
+            }
+            codes::OpAdd..=codes::OpSub | codes::OpBoolAnd..=codes::OpMax => {
+                // The top item is the second operand
+                let b = self.pop_num()?;
+                let a = self.pop_num()?;
+                match op.opcode {
+                    codes::OpAdd => self.push_num(a + b),
+                    codes::OpSub => self.push_num(a - b),
+                    codes::OpBoolAnd => self.push_bool(a != 0 && b != 0),
+                    codes::OpBoolOr => self.push_bool(a != 0 || b != 0),
+                    codes::OpNumEqual => self.push_bool(a == b),
+                    codes::OpNumEqualVerify if a != b => return Err(ErrorCode::NumEqualVerify),
+                    codes::OpNumEqualVerify => {}
+                    codes::OpNumNotEqual => self.push_bool(a != b),
+                    codes::OpLessThan => self.push_bool(a < b),
+                    codes::OpGreaterThan => self.push_bool(a > b),
+                    codes::OpLessThanOrEqual => self.push_bool(a <= b),
+                    codes::OpGreaterThanOrEqual => self.push_bool(a >= b),
+                    codes::OpMin => self.push_num(a.min(b)),
+                    _ => self.push_num(a.max(b)),
+                }
+            }
+            codes::OpWithin => {
+                let max = self.pop_num()?;
+                let min = self.pop_num()?;
+                let x = self.pop_num()?;
+                self.push_bool(min <= x && x < max);
+            }
+            // Reserved, undefined and pseudo opcodes
+            _ => return Err(ErrorCode::ReservedOpcode),
+        }
+        Ok(())
+    }
+}
+
+/// Executes a signature script followed by the script public key it spends, as kaspad would.
+/// Returns `None` if the scripts use opcodes outside of the modeled instruction set
+pub fn execute(sig_script: &[u8], script_public_key: &[u8]) -> Option<Outcome> {
+    if sig_script.is_empty() && script_public_key.is_empty() {
+        return Some(Outcome::executed(Err(ErrorCode::EvalFalse)));
+    }
+    let mut scripts = Vec::with_capacity(2);
+    for script in [sig_script, script_public_key] {
+        if script.len() > MAX_SCRIPTS_SIZE {
+            return Some(Outcome::pre_execution(ErrorCode::ScriptTooBig));
+        }
+        match parse(script) {
+            Ok(ops) => scripts.push(ops),
+            Err(code) => return Some(Outcome::pre_execution(code)),
+        }
+    }
+    if scripts[0].iter().any(|op| op.opcode > codes::Op16) {
+        return Some(Outcome::pre_execution(ErrorCode::NotPushOnly));
+    }
+    if !scripts.iter().flatten().all(|op| is_modeled(op.opcode)) {
+        return None;
+    }
+
+    let mut machine = Machine::default();
+    let result = scripts.iter().try_for_each(|ops| machine.execute_script(ops)).and_then(|_| match machine.dstack.len() {
+        0 => Err(ErrorCode::EmptyStack),
+        1 if as_bool(&machine.dstack[0]) => Ok(()),
+        1 => Err(ErrorCode::EvalFalse),
+        _ => Err(ErrorCode::CleanStack),
+    });
+    Some(Outcome::executed(result))
+}
+
+/// Runs a signature script spending a script public key through [`TxScriptEngine`], within a minimal spending
+/// transaction (as done by the script test vectors)
+pub fn execute_engine(sig_script: &[u8], script_public_key: &[u8]) -> Result<(), TxScriptError> {
+    let script_public_key = ScriptPublicKey::from_vec(0, script_public_key.to_vec());
+    let tx = Transaction::new(
+        1,
+        vec![TransactionInput::new(
+            TransactionOutpoint::new(TransactionId::default(), 0),
+            sig_script.to_vec(),
+            MAX_TX_IN_SEQUENCE_NUM,
+            0,
+        )],
+        vec![TransactionOutput::new(0, Default::default())],
+        0,
+        Default::default(),
+        0,
+        vec![],
+    );
+    let populated_tx = PopulatedTransaction::new(&tx, vec![UtxoEntry::new(0, script_public_key, 0, true)]);
+    let sig_cache = Cache::new(10_000);
+    let mut reused_values = SigHashReusedValues::new();
+    let mut vm = TxScriptEngine::from_transaction_input(
+        &populated_tx,
+        &tx.inputs[0],
+        0,
+        &populated_tx.entries[0],
+        &mut reused_values,
+        &sig_cache,
+    )?;
+    vm.execute()
+}
+
+/// Runs a script pair through both [`TxScriptEngine`] and the reference model, panicking if their outcomes
+/// disagree. Script pairs outside of the modeled instruction set are ignored
+pub fn assert_differential(sig_script: &[u8], script_public_key: &[u8]) {
+    let Some(expected) = execute(sig_script, script_public_key) else {
+        return;
+    };
+    let result = execute_engine(sig_script, script_public_key);
+    assert!(
+        expected.agrees_with(&result),
+        "engine result {:?} disagrees with the reference outcome {:?}\nsig script: {:02x?}\nscript public key: {:02x?}",
+        result,
+        expected,
+        sig_script,
+        script_public_key
+    );
+}
@@ -2,10 +2,38 @@ use crate::{db::DB, errors::StoreError};
 
 use super::prelude::{Cache, DbKey, DbWriter};
 use itertools::Itertools;
-use rocksdb::{Direction, IteratorMode, ReadOptions};
+use rocksdb::{Direction, IteratorMode, ReadOptions, SnapshotWithThreadMode};
 use serde::{de::DeserializeOwned, Serialize};
 use std::{collections::hash_map::RandomState, error::Error, hash::BuildHasher, sync::Arc};
 
+/// Read options applied to the iterators of a store which is scanned sequentially
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ScanOptions {
+    /// The iterator read-ahead size in bytes, where 0 keeps the RocksDB default (auto-tuned read-ahead)
+    pub readahead_size: usize,
+    /// Keep the blocks read by an iterator pinned for as long as the iterator is alive
+    pub pin_data: bool,
+    /// Do not populate the block cache with blocks read by iterators, so that long scans do not evict hot blocks
+    pub bypass_block_cache: bool,
+}
+
+impl ScanOptions {
+    /// Options for stores which are mostly scanned from start to end, such as during index resyncs and pruning
+    pub fn sequential(readahead_size: usize) -> Self {
+        Self { readahead_size, pin_data: true, bypass_block_cache: true }
+    }
+
+    fn read_options(&self) -> ReadOptions {
+        let mut read_opts = ReadOptions::default();
+        if self.readahead_size > 0 {
+            read_opts.set_readahead_size(self.readahead_size);
+        }
+        read_opts.set_pin_data(self.pin_data);
+        read_opts.fill_cache(!self.bypass_block_cache);
+        read_opts
+    }
+}
+
 /// A concurrent DB store access with typed caching.
 #[derive(Clone)]
 pub struct CachedDbAccess<TKey, TData, S = RandomState>
@@ -20,6 +48,9 @@ where
 
     // DB bucket/path
     prefix: Vec<u8>,
+
+    // Read options for iterators
+    scan_options: ScanOptions,
 }
 
 impl<TKey, TData, S> CachedDbAccess<TKey, TData, S>
@@ -29,7 +60,12 @@ where
     S: BuildHasher + Default,
 {
     pub fn new(db: Arc<DB>, cache_size: u64, prefix: Vec<u8>) -> Self {
-        Self { db, cache: Cache::new(cache_size), prefix }
+        Self { db, cache: Cache::new(cache_size), prefix, scan_options: Default::default() }
+    }
+
+    /// Applies `scan_options` to all iterators created by this access
+    pub fn with_scan_options(self, scan_options: ScanOptions) -> Self {
+        Self { scan_options, ..self }
     }
 
     pub fn read_from_cache(&self, key: TKey) -> Option<TData>
@@ -55,6 +91,7 @@ where
             Ok(data)
         } else {
             let db_key = DbKey::new(&self.prefix, key.clone());
+            let _span = tracing::trace_span!("db_read", prefix = self.prefix.first().copied()).entered();
             if let Some(slice) = self.db.get_pinned(&db_key)? {
                 let data: TData = bincode::deserialize(&slice)?;
                 self.cache.insert(key, data.clone());
@@ -65,13 +102,71 @@ where
         }
     }
 
+    /// Reads the data of all `keys`, in order. Cache hits are served from the cache while the remaining keys are
+    /// read from the DB with a single batched read, and the found entries are cached. Keys which do not exist in
+    /// the DB map to `None`
+    pub fn multi_get(&self, keys: impl IntoIterator<Item = TKey>) -> Result<Vec<Option<TData>>, StoreError>
+    where
+        TKey: Clone + AsRef<[u8]>,
+        TData: DeserializeOwned,
+    {
+        let mut results = Vec::new();
+        let mut missing = Vec::new();
+        for key in keys {
+            let data = self.cache.get(&key);
+            if data.is_none() {
+                missing.push((results.len(), key));
+            }
+            results.push(data);
+        }
+        if missing.is_empty() {
+            return Ok(results);
+        }
+
+        let _span = tracing::trace_span!("db_multi_get", prefix = self.prefix.first().copied(), keys = missing.len()).entered();
+        let slices = self.db.multi_get(missing.iter().map(|(_, key)| DbKey::new(&self.prefix, key.clone())));
+        let mut found = Vec::with_capacity(missing.len());
+        for ((index, key), slice) in missing.into_iter().zip(slices) {
+            if let Some(slice) = slice? {
+                let data: TData = bincode::deserialize(&slice)?;
+                results[index] = Some(data.clone());
+                found.push((key, data));
+            }
+        }
+        self.cache.insert_many(&mut found.into_iter());
+        Ok(results)
+    }
+
+    /// Same as [`Self::multi_get`] but fails with [`StoreError::KeyNotFound`] if any of the keys does not exist
+    pub fn read_many(&self, keys: impl IntoIterator<Item = TKey>) -> Result<Vec<TData>, StoreError>
+    where
+        TKey: Clone + AsRef<[u8]>,
+        TData: DeserializeOwned,
+    {
+        let keys = keys.into_iter().collect_vec();
+        let results = self.multi_get(keys.iter().cloned())?;
+        keys.into_iter()
+            .zip(results)
+            .map(|(key, data)| data.ok_or_else(|| StoreError::KeyNotFound(DbKey::new(&self.prefix, key))))
+            .collect()
+    }
+
+    /// Loads the entries of all `keys` missing from the cache into the cache, using a single batched DB read
+    pub fn prefetch(&self, keys: impl IntoIterator<Item = TKey>) -> Result<(), StoreError>
+    where
+        TKey: Clone + AsRef<[u8]>,
+        TData: DeserializeOwned,
+    {
+        self.multi_get(keys).map(|_| ())
+    }
+
     pub fn iterator(&self) -> impl Iterator<Item = Result<(Box<[u8]>, TData), Box<dyn Error>>> + '_
     where
         TKey: Clone + AsRef<[u8]>,
         TData: DeserializeOwned, // We need `DeserializeOwned` since the slice coming from `db.get_pinned` has short lifetime
     {
         let prefix_key = DbKey::prefix_only(&self.prefix);
-        let mut read_opts = ReadOptions::default();
+        let mut read_opts = self.scan_options.read_options();
         read_opts.set_iterate_range(rocksdb::PrefixRange(prefix_key.as_ref()));
         self.db.iterator_opt(IteratorMode::From(prefix_key.as_ref(), Direction::Forward), read_opts).map(move |iter_result| {
             match iter_result {
@@ -160,7 +255,7 @@ where
         self.cache.remove_all();
         //TODO: Consider using column families to make it faster
         let db_key = DbKey::prefix_only(&self.prefix);
-        let mut read_opts = ReadOptions::default();
+        let mut read_opts = self.scan_options.read_options();
         read_opts.set_iterate_range(rocksdb::PrefixRange(db_key.as_ref()));
         let keys = self
             .db
@@ -185,6 +280,37 @@ where
         limit: usize,            // amount to take.
         skip_first: bool,        // skips the first value, (useful in conjunction with the seek-key, as to not re-retrieve).
     ) -> impl Iterator<Item = Result<(Box<[u8]>, TData), Box<dyn Error>>> + '_
+    where
+        TKey: Clone + AsRef<[u8]>,
+        TData: DeserializeOwned,
+    {
+        self.seek_iterator_impl(None, bucket, seek_from, limit, skip_first)
+    }
+
+    /// Same as [`Self::seek_iterator`] but reading the DB as it was when `snapshot` was taken
+    pub fn seek_iterator_at<'a>(
+        &'a self,
+        snapshot: &'a SnapshotWithThreadMode<'a, DB>,
+        bucket: Option<&[u8]>,
+        seek_from: Option<TKey>,
+        limit: usize,
+        skip_first: bool,
+    ) -> impl Iterator<Item = Result<(Box<[u8]>, TData), Box<dyn Error>>> + 'a
+    where
+        TKey: Clone + AsRef<[u8]>,
+        TData: DeserializeOwned,
+    {
+        self.seek_iterator_impl(Some(snapshot), bucket, seek_from, limit, skip_first)
+    }
+
+    fn seek_iterator_impl(
+        &self,
+        snapshot: Option<&SnapshotWithThreadMode<'_, DB>>,
+        bucket: Option<&[u8]>,
+        seek_from: Option<TKey>,
+        limit: usize,
+        skip_first: bool,
+    ) -> impl Iterator<Item = Result<(Box<[u8]>, TData), Box<dyn Error>>> + '_
     where
         TKey: Clone + AsRef<[u8]>,
         TData: DeserializeOwned,
@@ -198,8 +324,11 @@ where
             },
         );
 
-        let mut read_opts = ReadOptions::default();
+        let mut read_opts = self.scan_options.read_options();
         read_opts.set_iterate_range(rocksdb::PrefixRange(db_key.as_ref()));
+        if let Some(snapshot) = snapshot {
+            read_opts.set_snapshot(snapshot);
+        }
 
         let mut db_iterator = match seek_from {
             Some(seek_key) => {
@@ -225,3 +354,28 @@ where
         &self.prefix
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        create_temp_db,
+        prelude::{ConnBuilder, DirectDbWriter},
+    };
+    use kaspa_hashes::Hash;
+
+    #[test]
+    fn test_multi_get() {
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let access = CachedDbAccess::<Hash, u64>::new(db.clone(), 2, vec![1, 2]);
+        access.write_many(DirectDbWriter::new(&db), &mut (1..=4).map(|i| (i.into(), i))).unwrap();
+
+        // A fresh access where only the entry of 1 is cached, so the rest are read from the DB in a single batch
+        let access = CachedDbAccess::<Hash, u64>::new(db.clone(), 2, vec![1, 2]);
+        access.read(1.into()).unwrap();
+        let keys = [3, 1, 5, 4].map(Hash::from_u64_word);
+        assert_eq!(access.multi_get(keys).unwrap(), vec![Some(3), Some(1), None, Some(4)]);
+        assert!(access.read_many(keys).is_err());
+        assert_eq!(access.read_many([4, 3].map(Hash::from_u64_word)).unwrap(), vec![4, 3]);
+    }
+}
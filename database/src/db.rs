@@ -1,21 +1,98 @@
-use rocksdb::{DBWithThreadMode, MultiThreaded};
+use log::{error, warn};
+use parking_lot::RwLock;
+use rocksdb::{DBWithThreadMode, MultiThreaded, WriteBatch, WriteOptions};
 use std::ops::{Deref, DerefMut};
 use std::path::PathBuf;
 
-pub use conn_builder::ConnBuilder;
+pub use compaction::CompactionReport;
+pub use conn_builder::{ConnBuilder, ConnError};
+pub use durability::DurabilityProfile;
 use kaspa_utils::fd_budget::FDGuard;
+pub use stats::DbStats;
 
+mod compaction;
 mod conn_builder;
+mod durability;
+mod stats;
 
 /// The DB type used for Kaspad stores
 pub struct DB {
     inner: DBWithThreadMode<MultiThreaded>,
     _fd_guard: FDGuard,
+    durability: DurabilityProfile,
+    /// Whether the WAL is currently skipped due to a bulk sync. Writes hold the read lock throughout, so that no
+    /// write skipping the WAL can land after the memtable flush which ends a bulk sync
+    wal_disabled: RwLock<bool>,
 }
 
 impl DB {
-    pub fn new(inner: DBWithThreadMode<MultiThreaded>, fd_guard: FDGuard) -> Self {
-        Self { inner, _fd_guard: fd_guard }
+    pub fn new(inner: DBWithThreadMode<MultiThreaded>, fd_guard: FDGuard, durability: DurabilityProfile) -> Self {
+        Self { inner, _fd_guard: fd_guard, durability, wal_disabled: RwLock::new(false) }
+    }
+
+    pub fn durability(&self) -> DurabilityProfile {
+        self.durability
+    }
+
+    /// Reports whether the DB owner is performing a bulk sync (e.g., IBD). Under the [`DurabilityProfile::FastSync`]
+    /// profile the WAL is skipped while a bulk sync is active, and memtables are flushed to disk once it ends. Under
+    /// other profiles this is a no-op
+    pub fn set_bulk_sync(&self, active: bool) {
+        if self.durability != DurabilityProfile::FastSync || *self.wal_disabled.read() == active {
+            return;
+        }
+        let mut wal_disabled = self.wal_disabled.write();
+        if *wal_disabled == active {
+            return;
+        }
+        if !active {
+            // Persist all writes which skipped the WAL before acknowledging further writes through it. On failure the
+            // WAL remains skipped, so that the flush is retried the next time the bulk sync is reported as ended
+            if let Err(err) = self.inner.flush() {
+                warn!("Failed flushing the memtables at the end of a bulk sync: {err}");
+                return;
+            }
+        }
+        *wal_disabled = active;
+    }
+
+    fn write_options(&self, wal_disabled: bool) -> WriteOptions {
+        let mut options = WriteOptions::default();
+        match self.durability {
+            DurabilityProfile::Default => {}
+            DurabilityProfile::HighDurability => options.set_sync(true),
+            DurabilityProfile::FastSync => options.disable_wal(wal_disabled),
+        }
+        options
+    }
+
+    /// Writes `batch` atomically, with the durability guarantees of the DB profile
+    pub fn write(&self, batch: WriteBatch) -> Result<(), rocksdb::Error> {
+        let wal_disabled = self.wal_disabled.read();
+        self.inner.write_opt(batch, &self.write_options(*wal_disabled))
+    }
+
+    /// Writes a single key, with the durability guarantees of the DB profile
+    pub fn put<K: AsRef<[u8]>, V: AsRef<[u8]>>(&self, key: K, value: V) -> Result<(), rocksdb::Error> {
+        let wal_disabled = self.wal_disabled.read();
+        self.inner.put_opt(key, value, &self.write_options(*wal_disabled))
+    }
+
+    /// Deletes a single key, with the durability guarantees of the DB profile
+    pub fn delete<K: AsRef<[u8]>>(&self, key: K) -> Result<(), rocksdb::Error> {
+        let wal_disabled = self.wal_disabled.read();
+        self.inner.delete_opt(key, &self.write_options(*wal_disabled))
+    }
+}
+
+impl Drop for DB {
+    fn drop(&mut self) {
+        // Persist writes which skipped the WAL in case the owner did not end its bulk sync
+        if *self.wal_disabled.get_mut() {
+            if let Err(err) = self.inner.flush() {
+                error!("Failed flushing the memtables of a DB closed during a bulk sync, recent writes are lost: {err}");
+            }
+        }
     }
 }
 
@@ -33,6 +110,12 @@ impl Deref for DB {
     }
 }
 
+/// Creates a consistent point-in-time copy of `db` at `checkpoint_dir`, which must not exist. SST files are
+/// hard linked when residing on the same file system, so the checkpoint is cheap to create
+pub fn create_checkpoint(db: &DB, checkpoint_dir: PathBuf) -> Result<(), rocksdb::Error> {
+    rocksdb::checkpoint::Checkpoint::new(&db.inner)?.create_checkpoint(checkpoint_dir)
+}
+
 /// Deletes an existing DB if it exists
 pub fn delete_db(db_dir: PathBuf) {
     if !db_dir.exists() {
@@ -42,3 +125,56 @@ pub fn delete_db(db_dir: PathBuf) {
     let path = db_dir.to_str().unwrap();
     <DBWithThreadMode<MultiThreaded>>::destroy(&options, path).expect("DB is expected to be deletable");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_temp_db, utils::get_kaspa_tempdir};
+
+    fn active_memtable_entries(db: &DB) -> u64 {
+        db.property_int_value("rocksdb.num-entries-active-mem-table").unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_bulk_sync() {
+        // Bulk syncs are ignored by the other profiles
+        for durability in [DurabilityProfile::Default, DurabilityProfile::HighDurability] {
+            let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10).with_durability(durability));
+            db.set_bulk_sync(true);
+            assert!(!*db.wal_disabled.read());
+        }
+
+        let (_lifetime, db) =
+            create_temp_db!(ConnBuilder::default().with_files_limit(10).with_durability(DurabilityProfile::FastSync));
+        db.set_bulk_sync(true);
+        assert!(*db.wal_disabled.read());
+        db.put(b"key", b"value").unwrap();
+        assert_eq!(active_memtable_entries(&db), 1);
+
+        // Ending the bulk sync flushes the writes which skipped the WAL
+        db.set_bulk_sync(false);
+        assert!(!*db.wal_disabled.read());
+        assert_eq!(active_memtable_entries(&db), 0);
+        assert_eq!(db.get(b"key").unwrap().unwrap(), b"value");
+    }
+
+    #[test]
+    fn test_drop_during_bulk_sync() {
+        let db_tempdir = get_kaspa_tempdir();
+        let conn_builder = || {
+            ConnBuilder::default()
+                .with_db_path(db_tempdir.path().to_owned())
+                .with_files_limit(10)
+                .with_durability(DurabilityProfile::FastSync)
+        };
+
+        let db = conn_builder().build().unwrap();
+        db.set_bulk_sync(true);
+        db.put(b"key", b"value").unwrap();
+        drop(db);
+
+        // The writes which skipped the WAL are persisted when the DB is closed
+        let db = conn_builder().build().unwrap();
+        assert_eq!(db.get(b"key").unwrap().unwrap(), b"value");
+    }
+}
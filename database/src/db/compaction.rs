@@ -0,0 +1,82 @@
+use super::DB;
+use crate::registry::DatabaseStorePrefixes;
+use rocksdb::CompactOptions;
+use std::time::{Duration, Instant};
+
+/// The outcome of compacting a set of stores
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CompactionReport {
+    /// The total size of the SST files before the compaction
+    pub size_before: u64,
+    /// The total size of the SST files after the compaction
+    pub size_after: u64,
+    pub elapsed: Duration,
+}
+
+impl CompactionReport {
+    /// The disk space returned by the compaction. Files still referenced by checkpoints or iterators are only
+    /// released once these are dropped, so the reclaimed space might be lower than expected
+    pub fn reclaimed_bytes(&self) -> u64 {
+        self.size_before.saturating_sub(self.size_after)
+    }
+}
+
+impl DB {
+    /// Compacts the key ranges of the stores identified by `prefixes`. Deleted entries only mark their keys as
+    /// removed, so the space they occupy is returned to the file system only once the ranges holding them are
+    /// compacted. Blocks until the compactions are complete, while automatic compactions keep running alongside
+    pub fn compact_stores(&self, prefixes: &[DatabaseStorePrefixes]) -> CompactionReport {
+        let start = Instant::now();
+        let size_before = self.total_sst_files_size();
+        let mut options = CompactOptions::default();
+        options.set_exclusive_manual_compaction(false);
+        for &prefix in prefixes {
+            let (from, to) = prefix_range(prefix);
+            self.inner.compact_range_opt(Some(from), Some(to), &options);
+        }
+        CompactionReport { size_before, size_after: self.total_sst_files_size(), elapsed: start.elapsed() }
+    }
+
+    fn total_sst_files_size(&self) -> u64 {
+        self.property_int_value("rocksdb.total-sst-files-size").ok().flatten().unwrap_or_default()
+    }
+}
+
+/// Returns the key range `[from, to)` holding all the keys of the store identified by `prefix`
+fn prefix_range(prefix: DatabaseStorePrefixes) -> ([u8; 1], [u8; 1]) {
+    let prefix = prefix as u8;
+    // The separator is never used as a store prefix, hence the successor of a prefix is always a valid byte
+    ([prefix], [prefix + 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_temp_db, prelude::ConnBuilder};
+
+    #[test]
+    fn test_compact_stores() {
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let prefix = DatabaseStorePrefixes::BlockTransactions;
+        let value = vec![7u8; 1024];
+        for i in 0..1000u32 {
+            db.put([&[prefix as u8][..], &i.to_le_bytes()].concat(), &value).unwrap();
+        }
+        db.flush().unwrap();
+        for i in 0..1000u32 {
+            db.delete([&[prefix as u8][..], &i.to_le_bytes()].concat()).unwrap();
+        }
+        db.flush().unwrap();
+
+        // Deletes alone do not shrink the files, compacting the range drops both the entries and their tombstones
+        let report = db.compact_stores(&[prefix]);
+        assert!(report.size_before > 0);
+        assert!(report.reclaimed_bytes() > 0);
+    }
+
+    #[test]
+    fn test_prefix_range() {
+        assert_eq!(prefix_range(DatabaseStorePrefixes::Headers), ([8], [9]));
+        assert_eq!(prefix_range(DatabaseStorePrefixes::TxIndexSpentOutpoints), ([199], [200]));
+    }
+}
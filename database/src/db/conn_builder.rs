@@ -1,6 +1,17 @@
-use crate::db::DB;
+use crate::db::{DurabilityProfile, DB};
 use rocksdb::{DBWithThreadMode, MultiThreaded};
 use std::{path::PathBuf, sync::Arc};
+use thiserror::Error;
+
+/// An error opening a DB connection, see [`ConnBuilder::try_build`]
+#[derive(Error, Debug)]
+pub enum ConnError {
+    #[error(transparent)]
+    FdBudget(#[from] kaspa_utils::fd_budget::Error),
+
+    #[error(transparent)]
+    RocksDb(#[from] rocksdb::Error),
+}
 
 #[derive(Debug)]
 pub struct Unspecified;
@@ -13,6 +24,7 @@ pub struct ConnBuilder<Path, const STATS_ENABLED: bool, StatsPeriod, FDLimit> {
     files_limit: FDLimit,
     mem_budget: usize,
     stats_period: StatsPeriod,
+    durability: DurabilityProfile,
 }
 
 impl Default for ConnBuilder<Unspecified, false, Unspecified, Unspecified> {
@@ -24,6 +36,7 @@ impl Default for ConnBuilder<Unspecified, false, Unspecified, Unspecified> {
             mem_budget: 64 * 1024 * 1024,
             stats_period: Unspecified,
             files_limit: Unspecified,
+            durability: DurabilityProfile::Default,
         }
     }
 }
@@ -37,6 +50,7 @@ impl<Path, const STATS_ENABLED: bool, StatsPeriod, FDLimit> ConnBuilder<Path, ST
             parallelism: self.parallelism,
             mem_budget: self.mem_budget,
             stats_period: self.stats_period,
+            durability: self.durability,
         }
     }
     pub fn with_create_if_missing(self, create_if_missing: bool) -> ConnBuilder<Path, STATS_ENABLED, StatsPeriod, FDLimit> {
@@ -45,6 +59,9 @@ impl<Path, const STATS_ENABLED: bool, StatsPeriod, FDLimit> ConnBuilder<Path, ST
     pub fn with_parallelism(self, parallelism: impl Into<usize>) -> ConnBuilder<Path, STATS_ENABLED, StatsPeriod, FDLimit> {
         ConnBuilder { parallelism: parallelism.into(), ..self }
     }
+    pub fn with_durability(self, durability: DurabilityProfile) -> ConnBuilder<Path, STATS_ENABLED, StatsPeriod, FDLimit> {
+        ConnBuilder { durability, ..self }
+    }
     pub fn with_mem_budget(self, mem_budget: impl Into<usize>) -> ConnBuilder<Path, STATS_ENABLED, StatsPeriod, FDLimit> {
         ConnBuilder { mem_budget: mem_budget.into(), ..self }
     }
@@ -56,6 +73,7 @@ impl<Path, const STATS_ENABLED: bool, StatsPeriod, FDLimit> ConnBuilder<Path, ST
             parallelism: self.parallelism,
             mem_budget: self.mem_budget,
             stats_period: self.stats_period,
+            durability: self.durability,
         }
     }
 }
@@ -69,6 +87,7 @@ impl<Path, FDLimit> ConnBuilder<Path, false, Unspecified, FDLimit> {
             files_limit: self.files_limit,
             mem_budget: self.mem_budget,
             stats_period: self.stats_period,
+            durability: self.durability,
         }
     }
 }
@@ -82,6 +101,7 @@ impl<Path, StatsPeriod, FDLimit> ConnBuilder<Path, true, StatsPeriod, FDLimit> {
             files_limit: self.files_limit,
             mem_budget: self.mem_budget,
             stats_period: Unspecified,
+            durability: self.durability,
         }
     }
     pub fn with_stats_period(self, stats_period: impl Into<u32>) -> ConnBuilder<Path, true, u32, FDLimit> {
@@ -92,6 +112,7 @@ impl<Path, StatsPeriod, FDLimit> ConnBuilder<Path, true, StatsPeriod, FDLimit> {
             files_limit: self.files_limit,
             mem_budget: self.mem_budget,
             stats_period: stats_period.into(),
+            durability: self.durability,
         }
     }
 }
@@ -114,16 +135,32 @@ macro_rules! default_opts {
 impl ConnBuilder<PathBuf, false, Unspecified, i32> {
     pub fn build(self) -> Result<Arc<DB>, kaspa_utils::fd_budget::Error> {
         let (opts, guard) = default_opts!(self)?;
-        let db = Arc::new(DB::new(<DBWithThreadMode<MultiThreaded>>::open(&opts, self.db_path.to_str().unwrap()).unwrap(), guard));
+        let db = Arc::new(DB::new(
+            <DBWithThreadMode<MultiThreaded>>::open(&opts, self.db_path.to_str().unwrap()).unwrap(),
+            guard,
+            self.durability,
+        ));
         Ok(db)
     }
+
+    /// Same as [`Self::build`], only failing to open the DB (e.g., due to a corruption or a lock held by another
+    /// process) is returned as an error rather than panicking
+    pub fn try_build(self) -> Result<Arc<DB>, ConnError> {
+        let (opts, guard) = default_opts!(self)?;
+        let db = <DBWithThreadMode<MultiThreaded>>::open(&opts, self.db_path.to_str().unwrap())?;
+        Ok(Arc::new(DB::new(db, guard, self.durability)))
+    }
 }
 
 impl ConnBuilder<PathBuf, true, Unspecified, i32> {
     pub fn build(self) -> Result<Arc<DB>, kaspa_utils::fd_budget::Error> {
         let (mut opts, guard) = default_opts!(self)?;
         opts.enable_statistics();
-        let db = Arc::new(DB::new(<DBWithThreadMode<MultiThreaded>>::open(&opts, self.db_path.to_str().unwrap()).unwrap(), guard));
+        let db = Arc::new(DB::new(
+            <DBWithThreadMode<MultiThreaded>>::open(&opts, self.db_path.to_str().unwrap()).unwrap(),
+            guard,
+            self.durability,
+        ));
         Ok(db)
     }
 }
@@ -134,7 +171,11 @@ impl ConnBuilder<PathBuf, true, u32, i32> {
         opts.enable_statistics();
         opts.set_report_bg_io_stats(true);
         opts.set_stats_dump_period_sec(self.stats_period);
-        let db = Arc::new(DB::new(<DBWithThreadMode<MultiThreaded>>::open(&opts, self.db_path.to_str().unwrap()).unwrap(), guard));
+        let db = Arc::new(DB::new(
+            <DBWithThreadMode<MultiThreaded>>::open(&opts, self.db_path.to_str().unwrap()).unwrap(),
+            guard,
+            self.durability,
+        ));
         Ok(db)
     }
 }
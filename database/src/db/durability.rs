@@ -0,0 +1,47 @@
+use std::{fmt::Display, str::FromStr};
+
+/// Trade-off between write throughput and crash safety, applied by [`DB`](super::DB) to every write it performs.
+///
+/// All stores of a DB reside in a single column family, so a RocksDB recovery always restores a prefix of the
+/// sequence of writes. Losing recent writes is therefore equivalent to the node having stopped earlier, and never
+/// leaves a DB internally inconsistent. What the profiles differ in is how many recent writes might be lost:
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DurabilityProfile {
+    /// Writes are logged to the WAL without syncing it. A process crash loses nothing, while an OS crash or a power
+    /// loss might lose the writes of the last few seconds
+    #[default]
+    Default,
+
+    /// The WAL is synced to disk on every write. Nothing acknowledged is ever lost, even on power loss, at the price
+    /// of an fsync per written batch (significantly slowing down IBD on disks with high sync latency)
+    HighDurability,
+
+    /// Like [`DurabilityProfile::Default`], except that the WAL is skipped entirely while the DB owner reports a bulk
+    /// sync (see [`DB::set_bulk_sync`](super::DB::set_bulk_sync)), i.e., during IBD. A crash while syncing loses all
+    /// writes since the last memtable flush, which the node then re-syncs. When the bulk sync ends, memtables are
+    /// flushed before the WAL is re-enabled
+    FastSync,
+}
+
+impl FromStr for DurabilityProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(Self::Default),
+            "high" => Ok(Self::HighDurability),
+            "fast-sync" => Ok(Self::FastSync),
+            _ => Err(format!("unknown durability profile '{s}' (expected one of: default, high, fast-sync)")),
+        }
+    }
+}
+
+impl Display for DurabilityProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::HighDurability => write!(f, "high"),
+            Self::FastSync => write!(f, "fast-sync"),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use super::DB;
+
+/// RocksDB statistics relevant for tuning the stores: compaction load, write amplification and write stalls
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DbStats {
+    /// The bytes written by compactions (including memtable flushes) per byte written by the application
+    pub write_amplification: f64,
+    /// Estimated total bytes compaction needs to rewrite to bring all levels down to their target size
+    pub pending_compaction_bytes: u64,
+    /// The number of currently running compactions
+    pub running_compactions: u64,
+    /// The cumulative time writes were stalled (slowed down or stopped) since the DB was opened
+    pub stall_seconds: f64,
+    /// The current delayed write rate in bytes per second (0 if writes are not being slowed down)
+    pub delayed_write_rate: u64,
+    /// Whether writes are currently stopped
+    pub is_write_stopped: bool,
+}
+
+impl DB {
+    /// Collects the current RocksDB statistics of this DB. Properties which cannot be read are reported as zero
+    pub fn stats(&self) -> DbStats {
+        let int_property = |name: &str| self.property_int_value(name).ok().flatten().unwrap_or_default();
+        let stats = self.property_value("rocksdb.stats").ok().flatten().unwrap_or_default();
+        let (ingested_gb, compaction_written_gb, stall_seconds) = parse_stats(&stats);
+        DbStats {
+            write_amplification: if ingested_gb > 0.0 { compaction_written_gb / ingested_gb } else { 0.0 },
+            pending_compaction_bytes: int_property("rocksdb.estimate-pending-compaction-bytes"),
+            running_compactions: int_property("rocksdb.num-running-compactions"),
+            stall_seconds,
+            delayed_write_rate: int_property("rocksdb.actual-delayed-write-rate"),
+            is_write_stopped: int_property("rocksdb.is-write-stopped") != 0,
+        }
+    }
+}
+
+/// Extracts the cumulative ingested GB, the cumulative GB written by compactions (summed over all column families)
+/// and the cumulative stall time in seconds out of the textual `rocksdb.stats` property
+fn parse_stats(stats: &str) -> (f64, f64, f64) {
+    let (mut ingested_gb, mut compaction_written_gb, mut stall_seconds) = (0.0, 0.0, 0.0);
+    for line in stats.lines() {
+        if let Some(rest) = line.strip_prefix("Cumulative writes:") {
+            // ..., ingest: 0.12 GB, 0.01 MB/s
+            if let Some(value) = rest.split("ingest:").nth(1).and_then(leading_number) {
+                ingested_gb = value;
+            }
+        } else if let Some(rest) = line.strip_prefix("Cumulative compaction:") {
+            // 0.05 GB write, 0.00 MB/s write, 0.04 GB read, 0.00 MB/s read, 0.1 seconds
+            compaction_written_gb += leading_number(rest).unwrap_or_default();
+        } else if let Some(rest) = line.strip_prefix("Cumulative stall:") {
+            // 00:00:1.250 H:M:S, 0.1 percent
+            let elapsed = rest.trim_start().split(' ').next().unwrap_or_default();
+            let parts = elapsed.split(':').map(|part| part.parse::<f64>().ok()).collect::<Option<Vec<_>>>();
+            if let Some([hours, minutes, seconds]) = parts.as_deref() {
+                stall_seconds = hours * 3600.0 + minutes * 60.0 + seconds;
+            }
+        }
+    }
+    (ingested_gb, compaction_written_gb, stall_seconds)
+}
+
+fn leading_number(s: &str) -> Option<f64> {
+    s.split_whitespace().next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stats() {
+        let stats = "
+** DB Stats **
+Uptime(secs): 3600.0 total, 600.0 interval
+Cumulative writes: 1000K writes, 2000K keys, 900K commit groups, 1.1 writes per commit group, ingest: 2.00 GB, 0.57 MB/s
+Cumulative WAL: 1000K writes, 0 syncs, 1000000.00 writes per sync, written: 2.00 GB, 0.57 MB/s
+Cumulative stall: 00:01:2.500 H:M:S, 1.7 percent
+Interval writes: 100K writes, 200K keys, 90K commit groups, 1.1 writes per commit group, ingest: 200.00 MB, 0.33 MB/s
+
+** Compaction Stats [default] **
+Cumulative compaction: 5.50 GB write, 1.56 MB/s write, 4.00 GB read, 1.14 MB/s read, 120.0 seconds
+Interval compaction: 0.50 GB write, 0.85 MB/s write, 0.40 GB read, 0.68 MB/s read, 12.0 seconds
+
+** Compaction Stats [other] **
+Cumulative compaction: 0.50 GB write, 0.14 MB/s write, 0.40 GB read, 0.11 MB/s read, 10.0 seconds
+";
+        let (ingested_gb, compaction_written_gb, stall_seconds) = parse_stats(stats);
+        assert_eq!(ingested_gb, 2.0);
+        assert_eq!(compaction_written_gb, 6.0);
+        assert_eq!(stall_seconds, 62.5);
+        assert_eq!(parse_stats(""), (0.0, 0.0, 0.0));
+    }
+}
@@ -13,12 +13,12 @@ pub mod utils;
 pub mod prelude {
     use crate::{db, errors};
 
-    pub use super::access::CachedDbAccess;
+    pub use super::access::{CachedDbAccess, ScanOptions};
     pub use super::cache::Cache;
     pub use super::item::CachedDbItem;
     pub use super::key::DbKey;
     pub use super::set_access::{CachedDbSetAccess, ReadLock};
     pub use super::writer::{BatchDbWriter, DbWriter, DirectDbWriter, DirectWriter, MemoryWriter};
-    pub use db::{delete_db, ConnBuilder, DB};
+    pub use db::{create_checkpoint, delete_db, CompactionReport, ConnBuilder, ConnError, DbStats, DurabilityProfile, DB};
     pub use errors::{StoreError, StoreResult, StoreResultEmptyTuple, StoreResultExtensions};
 }
@@ -36,19 +36,31 @@ pub enum DatabaseStorePrefixes {
     UtxoMultisets = 26,
     VirtualUtxoset = 27,
     VirtualState = 28,
+    QuarantinedBlocks = 29,
+    InvalidBlocks = 30,
+    ManualInvalidations = 31,
+    ValidationVersions = 32,
 
     // ---- Metadata ----
     MultiConsensusMetadata = 124,
     ConsensusEntries = 125,
+    LifetimeMetrics = 126,
 
     // ---- Components ----
     Addresses = 128,
     BannedAddresses = 129,
+    NodeIdentity = 130,
+    SystemIdentity = 131,
 
     // ---- Indexes ----
     UtxoIndex = 192,
     UtxoIndexTips = 193,
     CirculatingSupply = 194,
+    TxIndexAcceptedTransactions = 195,
+    TxIndexSink = 196,
+    UtxoIndexResyncProgress = 197,
+    TxIndexResyncProgress = 198,
+    TxIndexSpentOutpoints = 199,
 
     // ---- Separator ----
     /// Reserved as a separator
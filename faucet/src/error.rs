@@ -0,0 +1,61 @@
+use hyper::StatusCode;
+use kaspa_consensus_core::network::NetworkId;
+use kaspa_rpc_core::RpcError;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FaucetError {
+    #[error("not found")]
+    NotFound,
+
+    #[error("method not allowed")]
+    MethodNotAllowed,
+
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("request body exceeds {0} bytes")]
+    PayloadTooLarge(usize),
+
+    #[error("rate limit exceeded, retry in {} seconds", .0.as_secs() + 1)]
+    RateLimited(Duration),
+
+    #[error("insufficient faucet funds")]
+    InsufficientFunds,
+
+    #[error("the faucet refuses to run on {0}")]
+    MainnetNotSupported(NetworkId),
+
+    #[error("mining is not supported on {0}, the network requires a proof of work")]
+    MiningNotSupported(NetworkId),
+
+    #[error("block rejected: {0}")]
+    BlockRejected(String),
+
+    #[error("server error: {0}")]
+    Server(String),
+
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+}
+
+impl FaucetError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            FaucetError::NotFound => StatusCode::NOT_FOUND,
+            FaucetError::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            FaucetError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            FaucetError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            FaucetError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            FaucetError::InsufficientFunds => StatusCode::SERVICE_UNAVAILABLE,
+            FaucetError::MainnetNotSupported(_)
+            | FaucetError::MiningNotSupported(_)
+            | FaucetError::BlockRejected(_)
+            | FaucetError::Server(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            FaucetError::Rpc(_) => StatusCode::BAD_GATEWAY,
+        }
+    }
+}
+
+pub type FaucetResult<T> = std::result::Result<T, FaucetError>;
@@ -0,0 +1,200 @@
+use crate::error::{FaucetError, FaucetResult};
+use itertools::Itertools;
+use kaspa_addresses::{Address, Version};
+use kaspa_consensus_core::{
+    config::params::Params,
+    constants::{SOMPI_PER_KASPA, TX_VERSION},
+    network::NetworkId,
+    sign::sign,
+    subnets::SUBNETWORK_ID_NATIVE,
+    tx::{MutableTransaction, Transaction, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry},
+};
+use kaspa_core::info;
+use kaspa_rpc_core::api::rpc::DynRpcService;
+use kaspa_txscript::pay_to_address_script;
+use secp256k1::KeyPair;
+use std::collections::HashSet;
+use tokio::sync::Mutex;
+
+/// Default amount dispensed by a single request (10 KAS)
+pub const DEFAULT_DISPENSE_AMOUNT: u64 = 10 * SOMPI_PER_KASPA;
+/// Default maximum amount a request may ask for (100 KAS)
+pub const DEFAULT_MAX_DISPENSE_AMOUNT: u64 = 100 * SOMPI_PER_KASPA;
+
+const FEE_PER_MASS: u64 = 10;
+const MAX_INPUTS: usize = 84;
+/// Change below this amount is added to the fee instead of creating an output
+const MIN_CHANGE_AMOUNT: u64 = 10_000;
+/// Number of DAA scores a non-coinbase UTXO waits before being spent
+const UTXO_CONFIRMATIONS: u64 = 10;
+
+/// Holds the funds of a single address and dispenses them on request
+pub struct Faucet {
+    rpc: DynRpcService,
+    keypair: KeyPair,
+    address: Address,
+    network_id: NetworkId,
+    params: Params,
+    /// Outpoints spent by submitted transactions that may still be reported by the UTXO index.
+    /// Locking also serializes the dispensing so that concurrent requests do not spend the same UTXOs.
+    pending: Mutex<HashSet<TransactionOutpoint>>,
+}
+
+impl Faucet {
+    /// Creates a faucet holding the funds of the pay-to-pubkey address of `keypair` on the network of the node.
+    /// Fails if the node runs on mainnet, where giving funds away is never intended.
+    pub async fn try_new(rpc: DynRpcService, keypair: KeyPair) -> FaucetResult<Self> {
+        let network_id = rpc.get_block_dag_info().await?.network;
+        if network_id.is_mainnet() {
+            return Err(FaucetError::MainnetNotSupported(network_id));
+        }
+        let address = Address::new(network_id.into(), Version::PubKey, &keypair.x_only_public_key().0.serialize());
+        let params = Params::from(network_id);
+        Ok(Self { rpc, keypair, address, network_id, params, pending: Mutex::new(HashSet::new()) })
+    }
+
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    pub fn network_id(&self) -> NetworkId {
+        self.network_id
+    }
+
+    /// Total amount held by the faucet address, spendable or not
+    pub async fn balance(&self) -> FaucetResult<u64> {
+        Ok(self.rpc.get_balance_by_address(self.address.clone()).await?)
+    }
+
+    /// Mines `count` blocks paying to the faucet address. Only supported on networks skipping
+    /// the proof of work (simnet), the coinbase outputs becoming spendable after the coinbase maturity.
+    pub async fn mine(&self, count: u64) -> FaucetResult<()> {
+        if !self.params.skip_proof_of_work {
+            return Err(FaucetError::MiningNotSupported(self.network_id));
+        }
+        for _ in 0..count {
+            let template = self.rpc.get_block_template(self.address.clone(), vec![]).await?;
+            let report = self.rpc.submit_block(template.block, false).await?.report;
+            if !report.is_success() {
+                return Err(FaucetError::BlockRejected(format!("{report:?}")));
+            }
+        }
+        info!("Mined {} blocks to {}", count, self.address);
+        Ok(())
+    }
+
+    /// Sends `amount` to `recipient`, returning the id of the submitted transaction
+    pub async fn dispense(&self, recipient: &Address, amount: u64) -> FaucetResult<TransactionId> {
+        if recipient.prefix != self.address.prefix {
+            return Err(FaucetError::InvalidRequest(format!("address {recipient} does not belong to {}", self.network_id)));
+        }
+
+        let mut pending = self.pending.lock().await;
+        let utxos = self.rpc.get_utxos_by_addresses(vec![self.address.clone()]).await?;
+        let virtual_daa_score = self.rpc.get_block_dag_info().await?.virtual_daa_score;
+
+        // outpoints no longer reported by the UTXO index have been spent
+        let outpoints = utxos.iter().map(|entry| entry.outpoint).collect::<HashSet<_>>();
+        pending.retain(|outpoint| outpoints.contains(outpoint));
+
+        let spendable = utxos
+            .into_iter()
+            .filter(|entry| !pending.contains(&entry.outpoint) && self.is_spendable(&entry.utxo_entry, virtual_daa_score))
+            .map(|entry| (entry.outpoint, entry.utxo_entry))
+            .sorted_by(|a, b| b.1.amount.cmp(&a.1.amount))
+            .collect_vec();
+        let (selected, fee) = select_utxos(&spendable, amount).ok_or(FaucetError::InsufficientFunds)?;
+
+        let tx = self.create_transaction(&selected, recipient, amount, fee);
+        let transaction_id = self.rpc.submit_transaction((&tx).into(), false).await?;
+        pending.extend(tx.inputs.iter().map(|input| input.previous_outpoint));
+
+        info!("Dispensed {} sompi to {} in transaction {}", amount, recipient, transaction_id);
+        Ok(transaction_id)
+    }
+
+    fn is_spendable(&self, entry: &UtxoEntry, virtual_daa_score: u64) -> bool {
+        let confirmations = if entry.is_coinbase { self.params.coinbase_maturity } else { UTXO_CONFIRMATIONS };
+        entry.block_daa_score + confirmations < virtual_daa_score
+    }
+
+    fn create_transaction(
+        &self,
+        utxos: &[(TransactionOutpoint, UtxoEntry)],
+        recipient: &Address,
+        amount: u64,
+        fee: u64,
+    ) -> Transaction {
+        let inputs = utxos
+            .iter()
+            .map(|(outpoint, _)| TransactionInput {
+                previous_outpoint: *outpoint,
+                signature_script: vec![],
+                sequence: 0,
+                sig_op_count: 1,
+            })
+            .collect_vec();
+
+        let mut outputs = vec![TransactionOutput { value: amount, script_public_key: pay_to_address_script(recipient) }];
+        let change = utxos.iter().map(|(_, entry)| entry.amount).sum::<u64>() - amount - fee;
+        if change >= MIN_CHANGE_AMOUNT {
+            outputs.push(TransactionOutput { value: change, script_public_key: pay_to_address_script(&self.address) });
+        }
+
+        let unsigned_tx = Transaction::new(TX_VERSION, inputs, outputs, 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+        let entries = utxos.iter().map(|(_, entry)| entry.clone()).collect_vec();
+        sign(MutableTransaction::with_entries(unsigned_tx, entries), self.keypair).tx
+    }
+}
+
+fn required_fee(num_utxos: usize, num_outs: u64) -> u64 {
+    FEE_PER_MASS * estimated_mass(num_utxos, num_outs)
+}
+
+fn estimated_mass(num_utxos: usize, num_outs: u64) -> u64 {
+    200 + 34 * num_outs + 1000 * (num_utxos as u64)
+}
+
+/// Selects UTXOs, the largest first, covering `amount` and the fee of a transaction with a change output.
+/// Returns the selected UTXOs and the fee.
+fn select_utxos(utxos: &[(TransactionOutpoint, UtxoEntry)], amount: u64) -> Option<(Vec<(TransactionOutpoint, UtxoEntry)>, u64)> {
+    let mut selected_amount = 0;
+    for (count, (_, entry)) in utxos.iter().take(MAX_INPUTS).enumerate() {
+        selected_amount += entry.amount;
+        let fee = required_fee(count + 1, 2);
+        if selected_amount >= amount + fee {
+            return Some((utxos[..=count].to_vec(), fee));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::tx::ScriptPublicKey;
+
+    #[test]
+    fn test_select_utxos() {
+        let utxos = [50_000_000, 30_000_000, 1_000_000]
+            .into_iter()
+            .enumerate()
+            .map(|(index, amount)| {
+                let outpoint = TransactionOutpoint::new(TransactionId::from_u64_word(index as u64), 0);
+                (outpoint, UtxoEntry::new(amount, ScriptPublicKey::default(), 0, false))
+            })
+            .collect_vec();
+
+        let (selected, fee) = select_utxos(&utxos, 10_000_000).unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(fee, required_fee(1, 2));
+
+        let (selected, fee) = select_utxos(&utxos, 70_000_000).unwrap();
+        assert_eq!(selected.len(), 2);
+        assert_eq!(fee, required_fee(2, 2));
+
+        // the fee can not be covered
+        assert!(select_utxos(&utxos, 81_000_000).is_none());
+        assert!(select_utxos(&[], 1).is_none());
+    }
+}
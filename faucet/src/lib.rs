@@ -0,0 +1,11 @@
+//!
+//! A faucet for simnet and testnet holding the funds of a single address and dispensing them over HTTP,
+//! with a rate limit per client IP and per recipient address. On simnet, the faucet can mine its own funds.
+//!
+//! The [`faucet::Faucet`] can also be used directly, e.g. to fund the wallets of integration tests.
+//!
+
+pub mod error;
+pub mod faucet;
+pub mod limiter;
+pub mod service;
@@ -0,0 +1,66 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Allows a single request per key (a client IP or a recipient address) in every `period`
+pub struct RateLimiter {
+    period: Duration,
+    last_requests: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(period: Duration) -> Self {
+        Self { period, last_requests: Mutex::new(HashMap::new()) }
+    }
+
+    /// Records a request for all `keys` if none of them is limited, otherwise returns the time
+    /// left until the request is allowed
+    pub fn try_acquire(&self, keys: &[&str]) -> Result<(), Duration> {
+        self.try_acquire_at(keys, Instant::now())
+    }
+
+    fn try_acquire_at(&self, keys: &[&str], now: Instant) -> Result<(), Duration> {
+        let mut last_requests = self.last_requests.lock().unwrap();
+        last_requests.retain(|_, last| now.saturating_duration_since(*last) < self.period);
+        if let Some(wait) = keys.iter().filter_map(|key| last_requests.get(*key)).map(|last| self.period - (now - *last)).max() {
+            return Err(wait);
+        }
+        for key in keys {
+            last_requests.insert(key.to_string(), now);
+        }
+        Ok(())
+    }
+
+    /// Forgets the requests recorded for `keys`, used when a request fails
+    pub fn release(&self, keys: &[&str]) {
+        let mut last_requests = self.last_requests.lock().unwrap();
+        for key in keys {
+            last_requests.remove(*key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::new(Duration::from_secs(60));
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire_at(&["127.0.0.1", "kaspasim:a"], now).is_ok());
+        // limited by either key
+        assert_eq!(limiter.try_acquire_at(&["127.0.0.2", "kaspasim:a"], now + Duration::from_secs(20)), Err(Duration::from_secs(40)));
+        assert_eq!(limiter.try_acquire_at(&["127.0.0.1", "kaspasim:b"], now + Duration::from_secs(50)), Err(Duration::from_secs(10)));
+        // a rejected request is not recorded
+        assert!(limiter.try_acquire_at(&["127.0.0.2", "kaspasim:b"], now + Duration::from_secs(50)).is_ok());
+        // the period elapsed
+        assert!(limiter.try_acquire_at(&["127.0.0.1", "kaspasim:a"], now + Duration::from_secs(60)).is_ok());
+
+        limiter.release(&["127.0.0.1", "kaspasim:a"]);
+        assert!(limiter.try_acquire_at(&["127.0.0.1", "kaspasim:a"], now + Duration::from_secs(61)).is_ok());
+    }
+}
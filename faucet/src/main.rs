@@ -0,0 +1,134 @@
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use clap::{Arg, ArgAction, Command};
+use kaspa_core::{info, kaspad_env::version, warn};
+use kaspa_faucet::{
+    faucet::{Faucet, DEFAULT_DISPENSE_AMOUNT, DEFAULT_MAX_DISPENSE_AMOUNT},
+    service::{FaucetServer, FaucetSettings, DEFAULT_RATE_LIMIT_PERIOD},
+};
+use kaspa_grpc_client::GrpcClient;
+use kaspa_rpc_core::notify::mode::NotificationMode;
+use secp256k1::rand::thread_rng;
+
+pub struct Args {
+    pub private_key: Option<String>,
+    pub rpc_server: String,
+    pub listen: SocketAddr,
+    pub amount: u64,
+    pub max_amount: u64,
+    pub rate_limit: u64,
+    pub mine: Option<u64>,
+}
+
+impl Args {
+    fn parse() -> Self {
+        let m = cli().get_matches();
+        Args {
+            private_key: m.get_one::<String>("private-key").cloned(),
+            rpc_server: m.get_one::<String>("rpcserver").cloned().unwrap(),
+            listen: m.get_one::<SocketAddr>("listen").cloned().unwrap(),
+            amount: m.get_one::<u64>("amount").cloned().unwrap(),
+            max_amount: m.get_one::<u64>("max-amount").cloned().unwrap(),
+            rate_limit: m.get_one::<u64>("rate-limit").cloned().unwrap(),
+            mine: m.get_one::<u64>("mine").cloned(),
+        }
+    }
+}
+
+pub fn cli() -> Command {
+    Command::new("faucet")
+        .about(format!("{} (faucet) v{}", env!("CARGO_PKG_DESCRIPTION"), version()))
+        .version(env!("CARGO_PKG_VERSION"))
+        .arg(Arg::new("private-key").long("private-key").short('k').value_name("private-key").help("Private key in hex format"))
+        .arg(
+            Arg::new("rpcserver")
+                .long("rpcserver")
+                .short('s')
+                .value_name("rpcserver")
+                .default_value("localhost:16510")
+                .help("gRPC server of the node, which must run with the UTXO index"),
+        )
+        .arg(
+            Arg::new("listen")
+                .long("listen")
+                .short('l')
+                .value_name("address")
+                .default_value("127.0.0.1:16710")
+                .value_parser(clap::value_parser!(SocketAddr))
+                .help("Interface:port of the HTTP server"),
+        )
+        .arg(
+            Arg::new("amount")
+                .long("amount")
+                .value_name("sompi")
+                .default_value(DEFAULT_DISPENSE_AMOUNT.to_string())
+                .value_parser(clap::value_parser!(u64))
+                .help("Amount dispensed when a request does not specify one"),
+        )
+        .arg(
+            Arg::new("max-amount")
+                .long("max-amount")
+                .value_name("sompi")
+                .default_value(DEFAULT_MAX_DISPENSE_AMOUNT.to_string())
+                .value_parser(clap::value_parser!(u64))
+                .help("Maximum amount a request may ask for"),
+        )
+        .arg(
+            Arg::new("rate-limit")
+                .long("rate-limit")
+                .value_name("seconds")
+                .default_value(DEFAULT_RATE_LIMIT_PERIOD.as_secs().to_string())
+                .value_parser(clap::value_parser!(u64))
+                .help("Period during which a client IP or a recipient address is dispensed funds only once"),
+        )
+        .arg(
+            Arg::new("mine")
+                .long("mine")
+                .value_name("blocks")
+                .num_args(0..=1)
+                .default_missing_value("1000")
+                .action(ArgAction::Set)
+                .value_parser(clap::value_parser!(u64))
+                .help("Mine blocks paying to the faucet before serving requests (simnet only)"),
+        )
+}
+
+#[tokio::main]
+async fn main() {
+    kaspa_core::log::init_logger(None, "");
+    let args = Args::parse();
+
+    let keypair = if let Some(private_key_hex) = args.private_key {
+        let mut private_key_bytes = [0u8; 32];
+        faster_hex::hex_decode(private_key_hex.as_bytes(), &mut private_key_bytes).unwrap();
+        secp256k1::KeyPair::from_seckey_slice(secp256k1::SECP256K1, &private_key_bytes).unwrap()
+    } else {
+        let keypair = secp256k1::KeyPair::new(secp256k1::SECP256K1, &mut thread_rng());
+        warn!("Generated private key {}, rerun the faucet with `--private-key` to keep its funds", keypair.display_secret());
+        keypair
+    };
+
+    let rpc_client =
+        GrpcClient::connect(NotificationMode::Direct, format!("grpc://{}", args.rpc_server), true, None, false, Some(500_000))
+            .await
+            .unwrap();
+    info!("Connected to RPC");
+
+    let faucet = Arc::new(Faucet::try_new(Arc::new(rpc_client), keypair).await.unwrap());
+    info!("Faucet address {} on {}", faucet.address(), faucet.network_id());
+    if let Some(blocks) = args.mine {
+        faucet.mine(blocks).await.unwrap();
+    }
+    info!("Faucet balance: {} sompi", faucet.balance().await.unwrap());
+
+    let settings =
+        FaucetSettings { amount: args.amount, max_amount: args.max_amount, rate_limit_period: Duration::from_secs(args.rate_limit) };
+    let server = Arc::new(FaucetServer::new(faucet, settings));
+    info!("Serving requests on http://{}", args.listen);
+    server
+        .run(args.listen, async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await
+        .unwrap();
+}
@@ -0,0 +1,199 @@
+use crate::{
+    error::{FaucetError, FaucetResult},
+    faucet::{Faucet, DEFAULT_DISPENSE_AMOUNT, DEFAULT_MAX_DISPENSE_AMOUNT},
+    limiter::RateLimiter,
+};
+use hyper::{
+    body::HttpBody,
+    header::CONTENT_TYPE,
+    server::conn::AddrStream,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use kaspa_addresses::Address;
+use kaspa_core::debug;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    convert::Infallible,
+    future::Future,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+/// Default period during which a client IP and a recipient address may be dispensed funds only once
+pub const DEFAULT_RATE_LIMIT_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+const STATUS_PATH: &str = "/v1/status";
+const DISPENSE_PATH: &str = "/v1/dispense";
+const MAX_REQUEST_SIZE: usize = 4096;
+
+#[derive(Clone, Debug)]
+pub struct FaucetSettings {
+    /// Amount (in sompi) dispensed when the request does not specify one
+    pub amount: u64,
+    /// Maximum amount (in sompi) a request may ask for
+    pub max_amount: u64,
+    pub rate_limit_period: Duration,
+}
+
+impl Default for FaucetSettings {
+    fn default() -> Self {
+        Self { amount: DEFAULT_DISPENSE_AMOUNT, max_amount: DEFAULT_MAX_DISPENSE_AMOUNT, rate_limit_period: DEFAULT_RATE_LIMIT_PERIOD }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct DispenseRequest {
+    address: String,
+    amount: Option<u64>,
+}
+
+/// Serves the faucet over HTTP:
+///
+/// - `GET /v1/status` returns the faucet address, network and balance
+/// - `POST /v1/dispense` with a `{ "address": "...", "amount": <sompi> }` JSON body sends funds to the
+///   address, `amount` being optional
+pub struct FaucetServer {
+    faucet: Arc<Faucet>,
+    settings: FaucetSettings,
+    limiter: RateLimiter,
+}
+
+impl FaucetServer {
+    pub fn new(faucet: Arc<Faucet>, settings: FaucetSettings) -> Self {
+        let limiter = RateLimiter::new(settings.rate_limit_period);
+        Self { faucet, settings, limiter }
+    }
+
+    /// Serves requests on `address` until `shutdown` completes
+    pub async fn run(self: Arc<Self>, address: SocketAddr, shutdown: impl Future<Output = ()>) -> FaucetResult<()> {
+        let make_service = make_service_fn(move |connection: &AddrStream| {
+            let this = self.clone();
+            let remote = connection.remote_addr().ip();
+            async move { Ok::<_, Infallible>(service_fn(move |request| this.clone().serve(remote, request))) }
+        });
+        let server = Server::try_bind(&address).map_err(|err| FaucetError::Server(format!("failed binding {address}: {err}")))?;
+        debug!("Faucet listening on {}", address);
+        server.serve(make_service).with_graceful_shutdown(shutdown).await.map_err(|err| FaucetError::Server(err.to_string()))
+    }
+
+    async fn serve(self: Arc<Self>, remote: IpAddr, request: Request<Body>) -> Result<Response<Body>, Infallible> {
+        let method = request.method().clone();
+        let uri = request.uri().clone();
+        let result = match (request.method(), request.uri().path()) {
+            (&Method::GET, STATUS_PATH) => self.status().await,
+            (&Method::POST, DISPENSE_PATH) => self.dispense(remote, request).await,
+            (_, STATUS_PATH | DISPENSE_PATH) => Err(FaucetError::MethodNotAllowed),
+            _ => Err(FaucetError::NotFound),
+        };
+        Ok(match result {
+            Ok(body) => json_response(StatusCode::OK, &body),
+            Err(err) => {
+                debug!("Faucet request {} {} from {} failed: {}", method, uri, remote, err);
+                json_response(err.status(), &json!({ "error": err.to_string() }))
+            }
+        })
+    }
+
+    async fn status(&self) -> FaucetResult<Value> {
+        Ok(json!({
+            "address": self.faucet.address().to_string(),
+            "network": self.faucet.network_id().to_string(),
+            "balance": self.faucet.balance().await?,
+            "amount": self.settings.amount,
+            "maxAmount": self.settings.max_amount,
+        }))
+    }
+
+    async fn dispense(&self, remote: IpAddr, request: Request<Body>) -> FaucetResult<Value> {
+        let body = read_body(request.into_body(), MAX_REQUEST_SIZE).await?;
+        let (address, amount) = parse_dispense_request(&body, &self.settings)?;
+
+        let (remote, recipient) = (remote.to_string(), address.to_string());
+        let keys = [remote.as_str(), recipient.as_str()];
+        self.limiter.try_acquire(&keys).map_err(FaucetError::RateLimited)?;
+        match self.faucet.dispense(&address, amount).await {
+            Ok(transaction_id) => Ok(json!({ "transactionId": transaction_id.to_string(), "amount": amount })),
+            Err(err) => {
+                self.limiter.release(&keys);
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Reads the request body, failing as soon as it exceeds `limit` bytes so that oversized bodies are never buffered
+async fn read_body(mut body: Body, limit: usize) -> FaucetResult<Vec<u8>> {
+    if body.size_hint().lower() > limit as u64 {
+        return Err(FaucetError::PayloadTooLarge(limit));
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|err| FaucetError::InvalidRequest(err.to_string()))?;
+        if bytes.len() + chunk.len() > limit {
+            return Err(FaucetError::PayloadTooLarge(limit));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+fn parse_dispense_request(body: &[u8], settings: &FaucetSettings) -> FaucetResult<(Address, u64)> {
+    let request: DispenseRequest = serde_json::from_slice(body).map_err(|err| FaucetError::InvalidRequest(err.to_string()))?;
+    let address = Address::try_from(request.address.as_str()).map_err(|err| FaucetError::InvalidRequest(err.to_string()))?;
+    let amount = request.amount.unwrap_or(settings.amount);
+    if amount == 0 || amount > settings.max_amount {
+        return Err(FaucetError::InvalidRequest(format!("amount must be between 1 and {} sompi", settings.max_amount)));
+    }
+    Ok((address, amount))
+}
+
+fn json_response(status: StatusCode, body: &Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("the response parts are valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_addresses::{Prefix, Version};
+
+    #[test]
+    fn test_parse_dispense_request() {
+        let settings = FaucetSettings { amount: 1_000, max_amount: 5_000, ..Default::default() };
+        let address = Address::new(Prefix::Simnet, Version::PubKey, &[1; 32]);
+
+        let body = json!({ "address": address.to_string() }).to_string();
+        assert_eq!(parse_dispense_request(body.as_bytes(), &settings).unwrap(), (address.clone(), 1_000));
+        let body = json!({ "address": address.to_string(), "amount": 5_000 }).to_string();
+        assert_eq!(parse_dispense_request(body.as_bytes(), &settings).unwrap(), (address.clone(), 5_000));
+
+        for body in [
+            json!({ "address": address.to_string(), "amount": 5_001 }),
+            json!({ "address": address.to_string(), "amount": 0 }),
+            json!({ "address": "kaspasim:invalid" }),
+            json!({ "address": address.to_string(), "unknown": true }),
+            json!({}),
+        ] {
+            let result = parse_dispense_request(body.to_string().as_bytes(), &settings);
+            assert!(matches!(result, Err(FaucetError::InvalidRequest(_))), "{body}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_body() {
+        assert_eq!(read_body(Body::from(vec![7u8; 16]), 16).await.unwrap(), vec![7u8; 16]);
+        assert!(matches!(read_body(Body::from(vec![7u8; 17]), 16).await, Err(FaucetError::PayloadTooLarge(16))));
+
+        // A streamed body without a known length is cut off once the limit is exceeded
+        let (mut sender, body) = Body::channel();
+        tokio::spawn(async move { while sender.send_data(vec![7u8; 8].into()).await.is_ok() {} });
+        assert!(matches!(read_body(body, 16).await, Err(FaucetError::PayloadTooLarge(16))));
+    }
+}
@@ -2,3 +2,4 @@ pub mod connection;
 pub mod indexed_utxos;
 pub mod notification;
 pub mod notifier;
+pub mod progress;
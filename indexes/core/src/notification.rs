@@ -5,7 +5,7 @@ use kaspa_notify::{
     full_featured,
     notification::Notification as NotificationTrait,
     subscription::{
-        single::{OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
+        single::{AcceptedTransactionsSubscription, OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
         Subscription,
     },
 };
@@ -44,6 +44,10 @@ impl NotificationTrait for Notification {
         }
     }
 
+    fn apply_accepted_transactions_subscription(&self, _subscription: &AcceptedTransactionsSubscription) -> Option<Self> {
+        Some(self.clone())
+    }
+
     fn event_type(&self) -> EventType {
         self.into()
     }
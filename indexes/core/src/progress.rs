@@ -0,0 +1,47 @@
+use log::info;
+use std::time::{Duration, Instant};
+
+/// Maximum time to go without report
+const REPORT_TIME_GRANULARITY: Duration = Duration::from_secs(5);
+
+/// Reports the progress of an index resync to the log, at most once per [`REPORT_TIME_GRANULARITY`]
+pub struct ResyncProgressReporter {
+    ident: &'static str,
+    object_name: &'static str,
+    /// The total number of objects to process, if known in advance
+    total: Option<u64>,
+    processed: u64,
+    last_log_time: Instant,
+}
+
+impl ResyncProgressReporter {
+    /// Creates a reporter for a resync starting with `processed` objects already processed, as is the
+    /// case when resuming an interrupted resync
+    pub fn new(ident: &'static str, object_name: &'static str, total: Option<u64>, processed: u64) -> Self {
+        Self { ident, object_name, total, processed, last_log_time: Instant::now() }
+    }
+
+    pub fn processed(&self) -> u64 {
+        self.processed
+    }
+
+    pub fn report(&mut self, processed_delta: u64) {
+        self.processed += processed_delta;
+        let now = Instant::now();
+        if now - self.last_log_time < REPORT_TIME_GRANULARITY {
+            return;
+        }
+        match self.total {
+            Some(total) if total > 0 => {
+                let percent = ((self.processed as f64 / total as f64) * 100.0) as i32;
+                info!("[{}] resync: processed {} {} ({}%)", self.ident, self.processed, self.object_name, percent.min(99));
+            }
+            _ => info!("[{}] resync: processed {} {}", self.ident, self.processed, self.object_name),
+        }
+        self.last_log_time = now;
+    }
+
+    pub fn report_completion(self) {
+        info!("[{}] resync complete: processed {} {} (100%)", self.ident, self.processed, self.object_name);
+    }
+}
@@ -4,7 +4,7 @@ use kaspa_notify::{
     full_featured,
     notification::Notification as NotificationTrait,
     subscription::{
-        single::{OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
+        single::{AcceptedTransactionsSubscription, OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
         Single,
     },
 };
@@ -39,6 +39,10 @@ impl NotificationTrait for Notification {
         Some(self.clone())
     }
 
+    fn apply_accepted_transactions_subscription(&self, _subscription: &AcceptedTransactionsSubscription) -> Option<Self> {
+        Some(self.clone())
+    }
+
     fn event_type(&self) -> EventType {
         self.into()
     }
@@ -0,0 +1,90 @@
+use kaspa_consensus_core::{
+    acceptance_data::AcceptanceData,
+    tx::{TransactionId, TransactionOutpoint},
+};
+use kaspa_consensusmanager::spawn_blocking;
+use kaspa_database::prelude::StoreResult;
+use kaspa_hashes::Hash;
+use parking_lot::RwLock;
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{errors::TxIndexResult, model::TxAcceptanceLocation};
+
+/// Txindex API targeted at retrieval calls.
+pub trait TxIndexApi: Send + Sync + Debug {
+    /// Retrieve the acceptance location of a transaction, or `None` if it is not accepted by the selected chain.
+    ///
+    /// Note: Use a read lock when accessing this method
+    fn get_transaction_location(&self, transaction_id: TransactionId) -> StoreResult<Option<TxAcceptanceLocation>>;
+
+    /// Retrieve the id of the accepted transaction spending `outpoint`, or `None` if no transaction accepted by the
+    /// selected chain spends it.
+    ///
+    /// Note: Use a read lock when accessing this method
+    fn get_spending_transaction_id(&self, outpoint: TransactionOutpoint) -> StoreResult<Option<TransactionId>>;
+
+    /// Retrieve the sink the txindex is synced with.
+    ///
+    /// Note: Use a read lock when accessing this method
+    fn get_sink(&self) -> StoreResult<Hash>;
+
+    /// Checks if the txindex's db is synced with consensus.
+    ///
+    /// Note:
+    /// 1) Use a read lock when accessing this method
+    /// 2) due to potential sync-gaps is_synced is unreliable while consensus is actively resolving virtual states.
+    fn is_synced(&self) -> TxIndexResult<bool>;
+
+    /// Update the txindex with the given virtual chain change, rolling back the acceptance diff of the removed chain blocks
+    /// and indexing the transactions accepted by the added ones, atomically.
+    ///
+    /// Note: Use a write lock when accessing this method
+    fn update(
+        &mut self,
+        removed_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+    ) -> TxIndexResult<()>;
+
+    /// Resync the txindex from the consensus db
+    ///
+    /// Note: Use a write lock when accessing this method
+    fn resync(&mut self) -> TxIndexResult<()>;
+}
+
+/// Async proxy for the transaction index
+#[derive(Debug, Clone)]
+pub struct TxIndexProxy {
+    inner: Arc<RwLock<dyn TxIndexApi>>,
+}
+
+impl TxIndexProxy {
+    pub fn new(inner: Arc<RwLock<dyn TxIndexApi>>) -> Self {
+        Self { inner }
+    }
+
+    pub async fn get_transaction_location(self, transaction_id: TransactionId) -> StoreResult<Option<TxAcceptanceLocation>> {
+        spawn_blocking(move || self.inner.read().get_transaction_location(transaction_id)).await.unwrap()
+    }
+
+    pub async fn get_spending_transaction_id(self, outpoint: TransactionOutpoint) -> StoreResult<Option<TransactionId>> {
+        spawn_blocking(move || self.inner.read().get_spending_transaction_id(outpoint)).await.unwrap()
+    }
+
+    pub async fn update(
+        self,
+        removed_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+    ) -> TxIndexResult<()> {
+        spawn_blocking(move || {
+            self.inner.write().update(
+                removed_chain_blocks_acceptance_data,
+                added_chain_block_hashes,
+                added_chain_blocks_acceptance_data,
+            )
+        })
+        .await
+        .unwrap()
+    }
+}
@@ -0,0 +1,13 @@
+use kaspa_hashes::Hash;
+use serde::{Deserialize, Serialize};
+
+/// The location of an accepted transaction within the DAG
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxAcceptanceLocation {
+    /// The selected chain block which accepted the transaction
+    pub accepting_block_hash: Hash,
+    /// The (merged) block whose body includes the transaction
+    pub including_block_hash: Hash,
+    /// The index of the transaction within the body of the including block
+    pub index_within_block: u32,
+}
@@ -0,0 +1,330 @@
+use crate::{
+    api::TxIndexApi,
+    errors::{TxIndexError, TxIndexResult},
+    model::TxAcceptanceLocation,
+    stores::store_manager::Store,
+    IDENT,
+};
+use kaspa_consensus_core::{
+    acceptance_data::AcceptanceData,
+    api::ConsensusApi,
+    tx::{TransactionId, TransactionOutpoint},
+};
+use kaspa_consensusmanager::{ConsensusManager, ConsensusResetHandler};
+use kaspa_core::{info, trace};
+use kaspa_database::prelude::{StoreError, StoreResult, DB};
+use kaspa_hashes::Hash;
+use kaspa_index_core::progress::ResyncProgressReporter;
+use parking_lot::RwLock;
+use std::{
+    fmt::Debug,
+    sync::{Arc, Weak},
+};
+
+/// The number of chain blocks whose acceptance data is processed per batch during a resync
+const RESYNC_CHUNK_SIZE: usize = 256;
+
+/// TxIndex indexes the transactions accepted by the selected chain by their [`TransactionId`], keeping track of
+/// the accepting chain block and of the block including the transaction.
+/// Note: The TxIndex struct by itself is not thread save, only correct usage of the supplied RwLock via `new` makes it so.
+pub struct TxIndex {
+    consensus_manager: Arc<ConsensusManager>,
+    store: Store,
+}
+
+impl TxIndex {
+    /// Creates a new [`TxIndex`] within a [`RwLock`]
+    pub fn new(consensus_manager: Arc<ConsensusManager>, db: Arc<DB>) -> TxIndexResult<Arc<RwLock<Self>>> {
+        let mut txindex = Self { consensus_manager: consensus_manager.clone(), store: Store::new(db) };
+        if !txindex.is_synced()? && !txindex.try_partial_resync()? {
+            txindex.resync()?;
+        }
+        let txindex = Arc::new(RwLock::new(txindex));
+        consensus_manager.register_consensus_reset_handler(Arc::new(TxIndexConsensusResetHandler::new(Arc::downgrade(&txindex))));
+        Ok(txindex)
+    }
+
+    /// Brings the txindex up to date with consensus by indexing the virtual chain change from the txindex sink to the
+    /// consensus sink, as needed when consensus moved while the txindex was disabled. Returns false, leaving a full
+    /// resync to the caller, if the txindex holds no sink or if consensus pruned it meanwhile.
+    fn try_partial_resync(&mut self) -> TxIndexResult<bool> {
+        // An interrupted resync is resumed by a full resync
+        match self.store.get_resync_pruning_point() {
+            Ok(_) => return Ok(false),
+            Err(StoreError::KeyNotFound(_)) => {}
+            Err(err) => return Err(TxIndexError::StoreAccessError(err)),
+        }
+        let txindex_sink = match self.store.get_sink() {
+            Ok(sink) => sink,
+            Err(StoreError::KeyNotFound(_)) => return Ok(false),
+            Err(err) => return Err(TxIndexError::StoreAccessError(err)),
+        };
+
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        let consensus_sink = session.get_sink();
+        if !session.is_chain_ancestor_of(session.pruning_point(), txindex_sink).unwrap_or(false) {
+            info!("The txindex sink {} was pruned by consensus (sink {}), a full resync is required", txindex_sink, consensus_sink);
+            return Ok(false);
+        }
+
+        let chain_path = session.get_virtual_chain_from_block(txindex_sink)?;
+        info!(
+            "The txindex sink {} diverges from the consensus sink {}, partially resyncing by removing {} and adding {} chain blocks...",
+            txindex_sink,
+            consensus_sink,
+            chain_path.removed.len(),
+            chain_path.added.len()
+        );
+
+        // Removals are committed along with the first added batch, so that the txindex sink always reflects the indexed chain
+        let mut removed_acceptance_data = session.get_blocks_acceptance_data(&chain_path.removed)?;
+        if chain_path.added.is_empty() {
+            self.commit_acceptance_changes(&session, &removed_acceptance_data, &[], &[], Some(consensus_sink))?;
+            return Ok(true);
+        }
+
+        let mut progress_reporter = ResyncProgressReporter::new(IDENT, "chain blocks", Some(chain_path.added.len() as u64), 0);
+        for chunk in chain_path.added.chunks(RESYNC_CHUNK_SIZE) {
+            trace!("[{0}] resyncing with a batch of {1} chain blocks from consensus db", IDENT, chunk.len());
+            let acceptance_data = session.get_blocks_acceptance_data(chunk)?;
+            let removed_acceptance_data = std::mem::take(&mut removed_acceptance_data);
+            self.commit_acceptance_changes(&session, &removed_acceptance_data, chunk, &acceptance_data, chunk.last().copied())?;
+            progress_reporter.report(chunk.len() as u64);
+        }
+        progress_reporter.report_completion();
+        Ok(true)
+    }
+
+    /// Atomically un-indexes the transactions accepted according to `removed_acceptance_data` and indexes the ones
+    /// accepted by `added_chain_block_hashes` according to `added_acceptance_data`, along with the outpoints they spend,
+    /// and sets `sink` as the txindex sink
+    fn commit_acceptance_changes(
+        &mut self,
+        consensus: &dyn ConsensusApi,
+        removed_acceptance_data: &[Arc<AcceptanceData>],
+        added_chain_block_hashes: &[Hash],
+        added_acceptance_data: &[Arc<AcceptanceData>],
+        sink: Option<Hash>,
+    ) -> TxIndexResult<()> {
+        let mut to_remove = Self::unaccepted_transactions(removed_acceptance_data);
+        let mut to_add = added_chain_block_hashes
+            .iter()
+            .zip(added_acceptance_data.iter())
+            .flat_map(|(&accepting_block_hash, acceptance_data)| Self::accepted_transactions(accepting_block_hash, acceptance_data));
+        let spent_to_remove = Self::spent_outpoints(consensus, removed_acceptance_data)?;
+        let spent_to_add = Self::spent_outpoints(consensus, added_acceptance_data)?;
+
+        self.store.update_accepted_transactions(
+            &mut to_remove,
+            &mut to_add,
+            &mut spent_to_remove.into_iter().map(|(outpoint, _)| outpoint),
+            &mut spent_to_add.into_iter(),
+            sink,
+        )?;
+        Ok(())
+    }
+
+    /// Returns the outpoints spent by the transactions accepted according to `acceptance_data`, along with the id
+    /// of the spending transaction, reading the transactions of their including blocks with a single batched read
+    fn spent_outpoints(
+        consensus: &dyn ConsensusApi,
+        acceptance_data: &[Arc<AcceptanceData>],
+    ) -> TxIndexResult<Vec<(TransactionOutpoint, TransactionId)>> {
+        let mergeset_blocks = acceptance_data
+            .iter()
+            .flat_map(|acceptance_data| acceptance_data.iter())
+            .filter(|mergeset_block| !mergeset_block.accepted_transactions.is_empty())
+            .collect::<Vec<_>>();
+        let block_hashes = mergeset_blocks.iter().map(|mergeset_block| mergeset_block.block_hash).collect::<Vec<_>>();
+        let blocks_transactions = consensus.get_blocks_transactions(&block_hashes)?;
+
+        let mut spent_outpoints = vec![];
+        for (mergeset_block, transactions) in mergeset_blocks.into_iter().zip(blocks_transactions) {
+            for entry in mergeset_block.accepted_transactions.iter() {
+                let transaction = &transactions[entry.index_within_block as usize];
+                spent_outpoints.extend(transaction.inputs.iter().map(|input| (input.previous_outpoint, entry.transaction_id)));
+            }
+        }
+        Ok(spent_outpoints)
+    }
+
+    fn unaccepted_transactions(removed_acceptance_data: &[Arc<AcceptanceData>]) -> impl Iterator<Item = TransactionId> + Clone + '_ {
+        removed_acceptance_data.iter().flat_map(|acceptance_data| {
+            acceptance_data
+                .iter()
+                .flat_map(|mergeset_block| mergeset_block.accepted_transactions.iter().map(|entry| entry.transaction_id))
+        })
+    }
+
+    fn accepted_transactions(
+        accepting_block_hash: Hash,
+        acceptance_data: &AcceptanceData,
+    ) -> impl Iterator<Item = (TransactionId, TxAcceptanceLocation)> + Clone + '_ {
+        acceptance_data.iter().flat_map(move |mergeset_block| {
+            mergeset_block.accepted_transactions.iter().map(move |entry| {
+                (
+                    entry.transaction_id,
+                    TxAcceptanceLocation {
+                        accepting_block_hash,
+                        including_block_hash: mergeset_block.block_hash,
+                        index_within_block: entry.index_within_block,
+                    },
+                )
+            })
+        })
+    }
+}
+
+impl TxIndexApi for TxIndex {
+    fn get_transaction_location(&self, transaction_id: TransactionId) -> StoreResult<Option<TxAcceptanceLocation>> {
+        trace!("[{0}] retrieving location of transaction {1}", IDENT, transaction_id);
+
+        self.store.get_transaction_location(transaction_id)
+    }
+
+    fn get_spending_transaction_id(&self, outpoint: TransactionOutpoint) -> StoreResult<Option<TransactionId>> {
+        trace!("[{0}] retrieving the transaction spending {1}", IDENT, outpoint);
+
+        self.store.get_spending_transaction_id(outpoint)
+    }
+
+    fn get_sink(&self) -> StoreResult<Hash> {
+        self.store.get_sink()
+    }
+
+    /// Checks to see if the [TxIndex] is sync'd. This is done via comparing the txindex committed sink with the consensus sink.
+    ///
+    /// **Note:** Due to sync gaps between the txindex and consensus, this function is only reliable while consensus is not processing new blocks.
+    fn is_synced(&self) -> TxIndexResult<bool> {
+        trace!("[{0}] checking sync status...", IDENT);
+
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        // An interrupted resync leaves the txindex partially synced
+        match self.store.get_resync_pruning_point() {
+            Ok(_) => {
+                trace!("[{0}] sync status is {1}", IDENT, false);
+                return Ok(false);
+            }
+            Err(StoreError::KeyNotFound(_)) => {}
+            Err(err) => return Err(TxIndexError::StoreAccessError(err)),
+        }
+
+        match self.store.get_sink() {
+            Ok(txindex_sink) => {
+                let res = txindex_sink == session.get_sink();
+                trace!("[{0}] sync status is {1}", IDENT, res);
+                Ok(res)
+            }
+            Err(StoreError::KeyNotFound(_)) => {
+                // Means the txindex sink is empty i.e. not sync'd.
+                trace!("[{0}] sync status is {1}", IDENT, false);
+                Ok(false)
+            }
+            Err(err) => Err(TxIndexError::StoreAccessError(err)),
+        }
+    }
+
+    /// Updates the [TxIndex] with a virtual chain change: transactions accepted by removed chain blocks are
+    /// un-indexed, and the ones accepted by the added chain blocks are indexed, in a single atomic write.
+    fn update(
+        &mut self,
+        removed_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+    ) -> TxIndexResult<()> {
+        trace!("[{0}] updating...", IDENT);
+        trace!("[{0}] removing {1} chain blocks", IDENT, removed_chain_blocks_acceptance_data.len());
+        trace!("[{0}] adding {1} chain blocks", IDENT, added_chain_block_hashes.len());
+
+        // The spent outpoints are read from the bodies of the blocks including the transactions
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        self.commit_acceptance_changes(
+            &session,
+            &removed_chain_blocks_acceptance_data,
+            &added_chain_block_hashes,
+            &added_chain_blocks_acceptance_data,
+            added_chain_block_hashes.last().copied(),
+        )
+    }
+
+    /// Deletes and reinstates the txindex database, syncing it from scratch via the acceptance data of
+    /// the selected chain from the pruning point to the sink.
+    ///
+    /// Every batch of chain blocks is committed along with its last block as the txindex sink, so that a
+    /// resync interrupted by a shutdown is resumed by the next one from that block, provided that the
+    /// pruning point did not move and that the block is still on the selected chain.
+    fn resync(&mut self) -> TxIndexResult<()> {
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        let pruning_point = session.pruning_point();
+        let chain_path = session.get_virtual_chain_from_block(pruning_point)?;
+
+        let resume_position = match (self.store.get_resync_pruning_point(), self.store.get_sink()) {
+            (Ok(resync_pruning_point), Ok(sink)) if resync_pruning_point == pruning_point => {
+                chain_path.added.iter().position(|&hash| hash == sink).map(|position| position + 1)
+            }
+            (Ok(_), Ok(_)) | (Err(StoreError::KeyNotFound(_)), _) | (_, Err(StoreError::KeyNotFound(_))) => None,
+            (Err(err), _) | (_, Err(err)) => return Err(TxIndexError::StoreAccessError(err)),
+        };
+        let resume_position = match resume_position {
+            Some(position) => {
+                info!("Resuming the txindex resync after {} of {} chain blocks...", position, chain_path.added.len());
+                position
+            }
+            None => {
+                info!("Resyncing the txindex...");
+                self.store.delete_all()?;
+                self.store.set_resync_pruning_point(pruning_point)?;
+                0
+            }
+        };
+        let mut progress_reporter =
+            ResyncProgressReporter::new(IDENT, "chain blocks", Some(chain_path.added.len() as u64), resume_position as u64);
+
+        for chunk in chain_path.added[resume_position..].chunks(RESYNC_CHUNK_SIZE) {
+            trace!("[{0}] resyncing with a batch of {1} chain blocks from consensus db", IDENT, chunk.len());
+            let acceptance_data = session.get_blocks_acceptance_data(chunk)?;
+            self.commit_acceptance_changes(&session, &[], chunk, &acceptance_data, chunk.last().copied())?;
+            progress_reporter.report(chunk.len() as u64);
+        }
+
+        if chain_path.added.is_empty() {
+            self.commit_acceptance_changes(&session, &[], &[], &[], Some(pruning_point))?;
+        }
+
+        self.store.remove_resync_pruning_point()?;
+        progress_reporter.report_completion();
+        Ok(())
+    }
+}
+
+impl Debug for TxIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TxIndex").finish()
+    }
+}
+
+struct TxIndexConsensusResetHandler {
+    txindex: Weak<RwLock<TxIndex>>,
+}
+
+impl TxIndexConsensusResetHandler {
+    fn new(txindex: Weak<RwLock<TxIndex>>) -> Self {
+        Self { txindex }
+    }
+}
+
+impl ConsensusResetHandler for TxIndexConsensusResetHandler {
+    fn handle_consensus_reset(&self) {
+        if let Some(txindex) = self.txindex.upgrade() {
+            txindex.write().resync().unwrap();
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use kaspa_consensus_core::tx::TransactionId;
+use kaspa_database::{
+    prelude::{CachedDbAccess, DbWriter, StoreError, StoreResult, DB},
+    registry::DatabaseStorePrefixes,
+};
+
+use crate::model::TxAcceptanceLocation;
+
+/// Reader API for `AcceptedTransactionsStore`.
+pub trait AcceptedTransactionsStoreReader {
+    fn get(&self, transaction_id: TransactionId) -> StoreResult<Option<TxAcceptanceLocation>>;
+}
+
+pub trait AcceptedTransactionsStore: AcceptedTransactionsStoreReader {
+    fn insert_many(
+        &mut self,
+        writer: impl DbWriter,
+        entries: &mut (impl Iterator<Item = (TransactionId, TxAcceptanceLocation)> + Clone),
+    ) -> StoreResult<()>;
+    fn remove_many(
+        &mut self,
+        writer: impl DbWriter,
+        transaction_ids: &mut (impl Iterator<Item = TransactionId> + Clone),
+    ) -> StoreResult<()>;
+    fn delete_all(&mut self, writer: impl DbWriter) -> StoreResult<()>;
+}
+
+/// A DB + cache implementation of `AcceptedTransactionsStore` trait, mapping accepted transaction ids to their location
+#[derive(Clone)]
+pub struct DbAcceptedTransactionsStore {
+    access: CachedDbAccess<TransactionId, TxAcceptanceLocation>,
+}
+
+impl DbAcceptedTransactionsStore {
+    pub fn new(db: Arc<DB>, cache_size: u64) -> Self {
+        Self { access: CachedDbAccess::new(db, cache_size, DatabaseStorePrefixes::TxIndexAcceptedTransactions.into()) }
+    }
+}
+
+impl AcceptedTransactionsStoreReader for DbAcceptedTransactionsStore {
+    fn get(&self, transaction_id: TransactionId) -> StoreResult<Option<TxAcceptanceLocation>> {
+        match self.access.read(transaction_id) {
+            Ok(location) => Ok(Some(location)),
+            Err(StoreError::KeyNotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl AcceptedTransactionsStore for DbAcceptedTransactionsStore {
+    fn insert_many(
+        &mut self,
+        writer: impl DbWriter,
+        entries: &mut (impl Iterator<Item = (TransactionId, TxAcceptanceLocation)> + Clone),
+    ) -> StoreResult<()> {
+        self.access.write_many(writer, entries)
+    }
+
+    fn remove_many(
+        &mut self,
+        writer: impl DbWriter,
+        transaction_ids: &mut (impl Iterator<Item = TransactionId> + Clone),
+    ) -> StoreResult<()> {
+        self.access.delete_many(writer, transaction_ids)
+    }
+
+    fn delete_all(&mut self, writer: impl DbWriter) -> StoreResult<()> {
+        self.access.delete_all(writer)
+    }
+}
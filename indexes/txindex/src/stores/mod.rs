@@ -0,0 +1,5 @@
+pub mod accepted_transactions;
+pub mod resync;
+pub mod sink;
+pub mod spent_outpoints;
+pub mod store_manager;
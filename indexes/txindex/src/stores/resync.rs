@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use kaspa_database::{
+    prelude::{CachedDbItem, DbWriter, StoreResult, DB},
+    registry::DatabaseStorePrefixes,
+};
+use kaspa_hashes::Hash;
+
+/// Reader API for `TxIndexResyncStore`.
+pub trait TxIndexResyncStoreReader {
+    fn get(&self) -> StoreResult<Hash>;
+}
+
+pub trait TxIndexResyncStore: TxIndexResyncStoreReader {
+    fn set(&mut self, writer: impl DbWriter, pruning_point: Hash) -> StoreResult<()>;
+    fn remove(&mut self, writer: impl DbWriter) -> StoreResult<()>;
+}
+
+/// A DB + cache implementation of `TxIndexResyncStore` trait, holding the pruning point from which an ongoing
+/// resync indexes the selected chain. Along with the sink, it allows resuming an interrupted resync.
+#[derive(Clone)]
+pub struct DbTxIndexResyncStore {
+    access: CachedDbItem<Hash>,
+}
+
+impl DbTxIndexResyncStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { access: CachedDbItem::new(db, DatabaseStorePrefixes::TxIndexResyncProgress.into()) }
+    }
+}
+
+impl TxIndexResyncStoreReader for DbTxIndexResyncStore {
+    fn get(&self) -> StoreResult<Hash> {
+        self.access.read()
+    }
+}
+
+impl TxIndexResyncStore for DbTxIndexResyncStore {
+    fn set(&mut self, writer: impl DbWriter, pruning_point: Hash) -> StoreResult<()> {
+        self.access.write(writer, &pruning_point)
+    }
+
+    fn remove(&mut self, writer: impl DbWriter) -> StoreResult<()> {
+        self.access.remove(writer)
+    }
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use kaspa_database::{
+    prelude::{CachedDbItem, DbWriter, StoreResult, DB},
+    registry::DatabaseStorePrefixes,
+};
+use kaspa_hashes::Hash;
+
+/// Reader API for `TxIndexSinkStore`.
+pub trait TxIndexSinkStoreReader {
+    fn get(&self) -> StoreResult<Hash>;
+}
+
+pub trait TxIndexSinkStore: TxIndexSinkStoreReader {
+    fn set(&mut self, writer: impl DbWriter, sink: Hash) -> StoreResult<()>;
+    fn remove(&mut self, writer: impl DbWriter) -> StoreResult<()>;
+}
+
+/// A DB + cache implementation of `TxIndexSinkStore` trait, holding the sink the txindex is synced with
+#[derive(Clone)]
+pub struct DbTxIndexSinkStore {
+    access: CachedDbItem<Hash>,
+}
+
+impl DbTxIndexSinkStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { access: CachedDbItem::new(db, DatabaseStorePrefixes::TxIndexSink.into()) }
+    }
+}
+
+impl TxIndexSinkStoreReader for DbTxIndexSinkStore {
+    fn get(&self) -> StoreResult<Hash> {
+        self.access.read()
+    }
+}
+
+impl TxIndexSinkStore for DbTxIndexSinkStore {
+    fn set(&mut self, writer: impl DbWriter, sink: Hash) -> StoreResult<()> {
+        self.access.write(writer, &sink)
+    }
+
+    fn remove(&mut self, writer: impl DbWriter) -> StoreResult<()> {
+        self.access.remove(writer)
+    }
+}
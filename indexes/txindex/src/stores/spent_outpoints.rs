@@ -0,0 +1,107 @@
+use std::{fmt::Display, mem::size_of, sync::Arc};
+
+use kaspa_consensus_core::tx::{TransactionId, TransactionIndexType, TransactionOutpoint};
+use kaspa_database::{
+    prelude::{CachedDbAccess, DbWriter, StoreError, StoreResult, DB},
+    registry::DatabaseStorePrefixes,
+};
+use kaspa_hashes::{Hash, HASH_SIZE};
+
+pub const SPENT_OUTPOINT_KEY_SIZE: usize = HASH_SIZE + size_of::<TransactionIndexType>();
+
+/// A [`TransactionOutpoint`] serialized as a DB key
+#[derive(Eq, Hash, PartialEq, Debug, Copy, Clone)]
+pub struct SpentOutpointKey([u8; SPENT_OUTPOINT_KEY_SIZE]);
+
+impl AsRef<[u8]> for SpentOutpointKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<TransactionOutpoint> for SpentOutpointKey {
+    fn from(outpoint: TransactionOutpoint) -> Self {
+        let mut bytes = [0; SPENT_OUTPOINT_KEY_SIZE];
+        bytes[..HASH_SIZE].copy_from_slice(&outpoint.transaction_id.as_bytes());
+        bytes[HASH_SIZE..].copy_from_slice(&outpoint.index.to_le_bytes());
+        Self(bytes)
+    }
+}
+
+impl From<SpentOutpointKey> for TransactionOutpoint {
+    fn from(key: SpentOutpointKey) -> Self {
+        let transaction_id = Hash::from_slice(&key.0[..HASH_SIZE]);
+        let index = TransactionIndexType::from_le_bytes(key.0[HASH_SIZE..].try_into().expect("expecting index size"));
+        Self::new(transaction_id, index)
+    }
+}
+
+impl Display for SpentOutpointKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        TransactionOutpoint::from(*self).fmt(f)
+    }
+}
+
+/// Reader API for `SpentOutpointsStore`.
+pub trait SpentOutpointsStoreReader {
+    fn get(&self, outpoint: TransactionOutpoint) -> StoreResult<Option<TransactionId>>;
+}
+
+pub trait SpentOutpointsStore: SpentOutpointsStoreReader {
+    fn insert_many(
+        &mut self,
+        writer: impl DbWriter,
+        entries: &mut (impl Iterator<Item = (TransactionOutpoint, TransactionId)> + Clone),
+    ) -> StoreResult<()>;
+    fn remove_many(
+        &mut self,
+        writer: impl DbWriter,
+        outpoints: &mut (impl Iterator<Item = TransactionOutpoint> + Clone),
+    ) -> StoreResult<()>;
+    fn delete_all(&mut self, writer: impl DbWriter) -> StoreResult<()>;
+}
+
+/// A DB + cache implementation of `SpentOutpointsStore` trait, mapping the outpoints spent by accepted transactions
+/// to the id of the spending transaction
+#[derive(Clone)]
+pub struct DbSpentOutpointsStore {
+    access: CachedDbAccess<SpentOutpointKey, TransactionId>,
+}
+
+impl DbSpentOutpointsStore {
+    pub fn new(db: Arc<DB>, cache_size: u64) -> Self {
+        Self { access: CachedDbAccess::new(db, cache_size, DatabaseStorePrefixes::TxIndexSpentOutpoints.into()) }
+    }
+}
+
+impl SpentOutpointsStoreReader for DbSpentOutpointsStore {
+    fn get(&self, outpoint: TransactionOutpoint) -> StoreResult<Option<TransactionId>> {
+        match self.access.read(outpoint.into()) {
+            Ok(transaction_id) => Ok(Some(transaction_id)),
+            Err(StoreError::KeyNotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl SpentOutpointsStore for DbSpentOutpointsStore {
+    fn insert_many(
+        &mut self,
+        writer: impl DbWriter,
+        entries: &mut (impl Iterator<Item = (TransactionOutpoint, TransactionId)> + Clone),
+    ) -> StoreResult<()> {
+        self.access.write_many(writer, &mut entries.clone().map(|(outpoint, transaction_id)| (outpoint.into(), transaction_id)))
+    }
+
+    fn remove_many(
+        &mut self,
+        writer: impl DbWriter,
+        outpoints: &mut (impl Iterator<Item = TransactionOutpoint> + Clone),
+    ) -> StoreResult<()> {
+        self.access.delete_many(writer, &mut outpoints.clone().map(SpentOutpointKey::from))
+    }
+
+    fn delete_all(&mut self, writer: impl DbWriter) -> StoreResult<()> {
+        self.access.delete_all(writer)
+    }
+}
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use kaspa_consensus_core::tx::{TransactionId, TransactionOutpoint};
+use kaspa_core::trace;
+use kaspa_database::prelude::{BatchDbWriter, DirectDbWriter, StoreResult, DB};
+use kaspa_hashes::Hash;
+use rocksdb::WriteBatch;
+
+use crate::{
+    model::TxAcceptanceLocation,
+    stores::{
+        accepted_transactions::{AcceptedTransactionsStore, AcceptedTransactionsStoreReader, DbAcceptedTransactionsStore},
+        resync::{DbTxIndexResyncStore, TxIndexResyncStore, TxIndexResyncStoreReader},
+        sink::{DbTxIndexSinkStore, TxIndexSinkStore, TxIndexSinkStoreReader},
+        spent_outpoints::{DbSpentOutpointsStore, SpentOutpointsStore, SpentOutpointsStoreReader},
+    },
+    IDENT,
+};
+
+#[derive(Clone)]
+pub struct Store {
+    db: Arc<DB>,
+    accepted_transactions_store: DbAcceptedTransactionsStore,
+    spent_outpoints_store: DbSpentOutpointsStore,
+    sink_store: DbTxIndexSinkStore,
+    resync_store: DbTxIndexResyncStore,
+}
+
+impl Store {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self {
+            accepted_transactions_store: DbAcceptedTransactionsStore::new(db.clone(), 0),
+            spent_outpoints_store: DbSpentOutpointsStore::new(db.clone(), 0),
+            sink_store: DbTxIndexSinkStore::new(db.clone()),
+            resync_store: DbTxIndexResyncStore::new(db.clone()),
+            db,
+        }
+    }
+
+    pub fn get_transaction_location(&self, transaction_id: TransactionId) -> StoreResult<Option<TxAcceptanceLocation>> {
+        self.accepted_transactions_store.get(transaction_id)
+    }
+
+    pub fn get_spending_transaction_id(&self, outpoint: TransactionOutpoint) -> StoreResult<Option<TransactionId>> {
+        self.spent_outpoints_store.get(outpoint)
+    }
+
+    pub fn get_sink(&self) -> StoreResult<Hash> {
+        self.sink_store.get()
+    }
+
+    /// Atomically removes the transactions un-accepted by the chain change along with the outpoints they spend,
+    /// adds the newly accepted ones and their spent outpoints and updates the sink. Removals are applied first,
+    /// so a transaction re-accepted by another chain block within the same change remains indexed.
+    pub fn update_accepted_transactions(
+        &mut self,
+        to_remove: &mut (impl Iterator<Item = TransactionId> + Clone),
+        to_add: &mut (impl Iterator<Item = (TransactionId, TxAcceptanceLocation)> + Clone),
+        spent_to_remove: &mut (impl Iterator<Item = TransactionOutpoint> + Clone),
+        spent_to_add: &mut (impl Iterator<Item = (TransactionOutpoint, TransactionId)> + Clone),
+        sink: Option<Hash>,
+    ) -> StoreResult<()> {
+        let mut batch = WriteBatch::default();
+        self.accepted_transactions_store.remove_many(BatchDbWriter::new(&mut batch), to_remove)?;
+        self.spent_outpoints_store.remove_many(BatchDbWriter::new(&mut batch), spent_to_remove)?;
+        self.accepted_transactions_store.insert_many(BatchDbWriter::new(&mut batch), to_add)?;
+        self.spent_outpoints_store.insert_many(BatchDbWriter::new(&mut batch), spent_to_add)?;
+        if let Some(sink) = sink {
+            self.sink_store.set(BatchDbWriter::new(&mut batch), sink)?;
+        }
+        self.db.write(batch)?;
+        Ok(())
+    }
+
+    /// The pruning point from which the ongoing resync indexes the selected chain
+    pub fn get_resync_pruning_point(&self) -> StoreResult<Hash> {
+        self.resync_store.get()
+    }
+
+    pub fn set_resync_pruning_point(&mut self, pruning_point: Hash) -> StoreResult<()> {
+        self.resync_store.set(DirectDbWriter::new(&self.db), pruning_point)
+    }
+
+    pub fn remove_resync_pruning_point(&mut self) -> StoreResult<()> {
+        self.resync_store.remove(DirectDbWriter::new(&self.db))
+    }
+
+    /// Resets the txindex database
+    pub fn delete_all(&mut self) -> StoreResult<()> {
+        trace!("[{0}] attempting to clear txindex database...", IDENT);
+
+        self.sink_store.remove(DirectDbWriter::new(&self.db))?;
+        self.resync_store.remove(DirectDbWriter::new(&self.db))?;
+        self.accepted_transactions_store.delete_all(DirectDbWriter::new(&self.db))?;
+        self.spent_outpoints_store.delete_all(DirectDbWriter::new(&self.db))?;
+
+        trace!("[{0}] clearing txindex database - success!", IDENT);
+
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     api::UtxoIndexApi,
     errors::{UtxoIndexError, UtxoIndexResult},
     model::{CirculatingSupply, UtxoChanges, UtxoSetByScriptPublicKey},
-    stores::store_manager::Store,
+    stores::{resync::UtxoIndexResyncProgress, store_manager::Store},
     update_container::UtxoIndexChanges,
     IDENT,
 };
@@ -11,7 +11,7 @@ use kaspa_consensusmanager::{ConsensusManager, ConsensusResetHandler};
 use kaspa_core::{info, trace};
 use kaspa_database::prelude::{StoreError, StoreResult, DB};
 use kaspa_hashes::Hash;
-use kaspa_index_core::indexed_utxos::BalanceByScriptPublicKey;
+use kaspa_index_core::{indexed_utxos::BalanceByScriptPublicKey, progress::ResyncProgressReporter};
 use kaspa_utils::arc::ArcExtensions;
 use parking_lot::RwLock;
 use std::{
@@ -34,6 +34,8 @@ impl UtxoIndex {
     pub fn new(consensus_manager: Arc<ConsensusManager>, db: Arc<DB>) -> UtxoIndexResult<Arc<RwLock<Self>>> {
         let mut utxoindex = Self { consensus_manager: consensus_manager.clone(), store: Store::new(db) };
         if !utxoindex.is_synced()? {
+            // Utxo diffs of past virtual states are not retained by consensus, so a diverging utxoindex is fully resynced
+            info!("The utxoindex tips diverge from the consensus virtual parents, a full resync is required");
             utxoindex.resync()?;
         }
         let utxoindex = Arc::new(RwLock::new(utxoindex));
@@ -130,44 +132,71 @@ impl UtxoIndexApi for UtxoIndex {
     }
     /// Deletes and reinstates the utxoindex database, syncing it from scratch via the consensus database.
     ///
+    /// The resync commits its progress after every batch of utxos, so that a resync interrupted by a shutdown
+    /// is resumed by the next one, provided that the consensus virtual state did not change in between.
+    ///
     /// **Notes:**
     /// 1) There is an implicit expectation that the consensus store must have [VirtualParent] tips. i.e. consensus database must be initiated.
     /// 2) resyncing while consensus notifies of utxo differences, may result in a corrupted db.
     fn resync(&mut self) -> UtxoIndexResult<()> {
-        info!("Resyncing the utxoindex...");
-
-        self.store.delete_all()?;
         let consensus = self.consensus_manager.consensus();
         let session = futures::executor::block_on(consensus.session_blocking());
 
         let consensus_tips = session.get_virtual_parents();
-        let mut circulating_supply: CirculatingSupply = 0;
-
-        //Initial batch is without specified seek and none-skipping.
-        let mut virtual_utxo_batch = session.get_virtual_utxos(None, RESYNC_CHUNK_SIZE, false);
-        let mut current_chunk_size = virtual_utxo_batch.len();
-        trace!("[{0}] resyncing with batch of {1} utxos from consensus db", IDENT, current_chunk_size);
-        // While loop stops resync attempts from an empty utxo db, and unneeded processing when the utxo state size happens to be a multiple of [`RESYNC_CHUNK_SIZE`]
-        while current_chunk_size > 0 {
+
+        let progress = match self.store.get_resync_progress() {
+            Ok(progress) if progress.tips == consensus_tips => Some(progress),
+            Ok(_) | Err(StoreError::KeyNotFound(_)) => None,
+            Err(err) => return Err(UtxoIndexError::StoreAccessError(err)),
+        };
+        let (mut from_outpoint, mut circulating_supply, utxo_count) = match progress {
+            Some(progress) => {
+                info!("Resuming the utxoindex resync after {} utxos...", progress.utxo_count);
+                (Some(progress.last_outpoint), progress.circulating_supply, progress.utxo_count)
+            }
+            None => {
+                info!("Resyncing the utxoindex...");
+                self.store.delete_all()?;
+                (None, 0, 0)
+            }
+        };
+        let mut progress_reporter = ResyncProgressReporter::new(IDENT, "utxos", None, utxo_count);
+
+        loop {
+            // Initial batch of a new resync is without specified seek and none-skipping, the others skip the last committed outpoint.
+            let virtual_utxo_batch = session.get_virtual_utxos(from_outpoint, RESYNC_CHUNK_SIZE, from_outpoint.is_some());
+            let current_chunk_size = virtual_utxo_batch.len();
+            trace!("[{0}] resyncing with batch of {1} utxos from consensus db", IDENT, current_chunk_size);
+            // Stops resync attempts from an empty utxo db, and unneeded processing when the utxo state size happens to be a multiple of [`RESYNC_CHUNK_SIZE`]
+            if current_chunk_size == 0 {
+                break;
+            }
+
             // Potential optimization TODO: iterating virtual utxos into an [UtxoIndexChanges] struct is a bit of overhead (i.e. a potentially unneeded loop),
             // but some form of pre-iteration is done to extract and commit circulating supply separately.
 
             let mut utxoindex_changes = UtxoIndexChanges::new(); //reset changes.
 
-            let next_outpoint_from = Some(virtual_utxo_batch.last().expect("expected a last outpoint").0);
+            let last_outpoint = virtual_utxo_batch.last().expect("expected a last outpoint").0;
             utxoindex_changes.add_utxos_from_vector(virtual_utxo_batch);
 
             circulating_supply += utxoindex_changes.supply_change as CirculatingSupply;
 
             self.store.update_utxo_state(&utxoindex_changes.utxo_changes.added, &utxoindex_changes.utxo_changes.removed, true)?;
 
+            // Commit the progress only once the batch is, re-adding the batch utxos on resumption being harmless.
+            progress_reporter.report(current_chunk_size as u64);
+            self.store.set_resync_progress(UtxoIndexResyncProgress {
+                tips: consensus_tips.clone(),
+                last_outpoint,
+                circulating_supply,
+                utxo_count: progress_reporter.processed(),
+            })?;
+            from_outpoint = Some(last_outpoint);
+
             if current_chunk_size < RESYNC_CHUNK_SIZE {
                 break;
             };
-
-            virtual_utxo_batch = session.get_virtual_utxos(next_outpoint_from, RESYNC_CHUNK_SIZE, true);
-            current_chunk_size = virtual_utxo_batch.len();
-            trace!("[{0}] resyncing with batch of {1} utxos from consensus db", IDENT, current_chunk_size);
         }
 
         // Commit to the the remaining stores.
@@ -175,9 +204,13 @@ impl UtxoIndexApi for UtxoIndex {
         trace!("[{0}] committing circulating supply {1} from consensus db", IDENT, circulating_supply);
         self.store.insert_circulating_supply(circulating_supply, true)?;
 
+        // The progress is removed before the tips are set, so that an interruption in between leads to a new resync
+        self.store.remove_resync_progress()?;
+
         trace!("[{0}] committing consensus tips {consensus_tips:?} from consensus db", IDENT);
         self.store.set_tips(consensus_tips, true)?;
 
+        progress_reporter.report_completion();
         Ok(())
     }
 
@@ -1,4 +1,5 @@
 mod indexed_utxos;
+pub mod resync;
 pub mod store_manager;
 mod supply;
 mod tips;
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use kaspa_consensus_core::{tx::TransactionOutpoint, BlockHashSet};
+use kaspa_database::{
+    prelude::{CachedDbItem, DirectDbWriter, StoreResult, DB},
+    registry::DatabaseStorePrefixes,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::model::CirculatingSupply;
+
+/// The progress of an ongoing utxoindex resync, allowing to resume it after an interruption
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UtxoIndexResyncProgress {
+    /// The consensus virtual parents the resync syncs the utxoindex with
+    pub tips: BlockHashSet,
+    /// The last virtual utxo committed to the utxoindex
+    pub last_outpoint: TransactionOutpoint,
+    /// The circulating supply of the committed utxos
+    pub circulating_supply: CirculatingSupply,
+    /// The number of committed utxos
+    pub utxo_count: u64,
+}
+
+/// Reader API for `UtxoIndexResyncProgressStore`.
+pub trait UtxoIndexResyncProgressStoreReader {
+    fn get(&self) -> StoreResult<UtxoIndexResyncProgress>;
+}
+
+pub trait UtxoIndexResyncProgressStore: UtxoIndexResyncProgressStoreReader {
+    fn set(&mut self, progress: UtxoIndexResyncProgress) -> StoreResult<()>;
+    fn remove(&mut self) -> StoreResult<()>;
+}
+
+/// A DB + cache implementation of `UtxoIndexResyncProgressStore` trait
+#[derive(Clone)]
+pub struct DbUtxoIndexResyncProgressStore {
+    db: Arc<DB>,
+    access: CachedDbItem<UtxoIndexResyncProgress>,
+}
+
+impl DbUtxoIndexResyncProgressStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbItem::new(db, DatabaseStorePrefixes::UtxoIndexResyncProgress.into()) }
+    }
+}
+
+impl UtxoIndexResyncProgressStoreReader for DbUtxoIndexResyncProgressStore {
+    fn get(&self) -> StoreResult<UtxoIndexResyncProgress> {
+        self.access.read()
+    }
+}
+
+impl UtxoIndexResyncProgressStore for DbUtxoIndexResyncProgressStore {
+    fn set(&mut self, progress: UtxoIndexResyncProgress) -> StoreResult<()> {
+        self.access.write(DirectDbWriter::new(&self.db), &progress)
+    }
+
+    fn remove(&mut self) -> StoreResult<()> {
+        self.access.remove(DirectDbWriter::new(&self.db))
+    }
+}
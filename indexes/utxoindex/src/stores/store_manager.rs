@@ -12,6 +12,9 @@ use crate::{
     model::UtxoSetByScriptPublicKey,
     stores::{
         indexed_utxos::{DbUtxoSetByScriptPublicKeyStore, UtxoSetByScriptPublicKeyStore, UtxoSetByScriptPublicKeyStoreReader},
+        resync::{
+            DbUtxoIndexResyncProgressStore, UtxoIndexResyncProgress, UtxoIndexResyncProgressStore, UtxoIndexResyncProgressStoreReader,
+        },
         supply::{CirculatingSupplyStore, CirculatingSupplyStoreReader, DbCirculatingSupplyStore},
         tips::{DbUtxoIndexTipsStore, UtxoIndexTipsStore, UtxoIndexTipsStoreReader},
     },
@@ -23,6 +26,7 @@ pub struct Store {
     utxoindex_tips_store: DbUtxoIndexTipsStore,
     circulating_supply_store: DbCirculatingSupplyStore,
     utxos_by_script_public_key_store: DbUtxoSetByScriptPublicKeyStore,
+    resync_progress_store: DbUtxoIndexResyncProgressStore,
 }
 
 impl Store {
@@ -30,6 +34,7 @@ impl Store {
         Self {
             utxoindex_tips_store: DbUtxoIndexTipsStore::new(db.clone()),
             circulating_supply_store: DbCirculatingSupplyStore::new(db.clone()),
+            resync_progress_store: DbUtxoIndexResyncProgressStore::new(db.clone()),
             utxos_by_script_public_key_store: DbUtxoSetByScriptPublicKeyStore::new(db, 0),
         }
     }
@@ -103,6 +108,18 @@ impl Store {
         res
     }
 
+    pub fn get_resync_progress(&self) -> StoreResult<UtxoIndexResyncProgress> {
+        self.resync_progress_store.get()
+    }
+
+    pub fn set_resync_progress(&mut self, progress: UtxoIndexResyncProgress) -> StoreResult<()> {
+        self.resync_progress_store.set(progress)
+    }
+
+    pub fn remove_resync_progress(&mut self) -> StoreResult<()> {
+        self.resync_progress_store.remove()
+    }
+
     /// Resets the utxoindex database:
     pub fn delete_all(&mut self) -> StoreResult<()> {
         // TODO: explore possibility of deleting and replacing whole db, currently there is an issue because of file lock and db being in an arc.
@@ -111,6 +128,7 @@ impl Store {
         // Clear all
         self.utxoindex_tips_store.remove()?;
         self.circulating_supply_store.remove()?;
+        self.resync_progress_store.remove()?;
         self.utxos_by_script_public_key_store.delete_all()?;
 
         trace!("[{0}] clearing utxoindex database - success!", IDENT);
@@ -0,0 +1,187 @@
+//!
+//! # Kaspa Rust API
+//!
+//! A single entry point for Rust applications integrating with Kaspa: the consensus API, the primitive
+//! address and transaction types, the RPC clients and the wallet core.
+//!
+//! The workspace crates re-exported here are internal building blocks, their modules moving along with
+//! the node implementation. This crate pins a curated set of their items at stable paths and evolves
+//! them following semver, so depending on `kaspa` rather than on the internal crates protects downstream
+//! applications from these reorganizations. Items which are not re-exported here carry no such guarantee.
+//!
+//! The RPC clients and the wallet core are gated behind the `grpc`, `wrpc` and `wallet` features, all enabled
+//! by default.
+//!
+//! ```ignore
+//! use kaspa::prelude::*;
+//!
+//! let client = GrpcClient::connect(NotificationMode::Direct, "grpc://127.0.0.1:16110".to_string(), false, None, false, None).await?;
+//! let info = client.get_server_info().await?;
+//! ```
+//!
+
+pub mod addresses {
+    //! Kaspa addresses
+    pub use kaspa_addresses::{Address, AddressError, AddressList, Prefix, Version};
+}
+
+pub mod hashes {
+    //! Hashes identifying blocks and transactions
+    pub use kaspa_hashes::{Hash, HASH_SIZE};
+}
+
+pub mod consensus {
+    //! Consensus API and primitive types
+
+    pub use kaspa_consensus_core::api::{ConsensusApi, DynConsensus};
+    pub use kaspa_consensus_core::config::params::Params;
+    pub use kaspa_consensus_core::constants::{MAX_SOMPI, SOMPI_PER_KASPA};
+    pub use kaspa_consensus_core::network::{NetworkId, NetworkType};
+
+    pub mod block {
+        //! Blocks and headers
+        pub use kaspa_consensus_core::block::Block;
+        pub use kaspa_consensus_core::header::Header;
+    }
+
+    pub mod tx {
+        //! Transactions and UTXOs
+        pub use kaspa_consensus_core::tx::{
+            ScriptPublicKey, ScriptPublicKeyVersion, Transaction, TransactionId, TransactionInput, TransactionOutpoint,
+            TransactionOutput, UtxoEntry,
+        };
+    }
+}
+
+pub mod rpc {
+    //! RPC API, messages and notifications, along with the gRPC and wRPC clients implementing the API
+
+    pub use kaspa_notify::listener::ListenerId;
+    pub use kaspa_notify::scope::{
+        AcceptedTransactionsScope, BlockAddedScope, FinalityConflictResolvedScope, FinalityConflictScope,
+        MempoolTransactionRemovedScope, NewBlockTemplateScope, PruningPointUtxoSetOverrideScope, Scope, SinkBlueScoreChangedScope,
+        SyncStateChangedScope, UtxosChangedScope, VirtualChainChangedScope, VirtualDaaScoreChangedScope,
+    };
+    pub use kaspa_notify::subscription::Command;
+    pub use kaspa_rpc_core::api::rpc::RpcApi;
+    pub use kaspa_rpc_core::error::{RpcError, RpcResult};
+    pub use kaspa_rpc_core::notify::mode::NotificationMode;
+    pub use kaspa_rpc_core::Notification;
+
+    pub mod messages {
+        //! RPC requests, responses and notifications, and the types they carry. Items are re-exported one by one, so
+        //! that types added to the internal crates do not join the stable surface unnoticed
+        pub use kaspa_rpc_core::model::{
+            RpcAcceptedTransactionIds, RpcAcceptedTransactions, RpcAddress, RpcBalancesByAddressesEntry, RpcBlock,
+            RpcBlockVerboseData, RpcBlueWorkType, RpcContextualPeerAddress, RpcFeeRateBucket, RpcHash, RpcHeader, RpcIpAddress,
+            RpcMempoolEntry, RpcMempoolEntryByAddress, RpcMempoolRemovalReason, RpcNetworkId, RpcNetworkType, RpcNodeId,
+            RpcPeerAddress, RpcPeerInfo, RpcPeerTraffic, RpcScriptPublicKey, RpcScriptVec, RpcSubnetworkId, RpcTransaction,
+            RpcTransactionAcceptanceProof, RpcTransactionId, RpcTransactionInput, RpcTransactionInputVerboseData,
+            RpcTransactionOutpoint, RpcTransactionOutput, RpcTransactionOutputVerboseData, RpcTransactionVerboseData, RpcUtxoEntry,
+            RpcUtxosByAddressesEntry,
+        };
+
+        pub use kaspa_rpc_core::model::{
+            AcceptedTransactionsNotification, AddPeerRequest, AddPeerResponse, BanRequest, BanResponse, BlockAddedNotification,
+            CheckTransactionRequest, CheckTransactionResponse, ConsensusMetrics, EstimateNetworkHashesPerSecondRequest,
+            EstimateNetworkHashesPerSecondResponse, ExtendedConsensusMetrics, FinalityConflictNotification,
+            FinalityConflictResolvedNotification, GetBalanceByAddressRequest, GetBalanceByAddressResponse,
+            GetBalancesByAddressesRequest, GetBalancesByAddressesResponse, GetBlockCountRequest, GetBlockCountResponse,
+            GetBlockDagInfoRequest, GetBlockDagInfoResponse, GetBlockDagTimingInfoRequest, GetBlockDagTimingInfoResponse,
+            GetBlockRelationsRequest, GetBlockRelationsResponse, GetBlockRequest, GetBlockResponse, GetBlockTemplateRequest,
+            GetBlockTemplateResponse, GetBlockValidationTraceRequest, GetBlockValidationTraceResponse, GetBlocksRequest,
+            GetBlocksResponse, GetCoinSupplyRequest, GetCoinSupplyResponse, GetConnectedPeerInfoRequest, GetConnectedPeerInfoResponse,
+            GetCurrentDifficultyRequest, GetCurrentDifficultyResponse, GetCurrentNetworkRequest, GetCurrentNetworkResponse,
+            GetDaaScoreTimestampEstimateRequest, GetDaaScoreTimestampEstimateResponse, GetDagBlocksAroundRequest,
+            GetDagBlocksAroundResponse, GetHeadersRequest, GetHeadersResponse, GetHealthRequest, GetHealthResponse, GetInfoRequest,
+            GetInfoResponse, GetMempoolEntriesByAddressesRequest, GetMempoolEntriesByAddressesResponse, GetMempoolEntriesRequest,
+            GetMempoolEntriesResponse, GetMempoolEntryRequest, GetMempoolEntryResponse, GetMempoolFeeHistogramRequest,
+            GetMempoolFeeHistogramResponse, GetMetricsRequest, GetMetricsResponse, GetNetworkStatsRequest, GetNetworkStatsResponse,
+            GetPeerAddressesRequest, GetPeerAddressesResponse, GetRelayPolicyRequest, GetRelayPolicyResponse, GetServerInfoRequest,
+            GetServerInfoResponse, GetSinkBlueScoreRequest, GetSinkBlueScoreResponse, GetSinkRequest, GetSinkResponse,
+            GetSpendingTransactionRequest, GetSpendingTransactionResponse, GetStateDigestsRequest, GetStateDigestsResponse,
+            GetSubnetworkRequest, GetSubnetworkResponse, GetSyncStatusRequest, GetSyncStatusResponse, GetSystemInfoRequest,
+            GetSystemInfoResponse, GetTransactionAcceptanceProofRequest, GetTransactionAcceptanceProofResponse, GetTransactionRequest,
+            GetTransactionResponse, GetUtxoReturnAddressRequest, GetUtxoReturnAddressResponse, GetUtxoSetChunkRequest,
+            GetUtxoSetChunkResponse, GetUtxosByAddressesRequest, GetUtxosByAddressesResponse, GetVirtualChainFromBlockRequest,
+            GetVirtualChainFromBlockResponse, HandshakeRequest, HandshakeResponse, InvalidateBlockRequest, InvalidateBlockResponse,
+            LifetimeMetrics, LifetimeRunRecord, MempoolTransactionRemovedNotification, NewBlockTemplateNotification,
+            NotifyAcceptedTransactionsRequest, NotifyAcceptedTransactionsResponse, NotifyBlockAddedRequest, NotifyBlockAddedResponse,
+            NotifyFinalityConflictRequest, NotifyFinalityConflictResolvedRequest, NotifyFinalityConflictResolvedResponse,
+            NotifyFinalityConflictResponse, NotifyMempoolTransactionRemovedRequest, NotifyMempoolTransactionRemovedResponse,
+            NotifyNewBlockTemplateRequest, NotifyNewBlockTemplateResponse, NotifyPruningPointUtxoSetOverrideRequest,
+            NotifyPruningPointUtxoSetOverrideResponse, NotifySinkBlueScoreChangedRequest, NotifySinkBlueScoreChangedResponse,
+            NotifySyncStateChangedRequest, NotifySyncStateChangedResponse, NotifyUtxosChangedRequest, NotifyUtxosChangedResponse,
+            NotifyVirtualChainChangedRequest, NotifyVirtualChainChangedResponse, NotifyVirtualDaaScoreChangedRequest,
+            NotifyVirtualDaaScoreChangedResponse, P2pMessageMetrics, PingRequest, PingResponse, ProcessMetrics,
+            PruningPointUtxoSetOverrideNotification, ReconsiderBlockRequest, ReconsiderBlockResponse, ReloadSettingsRequest,
+            ReloadSettingsResponse, ResetLifetimeMetricsRequest, ResetLifetimeMetricsResponse, ResolveFinalityConflictRequest,
+            ResolveFinalityConflictResponse, RpcDagBlock, RpcMethodMetrics, RpcRuleTrace, RpcServiceHealth, RpcStateDigest,
+            RpcSyncState, ShutdownRequest, ShutdownResponse, SinkBlueScoreChangedNotification, StartBlockProductionRequest,
+            StartBlockProductionResponse, StopBlockProductionRequest, StopBlockProductionResponse, SubmitBlockRejectReason,
+            SubmitBlockReport, SubmitBlockRequest, SubmitBlockResponse, SubmitTransactionRequest, SubmitTransactionResponse,
+            SubscribeResponse, SyncStateChangedNotification, UnbanRequest, UnbanResponse, UnsubscribeResponse,
+            UtxosChangedNotification, VirtualChainChangedNotification, VirtualDaaScoreChangedNotification,
+        };
+    }
+
+    #[cfg(feature = "grpc")]
+    pub mod grpc {
+        //! gRPC client
+        pub use kaspa_grpc_client::error::{Error, Result};
+        pub use kaspa_grpc_client::GrpcClient;
+    }
+
+    #[cfg(feature = "wrpc")]
+    pub mod wrpc {
+        //! wRPC (WebSocket) client
+        pub use kaspa_wrpc_client::client::{ConnectOptions, ConnectStrategy};
+        pub use kaspa_wrpc_client::error::Error;
+        pub use kaspa_wrpc_client::result::Result;
+        pub use kaspa_wrpc_client::{KaspaRpcClient, WrpcEncoding};
+    }
+}
+
+#[cfg(feature = "wallet")]
+pub mod wallet {
+    //! Wallet core: wallet and account management, UTXO tracking and transaction generation
+
+    pub use kaspa_wallet_core::error::Error;
+    pub use kaspa_wallet_core::events::{Events, SyncState};
+    pub use kaspa_wallet_core::result::Result;
+    pub use kaspa_wallet_core::runtime::{Account, AccountId, AccountKind, Balance, Wallet};
+    pub use kaspa_wallet_core::secret::Secret;
+    pub use kaspa_wallet_core::tx::{
+        Fees, Generator, GeneratorSummary, PaymentDestination, PaymentOutput, PaymentOutputs, PendingTransaction,
+    };
+    pub use kaspa_wallet_core::utils::{kaspa_to_sompi, sompi_to_kaspa, sompi_to_kaspa_string};
+    pub use kaspa_wallet_core::utxo::{UtxoContext, UtxoProcessor};
+}
+
+pub mod prelude {
+    //! The most commonly used items
+    pub use crate::addresses::Address;
+    pub use crate::consensus::tx::{Transaction, TransactionId};
+    pub use crate::consensus::{NetworkId, NetworkType};
+    pub use crate::hashes::Hash;
+    #[cfg(feature = "grpc")]
+    pub use crate::rpc::grpc::GrpcClient;
+    #[cfg(feature = "wrpc")]
+    pub use crate::rpc::wrpc::{KaspaRpcClient, WrpcEncoding};
+    pub use crate::rpc::{NotificationMode, RpcApi, RpcError, RpcResult};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{addresses::*, consensus::*};
+
+    #[test]
+    fn test_public_surface() {
+        let address = Address::try_from("kaspa:qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqkx9awp4e").unwrap();
+        assert_eq!((address.prefix, address.version), (Prefix::Mainnet, Version::PubKey));
+
+        let network_id: NetworkId = "testnet-11".parse().unwrap();
+        assert_eq!(network_id.network_type(), NetworkType::Testnet);
+        assert_eq!(MAX_SOMPI / SOMPI_PER_KASPA, 29_000_000_000);
+    }
+}
@@ -0,0 +1,230 @@
+//!
+//! Management of the per-network application directory: `<appdir>/<network>/{datadir,logs}`.
+//!
+//! A node holds an exclusive lock on a `.lock` file within the network directory for as long as it runs, so
+//! that two processes never open the same databases. The lock is an OS advisory lock, hence it is released
+//! by the OS when a node crashes and a stale lockfile never blocks a restart.
+//!
+
+use kaspa_core::{core::Core, info, service::Service, warn};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::Arc,
+    thread::JoinHandle,
+};
+use thiserror::Error;
+
+const LOCK_FILE: &str = ".lock";
+
+#[derive(Error, Debug)]
+pub enum AppDirError {
+    #[error("the application directory {0} is in use by another kaspad process{1}")]
+    Locked(String, String),
+
+    #[error("both a legacy directory {0} and its replacement {1} exist, please remove one of them")]
+    MigrationConflict(String, String),
+
+    #[error("failed moving the legacy directory {0} to {1}: {2}")]
+    MigrationFailed(String, String, io::Error),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+pub type AppDirResult<T> = std::result::Result<T, AppDirError>;
+
+/// An exclusive lock on a network directory, released when dropped
+pub struct AppDirLock {
+    _file: File,
+    path: PathBuf,
+}
+
+impl AppDirLock {
+    pub const IDENT: &'static str = "app-dir-lock";
+
+    /// Creates `network_dir` if missing and acquires its lock. Fails with [`AppDirError::Locked`] if the
+    /// directory is locked by another process (or by another lock within this process)
+    pub fn acquire(network_dir: &Path) -> AppDirResult<Self> {
+        fs::create_dir_all(network_dir)?;
+        let path = network_dir.join(LOCK_FILE);
+        let mut file = match open_exclusive(&path) {
+            Ok(file) => file,
+            Err(err) if is_lock_conflict(&err) => {
+                // The lock holder writes its pid for diagnostics
+                let mut pid = String::new();
+                let _ = File::open(&path).and_then(|mut file| file.read_to_string(&mut pid));
+                let pid = if pid.trim().is_empty() { String::new() } else { format!(" (pid {})", pid.trim()) };
+                return Err(AppDirError::Locked(network_dir.display().to_string(), pid));
+            }
+            Err(err) => return Err(err.into()),
+        };
+        file.set_len(0)?;
+        file.rewind()?;
+        write!(file, "{}", process::id())?;
+        file.sync_all()?;
+        Ok(Self { _file: file, path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(unix)]
+fn open_exclusive(path: &Path) -> io::Result<File> {
+    use std::os::unix::io::AsRawFd;
+
+    let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(file)
+}
+
+#[cfg(unix)]
+fn is_lock_conflict(err: &io::Error) -> bool {
+    err.kind() == io::ErrorKind::WouldBlock
+}
+
+#[cfg(windows)]
+fn open_exclusive(path: &Path) -> io::Result<File> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    // A share mode of zero denies any other open of the file for as long as the handle is held
+    OpenOptions::new().read(true).write(true).create(true).truncate(false).share_mode(0).open(path)
+}
+
+#[cfg(windows)]
+fn is_lock_conflict(err: &io::Error) -> bool {
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+    err.raw_os_error() == Some(ERROR_SHARING_VIOLATION)
+}
+
+/// The lock is bound to the [`Core`] so that it is held until all services are shut down
+impl Service for AppDirLock {
+    fn ident(self: Arc<Self>) -> &'static str {
+        Self::IDENT
+    }
+
+    fn start(self: Arc<Self>, _core: Arc<Core>) -> Vec<JoinHandle<()>> {
+        vec![]
+    }
+
+    fn stop(self: Arc<Self>) {}
+}
+
+/// Moves the directories of the legacy flat layout, `<appdir>/{datadir,logs}`, to the per-network layout
+/// `<appdir>/<network>/{datadir,logs}`. Returns the directories which were moved. If moving any of the directories
+/// fails, the ones already moved are moved back, so that the legacy layout is left as is
+pub fn migrate_legacy_layout(app_dir: &Path, network_dir: &Path, dirs: &[&str]) -> AppDirResult<Vec<PathBuf>> {
+    let pending = legacy_dirs(app_dir, network_dir, dirs)?;
+    fs::create_dir_all(network_dir)?;
+    migrate(&pending)?;
+    Ok(pending.into_iter().map(|(_, target)| target).collect())
+}
+
+fn migrate(pending: &[(PathBuf, PathBuf)]) -> AppDirResult<()> {
+    for (i, (legacy, target)) in pending.iter().enumerate() {
+        if let Err(err) = checked_rename(legacy, target) {
+            for (legacy, target) in pending[..i].iter().rev() {
+                match fs::rename(target, legacy) {
+                    Ok(()) => info!("Moved {} back to {}", target.display(), legacy.display()),
+                    Err(err) => warn!("Failed moving {} back to {}: {}", target.display(), legacy.display(), err),
+                }
+            }
+            return Err(AppDirError::MigrationFailed(legacy.display().to_string(), target.display().to_string(), err));
+        }
+        info!("Migrated legacy directory {} to {}", legacy.display(), target.display());
+    }
+    Ok(())
+}
+
+/// Renames the `legacy` directory to `target`, refusing to replace an existing `target` (which a rename silently
+/// does for an empty directory on some platforms) and checking the directory was actually moved
+fn checked_rename(legacy: &Path, target: &Path) -> io::Result<()> {
+    if target.exists() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, "the target already exists"));
+    }
+    fs::rename(legacy, target)?;
+    if legacy.exists() || !target.is_dir() {
+        return Err(io::Error::new(io::ErrorKind::Other, "the directory was not moved"));
+    }
+    Ok(())
+}
+
+/// Returns the pairs of legacy directories and their per-network replacements which are yet to be migrated
+pub fn legacy_dirs(app_dir: &Path, network_dir: &Path, dirs: &[&str]) -> AppDirResult<Vec<(PathBuf, PathBuf)>> {
+    let mut pending = Vec::new();
+    for dir in dirs {
+        let (legacy, target) = (app_dir.join(dir), network_dir.join(dir));
+        if !legacy.is_dir() {
+            continue;
+        }
+        if target.exists() {
+            return Err(AppDirError::MigrationConflict(legacy.display().to_string(), target.display().to_string()));
+        }
+        pending.push((legacy, target));
+    }
+    Ok(pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_dir_lock() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let network_dir = app_dir.path().join("kaspa-devnet");
+
+        let lock = AppDirLock::acquire(&network_dir).unwrap();
+        assert_eq!(fs::read_to_string(lock.path()).unwrap(), process::id().to_string());
+        assert!(matches!(AppDirLock::acquire(&network_dir), Err(AppDirError::Locked(..))));
+
+        // A released lock, as well as a lockfile left behind, does not block acquiring the directory again
+        drop(lock);
+        let _lock = AppDirLock::acquire(&network_dir).unwrap();
+    }
+
+    #[test]
+    fn test_legacy_layout_migration() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let network_dir = app_dir.path().join("kaspa-devnet");
+        fs::create_dir_all(app_dir.path().join("datadir").join("consensus")).unwrap();
+
+        let migrated = migrate_legacy_layout(app_dir.path(), &network_dir, &["datadir", "logs"]).unwrap();
+        assert_eq!(migrated, vec![network_dir.join("datadir")]);
+        assert!(network_dir.join("datadir").join("consensus").is_dir());
+        assert!(!app_dir.path().join("datadir").exists());
+
+        // Nothing is left to migrate, while a legacy directory conflicting with an existing one is refused
+        assert!(legacy_dirs(app_dir.path(), &network_dir, &["datadir", "logs"]).unwrap().is_empty());
+        fs::create_dir_all(app_dir.path().join("datadir")).unwrap();
+        assert!(matches!(migrate_legacy_layout(app_dir.path(), &network_dir, &["datadir"]), Err(AppDirError::MigrationConflict(..))));
+    }
+
+    #[test]
+    fn test_legacy_layout_migration_rollback() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let network_dir = app_dir.path().join("kaspa-devnet");
+        fs::create_dir_all(app_dir.path().join("datadir").join("consensus")).unwrap();
+        fs::create_dir_all(&network_dir).unwrap();
+
+        // The second directory cannot be moved, so the first one is moved back to the legacy layout
+        let pending = vec![
+            (app_dir.path().join("datadir"), network_dir.join("datadir")),
+            (app_dir.path().join("logs"), network_dir.join("logs")),
+        ];
+        assert!(matches!(migrate(&pending), Err(AppDirError::MigrationFailed(..))));
+        assert!(app_dir.path().join("datadir").join("consensus").is_dir());
+        assert!(!network_dir.join("datadir").exists());
+
+        // A rename never replaces an existing directory, even an empty one
+        fs::create_dir_all(network_dir.join("datadir")).unwrap();
+        assert!(matches!(migrate(&pending[..1]), Err(AppDirError::MigrationFailed(..))));
+        assert!(app_dir.path().join("datadir").join("consensus").is_dir());
+    }
+}
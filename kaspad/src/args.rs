@@ -2,23 +2,29 @@ use clap::ArgAction;
 #[allow(unused)]
 use clap::{arg, command, Arg, Command};
 
-#[cfg(feature = "devnet-prealloc")]
 use kaspa_addresses::Address;
 #[cfg(feature = "devnet-prealloc")]
 use kaspa_consensus_core::tx::{TransactionOutpoint, UtxoEntry};
-#[cfg(feature = "devnet-prealloc")]
 use kaspa_txscript::pay_to_address_script;
 #[cfg(feature = "devnet-prealloc")]
 use std::sync::Arc;
 
 use kaspa_consensus_core::{
-    config::Config,
+    config::{genesis::GenesisAllocation, Config, DEFAULT_BLOCK_PROCESSING_BUDGET, DEFAULT_STALE_TIP_TIMEOUT},
     network::{NetworkId, NetworkType},
+    state_digest::DEFAULT_STATE_DIGEST_INTERVAL,
 };
 
 use kaspa_core::kaspad_env::version;
+use kaspa_database::prelude::DurabilityProfile;
+use kaspa_rpc_core::api::profile::{RpcListener, RpcProfile};
+use std::{
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+};
 
-use kaspa_utils::networking::ContextualNetAddress;
+use ipnet::IpNet;
+use kaspa_utils::networking::{ContextualNetAddress, IpAddress, NetAddress};
 use kaspa_wrpc_server::address::WrpcNetAddress;
 
 #[derive(Debug, Clone)]
@@ -27,21 +33,30 @@ pub struct Args {
     pub appdir: Option<String>,
     pub logdir: Option<String>,
     pub no_log_files: bool,
-    pub rpclisten: Option<ContextualNetAddress>,
-    pub rpclisten_borsh: Option<WrpcNetAddress>,
-    pub rpclisten_json: Option<WrpcNetAddress>,
+    pub rpclisten: Vec<RpcListener<ContextualNetAddress>>,
+    pub rpclisten_borsh: Vec<RpcListener<WrpcNetAddress>>,
+    pub rpclisten_json: Vec<RpcListener<WrpcNetAddress>>,
+    pub restlisten: Option<SocketAddr>,
     pub unsafe_rpc: bool,
     pub wrpc_verbose: bool,
     pub log_level: String,
     pub async_threads: usize,
+    pub validation_threads: Option<usize>,
+    pub reserved_cores: usize,
+    pub pin_validation_threads: bool,
+    pub block_pipeline_capacity: Option<usize>,
+    pub block_pipeline_max_mb: Option<u64>,
     pub connect_peers: Vec<ContextualNetAddress>,
     pub add_peers: Vec<ContextualNetAddress>,
     pub listen: Option<ContextualNetAddress>,
     pub user_agent_comments: Vec<String>,
     pub utxoindex: bool,
+    pub txindex: bool,
     pub reset_db: bool,
     pub outbound_target: usize,
     pub inbound_limit: usize,
+    pub whitelist: Vec<IpNet>,
+    pub reserved_inbound: usize,
     pub rpc_max_clients: usize,
     pub enable_unsynced_mining: bool,
     pub enable_mainnet_mining: bool,
@@ -63,8 +78,48 @@ pub struct Args {
     pub prealloc_address: Option<String>,
     #[cfg(feature = "devnet-prealloc")]
     pub prealloc_amount: u64,
+    /// UTXOs paying the given amounts (in sompi) to the given addresses, materialized into the genesis UTXO set
+    pub genesis_allocations: Vec<(Address, u64)>,
 
     pub disable_upnp: bool,
+    pub disable_netgroup_diversity: bool,
+    pub proxy: Option<SocketAddr>,
+    pub disable_clearnet: bool,
+    pub stale_tip_timeout: Option<u64>,
+    pub compact_block_relay: bool,
+    pub headers_only: bool,
+    pub utxo_snapshot: Option<String>,
+    pub export_utxo_snapshot: Option<String>,
+    pub pruned_archive_dir: Option<String>,
+    pub durability: DurabilityProfile,
+    pub export_state: Option<String>,
+    pub import_state: Option<String>,
+    pub integrity_check: bool,
+    pub revalidate_invalid: Option<u16>,
+    pub revalidate_version: Option<u16>,
+    pub trace_validation: bool,
+    pub block_processing_budget: Option<u64>,
+    pub stale_branch_daa_threshold: u64,
+    pub stale_template_daa_threshold: Option<u64>,
+    pub reload_settings: Option<String>,
+    pub coinbase_tag: Option<String>,
+    pub journal: Option<String>,
+    pub journal_listen: Option<SocketAddr>,
+    pub nats_sink: Option<String>,
+    pub nats_subject_prefix: String,
+    pub state_digest_interval: Option<u64>,
+    pub rpc_shed_pipeline_threshold: u64,
+    pub rpc_max_heavy_requests: u64,
+    pub trace_requests: Option<String>,
+    pub min_relay_tx_fee: Option<u64>,
+    pub min_output_value: Option<u64>,
+    pub max_mempool_ancestors: Option<u64>,
+    pub max_mempool_descendants: Option<u64>,
+    pub max_mempool_chain_mass: Option<u64>,
+    pub cache_warmup_blocks: u64,
+    pub produce_blocks_address: Option<String>,
+    pub produce_blocks_rate: f64,
+    pub doctor: bool,
 }
 
 impl Default for Args {
@@ -72,14 +127,23 @@ impl Default for Args {
         Self {
             appdir: Some("datadir".into()),
             no_log_files: false,
-            rpclisten_borsh: None,
-            rpclisten_json: None,
+            rpclisten_borsh: vec![],
+            rpclisten_json: vec![],
+            restlisten: None,
             unsafe_rpc: false,
             async_threads: num_cpus::get(),
+            validation_threads: None,
+            reserved_cores: 1,
+            pin_validation_threads: false,
+            block_pipeline_capacity: None,
+            block_pipeline_max_mb: None,
             utxoindex: false,
+            txindex: false,
             reset_db: false,
             outbound_target: 8,
             inbound_limit: 128,
+            whitelist: vec![],
+            reserved_inbound: 8,
             rpc_max_clients: 128,
             enable_unsynced_mining: false,
             enable_mainnet_mining: false,
@@ -90,7 +154,7 @@ impl Default for Args {
             archival: false,
             sanity: false,
             logdir: Some("".into()),
-            rpclisten: None,
+            rpclisten: vec![],
             wrpc_verbose: false,
             log_level: "INFO".into(),
             connect_peers: vec![],
@@ -109,8 +173,47 @@ impl Default for Args {
             prealloc_address: None,
             #[cfg(feature = "devnet-prealloc")]
             prealloc_amount: 1_000_000,
+            genesis_allocations: vec![],
 
             disable_upnp: false,
+            disable_netgroup_diversity: false,
+            proxy: None,
+            disable_clearnet: false,
+            stale_tip_timeout: None,
+            compact_block_relay: false,
+            headers_only: false,
+            utxo_snapshot: None,
+            export_utxo_snapshot: None,
+            pruned_archive_dir: None,
+            durability: DurabilityProfile::Default,
+            export_state: None,
+            import_state: None,
+            integrity_check: false,
+            revalidate_invalid: None,
+            revalidate_version: None,
+            trace_validation: false,
+            block_processing_budget: None,
+            stale_branch_daa_threshold: 0,
+            stale_template_daa_threshold: None,
+            reload_settings: None,
+            coinbase_tag: None,
+            journal: None,
+            journal_listen: None,
+            nats_sink: None,
+            nats_subject_prefix: "kaspa".to_string(),
+            state_digest_interval: None,
+            rpc_shed_pipeline_threshold: 0,
+            rpc_max_heavy_requests: 0,
+            trace_requests: None,
+            min_relay_tx_fee: None,
+            min_output_value: None,
+            max_mempool_ancestors: None,
+            max_mempool_descendants: None,
+            max_mempool_chain_mass: None,
+            cache_warmup_blocks: 0,
+            produce_blocks_address: None,
+            produce_blocks_rate: 1.0,
+            doctor: false,
         }
     }
 }
@@ -118,8 +221,40 @@ impl Default for Args {
 impl Args {
     pub fn apply_to_config(&self, config: &mut Config) {
         config.utxoindex = self.utxoindex;
+        config.txindex = self.txindex;
         config.disable_upnp = self.disable_upnp;
-        config.unsafe_rpc = self.unsafe_rpc;
+        config.disable_netgroup_diversity = self.disable_netgroup_diversity;
+        config.p2p_proxy = self.proxy.map(NetAddress::from);
+        config.disable_clearnet = self.disable_clearnet;
+        config.stale_tip_timeout = self.stale_tip_timeout.unwrap_or(DEFAULT_STALE_TIP_TIMEOUT);
+        config.perf.set_validation_threads(self.validation_threads());
+        config.perf.pin_worker_threads = self.pin_validation_threads;
+        if let Some(capacity) = self.block_pipeline_capacity {
+            config.perf.block_pipeline_capacity = capacity;
+        }
+        if let Some(max_mb) = self.block_pipeline_max_mb {
+            config.perf.block_pipeline_max_bytes = max_mb * 1024 * 1024;
+        }
+        config.compact_block_relay = self.compact_block_relay;
+        config.headers_only = self.headers_only;
+        config.utxo_snapshot_path = self.utxo_snapshot.as_ref().map(PathBuf::from);
+        config.pruned_archive_dir = self.pruned_archive_dir.as_ref().map(PathBuf::from);
+        config.trace_validation = self.trace_validation;
+        config.block_processing_budget = self.block_processing_budget.unwrap_or(DEFAULT_BLOCK_PROCESSING_BUDGET);
+        config.state_digest_interval = self.state_digest_interval.unwrap_or(DEFAULT_STATE_DIGEST_INTERVAL);
+        config.stale_branch_daa_threshold = self.stale_branch_daa_threshold;
+        config.stale_template_daa_threshold = self.stale_template_daa_threshold;
+        config.rpc_shed_pipeline_threshold = self.rpc_shed_pipeline_threshold;
+        config.rpc_max_heavy_requests = self.rpc_max_heavy_requests;
+        config.cache_warmup_blocks = self.cache_warmup_blocks;
+        // The node runs in unsafe RPC mode as soon as one listener allows it, each RPC call being then restricted by the
+        // profile of the listener which received it
+        let mut listener_profiles = self
+            .rpclisten
+            .iter()
+            .map(|listener| listener.profile)
+            .chain(self.rpclisten_borsh.iter().chain(self.rpclisten_json.iter()).map(|listener| listener.profile));
+        config.unsafe_rpc = self.unsafe_rpc || listener_profiles.any(|profile| profile == Some(RpcProfile::Unsafe));
         config.enable_unsynced_mining = self.enable_unsynced_mining;
         config.is_archival = self.archival;
         // TODO: change to `config.enable_sanity_checks = self.sanity` when we reach stable versions
@@ -133,6 +268,40 @@ impl Args {
         if let Some(num_prealloc_utxos) = self.num_prealloc_utxos {
             config.initial_utxo_set = Arc::new(self.generate_prealloc_utxos(num_prealloc_utxos));
         }
+
+        if !self.genesis_allocations.is_empty() {
+            // Params only hold static data, so the allocations are leaked once at startup to live along the config
+            let allocations = self
+                .genesis_allocations
+                .iter()
+                .map(|(address, amount)| {
+                    let script_public_key = pay_to_address_script(address);
+                    GenesisAllocation {
+                        script_public_key_version: script_public_key.version(),
+                        script: Box::leak(Box::<[u8]>::from(script_public_key.script())),
+                        amount: *amount,
+                    }
+                })
+                .collect::<Vec<_>>();
+            config.params.genesis_allocations = Box::leak(allocations.into_boxed_slice());
+        }
+    }
+
+    /// Returns the total amount of sompi in the genesis UTXO set, or `None` on overflow
+    pub fn genesis_supply(&self) -> Option<u64> {
+        #[allow(unused_mut)]
+        let mut supply = self.genesis_allocations.iter().try_fold(0u64, |supply, (_, amount)| supply.checked_add(*amount));
+        #[cfg(feature = "devnet-prealloc")]
+        if let Some(num_prealloc_utxos) = self.num_prealloc_utxos {
+            supply = supply.and_then(|supply| supply.checked_add(num_prealloc_utxos.checked_mul(self.prealloc_amount)?));
+        }
+        supply
+    }
+
+    /// The number of validation threads shared by the validation pools: as specified, or otherwise the number of
+    /// physical cores minus the reserved cores (at least one)
+    pub fn validation_threads(&self) -> usize {
+        self.validation_threads.unwrap_or_else(|| num_cpus::get_physical().saturating_sub(self.reserved_cores).max(1))
     }
 
     #[cfg(feature = "devnet-prealloc")]
@@ -149,6 +318,24 @@ impl Args {
             .collect()
     }
 
+    /// Returns the permission profile of the RPC listeners not setting one explicitly
+    pub fn default_rpc_profile(&self) -> RpcProfile {
+        if self.unsafe_rpc {
+            RpcProfile::Unsafe
+        } else {
+            RpcProfile::Safe
+        }
+    }
+
+    /// Returns the gRPC listeners, defaulting to a single listener on all interfaces
+    pub fn grpc_listeners(&self) -> Vec<RpcListener<ContextualNetAddress>> {
+        if self.rpclisten.is_empty() {
+            vec![RpcListener::new(ContextualNetAddress::unspecified(), None)]
+        } else {
+            self.rpclisten.clone()
+        }
+    }
+
     pub fn network(&self) -> NetworkId {
         match (self.testnet, self.devnet, self.simnet) {
             (false, false, false) => NetworkId::new(NetworkType::Mainnet),
@@ -179,6 +366,39 @@ pub fn cli() -> Command {
                 .value_parser(clap::value_parser!(usize))
                 .help(format!("Specify number of async threads (default: {}).", defaults.async_threads)),
         )
+        .arg(
+            Arg::new("validation-threads")
+                .long("validation-threads")
+                .value_name("COUNT")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(usize).range(1..))
+                .help("Total number of consensus validation threads, split between the block and virtual processors (default: physical cores minus --reserved-cores)."),
+        )
+        .arg(
+            Arg::new("reserved-cores")
+                .long("reserved-cores")
+                .value_name("COUNT")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(usize))
+                .help(format!("Number of physical cores left out of the default validation thread count (default: {}).", defaults.reserved_cores)),
+        )
+        .arg(arg!(--"pin-validation-threads" "Pin the consensus validation threads to CPU cores (supported on Linux)"))
+        .arg(
+            Arg::new("block-pipeline-capacity")
+                .long("block-pipeline-capacity")
+                .value_name("COUNT")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(usize))
+                .help("Maximal number of blocks in the validation pipeline before the admission of submitted blocks is paused (default: 2000 per BPS, 0 for no limit)."),
+        )
+        .arg(
+            Arg::new("block-pipeline-max-mb")
+                .long("block-pipeline-max-mb")
+                .value_name("MB")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Maximal total size of the blocks in the validation pipeline before the admission of submitted blocks is paused (default: 256, 0 for no limit)."),
+        )
         .arg(
             Arg::new("log_level")
                 .short('d')
@@ -191,50 +411,61 @@ pub fn cli() -> Command {
         .arg(
             Arg::new("rpclisten")
                 .long("rpclisten")
-                .value_name("IP[:PORT]")
+                .value_name("IP[:PORT][@PROFILE]")
                 .num_args(0..=1)
+                .action(ArgAction::Append)
                 .require_equals(true)
-                .value_parser(clap::value_parser!(ContextualNetAddress))
-                .help("Interface:port to listen for gRPC connections (default port: 16110, testnet: 16210)."),
+                .value_parser(clap::value_parser!(RpcListener<ContextualNetAddress>))
+                .help("Interface:port to listen for gRPC connections (default port: 16110, testnet: 16210). May be repeated to listen on several interfaces, e.g. on both IPv4 and IPv6. A suffix of @safe or @unsafe sets the permission profile of the listener (default: unsafe with --unsaferpc, safe otherwise)."),
         )
         .arg(
             Arg::new("rpclisten-borsh")
                 .long("rpclisten-borsh")
-                .value_name("IP[:PORT]")
+                .value_name("IP[:PORT][@PROFILE]")
                 .num_args(0..=1)
+                .action(ArgAction::Append)
                 .require_equals(true)
                 .default_missing_value("default") // TODO: Find a way to use defaults.rpclisten_borsh
-                .value_parser(clap::value_parser!(WrpcNetAddress))
-                .help("Interface:port to listen for wRPC Borsh connections (default port: 17110, testnet: 17210)."),
+                .value_parser(clap::value_parser!(RpcListener<WrpcNetAddress>))
+                .help("Interface:port to listen for wRPC Borsh connections (default port: 17110, testnet: 17210). May be repeated and suffixed by a permission profile as with --rpclisten."),
 
         )
         .arg(
             Arg::new("rpclisten-json")
                 .long("rpclisten-json")
-                .value_name("IP[:PORT]")
+                .value_name("IP[:PORT][@PROFILE]")
                 .num_args(0..=1)
+                .action(ArgAction::Append)
                 .require_equals(true)
                 .default_missing_value("default") // TODO: Find a way to use defaults.rpclisten_json
-                .value_parser(clap::value_parser!(WrpcNetAddress))
-                .help("Interface:port to listen for wRPC JSON connections (default port: 18110, testnet: 18210)."),
+                .value_parser(clap::value_parser!(RpcListener<WrpcNetAddress>))
+                .help("Interface:port to listen for wRPC JSON connections (default port: 18110, testnet: 18210). May be repeated and suffixed by a permission profile as with --rpclisten."),
+        )
+        .arg(
+            Arg::new("restlisten")
+                .long("restlisten")
+                .value_name("IP:PORT")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(SocketAddr))
+                .help("Interface:port to serve the REST gateway on, with its OpenAPI document at /openapi.json and Prometheus metrics at /metrics"),
         )
         .arg(arg!(--unsaferpc "Enable RPC commands which affect the state of the node"))
         .arg(
             Arg::new("connect-peers")
                 .long("connect")
-                .value_name("IP[:PORT]")
+                .value_name("IP|ONION[:PORT]")
                 .action(ArgAction::Append)
                 .require_equals(true)
-                .value_parser(clap::value_parser!(ContextualNetAddress))
+                .value_parser(parse_peer_address)
                 .help("Connect only to the specified peers at startup."),
         )
         .arg(
             Arg::new("add-peers")
                 .long("addpeer")
-                .value_name("IP[:PORT]")
+                .value_name("IP|ONION[:PORT]")
                 .action(ArgAction::Append)
                 .require_equals(true)
-                .value_parser(clap::value_parser!(ContextualNetAddress))
+                .value_parser(parse_peer_address)
                 .help("Add peers to connect with at startup."),
         )
         .arg(
@@ -261,6 +492,23 @@ pub fn cli() -> Command {
                 .value_parser(clap::value_parser!(usize))
                 .help("Max number of inbound peers (default: 128)."),
         )
+        .arg(
+            Arg::new("whitelist")
+                .long("whitelist")
+                .value_name("IP[/PREFIX]")
+                .action(ArgAction::Append)
+                .require_equals(true)
+                .value_parser(parse_ip_net)
+                .help("Add an IP network or IP (eg. 192.168.1.0/24 or ::1) whose peers may use the reserved inbound slots."),
+        )
+        .arg(
+            Arg::new("reserved-inbound")
+                .long("reserved-inbound")
+                .value_name("COUNT")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(usize))
+                .help("Number of inbound slots reserved for whitelisted peers on top of --maxinpeers (default: 8)."),
+        )
         .arg(
             Arg::new("rpcmaxclients")
                 .long("rpcmaxclients")
@@ -279,6 +527,7 @@ pub fn cli() -> Command {
                 .help("Allow mainnet mining (do not use unless you know what you are doing)"),
         )
         .arg(arg!(--utxoindex "Enable the UTXO index"))
+        .arg(arg!(--txindex "Enable the transaction index"))
         .arg(arg!(--testnet "Use the test network"))
         .arg(
             Arg::new("netsuffix")
@@ -317,7 +566,218 @@ pub fn cli() -> Command {
                 .value_parser(clap::value_parser!(u64))
                 .help("Interval in seconds for performance metrics collection."),
         )
-        .arg(arg!(--"disable-upnp" "Disable upnp"));
+        .arg(arg!(--"disable-upnp" "Disable upnp and NAT-PMP port mapping"))
+        .arg(arg!(--"disable-netgroup-diversity" "Disable preferring outbound peers from distinct network groups (for private topologies)"))
+        .arg(
+            Arg::new("proxy")
+                .long("proxy")
+                .value_name("IP:PORT")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(SocketAddr))
+                .help("Dial outbound peers through a SOCKS5 proxy (e.g. Tor at 127.0.0.1:9050), which is required for reaching (v2 only) onion peers"),
+        )
+        .arg(arg!(--"disable-clearnet" "Only connect to onion peers through the --proxy and only accept inbound peers relayed by a local onion service (loopback)").requires("proxy"))
+        .arg(
+            Arg::new("stale-tip-timeout")
+                .long("stale-tip-timeout")
+                .value_name("SECONDS")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Rotate the outbound peers if the sink timestamp does not advance for this long while peers are ahead of the node, e.g. 600 (default: 0, disabled)"),
+        )
+        .arg(arg!(--"compact-block-relay" "Exchange relay blocks in compact form with supporting peers to reduce bandwidth"))
+        .arg(arg!(--"headers-only" "Run a header-only light node which skips block bodies and UTXO tracking (serves DAG topology and headers)"))
+        .arg(arg!(--"utxo-snapshot" <PATH> "Import the pruning point UTXO set from a local snapshot file during IBD rather than downloading it (verified against the pruning point UTXO commitment)"))
+        .arg(arg!(--"export-utxo-snapshot" <PATH> "Export the UTXO set of the current pruning point to a snapshot file and exit"))
+        .arg(arg!(--"pruned-archive-dir" <DIR> "Export the bodies of pruned blocks as era files to this directory before deleting them from the database"))
+        .arg(
+            Arg::new("durability")
+                .long("durability")
+                .value_name("PROFILE")
+                .require_equals(true)
+                .value_parser(|s: &str| s.parse::<DurabilityProfile>())
+                .help("Database durability profile: 'default' (WAL without fsync, recent writes may be lost on power loss), 'high' (fsync the WAL on every write) or 'fast-sync' (skip the WAL during IBD, a crash while syncing loses the sync progress since the last flush)"),
+        )
+        .arg(arg!(--"export-state" <PATH> "Export the full node state (database checkpoints, metadata and version) to a single archive and exit"))
+        .arg(arg!(--"import-state" <PATH> "Import the full node state from an archive created by --export-state before starting the node"))
+        .arg(arg!(--"integrity-check" "Periodically verify the consistency of recently processed consensus data in the background"))
+        .arg(
+            Arg::new("revalidate-invalid")
+                .long("revalidate-invalid")
+                .value_name("RULE_CODE")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u16))
+                .help("Clear the invalid marks of blocks rejected for violating the consensus rule with this code and revalidate them on startup (for recovering from a validation bug without a resync)"),
+        )
+        .arg(
+            Arg::new("revalidate-version")
+                .long("revalidate-version")
+                .value_name("VERSION")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u16))
+                .help("Clear the invalid marks of blocks rejected by this version of the validation logic and revalidate them on startup (for recovering from a validation bug fixed in a later version which wrongly rejected blocks; blocks wrongly accepted still require a resync)"),
+        )
+        .arg(arg!(--"trace-validation" "Record the consensus rules evaluated for recently validated blocks along with their timings"))
+        .arg(
+            Arg::new("block-processing-budget")
+                .long("block-processing-budget")
+                .value_name("MILLISECONDS")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Log the dependency structure (mergeset, window, parent and transaction counts) of blocks whose header, body or UTXO processing takes longer than this (default: 1000, 0 disables)"),
+        )
+        .arg(
+            Arg::new("stale-branch-daa-threshold")
+                .long("stale-branch-daa-threshold")
+                .value_name("DAA_SCORE")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Stop merging tips whose DAA score lags the sink by more than this, treating them as stale branches (default: 0, disabled)"),
+        )
+        .arg(
+            Arg::new("stale-template-daa-threshold")
+                .long("stale-template-daa-threshold")
+                .value_name("DAA_SCORE")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Reject submitted blocks whose DAA score lags the virtual DAA score by more than this, as mined over a stale template (default: the difficulty window duration)"),
+        )
+        .arg(arg!(--"reload-settings" <PATH> "Settings file (loglevel, outpeers, maxinpeers, mempool-max-txs and mempool-max-orphans as key=value lines) applied upon a SIGHUP signal"))
+        .arg(arg!(--"coinbase-tag" <TAG> "Pool tag inserted into the coinbase payload of the block templates served over RPC (at most 64 bytes)"))
+        .arg(arg!(--journal <PATH> "Append the blocks added, virtual chain changes and accepted transactions to a binary journal file for external stream processors"))
+        .arg(
+            Arg::new("journal-listen")
+                .long("journal-listen")
+                .value_name("IP:PORT")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(SocketAddr))
+                .help("Interface:port to stream the --journal records from over TCP, replaying from a sequence number sent by the client"),
+        )
+        .arg(
+            Arg::new("nats-sink")
+                .long("nats-sink")
+                .value_name("HOST:PORT")
+                .require_equals(true)
+                .help("NATS server to publish the block added and virtual chain changed notifications to, as JSON messages"),
+        )
+        .arg(
+            Arg::new("nats-subject-prefix")
+                .long("nats-subject-prefix")
+                .value_name("PREFIX")
+                .require_equals(true)
+                .help("Prefix of the subjects the --nats-sink messages are published under (default: kaspa)"),
+        )
+        .arg(
+            Arg::new("state-digest-interval")
+                .long("state-digest-interval")
+                .value_name("BLUE_SCORE")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .help("Log a digest of the consensus state every time the selected chain crosses a multiple of this blue score, for comparing node states (also the interval of the digests served over RPC, 1000 by default)"),
+        )
+        .arg(
+            Arg::new("rpc-shed-pipeline-threshold")
+                .long("rpc-shed-pipeline-threshold")
+                .value_name("BLOCKS")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Reject RPC requests for bulk data (blocks with transactions, UTXO sets) with a retry-after hint while more blocks than this are pending processing (default: 0, disabled)"),
+        )
+        .arg(
+            Arg::new("rpc-max-heavy-requests")
+                .long("rpc-max-heavy-requests")
+                .value_name("COUNT")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Maximal number of RPC requests for bulk data served concurrently, further requests being rejected with a retry-after hint (default: 0, no limit)"),
+        )
+        .arg(
+            Arg::new("trace-requests")
+                .long("trace-requests")
+                .value_name("PATH")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(String))
+                .help("Trace RPC requests through consensus calls and store reads, writing folded stacks suitable for flame graphs (e.g. inferno-flamegraph) to the given file and the completed requests with their correlation IDs to <PATH>.log"),
+        )
+        .arg(
+            Arg::new("min-relay-tx-fee")
+                .long("min-relay-tx-fee")
+                .value_name("SOMPI_PER_KG")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Minimum fee rate in sompi per kilogram of mass for a transaction to enter the mempool and be relayed, outputs costing more than a third of it to spend being dust (default: 1000)"),
+        )
+        .arg(
+            Arg::new("min-output-value")
+                .long("min-output-value")
+                .value_name("SOMPI")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Reject transactions with outputs below this value from the mempool as dust (default: 0, dust being defined by --min-relay-tx-fee alone)"),
+        )
+        .arg(
+            Arg::new("max-mempool-ancestors")
+                .long("max-mempool-ancestors")
+                .value_name("COUNT")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Reject transactions having more unconfirmed ancestors in the mempool (default: 0, no limit)"),
+        )
+        .arg(
+            Arg::new("max-mempool-descendants")
+                .long("max-mempool-descendants")
+                .value_name("COUNT")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Reject transactions which would give one of their unconfirmed ancestors more descendants in the mempool (default: 0, no limit)"),
+        )
+        .arg(
+            Arg::new("max-mempool-chain-mass")
+                .long("max-mempool-chain-mass")
+                .value_name("GRAMS")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Reject transactions whose mass added to the mass of their unconfirmed ancestors in the mempool exceeds this value (default: 0, no limit)"),
+        )
+        .arg(
+            Arg::new("cache-warmup-blocks")
+                .long("cache-warmup-blocks")
+                .value_name("COUNT")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Pre-warm the GHOSTDAG, relations and reachability caches with the given number of most recent blocks on startup (default: 0, disabled)"),
+        )
+        .arg(
+            Arg::new("produce-blocks-address")
+                .long("produce-blocks-address")
+                .value_name("ADDRESS")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(String))
+                .help("Produce blocks paying to this address from startup, without an external miner (devnet and simnet only, controllable with the StartBlockProduction and StopBlockProduction RPCs)"),
+        )
+        .arg(
+            Arg::new("produce-blocks-rate")
+                .long("produce-blocks-rate")
+                .value_name("BLOCKS_PER_SECOND")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(f64))
+                .help("Average rate of the blocks produced for --produce-blocks-address (default: 1)"),
+        );
+
+    let cmd = cmd.arg(
+        Arg::new("genesis-allocation")
+            .long("genesis-allocation")
+            .value_name("ADDRESS:AMOUNT")
+            .require_equals(true)
+            .action(ArgAction::Append)
+            .value_parser(parse_genesis_allocation)
+            .help("Fund ADDRESS with AMOUNT sompi in the genesis UTXO set (devnet and simnet only). May be repeated."),
+    );
+
+    let cmd = cmd.subcommand(
+        Command::new("doctor")
+            .about("Check the databases, disk space, clock, ports and params of the node configured by the preceding arguments, print diagnostics and exit"),
+    );
 
     #[cfg(feature = "devnet-prealloc")]
     let cmd = cmd
@@ -328,6 +788,37 @@ pub fn cli() -> Command {
     cmd
 }
 
+/// Parses an IP network in CIDR notation, or a single IP as a network of its own
+fn parse_ip_net(s: &str) -> Result<IpNet, String> {
+    s.parse::<IpNet>().or_else(|_| s.parse::<IpAddr>().map(IpNet::from)).map_err(|_| format!("invalid IP network: {s}"))
+}
+
+/// Parses a peer address, which may also be given as a v2 `.onion` host name mapped to its OnionCat address
+fn parse_peer_address(s: &str) -> Result<ContextualNetAddress, String> {
+    let (host, port) = match s.rsplit_once(':') {
+        Some((host, port)) if host.ends_with(".onion") => (host, Some(port)),
+        _ if s.ends_with(".onion") => (s, None),
+        _ => return s.parse::<ContextualNetAddress>().map_err(|err| format!("invalid peer address {s}: {err}")),
+    };
+    let ip = IpAddress::from_onion_host(host)
+        .ok_or_else(|| format!("unsupported onion address {host}: only v2 onion services can be mapped to OnionCat addresses"))?;
+    match port {
+        Some(port) => {
+            let port = port.parse::<u16>().map_err(|_| format!("invalid port {port}"))?;
+            Ok(NetAddress::new(ip, port).into())
+        }
+        None => Ok(ip.into()),
+    }
+}
+
+/// Parses a genesis allocation given as `<address>:<amount in sompi>`
+fn parse_genesis_allocation(s: &str) -> Result<(Address, u64), String> {
+    let (address, amount) = s.rsplit_once(':').ok_or_else(|| format!("expected <address>:<amount>, got {s}"))?;
+    let address = Address::try_from(address).map_err(|err| format!("invalid address {address}: {err}"))?;
+    let amount = amount.parse::<u64>().ok().filter(|&amount| amount > 0).ok_or_else(|| format!("invalid amount {amount}"))?;
+    Ok((address, amount))
+}
+
 pub fn parse_args() -> Args {
     let m: clap::ArgMatches = cli().get_matches();
     let defaults: Args = Default::default();
@@ -336,23 +827,32 @@ pub fn parse_args() -> Args {
         appdir: m.get_one::<String>("appdir").cloned(),
         logdir: m.get_one::<String>("logdir").cloned(),
         no_log_files: m.get_one::<bool>("nologfiles").cloned().unwrap_or(defaults.no_log_files),
-        rpclisten: m.get_one::<ContextualNetAddress>("rpclisten").cloned(),
-        rpclisten_borsh: m.get_one::<WrpcNetAddress>("rpclisten-borsh").cloned(),
-        rpclisten_json: m.get_one::<WrpcNetAddress>("rpclisten-json").cloned(),
+        rpclisten: m.get_many::<RpcListener<ContextualNetAddress>>("rpclisten").unwrap_or_default().copied().collect(),
+        rpclisten_borsh: m.get_many::<RpcListener<WrpcNetAddress>>("rpclisten-borsh").unwrap_or_default().cloned().collect(),
+        rpclisten_json: m.get_many::<RpcListener<WrpcNetAddress>>("rpclisten-json").unwrap_or_default().cloned().collect(),
+        restlisten: m.get_one::<SocketAddr>("restlisten").cloned(),
         unsafe_rpc: m.get_one::<bool>("unsaferpc").cloned().unwrap_or(defaults.unsafe_rpc),
         wrpc_verbose: false,
         log_level: m.get_one::<String>("log_level").cloned().unwrap(),
         async_threads: m.get_one::<usize>("async_threads").cloned().unwrap_or(defaults.async_threads),
+        validation_threads: m.get_one::<usize>("validation-threads").cloned(),
+        reserved_cores: m.get_one::<usize>("reserved-cores").cloned().unwrap_or(defaults.reserved_cores),
+        pin_validation_threads: m.get_one::<bool>("pin-validation-threads").cloned().unwrap_or(defaults.pin_validation_threads),
+        block_pipeline_capacity: m.get_one::<usize>("block-pipeline-capacity").cloned(),
+        block_pipeline_max_mb: m.get_one::<u64>("block-pipeline-max-mb").cloned(),
         connect_peers: m.get_many::<ContextualNetAddress>("connect-peers").unwrap_or_default().copied().collect(),
         add_peers: m.get_many::<ContextualNetAddress>("add-peers").unwrap_or_default().copied().collect(),
         listen: m.get_one::<ContextualNetAddress>("listen").cloned(),
         outbound_target: m.get_one::<usize>("outpeers").cloned().unwrap_or(defaults.outbound_target),
         inbound_limit: m.get_one::<usize>("maxinpeers").cloned().unwrap_or(defaults.inbound_limit),
+        whitelist: m.get_many::<IpNet>("whitelist").unwrap_or_default().copied().collect(),
+        reserved_inbound: m.get_one::<usize>("reserved-inbound").cloned().unwrap_or(defaults.reserved_inbound),
         rpc_max_clients: m.get_one::<usize>("rpcmaxclients").cloned().unwrap_or(defaults.rpc_max_clients),
         reset_db: m.get_one::<bool>("reset-db").cloned().unwrap_or(defaults.reset_db),
         enable_unsynced_mining: m.get_one::<bool>("enable-unsynced-mining").cloned().unwrap_or(defaults.enable_unsynced_mining),
         enable_mainnet_mining: m.get_one::<bool>("enable-mainnet-mining").cloned().unwrap_or(defaults.enable_mainnet_mining),
         utxoindex: m.get_one::<bool>("utxoindex").cloned().unwrap_or(defaults.utxoindex),
+        txindex: m.get_one::<bool>("txindex").cloned().unwrap_or(defaults.txindex),
         testnet: m.get_one::<bool>("testnet").cloned().unwrap_or(defaults.testnet),
         testnet_suffix: m.get_one::<u32>("netsuffix").cloned().unwrap_or(defaults.testnet_suffix),
         devnet: m.get_one::<bool>("devnet").cloned().unwrap_or(defaults.devnet),
@@ -376,7 +876,55 @@ pub fn parse_args() -> Args {
         prealloc_address: m.get_one::<String>("prealloc-address").cloned(),
         #[cfg(feature = "devnet-prealloc")]
         prealloc_amount: m.get_one::<u64>("prealloc-amount").cloned().unwrap_or(defaults.prealloc_amount),
+        genesis_allocations: m.get_many::<(Address, u64)>("genesis-allocation").unwrap_or_default().cloned().collect(),
         disable_upnp: m.get_one::<bool>("disable-upnp").cloned().unwrap_or(defaults.disable_upnp),
+        disable_netgroup_diversity: m
+            .get_one::<bool>("disable-netgroup-diversity")
+            .cloned()
+            .unwrap_or(defaults.disable_netgroup_diversity),
+        proxy: m.get_one::<SocketAddr>("proxy").cloned(),
+        disable_clearnet: m.get_one::<bool>("disable-clearnet").cloned().unwrap_or(defaults.disable_clearnet),
+        stale_tip_timeout: m.get_one::<u64>("stale-tip-timeout").cloned(),
+        compact_block_relay: m.get_one::<bool>("compact-block-relay").cloned().unwrap_or(defaults.compact_block_relay),
+        headers_only: m.get_one::<bool>("headers-only").cloned().unwrap_or(defaults.headers_only),
+        utxo_snapshot: m.get_one::<String>("utxo-snapshot").cloned(),
+        export_utxo_snapshot: m.get_one::<String>("export-utxo-snapshot").cloned(),
+        pruned_archive_dir: m.get_one::<String>("pruned-archive-dir").cloned(),
+        durability: m.get_one::<DurabilityProfile>("durability").cloned().unwrap_or(defaults.durability),
+        export_state: m.get_one::<String>("export-state").cloned(),
+        import_state: m.get_one::<String>("import-state").cloned(),
+        integrity_check: m.get_one::<bool>("integrity-check").cloned().unwrap_or(defaults.integrity_check),
+        revalidate_invalid: m.get_one::<u16>("revalidate-invalid").cloned(),
+        revalidate_version: m.get_one::<u16>("revalidate-version").cloned(),
+        trace_validation: m.get_one::<bool>("trace-validation").cloned().unwrap_or(defaults.trace_validation),
+        block_processing_budget: m.get_one::<u64>("block-processing-budget").cloned(),
+        stale_branch_daa_threshold: m
+            .get_one::<u64>("stale-branch-daa-threshold")
+            .cloned()
+            .unwrap_or(defaults.stale_branch_daa_threshold),
+        stale_template_daa_threshold: m.get_one::<u64>("stale-template-daa-threshold").cloned(),
+        reload_settings: m.get_one::<String>("reload-settings").cloned(),
+        coinbase_tag: m.get_one::<String>("coinbase-tag").cloned(),
+        journal: m.get_one::<String>("journal").cloned(),
+        journal_listen: m.get_one::<SocketAddr>("journal-listen").cloned(),
+        nats_sink: m.get_one::<String>("nats-sink").cloned(),
+        nats_subject_prefix: m.get_one::<String>("nats-subject-prefix").cloned().unwrap_or(defaults.nats_subject_prefix),
+        state_digest_interval: m.get_one::<u64>("state-digest-interval").cloned(),
+        rpc_shed_pipeline_threshold: m
+            .get_one::<u64>("rpc-shed-pipeline-threshold")
+            .cloned()
+            .unwrap_or(defaults.rpc_shed_pipeline_threshold),
+        rpc_max_heavy_requests: m.get_one::<u64>("rpc-max-heavy-requests").cloned().unwrap_or(defaults.rpc_max_heavy_requests),
+        trace_requests: m.get_one::<String>("trace-requests").cloned(),
+        min_relay_tx_fee: m.get_one::<u64>("min-relay-tx-fee").cloned(),
+        min_output_value: m.get_one::<u64>("min-output-value").cloned(),
+        max_mempool_ancestors: m.get_one::<u64>("max-mempool-ancestors").cloned(),
+        max_mempool_descendants: m.get_one::<u64>("max-mempool-descendants").cloned(),
+        max_mempool_chain_mass: m.get_one::<u64>("max-mempool-chain-mass").cloned(),
+        cache_warmup_blocks: m.get_one::<u64>("cache-warmup-blocks").cloned().unwrap_or(defaults.cache_warmup_blocks),
+        produce_blocks_address: m.get_one::<String>("produce-blocks-address").cloned(),
+        produce_blocks_rate: m.get_one::<f64>("produce-blocks-rate").cloned().unwrap_or(defaults.produce_blocks_rate),
+        doctor: m.subcommand_matches("doctor").is_some(),
     }
 }
 
@@ -463,3 +1011,55 @@ pub fn parse_args() -> Args {
   -s, --service=                            Service command {install, remove, start, stop}
 
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_addresses::{Prefix, Version};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_genesis_allocations() {
+        let address = Address::new(Prefix::Devnet, Version::PubKey, &[7u8; 32]);
+        assert_eq!(parse_genesis_allocation(&format!("{address}:500")), Ok((address.clone(), 500)));
+        assert!(parse_genesis_allocation(&format!("{address}:0")).is_err());
+        assert!(parse_genesis_allocation(&address.to_string()).is_err());
+
+        // Allocations are applied on their own, without requiring any preallocation argument
+        let args =
+            Args { devnet: true, genesis_allocations: vec![(address.clone(), 500), (address.clone(), 700)], ..Default::default() };
+        assert_eq!(args.genesis_supply(), Some(1200));
+        let mut config = Config::new(args.network().into());
+        let genesis_hash = config.genesis.hash;
+        args.apply_to_config(&mut config);
+        assert_eq!(config.genesis_allocations.len(), 2);
+        let utxo_set = config.genesis_utxo_set();
+        assert_eq!(utxo_set.values().map(|entry| entry.amount).sum::<u64>(), 1200);
+        assert!(utxo_set.values().all(|entry| entry.script_public_key == pay_to_address_script(&address)));
+        config.commit_genesis_to_utxo_set();
+        assert_ne!(config.genesis.hash, genesis_hash);
+
+        let args = Args { genesis_allocations: vec![(address.clone(), u64::MAX), (address, 1)], ..Default::default() };
+        assert_eq!(args.genesis_supply(), None);
+    }
+
+    #[test]
+    fn test_peer_addresses() {
+        let onion = IpAddress::from_str("fd87:d87e:eb43:744:208d:5408:63e3:3b4e").unwrap();
+        assert_eq!(parse_peer_address("a5ccbdkubbr6go2o.onion"), Ok(onion.into()));
+        assert_eq!(parse_peer_address("a5ccbdkubbr6go2o.onion:16111"), Ok(NetAddress::new(onion, 16111).into()));
+        assert_eq!(parse_peer_address("1.2.3.4:16111"), Ok(NetAddress::from_str("1.2.3.4:16111").unwrap().into()));
+        // v3 onion services cannot be mapped to OnionCat addresses
+        assert!(parse_peer_address("pg6mmjiyjmcrsslvykfwnntlaru7p5svn6y2ymmju6nubxndf4pscryd.onion:16111").is_err());
+        assert!(parse_peer_address("a5ccbdkubbr6go2o.onion:port").is_err());
+    }
+
+    #[cfg(feature = "devnet-prealloc")]
+    #[test]
+    fn test_genesis_supply_includes_prealloc() {
+        let args = Args { num_prealloc_utxos: Some(10), prealloc_amount: 100, ..Default::default() };
+        assert_eq!(args.genesis_supply(), Some(1000));
+        let args = Args { num_prealloc_utxos: Some(u64::MAX), prealloc_amount: 2, ..Default::default() };
+        assert_eq!(args.genesis_supply(), None);
+    }
+}
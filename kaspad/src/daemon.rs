@@ -1,36 +1,74 @@
-use std::{fs, path::PathBuf, process::exit, sync::Arc, time::Duration};
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+    process::exit,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 
 use async_channel::unbounded;
 use kaspa_consensus_core::{
     config::ConfigBuilder,
     errors::config::{ConfigError, ConfigResult},
+    utxo::snapshot::export_pruning_point_utxo_set,
 };
+use kaspa_consensus_journal::service::JournalService;
 use kaspa_consensus_notify::{root::ConsensusNotificationRoot, service::NotifyService};
-use kaspa_core::{core::Core, info, trace};
+use kaspa_core::{core::Core, info, trace, warn};
 use kaspa_core::{kaspad_env::version, task::tick::TickService};
 use kaspa_grpc_server::service::GrpcService;
-use kaspa_rpc_service::service::RpcCoreService;
+use kaspa_rest_server::service::RestService;
+use kaspa_rpc_core::metrics::RpcMethodCounters;
+use kaspa_rpc_service::{
+    producer::{BlockProducer, BlockProductionSettings, MAX_BLOCKS_PER_SECOND},
+    service::RpcCoreService,
+    sink::{nats::NatsSink, SinkService},
+};
 use kaspa_txscript::caches::TxScriptCacheCounters;
 use kaspa_utils::networking::ContextualNetAddress;
 
+use kaspa_addresses::{Address, Prefix};
 use kaspa_addressmanager::AddressManager;
-use kaspa_consensus::{consensus::factory::Factory as ConsensusFactory, pipeline::ProcessingCounters};
 use kaspa_consensus::{
-    consensus::factory::MultiConsensusManagementStore, model::stores::headers::DbHeadersStore, pipeline::monitor::ConsensusMonitor,
+    consensus::factory::Factory as ConsensusFactory,
+    pipeline::{lifetime::LifetimeMetricsMonitor, ProcessingCounters},
+};
+use kaspa_consensus::{
+    consensus::factory::MultiConsensusManagementStore,
+    model::stores::headers::DbHeadersStore,
+    pipeline::{
+        integrity::{IntegrityCounters, IntegrityMonitor},
+        monitor::ConsensusMonitor,
+        state_digest::StateDigestMonitor,
+    },
 };
 use kaspa_consensusmanager::ConsensusManager;
 use kaspa_core::task::runtime::AsyncRuntime;
 use kaspa_index_processor::service::IndexService;
 use kaspa_mining::{
     manager::{MiningManager, MiningManagerProxy},
+    mempool::config::{Config as MiningConfig, MAXIMUM_COINBASE_TAG_LENGTH},
     monitor::MiningMonitor,
     MiningCounters,
 };
 use kaspa_p2p_flows::{flow_context::FlowContext, service::P2pService};
 
 use kaspa_perf_monitor::builder::Builder as PerfMonitorBuilder;
+use kaspa_txindex::{api::TxIndexProxy, TxIndex};
 use kaspa_utxoindex::{api::UtxoIndexProxy, UtxoIndex};
 use kaspa_wrpc_server::service::{Options as WrpcServerOptions, ServerCounters as WrpcServerCounters, WrpcEncoding, WrpcService};
+use tracing_flame::{FlameLayer, FlushGuard};
+use tracing_subscriber::{
+    filter::LevelFilter,
+    fmt::{self, format::FmtSpan},
+    layer::SubscriberExt,
+    util::SubscriberInitExt,
+    Layer,
+};
 
 /// Desired soft FD limit that needs to be configured
 /// for the kaspad process.
@@ -41,13 +79,19 @@ pub const DESIRED_DAEMON_SOFT_FD_LIMIT: u64 = 8 * 1024;
 /// this value may impact the database performance).
 pub const MINIMUM_DAEMON_SOFT_FD_LIMIT: u64 = 4 * 1024;
 
-use crate::args::Args;
+use crate::{
+    appdir::{legacy_dirs, migrate_legacy_layout, AppDirLock},
+    args::Args,
+    reload::SettingsReloader,
+    state::{export_state, import_state},
+};
 
-const DEFAULT_DATA_DIR: &str = "datadir";
-const CONSENSUS_DB: &str = "consensus";
-const UTXOINDEX_DB: &str = "utxoindex";
-const META_DB: &str = "meta";
-const META_DB_FILE_LIMIT: i32 = 5;
+pub(crate) const DEFAULT_DATA_DIR: &str = "datadir";
+pub(crate) const CONSENSUS_DB: &str = "consensus";
+pub(crate) const UTXOINDEX_DB: &str = "utxoindex";
+pub(crate) const TXINDEX_DB: &str = "txindex";
+pub(crate) const META_DB: &str = "meta";
+pub(crate) const META_DB_FILE_LIMIT: i32 = 5;
 const DEFAULT_LOG_DIR: &str = "logs";
 
 fn get_home_dir() -> PathBuf {
@@ -77,15 +121,72 @@ pub fn validate_args(args: &Args) -> ConfigResult<()> {
         }
     }
 
+    if !args.genesis_allocations.is_empty() {
+        if !(args.devnet || args.simnet) {
+            return Err(ConfigError::GenesisAllocationOnNonDevnet);
+        }
+        let prefix = Prefix::from(args.network());
+        if let Some((address, _)) = args.genesis_allocations.iter().find(|(address, _)| address.prefix != prefix) {
+            return Err(ConfigError::InvalidGenesisAllocationAddress(address.to_string()));
+        }
+    }
+
+    // Preallocated UTXOs count toward the supply as well
+    if !args.genesis_supply().is_some_and(|supply| supply <= kaspa_consensus_core::constants::MAX_SOMPI) {
+        return Err(ConfigError::GenesisAllocationsExceedMaxSupply);
+    }
+
     if !args.connect_peers.is_empty() && !args.add_peers.is_empty() {
         return Err(ConfigError::MixedConnectAndAddPeers);
     }
     if args.logdir.is_some() && args.no_log_files {
         return Err(ConfigError::MixedLogDirAndNoLogFiles);
     }
+    if args.headers_only && (args.utxoindex || args.txindex) {
+        return Err(ConfigError::MixedHeadersOnlyAndIndexes);
+    }
+    if args.export_state.is_some() && args.import_state.is_some() {
+        return Err(ConfigError::MixedExportAndImportState);
+    }
+    if args.journal_listen.is_some() && args.journal.is_none() {
+        return Err(ConfigError::JournalListenWithoutJournal);
+    }
+    if let Some(tag) = args.coinbase_tag.as_ref().filter(|tag| tag.len() > MAXIMUM_COINBASE_TAG_LENGTH) {
+        return Err(ConfigError::CoinbaseTagTooLong(tag.len(), MAXIMUM_COINBASE_TAG_LENGTH));
+    }
+    if let Some(address) = args.produce_blocks_address.as_ref() {
+        let network = args.network();
+        if !BlockProducer::is_supported(network.network_type()) {
+            return Err(ConfigError::BlockProductionOnNonDevnet);
+        }
+        if !Address::try_from(address.as_str()).is_ok_and(|address| address.prefix == Prefix::from(network)) {
+            return Err(ConfigError::InvalidBlockProductionAddress(address.clone()));
+        }
+        if !BlockProducer::is_valid_rate(args.produce_blocks_rate) {
+            return Err(ConfigError::InvalidBlockProductionRate(args.produce_blocks_rate, MAX_BLOCKS_PER_SECOND));
+        }
+    }
     Ok(())
 }
 
+/// Exports the UTXO set of the current pruning point to `path` and exits the process
+fn export_utxo_snapshot_and_exit(consensus_manager: &ConsensusManager, path: &str) -> ! {
+    const CHUNK_SIZE: usize = 1000;
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let consensus = consensus_manager.consensus();
+    let session = runtime.block_on(consensus.session_blocking());
+    match File::create(path).and_then(|file| export_pruning_point_utxo_set(&*session, BufWriter::new(file), CHUNK_SIZE)) {
+        Ok((pruning_point, count)) => {
+            info!("Exported {} UTXOs of pruning point {} to {}", count, pruning_point, path);
+            exit(0);
+        }
+        Err(err) => {
+            println!("Failed exporting the UTXO snapshot to {}: {}", path, err);
+            exit(1);
+        }
+    }
+}
+
 fn get_user_approval_or_exit(message: &str, approve: bool) {
     if approve {
         return;
@@ -132,14 +233,17 @@ pub fn get_app_dir_from_args(args: &Args) -> PathBuf {
     }
 }
 
+/// Get the directory of the network selected by the supplied [`Args`], i.e., `<appdir>/<network>`,
+/// holding the databases and the logs of the node.
+pub fn get_network_dir(args: &Args) -> PathBuf {
+    get_app_dir_from_args(args).join(args.network().to_prefixed())
+}
+
 /// Get the log directory from the supplied [`Args`].
 pub fn get_log_dir(args: &Args) -> Option<String> {
-    let network = args.network();
-    let app_dir = get_app_dir_from_args(args);
-
-    // Logs directory is usually under the application directory, unless otherwise specified
+    // Logs directory is usually under the network directory, unless otherwise specified
     let log_dir = args.logdir.clone().unwrap_or_default().replace('~', get_home_dir().as_path().to_str().unwrap());
-    let log_dir = if log_dir.is_empty() { app_dir.join(network.to_prefixed()).join(DEFAULT_LOG_DIR) } else { PathBuf::from(log_dir) };
+    let log_dir = if log_dir.is_empty() { get_network_dir(args).join(DEFAULT_LOG_DIR) } else { PathBuf::from(log_dir) };
     let log_dir = if args.no_log_files { None } else { log_dir.to_str().map(String::from) };
     log_dir
 }
@@ -158,6 +262,35 @@ impl Runtime {
     }
 }
 
+/// Installs a global subscriber recording the spans of traced RPC requests (see [`kaspa_rpc_core::trace`])
+/// as folded stacks in the file at `path`, while every completed request is logged along with its correlation ID
+/// and duration to `<path>.log`. The returned guard flushes the folded stacks when dropped.
+///
+/// Only the first call installs the subscriber, later ones returning `None`.
+pub fn init_request_tracing(path: &str) -> Option<FlushGuard<BufWriter<File>>> {
+    static INITIALIZED: AtomicBool = AtomicBool::new(false);
+    if INITIALIZED.swap(true, Ordering::SeqCst) {
+        warn!("Request tracing is already initialized, ignoring the trace file {}", path);
+        return None;
+    }
+
+    let (flame_layer, guard) =
+        FlameLayer::with_file(path).unwrap_or_else(|err| panic!("Failed to create the trace file {path}: {err}"));
+    let log_path = format!("{path}.log");
+    let log_file = File::create(&log_path).unwrap_or_else(|err| panic!("Failed to create the trace file {log_path}: {err}"));
+    // Request spans are at the info level, their nested consensus and store spans being left to the folded stacks
+    let requests_layer = fmt::layer()
+        .with_writer(Mutex::new(log_file))
+        .with_ansi(false)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_filter(LevelFilter::INFO);
+    if let Err(err) = tracing_subscriber::registry().with(flame_layer.with_threads_collapsed(true)).with(requests_layer).try_init() {
+        warn!("Failed installing the request tracing subscriber: {}", err);
+        return None;
+    }
+    Some(guard)
+}
+
 /// Create [`Core`] instance with supplied [`Args`].
 /// This function will automatically create a [`Runtime`]
 /// instance with the supplied [`Args`] and then
@@ -195,6 +328,13 @@ pub fn create_core_with_runtime(runtime: &Runtime, args: &Args, fd_total_budget:
     } else {
         0
     };
+    let tx_files_limit = if args.txindex {
+        let tx_files_limit = fd_remaining * 10 / 100;
+        fd_remaining -= tx_files_limit;
+        tx_files_limit
+    } else {
+        0
+    };
     // Make sure args forms a valid set of properties
     if let Err(err) = validate_args(args) {
         println!("{}", err);
@@ -211,7 +351,8 @@ pub fn create_core_with_runtime(runtime: &Runtime, args: &Args, fd_total_budget:
     // TODO: Validate `config` forms a valid set of properties
 
     let app_dir = get_app_dir_from_args(args);
-    let db_dir = app_dir.join(network.to_prefixed()).join(DEFAULT_DATA_DIR);
+    let network_dir = get_network_dir(args);
+    let db_dir = network_dir.join(DEFAULT_DATA_DIR);
 
     // Print package name and version
     info!("{} v{}", env!("CARGO_PKG_NAME"), version());
@@ -219,6 +360,14 @@ pub fn create_core_with_runtime(runtime: &Runtime, args: &Args, fd_total_budget:
     assert!(!db_dir.to_str().unwrap().is_empty());
     info!("Application directory: {}", app_dir.display());
     info!("Data directory: {}", db_dir.display());
+    info!(
+        "Consensus validation: {} block processor threads and {} virtual processor threads ({} logical / {} physical cores{})",
+        config.perf.block_processors_num_threads,
+        config.perf.virtual_processor_num_threads,
+        num_cpus::get(),
+        num_cpus::get_physical(),
+        if config.perf.pin_worker_threads { ", pinned" } else { "" }
+    );
     match runtime.log_dir.as_ref() {
         Some(s) => {
             info!("Logs directory: {}", s);
@@ -228,8 +377,69 @@ pub fn create_core_with_runtime(runtime: &Runtime, args: &Args, fd_total_budget:
         }
     }
 
+    // Prevent other processes from opening the same databases for as long as the core is running
+    let app_dir_lock = match AppDirLock::acquire(&network_dir) {
+        Ok(lock) => Arc::new(lock),
+        Err(err) => {
+            println!("{}", err);
+            exit(1);
+        }
+    };
+
+    // Earlier versions stored the databases directly under the application directory
+    match legacy_dirs(&app_dir, &network_dir, &[DEFAULT_DATA_DIR]) {
+        Ok(pending) if !pending.is_empty() => {
+            let msg = format!(
+                "A legacy data directory was found at {}, do you confirm moving it to {} (network {})? (answer y/n or pass --yes to the Kaspad command line to confirm all interactive questions)",
+                pending[0].0.display(),
+                pending[0].1.display(),
+                network
+            );
+            get_user_approval_or_exit(&msg, args.yes);
+            if let Err(err) = migrate_legacy_layout(&app_dir, &network_dir, &[DEFAULT_DATA_DIR]) {
+                println!("Failed migrating the legacy data directory: {}", err);
+                exit(1);
+            }
+        }
+        Ok(_) => {}
+        Err(err) => {
+            println!("{}", err);
+            exit(1);
+        }
+    }
+
+    if let Some(path) = args.export_state.as_ref() {
+        match export_state(&db_dir, &network.to_prefixed(), Path::new(path)) {
+            Ok(metadata) => {
+                info!("Exported {} files of the node state to {}", metadata.entries, path);
+                exit(0);
+            }
+            Err(err) => {
+                println!("Failed exporting the node state to {}: {}", path, err);
+                exit(1);
+            }
+        }
+    }
+
+    if let Some(path) = args.import_state.as_ref() {
+        if db_dir.exists() {
+            let msg = "Importing the node state will fully replace the current databases once the archive is verified, do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm all interactive questions)";
+            get_user_approval_or_exit(msg, args.yes);
+        }
+        match import_state(Path::new(path), &network.to_prefixed(), &db_dir) {
+            Ok(metadata) => {
+                info!("Imported {} files of the node state exported by kaspad v{}", metadata.entries, metadata.kaspad_version)
+            }
+            Err(err) => {
+                println!("Failed importing the node state from {}: {}", path, err);
+                exit(1);
+            }
+        }
+    }
+
     let consensus_db_dir = db_dir.join(CONSENSUS_DB);
     let utxoindex_db_dir = db_dir.join(UTXOINDEX_DB);
+    let txindex_db_dir = db_dir.join(TXINDEX_DB);
     let meta_db_dir = db_dir.join(META_DB);
 
     let mut is_db_reset_needed = args.reset_db;
@@ -249,11 +459,16 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
         info!("Utxoindex Data directory {}", utxoindex_db_dir.display());
         fs::create_dir_all(utxoindex_db_dir.as_path()).unwrap();
     }
+    if args.txindex {
+        info!("Txindex Data directory {}", txindex_db_dir.display());
+        fs::create_dir_all(txindex_db_dir.as_path()).unwrap();
+    }
 
     // DB used for addresses store and for multi-consensus management
     let mut meta_db = kaspa_database::prelude::ConnBuilder::default()
         .with_db_path(meta_db_dir.clone())
         .with_files_limit(META_DB_FILE_LIMIT)
+        .with_durability(args.durability)
         .build()
         .unwrap();
 
@@ -320,11 +535,15 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
         if args.utxoindex {
             fs::create_dir_all(utxoindex_db_dir.as_path()).unwrap();
         }
+        if args.txindex {
+            fs::create_dir_all(txindex_db_dir.as_path()).unwrap();
+        }
 
         // Reopen the DB
         meta_db = kaspa_database::prelude::ConnBuilder::default()
             .with_db_path(meta_db_dir)
             .with_files_limit(META_DB_FILE_LIMIT)
+            .with_durability(args.durability)
             .build()
             .unwrap();
     }
@@ -338,9 +557,15 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
     let p2p_server_addr = args.listen.unwrap_or(ContextualNetAddress::unspecified()).normalize(config.default_p2p_port());
     // connect_peers means no DNS seeding and no outbound peers
     let outbound_target = if connect_peers.is_empty() { args.outbound_target } else { 0 };
-    let dns_seeders = if connect_peers.is_empty() { config.dns_seeders } else { &[] };
+    // DNS seeders only serve clearnet addresses and would leak lookups outside of the proxy
+    let dns_seeders = if connect_peers.is_empty() && !config.disable_clearnet { config.dns_seeders } else { &[] };
 
-    let grpc_server_addr = args.rpclisten.unwrap_or(ContextualNetAddress::unspecified()).normalize(config.default_rpc_port());
+    let default_rpc_profile = args.default_rpc_profile();
+    let grpc_listeners = args
+        .grpc_listeners()
+        .into_iter()
+        .map(|listener| (listener.address.normalize(config.default_rpc_port()), listener.profile_or(default_rpc_profile)))
+        .collect();
 
     let core = Arc::new(Core::new());
 
@@ -353,7 +578,12 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
     let mining_counters = Arc::new(MiningCounters::default());
     let wrpc_borsh_counters = Arc::new(WrpcServerCounters::default());
     let wrpc_json_counters = Arc::new(WrpcServerCounters::default());
+    let rpc_method_counters = Arc::new(RpcMethodCounters::default());
     let tx_script_cache_counters = Arc::new(TxScriptCacheCounters::default());
+    let lifetime_metrics = Arc::new(
+        LifetimeMetricsMonitor::new(meta_db.clone(), processing_counters.clone(), tick_service.clone())
+            .unwrap_or_else(|err| panic!("Failed to load the lifetime metrics: {err}")),
+    );
 
     // Use `num_cpus` background threads for the consensus database as recommended by rocksdb
     let consensus_db_parallelism = num_cpus::get();
@@ -366,9 +596,35 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
         processing_counters.clone(),
         tx_script_cache_counters.clone(),
         fd_remaining,
+        args.durability,
     ));
     let consensus_manager = Arc::new(ConsensusManager::new(consensus_factory));
+    if let Some(path) = args.export_utxo_snapshot.as_ref() {
+        export_utxo_snapshot_and_exit(&consensus_manager, path);
+    }
+    if let Some(rule_code) = args.revalidate_invalid {
+        // The blocks are queued for processing and get processed once consensus starts
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let consensus = consensus_manager.consensus();
+        let session = runtime.block_on(consensus.session_blocking());
+        let hashes = session.revalidate_invalid_blocks(rule_code);
+        info!("Cleared the invalid marks of {} blocks rejected by rule {}", hashes.len(), rule_code);
+    }
+    if let Some(version) = args.revalidate_version {
+        // The blocks are queued for processing and get processed once consensus starts
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let consensus = consensus_manager.consensus();
+        let session = runtime.block_on(consensus.session_blocking());
+        let hashes = session.revalidate_blocks_by_version(version);
+        info!("Cleared the invalid marks of {} blocks rejected by validation version {}", hashes.len(), version);
+    }
     let consensus_monitor = Arc::new(ConsensusMonitor::new(processing_counters.clone(), tick_service.clone()));
+    let integrity_counters = Arc::new(IntegrityCounters::default());
+    let integrity_monitor = args
+        .integrity_check
+        .then(|| Arc::new(IntegrityMonitor::new(consensus_manager.clone(), integrity_counters.clone(), tick_service.clone())));
+    let state_digest_monitor =
+        args.state_digest_interval.map(|_| Arc::new(StateDigestMonitor::new(consensus_manager.clone(), tick_service.clone())));
 
     let perf_monitor_builder = PerfMonitorBuilder::new()
         .with_fetch_interval(Duration::from_secs(args.perf_metrics_interval_sec))
@@ -385,28 +641,61 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
     };
 
     let notify_service = Arc::new(NotifyService::new(notification_root.clone(), notification_recv));
-    let index_service: Option<Arc<IndexService>> = if args.utxoindex {
+    let index_service: Option<Arc<IndexService>> = if args.utxoindex || args.txindex {
         // Use only a single thread for none-consensus databases
-        let utxoindex_db = kaspa_database::prelude::ConnBuilder::default()
-            .with_db_path(utxoindex_db_dir)
-            .with_files_limit(utxo_files_limit)
-            .build()
-            .unwrap();
-        let utxoindex = UtxoIndexProxy::new(UtxoIndex::new(consensus_manager.clone(), utxoindex_db).unwrap());
-        let index_service = Arc::new(IndexService::new(&notify_service.notifier(), Some(utxoindex)));
+        let utxoindex = args.utxoindex.then(|| {
+            let utxoindex_db = kaspa_database::prelude::ConnBuilder::default()
+                .with_db_path(utxoindex_db_dir)
+                .with_files_limit(utxo_files_limit)
+                .with_durability(args.durability)
+                .build()
+                .unwrap();
+            UtxoIndexProxy::new(UtxoIndex::new(consensus_manager.clone(), utxoindex_db).unwrap())
+        });
+        let txindex = args.txindex.then(|| {
+            let txindex_db = kaspa_database::prelude::ConnBuilder::default()
+                .with_db_path(txindex_db_dir)
+                .with_files_limit(tx_files_limit)
+                .with_durability(args.durability)
+                .build()
+                .unwrap();
+            TxIndexProxy::new(TxIndex::new(consensus_manager.clone(), txindex_db).unwrap())
+        });
+        let index_service = Arc::new(IndexService::new(&notify_service.notifier(), utxoindex, txindex));
         Some(index_service)
     } else {
         None
     };
+    let journal_service = args.journal.as_ref().map(|path| {
+        info!("Consensus journal file {}", path);
+        Arc::new(
+            JournalService::new(&notify_service.notifier(), PathBuf::from(path), args.journal_listen)
+                .unwrap_or_else(|err| panic!("Failed to open the consensus journal {path}: {err}")),
+        )
+    });
 
     let (address_manager, port_mapping_extender_svc) = AddressManager::new(config.clone(), meta_db, tick_service.clone());
 
     let mining_monitor = Arc::new(MiningMonitor::new(mining_counters.clone(), tx_script_cache_counters.clone(), tick_service.clone()));
-    let mining_manager = MiningManagerProxy::new(Arc::new(MiningManager::new_with_spam_blocking_option(
+    let default_mining_config = MiningConfig::build_default_with_spam_blocking_option(
         network.is_mainnet(),
         config.target_time_per_block,
         false,
         config.max_block_mass,
+    );
+    let mining_config = MiningConfig {
+        coinbase_tag: args.coinbase_tag.clone().unwrap_or_default().into_bytes(),
+        maximum_coinbase_payload_length: config.max_coinbase_payload_len,
+        minimum_relay_transaction_fee: args.min_relay_tx_fee.unwrap_or(default_mining_config.minimum_relay_transaction_fee),
+        minimum_output_value: args.min_output_value.unwrap_or(default_mining_config.minimum_output_value),
+        maximum_ancestor_count: args.max_mempool_ancestors.unwrap_or(default_mining_config.maximum_ancestor_count),
+        maximum_descendant_count: args.max_mempool_descendants.unwrap_or(default_mining_config.maximum_descendant_count),
+        maximum_chain_mass: args.max_mempool_chain_mass.unwrap_or(default_mining_config.maximum_chain_mass),
+        enforce_script_policy: config.enforce_script_policy,
+        ..default_mining_config
+    };
+    let mining_manager = MiningManagerProxy::new(Arc::new(MiningManager::with_config(
+        mining_config,
         config.block_template_cache_lifetime,
         mining_counters,
     )));
@@ -426,42 +715,101 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
         p2p_server_addr,
         outbound_target,
         args.inbound_limit,
+        args.whitelist.clone(),
+        args.reserved_inbound,
         dns_seeders,
         config.default_p2p_port(),
     ));
 
+    let block_producer = BlockProducer::is_supported(network.network_type()).then(|| {
+        let initial_settings = args.produce_blocks_address.as_ref().map(|address| BlockProductionSettings {
+            pay_address: Address::try_from(address.as_str()).unwrap(),
+            blocks_per_second: args.produce_blocks_rate,
+        });
+        Arc::new(BlockProducer::new(
+            consensus_manager.clone(),
+            mining_manager.clone(),
+            flow_context.clone(),
+            config.clone(),
+            tick_service.clone(),
+            initial_settings,
+        ))
+    });
+
     let rpc_core_service = Arc::new(RpcCoreService::new(
         consensus_manager.clone(),
         notify_service.notifier(),
         index_service.as_ref().map(|x| x.notifier()),
         mining_manager,
         flow_context,
-        index_service.as_ref().map(|x| x.utxoindex().unwrap()),
+        index_service.as_ref().and_then(|x| x.utxoindex()),
+        index_service.as_ref().and_then(|x| x.txindex()),
         config,
         core.clone(),
         processing_counters,
+        integrity_counters,
         wrpc_borsh_counters.clone(),
         wrpc_json_counters.clone(),
+        rpc_method_counters,
         perf_monitor.clone(),
+        block_producer.clone(),
+        lifetime_metrics.clone(),
     ));
-    let grpc_service = Arc::new(GrpcService::new(grpc_server_addr, rpc_core_service.clone(), args.rpc_max_clients));
-
-    // Create an async runtime and register the top-level async services
+    let grpc_service = Arc::new(GrpcService::new(grpc_listeners, rpc_core_service.clone(), args.rpc_max_clients));
+    let nats_sink_service = args.nats_sink.as_ref().map(|address| {
+        info!("Publishing notifications to the NATS server {} under the subject prefix {}", address, args.nats_subject_prefix);
+        let sink = Arc::new(NatsSink::new(address.clone(), args.nats_subject_prefix.clone()));
+        Arc::new(SinkService::new(sink, &rpc_core_service.notifier()).expect("the subscription always succeeds"))
+    });
+
+    // Create an async runtime and register the top-level async services. Services consuming consensus notifications
+    // start once the notify service is running, and the RPC servers once the RPC core service is, so that each service
+    // is stopped before the services it depends on
     let async_runtime = Arc::new(AsyncRuntime::new(args.async_threads));
     async_runtime.register(tick_service);
     async_runtime.register(notify_service);
+    let mut rpc_core_dependencies = vec![NotifyService::IDENT];
     if let Some(index_service) = index_service {
-        async_runtime.register(index_service)
+        async_runtime.register_with_dependencies(index_service, &[NotifyService::IDENT]);
+        rpc_core_dependencies.push(IndexService::IDENT);
+    };
+    if let Some(journal_service) = journal_service {
+        async_runtime.register_with_dependencies(journal_service, &[NotifyService::IDENT])
     };
     if let Some(port_mapping_extender_svc) = port_mapping_extender_svc {
         async_runtime.register(Arc::new(port_mapping_extender_svc))
     };
-    async_runtime.register(rpc_core_service.clone());
-    async_runtime.register(grpc_service);
-    async_runtime.register(p2p_service);
+    async_runtime.register_with_dependencies(rpc_core_service.clone(), &rpc_core_dependencies);
+    if let Some(nats_sink_service) = nats_sink_service {
+        async_runtime.register_with_dependencies(nats_sink_service, &[RpcCoreService::IDENT])
+    };
+    async_runtime.register_with_dependencies(grpc_service, &[RpcCoreService::IDENT]);
+    if let Some(address) = args.restlisten {
+        info!("REST gateway listening on {}", address);
+        async_runtime
+            .register_with_dependencies(Arc::new(RestService::new(address, rpc_core_service.clone())), &[RpcCoreService::IDENT])
+    };
+    // The P2P service feeds blocks into consensus, so it is stopped before the notifications are no longer consumed
+    async_runtime.register_with_dependencies(p2p_service, &[TickService::IDENT, NotifyService::IDENT]);
+    if let Some(block_producer) = block_producer {
+        async_runtime.register_with_dependencies(block_producer, &[P2pService::IDENT])
+    };
     async_runtime.register(consensus_monitor);
+    if let Some(integrity_monitor) = integrity_monitor {
+        async_runtime.register(integrity_monitor)
+    };
+    if let Some(state_digest_monitor) = state_digest_monitor {
+        async_runtime.register(state_digest_monitor)
+    };
     async_runtime.register(mining_monitor);
     async_runtime.register(perf_monitor);
+    async_runtime.register(lifetime_metrics);
+    if let Some(settings_file) = args.reload_settings.as_ref() {
+        async_runtime.register_with_dependencies(
+            Arc::new(SettingsReloader::new(rpc_core_service.clone(), PathBuf::from(settings_file))),
+            &[RpcCoreService::IDENT],
+        )
+    };
     let wrpc_service_tasks: usize = 2; // num_cpus::get() / 2;
                                        // Register wRPC servers based on command line arguments
     [
@@ -469,26 +817,29 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
         (args.rpclisten_json.clone(), WrpcEncoding::SerdeJson, wrpc_json_counters),
     ]
     .into_iter()
-    .filter_map(|(listen_address, encoding, wrpc_server_counters)| {
-        listen_address.map(|listen_address| {
-            Arc::new(WrpcService::new(
-                wrpc_service_tasks,
-                Some(rpc_core_service.clone()),
-                &encoding,
-                wrpc_server_counters,
-                WrpcServerOptions {
-                    listen_address: listen_address.to_address(&network.network_type, &encoding).to_string(), // TODO: use a normalized ContextualNetAddress instead of a String
-                    verbose: args.wrpc_verbose,
-                    ..WrpcServerOptions::default()
-                },
-            ))
-        })
+    .flat_map(|(listeners, encoding, wrpc_server_counters)| {
+        listeners.into_iter().map(move |listener| (listener, encoding, wrpc_server_counters.clone()))
+    })
+    .map(|(listener, encoding, wrpc_server_counters)| {
+        Arc::new(WrpcService::new(
+            wrpc_service_tasks,
+            Some(rpc_core_service.clone()),
+            &encoding,
+            wrpc_server_counters,
+            WrpcServerOptions {
+                listen_address: listener.address.to_address(&network.network_type, &encoding).to_string(), // TODO: use a normalized ContextualNetAddress instead of a String
+                verbose: args.wrpc_verbose,
+                profile: listener.profile_or(default_rpc_profile),
+                ..WrpcServerOptions::default()
+            },
+        ))
     })
-    .for_each(|server| async_runtime.register(server));
+    .for_each(|server| async_runtime.register_with_dependencies(server, &[RpcCoreService::IDENT]));
 
-    // Consensus must start first in order to init genesis in stores
-    core.bind(consensus_manager);
-    core.bind(async_runtime);
+    // The lock is stopped last, and the async services (RPC, indexes, P2P) start once consensus has initialized its stores
+    core.bind(app_dir_lock);
+    core.bind_with_dependencies(consensus_manager, &[AppDirLock::IDENT]);
+    core.bind_with_dependencies(async_runtime, &[ConsensusManager::IDENT]);
 
     (core, rpc_core_service)
 }
@@ -0,0 +1,339 @@
+//!
+//! The `doctor` subcommand, which checks the environment and the configuration of the node and prints actionable
+//! diagnostics, without actually starting the node.
+//!
+
+use std::{
+    fmt::Display,
+    io,
+    net::{SocketAddr, TcpListener},
+    path::Path,
+    sync::Arc,
+};
+
+use kaspa_consensus::{
+    consensus::factory::MultiConsensusManagementStore,
+    model::stores::{
+        headers::{DbHeadersStore, HeaderStoreReader},
+        headers_selected_tip::{DbHeadersSelectedTipStore, HeadersSelectedTipStoreReader},
+    },
+};
+use kaspa_consensus_core::{
+    blockhash::BlockHashExtensions,
+    config::{Config, ConfigBuilder},
+    header::Header,
+};
+use kaspa_core::{kaspad_env::version, time::unix_now};
+use kaspa_database::prelude::{ConnBuilder, DB};
+use kaspa_utils::networking::ContextualNetAddress;
+use kaspa_wrpc_server::service::WrpcEncoding;
+
+use crate::{
+    appdir::AppDirLock,
+    args::Args,
+    daemon::{get_network_dir, validate_args, CONSENSUS_DB, DEFAULT_DATA_DIR, META_DB, META_DB_FILE_LIMIT, TXINDEX_DB, UTXOINDEX_DB},
+};
+
+/// Free disk space below which the node is likely to run out of space while syncing
+const LOW_DISK_SPACE_BYTES: u64 = 20 * 1024 * 1024 * 1024;
+
+/// Free disk space below which the node cannot be expected to operate at all
+const CRITICAL_DISK_SPACE_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Ok,
+    Warning,
+    Failure,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Ok => write!(f, "  OK"),
+            Outcome::Warning => write!(f, "WARN"),
+            Outcome::Failure => write!(f, "FAIL"),
+        }
+    }
+}
+
+/// The result of a single check along with a suggested action, if any
+struct Diagnostic {
+    check: &'static str,
+    outcome: Outcome,
+    message: String,
+    action: Option<String>,
+}
+
+impl Diagnostic {
+    fn ok(check: &'static str, message: impl Into<String>) -> Self {
+        Self { check, outcome: Outcome::Ok, message: message.into(), action: None }
+    }
+
+    fn warning(check: &'static str, message: impl Into<String>, action: impl Into<String>) -> Self {
+        Self { check, outcome: Outcome::Warning, message: message.into(), action: Some(action.into()) }
+    }
+
+    fn failure(check: &'static str, message: impl Into<String>, action: impl Into<String>) -> Self {
+        Self { check, outcome: Outcome::Failure, message: message.into(), action: Some(action.into()) }
+    }
+}
+
+/// Runs all checks for the node configured by `args` and prints their diagnostics. Returns whether no check failed
+pub fn run_doctor(args: &Args) -> bool {
+    println!("kaspad v{} doctor, network {}", version(), args.network());
+
+    let mut diagnostics = vec![];
+    if let Err(err) = validate_args(args) {
+        diagnostics.push(Diagnostic::failure("arguments", err.to_string(), "fix the conflicting command line arguments"));
+    }
+    let config = ConfigBuilder::new(args.network().into())
+        .adjust_perf_params_to_consensus_params()
+        .apply_args(|config| args.apply_to_config(config))
+        .build();
+    diagnostics.push(check_params(&config));
+
+    let network_dir = get_network_dir(args);
+    diagnostics.push(check_disk_space(&network_dir));
+    let tip_timestamp = check_databases(args, &config, &network_dir, &mut diagnostics);
+    diagnostics.push(check_clock(&config, tip_timestamp));
+    check_ports(args, &config, &mut diagnostics);
+
+    for diagnostic in diagnostics.iter() {
+        println!("[{}] {}: {}", diagnostic.outcome, diagnostic.check, diagnostic.message);
+        if let Some(action) = diagnostic.action.as_ref() {
+            println!("       -> {action}");
+        }
+    }
+    let failures = diagnostics.iter().filter(|d| d.outcome == Outcome::Failure).count();
+    let warnings = diagnostics.iter().filter(|d| d.outcome == Outcome::Warning).count();
+    println!("{} checks: {} failed, {} with warnings", diagnostics.len(), failures, warnings);
+    failures == 0
+}
+
+/// Verifies that the genesis of the configured params hashes to the expected genesis hash
+fn check_params(config: &Config) -> Diagnostic {
+    const CHECK: &str = "params";
+    let genesis_header: Header = (&config.genesis).into();
+    if genesis_header.hash == config.genesis.hash {
+        Diagnostic::ok(CHECK, format!("genesis {} is consistent with the params of {}", config.genesis.hash, config.net))
+    } else {
+        Diagnostic::failure(
+            CHECK,
+            format!("the genesis header hashes to {} while the params declare {}", genesis_header.hash, config.genesis.hash),
+            "this binary is misbuilt, reinstall kaspad from an official release",
+        )
+    }
+}
+
+fn check_disk_space(network_dir: &Path) -> Diagnostic {
+    const CHECK: &str = "disk space";
+    // The space is measured on the nearest existing ancestor, since the directories are only created on first start
+    let Some(existing_dir) = network_dir.ancestors().find(|dir| dir.exists()) else {
+        return Diagnostic::warning(
+            CHECK,
+            format!("no existing ancestor of {}", network_dir.display()),
+            "check the --appdir argument",
+        );
+    };
+    match available_space(existing_dir) {
+        Ok(available) => {
+            let message = format!("{:.1} GB available at {}", available as f64 / 1e9, existing_dir.display());
+            if available < CRITICAL_DISK_SPACE_BYTES {
+                Diagnostic::failure(CHECK, message, "free disk space or point --appdir to a larger volume")
+            } else if available < LOW_DISK_SPACE_BYTES {
+                Diagnostic::warning(CHECK, message, "the databases might outgrow the available space, consider a larger volume")
+            } else {
+                Diagnostic::ok(CHECK, message)
+            }
+        }
+        Err(err) => {
+            Diagnostic::warning(CHECK, format!("could not be measured: {err}"), "verify manually that enough space is available")
+        }
+    }
+}
+
+#[cfg(unix)]
+fn available_space(path: &Path) -> io::Result<u64> {
+    use std::{ffi::CString, mem::MaybeUninit, os::unix::ffi::OsStrExt};
+
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+    if unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let stat = unsafe { stat.assume_init() };
+    // The field types differ across platforms
+    #[allow(clippy::unnecessary_cast)]
+    let available = stat.f_bavail as u64 * stat.f_frsize as u64;
+    Ok(available)
+}
+
+#[cfg(not(unix))]
+fn available_space(_path: &Path) -> io::Result<u64> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "not supported on this platform"))
+}
+
+/// Checks that the databases can be opened and are of the supported schema version. Returns the timestamp of the
+/// headers selected tip of the active consensus, if any
+fn check_databases(args: &Args, config: &Config, network_dir: &Path, diagnostics: &mut Vec<Diagnostic>) -> Option<u64> {
+    const CHECK: &str = "databases";
+    let db_dir = network_dir.join(DEFAULT_DATA_DIR);
+    if !db_dir.exists() {
+        diagnostics
+            .push(Diagnostic::ok(CHECK, format!("no databases at {} yet, they will be created on first start", db_dir.display())));
+        return None;
+    }
+
+    // Held until the checks complete, so that a node started meanwhile does not race with them
+    let _lock = match AppDirLock::acquire(network_dir) {
+        Ok(lock) => lock,
+        Err(err) => {
+            diagnostics.push(Diagnostic::failure(CHECK, err.to_string(), "stop the running node before running the doctor"));
+            return None;
+        }
+    };
+
+    let meta_db = match open_db(&db_dir.join(META_DB), META_DB_FILE_LIMIT) {
+        Ok(db) => db,
+        Err(diagnostic) => {
+            diagnostics.push(diagnostic);
+            return None;
+        }
+    };
+    let management_store = MultiConsensusManagementStore::new(meta_db.clone());
+    if meta_db.get_pinned(b"multi-consensus-metadata-key").is_ok_and(|r| r.is_some()) || management_store.should_upgrade().unwrap() {
+        diagnostics.push(Diagnostic::failure(
+            "schema version",
+            "the databases were created by an incompatible kaspad version",
+            "start the node with --reset-db in order to delete them and resync",
+        ));
+        return None;
+    }
+    diagnostics.push(Diagnostic::ok("schema version", "the databases are of the current schema version"));
+
+    for (name, enabled) in [(UTXOINDEX_DB, args.utxoindex), (TXINDEX_DB, args.txindex)] {
+        let dir = db_dir.join(name);
+        if enabled && dir.exists() {
+            if let Err(diagnostic) = open_db(&dir, 1) {
+                diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    let Some(dir_name) = management_store.active_consensus_dir_name().unwrap() else {
+        diagnostics.push(Diagnostic::ok(CHECK, "the meta database opened successfully, consensus is not initialized yet"));
+        return None;
+    };
+    let consensus_db = match open_db(&db_dir.join(CONSENSUS_DB).join(dir_name), 10) {
+        Ok(db) => db,
+        Err(diagnostic) => {
+            diagnostics.push(diagnostic);
+            return None;
+        }
+    };
+    diagnostics.push(Diagnostic::ok(CHECK, format!("the databases at {} opened successfully", db_dir.display())));
+
+    let headers_store = DbHeadersStore::new(consensus_db.clone(), 0);
+    // Mainnet is never restarted, while a pruned mainnet node might no longer hold the genesis header
+    if !args.network().is_mainnet() && !headers_store.has(config.genesis.hash).unwrap() {
+        diagnostics.push(Diagnostic::failure(
+            "genesis",
+            format!("genesis {} is not found in the active consensus database", config.genesis.hash),
+            "the network was likely restarted, start the node with --reset-db in order to resync",
+        ));
+    }
+
+    let selected_tip = DbHeadersSelectedTipStore::new(consensus_db).get().ok()?;
+    if selected_tip.hash.is_origin() {
+        return None;
+    }
+    headers_store.get_timestamp(selected_tip.hash).ok()
+}
+
+/// Opens the DB at `path` without creating it, reporting a failure as a diagnostic
+fn open_db(path: &Path, files_limit: i32) -> Result<Arc<DB>, Diagnostic> {
+    ConnBuilder::default()
+        .with_db_path(path.to_path_buf())
+        .with_create_if_missing(false)
+        .with_files_limit(files_limit)
+        .try_build()
+        .map_err(|err| {
+            Diagnostic::failure(
+                "databases",
+                format!("failed opening {}: {}", path.display(), err),
+                "restore the node state with --import-state or start the node with --reset-db in order to resync",
+            )
+        })
+}
+
+/// Checks that the local clock is not behind the network time, as implied by the timestamp of the latest known header
+fn check_clock(config: &Config, tip_timestamp: Option<u64>) -> Diagnostic {
+    const CHECK: &str = "clock";
+    let now = unix_now();
+    if now < config.genesis.timestamp {
+        return Diagnostic::failure(CHECK, format!("the local time {now} precedes the genesis time"), "synchronize the system clock");
+    }
+    let tolerance = config.legacy_timestamp_deviation_tolerance * config.target_time_per_block;
+    match tip_timestamp {
+        Some(tip_timestamp) if tip_timestamp > now + tolerance => Diagnostic::failure(
+            CHECK,
+            format!("the local clock is {} seconds behind the latest known header", (tip_timestamp - now) / 1000),
+            "synchronize the system clock (e.g., enable NTP), otherwise blocks will be rejected as being from the future",
+        ),
+        Some(_) => Diagnostic::ok(CHECK, "the local clock is consistent with the latest known header"),
+        None => Diagnostic::ok(CHECK, "the local clock follows genesis (no known headers to compare with)"),
+    }
+}
+
+/// Checks that the addresses the node is configured to listen on are available
+fn check_ports(args: &Args, config: &Config, diagnostics: &mut Vec<Diagnostic>) {
+    let network_type = args.network().network_type;
+    let mut listeners: Vec<(&'static str, SocketAddr)> =
+        vec![("p2p port", args.listen.unwrap_or(ContextualNetAddress::unspecified()).normalize(config.default_p2p_port()).into())];
+    for listener in args.grpc_listeners() {
+        listeners.push(("gRPC port", listener.address.normalize(config.default_rpc_port()).into()));
+    }
+    for listener in args.rpclisten_borsh.iter() {
+        listeners.push(("wRPC Borsh port", listener.address.to_address(&network_type, &WrpcEncoding::Borsh).normalize(0).into()));
+    }
+    for listener in args.rpclisten_json.iter() {
+        listeners.push(("wRPC JSON port", listener.address.to_address(&network_type, &WrpcEncoding::SerdeJson).normalize(0).into()));
+    }
+    if let Some(address) = args.restlisten {
+        listeners.push(("REST port", address));
+    }
+
+    for (check, address) in listeners {
+        diagnostics.push(match TcpListener::bind(address) {
+            Ok(_) => Diagnostic::ok(check, format!("{address} is available")),
+            Err(err) if err.kind() == io::ErrorKind::AddrInUse => Diagnostic::failure(
+                check,
+                format!("{address} is already in use"),
+                "stop the process listening on it (possibly another kaspad) or configure a different address",
+            ),
+            Err(err) => Diagnostic::failure(check, format!("cannot listen on {address}: {err}"), "configure a different address"),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::config::params::DEVNET_PARAMS;
+
+    #[test]
+    fn test_doctor_checks() {
+        let config = ConfigBuilder::new(DEVNET_PARAMS).build();
+        assert!(check_params(&config).outcome == Outcome::Ok);
+
+        let mut tampered = config.clone();
+        tampered.params.genesis.nonce += 1;
+        assert!(check_params(&tampered).outcome == Outcome::Failure);
+
+        let now = unix_now();
+        assert!(check_clock(&config, None).outcome == Outcome::Ok);
+        assert!(check_clock(&config, Some(now - 60_000)).outcome == Outcome::Ok);
+        assert!(check_clock(&config, Some(now + 3_600_000)).outcome == Outcome::Failure);
+    }
+}
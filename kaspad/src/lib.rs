@@ -1,2 +1,6 @@
+pub mod appdir;
 pub mod args;
 pub mod daemon;
+pub mod doctor;
+pub mod reload;
+pub mod state;
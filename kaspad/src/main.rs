@@ -2,13 +2,14 @@ extern crate kaspa_consensus;
 extern crate kaspa_core;
 extern crate kaspa_hashes;
 
-use std::sync::Arc;
+use std::{process::exit, sync::Arc};
 
 use kaspa_core::{info, signals::Signals};
 use kaspa_utils::fd_budget;
 use kaspad_lib::{
     args::parse_args,
-    daemon::{create_core, DESIRED_DAEMON_SOFT_FD_LIMIT, MINIMUM_DAEMON_SOFT_FD_LIMIT},
+    daemon::{create_core, init_request_tracing, DESIRED_DAEMON_SOFT_FD_LIMIT, MINIMUM_DAEMON_SOFT_FD_LIMIT},
+    doctor::run_doctor,
 };
 
 #[cfg(feature = "heap")]
@@ -20,6 +21,9 @@ pub fn main() {
     let _profiler = dhat::Profiler::builder().file_name("kaspad-heap.json").build();
 
     let args = parse_args();
+    if args.doctor {
+        exit(if run_doctor(&args) { 0 } else { 1 });
+    }
 
     match fd_budget::try_set_fd_limit(DESIRED_DAEMON_SOFT_FD_LIMIT) {
         Ok(limit) => {
@@ -36,7 +40,13 @@ pub fn main() {
         }
     }
 
-    let fd_total_budget = fd_budget::limit() - args.rpc_max_clients as i32 - args.inbound_limit as i32 - args.outbound_target as i32;
+    let fd_total_budget = fd_budget::limit()
+        - args.rpc_max_clients as i32
+        - args.inbound_limit as i32
+        - args.reserved_inbound as i32
+        - args.outbound_target as i32;
+    // Keep the guard alive until exit so that the trace file is flushed
+    let _trace_guard = args.trace_requests.as_deref().and_then(init_request_tracing);
     let (core, _) = create_core(args, fd_total_budget);
 
     // Bind the keyboard signal to the core
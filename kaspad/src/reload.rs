@@ -0,0 +1,134 @@
+use kaspa_core::{
+    info,
+    task::service::{AsyncService, AsyncServiceFuture},
+    trace, warn,
+};
+use kaspa_rpc_core::ReloadSettingsRequest;
+use kaspa_rpc_service::service::RpcCoreService;
+use kaspa_utils::triggers::SingleTrigger;
+use std::{path::PathBuf, sync::Arc};
+
+const SETTINGS_RELOADER: &str = "settings-reloader";
+
+/// Parses a settings file made of `key=value` lines, where keys are named after the matching command line
+/// arguments (`loglevel`, `outpeers`, `maxinpeers`) or the mempool caps (`mempool-max-txs`, `mempool-max-orphans`).
+/// Empty lines and lines starting with `#` are ignored
+pub fn parse_settings(content: &str) -> Result<ReloadSettingsRequest, String> {
+    let mut request = ReloadSettingsRequest::default();
+    for (index, line) in content.lines().enumerate().map(|(index, line)| (index + 1, line.trim())) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {index}: expected a `key=value` pair"));
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let number = || value.parse::<u64>().map_err(|err| format!("line {index}: invalid value for `{key}`: {err}"));
+        match key {
+            "loglevel" => request.log_level = Some(value.to_string()),
+            "outpeers" => request.outbound_target = Some(number()?),
+            "maxinpeers" => request.inbound_limit = Some(number()?),
+            "mempool-max-txs" => request.mempool_max_transactions = Some(number()?),
+            "mempool-max-orphans" => request.mempool_max_orphans = Some(number()?),
+            _ => return Err(format!("line {index}: unknown setting `{key}`")),
+        }
+    }
+    Ok(request)
+}
+
+/// Re-applies the settings of a settings file whenever the process receives a SIGHUP signal
+pub struct SettingsReloader {
+    rpc_core_service: Arc<RpcCoreService>,
+    settings_file: PathBuf,
+    shutdown: SingleTrigger,
+}
+
+impl SettingsReloader {
+    pub fn new(rpc_core_service: Arc<RpcCoreService>, settings_file: PathBuf) -> Self {
+        Self { rpc_core_service, settings_file, shutdown: SingleTrigger::default() }
+    }
+
+    async fn reload(&self) {
+        let request =
+            match std::fs::read_to_string(&self.settings_file).map_err(|err| err.to_string()).and_then(|x| parse_settings(&x)) {
+                Ok(request) => request,
+                Err(err) => {
+                    warn!("Failed reading settings file {}: {}", self.settings_file.display(), err);
+                    return;
+                }
+            };
+        match self.rpc_core_service.apply_settings(request).await {
+            Ok(_) => info!("Reloaded settings from {}", self.settings_file.display()),
+            Err(err) => warn!("Failed reloading settings from {}: {}", self.settings_file.display(), err),
+        }
+    }
+
+    #[cfg(unix)]
+    async fn worker(&self) {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(err) => {
+                warn!("Unable to listen to SIGHUP, settings will not be reloaded: {}", err);
+                return;
+            }
+        };
+        loop {
+            tokio::select! {
+                _ = hangup.recv() => self.reload().await,
+                _ = self.shutdown.listener.clone() => break,
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    async fn worker(&self) {
+        warn!("Reloading settings upon SIGHUP is only supported on unix systems, use the ReloadSettings RPC call instead");
+        self.shutdown.listener.clone().await;
+    }
+}
+
+impl AsyncService for SettingsReloader {
+    fn ident(self: Arc<Self>) -> &'static str {
+        SETTINGS_RELOADER
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            self.worker().await;
+            Ok(())
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", SETTINGS_RELOADER);
+        self.shutdown.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            trace!("{} stopped", SETTINGS_RELOADER);
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_settings() {
+        let request = parse_settings("# peers\noutpeers = 16\n\nloglevel=info,kaspa_p2p_flows=debug\nmempool-max-txs=5000\n").unwrap();
+        assert_eq!(request.log_level.as_deref(), Some("info,kaspa_p2p_flows=debug"));
+        assert_eq!(request.outbound_target, Some(16));
+        assert_eq!(request.inbound_limit, None);
+        assert_eq!(request.mempool_max_transactions, Some(5000));
+        assert_eq!(request.mempool_max_orphans, None);
+
+        assert!(parse_settings("outpeers=many").is_err());
+        assert!(parse_settings("rpcmaxclients=10").is_err());
+        assert!(parse_settings("outpeers").is_err());
+    }
+}
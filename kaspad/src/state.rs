@@ -0,0 +1,362 @@
+//!
+//! Export and import of the full node state as a single archive, allowing to migrate a node between machines
+//! without re-downloading the DAG.
+//!
+//! Archive layout: `magic | format version (u16 LE) | metadata | metadata hash | entry*`, where the metadata
+//! holds the kaspad version, the network id, the creation time and the number of entries, and each entry is
+//! `path | size (u64 LE) | content | hash of path and content`. Strings are prefixed by their u16 LE length and
+//! all hashes are 32-byte blake2b digests, so any corruption is detected before the state is put in place.
+//!
+
+use blake2b_simd::{Params, State};
+use kaspa_core::{info, kaspad_env::version, time::unix_now, warn};
+use kaspa_database::prelude::{create_checkpoint, ConnBuilder};
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+const STATE_ARCHIVE_MAGIC: [u8; 8] = *b"KASSTATE";
+const STATE_ARCHIVE_VERSION: u16 = 1;
+const HASH_SIZE: usize = 32;
+
+/// The file each RocksDB directory holds, used for locating the databases to checkpoint
+const ROCKSDB_CURRENT_FILE: &str = "CURRENT";
+const CHECKPOINT_FILES_LIMIT: i32 = 128;
+
+#[derive(Error, Debug)]
+pub enum StateArchiveError {
+    #[error("not a node state archive")]
+    InvalidMagic,
+
+    #[error("unsupported state archive version {0}")]
+    UnsupportedVersion(u16),
+
+    #[error("state archive was exported on network {0} while the node runs on {1}")]
+    NetworkMismatch(String, String),
+
+    #[error("integrity check failed for {0}")]
+    IntegrityMismatch(String),
+
+    #[error("invalid entry path {0}")]
+    InvalidPath(String),
+
+    #[error("no databases found in {0}")]
+    NoDatabases(String),
+
+    #[error("failed creating a DB checkpoint: {0}")]
+    Checkpoint(String),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+pub type StateArchiveResult<T> = std::result::Result<T, StateArchiveError>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateArchiveMetadata {
+    pub kaspad_version: String,
+    pub network: String,
+    pub created_at: u64,
+    pub entries: u32,
+}
+
+impl StateArchiveMetadata {
+    fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        write_string(&mut bytes, &self.kaspad_version)?;
+        write_string(&mut bytes, &self.network)?;
+        bytes.extend(self.created_at.to_le_bytes());
+        bytes.extend(self.entries.to_le_bytes());
+        Ok(bytes)
+    }
+
+    fn read(reader: &mut impl Read, hasher: &mut State) -> io::Result<Self> {
+        let mut reader = HashingReader { reader, hasher };
+        let kaspad_version = read_string(&mut reader)?;
+        let network = read_string(&mut reader)?;
+        let created_at = u64::from_le_bytes(read_array(&mut reader)?);
+        let entries = u32::from_le_bytes(read_array(&mut reader)?);
+        Ok(Self { kaspad_version, network, created_at, entries })
+    }
+}
+
+/// Checkpoints all databases found under `db_dir` and writes them to a single archive at `archive_path`. Returns the
+/// metadata of the written archive
+pub fn export_state(db_dir: &Path, network: &str, archive_path: &Path) -> StateArchiveResult<StateArchiveMetadata> {
+    let databases = find_databases(db_dir)?;
+    if databases.is_empty() {
+        return Err(StateArchiveError::NoDatabases(db_dir.display().to_string()));
+    }
+
+    // Checkpoints are created next to the databases so that they can hard link the immutable files
+    let staging = tempfile::Builder::new().prefix("state-export").tempdir_in(db_dir.parent().unwrap_or(db_dir))?;
+    for relative in databases.iter() {
+        info!("Creating a checkpoint of {}", relative.display());
+        let db = ConnBuilder::default()
+            .with_db_path(db_dir.join(relative))
+            .with_create_if_missing(false)
+            .with_files_limit(CHECKPOINT_FILES_LIMIT)
+            .build()
+            .map_err(|err| StateArchiveError::Checkpoint(err.to_string()))?;
+        let target = staging.path().join(relative);
+        fs::create_dir_all(target.parent().expect("a checkpoint target is nested in the staging dir"))?;
+        create_checkpoint(&db, target).map_err(|err| StateArchiveError::Checkpoint(err.to_string()))?;
+    }
+
+    let mut files = Vec::new();
+    collect_files(staging.path(), Path::new(""), &mut files)?;
+    let metadata = StateArchiveMetadata {
+        kaspad_version: version().to_string(),
+        network: network.to_string(),
+        created_at: unix_now(),
+        entries: files.len() as u32,
+    };
+
+    let mut writer = BufWriter::new(File::create(archive_path)?);
+    writer.write_all(&STATE_ARCHIVE_MAGIC)?;
+    writer.write_all(&STATE_ARCHIVE_VERSION.to_le_bytes())?;
+    let metadata_bytes = metadata.to_bytes()?;
+    writer.write_all(&metadata_bytes)?;
+    writer.write_all(new_hasher().update(&metadata_bytes).finalize().as_bytes())?;
+    for relative in files.iter() {
+        let path = relative_path_string(relative)?;
+        let mut file = File::open(staging.path().join(relative))?;
+        let mut hasher = new_hasher();
+        hasher.update(path.as_bytes());
+        write_string(&mut writer, &path)?;
+        writer.write_all(&file.metadata()?.len().to_le_bytes())?;
+        io::copy(&mut HashingReader { reader: &mut file, hasher: &mut hasher }, &mut writer)?;
+        writer.write_all(hasher.finalize().as_bytes())?;
+    }
+    writer.flush()?;
+    Ok(metadata)
+}
+
+/// Verifies and extracts the archive at `archive_path` into `db_dir`, replacing its current content if any. The archive
+/// content is extracted to a sibling directory which is swapped into place only once all entries passed their integrity
+/// checks, so that a failed import leaves the current state untouched
+pub fn import_state(archive_path: &Path, network: &str, db_dir: &Path) -> StateArchiveResult<StateArchiveMetadata> {
+    let mut reader = BufReader::new(File::open(archive_path)?);
+    if read_array::<8>(&mut reader)? != STATE_ARCHIVE_MAGIC {
+        return Err(StateArchiveError::InvalidMagic);
+    }
+    let format_version = u16::from_le_bytes(read_array(&mut reader)?);
+    if format_version != STATE_ARCHIVE_VERSION {
+        return Err(StateArchiveError::UnsupportedVersion(format_version));
+    }
+    let mut hasher = new_hasher();
+    let metadata = StateArchiveMetadata::read(&mut reader, &mut hasher)?;
+    if hasher.finalize().as_bytes() != read_array::<HASH_SIZE>(&mut reader)? {
+        return Err(StateArchiveError::IntegrityMismatch("metadata".to_string()));
+    }
+    if metadata.network != network {
+        return Err(StateArchiveError::NetworkMismatch(metadata.network, network.to_string()));
+    }
+    if metadata.kaspad_version != version() {
+        warn!("State archive was exported by kaspad v{} while this is v{}", metadata.kaspad_version, version());
+    }
+
+    let parent = db_dir.parent().unwrap_or(db_dir);
+    fs::create_dir_all(parent)?;
+    let staging = tempfile::Builder::new().prefix("state-import").tempdir_in(parent)?;
+    for _ in 0..metadata.entries {
+        let mut hasher = new_hasher();
+        let path = read_string(&mut reader)?;
+        let relative = parse_relative_path(&path)?;
+        hasher.update(path.as_bytes());
+        let size = u64::from_le_bytes(read_array(&mut reader)?);
+
+        let target = staging.path().join(relative);
+        fs::create_dir_all(target.parent().expect("an entry is nested in the staging dir"))?;
+        let mut file = File::create(&target)?;
+        let copied = io::copy(&mut HashingReader { reader: &mut (&mut reader).take(size), hasher: &mut hasher }, &mut file)?;
+        if copied != size || hasher.finalize().as_bytes() != read_array::<HASH_SIZE>(&mut reader)? {
+            return Err(StateArchiveError::IntegrityMismatch(path));
+        }
+        file.sync_all()?;
+    }
+
+    // The current state is moved aside rather than deleted, so that it can be restored if the swap fails
+    let replaced = tempfile::Builder::new().prefix("state-replaced").tempdir_in(parent)?;
+    let replaced_db_dir = replaced.path().join("datadir");
+    if db_dir.exists() {
+        fs::rename(db_dir, &replaced_db_dir)?;
+    }
+    if let Err(err) = fs::rename(staging.path(), db_dir) {
+        if replaced_db_dir.exists() {
+            fs::rename(&replaced_db_dir, db_dir)?;
+        }
+        return Err(err.into());
+    }
+    Ok(metadata)
+}
+
+/// Returns the paths, relative to `db_dir`, of all RocksDB databases nested in it
+fn find_databases(db_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    fn visit(root: &Path, relative: &Path, databases: &mut Vec<PathBuf>) -> io::Result<()> {
+        let dir = root.join(relative);
+        if dir.join(ROCKSDB_CURRENT_FILE).is_file() {
+            databases.push(relative.to_path_buf());
+            return Ok(());
+        }
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                visit(root, &relative.join(entry.file_name()), databases)?;
+            }
+        }
+        Ok(())
+    }
+    let mut databases = Vec::new();
+    if db_dir.is_dir() {
+        visit(db_dir, Path::new(""), &mut databases)?;
+    }
+    databases.sort();
+    Ok(databases)
+}
+
+fn collect_files(root: &Path, relative: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries = fs::read_dir(root.join(relative))?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = relative.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            collect_files(root, &path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Archive paths are always `/`-separated regardless of the platform they were exported on
+fn relative_path_string(relative: &Path) -> StateArchiveResult<String> {
+    let components = relative
+        .components()
+        .map(|c| c.as_os_str().to_str().map(|s| s.to_string()))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| StateArchiveError::InvalidPath(relative.display().to_string()))?;
+    Ok(components.join("/"))
+}
+
+/// Parses an archive path, rejecting anything which could escape the extraction directory
+fn parse_relative_path(path: &str) -> StateArchiveResult<PathBuf> {
+    if path.is_empty() || path.split('/').any(|c| c.is_empty() || c == "." || c == ".." || c.contains('\\') || c.contains(':')) {
+        return Err(StateArchiveError::InvalidPath(path.to_string()));
+    }
+    Ok(path.split('/').collect())
+}
+
+fn new_hasher() -> State {
+    Params::new().hash_length(HASH_SIZE).to_state()
+}
+
+/// Feeds all bytes read through it to a hasher
+struct HashingReader<'a, R: Read> {
+    reader: R,
+    hasher: &'a mut State,
+}
+
+impl<R: Read> Read for HashingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+fn write_string(writer: &mut impl Write, s: &str) -> io::Result<()> {
+    let len = u16::try_from(s.len()).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "string is too long"))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(s.as_bytes())
+}
+
+fn read_string(reader: &mut impl Read) -> io::Result<String> {
+    let len = u16::from_le_bytes(read_array(reader)?) as usize;
+    let mut bytes = vec![0u8; len];
+    reader.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_archive_roundtrip() {
+        let root = tempfile::tempdir().unwrap();
+        let db_dir = root.path().join("source").join("datadir");
+        for name in ["meta", "consensus/consensus-001"] {
+            let db = ConnBuilder::default().with_db_path(db_dir.join(name)).with_files_limit(16).build().unwrap();
+            db.put(name.as_bytes(), b"value").unwrap();
+        }
+
+        let archive = root.path().join("state.bin");
+        let exported = export_state(&db_dir, "kaspa-simnet", &archive).unwrap();
+        assert!(exported.entries > 0);
+
+        // The archive is bound to its network
+        let target = root.path().join("target").join("datadir");
+        assert!(matches!(import_state(&archive, "kaspa-mainnet", &target), Err(StateArchiveError::NetworkMismatch(..))));
+
+        let imported = import_state(&archive, "kaspa-simnet", &target).unwrap();
+        assert_eq!(imported, exported);
+        for name in ["meta", "consensus/consensus-001"] {
+            let db = ConnBuilder::default().with_db_path(target.join(name)).with_files_limit(16).build().unwrap();
+            assert_eq!(db.get(name.as_bytes()).unwrap().unwrap(), b"value");
+        }
+
+        // Corrupting any content byte fails the import
+        let mut bytes = fs::read(&archive).unwrap();
+        let last = bytes.len() - HASH_SIZE - 1;
+        bytes[last] ^= 1;
+        fs::write(&archive, bytes).unwrap();
+        let corrupted_target = root.path().join("corrupted").join("datadir");
+        assert!(matches!(import_state(&archive, "kaspa-simnet", &corrupted_target), Err(StateArchiveError::IntegrityMismatch(..))));
+        assert!(!corrupted_target.exists());
+
+        // A failed import leaves the current state in place, and nothing is left behind next to it
+        assert!(matches!(import_state(&archive, "kaspa-simnet", &target), Err(StateArchiveError::IntegrityMismatch(..))));
+        let db = ConnBuilder::default().with_db_path(target.join("meta")).with_files_limit(16).build().unwrap();
+        assert_eq!(db.get(b"meta").unwrap().unwrap(), b"value");
+        drop(db);
+        assert_eq!(fs::read_dir(target.parent().unwrap()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_state_import_replaces_existing() {
+        let root = tempfile::tempdir().unwrap();
+        let db_dir = root.path().join("source").join("datadir");
+        let db = ConnBuilder::default().with_db_path(db_dir.join("meta")).with_files_limit(16).build().unwrap();
+        db.put(b"key", b"imported").unwrap();
+        drop(db);
+        let archive = root.path().join("state.bin");
+        export_state(&db_dir, "kaspa-simnet", &archive).unwrap();
+
+        let target = root.path().join("target").join("datadir");
+        fs::create_dir_all(target.join("stale")).unwrap();
+        import_state(&archive, "kaspa-simnet", &target).unwrap();
+        assert!(!target.join("stale").exists());
+        let db = ConnBuilder::default().with_db_path(target.join("meta")).with_files_limit(16).build().unwrap();
+        assert_eq!(db.get(b"key").unwrap().unwrap(), b"imported");
+        drop(db);
+        assert_eq!(fs::read_dir(target.parent().unwrap()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_parse_relative_path() {
+        assert_eq!(parse_relative_path("consensus/consensus-001/CURRENT").unwrap(), PathBuf::from("consensus/consensus-001/CURRENT"));
+        for path in ["", "/etc/passwd", "../outside", "a//b", "a/./b", "a\\b", "c:"] {
+            assert!(parse_relative_path(path).is_err(), "{path}");
+        }
+    }
+}
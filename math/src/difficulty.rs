@@ -0,0 +1,79 @@
+//!
+//! Conversions between a proof-of-work target, its compact bits encoding (as found in block headers) and its
+//! difficulty, which is the ratio of the maximal target of the network to the target.
+//!
+
+use crate::{Uint256, Uint320};
+
+/// Returns the difficulty of the target encoded by the compact `bits`
+pub fn difficulty_from_bits(bits: u32, max_target: Uint256) -> f64 {
+    difficulty_from_target(Uint256::from_compact_target_bits(bits), max_target)
+}
+
+/// Returns the difficulty of `target`, which is infinite for a zero target
+pub fn difficulty_from_target(target: Uint256, max_target: Uint256) -> f64 {
+    max_target.as_f64() / target.as_f64()
+}
+
+/// Returns the target of `difficulty`. Difficulties below 1 (and NaN) map to the maximal target
+pub fn target_from_difficulty(difficulty: f64, max_target: Uint256) -> Uint256 {
+    if difficulty.is_nan() || difficulty <= 1.0 {
+        return max_target;
+    }
+    if difficulty.is_infinite() {
+        return Uint256::ZERO;
+    }
+    // A normal float above 1 is exactly `mantissa * 2^exponent` with a 53-bit mantissa and an exponent of at least -52
+    let float_bits = difficulty.to_bits();
+    let mantissa = (float_bits & ((1 << 52) - 1)) | (1 << 52);
+    let exponent = ((float_bits >> 52) & 0x7ff) as i32 - 1075;
+    let target = if exponent >= 0 {
+        if exponent > 255 {
+            return Uint256::ZERO;
+        }
+        Uint320::from(max_target) / (Uint320::from_u64(mantissa) << exponent as u32)
+    } else {
+        (Uint320::from(max_target) << exponent.unsigned_abs()) / Uint320::from_u64(mantissa)
+    };
+    // The target is below the maximal target since the difficulty is above 1
+    Uint256::try_from(target).unwrap()
+}
+
+/// Returns the compact bits of the target of `difficulty`
+pub fn bits_from_difficulty(difficulty: f64, max_target: Uint256) -> u32 {
+    let target = target_from_difficulty(difficulty, max_target);
+    // The difficulty carries the target with the 53-bit precision of a float, so the target might fall just below
+    // the one it was computed from. Rounding it up by far less than the 23-bit precision of the compact encoding
+    // recovers the original bits before they are truncated
+    (target + (target >> 32)).compact_target_bits()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_TARGET: Uint256 = Uint256([u64::MAX, u64::MAX, u64::MAX, u64::MAX >> 1]);
+
+    #[test]
+    fn test_difficulty_conversions() {
+        assert_eq!(difficulty_from_target(MAX_TARGET, MAX_TARGET), 1.0);
+        assert_eq!(difficulty_from_target(MAX_TARGET >> 10, MAX_TARGET), 1024.0);
+        assert_eq!(difficulty_from_target(Uint256::ZERO, MAX_TARGET), f64::INFINITY);
+
+        assert_eq!(target_from_difficulty(0.5, MAX_TARGET), MAX_TARGET);
+        assert_eq!(target_from_difficulty(f64::NAN, MAX_TARGET), MAX_TARGET);
+        assert_eq!(target_from_difficulty(f64::INFINITY, MAX_TARGET), Uint256::ZERO);
+        assert_eq!(target_from_difficulty(1024.0, MAX_TARGET), MAX_TARGET >> 10);
+        assert_eq!(
+            target_from_difficulty(1.5, MAX_TARGET),
+            Uint256::try_from((Uint320::from(MAX_TARGET) << 1) / Uint320::from_u64(3)).unwrap()
+        );
+        assert_eq!(target_from_difficulty(2f64.powi(300), MAX_TARGET), Uint256::ZERO);
+
+        // Bits of the genesis and of various blocks, all in the normalized compact encoding
+        for bits in [0x1e7fffff, 0x1e21bc1c, 0x1b4dd8b9, 0x1a0b3f34, 0x1915b8c3, 0x207fffff] {
+            let difficulty = difficulty_from_bits(bits, MAX_TARGET);
+            assert_eq!(bits_from_difficulty(difficulty, MAX_TARGET), bits, "bits {bits:#x} with difficulty {difficulty}");
+        }
+    }
+}
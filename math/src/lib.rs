@@ -2,9 +2,11 @@ use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use wasm_bindgen::JsValue;
 use workflow_core::sendable::Sendable;
 
+pub mod difficulty;
 pub mod int;
 pub mod uint;
 pub mod wasm;
+pub mod work;
 
 construct_uint!(Uint192, 3, BorshSerialize, BorshDeserialize, BorshSchema);
 construct_uint!(Uint256, 4);
@@ -195,6 +195,52 @@ macro_rules! construct_uint {
                 }
                 (result, carry_out)
             }
+
+            #[inline]
+            pub fn checked_add(self, other: Self) -> Option<Self> {
+                let (sum, carry) = self.overflowing_add(other);
+                (!carry).then_some(sum)
+            }
+
+            #[inline]
+            pub fn checked_add_u64(self, other: u64) -> Option<Self> {
+                let (sum, carry) = self.overflowing_add_u64(other);
+                (!carry).then_some(sum)
+            }
+
+            #[inline]
+            pub fn checked_sub(self, other: Self) -> Option<Self> {
+                let (difference, borrow) = self.overflowing_sub(other);
+                (!borrow).then_some(difference)
+            }
+
+            #[inline]
+            pub fn checked_mul(self, other: Self) -> Option<Self> {
+                let (product, carry) = self.overflowing_mul(other);
+                (!carry).then_some(product)
+            }
+
+            #[inline]
+            pub fn checked_mul_u64(self, other: u64) -> Option<Self> {
+                let (product, carry) = self.overflowing_mul_u64(other);
+                (!carry).then_some(product)
+            }
+
+            #[inline]
+            pub fn saturating_add(self, other: Self) -> Self {
+                self.checked_add(other).unwrap_or(Self::MAX)
+            }
+
+            #[inline]
+            pub fn saturating_sub(self, other: Self) -> Self {
+                self.checked_sub(other).unwrap_or(Self::ZERO)
+            }
+
+            #[inline]
+            pub fn saturating_mul(self, other: Self) -> Self {
+                self.checked_mul(other).unwrap_or(Self::MAX)
+            }
+
             /// Creates big integer value from a byte slice using
             /// little-endian encoding
             #[inline(always)]
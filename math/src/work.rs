@@ -0,0 +1,46 @@
+//!
+//! Proof-of-work accounting. Targets are 256-bit while the work they represent, and the blue work accumulated
+//! over the DAG, is kept in 192 bits which leaves ample headroom for any realistic chain.
+//!
+
+use crate::{Uint192, Uint256};
+
+/// Returns the expected number of hashes required to find a block meeting the target encoded by the compact `bits`
+pub fn calc_work(bits: u32) -> Uint192 {
+    let target = Uint256::from_compact_target_bits(bits);
+    // Source: https://github.com/bitcoin/bitcoin/blob/2e34374bf3e12b37b0c66824a6c998073cdfab01/src/chain.cpp#L131
+    // We need to compute 2**256 / (bnTarget+1), but we can't represent 2**256
+    // as it's too large for an arith_uint256. However, as 2**256 is at least as large
+    // as bnTarget+1, it is equal to ((2**256 - bnTarget - 1) / (bnTarget+1)) + 1,
+    // or ~bnTarget / (bnTarget+1) + 1.
+
+    let res = (!target / (target + 1)) + 1;
+    res.try_into().expect("Work should not exceed 2**192")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calc_work() {
+        // The easiest possible target still requires at least one hash
+        assert_eq!(calc_work(0x2100ffff), Uint192::from_u64(1));
+        // A target of 2^255 - 1 (approximately) requires two hashes
+        assert_eq!(calc_work(0x207fffff), Uint192::from_u64(2));
+        // Bitcoin genesis bits, whose work is well known
+        assert_eq!(calc_work(0x1d00ffff), Uint192::from_u64(0x100010001));
+    }
+
+    #[test]
+    fn test_blue_work_overflow() {
+        let work = calc_work(0x1d00ffff);
+        assert_eq!(Uint192::ZERO.checked_add(work), Some(work));
+        assert_eq!((Uint192::MAX - work).checked_add(work), Some(Uint192::MAX));
+        assert_eq!(Uint192::MAX.checked_add(work), None);
+        assert_eq!(Uint192::MAX.saturating_add(work), Uint192::MAX);
+        assert_eq!(Uint192::ZERO.saturating_sub(work), Uint192::ZERO);
+        assert_eq!(Uint192::MAX.checked_mul_u64(2), None);
+        assert_eq!(work.checked_mul_u64(2), Some(work + work));
+    }
+}
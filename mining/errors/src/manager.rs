@@ -10,6 +10,10 @@ pub enum MiningManagerError {
     /// A mempool rule error
     #[error(transparent)]
     MempoolError(#[from] RuleError),
+
+    /// The miner data does not fit into the coinbase payload
+    #[error("coinbase payload length is {0} while the maximum allowed length is {1}")]
+    CoinbasePayloadTooLong(usize, usize),
 }
 
 pub type MiningManagerResult<T> = std::result::Result<T, MiningManagerError>;
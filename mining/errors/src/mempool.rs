@@ -72,6 +72,15 @@ pub enum RuleError {
 
     #[error("Rejected spam tx {0} from mempool")]
     RejectSpamTransaction(TransactionId),
+
+    #[error("transaction {0} has more than {1} unconfirmed ancestors in the mempool")]
+    RejectTooManyAncestors(TransactionId, u64),
+
+    #[error("transaction {0} and its unconfirmed ancestors have a total mass of {1} which is larger than max allowed mass of {2}")]
+    RejectChainMass(TransactionId, u64, u64),
+
+    #[error("transaction {0} would make its unconfirmed ancestor {1} have more than {2} descendants in the mempool")]
+    RejectTooManyDescendants(TransactionId, TransactionId, u64),
 }
 
 impl From<NonStandardError> for RuleError {
@@ -120,6 +129,9 @@ pub enum NonStandardError {
 
     #[error("transaction input #{1} has {2} signature operations which is more than the allowed max amount of {3}")]
     RejectSignatureCount(TransactionId, usize, u8, u8),
+
+    #[error("transaction input #{1}: {2}")]
+    RejectScriptPolicy(TransactionId, usize, String),
 }
 
 impl NonStandardError {
@@ -134,6 +146,7 @@ impl NonStandardError {
             NonStandardError::RejectInputScriptClass(id, _) => id,
             NonStandardError::RejectInsufficientFee(id, _, _) => id,
             NonStandardError::RejectSignatureCount(id, _, _, _) => id,
+            NonStandardError::RejectScriptPolicy(id, _, _) => id,
         }
     }
 }
@@ -0,0 +1,203 @@
+//! Tracking of the time at which transactions are first seen by the node, either when entering the mempool
+//! (relayed by a peer or submitted via RPC) or when included in a block, and of the derived latencies:
+//!
+//! - the _inclusion latency_ of a transaction first seen in the mempool is the time elapsed until a block
+//!   including it is received
+//! - the _propagation latency_ of a transaction first seen in a block is the time elapsed between the block
+//!   timestamp and its reception
+//!
+//! Both are aggregated as percentiles over a window of recent samples.
+
+use kaspa_consensus_core::tx::TransactionId;
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+
+/// Number of recent samples the latency percentiles are computed over
+pub const LATENCY_SAMPLES_WINDOW: usize = 10_000;
+
+/// Maximum number of transactions the first-seen timestamps are kept for, the oldest being forgotten first
+const MAX_TRACKED_TRANSACTIONS: usize = 100_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FirstSeen {
+    /// The transaction entered the mempool before being included in a block
+    Mempool,
+    /// The transaction was first seen in a block
+    Block,
+}
+
+struct FirstSeenEntry {
+    /// Unix timestamp in milliseconds
+    timestamp: u64,
+    source: FirstSeen,
+    included: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LatencyPercentiles {
+    /// Median latency in milliseconds
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransactionLatencySnapshot {
+    /// Number of transactions first seen in the mempool
+    pub mempool_first_seen_count: u64,
+    /// Number of transactions first seen in a block
+    pub block_first_seen_count: u64,
+    pub inclusion: LatencyPercentiles,
+    pub propagation: LatencyPercentiles,
+}
+
+struct LatencySamples {
+    samples: VecDeque<u64>,
+}
+
+impl LatencySamples {
+    fn new() -> Self {
+        Self { samples: VecDeque::with_capacity(LATENCY_SAMPLES_WINDOW) }
+    }
+
+    fn push(&mut self, latency: u64) {
+        if self.samples.len() == LATENCY_SAMPLES_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(latency);
+    }
+
+    fn percentiles(&self) -> LatencyPercentiles {
+        let mut sorted = self.samples.iter().copied().collect::<Vec<_>>();
+        sorted.sort_unstable();
+        // Nearest-rank percentile
+        let percentile = |p: usize| if sorted.is_empty() { 0 } else { sorted[((sorted.len() * p + 99) / 100).max(1) - 1] };
+        LatencyPercentiles { p50: percentile(50), p90: percentile(90), p99: percentile(99) }
+    }
+}
+
+struct Inner {
+    first_seen: HashMap<TransactionId, FirstSeenEntry>,
+    /// Tracked transactions in insertion order
+    order: VecDeque<TransactionId>,
+    mempool_first_seen_count: u64,
+    block_first_seen_count: u64,
+    inclusion: LatencySamples,
+    propagation: LatencySamples,
+}
+
+impl Inner {
+    fn insert(&mut self, transaction_id: TransactionId, entry: FirstSeenEntry) {
+        if self.order.len() == MAX_TRACKED_TRANSACTIONS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.first_seen.remove(&oldest);
+            }
+        }
+        self.first_seen.insert(transaction_id, entry);
+        self.order.push_back(transaction_id);
+    }
+}
+
+pub struct TransactionLatencyTracker {
+    inner: Mutex<Inner>,
+}
+
+impl Default for TransactionLatencyTracker {
+    fn default() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                first_seen: HashMap::new(),
+                order: VecDeque::new(),
+                mempool_first_seen_count: 0,
+                block_first_seen_count: 0,
+                inclusion: LatencySamples::new(),
+                propagation: LatencySamples::new(),
+            }),
+        }
+    }
+}
+
+impl TransactionLatencyTracker {
+    /// Records the transaction entering the mempool at `now` (unix milliseconds)
+    pub fn record_mempool_transaction(&self, transaction_id: TransactionId, now: u64) {
+        let mut inner = self.inner.lock();
+        if !inner.first_seen.contains_key(&transaction_id) {
+            inner.insert(transaction_id, FirstSeenEntry { timestamp: now, source: FirstSeen::Mempool, included: false });
+            inner.mempool_first_seen_count += 1;
+        }
+    }
+
+    /// Records the (non-coinbase) transactions of a block with timestamp `block_timestamp` received at `now`
+    pub fn record_block_transactions(&self, block_timestamp: u64, transaction_ids: impl Iterator<Item = TransactionId>, now: u64) {
+        let mut guard = self.inner.lock();
+        let inner = &mut *guard;
+        for transaction_id in transaction_ids {
+            if let Some(entry) = inner.first_seen.get_mut(&transaction_id) {
+                if entry.source == FirstSeen::Mempool && !entry.included {
+                    entry.included = true;
+                    inner.inclusion.push(now.saturating_sub(entry.timestamp));
+                }
+            } else {
+                inner.insert(transaction_id, FirstSeenEntry { timestamp: now, source: FirstSeen::Block, included: true });
+                inner.block_first_seen_count += 1;
+                inner.propagation.push(now.saturating_sub(block_timestamp));
+            }
+        }
+    }
+
+    /// Where and when (unix milliseconds) the transaction was first seen, if still tracked
+    pub fn first_seen(&self, transaction_id: &TransactionId) -> Option<(FirstSeen, u64)> {
+        self.inner.lock().first_seen.get(transaction_id).map(|entry| (entry.source, entry.timestamp))
+    }
+
+    pub fn snapshot(&self) -> TransactionLatencySnapshot {
+        let inner = self.inner.lock();
+        TransactionLatencySnapshot {
+            mempool_first_seen_count: inner.mempool_first_seen_count,
+            block_first_seen_count: inner.block_first_seen_count,
+            inclusion: inner.inclusion.percentiles(),
+            propagation: inner.propagation.percentiles(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transaction_latency_tracker() {
+        let tracker = TransactionLatencyTracker::default();
+        let ids = (0..4).map(TransactionId::from_u64_word).collect::<Vec<_>>();
+
+        tracker.record_mempool_transaction(ids[0], 1_000);
+        tracker.record_mempool_transaction(ids[1], 1_500);
+        // Seen again, the first timestamp is kept
+        tracker.record_mempool_transaction(ids[0], 1_800);
+        assert_eq!(tracker.first_seen(&ids[0]), Some((FirstSeen::Mempool, 1_000)));
+
+        // ids[2] and ids[3] are first seen in the block
+        tracker.record_block_transactions(1_900, ids.iter().copied(), 2_000);
+        // A second block including the same transactions adds no sample
+        tracker.record_block_transactions(2_100, ids.iter().copied(), 2_200);
+
+        let snapshot = tracker.snapshot();
+        assert_eq!(snapshot.mempool_first_seen_count, 2);
+        assert_eq!(snapshot.block_first_seen_count, 2);
+        assert_eq!(snapshot.inclusion, LatencyPercentiles { p50: 500, p90: 1_000, p99: 1_000 });
+        assert_eq!(snapshot.propagation, LatencyPercentiles { p50: 100, p90: 100, p99: 100 });
+        assert_eq!(tracker.first_seen(&ids[3]), Some((FirstSeen::Block, 2_000)));
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let mut samples = LatencySamples::new();
+        assert_eq!(samples.percentiles(), LatencyPercentiles::default());
+        (1..=100).rev().for_each(|latency| samples.push(latency));
+        assert_eq!(samples.percentiles(), LatencyPercentiles { p50: 50, p90: 90, p99: 99 });
+
+        // Only the most recent samples are kept
+        (0..LATENCY_SAMPLES_WINDOW).for_each(|_| samples.push(7));
+        assert_eq!(samples.percentiles(), LatencyPercentiles { p50: 7, p90: 7, p99: 7 });
+    }
+}
@@ -2,20 +2,25 @@ use crate::{
     block_template::{builder::BlockTemplateBuilder, errors::BuilderError},
     cache::BlockTemplateCache,
     errors::MiningManagerResult,
+    latency::{TransactionLatencySnapshot, TransactionLatencyTracker},
     mempool::{
-        config::Config,
+        config::{Config, RelayPolicy},
         model::tx::{MempoolTransaction, TxRemovalReason},
         populate_entries_and_try_validate::{
             populate_mempool_transactions_in_parallel, validate_mempool_transaction, validate_mempool_transactions_in_parallel,
         },
+        snapshot::SnapshotCache,
         tx::{Orphan, Priority},
         Mempool,
     },
     model::{
         candidate_tx::CandidateTransaction,
+        fee_rate_histogram::FeeRateHistogram,
         owner_txs::{GroupedOwnerTransactions, ScriptPublicKeySet},
         topological_sort::IntoIterTopologically,
+        tx_check::TransactionCheck,
         tx_query::TransactionQuery,
+        tx_removal::MempoolRemovalSender,
     },
     MiningCounters,
 };
@@ -28,7 +33,12 @@ use kaspa_consensus_core::{
     tx::{MutableTransaction, Transaction, TransactionId, TransactionOutput},
 };
 use kaspa_consensusmanager::{spawn_blocking, ConsensusProxy};
-use kaspa_core::{debug, error, info, time::Stopwatch, warn};
+use kaspa_core::{
+    debug, error, info,
+    kaspad_env::version,
+    time::{unix_now, Stopwatch},
+    warn,
+};
 use kaspa_mining_errors::{manager::MiningManagerError, mempool::RuleError};
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -39,6 +49,9 @@ pub struct MiningManager {
     block_template_cache: BlockTemplateCache,
     mempool: RwLock<Mempool>,
     counters: Arc<MiningCounters>,
+    tx_latency: TransactionLatencyTracker,
+    candidates_snapshot: SnapshotCache<Vec<CandidateTransaction>>,
+    transactions_snapshot: SnapshotCache<Vec<MutableTransaction>>,
 }
 
 impl MiningManager {
@@ -70,14 +83,46 @@ impl MiningManager {
         Self::with_config(config, cache_lifetime, counters)
     }
 
-    pub(crate) fn with_config(config: Config, cache_lifetime: Option<u64>, counters: Arc<MiningCounters>) -> Self {
+    pub fn with_config(config: Config, cache_lifetime: Option<u64>, counters: Arc<MiningCounters>) -> Self {
         let config = Arc::new(config);
         let mempool = RwLock::new(Mempool::new(config.clone(), counters.clone()));
         let block_template_cache = BlockTemplateCache::new(cache_lifetime);
-        Self { config, block_template_cache, mempool, counters }
+        Self {
+            config,
+            block_template_cache,
+            mempool,
+            counters,
+            tx_latency: TransactionLatencyTracker::default(),
+            candidates_snapshot: SnapshotCache::new(),
+            transactions_snapshot: SnapshotCache::new(),
+        }
+    }
+
+    /// Sets the channel receiving the transactions dropped from the mempool without being accepted by a block
+    pub fn set_removal_sender(&self, sender: MempoolRemovalSender) {
+        self.mempool.write().set_removal_sender(sender);
+    }
+
+    /// Composes the extra data of a coinbase payload out of the node version, the pool tag of the config if any
+    /// and the extra data provided by the miner, each separated by a slash
+    pub fn coinbase_extra_data(&self, miner_extra_data: &[u8]) -> Vec<u8> {
+        let mut extra_data = version().as_bytes().to_vec();
+        extra_data.push(b'/');
+        if !self.config.coinbase_tag.is_empty() {
+            extra_data.extend_from_slice(&self.config.coinbase_tag);
+            extra_data.push(b'/');
+        }
+        extra_data.extend_from_slice(miner_extra_data);
+        extra_data
     }
 
     pub fn get_block_template(&self, consensus: &dyn ConsensusApi, miner_data: &MinerData) -> MiningManagerResult<BlockTemplate> {
+        // Reject miner data which would produce an invalid coinbase payload before any template is built or reused
+        let payload_len = miner_data.payload_len();
+        if payload_len > self.config.maximum_coinbase_payload_length {
+            return Err(MiningManagerError::CoinbasePayloadTooLong(payload_len, self.config.maximum_coinbase_payload_length));
+        }
+
         let virtual_state_approx_id = consensus.get_virtual_state_approx_id();
         let mut cache_lock = self.block_template_cache.lock(virtual_state_approx_id);
         let immutable_template = cache_lock.get_immutable_cached_template();
@@ -198,7 +243,16 @@ impl MiningManager {
     }
 
     pub(crate) fn block_candidate_transactions(&self) -> Vec<CandidateTransaction> {
-        self.mempool.read().block_candidate_transactions()
+        // read lock on mempool only if the pool changed since the candidates were last collected
+        let candidates = self.candidates_snapshot.get_or_build(
+            || self.mempool.read().epoch(),
+            || {
+                let mempool = self.mempool.read();
+                (mempool.epoch(), mempool.block_candidate_transactions())
+            },
+        );
+        // no lock on mempool
+        candidates.as_ref().clone()
     }
 
     /// Clears the block template cache, forcing the next call to get_block_template to build a new block template.
@@ -250,6 +304,7 @@ impl MiningManager {
             // The capacity used here may be exceeded since accepted unorphaned transaction may themselves unorphan other transactions.
             let mut accepted_transactions = Vec::with_capacity(unorphaned_transactions.len() + 1);
             // We include the original accepted transaction as well
+            self.tx_latency.record_mempool_transaction(accepted_transaction.id(), unix_now());
             accepted_transactions.push(accepted_transaction);
             accepted_transactions.extend(self.validate_and_insert_unorphaned_transactions(consensus, unorphaned_transactions));
             self.counters.increase_tx_counts(1, priority);
@@ -260,6 +315,24 @@ impl MiningManager {
         }
     }
 
+    /// Runs the mempool validation of a transaction without inserting it, so that its mass and fee can be known and its
+    /// acceptance anticipated before it is broadcast. A transaction which would be accepted as an orphan fails with a
+    /// missing outpoints error.
+    pub fn check_transaction(&self, consensus: &dyn ConsensusApi, transaction: Transaction) -> TransactionCheck {
+        let mass = consensus.calculate_transaction_mass(&transaction);
+        // read lock on mempool
+        let mut transaction =
+            match self.mempool.read().pre_validate_and_populate_transaction(consensus, MutableTransaction::from_tx(transaction)) {
+                Ok(transaction) => transaction,
+                Err(err) => return TransactionCheck { mass, fee: None, result: Err(err.into()) },
+            };
+        // no lock on mempool
+        let validation_result = validate_mempool_transaction(consensus, &mut transaction);
+        // read lock on mempool
+        let result = self.mempool.read().post_validate_transaction(validation_result, &transaction).map_err(Into::into);
+        TransactionCheck { mass, fee: transaction.calculated_fee, result }
+    }
+
     fn validate_and_insert_unorphaned_transactions(
         &self,
         consensus: &dyn ConsensusApi,
@@ -305,6 +378,7 @@ impl MiningManager {
                         Ok(Some(accepted_transaction)) => {
                             accepted_transactions.push(accepted_transaction.clone());
                             self.counters.increase_tx_counts(1, priority);
+                            self.tx_latency.record_mempool_transaction(accepted_transaction.id(), unix_now());
                             mempool.get_unorphaned_transactions_after_accepted_transaction(&accepted_transaction)
                         }
                         Ok(None) => vec![],
@@ -394,6 +468,7 @@ impl MiningManager {
                     Ok(Some(accepted_transaction)) => {
                         insert_results.push(Ok(accepted_transaction.clone()));
                         self.counters.increase_tx_counts(1, priority);
+                        self.tx_latency.record_mempool_transaction(accepted_transaction.id(), unix_now());
                         mempool.get_unorphaned_transactions_after_accepted_transaction(&accepted_transaction)
                     }
                     Ok(None) => {
@@ -447,15 +522,27 @@ impl MiningManager {
 
     pub fn get_all_transactions(&self, query: TransactionQuery) -> (Vec<MutableTransaction>, Vec<MutableTransaction>) {
         const TRANSACTION_CHUNK_SIZE: usize = 1000;
-        // read lock on mempool by transaction chunks
         let transactions = if query.include_transaction_pool() {
-            let transaction_ids = self.mempool.read().get_all_transaction_ids(TransactionQuery::TransactionsOnly).0;
-            let mut transactions = Vec::with_capacity(self.mempool.read().transaction_count(TransactionQuery::TransactionsOnly));
-            for chunks in transaction_ids.chunks(TRANSACTION_CHUNK_SIZE) {
-                let mempool = self.mempool.read();
-                transactions.extend(chunks.iter().filter_map(|x| mempool.get_transaction(x, TransactionQuery::TransactionsOnly)));
-            }
-            transactions
+            let transactions = self.transactions_snapshot.get_or_build(
+                || self.mempool.read().epoch(),
+                || {
+                    // read lock on mempool by transaction chunks, the snapshot being tagged with the epoch at which
+                    // its building started so that it gets rebuilt if the pool changed in between
+                    let (epoch, transaction_ids) = {
+                        let mempool = self.mempool.read();
+                        (mempool.epoch(), mempool.get_all_transaction_ids(TransactionQuery::TransactionsOnly).0)
+                    };
+                    let mut transactions = Vec::with_capacity(transaction_ids.len());
+                    for chunks in transaction_ids.chunks(TRANSACTION_CHUNK_SIZE) {
+                        let mempool = self.mempool.read();
+                        transactions
+                            .extend(chunks.iter().filter_map(|x| mempool.get_transaction(x, TransactionQuery::TransactionsOnly)));
+                    }
+                    (epoch, transactions)
+                },
+            );
+            // no lock on mempool
+            transactions.as_ref().clone()
         } else {
             vec![]
         };
@@ -485,6 +572,10 @@ impl MiningManager {
         self.mempool.read().transaction_count(query)
     }
 
+    pub fn fee_rate_histogram(&self, bucket_bounds: Vec<f64>) -> FeeRateHistogram {
+        self.mempool.read().fee_rate_histogram(bucket_bounds)
+    }
+
     pub fn handle_new_block_transactions(
         &self,
         consensus: &dyn ConsensusApi,
@@ -504,6 +595,39 @@ impl MiningManager {
         Ok(accepted_transactions)
     }
 
+    /// Returns to the mempool the transactions un-accepted by a reorg, i.e., accepted by chain blocks which left the
+    /// selected chain and not accepted by the new chain. The transactions are validated against the new virtual state
+    /// and inserted with low priority, as their original priority is unknown. Transactions spending the outputs of other
+    /// un-accepted transactions are inserted as orphans until these are. Transactions double spent by the new chain are
+    /// either rejected or expire as orphans.
+    ///
+    /// Returns the transactions inserted in the mempool, including those unorphaned as a result.
+    pub fn handle_unaccepted_transactions(
+        &self,
+        consensus: &dyn ConsensusApi,
+        transactions: Vec<Transaction>,
+    ) -> Vec<Arc<Transaction>> {
+        let transaction_ids = transactions.iter().map(|tx| tx.id()).collect::<Vec<_>>();
+        // write lock on mempool
+        self.mempool.write().remove_accepted_transactions(&transaction_ids);
+
+        self.validate_and_insert_transaction_batch(consensus, transactions, Priority::Low, Orphan::Allowed)
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .collect()
+    }
+
+    /// Records the reception of a block with timestamp `block_timestamp` for measuring the inclusion
+    /// and propagation latencies of its transactions, see [`crate::latency`]
+    pub fn record_block_transactions(&self, block_timestamp: u64, block_transactions: &[Transaction]) {
+        let transaction_ids = block_transactions.iter().filter(|tx| !tx.is_coinbase()).map(|tx| tx.id());
+        self.tx_latency.record_block_transactions(block_timestamp, transaction_ids, unix_now());
+    }
+
+    pub fn transaction_latency_snapshot(&self) -> TransactionLatencySnapshot {
+        self.tx_latency.snapshot()
+    }
+
     pub fn expire_low_priority_transactions(&self, consensus: &dyn ConsensusApi) {
         // very fine-grained write locks on mempool
         debug!("<> Expiring low priority transactions...");
@@ -521,16 +645,11 @@ impl MiningManager {
         for chunk in &expired_low_priority_transactions.iter().chunks(24) {
             let mut mempool = self.mempool.write();
             chunk.into_iter().for_each(|tx| {
-                if let Err(err) = mempool.remove_transaction(tx, true, TxRemovalReason::Muted, "") {
+                if let Err(err) = mempool.remove_transaction(tx, true, TxRemovalReason::Expired, "") {
                     warn!("Failed to remove transaction {} from mempool: {}", tx, err);
                 }
             });
         }
-        match expired_low_priority_transactions.len() {
-            0 => {}
-            1 => debug!("Removed transaction ({}) {}", TxRemovalReason::Expired, expired_low_priority_transactions[0]),
-            n => debug!("Removed {} transactions ({}): {}...", n, TxRemovalReason::Expired, expired_low_priority_transactions[0]),
-        }
     }
 
     pub fn revalidate_high_priority_transactions(
@@ -736,6 +855,21 @@ impl MiningManager {
         self.mempool.read().has_accepted_transaction(transaction_id)
     }
 
+    /// Returns the policy enforced on transactions entering the mempool (minimum relay fee rate, dust thresholds and chain limits)
+    pub fn relay_policy(&self) -> RelayPolicy {
+        self.mempool.read().relay_policy()
+    }
+
+    /// Returns the maximal numbers of transactions in the transaction and orphan pools
+    pub fn mempool_size_limits(&self) -> (u64, u64) {
+        self.mempool.read().size_limits()
+    }
+
+    /// Updates the maximal numbers of transactions in the transaction and orphan pools at runtime
+    pub fn set_mempool_size_limits(&self, maximum_transaction_count: u64, maximum_orphan_transaction_count: u64) {
+        self.mempool.write().set_size_limits(maximum_transaction_count, maximum_orphan_transaction_count)
+    }
+
     pub fn unaccepted_transactions(&self, transactions: Vec<TransactionId>) -> Vec<TransactionId> {
         self.mempool.read().unaccepted_transactions(transactions)
     }
@@ -756,6 +890,16 @@ impl MiningManagerProxy {
         Self { inner }
     }
 
+    /// Composes the extra data of a coinbase payload, see [`MiningManager::coinbase_extra_data`]
+    pub fn coinbase_extra_data(&self, miner_extra_data: &[u8]) -> Vec<u8> {
+        self.inner.coinbase_extra_data(miner_extra_data)
+    }
+
+    /// Sets the channel receiving the transactions dropped from the mempool, see [`MiningManager::set_removal_sender`]
+    pub fn set_removal_sender(&self, sender: MempoolRemovalSender) {
+        self.inner.set_removal_sender(sender)
+    }
+
     pub async fn get_block_template(self, consensus: &ConsensusProxy, miner_data: MinerData) -> MiningManagerResult<BlockTemplate> {
         consensus.clone().spawn_blocking(move |c| self.inner.get_block_template(c, &miner_data)).await
     }
@@ -774,6 +918,11 @@ impl MiningManagerProxy {
         consensus.clone().spawn_blocking(move |c| self.inner.validate_and_insert_transaction(c, transaction, priority, orphan)).await
     }
 
+    /// Runs the mempool validation of a transaction without inserting it
+    pub async fn check_transaction(self, consensus: &ConsensusProxy, transaction: Transaction) -> TransactionCheck {
+        consensus.clone().spawn_blocking(move |c| self.inner.check_transaction(c, transaction)).await
+    }
+
     /// Validates a batch of transactions, handling iteratively only the independent ones, and
     /// adds those to the set of known transactions that have not yet been added to any block.
     ///
@@ -796,14 +945,35 @@ impl MiningManagerProxy {
         self,
         consensus: &ConsensusProxy,
         block_daa_score: u64,
+        block_timestamp: u64,
         block_transactions: Arc<Vec<Transaction>>,
     ) -> MiningManagerResult<Vec<Arc<Transaction>>> {
         consensus
             .clone()
-            .spawn_blocking(move |c| self.inner.handle_new_block_transactions(c, block_daa_score, &block_transactions))
+            .spawn_blocking(move |c| {
+                self.inner.record_block_transactions(block_timestamp, &block_transactions);
+                self.inner.handle_new_block_transactions(c, block_daa_score, &block_transactions)
+            })
             .await
     }
 
+    pub async fn handle_unaccepted_transactions(
+        self,
+        consensus: &ConsensusProxy,
+        transactions: Vec<Transaction>,
+    ) -> Vec<Arc<Transaction>> {
+        consensus.clone().spawn_blocking(move |c| self.inner.handle_unaccepted_transactions(c, transactions)).await
+    }
+
+    /// Returns the transaction inclusion and propagation latency statistics, see [`crate::latency`]
+    pub fn transaction_latency_snapshot(&self) -> TransactionLatencySnapshot {
+        self.inner.transaction_latency_snapshot()
+    }
+
+    pub fn relay_policy(&self) -> RelayPolicy {
+        self.inner.relay_policy()
+    }
+
     pub async fn expire_low_priority_transactions(self, consensus: &ConsensusProxy) {
         consensus.clone().spawn_blocking(move |c| self.inner.expire_low_priority_transactions(c)).await;
     }
@@ -836,6 +1006,12 @@ impl MiningManagerProxy {
         spawn_blocking(move || self.inner.get_all_transactions(query)).await.unwrap()
     }
 
+    /// Returns the number and total mass of the mempool transactions in each of the fee rate buckets delimited by the
+    /// strictly ascending `bucket_bounds`, see [`FeeRateHistogram`]
+    pub async fn fee_rate_histogram(self, bucket_bounds: Vec<f64>) -> FeeRateHistogram {
+        spawn_blocking(move || self.inner.fee_rate_histogram(bucket_bounds)).await.unwrap()
+    }
+
     /// get_transactions_by_addresses returns the sending and receiving transactions for
     /// a set of addresses.
     ///
@@ -871,4 +1047,16 @@ impl MiningManagerProxy {
     pub async fn unknown_transactions(self, transactions: Vec<TransactionId>) -> Vec<TransactionId> {
         spawn_blocking(move || self.inner.unknown_transactions(transactions)).await.unwrap()
     }
+
+    /// Returns the maximal numbers of transactions in the transaction and orphan pools
+    pub async fn mempool_size_limits(self) -> (u64, u64) {
+        spawn_blocking(move || self.inner.mempool_size_limits()).await.unwrap()
+    }
+
+    /// Updates the maximal numbers of transactions in the transaction and orphan pools at runtime
+    pub async fn set_mempool_size_limits(self, maximum_transaction_count: u64, maximum_orphan_transaction_count: u64) {
+        spawn_blocking(move || self.inner.set_mempool_size_limits(maximum_transaction_count, maximum_orphan_transaction_count))
+            .await
+            .unwrap()
+    }
 }
@@ -9,7 +9,11 @@ mod tests {
             errors::RuleError,
             tx::{Orphan, Priority},
         },
-        model::{candidate_tx::CandidateTransaction, tx_query::TransactionQuery},
+        model::{
+            candidate_tx::CandidateTransaction,
+            tx_query::TransactionQuery,
+            tx_removal::{MempoolRemovalReason, MempoolTransactionRemoval},
+        },
         testutils::consensus_mock::ConsensusMock,
         MiningCounters,
     };
@@ -27,6 +31,7 @@ mod tests {
             TransactionOutput, UtxoEntry,
         },
     };
+    use kaspa_core::kaspad_env::version;
     use kaspa_hashes::Hash;
     use kaspa_txscript::{
         pay_to_address_script, pay_to_script_hash_signature_script,
@@ -110,6 +115,41 @@ mod tests {
         );
     }
 
+    /// test_check_transaction verifies that checking a transaction reports its mass, fee and validation result
+    /// without inserting it into the mempool.
+    #[test]
+    fn test_check_transaction() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        // A valid transaction is reported along with its mass and fee, and is not inserted
+        let transaction = create_child_and_parent_txs_and_add_parent_to_consensus(&consensus);
+        let check = mining_manager.check_transaction(consensus.as_ref(), transaction.clone());
+        assert!(check.result.is_ok(), "checking a valid transaction failed: {:?}", check.result);
+        assert_eq!(check.mass, transaction_estimated_serialized_size(&transaction));
+        assert_eq!(check.fee, Some(1000));
+        assert_eq!(check.fee_rate(), Some(1000.0 / check.mass as f64));
+        assert!(!mining_manager.has_transaction(&transaction.id(), TransactionQuery::All));
+
+        // Once inserted, the transaction is reported as a duplicate
+        mining_manager
+            .validate_and_insert_transaction(consensus.as_ref(), transaction.clone(), Priority::Low, Orphan::Allowed)
+            .unwrap();
+        let check = mining_manager.check_transaction(consensus.as_ref(), transaction.clone());
+        assert!(
+            matches!(check.result, Err(MiningManagerError::MempoolError(RuleError::RejectDuplicate(id))) if id == transaction.id())
+        );
+        assert_eq!(check.fee, None);
+
+        // A transaction which would be an orphan is reported as missing outpoints, and is not inserted
+        let orphan = create_transaction(&create_transaction_without_input(vec![SOMPI_PER_KASPA]), 1000);
+        let check = mining_manager.check_transaction(consensus.as_ref(), orphan.clone());
+        assert!(matches!(check.result, Err(MiningManagerError::MempoolError(RuleError::RejectMissingOutpoint))));
+        assert_eq!(check.fee, None);
+        assert!(!mining_manager.has_transaction(&orphan.id(), TransactionQuery::All));
+    }
+
     /// test_simulated_error_in_consensus verifies that a predefined result is actually
     /// returned by the consensus mock as expected when the mempool tries to validate and
     /// insert a transaction.
@@ -294,13 +334,52 @@ mod tests {
         }
     }
 
+    // test_handle_unaccepted_transactions verifies that transactions un-accepted by a reorg are returned to the mempool.
+    #[test]
+    fn test_handle_unaccepted_transactions() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        let (parent_tx, child_tx) = create_parent_and_children_transactions(&consensus, vec![500 * SOMPI_PER_KASPA]);
+        for transaction in [&parent_tx, &child_tx] {
+            let result = mining_manager.validate_and_insert_transaction(
+                consensus.as_ref(),
+                transaction.clone(),
+                Priority::Low,
+                Orphan::Allowed,
+            );
+            assert!(result.is_ok(), "the insertion of a new valid transaction in the mempool failed");
+        }
+
+        // Simulate: a block accepting both transactions is added and is later reorged out of the selected chain
+        let block_transactions = build_block_transactions([&parent_tx, &child_tx].into_iter());
+        let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), 2, &block_transactions);
+        assert!(result.is_ok(), "the handling by the mempool of the transactions of a block should succeed but returned {result:?}");
+        assert_eq!(mining_manager.transaction_count(TransactionQuery::All), 0, "the mempool should be empty");
+
+        // The child is listed first, the transactions being sorted topologically when handled
+        let inserted = mining_manager.handle_unaccepted_transactions(consensus.as_ref(), vec![child_tx.clone(), parent_tx.clone()]);
+        assert_eq!(inserted.len(), 2, "both un-accepted transactions should be returned to the mempool");
+        for transaction in [&parent_tx, &child_tx] {
+            assert!(
+                mining_manager.get_transaction(&transaction.id(), TransactionQuery::TransactionsOnly).is_some(),
+                "the un-accepted transaction {} is lacking from the mempool",
+                transaction.id()
+            );
+            assert!(!mining_manager.has_accepted_transaction(&transaction.id()), "the transaction should no longer be accepted");
+        }
+    }
+
     #[test]
     // test_double_spend_with_block verifies that any transactions which are now double spends as a result of the block's new transactions
-    // will be removed from the mempool.
+    // will be removed from the mempool and reported to the removal subscriber.
     fn test_double_spend_with_block() {
         let consensus = Arc::new(ConsensusMock::new());
         let counters = Arc::new(MiningCounters::default());
         let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+        let (removal_sender, removal_receiver) = async_channel::unbounded();
+        mining_manager.set_removal_sender(removal_sender);
 
         let transaction_in_the_mempool = create_transaction_with_utxo_entry(0, 0);
         let result = mining_manager.validate_and_insert_transaction(
@@ -324,6 +403,11 @@ mod tests {
             "the transaction {} shouldn't be in the mempool since at least one output was already spent",
             transaction_in_the_mempool.id()
         );
+        assert_eq!(
+            removal_receiver.try_recv(),
+            Ok(MempoolTransactionRemoval::new(transaction_in_the_mempool.id(), MempoolRemovalReason::ConflictWithBlock))
+        );
+        assert!(removal_receiver.is_empty(), "the transaction accepted by the block should not be reported");
     }
 
     // test_orphan_transactions verifies that a transaction could be a part of a new block template only if it's not an orphan.
@@ -725,6 +809,146 @@ mod tests {
         assert!(orphan_txs.is_empty(), "orphan pool should be empty");
     }
 
+    // test_coinbase_tag verifies that the pool tag is inserted into the coinbase extra data and that miner data
+    // exceeding the maximum coinbase payload length is rejected.
+    #[test]
+    fn test_coinbase_tag() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let mut config = Config::build_default(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS);
+        config.coinbase_tag = b"pool".to_vec();
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::with_config(config.clone(), None, counters);
+
+        let extra_data = mining_manager.coinbase_extra_data(b"miner");
+        assert_eq!(extra_data, [version().as_bytes(), b"/pool/miner"].concat());
+
+        let mut miner_data = get_miner_data(Prefix::Testnet);
+        miner_data.extra_data = extra_data;
+        let result = mining_manager.get_block_template(consensus.as_ref(), &miner_data);
+        assert!(result.is_ok(), "a block template with a tagged coinbase should be built");
+        let payload = &result.unwrap().block.transactions[0].payload;
+        assert!(payload.ends_with(&miner_data.extra_data), "the coinbase payload should end with the tagged extra data");
+
+        let free_len = config.maximum_coinbase_payload_length - miner_data.payload_len() + miner_data.extra_data.len();
+        miner_data.extra_data = vec![0; free_len];
+        assert!(mining_manager.get_block_template(consensus.as_ref(), &miner_data).is_ok());
+        miner_data.extra_data.push(0);
+        let result = mining_manager.get_block_template(consensus.as_ref(), &miner_data);
+        assert!(
+            matches!(result, Err(MiningManagerError::CoinbasePayloadTooLong(len, max)) if len == max + 1),
+            "miner data exceeding the maximum coinbase payload length should be rejected"
+        );
+    }
+
+    // test_chain_limits verifies that transactions extending unconfirmed chains beyond the configured ancestor count,
+    // descendant count or chain mass are rejected.
+    #[test]
+    fn test_chain_limits() {
+        struct Test {
+            name: &'static str,
+            maximum_ancestor_count: u64,
+            maximum_descendant_count: u64,
+            maximum_chain_mass: u64,
+            accepted: usize,
+        }
+
+        // Each transaction of the chain has the same mass
+        let funding_tx = create_transaction_without_input(vec![500 * SOMPI_PER_KASPA]);
+        let chain = (0..4).fold(vec![], |mut chain: Vec<Transaction>, _| {
+            let tx = create_transaction(chain.last().unwrap_or(&funding_tx), DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+            chain.push(tx);
+            chain
+        });
+        let mass = transaction_estimated_serialized_size(&chain[0]);
+
+        let tests = [
+            Test {
+                name: "no limit reached",
+                maximum_ancestor_count: 3,
+                maximum_descendant_count: 3,
+                maximum_chain_mass: 4 * mass,
+                accepted: 4,
+            },
+            Test {
+                name: "ancestor count",
+                maximum_ancestor_count: 2,
+                maximum_descendant_count: 3,
+                maximum_chain_mass: 4 * mass,
+                accepted: 3,
+            },
+            Test {
+                name: "descendant count",
+                maximum_ancestor_count: 3,
+                maximum_descendant_count: 1,
+                maximum_chain_mass: 4 * mass,
+                accepted: 2,
+            },
+            Test {
+                name: "chain mass",
+                maximum_ancestor_count: 3,
+                maximum_descendant_count: 3,
+                maximum_chain_mass: 3 * mass - 1,
+                accepted: 2,
+            },
+        ];
+
+        for test in tests {
+            let consensus = Arc::new(ConsensusMock::new());
+            consensus.add_transaction(funding_tx.clone(), 1);
+            let mut config = Config::build_default(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS);
+            config.maximum_ancestor_count = test.maximum_ancestor_count;
+            config.maximum_descendant_count = test.maximum_descendant_count;
+            config.maximum_chain_mass = test.maximum_chain_mass;
+            let mining_manager = MiningManager::with_config(config, None, Arc::new(MiningCounters::default()));
+
+            // The transactions following the first rejected one are orphans, so they are not inserted
+            for (i, tx) in chain.iter().enumerate().take(test.accepted + 1) {
+                let result =
+                    mining_manager.validate_and_insert_transaction(consensus.as_ref(), tx.clone(), Priority::Low, Orphan::Forbidden);
+                if i < test.accepted {
+                    assert!(result.is_ok(), "test '{}': transaction {} should be accepted: {:?}", test.name, i, result);
+                } else {
+                    assert!(
+                        matches!(
+                            result,
+                            Err(MiningManagerError::MempoolError(
+                                RuleError::RejectTooManyAncestors(..)
+                                    | RuleError::RejectTooManyDescendants(..)
+                                    | RuleError::RejectChainMass(..)
+                            ))
+                        ),
+                        "test '{}': transaction {} should be rejected: {:?}",
+                        test.name,
+                        i,
+                        result
+                    );
+                }
+            }
+        }
+    }
+
+    // test_default_chain_limits verifies that the default config accepts a long unconfirmed chain, as built by a wallet
+    // compounding its UTXOs.
+    #[test]
+    fn test_default_chain_limits() {
+        const CHAIN_LENGTH: usize = 200;
+        let funding_tx = create_transaction_without_input(vec![500 * SOMPI_PER_KASPA]);
+        let consensus = Arc::new(ConsensusMock::new());
+        consensus.add_transaction(funding_tx.clone(), 1);
+        let config = Config::build_default(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS);
+        let mining_manager = MiningManager::with_config(config, None, Arc::new(MiningCounters::default()));
+
+        let mut parent = funding_tx;
+        for i in 0..CHAIN_LENGTH {
+            let tx = create_transaction(&parent, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+            let result =
+                mining_manager.validate_and_insert_transaction(consensus.as_ref(), tx.clone(), Priority::Low, Orphan::Forbidden);
+            assert!(result.is_ok(), "transaction {} of the chain should be accepted: {:?}", i, result);
+            parent = tx;
+        }
+        assert_eq!(mining_manager.transaction_count(TransactionQuery::TransactionsOnly), CHAIN_LENGTH);
+    }
+
     // test_modify_block_template verifies that modifying a block template changes coinbase data correctly.
     #[test]
     fn test_modify_block_template() {
@@ -1,4 +1,5 @@
 use crate::mempool::{
+    config::RelayPolicy,
     errors::{NonStandardError, NonStandardResult},
     Mempool,
 };
@@ -7,7 +8,7 @@ use kaspa_consensus_core::{
     mass,
     tx::{MutableTransaction, PopulatedTransaction, TransactionOutput},
 };
-use kaspa_txscript::{get_sig_op_count, is_unspendable, script_class::ScriptClass};
+use kaspa_txscript::{get_sig_op_count, is_unspendable, policy::check_input_scripts_policy, script_class::ScriptClass};
 
 /// MAX_STANDARD_P2SH_SIG_OPS is the maximum number of signature operations
 /// that are considered standard in a pay-to-script-hash script.
@@ -111,6 +112,8 @@ impl Mempool {
     /// if the cost to the network to spend coins is more than 1/3 of the minimum
     /// transaction relay fee, it is considered dust.
     ///
+    /// Outputs with a value below the configured minimum output value are dust as well.
+    ///
     /// It is exposed by [MiningManager] for use by transaction generators and wallets.
     pub(crate) fn is_transaction_output_dust(&self, transaction_output: &TransactionOutput) -> bool {
         // Unspendable outputs are considered dust.
@@ -118,6 +121,10 @@ impl Mempool {
             return true;
         }
 
+        if transaction_output.value < self.config.minimum_output_value {
+            return true;
+        }
+
         // The total serialized size consists of the output and the associated
         // input script to redeem it. Since there is no input script
         // to redeem it yet, use the minimum size of a typical input script.
@@ -161,11 +168,30 @@ impl Mempool {
         }
     }
 
+    /// relay_policy returns the policy enforced on transactions entering the mempool, along with
+    /// the resulting dust threshold of pay-to-pubkey outputs, so that wallets can adapt to it.
+    pub(crate) fn relay_policy(&self) -> RelayPolicy {
+        // Same computation as in is_transaction_output_dust, with a 34 bytes pay-to-pubkey script
+        // and the 148 bytes of the input redeeming it, solved for the lowest non-dust value
+        let total_serialized_size = (8 + 2 + 8 + 34 + 148) as u128;
+        let fee_dust_threshold = (3 * total_serialized_size * self.config.minimum_relay_transaction_fee as u128).div_ceil(1000);
+        RelayPolicy {
+            minimum_relay_transaction_fee: self.config.minimum_relay_transaction_fee,
+            minimum_output_value: self.config.minimum_output_value,
+            pay_to_pubkey_dust_threshold: (fee_dust_threshold.min(u64::MAX as u128) as u64).max(self.config.minimum_output_value),
+            accept_non_standard: self.config.accept_non_standard,
+            maximum_ancestor_count: self.config.maximum_ancestor_count,
+            maximum_descendant_count: self.config.maximum_descendant_count,
+            maximum_chain_mass: self.config.maximum_chain_mass,
+        }
+    }
+
     /// check_transaction_standard_in_context performs a series of checks on a transaction's
     /// inputs to ensure they are "standard". A standard transaction input within the
     /// context of this function is one whose referenced public key script is of a
     /// standard form and, for pay-to-script-hash, does not have more than
-    /// maxStandardP2SHSigOps signature operations.
+    /// maxStandardP2SHSigOps signature operations, and whose executed scripts conform to the
+    /// script policy if enforced by the config.
     /// In addition, makes sure that the transaction's fee is above the minimum for acceptance
     /// into the mempool and relay.
     pub(crate) fn check_transaction_standard_in_context(&self, transaction: &MutableTransaction) -> NonStandardResult<()> {
@@ -191,6 +217,11 @@ impl Mempool {
                 }
             }
 
+            if self.config.enforce_script_policy {
+                check_input_scripts_policy(&input.signature_script, &entry.script_public_key)
+                    .map_err(|err| NonStandardError::RejectScriptPolicy(transaction_id, i, err.to_string()))?;
+            }
+
             let minimum_fee = self.minimum_required_transaction_relay_fee(transaction.calculated_mass.unwrap());
             if transaction.calculated_fee.unwrap() < minimum_fee {
                 return Err(NonStandardError::RejectInsufficientFee(transaction_id, transaction.calculated_fee.unwrap(), minimum_fee));
@@ -383,6 +414,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_relay_policy() {
+        let params: Params = NetworkType::Mainnet.into();
+        let address = Address::new(Prefix::Mainnet, Version::PubKey, &[1u8; 32]);
+        let script_public_key = kaspa_txscript::pay_to_address_script(&address);
+
+        for (minimum_relay_transaction_fee, minimum_output_value, dust_threshold) in
+            [(1000, 0, 600), (3000, 0, 1800), (1000, 5000, 5000), (0, 0, 0)]
+        {
+            let mut config = Config::build_default(params.target_time_per_block, false, params.max_block_mass);
+            config.minimum_relay_transaction_fee = minimum_relay_transaction_fee;
+            config.minimum_output_value = minimum_output_value;
+            let mempool = Mempool::new(Arc::new(config), Arc::new(MiningCounters::default()));
+
+            let policy = mempool.relay_policy();
+            assert_eq!(policy.pay_to_pubkey_dust_threshold, dust_threshold);
+            assert!(!mempool.is_transaction_output_dust(&TransactionOutput::new(dust_threshold, script_public_key.clone())));
+            if dust_threshold > 0 {
+                assert!(mempool.is_transaction_output_dust(&TransactionOutput::new(dust_threshold - 1, script_public_key.clone())));
+            }
+        }
+    }
+
     #[test]
     fn test_check_transaction_standard_in_isolation() {
         // Create some dummy, but otherwise standard, data for transactions.
@@ -20,6 +20,17 @@ pub(crate) const DEFAULT_MAXIMUM_ORPHAN_TRANSACTION_COUNT: u64 = 50;
 /// the mempool and relayed. It is specified in sompi per 1kg (or 1000 grams) of transaction mass.
 pub(crate) const DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE: u64 = 1000;
 
+/// Default limits of the unconfirmed transaction chains in the mempool: the maximal number of in-mempool ancestors and
+/// descendants of a transaction, and the maximal cumulative mass of a transaction and its in-mempool ancestors. The
+/// limits are opt-in (0 meaning no limit) since wallets routinely chain many transactions, e.g. when compounding UTXOs.
+pub(crate) const DEFAULT_MAXIMUM_ANCESTOR_COUNT: u64 = 0;
+pub(crate) const DEFAULT_MAXIMUM_DESCENDANT_COUNT: u64 = 0;
+pub(crate) const DEFAULT_MAXIMUM_CHAIN_MASS: u64 = 0;
+
+/// DEFAULT_MINIMUM_OUTPUT_VALUE is the default absolute dust threshold in sompi. It is disabled by default, leaving
+/// dust to be defined by the minimum relay transaction fee alone.
+pub(crate) const DEFAULT_MINIMUM_OUTPUT_VALUE: u64 = 0;
+
 /// Standard transaction version range might be different from what consensus accepts, therefore
 /// we define separate values in mempool.
 /// However, currently there's exactly one transaction version, so mempool accepts the same version
@@ -27,6 +38,13 @@ pub(crate) const DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE: u64 = 1000;
 pub(crate) const DEFAULT_MINIMUM_STANDARD_TRANSACTION_VERSION: u16 = TX_VERSION;
 pub(crate) const DEFAULT_MAXIMUM_STANDARD_TRANSACTION_VERSION: u16 = TX_VERSION;
 
+/// The maximum coinbase payload length of all networks, see [`kaspa_consensus_core::config::params::Params`]
+pub(crate) const DEFAULT_MAXIMUM_COINBASE_PAYLOAD_LENGTH: usize = 204;
+
+/// MAXIMUM_COINBASE_TAG_LENGTH bounds the pool tag, leaving most of the coinbase payload to the
+/// script public key and the extra data of the miner
+pub const MAXIMUM_COINBASE_TAG_LENGTH: usize = 64;
+
 #[derive(Clone, Debug)]
 pub struct Config {
     pub maximum_transaction_count: u64,
@@ -43,11 +61,25 @@ pub struct Config {
     pub maximum_orphan_transaction_mass: u64,
     pub maximum_orphan_transaction_count: u64,
     pub accept_non_standard: bool,
+    /// Whether the standardness policy of scripts (see [`kaspa_txscript::policy`]) is enforced on top of the
+    /// other standardness checks. Has no effect if non-standard transactions are accepted
+    pub enforce_script_policy: bool,
     pub maximum_mass_per_block: u64,
     pub minimum_relay_transaction_fee: u64,
+    /// Outputs with a lower value (in sompi) are considered dust, on top of the dust defined by the minimum relay fee
+    pub minimum_output_value: u64,
     pub minimum_standard_transaction_version: u16,
     pub maximum_standard_transaction_version: u16,
     pub block_spam_txs: bool,
+    /// Maximal number of unconfirmed ancestors in the mempool of a transaction entering it (0 for no limit)
+    pub maximum_ancestor_count: u64,
+    /// Maximal number of unconfirmed descendants in the mempool of any transaction (0 for no limit)
+    pub maximum_descendant_count: u64,
+    /// Maximal cumulative mass of a transaction entering the mempool and its unconfirmed ancestors (0 for no limit)
+    pub maximum_chain_mass: u64,
+    /// A tag inserted into the extra data of the coinbase payload of all block templates, identifying the pool
+    pub coinbase_tag: Vec<u8>,
+    pub maximum_coinbase_payload_length: usize,
 }
 
 impl Config {
@@ -88,11 +120,18 @@ impl Config {
             maximum_orphan_transaction_mass,
             maximum_orphan_transaction_count,
             accept_non_standard,
+            enforce_script_policy: true,
             maximum_mass_per_block,
             minimum_relay_transaction_fee,
+            minimum_output_value: DEFAULT_MINIMUM_OUTPUT_VALUE,
             minimum_standard_transaction_version,
             maximum_standard_transaction_version,
             block_spam_txs,
+            maximum_ancestor_count: DEFAULT_MAXIMUM_ANCESTOR_COUNT,
+            maximum_descendant_count: DEFAULT_MAXIMUM_DESCENDANT_COUNT,
+            maximum_chain_mass: DEFAULT_MAXIMUM_CHAIN_MASS,
+            coinbase_tag: Vec::new(),
+            maximum_coinbase_payload_length: DEFAULT_MAXIMUM_COINBASE_PAYLOAD_LENGTH,
         }
     }
 
@@ -117,11 +156,18 @@ impl Config {
             maximum_orphan_transaction_mass: DEFAULT_MAXIMUM_ORPHAN_TRANSACTION_MASS,
             maximum_orphan_transaction_count: DEFAULT_MAXIMUM_ORPHAN_TRANSACTION_COUNT,
             accept_non_standard: relay_non_std_transactions,
+            enforce_script_policy: true,
             maximum_mass_per_block: max_block_mass,
             minimum_relay_transaction_fee: DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE,
+            minimum_output_value: DEFAULT_MINIMUM_OUTPUT_VALUE,
             minimum_standard_transaction_version: DEFAULT_MINIMUM_STANDARD_TRANSACTION_VERSION,
             maximum_standard_transaction_version: DEFAULT_MAXIMUM_STANDARD_TRANSACTION_VERSION,
             block_spam_txs: false,
+            maximum_ancestor_count: DEFAULT_MAXIMUM_ANCESTOR_COUNT,
+            maximum_descendant_count: DEFAULT_MAXIMUM_DESCENDANT_COUNT,
+            maximum_chain_mass: DEFAULT_MAXIMUM_CHAIN_MASS,
+            coinbase_tag: Vec::new(),
+            maximum_coinbase_payload_length: DEFAULT_MAXIMUM_COINBASE_PAYLOAD_LENGTH,
         }
     }
 
@@ -133,6 +179,24 @@ impl Config {
         relay_non_std_transactions: bool,
         max_block_mass: u64,
     ) -> Self {
-        Self { block_spam_txs, ..Self::build_default(target_milliseconds_per_block, relay_non_std_transactions, max_block_mass) }
+        let mut config = Self::build_default(target_milliseconds_per_block, relay_non_std_transactions, max_block_mass);
+        config.block_spam_txs = block_spam_txs;
+        config
     }
 }
+
+/// The node policy enforced on transactions entering the mempool, which is not part of consensus
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RelayPolicy {
+    /// Minimum fee in sompi per kilogram (1000 grams) of transaction mass
+    pub minimum_relay_transaction_fee: u64,
+    /// Outputs with a lower value (in sompi) are considered dust
+    pub minimum_output_value: u64,
+    /// The minimal value (in sompi) of a pay-to-pubkey output not considered dust under both dust rules
+    pub pay_to_pubkey_dust_threshold: u64,
+    pub accept_non_standard: bool,
+    /// Limits of the unconfirmed transaction chains, see [`Config`]
+    pub maximum_ancestor_count: u64,
+    pub maximum_descendant_count: u64,
+    pub maximum_chain_mass: u64,
+}
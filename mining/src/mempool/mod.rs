@@ -1,8 +1,10 @@
 use crate::{
     model::{
         candidate_tx::CandidateTransaction,
+        fee_rate_histogram::FeeRateHistogram,
         owner_txs::{GroupedOwnerTransactions, ScriptPublicKeySet},
         tx_query::TransactionQuery,
+        tx_removal::MempoolRemovalSender,
     },
     MiningCounters,
 };
@@ -23,6 +25,7 @@ pub(crate) mod handle_new_block_transactions;
 pub(crate) mod model;
 pub(crate) mod populate_entries_and_try_validate;
 pub(crate) mod remove_transaction;
+pub(crate) mod snapshot;
 pub(crate) mod validate_and_insert_transaction;
 
 /// Mempool contains transactions intended to be inserted into a block and mined.
@@ -47,6 +50,7 @@ pub(crate) struct Mempool {
     orphan_pool: OrphanPool,
     accepted_transactions: AcceptedTransactions,
     counters: Arc<MiningCounters>,
+    removal_sender: Option<MempoolRemovalSender>,
 }
 
 impl Mempool {
@@ -54,7 +58,11 @@ impl Mempool {
         let transaction_pool = TransactionsPool::new(config.clone());
         let orphan_pool = OrphanPool::new(config.clone());
         let accepted_transactions = AcceptedTransactions::new(config.clone());
-        Self { config, transaction_pool, orphan_pool, accepted_transactions, counters }
+        Self { config, transaction_pool, orphan_pool, accepted_transactions, counters, removal_sender: None }
+    }
+
+    pub(crate) fn set_removal_sender(&mut self, sender: MempoolRemovalSender) {
+        self.removal_sender = Some(sender);
     }
 
     pub(crate) fn get_transaction(&self, transaction_id: &TransactionId, query: TransactionQuery) -> Option<MutableTransaction> {
@@ -111,6 +119,18 @@ impl Mempool {
         count
     }
 
+    /// Aggregates the transaction pool by fee rate, orphans being excluded since their fee is not known yet
+    pub(crate) fn fee_rate_histogram(&self, bucket_bounds: Vec<f64>) -> FeeRateHistogram {
+        let mut histogram = FeeRateHistogram::new(bucket_bounds);
+        self.transaction_pool.fill_fee_rate_histogram(&mut histogram);
+        histogram
+    }
+
+    /// The epoch of the transaction pool, incremented on every modification
+    pub(crate) fn epoch(&self) -> u64 {
+        self.transaction_pool.epoch()
+    }
+
     pub(crate) fn block_candidate_transactions(&self) -> Vec<CandidateTransaction> {
         let _sw = Stopwatch::<10>::with_threshold("block_candidate_transactions op");
         self.transaction_pool.all_ready_transactions()
@@ -134,6 +154,13 @@ impl Mempool {
         self.accepted_transactions.has(transaction_id)
     }
 
+    /// Forgets that the given transactions were accepted, allowing them back into the mempool
+    pub(crate) fn remove_accepted_transactions(&mut self, transactions: &[TransactionId]) {
+        transactions.iter().for_each(|transaction_id| {
+            self.accepted_transactions.remove(transaction_id);
+        });
+    }
+
     pub(crate) fn unaccepted_transactions(&self, transactions: Vec<TransactionId>) -> Vec<TransactionId> {
         self.accepted_transactions.unaccepted(&mut transactions.into_iter())
     }
@@ -144,6 +171,23 @@ impl Mempool {
             .filter(|transaction_id| !(self.transaction_pool.has(transaction_id) || self.orphan_pool.has(transaction_id)));
         self.accepted_transactions.unaccepted(&mut not_in_pools_txs)
     }
+
+    pub(crate) fn size_limits(&self) -> (u64, u64) {
+        (self.config.maximum_transaction_count, self.config.maximum_orphan_transaction_count)
+    }
+
+    /// Updates the maximal numbers of transactions in the transaction and orphan pools. Lowered limits
+    /// are enforced by evicting low-priority transactions upon the next insertion to the respective pool
+    pub(crate) fn set_size_limits(&mut self, maximum_transaction_count: u64, maximum_orphan_transaction_count: u64) {
+        let mut config = (*self.config).clone();
+        config.maximum_transaction_count = maximum_transaction_count;
+        config.maximum_orphan_transaction_count = maximum_orphan_transaction_count;
+        let config = Arc::new(config);
+        self.transaction_pool.set_config(config.clone());
+        self.orphan_pool.set_config(config.clone());
+        self.accepted_transactions.set_config(config.clone());
+        self.config = config;
+    }
 }
 
 pub mod tx {
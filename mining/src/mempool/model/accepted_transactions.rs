@@ -17,6 +17,10 @@ pub(crate) struct AcceptedTransactions {
 }
 
 impl AcceptedTransactions {
+    pub(crate) fn set_config(&mut self, config: Arc<Config>) {
+        self.config = config;
+    }
+
     pub(crate) fn new(config: Arc<Config>) -> Self {
         Self { config, transactions: Default::default(), last_expire_scan_daa_score: 0, last_expire_scan_time: unix_now() }
     }
@@ -41,6 +41,10 @@ pub(crate) struct OrphanPool {
 }
 
 impl OrphanPool {
+    pub(crate) fn set_config(&mut self, config: Arc<Config>) {
+        self.config = config;
+    }
+
     pub(crate) fn new(config: Arc<Config>) -> Self {
         Self {
             config,
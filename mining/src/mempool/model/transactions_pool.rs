@@ -10,7 +10,10 @@ use crate::{
         },
         tx::Priority,
     },
-    model::{candidate_tx::CandidateTransaction, topological_index::TopologicalIndex},
+    model::{
+        candidate_tx::CandidateTransaction, fee_rate_histogram::FeeRateHistogram, topological_index::TopologicalIndex,
+        TransactionIdSet,
+    },
 };
 use kaspa_consensus_core::{
     tx::TransactionId,
@@ -18,7 +21,7 @@ use kaspa_consensus_core::{
 };
 use kaspa_core::{time::unix_now, trace, warn};
 use std::{
-    collections::{hash_map::Keys, hash_set::Iter, HashSet},
+    collections::{hash_map::Keys, hash_set::Iter, HashSet, VecDeque},
     sync::Arc,
 };
 
@@ -62,9 +65,18 @@ pub(crate) struct TransactionsPool {
 
     /// Store of UTXOs
     utxo_set: MempoolUtxoSet,
+
+    /// Incremented on every modification of the pool, identifying its successive states (see [`crate::mempool::snapshot::SnapshotCache`])
+    epoch: u64,
 }
 
 impl TransactionsPool {
+    pub(crate) fn set_config(&mut self, config: Arc<Config>) {
+        self.config = config;
+        // The limits of the config shape the views of the pool
+        self.epoch += 1;
+    }
+
     pub(crate) fn new(config: Arc<Config>) -> Self {
         Self {
             config,
@@ -75,6 +87,7 @@ impl TransactionsPool {
             last_expire_scan_daa_score: 0,
             last_expire_scan_time: unix_now(),
             utxo_set: MempoolUtxoSet::new(),
+            epoch: 0,
         }
     }
 
@@ -114,12 +127,15 @@ impl TransactionsPool {
 
         self.utxo_set.add_transaction(&transaction.mtx);
         self.all_transactions.insert(id, transaction);
+        self.epoch += 1;
         trace!("Added transaction {}", id);
         Ok(())
     }
 
     /// Fully removes the transaction from all relational sets, as well as from the UTXO set
     pub(crate) fn remove_transaction(&mut self, transaction_id: &TransactionId) -> RuleResult<MempoolTransaction> {
+        self.epoch += 1;
+
         // Remove all bijective parent/chained relations
         if let Some(parents) = self.parent_transactions.get(transaction_id) {
             for parent in parents.iter() {
@@ -157,6 +173,10 @@ impl TransactionsPool {
         Ok(removed_tx)
     }
 
+    pub(crate) fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
     pub(crate) fn ready_transaction_count(&self) -> usize {
         self.ready_transactions.len()
     }
@@ -237,6 +257,67 @@ impl TransactionsPool {
         Ok(transactions_to_remove.iter().map(|x| x.id()).collect())
     }
 
+    /// Checks that adding `transaction` to the pool keeps the unconfirmed chains it extends within the configured
+    /// limits: its number of ancestors in the pool, the cumulative mass of the transaction and these ancestors, and
+    /// the number of descendants of each of these ancestors. A zero limit is disabled.
+    ///
+    /// The traversals stop as soon as a limit is exceeded, bounding the cost of the check.
+    pub(crate) fn check_chain_limits(&self, transaction: &MutableTransaction) -> RuleResult<()> {
+        let Config { maximum_ancestor_count, maximum_descendant_count, maximum_chain_mass, .. } = *self.config;
+        if maximum_ancestor_count == 0 && maximum_descendant_count == 0 && maximum_chain_mass == 0 {
+            return Ok(());
+        }
+        let transaction_id = transaction.id();
+        let mut ancestors = TransactionIdSet::new();
+        let mut queue = self.get_parent_transaction_ids_in_pool(transaction).into_iter().collect::<VecDeque<_>>();
+        let mut chain_mass = transaction.calculated_mass.unwrap();
+        while let Some(ancestor_id) = queue.pop_front() {
+            if !ancestors.insert(ancestor_id) {
+                continue;
+            }
+            if maximum_ancestor_count > 0 && ancestors.len() as u64 > maximum_ancestor_count {
+                return Err(RuleError::RejectTooManyAncestors(transaction_id, maximum_ancestor_count));
+            }
+            chain_mass += self.all_transactions.get(&ancestor_id).unwrap().mtx.calculated_mass.unwrap();
+            if maximum_chain_mass > 0 && chain_mass > maximum_chain_mass {
+                return Err(RuleError::RejectChainMass(transaction_id, chain_mass, maximum_chain_mass));
+            }
+            if let Some(parents) = self.parent_transactions.get(&ancestor_id) {
+                queue.extend(parents.iter().copied());
+            }
+        }
+
+        if maximum_descendant_count > 0 {
+            for ancestor_id in ancestors.iter() {
+                if self.count_descendants(ancestor_id, maximum_descendant_count) >= maximum_descendant_count {
+                    return Err(RuleError::RejectTooManyDescendants(transaction_id, *ancestor_id, maximum_descendant_count));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts the descendants in the pool of the transaction identified by `transaction_id`, stopping at `limit`
+    fn count_descendants(&self, transaction_id: &TransactionId, limit: u64) -> u64 {
+        let mut visited = TransactionIdSet::new();
+        let mut queue = VecDeque::from([*transaction_id]);
+        while let Some(id) = queue.pop_front() {
+            for redeemer_id in self.chained_transactions.get(&id).into_iter().flatten() {
+                if visited.insert(*redeemer_id) {
+                    if visited.len() as u64 >= limit {
+                        return limit;
+                    }
+                    queue.push_back(*redeemer_id);
+                }
+            }
+        }
+        visited.len() as u64
+    }
+
+    pub(crate) fn fill_fee_rate_histogram(&self, histogram: &mut FeeRateHistogram) {
+        self.all_transactions.values().for_each(|tx| histogram.add(tx.fee_rate(), tx.mtx.calculated_mass.unwrap()));
+    }
+
     pub(crate) fn all_transaction_ids_with_priority(&self, priority: Priority) -> Vec<TransactionId> {
         self.all().values().filter_map(|x| if x.priority == priority { Some(x.id()) } else { None }).collect()
     }
@@ -302,6 +383,8 @@ impl Pool for TransactionsPool {
     }
 
     fn get_mut(&mut self, transaction_id: &TransactionId) -> Option<&mut MempoolTransaction> {
+        // The transaction may be modified by the caller
+        self.epoch += 1;
         self.all_transactions.get_mut(transaction_id)
     }
 }
@@ -1,4 +1,4 @@
-use crate::mempool::tx::Priority;
+use crate::{mempool::tx::Priority, model::tx_removal::MempoolRemovalReason};
 use kaspa_consensus_core::{tx::MutableTransaction, tx::TransactionId};
 use std::{
     cmp::Ordering,
@@ -80,6 +80,21 @@ impl TxRemovalReason {
     pub(crate) fn verbose(&self) -> bool {
         !matches!(self, TxRemovalReason::Muted)
     }
+
+    /// The reason reported to the subscribers of mempool removals, if the removal drops the transaction
+    pub(crate) fn removal_reason(&self) -> Option<MempoolRemovalReason> {
+        match self {
+            TxRemovalReason::MakingRoom => Some(MempoolRemovalReason::Evicted),
+            TxRemovalReason::Expired => Some(MempoolRemovalReason::Expired),
+            TxRemovalReason::DoubleSpend | TxRemovalReason::RevalidationWithMissingOutpoints => {
+                Some(MempoolRemovalReason::ConflictWithBlock)
+            }
+            TxRemovalReason::Muted
+            | TxRemovalReason::Accepted
+            | TxRemovalReason::Unorphaned
+            | TxRemovalReason::InvalidInBlockTemplate => None,
+        }
+    }
 }
 
 impl Display for TxRemovalReason {
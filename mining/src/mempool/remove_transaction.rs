@@ -1,10 +1,13 @@
-use crate::mempool::{
-    errors::RuleResult,
-    model::{pool::Pool, tx::TxRemovalReason},
-    Mempool,
+use crate::{
+    mempool::{
+        errors::RuleResult,
+        model::{pool::Pool, tx::TxRemovalReason},
+        Mempool,
+    },
+    model::tx_removal::MempoolTransactionRemoval,
 };
 use kaspa_consensus_core::tx::TransactionId;
-use kaspa_core::{debug, warn};
+use kaspa_core::{debug, trace, warn};
 use kaspa_utils::iter::IterExtensions;
 
 impl Mempool {
@@ -16,7 +19,9 @@ impl Mempool {
         extra_info: &str,
     ) -> RuleResult<()> {
         if self.orphan_pool.has(transaction_id) {
-            return self.orphan_pool.remove_orphan(transaction_id, true, reason, extra_info).map(|_| ());
+            let removed_orphans = self.orphan_pool.remove_orphan(transaction_id, true, reason, extra_info)?;
+            self.notify_removed_transactions(removed_orphans.iter().map(|x| x.id()), &reason);
+            return Ok(());
         }
 
         if !self.transaction_pool.has(transaction_id) {
@@ -38,6 +43,7 @@ impl Mempool {
             removed_orphans.extend(txs.into_iter().map(|x| x.id()));
         }
         removed_transactions.extend(removed_orphans);
+        self.notify_removed_transactions(removed_transactions.iter().copied(), &reason);
 
         match reason {
             TxRemovalReason::Muted => {}
@@ -67,4 +73,16 @@ impl Mempool {
 
         Ok(())
     }
+
+    /// Reports the transactions dropped from the mempool to the removal subscriber, if any
+    fn notify_removed_transactions(&self, transaction_ids: impl Iterator<Item = TransactionId>, reason: &TxRemovalReason) {
+        let (Some(sender), Some(reason)) = (self.removal_sender.as_ref(), reason.removal_reason()) else { return };
+        for transaction_id in transaction_ids {
+            // The channel is unbounded so sending only fails once the subscriber is gone
+            if sender.try_send(MempoolTransactionRemoval::new(transaction_id, reason)).is_err() {
+                trace!("The mempool removal subscriber is closed");
+                return;
+            }
+        }
+    }
 }
@@ -0,0 +1,58 @@
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// Caches an immutable view of the transaction pool tagged with the pool epoch at which it was taken.
+///
+/// Every modification of the transaction pool increments its epoch, so a view is valid for as long as its epoch
+/// is the current one. Read-heavy operations (block template building, mempool queries) share the cached view
+/// instead of each cloning the pool while holding the mempool lock, and iterate it without holding any lock,
+/// which keeps the contention with transaction admission low.
+pub(crate) struct SnapshotCache<T> {
+    cached: Mutex<Option<(u64, Arc<T>)>>,
+}
+
+impl<T> SnapshotCache<T> {
+    pub(crate) fn new() -> Self {
+        Self { cached: Mutex::new(None) }
+    }
+
+    /// Returns the cached view if taken at `current_epoch`, otherwise builds, caches and returns a new one.
+    ///
+    /// `build` returns the view along with the pool epoch at which its building started. A view built while the
+    /// pool is being modified is thus tagged with an outdated epoch and gets rebuilt by the next call.
+    ///
+    /// Concurrent callers are serialized so that a single one builds a new view which is then shared by the others.
+    /// Note that `current_epoch` and `build` are expected to lock the mempool by themselves, never the caller, so that
+    /// no mempool lock is ever waited for while holding the cache lock.
+    pub(crate) fn get_or_build(&self, current_epoch: impl FnOnce() -> u64, build: impl FnOnce() -> (u64, T)) -> Arc<T> {
+        let mut cached = self.cached.lock();
+        let current_epoch = current_epoch();
+        if let Some((epoch, snapshot)) = cached.as_ref() {
+            if *epoch == current_epoch {
+                return snapshot.clone();
+            }
+        }
+        let (epoch, snapshot) = build();
+        let snapshot = Arc::new(snapshot);
+        *cached = Some((epoch, snapshot.clone()));
+        snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_cache() {
+        let cache = SnapshotCache::new();
+        assert_eq!(*cache.get_or_build(|| 1, || (1, vec![1])), vec![1]);
+        // The epoch is unchanged so the cached view is returned
+        assert_eq!(*cache.get_or_build(|| 1, || (1, vec![2])), vec![1]);
+        // The pool was modified while building, so the view is tagged with the former epoch
+        assert_eq!(*cache.get_or_build(|| 2, || (2, vec![3])), vec![3]);
+        assert_eq!(*cache.get_or_build(|| 3, || (2, vec![4])), vec![4]);
+        assert_eq!(*cache.get_or_build(|| 3, || (3, vec![5])), vec![5]);
+        assert_eq!(*cache.get_or_build(|| 3, || (3, vec![6])), vec![5]);
+    }
+}
@@ -70,6 +70,9 @@ impl Mempool {
 
         self.validate_transaction_in_context(&transaction)?;
 
+        // Reject transactions extending unconfirmed chains beyond the configured limits
+        self.transaction_pool.check_chain_limits(&transaction)?;
+
         // Before adding the transaction, check if there is room in the pool
         self.transaction_pool.limit_transaction_count(1, &transaction)?.iter().try_for_each(|x| {
             self.remove_transaction(x, true, TxRemovalReason::MakingRoom, format!(" for {}", transaction_id).as_str())
@@ -81,6 +84,20 @@ impl Mempool {
         Ok(Some(accepted_transaction))
     }
 
+    /// Runs the checks of `post_validate_and_insert_transaction` following a successful pre-validation, without
+    /// inserting the transaction. A transaction which would be accepted as an orphan fails with its validation error.
+    pub(crate) fn post_validate_transaction(
+        &self,
+        validation_result: RuleResult<()>,
+        transaction: &MutableTransaction,
+    ) -> RuleResult<()> {
+        validation_result?;
+        self.validate_transaction_in_context(transaction)?;
+        self.transaction_pool.check_chain_limits(transaction)?;
+        self.transaction_pool.limit_transaction_count(1, transaction)?;
+        Ok(())
+    }
+
     /// Validates that the transaction wasn't already accepted into the DAG
     fn validate_transaction_unacceptance(&self, transaction: &MutableTransaction) -> RuleResult<()> {
         // Reject if the transaction is registered as an accepted transaction
@@ -0,0 +1,82 @@
+/// Bucket bounds (in sompi per gram of mass) used when none are provided
+pub const DEFAULT_FEE_RATE_BUCKET_BOUNDS: [f64; 10] = [1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0];
+
+/// Maximum number of bounds a histogram may be requested with
+pub const MAX_FEE_RATE_BUCKET_BOUNDS: usize = 256;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeRateBucket {
+    pub transaction_count: u64,
+    pub total_mass: u64,
+}
+
+/// Aggregates transactions by fee rate (sompi per gram of mass). Given strictly ascending `bounds`, bucket `i`
+/// holds the transactions with a fee rate in `[bounds[i - 1], bounds[i])`, the first bucket starting at 0 and
+/// the last one being unbounded, so that there is one more bucket than there are bounds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeRateHistogram {
+    bounds: Vec<f64>,
+    buckets: Vec<FeeRateBucket>,
+}
+
+impl FeeRateHistogram {
+    pub fn new(bounds: Vec<f64>) -> Self {
+        debug_assert!(bounds.windows(2).all(|pair| pair[0] < pair[1]));
+        let buckets = vec![FeeRateBucket::default(); bounds.len() + 1];
+        Self { bounds, buckets }
+    }
+
+    pub fn add(&mut self, fee_rate: f64, mass: u64) {
+        let bucket = &mut self.buckets[self.bounds.partition_point(|bound| *bound <= fee_rate)];
+        bucket.transaction_count += 1;
+        bucket.total_mass += mass;
+    }
+
+    /// Iterates the buckets in ascending fee rate order along with their lower bound
+    pub fn buckets(&self) -> impl Iterator<Item = (f64, FeeRateBucket)> + '_ {
+        std::iter::once(0.0).chain(self.bounds.iter().copied()).zip(self.buckets.iter().copied())
+    }
+}
+
+/// Checks that `bounds` are positive, finite and strictly ascending, and that there are no more than [`MAX_FEE_RATE_BUCKET_BOUNDS`]
+pub fn are_valid_fee_rate_bucket_bounds(bounds: &[f64]) -> bool {
+    bounds.len() <= MAX_FEE_RATE_BUCKET_BOUNDS
+        && bounds.iter().all(|bound| bound.is_finite() && *bound > 0.0)
+        && bounds.windows(2).all(|pair| pair[0] < pair[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_rate_histogram() {
+        let mut histogram = FeeRateHistogram::new(vec![1.0, 10.0]);
+        histogram.add(0.5, 1000);
+        histogram.add(1.0, 2000);
+        histogram.add(9.99, 3000);
+        histogram.add(250.0, 4000);
+
+        let buckets = histogram.buckets().collect::<Vec<_>>();
+        assert_eq!(
+            buckets,
+            vec![
+                (0.0, FeeRateBucket { transaction_count: 1, total_mass: 1000 }),
+                (1.0, FeeRateBucket { transaction_count: 2, total_mass: 5000 }),
+                (10.0, FeeRateBucket { transaction_count: 1, total_mass: 4000 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fee_rate_bucket_bounds_validation() {
+        assert!(are_valid_fee_rate_bucket_bounds(&[]));
+        assert!(are_valid_fee_rate_bucket_bounds(&DEFAULT_FEE_RATE_BUCKET_BOUNDS));
+        assert!(!are_valid_fee_rate_bucket_bounds(&[0.0, 1.0]));
+        assert!(!are_valid_fee_rate_bucket_bounds(&[2.0, 1.0]));
+        assert!(!are_valid_fee_rate_bucket_bounds(&[1.0, 1.0]));
+        assert!(!are_valid_fee_rate_bucket_bounds(&[1.0, f64::INFINITY]));
+        assert!(!are_valid_fee_rate_bucket_bounds(&[f64::NAN]));
+        assert!(!are_valid_fee_rate_bucket_bounds(&vec![1.0; MAX_FEE_RATE_BUCKET_BOUNDS + 1]));
+    }
+}
@@ -2,10 +2,13 @@ use kaspa_consensus_core::tx::TransactionId;
 use std::collections::HashSet;
 
 pub(crate) mod candidate_tx;
+pub mod fee_rate_histogram;
 pub mod owner_txs;
 pub mod topological_index;
 pub mod topological_sort;
+pub mod tx_check;
 pub mod tx_query;
+pub mod tx_removal;
 
 /// A set of unique transaction ids
 pub type TransactionIdSet = HashSet<TransactionId>;
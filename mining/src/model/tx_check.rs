@@ -0,0 +1,19 @@
+use crate::errors::MiningManagerResult;
+
+/// The outcome of running the mempool validation of a transaction without inserting it
+#[derive(Debug)]
+pub struct TransactionCheck {
+    /// The mass of the transaction
+    pub mass: u64,
+    /// The fee paid by the transaction, known once its UTXO entries are populated
+    pub fee: Option<u64>,
+    /// The validation result, an error being the first rule the transaction violates
+    pub result: MiningManagerResult<()>,
+}
+
+impl TransactionCheck {
+    /// The fee paid per gram of mass
+    pub fn fee_rate(&self) -> Option<f64> {
+        self.fee.filter(|_| self.mass > 0).map(|fee| fee as f64 / self.mass as f64)
+    }
+}
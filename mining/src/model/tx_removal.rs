@@ -0,0 +1,44 @@
+use async_channel::Sender;
+use kaspa_consensus_core::tx::TransactionId;
+use std::fmt::{Display, Formatter};
+
+/// Reason of a transaction leaving the mempool without being accepted by a block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MempoolRemovalReason {
+    /// Evicted to make room for transactions paying a higher fee rate
+    Evicted,
+    /// Replaced by a conflicting transaction paying a higher fee
+    Replaced,
+    /// Expired before being included in a block
+    Expired,
+    /// Double spends an outpoint spent by a transaction accepted by a block
+    ConflictWithBlock,
+}
+
+impl Display for MempoolRemovalReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            MempoolRemovalReason::Evicted => "evicted",
+            MempoolRemovalReason::Replaced => "replaced",
+            MempoolRemovalReason::Expired => "expired",
+            MempoolRemovalReason::ConflictWithBlock => "conflict with block",
+        };
+        f.write_str(label)
+    }
+}
+
+/// A transaction dropped from the mempool
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MempoolTransactionRemoval {
+    pub transaction_id: TransactionId,
+    pub reason: MempoolRemovalReason,
+}
+
+impl MempoolTransactionRemoval {
+    pub fn new(transaction_id: TransactionId, reason: MempoolRemovalReason) -> Self {
+        Self { transaction_id, reason }
+    }
+}
+
+/// Channel sender of the transactions dropped from the mempool
+pub type MempoolRemovalSender = Sender<MempoolTransactionRemoval>;
@@ -1,13 +1,17 @@
 extern crate derive_more;
 use super::{
-    connection::Connection, error::Result, events::EventArray, listener::ListenerId, notification::Notification,
-    subscription::DynSubscription,
+    connection::Connection,
+    error::Result,
+    events::{EventArray, EventType, EVENT_TYPE_ARRAY},
+    listener::{ListenerId, ListenerStats},
+    notification::Notification,
+    subscription::{index::UtxosChangedIndex, DynSubscription},
 };
 use async_channel::{Receiver, Sender};
 use core::fmt::Debug;
 use derive_more::Deref;
 use futures::{future::FutureExt, select};
-use kaspa_core::{debug, trace};
+use kaspa_core::{debug, trace, warn};
 use std::{
     collections::HashMap,
     sync::{
@@ -15,10 +19,16 @@ use std::{
         Arc,
     },
 };
-use workflow_core::channel::Channel;
+use workflow_core::{
+    channel::Channel,
+    time::{Duration, Instant},
+};
 
 type ConnectionSet<T> = HashMap<ListenerId, T>;
 
+/// Default duration a listener queue may stay full before the listener gets evicted as a slow consumer
+pub const DEFAULT_OVERFLOW_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Broadcast plan
 #[derive(Deref)]
 struct Plan<C: Connection>(HashMap<DynSubscription, HashMap<C::Encoding, ConnectionSet<C>>>);
@@ -64,6 +74,10 @@ where
         result
     }
 
+    fn contains(&self, id: &ListenerId) -> bool {
+        self.0.values().any(|encoding_set| encoding_set.values().any(|connection_set| connection_set.contains_key(id)))
+    }
+
     // fn len(&self) -> usize {
     //     self.0.values().map(|encodings| encodings.values().map(|connections| connections.len()).count()).count()
     // }
@@ -80,7 +94,7 @@ enum Ctl<C>
 where
     C: Connection,
 {
-    Register(DynSubscription, ListenerId, C),
+    Register(DynSubscription, ListenerId, C, Arc<ListenerStats>),
     Unregister(DynSubscription, ListenerId),
 }
 
@@ -95,6 +109,8 @@ where
     ctl: Channel<Ctl<C>>,
     incoming: Receiver<N>,
     shutdown: Channel<()>,
+    /// Duration a listener queue may stay full before the listener gets evicted
+    overflow_timeout: Duration,
     /// Sync channel, for handling of messages in predictable sequence; exclusively intended for tests.
     _sync: Option<Sender<()>>,
 }
@@ -104,12 +120,13 @@ where
     N: Notification,
     C: Connection<Notification = N>,
 {
-    pub fn new(name: &'static str, incoming: Receiver<N>, _sync: Option<Sender<()>>) -> Self {
+    pub fn new(name: &'static str, incoming: Receiver<N>, overflow_timeout: Duration, _sync: Option<Sender<()>>) -> Self {
         Self {
             name,
             started: Arc::new(AtomicBool::default()),
             ctl: Channel::unbounded(),
             incoming,
+            overflow_timeout,
             _sync,
             shutdown: Channel::oneshot(),
         }
@@ -130,16 +147,35 @@ where
             let mut plan = EventArray::<Plan<C>>::default();
             // Create a store for closed connections to be removed from the plan
             let mut purge: Vec<ListenerId> = Vec::new();
+            // Create a store for slow consumers to be removed from the plans of all event types
+            let mut evict: Vec<ListenerId> = Vec::new();
+            // Stats of the registered listeners
+            let mut stats: HashMap<ListenerId, Arc<ListenerStats>> = HashMap::new();
+            // Time since when the queue of a listener is full
+            let mut full_since: HashMap<ListenerId, Instant> = HashMap::new();
+            // Inverted index of the UtxosChanged subscriptions, rebuilt lazily after any change of their plan
+            let mut utxos_changed_index: Option<UtxosChangedIndex> = None;
             loop {
                 select! {
                     ctl = self.ctl.recv().fuse() => {
                         if let Ok(ctl) = ctl {
                             match ctl {
-                                Ctl::Register(subscription, id, connection) => {
+                                Ctl::Register(subscription, id, connection, listener_stats) => {
+                                    if subscription.event_type() == EventType::UtxosChanged {
+                                        utxos_changed_index = None;
+                                    }
                                     plan[subscription.event_type()].insert(subscription, id, connection);
+                                    stats.insert(id, listener_stats);
                                 },
                                 Ctl::Unregister(subscription, id) => {
+                                    if subscription.event_type() == EventType::UtxosChanged {
+                                        utxos_changed_index = None;
+                                    }
                                     plan[subscription.event_type()].remove(&id);
+                                    if !plan.iter().any(|event_plan| event_plan.contains(&id)) {
+                                        stats.remove(&id);
+                                        full_since.remove(&id);
+                                    }
                                 },
                             }
                         }
@@ -149,14 +185,51 @@ where
                         if let Ok(notification) = notification {
                             // Broadcast the notification...
                             let event = notification.event_type();
-                            for (subscription, encoding_set) in plan[event].iter() {
-                                // ... by subscription scope
-                                if let Some(applied_notification) = notification.apply_subscription(&**subscription) {
+                            // ... by subscription scope, looking up the UtxosChanged subscriptions affected by the changes in the index
+                            let applied_notifications = match event {
+                                EventType::UtxosChanged => {
+                                    utxos_changed_index.get_or_insert_with(|| UtxosChangedIndex::new(plan[event].keys())).apply(&notification)
+                                }
+                                _ => plan[event]
+                                    .keys()
+                                    .filter_map(|subscription| notification.apply_subscription(&**subscription).map(|applied| (subscription.clone(), applied)))
+                                    .collect(),
+                            };
+                            for (subscription, applied_notification) in applied_notifications {
+                                if let Some(encoding_set) = plan[event].get(&subscription) {
                                     for (encoding, connection_set) in encoding_set.iter() {
                                         // ... by message encoding
                                         let message = C::into_message(&applied_notification, encoding);
                                         for (id, connection) in connection_set.iter() {
-                                            // ... to listeners connections
+                                            // ... to listeners connections, skipping the ones with a full queue
+                                            if connection.is_full() {
+                                                if let Some(listener_stats) = stats.get(id) {
+                                                    listener_stats.dropped.fetch_add(1, Ordering::Relaxed);
+                                                }
+                                                let since = *full_since.entry(*id).or_insert_with(|| {
+                                                    warn!("[Broadcaster-{}] the queue of listener {id} is full - dropping its notifications until it catches up, or evicting it after {:?}", self.name, self.overflow_timeout);
+                                                    if let Some(listener_stats) = stats.get(id) {
+                                                        listener_stats.lagging.store(true, Ordering::Relaxed);
+                                                    }
+                                                    Instant::now()
+                                                });
+                                                if since.elapsed() >= self.overflow_timeout && connection.overflow() {
+                                                    warn!("[Broadcaster-{}] the queue of listener {id} stayed full for {:?} - evicting it as a slow consumer", self.name, since.elapsed());
+                                                    if let Some(listener_stats) = stats.get(id) {
+                                                        listener_stats.overflowed.store(true, Ordering::Relaxed);
+                                                    }
+                                                    evict.push(*id);
+                                                } else {
+                                                    trace!("[Broadcaster-{}] dropped notification {notification} for listener {id} because its queue is full", self.name);
+                                                }
+                                                continue;
+                                            }
+                                            if full_since.remove(id).is_some() {
+                                                debug!("[Broadcaster-{}] listener {id} caught up with its notifications", self.name);
+                                                if let Some(listener_stats) = stats.get(id) {
+                                                    listener_stats.lagging.store(false, Ordering::Relaxed);
+                                                }
+                                            }
                                             match connection.send(message.clone()).await {
                                                 Ok(_) => {
                                                     trace!("[Broadcaster-{}] sent notification {notification} to listener {id}", self.name);
@@ -172,8 +245,17 @@ where
                                     }
                                 }
                             }
+                            if (event == EventType::UtxosChanged && !purge.is_empty()) || !evict.is_empty() {
+                                utxos_changed_index = None;
+                            }
                             // Remove closed connections
                             purge.drain(..).for_each(|id| { plan[event].remove(&id); });
+                            // Remove evicted slow consumers
+                            evict.drain(..).for_each(|id| {
+                                EVENT_TYPE_ARRAY.iter().for_each(|event| { plan[*event].remove(&id); });
+                                stats.remove(&id);
+                                full_since.remove(&id);
+                            });
 
                         } else {
                             debug!("[Broadcaster-{}] notification stream ended", self.name);
@@ -193,9 +275,9 @@ where
         });
     }
 
-    pub fn register(&self, subscription: DynSubscription, id: ListenerId, connection: C) -> Result<()> {
+    pub fn register(&self, subscription: DynSubscription, id: ListenerId, connection: C, stats: Arc<ListenerStats>) -> Result<()> {
         if subscription.active() {
-            self.ctl.try_send(Ctl::Register(subscription, id, connection))?;
+            self.ctl.try_send(Ctl::Register(subscription, id, connection, stats))?;
         } else {
             self.ctl.try_send(Ctl::Unregister(subscription, id))?;
         }
@@ -219,13 +301,15 @@ mod tests {
     use super::*;
     use crate::{
         connection::{ChannelConnection, ChannelType},
-        listener::Listener,
+        listener::{Listener, ListenerMetrics},
         notification::test_helpers::*,
         notifier::test_helpers::{
             overall_test_steps, utxos_changed_test_steps, virtual_chain_changed_test_steps, Step, TestConnection,
         },
+        scope::{BlockAddedScope, Scope},
+        subscription::{Command, Mutation},
     };
-    use async_channel::{unbounded, Sender};
+    use async_channel::{bounded, unbounded, Sender};
 
     type TestBroadcaster = Broadcaster<TestNotification, ChannelConnection<TestNotification>>;
 
@@ -245,7 +329,8 @@ mod tests {
         fn new(name: &'static str, listener_count: usize, steps: Vec<Step>) -> Self {
             let (sync_sender, sync_receiver) = unbounded();
             let (notification_sender, notification_receiver) = unbounded();
-            let broadcaster = Arc::new(TestBroadcaster::new("test", notification_receiver, Some(sync_sender)));
+            let broadcaster =
+                Arc::new(TestBroadcaster::new("test", notification_receiver, DEFAULT_OVERFLOW_TIMEOUT, Some(sync_sender)));
             let mut listeners = Vec::with_capacity(listener_count);
             let mut notification_receivers = Vec::with_capacity(listener_count);
             for _ in 0..listener_count {
@@ -282,6 +367,7 @@ mod tests {
                                     self.listeners[idx].subscriptions[event].clone_arc(),
                                     idx as u64,
                                     self.listeners[idx].connection(),
+                                    self.listeners[idx].stats.clone(),
                                 ),
                                 false => Ctl::Unregister(self.listeners[idx].subscriptions[event].clone_arc(), idx as u64),
                             };
@@ -351,4 +437,51 @@ mod tests {
         let mut test = Test::new("UtxosChanged broadcast", 3, utxos_changed_test_steps(0));
         test.run().await;
     }
+
+    #[tokio::test]
+    async fn test_slow_consumer_eviction() {
+        kaspa_core::log::try_init_logger("trace,kaspa_notify=trace");
+        let (sync_sender, sync_receiver) = unbounded();
+        let (notification_sender, notification_receiver) = unbounded();
+        let broadcaster = Arc::new(TestBroadcaster::new("test", notification_receiver, Duration::from_millis(50), Some(sync_sender)));
+        broadcaster.start();
+
+        // A listener with a queue of a single notification, never consumed
+        let (sender, receiver) = bounded(1);
+        let mut listener = Listener::new(TestConnection::new(sender, ChannelType::Closable));
+        let event = EventType::BlockAdded;
+        listener.mutate(Mutation::new(Command::Start, Scope::BlockAdded(BlockAddedScope {}))).unwrap();
+        broadcaster.register(listener.subscriptions[event].clone_arc(), 0, listener.connection(), listener.stats.clone()).unwrap();
+        sync_receiver.recv().await.unwrap();
+
+        let notification = TestNotification::BlockAdded(BlockAddedNotification::default());
+        async fn broadcast(sender: &Sender<TestNotification>, sync_receiver: &Receiver<()>, notification: &TestNotification) {
+            sender.send(notification.clone()).await.unwrap();
+            sync_receiver.recv().await.unwrap();
+        }
+
+        // The first notification fills the queue, the next ones are dropped, with the listener flagged as lagging,
+        // until the overflow timeout elapses
+        broadcast(&notification_sender, &sync_receiver, &notification).await;
+        assert!(!listener.stats.lagging.load(Ordering::Relaxed));
+        broadcast(&notification_sender, &sync_receiver, &notification).await;
+        assert_eq!(
+            listener.metrics(),
+            ListenerMetrics { queue_len: 1, queue_capacity: Some(1), dropped_notifications: 1, lagging: true, overflowed: false }
+        );
+        assert!(!listener.is_closed());
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        broadcast(&notification_sender, &sync_receiver, &notification).await;
+        assert_eq!(listener.metrics().dropped_notifications, 2);
+        assert!(listener.metrics().overflowed);
+        assert!(listener.is_closed());
+
+        // The queued notification is still delivered, followed by the end of the stream
+        assert_eq!(receiver.recv().await.unwrap(), notification);
+        assert!(receiver.recv().await.is_err());
+
+        notification_sender.close();
+        assert!(broadcaster.join().await.is_ok(), "broadcaster failed to stop");
+    }
 }
@@ -127,7 +127,9 @@ mod tests {
         converter::ConverterFrom,
         events::EventType,
         notifier::test_helpers::NotifyMock,
-        subscription::single::{OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
+        subscription::single::{
+            AcceptedTransactionsSubscription, OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription,
+        },
     };
     use derive_more::Display;
 
@@ -165,6 +167,10 @@ mod tests {
             unimplemented!()
         }
 
+        fn apply_accepted_transactions_subscription(&self, _: &AcceptedTransactionsSubscription) -> Option<Self> {
+            unimplemented!()
+        }
+
         fn event_type(&self) -> EventType {
             unimplemented!()
         }
@@ -16,6 +16,30 @@ pub trait Connection: Clone + Debug + Send + Sync + 'static {
     async fn send(&self, message: Self::Message) -> Result<(), Self::Error>;
     fn close(&self) -> bool;
     fn is_closed(&self) -> bool;
+
+    /// Returns the number of messages queued and not yet consumed by the remote end
+    fn queue_len(&self) -> usize {
+        0
+    }
+
+    /// Returns the capacity of the message queue or `None` if the queue is unbounded
+    fn queue_capacity(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns true if the message queue is bounded and full
+    fn is_full(&self) -> bool {
+        self.queue_capacity().is_some_and(|capacity| self.queue_len() >= capacity)
+    }
+
+    /// Terminates the connection of a listener evicted for not consuming its notifications in time.
+    ///
+    /// Implementations may override this to signal the overflow to the remote end before closing.
+    ///
+    /// Returns true if the connection got closed.
+    fn overflow(&self) -> bool {
+        self.close()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -88,4 +112,12 @@ where
     fn is_closed(&self) -> bool {
         self.sender.is_closed()
     }
+
+    fn queue_len(&self) -> usize {
+        self.sender.len()
+    }
+
+    fn queue_capacity(&self) -> Option<usize> {
+        self.sender.capacity()
+    }
 }
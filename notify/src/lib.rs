@@ -12,5 +12,6 @@ pub mod notification;
 pub mod notifier;
 pub mod root;
 pub mod scope;
+pub mod sink;
 pub mod subscriber;
 pub mod subscription;
@@ -1,4 +1,10 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+};
 extern crate derive_more;
 use super::{
     connection::Connection,
@@ -8,6 +14,32 @@ use super::{
 
 pub type ListenerId = u64;
 
+/// Counters of the notifications a listener failed to consume, shared between the listener and the broadcasters
+#[derive(Debug, Default)]
+pub(crate) struct ListenerStats {
+    /// Number of notifications dropped because the listener queue was full
+    pub(crate) dropped: AtomicU64,
+    /// Whether the listener queue is currently full, its notifications being dropped
+    pub(crate) lagging: AtomicBool,
+    /// Whether the listener got evicted for staying full beyond the overflow timeout
+    pub(crate) overflowed: AtomicBool,
+}
+
+/// Queue metrics of a listener, helping to diagnose slow consumers
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ListenerMetrics {
+    /// Number of notifications queued and not yet consumed
+    pub queue_len: usize,
+    /// Capacity of the queue, `None` if unbounded
+    pub queue_capacity: Option<usize>,
+    /// Number of notifications dropped because the queue was full
+    pub dropped_notifications: u64,
+    /// Whether the queue is currently full, the notifications being dropped
+    pub lagging: bool,
+    /// Whether the listener got evicted for consuming its notifications too slowly
+    pub overflowed: bool,
+}
+
 /// A listener of [`super::notifier::Notifier`] notifications.
 #[derive(Debug)]
 pub(crate) struct Listener<C>
@@ -16,6 +48,7 @@ where
 {
     connection: C,
     pub(crate) subscriptions: EventArray<SingleSubscription>,
+    pub(crate) stats: Arc<ListenerStats>,
 }
 
 impl<C> Listener<C>
@@ -23,7 +56,7 @@ where
     C: Connection,
 {
     pub fn new(connection: C) -> Self {
-        Self { connection, subscriptions: ArrayBuilder::single() }
+        Self { connection, subscriptions: ArrayBuilder::single(), stats: Default::default() }
     }
 
     pub fn connection(&self) -> C {
@@ -47,4 +80,14 @@ where
     pub fn is_closed(&self) -> bool {
         self.connection.is_closed()
     }
+
+    pub fn metrics(&self) -> ListenerMetrics {
+        ListenerMetrics {
+            queue_len: self.connection.queue_len(),
+            queue_capacity: self.connection.queue_capacity(),
+            dropped_notifications: self.stats.dropped.load(Ordering::Relaxed),
+            lagging: self.stats.lagging.load(Ordering::Relaxed),
+            overflowed: self.stats.overflowed.load(Ordering::Relaxed),
+        }
+    }
 }
@@ -1,7 +1,8 @@
 use super::{
     events::EventType,
     subscription::{
-        single::{OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
+        index::UtxosChangedIndex,
+        single::{AcceptedTransactionsSubscription, OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
         Single,
     },
 };
@@ -14,6 +15,8 @@ pub trait Notification: Clone + Debug + Display + Send + Sync + 'static {
 
     fn apply_utxos_changed_subscription(&self, subscription: &UtxosChangedSubscription) -> Option<Self>;
 
+    fn apply_accepted_transactions_subscription(&self, subscription: &AcceptedTransactionsSubscription) -> Option<Self>;
+
     fn apply_subscription(&self, subscription: &dyn Single) -> Option<Self> {
         match subscription.event_type() {
             EventType::VirtualChainChanged => self.apply_virtual_chain_changed_subscription(
@@ -22,10 +25,27 @@ pub trait Notification: Clone + Debug + Display + Send + Sync + 'static {
             EventType::UtxosChanged => {
                 self.apply_utxos_changed_subscription(subscription.as_any().downcast_ref::<UtxosChangedSubscription>().unwrap())
             }
+            EventType::AcceptedTransactions => self.apply_accepted_transactions_subscription(
+                subscription.as_any().downcast_ref::<AcceptedTransactionsSubscription>().unwrap(),
+            ),
             _ => self.apply_overall_subscription(subscription.as_any().downcast_ref::<OverallSubscription>().unwrap()),
         }
     }
 
+    /// Applies the notification to the subscriptions of `index`, returning the resulting notification of every
+    /// affected subscription along with its position in the index.
+    ///
+    /// The default implementation applies every subscription in turn. Notifications carrying UTXO changes should
+    /// override it and look up the subscriptions affected by each change.
+    fn apply_utxos_changed_index(&self, index: &UtxosChangedIndex) -> Vec<(usize, Self)> {
+        index
+            .subscriptions()
+            .iter()
+            .enumerate()
+            .filter_map(|(position, subscription)| self.apply_subscription(&**subscription).map(|applied| (position, applied)))
+            .collect()
+    }
+
     fn event_type(&self) -> EventType;
 }
 
@@ -74,7 +94,8 @@ pub mod test_helpers {
     use derive_more::Display;
     use kaspa_addresses::Address;
     use kaspa_core::trace;
-    use std::sync::Arc;
+    use kaspa_txscript::pay_to_address_script;
+    use std::{collections::HashMap, sync::Arc};
 
     #[derive(Clone, Debug, Default, PartialEq, Eq)]
     pub struct BlockAddedNotification {
@@ -154,6 +175,35 @@ pub mod test_helpers {
             }
         }
 
+        fn apply_accepted_transactions_subscription(&self, subscription: &AcceptedTransactionsSubscription) -> Option<Self> {
+            match subscription.active() {
+                true => Some(self.clone()),
+                false => None,
+            }
+        }
+
+        fn apply_utxos_changed_index(&self, index: &UtxosChangedIndex) -> Vec<(usize, Self)> {
+            let TestNotification::UtxosChanged(ref payload) = self else { return vec![] };
+            let mut addresses: HashMap<usize, Vec<Address>> = HashMap::new();
+            for address in payload.addresses.iter() {
+                for position in index.positions(&pay_to_address_script(address)) {
+                    addresses.entry(*position).or_default().push(address.clone());
+                }
+            }
+            addresses
+                .into_iter()
+                .map(|(position, addresses)| {
+                    (
+                        position,
+                        TestNotification::UtxosChanged(UtxosChangedNotification {
+                            data: payload.data,
+                            addresses: Arc::new(addresses),
+                        }),
+                    )
+                })
+                .collect()
+        }
+
         fn event_type(&self) -> EventType {
             self.into()
         }
@@ -1,12 +1,12 @@
 use crate::events::EVENT_TYPE_ARRAY;
 
 use super::{
-    broadcaster::Broadcaster,
+    broadcaster::{Broadcaster, DEFAULT_OVERFLOW_TIMEOUT},
     collector::DynCollector,
     connection::Connection,
     error::{Error, Result},
     events::{EventArray, EventSwitches, EventType},
-    listener::{Listener, ListenerId},
+    listener::{Listener, ListenerId, ListenerMetrics},
     notification::Notification,
     scope::Scope,
     subscriber::{Subscriber, SubscriptionManager},
@@ -37,6 +37,17 @@ where
 
 pub type DynNotify<N> = Arc<dyn Notify<N>>;
 
+/// Reports the queue metrics of the listeners of a notifier, whatever its notification and connection types
+pub trait ListenerMetricsSource: Send + Sync + Debug {
+    /// The name of the notifier
+    fn name(&self) -> &'static str;
+
+    /// Returns the queue metrics of every registered listener
+    fn listener_metrics(&self) -> HashMap<ListenerId, ListenerMetrics>;
+}
+
+pub type DynListenerMetricsSource = Arc<dyn ListenerMetricsSource>;
+
 // pub trait Registrar<N>: Send + Sync + Debug
 // where
 //     N: Notification,
@@ -142,6 +153,20 @@ where
     }
 }
 
+impl<N, C> ListenerMetricsSource for Notifier<N, C>
+where
+    N: Notification,
+    C: Connection<Notification = N>,
+{
+    fn name(&self) -> &'static str {
+        self.inner.name
+    }
+
+    fn listener_metrics(&self) -> HashMap<ListenerId, ListenerMetrics> {
+        self.inner.listener_metrics()
+    }
+}
+
 #[async_trait]
 impl<N, C> SubscriptionManager for Notifier<N, C>
 where
@@ -214,7 +239,7 @@ where
         assert!(broadcasters > 0, "a notifier requires a minimum of one broadcaster");
         let notification_channel = Channel::unbounded();
         let broadcasters = (0..broadcasters)
-            .map(|_| Arc::new(Broadcaster::new(name, notification_channel.receiver.clone(), _sync.clone())))
+            .map(|_| Arc::new(Broadcaster::new(name, notification_channel.receiver.clone(), DEFAULT_OVERFLOW_TIMEOUT, _sync.clone())))
             .collect::<Vec<_>>();
         Self {
             enabled_events,
@@ -257,6 +282,10 @@ where
         }
     }
 
+    fn listener_metrics(&self) -> HashMap<ListenerId, ListenerMetrics> {
+        self.listeners.lock().iter().map(|(id, listener)| (*id, listener.metrics())).collect()
+    }
+
     fn unregister_listener(self: &Arc<Self>, id: ListenerId) -> Result<()> {
         // Try to remove the listener, preventing any possible new subscription
         let listener = self.listeners.lock().remove(&id);
@@ -315,9 +344,9 @@ where
             trace!("[Notifier {}] {command} notifying listener {id} about {scope:?} involves mutations {mutations:?}", self.name);
             // Update broadcasters
             let subscription = listener.subscriptions[event].clone_arc();
-            self.broadcasters
-                .iter()
-                .try_for_each(|broadcaster| broadcaster.register(subscription.clone(), id, listener.connection()))?;
+            self.broadcasters.iter().try_for_each(|broadcaster| {
+                broadcaster.register(subscription.clone(), id, listener.connection(), listener.stats.clone())
+            })?;
             // Compound mutations
             let mut compound_result = None;
             for mutation in mutations {
@@ -43,6 +43,9 @@ pub enum Scope {
     VirtualDaaScoreChanged,
     PruningPointUtxoSetOverride,
     NewBlockTemplate,
+    MempoolTransactionRemoved,
+    AcceptedTransactions,
+    SyncStateChanged,
 }
 }
 
@@ -113,3 +116,62 @@ pub struct PruningPointUtxoSetOverrideScope {}
 
 #[derive(Clone, Display, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 pub struct NewBlockTemplateScope {}
+
+#[derive(Clone, Display, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct MempoolTransactionRemovedScope {}
+
+#[derive(Clone, Display, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct SyncStateChangedScope {}
+
+/// Scope of the AcceptedTransactions notifications, restricted to the transactions paying to `addresses`
+/// or covering all accepted transactions if empty
+#[derive(Clone, Debug, Default, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct AcceptedTransactionsScope {
+    pub addresses: Vec<Address>,
+}
+
+impl std::fmt::Display for AcceptedTransactionsScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let addresses = match self.addresses.len() {
+            0 => "all".to_string(),
+            1 => format!("{}", self.addresses[0]),
+            n => format!("{} addresses", n),
+        };
+        write!(f, "AcceptedTransactionsScope ({})", addresses)
+    }
+}
+
+impl PartialEq for AcceptedTransactionsScope {
+    fn eq(&self, other: &Self) -> bool {
+        self.addresses.len() == other.addresses.len() && self.addresses.iter().all(|x| other.addresses.contains(x))
+    }
+}
+
+impl Eq for AcceptedTransactionsScope {}
+
+impl AcceptedTransactionsScope {
+    pub fn new(addresses: Vec<Address>) -> Self {
+        Self { addresses }
+    }
+}
+
+impl Scope {
+    /// Converts an AcceptedTransactions scope into the UtxosChanged scope covering the same addresses.
+    ///
+    /// Both events share the same address-scoped subscription model, so the AcceptedTransactions
+    /// subscriptions delegate their state management to the UtxosChanged ones.
+    pub(crate) fn into_utxos_changed(self) -> Self {
+        match self {
+            Scope::AcceptedTransactions(scope) => Scope::UtxosChanged(UtxosChangedScope::new(scope.addresses)),
+            scope => scope,
+        }
+    }
+
+    /// Converts a UtxosChanged scope into the AcceptedTransactions scope covering the same addresses
+    pub(crate) fn into_accepted_transactions(self) -> Self {
+        match self {
+            Scope::UtxosChanged(scope) => Scope::AcceptedTransactions(AcceptedTransactionsScope::new(scope.addresses)),
+            scope => scope,
+        }
+    }
+}
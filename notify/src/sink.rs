@@ -0,0 +1,142 @@
+//!
+//! Sinks publish notifications to systems external to the node, typically message queues, for consumers
+//! preferring these over RPC subscriptions.
+//!
+
+use crate::{
+    connection::{ChannelConnection, ChannelType},
+    error::Result,
+    listener::ListenerId,
+    notification::Notification,
+    notifier::Notifier,
+    scope::Scope,
+};
+use kaspa_core::warn;
+use kaspa_utils::channel::Channel;
+use std::{fmt::Debug, sync::Arc};
+
+/// An external destination of notifications
+#[async_trait::async_trait]
+pub trait NotificationSink<N>: Debug + Send + Sync
+where
+    N: Notification,
+{
+    /// A short name identifying the sink in logs
+    fn name(&self) -> &'static str;
+
+    /// Publishes a notification of one of the scopes the sink is relayed
+    async fn publish(&self, notification: N) -> Result<()>;
+}
+
+pub type DynNotificationSink<N> = Arc<dyn NotificationSink<N>>;
+
+/// Relays the notifications of a notifier matching some scopes to a sink
+#[derive(Debug)]
+pub struct SinkRelay<N>
+where
+    N: Notification,
+{
+    sink: DynNotificationSink<N>,
+    listener_id: ListenerId,
+    channel: Channel<N>,
+}
+
+impl<N> SinkRelay<N>
+where
+    N: Notification,
+{
+    /// Registers the relay as a listener of `notifier` subscribed to `scopes`
+    pub fn new(sink: DynNotificationSink<N>, notifier: &Notifier<N, ChannelConnection<N>>, scopes: Vec<Scope>) -> Result<Self> {
+        let channel = Channel::<N>::default();
+        let listener_id = notifier.register_new_listener(ChannelConnection::new(channel.sender(), ChannelType::Closable));
+        for scope in scopes {
+            notifier.try_start_notify(listener_id, scope)?;
+        }
+        Ok(Self { sink, listener_id, channel })
+    }
+
+    pub fn listener_id(&self) -> ListenerId {
+        self.listener_id
+    }
+
+    /// Publishes the relayed notifications to the sink until the notifier closes the listener connection.
+    ///
+    /// A failure to publish a notification is logged and the notification is dropped, sinks being expected to
+    /// handle reconnections to their destination by themselves
+    pub async fn run(&self) {
+        while let Ok(notification) = self.channel.recv().await {
+            if let Err(err) = self.sink.publish(notification).await {
+                warn!("[{}] failed to publish a notification: {}", self.sink.name(), err);
+            }
+        }
+    }
+
+    /// Closes the listener connection, ending [`Self::run`] once the pending notifications are published
+    pub fn close(&self) {
+        self.channel.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        collector::CollectorFrom,
+        converter::ConverterFrom,
+        events::EVENT_TYPE_ARRAY,
+        notification::test_helpers::{BlockAddedNotification, TestNotification, VirtualChainChangedNotification},
+        notifier::test_helpers::TestNotifier,
+        scope::BlockAddedScope,
+    };
+    use async_channel::{unbounded, Sender};
+
+    #[derive(Debug)]
+    struct SinkMock {
+        sender: Sender<TestNotification>,
+    }
+
+    #[async_trait::async_trait]
+    impl NotificationSink<TestNotification> for SinkMock {
+        fn name(&self) -> &'static str {
+            "sink-mock"
+        }
+
+        async fn publish(&self, notification: TestNotification) -> Result<()> {
+            Ok(self.sender.send(notification).await?)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sink_relay() {
+        type TestConverter = ConverterFrom<TestNotification, TestNotification>;
+        type TestCollector = CollectorFrom<TestConverter>;
+        let (notification_sender, notification_receiver) = unbounded();
+        let collector = Arc::new(TestCollector::new("test", notification_receiver, Arc::new(TestConverter::new())));
+        let (sync_sender, sync_receiver) = unbounded();
+        let notifier =
+            Arc::new(TestNotifier::with_sync("test", EVENT_TYPE_ARRAY[..].into(), vec![collector], vec![], 1, Some(sync_sender)));
+
+        let (sink_sender, sink_receiver) = unbounded();
+        let sink = Arc::new(SinkMock { sender: sink_sender });
+        let relay = Arc::new(SinkRelay::new(sink, &notifier, vec![Scope::BlockAdded(BlockAddedScope {})]).unwrap());
+        notifier.clone().start();
+        let relay_task = tokio::spawn({
+            let relay = relay.clone();
+            async move { relay.run().await }
+        });
+        // Wait for the broadcaster to register the relay before sending it notifications
+        sync_receiver.recv().await.unwrap();
+
+        // Only the notifications of the relayed scopes reach the sink
+        let block_added = TestNotification::BlockAdded(BlockAddedNotification { data: 1 });
+        notification_sender.send(TestNotification::VirtualChainChanged(VirtualChainChangedNotification::default())).await.unwrap();
+        notification_sender.send(block_added.clone()).await.unwrap();
+        assert_eq!(sink_receiver.recv().await.unwrap(), block_added);
+
+        // Stopping the notifier closes the relay connection
+        notification_sender.close();
+        notifier.join().await.unwrap();
+        relay_task.await.unwrap();
+        assert!(sink_receiver.try_recv().is_err());
+    }
+}
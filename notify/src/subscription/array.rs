@@ -10,6 +10,7 @@ impl ArrayBuilder {
             let subscription: SingleSubscription = match event_type {
                 EventType::VirtualChainChanged => Box::<single::VirtualChainChangedSubscription>::default(),
                 EventType::UtxosChanged => Box::<single::UtxosChangedSubscription>::default(),
+                EventType::AcceptedTransactions => Box::<single::AcceptedTransactionsSubscription>::default(),
                 _ => Box::new(single::OverallSubscription::new(event_type, false)),
             };
             subscription
@@ -22,6 +23,7 @@ impl ArrayBuilder {
             let subscription: CompoundedSubscription = match event_type {
                 EventType::VirtualChainChanged => Box::<compounded::VirtualChainChangedSubscription>::default(),
                 EventType::UtxosChanged => Box::<compounded::UtxosChangedSubscription>::default(),
+                EventType::AcceptedTransactions => Box::<compounded::AcceptedTransactionsSubscription>::default(),
                 _ => Box::new(compounded::OverallSubscription::new(event_type)),
             };
             subscription
@@ -243,6 +243,36 @@ impl Subscription for UtxosChangedSubscription {
     }
 }
 
+/// Compounded subscription to AcceptedTransactions notifications, counting the subscribed addresses
+/// exactly like [`UtxosChangedSubscription`]
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct AcceptedTransactionsSubscription {
+    inner: UtxosChangedSubscription,
+}
+
+impl Compounded for AcceptedTransactionsSubscription {
+    fn compound(&mut self, mutation: Mutation) -> Option<Mutation> {
+        assert_eq!(self.event_type(), mutation.event_type());
+        let mutation = Mutation::new(mutation.command, mutation.scope.into_utxos_changed());
+        self.inner.compound(mutation).map(|mutation| Mutation::new(mutation.command, mutation.scope.into_accepted_transactions()))
+    }
+}
+
+impl Subscription for AcceptedTransactionsSubscription {
+    #[inline(always)]
+    fn event_type(&self) -> EventType {
+        EventType::AcceptedTransactions
+    }
+
+    fn active(&self) -> bool {
+        self.inner.active()
+    }
+
+    fn scope(&self) -> Scope {
+        self.inner.scope().into_accepted_transactions()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::*;
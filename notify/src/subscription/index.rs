@@ -0,0 +1,102 @@
+use super::{single::UtxosChangedSubscription, DynSubscription};
+use crate::notification::Notification;
+use kaspa_consensus_core::tx::ScriptPublicKey;
+use std::collections::HashMap;
+
+/// Inverted index of a set of [`UtxosChangedSubscription`]s, mapping every script public key to the subscriptions
+/// containing it.
+///
+/// Filtering a UtxosChanged notification through the index costs a lookup per UTXO change instead of a scan of
+/// the changes for every subscription, so the fan-out cost is proportional to the affected addresses.
+#[derive(Debug, Default)]
+pub struct UtxosChangedIndex {
+    /// Subscriptions to all addresses, not indexed
+    to_all: Vec<DynSubscription>,
+    /// Subscriptions to a set of addresses, referred to by their position in the index
+    subscriptions: Vec<DynSubscription>,
+    /// Positions of the subscriptions by script public key
+    scripts: HashMap<ScriptPublicKey, Vec<usize>>,
+}
+
+impl UtxosChangedIndex {
+    pub fn new<'a>(subscriptions: impl Iterator<Item = &'a DynSubscription>) -> Self {
+        let mut index = Self::default();
+        for subscription in subscriptions {
+            let Some(utxos_changed) = subscription.as_any().downcast_ref::<UtxosChangedSubscription>() else { continue };
+            if utxos_changed.to_all() {
+                index.to_all.push(subscription.clone());
+            } else {
+                let position = index.subscriptions.len();
+                utxos_changed.addresses().keys().for_each(|script| index.scripts.entry(script.clone()).or_default().push(position));
+                index.subscriptions.push(subscription.clone());
+            }
+        }
+        index
+    }
+
+    /// Returns the subscriptions to a set of addresses, ordered by position
+    pub fn subscriptions(&self) -> &[DynSubscription] {
+        &self.subscriptions
+    }
+
+    /// Returns the positions of the subscriptions containing `script`
+    pub fn positions(&self, script: &ScriptPublicKey) -> &[usize] {
+        self.scripts.get(script).map_or(&[], |positions| positions.as_slice())
+    }
+
+    /// Applies `notification` to all the indexed subscriptions, returning the resulting notification of every
+    /// subscription it affects
+    pub fn apply<N: Notification>(&self, notification: &N) -> Vec<(DynSubscription, N)> {
+        let mut result = self
+            .to_all
+            .iter()
+            .filter_map(|subscription| notification.apply_subscription(&**subscription).map(|applied| (subscription.clone(), applied)))
+            .collect::<Vec<_>>();
+        result.extend(
+            notification
+                .apply_utxos_changed_index(self)
+                .into_iter()
+                .map(|(position, applied)| (self.subscriptions[position].clone(), applied)),
+        );
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        address::test_helpers::get_3_addresses,
+        notification::test_helpers::{TestNotification, UtxosChangedNotification},
+        subscription::SingleClone,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn test_utxos_changed_index() {
+        let addresses = get_3_addresses(false);
+        let subscription = |addresses: Vec<kaspa_addresses::Address>| UtxosChangedSubscription::new(true, addresses).clone_arc();
+        let subscriptions = vec![
+            subscription(vec![addresses[0].clone(), addresses[1].clone()]),
+            subscription(vec![addresses[1].clone()]),
+            subscription(vec![]),
+        ];
+        let index = UtxosChangedIndex::new(subscriptions.iter());
+        assert_eq!(index.subscriptions().len(), 2);
+        assert_eq!(index.positions(&kaspa_txscript::pay_to_address_script(&addresses[1])), &[0, 1]);
+        assert!(index.positions(&kaspa_txscript::pay_to_address_script(&addresses[2])).is_empty());
+
+        let notification = |addresses: Vec<kaspa_addresses::Address>| {
+            TestNotification::UtxosChanged(UtxosChangedNotification { data: 0, addresses: Arc::new(addresses) })
+        };
+        let mut applied = index.apply(&notification(vec![addresses[0].clone(), addresses[2].clone()]));
+        applied.sort_by_key(|(subscription, _)| subscriptions.iter().position(|x| x == subscription));
+        assert_eq!(
+            applied,
+            vec![
+                (subscriptions[0].clone(), notification(vec![addresses[0].clone()])),
+                (subscriptions[2].clone(), notification(vec![addresses[0].clone(), addresses[2].clone()])),
+            ]
+        );
+    }
+}
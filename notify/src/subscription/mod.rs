@@ -11,6 +11,7 @@ use std::{
 
 pub mod array;
 pub mod compounded;
+pub mod index;
 pub mod single;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
@@ -316,11 +316,68 @@ impl Subscription for UtxosChangedSubscription {
     }
 }
 
+/// Subscription to AcceptedTransactions notifications, selecting the transactions by the addresses they pay to
+/// exactly like [`UtxosChangedSubscription`] selects the UTXOs
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AcceptedTransactionsSubscription {
+    inner: UtxosChangedSubscription,
+}
+
+impl AcceptedTransactionsSubscription {
+    pub fn new(active: bool, addresses: Vec<Address>) -> Self {
+        Self { inner: UtxosChangedSubscription::new(active, addresses) }
+    }
+
+    pub fn contains_script_public_key(&self, script_public_key: &ScriptPublicKey) -> bool {
+        self.inner.addresses().contains_key(script_public_key)
+    }
+
+    pub fn script_public_keys(&self) -> impl Iterator<Item = &ScriptPublicKey> {
+        self.inner.addresses().keys()
+    }
+
+    pub fn to_all(&self) -> bool {
+        self.inner.to_all()
+    }
+}
+
+impl Single for AcceptedTransactionsSubscription {
+    fn mutate(&mut self, mutation: Mutation) -> Option<Vec<Mutation>> {
+        if mutation.event_type() != EventType::AcceptedTransactions {
+            return None;
+        }
+        let mutation = Mutation::new(mutation.command, mutation.scope.into_utxos_changed());
+        self.inner.mutate(mutation).map(|mutations| {
+            mutations
+                .into_iter()
+                .map(|mutation| Mutation::new(mutation.command, mutation.scope.into_accepted_transactions()))
+                .collect()
+        })
+    }
+}
+
+impl Subscription for AcceptedTransactionsSubscription {
+    fn event_type(&self) -> EventType {
+        EventType::AcceptedTransactions
+    }
+
+    fn active(&self) -> bool {
+        self.inner.active()
+    }
+
+    fn scope(&self) -> Scope {
+        self.inner.scope().into_accepted_transactions()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::*;
     use super::*;
-    use crate::{address::test_helpers::get_3_addresses, scope::BlockAddedScope};
+    use crate::{
+        address::test_helpers::get_3_addresses,
+        scope::{AcceptedTransactionsScope, BlockAddedScope},
+    };
     use std::collections::hash_map::DefaultHasher;
 
     #[test]
@@ -781,4 +838,69 @@ mod tests {
         ]);
         tests.run()
     }
+    #[test]
+    fn test_accepted_transactions_mutation() {
+        let a_stock = get_3_addresses(true);
+
+        let av = |indexes: &[usize]| indexes.iter().map(|idx| (a_stock[*idx]).clone()).collect::<Vec<_>>();
+        let s = |active: bool, indexes: &[usize]| {
+            Box::new(AcceptedTransactionsSubscription::new(active, av(indexes))) as SingleSubscription
+        };
+        let m = |command: Command, indexes: &[usize]| -> Mutation {
+            Mutation { command, scope: Scope::AcceptedTransactions(AcceptedTransactionsScope { addresses: av(indexes) }) }
+        };
+
+        // Subscriptions
+        let none = || s(false, &[]);
+        let selected_0 = || s(true, &[0]);
+        let selected_01 = || s(true, &[0, 1]);
+        let all = || s(true, &[]);
+
+        // Mutations
+        let start_all = || m(Command::Start, &[]);
+        let stop_all = || m(Command::Stop, &[]);
+        let start_0 = || m(Command::Start, &[0]);
+        let start_1 = || m(Command::Start, &[1]);
+        let stop_1 = || m(Command::Stop, &[1]);
+
+        // Tests
+        let tests = MutationTests::new(vec![
+            MutationTest {
+                name: "AcceptedTransactionsSubscription None to Selected 0 (add set)",
+                state: none(),
+                mutation: start_0(),
+                new_state: selected_0(),
+                result: Some(vec![start_0()]),
+            },
+            MutationTest {
+                name: "AcceptedTransactionsSubscription Selected 0 to 01 (add set with no intersection)",
+                state: selected_0(),
+                mutation: start_1(),
+                new_state: selected_01(),
+                result: Some(vec![start_1()]),
+            },
+            MutationTest {
+                name: "AcceptedTransactionsSubscription Selected 01 to 0 (remove set with partial intersection)",
+                state: selected_01(),
+                mutation: stop_1(),
+                new_state: selected_0(),
+                result: Some(vec![stop_1()]),
+            },
+            MutationTest {
+                name: "AcceptedTransactionsSubscription Selected 0 to All (add all)",
+                state: selected_0(),
+                mutation: start_all(),
+                new_state: all(),
+                result: Some(vec![m(Command::Stop, &[0]), start_all()]),
+            },
+            MutationTest {
+                name: "AcceptedTransactionsSubscription All to None (remove all)",
+                state: all(),
+                mutation: stop_all(),
+                new_state: none(),
+                result: Some(vec![stop_all()]),
+            },
+        ]);
+        tests.run()
+    }
 }
@@ -1,13 +1,26 @@
-use crate::flowcontext::{orphans::OrphanBlocksPool, process_queue::ProcessQueue, transactions::TransactionsSpread};
-use crate::{v5, v6};
+use crate::flowcontext::{
+    ibd::{IbdManager, IbdRunningGuard},
+    inventory::RecentInventory,
+    network_time::NetworkTime,
+    orphans::OrphanBlocksPool,
+    process_queue::ProcessQueue,
+    reorg::ReorgTracker,
+    stale_tip::StaleTipMonitor,
+    sync_state::{SyncState, SyncStateSender},
+    transactions::TransactionsSpread,
+    validation_cache::{BlockValidationCache, BlockValidationResult},
+};
+use crate::{flow_trait::Flow, v5, v6};
 use async_trait::async_trait;
 use futures::future::join_all;
+use itertools::Itertools;
 use kaspa_addressmanager::AddressManager;
 use kaspa_connectionmanager::ConnectionManager;
 use kaspa_consensus_core::api::{BlockValidationFuture, BlockValidationFutures};
-use kaspa_consensus_core::block::Block;
+use kaspa_consensus_core::block::{Block, BlockOrigin};
+use kaspa_consensus_core::blockstatus::BlockStatus;
 use kaspa_consensus_core::config::Config;
-use kaspa_consensus_core::errors::block::RuleError;
+use kaspa_consensus_core::errors::block::{BlockProcessResult, RuleError};
 use kaspa_consensus_core::tx::{Transaction, TransactionId};
 use kaspa_consensus_notify::{
     notification::{Notification, PruningPointUtxoSetOverrideNotification},
@@ -17,7 +30,7 @@ use kaspa_consensusmanager::{ConsensusInstance, ConsensusManager, ConsensusProxy
 use kaspa_core::{
     debug, info,
     kaspad_env::{name, version},
-    task::tick::TickService,
+    task::tick::{TickReason, TickService},
 };
 use kaspa_core::{time::unix_now, warn};
 use kaspa_hashes::Hash;
@@ -26,52 +39,50 @@ use kaspa_mining::mempool::tx::{Orphan, Priority};
 use kaspa_notify::notifier::Notify;
 use kaspa_p2p_lib::{
     common::ProtocolError,
-    convert::model::version::Version,
+    convert::model::version::{ServiceFlags, Version},
     make_message,
     pb::{kaspad_message::Payload, InvRelayBlockMessage},
-    ConnectionInitializer, Hub, KaspadHandshake, PeerKey, PeerProperties, Router,
+    ConnectionInitializer, Hub, KaspadHandshake, PeerKey, PeerProperties, Router, Traffic,
 };
 use kaspa_utils::iter::IterExtensions;
 use kaspa_utils::networking::PeerId;
 use parking_lot::{Mutex, RwLock};
+use std::cmp::Reverse;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::time::Instant;
-use std::{
-    iter::once,
-    ops::Deref,
-    sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
-    },
-    time::Duration,
-};
+use std::{iter::once, ops::Deref, sync::Arc, time::Duration};
 use tokio::sync::{
     mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
     RwLock as AsyncRwLock,
 };
 use tokio_stream::{wrappers::UnboundedReceiverStream, StreamExt};
-use uuid::Uuid;
 
-/// The P2P protocol version. Currently the only one supported.
+/// The P2P protocol version advertised by this node
 const PROTOCOL_VERSION: u32 = 6;
 
+/// The minimal P2P protocol version of peers this node can communicate with
+const MIN_PROTOCOL_VERSION: u32 = 5;
+
 /// See `check_orphan_resolution_range`
 const BASELINE_ORPHAN_RESOLUTION_RANGE: u32 = 5;
 
 /// The min time to wait before allowing another parallel request
 const REQUEST_SCOPE_WAIT_TIME: Duration = Duration::from_secs(1);
 
-#[derive(Debug, PartialEq)]
-pub enum BlockSource {
-    Relay,
-    Submit,
-}
+/// The interval between consecutive stale tip checks
+const STALE_TIP_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The interval for reporting the P2P traffic of the node and of its heaviest peers
+const BANDWIDTH_REPORT_INTERVAL: Duration = Duration::from_secs(600);
+
+/// The number of peers exchanging the most traffic listed in the bandwidth report
+const BANDWIDTH_REPORT_HEAVIEST_PEERS: usize = 3;
 
 pub struct AcceptedBlockLogger {
     bps: usize,
-    sender: UnboundedSender<(Hash, BlockSource)>,
-    receiver: Mutex<Option<UnboundedReceiver<(Hash, BlockSource)>>>,
+    sender: UnboundedSender<(Hash, BlockOrigin)>,
+    receiver: Mutex<Option<UnboundedReceiver<(Hash, BlockOrigin)>>>,
 }
 
 impl AcceptedBlockLogger {
@@ -80,8 +91,8 @@ impl AcceptedBlockLogger {
         Self { bps, sender, receiver: Mutex::new(Some(receiver)) }
     }
 
-    pub fn log(&self, hash: Hash, source: BlockSource) {
-        self.sender.send((hash, source)).unwrap();
+    pub fn log(&self, hash: Hash, origin: BlockOrigin) {
+        self.sender.send((hash, origin)).unwrap();
     }
 
     /// Start the logger listener. Must be called from an async tokio context
@@ -93,7 +104,7 @@ impl AcceptedBlockLogger {
             tokio::pin!(chunk_stream);
             while let Some(chunk) = chunk_stream.next().await {
                 if let Some((i, h)) =
-                    chunk.iter().filter_map(|(h, s)| if *s == BlockSource::Submit { Some(*h) } else { None }).enumerate().last()
+                    chunk.iter().filter_map(|(h, s)| if *s == BlockOrigin::Rpc { Some(*h) } else { None }).enumerate().last()
                 {
                     let submit = i + 1; // i is the last index so i + 1 is the number of submit blocks
                     let relay = chunk.len() - submit;
@@ -123,10 +134,15 @@ pub struct FlowContextInner {
     shared_block_requests: Arc<Mutex<HashMap<Hash, RequestScopeMetadata>>>,
     transactions_spread: AsyncRwLock<TransactionsSpread>,
     shared_transaction_requests: Arc<Mutex<HashMap<TransactionId, RequestScopeMetadata>>>,
-    is_ibd_running: Arc<AtomicBool>,
-    ibd_peer_key: Arc<RwLock<Option<PeerKey>>>,
+    recent_inventory: RecentInventory,
+    block_validation_cache: BlockValidationCache,
+    reorg_tracker: ReorgTracker,
+    network_time: NetworkTime,
+    stale_tip_monitor: StaleTipMonitor,
+    ibd_manager: Arc<IbdManager>,
     pub address_manager: Arc<Mutex<AddressManager>>,
     connection_manager: RwLock<Option<Arc<ConnectionManager>>>,
+    sync_state_sender: RwLock<Option<SyncStateSender>>,
     mining_manager: MiningManagerProxy,
     pub(crate) tick_service: Arc<TickService>,
     notification_root: Arc<ConsensusNotificationRoot>,
@@ -143,17 +159,6 @@ pub struct FlowContext {
     inner: Arc<FlowContextInner>,
 }
 
-pub struct IbdRunningGuard {
-    indicator: Arc<AtomicBool>,
-}
-
-impl Drop for IbdRunningGuard {
-    fn drop(&mut self) {
-        let result = self.indicator.compare_exchange(true, false, Ordering::SeqCst, Ordering::SeqCst);
-        assert!(result.is_ok())
-    }
-}
-
 pub struct RequestScopeMetadata {
     pub timestamp: Instant,
     pub obtained: bool,
@@ -208,19 +213,28 @@ impl FlowContext {
         // The maximum amount of orphans allowed in the orphans pool. This number is an
         // approximation of how many orphans there can possibly be on average.
         let max_orphans = 2u64.pow(orphan_resolution_range) as usize * config.ghostdag_k as usize;
+        let node_id = address_manager.lock().node_id();
+
+        // Warn well before the local clock drifts far enough for header validation to reject blocks of the network
+        let max_clock_drift = config.timestamp_deviation_tolerance(0) * config.target_time_per_block / 2;
         Self {
             inner: Arc::new(FlowContextInner {
-                node_id: Uuid::new_v4().into(),
+                node_id,
                 consensus_manager,
                 orphans_pool: AsyncRwLock::new(OrphanBlocksPool::new(max_orphans)),
                 shared_block_requests: Arc::new(Mutex::new(HashMap::new())),
                 transactions_spread: AsyncRwLock::new(TransactionsSpread::new(hub.clone())),
                 shared_transaction_requests: Arc::new(Mutex::new(HashMap::new())),
-                is_ibd_running: Default::default(),
-                ibd_peer_key: Default::default(),
+                recent_inventory: RecentInventory::new(),
+                block_validation_cache: BlockValidationCache::new(),
+                reorg_tracker: ReorgTracker::new(),
+                network_time: NetworkTime::new(max_clock_drift),
+                stale_tip_monitor: StaleTipMonitor::new(config.stale_tip_timeout * 1000, unix_now()),
+                ibd_manager: Arc::new(IbdManager::new()),
                 hub,
                 address_manager,
                 connection_manager: Default::default(),
+                sync_state_sender: Default::default(),
                 mining_manager,
                 tick_service,
                 notification_root,
@@ -239,10 +253,114 @@ impl FlowContext {
         self.orphan_resolution_range
     }
 
+    /// The maximal blue score distance below a relayed orphan down to which its missing ancestors are requested via
+    /// the relay flow before falling back to IBD. Matches the depth covered by the block locator which is queried with
+    /// a limit of `orphan_resolution_range` in order to decide whether the orphan is within resolution range
+    pub fn max_orphan_ancestors_depth(&self) -> u64 {
+        2u64.pow(self.orphan_resolution_range)
+    }
+
+    /// The capability flags advertised by this node during the handshake
+    pub fn local_services(&self) -> ServiceFlags {
+        let mut services = ServiceFlags::DEFAULT | ServiceFlags::HEADER_RELAY;
+        if self.config.compact_block_relay {
+            services.insert(ServiceFlags::COMPACT_BLOCK_RELAY);
+        }
+        if self.config.headers_only {
+            // A header-only node can neither serve block bodies nor validate transactions, but still relays headers
+            // to header-only peers
+            services.remove(ServiceFlags::FULL_NODE | ServiceFlags::TX_RELAY | ServiceFlags::COMPACT_BLOCK_RELAY);
+        }
+        services
+    }
+
+    /// Returns the protocol version applied for a peer advertising `peer_protocol_version`, i.e., the
+    /// highest version supported by both sides, or `None` if the peer version is no longer supported
+    pub fn negotiate_protocol_version(peer_protocol_version: u32) -> Option<u32> {
+        let applied = peer_protocol_version.min(PROTOCOL_VERSION);
+        (applied >= MIN_PROTOCOL_VERSION).then_some(applied)
+    }
+
+    /// Builds the flows to be registered for a peer according to the negotiated protocol version
+    fn register_flows(&self, protocol_version: u32, router: Arc<Router>) -> Vec<Box<dyn Flow>> {
+        match protocol_version {
+            6 => v6::register(self.clone(), router),
+            5 => v5::register(self.clone(), router),
+            v => unreachable!("protocol version {v} was negotiated but has no registered flows"),
+        }
+    }
+
     pub fn start_async_services(&self) {
         if let Some(logger) = self.accepted_block_logger.as_ref() {
             logger.start();
         }
+        if self.config.stale_tip_timeout > 0 {
+            self.start_stale_tip_monitor();
+        }
+        self.start_bandwidth_report();
+    }
+
+    /// Periodically logs the P2P traffic exchanged since the node started, along with the peers exchanging the most
+    /// traffic since they connected. Must be called from an async tokio context
+    fn start_bandwidth_report(&self) {
+        let ctx = self.clone();
+        tokio::spawn(async move {
+            while let TickReason::Wakeup = ctx.tick_service.tick(BANDWIDTH_REPORT_INTERVAL).await {
+                let total = ctx.hub.bandwidth_by_message_type().into_iter().fold(Traffic::default(), |mut total, (_, traffic)| {
+                    total.merge(&traffic);
+                    total
+                });
+                let mut peers = ctx.hub.active_peers();
+                peers.sort_by_key(|peer| Reverse(peer.traffic().total_bytes()));
+                let heaviest_peers = peers
+                    .iter()
+                    .take(BANDWIDTH_REPORT_HEAVIEST_PEERS)
+                    .map(|peer| format!("{} ({:.2} MB)", peer.net_address(), peer.traffic().total_bytes() as f64 / 1_000_000.0))
+                    .join(", ");
+                info!(
+                    "P2P traffic: sent {:.2} MB, received {:.2} MB. Heaviest peers: {}",
+                    total.sent_bytes as f64 / 1_000_000.0,
+                    total.received_bytes as f64 / 1_000_000.0,
+                    if heaviest_peers.is_empty() { "none" } else { &heaviest_peers }
+                );
+            }
+        });
+    }
+
+    /// Periodically checks for a stale tip and, if detected, replaces the outbound peers with fresh ones.
+    /// Must be called from an async tokio context
+    fn start_stale_tip_monitor(&self) {
+        let ctx = self.clone();
+        tokio::spawn(async move {
+            while let TickReason::Wakeup = ctx.tick_service.tick(STALE_TIP_CHECK_INTERVAL).await {
+                // IBD keeps the sink behind until the synced segment is processed, and has timeouts of its own
+                if ctx.is_ibd_running() {
+                    continue;
+                }
+                let session = ctx.consensus().unguarded_session();
+                let sink_timestamp = session.async_get_sink_timestamp().await;
+                let virtual_daa_score = session.async_get_virtual_daa_score().await;
+                let active_routers = ctx.hub.active_routers();
+                for router in active_routers.iter() {
+                    ctx.resolve_pending_chain_claim(&session, router).await;
+                }
+                let peer_daa_scores = active_routers.iter().filter_map(|router| router.chain_claim()).map(|claim| claim.daa_score);
+                let Some(stale_tip) = ctx.stale_tip_monitor.check(sink_timestamp, virtual_daa_score, peer_daa_scores, unix_now())
+                else {
+                    continue;
+                };
+                warn!(
+                    "Stale tip detected: the sink timestamp did not advance for {} seconds while peers claim DAA score {} (local virtual DAA score {})",
+                    stale_tip.stalled_for / 1000,
+                    stale_tip.peer_daa_score,
+                    virtual_daa_score
+                );
+                if let Some(connection_manager) = ctx.connection_manager() {
+                    let rotated = connection_manager.rotate_outbound_peers().await;
+                    info!("Disconnected {} outbound peers in order to dial fresh peers", rotated);
+                }
+            }
+        });
     }
 
     pub fn set_connection_manager(&self, connection_manager: Arc<ConnectionManager>) {
@@ -257,6 +375,11 @@ impl FlowContext {
         self.connection_manager.read().clone()
     }
 
+    /// Sets the channel through which the sync state changes are reported
+    pub fn set_sync_state_sender(&self, sender: SyncStateSender) {
+        self.sync_state_sender.write().replace(sender);
+    }
+
     pub fn consensus(&self) -> ConsensusInstance {
         self.consensus_manager.consensus()
     }
@@ -269,25 +392,73 @@ impl FlowContext {
         &self.mining_manager
     }
 
-    pub fn try_set_ibd_running(&self, peer_key: PeerKey) -> Option<IbdRunningGuard> {
-        if self.is_ibd_running.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
-            self.ibd_peer_key.write().replace(peer_key);
-            Some(IbdRunningGuard { indicator: self.is_ibd_running.clone() })
-        } else {
-            None
+    /// Relay blocks and transactions recently processed via any peer
+    pub fn recent_inventory(&self) -> &RecentInventory {
+        &self.recent_inventory
+    }
+
+    pub fn block_validation_cache(&self) -> &BlockValidationCache {
+        &self.block_validation_cache
+    }
+
+    /// Records the result of validating the block `hash` in the validation cache and the block in the recent inventory.
+    /// A rule violation is recorded only if consensus persisted the block as invalid, since other violations (e.g. a bad
+    /// merkle root) are specific to the submitted body and say nothing about the block itself
+    pub async fn cache_block_validation_result(
+        &self,
+        consensus: &ConsensusProxy,
+        hash: Hash,
+        result: &BlockProcessResult<BlockStatus>,
+    ) {
+        match result {
+            Ok(_) => self.block_validation_cache.insert(hash, BlockValidationResult::Accepted),
+            Err(err) => {
+                if consensus.async_get_block_status(hash).await != Some(BlockStatus::StatusInvalid) {
+                    return;
+                }
+                self.block_validation_cache.insert(hash, BlockValidationResult::Invalid(err.clone()));
+            }
+        }
+        self.recent_inventory.add_block(hash);
+    }
+
+    pub fn network_time(&self) -> &NetworkTime {
+        &self.network_time
+    }
+
+    pub fn stale_tip_monitor(&self) -> &StaleTipMonitor {
+        &self.stale_tip_monitor
+    }
+
+    /// Turns the sink announced by the peer of `router` at handshake into a chain claim once its header is known
+    /// and valid locally, so that peers cannot claim arbitrary DAA scores
+    pub async fn resolve_pending_chain_claim(&self, consensus: &ConsensusProxy, router: &Router) {
+        let Some(sink) = router.pending_sink() else {
+            return;
+        };
+        if !consensus.async_get_block_status(sink).await.is_some_and(|status| status.has_block_header()) {
+            return;
+        }
+        if let Ok(header) = consensus.async_get_header(sink).await {
+            router.update_chain_claim(sink, header.daa_score);
         }
     }
 
+    /// Coordinates the IBD flows of all peers, see [`IbdManager`]
+    pub fn ibd_manager(&self) -> &Arc<IbdManager> {
+        &self.ibd_manager
+    }
+
+    pub fn try_set_ibd_running(&self, peer_key: PeerKey) -> Option<IbdRunningGuard> {
+        self.ibd_manager.try_acquire(peer_key)
+    }
+
     pub fn is_ibd_running(&self) -> bool {
-        self.is_ibd_running.load(Ordering::SeqCst)
+        self.ibd_manager.is_running()
     }
 
     pub fn ibd_peer_key(&self) -> Option<PeerKey> {
-        if self.is_ibd_running() {
-            *self.ibd_peer_key.read()
-        } else {
-            None
-        }
+        self.ibd_manager.running_peer()
     }
 
     fn try_adding_request_impl(req: Hash, map: &Arc<Mutex<HashMap<Hash, RequestScopeMetadata>>>) -> Option<RequestScope<Hash>> {
@@ -333,6 +504,10 @@ impl FlowContext {
         self.orphans_pool.read().await.is_known_orphan(hash)
     }
 
+    pub async fn get_orphan_blue_score(&self, orphan: Hash) -> Option<u64> {
+        self.orphans_pool.read().await.get_orphan_blue_score(orphan)
+    }
+
     pub async fn get_orphan_roots(&self, consensus: &ConsensusProxy, orphan: Hash) -> Option<Vec<Hash>> {
         self.orphans_pool.read().await.get_orphan_roots(consensus, orphan).await
     }
@@ -366,8 +541,20 @@ impl FlowContext {
             return Err(RuleError::NoTransactions)?;
         }
         let hash = block.hash();
-        let BlockValidationFutures { block_task, virtual_state_task } = consensus.validate_and_insert_block(block.clone());
-        if let Err(err) = block_task.await {
+        if let Some(BlockValidationResult::Invalid(err)) = self.block_validation_cache.get(hash) {
+            debug!("Block {} is known to be invalid: {}", hash, err);
+            return Err(err)?;
+        }
+        // Reject stale or malformed submissions immediately rather than after going through the processing queue
+        if let Err(err) = consensus.precheck_block(&block) {
+            debug!("Pre-validation failed for block {}: {}", hash, err);
+            return Err(err)?;
+        }
+        let BlockValidationFutures { block_task, virtual_state_task } =
+            consensus.validate_and_insert_block_with_origin(block.clone(), BlockOrigin::Rpc);
+        let result = block_task.await;
+        self.cache_block_validation_result(consensus, hash, &result).await;
+        if let Err(err) = result {
             warn!("Validation failed for block {}: {}", hash, err);
             return Err(err)?;
         }
@@ -378,20 +565,17 @@ impl FlowContext {
         let consensus = consensus.clone();
         tokio::spawn(async move {
             ctx.on_new_block(&consensus, block, virtual_state_task).await;
-            ctx.log_block_acceptance(hash, BlockSource::Submit);
+            ctx.log_block_acceptance(hash, BlockOrigin::Rpc);
         });
 
         Ok(())
     }
 
-    pub fn log_block_acceptance(&self, hash: Hash, source: BlockSource) {
+    pub fn log_block_acceptance(&self, hash: Hash, origin: BlockOrigin) {
         if let Some(logger) = self.accepted_block_logger.as_ref() {
-            logger.log(hash, source)
+            logger.log(hash, origin)
         } else {
-            match source {
-                BlockSource::Relay => info!("Accepted block {} via relay", hash),
-                BlockSource::Submit => info!("Accepted block {} via submit block", hash),
-            }
+            info!("Accepted block {} via {}", hash, origin);
         }
     }
 
@@ -399,8 +583,9 @@ impl FlowContext {
         self.accepted_block_logger.is_some()
     }
 
-    /// Updates the mempool after a new block arrival, relays newly unorphaned transactions
-    /// and possibly rebroadcast manually added transactions when not in IBD.
+    /// Updates the mempool after a new block arrival, returns transactions un-accepted by a reorg to the mempool,
+    /// relays newly unorphaned and returned transactions and possibly rebroadcast manually added transactions
+    /// when not in IBD.
     ///
     /// _GO-KASPAD: OnNewBlock + broadcastTransactionsAfterBlockAdded_
     pub async fn on_new_block(&self, consensus: &ConsensusProxy, block: Block, virtual_state_task: BlockValidationFuture) {
@@ -425,13 +610,27 @@ impl FlowContext {
             if let Ok(txs) = self
                 .mining_manager()
                 .clone()
-                .handle_new_block_transactions(consensus, block.header.daa_score, block.transactions.clone())
+                .handle_new_block_transactions(consensus, block.header.daa_score, block.header.timestamp, block.transactions.clone())
                 .await
             {
                 transactions_to_broadcast.enqueue_chunk(txs.into_iter().map(|x| x.id()));
             }
         }
 
+        // Return the transactions accepted by chain blocks a reorg removed from the selected chain, and not accepted
+        // by the new chain, to the mempool so they get mined again. This is skipped in IBD, where the chain changes
+        // between consecutive new blocks are arbitrarily long
+        if self.is_ibd_running() {
+            self.reorg_tracker.reset().await;
+        } else {
+            let unaccepted_transactions = self.reorg_tracker.unaccepted_transactions(consensus).await;
+            if !unaccepted_transactions.is_empty() {
+                info!("Returning {} transactions un-accepted by a reorg to the mempool", unaccepted_transactions.len());
+                let txs = self.mining_manager().clone().handle_unaccepted_transactions(consensus, unaccepted_transactions).await;
+                transactions_to_broadcast.enqueue_chunk(txs.into_iter().map(|x| x.id()));
+            }
+        }
+
         // Don't relay transactions when in IBD
         if self.is_ibd_running() {
             return;
@@ -473,6 +672,15 @@ impl FlowContext {
         let _ = self.notification_root.notify(Notification::PruningPointUtxoSetOverride(PruningPointUtxoSetOverrideNotification {}));
     }
 
+    /// Notifies that the synchronization state of the node changed along an IBD session.
+    pub fn on_sync_state_changed(&self, state: SyncState) {
+        debug!("Sync state changed: {}", state);
+        if let Some(sender) = self.sync_state_sender.read().as_ref() {
+            // The channel is unbounded so sending only fails once the subscriber is gone
+            let _ = sender.try_send(state);
+        }
+    }
+
     /// Notifies that a transaction has been added to the mempool.
     pub async fn on_transaction_added_to_mempool(&self) {
         // TODO: call a handler function or a predefined registered service
@@ -526,6 +734,11 @@ impl FlowContext {
 #[async_trait]
 impl ConnectionInitializer for FlowContext {
     async fn initialize_connection(&self, router: Arc<Router>) -> Result<(), ProtocolError> {
+        // Inbound onion peers are relayed by the local Tor daemon, so any other inbound peer is a clearnet one
+        if self.config.disable_clearnet && !router.is_outbound() && !router.net_address().ip().is_loopback() {
+            return Err(ProtocolError::OtherOwned(format!("inbound clearnet peer {router} refused since clearnet is disabled")));
+        }
+
         // Build the handshake object and subscribe to handshake messages
         let mut handshake = KaspadHandshake::new(&router);
 
@@ -538,9 +751,17 @@ impl ConnectionInitializer for FlowContext {
 
         // Build the local version message
         // Subnets are not currently supported
-        let mut self_version_message = Version::new(local_address, self.node_id, network_name.clone(), None, PROTOCOL_VERSION);
+        let local_services = self.local_services();
+        let mut self_version_message =
+            Version::new(local_address, self.node_id, network_name.clone(), None, PROTOCOL_VERSION, local_services);
         self_version_message.add_user_agent(name(), version(), &self.config.user_agent_comments);
-        // TODO: get number of live services
+        self_version_message.net_magic = self.config.net_magic();
+        // Let the peer know the address we see it through, which helps it detect its external address
+        self_version_message.observed_address = Some(router.net_address().into());
+        // Announce our sink so that the peer can tell how far along the chain we are
+        let session = self.consensus().unguarded_session();
+        let sink = session.async_get_sink().await;
+        self_version_message.sink = Some(sink);
         // TODO: disable_relay_tx from config/cmd
 
         // Perform the handshake
@@ -550,31 +771,52 @@ impl ConnectionInitializer for FlowContext {
 
         let peer_version: Version = peer_version_message.try_into()?;
         router.set_identity(peer_version.id);
-        // Avoid duplicate connections
-        if self.hub.has_peer(router.key()) {
-            return Err(ProtocolError::PeerAlreadyExists(router.key()));
-        }
-        // And loopback connections...
+        // Avoid loopback connections. Since the node id is persistent this also catches
+        // connections to ourselves through any of our external addresses
         if self.node_id == router.identity() {
             return Err(ProtocolError::LoopbackConnection(router.key()));
         }
+        // And duplicate connections, including ones to the same peer through a different address
+        if self.hub.has_peer(router.key()) || self.hub.has_peer_identity(router.identity()) {
+            return Err(ProtocolError::PeerAlreadyExists(router.key()));
+        }
 
+        // Older peers do not advertise a magic, in which case the network name alone is checked
+        let net_magic = self.config.net_magic();
+        if peer_version.net_magic != 0 && peer_version.net_magic != net_magic {
+            return Err(ProtocolError::WrongNetworkMagic(net_magic, peer_version.net_magic));
+        }
         if peer_version.network != network_name {
             return Err(ProtocolError::WrongNetwork(network_name, peer_version.network));
         }
 
         debug!("protocol versions - self: {}, peer: {}", PROTOCOL_VERSION, peer_version.protocol_version);
 
-        // Register all flows according to version
-        let (flows, applied_protocol_version) = match peer_version.protocol_version {
-            v if v >= PROTOCOL_VERSION => (v6::register(self.clone(), router.clone()), PROTOCOL_VERSION),
-            5 => (v5::register(self.clone(), router.clone()), 5),
-            v => return Err(ProtocolError::VersionMismatch(PROTOCOL_VERSION, v)),
+        // Negotiate the highest protocol version supported by both sides and register all flows according to it
+        let Some(applied_protocol_version) = Self::negotiate_protocol_version(peer_version.protocol_version) else {
+            return Err(ProtocolError::VersionMismatch(PROTOCOL_VERSION, peer_version.protocol_version));
         };
+        let flows = self.register_flows(applied_protocol_version, router.clone());
+
+        debug!("service flags - self: {}, peer: {}", local_services, peer_version.services);
+
+        if let Some(observed_address) = peer_version.observed_address {
+            self.address_manager.lock().add_external_address_observation(observed_address.ip, router.net_address().ip().into());
+        }
+
+        // Only peers passing the checks above take part in the network time estimation
+        self.network_time.add_peer_offset(time_offset);
+
+        // The peer sink is only taken into account once its header is validated locally
+        if let Some(sink) = peer_version.sink {
+            router.set_pending_sink(sink);
+            self.resolve_pending_chain_claim(&session, &router).await;
+        }
 
         // Build and register the peer properties
         let peer_properties = Arc::new(PeerProperties {
             user_agent: peer_version.user_agent.to_owned(),
+            services: peer_version.services,
             advertised_protocol_version: peer_version.protocol_version,
             protocol_version: applied_protocol_version,
             disable_relay_tx: peer_version.disable_relay_tx,
@@ -590,7 +832,7 @@ impl ConnectionInitializer for FlowContext {
 
         // Launch all flows. Note we launch only after the ready signal was exchanged
         for flow in flows {
-            flow.launch();
+            flow.launch(self.clone());
         }
 
         if router.is_outbound() || peer_version.address.is_some() {
@@ -1,3 +1,5 @@
+use crate::flow_context::FlowContext;
+use kaspa_consensus_core::errors::block::BanSeverity;
 use kaspa_core::warn;
 use kaspa_p2p_lib::{common::ProtocolError, Router};
 use kaspa_utils::any::type_name_short;
@@ -16,7 +18,7 @@ where
 
     async fn start(&mut self) -> Result<(), ProtocolError>;
 
-    fn launch(mut self: Box<Self>) {
+    fn launch(mut self: Box<Self>, ctx: FlowContext) {
         tokio::spawn(async move {
             let res = self.start().await;
             if let Err(err) = res {
@@ -25,6 +27,19 @@ where
                     if router.close().await || !err.is_connection_closed_error() {
                         warn!("{} flow error: {}, disconnecting from peer {}.", self.name(), err, router);
                     }
+                    if err.ban_severity() == BanSeverity::Ban {
+                        // Whitelisted peers are trusted by the operator, so they are only disconnected
+                        if let Some(connection_manager) =
+                            ctx.connection_manager().filter(|manager| !manager.is_whitelisted(router.net_address().ip()))
+                        {
+                            warn!(
+                                "Banning peer {} for relaying provably invalid data (rule code {:?})",
+                                router,
+                                err.rule_error_code()
+                            );
+                            connection_manager.ban(router.net_address().ip()).await;
+                        }
+                    }
                 }
             }
         });
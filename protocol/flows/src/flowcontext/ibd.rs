@@ -0,0 +1,144 @@
+use kaspa_p2p_lib::PeerKey;
+use parking_lot::Mutex;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::{futures::Notified, Notify};
+
+#[derive(Default)]
+struct IbdState {
+    /// The peer currently holding the IBD lock
+    running: Option<PeerKey>,
+
+    /// Peers which were triggered to perform IBD, mapped to the highest DAA score they claimed
+    candidates: HashMap<PeerKey, u64>,
+}
+
+/// Coordinates the IBD flows of all peers such that a single IBD session runs at any given time. When the lock
+/// is free, it is granted to the candidate peer claiming the highest DAA score, while candidates which lost
+/// the race wait for the lock to be released, so that a stalling IBD peer is seamlessly replaced by the next one
+#[derive(Default)]
+pub struct IbdManager {
+    state: Mutex<IbdState>,
+    released: Notify,
+}
+
+impl IbdManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `peer_key` as an IBD candidate claiming `daa_score`. A lower claim never overrides a higher one
+    pub fn register_candidate(&self, peer_key: PeerKey, daa_score: u64) {
+        let mut state = self.state.lock();
+        let claim = state.candidates.entry(peer_key).or_default();
+        *claim = (*claim).max(daa_score);
+    }
+
+    /// Withdraws the candidacy of `peer_key`, e.g., when the peer disconnects or its claim was already synced
+    pub fn remove_candidate(&self, peer_key: PeerKey) {
+        if self.state.lock().candidates.remove(&peer_key).is_some() {
+            // Candidates which deferred to this peer should reconsider
+            self.released.notify_waiters();
+        }
+    }
+
+    /// Attempts to acquire the IBD lock for `peer_key`. Fails if an IBD session is already running or if another
+    /// candidate claims a higher DAA score. Peers which are not candidates (e.g., for repairing quarantined blocks)
+    /// yield to any pending candidate
+    pub fn try_acquire(self: &Arc<Self>, peer_key: PeerKey) -> Option<IbdRunningGuard> {
+        let mut state = self.state.lock();
+        if state.running.is_some() {
+            return None;
+        }
+        let claim = state.candidates.get(&peer_key).copied();
+        if state.candidates.iter().any(|(key, &other)| *key != peer_key && claim.map_or(true, |claim| other > claim)) {
+            return None;
+        }
+        state.candidates.remove(&peer_key);
+        state.running = Some(peer_key);
+        Some(IbdRunningGuard { manager: self.clone() })
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state.lock().running.is_some()
+    }
+
+    pub fn running_peer(&self) -> Option<PeerKey> {
+        self.state.lock().running
+    }
+
+    /// Returns a future completing when the IBD lock is released or a candidate is withdrawn. Callers should
+    /// [`Notified::enable`] the future before attempting to acquire the lock in order to not miss a notification
+    pub fn released(&self) -> Notified<'_> {
+        self.released.notified()
+    }
+}
+
+/// Holds the IBD lock, releasing it and notifying the waiting candidates when dropped
+pub struct IbdRunningGuard {
+    manager: Arc<IbdManager>,
+}
+
+impl Drop for IbdRunningGuard {
+    fn drop(&mut self) {
+        let released = self.manager.state.lock().running.take();
+        assert!(released.is_some());
+        self.manager.released.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_utils::networking::PeerId;
+    use std::net::Ipv4Addr;
+
+    fn peer_key(id: u8) -> PeerKey {
+        PeerKey::new(PeerId::from_slice(&[id; 16]).unwrap(), Ipv4Addr::LOCALHOST.into())
+    }
+
+    #[test]
+    fn test_ibd_exclusivity_and_preference() {
+        let manager = Arc::new(IbdManager::new());
+        let (a, b, c) = (peer_key(1), peer_key(2), peer_key(3));
+
+        manager.register_candidate(a, 100);
+        manager.register_candidate(b, 200);
+        manager.register_candidate(b, 150);
+
+        // The highest claim wins the lock and the lock is exclusive
+        assert!(manager.try_acquire(a).is_none());
+        let guard = manager.try_acquire(b).expect("highest claim acquires the lock");
+        assert_eq!(manager.running_peer(), Some(b));
+        manager.register_candidate(c, 300);
+        assert!(manager.try_acquire(c).is_none());
+
+        // Non-candidates yield to pending candidates
+        drop(guard);
+        assert!(!manager.is_running());
+        assert!(manager.try_acquire(peer_key(4)).is_none());
+
+        manager.remove_candidate(c);
+        let guard = manager.try_acquire(a).expect("the only remaining candidate acquires the lock");
+        drop(guard);
+        assert!(manager.try_acquire(peer_key(4)).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_ibd_handoff_notification() {
+        let manager = Arc::new(IbdManager::new());
+        let (a, b) = (peer_key(1), peer_key(2));
+        manager.register_candidate(a, 200);
+        manager.register_candidate(b, 100);
+        let guard = manager.try_acquire(a).unwrap();
+
+        let released = manager.released();
+        tokio::pin!(released);
+        released.as_mut().enable();
+        assert!(manager.try_acquire(b).is_none());
+
+        // Dropping the lock of a stalled peer hands IBD off to the waiting candidate
+        drop(guard);
+        released.await;
+        assert!(manager.try_acquire(b).is_some());
+    }
+}
@@ -0,0 +1,206 @@
+use kaspa_consensus_core::tx::TransactionId;
+use kaspa_hashes::Hash;
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash as StdHash,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Max number of recently processed relay blocks to remember
+const MAX_RECENT_BLOCKS: usize = 8192;
+
+/// Max number of recently processed relay transactions to remember
+const MAX_RECENT_TRANSACTIONS: usize = 65536;
+
+/// A bounded set evicting its least recently used items
+struct LruSet<T: Copy + Eq + StdHash> {
+    capacity: usize,
+    /// Maps each item to the tick of its last use
+    ticks: HashMap<T, u64>,
+    /// Usage order. Entries whose tick is older than the one in `ticks` are stale and are skipped on eviction
+    order: VecDeque<(T, u64)>,
+    tick: u64,
+}
+
+impl<T: Copy + Eq + StdHash> LruSet<T> {
+    fn new(capacity: usize) -> Self {
+        Self { capacity, ticks: HashMap::with_capacity(capacity), order: VecDeque::with_capacity(capacity), tick: 0 }
+    }
+
+    /// Marks `item` as used and returns whether it was already present
+    fn touch(&mut self, item: T) -> bool {
+        self.tick += 1;
+        let existed = self.ticks.insert(item, self.tick).is_some();
+        self.order.push_back((item, self.tick));
+        while self.ticks.len() > self.capacity {
+            let (evicted, tick) = self.order.pop_front().expect("order holds an entry for each item");
+            if self.ticks.get(&evicted) == Some(&tick) {
+                self.ticks.remove(&evicted);
+            }
+        }
+        // Keep stale entries bounded when the same items are touched repeatedly
+        if self.order.len() > 2 * self.capacity {
+            let ticks = &self.ticks;
+            self.order.retain(|(item, tick)| ticks.get(item) == Some(tick));
+        }
+        existed
+    }
+
+    fn contains(&self, item: &T) -> bool {
+        self.ticks.contains_key(item)
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.ticks.len()
+    }
+}
+
+#[derive(Default)]
+pub struct RecentInventoryCounters {
+    pub block_hits: AtomicU64,
+    pub block_misses: AtomicU64,
+    pub transaction_hits: AtomicU64,
+    pub transaction_misses: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecentInventorySnapshot {
+    pub block_hits: u64,
+    pub block_misses: u64,
+    pub transaction_hits: u64,
+    pub transaction_misses: u64,
+}
+
+impl RecentInventoryCounters {
+    pub fn snapshot(&self) -> RecentInventorySnapshot {
+        RecentInventorySnapshot {
+            block_hits: self.block_hits.load(Ordering::Relaxed),
+            block_misses: self.block_misses.load(Ordering::Relaxed),
+            transaction_hits: self.transaction_hits.load(Ordering::Relaxed),
+            transaction_misses: self.transaction_misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Remembers relay blocks and transactions which were recently obtained and processed, shared by the relay flows
+/// of all peers. Consulting it on every inv prevents re-requesting and re-validating an object announced by
+/// several peers, including objects which were rejected and thus are known neither to consensus nor to the mempool
+pub struct RecentInventory {
+    blocks: Mutex<LruSet<Hash>>,
+    transactions: Mutex<LruSet<TransactionId>>,
+    counters: RecentInventoryCounters,
+}
+
+impl RecentInventory {
+    pub fn new() -> Self {
+        Self::with_capacity(MAX_RECENT_BLOCKS, MAX_RECENT_TRANSACTIONS)
+    }
+
+    pub fn with_capacity(max_blocks: usize, max_transactions: usize) -> Self {
+        Self {
+            blocks: Mutex::new(LruSet::new(max_blocks)),
+            transactions: Mutex::new(LruSet::new(max_transactions)),
+            counters: Default::default(),
+        }
+    }
+
+    /// Returns whether the block was recently processed, updating the hit/miss counters
+    pub fn contains_block(&self, hash: Hash) -> bool {
+        let mut blocks = self.blocks.lock();
+        if blocks.contains(&hash) {
+            blocks.touch(hash);
+            self.counters.block_hits.fetch_add(1, Ordering::Relaxed);
+            true
+        } else {
+            self.counters.block_misses.fetch_add(1, Ordering::Relaxed);
+            false
+        }
+    }
+
+    pub fn add_block(&self, hash: Hash) {
+        self.blocks.lock().touch(hash);
+    }
+
+    /// Filters out the transactions which were recently processed, updating the hit/miss counters
+    pub fn filter_unknown_transactions(&self, transaction_ids: Vec<TransactionId>) -> Vec<TransactionId> {
+        let total = transaction_ids.len();
+        let mut transactions = self.transactions.lock();
+        let unknown: Vec<_> = transaction_ids
+            .into_iter()
+            .filter(|id| {
+                if transactions.contains(id) {
+                    transactions.touch(*id);
+                    false
+                } else {
+                    true
+                }
+            })
+            .collect();
+        drop(transactions);
+        self.counters.transaction_hits.fetch_add((total - unknown.len()) as u64, Ordering::Relaxed);
+        self.counters.transaction_misses.fetch_add(unknown.len() as u64, Ordering::Relaxed);
+        unknown
+    }
+
+    pub fn add_transactions(&self, transaction_ids: impl IntoIterator<Item = TransactionId>) {
+        let mut transactions = self.transactions.lock();
+        for id in transaction_ids {
+            transactions.touch(id);
+        }
+    }
+
+    pub fn counters(&self) -> &RecentInventoryCounters {
+        &self.counters
+    }
+}
+
+impl Default for RecentInventory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_set_eviction() {
+        let mut set = LruSet::new(3);
+        for i in 0..3u64 {
+            assert!(!set.touch(i));
+        }
+        // Refresh the oldest item so that the next insertion evicts 1 instead
+        assert!(set.touch(0));
+        set.touch(3);
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&0));
+        assert!(!set.contains(&1));
+        assert!(set.contains(&2));
+        assert!(set.contains(&3));
+
+        // Repeated touches keep the order queue bounded
+        for _ in 0..100 {
+            set.touch(2);
+        }
+        assert!(set.order.len() <= 6);
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn test_recent_inventory_counters() {
+        let inventory = RecentInventory::with_capacity(2, 2);
+        let (a, b) = (Hash::from_u64_word(1), Hash::from_u64_word(2));
+        assert!(!inventory.contains_block(a));
+        inventory.add_block(a);
+        assert!(inventory.contains_block(a));
+
+        inventory.add_transactions([a]);
+        assert_eq!(inventory.filter_unknown_transactions(vec![a, b]), vec![b]);
+
+        let snapshot = inventory.counters().snapshot();
+        assert_eq!(snapshot, RecentInventorySnapshot { block_hits: 1, block_misses: 1, transaction_hits: 1, transaction_misses: 1 });
+    }
+}
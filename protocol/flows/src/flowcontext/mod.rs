@@ -1,3 +1,10 @@
+pub mod ibd;
+pub mod inventory;
+pub mod network_time;
 pub mod orphans;
 pub(crate) mod process_queue;
+pub mod reorg;
+pub mod stale_tip;
+pub mod sync_state;
 pub mod transactions;
+pub mod validation_cache;
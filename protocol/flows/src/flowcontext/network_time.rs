@@ -0,0 +1,148 @@
+use itertools::Itertools;
+use kaspa_core::{info, time::unix_now, warn};
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// The number of most recent offset samples kept per source
+const MAX_SAMPLES: usize = 64;
+
+/// The minimal number of offset samples of a source before its median is taken into account
+const MIN_SAMPLES: usize = 5;
+
+#[derive(Default)]
+struct OffsetSamples(VecDeque<i64>);
+
+impl OffsetSamples {
+    fn add(&mut self, offset: i64) {
+        if self.0.len() == MAX_SAMPLES {
+            self.0.pop_front();
+        }
+        self.0.push_back(offset);
+    }
+
+    fn median(&self) -> Option<i64> {
+        (self.0.len() >= MIN_SAMPLES).then(|| {
+            let sorted = self.0.iter().copied().sorted_unstable().collect_vec();
+            sorted[sorted.len() / 2]
+        })
+    }
+}
+
+#[derive(Default)]
+struct Samples {
+    peers: OffsetSamples,
+    blocks: OffsetSamples,
+}
+
+/// Estimates the offset of the local clock relative to the network from the timestamps sent by peers during the
+/// handshake and from the timestamps of freshly relayed blocks. Offsets are signed milliseconds, positive when the
+/// local clock is ahead of the network.
+///
+/// The local clock is considered drifted once either median offset exceeds `max_drift`. A drifted clock breaks
+/// header validation, since blocks of the network are rejected as being too far into the future of a late clock
+/// while blocks mined over an early clock are rejected by the network
+pub struct NetworkTime {
+    max_drift: u64,
+    samples: Mutex<Samples>,
+    is_drifted: AtomicBool,
+}
+
+impl NetworkTime {
+    pub fn new(max_drift: u64) -> Self {
+        Self { max_drift, samples: Default::default(), is_drifted: AtomicBool::new(false) }
+    }
+
+    /// Adds the offset measured against the timestamp of a peer handshake
+    pub fn add_peer_offset(&self, offset: i64) {
+        let mut samples = self.samples.lock();
+        samples.peers.add(offset);
+        self.update(&samples);
+    }
+
+    /// Adds the offset measured against the timestamp of a block relayed as soon as it was mined
+    pub fn add_block_timestamp(&self, timestamp: u64) {
+        let mut samples = self.samples.lock();
+        samples.blocks.add(unix_now() as i64 - timestamp as i64);
+        self.update(&samples);
+    }
+
+    /// The median offset measured against peer handshakes, if enough were sampled
+    pub fn peer_offset(&self) -> Option<i64> {
+        self.samples.lock().peers.median()
+    }
+
+    /// The median offset measured against relayed blocks, if enough were sampled
+    pub fn block_offset(&self) -> Option<i64> {
+        self.samples.lock().blocks.median()
+    }
+
+    pub fn is_clock_drifted(&self) -> bool {
+        self.is_drifted.load(Ordering::Relaxed)
+    }
+
+    fn update(&self, samples: &Samples) {
+        let drift = [samples.peers.median(), samples.blocks.median()]
+            .into_iter()
+            .flatten()
+            .find(|offset| offset.unsigned_abs() > self.max_drift);
+        let was_drifted = self.is_drifted.swap(drift.is_some(), Ordering::Relaxed);
+        match drift {
+            Some(offset) if !was_drifted => {
+                let direction = if offset > 0 { "ahead of" } else { "behind" };
+                warn!("===================================================================================================");
+                warn!(
+                    "The local clock is {:.1} seconds {} the network time. Blocks may be rejected until the system clock is synchronized",
+                    offset.unsigned_abs() as f64 / 1000.0,
+                    direction
+                );
+                warn!("===================================================================================================");
+            }
+            None if was_drifted => info!("The local clock is back in sync with the network time"),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_drift() {
+        let network_time = NetworkTime::new(10_000);
+        for _ in 0..MIN_SAMPLES - 1 {
+            network_time.add_peer_offset(60_000);
+        }
+        // Too few samples to conclude
+        assert_eq!(network_time.peer_offset(), None);
+        assert!(!network_time.is_clock_drifted());
+
+        network_time.add_peer_offset(-60_000);
+        assert_eq!(network_time.peer_offset(), Some(60_000));
+        assert!(network_time.is_clock_drifted());
+
+        // Outliers do not affect the median
+        for offset in [-1000, 0, 500, 1000, 2000, -2000, 100, 300, 400] {
+            network_time.add_peer_offset(offset);
+        }
+        assert_eq!(network_time.peer_offset(), Some(500));
+        assert!(!network_time.is_clock_drifted());
+
+        // Blocks timestamped far in the past of the local clock
+        for _ in 0..MIN_SAMPLES {
+            network_time.add_block_timestamp(unix_now() - 30_000);
+        }
+        assert!(network_time.block_offset().unwrap() >= 30_000);
+        assert!(network_time.is_clock_drifted());
+
+        // The oldest samples are evicted
+        for _ in 0..MAX_SAMPLES {
+            network_time.add_block_timestamp(unix_now());
+        }
+        assert!(network_time.block_offset().unwrap() < 10_000);
+        assert!(!network_time.is_clock_drifted());
+    }
+}
@@ -1,7 +1,7 @@
 use indexmap::{map::Entry::Occupied, IndexMap};
 use kaspa_consensus_core::{
     api::{BlockValidationFuture, BlockValidationFutures},
-    block::Block,
+    block::{Block, BlockOrigin},
 };
 use kaspa_consensusmanager::ConsensusProxy;
 use kaspa_core::debug;
@@ -68,6 +68,11 @@ impl OrphanBlocksPool {
         self.orphans.contains_key(&hash)
     }
 
+    /// Returns the blue score of the provided orphan, or `None` if it is not in the orphan pool
+    pub fn get_orphan_blue_score(&self, hash: Hash) -> Option<u64> {
+        self.orphans.get(&hash).map(|entry| entry.block.header.blue_score)
+    }
+
     /// Returns the orphan roots of the provided orphan. Orphan roots are ancestors of this orphan which are
     /// not in the orphan pool AND do not exist consensus-wise or are header-only. Given an orphan relayed by
     /// a peer, these blocks should be the next-in-line to be requested from that peer.
@@ -118,7 +123,7 @@ impl OrphanBlocksPool {
                 if processable {
                     let orphan_block = entry.remove();
                     let BlockValidationFutures { block_task, virtual_state_task } =
-                        consensus.validate_and_insert_block(orphan_block.block.clone());
+                        consensus.validate_and_insert_block_with_origin(orphan_block.block.clone(), BlockOrigin::Relay);
                     processing.insert(orphan_hash, (orphan_block.block, block_task, virtual_state_task));
                     process_queue.enqueue_chunk(orphan_block.children);
                 }
@@ -162,6 +167,10 @@ mod tests {
 
     impl ConsensusApi for MockProcessor {
         fn validate_and_insert_block(&self, block: Block) -> BlockValidationFutures {
+            self.validate_and_insert_block_with_origin(block, BlockOrigin::Local)
+        }
+
+        fn validate_and_insert_block_with_origin(&self, block: Block, _origin: BlockOrigin) -> BlockValidationFutures {
             self.processed.write().insert(block.hash());
             BlockValidationFutures { block_task: Box::pin(block_process_mock()), virtual_state_task: Box::pin(block_process_mock()) }
         }
@@ -188,6 +197,8 @@ mod tests {
         pool.add_orphan(d.clone());
 
         assert_eq!(pool.get_orphan_roots(&consensus, d.hash()).await.unwrap(), roots);
+        assert_eq!(pool.get_orphan_blue_score(d.hash()), Some(d.header.blue_score));
+        assert_eq!(pool.get_orphan_blue_score(a.hash()), None);
 
         consensus.validate_and_insert_block(a.clone()).virtual_state_task.await.unwrap();
         consensus.validate_and_insert_block(b.clone()).virtual_state_task.await.unwrap();
@@ -0,0 +1,149 @@
+use kaspa_consensus_core::{acceptance_data::AcceptanceData, tx::Transaction, tx::TransactionId};
+use kaspa_consensusmanager::ConsensusProxy;
+use kaspa_core::debug;
+use kaspa_hashes::Hash;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use tokio::sync::Mutex as AsyncMutex;
+
+/// Max number of chain blocks removed by a reorg whose accepted transactions are returned to the mempool. Deeper
+/// reorgs are handled only partially, starting from the removed chain blocks nearest to the former sink. Likewise,
+/// only the added chain blocks nearest to the common chain ancestor are checked for re-accepting the transactions,
+/// which is harmless since a transaction re-accepted further up the new chain fails validation against its state
+const MAX_REORG_CHAIN_BLOCKS: usize = 1000;
+
+/// Tracks the selected chain across new blocks in order to recover the transactions un-accepted by reorgs, i.e.,
+/// transactions accepted by chain blocks which left the selected chain and not accepted by the chain replacing them
+pub struct ReorgTracker {
+    /// The sink observed by the last call to `unaccepted_transactions`
+    last_sink: AsyncMutex<Option<Hash>>,
+}
+
+impl ReorgTracker {
+    pub fn new() -> Self {
+        Self { last_sink: AsyncMutex::new(None) }
+    }
+
+    /// Forgets the recorded sink, so that the following call only records the current sink. Meant for IBD, where the
+    /// sink advances along a long chain and no transactions are to be returned to the mempool
+    pub async fn reset(&self) {
+        self.last_sink.lock().await.take();
+    }
+
+    /// Returns the transactions un-accepted by the reorgs which occurred since the previous call, in acceptance order.
+    /// Calls are serialized, so a reorg is reported only once. The first call only records the current sink
+    pub async fn unaccepted_transactions(&self, consensus: &ConsensusProxy) -> Vec<Transaction> {
+        let mut last_sink = self.last_sink.lock().await;
+        let sink = consensus.async_get_sink().await;
+        let Some(previous_sink) = last_sink.replace(sink).filter(|&previous_sink| previous_sink != sink) else {
+            return vec![];
+        };
+        let chain_path = match consensus.async_get_virtual_chain_from_block(previous_sink).await {
+            Ok(chain_path) if !chain_path.removed.is_empty() => chain_path,
+            Ok(_) => return vec![],
+            Err(err) => {
+                // The former sink might have been pruned in the meantime
+                debug!("Failed to compute the chain changes since the former sink {}: {}", previous_sink, err);
+                return vec![];
+            }
+        };
+        if chain_path.removed.len() > MAX_REORG_CHAIN_BLOCKS {
+            debug!(
+                "A reorg removed {} chain blocks, recovering the transactions of the {} nearest to the former sink only",
+                chain_path.removed.len(),
+                MAX_REORG_CHAIN_BLOCKS
+            );
+        }
+        // Removed chain blocks are listed from the former sink downwards, so they are reversed into acceptance order
+        let removed = chain_path.removed.into_iter().take(MAX_REORG_CHAIN_BLOCKS).rev().collect();
+        let added = chain_path.added.into_iter().take(MAX_REORG_CHAIN_BLOCKS).collect();
+        let (Ok(removed_acceptance_data), Ok(added_acceptance_data)) =
+            (consensus.async_get_blocks_acceptance_data(removed).await, consensus.async_get_blocks_acceptance_data(added).await)
+        else {
+            debug!("Missing acceptance data of the chain blocks involved in a reorg from the former sink {}", previous_sink);
+            return vec![];
+        };
+
+        let mut transactions = Vec::new();
+        for (block_hash, indices) in unaccepted_transaction_locations(&removed_acceptance_data, &added_acceptance_data) {
+            match consensus.async_get_block(block_hash).await {
+                Ok(block) => transactions.extend(indices.into_iter().map(|index| block.transactions[index as usize].clone())),
+                Err(err) => debug!("Failed to get block {} holding un-accepted transactions: {}", block_hash, err),
+            }
+        }
+        transactions
+    }
+}
+
+impl Default for ReorgTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the blocks holding the non-coinbase transactions accepted in `removed` and not accepted in `added`, along
+/// with the indices of these transactions within each block, in acceptance order
+fn unaccepted_transaction_locations(removed: &[Arc<AcceptanceData>], added: &[Arc<AcceptanceData>]) -> Vec<(Hash, Vec<u32>)> {
+    let accepted: HashSet<TransactionId> = added
+        .iter()
+        .flat_map(|acceptance_data| acceptance_data.iter())
+        .flat_map(|mergeset_data| mergeset_data.accepted_transactions.iter().map(|entry| entry.transaction_id))
+        .collect();
+    let mut locations: Vec<(Hash, Vec<u32>)> = Vec::new();
+    let mut block_positions = HashMap::new();
+    let mut seen = HashSet::new();
+    for mergeset_data in removed.iter().flat_map(|acceptance_data| acceptance_data.iter()) {
+        for entry in mergeset_data.accepted_transactions.iter() {
+            // The coinbase transaction is always first and is specific to its chain block
+            if entry.index_within_block == 0 || accepted.contains(&entry.transaction_id) || !seen.insert(entry.transaction_id) {
+                continue;
+            }
+            let position = *block_positions.entry(mergeset_data.block_hash).or_insert_with(|| {
+                locations.push((mergeset_data.block_hash, Vec::new()));
+                locations.len() - 1
+            });
+            locations[position].1.push(entry.index_within_block);
+        }
+    }
+    locations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::acceptance_data::{AcceptedTxEntry, MergesetBlockAcceptanceData};
+
+    fn acceptance_data(mergeset: &[(u64, &[(u64, u32)])]) -> Arc<AcceptanceData> {
+        Arc::new(
+            mergeset
+                .iter()
+                .map(|&(block, entries)| MergesetBlockAcceptanceData {
+                    block_hash: block.into(),
+                    accepted_transactions: entries
+                        .iter()
+                        .map(|&(id, index)| AcceptedTxEntry { transaction_id: id.into(), index_within_block: index })
+                        .collect(),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_unaccepted_transaction_locations() {
+        let removed = vec![
+            acceptance_data(&[(1, &[(10, 0), (11, 1), (12, 2)]), (2, &[(20, 0), (12, 1)])]),
+            acceptance_data(&[(3, &[(30, 0), (31, 1), (32, 2)])]),
+        ];
+        // The new chain accepts transaction 11 as well, possibly out of another block
+        let added = vec![acceptance_data(&[(4, &[(40, 0), (11, 3)])])];
+
+        // Coinbase transactions are skipped, as are transactions accepted by the new chain, and a transaction accepted
+        // out of several blocks is reported once
+        let locations = unaccepted_transaction_locations(&removed, &added);
+        assert_eq!(locations, vec![(1.into(), vec![2]), (3.into(), vec![1, 2])]);
+
+        assert!(unaccepted_transaction_locations(&[], &added).is_empty());
+    }
+}
@@ -0,0 +1,89 @@
+use parking_lot::Mutex;
+
+/// Details of a detected stale tip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaleTip {
+    /// The time in milliseconds since the sink timestamp last advanced
+    pub stalled_for: u64,
+    /// The highest DAA score claimed by an active peer
+    pub peer_daa_score: u64,
+}
+
+struct State {
+    sink_timestamp: u64,
+    /// The local time at which the sink timestamp was last seen advancing
+    last_advance: u64,
+}
+
+/// Detects a stale tip, i.e., a sink timestamp which stopped advancing for `timeout` milliseconds while peers
+/// claim DAA scores above the local virtual, which usually indicates that the current peers are unable to
+/// serve the node with the blocks it is missing
+pub struct StaleTipMonitor {
+    timeout: u64,
+    state: Mutex<State>,
+}
+
+impl StaleTipMonitor {
+    pub fn new(timeout: u64, now: u64) -> Self {
+        Self { timeout, state: Mutex::new(State { sink_timestamp: 0, last_advance: now }) }
+    }
+
+    /// Checks whether the tip is stale at local time `now`, given the DAA scores claimed by the active peers.
+    /// Once a stale tip is reported the timeout starts over, giving fresh peers the chance to advance the tip
+    pub fn check(
+        &self,
+        sink_timestamp: u64,
+        virtual_daa_score: u64,
+        peer_daa_scores: impl IntoIterator<Item = u64>,
+        now: u64,
+    ) -> Option<StaleTip> {
+        let mut state = self.state.lock();
+        if sink_timestamp != state.sink_timestamp {
+            state.sink_timestamp = sink_timestamp;
+            state.last_advance = now;
+            return None;
+        }
+
+        let stalled_for = now.saturating_sub(state.last_advance);
+        if stalled_for < self.timeout {
+            return None;
+        }
+        let peer_daa_score = peer_daa_scores.into_iter().max().filter(|&score| score > virtual_daa_score)?;
+
+        state.last_advance = now;
+        Some(StaleTip { stalled_for, peer_daa_score })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_utils::networking::{IpAddress, PeerId};
+    use std::{net::Ipv4Addr, str::FromStr};
+
+    #[test]
+    fn test_stale_tip_detection() {
+        let monitor = StaleTipMonitor::new(1000, 0);
+        assert_eq!(monitor.check(100, 50, [], 0), None);
+
+        // A peer claims a higher DAA score but the timeout did not elapse yet
+        assert_eq!(monitor.check(100, 50, [60], 999), None);
+
+        // An advancing sink resets the timeout
+        assert_eq!(monitor.check(101, 50, [60], 1500), None);
+        assert_eq!(monitor.check(101, 50, [60], 2000), None);
+
+        // Peers not ahead of the local virtual do not indicate a stale tip
+        assert_eq!(monitor.check(101, 60, [60], 2500), None);
+
+        // The highest claim is reported
+        assert_eq!(monitor.check(101, 60, [60, 70, 65], 2500), Some(StaleTip { stalled_for: 1000, peer_daa_score: 70 }));
+
+        // The timeout starts over once a stale tip is reported
+        assert_eq!(monitor.check(101, 60, [80], 3000), None);
+        assert_eq!(monitor.check(101, 60, [80], 3500), Some(StaleTip { stalled_for: 1000, peer_daa_score: 80 }));
+
+        // Without active peers claiming a higher DAA score, nothing is reported
+        assert_eq!(monitor.check(101, 60, [], 5000), None);
+    }
+}
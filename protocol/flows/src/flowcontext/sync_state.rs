@@ -0,0 +1,39 @@
+use async_channel::Sender;
+use std::fmt::{Display, Formatter};
+
+/// A change of the synchronization state of the node, reported along the phases of an IBD session
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncState {
+    /// An IBD session started with a syncer peer
+    IbdStarted,
+    /// The pruning point proof and the trusted data of the pruning point are downloaded and applied
+    HeadersProof,
+    /// Block headers are downloaded
+    Headers,
+    /// The UTXO set of the pruning point is imported
+    UtxoSetImport,
+    /// Block bodies are downloaded
+    Bodies,
+    /// The IBD session completed, the node being synced with the syncer peer
+    IbdCompleted,
+    /// The IBD session failed, the next syncer candidate taking over if any
+    IbdFailed,
+}
+
+impl Display for SyncState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            SyncState::IbdStarted => "IBD started",
+            SyncState::HeadersProof => "headers proof",
+            SyncState::Headers => "headers",
+            SyncState::UtxoSetImport => "UTXO set import",
+            SyncState::Bodies => "bodies",
+            SyncState::IbdCompleted => "IBD completed",
+            SyncState::IbdFailed => "IBD failed",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Channel sender of the sync state changes
+pub type SyncStateSender = Sender<SyncState>;
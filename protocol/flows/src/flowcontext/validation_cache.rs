@@ -0,0 +1,104 @@
+use kaspa_consensus_core::errors::block::RuleError;
+use kaspa_hashes::Hash;
+use parking_lot::Mutex;
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+/// Max number of recent block validation results to remember
+const MAX_CACHED_RESULTS: usize = 8192;
+
+/// The final outcome of validating a block
+#[derive(Debug, Clone)]
+pub enum BlockValidationResult {
+    Accepted,
+    /// The block was rejected for the given reason
+    Invalid(RuleError),
+}
+
+struct CachedResults {
+    results: HashMap<Hash, BlockValidationResult>,
+    /// Insertion order, used for evicting the oldest results
+    order: VecDeque<Hash>,
+}
+
+/// Remembers the final validation results of recently submitted blocks, shared by the relay flows of all peers
+/// and by RPC submissions. A block resubmitted many times, e.g. an invalid block relayed by many peers, is then
+/// answered from the cache without being validated again, and each resubmission of an invalid block is treated
+/// as the original rule violation.
+///
+/// Only final results are cached: a rule violation is cached only once consensus persisted the block as invalid.
+/// Violations such as a bad merkle root or missing parents reject the body relayed by a peer rather than the block,
+/// so caching them under the header hash would let a single peer have the valid block rejected from all others
+pub struct BlockValidationCache {
+    capacity: usize,
+    inner: Mutex<CachedResults>,
+}
+
+impl BlockValidationCache {
+    pub fn new() -> Self {
+        Self::with_capacity(MAX_CACHED_RESULTS)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(CachedResults { results: HashMap::with_capacity(capacity), order: VecDeque::with_capacity(capacity) }),
+        }
+    }
+
+    pub fn get(&self, hash: Hash) -> Option<BlockValidationResult> {
+        self.inner.lock().results.get(&hash).cloned()
+    }
+
+    /// Records the final result of validating the block `hash`, unless a result is already known
+    pub fn insert(&self, hash: Hash, result: BlockValidationResult) {
+        let mut inner = self.inner.lock();
+        // A known result is never replaced since a later submission might only report `KnownInvalid`
+        if let Entry::Vacant(entry) = inner.results.entry(hash) {
+            entry.insert(result);
+            inner.order.push_back(hash);
+            while inner.order.len() > self.capacity {
+                let evicted = inner.order.pop_front().expect("order holds an entry for each result");
+                inner.results.remove(&evicted);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().results.is_empty()
+    }
+}
+
+impl Default for BlockValidationCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_validation_cache() {
+        let cache = BlockValidationCache::with_capacity(2);
+        let (a, b, c) = (Hash::from_u64_word(1), Hash::from_u64_word(2), Hash::from_u64_word(3));
+
+        // The original reason is kept over later submissions
+        cache.insert(a, BlockValidationResult::Invalid(RuleError::InvalidPoW));
+        cache.insert(a, BlockValidationResult::Invalid(RuleError::KnownInvalid));
+        assert!(matches!(cache.get(a), Some(BlockValidationResult::Invalid(RuleError::InvalidPoW))));
+
+        cache.insert(b, BlockValidationResult::Accepted);
+        assert!(matches!(cache.get(b), Some(BlockValidationResult::Accepted)));
+
+        // The oldest result is evicted
+        cache.insert(c, BlockValidationResult::Accepted);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(a).is_none());
+        assert!(cache.get(c).is_some());
+    }
+}
@@ -1,10 +1,15 @@
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 
+use ipnet::IpNet;
 use kaspa_addressmanager::NetAddress;
 use kaspa_connectionmanager::ConnectionManager;
 use kaspa_core::{
+    service::ServiceHealth,
     task::service::{AsyncService, AsyncServiceFuture},
-    trace,
+    trace, warn,
 };
 use kaspa_p2p_lib::Adaptor;
 use kaspa_utils::triggers::SingleTrigger;
@@ -20,12 +25,18 @@ pub struct P2pService {
     listen: NetAddress,
     outbound_target: usize,
     inbound_limit: usize,
+    whitelist: Vec<IpNet>,
+    reserved_inbound: usize,
     dns_seeders: &'static [&'static str],
     default_port: u16,
     shutdown: SingleTrigger,
+    /// Set once the P2P server listens and connects to peers
+    started: AtomicBool,
 }
 
 impl P2pService {
+    pub const IDENT: &'static str = P2P_CORE_SERVICE;
+
     pub fn new(
         flow_context: Arc<FlowContext>,
         connect_peers: Vec<NetAddress>,
@@ -33,6 +44,8 @@ impl P2pService {
         listen: NetAddress,
         outbound_target: usize,
         inbound_limit: usize,
+        whitelist: Vec<IpNet>,
+        reserved_inbound: usize,
         dns_seeders: &'static [&'static str],
         default_port: u16,
     ) -> Self {
@@ -44,8 +57,11 @@ impl P2pService {
             listen,
             outbound_target,
             inbound_limit,
+            whitelist,
+            reserved_inbound,
             dns_seeders,
             default_port,
+            started: AtomicBool::new(false),
         }
     }
 }
@@ -61,22 +77,39 @@ impl AsyncService for P2pService {
         // Prepare a shutdown signal receiver
         let shutdown_signal = self.shutdown.listener.clone();
 
-        let p2p_adaptor = Adaptor::bidirectional(self.listen, self.flow_context.hub().clone(), self.flow_context.clone()).unwrap();
-        let connection_manager = ConnectionManager::new(
-            p2p_adaptor.clone(),
-            self.outbound_target,
-            self.inbound_limit,
-            self.dns_seeders,
-            self.default_port,
-            self.flow_context.address_manager.clone(),
-        );
-
-        self.flow_context.set_connection_manager(connection_manager.clone());
-        self.flow_context.start_async_services();
-
         // Launch the service and wait for a shutdown signal
         Box::pin(async move {
+            // The node is not advertised to the network, i.e., neither listens nor connects to peers,
+            // before consensus is ready to serve them
+            let consensus_ready = self.flow_context.consensus_manager.ready_listener();
+            tokio::select! {
+                _ = consensus_ready => {}
+                _ = shutdown_signal.clone() => return Ok(()),
+            }
+
+            let proxy = self.flow_context.config.p2p_proxy.map(Into::into);
+            let p2p_adaptor =
+                Adaptor::bidirectional(self.listen, self.flow_context.hub().clone(), self.flow_context.clone(), proxy).unwrap();
+            let connection_manager = ConnectionManager::new(
+                p2p_adaptor.clone(),
+                self.outbound_target,
+                self.inbound_limit,
+                self.whitelist.clone(),
+                self.reserved_inbound,
+                self.dns_seeders,
+                self.default_port,
+                self.flow_context.address_manager.clone(),
+            );
+
+            self.flow_context.set_connection_manager(connection_manager.clone());
+            self.flow_context.start_async_services();
+            self.started.store(true, Ordering::SeqCst);
+
             for peer_address in self.connect_peers.iter().cloned().chain(self.add_peers.iter().cloned()) {
+                if self.flow_context.config.disable_clearnet && !peer_address.ip.is_onion() {
+                    warn!("Skipping requested peer {} since clearnet connections are disabled", peer_address);
+                    continue;
+                }
                 connection_manager.add_connection_request(peer_address.into(), true).await;
             }
 
@@ -102,4 +135,17 @@ impl AsyncService for P2pService {
             Ok(())
         })
     }
+
+    /// The service is degraded while it is not connected to any peer
+    fn health(self: Arc<Self>) -> ServiceHealth {
+        if self.shutdown.listener.is_triggered() {
+            ServiceHealth::Stopped
+        } else if !self.started.load(Ordering::SeqCst) {
+            ServiceHealth::Starting
+        } else if !self.flow_context.hub().has_peers() {
+            ServiceHealth::Degraded
+        } else {
+            ServiceHealth::Ready
+        }
+    }
 }
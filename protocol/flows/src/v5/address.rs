@@ -87,9 +87,20 @@ impl SendAddressesFlow {
     async fn start_impl(&mut self) -> Result<(), ProtocolError> {
         loop {
             dequeue!(self.incoming_route, Payload::RequestAddresses)?;
-            let addresses = self.ctx.address_manager.lock().iterate_addresses().collect_vec();
-            let address_list = addresses
-                .choose_multiple(&mut rand::thread_rng(), MAX_ADDRESSES_SEND)
+            let (local_address, addresses) = {
+                let mut amgr_lock = self.ctx.address_manager.lock();
+                (amgr_lock.best_local_address(), amgr_lock.iterate_addresses().collect_vec())
+            };
+            // Advertise our own external address (if known) so that peers can discover us as an inbound-capable node
+            let address_list = local_address
+                .into_iter()
+                .chain(
+                    addresses
+                        .choose_multiple(&mut rand::thread_rng(), MAX_ADDRESSES_SEND)
+                        .copied()
+                        .filter(|addr| Some(*addr) != local_address),
+                )
+                .take(MAX_ADDRESSES_SEND)
                 .map(|addr| (addr.ip, addr.port).into())
                 .collect();
             self.router.enqueue(make_message!(Payload::Addresses, AddressesMessage { address_list })).await?;
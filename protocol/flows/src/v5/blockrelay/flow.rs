@@ -1,15 +1,26 @@
 use crate::{
-    flow_context::{BlockSource, FlowContext, RequestScope},
+    flow_context::{FlowContext, RequestScope},
     flow_trait::Flow,
+    flowcontext::validation_cache::BlockValidationResult,
+};
+use kaspa_consensus_core::{
+    api::BlockValidationFutures,
+    block::{Block, BlockOrigin},
+    blockstatus::BlockStatus,
+    errors::block::RuleError,
 };
-use kaspa_consensus_core::{api::BlockValidationFutures, block::Block, blockstatus::BlockStatus, errors::block::RuleError};
 use kaspa_consensusmanager::ConsensusProxy;
 use kaspa_core::{debug, info};
 use kaspa_hashes::Hash;
+use kaspa_mining::model::tx_query::TransactionQuery;
 use kaspa_p2p_lib::{
     common::ProtocolError,
+    convert::model::{compact::CompactBlock, version::ServiceFlags},
     dequeue, dequeue_with_timeout, make_message, make_request,
-    pb::{kaspad_message::Payload, InvRelayBlockMessage, RequestBlockLocatorMessage, RequestRelayBlocksMessage},
+    pb::{
+        kaspad_message::Payload, InvRelayBlockMessage, RequestBlockLocatorMessage, RequestCompactBlockMessage,
+        RequestRelayBlocksMessage,
+    },
     IncomingRoute, Router, SharedIncomingRoute,
 };
 use kaspa_utils::channel::{JobSender, JobTrySendError as TrySendError};
@@ -17,13 +28,20 @@ use std::{collections::VecDeque, sync::Arc};
 
 pub struct RelayInvMessage {
     hash: Hash,
-    is_indirect: bool,
+    /// For indirect invs (i.e., orphan roots), the blue score of the relayed orphan whose resolution queued this inv
+    orphan_blue_score: Option<u64>,
+}
+
+impl RelayInvMessage {
+    fn is_indirect(&self) -> bool {
+        self.orphan_blue_score.is_some()
+    }
 }
 
 /// Encapsulates an incoming invs route which also receives data locally
 pub struct TwoWayIncomingRoute {
     incoming_route: SharedIncomingRoute,
-    indirect_invs: VecDeque<Hash>,
+    indirect_invs: VecDeque<(Hash, u64)>,
 }
 
 impl TwoWayIncomingRoute {
@@ -31,17 +49,17 @@ impl TwoWayIncomingRoute {
         Self { incoming_route, indirect_invs: VecDeque::new() }
     }
 
-    pub fn enqueue_indirect_invs<I: IntoIterator<Item = Hash>>(&mut self, iter: I) {
-        self.indirect_invs.extend(iter)
+    pub fn enqueue_indirect_invs<I: IntoIterator<Item = Hash>>(&mut self, iter: I, orphan_blue_score: u64) {
+        self.indirect_invs.extend(iter.into_iter().map(|hash| (hash, orphan_blue_score)))
     }
 
     pub async fn dequeue(&mut self) -> Result<RelayInvMessage, ProtocolError> {
-        if let Some(inv) = self.indirect_invs.pop_front() {
-            Ok(RelayInvMessage { hash: inv, is_indirect: true })
+        if let Some((inv, orphan_blue_score)) = self.indirect_invs.pop_front() {
+            Ok(RelayInvMessage { hash: inv, orphan_blue_score: Some(orphan_blue_score) })
         } else {
             let msg = dequeue!(self.incoming_route, Payload::InvRelayBlock)?;
             let inv = msg.try_into()?;
-            Ok(RelayInvMessage { hash: inv, is_indirect: false })
+            Ok(RelayInvMessage { hash: inv, orphan_blue_score: None })
         }
     }
 }
@@ -83,6 +101,32 @@ impl HandleRelayInvsFlow {
         loop {
             // Loop over incoming block inv messages
             let inv = self.invs_route.dequeue().await?;
+
+            // Header-only peers cannot serve block bodies, so a full node ignores their invs
+            let headers_only = self.ctx.config.headers_only;
+            if !headers_only && self.router.properties().is_header_only() {
+                debug!("Relay block {} announced by header-only peer {}, continuing...", inv.hash, self.router);
+                continue;
+            }
+
+            // A block announced by many peers is validated once, and announcing a block known to be invalid is
+            // treated as relaying it again
+            match self.ctx.block_validation_cache().get(inv.hash) {
+                Some(BlockValidationResult::Invalid(rule_error)) => return Err(rule_error.into()),
+                Some(BlockValidationResult::Accepted) => {
+                    debug!("Relay block {} was recently accepted, continuing...", inv.hash);
+                    continue;
+                }
+                None => {}
+            }
+
+            // Blocks recently accepted or found invalid via any peer need not be requested again. Orphan roots are
+            // exempt since they are queued exactly because they are still missing
+            if !inv.is_indirect() && self.ctx.recent_inventory().contains_block(inv.hash) {
+                debug!("Relay block {} was recently obtained, continuing...", inv.hash);
+                continue;
+            }
+
             let session = self.ctx.consensus().unguarded_session();
 
             match session.async_get_block_status(inv.hash).await {
@@ -99,7 +143,7 @@ impl HandleRelayInvsFlow {
             }
 
             if self.ctx.is_known_orphan(inv.hash).await {
-                self.enqueue_orphan_roots(&session, inv.hash).await;
+                self.enqueue_orphan_roots(&session, inv.hash, inv.orphan_blue_score).await;
                 continue;
             }
 
@@ -117,10 +161,23 @@ impl HandleRelayInvsFlow {
             };
             request_scope.report_obtained();
 
-            if block.is_header_only() {
+            if block.is_header_only() && !headers_only {
                 return Err(ProtocolError::OtherOwned(format!("sent header of {} where expected block with body", block.hash())));
             }
 
+            if let Some(orphan_blue_score) = inv.orphan_blue_score {
+                // The gap below the relayed orphan turned out deeper than expected, so we stop requesting
+                // its ancestors one by one and let IBD sync the missing segment
+                if orphan_blue_score.saturating_sub(block.header.blue_score) > self.ctx.max_orphan_ancestors_depth() {
+                    debug!("Missing ancestor {} is too deep below its relayed orphan, falling back to IBD", inv.hash);
+                    self.send_to_ibd(block)?;
+                    continue;
+                }
+            }
+
+            // Header-only nodes process the header alone and discard the body, if any
+            let block = if headers_only { Block::from_header_arc(block.header) } else { block };
+
             let blue_work_threshold = session.async_get_virtual_merge_depth_blue_work_threshold().await;
             // Since `blue_work` respects topology, the negation of this condition means that the relay
             // block is not in the future of virtual's merge depth root, and thus cannot be merged unless
@@ -129,7 +186,7 @@ impl HandleRelayInvsFlow {
 
             // We do not apply the skip heuristic below if inv was queued indirectly (as an orphan root), since
             // that means the process started by a proper and relevant relay block
-            if !inv.is_indirect && !broadcast {
+            if !inv.is_indirect() && !broadcast {
                 debug!(
                     "Relay block {} has lower blue work than virtual's merge depth root ({} <= {}), hence we are skipping it",
                     inv.hash, block.header.blue_work, blue_work_threshold
@@ -137,26 +194,45 @@ impl HandleRelayInvsFlow {
                 continue;
             }
 
-            let BlockValidationFutures { block_task, virtual_state_task } = session.validate_and_insert_block(block.clone());
+            let BlockValidationFutures { block_task, virtual_state_task } =
+                session.validate_and_insert_block_with_origin(block.clone(), BlockOrigin::Relay);
 
-            match block_task.await {
+            let result = block_task.await;
+            self.ctx.cache_block_validation_result(&session, inv.hash, &result).await;
+            match result {
                 Ok(_) => {}
                 Err(RuleError::MissingParents(missing_parents)) => {
                     debug!("Block {} is orphan and has missing parents: {:?}", block.hash(), missing_parents);
-                    self.process_orphan(&session, block, inv.is_indirect).await?;
+                    self.process_orphan(&session, block, inv.orphan_blue_score).await?;
                     continue;
                 }
                 Err(rule_error) => return Err(rule_error.into()),
             }
 
+            // The header was validated, so the peer is known to hold a chain reaching its DAA score
+            self.router.update_chain_claim(block.hash(), block.header.daa_score);
+
+            // Blocks relayed directly were just mined, so their timestamps sample the network time
+            if !inv.is_indirect() {
+                self.ctx.network_time().add_block_timestamp(block.header.timestamp);
+            }
+
             // As a policy, we only relay blocks who stand a chance to enter past(virtual).
             // The only mining rule which permanently excludes a block is the merge depth bound
             // (as opposed to "max parents" and "mergeset size limit" rules)
             if broadcast {
-                self.ctx
-                    .hub()
-                    .broadcast(make_message!(Payload::InvRelayBlock, InvRelayBlockMessage { hash: Some(inv.hash.into()) }))
-                    .await;
+                let msg = make_message!(Payload::InvRelayBlock, InvRelayBlockMessage { hash: Some(inv.hash.into()) });
+                match headers_only {
+                    // Header-only nodes relay headers to header-only peers alone, so that full peers are not sent
+                    // invs of blocks whose bodies cannot be served
+                    true => self.ctx.hub().broadcast_to(msg, |router| router.properties().is_header_only()).await,
+                    false => self.ctx.hub().broadcast(msg).await,
+                }
+            }
+
+            if headers_only {
+                self.ctx.log_block_acceptance(inv.hash, BlockOrigin::Relay);
+                continue;
             }
 
             // We spawn post-processing as a separate task so that this loop
@@ -164,12 +240,17 @@ impl HandleRelayInvsFlow {
             let ctx = self.ctx.clone();
             tokio::spawn(async move {
                 ctx.on_new_block(&session, block, virtual_state_task).await;
-                ctx.log_block_acceptance(inv.hash, BlockSource::Relay);
+                ctx.log_block_acceptance(inv.hash, BlockOrigin::Relay);
             });
         }
     }
 
-    async fn enqueue_orphan_roots(&mut self, consensus: &ConsensusProxy, orphan: Hash) {
+    /// Queues the missing ancestors of `orphan` as indirect invs. The depth of ancestors is measured relative to
+    /// `orphan_blue_score` if the orphan itself was queued indirectly, or otherwise relative to the orphan
+    async fn enqueue_orphan_roots(&mut self, consensus: &ConsensusProxy, orphan: Hash, orphan_blue_score: Option<u64>) {
+        let Some(orphan_blue_score) = orphan_blue_score.or(self.ctx.get_orphan_blue_score(orphan).await) else {
+            return;
+        };
         if let Some(roots) = self.ctx.get_orphan_roots(consensus, orphan).await {
             if roots.is_empty() {
                 return;
@@ -179,7 +260,7 @@ impl HandleRelayInvsFlow {
             } else {
                 info!("Block {} has {} missing ancestors. Adding them to the invs queue...", orphan, roots.len());
             }
-            self.invs_route.enqueue_indirect_invs(roots)
+            self.invs_route.enqueue_indirect_invs(roots, orphan_blue_score)
         }
     }
 
@@ -192,6 +273,12 @@ impl HandleRelayInvsFlow {
         let Some(request_scope) = self.ctx.try_adding_block_request(requested_hash) else {
             return Ok(None);
         };
+        if self.is_compact_relay_supported() {
+            if let Some(block) = self.request_compact_block(requested_hash, request_id).await? {
+                return Ok(Some((block, request_scope)));
+            }
+            debug!("Could not reconstruct compact block {} from the mempool, requesting the full block", requested_hash);
+        }
         self.router
             .enqueue(make_request!(
                 Payload::RequestRelayBlocks,
@@ -208,7 +295,44 @@ impl HandleRelayInvsFlow {
         }
     }
 
-    async fn process_orphan(&mut self, consensus: &ConsensusProxy, block: Block, is_indirect_inv: bool) -> Result<(), ProtocolError> {
+    /// Compact relay is used only if enabled locally and advertised by a peer which registered the request handling flow
+    fn is_compact_relay_supported(&self) -> bool {
+        let properties = self.router.properties();
+        self.ctx.config.compact_block_relay
+            && properties.protocol_version >= 6
+            && properties.services.contains(ServiceFlags::COMPACT_BLOCK_RELAY)
+    }
+
+    /// Requests the compact form of `requested_hash` and attempts to rebuild it from the mempool. Returns `None`
+    /// if reconstruction failed, in which case the full block should be requested
+    async fn request_compact_block(&mut self, requested_hash: Hash, request_id: u32) -> Result<Option<Block>, ProtocolError> {
+        self.router
+            .enqueue(make_request!(
+                Payload::RequestCompactBlock,
+                RequestCompactBlockMessage { hash: Some(requested_hash.into()) },
+                request_id
+            ))
+            .await?;
+        let msg = dequeue_with_timeout!(self.msg_route, Payload::CompactBlock)?;
+        let compact_block: CompactBlock = msg.try_into()?;
+        if compact_block.hash() != requested_hash {
+            return Err(ProtocolError::OtherOwned(format!(
+                "requested compact block {} but got block {}",
+                requested_hash,
+                compact_block.hash()
+            )));
+        }
+        let (transactions, _) = self.ctx.mining_manager().clone().get_all_transactions(TransactionQuery::TransactionsOnly).await;
+        Ok(compact_block.reconstruct(transactions.iter().map(|tx| tx.tx.as_ref())))
+    }
+
+    /// Processes an orphan block. `orphan_blue_score` is set if the block was queued indirectly as an ancestor of a relayed orphan
+    async fn process_orphan(
+        &mut self,
+        consensus: &ConsensusProxy,
+        block: Block,
+        orphan_blue_score: Option<u64>,
+    ) -> Result<(), ProtocolError> {
         // Return if the block has been orphaned from elsewhere already
         if self.ctx.is_known_orphan(block.hash()).await {
             return Ok(());
@@ -217,19 +341,23 @@ impl HandleRelayInvsFlow {
         // Add the block to the orphan pool if it's within orphan resolution range.
         // If the block is indirect it means one of its descendants was already is resolution range, so
         // we can avoid the query.
-        if is_indirect_inv || self.check_orphan_resolution_range(consensus, block.hash(), self.msg_route.id()).await? {
+        if orphan_blue_score.is_some() || self.check_orphan_resolution_range(consensus, block.hash(), self.msg_route.id()).await? {
             let hash = block.hash();
             self.ctx.add_orphan(block).await;
-            self.enqueue_orphan_roots(consensus, hash).await;
+            self.enqueue_orphan_roots(consensus, hash, orphan_blue_score).await;
+            Ok(())
         } else {
-            // Send the block to IBD flow via the dedicated job channel. If the channel has a pending job, we prefer
-            // the block with higher blue work, since it is usually more recent
-            match self.ibd_sender.try_send(block, |b, c| if b.header.blue_work > c.header.blue_work { b } else { c }) {
-                Ok(_) | Err(TrySendError::Full(_)) => {}
-                Err(TrySendError::Closed(_)) => return Err(ProtocolError::ConnectionClosed), // This indicates that IBD flow has exited
-            }
+            self.send_to_ibd(block)
+        }
+    }
+
+    /// Sends the block to IBD flow via the dedicated job channel. If the channel has a pending job, we prefer
+    /// the block with higher blue work, since it is usually more recent
+    fn send_to_ibd(&self, block: Block) -> Result<(), ProtocolError> {
+        match self.ibd_sender.try_send(block, |b, c| if b.header.blue_work > c.header.blue_work { b } else { c }) {
+            Ok(_) | Err(TrySendError::Full(_)) => Ok(()),
+            Err(TrySendError::Closed(_)) => Err(ProtocolError::ConnectionClosed), // This indicates that IBD flow has exited
         }
-        Ok(())
     }
 
     /// Finds out whether the given block hash should be retrieved via the unorphaning
@@ -1,7 +1,10 @@
 use crate::{flow_context::FlowContext, flow_trait::Flow};
+use kaspa_consensus_core::block::Block;
 use kaspa_core::debug;
+use kaspa_hashes::Hash;
 use kaspa_p2p_lib::{
     common::ProtocolError,
+    convert::model::compact::CompactBlock,
     dequeue_with_request_id, make_message, make_response,
     pb::{kaspad_message::Payload, InvRelayBlockMessage},
     IncomingRoute, Router,
@@ -40,9 +43,14 @@ impl HandleRelayBlockRequests {
             let hashes: Vec<_> = msg.try_into()?;
 
             let session = self.ctx.consensus().unguarded_session();
+            // Header-only peers are served headers alone, which is also all a header-only node can serve
+            let headers_only = self.ctx.config.headers_only || self.router.properties().is_header_only();
 
             for hash in hashes {
-                let block = session.async_get_block(hash).await?;
+                let block = match headers_only {
+                    true => Block::from_header_arc(session.async_get_header(hash).await?),
+                    false => session.async_get_block(hash).await?,
+                };
                 self.router.enqueue(make_response!(Payload::Block, (&block).into(), request_id)).await?;
                 debug!("relayed block with hash {} to peer {}", hash, self.router);
             }
@@ -50,6 +58,10 @@ impl HandleRelayBlockRequests {
     }
 
     async fn send_sink(&mut self) -> Result<(), ProtocolError> {
+        if self.ctx.config.headers_only {
+            // The body of the sink is unknown to header-only nodes
+            return Ok(());
+        }
         let sink = self.ctx.consensus().unguarded_session().async_get_sink().await;
         if sink == self.ctx.config.genesis.hash {
             return Ok(());
@@ -58,3 +70,39 @@ impl HandleRelayBlockRequests {
         Ok(())
     }
 }
+
+/// Serves relay blocks in compact form to peers which negotiated compact block relay
+pub struct HandleCompactBlockRequests {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for HandleCompactBlockRequests {
+    fn router(&self) -> Option<Arc<Router>> {
+        Some(self.router.clone())
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        self.start_impl().await
+    }
+}
+
+impl HandleCompactBlockRequests {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { ctx, router, incoming_route }
+    }
+
+    async fn start_impl(&mut self) -> Result<(), ProtocolError> {
+        loop {
+            let (msg, request_id) = dequeue_with_request_id!(self.incoming_route, Payload::RequestCompactBlock)?;
+            let hash: Hash = msg.try_into()?;
+
+            let block = self.ctx.consensus().unguarded_session().async_get_block(hash).await?;
+            let compact_block = CompactBlock::from_block(&block);
+            self.router.enqueue(make_response!(Payload::CompactBlock, (&compact_block).into(), request_id)).await?;
+            debug!("relayed compact block with hash {} to peer {}", hash, self.router);
+        }
+    }
+}
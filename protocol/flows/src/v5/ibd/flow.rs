@@ -1,5 +1,6 @@
 use crate::{
     flow_context::FlowContext,
+    flowcontext::sync_state::SyncState,
     v5::{
         ibd::{HeadersChunkStream, TrustedEntryStream},
         Flow,
@@ -8,9 +9,11 @@ use crate::{
 use futures::future::try_join_all;
 use kaspa_consensus_core::{
     api::BlockValidationFuture,
-    block::Block,
+    block::{Block, BlockOrigin},
+    errors::{consensus::ConsensusError, pruning::PruningImportError},
     header::Header,
     pruning::{PruningPointProof, PruningPointsList},
+    utxo::snapshot::UtxoSnapshotReader,
     BlockHashSet,
 };
 use kaspa_consensusmanager::{spawn_blocking, ConsensusProxy, StagingConsensus};
@@ -29,12 +32,19 @@ use kaspa_p2p_lib::{
 };
 use kaspa_utils::channel::JobReceiver;
 use std::{
+    fs::File,
+    io::BufReader,
+    path::PathBuf,
     sync::Arc,
     time::{Duration, Instant},
 };
+use tokio::time::timeout;
 
 use super::{progress::ProgressReporter, HeadersChunk, PruningPointUtxosetChunkStream, IBD_BATCH_SIZE};
 
+/// The interval at which an idle IBD flow checks for quarantined blocks to re-download
+const QUARANTINE_REPAIR_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Flow for managing IBD - Initial Block Download
 pub struct IbdFlow {
     pub(super) ctx: FlowContext,
@@ -64,26 +74,140 @@ pub enum IbdType {
 
 // TODO: define a peer banning strategy
 
+impl Drop for IbdFlow {
+    fn drop(&mut self) {
+        // A disconnected peer must not hold back the IBD of other candidates
+        self.ctx.ibd_manager().remove_candidate(self.router.key());
+    }
+}
+
 impl IbdFlow {
     pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute, relay_receiver: JobReceiver<Block>) -> Self {
         Self { ctx, router, incoming_route, relay_receiver }
     }
 
     async fn start_impl(&mut self) -> Result<(), ProtocolError> {
-        while let Ok(relay_block) = self.relay_receiver.recv().await {
-            if let Some(_guard) = self.ctx.try_set_ibd_running(self.router.key()) {
-                info!("IBD started with peer {}", self.router);
+        // A relay block whose IBD was deferred since another peer is running IBD or claims a higher DAA score
+        let mut pending: Option<Block> = None;
+        loop {
+            let ibd_manager = self.ctx.ibd_manager().clone();
+            let released = ibd_manager.released();
+            tokio::pin!(released);
+            released.as_mut().enable();
+
+            if let Some(relay_block) = pending.take() {
+                pending = self.try_ibd(relay_block).await?;
+            }
 
-                match self.ibd(relay_block).await {
-                    Ok(_) => info!("IBD with peer {} completed successfully", self.router),
-                    Err(e) => {
-                        info!("IBD with peer {} completed with error: {}", self.router, e);
-                        return Err(e);
+            tokio::select! {
+                result = timeout(QUARANTINE_REPAIR_INTERVAL, self.relay_receiver.recv()) => match result {
+                    Ok(Ok(relay_block)) => {
+                        // A newer relay block supersedes the deferred one unless the latter has more blue work
+                        pending = match pending {
+                            Some(deferred) if deferred.header.blue_work > relay_block.header.blue_work => Some(deferred),
+                            _ => Some(relay_block),
+                        };
                     }
+                    Ok(Err(_)) => break,
+                    Err(_) => {
+                        if pending.is_none() {
+                            self.repair_quarantined_blocks().await?;
+                        }
+                    }
+                },
+                // The IBD lock was released, possibly by a stalling peer, so a deferred IBD can be retried
+                _ = &mut released, if pending.is_some() => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs IBD triggered by `relay_block` if this peer acquires the IBD lock. Returns the relay block
+    /// back if IBD was deferred, in which case it should be retried once the lock is released
+    async fn try_ibd(&mut self, relay_block: Block) -> Result<Option<Block>, ProtocolError> {
+        let ibd_manager = self.ctx.ibd_manager().clone();
+        let peer_key = self.router.key();
+        let session = self.ctx.consensus().unguarded_session();
+        if session
+            .async_get_block_status(relay_block.hash())
+            .await
+            .is_some_and(|status| status.has_block_body() || self.ctx.config.headers_only)
+        {
+            // The claim was already synced in the meantime, possibly via IBD with another peer
+            ibd_manager.remove_candidate(peer_key);
+            return Ok(None);
+        }
+
+        // The peer may have claimed a higher DAA score than the one of the relay block, e.g., by its handshake sink
+        self.ctx.resolve_pending_chain_claim(&session, &self.router).await;
+        let claimed_daa_score = self.router.chain_claim().map_or(0, |claim| claim.daa_score);
+        ibd_manager.register_candidate(peer_key, relay_block.header.daa_score.max(claimed_daa_score));
+        let Some(_guard) = ibd_manager.try_acquire(peer_key) else {
+            debug!("IBD with peer {} was deferred to a running IBD or to a peer claiming a higher DAA score", self.router);
+            return Ok(Some(relay_block));
+        };
+
+        info!("IBD started with peer {}", self.router);
+        self.ctx.on_sync_state_changed(SyncState::IbdStarted);
+        match self.ibd(relay_block).await {
+            Ok(_) => {
+                info!("IBD with peer {} completed successfully", self.router);
+                self.ctx.on_sync_state_changed(SyncState::IbdCompleted);
+                Ok(None)
+            }
+            Err(e) => {
+                self.ctx.on_sync_state_changed(SyncState::IbdFailed);
+                if let ProtocolError::Timeout(_) = e {
+                    warn!("IBD peer {} stalled, handing IBD off to the next candidate peer", self.router);
                 }
+                info!("IBD with peer {} completed with error: {}", self.router, e);
+                Err(e)
             }
         }
+    }
 
+    /// Re-downloads the bodies of blocks quarantined by consensus due to corrupted or missing data. The
+    /// repair is mutually exclusive with IBD, so at most a single peer serves it at any given time
+    async fn repair_quarantined_blocks(&mut self) -> Result<(), ProtocolError> {
+        if self.ctx.config.headers_only {
+            return Ok(());
+        }
+        let session = self.ctx.consensus().session().await;
+        let hashes = session.async_get_quarantined_blocks().await;
+        if hashes.is_empty() {
+            return Ok(());
+        }
+        let Some(_guard) = self.ctx.try_set_ibd_running(self.router.key()) else {
+            return Ok(());
+        };
+
+        info!("Re-downloading {} quarantined blocks from peer {}", hashes.len(), self.router);
+        let mut repaired = 0;
+        for chunk in hashes.chunks(IBD_BATCH_SIZE) {
+            self.router
+                .enqueue(make_message!(
+                    Payload::RequestIbdBlocks,
+                    RequestIbdBlocksMessage { hashes: chunk.iter().map(|h| h.into()).collect() }
+                ))
+                .await?;
+            for &expected_hash in chunk {
+                let msg = dequeue_with_timeout!(self.incoming_route, Payload::IbdBlock)?;
+                let block: Block = msg.try_into()?;
+                if block.hash() != expected_hash {
+                    return Err(ProtocolError::OtherOwned(format!("expected block {} but got {}", expected_hash, block.hash())));
+                }
+                match session.async_repair_quarantined_block(block).await {
+                    Ok(()) => repaired += 1,
+                    // A body not matching the header merkle root is a protocol violation by the peer
+                    Err(err @ ConsensusError::BlockTransactionsMismatch(_)) => return Err(err.into()),
+                    // Other failures are local, e.g., the block was released from quarantine in the meantime, hence the
+                    // peer is not penalized and the remaining blocks are still received and repaired
+                    Err(err) => warn!("Failed repairing quarantined block {}: {}", expected_hash, err),
+                }
+            }
+        }
+        info!("Repaired {} of {} quarantined blocks with peer {}", repaired, hashes.len(), self.router);
         Ok(())
     }
 
@@ -124,7 +248,13 @@ impl IbdFlow {
             }
         }
 
+        if self.ctx.config.headers_only {
+            // Header-only nodes never download block bodies
+            return Ok(());
+        }
+
         // Sync missing bodies in the past of syncer sink (virtual selected parent)
+        self.ctx.on_sync_state_changed(SyncState::Bodies);
         self.sync_missing_block_bodies(&session, negotiation_output.syncer_virtual_selected_parent).await?;
 
         // Relay block might be in the antipast of syncer sink, thus
@@ -187,11 +317,21 @@ impl IbdFlow {
 
         let staging_session = staging.session().await;
 
+        self.ctx.on_sync_state_changed(SyncState::HeadersProof);
         let pruning_point = self.sync_and_validate_pruning_proof(&staging_session).await?;
         self.sync_headers(&staging_session, syncer_virtual_selected_parent, pruning_point, relay_block).await?;
         staging_session.async_validate_pruning_points().await?;
         self.validate_staging_timestamps(&self.ctx.consensus().session().await, &staging_session).await?;
-        self.sync_pruning_point_utxoset(&staging_session, pruning_point).await?;
+        if !self.ctx.config.headers_only {
+            self.ctx.on_sync_state_changed(SyncState::UtxoSetImport);
+            let imported = match self.ctx.config.utxo_snapshot_path.clone() {
+                Some(path) => self.import_pruning_point_utxoset_snapshot(&staging_session, pruning_point, path).await?,
+                None => false,
+            };
+            if !imported {
+                self.sync_pruning_point_utxoset(&staging_session, pruning_point).await?;
+            }
+        }
         Ok(())
     }
 
@@ -331,6 +471,7 @@ impl IbdFlow {
         highest_known_syncer_chain_hash: Hash,
         relay_block: &Block,
     ) -> Result<(), ProtocolError> {
+        self.ctx.on_sync_state_changed(SyncState::Headers);
         let highest_shared_header_score = consensus.async_get_header(highest_known_syncer_chain_hash).await?.daa_score;
         let mut progress_reporter = ProgressReporter::new(highest_shared_header_score, relay_block.header.daa_score, "block headers");
 
@@ -347,14 +488,20 @@ impl IbdFlow {
 
         if let Some(chunk) = chunk_stream.next().await? {
             let mut prev_daa_score = chunk.last().expect("chunk is never empty").daa_score;
-            let mut prev_jobs: Vec<BlockValidationFuture> =
-                chunk.into_iter().map(|h| consensus.validate_and_insert_block(Block::from_header_arc(h)).virtual_state_task).collect();
+            let mut prev_jobs: Vec<BlockValidationFuture> = chunk
+                .into_iter()
+                .map(|h| {
+                    consensus.validate_and_insert_block_with_origin(Block::from_header_arc(h), BlockOrigin::Ibd).virtual_state_task
+                })
+                .collect();
 
             while let Some(chunk) = chunk_stream.next().await? {
                 let current_daa_score = chunk.last().expect("chunk is never empty").daa_score;
                 let current_jobs = chunk
                     .into_iter()
-                    .map(|h| consensus.validate_and_insert_block(Block::from_header_arc(h)).virtual_state_task)
+                    .map(|h| {
+                        consensus.validate_and_insert_block_with_origin(Block::from_header_arc(h), BlockOrigin::Ibd).virtual_state_task
+                    })
                     .collect();
                 let prev_chunk_len = prev_jobs.len();
                 // Join the previous chunk so that we always concurrently process a chunk and receive another
@@ -402,8 +549,10 @@ impl IbdFlow {
 
         let msg = dequeue_with_timeout!(self.incoming_route, Payload::BlockHeaders)?;
         let chunk: HeadersChunk = msg.try_into()?;
-        let jobs: Vec<BlockValidationFuture> =
-            chunk.into_iter().map(|h| consensus.validate_and_insert_block(Block::from_header_arc(h)).virtual_state_task).collect();
+        let jobs: Vec<BlockValidationFuture> = chunk
+            .into_iter()
+            .map(|h| consensus.validate_and_insert_block_with_origin(Block::from_header_arc(h), BlockOrigin::Ibd).virtual_state_task)
+            .collect();
         try_join_all(jobs).await?;
         dequeue_with_timeout!(self.incoming_route, Payload::DoneHeaders)?;
 
@@ -459,6 +608,84 @@ staging selected tip ({}) is too small or negative. Aborting IBD...",
         Ok(())
     }
 
+    /// Imports the pruning point UTXO set from a local snapshot file. Returns `false` if the snapshot cannot be read,
+    /// belongs to another pruning point or does not match the UTXO commitment of the pruning point header, in which case
+    /// the set should be downloaded from the syncer instead. Such failures are local, so they never fail the flow, which
+    /// would otherwise disconnect an honest syncer
+    async fn import_pruning_point_utxoset_snapshot(
+        &self,
+        consensus: &ConsensusProxy,
+        pruning_point: Hash,
+        path: PathBuf,
+    ) -> Result<bool, ProtocolError> {
+        let reader = match File::open(&path).and_then(|file| UtxoSnapshotReader::new(BufReader::new(file))) {
+            Ok(reader) if reader.pruning_point() == pruning_point => reader,
+            Ok(reader) => {
+                warn!(
+                    "UTXO snapshot {} is of pruning point {} while the synced pruning point is {}, downloading the UTXO set from peer {} instead",
+                    path.display(),
+                    reader.pruning_point(),
+                    pruning_point,
+                    self.router
+                );
+                return Ok(false);
+            }
+            Err(err) => {
+                warn!(
+                    "Failed opening UTXO snapshot {}: {}, downloading the UTXO set from peer {} instead",
+                    path.display(),
+                    err,
+                    self.router
+                );
+                return Ok(false);
+            }
+        };
+
+        info!("Importing the UTXO set of pruning point {} from snapshot {}", pruning_point, path.display());
+        let read_result = consensus
+            .clone()
+            .spawn_blocking(move |c| {
+                let mut reader = reader;
+                let mut multiset = MuHash::new();
+                while let Some(chunk) = reader.next_chunk()? {
+                    c.append_imported_pruning_point_utxos(&chunk, &mut multiset);
+                }
+                Ok::<_, std::io::Error>(multiset)
+            })
+            .await;
+        let multiset = match read_result {
+            Ok(multiset) => multiset,
+            Err(err) => {
+                warn!(
+                    "Failed reading UTXO snapshot {}: {}, downloading the UTXO set from peer {} instead",
+                    path.display(),
+                    err,
+                    self.router
+                );
+                consensus.clone().spawn_blocking(|c| c.clear_imported_pruning_point_utxos()).await;
+                return Ok(false);
+            }
+        };
+        match consensus.clone().spawn_blocking(move |c| c.import_pruning_point_utxo_set(pruning_point, multiset)).await {
+            Ok(()) => Ok(true),
+            // The pruning point header was validated along with the proof, hence its UTXO commitment is authentic
+            // and a mismatch means the snapshot is corrupted
+            Err(PruningImportError::ImportedMultisetHashMismatch(expected, actual)) => {
+                warn!(
+                    "UTXO snapshot {} does not match the UTXO commitment {} of pruning point {} (got {}), downloading the UTXO set from peer {} instead",
+                    path.display(),
+                    expected,
+                    pruning_point,
+                    actual,
+                    self.router
+                );
+                consensus.clone().spawn_blocking(|c| c.clear_imported_pruning_point_utxos()).await;
+                Ok(false)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
     async fn sync_missing_block_bodies(&mut self, consensus: &ConsensusProxy, high: Hash) -> Result<(), ProtocolError> {
         // TODO: query consensus in batches
         let hashes = consensus.async_get_missing_block_body_hashes(high).await?;
@@ -515,7 +742,7 @@ staging selected tip ({}) is too small or negative. Aborting IBD...",
                 return Err(ProtocolError::OtherOwned(format!("sent header of {} where expected block with body", block.hash())));
             }
             current_daa_score = block.header.daa_score;
-            jobs.push(consensus.validate_and_insert_block(block).virtual_state_task);
+            jobs.push(consensus.validate_and_insert_block_with_origin(block, BlockOrigin::Ibd).virtual_state_task);
         }
 
         Ok((jobs, current_daa_score))
@@ -89,6 +89,11 @@ impl RelayTransactionsFlow {
                 return Err(ProtocolError::Other("Number of invs in tx inv message is over the limit"));
             }
 
+            // Header-only nodes hold no UTXO set to validate transactions against
+            if self.ctx.config.headers_only {
+                continue;
+            }
+
             let session = self.ctx.consensus().unguarded_session();
 
             // Transaction relay is disabled if the node is out of sync and thus not mining
@@ -105,8 +110,9 @@ impl RelayTransactionsFlow {
         &self,
         transaction_ids: Vec<TransactionId>,
     ) -> Result<Vec<RequestScope<TransactionId>>, ProtocolError> {
-        // Build a vector with the transaction ids unknown in the mempool and not already requested
-        // by another peer
+        // Build a vector with the transaction ids not recently processed, unknown in the mempool and
+        // not already requested by another peer
+        let transaction_ids = self.ctx.recent_inventory().filter_unknown_transactions(transaction_ids);
         let transaction_ids = self.ctx.mining_manager().clone().unknown_transactions(transaction_ids).await;
         let mut requests = Vec::new();
         for transaction_id in transaction_ids {
@@ -162,6 +168,7 @@ impl RelayTransactionsFlow {
         requests: Vec<RequestScope<TransactionId>>,
     ) -> Result<(), ProtocolError> {
         let mut transactions: Vec<Transaction> = Vec::with_capacity(requests.len());
+        let mut transaction_ids: Vec<TransactionId> = Vec::with_capacity(requests.len());
         for request in requests {
             let response = self.read_response().await?;
             let transaction_id = response.transaction_id();
@@ -172,6 +179,7 @@ impl RelayTransactionsFlow {
                 )));
             }
             if let Response::Transaction(transaction) = response {
+                transaction_ids.push(transaction_id);
                 transactions.push(transaction);
             }
         }
@@ -182,6 +190,9 @@ impl RelayTransactionsFlow {
             .validate_and_insert_transaction_batch(&consensus, transactions, Priority::Low, Orphan::Allowed)
             .await;
 
+        // Whether accepted or rejected, the transactions should not be validated again if announced by other peers
+        self.ctx.recent_inventory().add_transactions(transaction_ids);
+
         for res in insert_results.iter() {
             match res {
                 Ok(_) => {}
@@ -1,6 +1,10 @@
 use crate::{convert::error::ConversionError, core::peer::PeerKey, KaspadMessagePayloadType};
-use kaspa_consensus_core::errors::{block::RuleError, consensus::ConsensusError, pruning::PruningImportError};
-use kaspa_mining_errors::manager::MiningManagerError;
+use kaspa_consensus_core::errors::{
+    block::{BanSeverity, RuleError},
+    consensus::ConsensusError,
+    pruning::PruningImportError,
+};
+use kaspa_mining_errors::{manager::MiningManagerError, mempool::RuleError as MempoolRuleError};
 use std::time::Duration;
 use thiserror::Error;
 
@@ -18,6 +22,9 @@ pub enum ProtocolError {
     #[error("Network mismatch - local: {0}, remote: {1}")]
     WrongNetwork(String, String),
 
+    #[error("Network magic mismatch - local: {0:#010x}, remote: {1:#010x}")]
+    WrongNetworkMagic(u32, u32),
+
     #[error("expected message type/s {0} but got {1:?}")]
     UnexpectedMessage(&'static str, Option<KaspadMessagePayloadType>),
 
@@ -89,6 +96,26 @@ impl ProtocolError {
         !matches!(self, Self::ConnectionClosed | Self::OutgoingRouteCapacityReached(_))
     }
 
+    /// The stable code of the violated consensus rule, if the error originates from a block or transaction rule violation.
+    /// For blocks rejected due to an invalid transaction, the code of the transaction rule is returned
+    pub fn rule_error_code(&self) -> Option<u16> {
+        match self {
+            Self::RuleError(err) => Some(err.tx_rule_code().unwrap_or_else(|| err.code())),
+            Self::MiningManagerError(MiningManagerError::MempoolError(
+                MempoolRuleError::RejectTxRule(err) | MempoolRuleError::RejectImmatureSpend(err),
+            )) => Some(err.code()),
+            _ => None,
+        }
+    }
+
+    pub fn ban_severity(&self) -> BanSeverity {
+        match self {
+            Self::RuleError(err) => err.ban_severity(),
+            Self::ConnectionClosed => BanSeverity::None,
+            _ => BanSeverity::Disconnect,
+        }
+    }
+
     pub fn to_reject_message(&self) -> String {
         match self {
             Self::LoopbackConnection(_) => LOOPBACK_CONNECTION_MESSAGE.to_owned(),
@@ -1,6 +1,11 @@
-use super::{error::ConversionError, option::TryIntoOptionEx};
+use super::{
+    error::{check_limit, ConversionError},
+    model::compact::CompactBlock,
+    option::TryIntoOptionEx,
+};
 use crate::pb as protowire;
-use kaspa_consensus_core::{block::Block, tx::Transaction};
+use kaspa_consensus_core::{block::Block, config::constants::decoding::MAX_BLOCK_TRANSACTIONS, header::Header, tx::Transaction};
+use std::sync::Arc;
 
 // ----------------------------------------------------------------------------
 // consensus_core to protowire
@@ -12,6 +17,20 @@ impl From<&Block> for protowire::BlockMessage {
     }
 }
 
+impl From<&CompactBlock> for protowire::CompactBlockMessage {
+    fn from(block: &CompactBlock) -> Self {
+        Self {
+            header: Some(block.header.as_ref().into()),
+            short_ids: block.short_ids.clone(),
+            prefilled_transactions: block
+                .prefilled_transactions
+                .iter()
+                .map(|(index, tx)| protowire::PrefilledTransactionMessage { index: *index, transaction: Some(tx.into()) })
+                .collect(),
+        }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // protowire to consensus_core
 // ----------------------------------------------------------------------------
@@ -20,9 +39,28 @@ impl TryFrom<protowire::BlockMessage> for Block {
     type Error = ConversionError;
 
     fn try_from(block: protowire::BlockMessage) -> Result<Self, Self::Error> {
+        check_limit("block transactions", block.transactions.len(), MAX_BLOCK_TRANSACTIONS)?;
         Ok(Self::new(
             block.header.try_into_ex()?,
             block.transactions.into_iter().map(|i| i.try_into()).collect::<Result<Vec<Transaction>, Self::Error>>()?,
         ))
     }
 }
+
+impl TryFrom<protowire::CompactBlockMessage> for CompactBlock {
+    type Error = ConversionError;
+
+    fn try_from(block: protowire::CompactBlockMessage) -> Result<Self, Self::Error> {
+        check_limit("block transactions", block.short_ids.len() + block.prefilled_transactions.len(), MAX_BLOCK_TRANSACTIONS)?;
+        let header: Header = block.header.try_into_ex()?;
+        Ok(Self::new(
+            Arc::new(header),
+            block.short_ids,
+            block
+                .prefilled_transactions
+                .into_iter()
+                .map(|p| Ok((p.index, p.transaction.try_into_ex()?)))
+                .collect::<Result<Vec<(u32, Transaction)>, Self::Error>>()?,
+        ))
+    }
+}
@@ -25,4 +25,15 @@ pub enum ConversionError {
 
     #[error("{0}")]
     IdentityError(#[from] uuid::Error),
+
+    #[error("{0} of size {1} exceeds the decoding limit of {2}")]
+    LimitExceeded(&'static str, usize, usize),
+}
+
+/// Fails if `len` exceeds the decoding limit `max` of `item`
+pub(crate) fn check_limit(item: &'static str, len: usize, max: usize) -> Result<(), ConversionError> {
+    if len > max {
+        return Err(ConversionError::LimitExceeded(item, len, max));
+    }
+    Ok(())
 }
@@ -29,3 +29,11 @@ impl TryFrom<protowire::Hash> for Hash {
         Ok(Self::from_bytes(hash.bytes.as_slice().try_into()?))
     }
 }
+
+impl TryFrom<&protowire::Hash> for Hash {
+    type Error = ConversionError;
+
+    fn try_from(hash: &protowire::Hash) -> Result<Self, Self::Error> {
+        Ok(Self::from_bytes(hash.bytes.as_slice().try_into()?))
+    }
+}
@@ -1,8 +1,12 @@
 use crate::pb as protowire;
-use kaspa_consensus_core::{header::Header, BlueWorkType};
+use kaspa_consensus_core::{
+    config::constants::decoding::{MAX_HEADER_PARENTS, MAX_HEADER_PARENT_LEVELS},
+    header::Header,
+    BlueWorkType,
+};
 use kaspa_hashes::Hash;
 
-use super::error::ConversionError;
+use super::error::{check_limit, ConversionError};
 use super::option::TryIntoOptionEx;
 
 // ----------------------------------------------------------------------------
@@ -39,15 +43,27 @@ impl From<&Vec<Hash>> for protowire::BlockLevelParents {
 // protowire to consensus_core
 // ----------------------------------------------------------------------------
 
-impl TryFrom<protowire::BlockHeader> for Header {
+impl TryFrom<&protowire::BlockHeader> for Header {
     type Error = ConversionError;
-    fn try_from(item: protowire::BlockHeader) -> Result<Self, Self::Error> {
+    fn try_from(item: &protowire::BlockHeader) -> Result<Self, Self::Error> {
+        check_limit("header parent levels", item.parents.len(), MAX_HEADER_PARENT_LEVELS)?;
+        check_limit("header parents", item.parents.iter().map(|level| level.parent_hashes.len()).sum(), MAX_HEADER_PARENTS)?;
+        // Parents are allocated with their exact sizes, keeping the allocator pressure low when converting
+        // thousands of headers per message during IBD
+        let mut parents_by_level = Vec::with_capacity(item.parents.len());
+        for level in item.parents.iter() {
+            let mut parents = Vec::with_capacity(level.parent_hashes.len());
+            for hash in level.parent_hashes.iter() {
+                parents.push(Hash::try_from(hash)?);
+            }
+            parents_by_level.push(parents);
+        }
         Ok(Self::new_finalized(
             item.version.try_into()?,
-            item.parents.into_iter().map(Vec::<Hash>::try_from).collect::<Result<Vec<Vec<Hash>>, ConversionError>>()?,
-            item.hash_merkle_root.try_into_ex()?,
-            item.accepted_id_merkle_root.try_into_ex()?,
-            item.utxo_commitment.try_into_ex()?,
+            parents_by_level,
+            (&item.hash_merkle_root).try_into_ex()?,
+            (&item.accepted_id_merkle_root).try_into_ex()?,
+            (&item.utxo_commitment).try_into_ex()?,
             item.timestamp.try_into()?,
             item.bits,
             item.nonce,
@@ -55,14 +71,51 @@ impl TryFrom<protowire::BlockHeader> for Header {
             // We follow the golang specification of variable big-endian here
             BlueWorkType::from_be_bytes_var(&item.blue_work)?,
             item.blue_score,
-            item.pruning_point.try_into_ex()?,
+            (&item.pruning_point).try_into_ex()?,
         ))
     }
 }
 
+impl TryFrom<protowire::BlockHeader> for Header {
+    type Error = ConversionError;
+    fn try_from(item: protowire::BlockHeader) -> Result<Self, Self::Error> {
+        (&item).try_into()
+    }
+}
+
 impl TryFrom<protowire::BlockLevelParents> for Vec<Hash> {
     type Error = ConversionError;
     fn try_from(item: protowire::BlockLevelParents) -> Result<Self, Self::Error> {
         item.parent_hashes.into_iter().map(|x| x.try_into()).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> Header {
+        let parents = vec![(1..=10).map(Hash::from_u64_word).collect(), (11..=13).map(Hash::from_u64_word).collect()];
+        Header::new_finalized(1, parents, 2.into(), 3.into(), 4.into(), 5, 6, 7, 8, 9.into(), 10, 11.into())
+    }
+
+    #[test]
+    fn test_header_conversion() {
+        let header = header();
+        let wire = protowire::BlockHeader::from(&header);
+        let converted = Header::try_from(&wire).unwrap();
+        assert_eq!(converted.hash, header.hash);
+        assert_eq!(converted.parents_by_level, header.parents_by_level);
+        // Parents are allocated with their exact sizes
+        assert!(converted.parents_by_level.iter().all(|level| level.capacity() == level.len()));
+
+        let mut wire = wire;
+        wire.parents[1].parent_hashes[2].bytes.pop();
+        assert!(Header::try_from(&wire).is_err());
+
+        // Headers exceeding the decoding limits are rejected
+        let mut wire = protowire::BlockHeader::from(&header);
+        wire.parents.resize(MAX_HEADER_PARENT_LEVELS + 1, wire.parents[1].clone());
+        assert!(matches!(Header::try_from(&wire), Err(ConversionError::LimitExceeded(..))));
+    }
+}
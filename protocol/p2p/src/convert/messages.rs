@@ -2,7 +2,7 @@ use super::{
     error::ConversionError,
     model::{
         trusted::{TrustedDataEntry, TrustedDataPackage},
-        version::Version,
+        version::{ServiceFlags, Version},
     },
     option::TryIntoOptionEx,
 };
@@ -25,14 +25,17 @@ impl From<Version> for protowire::VersionMessage {
     fn from(item: Version) -> Self {
         Self {
             protocol_version: item.protocol_version,
-            services: item.services,
+            services: item.services.bits(),
             timestamp: item.timestamp as i64,
             address: item.address.map(|x| x.into()),
+            observed_address: item.observed_address.map(|x| x.into()),
             id: item.id.as_bytes().to_vec(),
             user_agent: item.user_agent,
             disable_relay_tx: item.disable_relay_tx,
             subnetwork_id: item.subnetwork_id.map(|x| x.into()),
             network: item.network.clone(),
+            sink: item.sink.map(|x| x.into()),
+            net_magic: item.net_magic,
         }
     }
 }
@@ -46,14 +49,17 @@ impl TryFrom<protowire::VersionMessage> for Version {
     fn try_from(msg: protowire::VersionMessage) -> Result<Self, Self::Error> {
         Ok(Self {
             protocol_version: msg.protocol_version,
-            services: msg.services,
+            services: ServiceFlags::from_bits(msg.services),
             timestamp: msg.timestamp as u64,
             address: if msg.address.is_none() { None } else { Some(msg.address.unwrap().try_into()?) },
+            observed_address: msg.observed_address.map(|x| x.try_into()).transpose()?,
             id: PeerId::from_slice(&msg.id)?,
             user_agent: msg.user_agent.clone(),
             disable_relay_tx: msg.disable_relay_tx,
             subnetwork_id: if msg.subnetwork_id.is_none() { None } else { Some(msg.subnetwork_id.unwrap().try_into()?) },
             network: msg.network.clone(),
+            sink: msg.sink.map(|x| x.try_into()).transpose()?,
+            net_magic: msg.net_magic,
         })
     }
 }
@@ -123,7 +129,11 @@ impl TryFrom<protowire::IbdChainBlockLocatorMessage> for Vec<Hash> {
 impl TryFrom<protowire::BlockHeadersMessage> for Vec<Arc<Header>> {
     type Error = ConversionError;
     fn try_from(msg: protowire::BlockHeadersMessage) -> Result<Self, Self::Error> {
-        msg.block_headers.into_iter().map(|v| v.try_into().map(Arc::new)).collect()
+        let mut headers = Vec::with_capacity(msg.block_headers.len());
+        for header in msg.block_headers.iter() {
+            headers.push(Arc::new(Header::try_from(header)?));
+        }
+        Ok(headers)
     }
 }
 
@@ -151,6 +161,14 @@ impl TryFrom<protowire::InvRelayBlockMessage> for Hash {
     }
 }
 
+impl TryFrom<protowire::RequestCompactBlockMessage> for Hash {
+    type Error = ConversionError;
+
+    fn try_from(msg: protowire::RequestCompactBlockMessage) -> Result<Self, Self::Error> {
+        msg.hash.try_into_ex()
+    }
+}
+
 impl TryFrom<protowire::RequestRelayBlocksMessage> for Vec<Hash> {
     type Error = ConversionError;
 
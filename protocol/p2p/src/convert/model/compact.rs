@@ -0,0 +1,151 @@
+//!
+//! Model structures related to compact block relay, where a block is announced via its header and
+//! short ids of its transactions, and is rebuilt by the receiver from its own mempool.
+//!
+
+use kaspa_consensus_core::{
+    block::Block,
+    header::Header,
+    merkle::calc_hash_merkle_root,
+    tx::{Transaction, TransactionId},
+};
+use kaspa_hashes::Hash;
+use std::{
+    borrow::Borrow,
+    collections::{hash_map::Entry, HashMap},
+    sync::Arc,
+};
+use xxhash_rust::xxh3::xxh3_64_with_seed;
+
+/// Returns the short id of `transaction_id` within the compact block `block_hash`. Short ids are seeded
+/// by the block hash so that ids colliding within one block are unlikely to collide within another
+pub fn short_transaction_id(block_hash: Hash, transaction_id: TransactionId) -> u64 {
+    xxh3_64_with_seed(&transaction_id.as_bytes(), block_hash.to_le_u64()[0])
+}
+
+pub struct CompactBlock {
+    pub header: Arc<Header>,
+    /// Short ids of all non-prefilled transactions, in block order
+    pub short_ids: Vec<u64>,
+    /// Transactions sent in full along with their index within the block
+    pub prefilled_transactions: Vec<(u32, Transaction)>,
+}
+
+impl CompactBlock {
+    pub fn new(header: Arc<Header>, short_ids: Vec<u64>, prefilled_transactions: Vec<(u32, Transaction)>) -> Self {
+        Self { header, short_ids, prefilled_transactions }
+    }
+
+    /// Builds the compact form of `block`. The coinbase transaction is always prefilled since it never
+    /// resides in the mempool of the receiver
+    pub fn from_block(block: &Block) -> Self {
+        let hash = block.hash();
+        let mut short_ids = Vec::with_capacity(block.transactions.len().saturating_sub(1));
+        let mut prefilled_transactions = Vec::new();
+        for (i, tx) in block.transactions.iter().enumerate() {
+            if tx.is_coinbase() {
+                prefilled_transactions.push((i as u32, tx.clone()));
+            } else {
+                short_ids.push(short_transaction_id(hash, tx.id()));
+            }
+        }
+        Self::new(block.header.clone(), short_ids, prefilled_transactions)
+    }
+
+    pub fn hash(&self) -> Hash {
+        self.header.hash
+    }
+
+    pub fn transaction_count(&self) -> usize {
+        self.short_ids.len() + self.prefilled_transactions.len()
+    }
+
+    /// Attempts to rebuild the full block from the prefilled transactions and the given `candidates` (usually
+    /// the mempool content). Returns `None` if any short id is missing or ambiguous among the candidates, or if
+    /// the rebuilt block does not match the header merkle root, in which case the full block should be requested.
+    pub fn reconstruct<T: Borrow<Transaction>>(&self, candidates: impl IntoIterator<Item = T>) -> Option<Block> {
+        let hash = self.hash();
+
+        // Map short ids to candidates, marking colliding ids as ambiguous (`None`)
+        let mut by_short_id: HashMap<u64, Option<T>> = HashMap::with_capacity(self.short_ids.len());
+        for tx in candidates {
+            match by_short_id.entry(short_transaction_id(hash, tx.borrow().id())) {
+                Entry::Occupied(mut e) => {
+                    e.insert(None);
+                }
+                Entry::Vacant(e) => {
+                    e.insert(Some(tx));
+                }
+            }
+        }
+
+        let count = self.transaction_count();
+        let mut slots: Vec<Option<Transaction>> = vec![None; count];
+        for (index, tx) in self.prefilled_transactions.iter() {
+            let slot = slots.get_mut(*index as usize)?;
+            if slot.is_some() {
+                return None;
+            }
+            *slot = Some(tx.clone());
+        }
+
+        let mut short_ids = self.short_ids.iter();
+        for slot in slots.iter_mut().filter(|slot| slot.is_none()) {
+            let short_id = short_ids.next()?;
+            *slot = Some(by_short_id.get(short_id)?.as_ref()?.borrow().clone());
+        }
+
+        let transactions = slots.into_iter().collect::<Option<Vec<_>>>()?;
+        if calc_hash_merkle_root(transactions.iter()) != self.header.hash_merkle_root {
+            return None;
+        }
+        Some(Block::from_arcs(self.header.clone(), Arc::new(transactions)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::{
+        subnets::{SUBNETWORK_ID_COINBASE, SUBNETWORK_ID_NATIVE},
+        tx::{TransactionInput, TransactionOutpoint},
+    };
+
+    fn transaction(seed: u64, subnetwork_id: kaspa_consensus_core::subnets::SubnetworkId) -> Transaction {
+        let input = TransactionInput::new(TransactionOutpoint::new(Hash::from_u64_word(seed), 0), vec![], 0, 0);
+        Transaction::new(0, vec![input], vec![], 0, subnetwork_id, 0, vec![])
+    }
+
+    fn block(transactions: Vec<Transaction>) -> Block {
+        let mut header = Header::from_precomputed_hash(Hash::from_u64_word(1), vec![]);
+        header.hash_merkle_root = calc_hash_merkle_root(transactions.iter());
+        Block::new(header, transactions)
+    }
+
+    #[test]
+    fn test_compact_block_reconstruction() {
+        let coinbase = transaction(0, SUBNETWORK_ID_COINBASE);
+        let txs = (1..=4).map(|i| transaction(i, SUBNETWORK_ID_NATIVE)).collect::<Vec<_>>();
+        let block = block(std::iter::once(coinbase).chain(txs.iter().cloned()).collect());
+        let compact = CompactBlock::from_block(&block);
+        assert_eq!(compact.prefilled_transactions.len(), 1);
+        assert_eq!(compact.short_ids.len(), 4);
+
+        // All transactions are known, including unrelated ones
+        let mut mempool = txs.clone();
+        mempool.push(transaction(5, SUBNETWORK_ID_NATIVE));
+        let rebuilt = compact.reconstruct(mempool.into_iter().rev()).unwrap();
+        assert_eq!(
+            rebuilt.transactions.iter().map(|tx| tx.id()).collect::<Vec<_>>(),
+            block.transactions.iter().map(|tx| tx.id()).collect::<Vec<_>>()
+        );
+
+        // A missing transaction fails the reconstruction
+        assert!(compact.reconstruct(txs.iter().skip(1).cloned()).is_none());
+
+        // A block not committing to the rebuilt transactions fails the reconstruction
+        let mut forged = CompactBlock::from_block(&block);
+        forged.short_ids.swap(0, 1);
+        assert!(forged.reconstruct(txs.iter().cloned()).is_none());
+    }
+}
@@ -3,21 +3,97 @@ use kaspa_core::{
     kaspad_env::{name, version},
     time::unix_now,
 };
+use kaspa_hashes::Hash;
 use kaspa_utils::networking::{NetAddress, PeerId};
 
 /// Maximum allowed length for the user agent field in a version message `VersionMessage`.
 pub const MAX_USER_AGENT_LEN: usize = 256;
 
+/// Capability flags advertised by a node through the `services` field of the version message.
+///
+/// Unknown bits are preserved as-is so that flags introduced by future versions can be
+/// deployed gradually without breaking older nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ServiceFlags(u64);
+
+impl ServiceFlags {
+    /// No special capabilities
+    pub const NONE: Self = Self(0);
+
+    /// The node stores and serves full blocks (headers, bodies and the pruning point UTXO set)
+    pub const FULL_NODE: Self = Self(1 << 0);
+
+    /// The node relays transactions
+    pub const TX_RELAY: Self = Self(1 << 1);
+
+    /// The node serves and requests relay blocks in compact form
+    pub const COMPACT_BLOCK_RELAY: Self = Self(1 << 2);
+
+    /// The node serves relay blocks as headers alone to peers advertising this flag without [`Self::FULL_NODE`],
+    /// i.e., to header-only peers, which in turn accept relay blocks without bodies
+    pub const HEADER_RELAY: Self = Self(1 << 3);
+
+    /// The default capabilities of a node running with the default configuration
+    pub const DEFAULT: Self = Self(Self::FULL_NODE.0 | Self::TX_RELAY.0);
+
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    pub const fn bits(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns `true` if all flags in `other` are set
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the flags set in both `self` and `other`, i.e., the capabilities which can be
+    /// used over a connection between two peers advertising `self` and `other` respectively
+    pub const fn intersection(&self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+}
+
+impl std::ops::BitOr for ServiceFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::fmt::Display for ServiceFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
 pub struct Version {
     pub protocol_version: u32,
     pub network: String,
-    pub services: u64, // TODO
+    pub services: ServiceFlags,
     pub timestamp: u64,
     pub address: Option<NetAddress>,
+    /// The address of the remote peer as observed locally. Allows nodes to learn their external address
+    pub observed_address: Option<NetAddress>,
     pub id: PeerId,
     pub user_agent: String,
     pub disable_relay_tx: bool,
     pub subnetwork_id: Option<SubnetworkId>,
+    /// The sink of the sender at handshake time
+    pub sink: Option<Hash>,
+    /// The magic number of the network of the sender, zero if not advertised
+    pub net_magic: u32,
 }
 
 impl Version {
@@ -27,17 +103,21 @@ impl Version {
         network: String,
         subnetwork_id: Option<SubnetworkId>,
         protocol_version: u32,
+        services: ServiceFlags,
     ) -> Self {
         Self {
             protocol_version,
             network,
-            services: 0, // TODO: get number of live services
+            services,
             timestamp: unix_now(),
             address,
+            observed_address: None,
             id,
             user_agent: format!("/{}:{}/", name(), version()),
             disable_relay_tx: false,
             subnetwork_id,
+            sink: None,
+            net_magic: 0,
         }
     }
 
@@ -48,3 +128,32 @@ impl Version {
         self.user_agent.truncate(MAX_USER_AGENT_LEN);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_flags() {
+        let flags = ServiceFlags::DEFAULT;
+        assert!(flags.contains(ServiceFlags::FULL_NODE));
+        assert!(flags.contains(ServiceFlags::TX_RELAY));
+        assert!(flags.contains(ServiceFlags::NONE));
+
+        // Unknown future flags must survive a round trip through the raw bits
+        let future = ServiceFlags::from_bits(1 << 63);
+        let peer = ServiceFlags::from_bits((flags | future).bits());
+        assert!(peer.contains(future));
+
+        // Negotiated capabilities are the ones supported by both sides
+        let negotiated = flags.intersection(ServiceFlags::FULL_NODE);
+        assert_eq!(negotiated, ServiceFlags::FULL_NODE);
+        assert!(!negotiated.contains(ServiceFlags::TX_RELAY));
+
+        let mut flags = flags;
+        flags.remove(ServiceFlags::TX_RELAY);
+        assert_eq!(flags, ServiceFlags::FULL_NODE);
+        flags.insert(ServiceFlags::TX_RELAY);
+        assert_eq!(flags, ServiceFlags::DEFAULT);
+    }
+}
@@ -1,6 +1,10 @@
-use super::{error::ConversionError, option::TryIntoOptionEx};
+use super::{
+    error::{check_limit, ConversionError},
+    option::TryIntoOptionEx,
+};
 use crate::pb as protowire;
 use kaspa_consensus_core::{
+    config::constants::decoding::{MAX_PAYLOAD_LEN, MAX_SCRIPT_LEN, MAX_TRANSACTION_INPUTS, MAX_TRANSACTION_OUTPUTS},
     subnets::SubnetworkId,
     tx::{ScriptPublicKey, Transaction, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry},
 };
@@ -95,6 +99,7 @@ impl TryFrom<protowire::ScriptPublicKey> for ScriptPublicKey {
     type Error = ConversionError;
 
     fn try_from(value: protowire::ScriptPublicKey) -> Result<Self, Self::Error> {
+        check_limit("script public key", value.script.len(), MAX_SCRIPT_LEN)?;
         Ok(Self::from_vec(value.version.try_into()?, value.script))
     }
 }
@@ -119,6 +124,7 @@ impl TryFrom<protowire::TransactionInput> for TransactionInput {
     type Error = ConversionError;
 
     fn try_from(value: protowire::TransactionInput) -> Result<Self, Self::Error> {
+        check_limit("signature script", value.signature_script.len(), MAX_SCRIPT_LEN)?;
         Ok(Self::new(value.previous_outpoint.try_into_ex()?, value.signature_script, value.sequence, value.sig_op_count.try_into()?))
     }
 }
@@ -135,6 +141,9 @@ impl TryFrom<protowire::TransactionMessage> for Transaction {
     type Error = ConversionError;
 
     fn try_from(tx: protowire::TransactionMessage) -> Result<Self, Self::Error> {
+        check_limit("transaction inputs", tx.inputs.len(), MAX_TRANSACTION_INPUTS)?;
+        check_limit("transaction outputs", tx.outputs.len(), MAX_TRANSACTION_OUTPUTS)?;
+        check_limit("transaction payload", tx.payload.len(), MAX_PAYLOAD_LEN)?;
         Ok(Self::new(
             tx.version.try_into()?,
             tx.inputs.into_iter().map(|i| i.try_into()).collect::<Result<Vec<TransactionInput>, Self::Error>>()?,
@@ -0,0 +1,110 @@
+use crate::KaspadMessagePayloadType;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// The number of messages and bytes exchanged in each direction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Traffic {
+    pub sent_messages: u64,
+    pub sent_bytes: u64,
+    pub received_messages: u64,
+    pub received_bytes: u64,
+}
+
+impl Traffic {
+    pub fn total_bytes(&self) -> u64 {
+        self.sent_bytes + self.received_bytes
+    }
+
+    pub fn merge(&mut self, other: &Traffic) {
+        self.sent_messages += other.sent_messages;
+        self.sent_bytes += other.sent_bytes;
+        self.received_messages += other.received_messages;
+        self.received_bytes += other.received_bytes;
+    }
+}
+
+/// Accounts the traffic exchanged with peers per P2P message type. The size of a message is the length of its
+/// protobuf encoding, excluding the gRPC and HTTP/2 framing
+#[derive(Debug, Default)]
+pub struct BandwidthCounters {
+    by_message_type: Mutex<HashMap<KaspadMessagePayloadType, Traffic>>,
+}
+
+impl BandwidthCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_sent(&self, message_type: KaspadMessagePayloadType, bytes: usize) {
+        let mut by_message_type = self.by_message_type.lock();
+        let traffic = by_message_type.entry(message_type).or_default();
+        traffic.sent_messages += 1;
+        traffic.sent_bytes += bytes as u64;
+    }
+
+    pub fn record_received(&self, message_type: KaspadMessagePayloadType, bytes: usize) {
+        let mut by_message_type = self.by_message_type.lock();
+        let traffic = by_message_type.entry(message_type).or_default();
+        traffic.received_messages += 1;
+        traffic.received_bytes += bytes as u64;
+    }
+
+    /// Adds the traffic accounted by `other` to the traffic accounted by `self`
+    pub fn merge(&self, other: &BandwidthCounters) {
+        let other = other.by_message_type();
+        let mut by_message_type = self.by_message_type.lock();
+        for (message_type, traffic) in other {
+            by_message_type.entry(message_type).or_default().merge(&traffic);
+        }
+    }
+
+    /// Returns the traffic of each message type exchanged so far
+    pub fn by_message_type(&self) -> Vec<(KaspadMessagePayloadType, Traffic)> {
+        self.by_message_type.lock().iter().map(|(message_type, traffic)| (*message_type, *traffic)).collect()
+    }
+
+    /// Returns the traffic of all message types
+    pub fn total(&self) -> Traffic {
+        self.by_message_type.lock().values().fold(Traffic::default(), |mut total, traffic| {
+            total.merge(traffic);
+            total
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bandwidth_counters() {
+        let peer = BandwidthCounters::new();
+        peer.record_received(KaspadMessagePayloadType::Block, 1000);
+        peer.record_received(KaspadMessagePayloadType::Block, 500);
+        peer.record_sent(KaspadMessagePayloadType::Ping, 8);
+        peer.record_received(KaspadMessagePayloadType::Pong, 8);
+
+        let traffic = peer.by_message_type().into_iter().collect::<HashMap<_, _>>();
+        assert_eq!(
+            traffic[&KaspadMessagePayloadType::Block],
+            Traffic { received_messages: 2, received_bytes: 1500, ..Default::default() }
+        );
+        assert_eq!(traffic[&KaspadMessagePayloadType::Ping], Traffic { sent_messages: 1, sent_bytes: 8, ..Default::default() });
+        assert_eq!(peer.total(), Traffic { sent_messages: 1, sent_bytes: 8, received_messages: 3, received_bytes: 1508 });
+        assert_eq!(peer.total().total_bytes(), 1516);
+
+        // Aggregating the traffic of several peers
+        let node = BandwidthCounters::new();
+        node.record_sent(KaspadMessagePayloadType::Block, 100);
+        node.merge(&peer);
+        node.merge(&peer);
+        let traffic = node.by_message_type().into_iter().collect::<HashMap<_, _>>();
+        assert_eq!(traffic.len(), 3);
+        assert_eq!(
+            traffic[&KaspadMessagePayloadType::Block],
+            Traffic { sent_messages: 1, sent_bytes: 100, received_messages: 4, received_bytes: 3000 }
+        );
+        assert_eq!(node.total().total_bytes(), 3132);
+    }
+}
@@ -1,13 +1,14 @@
 use crate::common::ProtocolError;
 use crate::core::hub::HubEvent;
+use crate::core::socks::{self, SocksTarget};
 use crate::pb::{
     p2p_client::P2pClient as ProtoP2pClient, p2p_server::P2p as ProtoP2p, p2p_server::P2pServer as ProtoP2pServer, KaspadMessage,
 };
 use crate::{ConnectionInitializer, Router};
 use futures::FutureExt;
 use kaspa_core::{debug, info};
-use kaspa_utils::networking::NetAddress;
-use std::net::ToSocketAddrs;
+use kaspa_utils::networking::{IpAddress, NetAddress};
+use std::net::{SocketAddr, ToSocketAddrs};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
@@ -16,14 +17,18 @@ use tokio::sync::mpsc::{channel as mpsc_channel, Sender as MpscSender};
 use tokio::sync::oneshot::{channel as oneshot_channel, Sender as OneshotSender};
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::StreamExt;
-use tonic::transport::{Error as TonicError, Server as TonicServer};
+use tonic::transport::{Endpoint, Error as TonicError, Server as TonicServer, Uri};
 use tonic::{Request, Response, Status as TonicStatus, Streaming};
+use tower::service_fn;
 
 #[derive(Error, Debug)]
 pub enum ConnectionError {
     #[error("missing socket address")]
     NoAddress,
 
+    #[error("onion address {0} can only be dialed through a proxy")]
+    OnionWithoutProxy(SocketAddr),
+
     #[error("{0}")]
     IoError(#[from] std::io::Error),
 
@@ -46,11 +51,17 @@ pub struct ConnectionHandler {
     /// Cloned on each new connection so that routers can communicate with a central hub
     hub_sender: MpscSender<HubEvent>,
     initializer: Arc<dyn ConnectionInitializer>,
+    /// An optional SOCKS5 proxy through which all outbound connections are dialed
+    proxy: Option<SocketAddr>,
 }
 
 impl ConnectionHandler {
-    pub(crate) fn new(hub_sender: MpscSender<HubEvent>, initializer: Arc<dyn ConnectionInitializer>) -> Self {
-        Self { hub_sender, initializer }
+    pub(crate) fn new(
+        hub_sender: MpscSender<HubEvent>,
+        initializer: Arc<dyn ConnectionInitializer>,
+        proxy: Option<SocketAddr>,
+    ) -> Self {
+        Self { hub_sender, initializer, proxy }
     }
 
     /// Launches a P2P server listener loop
@@ -85,12 +96,29 @@ impl ConnectionHandler {
         };
         let peer_address = format!("http://{}", peer_address); // Add scheme prefix as required by Tonic
 
-        let channel = tonic::transport::Endpoint::new(peer_address)?
+        let endpoint = Endpoint::new(peer_address)?
             .timeout(Duration::from_millis(Self::communication_timeout()))
             .connect_timeout(Duration::from_millis(Self::connect_timeout()))
-            .tcp_keepalive(Some(Duration::from_millis(Self::keep_alive())))
-            .connect()
-            .await?;
+            .tcp_keepalive(Some(Duration::from_millis(Self::keep_alive())));
+
+        let onion_host = IpAddress::from(socket_address.ip()).onion_host();
+        let channel = match self.proxy {
+            Some(proxy) => {
+                // Onion hosts are resolved by the proxy, other addresses are relayed as is
+                let target = match onion_host {
+                    Some(host) => SocksTarget::Domain(host, socket_address.port()),
+                    None => SocksTarget::Ip(socket_address),
+                };
+                endpoint
+                    .connect_with_connector(service_fn(move |_: Uri| {
+                        let target = target.clone();
+                        async move { socks::connect(proxy, &target).await }
+                    }))
+                    .await?
+            }
+            None if onion_host.is_some() => return Err(ConnectionError::OnionWithoutProxy(socket_address)),
+            None => endpoint.connect().await?,
+        };
 
         let mut client = ProtoP2pClient::new(channel)
             .send_compressed(tonic::codec::CompressionEncoding::Gzip)
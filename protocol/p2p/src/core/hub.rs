@@ -1,5 +1,6 @@
-use crate::{common::ProtocolError, pb::KaspadMessage, ConnectionInitializer, Peer, Router};
+use crate::{common::ProtocolError, pb::KaspadMessage, ConnectionInitializer, KaspadMessagePayloadType, Peer, Router};
 use kaspa_core::{debug, info, warn};
+use kaspa_utils::networking::PeerId;
 use parking_lot::RwLock;
 use std::{
     collections::{hash_map::Entry::Occupied, HashMap},
@@ -7,7 +8,10 @@ use std::{
 };
 use tokio::sync::mpsc::Receiver as MpscReceiver;
 
-use super::peer::PeerKey;
+use super::{
+    bandwidth::{BandwidthCounters, Traffic},
+    peer::PeerKey,
+};
 
 #[derive(Debug)]
 pub(crate) enum HubEvent {
@@ -22,11 +26,14 @@ pub struct Hub {
     ///
     /// Note: the map key holds the node id and IP to prevent node impersonating.
     pub(crate) peers: Arc<RwLock<HashMap<PeerKey, Arc<Router>>>>,
+
+    /// The traffic exchanged with peers which already disconnected
+    closed_peers_bandwidth: Arc<BandwidthCounters>,
 }
 
 impl Hub {
     pub fn new() -> Self {
-        Self { peers: Arc::new(RwLock::new(HashMap::new())) }
+        Self { peers: Arc::new(RwLock::new(HashMap::new())), closed_peers_bandwidth: Default::default() }
     }
 
     /// Starts a loop for receiving central hub events from all peer routers. This mechanism is used for
@@ -60,7 +67,10 @@ impl Hub {
                         }
                     }
                     HubEvent::PeerClosing(router) => {
-                        if let Occupied(entry) = self.peers.write().entry(router.key()) {
+                        let mut peers = self.peers.write();
+                        // Accounted under the same lock as the removal so that the aggregated traffic never misses the peer
+                        self.closed_peers_bandwidth.merge(router.bandwidth());
+                        if let Occupied(entry) = peers.entry(router.key()) {
                             // We search for the router by identity, but make sure to delete it only if it's actually the same object.
                             // This is extremely important in cases of duplicate connection rejection etc.
                             if Arc::ptr_eq(entry.get(), &router) {
@@ -103,6 +113,14 @@ impl Hub {
         }
     }
 
+    /// Broadcast a message to all peers satisfying `filter`
+    pub async fn broadcast_to(&self, msg: KaspadMessage, filter: impl Fn(&Router) -> bool) {
+        let peers = self.peers.read().values().filter(|router| filter(router)).cloned().collect::<Vec<_>>();
+        for router in peers {
+            let _ = router.enqueue(msg.clone()).await;
+        }
+    }
+
     /// Broadcast a vector of messages to all peers
     pub async fn broadcast_many(&self, msgs: Vec<KaspadMessage>) {
         if msgs.is_empty() {
@@ -138,6 +156,23 @@ impl Hub {
         self.peers.read().values().map(|r| r.as_ref().into()).collect()
     }
 
+    /// Returns the routers of all currently active peers
+    pub fn active_routers(&self) -> Vec<Arc<Router>> {
+        self.peers.read().values().cloned().collect()
+    }
+
+    /// Returns the traffic exchanged with all peers since the node started per message type, including the peers
+    /// which already disconnected
+    pub fn bandwidth_by_message_type(&self) -> Vec<(KaspadMessagePayloadType, Traffic)> {
+        let peers = self.peers.read();
+        let aggregate = BandwidthCounters::new();
+        aggregate.merge(&self.closed_peers_bandwidth);
+        for router in peers.values() {
+            aggregate.merge(router.bandwidth());
+        }
+        aggregate.by_message_type()
+    }
+
     /// Returns whether there are currently active peers
     pub fn has_peers(&self) -> bool {
         !self.peers.read().is_empty()
@@ -147,6 +182,11 @@ impl Hub {
     pub fn has_peer(&self, peer_key: PeerKey) -> bool {
         self.peers.read().contains_key(&peer_key)
     }
+
+    /// Returns whether a peer with node id `identity` is registered, regardless of the address it is connected through
+    pub fn has_peer_identity(&self, identity: PeerId) -> bool {
+        self.peers.read().values().any(|r| r.identity() == identity)
+    }
 }
 
 impl Default for Hub {
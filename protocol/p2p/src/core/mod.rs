@@ -1,6 +1,8 @@
 pub mod adaptor;
+pub mod bandwidth;
 pub mod connection_handler;
 pub mod hub;
 pub mod payload_type;
 pub mod peer;
 pub mod router;
+pub mod socks;
@@ -46,6 +46,8 @@ pub enum KaspadMessagePayloadType {
     IbdChainBlockLocator,
     RequestAntipast,
     RequestNextPruningPointAndItsAnticoneBlocks,
+    RequestCompactBlock,
+    CompactBlock,
 }
 
 impl From<&KaspadMessagePayload> for KaspadMessagePayloadType {
@@ -100,6 +102,8 @@ impl From<&KaspadMessagePayload> for KaspadMessagePayloadType {
             KaspadMessagePayload::RequestNextPruningPointAndItsAnticoneBlocks(_) => {
                 KaspadMessagePayloadType::RequestNextPruningPointAndItsAnticoneBlocks
             }
+            KaspadMessagePayload::RequestCompactBlock(_) => KaspadMessagePayloadType::RequestCompactBlock,
+            KaspadMessagePayload::CompactBlock(_) => KaspadMessagePayloadType::CompactBlock,
         }
     }
 }
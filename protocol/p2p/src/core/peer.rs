@@ -1,11 +1,15 @@
+use super::bandwidth::Traffic;
+use crate::convert::model::version::ServiceFlags;
 use kaspa_consensus_core::subnets::SubnetworkId;
+use kaspa_hashes::Hash;
 use kaspa_utils::networking::{IpAddress, PeerId};
 use std::{fmt::Display, net::SocketAddr, sync::Arc, time::Instant};
 
 #[derive(Debug, Clone, Default)]
 pub struct PeerProperties {
     pub user_agent: String,
-    // TODO: add services
+    /// The capability flags advertised by the peer
+    pub services: ServiceFlags,
     pub advertised_protocol_version: u32,
     pub protocol_version: u32,
     pub disable_relay_tx: bool,
@@ -13,6 +17,22 @@ pub struct PeerProperties {
     pub time_offset: i64,
 }
 
+impl PeerProperties {
+    /// Whether the peer is a header-only node which negotiated header relay. Such a peer is served relay blocks as
+    /// headers alone and cannot serve block bodies
+    pub fn is_header_only(&self) -> bool {
+        self.services.contains(ServiceFlags::HEADER_RELAY) && !self.services.contains(ServiceFlags::FULL_NODE)
+    }
+}
+
+/// The chain state claimed by a peer: the highest DAA score among the validated headers of the blocks it announced,
+/// either as its sink at handshake or by relaying them, along with the respective block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerChainClaim {
+    pub sink: Hash,
+    pub daa_score: u64,
+}
+
 #[derive(Debug)]
 pub struct Peer {
     identity: PeerId,
@@ -21,9 +41,12 @@ pub struct Peer {
     connection_started: Instant,
     properties: Arc<PeerProperties>,
     last_ping_duration: u64,
+    chain_claim: Option<PeerChainClaim>,
+    traffic: Traffic,
 }
 
 impl Peer {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         identity: PeerId,
         net_address: SocketAddr,
@@ -31,8 +54,10 @@ impl Peer {
         connection_started: Instant,
         properties: Arc<PeerProperties>,
         last_ping_duration: u64,
+        chain_claim: Option<PeerChainClaim>,
+        traffic: Traffic,
     ) -> Self {
-        Self { identity, net_address, is_outbound, connection_started, properties, last_ping_duration }
+        Self { identity, net_address, is_outbound, connection_started, properties, last_ping_duration, chain_claim, traffic }
     }
 
     /// Internal identity of this peer
@@ -65,6 +90,16 @@ impl Peer {
     pub fn last_ping_duration(&self) -> u64 {
         self.last_ping_duration
     }
+
+    /// The chain state claimed by this peer, if it announced any
+    pub fn chain_claim(&self) -> Option<PeerChainClaim> {
+        self.chain_claim
+    }
+
+    /// The traffic exchanged with this peer over all message types
+    pub fn traffic(&self) -> Traffic {
+        self.traffic
+    }
 }
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
@@ -90,3 +125,17 @@ impl Display for PeerKey {
         write!(f, "{}+{}", self.identity, self.ip)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_only_peer() {
+        let properties = |services| PeerProperties { services, ..Default::default() };
+        assert!(properties(ServiceFlags::HEADER_RELAY).is_header_only());
+        assert!(!properties(ServiceFlags::DEFAULT | ServiceFlags::HEADER_RELAY).is_header_only());
+        // Peers which did not negotiate header relay, e.g., older ones advertising no flags, are served full blocks
+        assert!(!properties(ServiceFlags::NONE).is_header_only());
+    }
+}
@@ -4,8 +4,10 @@ use crate::pb::{kaspad_message::Payload as KaspadMessagePayload, KaspadMessage};
 use crate::{common::ProtocolError, KaspadMessagePayloadType};
 use crate::{make_message, Peer};
 use kaspa_core::{debug, error, info, trace, warn};
+use kaspa_hashes::Hash;
 use kaspa_utils::networking::PeerId;
 use parking_lot::{Mutex, RwLock};
+use prost::Message;
 use seqlock::SeqLock;
 use std::fmt::{Debug, Display};
 use std::net::SocketAddr;
@@ -19,7 +21,8 @@ use tokio::sync::mpsc::{channel as mpsc_channel, Receiver as MpscReceiver, Sende
 use tokio::sync::oneshot::{channel as oneshot_channel, Sender as OneshotSender};
 use tonic::Streaming;
 
-use super::peer::{PeerKey, PeerProperties};
+use super::bandwidth::BandwidthCounters;
+use super::peer::{PeerChainClaim, PeerKey, PeerProperties};
 
 pub struct IncomingRoute {
     rx: MpscReceiver<KaspadMessage>,
@@ -102,6 +105,12 @@ struct RouterMutableState {
 
     /// Duration of the last ping to this peer
     last_ping_duration: u64,
+
+    /// The chain state claimed by this peer, as validated locally
+    chain_claim: Option<PeerChainClaim>,
+
+    /// The sink announced by this peer at handshake, as long as its header is unknown locally
+    pending_sink: Option<Hash>,
 }
 
 impl RouterMutableState {
@@ -139,6 +148,9 @@ pub struct Router {
 
     /// Used for managing router mutable state
     mutable_state: Mutex<RouterMutableState>,
+
+    /// The traffic exchanged with this peer per message type
+    bandwidth: BandwidthCounters,
 }
 
 impl Display for Router {
@@ -162,6 +174,8 @@ impl From<&Router> for Peer {
             router.connection_started,
             router.properties(),
             router.last_ping_duration(),
+            router.chain_claim(),
+            router.bandwidth.total(),
         )
     }
 }
@@ -193,6 +207,7 @@ impl Router {
             outgoing_route,
             hub_sender,
             mutable_state: Mutex::new(RouterMutableState::new(Some(start_sender), Some(shutdown_sender))),
+            bandwidth: BandwidthCounters::new(),
         });
 
         let router_clone = router.clone();
@@ -212,6 +227,9 @@ impl Router {
                     res = incoming_stream.message() => match res {
                         Ok(Some(msg)) => {
                             trace!("P2P msg: {:?}, router-id: {}, peer: {}", message_summary(&msg), router.identity(), router);
+                            if let Some(payload) = msg.payload.as_ref() {
+                                router.bandwidth.record_received(payload.into(), msg.encoded_len());
+                            }
                             match router.route_to_flow(msg) {
                                 Ok(()) => {},
                                 Err(e) => {
@@ -294,6 +312,37 @@ impl Router {
         self.mutable_state.lock().last_ping_duration
     }
 
+    /// Records that this peer claims to know `sink`, whose header was validated locally with DAA score `daa_score`.
+    /// A lower claim never overrides a higher one
+    pub fn update_chain_claim(&self, sink: Hash, daa_score: u64) {
+        let mut state = self.mutable_state.lock();
+        if state.pending_sink == Some(sink) {
+            state.pending_sink = None;
+        }
+        if state.chain_claim.map_or(true, |claim| daa_score > claim.daa_score) {
+            state.chain_claim = Some(PeerChainClaim { sink, daa_score });
+        }
+    }
+
+    /// Records the sink announced by this peer at handshake, which is only turned into a chain claim once its
+    /// header is validated locally
+    pub fn set_pending_sink(&self, sink: Hash) {
+        self.mutable_state.lock().pending_sink = Some(sink);
+    }
+
+    pub fn pending_sink(&self) -> Option<Hash> {
+        self.mutable_state.lock().pending_sink
+    }
+
+    pub fn chain_claim(&self) -> Option<PeerChainClaim> {
+        self.mutable_state.lock().chain_claim
+    }
+
+    /// The traffic exchanged with this peer per message type
+    pub fn bandwidth(&self) -> &BandwidthCounters {
+        &self.bandwidth
+    }
+
     pub fn incoming_flow_baseline_channel_size() -> usize {
         256
     }
@@ -401,9 +450,13 @@ impl Router {
 
     /// Enqueues a locally-originated message to be sent to the network peer
     pub async fn enqueue(&self, msg: KaspadMessage) -> Result<(), ProtocolError> {
-        assert!(msg.payload.is_some(), "Kaspad P2P message should always have a value");
+        let msg_type: KaspadMessagePayloadType = msg.payload.as_ref().expect("Kaspad P2P message should always have a value").into();
+        let msg_len = msg.encoded_len();
         match self.outgoing_route.try_send(msg) {
-            Ok(_) => Ok(()),
+            Ok(_) => {
+                self.bandwidth.record_sent(msg_type, msg_len);
+                Ok(())
+            }
             Err(TrySendError::Closed(_)) => Err(ProtocolError::ConnectionClosed),
             Err(TrySendError::Full(_)) => Err(ProtocolError::OutgoingRouteCapacityReached(self.to_string())),
         }
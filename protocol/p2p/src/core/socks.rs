@@ -0,0 +1,84 @@
+//! A minimal SOCKS5 client (RFC 1928) supporting unauthenticated CONNECT requests, as required for dialing peers
+//! through a Tor proxy
+
+use std::io::{Error, ErrorKind, Result};
+use std::net::{IpAddr, SocketAddr};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const SOCKS_VERSION: u8 = 5;
+const NO_AUTHENTICATION: u8 = 0;
+const CONNECT_COMMAND: u8 = 1;
+const SUCCEEDED_REPLY: u8 = 0;
+
+const IPV4_ADDRESS_TYPE: u8 = 1;
+const DOMAIN_ADDRESS_TYPE: u8 = 3;
+const IPV6_ADDRESS_TYPE: u8 = 4;
+
+/// The destination of a connection established through the proxy
+#[derive(Clone)]
+pub enum SocksTarget {
+    Ip(SocketAddr),
+    /// A host name resolved by the proxy, e.g., an onion service
+    Domain(String, u16),
+}
+
+/// Opens a TCP connection to `proxy` and asks it to connect to `target`. The returned stream is relayed to `target`
+pub async fn connect(proxy: SocketAddr, target: &SocksTarget) -> Result<TcpStream> {
+    let mut stream = TcpStream::connect(proxy).await?;
+
+    // Method negotiation
+    stream.write_all(&[SOCKS_VERSION, 1, NO_AUTHENTICATION]).await?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply != [SOCKS_VERSION, NO_AUTHENTICATION] {
+        return Err(Error::new(ErrorKind::Other, "SOCKS5 proxy requires an unsupported authentication method"));
+    }
+
+    // Connect request
+    let mut request = vec![SOCKS_VERSION, CONNECT_COMMAND, 0];
+    let port = match target {
+        SocksTarget::Ip(address) => {
+            match address.ip() {
+                IpAddr::V4(ip) => {
+                    request.push(IPV4_ADDRESS_TYPE);
+                    request.extend_from_slice(&ip.octets());
+                }
+                IpAddr::V6(ip) => {
+                    request.push(IPV6_ADDRESS_TYPE);
+                    request.extend_from_slice(&ip.octets());
+                }
+            }
+            address.port()
+        }
+        SocksTarget::Domain(host, port) => {
+            let length = u8::try_from(host.len()).map_err(|_| Error::new(ErrorKind::InvalidInput, "SOCKS5 host name is too long"))?;
+            request.push(DOMAIN_ADDRESS_TYPE);
+            request.push(length);
+            request.extend_from_slice(host.as_bytes());
+            *port
+        }
+    };
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+
+    // Connect reply: version, reply code, reserved byte and the bound address, which is skipped
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != SOCKS_VERSION {
+        return Err(Error::new(ErrorKind::InvalidData, "unexpected SOCKS version in proxy reply"));
+    }
+    if reply[1] != SUCCEEDED_REPLY {
+        return Err(Error::new(ErrorKind::ConnectionRefused, format!("SOCKS5 proxy failed connecting with reply code {}", reply[1])));
+    }
+    let address_length = match reply[3] {
+        IPV4_ADDRESS_TYPE => 4,
+        IPV6_ADDRESS_TYPE => 16,
+        DOMAIN_ADDRESS_TYPE => stream.read_u8().await? as usize,
+        address_type => return Err(Error::new(ErrorKind::InvalidData, format!("unknown SOCKS5 address type {address_type}"))),
+    };
+    let mut bound_address = vec![0u8; address_length + 2];
+    stream.read_exact(&mut bound_address).await?;
+
+    Ok(stream)
+}
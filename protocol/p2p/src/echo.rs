@@ -64,6 +64,8 @@ impl EchoFlow {
             KaspadMessagePayloadType::IbdChainBlockLocator,
             KaspadMessagePayloadType::RequestAntipast,
             KaspadMessagePayloadType::RequestNextPruningPointAndItsAnticoneBlocks,
+            KaspadMessagePayloadType::RequestCompactBlock,
+            KaspadMessagePayloadType::CompactBlock,
         ]);
         let mut echo_flow = EchoFlow { router, receiver };
         debug!("EchoFlow, start app-layer receiving loop");
@@ -96,11 +98,14 @@ fn build_dummy_version_message() -> VersionMessage {
         services: 0,
         timestamp: unix_now() as i64,
         address: None,
+        observed_address: None,
         id: Vec::from(Uuid::new_v4().as_ref()),
         user_agent: String::new(),
         disable_relay_tx: false,
         subnetwork_id: None,
         network: "kaspa-mainnet".to_string(),
+        sink: None,
+        net_magic: 0,
     }
 }
 
@@ -157,10 +162,10 @@ mod tests {
         kaspa_core::log::try_init_logger("debug");
 
         let address1 = NetAddress::from_str("[::1]:50053").unwrap();
-        let adaptor1 = Adaptor::bidirectional(address1, Hub::new(), Arc::new(EchoFlowInitializer::new())).unwrap();
+        let adaptor1 = Adaptor::bidirectional(address1, Hub::new(), Arc::new(EchoFlowInitializer::new()), None).unwrap();
 
         let address2 = NetAddress::from_str("[::1]:50054").unwrap();
-        let adaptor2 = Adaptor::bidirectional(address2, Hub::new(), Arc::new(EchoFlowInitializer::new())).unwrap();
+        let adaptor2 = Adaptor::bidirectional(address2, Hub::new(), Arc::new(EchoFlowInitializer::new()), None).unwrap();
 
         // Initiate the connection from `adaptor1` (outbound) to `adaptor2` (inbound)
         let peer2_id = adaptor1
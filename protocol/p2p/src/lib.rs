@@ -11,9 +11,10 @@ mod core;
 mod handshake;
 
 pub use crate::core::adaptor::{Adaptor, ConnectionInitializer};
+pub use crate::core::bandwidth::{BandwidthCounters, Traffic};
 pub use crate::core::connection_handler::ConnectionError;
 pub use crate::core::hub::Hub;
 pub use crate::core::payload_type::KaspadMessagePayloadType;
-pub use crate::core::peer::{Peer, PeerKey, PeerProperties};
+pub use crate::core::peer::{Peer, PeerChainClaim, PeerKey, PeerProperties};
 pub use crate::core::router::{IncomingRoute, Router, SharedIncomingRoute, BLANK_ROUTE_ID};
 pub use handshake::KaspadHandshake;
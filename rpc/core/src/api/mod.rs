@@ -1,4 +1,5 @@
 pub mod ctl;
 pub mod notifications;
 pub mod ops;
+pub mod profile;
 pub mod rpc;
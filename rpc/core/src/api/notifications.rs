@@ -5,7 +5,8 @@ use kaspa_notify::{
     events::EventType,
     notification::{full_featured, Notification as NotificationTrait},
     subscription::{
-        single::{OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
+        index::UtxosChangedIndex,
+        single::{AcceptedTransactionsSubscription, OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
         Subscription,
     },
 };
@@ -43,6 +44,15 @@ pub enum Notification {
 
     #[display(fmt = "NewBlockTemplate notification")]
     NewBlockTemplate(NewBlockTemplateNotification),
+
+    #[display(fmt = "MempoolTransactionRemoved notification: transaction {} ({:?})", "_0.transaction_id", "_0.reason")]
+    MempoolTransactionRemoved(MempoolTransactionRemovedNotification),
+
+    #[display(fmt = "AcceptedTransactions notification: {} accepting blocks", "_0.accepted_transactions.len()")]
+    AcceptedTransactions(AcceptedTransactionsNotification),
+
+    #[display(fmt = "SyncStateChanged notification: {:?}", "_0.state")]
+    SyncStateChanged(SyncStateChangedNotification),
 }
 }
 
@@ -54,6 +64,9 @@ impl Notification {
             Notification::FinalityConflict(v) => to_value(&v),
             Notification::FinalityConflictResolved(v) => to_value(&v),
             Notification::NewBlockTemplate(v) => to_value(&v),
+            Notification::MempoolTransactionRemoved(v) => to_value(&v),
+            Notification::AcceptedTransactions(v) => to_value(&v),
+            Notification::SyncStateChanged(v) => to_value(&v),
             Notification::PruningPointUtxoSetOverride(v) => to_value(&v),
             Notification::UtxosChanged(v) => to_value(&v),
             Notification::VirtualDaaScoreChanged(v) => to_value(&v),
@@ -99,6 +112,25 @@ impl NotificationTrait for Notification {
         }
     }
 
+    fn apply_accepted_transactions_subscription(&self, subscription: &AcceptedTransactionsSubscription) -> Option<Self> {
+        match subscription.active() {
+            true => {
+                let Self::AcceptedTransactions(notification) = self else { return None };
+                notification.apply_accepted_transactions_subscription(subscription).map(Self::AcceptedTransactions)
+            }
+            false => None,
+        }
+    }
+
+    fn apply_utxos_changed_index(&self, index: &UtxosChangedIndex) -> Vec<(usize, Self)> {
+        let Self::UtxosChanged(notification) = self else { return vec![] };
+        notification
+            .apply_utxos_changed_index(index)
+            .into_iter()
+            .map(|(position, applied)| (position, Self::UtxosChanged(applied)))
+            .collect()
+    }
+
     fn event_type(&self) -> EventType {
         self.into()
     }
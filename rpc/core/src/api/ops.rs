@@ -13,8 +13,24 @@ use workflow_core::enums::Describe;
 /// or using Serde attributes. This applies only to RPC infrastructure that uses internal
 /// data structures and does not affect gRPC. gRPC should issue and handle its
 /// own versioning.
-pub const RPC_API_VERSION: [u16; 4] = [0, 1, 0, 0];
+pub const RPC_API_VERSION: [u16; 4] = [0, 1, 1, 0];
 
+/// The RPC API version preceding the wRPC handshake, which serves the ops preceding [`RpcApiOps::Handshake`]
+const RPC_API_VERSION_PRE_HANDSHAKE: [u16; 4] = [0, 1, 0, 0];
+
+/// Negotiates the version of the messages exchanged over a wRPC connection between two
+/// peers speaking `local` and `remote` versions of the RPC API.
+///
+/// Peers of the same major version agree on the lowest of both versions, so a node keeps
+/// serving older clients (and vice versa) during a rolling upgrade. Returns `None` if the
+/// major versions differ.
+pub fn negotiate_rpc_api_version(local: [u16; 4], remote: [u16; 4]) -> Option<[u16; 4]> {
+    (local[1] == remote[1]).then(|| local.min(remote))
+}
+
+/// The ops of the RPC API, identified over wRPC by their index.
+///
+/// New ops must be appended at the end so that the ids of the existing ops remain unchanged.
 #[derive(Describe, Clone, Copy, Debug, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum RpcApiOps {
@@ -114,9 +130,98 @@ pub enum RpcApiOps {
     VirtualDaaScoreChangedNotification,
     PruningPointUtxoSetOverrideNotification,
     NewBlockTemplateNotification,
+
+    // wRPC message version negotiation, see `negotiate_rpc_api_version()`
+    Handshake,
+
+    NotifyMempoolTransactionRemoved,
+    MempoolTransactionRemovedNotification,
+
+    /// Get rolling statistics of the recently processed blocks (inter-arrival intervals, parent count, blue/red ratio)
+    GetNetworkStats,
+    /// Get the number and total mass of the mempool transactions per fee rate bucket
+    GetMempoolFeeHistogram,
+    /// Get the node policy enforced on transactions entering the mempool (minimum relay fee rate, dust thresholds, chain limits)
+    GetRelayPolicy,
+    /// Get the address funding a transaction, i.e. the address of the output spent by its first input
+    GetUtxoReturnAddress,
+    /// Get the accepted transaction spending an outpoint
+    GetSpendingTransaction,
+    /// Start producing blocks at a given rate (devnet and simnet only)
+    StartBlockProduction,
+    /// Stop producing blocks
+    StopBlockProduction,
+    /// Reset the lifetime metrics accumulated across restarts
+    ResetLifetimeMetrics,
+
+    NotifyAcceptedTransactions,
+    AcceptedTransactionsNotification,
+
+    /// Get the build version, host resources and stable system id of the node
+    GetSystemInfo,
+    /// Run the mempool validation of a transaction without submitting it
+    CheckTransaction,
+
+    NotifySyncStateChanged,
+    SyncStateChangedNotification,
+
+    /// Get a transaction accepted by the virtual selected chain (requires txindex)
+    GetTransaction,
+    /// Get the rules evaluated while validating a block along with their timings (requires validation tracing)
+    GetBlockValidationTrace,
+    /// Get the aggregated readiness of the node along with the health of each of its services
+    GetHealth,
+    /// Update selected node settings (log level, peer connection targets, mempool size limits) without restarting
+    ReloadSettings,
+    /// Get the difficulty of the virtual block as compact bits and as a float
+    GetCurrentDifficulty,
+    /// Get the difficulty window boundaries, past median time and expected bits consensus computes for a block
+    GetBlockDagTimingInfo,
+    /// Get a chunk of the virtual UTXO set read from a snapshot pinned by the first request
+    GetUtxoSetChunk,
+    /// Get the digests of the consensus state at the most recent checkpoint chain blocks
+    GetStateDigests,
+    /// Get the blocks of a bounded DAG neighborhood along with their relations and coloring
+    GetDagBlocksAround,
+    /// Get the direct relations and GHOSTDAG data of a block
+    GetBlockRelations,
+    /// Get a proof of the acceptance of a transaction verifiable by light clients
+    GetTransactionAcceptanceProof,
+    /// Invalidate a block, excluding it and its chain-future from the virtual selected chain
+    InvalidateBlock,
+    /// Reconsider a previously invalidated block
+    ReconsiderBlock,
 }
 
 impl RpcApiOps {
+    /// Returns the lowest RPC API version serving the op. The ops appended from [`RpcApiOps::Handshake`] on are only
+    /// served by peers having negotiated the current version
+    pub fn rpc_api_version(&self) -> [u16; 4] {
+        if u32::from(*self) >= u32::from(RpcApiOps::Handshake) {
+            RPC_API_VERSION
+        } else {
+            RPC_API_VERSION_PRE_HANDSHAKE
+        }
+    }
+
+    /// Returns whether the op affects the state of the node, hence is only served in unsafe RPC mode
+    pub fn is_unsafe(&self) -> bool {
+        matches!(
+            self,
+            RpcApiOps::Shutdown
+                | RpcApiOps::AddPeer
+                | RpcApiOps::Ban
+                | RpcApiOps::Unban
+                | RpcApiOps::ResolveFinalityConflict
+                | RpcApiOps::ReloadSettings
+                | RpcApiOps::InvalidateBlock
+                | RpcApiOps::ReconsiderBlock
+                | RpcApiOps::StartBlockProduction
+                | RpcApiOps::StopBlockProduction
+                | RpcApiOps::ResetLifetimeMetrics
+        )
+    }
+
     pub fn is_subscription(&self) -> bool {
         matches!(
             self,
@@ -129,6 +234,9 @@ impl RpcApiOps {
                 | RpcApiOps::NotifyFinalityConflictResolved
                 | RpcApiOps::NotifySinkBlueScoreChanged
                 | RpcApiOps::NotifyVirtualDaaScoreChanged
+                | RpcApiOps::NotifyMempoolTransactionRemoved
+                | RpcApiOps::NotifyAcceptedTransactions
+                | RpcApiOps::NotifySyncStateChanged
                 | RpcApiOps::Subscribe
                 | RpcApiOps::Unsubscribe
         )
@@ -155,6 +263,39 @@ impl From<EventType> for RpcApiOps {
             EventType::VirtualDaaScoreChanged => RpcApiOps::VirtualDaaScoreChangedNotification,
             EventType::PruningPointUtxoSetOverride => RpcApiOps::PruningPointUtxoSetOverrideNotification,
             EventType::NewBlockTemplate => RpcApiOps::NewBlockTemplateNotification,
+            EventType::MempoolTransactionRemoved => RpcApiOps::MempoolTransactionRemovedNotification,
+            EventType::AcceptedTransactions => RpcApiOps::AcceptedTransactionsNotification,
+            EventType::SyncStateChanged => RpcApiOps::SyncStateChangedNotification,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rpc_api_ops_ids() {
+        // The ids of ops served by released nodes must never change
+        assert_eq!(u32::from(RpcApiOps::Ping), 0);
+        assert_eq!(u32::from(RpcApiOps::GetBlock), 14);
+        assert_eq!(u32::from(RpcApiOps::GetDaaScoreTimestampEstimate), 33);
+        assert_eq!(u32::from(RpcApiOps::NotifyBlockAdded), 34);
+        assert_eq!(u32::from(RpcApiOps::Subscribe), 43);
+        assert_eq!(u32::from(RpcApiOps::BlockAddedNotification), 45);
+        assert_eq!(u32::from(RpcApiOps::NewBlockTemplateNotification), 53);
+        assert_eq!(RpcApiOps::GetDaaScoreTimestampEstimate.try_to_vec().unwrap(), vec![33]);
+    }
+
+    #[test]
+    fn test_rpc_api_version_negotiation() {
+        assert_eq!(negotiate_rpc_api_version(RPC_API_VERSION, [0, 1, 0, 0]), Some([0, 1, 0, 0]));
+        assert_eq!(negotiate_rpc_api_version([0, 1, 0, 0], RPC_API_VERSION), Some([0, 1, 0, 0]));
+        assert_eq!(negotiate_rpc_api_version(RPC_API_VERSION, [0, 2, 0, 0]), None);
+
+        // Peers having negotiated the version preceding the handshake are not served the ops appended since
+        assert!(RpcApiOps::GetBlock.rpc_api_version() <= [0, 1, 0, 0]);
+        assert!(RpcApiOps::Handshake.rpc_api_version() > [0, 1, 0, 0]);
+        assert!(RpcApiOps::ReconsiderBlock.rpc_api_version() <= RPC_API_VERSION);
+    }
+}
@@ -0,0 +1,161 @@
+//! Permission profiles of the RPC listeners
+
+use kaspa_notify::scope::Scope;
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+tokio::task_local! {
+    /// The profile of the listener which received the RPC call being served
+    static CALL_PROFILE: RpcProfile;
+}
+
+/// The permission profile of an RPC listener, restricting the ops served to the connections it accepts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RpcProfile {
+    /// Only ops leaving the state of the node untouched are served
+    Safe,
+    /// All ops are served, the unsafe ones still requiring the node to run in unsafe RPC mode
+    Unsafe,
+}
+
+impl RpcProfile {
+    /// Returns whether the profile allows an op, `is_unsafe` telling if the op affects the state of the node
+    pub fn allows(&self, is_unsafe: bool) -> bool {
+        !is_unsafe || *self == RpcProfile::Unsafe
+    }
+
+    /// Returns whether the profile allows starting a subscription to `scope`, blanket AcceptedTransactions being
+    /// restricted to unsafe listeners since every accepted transaction must then be fetched and converted
+    pub fn allows_subscription(&self, scope: &Scope) -> bool {
+        match scope {
+            Scope::AcceptedTransactions(scope) => self.allows(scope.addresses.is_empty()),
+            _ => true,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl RpcProfile {
+    /// Serves `call` on behalf of a listener with this profile, which the RPC service reads through [`RpcProfile::current`]
+    /// in order to restrict the call to what the listener allows
+    pub async fn scope<F: std::future::Future>(self, call: F) -> F::Output {
+        CALL_PROFILE.scope(self, call).await
+    }
+
+    /// Returns the profile of the listener which received the RPC call being served, or `None` for in-process calls
+    pub fn current() -> Option<Self> {
+        CALL_PROFILE.try_with(|profile| *profile).ok()
+    }
+}
+
+impl FromStr for RpcProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "safe" => Ok(Self::Safe),
+            "unsafe" => Ok(Self::Unsafe),
+            _ => Err(format!("unknown RPC permission profile '{s}' (expected one of: safe, unsafe)")),
+        }
+    }
+}
+
+impl Display for RpcProfile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Safe => write!(f, "safe"),
+            Self::Unsafe => write!(f, "unsafe"),
+        }
+    }
+}
+
+/// An address to listen for RPC connections on, along with the permission profile of the listener when set explicitly.
+///
+/// Parsed from `<address>[@<profile>]`, e.g. `127.0.0.1:16110@unsafe` or `[::]:16110@safe`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RpcListener<A> {
+    pub address: A,
+    pub profile: Option<RpcProfile>,
+}
+
+impl<A> RpcListener<A> {
+    pub fn new(address: A, profile: Option<RpcProfile>) -> Self {
+        Self { address, profile }
+    }
+
+    /// Returns the profile of the listener, falling back to `default` if none was set explicitly
+    pub fn profile_or(&self, default: RpcProfile) -> RpcProfile {
+        self.profile.unwrap_or(default)
+    }
+}
+
+impl<A: FromStr> FromStr for RpcListener<A>
+where
+    A::Err: Display,
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, profile) = match s.rsplit_once('@') {
+            Some((address, profile)) => (address, Some(profile.parse()?)),
+            None => (s, None),
+        };
+        let address = address.parse().map_err(|err| format!("invalid RPC listen address '{address}': {err}"))?;
+        Ok(Self { address, profile })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_addresses::{Address, Prefix, Version};
+    use kaspa_notify::scope::AcceptedTransactionsScope;
+    use std::net::SocketAddr;
+
+    #[test]
+    fn test_rpc_listener_parsing() {
+        let listener: RpcListener<SocketAddr> = "127.0.0.1:16110".parse().unwrap();
+        assert_eq!(listener, RpcListener::new("127.0.0.1:16110".parse().unwrap(), None));
+        assert_eq!(listener.profile_or(RpcProfile::Safe), RpcProfile::Safe);
+
+        let listener: RpcListener<SocketAddr> = "[::1]:16110@unsafe".parse().unwrap();
+        assert_eq!(listener, RpcListener::new("[::1]:16110".parse().unwrap(), Some(RpcProfile::Unsafe)));
+        assert_eq!(listener.profile_or(RpcProfile::Safe), RpcProfile::Unsafe);
+
+        assert!("0.0.0.0:16110@admin".parse::<RpcListener<SocketAddr>>().is_err());
+        assert!("localhost@safe".parse::<RpcListener<SocketAddr>>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rpc_profile_scope() {
+        assert_eq!(RpcProfile::current(), None);
+        assert_eq!(RpcProfile::Safe.scope(async { RpcProfile::current() }).await, Some(RpcProfile::Safe));
+        assert_eq!(RpcProfile::Unsafe.scope(async { RpcProfile::current() }).await, Some(RpcProfile::Unsafe));
+        assert_eq!(RpcProfile::current(), None);
+    }
+
+    #[test]
+    fn test_rpc_profile_allows() {
+        assert!(RpcProfile::Safe.allows(false));
+        assert!(!RpcProfile::Safe.allows(true));
+        assert!(RpcProfile::Unsafe.allows(false));
+        assert!(RpcProfile::Unsafe.allows(true));
+    }
+
+    #[test]
+    fn test_rpc_profile_allows_subscription() {
+        let blanket = Scope::AcceptedTransactions(AcceptedTransactionsScope::default());
+        let targeted = Scope::AcceptedTransactions(AcceptedTransactionsScope::new(vec![Address::new(
+            Prefix::Mainnet,
+            Version::PubKey,
+            &[0u8; 32],
+        )]));
+        assert!(!RpcProfile::Safe.allows_subscription(&blanket));
+        assert!(RpcProfile::Safe.allows_subscription(&targeted));
+        assert!(RpcProfile::Safe.allows_subscription(&Scope::BlockAdded(Default::default())));
+        assert!(RpcProfile::Unsafe.allows_subscription(&blanket));
+    }
+}
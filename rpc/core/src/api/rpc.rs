@@ -4,7 +4,8 @@
 //! All data provided by the RCP server can be trusted by the client
 //! No data submitted by the client to the server can be trusted
 
-use crate::{model::*, notify::connection::ChannelConnection, RpcResult};
+use crate::{model::*, notify::connection::ChannelConnection, RpcError, RpcResult};
+use async_channel::Sender;
 use async_trait::async_trait;
 use downcast::{downcast_sync, AnySync};
 use kaspa_notify::{listener::ListenerId, scope::Scope, subscription::Command};
@@ -29,7 +30,15 @@ pub trait RpcApi: Sync + Send + AnySync {
     // ---
 
     async fn get_metrics(&self, process_metrics: bool, consensus_metrics: bool) -> RpcResult<GetMetricsResponse> {
-        self.get_metrics_call(GetMetricsRequest { process_metrics, consensus_metrics }).await
+        self.get_metrics_call(GetMetricsRequest {
+            process_metrics,
+            consensus_metrics,
+            lifetime_metrics: false,
+            method_metrics: false,
+            p2p_message_metrics: false,
+            listener_metrics: false,
+        })
+        .await
     }
     async fn get_metrics_call(&self, request: GetMetricsRequest) -> RpcResult<GetMetricsResponse>;
 
@@ -105,9 +114,22 @@ pub trait RpcApi: Sync + Send + AnySync {
     }
     async fn get_mempool_entries_call(&self, request: GetMempoolEntriesRequest) -> RpcResult<GetMempoolEntriesResponse>;
 
+    /// Same as [`Self::get_mempool_entries_call`], sending the response through `chunks` as a sequence of responses
+    /// holding at most `chunk_size` entries each, every one as soon as it is produced. At least one chunk is sent.
+    /// The default implementation sends the full response as a single chunk
+    async fn get_mempool_entries_chunked_call(
+        &self,
+        request: GetMempoolEntriesRequest,
+        _chunk_size: usize,
+        chunks: Sender<GetMempoolEntriesResponse>,
+    ) -> RpcResult<()> {
+        let response = self.get_mempool_entries_call(request).await?;
+        chunks.send(response).await.map_err(|_| RpcError::ResponseChunksReceiverClosed)
+    }
+
     /// requests information about all the p2p peers currently connected to this node.
     async fn get_connected_peer_info(&self) -> RpcResult<GetConnectedPeerInfoResponse> {
-        self.get_connected_peer_info_call(GetConnectedPeerInfoRequest {}).await
+        self.get_connected_peer_info_call(GetConnectedPeerInfoRequest { include_traffic: false }).await
     }
     async fn get_connected_peer_info_call(&self, request: GetConnectedPeerInfoRequest) -> RpcResult<GetConnectedPeerInfoResponse>;
 
@@ -163,6 +185,19 @@ pub trait RpcApi: Sync + Send + AnySync {
     }
     async fn get_blocks_call(&self, request: GetBlocksRequest) -> RpcResult<GetBlocksResponse>;
 
+    /// Same as [`Self::get_blocks_call`], sending the response through `chunks` as a sequence of responses holding
+    /// at most `chunk_size` blocks (or block hashes if blocks are not included) each, every one as soon as it is
+    /// produced. At least one chunk is sent. The default implementation sends the full response as a single chunk
+    async fn get_blocks_chunked_call(
+        &self,
+        request: GetBlocksRequest,
+        _chunk_size: usize,
+        chunks: Sender<GetBlocksResponse>,
+    ) -> RpcResult<()> {
+        let response = self.get_blocks_call(request).await?;
+        chunks.send(response).await.map_err(|_| RpcError::ResponseChunksReceiverClosed)
+    }
+
     /// Requests the current number of blocks in this node.
     ///
     /// Note that this number may decrease as pruning occurs.
@@ -298,6 +333,184 @@ pub trait RpcApi: Sync + Send + AnySync {
         request: GetDaaScoreTimestampEstimateRequest,
     ) -> RpcResult<GetDaaScoreTimestampEstimateResponse>;
 
+    /// Requests a transaction accepted by the virtual selected chain, optionally along with a merkle
+    /// branch proving its inclusion in the including block. Requires the node to run with `--txindex`.
+    async fn get_transaction(&self, transaction_id: RpcTransactionId, include_proof: bool) -> RpcResult<GetTransactionResponse> {
+        self.get_transaction_call(GetTransactionRequest::new(transaction_id, include_proof)).await
+    }
+    async fn get_transaction_call(&self, request: GetTransactionRequest) -> RpcResult<GetTransactionResponse>;
+
+    /// Requests the consensus rules evaluated while validating a recently processed block along with the time
+    /// each evaluation took. Requires the node to run with `--trace-validation`.
+    async fn get_block_validation_trace(&self, hash: RpcHash) -> RpcResult<GetBlockValidationTraceResponse> {
+        self.get_block_validation_trace_call(GetBlockValidationTraceRequest::new(hash)).await
+    }
+    async fn get_block_validation_trace_call(
+        &self,
+        request: GetBlockValidationTraceRequest,
+    ) -> RpcResult<GetBlockValidationTraceResponse>;
+
+    /// Requests the health of the node services. A node is ready once all its services are able to serve
+    /// requests, which makes this call suitable as a readiness probe.
+    async fn get_health(&self) -> RpcResult<GetHealthResponse> {
+        self.get_health_call(GetHealthRequest {}).await
+    }
+    async fn get_health_call(&self, request: GetHealthRequest) -> RpcResult<GetHealthResponse>;
+
+    /// Updates selected node settings at runtime (requires `--unsafe-rpc`).
+    async fn reload_settings(&self, request: ReloadSettingsRequest) -> RpcResult<ReloadSettingsResponse> {
+        self.reload_settings_call(request).await
+    }
+    async fn reload_settings_call(&self, request: ReloadSettingsRequest) -> RpcResult<ReloadSettingsResponse>;
+
+    /// Requests the difficulty of the virtual block as compact bits and as a float.
+    async fn get_current_difficulty(&self) -> RpcResult<GetCurrentDifficultyResponse> {
+        self.get_current_difficulty_call(GetCurrentDifficultyRequest {}).await
+    }
+    async fn get_current_difficulty_call(&self, request: GetCurrentDifficultyRequest) -> RpcResult<GetCurrentDifficultyResponse>;
+
+    /// Requests the difficulty window boundaries, past median time and expected bits consensus computes for a block,
+    /// to help diagnose `bad-diff-bits` rejections.
+    async fn get_block_dag_timing_info(&self, hash: RpcHash) -> RpcResult<GetBlockDagTimingInfoResponse> {
+        self.get_block_dag_timing_info_call(GetBlockDagTimingInfoRequest::new(hash)).await
+    }
+    async fn get_block_dag_timing_info_call(&self, request: GetBlockDagTimingInfoRequest) -> RpcResult<GetBlockDagTimingInfoResponse>;
+
+    /// Requests a chunk of the virtual UTXO set. A `None` cursor pins a snapshot of the set and starts a new export,
+    /// which is continued by passing the `next_cursor` of each response until it is `None`. All chunks of an export
+    /// are read from the same snapshot, so the exported set is consistent regardless of the blocks processed meanwhile.
+    async fn get_utxo_set_chunk(&self, cursor: Option<String>) -> RpcResult<GetUtxoSetChunkResponse> {
+        self.get_utxo_set_chunk_call(GetUtxoSetChunkRequest::new(cursor)).await
+    }
+    async fn get_utxo_set_chunk_call(&self, request: GetUtxoSetChunkRequest) -> RpcResult<GetUtxoSetChunkResponse>;
+
+    /// Requests the digests of the consensus state at the `count` most recent checkpoint chain blocks, the most
+    /// recent first. Nodes following the same chain report the same digests, so comparing them detects forks.
+    async fn get_state_digests(&self, count: u32) -> RpcResult<GetStateDigestsResponse> {
+        self.get_state_digests_call(GetStateDigestsRequest::new(count)).await
+    }
+    async fn get_state_digests_call(&self, request: GetStateDigestsRequest) -> RpcResult<GetStateDigestsResponse>;
+
+    /// Requests the blocks reachable from `hash` by following at most `radius` parent or child edges, along with
+    /// their relations and GHOSTDAG coloring, so that DAG explorers can draw a neighborhood in a single call.
+    async fn get_dag_blocks_around(&self, hash: RpcHash, radius: u32) -> RpcResult<GetDagBlocksAroundResponse> {
+        self.get_dag_blocks_around_call(GetDagBlocksAroundRequest::new(hash, radius)).await
+    }
+    async fn get_dag_blocks_around_call(&self, request: GetDagBlocksAroundRequest) -> RpcResult<GetDagBlocksAroundResponse>;
+
+    /// Requests the direct parents and children of a block along with its selected parent, mergeset coloring and blue score.
+    async fn get_block_relations(&self, hash: RpcHash) -> RpcResult<GetBlockRelationsResponse> {
+        self.get_block_relations_call(GetBlockRelationsRequest::new(hash)).await
+    }
+    async fn get_block_relations_call(&self, request: GetBlockRelationsRequest) -> RpcResult<GetBlockRelationsResponse>;
+
+    /// Requests a proof that a transaction was accepted by a chain block, made of the chain segment headers starting at
+    /// the accepting block and of the merkle branch of the transaction ID in the accepted IDs of this block.
+    /// The accepting block is looked up in the transaction index unless provided.
+    async fn get_transaction_acceptance_proof(
+        &self,
+        transaction_id: RpcTransactionId,
+        accepting_block_hash: Option<RpcHash>,
+    ) -> RpcResult<RpcTransactionAcceptanceProof> {
+        Ok(self
+            .get_transaction_acceptance_proof_call(GetTransactionAcceptanceProofRequest::new(transaction_id, accepting_block_hash))
+            .await?
+            .proof)
+    }
+    async fn get_transaction_acceptance_proof_call(
+        &self,
+        request: GetTransactionAcceptanceProofRequest,
+    ) -> RpcResult<GetTransactionAcceptanceProofResponse>;
+
+    /// Invalidates the given block, excluding it and any block having it on its selected chain from the virtual
+    /// selected chain until reconsidered.
+    async fn invalidate_block(&self, hash: RpcHash) -> RpcResult<()> {
+        self.invalidate_block_call(InvalidateBlockRequest::new(hash)).await?;
+        Ok(())
+    }
+    async fn invalidate_block_call(&self, request: InvalidateBlockRequest) -> RpcResult<InvalidateBlockResponse>;
+
+    /// Reconsiders a block previously invalidated by `invalidate_block`.
+    async fn reconsider_block(&self, hash: RpcHash) -> RpcResult<()> {
+        self.reconsider_block_call(ReconsiderBlockRequest::new(hash)).await?;
+        Ok(())
+    }
+    async fn reconsider_block_call(&self, request: ReconsiderBlockRequest) -> RpcResult<ReconsiderBlockResponse>;
+
+    /// Requests rolling statistics of the recently processed blocks: inter-arrival intervals, average parent count
+    /// and the blue/red coloring of the merged blocks.
+    async fn get_network_stats(&self) -> RpcResult<GetNetworkStatsResponse> {
+        self.get_network_stats_call(GetNetworkStatsRequest {}).await
+    }
+    async fn get_network_stats_call(&self, request: GetNetworkStatsRequest) -> RpcResult<GetNetworkStatsResponse>;
+
+    /// Requests the number and total mass of the mempool transactions per fee rate (sompi per gram of mass) bucket,
+    /// the buckets being delimited by the strictly ascending `bucket_bounds` (default bounds are used if empty).
+    async fn get_mempool_fee_histogram(&self, bucket_bounds: Vec<f64>) -> RpcResult<Vec<RpcFeeRateBucket>> {
+        Ok(self.get_mempool_fee_histogram_call(GetMempoolFeeHistogramRequest::new(bucket_bounds)).await?.buckets)
+    }
+    async fn get_mempool_fee_histogram_call(
+        &self,
+        request: GetMempoolFeeHistogramRequest,
+    ) -> RpcResult<GetMempoolFeeHistogramResponse>;
+
+    /// Requests the node policy enforced on transactions entering the mempool: the minimum relay fee rate, the dust
+    /// thresholds and the limits of unconfirmed transaction chains. The policy is local to the node and is not part
+    /// of consensus.
+    async fn get_relay_policy(&self) -> RpcResult<GetRelayPolicyResponse> {
+        self.get_relay_policy_call(GetRelayPolicyRequest {}).await
+    }
+    async fn get_relay_policy_call(&self, request: GetRelayPolicyRequest) -> RpcResult<GetRelayPolicyResponse>;
+
+    /// Requests the return address of the UTXOs created by a transaction, i.e. the address of the output spent by
+    /// the first input of the transaction. Requires the node to run with `--txindex`.
+    async fn get_utxo_return_address(&self, transaction_id: RpcTransactionId) -> RpcResult<RpcAddress> {
+        Ok(self.get_utxo_return_address_call(GetUtxoReturnAddressRequest::new(transaction_id)).await?.return_address)
+    }
+    async fn get_utxo_return_address_call(&self, request: GetUtxoReturnAddressRequest) -> RpcResult<GetUtxoReturnAddressResponse>;
+
+    /// Requests the transaction accepted by the virtual selected chain which spends `outpoint`. Requires the node
+    /// to run with `--txindex`.
+    async fn get_spending_transaction(&self, outpoint: RpcTransactionOutpoint) -> RpcResult<GetSpendingTransactionResponse> {
+        self.get_spending_transaction_call(GetSpendingTransactionRequest::new(outpoint)).await
+    }
+    async fn get_spending_transaction_call(&self, request: GetSpendingTransactionRequest)
+        -> RpcResult<GetSpendingTransactionResponse>;
+
+    /// Starts producing blocks paying to `pay_address` at an average rate of `blocks_per_second`, replacing the
+    /// active production if any. Only available on devnet and simnet.
+    async fn start_block_production(&self, pay_address: RpcAddress, blocks_per_second: f64) -> RpcResult<()> {
+        self.start_block_production_call(StartBlockProductionRequest::new(pay_address, blocks_per_second)).await?;
+        Ok(())
+    }
+    async fn start_block_production_call(&self, request: StartBlockProductionRequest) -> RpcResult<StartBlockProductionResponse>;
+
+    /// Stops the block production started by `start_block_production` or on node startup.
+    async fn stop_block_production(&self) -> RpcResult<StopBlockProductionResponse> {
+        self.stop_block_production_call(StopBlockProductionRequest {}).await
+    }
+    async fn stop_block_production_call(&self, request: StopBlockProductionRequest) -> RpcResult<StopBlockProductionResponse>;
+
+    /// Discards the lifetime metrics accumulated across restarts, accumulating anew from now on.
+    async fn reset_lifetime_metrics(&self) -> RpcResult<()> {
+        self.reset_lifetime_metrics_call(ResetLifetimeMetricsRequest {}).await?;
+        Ok(())
+    }
+    async fn reset_lifetime_metrics_call(&self, request: ResetLifetimeMetricsRequest) -> RpcResult<ResetLifetimeMetricsResponse>;
+
+    /// Returns the build version and commit of the node, the resources of its host and the stable id of the node installation.
+    async fn get_system_info(&self) -> RpcResult<GetSystemInfoResponse> {
+        self.get_system_info_call(GetSystemInfoRequest {}).await
+    }
+    async fn get_system_info_call(&self, request: GetSystemInfoRequest) -> RpcResult<GetSystemInfoResponse>;
+
+    /// Runs the mempool validation of a transaction without submitting it, returning its mass, fee and fee rate along with
+    /// the rule it violates, if any.
+    async fn check_transaction(&self, transaction: RpcTransaction) -> RpcResult<CheckTransactionResponse> {
+        self.check_transaction_call(CheckTransactionRequest { transaction }).await
+    }
+    async fn check_transaction_call(&self, request: CheckTransactionRequest) -> RpcResult<CheckTransactionResponse>;
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
 
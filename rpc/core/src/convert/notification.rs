@@ -1,7 +1,8 @@
 use crate::{
-    convert::utxo::utxo_set_into_rpc, BlockAddedNotification, FinalityConflictNotification, FinalityConflictResolvedNotification,
-    NewBlockTemplateNotification, Notification, PruningPointUtxoSetOverrideNotification, RpcAcceptedTransactionIds,
-    SinkBlueScoreChangedNotification, UtxosChangedNotification, VirtualChainChangedNotification, VirtualDaaScoreChangedNotification,
+    convert::utxo::utxo_set_into_rpc, AcceptedTransactionsNotification, BlockAddedNotification, FinalityConflictNotification,
+    FinalityConflictResolvedNotification, NewBlockTemplateNotification, Notification, PruningPointUtxoSetOverrideNotification,
+    RpcAcceptedTransactionIds, RpcAcceptedTransactions, SinkBlueScoreChangedNotification, UtxosChangedNotification,
+    VirtualChainChangedNotification, VirtualDaaScoreChangedNotification,
 };
 use kaspa_consensus_notify::notification as consensus_notify;
 use kaspa_index_core::notification as index_notify;
@@ -29,6 +30,7 @@ impl From<&consensus_notify::Notification> for Notification {
             consensus_notify::Notification::VirtualDaaScoreChanged(msg) => Notification::VirtualDaaScoreChanged(msg.into()),
             consensus_notify::Notification::PruningPointUtxoSetOverride(msg) => Notification::PruningPointUtxoSetOverride(msg.into()),
             consensus_notify::Notification::NewBlockTemplate(msg) => Notification::NewBlockTemplate(msg.into()),
+            consensus_notify::Notification::AcceptedTransactions(msg) => Notification::AcceptedTransactions(msg.into()),
         }
     }
 }
@@ -110,6 +112,21 @@ impl From<&consensus_notify::NewBlockTemplateNotification> for NewBlockTemplateN
     }
 }
 
+impl From<&consensus_notify::AcceptedTransactionsNotification> for AcceptedTransactionsNotification {
+    // The consensus notification only carries the ids of the accepted transactions, so the transactions are left empty here.
+    // Use kaspa_rpc_service::converter::consensus::ConsensusConverter instead.
+    fn from(item: &consensus_notify::AcceptedTransactionsNotification) -> Self {
+        Self {
+            accepted_transactions: Arc::new(
+                item.added_chain_block_hashes
+                    .iter()
+                    .map(|hash| RpcAcceptedTransactions { accepting_block_hash: *hash, accepted_transactions: vec![] })
+                    .collect(),
+            ),
+        }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // index to rpc_core
 // ----------------------------------------------------------------------------
@@ -1,7 +1,7 @@
 use crate::{
-    NotifyBlockAddedRequest, NotifyFinalityConflictRequest, NotifyNewBlockTemplateRequest, NotifyPruningPointUtxoSetOverrideRequest,
-    NotifySinkBlueScoreChangedRequest, NotifyUtxosChangedRequest, NotifyVirtualChainChangedRequest,
-    NotifyVirtualDaaScoreChangedRequest,
+    NotifyAcceptedTransactionsRequest, NotifyBlockAddedRequest, NotifyFinalityConflictRequest, NotifyMempoolTransactionRemovedRequest,
+    NotifyNewBlockTemplateRequest, NotifyPruningPointUtxoSetOverrideRequest, NotifySinkBlueScoreChangedRequest,
+    NotifySyncStateChangedRequest, NotifyUtxosChangedRequest, NotifyVirtualChainChangedRequest, NotifyVirtualDaaScoreChangedRequest,
 };
 use kaspa_notify::scope::*;
 
@@ -60,3 +60,8 @@ from!(SinkBlueScoreChanged);
 from!(VirtualDaaScoreChanged);
 from!(PruningPointUtxoSetOverride);
 from!(NewBlockTemplate);
+from!(MempoolTransactionRemoved);
+from!(item: AcceptedTransactions, {
+    Self::new(item.addresses.clone())
+});
+from!(SyncStateChanged);
@@ -1,4 +1,4 @@
-use kaspa_consensus_core::tx::TransactionId;
+use kaspa_consensus_core::tx::{TransactionId, TransactionOutpoint};
 use kaspa_utils::networking::IpAddress;
 use std::{net::AddrParseError, num::TryFromIntError};
 use thiserror::Error;
@@ -35,6 +35,9 @@ pub enum RpcError {
     #[error("Missing required field {0}.{1}")]
     MissingRpcFieldError(String, String),
 
+    #[error("{0} of size {1} exceeds the decoding limit of {2}")]
+    DecodingLimitExceeded(String, usize, usize),
+
     #[error("Feature not supported")]
     UnsupportedFeature,
 
@@ -47,18 +50,42 @@ pub enum RpcError {
     #[error("Rejected transaction {0}: {1}")]
     RejectedTransaction(RpcTransactionId, String),
 
+    #[error("Rejected transaction {0} (rule error code {1}): {2}")]
+    RejectedTransactionRule(RpcTransactionId, u16, String),
+
     #[error("Block {0} is invalid. No verbose data can be built.")]
     InvalidBlock(RpcHash),
 
     #[error("If includeTransactions is set, then includeBlockVerboseData must be set as well.")]
     InvalidGetBlocksRequest,
 
+    #[error("The receiver of the response chunks is closed")]
+    ResponseChunksReceiverClosed,
+
     #[error("Transaction {0} not found")]
     TransactionNotFound(TransactionId),
 
     #[error("Method unavailable. Run the node with the --utxoindex argument.")]
     NoUtxoIndex,
 
+    #[error("Method unavailable. Run the node with the --txindex argument.")]
+    NoTxIndex,
+
+    #[error("Outpoint {0} is not spent by any transaction accepted by the selected chain")]
+    OutpointNotSpent(TransactionOutpoint),
+
+    #[error("Transaction {0} has no return address")]
+    NoReturnAddress(TransactionId),
+
+    #[error("Method unavailable. Run the node with the --trace-validation argument.")]
+    NoValidationTracing,
+
+    #[error("No validation trace was recorded for block {0}")]
+    ValidationTraceNotFound(RpcHash),
+
+    #[error("Method unavailable. The node runs in --headers-only mode.")]
+    HeadersOnlyMode,
+
     #[error("Method unavailable. No connection manager is currently available.")]
     NoConnectionManager,
 
@@ -71,12 +98,30 @@ pub enum RpcError {
     #[error("Method unavailable in safe mode. Run the node with --unsafe argument.")]
     UnavailableInSafeMode,
 
+    #[error("Method unavailable on this RPC listener. Connect to a listener with the unsafe permission profile.")]
+    UnavailableOnSafeListener,
+
+    #[error("Fee rate bucket bounds must be positive, finite and strictly ascending, and no more than {0}.")]
+    InvalidFeeRateBucketBounds(usize),
+
+    #[error("The node is under heavy load, retry after {0} seconds.")]
+    Overloaded(u64),
+
     #[error("Cannot ban IP {0} because it has some permanent connection.")]
     IpHasPermanentConnection(IpAddress),
 
     #[error("IP {0} is not registered as banned.")]
     IpIsNotBanned(IpAddress),
 
+    #[error("Method unavailable. Block production is only supported on devnet and simnet.")]
+    BlockProductionUnavailable,
+
+    #[error("Block production rate {0} is not within (0, {1}] blocks per second.")]
+    InvalidBlockProductionRate(f64, f64),
+
+    #[error("Invalid settings: {0}")]
+    InvalidSettings(String),
+
     #[error(transparent)]
     AddressError(#[from] kaspa_addresses::AddressError),
 
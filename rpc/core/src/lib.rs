@@ -4,8 +4,10 @@
 pub mod api;
 pub mod convert;
 pub mod error;
+pub mod metrics;
 pub mod model;
 pub mod notify;
+pub mod trace;
 pub mod wasm;
 
 pub mod prelude {
@@ -0,0 +1,89 @@
+//! Per-method RPC metrics. Every RPC request served by the gRPC and wRPC servers is measured with
+//! [`RpcMethodCounters::measure`], which accumulates the number of calls, the number of failed calls and a latency histogram
+//! per RPC method. The counters are shared by the servers and the RPC core service, which reports them in `GetMetrics`.
+
+use crate::{api::ops::RpcApiOps, RpcMethodMetrics, RpcResult};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The upper bounds (in milliseconds) of the latency histogram buckets, a last bucket counting the slower calls
+pub const LATENCY_BUCKET_BOUNDS_MS: [u64; 8] = [1, 5, 10, 50, 100, 500, 1_000, 5_000];
+
+#[derive(Clone, Default)]
+struct MethodStats {
+    calls: u64,
+    errors: u64,
+    total_latency_micros: u64,
+    latency_buckets: [u64; LATENCY_BUCKET_BOUNDS_MS.len() + 1],
+}
+
+#[derive(Default)]
+pub struct RpcMethodCounters {
+    stats: Mutex<HashMap<RpcApiOps, MethodStats>>,
+}
+
+impl RpcMethodCounters {
+    /// Awaits `call`, a call to the RPC method `op`, recording its latency and whether it failed
+    pub async fn measure<T>(&self, op: RpcApiOps, call: impl Future<Output = RpcResult<T>>) -> RpcResult<T> {
+        let start = Instant::now();
+        let result = call.await;
+        self.record(op, start.elapsed(), result.is_err());
+        result
+    }
+
+    /// Records a call to the RPC method `op` which lasted `latency`
+    pub fn record(&self, op: RpcApiOps, latency: Duration, failed: bool) {
+        let latency_ms = latency.as_millis() as u64;
+        let bucket = LATENCY_BUCKET_BOUNDS_MS.iter().position(|&bound| latency_ms < bound).unwrap_or(LATENCY_BUCKET_BOUNDS_MS.len());
+        let mut registry = self.stats.lock().unwrap();
+        let stats = registry.entry(op).or_default();
+        stats.calls += 1;
+        stats.errors += failed as u64;
+        stats.total_latency_micros += latency.as_micros() as u64;
+        stats.latency_buckets[bucket] += 1;
+    }
+
+    /// Returns the metrics of all the RPC methods called so far, sorted by method name
+    pub fn snapshot(&self) -> Vec<RpcMethodMetrics> {
+        let mut metrics = self
+            .stats
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(op, stats)| RpcMethodMetrics {
+                method: format!("{op:?}"),
+                calls: stats.calls,
+                errors: stats.errors,
+                total_latency_micros: stats.total_latency_micros,
+                latency_buckets: stats.latency_buckets.to_vec(),
+            })
+            .collect::<Vec<_>>();
+        metrics.sort_by(|a, b| a.method.cmp(&b.method));
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_metrics() {
+        let counters = RpcMethodCounters::default();
+        counters.record(RpcApiOps::GetCoinSupply, Duration::from_micros(300), false);
+        counters.record(RpcApiOps::GetCoinSupply, Duration::from_millis(70), true);
+        counters.record(RpcApiOps::GetCoinSupply, Duration::from_secs(9), false);
+        counters.record(RpcApiOps::Ping, Duration::from_micros(10), false);
+
+        let metrics = counters.snapshot();
+        assert_eq!(metrics.iter().map(|metrics| metrics.method.as_str()).collect::<Vec<_>>(), vec!["GetCoinSupply", "Ping"]);
+        let metrics = &metrics[0];
+        assert_eq!((metrics.calls, metrics.errors), (3, 1));
+        assert_eq!(metrics.total_latency_micros, 9_070_300);
+        assert_eq!(metrics.latency_buckets, vec![1, 0, 0, 0, 1, 0, 0, 0, 1]);
+    }
+}
@@ -0,0 +1,225 @@
+//!
+//! Golden-file tests pinning the Borsh and JSON encodings of the RPC messages exchanged over wRPC.
+//!
+//! The reference encodings are recorded in `testdata/golden.json`, including the ids of the [`RpcApiOps`] identifying
+//! the messages over wRPC. A failing entry means a change breaks
+//! the wire compatibility with nodes or clients speaking the current [`RPC_API_VERSION`](crate::api::ops::RPC_API_VERSION),
+//! which would for instance break the subscriptions of clients during a rolling upgrade. Such a change must
+//! either be reworked in a backward compatible way or ship with a new major RPC API version.
+//!
+//! The encodings of messages produced by v0.13.4 nodes, preceding the trailing fields appended to some messages, are
+//! recorded in `testdata/baseline.json`. The current messages must decode them with the appended fields unset.
+//!
+
+use crate::{api::ops::RpcApiOps, model::*, Notification};
+use borsh::{BorshDeserialize, BorshSerialize};
+use kaspa_consensus_core::network::{NetworkId, NetworkType};
+use kaspa_notify::{
+    scope::{Scope, UtxosChangedScope, VirtualDaaScoreChangedScope},
+    subscription::Command,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Deserialize)]
+struct Golden {
+    name: String,
+    borsh: String,
+    json: Value,
+}
+
+impl Golden {
+    /// Asserts that `sample` encodes to the golden encodings and that these decode back to the same value
+    fn check<T>(&self, sample: T)
+    where
+        T: BorshSerialize + BorshDeserialize + Serialize + DeserializeOwned,
+    {
+        let borsh = faster_hex::hex_string(&sample.try_to_vec().unwrap());
+        assert_eq!(borsh, self.borsh, "{}: Borsh encoding changed", self.name);
+        assert_eq!(serde_json::to_value(&sample).unwrap(), self.json, "{}: JSON encoding changed", self.name);
+
+        let mut bytes = vec![0u8; self.borsh.len() / 2];
+        // The hex decoder rejects empty input, as of messages without fields
+        if !bytes.is_empty() {
+            faster_hex::hex_decode(self.borsh.as_bytes(), &mut bytes).unwrap();
+        }
+        let decoded = T::try_from_slice(&bytes).unwrap_or_else(|err| panic!("{}: golden Borsh no longer decodes: {err}", self.name));
+        assert_eq!(faster_hex::hex_string(&decoded.try_to_vec().unwrap()), self.borsh, "{}", self.name);
+        let decoded: T = serde_json::from_value(self.json.clone())
+            .unwrap_or_else(|err| panic!("{}: golden JSON no longer decodes: {err}", self.name));
+        assert_eq!(serde_json::to_value(decoded).unwrap(), self.json, "{}", self.name);
+    }
+}
+
+#[test]
+fn test_golden_encodings() {
+    let goldens: Vec<Golden> = serde_json::from_str(include_str!("../../testdata/golden.json")).unwrap();
+    let hash = RpcHash::from_bytes([0x11; 32]);
+    for golden in goldens.iter() {
+        match golden.name.as_str() {
+            "SubscribeScopeVirtualDaaScoreChanged" => golden.check(Scope::VirtualDaaScoreChanged(VirtualDaaScoreChangedScope {})),
+            "SubscribeScopeUtxosChanged" => golden.check(Scope::UtxosChanged(UtxosChangedScope { addresses: vec![] })),
+            "SubscribeResponse" => golden.check(SubscribeResponse::new(7)),
+            "UnsubscribeResponse" => golden.check(UnsubscribeResponse {}),
+            "HandshakeRequest" => golden.check(HandshakeRequest::new([0, 1, 0, 0])),
+            "HandshakeResponse" => golden.check(HandshakeResponse::new([0, 1, 2, 0], [0, 1, 0, 0])),
+            "NotifyVirtualDaaScoreChangedRequest" => golden.check(NotifyVirtualDaaScoreChangedRequest::new(Command::Start)),
+            "VirtualDaaScoreChangedNotification" => {
+                golden.check(Notification::VirtualDaaScoreChanged(VirtualDaaScoreChangedNotification { virtual_daa_score: 38512553 }))
+            }
+            "SinkBlueScoreChangedNotification" => {
+                golden.check(Notification::SinkBlueScoreChanged(SinkBlueScoreChangedNotification { sink_blue_score: 1000 }))
+            }
+            "MempoolTransactionRemovedNotification" => {
+                golden.check(Notification::MempoolTransactionRemoved(MempoolTransactionRemovedNotification {
+                    transaction_id: hash,
+                    reason: RpcMempoolRemovalReason::ConflictWithBlock,
+                }))
+            }
+            "SyncStateChangedNotification" => {
+                golden.check(Notification::SyncStateChanged(SyncStateChangedNotification { state: RpcSyncState::Headers }))
+            }
+            "PingRequest" => golden.check(PingRequest {}),
+            "GetMetricsRequest" => golden.check(GetMetricsRequest {
+                process_metrics: true,
+                consensus_metrics: false,
+                lifetime_metrics: false,
+                method_metrics: false,
+                p2p_message_metrics: false,
+                listener_metrics: false,
+            }),
+            "GetMetricsResponse" => golden.check(GetMetricsResponse::new(1000, None, None, None, vec![], vec![], None, vec![])),
+            "GetMetricsRequestListeners" => golden.check(GetMetricsRequest {
+                process_metrics: true,
+                consensus_metrics: false,
+                lifetime_metrics: false,
+                method_metrics: false,
+                p2p_message_metrics: false,
+                listener_metrics: true,
+            }),
+            "GetMetricsResponseListeners" => golden.check(GetMetricsResponse::new(
+                1000,
+                None,
+                None,
+                None,
+                vec![],
+                vec![],
+                None,
+                vec![RpcListenerMetrics {
+                    notifier: "wrpc-server".to_string(),
+                    listener_id: 42,
+                    queue_len: 1,
+                    queue_capacity: 1024,
+                    dropped_notifications: 3,
+                    lagging: true,
+                    overflowed: false,
+                }],
+            )),
+            "GetMetricsResponseConsensus" => golden.check(GetMetricsResponse::new(
+                1000,
+                None,
+                Some(consensus_metrics()),
+                None,
+                vec![],
+                vec![],
+                Some(ExtendedConsensusMetrics { tip_hashes_count: 2, storage_write_amplification: 1.5, ..Default::default() }),
+                vec![],
+            )),
+            "GetServerInfoResponse" => golden.check(GetServerInfoResponse {
+                rpc_api_version: [0, 1, 0, 0],
+                server_version: "0.13.4".to_string(),
+                network_id: NetworkId::with_suffix(NetworkType::Testnet, 10),
+                has_utxo_index: true,
+                is_synced: false,
+                virtual_daa_score: 1000,
+            }),
+            "GetSyncStatusResponse" => golden.check(GetSyncStatusResponse { is_synced: true }),
+            "GetSinkResponse" => golden.check(GetSinkResponse::new(hash)),
+            "GetBlockRequest" => golden.check(GetBlockRequest::new(hash, true)),
+            "GetBalanceByAddressResponse" => golden.check(GetBalanceByAddressResponse::new(100_000_000)),
+            "GetCoinSupplyResponse" => golden.check(GetCoinSupplyResponse::new(2_900_000_000, 1000)),
+            "GetConnectedPeerInfoRequest" => golden.check(GetConnectedPeerInfoRequest { include_traffic: false }),
+            "GetConnectedPeerInfoResponse" => golden.check(GetConnectedPeerInfoResponse::new(vec![], vec![])),
+            "SubmitBlockResponse" => golden.check(SubmitBlockResponse {
+                report: SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid),
+                rule_error_code: None,
+            }),
+            // The ops are listed in id order, so an op inserted, removed or moved changes the entry
+            "RpcApiOps" => golden.check(RpcApiOps::list()),
+            "GetMempoolEntriesRequest" => golden.check(GetMempoolEntriesRequest::new(true, false)),
+            "GetVirtualChainFromBlockRequest" => golden.check(GetVirtualChainFromBlockRequest::new(hash, true)),
+            "GetHeadersRequest" => golden.check(GetHeadersRequest::new(hash, 100, true)),
+            "EstimateNetworkHashesPerSecondRequest" => golden.check(EstimateNetworkHashesPerSecondRequest::new(1000, None)),
+            "GetDaaScoreTimestampEstimateRequest" => golden.check(GetDaaScoreTimestampEstimateRequest::new(vec![1, 1000])),
+            "GetInfoResponse" => golden.check(GetInfoResponse {
+                p2p_id: "id".to_string(),
+                mempool_size: 5,
+                server_version: "0.13.4".to_string(),
+                is_utxo_indexed: true,
+                is_synced: true,
+                has_notify_command: true,
+                has_message_id: true,
+                is_clock_drifted: false,
+            }),
+            "GetInfoResponseClockDrifted" => golden.check(GetInfoResponse {
+                p2p_id: "id".to_string(),
+                mempool_size: 5,
+                server_version: "0.13.4".to_string(),
+                is_utxo_indexed: true,
+                is_synced: true,
+                has_notify_command: true,
+                has_message_id: true,
+                is_clock_drifted: true,
+            }),
+            "GetBlockDagInfoResponse" => golden.check(GetBlockDagInfoResponse {
+                network: NetworkId::with_suffix(NetworkType::Testnet, 10),
+                block_count: 10,
+                header_count: 12,
+                tip_hashes: vec![hash],
+                difficulty: 1.5,
+                past_median_time: 1000,
+                virtual_parent_hashes: vec![hash],
+                pruning_point_hash: hash,
+                virtual_daa_score: 1000,
+                sink: hash,
+            }),
+            name => panic!("no sample is defined for the golden entry {name}"),
+        }
+    }
+}
+
+#[test]
+fn test_baseline_encodings() {
+    let goldens: Vec<Golden> = serde_json::from_str(include_str!("../../testdata/baseline.json")).unwrap();
+    for golden in goldens.iter() {
+        // Checking the samples with the appended fields unset asserts that these are decoded as such
+        match golden.name.as_str() {
+            "GetInfoResponse" => golden.check(GetInfoResponse {
+                p2p_id: "id".to_string(),
+                mempool_size: 5,
+                server_version: "0.13.4".to_string(),
+                is_utxo_indexed: true,
+                is_synced: true,
+                has_notify_command: true,
+                has_message_id: true,
+                is_clock_drifted: false,
+            }),
+            "GetMetricsResponse" => {
+                golden.check(GetMetricsResponse::new(1000, None, Some(consensus_metrics()), None, vec![], vec![], None, vec![]))
+            }
+            name => panic!("no sample is defined for the baseline entry {name}"),
+        }
+    }
+}
+
+fn consensus_metrics() -> ConsensusMetrics {
+    ConsensusMetrics {
+        blocks_submitted: 1,
+        header_counts: 2,
+        dep_counts: 3,
+        body_counts: 4,
+        txs_counts: 5,
+        chain_block_counts: 6,
+        mass_counts: 7,
+    }
+}
@@ -28,3 +28,33 @@ impl RpcMempoolEntryByAddress {
         Self { address, sending, receiving }
     }
 }
+
+/// The mempool transactions with a fee rate (in sompi per gram of mass) at least `min_fee_rate` and below the
+/// `min_fee_rate` of the next bucket
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcFeeRateBucket {
+    pub min_fee_rate: f64,
+    pub transaction_count: u64,
+    pub total_mass: u64,
+}
+
+impl RpcFeeRateBucket {
+    pub fn new(min_fee_rate: f64, transaction_count: u64, total_mass: u64) -> Self {
+        Self { min_fee_rate, transaction_count, total_mass }
+    }
+}
+
+/// Reason of a transaction leaving the mempool without being accepted by a block
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcMempoolRemovalReason {
+    /// Evicted to make room for transactions paying a higher fee rate
+    Evicted,
+    /// Replaced by a conflicting transaction paying a higher fee
+    Replaced,
+    /// Expired before being included in a block
+    Expired,
+    /// Double spends an outpoint spent by a transaction accepted by a block
+    ConflictWithBlock,
+}
@@ -2,9 +2,14 @@ use crate::model::*;
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use kaspa_consensus_core::block_count::BlockCount;
 use kaspa_core::debug;
-use kaspa_notify::subscription::{single::UtxosChangedSubscription, Command};
+use kaspa_notify::subscription::{
+    index::UtxosChangedIndex,
+    single::{AcceptedTransactionsSubscription, UtxosChangedSubscription},
+    Command,
+};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     fmt::{Display, Formatter},
     sync::Arc,
 };
@@ -15,16 +20,38 @@ pub type RpcExtraData = Vec<u8>;
 /// Blocks are generally expected to have been generated using the getBlockTemplate call.
 ///
 /// See: [`GetBlockTemplateRequest`]
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[derive(Debug, Serialize, Deserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SubmitBlockRequest {
     pub block: RpcBlock,
     #[serde(alias = "allowNonDAABlocks")]
     pub allow_non_daa_blocks: bool,
+    /// Whether to report the code of the consensus rule violated by an invalid block.
+    /// Encoded as a trailing field, see [`trailing`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub report_rule_error_code: bool,
 }
 impl SubmitBlockRequest {
     pub fn new(block: RpcBlock, allow_non_daa_blocks: bool) -> Self {
-        Self { block, allow_non_daa_blocks }
+        Self { block, allow_non_daa_blocks, report_rule_error_code: false }
+    }
+}
+
+impl BorshSerialize for SubmitBlockRequest {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        BorshSerialize::serialize(&self.block, writer)?;
+        BorshSerialize::serialize(&self.allow_non_daa_blocks, writer)?;
+        trailing::serialize_trailing(&self.report_rule_error_code.then_some(true), writer)
+    }
+}
+
+impl BorshDeserialize for SubmitBlockRequest {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Self {
+            block: <RpcBlock as BorshDeserialize>::deserialize(buf)?,
+            allow_non_daa_blocks: <bool as BorshDeserialize>::deserialize(buf)?,
+            report_rule_error_code: trailing::deserialize_trailing(buf)?.unwrap_or_default(),
+        })
     }
 }
 
@@ -33,6 +60,8 @@ impl SubmitBlockRequest {
 pub enum SubmitBlockRejectReason {
     BlockInvalid = 1,
     IsInIBD = 2,
+    /// The block was mined over a template whose DAA score lags too far behind the virtual DAA score of the node
+    StaleTemplate = 3,
 }
 impl SubmitBlockRejectReason {
     fn as_str(&self) -> &'static str {
@@ -40,6 +69,7 @@ impl SubmitBlockRejectReason {
         match self {
             SubmitBlockRejectReason::BlockInvalid => "Block is invalid",
             SubmitBlockRejectReason::IsInIBD => "Node is in IBD",
+            SubmitBlockRejectReason::StaleTemplate => "Block template is stale",
         }
     }
 }
@@ -61,10 +91,30 @@ impl SubmitBlockReport {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[derive(Debug, Serialize, Deserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SubmitBlockResponse {
     pub report: SubmitBlockReport,
+    /// The stable code of the consensus rule violated by the block, if requested and the block was rejected as invalid by
+    /// consensus. Encoded as a trailing field, see [`trailing`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_error_code: Option<u16>,
+}
+
+impl BorshSerialize for SubmitBlockResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        BorshSerialize::serialize(&self.report, writer)?;
+        trailing::serialize_trailing(&self.rule_error_code, writer)
+    }
+}
+
+impl BorshDeserialize for SubmitBlockResponse {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Self {
+            report: <SubmitBlockReport as BorshDeserialize>::deserialize(buf)?,
+            rule_error_code: trailing::deserialize_trailing(buf)?,
+        })
+    }
 }
 
 /// GetBlockTemplateRequest requests a current block template.
@@ -75,6 +125,10 @@ pub struct SubmitBlockResponse {
 #[serde(rename_all = "camelCase")]
 pub struct GetBlockTemplateRequest {
     /// Which kaspa address should the coinbase block reward transaction pay into
+    ///
+    /// Note that the reward cannot be split between several payout addresses: the address is committed to as a single
+    /// script public key in the coinbase payload, and consensus requires the coinbase outputs of the merging block to
+    /// match the rewards expected for these script public keys exactly. Pools split payouts after maturity instead.
     pub pay_address: RpcAddress,
     pub extra_data: RpcExtraData,
 }
@@ -112,10 +166,30 @@ impl GetBlockRequest {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetBlockResponse {
     pub block: RpcBlock,
+    /// Whether the block was pruned and is served out of the node's era archive, in which case its verbose data
+    /// carries only the fields derivable from the block itself. Encoded as a trailing field, see [`trailing`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_archival: bool,
+}
+
+impl BorshSerialize for GetBlockResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        BorshSerialize::serialize(&self.block, writer)?;
+        trailing::serialize_trailing(&self.is_archival.then_some(true), writer)
+    }
+}
+
+impl BorshDeserialize for GetBlockResponse {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Self {
+            block: <RpcBlock as BorshDeserialize>::deserialize(buf)?,
+            is_archival: trailing::deserialize_trailing(buf)?.unwrap_or_default(),
+        })
+    }
 }
 
 /// GetInfoRequest returns info about the node.
@@ -123,7 +197,7 @@ pub struct GetBlockResponse {
 #[serde(rename_all = "camelCase")]
 pub struct GetInfoRequest {}
 
-#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetInfoResponse {
     pub p2p_id: String,
@@ -133,6 +207,38 @@ pub struct GetInfoResponse {
     pub is_synced: bool,
     pub has_notify_command: bool,
     pub has_message_id: bool,
+    /// Whether the local clock drifts from the network time beyond the tolerance of header validation. Encoded as a
+    /// trailing field, see [`trailing`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_clock_drifted: bool,
+}
+
+impl BorshSerialize for GetInfoResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        BorshSerialize::serialize(&self.p2p_id, writer)?;
+        BorshSerialize::serialize(&self.mempool_size, writer)?;
+        BorshSerialize::serialize(&self.server_version, writer)?;
+        BorshSerialize::serialize(&self.is_utxo_indexed, writer)?;
+        BorshSerialize::serialize(&self.is_synced, writer)?;
+        BorshSerialize::serialize(&self.has_notify_command, writer)?;
+        BorshSerialize::serialize(&self.has_message_id, writer)?;
+        trailing::serialize_trailing(&self.is_clock_drifted.then_some(true), writer)
+    }
+}
+
+impl BorshDeserialize for GetInfoResponse {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Self {
+            p2p_id: <String as BorshDeserialize>::deserialize(buf)?,
+            mempool_size: <u64 as BorshDeserialize>::deserialize(buf)?,
+            server_version: <String as BorshDeserialize>::deserialize(buf)?,
+            is_utxo_indexed: <bool as BorshDeserialize>::deserialize(buf)?,
+            is_synced: <bool as BorshDeserialize>::deserialize(buf)?,
+            has_notify_command: <bool as BorshDeserialize>::deserialize(buf)?,
+            has_message_id: <bool as BorshDeserialize>::deserialize(buf)?,
+            is_clock_drifted: trailing::deserialize_trailing(buf)?.unwrap_or_default(),
+        })
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
@@ -237,19 +343,54 @@ impl GetMempoolEntriesResponse {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct GetConnectedPeerInfoRequest {}
+pub struct GetConnectedPeerInfoRequest {
+    /// Encoded as a trailing field, see [`trailing`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_traffic: bool,
+}
 
-#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+impl BorshSerialize for GetConnectedPeerInfoRequest {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        trailing::serialize_trailing(&self.include_traffic.then_some(true), writer)
+    }
+}
+
+impl BorshDeserialize for GetConnectedPeerInfoRequest {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Self { include_traffic: trailing::deserialize_trailing(buf)?.unwrap_or_default() })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetConnectedPeerInfoResponse {
     pub peer_info: Vec<RpcPeerInfo>,
+    /// The traffic of the peers, in the order of `peer_info`, if requested. Encoded as a trailing field, see [`trailing`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub peer_traffic: Vec<RpcPeerTraffic>,
+}
+
+impl BorshSerialize for GetConnectedPeerInfoResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        BorshSerialize::serialize(&self.peer_info, writer)?;
+        trailing::serialize_trailing(&(!self.peer_traffic.is_empty()).then_some(&self.peer_traffic), writer)
+    }
+}
+
+impl BorshDeserialize for GetConnectedPeerInfoResponse {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Self {
+            peer_info: <Vec<RpcPeerInfo> as BorshDeserialize>::deserialize(buf)?,
+            peer_traffic: trailing::deserialize_trailing(buf)?.unwrap_or_default(),
+        })
+    }
 }
 
 impl GetConnectedPeerInfoResponse {
-    pub fn new(peer_info: Vec<RpcPeerInfo>) -> Self {
-        Self { peer_info }
+    pub fn new(peer_info: Vec<RpcPeerInfo>, peer_traffic: Vec<RpcPeerTraffic>) -> Self {
+        Self { peer_info, peer_traffic }
     }
 }
 
@@ -679,11 +820,44 @@ pub struct PingResponse {}
 
 // TODO - custom wRPC commands (need review and implementation in gRPC)
 
-#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetMetricsRequest {
     pub process_metrics: bool,
     pub consensus_metrics: bool,
+
+    // The fields below are encoded as a group of trailing fields, see [`trailing`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub lifetime_metrics: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub method_metrics: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub p2p_message_metrics: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub listener_metrics: bool,
+}
+
+impl BorshSerialize for GetMetricsRequest {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        BorshSerialize::serialize(&self.process_metrics, writer)?;
+        BorshSerialize::serialize(&self.consensus_metrics, writer)?;
+        let is_extended = self.lifetime_metrics || self.method_metrics || self.p2p_message_metrics || self.listener_metrics;
+        trailing::serialize_trailing(
+            &is_extended.then_some((self.lifetime_metrics, self.method_metrics, self.p2p_message_metrics)),
+            writer,
+        )?;
+        trailing::serialize_trailing(&self.listener_metrics.then_some(true), writer)
+    }
+}
+
+impl BorshDeserialize for GetMetricsRequest {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let process_metrics = <bool as BorshDeserialize>::deserialize(buf)?;
+        let consensus_metrics = <bool as BorshDeserialize>::deserialize(buf)?;
+        let (lifetime_metrics, method_metrics, p2p_message_metrics) = trailing::deserialize_trailing(buf)?.unwrap_or_default();
+        let listener_metrics = trailing::deserialize_trailing(buf)?.unwrap_or_default();
+        Ok(Self { process_metrics, consensus_metrics, lifetime_metrics, method_metrics, p2p_message_metrics, listener_metrics })
+    }
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
@@ -719,17 +893,209 @@ pub struct ConsensusMetrics {
     pub mass_counts: u64,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+/// Consensus metrics added after [`ConsensusMetrics`], whose encoding is fixed, returned along with them
+#[derive(Default, Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendedConsensusMetrics {
+    /// The current number of DAG tips
+    pub tip_hashes_count: u64,
+    /// Number of transactions first seen in the mempool
+    pub tx_mempool_first_seen_count: u64,
+    /// Number of transactions first seen in a received block
+    pub tx_block_first_seen_count: u64,
+    /// Percentiles (in milliseconds) of the time elapsed between a transaction entering the mempool and a block including it being received
+    pub tx_inclusion_latency_p50: u64,
+    pub tx_inclusion_latency_p90: u64,
+    pub tx_inclusion_latency_p99: u64,
+    /// Percentiles (in milliseconds) of the time elapsed between the timestamp of a block and its reception, for transactions first seen in it
+    pub tx_propagation_latency_p50: u64,
+    pub tx_propagation_latency_p90: u64,
+    pub tx_propagation_latency_p99: u64,
+    /// The bytes written by compactions of the consensus database per byte written by consensus
+    pub storage_write_amplification: f64,
+    /// Estimated total bytes pending compaction in the consensus database
+    pub storage_pending_compaction_bytes: u64,
+    /// The number of compactions of the consensus database currently running
+    pub storage_running_compactions: u64,
+    /// The cumulative time (in seconds) writes to the consensus database were stalled
+    pub storage_stall_seconds: f64,
+    /// The current delayed write rate (in bytes per second) of the consensus database, 0 if writes are not slowed down
+    pub storage_delayed_write_rate: u64,
+    /// Whether writes to the consensus database are currently stopped
+    pub storage_write_stopped: bool,
+    /// The submitted blocks by origin, locally inserted blocks being only counted by `blocks_submitted`
+    pub rpc_blocks_submitted: u64,
+    pub relay_blocks_submitted: u64,
+    pub ibd_blocks_submitted: u64,
+    /// The background verification of recently processed consensus data, all zero unless enabled by `--integrity-check`
+    pub integrity_check_rounds: u64,
+    pub integrity_checked_blocks: u64,
+    pub integrity_violations: u64,
+    /// The relay invs of blocks and transactions which were recently processed and thus were not requested again
+    pub relay_block_inv_hits: u64,
+    pub relay_block_inv_misses: u64,
+    pub relay_transaction_inv_hits: u64,
+    pub relay_transaction_inv_misses: u64,
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LifetimeRunRecord {
+    /// Unix time in milliseconds
+    pub start_time: u64,
+    pub uptime_seconds: u64,
+}
+
+/// Cumulative counters of the node surviving restarts, until reset by `ResetLifetimeMetrics`
+#[derive(Default, Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LifetimeMetrics {
+    /// Unix time in milliseconds the metrics are accumulated since, i.e. of the first start of the node or of the last reset
+    pub since: u64,
+    pub blocks_processed: u64,
+    pub txs_processed: u64,
+    pub uptime_seconds: u64,
+    /// The number of times the node was started
+    pub starts: u64,
+    /// The most recent runs of the node by ascending start time, the last being the current run
+    pub recent_runs: Vec<LifetimeRunRecord>,
+}
+
+/// The calls served by an RPC method since the node started
+#[derive(Default, Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcMethodMetrics {
+    pub method: String,
+    pub calls: u64,
+    /// The number of calls which returned an error
+    pub errors: u64,
+    pub total_latency_micros: u64,
+    /// The number of calls per latency bucket, bounded by [`crate::metrics::LATENCY_BUCKET_BOUNDS_MS`] and followed by a
+    /// bucket counting the slower calls
+    pub latency_buckets: Vec<u64>,
+}
+
+/// The P2P traffic of a message type since the node started, the size of a message being the length of its protobuf encoding
+#[derive(Default, Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct P2pMessageMetrics {
+    pub message_type: String,
+    pub sent_messages: u64,
+    pub sent_bytes: u64,
+    pub received_messages: u64,
+    pub received_bytes: u64,
+}
+
+/// The notification queue of an RPC notification listener, helping to diagnose clients consuming their notifications
+/// too slowly
+#[derive(Default, Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcListenerMetrics {
+    /// The notifier the listener is registered with (e.g., `grpc-server` or `wrpc-server`)
+    pub notifier: String,
+    pub listener_id: u64,
+    pub queue_len: u64,
+    /// The capacity of the queue, 0 if unbounded
+    pub queue_capacity: u64,
+    /// The number of notifications dropped because the queue was full
+    pub dropped_notifications: u64,
+    /// Whether the queue is currently full, the notifications of the listener being dropped
+    pub lagging: bool,
+    /// Whether the listener got evicted for consuming its notifications too slowly
+    pub overflowed: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetMetricsResponse {
     pub server_time: u64,
     pub process_metrics: Option<ProcessMetrics>,
     pub consensus_metrics: Option<ConsensusMetrics>,
+
+    // The fields below are encoded as a group of trailing fields, see [`trailing`]
+    /// Returned if requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lifetime_metrics: Option<LifetimeMetrics>,
+    /// Returned if requested, empty otherwise
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub method_metrics: Vec<RpcMethodMetrics>,
+    /// Returned if requested, empty otherwise
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub p2p_message_metrics: Vec<P2pMessageMetrics>,
+    /// Returned along with `consensus_metrics`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extended_consensus_metrics: Option<ExtendedConsensusMetrics>,
+    /// Returned if requested, empty otherwise
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub listener_metrics: Vec<RpcListenerMetrics>,
+}
+
+impl BorshSerialize for GetMetricsResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        BorshSerialize::serialize(&self.server_time, writer)?;
+        BorshSerialize::serialize(&self.process_metrics, writer)?;
+        BorshSerialize::serialize(&self.consensus_metrics, writer)?;
+        let is_extended = self.lifetime_metrics.is_some()
+            || !self.method_metrics.is_empty()
+            || !self.p2p_message_metrics.is_empty()
+            || self.extended_consensus_metrics.is_some()
+            || !self.listener_metrics.is_empty();
+        trailing::serialize_trailing(
+            &is_extended.then_some((
+                &self.lifetime_metrics,
+                &self.method_metrics,
+                &self.p2p_message_metrics,
+                &self.extended_consensus_metrics,
+            )),
+            writer,
+        )?;
+        trailing::serialize_trailing(&(!self.listener_metrics.is_empty()).then_some(&self.listener_metrics), writer)
+    }
+}
+
+impl BorshDeserialize for GetMetricsResponse {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let server_time = <u64 as BorshDeserialize>::deserialize(buf)?;
+        let process_metrics = <Option<ProcessMetrics> as BorshDeserialize>::deserialize(buf)?;
+        let consensus_metrics = <Option<ConsensusMetrics> as BorshDeserialize>::deserialize(buf)?;
+        let (lifetime_metrics, method_metrics, p2p_message_metrics, extended_consensus_metrics) =
+            trailing::deserialize_trailing(buf)?.unwrap_or_default();
+        let listener_metrics = trailing::deserialize_trailing(buf)?.unwrap_or_default();
+        Ok(Self {
+            server_time,
+            process_metrics,
+            consensus_metrics,
+            lifetime_metrics,
+            method_metrics,
+            p2p_message_metrics,
+            extended_consensus_metrics,
+            listener_metrics,
+        })
+    }
 }
 
 impl GetMetricsResponse {
-    pub fn new(server_time: u64, process_metrics: Option<ProcessMetrics>, consensus_metrics: Option<ConsensusMetrics>) -> Self {
-        Self { process_metrics, consensus_metrics, server_time }
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        server_time: u64,
+        process_metrics: Option<ProcessMetrics>,
+        consensus_metrics: Option<ConsensusMetrics>,
+        lifetime_metrics: Option<LifetimeMetrics>,
+        method_metrics: Vec<RpcMethodMetrics>,
+        p2p_message_metrics: Vec<P2pMessageMetrics>,
+        extended_consensus_metrics: Option<ExtendedConsensusMetrics>,
+        listener_metrics: Vec<RpcListenerMetrics>,
+    ) -> Self {
+        Self {
+            process_metrics,
+            consensus_metrics,
+            lifetime_metrics,
+            method_metrics,
+            p2p_message_metrics,
+            extended_consensus_metrics,
+            listener_metrics,
+            server_time,
+        }
     }
 }
 
@@ -782,6 +1148,573 @@ impl GetDaaScoreTimestampEstimateResponse {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionRequest {
+    pub transaction_id: RpcTransactionId,
+    pub include_proof: bool,
+}
+
+impl GetTransactionRequest {
+    pub fn new(transaction_id: RpcTransactionId, include_proof: bool) -> Self {
+        Self { transaction_id, include_proof }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionResponse {
+    pub transaction: RpcTransaction,
+    /// The block including the transaction
+    pub block_hash: RpcHash,
+    /// The chain block accepting the transaction
+    pub accepting_block_hash: RpcHash,
+    /// Number of chain blue scores since the acceptance of the transaction, starting at 1 for the sink
+    pub confirmations: u64,
+    pub index_within_block: u32,
+    /// Merkle branch from the transaction hash up to the hash merkle root of the including block.
+    /// Empty unless a proof was requested.
+    pub merkle_branch: Vec<RpcHash>,
+}
+
+impl GetTransactionResponse {
+    pub fn new(
+        transaction: RpcTransaction,
+        block_hash: RpcHash,
+        accepting_block_hash: RpcHash,
+        confirmations: u64,
+        index_within_block: u32,
+        merkle_branch: Vec<RpcHash>,
+    ) -> Self {
+        Self { transaction, block_hash, accepting_block_hash, confirmations, index_within_block, merkle_branch }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlockValidationTraceRequest {
+    pub hash: RpcHash,
+}
+
+impl GetBlockValidationTraceRequest {
+    pub fn new(hash: RpcHash) -> Self {
+        Self { hash }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcRuleTrace {
+    pub rule: String,
+    pub duration_micros: u64,
+    pub passed: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlockValidationTraceResponse {
+    /// The evaluated rules in evaluation order
+    pub rules: Vec<RpcRuleTrace>,
+    pub total_duration_micros: u64,
+}
+
+impl GetBlockValidationTraceResponse {
+    pub fn new(rules: Vec<RpcRuleTrace>, total_duration_micros: u64) -> Self {
+        Self { rules, total_duration_micros }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetHealthRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcServiceHealth {
+    pub name: String,
+    /// One of `starting`, `ready`, `degraded` or `stopped`
+    pub status: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetHealthResponse {
+    /// Whether all services are able to serve requests, possibly with limited functionality
+    pub is_ready: bool,
+    /// The least healthy status among all services
+    pub status: String,
+    pub services: Vec<RpcServiceHealth>,
+}
+
+impl GetHealthResponse {
+    pub fn new(is_ready: bool, status: String, services: Vec<RpcServiceHealth>) -> Self {
+        Self { is_ready, status, services }
+    }
+}
+
+/// Updates node settings at runtime. Settings left as `None` keep their current value.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadSettingsRequest {
+    /// Logging filters in the format of the `--loglevel` argument, e.g. `info,kaspa_p2p_flows=debug`
+    pub log_level: Option<String>,
+    pub outbound_target: Option<u64>,
+    pub inbound_limit: Option<u64>,
+    pub mempool_max_transactions: Option<u64>,
+    pub mempool_max_orphans: Option<u64>,
+}
+
+/// The settings in effect after a reload
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadSettingsResponse {
+    /// The peer connection targets. Both are zero while the P2P service is not started yet
+    pub outbound_target: u64,
+    pub inbound_limit: u64,
+    pub mempool_max_transactions: u64,
+    pub mempool_max_orphans: u64,
+}
+
+impl ReloadSettingsResponse {
+    pub fn new(outbound_target: u64, inbound_limit: u64, mempool_max_transactions: u64, mempool_max_orphans: u64) -> Self {
+        Self { outbound_target, inbound_limit, mempool_max_transactions, mempool_max_orphans }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCurrentDifficultyRequest {}
+
+/// The difficulty of the virtual block, which is the difficulty of the next block to be mined
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCurrentDifficultyResponse {
+    /// The target in its compact encoding, as found in block headers
+    pub bits: u32,
+    /// The ratio of the maximal target of the network to the target
+    pub difficulty: f64,
+}
+
+impl GetCurrentDifficultyResponse {
+    pub fn new(bits: u32, difficulty: f64) -> Self {
+        Self { bits, difficulty }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlockDagTimingInfoRequest {
+    pub hash: RpcHash,
+}
+
+impl GetBlockDagTimingInfoRequest {
+    pub fn new(hash: RpcHash) -> Self {
+        Self { hash }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlockDagTimingInfoResponse {
+    pub timestamp: u64,
+    pub daa_score: u64,
+    /// The bits found in the block header
+    pub bits: u32,
+    /// The bits consensus expects the header to carry, as computed from the difficulty window
+    pub expected_bits: u32,
+    pub past_median_time: u64,
+    /// The number of blocks in the difficulty window
+    pub window_size: u64,
+    pub window_sample_rate: u64,
+    /// The window block with the lowest blue work, absent if the window is empty
+    pub window_lowest_block: Option<RpcHash>,
+    /// The window block with the highest blue work, absent if the window is empty
+    pub window_highest_block: Option<RpcHash>,
+    pub window_min_timestamp: u64,
+    pub window_max_timestamp: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUtxoSetChunkRequest {
+    /// The `next_cursor` of the previous chunk, `None` to start a new export
+    pub cursor: Option<String>,
+}
+
+impl GetUtxoSetChunkRequest {
+    pub fn new(cursor: Option<String>) -> Self {
+        Self { cursor }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUtxoSetChunkResponse {
+    pub entries: Vec<RpcUtxosByAddressesEntry>,
+    /// The cursor of the next chunk, `None` once the export is complete
+    pub next_cursor: Option<String>,
+    /// The virtual DAA score at the time the exported snapshot was pinned
+    pub snapshot_daa_score: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetStateDigestsRequest {
+    pub count: u32,
+}
+
+impl GetStateDigestsRequest {
+    pub fn new(count: u32) -> Self {
+        Self { count }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcStateDigest {
+    /// The checkpoint chain block
+    pub hash: RpcHash,
+    pub blue_score: u64,
+    pub daa_score: u64,
+    pub utxo_commitment: RpcHash,
+    /// The hash of the block hash, DAA score and UTXO commitment
+    pub digest: RpcHash,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetStateDigestsResponse {
+    /// The blue score interval between consecutive checkpoints
+    pub interval: u64,
+    pub digests: Vec<RpcStateDigest>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDagBlocksAroundRequest {
+    pub hash: RpcHash,
+    /// The maximal number of parent or child edges separating a returned block from `hash`
+    pub radius: u32,
+}
+
+impl GetDagBlocksAroundRequest {
+    pub fn new(hash: RpcHash, radius: u32) -> Self {
+        Self { hash, radius }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcDagBlock {
+    pub hash: RpcHash,
+    /// The number of parent or child edges separating the block from the requested one
+    pub distance: u32,
+    pub timestamp: u64,
+    pub daa_score: u64,
+    pub blue_score: u64,
+    pub parent_hashes: Vec<RpcHash>,
+    pub children_hashes: Vec<RpcHash>,
+    pub selected_parent_hash: RpcHash,
+    pub merge_set_blues_hashes: Vec<RpcHash>,
+    pub merge_set_reds_hashes: Vec<RpcHash>,
+    pub is_chain_block: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDagBlocksAroundResponse {
+    /// The blocks of the neighborhood, closest first
+    pub blocks: Vec<RpcDagBlock>,
+    /// Whether the neighborhood was cut short because it exceeds the maximal number of returned blocks
+    pub truncated: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlockRelationsRequest {
+    pub hash: RpcHash,
+}
+
+impl GetBlockRelationsRequest {
+    pub fn new(hash: RpcHash) -> Self {
+        Self { hash }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlockRelationsResponse {
+    pub parent_hashes: Vec<RpcHash>,
+    pub children_hashes: Vec<RpcHash>,
+    pub selected_parent_hash: RpcHash,
+    pub merge_set_blues_hashes: Vec<RpcHash>,
+    pub merge_set_reds_hashes: Vec<RpcHash>,
+    pub blue_score: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionAcceptanceProofRequest {
+    pub transaction_id: RpcTransactionId,
+    /// The chain block accepting the transaction, looked up in the transaction index if missing
+    pub accepting_block_hash: Option<RpcHash>,
+}
+
+impl GetTransactionAcceptanceProofRequest {
+    pub fn new(transaction_id: RpcTransactionId, accepting_block_hash: Option<RpcHash>) -> Self {
+        Self { transaction_id, accepting_block_hash }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionAcceptanceProofResponse {
+    pub proof: RpcTransactionAcceptanceProof,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidateBlockRequest {
+    pub hash: RpcHash,
+}
+
+impl InvalidateBlockRequest {
+    pub fn new(hash: RpcHash) -> Self {
+        Self { hash }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidateBlockResponse {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconsiderBlockRequest {
+    pub hash: RpcHash,
+}
+
+impl ReconsiderBlockRequest {
+    pub fn new(hash: RpcHash) -> Self {
+        Self { hash }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconsiderBlockResponse {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNetworkStatsRequest {}
+
+/// Rolling statistics over the most recently processed block headers
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNetworkStatsResponse {
+    /// The number of blocks the statistics are computed over
+    pub window_size: u64,
+    /// Intervals (in milliseconds) between the timestamps of the window blocks, in timestamp order
+    pub inter_arrival_mean: u64,
+    pub inter_arrival_p50: u64,
+    pub inter_arrival_p90: u64,
+    pub inter_arrival_p99: u64,
+    pub inter_arrival_max: u64,
+    /// The average number of direct parents of the window blocks
+    pub average_parent_count: f64,
+    /// The number of blocks merged as blue by the window blocks, selected parents included
+    pub mergeset_blues: u64,
+    /// The number of blocks merged as red by the window blocks
+    pub mergeset_reds: u64,
+    /// The ratio of blue blocks among the merged blocks
+    pub blue_ratio: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMempoolFeeHistogramRequest {
+    /// Strictly ascending fee rates (in sompi per gram of mass) delimiting the buckets, the first bucket starting at 0
+    /// and the last one being unbounded. Default bounds are used if empty.
+    pub bucket_bounds: Vec<f64>,
+}
+
+impl GetMempoolFeeHistogramRequest {
+    pub fn new(bucket_bounds: Vec<f64>) -> Self {
+        Self { bucket_bounds }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMempoolFeeHistogramResponse {
+    /// The buckets in ascending fee rate order
+    pub buckets: Vec<RpcFeeRateBucket>,
+}
+
+impl GetMempoolFeeHistogramResponse {
+    pub fn new(buckets: Vec<RpcFeeRateBucket>) -> Self {
+        Self { buckets }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRelayPolicyRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRelayPolicyResponse {
+    /// Minimum fee in sompi per kilogram (1000 grams) of transaction mass
+    pub minimum_relay_fee_rate: u64,
+    /// Outputs with a lower value (in sompi) are rejected as dust
+    pub minimum_output_value: u64,
+    /// The minimal value (in sompi) of a pay-to-pubkey output not rejected as dust
+    pub pay_to_pubkey_dust_threshold: u64,
+    /// Whether non-standard transactions are accepted
+    pub accept_non_standard: bool,
+    /// Maximal number of unconfirmed ancestors in the mempool of a transaction entering it (0 for no limit)
+    pub maximum_ancestor_count: u64,
+    /// Maximal number of unconfirmed descendants in the mempool of any transaction (0 for no limit)
+    pub maximum_descendant_count: u64,
+    /// Maximal cumulative mass of a transaction entering the mempool and its unconfirmed ancestors (0 for no limit)
+    pub maximum_chain_mass: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUtxoReturnAddressRequest {
+    /// The transaction creating the UTXO
+    pub transaction_id: RpcTransactionId,
+}
+
+impl GetUtxoReturnAddressRequest {
+    pub fn new(transaction_id: RpcTransactionId) -> Self {
+        Self { transaction_id }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUtxoReturnAddressResponse {
+    /// The address of the output spent by the first input of the transaction
+    pub return_address: RpcAddress,
+}
+
+impl GetUtxoReturnAddressResponse {
+    pub fn new(return_address: RpcAddress) -> Self {
+        Self { return_address }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSpendingTransactionRequest {
+    pub outpoint: RpcTransactionOutpoint,
+}
+
+impl GetSpendingTransactionRequest {
+    pub fn new(outpoint: RpcTransactionOutpoint) -> Self {
+        Self { outpoint }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSpendingTransactionResponse {
+    pub transaction_id: RpcTransactionId,
+    /// The chain block accepting the spending transaction
+    pub accepting_block_hash: RpcHash,
+}
+
+impl GetSpendingTransactionResponse {
+    pub fn new(transaction_id: RpcTransactionId, accepting_block_hash: RpcHash) -> Self {
+        Self { transaction_id, accepting_block_hash }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StartBlockProductionRequest {
+    /// Which kaspa address should the coinbase of the produced blocks pay into
+    pub pay_address: RpcAddress,
+    /// The average number of blocks produced per second
+    pub blocks_per_second: f64,
+}
+
+impl StartBlockProductionRequest {
+    pub fn new(pay_address: RpcAddress, blocks_per_second: f64) -> Self {
+        Self { pay_address, blocks_per_second }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StartBlockProductionResponse {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StopBlockProductionRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StopBlockProductionResponse {
+    /// Whether blocks were being produced
+    pub was_producing: bool,
+    /// The number of produced blocks accepted by consensus since the node started
+    pub blocks_produced: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetLifetimeMetricsRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetLifetimeMetricsResponse {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSystemInfoRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSystemInfoResponse {
+    pub version: String,
+    /// The abbreviated hash of the commit the node was built from, if known
+    pub git_hash: Option<String>,
+    /// The number of logical CPU cores of the host
+    pub cpu_cores: u32,
+    /// The total memory of the host in bytes
+    pub total_memory: u64,
+    /// A stable id of the node installation generated on its first run, unrelated to its p2p id
+    pub system_id: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckTransactionRequest {
+    pub transaction: RpcTransaction,
+}
+
+impl CheckTransactionRequest {
+    pub fn new(transaction: RpcTransaction) -> Self {
+        Self { transaction }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckTransactionResponse {
+    pub transaction_id: RpcTransactionId,
+    pub mass: u64,
+    /// The fee paid by the transaction, known once all the UTXO entries it spends are found
+    pub fee: Option<u64>,
+    /// The fee paid per gram of mass
+    pub fee_rate: Option<f64>,
+    /// The first rule the transaction violates, `None` if the mempool would accept it
+    pub rule_error: Option<String>,
+}
+
 // ----------------------------------------------------------------------------
 // Subscriptions & notifications
 // ----------------------------------------------------------------------------
@@ -961,6 +1894,30 @@ impl UtxosChangedNotification {
     fn filter_utxos(utxo_set: &[RpcUtxosByAddressesEntry], subscription: &UtxosChangedSubscription) -> Vec<RpcUtxosByAddressesEntry> {
         utxo_set.iter().filter(|x| subscription.addresses().contains_key(&x.utxo_entry.script_public_key)).cloned().collect()
     }
+
+    /// Splits the UTXO changes among the indexed subscriptions they affect, looking up each change once in the index
+    pub(crate) fn apply_utxos_changed_index(&self, index: &UtxosChangedIndex) -> Vec<(usize, Self)> {
+        let mut changes: HashMap<usize, (Vec<RpcUtxosByAddressesEntry>, Vec<RpcUtxosByAddressesEntry>)> = HashMap::new();
+        for entry in self.added.iter() {
+            for position in index.positions(&entry.utxo_entry.script_public_key) {
+                changes.entry(*position).or_default().0.push(entry.clone());
+            }
+        }
+        for entry in self.removed.iter() {
+            for position in index.positions(&entry.utxo_entry.script_public_key) {
+                changes.entry(*position).or_default().1.push(entry.clone());
+            }
+        }
+        debug!(
+            "CRPC, Creating UtxosChanged notifications for {} of {} indexed subscriptions",
+            changes.len(),
+            index.subscriptions().len()
+        );
+        changes
+            .into_iter()
+            .map(|(position, (added, removed))| (position, Self { added: Arc::new(added), removed: Arc::new(removed) }))
+            .collect()
+    }
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -1081,6 +2038,160 @@ pub struct NotifyNewBlockTemplateResponse {}
 #[serde(rename_all = "camelCase")]
 pub struct NewBlockTemplateNotification {}
 
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// MempoolTransactionRemovedNotification
+
+/// NotifyMempoolTransactionRemovedRequest registers this connection for mempoolTransactionRemoved notifications.
+///
+/// See: MempoolTransactionRemovedNotification
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyMempoolTransactionRemovedRequest {
+    pub command: Command,
+}
+
+impl NotifyMempoolTransactionRemovedRequest {
+    pub fn new(command: Command) -> Self {
+        Self { command }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyMempoolTransactionRemovedResponse {}
+
+/// MempoolTransactionRemovedNotification is sent whenever a transaction is dropped from the mempool
+/// without being accepted by a block, so its issuer can mark it as dropped and rebuild it.
+///
+/// See: NotifyMempoolTransactionRemovedRequest
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolTransactionRemovedNotification {
+    pub transaction_id: RpcTransactionId,
+    pub reason: RpcMempoolRemovalReason,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// AcceptedTransactionsNotification
+
+/// NotifyAcceptedTransactionsRequest registers this connection for acceptedTransactions notifications
+/// for the given addresses. Depending on the provided `command`, notifications will start or stop
+/// for the provided `addresses`.
+///
+/// If `addresses` is empty, the notifications will start or stop for all accepted transactions.
+///
+/// See: AcceptedTransactionsNotification
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyAcceptedTransactionsRequest {
+    pub addresses: Vec<RpcAddress>,
+    pub command: Command,
+}
+
+impl NotifyAcceptedTransactionsRequest {
+    pub fn new(addresses: Vec<RpcAddress>, command: Command) -> Self {
+        Self { addresses, command }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyAcceptedTransactionsResponse {}
+
+/// AcceptedTransactionsNotification is sent whenever chain blocks are added to the selected chain, carrying
+/// the full transactions they accept, with their verbose data, which pay to one of the subscribed addresses.
+/// Wallets can thus process incoming payments without a GetTransaction round trip.
+///
+/// See: NotifyAcceptedTransactionsRequest
+#[derive(Clone, Debug, Default, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptedTransactionsNotification {
+    pub accepted_transactions: Arc<Vec<RpcAcceptedTransactions>>,
+}
+
+impl AcceptedTransactionsNotification {
+    pub(crate) fn apply_accepted_transactions_subscription(&self, subscription: &AcceptedTransactionsSubscription) -> Option<Self> {
+        if subscription.to_all() {
+            Some(self.clone())
+        } else {
+            let accepted_transactions = self
+                .accepted_transactions
+                .iter()
+                .map(|x| RpcAcceptedTransactions {
+                    accepting_block_hash: x.accepting_block_hash,
+                    accepted_transactions: x
+                        .accepted_transactions
+                        .iter()
+                        .filter(|tx| {
+                            tx.outputs.iter().any(|output| subscription.contains_script_public_key(&output.script_public_key))
+                        })
+                        .cloned()
+                        .collect(),
+                })
+                .filter(|x| !x.accepted_transactions.is_empty())
+                .collect::<Vec<_>>();
+            debug!("CRPC, Creating AcceptedTransactions notifications for {} accepting blocks", accepted_transactions.len());
+            if accepted_transactions.is_empty() {
+                None
+            } else {
+                Some(Self { accepted_transactions: Arc::new(accepted_transactions) })
+            }
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// SyncStateChangedNotification
+
+/// NotifySyncStateChangedRequest registers this connection for syncStateChanged notifications.
+///
+/// See: SyncStateChangedNotification
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifySyncStateChangedRequest {
+    pub command: Command,
+}
+
+impl NotifySyncStateChangedRequest {
+    pub fn new(command: Command) -> Self {
+        Self { command }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifySyncStateChangedResponse {}
+
+/// The synchronization state of the node, following the phases of an IBD session
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcSyncState {
+    /// An IBD session started with a syncer peer
+    IbdStarted,
+    /// The pruning point proof and the trusted data of the pruning point are downloaded and applied
+    HeadersProof,
+    /// Block headers are downloaded
+    Headers,
+    /// The UTXO set of the pruning point is imported
+    UtxoSetImport,
+    /// Block bodies are downloaded
+    Bodies,
+    /// The IBD session completed, the node being synced with the syncer peer
+    IbdCompleted,
+    /// The IBD session failed, the next syncer candidate taking over if any
+    IbdFailed,
+}
+
+/// SyncStateChangedNotification is sent whenever an IBD session starts, enters a new phase or ends, so that the
+/// sync progress of the node can be reported accurately.
+///
+/// See: NotifySyncStateChangedRequest
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStateChangedNotification {
+    pub state: RpcSyncState,
+}
+
 ///
 ///  wRPC response for RpcApiOps::Subscribe request
 ///
@@ -1102,3 +2213,36 @@ impl SubscribeResponse {
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UnsubscribeResponse {}
+
+///
+///  wRPC request for RpcApiOps::Handshake, carrying the RPC API version spoken by the client
+///
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HandshakeRequest {
+    pub rpc_api_version: [u16; 4],
+}
+
+impl HandshakeRequest {
+    pub fn new(rpc_api_version: [u16; 4]) -> Self {
+        Self { rpc_api_version }
+    }
+}
+
+///
+///  wRPC response for RpcApiOps::Handshake request
+///
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HandshakeResponse {
+    /// RPC API version spoken by the server
+    pub rpc_api_version: [u16; 4],
+    /// Version of the messages the server exchanges with this connection
+    pub negotiated_rpc_api_version: [u16; 4],
+}
+
+impl HandshakeResponse {
+    pub fn new(rpc_api_version: [u16; 4], negotiated_rpc_api_version: [u16; 4]) -> Self {
+        Self { rpc_api_version, negotiated_rpc_api_version }
+    }
+}
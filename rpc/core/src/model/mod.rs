@@ -10,8 +10,12 @@ pub mod network;
 pub mod peer;
 pub mod script_class;
 pub mod subnets;
+pub mod trailing;
 pub mod tx;
 
+#[cfg(test)]
+mod golden;
+
 pub use address::*;
 pub use block::*;
 pub use blue_work::*;
@@ -1,3 +1,4 @@
+use crate::prelude::RpcHash;
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use kaspa_utils::networking::{ContextualNetAddress, IpAddress, NetAddress, PeerId};
 use serde::{Deserialize, Serialize};
@@ -20,4 +21,17 @@ pub struct RpcPeerInfo {
     pub advertised_protocol_version: u32,
     pub time_connected: u64, // NOTE: i64 in gRPC protowire
     pub is_ibd_peer: bool,
+
+    /// The highest DAA score claimed by the peer, along with the respective block
+    pub claimed_sink: Option<RpcHash>,
+    pub claimed_daa_score: u64,
+}
+
+/// The P2P traffic exchanged with a peer since it connected, in bytes of protobuf-encoded messages
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPeerTraffic {
+    pub id: RpcNodeId,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
 }
@@ -0,0 +1,75 @@
+//!
+//! Borsh encoding of the optional fields appended to existing RPC messages.
+//!
+//! A trailing field is encoded only when set and decoded only if bytes remain, so a message leaving it unset keeps
+//! the encoding of the RPC API versions preceding the field, and a message of such a version decodes with the field
+//! unset. This only holds for the last fields of a top-level request or response, since the message then ends the
+//! payload.
+//!
+//! Fields appended together are encoded as a group, i.e. as a tuple encoded in full once any of them is set. Fields
+//! appended to a message already ending with a group form a new group, the former group being encoded whenever the new
+//! one is.
+//!
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::io::{Result, Write};
+
+/// Encodes a trailing field, writing nothing if unset
+pub fn serialize_trailing<T: BorshSerialize, W: Write>(field: &Option<T>, writer: &mut W) -> Result<()> {
+    match field {
+        Some(value) => value.serialize(writer),
+        None => Ok(()),
+    }
+}
+
+/// Decodes a trailing field, which is unset if the message ends before it
+pub fn deserialize_trailing<T: BorshDeserialize>(buf: &mut &[u8]) -> Result<Option<T>> {
+    match buf.is_empty() {
+        true => Ok(None),
+        false => T::deserialize(buf).map(Some),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+    struct Legacy {
+        value: u64,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Extended {
+        value: u64,
+        extension: Option<u32>,
+    }
+
+    impl BorshSerialize for Extended {
+        fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+            self.value.serialize(writer)?;
+            serialize_trailing(&self.extension, writer)
+        }
+    }
+
+    impl BorshDeserialize for Extended {
+        fn deserialize(buf: &mut &[u8]) -> Result<Self> {
+            Ok(Self { value: u64::deserialize(buf)?, extension: deserialize_trailing(buf)? })
+        }
+    }
+
+    #[test]
+    fn test_trailing_fields() {
+        // An unset trailing field keeps the legacy encoding, both ways
+        let legacy = Legacy { value: 7 }.try_to_vec().unwrap();
+        assert_eq!(Extended { value: 7, extension: None }.try_to_vec().unwrap(), legacy);
+        assert_eq!(Extended::try_from_slice(&legacy).unwrap(), Extended { value: 7, extension: None });
+        assert_eq!(
+            Legacy::try_from_slice(&Extended { value: 7, extension: None }.try_to_vec().unwrap()).unwrap(),
+            Legacy { value: 7 }
+        );
+
+        let extended = Extended { value: 7, extension: Some(3) };
+        assert_eq!(Extended::try_from_slice(&extended.try_to_vec().unwrap()).unwrap(), extended);
+    }
+}
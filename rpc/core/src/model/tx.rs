@@ -1,5 +1,6 @@
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use kaspa_addresses::Address;
+use kaspa_consensus_core::acceptance_proof::TransactionAcceptanceProof;
 use kaspa_consensus_core::tx::{
     ScriptPublicKey, ScriptVec, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry,
 };
@@ -17,6 +18,9 @@ pub type RpcUtxoEntry = UtxoEntry;
 /// Represents a Kaspa transaction outpoint
 pub type RpcTransactionOutpoint = TransactionOutpoint;
 
+/// Represents a proof of the acceptance of a Kaspa transaction by a chain block
+pub type RpcTransactionAcceptanceProof = TransactionAcceptanceProof;
+
 /// Represents a Kaspa transaction input
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
@@ -112,3 +116,11 @@ pub struct RpcAcceptedTransactionIds {
     pub accepting_block_hash: RpcHash,
     pub accepted_transaction_ids: Vec<RpcTransactionId>,
 }
+
+/// Represents the transactions accepted by a chain block
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAcceptedTransactions {
+    pub accepting_block_hash: RpcHash,
+    pub accepted_transactions: Vec<RpcTransaction>,
+}
@@ -0,0 +1,16 @@
+//! Request tracing. Every RPC request served by the gRPC and wRPC servers is instrumented with a span named
+//! after the RPC method and carrying a correlation ID, the consensus calls and store reads performed while
+//! serving the request being recorded as child spans. Spans are only collected when a subscriber is
+//! installed (see the `--trace-requests` kaspad argument), making the instrumentation nearly free otherwise.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub use tracing;
+pub use tracing::Instrument;
+
+static NEXT_CORRELATION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Returns a new ID, unique for the lifetime of the process, correlating the spans of a single request
+pub fn next_correlation_id() -> u64 {
+    NEXT_CORRELATION_ID.fetch_add(1, Ordering::Relaxed)
+}
@@ -31,7 +31,11 @@ use kaspa_rpc_core::{
     notify::{collector::RpcCoreConverter, connection::ChannelConnection, mode::NotificationMode},
     Notification,
 };
-use kaspa_utils::{channel::Channel, triggers::DuplexTrigger};
+use kaspa_utils::{
+    backoff::{Backoff, BackoffPolicy},
+    channel::Channel,
+    triggers::DuplexTrigger,
+};
 use regex::Regex;
 use std::{
     sync::{
@@ -215,6 +219,29 @@ impl RpcApi for GrpcClient {
     route!(get_mempool_entries_by_addresses_call, GetMempoolEntriesByAddresses);
     route!(get_coin_supply_call, GetCoinSupply);
     route!(get_daa_score_timestamp_estimate_call, GetDaaScoreTimestampEstimate);
+    route!(get_transaction_call, GetTransaction);
+    route!(get_block_validation_trace_call, GetBlockValidationTrace);
+    route!(get_health_call, GetHealth);
+    route!(reload_settings_call, ReloadSettings);
+    route!(get_current_difficulty_call, GetCurrentDifficulty);
+    route!(get_block_dag_timing_info_call, GetBlockDagTimingInfo);
+    route!(get_utxo_set_chunk_call, GetUtxoSetChunk);
+    route!(get_state_digests_call, GetStateDigests);
+    route!(get_dag_blocks_around_call, GetDagBlocksAround);
+    route!(get_block_relations_call, GetBlockRelations);
+    route!(get_transaction_acceptance_proof_call, GetTransactionAcceptanceProof);
+    route!(invalidate_block_call, InvalidateBlock);
+    route!(reconsider_block_call, ReconsiderBlock);
+    route!(get_network_stats_call, GetNetworkStats);
+    route!(get_mempool_fee_histogram_call, GetMempoolFeeHistogram);
+    route!(get_relay_policy_call, GetRelayPolicy);
+    route!(get_utxo_return_address_call, GetUtxoReturnAddress);
+    route!(get_spending_transaction_call, GetSpendingTransaction);
+    route!(start_block_production_call, StartBlockProduction);
+    route!(stop_block_production_call, StopBlockProduction);
+    route!(reset_lifetime_metrics_call, ResetLifetimeMetrics);
+    route!(get_system_info_call, GetSystemInfo);
+    route!(check_transaction_call, CheckTransaction);
 
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
@@ -280,6 +307,10 @@ pub const KEEP_ALIVE_DURATION: u64 = 5_000;
 pub const REQUEST_TIMEOUT_DURATION: u64 = 5_000;
 pub const TIMEOUT_MONITORING_INTERVAL: u64 = 10_000;
 pub const RECONNECT_INTERVAL: u64 = 2_000;
+/// Upper bound of the delay between consecutive failing reconnection attempts
+pub const MAX_RECONNECT_INTERVAL: u64 = 60_000;
+/// Maximum number of items per message of a response streamed by the server
+pub const STREAM_CHUNK_SIZE: u32 = 100;
 
 type KaspadRequestSender = async_channel::Sender<KaspadRequest>;
 type KaspadRequestReceiver = async_channel::Receiver<KaspadRequest>;
@@ -571,6 +602,10 @@ impl Inner {
             let id = u64::from_le_bytes(rand::random::<[u8; 8]>());
             let mut request: KaspadRequest = request.into();
             request.id = id;
+            // Streamed responses are told apart from other responses by their id
+            if self.handle_message_id() && request.is_streamable() {
+                request.chunk_size = STREAM_CHUNK_SIZE;
+            }
 
             trace!("GRPC client: resolver call: {:?}", request);
             if request.payload.is_some() {
@@ -703,9 +738,14 @@ impl Inner {
             trace!("GRPC client: connection monitor task - started");
             let shutdown = self.connector_shutdown.request.listener.clone().fuse();
             pin_mut!(shutdown);
+            // Consecutive failing reconnections are spaced out exponentially, with jitter so that the clients of
+            // a restarting server do not all reconnect at once
+            let connector_timer_interval = Duration::from_millis(self.connector_timer_interval);
+            let policy = BackoffPolicy::new(connector_timer_interval, 2, Duration::from_millis(MAX_RECONNECT_INTERVAL), 0.5, None);
+            let mut backoff = Backoff::new(policy);
+            let mut next_delay = connector_timer_interval;
             loop {
-                let connector_timer_interval = Duration::from_millis(self.connector_timer_interval);
-                let delay = tokio::time::sleep(connector_timer_interval).fuse();
+                let delay = tokio::time::sleep(next_delay).fuse();
                 pin_mut!(delay);
                 select! {
                     _ = shutdown => { break; },
@@ -715,9 +755,12 @@ impl Inner {
                             match self.clone().reconnect(notifier.clone(), subscriptions.clone()).await {
                                 Ok(_) => {
                                     trace!("GRPC client: reconnection to server succeeded");
+                                    backoff.on_success();
+                                    next_delay = connector_timer_interval;
                                 },
                                 Err(err) => {
                                     trace!("GRPC client: reconnection to server failed with error {err:?}");
+                                    next_delay = backoff.on_failure(rand::random()).unwrap_or(connector_timer_interval);
                                 }
                             }
                         }
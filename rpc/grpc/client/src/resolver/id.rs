@@ -18,11 +18,32 @@ use tokio::sync::oneshot;
 struct Pending {
     timestamp: Instant,
     sender: KaspadResponseSender,
+    /// Chunks of a streamed response received so far
+    partial: Option<KaspadResponse>,
 }
 
 impl Pending {
     fn new(sender: KaspadResponseSender) -> Self {
-        Self { timestamp: Instant::now(), sender }
+        Self { timestamp: Instant::now(), sender, partial: None }
+    }
+
+    /// Appends a chunk of a streamed response, resetting the timeout since the server is making progress
+    fn append(&mut self, chunk: KaspadResponse) {
+        self.timestamp = Instant::now();
+        match self.partial {
+            Some(ref mut partial) => partial.merge_chunk(chunk),
+            None => self.partial = Some(chunk),
+        }
+    }
+
+    fn complete(mut self, response: KaspadResponse) -> (KaspadResponseSender, KaspadResponse) {
+        match self.partial.take() {
+            Some(mut partial) => {
+                partial.merge_chunk(response);
+                (self.sender, partial)
+            }
+            None => (self.sender, response),
+        }
     }
 }
 
@@ -49,10 +70,26 @@ impl Resolver for IdResolver {
     }
 
     fn handle_response(&self, response: KaspadResponse) {
-        match self.pending_calls.lock().unwrap().remove(&response.id) {
+        let mut pending_calls = self.pending_calls.lock().unwrap();
+        if response.has_more {
+            match pending_calls.get_mut(&response.id) {
+                Some(pending) => {
+                    trace!("[Resolver] handle_response received a chunk of the streamed response with id {}", response.id);
+                    pending.append(response);
+                }
+                None => {
+                    trace!("[Resolver] handle_response: response chunk id {} has no pending request", response.id);
+                }
+            }
+            return;
+        }
+        let pending = pending_calls.remove(&response.id);
+        drop(pending_calls);
+        match pending {
             Some(pending) => {
                 trace!("[Resolver] handle_response has matching request with id {}", response.id);
-                match pending.sender.send(Ok(response)) {
+                let (sender, response) = pending.complete(response);
+                match sender.send(Ok(response)) {
                     Ok(_) => {}
                     Err(err) => {
                         trace!("[Resolver] handle_response failed to send the response of a pending request: {:?}", err);
@@ -1,5 +1,7 @@
+use super::error::check_limit;
 use crate::protowire;
 use crate::{from, try_from};
+use kaspa_consensus_core::config::constants::decoding::MAX_BLOCK_TRANSACTIONS;
 use kaspa_rpc_core::{RpcError, RpcHash};
 use std::str::FromStr;
 
@@ -35,6 +37,7 @@ from!(item: &kaspa_rpc_core::RpcBlockVerboseData, protowire::RpcBlockVerboseData
 // ----------------------------------------------------------------------------
 
 try_from!(item: &protowire::RpcBlock, kaspa_rpc_core::RpcBlock, {
+    check_limit("block transactions", item.transactions.len(), MAX_BLOCK_TRANSACTIONS)?;
     Self {
         header: item
             .header
@@ -1,5 +1,6 @@
 use crate::from;
 use crate::protowire;
+use kaspa_rpc_core::{RpcError, RpcResult};
 
 // ----------------------------------------------------------------------------
 // rpc_core to protowire
@@ -13,3 +14,11 @@ from!(item: &kaspa_rpc_core::RpcError, protowire::RpcError, { Self { message: it
 // ----------------------------------------------------------------------------
 
 from!(item: &protowire::RpcError, kaspa_rpc_core::RpcError, { kaspa_rpc_core::RpcError::from(item.message.to_string()) });
+
+/// Fails if `len` exceeds the decoding limit `max` of `item`
+pub(crate) fn check_limit(item: &str, len: usize, max: usize) -> RpcResult<()> {
+    if len > max {
+        return Err(RpcError::DecodingLimitExceeded(item.to_string(), len, max));
+    }
+    Ok(())
+}
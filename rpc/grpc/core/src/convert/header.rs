@@ -1,5 +1,7 @@
+use super::error::check_limit;
 use crate::protowire;
 use crate::{from, try_from};
+use kaspa_consensus_core::config::constants::decoding::{MAX_HEADER_PARENTS, MAX_HEADER_PARENT_LEVELS};
 use kaspa_rpc_core::{FromRpcHex, RpcError, RpcHash, RpcResult, ToRpcHex};
 use std::str::FromStr;
 
@@ -31,6 +33,8 @@ from!(item: &Vec<RpcHash>, protowire::RpcBlockLevelParents, { Self { parent_hash
 // ----------------------------------------------------------------------------
 
 try_from!(item: &protowire::RpcBlockHeader, kaspa_rpc_core::RpcHeader, {
+    check_limit("header parent levels", item.parents.len(), MAX_HEADER_PARENT_LEVELS)?;
+    check_limit("header parents", item.parents.iter().map(|level| level.parent_hashes.len()).sum(), MAX_HEADER_PARENTS)?;
     // We re-hash the block to remain as most trustless as possible
     Self::new_finalized(
         item.version.try_into()?,
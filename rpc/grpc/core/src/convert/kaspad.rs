@@ -2,7 +2,7 @@ use crate::protowire::{kaspad_request, KaspadRequest, KaspadResponse};
 
 impl From<kaspad_request::Payload> for KaspadRequest {
     fn from(item: kaspad_request::Payload) -> Self {
-        KaspadRequest { id: 0, payload: Some(item) }
+        KaspadRequest { id: 0, chunk_size: 0, payload: Some(item) }
     }
 }
 
@@ -57,6 +57,29 @@ pub mod kaspad_request_convert {
     impl_into_kaspad_request!(GetServerInfo);
     impl_into_kaspad_request!(GetSyncStatus);
     impl_into_kaspad_request!(GetDaaScoreTimestampEstimate);
+    impl_into_kaspad_request!(GetTransaction);
+    impl_into_kaspad_request!(GetBlockValidationTrace);
+    impl_into_kaspad_request!(GetHealth);
+    impl_into_kaspad_request!(ReloadSettings);
+    impl_into_kaspad_request!(GetCurrentDifficulty);
+    impl_into_kaspad_request!(GetBlockDagTimingInfo);
+    impl_into_kaspad_request!(GetUtxoSetChunk);
+    impl_into_kaspad_request!(GetStateDigests);
+    impl_into_kaspad_request!(GetDagBlocksAround);
+    impl_into_kaspad_request!(GetBlockRelations);
+    impl_into_kaspad_request!(GetTransactionAcceptanceProof);
+    impl_into_kaspad_request!(InvalidateBlock);
+    impl_into_kaspad_request!(ReconsiderBlock);
+    impl_into_kaspad_request!(GetNetworkStats);
+    impl_into_kaspad_request!(GetMempoolFeeHistogram);
+    impl_into_kaspad_request!(GetRelayPolicy);
+    impl_into_kaspad_request!(GetUtxoReturnAddress);
+    impl_into_kaspad_request!(GetSpendingTransaction);
+    impl_into_kaspad_request!(StartBlockProduction);
+    impl_into_kaspad_request!(StopBlockProduction);
+    impl_into_kaspad_request!(ResetLifetimeMetrics);
+    impl_into_kaspad_request!(GetSystemInfo);
+    impl_into_kaspad_request!(CheckTransaction);
 
     impl_into_kaspad_request!(NotifyBlockAdded);
     impl_into_kaspad_request!(NotifyNewBlockTemplate);
@@ -66,6 +89,9 @@ pub mod kaspad_request_convert {
     impl_into_kaspad_request!(NotifyVirtualDaaScoreChanged);
     impl_into_kaspad_request!(NotifyVirtualChainChanged);
     impl_into_kaspad_request!(NotifySinkBlueScoreChanged);
+    impl_into_kaspad_request!(NotifyMempoolTransactionRemoved);
+    impl_into_kaspad_request!(NotifyAcceptedTransactions);
+    impl_into_kaspad_request!(NotifySyncStateChanged);
 
     macro_rules! impl_into_kaspad_request {
         ($name:tt) => {
@@ -92,7 +118,7 @@ pub mod kaspad_request_convert {
 
             impl From<&$core_struct> for KaspadRequest {
                 fn from(item: &$core_struct) -> Self {
-                    Self { id: 0, payload: Some(item.into()) }
+                    Self { id: 0, chunk_size: 0, payload: Some(item.into()) }
                 }
             }
 
@@ -104,7 +130,7 @@ pub mod kaspad_request_convert {
 
             impl From<$core_struct> for KaspadRequest {
                 fn from(item: $core_struct) -> Self {
-                    Self { id: 0, payload: Some((&item).into()) }
+                    Self { id: 0, chunk_size: 0, payload: Some((&item).into()) }
                 }
             }
 
@@ -135,7 +161,7 @@ pub mod kaspad_request_convert {
 
             impl From<$protowire_struct> for KaspadRequest {
                 fn from(item: $protowire_struct) -> Self {
-                    Self { id: 0, payload: Some(kaspad_request::Payload::$variant(item)) }
+                    Self { id: 0, chunk_size: 0, payload: Some(kaspad_request::Payload::$variant(item)) }
                 }
             }
 
@@ -188,6 +214,29 @@ pub mod kaspad_response_convert {
     impl_into_kaspad_response!(GetServerInfo);
     impl_into_kaspad_response!(GetSyncStatus);
     impl_into_kaspad_response!(GetDaaScoreTimestampEstimate);
+    impl_into_kaspad_response!(GetTransaction);
+    impl_into_kaspad_response!(GetBlockValidationTrace);
+    impl_into_kaspad_response!(GetHealth);
+    impl_into_kaspad_response!(ReloadSettings);
+    impl_into_kaspad_response!(GetCurrentDifficulty);
+    impl_into_kaspad_response!(GetBlockDagTimingInfo);
+    impl_into_kaspad_response!(GetUtxoSetChunk);
+    impl_into_kaspad_response!(GetStateDigests);
+    impl_into_kaspad_response!(GetDagBlocksAround);
+    impl_into_kaspad_response!(GetBlockRelations);
+    impl_into_kaspad_response!(GetTransactionAcceptanceProof);
+    impl_into_kaspad_response!(InvalidateBlock);
+    impl_into_kaspad_response!(ReconsiderBlock);
+    impl_into_kaspad_response!(GetNetworkStats);
+    impl_into_kaspad_response!(GetMempoolFeeHistogram);
+    impl_into_kaspad_response!(GetRelayPolicy);
+    impl_into_kaspad_response!(GetUtxoReturnAddress);
+    impl_into_kaspad_response!(GetSpendingTransaction);
+    impl_into_kaspad_response!(StartBlockProduction);
+    impl_into_kaspad_response!(StopBlockProduction);
+    impl_into_kaspad_response!(ResetLifetimeMetrics);
+    impl_into_kaspad_response!(GetSystemInfo);
+    impl_into_kaspad_response!(CheckTransaction);
 
     impl_into_kaspad_notify_response!(NotifyBlockAdded);
     impl_into_kaspad_notify_response!(NotifyNewBlockTemplate);
@@ -197,6 +246,9 @@ pub mod kaspad_response_convert {
     impl_into_kaspad_notify_response!(NotifyVirtualDaaScoreChanged);
     impl_into_kaspad_notify_response!(NotifyVirtualChainChanged);
     impl_into_kaspad_notify_response!(NotifySinkBlueScoreChanged);
+    impl_into_kaspad_notify_response!(NotifyMempoolTransactionRemoved);
+    impl_into_kaspad_notify_response!(NotifyAcceptedTransactions);
+    impl_into_kaspad_notify_response!(NotifySyncStateChanged);
 
     impl_into_kaspad_notify_response!(NotifyUtxosChanged, StopNotifyingUtxosChanged);
     impl_into_kaspad_notify_response!(NotifyPruningPointUtxoSetOverride, StopNotifyingPruningPointUtxoSetOverride);
@@ -242,7 +294,7 @@ pub mod kaspad_response_convert {
 
             impl From<$protowire_struct> for KaspadResponse {
                 fn from(item: $protowire_struct) -> Self {
-                    Self { id: 0, payload: Some(kaspad_response::Payload::$variant(item)) }
+                    Self { id: 0, has_more: false, payload: Some(kaspad_response::Payload::$variant(item)) }
                 }
             }
         };
@@ -263,7 +315,7 @@ pub mod kaspad_response_convert {
 
             impl From<RpcResult<&$core_struct>> for KaspadResponse {
                 fn from(item: RpcResult<&$core_struct>) -> Self {
-                    Self { id: 0, payload: Some(item.into()) }
+                    Self { id: 0, has_more: false, payload: Some(item.into()) }
                 }
             }
 
@@ -275,7 +327,7 @@ pub mod kaspad_response_convert {
 
             impl From<RpcResult<$core_struct>> for KaspadResponse {
                 fn from(item: RpcResult<$core_struct>) -> Self {
-                    Self { id: 0, payload: Some(item.into()) }
+                    Self { id: 0, has_more: false, payload: Some(item.into()) }
                 }
             }
 
@@ -18,6 +18,10 @@ from!(item: &kaspa_rpc_core::RpcMempoolEntryByAddress, protowire::RpcMempoolEntr
     }
 });
 
+from!(item: &kaspa_rpc_core::RpcFeeRateBucket, protowire::RpcFeeRateBucket, {
+    Self { min_fee_rate: item.min_fee_rate, transaction_count: item.transaction_count, total_mass: item.total_mass }
+});
+
 // ----------------------------------------------------------------------------
 // protowire to rpc_core
 // ----------------------------------------------------------------------------
@@ -40,3 +44,7 @@ try_from!(item: &protowire::RpcMempoolEntryByAddress, kaspa_rpc_core::RpcMempool
         item.receiving.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
     )
 });
+
+try_from!(item: &protowire::RpcFeeRateBucket, kaspa_rpc_core::RpcFeeRateBucket, {
+    Self::new(item.min_fee_rate, item.transaction_count, item.total_mass)
+});
@@ -109,14 +109,23 @@ from!(item: &kaspa_rpc_core::SubmitBlockReport, RejectReason, {
         kaspa_rpc_core::SubmitBlockReport::Success => RejectReason::None,
         kaspa_rpc_core::SubmitBlockReport::Reject(kaspa_rpc_core::SubmitBlockRejectReason::BlockInvalid) => RejectReason::BlockInvalid,
         kaspa_rpc_core::SubmitBlockReport::Reject(kaspa_rpc_core::SubmitBlockRejectReason::IsInIBD) => RejectReason::IsInIbd,
+        kaspa_rpc_core::SubmitBlockReport::Reject(kaspa_rpc_core::SubmitBlockRejectReason::StaleTemplate) => RejectReason::StaleTemplate,
     }
 });
 
 from!(item: &kaspa_rpc_core::SubmitBlockRequest, protowire::SubmitBlockRequestMessage, {
-    Self { block: Some((&item.block).into()), allow_non_daa_blocks: item.allow_non_daa_blocks }
+    Self {
+        block: Some((&item.block).into()),
+        allow_non_daa_blocks: item.allow_non_daa_blocks,
+        report_rule_error_code: item.report_rule_error_code,
+    }
 });
 from!(item: RpcResult<&kaspa_rpc_core::SubmitBlockResponse>, protowire::SubmitBlockResponseMessage, {
-    Self { reject_reason: RejectReason::from(&item.report) as i32, error: None }
+    Self {
+        reject_reason: RejectReason::from(&item.report) as i32,
+        rule_error_code: item.rule_error_code.unwrap_or_default() as u32,
+        error: None,
+    }
 });
 
 from!(item: &kaspa_rpc_core::GetBlockTemplateRequest, protowire::GetBlockTemplateRequestMessage, {
@@ -133,7 +142,7 @@ from!(item: &kaspa_rpc_core::GetBlockRequest, protowire::GetBlockRequestMessage,
     Self { hash: item.hash.to_string(), include_transactions: item.include_transactions }
 });
 from!(item: RpcResult<&kaspa_rpc_core::GetBlockResponse>, protowire::GetBlockResponseMessage, {
-    Self { block: Some((&item.block).into()), error: None }
+    Self { block: Some((&item.block).into()), is_archival: item.is_archival, error: None }
 });
 
 from!(item: &kaspa_rpc_core::NotifyBlockAddedRequest, protowire::NotifyBlockAddedRequestMessage, {
@@ -151,6 +160,7 @@ from!(item: RpcResult<&kaspa_rpc_core::GetInfoResponse>, protowire::GetInfoRespo
         is_synced: item.is_synced,
         has_notify_command: item.has_notify_command,
         has_message_id: item.has_message_id,
+        is_clock_drifted: item.is_clock_drifted,
         error: None,
     }
 });
@@ -160,6 +170,21 @@ from!(item: &kaspa_rpc_core::NotifyNewBlockTemplateRequest, protowire::NotifyNew
 });
 from!(RpcResult<&kaspa_rpc_core::NotifyNewBlockTemplateResponse>, protowire::NotifyNewBlockTemplateResponseMessage);
 
+from!(item: &kaspa_rpc_core::NotifyMempoolTransactionRemovedRequest, protowire::NotifyMempoolTransactionRemovedRequestMessage, {
+    Self { command: item.command.into() }
+});
+from!(RpcResult<&kaspa_rpc_core::NotifyMempoolTransactionRemovedResponse>, protowire::NotifyMempoolTransactionRemovedResponseMessage);
+
+from!(item: &kaspa_rpc_core::NotifyAcceptedTransactionsRequest, protowire::NotifyAcceptedTransactionsRequestMessage, {
+    Self { addresses: item.addresses.iter().map(|x| x.into()).collect(), command: item.command.into() }
+});
+from!(RpcResult<&kaspa_rpc_core::NotifyAcceptedTransactionsResponse>, protowire::NotifyAcceptedTransactionsResponseMessage);
+
+from!(item: &kaspa_rpc_core::NotifySyncStateChangedRequest, protowire::NotifySyncStateChangedRequestMessage, {
+    Self { command: item.command.into() }
+});
+from!(RpcResult<&kaspa_rpc_core::NotifySyncStateChangedResponse>, protowire::NotifySyncStateChangedResponseMessage);
+
 // ~~~
 
 from!(&kaspa_rpc_core::GetCurrentNetworkRequest, protowire::GetCurrentNetworkRequestMessage);
@@ -199,9 +224,15 @@ from!(item: RpcResult<&kaspa_rpc_core::GetMempoolEntriesResponse>, protowire::Ge
     Self { entries: item.mempool_entries.iter().map(|x| x.into()).collect(), error: None }
 });
 
-from!(&kaspa_rpc_core::GetConnectedPeerInfoRequest, protowire::GetConnectedPeerInfoRequestMessage);
+from!(item: &kaspa_rpc_core::GetConnectedPeerInfoRequest, protowire::GetConnectedPeerInfoRequestMessage, {
+    Self { include_traffic: item.include_traffic }
+});
 from!(item: RpcResult<&kaspa_rpc_core::GetConnectedPeerInfoResponse>, protowire::GetConnectedPeerInfoResponseMessage, {
-    Self { infos: item.peer_info.iter().map(|x| x.into()).collect(), error: None }
+    Self {
+        infos: item.peer_info.iter().map(|x| x.into()).collect(),
+        peer_traffic: item.peer_traffic.iter().map(|x| x.into()).collect(),
+        error: None,
+    }
 });
 
 from!(item: &kaspa_rpc_core::AddPeerRequest, protowire::AddPeerRequestMessage, {
@@ -288,7 +319,7 @@ from!(item: &kaspa_rpc_core::GetHeadersRequest, protowire::GetHeadersRequestMess
     Self { start_hash: item.start_hash.to_string(), limit: item.limit, is_ascending: item.is_ascending }
 });
 from!(item: RpcResult<&kaspa_rpc_core::GetHeadersResponse>, protowire::GetHeadersResponseMessage, {
-    Self { headers: item.headers.iter().map(|x| x.hash.to_string()).collect(), error: None }
+    Self { headers: item.headers.iter().map(|x| x.into()).collect(), error: None }
 });
 
 from!(item: &kaspa_rpc_core::GetUtxosByAddressesRequest, protowire::GetUtxosByAddressesRequestMessage, {
@@ -362,13 +393,291 @@ from!(item: RpcResult<&kaspa_rpc_core::GetDaaScoreTimestampEstimateResponse>, pr
     Self { timestamps: item.timestamps.clone(), error: None }
 });
 
+from!(item: &kaspa_rpc_core::GetTransactionRequest, protowire::GetTransactionRequestMessage, {
+    Self { transaction_id: item.transaction_id.to_string(), include_proof: item.include_proof }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetTransactionResponse>, protowire::GetTransactionResponseMessage, {
+    Self {
+        transaction: Some((&item.transaction).into()),
+        block_hash: item.block_hash.to_string(),
+        accepting_block_hash: item.accepting_block_hash.to_string(),
+        confirmations: item.confirmations,
+        index_within_block: item.index_within_block,
+        merkle_branch: item.merkle_branch.iter().map(|x| x.to_string()).collect(),
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::GetBlockValidationTraceRequest, protowire::GetBlockValidationTraceRequestMessage, {
+    Self { hash: item.hash.to_string() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetBlockValidationTraceResponse>, protowire::GetBlockValidationTraceResponseMessage, {
+    Self {
+        rules: item
+            .rules
+            .iter()
+            .map(|x| protowire::RpcRuleTrace { rule: x.rule.clone(), duration_micros: x.duration_micros, passed: x.passed })
+            .collect(),
+        total_duration_micros: item.total_duration_micros,
+        error: None,
+    }
+});
+
+from!(&kaspa_rpc_core::GetHealthRequest, protowire::GetHealthRequestMessage);
+from!(item: RpcResult<&kaspa_rpc_core::GetHealthResponse>, protowire::GetHealthResponseMessage, {
+    Self {
+        is_ready: item.is_ready,
+        status: item.status.clone(),
+        services: item.services.iter().map(|x| protowire::RpcServiceHealth { name: x.name.clone(), status: x.status.clone() }).collect(),
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::ReloadSettingsRequest, protowire::ReloadSettingsRequestMessage, {
+    Self {
+        log_level: item.log_level.clone(),
+        outbound_target: item.outbound_target,
+        inbound_limit: item.inbound_limit,
+        mempool_max_transactions: item.mempool_max_transactions,
+        mempool_max_orphans: item.mempool_max_orphans,
+    }
+});
+from!(item: RpcResult<&kaspa_rpc_core::ReloadSettingsResponse>, protowire::ReloadSettingsResponseMessage, {
+    Self {
+        outbound_target: item.outbound_target,
+        inbound_limit: item.inbound_limit,
+        mempool_max_transactions: item.mempool_max_transactions,
+        mempool_max_orphans: item.mempool_max_orphans,
+        error: None,
+    }
+});
+
+from!(&kaspa_rpc_core::GetCurrentDifficultyRequest, protowire::GetCurrentDifficultyRequestMessage);
+from!(item: RpcResult<&kaspa_rpc_core::GetCurrentDifficultyResponse>, protowire::GetCurrentDifficultyResponseMessage, {
+    Self { bits: item.bits, difficulty: item.difficulty, error: None }
+});
+
+from!(item: &kaspa_rpc_core::GetBlockDagTimingInfoRequest, protowire::GetBlockDagTimingInfoRequestMessage, {
+    Self { hash: item.hash.to_string() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetBlockDagTimingInfoResponse>, protowire::GetBlockDagTimingInfoResponseMessage, {
+    Self {
+        timestamp: item.timestamp,
+        daa_score: item.daa_score,
+        bits: item.bits,
+        expected_bits: item.expected_bits,
+        past_median_time: item.past_median_time,
+        window_size: item.window_size,
+        window_sample_rate: item.window_sample_rate,
+        window_lowest_block: item.window_lowest_block.map_or(Default::default(), |x| x.to_string()),
+        window_highest_block: item.window_highest_block.map_or(Default::default(), |x| x.to_string()),
+        window_min_timestamp: item.window_min_timestamp,
+        window_max_timestamp: item.window_max_timestamp,
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::GetUtxoSetChunkRequest, protowire::GetUtxoSetChunkRequestMessage, {
+    Self { cursor: item.cursor.clone().unwrap_or_default() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetUtxoSetChunkResponse>, protowire::GetUtxoSetChunkResponseMessage, {
+    Self {
+        entries: item.entries.iter().map(|x| x.into()).collect(),
+        next_cursor: item.next_cursor.clone().unwrap_or_default(),
+        snapshot_daa_score: item.snapshot_daa_score,
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::GetStateDigestsRequest, protowire::GetStateDigestsRequestMessage, { Self { count: item.count } });
+from!(item: RpcResult<&kaspa_rpc_core::GetStateDigestsResponse>, protowire::GetStateDigestsResponseMessage, {
+    Self {
+        interval: item.interval,
+        digests: item
+            .digests
+            .iter()
+            .map(|x| protowire::RpcStateDigest {
+                hash: x.hash.to_string(),
+                blue_score: x.blue_score,
+                daa_score: x.daa_score,
+                utxo_commitment: x.utxo_commitment.to_string(),
+                digest: x.digest.to_string(),
+            })
+            .collect(),
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::GetDagBlocksAroundRequest, protowire::GetDagBlocksAroundRequestMessage, {
+    Self { hash: item.hash.to_string(), radius: item.radius }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetDagBlocksAroundResponse>, protowire::GetDagBlocksAroundResponseMessage, {
+    Self {
+        blocks: item
+            .blocks
+            .iter()
+            .map(|x| protowire::RpcDagBlock {
+                hash: x.hash.to_string(),
+                distance: x.distance,
+                timestamp: x.timestamp,
+                daa_score: x.daa_score,
+                blue_score: x.blue_score,
+                parent_hashes: x.parent_hashes.iter().map(|x| x.to_string()).collect(),
+                children_hashes: x.children_hashes.iter().map(|x| x.to_string()).collect(),
+                selected_parent_hash: x.selected_parent_hash.to_string(),
+                merge_set_blues_hashes: x.merge_set_blues_hashes.iter().map(|x| x.to_string()).collect(),
+                merge_set_reds_hashes: x.merge_set_reds_hashes.iter().map(|x| x.to_string()).collect(),
+                is_chain_block: x.is_chain_block,
+            })
+            .collect(),
+        truncated: item.truncated,
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::GetBlockRelationsRequest, protowire::GetBlockRelationsRequestMessage, {
+    Self { hash: item.hash.to_string() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetBlockRelationsResponse>, protowire::GetBlockRelationsResponseMessage, {
+    Self {
+        parent_hashes: item.parent_hashes.iter().map(|x| x.to_string()).collect(),
+        children_hashes: item.children_hashes.iter().map(|x| x.to_string()).collect(),
+        selected_parent_hash: item.selected_parent_hash.to_string(),
+        merge_set_blues_hashes: item.merge_set_blues_hashes.iter().map(|x| x.to_string()).collect(),
+        merge_set_reds_hashes: item.merge_set_reds_hashes.iter().map(|x| x.to_string()).collect(),
+        blue_score: item.blue_score,
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::GetTransactionAcceptanceProofRequest, protowire::GetTransactionAcceptanceProofRequestMessage, {
+    Self {
+        transaction_id: item.transaction_id.to_string(),
+        accepting_block_hash: item.accepting_block_hash.map_or(Default::default(), |x| x.to_string()),
+    }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetTransactionAcceptanceProofResponse>, protowire::GetTransactionAcceptanceProofResponseMessage, {
+    Self {
+        proof: Some(protowire::RpcTransactionAcceptanceProof {
+            transaction_id: item.proof.transaction_id.to_string(),
+            accepted_id_index: item.proof.accepted_id_index,
+            accepted_id_merkle_branch: item.proof.accepted_id_merkle_branch.iter().map(|x| x.to_string()).collect(),
+            chain_headers: item.proof.chain_headers.iter().map(|x| x.into()).collect(),
+            parent_headers: item.proof.parent_headers.iter().map(|x| x.into()).collect(),
+        }),
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::InvalidateBlockRequest, protowire::InvalidateBlockRequestMessage, { Self { hash: item.hash.to_string() } });
+from!(_item: RpcResult<&kaspa_rpc_core::InvalidateBlockResponse>, protowire::InvalidateBlockResponseMessage, { Self { error: None } });
+
+from!(item: &kaspa_rpc_core::ReconsiderBlockRequest, protowire::ReconsiderBlockRequestMessage, { Self { hash: item.hash.to_string() } });
+from!(_item: RpcResult<&kaspa_rpc_core::ReconsiderBlockResponse>, protowire::ReconsiderBlockResponseMessage, { Self { error: None } });
+
+from!(&kaspa_rpc_core::GetNetworkStatsRequest, protowire::GetNetworkStatsRequestMessage);
+from!(item: RpcResult<&kaspa_rpc_core::GetNetworkStatsResponse>, protowire::GetNetworkStatsResponseMessage, {
+    Self {
+        window_size: item.window_size,
+        inter_arrival_mean: item.inter_arrival_mean,
+        inter_arrival_p50: item.inter_arrival_p50,
+        inter_arrival_p90: item.inter_arrival_p90,
+        inter_arrival_p99: item.inter_arrival_p99,
+        inter_arrival_max: item.inter_arrival_max,
+        average_parent_count: item.average_parent_count,
+        mergeset_blues: item.mergeset_blues,
+        mergeset_reds: item.mergeset_reds,
+        blue_ratio: item.blue_ratio,
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::GetMempoolFeeHistogramRequest, protowire::GetMempoolFeeHistogramRequestMessage, {
+    Self { bucket_bounds: item.bucket_bounds.clone() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetMempoolFeeHistogramResponse>, protowire::GetMempoolFeeHistogramResponseMessage, {
+    Self { buckets: item.buckets.iter().map(|x| x.into()).collect(), error: None }
+});
+
+from!(&kaspa_rpc_core::GetRelayPolicyRequest, protowire::GetRelayPolicyRequestMessage);
+from!(item: RpcResult<&kaspa_rpc_core::GetRelayPolicyResponse>, protowire::GetRelayPolicyResponseMessage, {
+    Self {
+        minimum_relay_fee_rate: item.minimum_relay_fee_rate,
+        minimum_output_value: item.minimum_output_value,
+        pay_to_pubkey_dust_threshold: item.pay_to_pubkey_dust_threshold,
+        accept_non_standard: item.accept_non_standard,
+        maximum_ancestor_count: item.maximum_ancestor_count,
+        maximum_descendant_count: item.maximum_descendant_count,
+        maximum_chain_mass: item.maximum_chain_mass,
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::GetUtxoReturnAddressRequest, protowire::GetUtxoReturnAddressRequestMessage, {
+    Self { transaction_id: item.transaction_id.to_string() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetUtxoReturnAddressResponse>, protowire::GetUtxoReturnAddressResponseMessage, {
+    Self { return_address: (&item.return_address).into(), error: None }
+});
+
+from!(item: &kaspa_rpc_core::GetSpendingTransactionRequest, protowire::GetSpendingTransactionRequestMessage, {
+    Self { outpoint: Some((&item.outpoint).into()) }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetSpendingTransactionResponse>, protowire::GetSpendingTransactionResponseMessage, {
+    Self { transaction_id: item.transaction_id.to_string(), accepting_block_hash: item.accepting_block_hash.to_string(), error: None }
+});
+
+from!(item: &kaspa_rpc_core::StartBlockProductionRequest, protowire::StartBlockProductionRequestMessage, {
+    Self { pay_address: (&item.pay_address).into(), blocks_per_second: item.blocks_per_second }
+});
+from!(_item: RpcResult<&kaspa_rpc_core::StartBlockProductionResponse>, protowire::StartBlockProductionResponseMessage, { Self { error: None } });
+
+from!(&kaspa_rpc_core::StopBlockProductionRequest, protowire::StopBlockProductionRequestMessage);
+from!(item: RpcResult<&kaspa_rpc_core::StopBlockProductionResponse>, protowire::StopBlockProductionResponseMessage, {
+    Self { was_producing: item.was_producing, blocks_produced: item.blocks_produced, error: None }
+});
+
+from!(&kaspa_rpc_core::ResetLifetimeMetricsRequest, protowire::ResetLifetimeMetricsRequestMessage);
+from!(RpcResult<&kaspa_rpc_core::ResetLifetimeMetricsResponse>, protowire::ResetLifetimeMetricsResponseMessage);
+
+from!(&kaspa_rpc_core::GetSystemInfoRequest, protowire::GetSystemInfoRequestMessage);
+from!(item: RpcResult<&kaspa_rpc_core::GetSystemInfoResponse>, protowire::GetSystemInfoResponseMessage, {
+    Self {
+        version: item.version.clone(),
+        git_hash: item.git_hash.clone().unwrap_or_default(),
+        cpu_cores: item.cpu_cores,
+        total_memory: item.total_memory,
+        system_id: item.system_id.clone(),
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::CheckTransactionRequest, protowire::CheckTransactionRequestMessage, {
+    Self { transaction: Some((&item.transaction).into()) }
+});
+from!(item: RpcResult<&kaspa_rpc_core::CheckTransactionResponse>, protowire::CheckTransactionResponseMessage, {
+    Self {
+        transaction_id: item.transaction_id.to_string(),
+        mass: item.mass,
+        fee: item.fee.unwrap_or_default(),
+        has_fee: item.fee.is_some(),
+        fee_rate: item.fee_rate.unwrap_or_default(),
+        rule_error: item.rule_error.clone().unwrap_or_default(),
+        error: None,
+    }
+});
+
 from!(&kaspa_rpc_core::PingRequest, protowire::PingRequestMessage);
 from!(RpcResult<&kaspa_rpc_core::PingResponse>, protowire::PingResponseMessage);
 
 from!(item: &kaspa_rpc_core::GetMetricsRequest, protowire::GetMetricsRequestMessage, {
     Self {
         process_metrics: item.process_metrics,
-        consensus_metrics: item.consensus_metrics
+        consensus_metrics: item.consensus_metrics,
+        lifetime_metrics: item.lifetime_metrics,
+        method_metrics: item.method_metrics,
+        p2p_message_metrics: item.p2p_message_metrics,
+        listener_metrics: item.listener_metrics,
     }
 });
 from!(item: RpcResult<&kaspa_rpc_core::GetMetricsResponse>, protowire::GetMetricsResponseMessage, {
@@ -376,6 +685,11 @@ from!(item: RpcResult<&kaspa_rpc_core::GetMetricsResponse>, protowire::GetMetric
         server_time: item.server_time,
         process_metrics: item.process_metrics.as_ref().map(|x| x.into()),
         consensus_metrics: item.consensus_metrics.as_ref().map(|x| x.into()),
+        lifetime_metrics: item.lifetime_metrics.as_ref().map(|x| x.into()),
+        method_metrics: item.method_metrics.iter().map(|x| x.into()).collect(),
+        p2p_message_metrics: item.p2p_message_metrics.iter().map(|x| x.into()).collect(),
+        extended_consensus_metrics: item.extended_consensus_metrics.as_ref().map(|x| x.into()),
+        listener_metrics: item.listener_metrics.iter().map(|x| x.into()).collect(),
         error: None,
     }
 });
@@ -451,6 +765,7 @@ from!(item: RejectReason, kaspa_rpc_core::SubmitBlockReport, {
         RejectReason::None => kaspa_rpc_core::SubmitBlockReport::Success,
         RejectReason::BlockInvalid => kaspa_rpc_core::SubmitBlockReport::Reject(kaspa_rpc_core::SubmitBlockRejectReason::BlockInvalid),
         RejectReason::IsInIbd => kaspa_rpc_core::SubmitBlockReport::Reject(kaspa_rpc_core::SubmitBlockRejectReason::IsInIBD),
+        RejectReason::StaleTemplate => kaspa_rpc_core::SubmitBlockReport::Reject(kaspa_rpc_core::SubmitBlockRejectReason::StaleTemplate),
     }
 });
 
@@ -462,10 +777,14 @@ try_from!(item: &protowire::SubmitBlockRequestMessage, kaspa_rpc_core::SubmitBlo
             .ok_or_else(|| RpcError::MissingRpcFieldError("SubmitBlockRequestMessage".to_string(), "block".to_string()))?
             .try_into()?,
         allow_non_daa_blocks: item.allow_non_daa_blocks,
+        report_rule_error_code: item.report_rule_error_code,
     }
 });
 try_from!(item: &protowire::SubmitBlockResponseMessage, RpcResult<kaspa_rpc_core::SubmitBlockResponse>, {
-    Self { report: RejectReason::try_from(item.reject_reason).map_err(|_| RpcError::PrimitiveToEnumConversionError)?.into() }
+    Self {
+        report: RejectReason::try_from(item.reject_reason).map_err(|_| RpcError::PrimitiveToEnumConversionError)?.into(),
+        rule_error_code: (item.rule_error_code != 0).then_some(item.rule_error_code as u16),
+    }
 });
 
 try_from!(item: &protowire::GetBlockTemplateRequestMessage, kaspa_rpc_core::GetBlockTemplateRequest, {
@@ -492,6 +811,7 @@ try_from!(item: &protowire::GetBlockResponseMessage, RpcResult<kaspa_rpc_core::G
             .as_ref()
             .ok_or_else(|| RpcError::MissingRpcFieldError("GetBlockResponseMessage".to_string(), "block".to_string()))?
             .try_into()?,
+        is_archival: item.is_archival,
     }
 });
 
@@ -510,6 +830,7 @@ try_from!(item: &protowire::GetInfoResponseMessage, RpcResult<kaspa_rpc_core::Ge
         is_synced: item.is_synced,
         has_notify_command: item.has_notify_command,
         has_message_id: item.has_message_id,
+        is_clock_drifted: item.is_clock_drifted,
     }
 });
 
@@ -518,6 +839,27 @@ try_from!(item: &protowire::NotifyNewBlockTemplateRequestMessage, kaspa_rpc_core
 });
 try_from!(&protowire::NotifyNewBlockTemplateResponseMessage, RpcResult<kaspa_rpc_core::NotifyNewBlockTemplateResponse>);
 
+try_from!(item: &protowire::NotifyMempoolTransactionRemovedRequestMessage, kaspa_rpc_core::NotifyMempoolTransactionRemovedRequest, {
+    Self { command: item.command.into() }
+});
+try_from!(
+    &protowire::NotifyMempoolTransactionRemovedResponseMessage,
+    RpcResult<kaspa_rpc_core::NotifyMempoolTransactionRemovedResponse>
+);
+
+try_from!(item: &protowire::NotifyAcceptedTransactionsRequestMessage, kaspa_rpc_core::NotifyAcceptedTransactionsRequest, {
+    Self {
+        addresses: item.addresses.iter().map(|x| x.as_str().try_into()).collect::<Result<Vec<_>, _>>()?,
+        command: item.command.into(),
+    }
+});
+try_from!(&protowire::NotifyAcceptedTransactionsResponseMessage, RpcResult<kaspa_rpc_core::NotifyAcceptedTransactionsResponse>);
+
+try_from!(item: &protowire::NotifySyncStateChangedRequestMessage, kaspa_rpc_core::NotifySyncStateChangedRequest, {
+    Self { command: item.command.into() }
+});
+try_from!(&protowire::NotifySyncStateChangedResponseMessage, RpcResult<kaspa_rpc_core::NotifySyncStateChangedResponse>);
+
 // ~~~
 
 try_from!(&protowire::GetCurrentNetworkRequestMessage, kaspa_rpc_core::GetCurrentNetworkRequest);
@@ -565,9 +907,14 @@ try_from!(item: &protowire::GetMempoolEntriesResponseMessage, RpcResult<kaspa_rp
     Self { mempool_entries: item.entries.iter().map(kaspa_rpc_core::RpcMempoolEntry::try_from).collect::<Result<Vec<_>, _>>()? }
 });
 
-try_from!(&protowire::GetConnectedPeerInfoRequestMessage, kaspa_rpc_core::GetConnectedPeerInfoRequest);
+try_from!(item: &protowire::GetConnectedPeerInfoRequestMessage, kaspa_rpc_core::GetConnectedPeerInfoRequest, {
+    Self { include_traffic: item.include_traffic }
+});
 try_from!(item: &protowire::GetConnectedPeerInfoResponseMessage, RpcResult<kaspa_rpc_core::GetConnectedPeerInfoResponse>, {
-    Self { peer_info: item.infos.iter().map(kaspa_rpc_core::RpcPeerInfo::try_from).collect::<Result<Vec<_>, _>>()? }
+    Self {
+        peer_info: item.infos.iter().map(kaspa_rpc_core::RpcPeerInfo::try_from).collect::<Result<Vec<_>, _>>()?,
+        peer_traffic: item.peer_traffic.iter().map(kaspa_rpc_core::RpcPeerTraffic::try_from).collect::<Result<Vec<_>, _>>()?,
+    }
 });
 
 try_from!(item: &protowire::AddPeerRequestMessage, kaspa_rpc_core::AddPeerRequest, {
@@ -658,8 +1005,7 @@ try_from!(item: &protowire::GetHeadersRequestMessage, kaspa_rpc_core::GetHeaders
     Self { start_hash: RpcHash::from_str(&item.start_hash)?, limit: item.limit, is_ascending: item.is_ascending }
 });
 try_from!(item: &protowire::GetHeadersResponseMessage, RpcResult<kaspa_rpc_core::GetHeadersResponse>, {
-    // TODO
-    Self { headers: vec![] }
+    Self { headers: item.headers.iter().map(|x| x.try_into()).collect::<RpcResult<Vec<_>>>()? }
 });
 
 try_from!(item: &protowire::GetUtxosByAddressesRequestMessage, kaspa_rpc_core::GetUtxosByAddressesRequest, {
@@ -733,17 +1079,345 @@ try_from!(item: &protowire::GetDaaScoreTimestampEstimateResponseMessage, RpcResu
     Self { timestamps: item.timestamps.clone() }
 });
 
+try_from!(item: &protowire::GetTransactionRequestMessage, kaspa_rpc_core::GetTransactionRequest, {
+    Self { transaction_id: kaspa_rpc_core::RpcTransactionId::from_str(&item.transaction_id)?, include_proof: item.include_proof }
+});
+try_from!(item: &protowire::GetTransactionResponseMessage, RpcResult<kaspa_rpc_core::GetTransactionResponse>, {
+    Self {
+        transaction: item
+            .transaction
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("GetTransactionResponseMessage".to_string(), "transaction".to_string()))?
+            .try_into()?,
+        block_hash: RpcHash::from_str(&item.block_hash)?,
+        accepting_block_hash: RpcHash::from_str(&item.accepting_block_hash)?,
+        confirmations: item.confirmations,
+        index_within_block: item.index_within_block,
+        merkle_branch: item.merkle_branch.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>()?,
+    }
+});
+
+try_from!(item: &protowire::GetBlockValidationTraceRequestMessage, kaspa_rpc_core::GetBlockValidationTraceRequest, {
+    Self { hash: RpcHash::from_str(&item.hash)? }
+});
+try_from!(item: &protowire::GetBlockValidationTraceResponseMessage, RpcResult<kaspa_rpc_core::GetBlockValidationTraceResponse>, {
+    Self {
+        rules: item
+            .rules
+            .iter()
+            .map(|x| kaspa_rpc_core::RpcRuleTrace { rule: x.rule.clone(), duration_micros: x.duration_micros, passed: x.passed })
+            .collect(),
+        total_duration_micros: item.total_duration_micros,
+    }
+});
+
+try_from!(&protowire::GetHealthRequestMessage, kaspa_rpc_core::GetHealthRequest);
+try_from!(item: &protowire::GetHealthResponseMessage, RpcResult<kaspa_rpc_core::GetHealthResponse>, {
+    Self {
+        is_ready: item.is_ready,
+        status: item.status.clone(),
+        services: item.services.iter().map(|x| kaspa_rpc_core::RpcServiceHealth { name: x.name.clone(), status: x.status.clone() }).collect(),
+    }
+});
+
+try_from!(item: &protowire::ReloadSettingsRequestMessage, kaspa_rpc_core::ReloadSettingsRequest, {
+    Self {
+        log_level: item.log_level.clone(),
+        outbound_target: item.outbound_target,
+        inbound_limit: item.inbound_limit,
+        mempool_max_transactions: item.mempool_max_transactions,
+        mempool_max_orphans: item.mempool_max_orphans,
+    }
+});
+try_from!(item: &protowire::ReloadSettingsResponseMessage, RpcResult<kaspa_rpc_core::ReloadSettingsResponse>, {
+    Self {
+        outbound_target: item.outbound_target,
+        inbound_limit: item.inbound_limit,
+        mempool_max_transactions: item.mempool_max_transactions,
+        mempool_max_orphans: item.mempool_max_orphans,
+    }
+});
+
+try_from!(&protowire::GetCurrentDifficultyRequestMessage, kaspa_rpc_core::GetCurrentDifficultyRequest);
+try_from!(item: &protowire::GetCurrentDifficultyResponseMessage, RpcResult<kaspa_rpc_core::GetCurrentDifficultyResponse>, {
+    Self { bits: item.bits, difficulty: item.difficulty }
+});
+
+try_from!(item: &protowire::GetBlockDagTimingInfoRequestMessage, kaspa_rpc_core::GetBlockDagTimingInfoRequest, {
+    Self { hash: RpcHash::from_str(&item.hash)? }
+});
+try_from!(item: &protowire::GetBlockDagTimingInfoResponseMessage, RpcResult<kaspa_rpc_core::GetBlockDagTimingInfoResponse>, {
+    Self {
+        timestamp: item.timestamp,
+        daa_score: item.daa_score,
+        bits: item.bits,
+        expected_bits: item.expected_bits,
+        past_median_time: item.past_median_time,
+        window_size: item.window_size,
+        window_sample_rate: item.window_sample_rate,
+        window_lowest_block: if item.window_lowest_block.is_empty() {
+            None
+        } else {
+            Some(RpcHash::from_str(&item.window_lowest_block)?)
+        },
+        window_highest_block: if item.window_highest_block.is_empty() {
+            None
+        } else {
+            Some(RpcHash::from_str(&item.window_highest_block)?)
+        },
+        window_min_timestamp: item.window_min_timestamp,
+        window_max_timestamp: item.window_max_timestamp,
+    }
+});
+
+try_from!(item: &protowire::GetUtxoSetChunkRequestMessage, kaspa_rpc_core::GetUtxoSetChunkRequest, {
+    Self { cursor: if item.cursor.is_empty() { None } else { Some(item.cursor.clone()) } }
+});
+try_from!(item: &protowire::GetUtxoSetChunkResponseMessage, RpcResult<kaspa_rpc_core::GetUtxoSetChunkResponse>, {
+    Self {
+        entries: item.entries.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+        next_cursor: if item.next_cursor.is_empty() { None } else { Some(item.next_cursor.clone()) },
+        snapshot_daa_score: item.snapshot_daa_score,
+    }
+});
+
+try_from!(item: &protowire::GetStateDigestsRequestMessage, kaspa_rpc_core::GetStateDigestsRequest, { Self { count: item.count } });
+try_from!(item: &protowire::GetStateDigestsResponseMessage, RpcResult<kaspa_rpc_core::GetStateDigestsResponse>, {
+    Self {
+        interval: item.interval,
+        digests: item
+            .digests
+            .iter()
+            .map(|x| {
+                Ok(kaspa_rpc_core::RpcStateDigest {
+                    hash: RpcHash::from_str(&x.hash)?,
+                    blue_score: x.blue_score,
+                    daa_score: x.daa_score,
+                    utxo_commitment: RpcHash::from_str(&x.utxo_commitment)?,
+                    digest: RpcHash::from_str(&x.digest)?,
+                })
+            })
+            .collect::<RpcResult<Vec<_>>>()?,
+    }
+});
+
+try_from!(item: &protowire::GetDagBlocksAroundRequestMessage, kaspa_rpc_core::GetDagBlocksAroundRequest, {
+    Self { hash: RpcHash::from_str(&item.hash)?, radius: item.radius }
+});
+try_from!(item: &protowire::GetDagBlocksAroundResponseMessage, RpcResult<kaspa_rpc_core::GetDagBlocksAroundResponse>, {
+    let hashes = |hashes: &[String]| hashes.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>();
+    Self {
+        blocks: item
+            .blocks
+            .iter()
+            .map(|x| {
+                Ok(kaspa_rpc_core::RpcDagBlock {
+                    hash: RpcHash::from_str(&x.hash)?,
+                    distance: x.distance,
+                    timestamp: x.timestamp,
+                    daa_score: x.daa_score,
+                    blue_score: x.blue_score,
+                    parent_hashes: hashes(&x.parent_hashes)?,
+                    children_hashes: hashes(&x.children_hashes)?,
+                    selected_parent_hash: RpcHash::from_str(&x.selected_parent_hash)?,
+                    merge_set_blues_hashes: hashes(&x.merge_set_blues_hashes)?,
+                    merge_set_reds_hashes: hashes(&x.merge_set_reds_hashes)?,
+                    is_chain_block: x.is_chain_block,
+                })
+            })
+            .collect::<RpcResult<Vec<_>>>()?,
+        truncated: item.truncated,
+    }
+});
+
+try_from!(item: &protowire::GetBlockRelationsRequestMessage, kaspa_rpc_core::GetBlockRelationsRequest, {
+    Self { hash: RpcHash::from_str(&item.hash)? }
+});
+try_from!(item: &protowire::GetBlockRelationsResponseMessage, RpcResult<kaspa_rpc_core::GetBlockRelationsResponse>, {
+    let hashes = |hashes: &[String]| hashes.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>();
+    Self {
+        parent_hashes: hashes(&item.parent_hashes)?,
+        children_hashes: hashes(&item.children_hashes)?,
+        selected_parent_hash: RpcHash::from_str(&item.selected_parent_hash)?,
+        merge_set_blues_hashes: hashes(&item.merge_set_blues_hashes)?,
+        merge_set_reds_hashes: hashes(&item.merge_set_reds_hashes)?,
+        blue_score: item.blue_score,
+    }
+});
+
+try_from!(item: &protowire::GetTransactionAcceptanceProofRequestMessage, kaspa_rpc_core::GetTransactionAcceptanceProofRequest, {
+    Self {
+        transaction_id: RpcHash::from_str(&item.transaction_id)?,
+        accepting_block_hash: if item.accepting_block_hash.is_empty() {
+            None
+        } else {
+            Some(RpcHash::from_str(&item.accepting_block_hash)?)
+        },
+    }
+});
+try_from!(item: &protowire::GetTransactionAcceptanceProofResponseMessage, RpcResult<kaspa_rpc_core::GetTransactionAcceptanceProofResponse>, {
+    let proof = item
+        .proof
+        .as_ref()
+        .ok_or_else(|| RpcError::MissingRpcFieldError("GetTransactionAcceptanceProofResponseMessage".to_string(), "proof".to_string()))?;
+    Self {
+        proof: kaspa_rpc_core::RpcTransactionAcceptanceProof {
+            transaction_id: RpcHash::from_str(&proof.transaction_id)?,
+            accepted_id_index: proof.accepted_id_index,
+            accepted_id_merkle_branch: proof
+                .accepted_id_merkle_branch
+                .iter()
+                .map(|x| RpcHash::from_str(x))
+                .collect::<Result<Vec<_>, _>>()?,
+            chain_headers: proof.chain_headers.iter().map(|x| x.try_into()).collect::<RpcResult<Vec<_>>>()?,
+            parent_headers: proof.parent_headers.iter().map(|x| x.try_into()).collect::<RpcResult<Vec<_>>>()?,
+        },
+    }
+});
+
+try_from!(item: &protowire::InvalidateBlockRequestMessage, kaspa_rpc_core::InvalidateBlockRequest, {
+    Self { hash: RpcHash::from_str(&item.hash)? }
+});
+try_from!(&protowire::InvalidateBlockResponseMessage, RpcResult<kaspa_rpc_core::InvalidateBlockResponse>);
+
+try_from!(item: &protowire::ReconsiderBlockRequestMessage, kaspa_rpc_core::ReconsiderBlockRequest, {
+    Self { hash: RpcHash::from_str(&item.hash)? }
+});
+try_from!(&protowire::ReconsiderBlockResponseMessage, RpcResult<kaspa_rpc_core::ReconsiderBlockResponse>);
+
+try_from!(&protowire::GetNetworkStatsRequestMessage, kaspa_rpc_core::GetNetworkStatsRequest);
+try_from!(item: &protowire::GetNetworkStatsResponseMessage, RpcResult<kaspa_rpc_core::GetNetworkStatsResponse>, {
+    Self {
+        window_size: item.window_size,
+        inter_arrival_mean: item.inter_arrival_mean,
+        inter_arrival_p50: item.inter_arrival_p50,
+        inter_arrival_p90: item.inter_arrival_p90,
+        inter_arrival_p99: item.inter_arrival_p99,
+        inter_arrival_max: item.inter_arrival_max,
+        average_parent_count: item.average_parent_count,
+        mergeset_blues: item.mergeset_blues,
+        mergeset_reds: item.mergeset_reds,
+        blue_ratio: item.blue_ratio,
+    }
+});
+
+try_from!(item: &protowire::GetMempoolFeeHistogramRequestMessage, kaspa_rpc_core::GetMempoolFeeHistogramRequest, {
+    Self { bucket_bounds: item.bucket_bounds.clone() }
+});
+try_from!(item: &protowire::GetMempoolFeeHistogramResponseMessage, RpcResult<kaspa_rpc_core::GetMempoolFeeHistogramResponse>, {
+    Self { buckets: item.buckets.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()? }
+});
+
+try_from!(&protowire::GetRelayPolicyRequestMessage, kaspa_rpc_core::GetRelayPolicyRequest);
+try_from!(item: &protowire::GetRelayPolicyResponseMessage, RpcResult<kaspa_rpc_core::GetRelayPolicyResponse>, {
+    Self {
+        minimum_relay_fee_rate: item.minimum_relay_fee_rate,
+        minimum_output_value: item.minimum_output_value,
+        pay_to_pubkey_dust_threshold: item.pay_to_pubkey_dust_threshold,
+        accept_non_standard: item.accept_non_standard,
+        maximum_ancestor_count: item.maximum_ancestor_count,
+        maximum_descendant_count: item.maximum_descendant_count,
+        maximum_chain_mass: item.maximum_chain_mass,
+    }
+});
+
+try_from!(item: &protowire::GetUtxoReturnAddressRequestMessage, kaspa_rpc_core::GetUtxoReturnAddressRequest, {
+    Self { transaction_id: kaspa_rpc_core::RpcTransactionId::from_str(&item.transaction_id)? }
+});
+try_from!(item: &protowire::GetUtxoReturnAddressResponseMessage, RpcResult<kaspa_rpc_core::GetUtxoReturnAddressResponse>, {
+    Self { return_address: item.return_address.as_str().try_into()? }
+});
+
+try_from!(item: &protowire::GetSpendingTransactionRequestMessage, kaspa_rpc_core::GetSpendingTransactionRequest, {
+    Self {
+        outpoint: item
+            .outpoint
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("GetSpendingTransactionRequestMessage".to_string(), "outpoint".to_string()))?
+            .try_into()?,
+    }
+});
+try_from!(item: &protowire::GetSpendingTransactionResponseMessage, RpcResult<kaspa_rpc_core::GetSpendingTransactionResponse>, {
+    Self {
+        transaction_id: kaspa_rpc_core::RpcTransactionId::from_str(&item.transaction_id)?,
+        accepting_block_hash: RpcHash::from_str(&item.accepting_block_hash)?,
+    }
+});
+
+try_from!(item: &protowire::StartBlockProductionRequestMessage, kaspa_rpc_core::StartBlockProductionRequest, {
+    Self { pay_address: item.pay_address.clone().try_into()?, blocks_per_second: item.blocks_per_second }
+});
+try_from!(&protowire::StartBlockProductionResponseMessage, RpcResult<kaspa_rpc_core::StartBlockProductionResponse>);
+
+try_from!(&protowire::StopBlockProductionRequestMessage, kaspa_rpc_core::StopBlockProductionRequest);
+try_from!(item: &protowire::StopBlockProductionResponseMessage, RpcResult<kaspa_rpc_core::StopBlockProductionResponse>, {
+    Self { was_producing: item.was_producing, blocks_produced: item.blocks_produced }
+});
+
+try_from!(&protowire::ResetLifetimeMetricsRequestMessage, kaspa_rpc_core::ResetLifetimeMetricsRequest);
+try_from!(&protowire::ResetLifetimeMetricsResponseMessage, RpcResult<kaspa_rpc_core::ResetLifetimeMetricsResponse>);
+
+try_from!(&protowire::GetSystemInfoRequestMessage, kaspa_rpc_core::GetSystemInfoRequest);
+try_from!(item: &protowire::GetSystemInfoResponseMessage, RpcResult<kaspa_rpc_core::GetSystemInfoResponse>, {
+    Self {
+        version: item.version.clone(),
+        git_hash: if item.git_hash.is_empty() { None } else { Some(item.git_hash.clone()) },
+        cpu_cores: item.cpu_cores,
+        total_memory: item.total_memory,
+        system_id: item.system_id.clone(),
+    }
+});
+
+try_from!(item: &protowire::CheckTransactionRequestMessage, kaspa_rpc_core::CheckTransactionRequest, {
+    Self {
+        transaction: item
+            .transaction
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("CheckTransactionRequestMessage".to_string(), "transaction".to_string()))?
+            .try_into()?,
+    }
+});
+try_from!(item: &protowire::CheckTransactionResponseMessage, RpcResult<kaspa_rpc_core::CheckTransactionResponse>, {
+    Self {
+        transaction_id: RpcHash::from_str(&item.transaction_id)?,
+        mass: item.mass,
+        fee: item.has_fee.then_some(item.fee),
+        fee_rate: item.has_fee.then_some(item.fee_rate),
+        rule_error: if item.rule_error.is_empty() { None } else { Some(item.rule_error.clone()) },
+    }
+});
+
 try_from!(&protowire::PingRequestMessage, kaspa_rpc_core::PingRequest);
 try_from!(&protowire::PingResponseMessage, RpcResult<kaspa_rpc_core::PingResponse>);
 
 try_from!(item: &protowire::GetMetricsRequestMessage, kaspa_rpc_core::GetMetricsRequest, {
-    Self { process_metrics: item.process_metrics, consensus_metrics: item.consensus_metrics }
+    Self {
+        process_metrics: item.process_metrics,
+        consensus_metrics: item.consensus_metrics,
+        lifetime_metrics: item.lifetime_metrics,
+        method_metrics: item.method_metrics,
+        p2p_message_metrics: item.p2p_message_metrics,
+        listener_metrics: item.listener_metrics,
+    }
 });
 try_from!(item: &protowire::GetMetricsResponseMessage, RpcResult<kaspa_rpc_core::GetMetricsResponse>, {
     Self {
         server_time: item.server_time,
         process_metrics: item.process_metrics.as_ref().map(|x| x.try_into()).transpose()?,
         consensus_metrics: item.consensus_metrics.as_ref().map(|x| x.try_into()).transpose()?,
+        lifetime_metrics: item.lifetime_metrics.as_ref().map(|x| x.try_into()).transpose()?,
+        method_metrics: item.method_metrics.iter().map(kaspa_rpc_core::RpcMethodMetrics::try_from).collect::<Result<Vec<_>, _>>()?,
+        p2p_message_metrics: item
+            .p2p_message_metrics
+            .iter()
+            .map(kaspa_rpc_core::P2pMessageMetrics::try_from)
+            .collect::<Result<Vec<_>, _>>()?,
+        extended_consensus_metrics: item.extended_consensus_metrics.as_ref().map(|x| x.try_into()).transpose()?,
+        listener_metrics: item
+            .listener_metrics
+            .iter()
+            .map(kaspa_rpc_core::RpcListenerMetrics::try_from)
+            .collect::<Result<Vec<_>, _>>()?,
     }
 });
 
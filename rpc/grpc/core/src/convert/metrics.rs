@@ -38,6 +38,83 @@ from!(item: &kaspa_rpc_core::ConsensusMetrics, protowire::ConsensusMetrics, {
     }
 });
 
+from!(item: &kaspa_rpc_core::ExtendedConsensusMetrics, protowire::ExtendedConsensusMetrics, {
+    Self {
+        tip_hashes_count: item.tip_hashes_count,
+        tx_mempool_first_seen_count: item.tx_mempool_first_seen_count,
+        tx_block_first_seen_count: item.tx_block_first_seen_count,
+        tx_inclusion_latency_p50: item.tx_inclusion_latency_p50,
+        tx_inclusion_latency_p90: item.tx_inclusion_latency_p90,
+        tx_inclusion_latency_p99: item.tx_inclusion_latency_p99,
+        tx_propagation_latency_p50: item.tx_propagation_latency_p50,
+        tx_propagation_latency_p90: item.tx_propagation_latency_p90,
+        tx_propagation_latency_p99: item.tx_propagation_latency_p99,
+        storage_write_amplification: item.storage_write_amplification,
+        storage_pending_compaction_bytes: item.storage_pending_compaction_bytes,
+        storage_running_compactions: item.storage_running_compactions,
+        storage_stall_seconds: item.storage_stall_seconds,
+        storage_delayed_write_rate: item.storage_delayed_write_rate,
+        storage_write_stopped: item.storage_write_stopped,
+        rpc_blocks_submitted: item.rpc_blocks_submitted,
+        relay_blocks_submitted: item.relay_blocks_submitted,
+        ibd_blocks_submitted: item.ibd_blocks_submitted,
+        integrity_check_rounds: item.integrity_check_rounds,
+        integrity_checked_blocks: item.integrity_checked_blocks,
+        integrity_violations: item.integrity_violations,
+        relay_block_inv_hits: item.relay_block_inv_hits,
+        relay_block_inv_misses: item.relay_block_inv_misses,
+        relay_transaction_inv_hits: item.relay_transaction_inv_hits,
+        relay_transaction_inv_misses: item.relay_transaction_inv_misses,
+    }
+});
+
+from!(item: &kaspa_rpc_core::LifetimeRunRecord, protowire::LifetimeRunRecord, {
+    Self { start_time: item.start_time, uptime_seconds: item.uptime_seconds }
+});
+
+from!(item: &kaspa_rpc_core::LifetimeMetrics, protowire::LifetimeMetrics, {
+    Self {
+        since: item.since,
+        blocks_processed: item.blocks_processed,
+        txs_processed: item.txs_processed,
+        uptime_seconds: item.uptime_seconds,
+        starts: item.starts,
+        recent_runs: item.recent_runs.iter().map(|x| x.into()).collect(),
+    }
+});
+
+from!(item: &kaspa_rpc_core::RpcMethodMetrics, protowire::RpcMethodMetrics, {
+    Self {
+        method: item.method.clone(),
+        calls: item.calls,
+        errors: item.errors,
+        total_latency_micros: item.total_latency_micros,
+        latency_buckets: item.latency_buckets.clone(),
+    }
+});
+
+from!(item: &kaspa_rpc_core::P2pMessageMetrics, protowire::P2pMessageMetrics, {
+    Self {
+        message_type: item.message_type.clone(),
+        sent_messages: item.sent_messages,
+        sent_bytes: item.sent_bytes,
+        received_messages: item.received_messages,
+        received_bytes: item.received_bytes,
+    }
+});
+
+from!(item: &kaspa_rpc_core::RpcListenerMetrics, protowire::RpcListenerMetrics, {
+    Self {
+        notifier: item.notifier.clone(),
+        listener_id: item.listener_id,
+        queue_len: item.queue_len,
+        queue_capacity: item.queue_capacity,
+        dropped_notifications: item.dropped_notifications,
+        lagging: item.lagging,
+        overflowed: item.overflowed,
+    }
+});
+
 // ----------------------------------------------------------------------------
 // protowire to rpc_core
 // ----------------------------------------------------------------------------
@@ -73,3 +150,80 @@ try_from!(item: &protowire::ConsensusMetrics, kaspa_rpc_core::ConsensusMetrics,
         mass_counts: item.mass_counts,
     }
 });
+
+try_from!(item: &protowire::ExtendedConsensusMetrics, kaspa_rpc_core::ExtendedConsensusMetrics, {
+    Self {
+        tip_hashes_count: item.tip_hashes_count,
+        tx_mempool_first_seen_count: item.tx_mempool_first_seen_count,
+        tx_block_first_seen_count: item.tx_block_first_seen_count,
+        tx_inclusion_latency_p50: item.tx_inclusion_latency_p50,
+        tx_inclusion_latency_p90: item.tx_inclusion_latency_p90,
+        tx_inclusion_latency_p99: item.tx_inclusion_latency_p99,
+        tx_propagation_latency_p50: item.tx_propagation_latency_p50,
+        tx_propagation_latency_p90: item.tx_propagation_latency_p90,
+        tx_propagation_latency_p99: item.tx_propagation_latency_p99,
+        storage_write_amplification: item.storage_write_amplification,
+        storage_pending_compaction_bytes: item.storage_pending_compaction_bytes,
+        storage_running_compactions: item.storage_running_compactions,
+        storage_stall_seconds: item.storage_stall_seconds,
+        storage_delayed_write_rate: item.storage_delayed_write_rate,
+        storage_write_stopped: item.storage_write_stopped,
+        rpc_blocks_submitted: item.rpc_blocks_submitted,
+        relay_blocks_submitted: item.relay_blocks_submitted,
+        ibd_blocks_submitted: item.ibd_blocks_submitted,
+        integrity_check_rounds: item.integrity_check_rounds,
+        integrity_checked_blocks: item.integrity_checked_blocks,
+        integrity_violations: item.integrity_violations,
+        relay_block_inv_hits: item.relay_block_inv_hits,
+        relay_block_inv_misses: item.relay_block_inv_misses,
+        relay_transaction_inv_hits: item.relay_transaction_inv_hits,
+        relay_transaction_inv_misses: item.relay_transaction_inv_misses,
+    }
+});
+
+try_from!(item: &protowire::LifetimeRunRecord, kaspa_rpc_core::LifetimeRunRecord, {
+    Self { start_time: item.start_time, uptime_seconds: item.uptime_seconds }
+});
+
+try_from!(item: &protowire::LifetimeMetrics, kaspa_rpc_core::LifetimeMetrics, {
+    Self {
+        since: item.since,
+        blocks_processed: item.blocks_processed,
+        txs_processed: item.txs_processed,
+        uptime_seconds: item.uptime_seconds,
+        starts: item.starts,
+        recent_runs: item.recent_runs.iter().map(kaspa_rpc_core::LifetimeRunRecord::try_from).collect::<Result<Vec<_>, _>>()?,
+    }
+});
+
+try_from!(item: &protowire::RpcMethodMetrics, kaspa_rpc_core::RpcMethodMetrics, {
+    Self {
+        method: item.method.clone(),
+        calls: item.calls,
+        errors: item.errors,
+        total_latency_micros: item.total_latency_micros,
+        latency_buckets: item.latency_buckets.clone(),
+    }
+});
+
+try_from!(item: &protowire::P2pMessageMetrics, kaspa_rpc_core::P2pMessageMetrics, {
+    Self {
+        message_type: item.message_type.clone(),
+        sent_messages: item.sent_messages,
+        sent_bytes: item.sent_bytes,
+        received_messages: item.received_messages,
+        received_bytes: item.received_bytes,
+    }
+});
+
+try_from!(item: &protowire::RpcListenerMetrics, kaspa_rpc_core::RpcListenerMetrics, {
+    Self {
+        notifier: item.notifier.clone(),
+        listener_id: item.listener_id,
+        queue_len: item.queue_len,
+        queue_capacity: item.queue_capacity,
+        dropped_notifications: item.dropped_notifications,
+        lagging: item.lagging,
+        overflowed: item.overflowed,
+    }
+});
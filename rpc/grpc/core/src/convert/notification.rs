@@ -1,5 +1,8 @@
 use crate::protowire::{
-    kaspad_response::Payload, BlockAddedNotificationMessage, KaspadResponse, NewBlockTemplateNotificationMessage, RpcNotifyCommand,
+    kaspad_response::Payload, mempool_transaction_removed_notification_message::Reason,
+    sync_state_changed_notification_message::SyncState, AcceptedTransactionsNotificationMessage, BlockAddedNotificationMessage,
+    KaspadResponse, MempoolTransactionRemovedNotificationMessage, NewBlockTemplateNotificationMessage, RpcNotifyCommand,
+    SyncStateChangedNotificationMessage,
 };
 use crate::protowire::{
     FinalityConflictNotificationMessage, FinalityConflictResolvedNotificationMessage, NotifyPruningPointUtxoSetOverrideRequestMessage,
@@ -11,7 +14,7 @@ use crate::protowire::{
 };
 use crate::{from, try_from};
 use kaspa_notify::subscription::Command;
-use kaspa_rpc_core::{Notification, RpcError, RpcHash};
+use kaspa_rpc_core::{Notification, RpcError, RpcHash, RpcMempoolRemovalReason, RpcSyncState};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -19,7 +22,7 @@ use std::sync::Arc;
 // rpc_core to protowire
 // ----------------------------------------------------------------------------
 
-from!(item: &kaspa_rpc_core::Notification, KaspadResponse, { Self { id: 0, payload: Some(item.into()) } });
+from!(item: &kaspa_rpc_core::Notification, KaspadResponse, { Self { id: 0, has_more: false, payload: Some(item.into()) } });
 
 from!(item: &kaspa_rpc_core::Notification, Payload, {
     match item {
@@ -34,6 +37,9 @@ from!(item: &kaspa_rpc_core::Notification, Payload, {
         Notification::PruningPointUtxoSetOverride(ref notification) => {
             Payload::PruningPointUtxoSetOverrideNotification(notification.into())
         }
+        Notification::MempoolTransactionRemoved(ref notification) => Payload::MempoolTransactionRemovedNotification(notification.into()),
+        Notification::AcceptedTransactions(ref notification) => Payload::AcceptedTransactionsNotification(notification.into()),
+        Notification::SyncStateChanged(ref notification) => Payload::SyncStateChangedNotification(notification.into()),
     }
 });
 
@@ -41,6 +47,35 @@ from!(item: &kaspa_rpc_core::BlockAddedNotification, BlockAddedNotificationMessa
 
 from!(&kaspa_rpc_core::NewBlockTemplateNotification, NewBlockTemplateNotificationMessage);
 
+from!(item: &RpcMempoolRemovalReason, Reason, {
+    match item {
+        RpcMempoolRemovalReason::Evicted => Reason::Evicted,
+        RpcMempoolRemovalReason::Replaced => Reason::Replaced,
+        RpcMempoolRemovalReason::Expired => Reason::Expired,
+        RpcMempoolRemovalReason::ConflictWithBlock => Reason::ConflictWithBlock,
+    }
+});
+
+from!(item: &kaspa_rpc_core::MempoolTransactionRemovedNotification, MempoolTransactionRemovedNotificationMessage, {
+    Self { transaction_id: item.transaction_id.to_string(), reason: Reason::from(&item.reason) as i32 }
+});
+
+from!(item: &RpcSyncState, SyncState, {
+    match item {
+        RpcSyncState::IbdStarted => SyncState::IbdStarted,
+        RpcSyncState::HeadersProof => SyncState::HeadersProof,
+        RpcSyncState::Headers => SyncState::Headers,
+        RpcSyncState::UtxoSetImport => SyncState::UtxoSetImport,
+        RpcSyncState::Bodies => SyncState::Bodies,
+        RpcSyncState::IbdCompleted => SyncState::IbdCompleted,
+        RpcSyncState::IbdFailed => SyncState::IbdFailed,
+    }
+});
+
+from!(item: &kaspa_rpc_core::SyncStateChangedNotification, SyncStateChangedNotificationMessage, {
+    Self { state: SyncState::from(&item.state) as i32 }
+});
+
 from!(item: &kaspa_rpc_core::VirtualChainChangedNotification, VirtualChainChangedNotificationMessage, {
     Self {
         removed_chain_block_hashes: item.removed_chain_block_hashes.iter().map(|x| x.to_string()).collect(),
@@ -49,6 +84,10 @@ from!(item: &kaspa_rpc_core::VirtualChainChangedNotification, VirtualChainChange
     }
 });
 
+from!(item: &kaspa_rpc_core::AcceptedTransactionsNotification, AcceptedTransactionsNotificationMessage, {
+    Self { accepted_transactions: item.accepted_transactions.iter().map(|x| x.into()).collect() }
+});
+
 from!(item: &kaspa_rpc_core::FinalityConflictNotification, FinalityConflictNotificationMessage, {
     Self { violating_block_hash: item.violating_block_hash.to_string() }
 });
@@ -117,6 +156,11 @@ try_from!(item: &Payload, kaspa_rpc_core::Notification, {
         Payload::PruningPointUtxoSetOverrideNotification(ref notification) => {
             Notification::PruningPointUtxoSetOverride(notification.try_into()?)
         }
+        Payload::MempoolTransactionRemovedNotification(ref notification) => {
+            Notification::MempoolTransactionRemoved(notification.try_into()?)
+        }
+        Payload::AcceptedTransactionsNotification(ref notification) => Notification::AcceptedTransactions(notification.try_into()?),
+        Payload::SyncStateChangedNotification(ref notification) => Notification::SyncStateChanged(notification.try_into()?),
         _ => Err(RpcError::UnsupportedFeature)?,
     }
 });
@@ -134,6 +178,38 @@ try_from!(item: &BlockAddedNotificationMessage, kaspa_rpc_core::BlockAddedNotifi
 
 try_from!(&NewBlockTemplateNotificationMessage, kaspa_rpc_core::NewBlockTemplateNotification);
 
+from!(item: Reason, RpcMempoolRemovalReason, {
+    match item {
+        Reason::Evicted => RpcMempoolRemovalReason::Evicted,
+        Reason::Replaced => RpcMempoolRemovalReason::Replaced,
+        Reason::Expired => RpcMempoolRemovalReason::Expired,
+        Reason::ConflictWithBlock => RpcMempoolRemovalReason::ConflictWithBlock,
+    }
+});
+
+try_from!(item: &MempoolTransactionRemovedNotificationMessage, kaspa_rpc_core::MempoolTransactionRemovedNotification, {
+    Self {
+        transaction_id: RpcHash::from_str(&item.transaction_id)?,
+        reason: Reason::try_from(item.reason).map_err(|_| RpcError::PrimitiveToEnumConversionError)?.into(),
+    }
+});
+
+from!(item: SyncState, RpcSyncState, {
+    match item {
+        SyncState::IbdStarted => RpcSyncState::IbdStarted,
+        SyncState::HeadersProof => RpcSyncState::HeadersProof,
+        SyncState::Headers => RpcSyncState::Headers,
+        SyncState::UtxoSetImport => RpcSyncState::UtxoSetImport,
+        SyncState::Bodies => RpcSyncState::Bodies,
+        SyncState::IbdCompleted => RpcSyncState::IbdCompleted,
+        SyncState::IbdFailed => RpcSyncState::IbdFailed,
+    }
+});
+
+try_from!(item: &SyncStateChangedNotificationMessage, kaspa_rpc_core::SyncStateChangedNotification, {
+    Self { state: SyncState::try_from(item.state).map_err(|_| RpcError::PrimitiveToEnumConversionError)?.into() }
+});
+
 try_from!(item: &VirtualChainChangedNotificationMessage, kaspa_rpc_core::VirtualChainChangedNotification, {
     Self {
         removed_chain_block_hashes: Arc::new(
@@ -146,6 +222,10 @@ try_from!(item: &VirtualChainChangedNotificationMessage, kaspa_rpc_core::Virtual
     }
 });
 
+try_from!(item: &AcceptedTransactionsNotificationMessage, kaspa_rpc_core::AcceptedTransactionsNotification, {
+    Self { accepted_transactions: Arc::new(item.accepted_transactions.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?) }
+});
+
 try_from!(item: &FinalityConflictNotificationMessage, kaspa_rpc_core::FinalityConflictNotification, {
     Self { violating_block_hash: RpcHash::from_str(&item.violating_block_hash)? }
 });
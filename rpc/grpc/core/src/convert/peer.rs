@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use crate::protowire;
 use crate::{from, try_from};
-use kaspa_rpc_core::{RpcError, RpcNodeId, RpcPeerAddress};
+use kaspa_rpc_core::{RpcError, RpcHash, RpcNodeId, RpcPeerAddress};
 
 // ----------------------------------------------------------------------------
 // rpc_core to protowire
@@ -19,9 +19,15 @@ from!(item: &kaspa_rpc_core::RpcPeerInfo, protowire::GetConnectedPeerInfoMessage
         advertised_protocol_version: item.advertised_protocol_version,
         time_connected: item.time_connected as i64,
         is_ibd_peer: item.is_ibd_peer,
+        claimed_sink: item.claimed_sink.map(|x| x.to_string()).unwrap_or_default(),
+        claimed_daa_score: item.claimed_daa_score,
     }
 });
 
+from!(item: &kaspa_rpc_core::RpcPeerTraffic, protowire::PeerTrafficMessage, {
+    Self { id: item.id.to_string(), bytes_sent: item.bytes_sent, bytes_received: item.bytes_received }
+});
+
 from!(item: &kaspa_rpc_core::RpcPeerAddress, protowire::GetPeerAddressesKnownAddressMessage, { Self { addr: item.to_string() } });
 from!(item: &kaspa_rpc_core::RpcIpAddress, protowire::GetPeerAddressesKnownAddressMessage, { Self { addr: item.to_string() } });
 
@@ -40,8 +46,14 @@ try_from!(item: &protowire::GetConnectedPeerInfoMessage, kaspa_rpc_core::RpcPeer
         advertised_protocol_version: item.advertised_protocol_version,
         time_connected: item.time_connected as u64,
         is_ibd_peer: item.is_ibd_peer,
+        claimed_sink: if item.claimed_sink.is_empty() { None } else { Some(RpcHash::from_str(&item.claimed_sink)?) },
+        claimed_daa_score: item.claimed_daa_score,
     }
 });
 
+try_from!(item: &protowire::PeerTrafficMessage, kaspa_rpc_core::RpcPeerTraffic, {
+    Self { id: RpcNodeId::from_str(&item.id)?, bytes_sent: item.bytes_sent, bytes_received: item.bytes_received }
+});
+
 try_from!(item: &protowire::GetPeerAddressesKnownAddressMessage, kaspa_rpc_core::RpcPeerAddress, { Self::from_str(&item.addr)? });
 try_from!(item: &protowire::GetPeerAddressesKnownAddressMessage, kaspa_rpc_core::RpcIpAddress, { Self::from_str(&item.addr)? });
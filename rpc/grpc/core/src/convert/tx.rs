@@ -1,5 +1,9 @@
+use super::error::check_limit;
 use crate::protowire;
 use crate::{from, try_from};
+use kaspa_consensus_core::config::constants::decoding::{
+    MAX_PAYLOAD_LEN, MAX_SCRIPT_LEN, MAX_TRANSACTION_INPUTS, MAX_TRANSACTION_OUTPUTS,
+};
 use kaspa_rpc_core::{FromRpcHex, RpcError, RpcHash, RpcResult, RpcScriptVec, ToRpcHex};
 use std::str::FromStr;
 
@@ -81,6 +85,13 @@ from!(item: &kaspa_rpc_core::RpcAcceptedTransactionIds, protowire::RpcAcceptedTr
     }
 });
 
+from!(item: &kaspa_rpc_core::RpcAcceptedTransactions, protowire::RpcAcceptedTransactions, {
+    Self {
+        accepting_block_hash: item.accepting_block_hash.to_string(),
+        accepted_transactions: item.accepted_transactions.iter().map(|x| x.into()).collect(),
+    }
+});
+
 from!(item: &kaspa_rpc_core::RpcUtxosByAddressesEntry, protowire::RpcUtxosByAddressesEntry, {
     Self {
         address: item.address.as_ref().map_or("".to_string(), |x| x.into()),
@@ -94,6 +105,10 @@ from!(item: &kaspa_rpc_core::RpcUtxosByAddressesEntry, protowire::RpcUtxosByAddr
 // ----------------------------------------------------------------------------
 
 try_from!(item: &protowire::RpcTransaction, kaspa_rpc_core::RpcTransaction, {
+    check_limit("transaction inputs", item.inputs.len(), MAX_TRANSACTION_INPUTS)?;
+    check_limit("transaction outputs", item.outputs.len(), MAX_TRANSACTION_OUTPUTS)?;
+    // Byte fields are hex encoded
+    check_limit("transaction payload", item.payload.len() / 2, MAX_PAYLOAD_LEN)?;
     Self {
         version: item.version.try_into()?,
         inputs: item
@@ -115,6 +130,7 @@ try_from!(item: &protowire::RpcTransaction, kaspa_rpc_core::RpcTransaction, {
 });
 
 try_from!(item: &protowire::RpcTransactionInput, kaspa_rpc_core::RpcTransactionInput, {
+    check_limit("signature script", item.signature_script.len() / 2, MAX_SCRIPT_LEN)?;
     Self {
         previous_outpoint: item
             .previous_outpoint
@@ -158,6 +174,7 @@ try_from!(item: &protowire::RpcUtxoEntry, kaspa_rpc_core::RpcUtxoEntry, {
 });
 
 try_from!(item: &protowire::RpcScriptPublicKey, kaspa_rpc_core::RpcScriptPublicKey, {
+    check_limit("script public key", item.script_public_key.len() / 2, MAX_SCRIPT_LEN)?;
     Self::new(u16::try_from(item.version)?, RpcScriptVec::from_rpc_hex(item.script_public_key.as_str())?)
 });
 
@@ -187,6 +204,13 @@ try_from!(item: &protowire::RpcAcceptedTransactionIds, kaspa_rpc_core::RpcAccept
     }
 });
 
+try_from!(item: &protowire::RpcAcceptedTransactions, kaspa_rpc_core::RpcAcceptedTransactions, {
+    Self {
+        accepting_block_hash: RpcHash::from_str(&item.accepting_block_hash)?,
+        accepted_transactions: item.accepted_transactions.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+    }
+});
+
 try_from!(item: &protowire::RpcUtxosByAddressesEntry, kaspa_rpc_core::RpcUtxosByAddressesEntry, {
     let address = if item.address.is_empty() { None } else { Some(item.address.as_str().try_into()?) };
     Self {
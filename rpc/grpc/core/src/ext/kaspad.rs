@@ -1,20 +1,26 @@
 use kaspa_notify::{scope::Scope, subscription::Command};
 
 use crate::protowire::{
-    kaspad_request, kaspad_response, KaspadRequest, KaspadResponse, NotifyBlockAddedRequestMessage,
-    NotifyFinalityConflictRequestMessage, NotifyNewBlockTemplateRequestMessage, NotifyPruningPointUtxoSetOverrideRequestMessage,
-    NotifySinkBlueScoreChangedRequestMessage, NotifyUtxosChangedRequestMessage, NotifyVirtualChainChangedRequestMessage,
+    kaspad_request, kaspad_response, KaspadRequest, KaspadResponse, NotifyAcceptedTransactionsRequestMessage,
+    NotifyBlockAddedRequestMessage, NotifyFinalityConflictRequestMessage, NotifyMempoolTransactionRemovedRequestMessage,
+    NotifyNewBlockTemplateRequestMessage, NotifyPruningPointUtxoSetOverrideRequestMessage, NotifySinkBlueScoreChangedRequestMessage,
+    NotifySyncStateChangedRequestMessage, NotifyUtxosChangedRequestMessage, NotifyVirtualChainChangedRequestMessage,
     NotifyVirtualDaaScoreChangedRequestMessage,
 };
 
 impl KaspadRequest {
     pub fn from_notification_type(scope: &Scope, command: Command) -> Self {
-        KaspadRequest { id: 0, payload: Some(kaspad_request::Payload::from_notification_type(scope, command)) }
+        KaspadRequest { id: 0, chunk_size: 0, payload: Some(kaspad_request::Payload::from_notification_type(scope, command)) }
     }
 
     pub fn is_subscription(&self) -> bool {
         self.payload.as_ref().is_some_and(|x| x.is_subscription())
     }
+
+    /// Returns true if the server may stream the response to this request as a sequence of chunks
+    pub fn is_streamable(&self) -> bool {
+        self.payload.as_ref().is_some_and(|x| x.is_streamable())
+    }
 }
 
 impl kaspad_request::Payload {
@@ -64,6 +70,22 @@ impl kaspad_request::Payload {
                     command: command.into(),
                 })
             }
+            Scope::MempoolTransactionRemoved(_) => {
+                kaspad_request::Payload::NotifyMempoolTransactionRemovedRequest(NotifyMempoolTransactionRemovedRequestMessage {
+                    command: command.into(),
+                })
+            }
+            Scope::AcceptedTransactions(ref scope) => {
+                kaspad_request::Payload::NotifyAcceptedTransactionsRequest(NotifyAcceptedTransactionsRequestMessage {
+                    addresses: scope.addresses.iter().map(|x| x.into()).collect::<Vec<String>>(),
+                    command: command.into(),
+                })
+            }
+            Scope::SyncStateChanged(_) => {
+                kaspad_request::Payload::NotifySyncStateChangedRequest(NotifySyncStateChangedRequestMessage {
+                    command: command.into(),
+                })
+            }
         }
     }
 
@@ -79,10 +101,18 @@ impl kaspad_request::Payload {
                 | Payload::NotifyVirtualDaaScoreChangedRequest(_)
                 | Payload::NotifyPruningPointUtxoSetOverrideRequest(_)
                 | Payload::NotifyNewBlockTemplateRequest(_)
+                | Payload::NotifyMempoolTransactionRemovedRequest(_)
+                | Payload::NotifyAcceptedTransactionsRequest(_)
+                | Payload::NotifySyncStateChangedRequest(_)
                 | Payload::StopNotifyingUtxosChangedRequest(_)
                 | Payload::StopNotifyingPruningPointUtxoSetOverrideRequest(_)
         )
     }
+
+    pub fn is_streamable(&self) -> bool {
+        use crate::protowire::kaspad_request::Payload;
+        matches!(self, Payload::GetBlocksRequest(_) | Payload::GetMempoolEntriesRequest(_))
+    }
 }
 
 impl KaspadResponse {
@@ -92,6 +122,24 @@ impl KaspadResponse {
             None => false,
         }
     }
+
+    /// Appends the items of the next chunk of a streamed response to this response
+    pub fn merge_chunk(&mut self, chunk: KaspadResponse) {
+        use crate::protowire::kaspad_response::Payload;
+        self.has_more = chunk.has_more;
+        match (&mut self.payload, chunk.payload) {
+            (Some(Payload::GetBlocksResponse(response)), Some(Payload::GetBlocksResponse(chunk))) => {
+                response.block_hashes.extend(chunk.block_hashes);
+                response.blocks.extend(chunk.blocks);
+                response.error = response.error.take().or(chunk.error);
+            }
+            (Some(Payload::GetMempoolEntriesResponse(response)), Some(Payload::GetMempoolEntriesResponse(chunk))) => {
+                response.entries.extend(chunk.entries);
+                response.error = response.error.take().or(chunk.error);
+            }
+            (_, payload) => self.payload = payload,
+        }
+    }
 }
 
 #[allow(clippy::match_like_matches_macro)]
@@ -108,7 +156,50 @@ impl kaspad_response::Payload {
             Payload::VirtualDaaScoreChangedNotification(_) => true,
             Payload::PruningPointUtxoSetOverrideNotification(_) => true,
             Payload::NewBlockTemplateNotification(_) => true,
+            Payload::MempoolTransactionRemovedNotification(_) => true,
+            Payload::AcceptedTransactionsNotification(_) => true,
+            Payload::SyncStateChangedNotification(_) => true,
             _ => false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protowire::{
+        kaspad_response::Payload, GetBlocksResponseMessage, GetMempoolEntriesResponseMessage, RpcBlock, RpcError, RpcMempoolEntry,
+    };
+
+    #[test]
+    fn test_merge_chunks() {
+        let chunk = |id, has_more, range: std::ops::Range<usize>| KaspadResponse {
+            id,
+            has_more,
+            payload: Some(Payload::GetBlocksResponse(GetBlocksResponseMessage {
+                block_hashes: range.clone().map(|i| i.to_string()).collect(),
+                blocks: vec![RpcBlock::default(); range.len()],
+                error: None,
+            })),
+        };
+        let mut merged = chunk(7, true, 0..2);
+        merged.merge_chunk(chunk(7, true, 2..4));
+        assert!(merged.has_more);
+        merged.merge_chunk(chunk(7, false, 4..5));
+        assert_eq!(merged, chunk(7, false, 0..5));
+
+        // An error ending the stream is kept along with the entries received so far
+        let entries = |has_more, count, error: Option<RpcError>| KaspadResponse {
+            id: 8,
+            has_more,
+            payload: Some(Payload::GetMempoolEntriesResponse(GetMempoolEntriesResponseMessage {
+                entries: vec![RpcMempoolEntry::default(); count],
+                error,
+            })),
+        };
+        let error = RpcError { message: "failure".to_string() };
+        let mut merged = entries(true, 2, None);
+        merged.merge_chunk(entries(false, 0, Some(error.clone())));
+        assert_eq!(merged, entries(false, 2, Some(error)));
+    }
+}
@@ -81,6 +81,29 @@ pub enum KaspadPayloadOps {
     GetServerInfo,
     GetSyncStatus,
     GetDaaScoreTimestampEstimate,
+    GetTransaction,
+    GetBlockValidationTrace,
+    GetHealth,
+    ReloadSettings,
+    GetCurrentDifficulty,
+    GetBlockDagTimingInfo,
+    GetUtxoSetChunk,
+    GetStateDigests,
+    GetDagBlocksAround,
+    GetBlockRelations,
+    GetTransactionAcceptanceProof,
+    InvalidateBlock,
+    ReconsiderBlock,
+    GetNetworkStats,
+    GetMempoolFeeHistogram,
+    GetRelayPolicy,
+    GetUtxoReturnAddress,
+    GetSpendingTransaction,
+    StartBlockProduction,
+    StopBlockProduction,
+    ResetLifetimeMetrics,
+    GetSystemInfo,
+    CheckTransaction,
 
     // Subscription commands for starting/stopping notifications
     NotifyBlockAdded,
@@ -91,6 +114,9 @@ pub enum KaspadPayloadOps {
     NotifyPruningPointUtxoSetOverride,
     NotifyVirtualDaaScoreChanged,
     NotifyVirtualChainChanged,
+    NotifyMempoolTransactionRemoved,
+    NotifyAcceptedTransactions,
+    NotifySyncStateChanged,
 
     // Legacy stop subscription commands
     StopNotifyingUtxosChanged,
@@ -101,3 +127,23 @@ pub enum KaspadPayloadOps {
     // The conversion from a notification ResponsePayload into KaspadPayloadOps fails.
 }
 }
+
+impl KaspadPayloadOps {
+    /// Returns whether the op affects the state of the node, mirroring [`kaspa_rpc_core::api::ops::RpcApiOps::is_unsafe`]
+    pub fn is_unsafe(&self) -> bool {
+        matches!(
+            self,
+            KaspadPayloadOps::Shutdown
+                | KaspadPayloadOps::AddPeer
+                | KaspadPayloadOps::Ban
+                | KaspadPayloadOps::Unban
+                | KaspadPayloadOps::ResolveFinalityConflict
+                | KaspadPayloadOps::ReloadSettings
+                | KaspadPayloadOps::InvalidateBlock
+                | KaspadPayloadOps::ReconsiderBlock
+                | KaspadPayloadOps::StartBlockProduction
+                | KaspadPayloadOps::StopBlockProduction
+                | KaspadPayloadOps::ResetLifetimeMetrics
+        )
+    }
+}
@@ -1,7 +1,12 @@
-use crate::{connection_handler::ConnectionHandler, manager::Manager};
+use crate::{connection::Connection, connection_handler::ConnectionHandler, manager::Manager};
 use kaspa_core::debug;
 use kaspa_notify::notifier::Notifier;
-use kaspa_rpc_core::{api::rpc::DynRpcService, notify::connection::ChannelConnection, Notification, RpcResult};
+use kaspa_rpc_core::{
+    api::{profile::RpcProfile, rpc::DynRpcService},
+    metrics::RpcMethodCounters,
+    notify::connection::ChannelConnection,
+    Notification, RpcResult,
+};
 use kaspa_utils::networking::NetAddress;
 use std::{ops::Deref, sync::Arc};
 use tokio::sync::{mpsc::channel as mpsc_channel, oneshot::Sender as OneshotSender};
@@ -35,9 +40,11 @@ impl Adaptor {
         manager: Manager,
         core_service: DynRpcService,
         core_notifier: Arc<Notifier<Notification, ChannelConnection>>,
+        profile: RpcProfile,
+        method_counters: Arc<RpcMethodCounters>,
     ) -> Arc<Self> {
         let (manager_sender, manager_receiver) = mpsc_channel(Self::manager_channel_size());
-        let connection_handler = ConnectionHandler::new(manager_sender, core_service.clone(), core_notifier);
+        let connection_handler = ConnectionHandler::new(manager_sender, core_service.clone(), core_notifier, profile, method_counters);
         let server_termination = connection_handler.serve(serve_address);
         let adaptor = Arc::new(Adaptor::new(Some(server_termination), connection_handler, manager, serve_address));
         adaptor.manager.clone().start_event_loop(manager_receiver);
@@ -49,6 +56,11 @@ impl Adaptor {
         self.serve_address
     }
 
+    /// The notifier relaying the notifications to the connections of this server
+    pub fn notifier(&self) -> Arc<Notifier<Notification, Connection>> {
+        self.connection_handler.notifier()
+    }
+
     pub fn start(&self) {
         self.connection_handler.start()
     }
@@ -43,6 +43,9 @@ struct InnerMutableState {
     /// Used on connection close to signal the connection receive loop to exit
     shutdown_signal: Option<OneshotSender<()>>,
 
+    /// Used on listener overflow to end the outgoing stream with an error status
+    overflow_signal: Option<OneshotSender<tonic::Status>>,
+
     /// Notification listener Id
     ///
     /// Registered when handling the first subscription to any notifications
@@ -50,8 +53,8 @@ struct InnerMutableState {
 }
 
 impl InnerMutableState {
-    fn new(shutdown_signal: Option<OneshotSender<()>>) -> Self {
-        Self { shutdown_signal, ..Default::default() }
+    fn new(shutdown_signal: Option<OneshotSender<()>>, overflow_signal: Option<OneshotSender<tonic::Status>>) -> Self {
+        Self { shutdown_signal, overflow_signal, ..Default::default() }
     }
 }
 
@@ -153,6 +156,7 @@ impl Connection {
         manager_sender: MpscSender<ManagerEvent>,
         mut incoming_stream: Streaming<KaspadRequest>,
         outgoing_route: GrpcSender,
+        overflow_signal: OneshotSender<tonic::Status>,
     ) -> Self {
         let (shutdown_sender, mut shutdown_receiver) = oneshot_channel();
         let mut router = Router::new(server_context.clone(), interface.clone());
@@ -163,7 +167,7 @@ impl Connection {
                 outgoing_route,
                 manager_sender,
                 server_context,
-                mutable_state: Mutex::new(InnerMutableState::new(Some(shutdown_sender))),
+                mutable_state: Mutex::new(InnerMutableState::new(Some(shutdown_sender), Some(overflow_signal))),
                 is_closed: AtomicBool::new(false),
             }),
         };
@@ -274,6 +278,15 @@ impl Connection {
         256
     }
 
+    /// Enqueues a chunk of a streamed response, waiting for room in the outgoing route so that a large
+    /// response does not overflow it
+    pub async fn enqueue_chunk(&self, response: KaspadResponse) -> GrpcServerResult<()> {
+        if self.is_closed() {
+            return Err(GrpcServerError::ConnectionClosed);
+        }
+        self.inner.outgoing_route.send(response).await.map_err(|_| GrpcServerError::ConnectionClosed)
+    }
+
     /// Enqueues a response to be sent to the client
     pub async fn enqueue(&self, response: KaspadResponse) -> GrpcServerResult<()> {
         assert!(response.payload.is_some(), "Kaspad gRPC message should always have a value");
@@ -378,4 +391,24 @@ impl ConnectionT for Connection {
     fn is_closed(&self) -> bool {
         self.inner.is_closed.load(Ordering::SeqCst)
     }
+
+    fn queue_len(&self) -> usize {
+        self.inner.outgoing_route.max_capacity() - self.inner.outgoing_route.capacity()
+    }
+
+    fn queue_capacity(&self) -> Option<usize> {
+        Some(self.inner.outgoing_route.max_capacity())
+    }
+
+    /// Ends the outgoing stream with a `ResourceExhausted` status, bypassing the full outgoing route, so that
+    /// the client learns why it got disconnected, then closes the connection.
+    ///
+    /// Returns true if this is the first call to close.
+    fn overflow(&self) -> bool {
+        let signal = self.inner.mutable_state.lock().overflow_signal.take();
+        if let Some(signal) = signal {
+            let _ = signal.send(tonic::Status::resource_exhausted("Notifications were not consumed in time"));
+        }
+        self.close()
+    }
 }
@@ -15,7 +15,8 @@ use kaspa_grpc_core::{
 };
 use kaspa_notify::{connection::ChannelType, events::EVENT_TYPE_ARRAY, notifier::Notifier, subscriber::Subscriber};
 use kaspa_rpc_core::{
-    api::rpc::DynRpcService,
+    api::{profile::RpcProfile, rpc::DynRpcService},
+    metrics::RpcMethodCounters,
     notify::{channel::NotificationChannel, connection::ChannelConnection},
     Notification, RpcResult,
 };
@@ -43,11 +44,20 @@ pub struct ServerContext {
     pub core_service: DynRpcService,
     /// The notifier relaying RPC core notifications to connections
     pub notifier: Arc<Notifier<Notification, Connection>>,
+    /// The permission profile of the listener, restricting the RPC methods served
+    pub profile: RpcProfile,
+    /// The counters measuring the RPC methods served
+    pub method_counters: Arc<RpcMethodCounters>,
 }
 
 impl ServerContext {
-    pub fn new(core_service: DynRpcService, notifier: Arc<Notifier<Notification, Connection>>) -> Self {
-        Self { core_service, notifier }
+    pub fn new(
+        core_service: DynRpcService,
+        notifier: Arc<Notifier<Notification, Connection>>,
+        profile: RpcProfile,
+        method_counters: Arc<RpcMethodCounters>,
+    ) -> Self {
+        Self { core_service, notifier, profile, method_counters }
     }
 }
 
@@ -73,6 +83,8 @@ impl ConnectionHandler {
         manager_sender: MpscSender<ManagerEvent>,
         core_service: DynRpcService,
         core_notifier: Arc<Notifier<Notification, ChannelConnection>>,
+        profile: RpcProfile,
+        method_counters: Arc<RpcMethodCounters>,
     ) -> Self {
         // Prepare core objects
         let core_channel = NotificationChannel::default();
@@ -86,7 +98,7 @@ impl ConnectionHandler {
         let subscriber = Arc::new(Subscriber::new(GRPC_SERVER, core_events, core_notifier, core_listener_id));
         let notifier: Arc<Notifier<Notification, Connection>> =
             Arc::new(Notifier::new(GRPC_SERVER, core_events, vec![collector], vec![subscriber], 10));
-        let server_context = ServerContext::new(core_service, notifier);
+        let server_context = ServerContext::new(core_service, notifier, profile, method_counters);
         let interface = Arc::new(Factory::new_interface(server_context.clone()));
         let running = Default::default();
 
@@ -98,7 +110,7 @@ impl ConnectionHandler {
         let (termination_sender, termination_receiver) = oneshot_channel::<()>();
         let (signal_sender, signal_receiver) = oneshot_channel::<()>();
         let connection_handler = self.clone();
-        info!("GRPC Server starting on: {}", serve_address);
+        info!("GRPC Server starting on: {} ({} profile)", serve_address, self.server_context.profile);
 
         // Spawn server task
         let server_handle = tokio::spawn(async move {
@@ -155,7 +167,7 @@ impl ConnectionHandler {
     }
 
     #[inline(always)]
-    fn notifier(&self) -> Arc<Notifier<Notification, Connection>> {
+    pub(crate) fn notifier(&self) -> Arc<Notifier<Notification, Connection>> {
         self.server_context.notifier.clone()
     }
 
@@ -223,6 +235,7 @@ impl Rpc for ConnectionHandler {
 
         // Build the in/out pipes
         let (outgoing_route, outgoing_receiver) = mpsc_channel(Self::outgoing_route_channel_size());
+        let (overflow_sender, overflow_receiver) = oneshot_channel();
         let incoming_stream = request.into_inner();
 
         // Build the connection object
@@ -233,6 +246,7 @@ impl Rpc for ConnectionHandler {
             self.manager_sender(),
             incoming_stream,
             outgoing_route,
+            overflow_sender,
         );
 
         // Try to get the connection registered into the central Manager
@@ -264,7 +278,9 @@ impl Rpc for ConnectionHandler {
             }
         }
 
-        // Give tonic a receiver stream (messages sent to it will be forwarded to the client)
-        Ok(Response::new(Box::pin(ReceiverStream::new(outgoing_receiver).map(Ok)) as Self::MessageStreamStream))
+        // Give tonic a receiver stream (messages sent to it will be forwarded to the client), ended by an error status
+        // if the connection gets evicted for not consuming its notifications in time
+        let overflow = overflow_receiver.into_stream().filter_map(|status| status.ok().map(Err));
+        Ok(Response::new(Box::pin(ReceiverStream::new(outgoing_receiver).map(Ok).merge(overflow)) as Self::MessageStreamStream))
     }
 }
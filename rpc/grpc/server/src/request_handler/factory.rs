@@ -1,4 +1,4 @@
-use super::{handler::RequestHandler, handler_trait::Handler, interface::Interface, method::Method};
+use super::{handler::RequestHandler, handler_trait::Handler, interface::Interface, method::Method, stream::stream_response};
 use crate::{
     connection::{Connection, IncomingRoute},
     connection_handler::ServerContext,
@@ -7,8 +7,59 @@ use crate::{
 use kaspa_grpc_core::protowire::{kaspad_request::Payload, *};
 use kaspa_grpc_core::{ops::KaspadPayloadOps, protowire::NotifyFinalityConflictResponseMessage};
 use kaspa_notify::{scope::FinalityConflictResolvedScope, subscriber::SubscriptionManager};
+use kaspa_rpc_core::{
+    api::ops::RpcApiOps,
+    trace::{next_correlation_id, tracing::info_span, Instrument},
+};
 use kaspa_rpc_macros::build_grpc_server_interface;
 
+/// Builds a method serving a request whose response may be streamed as chunks (see [`KaspadRequest::is_streamable`]).
+/// A request with a non-zero chunk size is served by the chunked variant of the RPC call, each chunk being sent as soon
+/// as it is produced, while other requests are served as usual
+macro_rules! streamable_method {
+    ($handler:ident, $request_type:ident, $response_message_type:ident, $fn_call:ident, $fn_chunked_call:ident) => {{
+        let method: Method<ServerContext, Connection, KaspadRequest, KaspadResponse> =
+            Method::new(|server_ctx: ServerContext, connection: Connection, request: KaspadRequest| {
+                Box::pin(async move {
+                    let mut response: KaspadResponse = match request.payload {
+                        Some(Payload::$request_type(ref message)) => match message.try_into() {
+                            Ok(rpc_request) => {
+                                let span = info_span!(stringify!($handler), correlation_id = next_correlation_id());
+                                let core_service = server_ctx.core_service.clone();
+                                match request.chunk_size as usize {
+                                    0 => server_ctx
+                                        .method_counters
+                                        .measure(RpcApiOps::$handler, core_service.$fn_call(rpc_request))
+                                        .instrument(span)
+                                        .await
+                                        .into(),
+                                    chunk_size => {
+                                        let (sender, receiver) = async_channel::bounded(1);
+                                        let call = server_ctx
+                                            .method_counters
+                                            .measure(
+                                                RpcApiOps::$handler,
+                                                core_service.$fn_chunked_call(rpc_request, chunk_size, sender),
+                                            )
+                                            .instrument(span);
+                                        stream_response(&connection, request.id, call, receiver).await?
+                                    }
+                                }
+                            }
+                            Err(err) => $response_message_type::from(err).into(),
+                        },
+                        _ => {
+                            return Err(GrpcServerError::InvalidRequestPayload);
+                        }
+                    };
+                    response.id = request.id;
+                    Ok(response)
+                })
+            });
+        method
+    }};
+}
+
 pub struct Factory {}
 
 impl Factory {
@@ -67,6 +118,29 @@ impl Factory {
                 GetServerInfo,
                 GetSyncStatus,
                 GetDaaScoreTimestampEstimate,
+                GetTransaction,
+                GetBlockValidationTrace,
+                GetHealth,
+                ReloadSettings,
+                GetCurrentDifficulty,
+                GetBlockDagTimingInfo,
+                GetUtxoSetChunk,
+                GetStateDigests,
+                GetDagBlocksAround,
+                GetBlockRelations,
+                GetTransactionAcceptanceProof,
+                InvalidateBlock,
+                ReconsiderBlock,
+                GetNetworkStats,
+                GetMempoolFeeHistogram,
+                GetRelayPolicy,
+                GetUtxoReturnAddress,
+                GetSpendingTransaction,
+                StartBlockProduction,
+                StopBlockProduction,
+                ResetLifetimeMetrics,
+                GetSystemInfo,
+                CheckTransaction,
                 NotifyBlockAdded,
                 NotifyNewBlockTemplate,
                 NotifyFinalityConflict,
@@ -75,6 +149,9 @@ impl Factory {
                 NotifyPruningPointUtxoSetOverride,
                 NotifyVirtualDaaScoreChanged,
                 NotifyVirtualChainChanged,
+                NotifyMempoolTransactionRemoved,
+                NotifyAcceptedTransactions,
+                NotifySyncStateChanged,
                 StopNotifyingUtxosChanged,
                 StopNotifyingPruningPointUtxoSetOverride,
             ]
@@ -122,6 +199,22 @@ impl Factory {
             });
         interface.replace_method(KaspadPayloadOps::NotifyFinalityConflict, method);
 
+        // Large responses are streamed as chunks to the clients asking for it
+        interface.replace_method(
+            KaspadPayloadOps::GetBlocks,
+            streamable_method!(GetBlocks, GetBlocksRequest, GetBlocksResponseMessage, get_blocks_call, get_blocks_chunked_call),
+        );
+        interface.replace_method(
+            KaspadPayloadOps::GetMempoolEntries,
+            streamable_method!(
+                GetMempoolEntries,
+                GetMempoolEntriesRequest,
+                GetMempoolEntriesResponseMessage,
+                get_mempool_entries_call,
+                get_mempool_entries_chunked_call
+            ),
+        );
+
         interface
     }
 }
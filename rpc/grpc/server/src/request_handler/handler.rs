@@ -12,6 +12,7 @@ use kaspa_grpc_core::{
     ops::KaspadPayloadOps,
     protowire::{KaspadRequest, KaspadResponse},
 };
+use kaspa_rpc_core::RpcError;
 
 pub struct RequestHandler {
     rpc_op: KaspadPayloadOps,
@@ -35,7 +36,13 @@ impl RequestHandler {
 
     pub async fn handle_request(&self, request: KaspadRequest) -> GrpcServerResult<KaspadResponse> {
         let id = request.id;
-        let mut response = self.method.call(self.server_ctx.clone(), self.connection.clone(), request).await?;
+        if !self.server_ctx.profile.allows(self.rpc_op.is_unsafe()) {
+            let payload = self.rpc_op.to_error_response(RpcError::UnavailableOnSafeListener);
+            return Ok(KaspadResponse { id, has_more: false, payload: Some(payload) });
+        }
+        // The call is served within the profile of the listener, which the RPC core service restricts unsafe behaviors by
+        let mut response =
+            self.server_ctx.profile.scope(self.method.call(self.server_ctx.clone(), self.connection.clone(), request)).await?;
         response.id = id;
         Ok(response)
     }
@@ -34,6 +34,7 @@ impl Interface {
                 match kaspad_request.payload {
                     Some(ref request) => Ok(KaspadResponse {
                         id: kaspad_request.id,
+                        has_more: false,
                         payload: Some(KaspadPayloadOps::from(request).to_error_response(GrpcServerError::MethodNotImplemented.into())),
                     }),
                     None => Err(GrpcServerError::InvalidRequestPayload),
@@ -3,3 +3,4 @@ pub mod handler;
 pub mod handler_trait;
 pub mod interface;
 pub mod method;
+pub mod stream;
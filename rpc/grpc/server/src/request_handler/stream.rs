@@ -0,0 +1,40 @@
+use crate::{connection::Connection, error::GrpcServerResult};
+use async_channel::Receiver;
+use futures::{join, Future};
+use kaspa_grpc_core::protowire::KaspadResponse;
+use kaspa_rpc_core::{RpcError, RpcResult};
+
+/// Serves a request whose response is streamed by `call` as a sequence of chunks sent through `chunks`.
+///
+/// Every chunk is enqueued flagged with `has_more` as soon as the next one is produced, so that the full response
+/// is never held in memory, while the last chunk, or the error the call fails with, is returned as the response
+/// of the request.
+pub async fn stream_response<T>(
+    connection: &Connection,
+    id: u64,
+    call: impl Future<Output = RpcResult<()>>,
+    chunks: Receiver<T>,
+) -> GrpcServerResult<KaspadResponse>
+where
+    KaspadResponse: From<RpcResult<T>>,
+{
+    // The receiver is owned by the forwarding future, so that a failure to enqueue closes the channel and ends the call
+    let forward = async move {
+        let mut pending = None;
+        while let Ok(chunk) = chunks.recv().await {
+            if let Some(previous) = pending.replace(chunk) {
+                let mut response = KaspadResponse::from(Ok(previous));
+                response.id = id;
+                response.has_more = true;
+                connection.enqueue_chunk(response).await?;
+            }
+        }
+        GrpcServerResult::Ok(pending)
+    };
+    let (result, last) = join!(call, forward);
+    let last = last?;
+    let result = result.and_then(|_| last.ok_or_else(|| RpcError::General("the streamed response has no chunks".to_string())));
+    let mut response = KaspadResponse::from(result);
+    response.id = id;
+    Ok(response)
+}
@@ -4,22 +4,25 @@ use kaspa_core::{
     task::service::{AsyncService, AsyncServiceFuture},
     trace, warn,
 };
+use kaspa_rpc_core::api::profile::RpcProfile;
 use kaspa_rpc_service::service::RpcCoreService;
 use kaspa_utils::{networking::NetAddress, triggers::SingleTrigger};
 use std::sync::Arc;
 
 const GRPC_SERVICE: &str = "grpc-service";
 
+/// Serves the gRPC API on one or more listen addresses, each with its own permission profile.
+/// The maximum number of clients is shared by all the listeners.
 pub struct GrpcService {
-    net_address: NetAddress,
+    listeners: Vec<(NetAddress, RpcProfile)>,
     core_service: Arc<RpcCoreService>,
     rpc_max_clients: usize,
     shutdown: SingleTrigger,
 }
 
 impl GrpcService {
-    pub fn new(address: NetAddress, core_service: Arc<RpcCoreService>, rpc_max_clients: usize) -> Self {
-        Self { net_address: address, core_service, rpc_max_clients, shutdown: Default::default() }
+    pub fn new(listeners: Vec<(NetAddress, RpcProfile)>, core_service: Arc<RpcCoreService>, rpc_max_clients: usize) -> Self {
+        Self { listeners, core_service, rpc_max_clients, shutdown: Default::default() }
     }
 }
 
@@ -35,24 +38,40 @@ impl AsyncService for GrpcService {
         let shutdown_signal = self.shutdown.listener.clone();
 
         let manager = Manager::new(self.rpc_max_clients);
-        let grpc_adaptor = Adaptor::server(self.net_address, manager, self.core_service.clone(), self.core_service.notifier());
+        let grpc_adaptors = self
+            .listeners
+            .iter()
+            .map(|&(net_address, profile)| {
+                Adaptor::server(
+                    net_address,
+                    manager.clone(),
+                    self.core_service.clone(),
+                    self.core_service.notifier(),
+                    profile,
+                    self.core_service.method_counters(),
+                )
+            })
+            .collect::<Vec<_>>();
+        grpc_adaptors.iter().for_each(|adaptor| self.core_service.register_listener_metrics_source(adaptor.notifier()));
 
         // Launch the service and wait for a shutdown signal
         Box::pin(async move {
-            // Keep the gRPC server running until a service shutdown signal is received
+            // Keep the gRPC servers running until a service shutdown signal is received
             shutdown_signal.await;
 
-            // Stop the connection handler, closing all connections and refusing new ones
-            match grpc_adaptor.stop().await {
-                Ok(_) => {
-                    debug!("GRPC, Adaptor terminated successfully");
-                }
-                Err(err) => {
-                    warn!("{} error while stopping the connection handler: {}", GRPC_SERVICE, err);
+            // Stop the connection handlers, closing all connections and refusing new ones
+            for grpc_adaptor in grpc_adaptors.iter() {
+                match grpc_adaptor.stop().await {
+                    Ok(_) => {
+                        debug!("GRPC, Adaptor on {} terminated successfully", grpc_adaptor.serve_address());
+                    }
+                    Err(err) => {
+                        warn!("{} error while stopping the connection handler: {}", GRPC_SERVICE, err);
+                    }
                 }
             }
 
-            // On exit, the adaptor is dropped, causing the server termination
+            // On exit, the adaptors are dropped, causing the servers termination
             Ok(())
         })
     }
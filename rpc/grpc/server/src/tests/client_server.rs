@@ -3,7 +3,11 @@ use crate::{adaptor::Adaptor, manager::Manager};
 use kaspa_core::info;
 use kaspa_grpc_client::GrpcClient;
 use kaspa_notify::scope::{NewBlockTemplateScope, Scope};
-use kaspa_rpc_core::{api::rpc::RpcApi, notify::mode::NotificationMode};
+use kaspa_rpc_core::{
+    api::{profile::RpcProfile, rpc::RpcApi},
+    notify::mode::NotificationMode,
+    RpcError,
+};
 use kaspa_utils::networking::{ContextualNetAddress, NetAddress};
 use std::sync::Arc;
 
@@ -187,9 +191,43 @@ async fn test_client_server_notifications() {
     tokio::time::sleep(std::time::Duration::from_millis(50)).await;
 }
 
+#[tokio::test]
+async fn test_client_server_safe_profile() {
+    kaspa_core::log::try_init_logger("info, kaspa_grpc_core=trace, kaspa_grpc_server=trace, kaspa_grpc_client=trace");
+
+    // Create and start a fake core service
+    let rpc_core_service = Arc::new(RpcCoreMock::new());
+    rpc_core_service.start();
+
+    // Create and start a server with the safe permission profile
+    let server = create_server_with_profile(rpc_core_service.clone(), RpcProfile::Safe);
+    let client = create_client(server.serve_address()).await;
+
+    // Safe methods reach the core service, which does not implement them, while unsafe methods are refused by the listener
+    let safe_listener_error = RpcError::UnavailableOnSafeListener.to_string();
+    assert!(!client.ping().await.unwrap_err().to_string().contains(&safe_listener_error));
+    assert!(client.shutdown().await.unwrap_err().to_string().contains(&safe_listener_error));
+
+    // Stop the fake service
+    rpc_core_service.join().await;
+
+    // Stop the server
+    assert!(server.stop().await.is_ok(), "error stopping the server");
+
+    assert!(client.disconnect().await.is_ok(), "client failed to disconnect");
+    drop(client);
+
+    drop(server);
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+}
+
 fn create_server(core_service: Arc<RpcCoreMock>) -> Arc<Adaptor> {
+    create_server_with_profile(core_service, RpcProfile::Unsafe)
+}
+
+fn create_server_with_profile(core_service: Arc<RpcCoreMock>, profile: RpcProfile) -> Arc<Adaptor> {
     let manager = Manager::new(128);
-    Adaptor::server(get_free_net_address(), manager, core_service.clone(), core_service.core_notifier())
+    Adaptor::server(get_free_net_address(), manager, core_service.clone(), core_service.core_notifier(), profile, Default::default())
 }
 
 async fn create_client(server_address: NetAddress) -> GrpcClient {
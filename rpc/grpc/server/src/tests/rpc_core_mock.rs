@@ -65,6 +65,7 @@ impl RpcApi for RpcCoreMock {
             is_synced: false,
             has_notify_command: false,
             has_message_id: false,
+            is_clock_drifted: false,
         })
     }
 
@@ -218,6 +219,113 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
+    async fn get_transaction_call(&self, _request: GetTransactionRequest) -> RpcResult<GetTransactionResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_block_validation_trace_call(
+        &self,
+        _request: GetBlockValidationTraceRequest,
+    ) -> RpcResult<GetBlockValidationTraceResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_health_call(&self, _request: GetHealthRequest) -> RpcResult<GetHealthResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn reload_settings_call(&self, _request: ReloadSettingsRequest) -> RpcResult<ReloadSettingsResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_current_difficulty_call(&self, _request: GetCurrentDifficultyRequest) -> RpcResult<GetCurrentDifficultyResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_block_dag_timing_info_call(
+        &self,
+        _request: GetBlockDagTimingInfoRequest,
+    ) -> RpcResult<GetBlockDagTimingInfoResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_utxo_set_chunk_call(&self, _request: GetUtxoSetChunkRequest) -> RpcResult<GetUtxoSetChunkResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_state_digests_call(&self, _request: GetStateDigestsRequest) -> RpcResult<GetStateDigestsResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_dag_blocks_around_call(&self, _request: GetDagBlocksAroundRequest) -> RpcResult<GetDagBlocksAroundResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_block_relations_call(&self, _request: GetBlockRelationsRequest) -> RpcResult<GetBlockRelationsResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_transaction_acceptance_proof_call(
+        &self,
+        _request: GetTransactionAcceptanceProofRequest,
+    ) -> RpcResult<GetTransactionAcceptanceProofResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn invalidate_block_call(&self, _request: InvalidateBlockRequest) -> RpcResult<InvalidateBlockResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn reconsider_block_call(&self, _request: ReconsiderBlockRequest) -> RpcResult<ReconsiderBlockResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_network_stats_call(&self, _request: GetNetworkStatsRequest) -> RpcResult<GetNetworkStatsResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_mempool_fee_histogram_call(
+        &self,
+        _request: GetMempoolFeeHistogramRequest,
+    ) -> RpcResult<GetMempoolFeeHistogramResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_relay_policy_call(&self, _request: GetRelayPolicyRequest) -> RpcResult<GetRelayPolicyResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_utxo_return_address_call(&self, _request: GetUtxoReturnAddressRequest) -> RpcResult<GetUtxoReturnAddressResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_spending_transaction_call(
+        &self,
+        _request: GetSpendingTransactionRequest,
+    ) -> RpcResult<GetSpendingTransactionResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn start_block_production_call(&self, _request: StartBlockProductionRequest) -> RpcResult<StartBlockProductionResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn stop_block_production_call(&self, _request: StopBlockProductionRequest) -> RpcResult<StopBlockProductionResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn reset_lifetime_metrics_call(&self, _request: ResetLifetimeMetricsRequest) -> RpcResult<ResetLifetimeMetricsResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_system_info_call(&self, _request: GetSystemInfoRequest) -> RpcResult<GetSystemInfoResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn check_transaction_call(&self, _request: CheckTransactionRequest) -> RpcResult<CheckTransactionResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
 
@@ -72,7 +72,10 @@ impl ToTokens for RpcTable {
                                 Box::pin(async move {
                                     let mut response: #kaspad_response_type = match request.payload {
                                         Some(Payload::#request_type(ref request)) => match request.try_into() {
-                                            Ok(request) => server_ctx.core_service.#fn_call(request).await.into(),
+                                            Ok(request) => {
+                                                let span = kaspa_rpc_core::trace::tracing::info_span!(stringify!(#handler), correlation_id = kaspa_rpc_core::trace::next_correlation_id());
+                                                kaspa_rpc_core::trace::Instrument::instrument(server_ctx.method_counters.measure(kaspa_rpc_core::api::ops::RpcApiOps::#handler, server_ctx.core_service.#fn_call(request)), span).await.into()
+                                            }
                                             Err(err) => #response_message_type::from(err).into(),
                                         },
                                         _ => {
@@ -97,14 +100,19 @@ impl ToTokens for RpcTable {
                                         Some(Payload::#request_type(ref request)) => {
                                             match kaspa_rpc_core::#fallback_request_type::try_from(request) {
                                                 Ok(request) => {
-                                                    let listener_id = connection.get_or_register_listener_id()?;
                                                     let command = request.command;
-                                                    let result = server_ctx
-                                                        .notifier
-                                                        .clone()
-                                                        .execute_subscribe_command(listener_id, request.into(), command)
-                                                        .await;
-                                                    #response_message_type::from(result).into()
+                                                    let scope: kaspa_notify::scope::Scope = request.into();
+                                                    if command == kaspa_notify::subscription::Command::Start && !server_ctx.profile.allows_subscription(&scope) {
+                                                        #response_message_type::from(kaspa_rpc_core::RpcError::UnavailableOnSafeListener).into()
+                                                    } else {
+                                                        let listener_id = connection.get_or_register_listener_id()?;
+                                                        let result = server_ctx
+                                                            .notifier
+                                                            .clone()
+                                                            .execute_subscribe_command(listener_id, scope, command)
+                                                            .await;
+                                                        #response_message_type::from(result).into()
+                                                    }
                                                 }
                                                 Err(err) => #response_message_type::from(err).into(),
                                             }
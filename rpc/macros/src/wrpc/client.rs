@@ -66,6 +66,16 @@ impl ToTokens for RpcTable {
                         }
                         let __self = self;
                         //let request = request;
+                        // Ops the server does not serve under the negotiated RPC API version fail locally
+                        if let ::core::option::Option::Some(__version) = __self.rpc_api_version() {
+                            let __required = #rpc_api_ops::#handler.rpc_api_version();
+                            if __required > __version {
+                                return Err(kaspa_rpc_core::error::RpcError::General(format!(
+                                    "{:?} requires RPC API version {:?} while version {:?} was negotiated with the server",
+                                    #rpc_api_ops::#handler, __required, __version
+                                )));
+                            }
+                        }
                         let __ret: RpcResult<#response_type> = {
                             let resp: ClientResult<#response_type> = __self.inner.rpc_client.call(#rpc_api_ops::#handler, request).await;
                             Ok(resp.map_err(|e| kaspa_rpc_core::error::RpcError::RpcSubsystem(e.to_string()))?)
@@ -53,7 +53,11 @@ impl ToTokens for RpcTable {
                     interface.method(#rpc_api_ops::#handler, method!(|server_ctx: #server_ctx_type, connection_ctx: #connection_ctx_type, request: #request_type| async move {
                         let verbose = server_ctx.verbose();
                         if verbose { workflow_log::log_info!("request: {:?}",request); }
-                        let response: #response_type = server_ctx.rpc_service(&connection_ctx).#fn_call(request).await
+                        if !server_ctx.profile().allows(#rpc_api_ops::#handler.is_unsafe()) {
+                            return Err(ServerError::Text(kaspa_rpc_core::RpcError::UnavailableOnSafeListener.to_string()));
+                        }
+                        let span = kaspa_rpc_core::trace::tracing::info_span!(stringify!(#handler), correlation_id = kaspa_rpc_core::trace::next_correlation_id());
+                        let response: #response_type = server_ctx.profile().scope(kaspa_rpc_core::trace::Instrument::instrument(server_ctx.method_counters().measure(#rpc_api_ops::#handler, server_ctx.rpc_service(&connection_ctx).#fn_call(request)), span)).await
                             .map_err(|e|ServerError::Text(e.to_string()))?;
                         if verbose { workflow_log::log_info!("response: {:?}",response); }
                         Ok(response)
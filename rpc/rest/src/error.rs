@@ -0,0 +1,31 @@
+use hyper::StatusCode;
+use kaspa_rpc_core::RpcError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum RestError {
+    #[error("not found")]
+    NotFound,
+
+    #[error("method not allowed")]
+    MethodNotAllowed,
+
+    #[error("invalid {0}: {1}")]
+    InvalidParameter(&'static str, String),
+
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+}
+
+impl RestError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            RestError::NotFound => StatusCode::NOT_FOUND,
+            RestError::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            RestError::InvalidParameter(..) => StatusCode::BAD_REQUEST,
+            RestError::Rpc(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+}
+
+pub type RestResult<T> = std::result::Result<T, RestError>;
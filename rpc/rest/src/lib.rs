@@ -0,0 +1,15 @@
+//!
+//! An optional HTTP/JSON REST gateway over the RPC core service, serving a few common queries (block by hash,
+//! balance by address, transaction by id and DAG info) along with an OpenAPI document describing them.
+//!
+//! See [`routes::ROUTES`] for the served endpoints. The node metrics are additionally exposed in the Prometheus text
+//! format at [`prometheus::METRICS_PATH`].
+//!
+
+pub mod error;
+pub mod openapi;
+pub mod prometheus;
+pub mod routes;
+pub mod service;
+
+const IDENT: &str = "rest-server";
@@ -0,0 +1,92 @@
+use crate::routes::{ParameterLocation, ROUTES};
+use serde_json::{json, Map, Value};
+
+/// Path of the OpenAPI document
+pub const OPENAPI_PATH: &str = "/openapi.json";
+
+/// Builds the OpenAPI 3.0 document of the gateway from the route table.
+///
+/// Response bodies are the JSON serializations of the named RPC core response types, so their schemas are left
+/// as free-form objects referring to these types.
+pub fn document(version: &str) -> Value {
+    let mut paths = Map::new();
+    let mut schemas = Map::new();
+    for route in ROUTES {
+        let parameters = route
+            .parameters
+            .iter()
+            .map(|parameter| {
+                json!({
+                    "name": parameter.name,
+                    "in": match parameter.location {
+                        ParameterLocation::Path => "path",
+                        ParameterLocation::Query => "query",
+                    },
+                    "required": parameter.location == ParameterLocation::Path,
+                    "description": parameter.description,
+                    "schema": { "type": parameter.kind },
+                })
+            })
+            .collect::<Vec<_>>();
+        paths.insert(
+            route.path.to_string(),
+            json!({
+                "get": {
+                    "operationId": route.operation_id,
+                    "summary": route.summary,
+                    "parameters": parameters,
+                    "responses": {
+                        "200": {
+                            "description": route.response,
+                            "content": { "application/json": { "schema": { "$ref": format!("#/components/schemas/{}", route.response) } } },
+                        },
+                        "default": {
+                            "description": "Error",
+                            "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Error" } } },
+                        },
+                    },
+                },
+            }),
+        );
+        schemas.insert(
+            route.response.to_string(),
+            json!({
+                "type": "object",
+                "description": format!("JSON serialization of the RPC core {} type", route.response),
+                "additionalProperties": true,
+            }),
+        );
+    }
+    schemas.insert(
+        "Error".to_string(),
+        json!({
+            "type": "object",
+            "properties": { "error": { "type": "string" } },
+            "required": ["error"],
+        }),
+    );
+
+    json!({
+        "openapi": "3.0.3",
+        "info": { "title": "Kaspa REST API", "version": version },
+        "paths": paths,
+        "components": { "schemas": schemas },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document() {
+        let document = document("1.0.0");
+        for route in ROUTES {
+            let operation = &document["paths"][route.path]["get"];
+            assert_eq!(operation["operationId"], route.operation_id);
+            assert_eq!(operation["parameters"].as_array().unwrap().len(), route.parameters.len());
+            assert!(document["components"]["schemas"].get(route.response).is_some());
+        }
+        assert_eq!(document["paths"]["/v1/blocks/{hash}"]["get"]["parameters"][0]["in"], "path");
+    }
+}
@@ -0,0 +1,228 @@
+use crate::error::RestResult;
+use kaspa_rpc_core::{
+    api::rpc::{DynRpcService, RpcApi},
+    metrics::LATENCY_BUCKET_BOUNDS_MS,
+    GetMetricsRequest, P2pMessageMetrics, RpcListenerMetrics, RpcMethodMetrics,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Write;
+
+pub const METRICS_PATH: &str = "/metrics";
+
+/// The content type of the Prometheus text exposition format
+pub const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+const METRIC_PREFIX: &str = "kaspad";
+
+/// Renders the process, consensus and lifetime metrics of the RPC core service in the Prometheus text exposition format.
+/// Every numeric or boolean field of the metrics is exposed as a gauge named `kaspad_<group>_<field in snake case>`,
+/// while the per-method RPC metrics are exposed as a latency histogram and an error counter labeled by method and the P2P
+/// traffic as message and byte counters labeled by message type and direction. The notification queue of every RPC
+/// listener is exposed as gauges and a dropped notification counter labeled by notifier and listener
+pub async fn render(rpc: &DynRpcService) -> RestResult<String> {
+    let response = rpc
+        .get_metrics_call(GetMetricsRequest {
+            process_metrics: true,
+            consensus_metrics: true,
+            lifetime_metrics: true,
+            method_metrics: true,
+            p2p_message_metrics: true,
+            listener_metrics: true,
+        })
+        .await?;
+    let mut output = String::new();
+    if let Some(metrics) = response.process_metrics {
+        write_gauges(&mut output, "process", &metrics);
+    }
+    if let Some(metrics) = response.consensus_metrics {
+        write_gauges(&mut output, "consensus", &metrics);
+    }
+    if let Some(metrics) = response.extended_consensus_metrics {
+        write_gauges(&mut output, "consensus", &metrics);
+    }
+    if let Some(metrics) = response.lifetime_metrics {
+        write_gauges(&mut output, "lifetime", &metrics);
+    }
+    write_method_metrics(&mut output, &response.method_metrics);
+    write_p2p_message_metrics(&mut output, &response.p2p_message_metrics);
+    write_listener_metrics(&mut output, &response.listener_metrics);
+    Ok(output)
+}
+
+fn write_gauges(output: &mut String, group: &str, metrics: &impl Serialize) {
+    let Ok(Value::Object(fields)) = serde_json::to_value(metrics) else {
+        return;
+    };
+    for (field, value) in fields {
+        let value = match value {
+            Value::Number(number) => number.to_string(),
+            Value::Bool(flag) => (flag as u8).to_string(),
+            _ => continue,
+        };
+        let name = format!("{METRIC_PREFIX}_{group}_{}", snake_case(&field));
+        writeln!(output, "# TYPE {name} gauge\n{name} {value}").unwrap();
+    }
+}
+
+fn write_method_metrics(output: &mut String, metrics: &[RpcMethodMetrics]) {
+    if metrics.is_empty() {
+        return;
+    }
+    let latency = format!("{METRIC_PREFIX}_rpc_method_latency_seconds");
+    writeln!(output, "# TYPE {latency} histogram").unwrap();
+    for RpcMethodMetrics { method, calls, total_latency_micros, latency_buckets, .. } in metrics {
+        let mut cumulative_count = 0;
+        for (count, bound_ms) in latency_buckets.iter().zip(LATENCY_BUCKET_BOUNDS_MS) {
+            cumulative_count += count;
+            writeln!(output, "{latency}_bucket{{method=\"{method}\",le=\"{}\"}} {cumulative_count}", bound_ms as f64 / 1_000.0)
+                .unwrap();
+        }
+        writeln!(output, "{latency}_bucket{{method=\"{method}\",le=\"+Inf\"}} {calls}").unwrap();
+        writeln!(output, "{latency}_sum{{method=\"{method}\"}} {}", *total_latency_micros as f64 / 1_000_000.0).unwrap();
+        writeln!(output, "{latency}_count{{method=\"{method}\"}} {calls}").unwrap();
+    }
+    let errors = format!("{METRIC_PREFIX}_rpc_method_errors_total");
+    writeln!(output, "# TYPE {errors} counter").unwrap();
+    for RpcMethodMetrics { method, errors: count, .. } in metrics {
+        writeln!(output, "{errors}{{method=\"{method}\"}} {count}").unwrap();
+    }
+}
+
+fn write_p2p_message_metrics(output: &mut String, metrics: &[P2pMessageMetrics]) {
+    if metrics.is_empty() {
+        return;
+    }
+    let messages = format!("{METRIC_PREFIX}_p2p_messages_total");
+    writeln!(output, "# TYPE {messages} counter").unwrap();
+    for P2pMessageMetrics { message_type, sent_messages, received_messages, .. } in metrics {
+        writeln!(output, "{messages}{{type=\"{message_type}\",direction=\"sent\"}} {sent_messages}").unwrap();
+        writeln!(output, "{messages}{{type=\"{message_type}\",direction=\"received\"}} {received_messages}").unwrap();
+    }
+    let bytes = format!("{METRIC_PREFIX}_p2p_bytes_total");
+    writeln!(output, "# TYPE {bytes} counter").unwrap();
+    for P2pMessageMetrics { message_type, sent_bytes, received_bytes, .. } in metrics {
+        writeln!(output, "{bytes}{{type=\"{message_type}\",direction=\"sent\"}} {sent_bytes}").unwrap();
+        writeln!(output, "{bytes}{{type=\"{message_type}\",direction=\"received\"}} {received_bytes}").unwrap();
+    }
+}
+
+fn write_listener_metrics(output: &mut String, metrics: &[RpcListenerMetrics]) {
+    if metrics.is_empty() {
+        return;
+    }
+    let queue_length = format!("{METRIC_PREFIX}_rpc_listener_queue_length");
+    writeln!(output, "# TYPE {queue_length} gauge").unwrap();
+    for RpcListenerMetrics { notifier, listener_id, queue_len, .. } in metrics {
+        writeln!(output, "{queue_length}{{notifier=\"{notifier}\",listener=\"{listener_id}\"}} {queue_len}").unwrap();
+    }
+    let queue_capacity = format!("{METRIC_PREFIX}_rpc_listener_queue_capacity");
+    writeln!(output, "# TYPE {queue_capacity} gauge").unwrap();
+    for RpcListenerMetrics { notifier, listener_id, queue_capacity: capacity, .. } in metrics {
+        writeln!(output, "{queue_capacity}{{notifier=\"{notifier}\",listener=\"{listener_id}\"}} {capacity}").unwrap();
+    }
+    let dropped = format!("{METRIC_PREFIX}_rpc_listener_dropped_notifications_total");
+    writeln!(output, "# TYPE {dropped} counter").unwrap();
+    for RpcListenerMetrics { notifier, listener_id, dropped_notifications, .. } in metrics {
+        writeln!(output, "{dropped}{{notifier=\"{notifier}\",listener=\"{listener_id}\"}} {dropped_notifications}").unwrap();
+    }
+    let lagging = format!("{METRIC_PREFIX}_rpc_listener_lagging");
+    writeln!(output, "# TYPE {lagging} gauge").unwrap();
+    for RpcListenerMetrics { notifier, listener_id, lagging: flag, .. } in metrics {
+        writeln!(output, "{lagging}{{notifier=\"{notifier}\",listener=\"{listener_id}\"}} {}", *flag as u8).unwrap();
+    }
+}
+
+fn snake_case(camel_case: &str) -> String {
+    let mut name = String::with_capacity(camel_case.len() + 4);
+    for c in camel_case.chars() {
+        if c.is_ascii_uppercase() {
+            name.push('_');
+            name.push(c.to_ascii_lowercase());
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_rpc_core::ExtendedConsensusMetrics;
+
+    #[test]
+    fn test_write_gauges() {
+        let metrics = ExtendedConsensusMetrics {
+            tip_hashes_count: 7,
+            storage_write_amplification: 2.5,
+            storage_write_stopped: true,
+            ..Default::default()
+        };
+        let mut output = String::new();
+        write_gauges(&mut output, "consensus", &metrics);
+        assert!(output.contains("# TYPE kaspad_consensus_tip_hashes_count gauge\nkaspad_consensus_tip_hashes_count 7\n"));
+        assert!(output.contains("kaspad_consensus_storage_write_amplification 2.5\n"));
+        assert!(output.contains("kaspad_consensus_storage_write_stopped 1\n"));
+        assert!(output.contains("kaspad_consensus_tx_inclusion_latency_p50 0\n"));
+    }
+
+    #[test]
+    fn test_write_method_metrics() {
+        let metrics = RpcMethodMetrics {
+            method: "GetInfo".to_owned(),
+            calls: 3,
+            errors: 1,
+            total_latency_micros: 1_500_000,
+            latency_buckets: vec![1, 0, 1, 0, 0, 0, 0, 0, 1],
+        };
+        let mut output = String::new();
+        write_method_metrics(&mut output, &[metrics]);
+        assert!(output.contains("# TYPE kaspad_rpc_method_latency_seconds histogram\n"));
+        assert!(output.contains("kaspad_rpc_method_latency_seconds_bucket{method=\"GetInfo\",le=\"0.001\"} 1\n"));
+        assert!(output.contains("kaspad_rpc_method_latency_seconds_bucket{method=\"GetInfo\",le=\"0.01\"} 2\n"));
+        assert!(output.contains("kaspad_rpc_method_latency_seconds_bucket{method=\"GetInfo\",le=\"5\"} 2\n"));
+        assert!(output.contains("kaspad_rpc_method_latency_seconds_bucket{method=\"GetInfo\",le=\"+Inf\"} 3\n"));
+        assert!(output.contains("kaspad_rpc_method_latency_seconds_sum{method=\"GetInfo\"} 1.5\n"));
+        assert!(output.contains("kaspad_rpc_method_errors_total{method=\"GetInfo\"} 1\n"));
+    }
+
+    #[test]
+    fn test_write_p2p_message_metrics() {
+        let metrics = P2pMessageMetrics {
+            message_type: "Block".to_owned(),
+            sent_messages: 2,
+            sent_bytes: 3_000,
+            received_messages: 5,
+            received_bytes: 7_500,
+        };
+        let mut output = String::new();
+        write_p2p_message_metrics(&mut output, &[metrics]);
+        assert!(output.contains("# TYPE kaspad_p2p_messages_total counter\n"));
+        assert!(output.contains("kaspad_p2p_messages_total{type=\"Block\",direction=\"sent\"} 2\n"));
+        assert!(output.contains("kaspad_p2p_messages_total{type=\"Block\",direction=\"received\"} 5\n"));
+        assert!(output.contains("kaspad_p2p_bytes_total{type=\"Block\",direction=\"sent\"} 3000\n"));
+        assert!(output.contains("kaspad_p2p_bytes_total{type=\"Block\",direction=\"received\"} 7500\n"));
+    }
+
+    #[test]
+    fn test_write_listener_metrics() {
+        let metrics = RpcListenerMetrics {
+            notifier: "wrpc-server".to_owned(),
+            listener_id: 42,
+            queue_len: 1_024,
+            queue_capacity: 1_024,
+            dropped_notifications: 3,
+            lagging: true,
+            overflowed: false,
+        };
+        let mut output = String::new();
+        write_listener_metrics(&mut output, &[metrics]);
+        assert!(output.contains("# TYPE kaspad_rpc_listener_queue_length gauge\n"));
+        assert!(output.contains("kaspad_rpc_listener_queue_length{notifier=\"wrpc-server\",listener=\"42\"} 1024\n"));
+        assert!(output.contains("kaspad_rpc_listener_queue_capacity{notifier=\"wrpc-server\",listener=\"42\"} 1024\n"));
+        assert!(output.contains("# TYPE kaspad_rpc_listener_dropped_notifications_total counter\n"));
+        assert!(output.contains("kaspad_rpc_listener_dropped_notifications_total{notifier=\"wrpc-server\",listener=\"42\"} 3\n"));
+        assert!(output.contains("kaspad_rpc_listener_lagging{notifier=\"wrpc-server\",listener=\"42\"} 1\n"));
+    }
+}
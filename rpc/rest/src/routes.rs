@@ -0,0 +1,229 @@
+use crate::error::{RestError, RestResult};
+use kaspa_addresses::Address;
+use kaspa_hashes::Hash;
+use kaspa_rpc_core::{
+    api::rpc::{DynRpcService, RpcApi},
+    GetBalanceByAddressRequest, GetBlockDagInfoRequest, GetBlockRequest, GetTransactionRequest,
+};
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use serde_json::Value;
+use std::{collections::HashMap, str::FromStr};
+
+/// Where the value of a parameter is read from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParameterLocation {
+    Path,
+    Query,
+}
+
+/// A parameter of a route, as described in the OpenAPI document
+#[derive(Clone, Copy, Debug)]
+pub struct Parameter {
+    pub name: &'static str,
+    pub location: ParameterLocation,
+    /// OpenAPI type of the parameter
+    pub kind: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endpoint {
+    BlockDagInfo,
+    Block,
+    BalanceByAddress,
+    Transaction,
+}
+
+/// A GET route of the gateway. Path parameters are written in braces in the path template
+#[derive(Clone, Copy, Debug)]
+pub struct Route {
+    pub endpoint: Endpoint,
+    pub path: &'static str,
+    pub operation_id: &'static str,
+    pub summary: &'static str,
+    pub parameters: &'static [Parameter],
+    /// Name of the RPC core response type serialized in the response body
+    pub response: &'static str,
+}
+
+pub const ROUTES: &[Route] = &[
+    Route {
+        endpoint: Endpoint::BlockDagInfo,
+        path: "/v1/info/dag",
+        operation_id: "getBlockDagInfo",
+        summary: "Get the current state of the DAG",
+        parameters: &[],
+        response: "GetBlockDagInfoResponse",
+    },
+    Route {
+        endpoint: Endpoint::Block,
+        path: "/v1/blocks/{hash}",
+        operation_id: "getBlock",
+        summary: "Get a block by hash",
+        parameters: &[
+            Parameter { name: "hash", location: ParameterLocation::Path, kind: "string", description: "Hash of the block" },
+            Parameter {
+                name: "includeTransactions",
+                location: ParameterLocation::Query,
+                kind: "boolean",
+                description: "Whether to include the transactions of the block (default: false)",
+            },
+        ],
+        response: "GetBlockResponse",
+    },
+    Route {
+        endpoint: Endpoint::BalanceByAddress,
+        path: "/v1/addresses/{address}/balance",
+        operation_id: "getBalanceByAddress",
+        summary: "Get the balance of an address, requires the UTXO index",
+        parameters: &[Parameter {
+            name: "address",
+            location: ParameterLocation::Path,
+            kind: "string",
+            description: "Address including its network prefix",
+        }],
+        response: "GetBalanceByAddressResponse",
+    },
+    Route {
+        endpoint: Endpoint::Transaction,
+        path: "/v1/transactions/{transactionId}",
+        operation_id: "getTransaction",
+        summary: "Get an accepted transaction by id, requires the transaction index",
+        parameters: &[
+            Parameter {
+                name: "transactionId",
+                location: ParameterLocation::Path,
+                kind: "string",
+                description: "Id of the transaction",
+            },
+            Parameter {
+                name: "includeProof",
+                location: ParameterLocation::Query,
+                kind: "boolean",
+                description: "Whether to include a proof of the acceptance of the transaction (default: false)",
+            },
+        ],
+        response: "GetTransactionResponse",
+    },
+];
+
+/// Values of the path and query parameters of a request
+pub type Params = HashMap<String, String>;
+
+impl Route {
+    /// Matches `path` against the path template of the route, returning the values of the path parameters.
+    /// Segments are percent-decoded once split, so that an encoded `/` does not separate segments
+    fn matches(&self, path: &str) -> Option<Params> {
+        let mut template = self.path.trim_matches('/').split('/');
+        let mut segments = path.trim_matches('/').split('/');
+        let mut params = Params::new();
+        loop {
+            match (template.next(), segments.next()) {
+                (None, None) => return Some(params),
+                (Some(expected), Some(segment)) => {
+                    let segment = percent_decode_str(segment).decode_utf8().ok()?;
+                    if let Some(name) = expected.strip_prefix('{').and_then(|name| name.strip_suffix('}')) {
+                        if segment.is_empty() {
+                            return None;
+                        }
+                        params.insert(name.to_string(), segment.to_string());
+                    } else if expected != segment.as_ref() {
+                        return None;
+                    }
+                }
+                _ => return None,
+            }
+        }
+    }
+}
+
+/// Finds the route serving `path` and collects the parameters of the request, the query string included. Query
+/// parameters are decoded as form data, i.e., percent-encoded with `+` standing for a space
+pub fn resolve(path: &str, query: Option<&str>) -> Option<(&'static Route, Params)> {
+    let (route, mut params) = ROUTES.iter().find_map(|route| route.matches(path).map(|params| (route, params)))?;
+    for pair in query.unwrap_or_default().split('&').filter(|pair| !pair.is_empty()) {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, "true"));
+        params.entry(decode_query_component(name)).or_insert_with(|| decode_query_component(value));
+    }
+    Some((route, params))
+}
+
+fn decode_query_component(component: &str) -> String {
+    percent_decode_str(&component.replace('+', " ")).decode_utf8_lossy().into_owned()
+}
+
+/// Serves the request to `route` by calling the RPC core service
+pub async fn handle(rpc: &DynRpcService, route: &Route, params: &Params) -> RestResult<Value> {
+    match route.endpoint {
+        Endpoint::BlockDagInfo => to_json(rpc.get_block_dag_info_call(GetBlockDagInfoRequest {}).await?),
+        Endpoint::Block => {
+            let request = GetBlockRequest::new(hash_param(params, "hash")?, bool_param(params, "includeTransactions")?);
+            to_json(rpc.get_block_call(request).await?)
+        }
+        Endpoint::BalanceByAddress => {
+            let address = param(params, "address")?;
+            let address = Address::try_from(address).map_err(|err| RestError::InvalidParameter("address", err.to_string()))?;
+            to_json(rpc.get_balance_by_address_call(GetBalanceByAddressRequest::new(address)).await?)
+        }
+        Endpoint::Transaction => {
+            let request = GetTransactionRequest::new(hash_param(params, "transactionId")?, bool_param(params, "includeProof")?);
+            to_json(rpc.get_transaction_call(request).await?)
+        }
+    }
+}
+
+fn param<'a>(params: &'a Params, name: &'static str) -> RestResult<&'a str> {
+    params.get(name).map(String::as_str).ok_or(RestError::InvalidParameter(name, "missing value".to_string()))
+}
+
+fn hash_param(params: &Params, name: &'static str) -> RestResult<Hash> {
+    Hash::from_str(param(params, name)?).map_err(|err| RestError::InvalidParameter(name, err.to_string()))
+}
+
+fn bool_param(params: &Params, name: &'static str) -> RestResult<bool> {
+    match params.get(name).map(String::as_str) {
+        None | Some("false") | Some("0") => Ok(false),
+        Some("true") | Some("1") => Ok(true),
+        Some(value) => Err(RestError::InvalidParameter(name, format!("expected a boolean, got {value}"))),
+    }
+}
+
+fn to_json(response: impl Serialize) -> RestResult<Value> {
+    Ok(serde_json::to_value(response).expect("RPC responses always serialize to JSON"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let (route, params) = resolve("/v1/blocks/abcd", Some("includeTransactions=true")).unwrap();
+        assert_eq!(route.endpoint, Endpoint::Block);
+        assert_eq!(params.get("hash").unwrap(), "abcd");
+        assert!(bool_param(&params, "includeTransactions").unwrap());
+
+        let (route, params) = resolve("/v1/addresses/kaspa:qz/balance/", None).unwrap();
+        assert_eq!(route.endpoint, Endpoint::BalanceByAddress);
+        assert_eq!(params.get("address").unwrap(), "kaspa:qz");
+
+        assert_eq!(resolve("/v1/info/dag", None).unwrap().0.endpoint, Endpoint::BlockDagInfo);
+        assert!(resolve("/v1/blocks", None).is_none());
+        assert!(resolve("/v1/blocks//", None).is_none());
+        assert!(resolve("/v1/addresses/kaspa:qz/utxos", None).is_none());
+
+        let (_, params) = resolve("/v1/transactions/ff", Some("includeProof=maybe")).unwrap();
+        assert!(matches!(bool_param(&params, "includeProof"), Err(RestError::InvalidParameter("includeProof", _))));
+
+        // Path segments and query parameters are percent-decoded
+        let (route, params) = resolve("/v1/addresses/kaspa%3Aqz/balance", None).unwrap();
+        assert_eq!(route.endpoint, Endpoint::BalanceByAddress);
+        assert_eq!(params.get("address").unwrap(), "kaspa:qz");
+        let (_, params) = resolve("/v1/blocks/ab%2Fcd", Some("include%54ransactions=%74rue&flag")).unwrap();
+        assert_eq!(params.get("hash").unwrap(), "ab/cd");
+        assert!(bool_param(&params, "includeTransactions").unwrap());
+        assert_eq!(params.get("flag").unwrap(), "true");
+        assert!(resolve("/v1/blocks/%FF", None).is_none());
+    }
+}
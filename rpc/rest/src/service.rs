@@ -0,0 +1,107 @@
+use crate::{
+    error::RestError,
+    openapi::{document, OPENAPI_PATH},
+    prometheus::{self, METRICS_CONTENT_TYPE, METRICS_PATH},
+    routes::{handle, resolve},
+    IDENT,
+};
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use kaspa_core::{
+    debug,
+    task::service::{AsyncService, AsyncServiceError, AsyncServiceFuture},
+    trace,
+};
+use kaspa_rpc_core::api::rpc::DynRpcService;
+use kaspa_utils::triggers::SingleTrigger;
+use serde_json::{json, Value};
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+const REST_SERVICE: &str = IDENT;
+
+/// Serves the REST gateway routes and the OpenAPI document over HTTP
+pub struct RestService {
+    address: SocketAddr,
+    rpc_service: DynRpcService,
+    shutdown: SingleTrigger,
+}
+
+impl RestService {
+    pub fn new(address: SocketAddr, rpc_service: DynRpcService) -> Self {
+        Self { address, rpc_service, shutdown: SingleTrigger::default() }
+    }
+}
+
+async fn serve(rpc_service: DynRpcService, request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let path = request.uri().path();
+    if request.method() == Method::GET && path == METRICS_PATH {
+        return Ok(match prometheus::render(&rpc_service).await {
+            Ok(body) => response(StatusCode::OK, METRICS_CONTENT_TYPE, body),
+            Err(err) => json_response(err.status(), &json!({ "error": err.to_string() })),
+        });
+    }
+    let result = match (request.method(), resolve(path, request.uri().query())) {
+        (&Method::GET, _) if path == OPENAPI_PATH => Ok(document(env!("CARGO_PKG_VERSION"))),
+        (&Method::GET, Some((route, params))) => handle(&rpc_service, route, &params).await,
+        (_, Some(_)) => Err(RestError::MethodNotAllowed),
+        (_, None) => Err(RestError::NotFound),
+    };
+    Ok(match result {
+        Ok(body) => json_response(StatusCode::OK, &body),
+        Err(err) => {
+            debug!("REST request {} {} failed: {}", request.method(), request.uri(), err);
+            json_response(err.status(), &json!({ "error": err.to_string() }))
+        }
+    })
+}
+
+fn json_response(status: StatusCode, body: &Value) -> Response<Body> {
+    response(status, "application/json", body.to_string())
+}
+
+fn response(status: StatusCode, content_type: &str, body: String) -> Response<Body> {
+    Response::builder().status(status).header(CONTENT_TYPE, content_type).body(Body::from(body)).expect("the response parts are valid")
+}
+
+impl AsyncService for RestService {
+    fn ident(self: Arc<Self>) -> &'static str {
+        REST_SERVICE
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} starting", REST_SERVICE);
+
+        // Prepare a shutdown signal receiver
+        let shutdown_signal = self.shutdown.listener.clone();
+
+        Box::pin(async move {
+            let rpc_service = self.rpc_service.clone();
+            let make_service = make_service_fn(move |_| {
+                let rpc_service = rpc_service.clone();
+                async move { Ok::<_, Infallible>(service_fn(move |request| serve(rpc_service.clone(), request))) }
+            });
+            let server = Server::try_bind(&self.address)
+                .map_err(|err| AsyncServiceError::Service(format!("failed binding {}: {}", self.address, err)))?
+                .serve(make_service);
+            debug!("REST gateway listening on {}", self.address);
+
+            // Keep the server running until a service shutdown signal is received
+            server.with_graceful_shutdown(shutdown_signal).await.map_err(|err| AsyncServiceError::Service(err.to_string()))
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", REST_SERVICE);
+        self.shutdown.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            trace!("{} stopped", REST_SERVICE);
+            Ok(())
+        })
+    }
+}
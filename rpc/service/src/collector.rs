@@ -1,6 +1,12 @@
-use crate::converter::{consensus::ConsensusConverter, index::IndexConverter};
+use crate::converter::{
+    consensus::ConsensusConverter, index::IndexConverter, mempool::MempoolConverter, sync_state::SyncStateConverter,
+};
 use kaspa_notify::collector::CollectorFrom;
 
 pub(crate) type CollectorFromConsensus = CollectorFrom<ConsensusConverter>;
 
 pub(crate) type CollectorFromIndex = CollectorFrom<IndexConverter>;
+
+pub(crate) type CollectorFromMempool = CollectorFrom<MempoolConverter>;
+
+pub(crate) type CollectorFromSyncState = CollectorFrom<SyncStateConverter>;
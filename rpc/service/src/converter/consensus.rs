@@ -1,25 +1,30 @@
 use async_trait::async_trait;
 use kaspa_addresses::Address;
 use kaspa_consensus_core::{
+    acceptance_data::AcceptanceData,
     block::Block,
     config::Config,
     hashing::tx::hash,
     header::Header,
-    tx::{MutableTransaction, Transaction, TransactionId, TransactionInput, TransactionOutput},
+    tx::{MutableTransaction, ScriptPublicKey, Transaction, TransactionId, TransactionInput, TransactionOutput},
     ChainPath,
 };
 use kaspa_consensus_notify::notification::{self as consensus_notify, Notification as ConsensusNotification};
 use kaspa_consensusmanager::{ConsensusManager, ConsensusProxy};
-use kaspa_math::Uint256;
+use kaspa_math::difficulty::difficulty_from_bits;
 use kaspa_mining::model::{owner_txs::OwnerTransactions, TransactionIdSet};
 use kaspa_notify::converter::Converter;
 use kaspa_rpc_core::{
-    BlockAddedNotification, Notification, RpcAcceptedTransactionIds, RpcBlock, RpcBlockVerboseData, RpcHash, RpcMempoolEntry,
-    RpcMempoolEntryByAddress, RpcResult, RpcTransaction, RpcTransactionInput, RpcTransactionOutput, RpcTransactionOutputVerboseData,
-    RpcTransactionVerboseData,
+    AcceptedTransactionsNotification, BlockAddedNotification, Notification, RpcAcceptedTransactionIds, RpcAcceptedTransactions,
+    RpcBlock, RpcBlockVerboseData, RpcHash, RpcMempoolEntry, RpcMempoolEntryByAddress, RpcResult, RpcTransaction, RpcTransactionInput,
+    RpcTransactionOutput, RpcTransactionOutputVerboseData, RpcTransactionVerboseData,
 };
 use kaspa_txscript::{extract_script_pub_key_address, script_class::ScriptClass};
-use std::{collections::HashMap, fmt::Debug, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    sync::Arc,
+};
 
 /// Conversion of consensus_core to rpc_core structures
 pub struct ConsensusConverter {
@@ -39,8 +44,7 @@ impl ConsensusConverter {
         // converted back to a number. Note this is not the same as the proof of
         // work limit directly because the block difficulty is encoded in a block
         // with the compact form which loses precision.
-        let target = Uint256::from_compact_target_bits(bits);
-        self.config.max_difficulty_target_f64 / target.as_f64()
+        difficulty_from_bits(bits, self.config.max_difficulty_target)
     }
 
     /// Converts a consensus [`Block`] into an [`RpcBlock`], optionally including transaction verbose data.
@@ -84,6 +88,41 @@ impl ConsensusConverter {
         Ok(RpcBlock { header: (*block.header).clone(), transactions, verbose_data })
     }
 
+    /// Converts a block served out of the era archive into an [`RpcBlock`]. The DAG data of such a block was pruned,
+    /// hence its verbose data is limited to the fields derivable from the block itself
+    pub fn get_archived_block(
+        &self,
+        consensus: &ConsensusProxy,
+        block: &Block,
+        include_transactions: bool,
+        include_transaction_verbose_data: bool,
+    ) -> RpcBlock {
+        let verbose_data = Some(RpcBlockVerboseData {
+            hash: block.hash(),
+            difficulty: self.get_difficulty_ratio(block.header.bits),
+            selected_parent_hash: Default::default(),
+            transaction_ids: block.transactions.iter().map(|x| x.id()).collect(),
+            is_header_only: false,
+            blue_score: block.header.blue_score,
+            children_hashes: vec![],
+            merge_set_blues_hashes: vec![],
+            merge_set_reds_hashes: vec![],
+            is_chain_block: false,
+        });
+
+        let transactions = if include_transactions {
+            block
+                .transactions
+                .iter()
+                .map(|x| self.get_transaction(consensus, x, Some(&block.header), include_transaction_verbose_data))
+                .collect::<Vec<_>>()
+        } else {
+            vec![]
+        };
+
+        RpcBlock { header: (*block.header).clone(), transactions, verbose_data }
+    }
+
     pub fn get_mempool_entry(&self, consensus: &ConsensusProxy, transaction: &MutableTransaction) -> RpcMempoolEntry {
         let is_orphan = !transaction.is_fully_populated();
         let rpc_transaction = self.get_transaction(consensus, &transaction.tx, None, true);
@@ -176,6 +215,40 @@ impl ConsensusConverter {
             })
             .collect())
     }
+
+    /// Collects the transactions accepted by each chain block along with their verbose data, the transactions of
+    /// a mergeset block which cannot be fetched being skipped.
+    ///
+    /// If `script_public_keys` is provided, only the transactions paying to any of them are converted.
+    pub async fn get_accepted_transactions(
+        &self,
+        consensus: &ConsensusProxy,
+        chain_block_hashes: &[RpcHash],
+        acceptance_data: &[Arc<AcceptanceData>],
+        script_public_keys: Option<&HashSet<ScriptPublicKey>>,
+    ) -> Vec<RpcAcceptedTransactions> {
+        let mut accepted_transactions = Vec::with_capacity(chain_block_hashes.len());
+        for (hash, block_data) in chain_block_hashes.iter().zip(acceptance_data.iter()) {
+            let mut transactions = vec![];
+            for mergeset_block_data in block_data.iter() {
+                let Ok(block) = consensus.async_get_block(mergeset_block_data.block_hash).await else { continue };
+                let accepted_ids = mergeset_block_data.accepted_transactions.iter().map(|x| x.transaction_id).collect::<HashSet<_>>();
+                transactions.extend(
+                    block
+                        .transactions
+                        .iter()
+                        .filter(|tx| accepted_ids.contains(&tx.id()))
+                        .filter(|tx| {
+                            script_public_keys
+                                .map_or(true, |spks| tx.outputs.iter().any(|output| spks.contains(&output.script_public_key)))
+                        })
+                        .map(|tx| self.get_transaction(consensus, tx, Some(&block.header), true)),
+                );
+            }
+            accepted_transactions.push(RpcAcceptedTransactions { accepting_block_hash: *hash, accepted_transactions: transactions });
+        }
+        accepted_transactions
+    }
 }
 
 #[async_trait]
@@ -191,6 +264,19 @@ impl Converter for ConsensusConverter {
                 let block = Arc::new(self.get_block(&session, &msg.block, true, true).await.unwrap_or_else(|_| (&msg.block).into()));
                 Notification::BlockAdded(BlockAddedNotification { block })
             }
+            consensus_notify::Notification::AcceptedTransactions(msg) => {
+                let session = self.consensus_manager.consensus().unguarded_session();
+                let accepted_transactions = Arc::new(
+                    self.get_accepted_transactions(
+                        &session,
+                        &msg.added_chain_block_hashes,
+                        &msg.added_chain_blocks_acceptance_data,
+                        msg.script_public_keys.as_deref(),
+                    )
+                    .await,
+                );
+                Notification::AcceptedTransactions(AcceptedTransactionsNotification { accepted_transactions })
+            }
             _ => (&incoming).into(),
         }
     }
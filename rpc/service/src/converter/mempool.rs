@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+use kaspa_mining::model::tx_removal::{MempoolRemovalReason, MempoolTransactionRemoval};
+use kaspa_notify::converter::Converter;
+use kaspa_rpc_core::{MempoolTransactionRemovedNotification, Notification, RpcMempoolRemovalReason};
+
+/// Conversion of mempool events to rpc_core notifications
+#[derive(Debug, Default)]
+pub struct MempoolConverter {}
+
+impl MempoolConverter {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn get_removal_reason(&self, reason: MempoolRemovalReason) -> RpcMempoolRemovalReason {
+        match reason {
+            MempoolRemovalReason::Evicted => RpcMempoolRemovalReason::Evicted,
+            MempoolRemovalReason::Replaced => RpcMempoolRemovalReason::Replaced,
+            MempoolRemovalReason::Expired => RpcMempoolRemovalReason::Expired,
+            MempoolRemovalReason::ConflictWithBlock => RpcMempoolRemovalReason::ConflictWithBlock,
+        }
+    }
+}
+
+#[async_trait]
+impl Converter for MempoolConverter {
+    type Incoming = MempoolTransactionRemoval;
+    type Outgoing = Notification;
+
+    async fn convert(&self, incoming: MempoolTransactionRemoval) -> Notification {
+        Notification::MempoolTransactionRemoved(MempoolTransactionRemovedNotification {
+            transaction_id: incoming.transaction_id,
+            reason: self.get_removal_reason(incoming.reason),
+        })
+    }
+}
@@ -1,3 +1,5 @@
 pub mod consensus;
 pub mod index;
+pub mod mempool;
 pub mod protocol;
+pub mod sync_state;
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use kaspa_p2p_flows::flow_context::FlowContext;
 use kaspa_p2p_lib::{Peer, PeerKey};
-use kaspa_rpc_core::RpcPeerInfo;
+use kaspa_rpc_core::{RpcPeerInfo, RpcPeerTraffic};
 
 pub struct ProtocolConverter {
     flow_context: Arc<FlowContext>,
@@ -25,6 +25,8 @@ impl ProtocolConverter {
             user_agent: properties.user_agent.clone(),
             advertised_protocol_version: properties.advertised_protocol_version,
             time_connected: peer.time_connected(),
+            claimed_sink: peer.chain_claim().map(|claim| claim.sink),
+            claimed_daa_score: peer.chain_claim().map_or(0, |claim| claim.daa_score),
         }
     }
 
@@ -32,4 +34,14 @@ impl ProtocolConverter {
         let ibd_peer_key = self.flow_context.ibd_peer_key();
         peers.iter().map(|x| self.get_peer_info(x, &ibd_peer_key)).collect()
     }
+
+    pub fn get_peers_traffic(&self, peers: &[Peer]) -> Vec<RpcPeerTraffic> {
+        peers
+            .iter()
+            .map(|peer| {
+                let traffic = peer.traffic();
+                RpcPeerTraffic { id: peer.identity(), bytes_sent: traffic.sent_bytes, bytes_received: traffic.received_bytes }
+            })
+            .collect()
+    }
 }
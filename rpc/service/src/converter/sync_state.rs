@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+use kaspa_notify::converter::Converter;
+use kaspa_p2p_flows::flowcontext::sync_state::SyncState;
+use kaspa_rpc_core::{Notification, RpcSyncState, SyncStateChangedNotification};
+
+/// Conversion of the sync state changes reported by the P2P flows to rpc_core notifications
+#[derive(Debug, Default)]
+pub struct SyncStateConverter {}
+
+impl SyncStateConverter {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn get_sync_state(&self, state: SyncState) -> RpcSyncState {
+        match state {
+            SyncState::IbdStarted => RpcSyncState::IbdStarted,
+            SyncState::HeadersProof => RpcSyncState::HeadersProof,
+            SyncState::Headers => RpcSyncState::Headers,
+            SyncState::UtxoSetImport => RpcSyncState::UtxoSetImport,
+            SyncState::Bodies => RpcSyncState::Bodies,
+            SyncState::IbdCompleted => RpcSyncState::IbdCompleted,
+            SyncState::IbdFailed => RpcSyncState::IbdFailed,
+        }
+    }
+}
+
+#[async_trait]
+impl Converter for SyncStateConverter {
+    type Incoming = SyncState;
+    type Outgoing = Notification;
+
+    async fn convert(&self, incoming: SyncState) -> Notification {
+        Notification::SyncStateChanged(SyncStateChangedNotification { state: self.get_sync_state(incoming) })
+    }
+}
@@ -1,3 +1,6 @@
 pub mod collector;
 pub mod converter;
+pub mod load;
+pub mod producer;
 pub mod service;
+pub mod sink;
@@ -0,0 +1,108 @@
+use kaspa_rpc_core::{RpcError, RpcResult};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// The delay in seconds clients are advised to wait before retrying a rejected request
+pub const LOAD_SHEDDING_RETRY_AFTER: u64 = 5;
+
+/// Rejects RPC requests for bulk data (blocks with transactions, UTXO sets) while the node is under pressure,
+/// either because the block processing pipeline is backlogged or because too many such requests are already
+/// being served. Submissions, subscriptions and lightweight reads are never shed.
+pub struct LoadShedder {
+    /// Pipeline backlog above which heavy requests are rejected (0 disables the check)
+    pipeline_threshold: u64,
+    /// Maximal number of heavy requests served concurrently (0 for no limit)
+    max_heavy_requests: u64,
+    heavy_requests: Arc<AtomicU64>,
+}
+
+impl LoadShedder {
+    pub fn new(pipeline_threshold: u64, max_heavy_requests: u64) -> Self {
+        Self { pipeline_threshold, max_heavy_requests, heavy_requests: Default::default() }
+    }
+
+    /// Whether the pipeline backlog has to be provided to [`Self::try_admit`]
+    pub fn monitors_pipeline(&self) -> bool {
+        self.pipeline_threshold > 0
+    }
+
+    /// Admits a heavy request given the current `pipeline_backlog`, the returned guard counting the request
+    /// as being served until dropped
+    pub fn try_admit(&self, pipeline_backlog: u64) -> RpcResult<HeavyRequestGuard> {
+        if self.pipeline_threshold > 0 && pipeline_backlog > self.pipeline_threshold {
+            return Err(RpcError::Overloaded(LOAD_SHEDDING_RETRY_AFTER));
+        }
+        let admitted = self
+            .heavy_requests
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                (self.max_heavy_requests == 0 || count < self.max_heavy_requests).then_some(count + 1)
+            })
+            .is_ok();
+        if !admitted {
+            return Err(RpcError::Overloaded(LOAD_SHEDDING_RETRY_AFTER));
+        }
+        Ok(HeavyRequestGuard { heavy_requests: self.heavy_requests.clone() })
+    }
+}
+
+pub struct HeavyRequestGuard {
+    heavy_requests: Arc<AtomicU64>,
+}
+
+impl Drop for HeavyRequestGuard {
+    fn drop(&mut self) {
+        self.heavy_requests.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_shed<T>(result: &RpcResult<T>) -> bool {
+        matches!(result, Err(RpcError::Overloaded(LOAD_SHEDDING_RETRY_AFTER)))
+    }
+
+    #[test]
+    fn test_pipeline_threshold() {
+        let shedder = LoadShedder::new(10, 0);
+        assert!(shedder.monitors_pipeline());
+        assert!(shedder.try_admit(0).is_ok());
+        assert!(shedder.try_admit(10).is_ok());
+        assert!(is_shed(&shedder.try_admit(11)));
+
+        // A zero threshold disables the check
+        let shedder = LoadShedder::new(0, 0);
+        assert!(!shedder.monitors_pipeline());
+        assert!(shedder.try_admit(u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_max_heavy_requests() {
+        let shedder = LoadShedder::new(0, 2);
+        let first = shedder.try_admit(0).unwrap();
+        let second = shedder.try_admit(0).unwrap();
+        assert!(is_shed(&shedder.try_admit(0)));
+
+        // Dropping a guard frees its slot
+        drop(first);
+        let third = shedder.try_admit(0).unwrap();
+        assert!(is_shed(&shedder.try_admit(0)));
+        drop((second, third));
+        assert_eq!(shedder.heavy_requests.load(Ordering::SeqCst), 0);
+
+        // A request shed by the pipeline check takes no slot
+        let shedder = LoadShedder::new(10, 1);
+        assert!(is_shed(&shedder.try_admit(11)));
+        assert_eq!(shedder.heavy_requests.load(Ordering::SeqCst), 0);
+        let _guard = shedder.try_admit(0).unwrap();
+        assert!(is_shed(&shedder.try_admit(0)));
+
+        // A zero maximum means no limit
+        let shedder = LoadShedder::new(0, 0);
+        let _guards = (0..100).map(|_| shedder.try_admit(0).unwrap()).collect::<Vec<_>>();
+        assert_eq!(shedder.heavy_requests.load(Ordering::SeqCst), 100);
+    }
+}
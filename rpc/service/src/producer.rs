@@ -0,0 +1,234 @@
+//! A built-in block producer for devnet and simnet, mining at a configured rate so that test environments need no
+//! external miner
+
+use kaspa_addresses::{Address, AddressError};
+use kaspa_consensus_core::{block::MutableBlock, coinbase::MinerData, config::Config, network::NetworkType};
+use kaspa_consensusmanager::ConsensusManager;
+use kaspa_core::{
+    debug, info,
+    task::{
+        service::{AsyncService, AsyncServiceFuture},
+        tick::{TickReason, TickService},
+    },
+    trace, warn,
+};
+use kaspa_mining::manager::MiningManagerProxy;
+use kaspa_p2p_flows::flow_context::FlowContext;
+use kaspa_rpc_core::{RpcError, RpcResult};
+use kaspa_txscript::pay_to_address_script;
+use kaspa_utils::triggers::SingleTrigger;
+use rand::{thread_rng, Rng};
+use rand_distr::{Distribution, Exp};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+const BLOCK_PRODUCER: &str = "block-producer";
+
+/// The maximal rate blocks can be produced at
+pub const MAX_BLOCKS_PER_SECOND: f64 = 100.0;
+
+/// The number of nonces tried between two checks of whether the production run was stopped
+const NONCES_PER_CHECK: u64 = 4096;
+
+#[derive(Clone, Debug)]
+pub struct BlockProductionSettings {
+    /// The address the coinbase of the produced blocks pays into
+    pub pay_address: Address,
+    /// The average number of blocks produced per second
+    pub blocks_per_second: f64,
+}
+
+/// Mines blocks on top of the local virtual and submits them as if they were received over RPC, hence they are
+/// relayed to the peers. Blocks are produced whether or not the node is synced, like with `--enable-unsynced-mining`
+pub struct BlockProducer {
+    consensus_manager: Arc<ConsensusManager>,
+    mining_manager: MiningManagerProxy,
+    flow_context: Arc<FlowContext>,
+    config: Arc<Config>,
+    tick_service: Arc<TickService>,
+
+    /// Production started along with the service, if any
+    initial_settings: Option<BlockProductionSettings>,
+
+    /// The id of the active production run, if any. A run exits as soon as it is no longer the active one
+    active_run: Mutex<Option<u64>>,
+    next_run: AtomicU64,
+    blocks_produced: AtomicU64,
+    shutdown: SingleTrigger,
+}
+
+impl BlockProducer {
+    pub fn new(
+        consensus_manager: Arc<ConsensusManager>,
+        mining_manager: MiningManagerProxy,
+        flow_context: Arc<FlowContext>,
+        config: Arc<Config>,
+        tick_service: Arc<TickService>,
+        initial_settings: Option<BlockProductionSettings>,
+    ) -> Self {
+        Self {
+            consensus_manager,
+            mining_manager,
+            flow_context,
+            config,
+            tick_service,
+            initial_settings,
+            active_run: Mutex::new(None),
+            next_run: AtomicU64::new(0),
+            blocks_produced: AtomicU64::new(0),
+            shutdown: SingleTrigger::new(),
+        }
+    }
+
+    /// Returns whether blocks can be produced on networks of type `network_type`
+    pub fn is_supported(network_type: NetworkType) -> bool {
+        matches!(network_type, NetworkType::Devnet | NetworkType::Simnet)
+    }
+
+    /// Returns whether blocks can be produced at an average rate of `blocks_per_second`
+    pub fn is_valid_rate(blocks_per_second: f64) -> bool {
+        blocks_per_second > 0.0 && blocks_per_second <= MAX_BLOCKS_PER_SECOND
+    }
+
+    /// Starts producing blocks according to `settings`, replacing the active production run if any
+    pub fn start_production(self: &Arc<Self>, settings: BlockProductionSettings) -> RpcResult<()> {
+        if !Self::is_valid_rate(settings.blocks_per_second) {
+            return Err(RpcError::InvalidBlockProductionRate(settings.blocks_per_second, MAX_BLOCKS_PER_SECOND));
+        }
+        if settings.pay_address.prefix != self.config.prefix() {
+            return Err(AddressError::InvalidPrefix(settings.pay_address.prefix.to_string()))?;
+        }
+        let run = self.next_run.fetch_add(1, Ordering::SeqCst);
+        *self.active_run.lock().unwrap() = Some(run);
+        info!("Producing {} blocks per second paying to {}", settings.blocks_per_second, settings.pay_address);
+        tokio::spawn(self.clone().produce(run, settings));
+        Ok(())
+    }
+
+    /// Stops the active production run. Returns whether blocks were being produced
+    pub fn stop_production(&self) -> bool {
+        let stopped = self.active_run.lock().unwrap().take().is_some();
+        if stopped {
+            info!("Stopped producing blocks, {} blocks produced so far", self.blocks_produced());
+        }
+        stopped
+    }
+
+    /// Returns the number of produced blocks accepted by consensus since startup
+    pub fn blocks_produced(&self) -> u64 {
+        self.blocks_produced.load(Ordering::Relaxed)
+    }
+
+    fn is_active(&self, run: u64) -> bool {
+        *self.active_run.lock().unwrap() == Some(run)
+    }
+
+    async fn produce(self: Arc<Self>, run: u64, settings: BlockProductionSettings) {
+        // Intervals are drawn from an exponential distribution, as for independent miners, and are measured between
+        // scheduled times so that the time spent solving blocks does not lower the rate
+        let intervals = Exp::new(settings.blocks_per_second).unwrap();
+        let miner_data = MinerData::new(pay_to_address_script(&settings.pay_address), self.mining_manager.coinbase_extra_data(&[]));
+        let mut scheduled = Instant::now();
+        loop {
+            scheduled += Duration::from_secs_f64(intervals.sample(&mut thread_rng()));
+            if let TickReason::Shutdown = self.tick_service.tick(scheduled.saturating_duration_since(Instant::now())).await {
+                break;
+            }
+            if !self.is_active(run) {
+                break;
+            }
+            self.produce_block(run, miner_data.clone()).await;
+        }
+        trace!("{} run {} exited", BLOCK_PRODUCER, run);
+    }
+
+    async fn produce_block(self: &Arc<Self>, run: u64, miner_data: MinerData) {
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let template = match self.mining_manager.clone().get_block_template(&session, miner_data).await {
+            Ok(template) => template,
+            Err(err) => {
+                warn!("Block production failed building a block template: {}", err);
+                return;
+            }
+        };
+        let block = if self.config.skip_proof_of_work {
+            // A random nonce still makes the block unique if the template was built over the same virtual as the last one
+            let mut block = template.block;
+            block.header.nonce = thread_rng().gen();
+            block.header.finalize();
+            block
+        } else {
+            let producer = self.clone();
+            match tokio::task::spawn_blocking(move || producer.solve(run, template.block)).await.unwrap() {
+                Some(block) => block,
+                None => return,
+            }
+        };
+        let block = block.to_immutable();
+        let hash = block.hash();
+        match self.flow_context.submit_rpc_block(&session, block).await {
+            Ok(()) => {
+                self.blocks_produced.fetch_add(1, Ordering::Relaxed);
+                debug!("Produced block {}", hash);
+            }
+            Err(err) => warn!("The produced block {} was rejected: {}", hash, err),
+        }
+    }
+
+    /// Searches for a nonce meeting the target of `block`, giving up once `run` is no longer the active run
+    fn solve(&self, run: u64, mut block: MutableBlock) -> Option<MutableBlock> {
+        let state = kaspa_pow::State::new(&block.header);
+        let mut nonce = thread_rng().gen::<u64>();
+        loop {
+            for _ in 0..NONCES_PER_CHECK {
+                if state.check_pow(nonce).0 {
+                    block.header.nonce = nonce;
+                    block.header.finalize();
+                    return Some(block);
+                }
+                nonce = nonce.wrapping_add(1);
+            }
+            if !self.is_active(run) {
+                return None;
+            }
+        }
+    }
+}
+
+impl AsyncService for BlockProducer {
+    fn ident(self: Arc<Self>) -> &'static str {
+        BLOCK_PRODUCER
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} starting", BLOCK_PRODUCER);
+        let shutdown_signal = self.shutdown.listener.clone();
+        Box::pin(async move {
+            if let Some(settings) = self.initial_settings.clone() {
+                if let Err(err) = self.start_production(settings) {
+                    warn!("Block production could not be started: {}", err);
+                }
+            }
+            shutdown_signal.await;
+            Ok(())
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", BLOCK_PRODUCER);
+        self.stop_production();
+        self.shutdown.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            trace!("{} stopped", BLOCK_PRODUCER);
+            Ok(())
+        })
+    }
+}
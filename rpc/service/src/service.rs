@@ -1,28 +1,31 @@
 //! Core server implementation for ClientAPI
 
-use super::collector::{CollectorFromConsensus, CollectorFromIndex};
-use crate::converter::{consensus::ConsensusConverter, index::IndexConverter, protocol::ProtocolConverter};
+use super::collector::{CollectorFromConsensus, CollectorFromIndex, CollectorFromMempool, CollectorFromSyncState};
+use crate::converter::{
+    consensus::ConsensusConverter, index::IndexConverter, mempool::MempoolConverter, protocol::ProtocolConverter,
+    sync_state::SyncStateConverter,
+};
+use crate::load::{HeavyRequestGuard, LoadShedder};
+use crate::producer::{BlockProducer, BlockProductionSettings};
 use crate::service::NetworkType::{Mainnet, Testnet};
+use async_channel::Sender;
 use async_trait::async_trait;
-use kaspa_consensus::pipeline::ProcessingCounters;
+use kaspa_consensus::pipeline::{integrity::IntegrityCounters, lifetime::LifetimeMetricsMonitor, ProcessingCounters};
 use kaspa_consensus_core::{
-    block::Block,
-    coinbase::MinerData,
-    config::Config,
-    constants::MAX_SOMPI,
-    network::NetworkType,
-    tx::{Transaction, COINBASE_TRANSACTION_INDEX},
+    block::Block, coinbase::MinerData, config::Config, constants::MAX_SOMPI, merkle::create_hash_merkle_branch, network::NetworkType,
+    tx::Transaction, utxo::export::UtxoSetCursor,
 };
 use kaspa_consensus_notify::{
     notifier::ConsensusNotifier,
     {connection::ConsensusChannelConnection, notification::Notification as ConsensusNotification},
 };
-use kaspa_consensusmanager::ConsensusManager;
+use kaspa_consensusmanager::{ConsensusManager, ConsensusProxy};
 use kaspa_core::time::unix_now;
 use kaspa_core::{
     core::Core,
-    debug,
-    kaspad_env::version,
+    debug, info,
+    kaspad_env::{git_hash, version},
+    service::ServiceHealth,
     signals::Shutdown,
     task::service::{AsyncService, AsyncServiceError, AsyncServiceFuture},
     task::tick::TickService,
@@ -33,28 +36,35 @@ use kaspa_index_core::{
     connection::IndexChannelConnection, indexed_utxos::UtxoSetByScriptPublicKey, notification::Notification as IndexNotification,
     notifier::IndexNotifier,
 };
-use kaspa_mining::model::tx_query::TransactionQuery;
-use kaspa_mining::{manager::MiningManagerProxy, mempool::tx::Orphan};
+use kaspa_mining::model::{
+    fee_rate_histogram::{are_valid_fee_rate_bucket_bounds, DEFAULT_FEE_RATE_BUCKET_BOUNDS, MAX_FEE_RATE_BUCKET_BOUNDS},
+    tx_query::TransactionQuery,
+    tx_removal::MempoolTransactionRemoval,
+};
+use kaspa_mining::{errors::MiningManagerError, manager::MiningManagerProxy, mempool::tx::Orphan};
 use kaspa_notify::{
     collector::DynCollector,
     connection::ChannelType,
     events::{EventSwitches, EventType, EVENT_TYPE_ARRAY},
     listener::ListenerId,
-    notifier::Notifier,
+    notifier::{DynListenerMetricsSource, Notifier},
     scope::Scope,
     subscriber::{Subscriber, SubscriptionManager},
 };
-use kaspa_p2p_flows::flow_context::FlowContext;
+use kaspa_p2p_flows::{flow_context::FlowContext, flowcontext::sync_state::SyncState};
 use kaspa_perf_monitor::{counters::CountersSnapshot, Monitor as PerfMonitor};
 use kaspa_rpc_core::{
     api::{
         ops::RPC_API_VERSION,
+        profile::RpcProfile,
         rpc::{RpcApi, MAX_SAFE_WINDOW_SIZE},
     },
+    metrics::RpcMethodCounters,
     model::*,
     notify::connection::ChannelConnection,
     Notification, RpcError, RpcResult,
 };
+use kaspa_txindex::api::TxIndexProxy;
 use kaspa_txscript::{extract_script_pub_key_address, pay_to_address_script};
 use kaspa_utils::{channel::Channel, triggers::SingleTrigger};
 use kaspa_utxoindex::api::UtxoIndexProxy;
@@ -62,7 +72,7 @@ use kaspa_wrpc_core::ServerCounters as WrpcServerCounters;
 use std::{
     collections::HashMap,
     iter::once,
-    sync::{atomic::Ordering, Arc},
+    sync::{atomic::Ordering, Arc, Mutex},
     vec,
 };
 
@@ -86,24 +96,52 @@ use std::{
 pub struct RpcCoreService {
     consensus_manager: Arc<ConsensusManager>,
     notifier: Arc<Notifier<Notification, ChannelConnection>>,
+    mempool_notify_channel: Channel<MempoolTransactionRemoval>,
+    sync_state_notify_channel: Channel<SyncState>,
     mining_manager: MiningManagerProxy,
     flow_context: Arc<FlowContext>,
     utxoindex: Option<UtxoIndexProxy>,
+    txindex: Option<TxIndexProxy>,
     config: Arc<Config>,
     consensus_converter: Arc<ConsensusConverter>,
     index_converter: Arc<IndexConverter>,
     protocol_converter: Arc<ProtocolConverter>,
     core: Arc<Core>,
     processing_counters: Arc<ProcessingCounters>,
+    integrity_counters: Arc<IntegrityCounters>,
     wrpc_borsh_counters: Arc<WrpcServerCounters>,
     wrpc_json_counters: Arc<WrpcServerCounters>,
+    method_counters: Arc<RpcMethodCounters>,
+    /// The notifiers whose listener queues are reported by GetMetrics, i.e. this service's and those of the servers
+    listener_metrics_sources: Mutex<Vec<DynListenerMetricsSource>>,
     shutdown: SingleTrigger,
     perf_monitor: Arc<PerfMonitor<Arc<TickService>>>,
+    load_shedder: LoadShedder,
+    block_producer: Option<Arc<BlockProducer>>,
+    lifetime_metrics: Arc<LifetimeMetricsMonitor>,
 }
 
 const RPC_CORE: &str = "rpc-core";
 
+/// The number of UTXO entries returned by each `GetUtxoSetChunk` call
+const UTXO_SET_CHUNK_SIZE: usize = 10_000;
+
+/// The maximum number of digests returned by a `GetStateDigests` call, bounding the length of the chain walked
+const MAX_STATE_DIGESTS: usize = 100;
+
+/// Maximal number of headers returned by a single `GetHeaders` call, light clients paging through the chain
+const MAX_HEADERS_LIMIT: u64 = 1000;
+
+/// Maximal number of chain headers of an acceptance proof, clients linking the last one to the chain they track
+const MAX_ACCEPTANCE_PROOF_HEADERS: usize = 1000;
+
+/// Bounds of the DAG neighborhoods returned by `GetDagBlocksAround`
+const MAX_DAG_VIEW_RADIUS: u32 = 50;
+const MAX_DAG_VIEW_BLOCKS: usize = 1000;
+
 impl RpcCoreService {
+    pub const IDENT: &'static str = RPC_CORE_SERVICE;
+
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         consensus_manager: Arc<ConsensusManager>,
@@ -112,12 +150,17 @@ impl RpcCoreService {
         mining_manager: MiningManagerProxy,
         flow_context: Arc<FlowContext>,
         utxoindex: Option<UtxoIndexProxy>,
+        txindex: Option<TxIndexProxy>,
         config: Arc<Config>,
         core: Arc<Core>,
         processing_counters: Arc<ProcessingCounters>,
+        integrity_counters: Arc<IntegrityCounters>,
         wrpc_borsh_counters: Arc<WrpcServerCounters>,
         wrpc_json_counters: Arc<WrpcServerCounters>,
+        method_counters: Arc<RpcMethodCounters>,
         perf_monitor: Arc<PerfMonitor<Arc<TickService>>>,
+        block_producer: Option<Arc<BlockProducer>>,
+        lifetime_metrics: Arc<LifetimeMetricsMonitor>,
     ) -> Self {
         // Prepare consensus-notify objects
         let consensus_notify_channel = Channel::<ConsensusNotification>::default();
@@ -128,6 +171,8 @@ impl RpcCoreService {
         let mut consensus_events: EventSwitches = EVENT_TYPE_ARRAY[..].into();
         consensus_events[EventType::UtxosChanged] = false;
         consensus_events[EventType::PruningPointUtxoSetOverride] = index_notifier.is_none();
+        consensus_events[EventType::MempoolTransactionRemoved] = false;
+        consensus_events[EventType::SyncStateChanged] = false;
         let consensus_converter = Arc::new(ConsensusConverter::new(consensus_manager.clone(), config.clone()));
         let consensus_collector = Arc::new(CollectorFromConsensus::new(
             "rpc-core <= consensus",
@@ -158,28 +203,61 @@ impl RpcCoreService {
             subscribers.push(index_subscriber);
         }
 
+        // Prepare the mempool collector, the mempool reporting the transactions it drops through a channel
+        let mempool_notify_channel = Channel::<MempoolTransactionRemoval>::default();
+        mining_manager.set_removal_sender(mempool_notify_channel.sender());
+        let mempool_collector = Arc::new(CollectorFromMempool::new(
+            "rpc-core <= mempool",
+            mempool_notify_channel.receiver(),
+            Arc::new(MempoolConverter::new()),
+        ));
+        collectors.push(mempool_collector);
+
+        // Prepare the sync state collector, the IBD flows reporting their phases through a channel
+        let sync_state_notify_channel = Channel::<SyncState>::default();
+        flow_context.set_sync_state_sender(sync_state_notify_channel.sender());
+        let sync_state_collector = Arc::new(CollectorFromSyncState::new(
+            "rpc-core <= sync state",
+            sync_state_notify_channel.receiver(),
+            Arc::new(SyncStateConverter::new()),
+        ));
+        collectors.push(sync_state_collector);
+
         // Protocol converter
         let protocol_converter = Arc::new(ProtocolConverter::new(flow_context.clone()));
 
+        let load_shedder = LoadShedder::new(config.rpc_shed_pipeline_threshold, config.rpc_max_heavy_requests);
+
         // Create the rcp-core notifier
         let notifier = Arc::new(Notifier::new(RPC_CORE, EVENT_TYPE_ARRAY[..].into(), collectors, subscribers, 1));
 
+        let listener_metrics_sources = Mutex::new(vec![notifier.clone() as DynListenerMetricsSource]);
+
         Self {
             consensus_manager,
             notifier,
+            mempool_notify_channel,
+            sync_state_notify_channel,
             mining_manager,
             flow_context,
             utxoindex,
+            txindex,
             config,
             consensus_converter,
             index_converter,
             protocol_converter,
             core,
             processing_counters,
+            integrity_counters,
             wrpc_borsh_counters,
             wrpc_json_counters,
+            method_counters,
+            listener_metrics_sources,
             shutdown: SingleTrigger::default(),
             perf_monitor,
+            load_shedder,
+            block_producer,
+            lifetime_metrics,
         }
     }
 
@@ -188,6 +266,9 @@ impl RpcCoreService {
     }
 
     pub async fn join(&self) -> RpcResult<()> {
+        // The mempool outlives this service so its notification channel must be closed explicitly to end the collector
+        self.mempool_notify_channel.close();
+        self.sync_state_notify_channel.close();
         trace!("{} joining notifier", RPC_CORE_SERVICE);
         self.notifier().join().await?;
         Ok(())
@@ -198,6 +279,37 @@ impl RpcCoreService {
         self.notifier.clone()
     }
 
+    /// The counters of the RPC methods, measured by the servers relaying requests to this service
+    pub fn method_counters(&self) -> Arc<RpcMethodCounters> {
+        self.method_counters.clone()
+    }
+
+    /// Registers a notifier of a server relaying the notifications of this service, so that its listener queues are
+    /// reported by GetMetrics
+    pub fn register_listener_metrics_source(&self, source: DynListenerMetricsSource) {
+        self.listener_metrics_sources.lock().unwrap().push(source);
+    }
+
+    fn listener_metrics(&self) -> Vec<RpcListenerMetrics> {
+        let sources = self.listener_metrics_sources.lock().unwrap().clone();
+        let mut listener_metrics: Vec<RpcListenerMetrics> = sources
+            .iter()
+            .flat_map(|source| {
+                source.listener_metrics().into_iter().map(|(listener_id, metrics)| RpcListenerMetrics {
+                    notifier: source.name().to_string(),
+                    listener_id,
+                    queue_len: metrics.queue_len as u64,
+                    queue_capacity: metrics.queue_capacity.unwrap_or_default() as u64,
+                    dropped_notifications: metrics.dropped_notifications,
+                    lagging: metrics.lagging,
+                    overflowed: metrics.overflowed,
+                })
+            })
+            .collect();
+        listener_metrics.sort_by(|a, b| (&a.notifier, a.listener_id).cmp(&(&b.notifier, b.listener_id)));
+        listener_metrics
+    }
+
     async fn get_utxo_set_by_script_public_key<'a>(
         &self,
         addresses: impl Iterator<Item = &'a RpcAddress>,
@@ -219,6 +331,152 @@ impl RpcCoreService {
             .unwrap_or_default()
     }
 
+    /// Returns a transaction accepted by the virtual selected chain, located through the txindex
+    async fn get_accepted_transaction(&self, session: &ConsensusProxy, transaction_id: RpcTransactionId) -> RpcResult<Transaction> {
+        let location = self
+            .txindex
+            .clone()
+            .unwrap()
+            .get_transaction_location(transaction_id)
+            .await
+            .map_err(|e| RpcError::General(e.to_string()))?
+            .ok_or(RpcError::TransactionNotFound(transaction_id))?;
+        let block = session.async_get_block(location.including_block_hash).await?;
+        block
+            .transactions
+            .get(location.index_within_block as usize)
+            .filter(|tx| tx.id() == transaction_id)
+            .cloned()
+            .ok_or(RpcError::TransactionNotFound(transaction_id))
+    }
+
+    /// Returns whether the call being served may use unsafe RPC methods and behaviors: the node has to run in unsafe RPC
+    /// mode, and a call received by an RPC listener has to come through a listener with the unsafe profile
+    fn is_unsafe_allowed(&self) -> bool {
+        self.config.unsafe_rpc && RpcProfile::current().map_or(true, |profile| profile == RpcProfile::Unsafe)
+    }
+
+    /// Admits a request for bulk data unless the node is under pressure, see [`LoadShedder`]
+    async fn admit_heavy_request(&self) -> RpcResult<HeavyRequestGuard> {
+        let pipeline_backlog = if self.load_shedder.monitors_pipeline() {
+            self.consensus_manager.consensus().unguarded_session().async_get_pipeline_backlog().await as u64
+        } else {
+            0
+        };
+        self.load_shedder.try_admit(pipeline_backlog)
+    }
+
+    /// Validates a `GetBlocks` request and admits it, requests including transactions being heavy requests
+    async fn admit_get_blocks_request(&self, request: &GetBlocksRequest) -> RpcResult<Option<HeavyRequestGuard>> {
+        // Validate that user didn't set include_transactions without setting include_blocks
+        if !request.include_blocks && request.include_transactions {
+            return Err(RpcError::InvalidGetBlocksRequest);
+        }
+        match request.include_transactions {
+            true => Ok(Some(self.admit_heavy_request().await?)),
+            false => Ok(None),
+        }
+    }
+
+    /// Returns the hashes of the blocks served by a `GetBlocks` call, from `low_hash` (or genesis if unset) to the sink,
+    /// followed by the sink anticone
+    async fn get_blocks_hashes(&self, session: &ConsensusProxy, low_hash: Option<RpcHash>) -> RpcResult<Vec<RpcHash>> {
+        // If low_hash is empty - use genesis instead.
+        let low_hash = match low_hash {
+            Some(low_hash) => {
+                // Make sure low_hash points to an existing and valid block
+                session.async_get_ghostdag_data(low_hash).await?;
+                low_hash
+            }
+            None => self.config.genesis.hash,
+        };
+
+        // Get hashes between low_hash and sink
+        let sink_hash = session.async_get_sink().await;
+
+        // We use +1 because low_hash is also returned
+        // max_blocks MUST be >= mergeset_size_limit + 1
+        let max_blocks = self.config.mergeset_size_limit as usize + 1;
+        let (block_hashes, high_hash) = session.async_get_hashes_between(low_hash, sink_hash, max_blocks).await?;
+
+        // If the high hash is equal to sink it means get_hashes_between didn't skip any hashes, and
+        // there's space to add the sink anticone, otherwise we cannot add the anticone because
+        // there's no guarantee that all of the anticone root ancestors will be present.
+        let sink_anticone = if high_hash == sink_hash { session.async_get_anticone(sink_hash).await? } else { vec![] };
+        // Prepend low hash to make it inclusive and append the sink anticone
+        Ok(once(low_hash).chain(block_hashes).chain(sink_anticone).collect())
+    }
+
+    async fn get_rpc_blocks(
+        &self,
+        session: &ConsensusProxy,
+        hashes: &[RpcHash],
+        include_transactions: bool,
+    ) -> RpcResult<Vec<RpcBlock>> {
+        let mut blocks = Vec::with_capacity(hashes.len());
+        for hash in hashes.iter().copied() {
+            let block = session.async_get_block_even_if_header_only(hash).await?;
+            let rpc_block = self.consensus_converter.get_block(session, &block, include_transactions, include_transactions).await?;
+            blocks.push(rpc_block)
+        }
+        Ok(blocks)
+    }
+
+    /// Applies the settings set in `request` and returns the resulting settings. Unlike the RPC call, this
+    /// method does not require unsafe RPC mode and serves reloading settings upon a SIGHUP signal as well
+    pub async fn apply_settings(&self, request: ReloadSettingsRequest) -> RpcResult<ReloadSettingsResponse> {
+        let connection_manager = self.flow_context.connection_manager();
+        if connection_manager.is_none() && (request.outbound_target.is_some() || request.inbound_limit.is_some()) {
+            return Err(RpcError::NoConnectionManager);
+        }
+        Self::validate_settings(&request)?;
+
+        if let Some(filters) = request.log_level.as_deref() {
+            if !kaspa_core::log::reload_logger_filters(filters) {
+                return Err(RpcError::General("the logger does not support reloading its filters".to_string()));
+            }
+            info!("Log level set to {}", filters);
+        }
+
+        if let Some(connection_manager) = connection_manager.as_ref() {
+            if request.outbound_target.is_some() || request.inbound_limit.is_some() {
+                let outbound_target = request.outbound_target.map_or(connection_manager.outbound_target(), |x| x as usize);
+                let inbound_limit = request.inbound_limit.map_or(connection_manager.inbound_limit(), |x| x as usize);
+                connection_manager.set_connection_targets(outbound_target, inbound_limit);
+                info!("Peer connection targets set to {} outbound and {} inbound", outbound_target, inbound_limit);
+            }
+        }
+
+        let (mut max_transactions, mut max_orphans) = self.mining_manager.clone().mempool_size_limits().await;
+        if request.mempool_max_transactions.is_some() || request.mempool_max_orphans.is_some() {
+            max_transactions = request.mempool_max_transactions.unwrap_or(max_transactions);
+            max_orphans = request.mempool_max_orphans.unwrap_or(max_orphans);
+            self.mining_manager.clone().set_mempool_size_limits(max_transactions, max_orphans).await;
+            info!("Mempool size limits set to {} transactions and {} orphans", max_transactions, max_orphans);
+        }
+
+        let (outbound_target, inbound_limit) =
+            connection_manager.map_or((0, 0), |manager| (manager.outbound_target() as u64, manager.inbound_limit() as u64));
+        Ok(ReloadSettingsResponse::new(outbound_target, inbound_limit, max_transactions, max_orphans))
+    }
+
+    /// Checks all the settings of a reload request before any of them is applied, so that an invalid request
+    /// leaves the current settings unchanged
+    fn validate_settings(request: &ReloadSettingsRequest) -> RpcResult<()> {
+        if let Some(filters) = request.log_level.as_deref() {
+            kaspa_core::log::validate_logger_filters(filters).map_err(RpcError::InvalidSettings)?;
+        }
+        for (name, value) in [("outbound peers target", request.outbound_target), ("inbound peers limit", request.inbound_limit)] {
+            if value.is_some_and(|value| usize::try_from(value).is_err()) {
+                return Err(RpcError::InvalidSettings(format!("the {name} is out of range")));
+            }
+        }
+        if request.mempool_max_transactions == Some(0) {
+            return Err(RpcError::InvalidSettings("the mempool must admit at least one transaction".to_string()));
+        }
+        Ok(())
+    }
+
     fn has_sufficient_peer_connectivity(&self) -> bool {
         // Other network types can be used in an isolated environment without peers
         !matches!(self.flow_context.config.net.network_type, Mainnet | Testnet) || self.flow_context.hub().has_peers()
@@ -239,6 +497,9 @@ impl RpcCoreService {
 #[async_trait]
 impl RpcApi for RpcCoreService {
     async fn submit_block_call(&self, request: SubmitBlockRequest) -> RpcResult<SubmitBlockResponse> {
+        if self.config.headers_only {
+            return Err(RpcError::HeadersOnlyMode);
+        }
         let session = self.consensus_manager.consensus().unguarded_session();
 
         // TODO: consider adding an error field to SubmitBlockReport to document both the report and error fields
@@ -246,14 +507,20 @@ impl RpcApi for RpcCoreService {
 
         if !self.config.enable_unsynced_mining && !is_synced {
             // error = "Block not submitted - node is not synced"
-            return Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::IsInIBD) });
+            return Ok(SubmitBlockResponse {
+                report: SubmitBlockReport::Reject(SubmitBlockRejectReason::IsInIBD),
+                rule_error_code: None,
+            });
         }
 
         let try_block: RpcResult<Block> = (&request.block).try_into();
         if let Err(err) = &try_block {
             trace!("incoming SubmitBlockRequest with block conversion error: {}", err);
             // error = format!("Could not parse block: {0}", err)
-            return Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid) });
+            return Ok(SubmitBlockResponse {
+                report: SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid),
+                rule_error_code: None,
+            });
         }
         let block = try_block?;
         let hash = block.hash();
@@ -261,23 +528,35 @@ impl RpcApi for RpcCoreService {
         if !request.allow_non_daa_blocks {
             let virtual_daa_score = session.async_get_virtual_daa_score().await;
 
-            // A simple heuristic check which signals that the mined block is out of date
-            // and should not be accepted unless user explicitly requests
-            let daa_window_block_duration = self.config.daa_window_duration_in_blocks(virtual_daa_score);
-            if virtual_daa_score > daa_window_block_duration && block.header.daa_score < virtual_daa_score - daa_window_block_duration
-            {
+            // A simple heuristic check which signals that the mined block is out of date, i.e., that its template was built
+            // over long outdated parents, and should not be accepted unless user explicitly requests
+            let staleness_threshold = self
+                .config
+                .stale_template_daa_threshold
+                .unwrap_or_else(|| self.config.daa_window_duration_in_blocks(virtual_daa_score));
+            if block.header.daa_score.saturating_add(staleness_threshold) < virtual_daa_score {
+                debug!(
+                    "Rejecting submitted block {}: its DAA score {} is too far behind virtual's DAA score {}",
+                    hash, block.header.daa_score, virtual_daa_score
+                );
                 // error = format!("Block rejected. Reason: block DAA score {0} is too far behind virtual's DAA score {1}", block.header.daa_score, virtual_daa_score)
-                return Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid) });
+                return Ok(SubmitBlockResponse {
+                    report: SubmitBlockReport::Reject(SubmitBlockRejectReason::StaleTemplate),
+                    rule_error_code: None,
+                });
             }
         }
 
         trace!("incoming SubmitBlockRequest for block {}", hash);
         match self.flow_context.submit_rpc_block(&session, block.clone()).await {
-            Ok(_) => Ok(SubmitBlockResponse { report: SubmitBlockReport::Success }),
+            Ok(_) => Ok(SubmitBlockResponse { report: SubmitBlockReport::Success, rule_error_code: None }),
             Err(err) => {
                 warn!("The RPC submitted block triggered an error: {}\nPrinting the full header for debug purposes:\n{:?}", err, err);
                 // error = format!("Block rejected. Reason: {}", err))
-                Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid) })
+                Ok(SubmitBlockResponse {
+                    report: SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid),
+                    rule_error_code: if request.report_rule_error_code { err.rule_error_code() } else { None },
+                })
             }
         }
     }
@@ -285,6 +564,10 @@ impl RpcApi for RpcCoreService {
     async fn get_block_template_call(&self, request: GetBlockTemplateRequest) -> RpcResult<GetBlockTemplateResponse> {
         trace!("incoming GetBlockTemplate request");
 
+        if self.config.headers_only {
+            return Err(RpcError::HeadersOnlyMode);
+        }
+
         if *self.config.net == NetworkType::Mainnet && !self.config.enable_mainnet_mining {
             return Err(RpcError::General("Mining on mainnet is not supported for initial Rust versions".to_owned()));
         }
@@ -296,15 +579,14 @@ impl RpcApi for RpcCoreService {
 
         // Build block template
         let script_public_key = kaspa_txscript::pay_to_address_script(&request.pay_address);
-        let extra_data = version().as_bytes().iter().chain(once(&(b'/'))).chain(&request.extra_data).cloned().collect::<Vec<_>>();
+        let extra_data = self.mining_manager.coinbase_extra_data(&request.extra_data);
         let miner_data: MinerData = MinerData::new(script_public_key, extra_data);
         let session = self.consensus_manager.consensus().unguarded_session();
-        let block_template = self.mining_manager.clone().get_block_template(&session, miner_data).await?;
-
-        // Check coinbase tx payload length
-        if block_template.block.transactions[COINBASE_TRANSACTION_INDEX].payload.len() > self.config.max_coinbase_payload_len {
-            return Err(RpcError::CoinbasePayloadLengthAboveMax(self.config.max_coinbase_payload_len));
-        }
+        // The mining manager rejects miner data exceeding the maximum coinbase payload length, reported as by former versions
+        let block_template = self.mining_manager.clone().get_block_template(&session, miner_data).await.map_err(|err| match err {
+            MiningManagerError::CoinbasePayloadTooLong(_, max_length) => RpcError::CoinbasePayloadLengthAboveMax(max_length),
+            err => err.into(),
+        })?;
 
         let is_nearly_synced =
             self.config.is_nearly_synced(block_template.selected_parent_timestamp, block_template.selected_parent_daa_score);
@@ -317,64 +599,64 @@ impl RpcApi for RpcCoreService {
     async fn get_block_call(&self, request: GetBlockRequest) -> RpcResult<GetBlockResponse> {
         // TODO: test
         let session = self.consensus_manager.consensus().session().await;
-        let block = session.async_get_block_even_if_header_only(request.hash).await?;
+        let block = session.async_get_block_even_if_header_only(request.hash).await;
+        // Blocks whose body was pruned (whether or not their header was kept) are looked up in the era archive
+        if !block.as_ref().is_ok_and(|block| !block.transactions.is_empty()) {
+            if let Ok(block) = session.async_get_archived_block(request.hash).await {
+                return Ok(GetBlockResponse {
+                    block: self.consensus_converter.get_archived_block(
+                        &session,
+                        &block,
+                        request.include_transactions,
+                        request.include_transactions,
+                    ),
+                    is_archival: true,
+                });
+            }
+        }
         Ok(GetBlockResponse {
             block: self
                 .consensus_converter
-                .get_block(&session, &block, request.include_transactions, request.include_transactions)
+                .get_block(&session, &block?, request.include_transactions, request.include_transactions)
                 .await?,
+            is_archival: false,
         })
     }
 
     async fn get_blocks_call(&self, request: GetBlocksRequest) -> RpcResult<GetBlocksResponse> {
-        // Validate that user didn't set include_transactions without setting include_blocks
-        if !request.include_blocks && request.include_transactions {
-            return Err(RpcError::InvalidGetBlocksRequest);
-        }
-
+        let _guard = self.admit_get_blocks_request(&request).await?;
         let session = self.consensus_manager.consensus().session().await;
-
-        // If low_hash is empty - use genesis instead.
-        let low_hash = match request.low_hash {
-            Some(low_hash) => {
-                // Make sure low_hash points to an existing and valid block
-                session.async_get_ghostdag_data(low_hash).await?;
-                low_hash
-            }
-            None => self.config.genesis.hash,
-        };
-
-        // Get hashes between low_hash and sink
-        let sink_hash = session.async_get_sink().await;
-
-        // We use +1 because low_hash is also returned
-        // max_blocks MUST be >= mergeset_size_limit + 1
-        let max_blocks = self.config.mergeset_size_limit as usize + 1;
-        let (block_hashes, high_hash) = session.async_get_hashes_between(low_hash, sink_hash, max_blocks).await?;
-
-        // If the high hash is equal to sink it means get_hashes_between didn't skip any hashes, and
-        // there's space to add the sink anticone, otherwise we cannot add the anticone because
-        // there's no guarantee that all of the anticone root ancestors will be present.
-        let sink_anticone = if high_hash == sink_hash { session.async_get_anticone(sink_hash).await? } else { vec![] };
-        // Prepend low hash to make it inclusive and append the sink anticone
-        let block_hashes = once(low_hash).chain(block_hashes).chain(sink_anticone).collect::<Vec<_>>();
+        let block_hashes = self.get_blocks_hashes(&session, request.low_hash).await?;
         let blocks = if request.include_blocks {
-            let mut blocks = Vec::with_capacity(block_hashes.len());
-            for hash in block_hashes.iter().copied() {
-                let block = session.async_get_block_even_if_header_only(hash).await?;
-                let rpc_block = self
-                    .consensus_converter
-                    .get_block(&session, &block, request.include_transactions, request.include_transactions)
-                    .await?;
-                blocks.push(rpc_block)
-            }
-            blocks
+            self.get_rpc_blocks(&session, &block_hashes, request.include_transactions).await?
         } else {
             Vec::new()
         };
         Ok(GetBlocksResponse { block_hashes, blocks })
     }
 
+    async fn get_blocks_chunked_call(
+        &self,
+        request: GetBlocksRequest,
+        chunk_size: usize,
+        chunks: Sender<GetBlocksResponse>,
+    ) -> RpcResult<()> {
+        let _guard = self.admit_get_blocks_request(&request).await?;
+        let session = self.consensus_manager.consensus().session().await;
+        let block_hashes = self.get_blocks_hashes(&session, request.low_hash).await?;
+        // Each chunk is sent as soon as it is built, so the blocks of the full response are never held at once
+        for block_hashes in block_hashes.chunks(chunk_size.max(1)) {
+            let blocks = if request.include_blocks {
+                self.get_rpc_blocks(&session, block_hashes, request.include_transactions).await?
+            } else {
+                Vec::new()
+            };
+            let chunk = GetBlocksResponse { block_hashes: block_hashes.to_vec(), blocks };
+            chunks.send(chunk).await.map_err(|_| RpcError::ResponseChunksReceiverClosed)?;
+        }
+        Ok(())
+    }
+
     async fn get_info_call(&self, _request: GetInfoRequest) -> RpcResult<GetInfoResponse> {
         let is_nearly_synced = self.consensus_manager.consensus().unguarded_session().async_is_nearly_synced().await;
         Ok(GetInfoResponse {
@@ -385,6 +667,7 @@ impl RpcApi for RpcCoreService {
             is_synced: self.has_sufficient_peer_connectivity() && is_nearly_synced,
             has_notify_command: true,
             has_message_id: true,
+            is_clock_drifted: self.flow_context.network_time().is_clock_drifted(),
         })
     }
 
@@ -409,6 +692,28 @@ impl RpcApi for RpcCoreService {
         Ok(GetMempoolEntriesResponse::new(mempool_entries))
     }
 
+    async fn get_mempool_entries_chunked_call(
+        &self,
+        request: GetMempoolEntriesRequest,
+        chunk_size: usize,
+        chunks: Sender<GetMempoolEntriesResponse>,
+    ) -> RpcResult<()> {
+        let query = self.extract_tx_query(request.filter_transaction_pool, request.include_orphan_pool)?;
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let (transactions, orphans) = self.mining_manager.clone().get_all_transactions(query).await;
+        let transactions = transactions.into_iter().chain(orphans).collect::<Vec<_>>();
+        // Each chunk is sent as soon as it is built, so the entries of the full response are never held at once
+        for transactions in transactions.chunks(chunk_size.max(1)) {
+            let mempool_entries =
+                transactions.iter().map(|transaction| self.consensus_converter.get_mempool_entry(&session, transaction)).collect();
+            chunks.send(GetMempoolEntriesResponse::new(mempool_entries)).await.map_err(|_| RpcError::ResponseChunksReceiverClosed)?;
+        }
+        if transactions.is_empty() {
+            chunks.send(GetMempoolEntriesResponse::new(vec![])).await.map_err(|_| RpcError::ResponseChunksReceiverClosed)?;
+        }
+        Ok(())
+    }
+
     async fn get_mempool_entries_by_addresses_call(
         &self,
         request: GetMempoolEntriesByAddressesRequest,
@@ -435,8 +740,11 @@ impl RpcApi for RpcCoreService {
     }
 
     async fn submit_transaction_call(&self, request: SubmitTransactionRequest) -> RpcResult<SubmitTransactionResponse> {
-        let allow_orphan = self.config.unsafe_rpc && request.allow_orphan;
-        if !self.config.unsafe_rpc && request.allow_orphan {
+        if self.config.headers_only {
+            return Err(RpcError::HeadersOnlyMode);
+        }
+        let allow_orphan = self.is_unsafe_allowed() && request.allow_orphan;
+        if !self.is_unsafe_allowed() && request.allow_orphan {
             warn!("SubmitTransaction RPC command called with AllowOrphan enabled while node in safe RPC mode -- switching to ForbidOrphan.");
         }
 
@@ -448,13 +756,33 @@ impl RpcApi for RpcCoreService {
             false => Orphan::Forbidden,
         };
         self.flow_context.submit_rpc_transaction(&session, transaction, orphan).await.map_err(|err| {
-            let err = RpcError::RejectedTransaction(transaction_id, err.to_string());
+            let err = match err.rule_error_code() {
+                Some(code) => RpcError::RejectedTransactionRule(transaction_id, code, err.to_string()),
+                None => RpcError::RejectedTransaction(transaction_id, err.to_string()),
+            };
             debug!("{err}");
             err
         })?;
         Ok(SubmitTransactionResponse::new(transaction_id))
     }
 
+    async fn check_transaction_call(&self, request: CheckTransactionRequest) -> RpcResult<CheckTransactionResponse> {
+        if self.config.headers_only {
+            return Err(RpcError::HeadersOnlyMode);
+        }
+        let transaction: Transaction = (&request.transaction).try_into()?;
+        let transaction_id = transaction.id();
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let check = self.mining_manager.clone().check_transaction(&session, transaction).await;
+        Ok(CheckTransactionResponse {
+            transaction_id,
+            mass: check.mass,
+            fee: check.fee,
+            fee_rate: check.fee_rate(),
+            rule_error: check.result.err().map(|err| err.to_string()),
+        })
+    }
+
     async fn get_current_network_call(&self, _: GetCurrentNetworkRequest) -> RpcResult<GetCurrentNetworkResponse> {
         Ok(GetCurrentNetworkResponse::new(*self.config.net))
     }
@@ -494,6 +822,7 @@ impl RpcApi for RpcCoreService {
         if !self.config.utxoindex {
             return Err(RpcError::NoUtxoIndex);
         }
+        let _guard = self.admit_heavy_request().await?;
         // TODO: discuss if the entry order is part of the method requirements
         //       (the current impl does not retain an entry order matching the request addresses order)
         let entry_map = self.get_utxo_set_by_script_public_key(request.addresses.iter()).await;
@@ -596,12 +925,390 @@ impl RpcApi for RpcCoreService {
         Ok(GetDaaScoreTimestampEstimateResponse::new(timestamps))
     }
 
+    async fn get_transaction_call(&self, request: GetTransactionRequest) -> RpcResult<GetTransactionResponse> {
+        if !self.config.txindex {
+            return Err(RpcError::NoTxIndex);
+        }
+        let location = self
+            .txindex
+            .clone()
+            .unwrap()
+            .get_transaction_location(request.transaction_id)
+            .await
+            .map_err(|e| RpcError::General(e.to_string()))?
+            .ok_or(RpcError::TransactionNotFound(request.transaction_id))?;
+
+        let session = self.consensus_manager.consensus().session().await;
+        let block = session.async_get_block(location.including_block_hash).await?;
+        let index = location.index_within_block as usize;
+        let transaction = block
+            .transactions
+            .get(index)
+            .filter(|tx| tx.id() == request.transaction_id)
+            .ok_or(RpcError::TransactionNotFound(request.transaction_id))?;
+
+        // The sink counts as the first confirmation of the transactions it accepts
+        let sink_blue_score = session.async_get_ghostdag_data(session.async_get_sink().await).await?.blue_score;
+        let accepting_blue_score = session.async_get_ghostdag_data(location.accepting_block_hash).await?.blue_score;
+        let confirmations = sink_blue_score.saturating_sub(accepting_blue_score) + 1;
+
+        let merkle_branch = if request.include_proof {
+            create_hash_merkle_branch(block.transactions.iter(), index).expect("transaction index is within the block")
+        } else {
+            vec![]
+        };
+
+        Ok(GetTransactionResponse::new(
+            self.consensus_converter.get_transaction(&session, transaction, Some(&block.header), true),
+            location.including_block_hash,
+            location.accepting_block_hash,
+            confirmations,
+            location.index_within_block,
+            merkle_branch,
+        ))
+    }
+
+    async fn get_block_validation_trace_call(
+        &self,
+        request: GetBlockValidationTraceRequest,
+    ) -> RpcResult<GetBlockValidationTraceResponse> {
+        if !self.config.trace_validation {
+            return Err(RpcError::NoValidationTracing);
+        }
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let trace =
+            session.async_get_block_validation_trace(request.hash).await.ok_or(RpcError::ValidationTraceNotFound(request.hash))?;
+        let total_duration_micros = trace.total_duration_micros();
+        let rules = trace
+            .rules
+            .into_iter()
+            .map(|rule| RpcRuleTrace { rule: rule.rule, duration_micros: rule.duration_micros, passed: rule.passed })
+            .collect();
+        Ok(GetBlockValidationTraceResponse::new(rules, total_duration_micros))
+    }
+
+    async fn get_health_call(&self, _request: GetHealthRequest) -> RpcResult<GetHealthResponse> {
+        let report = self.core.health_report();
+        let status = ServiceHealth::aggregate(report.iter().map(|(_, health)| *health));
+        let services =
+            report.into_iter().map(|(name, health)| RpcServiceHealth { name: name.to_string(), status: health.to_string() }).collect();
+        Ok(GetHealthResponse::new(status.is_ready(), status.to_string(), services))
+    }
+
+    async fn reload_settings_call(&self, request: ReloadSettingsRequest) -> RpcResult<ReloadSettingsResponse> {
+        if !self.is_unsafe_allowed() {
+            warn!("ReloadSettings RPC command called while node in safe RPC mode -- ignoring.");
+            return Err(RpcError::UnavailableInSafeMode);
+        }
+        self.apply_settings(request).await
+    }
+
+    async fn get_current_difficulty_call(&self, _: GetCurrentDifficultyRequest) -> RpcResult<GetCurrentDifficultyResponse> {
+        let bits = self.consensus_manager.consensus().unguarded_session().async_get_virtual_bits().await;
+        Ok(GetCurrentDifficultyResponse::new(bits, self.consensus_converter.get_difficulty_ratio(bits)))
+    }
+
+    async fn get_block_dag_timing_info_call(&self, request: GetBlockDagTimingInfoRequest) -> RpcResult<GetBlockDagTimingInfoResponse> {
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let info = session.async_get_block_dag_timing_info(request.hash).await?;
+        let window = info.difficulty_window;
+        Ok(GetBlockDagTimingInfoResponse {
+            timestamp: info.timestamp,
+            daa_score: info.daa_score,
+            bits: info.bits,
+            expected_bits: info.expected_bits,
+            past_median_time: info.past_median_time,
+            window_size: window.size,
+            window_sample_rate: window.sample_rate,
+            window_lowest_block: window.lowest_block,
+            window_highest_block: window.highest_block,
+            window_min_timestamp: window.min_timestamp,
+            window_max_timestamp: window.max_timestamp,
+        })
+    }
+
+    async fn get_utxo_set_chunk_call(&self, request: GetUtxoSetChunkRequest) -> RpcResult<GetUtxoSetChunkResponse> {
+        let cursor = request
+            .cursor
+            .map(|cursor| cursor.parse::<UtxoSetCursor>())
+            .transpose()
+            .map_err(|err| RpcError::General(err.to_string()))?;
+        let _guard = self.admit_heavy_request().await?;
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let chunk = session.async_get_virtual_utxo_set_chunk(cursor, UTXO_SET_CHUNK_SIZE).await?;
+        let entries = chunk
+            .entries
+            .into_iter()
+            .map(|(outpoint, utxo_entry)| RpcUtxosByAddressesEntry {
+                address: extract_script_pub_key_address(&utxo_entry.script_public_key, self.config.prefix()).ok(),
+                outpoint,
+                utxo_entry,
+            })
+            .collect();
+        Ok(GetUtxoSetChunkResponse {
+            entries,
+            next_cursor: chunk.next_cursor.map(|cursor| cursor.to_string()),
+            snapshot_daa_score: chunk.snapshot_daa_score,
+        })
+    }
+
+    async fn get_state_digests_call(&self, request: GetStateDigestsRequest) -> RpcResult<GetStateDigestsResponse> {
+        if request.count as usize > MAX_STATE_DIGESTS {
+            return Err(RpcError::General(format!(
+                "Requested {} state digests while at most {} are allowed",
+                request.count, MAX_STATE_DIGESTS
+            )));
+        }
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let digests = session
+            .async_get_state_digests(request.count as usize)
+            .await
+            .into_iter()
+            .map(|x| RpcStateDigest {
+                hash: x.hash,
+                blue_score: x.blue_score,
+                daa_score: x.daa_score,
+                utxo_commitment: x.utxo_commitment,
+                digest: x.digest,
+            })
+            .collect();
+        Ok(GetStateDigestsResponse { interval: self.config.state_digest_interval, digests })
+    }
+
+    async fn get_dag_blocks_around_call(&self, request: GetDagBlocksAroundRequest) -> RpcResult<GetDagBlocksAroundResponse> {
+        if request.radius > MAX_DAG_VIEW_RADIUS {
+            return Err(RpcError::General(format!(
+                "Requested radius {} is larger than max {} allowed",
+                request.radius, MAX_DAG_VIEW_RADIUS
+            )));
+        }
+        let session = self.consensus_manager.consensus().unguarded_session();
+        // Query one block more than returned in order to tell whether the neighborhood was truncated
+        let mut blocks = session.async_get_dag_blocks_around(request.hash, request.radius, MAX_DAG_VIEW_BLOCKS + 1).await?;
+        let truncated = blocks.len() > MAX_DAG_VIEW_BLOCKS;
+        blocks.truncate(MAX_DAG_VIEW_BLOCKS);
+        let blocks = blocks
+            .into_iter()
+            .map(|x| RpcDagBlock {
+                hash: x.hash,
+                distance: x.distance,
+                timestamp: x.timestamp,
+                daa_score: x.daa_score,
+                blue_score: x.blue_score,
+                parent_hashes: x.parents,
+                children_hashes: x.children,
+                selected_parent_hash: x.selected_parent,
+                merge_set_blues_hashes: x.mergeset_blues,
+                merge_set_reds_hashes: x.mergeset_reds,
+                is_chain_block: x.is_chain_block,
+            })
+            .collect();
+        Ok(GetDagBlocksAroundResponse { blocks, truncated })
+    }
+
+    async fn get_block_relations_call(&self, request: GetBlockRelationsRequest) -> RpcResult<GetBlockRelationsResponse> {
+        let session = self.consensus_manager.consensus().session().await;
+        let ghostdag_data = session.async_get_ghostdag_data(request.hash).await?;
+        let parents = session.async_get_block_parents(request.hash).await.map(|parents| parents.to_vec()).unwrap_or_default();
+        let children = session.async_get_block_children(request.hash).await.unwrap_or_default();
+        Ok(GetBlockRelationsResponse {
+            parent_hashes: parents,
+            children_hashes: children,
+            selected_parent_hash: ghostdag_data.selected_parent,
+            merge_set_blues_hashes: ghostdag_data.mergeset_blues,
+            merge_set_reds_hashes: ghostdag_data.mergeset_reds,
+            blue_score: ghostdag_data.blue_score,
+        })
+    }
+
+    async fn get_transaction_acceptance_proof_call(
+        &self,
+        request: GetTransactionAcceptanceProofRequest,
+    ) -> RpcResult<GetTransactionAcceptanceProofResponse> {
+        let accepting_block_hash = match request.accepting_block_hash {
+            Some(accepting_block_hash) => accepting_block_hash,
+            None => {
+                if !self.config.txindex {
+                    return Err(RpcError::NoTxIndex);
+                }
+                self.txindex
+                    .clone()
+                    .unwrap()
+                    .get_transaction_location(request.transaction_id)
+                    .await
+                    .map_err(|e| RpcError::General(e.to_string()))?
+                    .ok_or(RpcError::TransactionNotFound(request.transaction_id))?
+                    .accepting_block_hash
+            }
+        };
+        let session = self.consensus_manager.consensus().session().await;
+        let proof = session
+            .async_get_transaction_acceptance_proof(request.transaction_id, accepting_block_hash, MAX_ACCEPTANCE_PROOF_HEADERS)
+            .await?;
+        Ok(GetTransactionAcceptanceProofResponse { proof })
+    }
+
+    async fn invalidate_block_call(&self, request: InvalidateBlockRequest) -> RpcResult<InvalidateBlockResponse> {
+        if !self.is_unsafe_allowed() {
+            warn!("InvalidateBlock RPC command called while node in safe RPC mode -- ignoring.");
+            return Err(RpcError::UnavailableInSafeMode);
+        }
+        // The invalidation acquires the virtual and pruning locks itself, hence an unguarded session is used
+        self.consensus_manager.consensus().unguarded_session().async_invalidate_block(request.hash).await?;
+        Ok(InvalidateBlockResponse {})
+    }
+
+    async fn reconsider_block_call(&self, request: ReconsiderBlockRequest) -> RpcResult<ReconsiderBlockResponse> {
+        if !self.is_unsafe_allowed() {
+            warn!("ReconsiderBlock RPC command called while node in safe RPC mode -- ignoring.");
+            return Err(RpcError::UnavailableInSafeMode);
+        }
+        self.consensus_manager.consensus().unguarded_session().async_reconsider_block(request.hash).await?;
+        Ok(ReconsiderBlockResponse {})
+    }
+
+    async fn get_network_stats_call(&self, _request: GetNetworkStatsRequest) -> RpcResult<GetNetworkStatsResponse> {
+        let stats = self.consensus_manager.consensus().unguarded_session().async_get_network_stats().await;
+        Ok(GetNetworkStatsResponse {
+            window_size: stats.window_size,
+            inter_arrival_mean: stats.inter_arrival.mean,
+            inter_arrival_p50: stats.inter_arrival.p50,
+            inter_arrival_p90: stats.inter_arrival.p90,
+            inter_arrival_p99: stats.inter_arrival.p99,
+            inter_arrival_max: stats.inter_arrival.max,
+            average_parent_count: stats.average_parent_count,
+            mergeset_blues: stats.mergeset_blues,
+            mergeset_reds: stats.mergeset_reds,
+            blue_ratio: stats.blue_ratio(),
+        })
+    }
+
+    async fn get_mempool_fee_histogram_call(
+        &self,
+        request: GetMempoolFeeHistogramRequest,
+    ) -> RpcResult<GetMempoolFeeHistogramResponse> {
+        let bucket_bounds = match request.bucket_bounds.is_empty() {
+            true => DEFAULT_FEE_RATE_BUCKET_BOUNDS.to_vec(),
+            false => request.bucket_bounds,
+        };
+        if !are_valid_fee_rate_bucket_bounds(&bucket_bounds) {
+            return Err(RpcError::InvalidFeeRateBucketBounds(MAX_FEE_RATE_BUCKET_BOUNDS));
+        }
+        let histogram = self.mining_manager.clone().fee_rate_histogram(bucket_bounds).await;
+        let buckets = histogram
+            .buckets()
+            .map(|(min_fee_rate, bucket)| RpcFeeRateBucket::new(min_fee_rate, bucket.transaction_count, bucket.total_mass))
+            .collect();
+        Ok(GetMempoolFeeHistogramResponse::new(buckets))
+    }
+
+    async fn get_relay_policy_call(&self, _request: GetRelayPolicyRequest) -> RpcResult<GetRelayPolicyResponse> {
+        let policy = self.mining_manager.relay_policy();
+        Ok(GetRelayPolicyResponse {
+            minimum_relay_fee_rate: policy.minimum_relay_transaction_fee,
+            minimum_output_value: policy.minimum_output_value,
+            pay_to_pubkey_dust_threshold: policy.pay_to_pubkey_dust_threshold,
+            accept_non_standard: policy.accept_non_standard,
+            maximum_ancestor_count: policy.maximum_ancestor_count,
+            maximum_descendant_count: policy.maximum_descendant_count,
+            maximum_chain_mass: policy.maximum_chain_mass,
+        })
+    }
+
+    async fn get_utxo_return_address_call(&self, request: GetUtxoReturnAddressRequest) -> RpcResult<GetUtxoReturnAddressResponse> {
+        if !self.config.txindex {
+            return Err(RpcError::NoTxIndex);
+        }
+        let session = self.consensus_manager.consensus().session().await;
+        let transaction = self.get_accepted_transaction(&session, request.transaction_id).await?;
+        // A coinbase transaction has no input and hence no return address
+        let previous_outpoint = transaction.inputs.first().ok_or(RpcError::NoReturnAddress(request.transaction_id))?.previous_outpoint;
+        let previous_transaction = self.get_accepted_transaction(&session, previous_outpoint.transaction_id).await?;
+        let script_public_key = &previous_transaction
+            .outputs
+            .get(previous_outpoint.index as usize)
+            .ok_or(RpcError::NoReturnAddress(request.transaction_id))?
+            .script_public_key;
+        let return_address = extract_script_pub_key_address(script_public_key, self.config.prefix())
+            .map_err(|_| RpcError::NoReturnAddress(request.transaction_id))?;
+        Ok(GetUtxoReturnAddressResponse::new(return_address))
+    }
+
+    async fn get_spending_transaction_call(
+        &self,
+        request: GetSpendingTransactionRequest,
+    ) -> RpcResult<GetSpendingTransactionResponse> {
+        if !self.config.txindex {
+            return Err(RpcError::NoTxIndex);
+        }
+        let txindex = self.txindex.clone().unwrap();
+        let transaction_id = txindex
+            .clone()
+            .get_spending_transaction_id(request.outpoint)
+            .await
+            .map_err(|e| RpcError::General(e.to_string()))?
+            .ok_or(RpcError::OutpointNotSpent(request.outpoint))?;
+        let location = txindex
+            .get_transaction_location(transaction_id)
+            .await
+            .map_err(|e| RpcError::General(e.to_string()))?
+            .ok_or(RpcError::TransactionNotFound(transaction_id))?;
+        Ok(GetSpendingTransactionResponse::new(transaction_id, location.accepting_block_hash))
+    }
+
+    async fn start_block_production_call(&self, request: StartBlockProductionRequest) -> RpcResult<StartBlockProductionResponse> {
+        if !self.is_unsafe_allowed() {
+            warn!("StartBlockProduction RPC command called while node in safe RPC mode -- ignoring.");
+            return Err(RpcError::UnavailableInSafeMode);
+        }
+        let block_producer = self.block_producer.as_ref().ok_or(RpcError::BlockProductionUnavailable)?;
+        block_producer.start_production(BlockProductionSettings {
+            pay_address: request.pay_address,
+            blocks_per_second: request.blocks_per_second,
+        })?;
+        Ok(StartBlockProductionResponse {})
+    }
+
+    async fn stop_block_production_call(&self, _request: StopBlockProductionRequest) -> RpcResult<StopBlockProductionResponse> {
+        if !self.is_unsafe_allowed() {
+            warn!("StopBlockProduction RPC command called while node in safe RPC mode -- ignoring.");
+            return Err(RpcError::UnavailableInSafeMode);
+        }
+        let block_producer = self.block_producer.as_ref().ok_or(RpcError::BlockProductionUnavailable)?;
+        let was_producing = block_producer.stop_production();
+        Ok(StopBlockProductionResponse { was_producing, blocks_produced: block_producer.blocks_produced() })
+    }
+
+    async fn reset_lifetime_metrics_call(&self, _request: ResetLifetimeMetricsRequest) -> RpcResult<ResetLifetimeMetricsResponse> {
+        if !self.is_unsafe_allowed() {
+            warn!("ResetLifetimeMetrics RPC command called while node in safe RPC mode -- ignoring.");
+            return Err(RpcError::UnavailableInSafeMode);
+        }
+        self.lifetime_metrics.reset().map_err(|err| RpcError::General(err.to_string()))?;
+        Ok(ResetLifetimeMetricsResponse {})
+    }
+
+    async fn get_system_info_call(&self, _request: GetSystemInfoRequest) -> RpcResult<GetSystemInfoResponse> {
+        let mut system = sysinfo::System::new();
+        system.refresh_memory();
+        Ok(GetSystemInfoResponse {
+            version: version().to_string(),
+            git_hash: git_hash().map(String::from),
+            cpu_cores: num_cpus::get() as u32,
+            total_memory: system.total_memory(),
+            system_id: self.flow_context.address_manager.lock().system_id().to_string(),
+        })
+    }
+
     async fn ping_call(&self, _: PingRequest) -> RpcResult<PingResponse> {
         Ok(PingResponse {})
     }
 
-    async fn get_headers_call(&self, _request: GetHeadersRequest) -> RpcResult<GetHeadersResponse> {
-        Err(RpcError::NotImplemented)
+    async fn get_headers_call(&self, request: GetHeadersRequest) -> RpcResult<GetHeadersResponse> {
+        let session = self.consensus_manager.consensus().session().await;
+        let limit = request.limit.min(MAX_HEADERS_LIMIT) as usize;
+        let headers = session.async_get_chain_headers(request.start_hash, limit, request.is_ascending).await?;
+        Ok(GetHeadersResponse::new(headers.iter().map(|header| (**header).clone()).collect()))
     }
 
     async fn get_block_dag_info_call(&self, _: GetBlockDagInfoRequest) -> RpcResult<GetBlockDagInfoResponse> {
@@ -625,7 +1332,7 @@ impl RpcApi for RpcCoreService {
         &self,
         request: EstimateNetworkHashesPerSecondRequest,
     ) -> RpcResult<EstimateNetworkHashesPerSecondResponse> {
-        if !self.config.unsafe_rpc && request.window_size > MAX_SAFE_WINDOW_SIZE {
+        if !self.is_unsafe_allowed() && request.window_size > MAX_SAFE_WINDOW_SIZE {
             return Err(RpcError::WindowSizeExceedingMaximum(request.window_size, MAX_SAFE_WINDOW_SIZE));
         }
         if request.window_size as u64 > self.config.pruning_depth {
@@ -653,7 +1360,7 @@ impl RpcApi for RpcCoreService {
     }
 
     async fn add_peer_call(&self, request: AddPeerRequest) -> RpcResult<AddPeerResponse> {
-        if !self.config.unsafe_rpc {
+        if !self.is_unsafe_allowed() {
             warn!("AddPeer RPC command called while node in safe RPC mode -- ignoring.");
             return Err(RpcError::UnavailableInSafeMode);
         }
@@ -672,7 +1379,7 @@ impl RpcApi for RpcCoreService {
     }
 
     async fn ban_call(&self, request: BanRequest) -> RpcResult<BanResponse> {
-        if !self.config.unsafe_rpc {
+        if !self.is_unsafe_allowed() {
             warn!("Ban RPC command called while node in safe RPC mode -- ignoring.");
             return Err(RpcError::UnavailableInSafeMode);
         }
@@ -689,7 +1396,7 @@ impl RpcApi for RpcCoreService {
     }
 
     async fn unban_call(&self, request: UnbanRequest) -> RpcResult<UnbanResponse> {
-        if !self.config.unsafe_rpc {
+        if !self.is_unsafe_allowed() {
             warn!("Unban RPC command called while node in safe RPC mode -- ignoring.");
             return Err(RpcError::UnavailableInSafeMode);
         }
@@ -702,14 +1409,15 @@ impl RpcApi for RpcCoreService {
         Ok(UnbanResponse {})
     }
 
-    async fn get_connected_peer_info_call(&self, _: GetConnectedPeerInfoRequest) -> RpcResult<GetConnectedPeerInfoResponse> {
+    async fn get_connected_peer_info_call(&self, request: GetConnectedPeerInfoRequest) -> RpcResult<GetConnectedPeerInfoResponse> {
         let peers = self.flow_context.hub().active_peers();
         let peer_info = self.protocol_converter.get_peers_info(&peers);
-        Ok(GetConnectedPeerInfoResponse::new(peer_info))
+        let peer_traffic = if request.include_traffic { self.protocol_converter.get_peers_traffic(&peers) } else { vec![] };
+        Ok(GetConnectedPeerInfoResponse::new(peer_info, peer_traffic))
     }
 
     async fn shutdown_call(&self, _: ShutdownRequest) -> RpcResult<ShutdownResponse> {
-        if !self.config.unsafe_rpc {
+        if !self.is_unsafe_allowed() {
             warn!("Shutdown RPC command called while node in safe RPC mode -- ignoring.");
             return Err(RpcError::UnavailableInSafeMode);
         }
@@ -729,7 +1437,7 @@ impl RpcApi for RpcCoreService {
         &self,
         _request: ResolveFinalityConflictRequest,
     ) -> RpcResult<ResolveFinalityConflictResponse> {
-        if !self.config.unsafe_rpc {
+        if !self.is_unsafe_allowed() {
             warn!("ResolveFinalityConflict RPC command called while node in safe RPC mode -- ignoring.");
             return Err(RpcError::UnavailableInSafeMode);
         }
@@ -767,6 +1475,10 @@ impl RpcApi for RpcCoreService {
             json_handshake_failures: self.wrpc_json_counters.handshake_failures.load(Ordering::Relaxed),
         });
 
+        let storage_stats = match req.consensus_metrics {
+            true => Some(self.consensus_manager.consensus().unguarded_session().async_get_storage_stats().await),
+            false => None,
+        };
         let consensus_metrics = req.consensus_metrics.then_some(ConsensusMetrics {
             blocks_submitted: self.processing_counters.blocks_submitted.load(Ordering::SeqCst),
             header_counts: self.processing_counters.header_counts.load(Ordering::SeqCst),
@@ -776,10 +1488,88 @@ impl RpcApi for RpcCoreService {
             chain_block_counts: self.processing_counters.chain_block_counts.load(Ordering::SeqCst),
             mass_counts: self.processing_counters.mass_counts.load(Ordering::SeqCst),
         });
+        let extended_consensus_metrics = storage_stats.map(|storage_stats| {
+            let tx_latency = self.mining_manager.transaction_latency_snapshot();
+            let inventory = self.flow_context.recent_inventory().counters().snapshot();
+            ExtendedConsensusMetrics {
+                tip_hashes_count: self.processing_counters.tips_count.load(Ordering::SeqCst),
+                tx_mempool_first_seen_count: tx_latency.mempool_first_seen_count,
+                tx_block_first_seen_count: tx_latency.block_first_seen_count,
+                tx_inclusion_latency_p50: tx_latency.inclusion.p50,
+                tx_inclusion_latency_p90: tx_latency.inclusion.p90,
+                tx_inclusion_latency_p99: tx_latency.inclusion.p99,
+                tx_propagation_latency_p50: tx_latency.propagation.p50,
+                tx_propagation_latency_p90: tx_latency.propagation.p90,
+                tx_propagation_latency_p99: tx_latency.propagation.p99,
+                storage_write_amplification: storage_stats.write_amplification,
+                storage_pending_compaction_bytes: storage_stats.pending_compaction_bytes,
+                storage_running_compactions: storage_stats.running_compactions,
+                storage_stall_seconds: storage_stats.stall_seconds,
+                storage_delayed_write_rate: storage_stats.delayed_write_rate,
+                storage_write_stopped: storage_stats.is_write_stopped,
+                rpc_blocks_submitted: self.processing_counters.rpc_blocks_submitted.load(Ordering::SeqCst),
+                relay_blocks_submitted: self.processing_counters.relay_blocks_submitted.load(Ordering::SeqCst),
+                ibd_blocks_submitted: self.processing_counters.ibd_blocks_submitted.load(Ordering::SeqCst),
+                integrity_check_rounds: self.integrity_counters.rounds.load(Ordering::SeqCst),
+                integrity_checked_blocks: self.integrity_counters.checked_blocks.load(Ordering::SeqCst),
+                integrity_violations: self.integrity_counters.violations.load(Ordering::SeqCst),
+                relay_block_inv_hits: inventory.block_hits,
+                relay_block_inv_misses: inventory.block_misses,
+                relay_transaction_inv_hits: inventory.transaction_hits,
+                relay_transaction_inv_misses: inventory.transaction_misses,
+            }
+        });
+
+        let lifetime_metrics = req.lifetime_metrics.then(|| {
+            let metrics = self.lifetime_metrics.metrics();
+            LifetimeMetrics {
+                since: metrics.since,
+                blocks_processed: metrics.blocks_processed,
+                txs_processed: metrics.txs_processed,
+                uptime_seconds: metrics.uptime_seconds,
+                starts: metrics.starts,
+                recent_runs: metrics
+                    .recent_runs
+                    .into_iter()
+                    .map(|run| LifetimeRunRecord { start_time: run.start_time, uptime_seconds: run.uptime_seconds })
+                    .collect(),
+            }
+        });
+
+        let method_metrics = if req.method_metrics { self.method_counters.snapshot() } else { vec![] };
+
+        let mut p2p_message_metrics: Vec<P2pMessageMetrics> = if req.p2p_message_metrics {
+            self.flow_context
+                .hub()
+                .bandwidth_by_message_type()
+                .into_iter()
+                .map(|(message_type, traffic)| P2pMessageMetrics {
+                    message_type: format!("{message_type:?}"),
+                    sent_messages: traffic.sent_messages,
+                    sent_bytes: traffic.sent_bytes,
+                    received_messages: traffic.received_messages,
+                    received_bytes: traffic.received_bytes,
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+        p2p_message_metrics.sort_by(|a, b| a.message_type.cmp(&b.message_type));
+
+        let listener_metrics = if req.listener_metrics { self.listener_metrics() } else { vec![] };
 
         let server_time = unix_now();
 
-        let response = GetMetricsResponse { server_time, process_metrics, consensus_metrics };
+        let response = GetMetricsResponse {
+            server_time,
+            process_metrics,
+            consensus_metrics,
+            lifetime_metrics,
+            method_metrics,
+            p2p_message_metrics,
+            extended_consensus_metrics,
+            listener_metrics,
+        };
 
         Ok(response)
     }
@@ -824,7 +1614,7 @@ impl RpcApi for RpcCoreService {
     /// Start sending notifications of some type to a listener.
     async fn start_notify(&self, id: ListenerId, scope: Scope) -> RpcResult<()> {
         match scope {
-            Scope::UtxosChanged(ref utxos_changed_scope) if !self.config.unsafe_rpc && utxos_changed_scope.addresses.is_empty() => {
+            Scope::UtxosChanged(ref utxos_changed_scope) if !self.is_unsafe_allowed() && utxos_changed_scope.addresses.is_empty() => {
                 // The subscription to blanket UtxosChanged notifications is restricted to unsafe mode only
                 // since the notifications yielded are highly resource intensive.
                 //
@@ -834,6 +1624,13 @@ impl RpcApi for RpcCoreService {
                 warn!("RPC subscription to blanket UtxosChanged called while node in safe RPC mode -- ignoring.");
                 Err(RpcError::UnavailableInSafeMode)
             }
+            Scope::AcceptedTransactions(ref accepted_transactions_scope)
+                if !self.is_unsafe_allowed() && accepted_transactions_scope.addresses.is_empty() =>
+            {
+                // Same as above, blanket AcceptedTransactions require fetching and converting every accepted transaction
+                warn!("RPC subscription to blanket AcceptedTransactions called while node in safe RPC mode -- ignoring.");
+                Err(RpcError::UnavailableInSafeMode)
+            }
             _ => {
                 self.notifier.clone().start_notify(id, scope).await?;
                 Ok(())
@@ -0,0 +1,67 @@
+//!
+//! Relay of the RPC notifications to external message queues
+//!
+
+use kaspa_core::{
+    task::service::{AsyncService, AsyncServiceFuture},
+    trace,
+};
+use kaspa_notify::{
+    error::Result,
+    notifier::Notifier,
+    scope::{BlockAddedScope, Scope, VirtualChainChangedScope},
+    sink::{DynNotificationSink, SinkRelay},
+};
+use kaspa_rpc_core::{notify::connection::ChannelConnection, Notification};
+use kaspa_utils::triggers::SingleTrigger;
+use std::sync::Arc;
+
+pub mod nats;
+
+const SINK_SERVICE: &str = "sink-service";
+
+/// Publishes the block added and virtual chain changed notifications of the RPC notifier to a sink
+pub struct SinkService {
+    relay: SinkRelay<Notification>,
+    shutdown: SingleTrigger,
+}
+
+impl SinkService {
+    pub fn new(sink: DynNotificationSink<Notification>, notifier: &Notifier<Notification, ChannelConnection>) -> Result<Self> {
+        let scopes = vec![Scope::BlockAdded(BlockAddedScope {}), Scope::VirtualChainChanged(VirtualChainChangedScope::new(true))];
+        Ok(Self { relay: SinkRelay::new(sink, notifier, scopes)?, shutdown: SingleTrigger::default() })
+    }
+}
+
+impl AsyncService for SinkService {
+    fn ident(self: Arc<Self>) -> &'static str {
+        SINK_SERVICE
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} starting", SINK_SERVICE);
+
+        // Prepare a shutdown signal receiver
+        let shutdown_signal = self.shutdown.listener.clone();
+
+        Box::pin(async move {
+            tokio::select! {
+                _ = self.relay.run() => {}
+                _ = shutdown_signal => self.relay.close(),
+            }
+            Ok(())
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", SINK_SERVICE);
+        self.shutdown.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            trace!("{} stopped", SINK_SERVICE);
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+use kaspa_core::{debug, warn};
+use kaspa_notify::{
+    error::{Error, Result},
+    sink::NotificationSink,
+};
+use kaspa_rpc_core::Notification;
+use std::{
+    io,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+        TcpStream,
+    },
+    sync::Mutex,
+    time::timeout,
+};
+
+const CONNECT: &[u8] = br#"CONNECT {"verbose":false,"pedantic":false,"name":"kaspad","lang":"rust","protocol":0}"#;
+
+/// Maximal duration of establishing the connection, including the server greeting
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+type SharedWriter = Arc<Mutex<OwnedWriteHalf>>;
+
+/// Publishes notifications as JSON messages to a NATS server, under the subjects `<prefix>.block-added`
+/// and `<prefix>.virtual-chain-changed`.
+///
+/// Only the core NATS protocol is spoken, with no authentication nor TLS. The connection is established
+/// lazily and re-established on the next publication after a failure. Connecting is bounded by a timeout and
+/// happens without holding the connection lock, so an unreachable server never stalls the publications for long.
+#[derive(Debug)]
+pub struct NatsSink {
+    address: String,
+    subject_prefix: String,
+    connection: Mutex<Option<SharedWriter>>,
+}
+
+impl NatsSink {
+    pub fn new(address: String, subject_prefix: String) -> Self {
+        Self { address, subject_prefix, connection: Mutex::new(None) }
+    }
+
+    async fn connect(&self) -> io::Result<SharedWriter> {
+        timeout(CONNECT_TIMEOUT, self.try_connect())
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, format!("connecting to {} timed out", self.address)))?
+    }
+
+    async fn try_connect(&self) -> io::Result<SharedWriter> {
+        let (reader, mut writer) = TcpStream::connect(&self.address).await?.into_split();
+
+        // The server greets with an INFO message before anything else
+        let mut reader = BufReader::new(reader);
+        let mut line = String::new();
+        reader.read_line(&mut line).await?;
+        if !line.starts_with("INFO") {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("unexpected NATS greeting: {}", line.trim_end())));
+        }
+        writer.write_all(&[CONNECT, b"\r\n"].concat()).await?;
+
+        let writer = Arc::new(Mutex::new(writer));
+        tokio::spawn(keep_alive(reader, Arc::downgrade(&writer)));
+        debug!("NATS sink connected to {}", self.address);
+        Ok(writer)
+    }
+}
+
+/// Answers the server pings for as long as the connection is in use
+async fn keep_alive(mut reader: BufReader<OwnedReadHalf>, writer: Weak<Mutex<OwnedWriteHalf>>) {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {}
+        }
+        if line.starts_with("PING") {
+            let Some(writer) = writer.upgrade() else { return };
+            if writer.lock().await.write_all(b"PONG\r\n").await.is_err() {
+                return;
+            }
+        } else if line.starts_with("-ERR") {
+            warn!("NATS server error: {}", line.trim_end());
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink<Notification> for NatsSink {
+    fn name(&self) -> &'static str {
+        "nats-sink"
+    }
+
+    async fn publish(&self, notification: Notification) -> Result<()> {
+        let (subject, payload) = match notification {
+            Notification::BlockAdded(ref notification) => ("block-added", serde_json::to_vec(notification)),
+            Notification::VirtualChainChanged(ref notification) => ("virtual-chain-changed", serde_json::to_vec(notification)),
+            _ => return Ok(()),
+        };
+        let payload = payload.map_err(|err| Error::General(err.to_string()))?;
+        let mut message = format!("PUB {}.{} {}\r\n", self.subject_prefix, subject, payload.len()).into_bytes();
+        message.extend(payload);
+        message.extend(b"\r\n");
+
+        let current = self.connection.lock().await.clone();
+        let writer = match current {
+            Some(writer) => writer,
+            None => {
+                let writer = self.connect().await.map_err(|err| Error::General(err.to_string()))?;
+                // Keep the connection established concurrently, if any, dropping ours
+                self.connection.lock().await.get_or_insert(writer).clone()
+            }
+        };
+        let result = writer.lock().await.write_all(&message).await;
+        if let Err(err) = result {
+            // Reconnect on the next publication, unless already reconnected
+            let mut connection = self.connection.lock().await;
+            if connection.as_ref().is_some_and(|current| Arc::ptr_eq(current, &writer)) {
+                *connection = None;
+            }
+            return Err(Error::General(err.to_string()));
+        }
+        Ok(())
+    }
+}
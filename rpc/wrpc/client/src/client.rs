@@ -3,7 +3,10 @@ use crate::imports::*;
 use crate::parse::parse_host;
 use kaspa_consensus_core::network::NetworkType;
 use kaspa_rpc_core::{
-    api::ctl::RpcCtl,
+    api::{
+        ctl::RpcCtl,
+        ops::{negotiate_rpc_api_version, RPC_API_VERSION},
+    },
     notify::collector::{RpcCoreCollector, RpcCoreConverter},
 };
 pub use kaspa_rpc_macros::build_wrpc_client_interface;
@@ -35,6 +38,7 @@ struct Inner {
     rpc_ctl: RpcCtl,
     background_services_running: Arc<AtomicBool>,
     service_ctl: DuplexChannel<()>,
+    rpc_api_version: Arc<Mutex<Option<[u16; 4]>>>,
 }
 
 impl Inner {
@@ -62,6 +66,9 @@ impl Inner {
             RpcApiOps::VirtualDaaScoreChangedNotification,
             RpcApiOps::PruningPointUtxoSetOverrideNotification,
             RpcApiOps::NewBlockTemplateNotification,
+            RpcApiOps::MempoolTransactionRemovedNotification,
+            RpcApiOps::AcceptedTransactionsNotification,
+            RpcApiOps::SyncStateChangedNotification,
         ]
         .into_iter()
         .for_each(|notification_op| {
@@ -101,6 +108,7 @@ impl Inner {
             rpc_ctl,
             service_ctl: DuplexChannel::unbounded(),
             background_services_running: Arc::new(AtomicBool::new(false)),
+            rpc_api_version: Arc::new(Mutex::new(None)),
         };
         Ok(client)
     }
@@ -113,6 +121,26 @@ impl Inner {
         self.notification_channel.receiver.close()
     }
 
+    async fn handshake(&self) -> Result<[u16; 4]> {
+        let response: ClientResult<HandshakeResponse> =
+            self.rpc_client.call(RpcApiOps::Handshake, HandshakeRequest::new(RPC_API_VERSION)).await;
+        let rpc_api_version = match response {
+            Ok(response) => response.negotiated_rpc_api_version,
+            Err(err) => {
+                log_trace!("[WrpcClient] handshake failed ({err}), falling back to the server info");
+                let response: GetServerInfoResponse = self.rpc_client.call(RpcApiOps::GetServerInfo, GetServerInfoRequest {}).await?;
+                let server_version = response.rpc_api_version;
+                negotiate_rpc_api_version(RPC_API_VERSION, server_version).ok_or_else(|| {
+                    Error::custom(format!(
+                        "incompatible RPC API version {server_version:?}, the client speaks version {RPC_API_VERSION:?}"
+                    ))
+                })?
+            }
+        };
+        self.rpc_api_version.lock().unwrap().replace(rpc_api_version);
+        Ok(rpc_api_version)
+    }
+
     /// Start sending notifications of some type to the client.
     async fn start_notify_to_client(&self, scope: Scope) -> RpcResult<()> {
         let _response: SubscribeResponse = self.rpc_client.call(RpcApiOps::Subscribe, scope).await.map_err(|err| err.to_string())?;
@@ -291,6 +319,21 @@ impl KaspaRpcClient {
         self.inner.notification_channel.receiver.clone()
     }
 
+    /// Negotiates the version of the RPC messages exchanged with the server, falling back to the
+    /// version reported by `GetServerInfo` for servers predating the handshake. Fails if the server
+    /// speaks an incompatible major version, in which case a client-side upgrade is required.
+    ///
+    /// The handshake is performed on every connection, calls of ops the server does not serve under
+    /// the negotiated version failing without reaching the server.
+    pub async fn handshake(&self) -> Result<[u16; 4]> {
+        self.inner.handshake().await
+    }
+
+    /// RPC API version negotiated by the last successful [`handshake`](Self::handshake) on the current connection
+    pub fn rpc_api_version(&self) -> Option<[u16; 4]> {
+        *self.inner.rpc_api_version.lock().unwrap()
+    }
+
     pub fn encoding(&self) -> Encoding {
         self.inner.encoding
     }
@@ -356,8 +399,15 @@ impl KaspaRpcClient {
                                 WrpcCtl::Open => {
                                     // inner.rpc_ctl.set_descriptor(Some(inner.rpc.url()));
                                     inner.rpc_ctl.signal_open().await.expect("(KaspaRpcClient) rpc_ctl.signal_open() error");
+                                    let inner = inner.clone();
+                                    spawn(async move {
+                                        if let Err(err) = inner.handshake().await {
+                                            log_warning!("[WrpcClient] RPC API version negotiation failed: {err}");
+                                        }
+                                    });
                                 }
                                 WrpcCtl::Close => {
+                                    inner.rpc_api_version.lock().unwrap().take();
                                     inner.rpc_ctl.signal_close().await.expect("(KaspaRpcClient) rpc_ctl.signal_close() error");
                                     // inner.rpc_ctl.set_descriptor(None);
                                 }
@@ -407,6 +457,29 @@ impl RpcApi for KaspaRpcClient {
             GetCoinSupply,
             GetConnectedPeerInfo,
             GetDaaScoreTimestampEstimate,
+            GetTransaction,
+            GetBlockValidationTrace,
+            GetHealth,
+            ReloadSettings,
+            GetCurrentDifficulty,
+            GetBlockDagTimingInfo,
+            GetUtxoSetChunk,
+            GetStateDigests,
+            GetDagBlocksAround,
+            GetBlockRelations,
+            GetTransactionAcceptanceProof,
+            InvalidateBlock,
+            ReconsiderBlock,
+            GetNetworkStats,
+            GetMempoolFeeHistogram,
+            GetRelayPolicy,
+            GetUtxoReturnAddress,
+            GetSpendingTransaction,
+            StartBlockProduction,
+            StopBlockProduction,
+            ResetLifetimeMetrics,
+            GetSystemInfo,
+            CheckTransaction,
             GetServerInfo,
             GetCurrentNetwork,
             GetHeaders,
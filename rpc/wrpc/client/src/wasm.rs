@@ -234,6 +234,30 @@ impl RpcClient {
         Ok(())
     }
 
+    /// Subscription to Accepted Transactions notifications
+    #[wasm_bindgen(js_name = subscribeAcceptedTransactions)]
+    pub async fn subscribe_accepted_transactions(&self, addresses: &JsValue) -> Result<()> {
+        let addresses = Array::from(addresses)
+            .to_vec()
+            .into_iter()
+            .map(|jsv| from_value(jsv).map_err(|err| JsError::new(&err.to_string())))
+            .collect::<std::result::Result<Vec<Address>, JsError>>()?;
+        self.client.start_notify(ListenerId::default(), Scope::AcceptedTransactions(AcceptedTransactionsScope { addresses })).await?;
+        Ok(())
+    }
+
+    /// Unsubscribe from Accepted Transactions notifications
+    #[wasm_bindgen(js_name = unsubscribeAcceptedTransactions)]
+    pub async fn unsubscribe_accepted_transactions(&self, addresses: &JsValue) -> Result<()> {
+        let addresses = Array::from(addresses)
+            .to_vec()
+            .into_iter()
+            .map(|jsv| from_value(jsv).map_err(|err| JsError::new(&err.to_string())))
+            .collect::<std::result::Result<Vec<Address>, JsError>>()?;
+        self.client.stop_notify(ListenerId::default(), Scope::AcceptedTransactions(AcceptedTransactionsScope { addresses })).await?;
+        Ok(())
+    }
+
     // scope variant with field functions
 
     #[wasm_bindgen(js_name = subscribeVirtualChainChanged)]
@@ -280,6 +304,9 @@ build_wrpc_wasm_bindgen_subscriptions!([
     VirtualDaaScoreChanged,
     PruningPointUtxoSetOverride,
     NewBlockTemplate,
+    MempoolTransactionRemoved,
+    //AcceptedTransactions, // can't used this here due to non-C-style enum variant
+    SyncStateChanged,
 ]);
 
 // Build RPC method invocation functions. This macro
@@ -315,6 +342,29 @@ build_wrpc_wasm_bindgen_interface!(
         GetBlocks,
         GetBlockTemplate,
         GetDaaScoreTimestampEstimate,
+        GetTransaction,
+        GetBlockValidationTrace,
+        GetHealth,
+        ReloadSettings,
+        GetCurrentDifficulty,
+        GetBlockDagTimingInfo,
+        GetUtxoSetChunk,
+        GetStateDigests,
+        GetDagBlocksAround,
+        GetBlockRelations,
+        GetTransactionAcceptanceProof,
+        InvalidateBlock,
+        ReconsiderBlock,
+        GetNetworkStats,
+        GetMempoolFeeHistogram,
+        GetRelayPolicy,
+        GetUtxoReturnAddress,
+        GetSpendingTransaction,
+        StartBlockProduction,
+        StopBlockProduction,
+        ResetLifetimeMetrics,
+        GetSystemInfo,
+        CheckTransaction,
         GetCurrentNetwork,
         GetHeaders,
         GetMempoolEntries,
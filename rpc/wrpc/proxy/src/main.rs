@@ -3,7 +3,7 @@ mod result;
 
 use clap::Parser;
 use kaspa_consensus_core::network::NetworkType;
-use kaspa_rpc_core::api::ops::RpcApiOps;
+use kaspa_rpc_core::api::{ops::RpcApiOps, profile::RpcProfile};
 use kaspa_wrpc_core::ServerCounters as WrpcServerCounters;
 use kaspa_wrpc_server::{
     connection::Connection,
@@ -75,6 +75,8 @@ async fn main() -> Result<()> {
         listen_address: interface.unwrap_or_else(|| format!("wrpc://127.0.0.1:{proxy_port}")),
         grpc_proxy_address: Some(grpc_proxy_address.unwrap_or_else(|| format!("grpc://127.0.0.1:{kaspad_port}"))),
         verbose,
+        // Unsafe methods are restricted by the gRPC server of the proxied node
+        profile: RpcProfile::Unsafe,
         // ..Options::default()
     });
     log_info!("");
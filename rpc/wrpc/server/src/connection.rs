@@ -8,9 +8,12 @@ use kaspa_notify::{
 };
 use kaspa_rpc_core::{api::ops::RpcApiOps, notify::mode::NotificationMode, Notification};
 use std::{
+    borrow::Cow,
     fmt::Debug,
     sync::{Arc, Mutex},
 };
+use tokio::sync::mpsc::{channel as mpsc_channel, Sender as MpscSender};
+use tungstenite::protocol::frame::{coding::CloseCode, CloseFrame};
 use workflow_log::log_trace;
 use workflow_rpc::{
     server::{prelude::*, result::Result as WrpcResult},
@@ -47,9 +50,14 @@ struct ConnectionInner {
     pub id: u64,
     pub peer: SocketAddr,
     pub messenger: Arc<Messenger>,
+    // Queue of the notifications not yet handed to the WebSocket sink, bounded so that a connection failing to keep up
+    // with its notifications gets detected by the notifier
+    pub notification_sender: MpscSender<Message>,
     pub grpc_client: Option<Arc<GrpcClient>>,
     // not using an atomic in case an Id will change type in the future...
     pub listener_id: Mutex<Option<ListenerId>>,
+    // RPC API version negotiated by a handshake, if the client performed one
+    pub rpc_api_version: Mutex<Option<[u16; 4]>>,
 }
 
 impl ConnectionInner {
@@ -81,7 +89,31 @@ impl Connection {
         assert!(grpc_client.is_none() || grpc_client.as_ref().unwrap().notification_mode() == NotificationMode::Direct);
         // Should a gRPC client be provided, no listener_id is required for subscriptions so the listener id is set to default
         let listener_id = Mutex::new(grpc_client.clone().map(|_| ListenerId::default()));
-        Connection { inner: Arc::new(ConnectionInner { id, peer: *peer, messenger, grpc_client, listener_id }) }
+        let rpc_api_version = Mutex::new(None);
+        let (notification_sender, mut notification_receiver) = mpsc_channel(Self::notification_channel_size());
+        let sink = messenger.clone();
+        tokio::spawn(async move {
+            while let Some(message) = notification_receiver.recv().await {
+                if sink.send_raw_message(message).is_err() {
+                    break;
+                }
+            }
+        });
+        Connection {
+            inner: Arc::new(ConnectionInner {
+                id,
+                peer: *peer,
+                messenger,
+                notification_sender,
+                grpc_client,
+                listener_id,
+                rpc_api_version,
+            }),
+        }
+    }
+
+    pub fn notification_channel_size() -> usize {
+        1024
     }
 
     /// Obtain the connection id
@@ -114,6 +146,15 @@ impl Connection {
         self.inner.listener_id.lock().unwrap().replace(listener_id);
     }
 
+    /// Get the RPC API version negotiated with the client, `None` if the client did not perform a handshake
+    pub fn rpc_api_version(&self) -> Option<[u16; 4]> {
+        *self.inner.rpc_api_version.lock().unwrap()
+    }
+
+    pub fn set_rpc_api_version(&self, rpc_api_version: [u16; 4]) {
+        self.inner.rpc_api_version.lock().unwrap().replace(rpc_api_version);
+    }
+
     pub fn peer(&self) -> &SocketAddr {
         &self.inner.peer
     }
@@ -149,7 +190,7 @@ impl ConnectionT for Connection {
     }
 
     async fn send(&self, message: Self::Message) -> core::result::Result<(), Self::Error> {
-        self.inner.send(message).map_err(|err| NotifyError::General(err.to_string()))
+        self.inner.notification_sender.send(message).await.map_err(|_| NotifyError::ConnectionClosed)
     }
 
     fn close(&self) -> bool {
@@ -166,6 +207,30 @@ impl ConnectionT for Connection {
     fn is_closed(&self) -> bool {
         self.messenger().sink().is_closed()
     }
+
+    fn queue_len(&self) -> usize {
+        self.inner.notification_sender.max_capacity() - self.inner.notification_sender.capacity()
+    }
+
+    fn queue_capacity(&self) -> Option<usize> {
+        Some(self.inner.notification_sender.max_capacity())
+    }
+
+    /// Closes the WebSocket with a policy violation close frame, bypassing the full notification queue, so that
+    /// the client learns why it got disconnected.
+    ///
+    /// Returns true if the connection got closed.
+    fn overflow(&self) -> bool {
+        if !self.is_closed() {
+            let frame = CloseFrame { code: CloseCode::Policy, reason: Cow::Borrowed("notifications were not consumed in time") };
+            if let Err(err) = self.inner.send(Message::Close(Some(frame))) {
+                log_trace!("Error closing connection {}: {}", self.peer(), err);
+            } else {
+                return true;
+            }
+        }
+        false
+    }
 }
 
 pub type ConnectionReference = Arc<Connection>;
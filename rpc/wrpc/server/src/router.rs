@@ -1,6 +1,9 @@
 use crate::{connection::*, server::*};
 use kaspa_notify::scope::Scope;
-use kaspa_rpc_core::{api::ops::RpcApiOps, prelude::*};
+use kaspa_rpc_core::{
+    api::ops::{negotiate_rpc_api_version, RpcApiOps, RPC_API_VERSION},
+    prelude::*,
+};
 use kaspa_rpc_macros::build_wrpc_server_interface;
 use std::sync::Arc;
 use workflow_rpc::server::prelude::*;
@@ -45,6 +48,29 @@ impl Router {
                 GetCoinSupply,
                 GetConnectedPeerInfo,
                 GetDaaScoreTimestampEstimate,
+                GetTransaction,
+                GetBlockValidationTrace,
+                GetHealth,
+                ReloadSettings,
+                GetCurrentDifficulty,
+                GetBlockDagTimingInfo,
+                GetUtxoSetChunk,
+                GetStateDigests,
+                GetDagBlocksAround,
+                GetBlockRelations,
+                GetTransactionAcceptanceProof,
+                InvalidateBlock,
+                ReconsiderBlock,
+                GetNetworkStats,
+                GetMempoolFeeHistogram,
+                GetRelayPolicy,
+                GetUtxoReturnAddress,
+                GetSpendingTransaction,
+                StartBlockProduction,
+                StopBlockProduction,
+                ResetLifetimeMetrics,
+                GetSystemInfo,
+                CheckTransaction,
                 GetServerInfo,
                 GetCurrentNetwork,
                 GetHeaders,
@@ -92,6 +118,22 @@ impl Router {
             }),
         );
 
+        interface.method(
+            RpcApiOps::Handshake,
+            workflow_rpc::server::Method::new(move |_manager: Server, connection: Connection, request: HandshakeRequest| {
+                Box::pin(async move {
+                    let negotiated = negotiate_rpc_api_version(RPC_API_VERSION, request.rpc_api_version).ok_or_else(|| {
+                        format!(
+                            "incompatible RPC API version {:?}, the server speaks version {:?}",
+                            request.rpc_api_version, RPC_API_VERSION
+                        )
+                    })?;
+                    connection.set_rpc_api_version(negotiated);
+                    Ok(HandshakeResponse::new(RPC_API_VERSION, negotiated))
+                })
+            }),
+        );
+
         Router { interface: Arc::new(interface), server_context }
     }
 }
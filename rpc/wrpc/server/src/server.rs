@@ -7,9 +7,13 @@ use crate::{
 use kaspa_grpc_client::GrpcClient;
 use kaspa_notify::{connection::ChannelType, events::EVENT_TYPE_ARRAY, notifier::Notifier, scope::Scope, subscriber::Subscriber};
 use kaspa_rpc_core::{
-    api::rpc::{DynRpcService, RpcApi},
+    api::{
+        profile::RpcProfile,
+        rpc::{DynRpcService, RpcApi},
+    },
+    metrics::RpcMethodCounters,
     notify::{channel::NotificationChannel, connection::ChannelConnection, mode::NotificationMode},
-    Notification, RpcResult,
+    Notification, RpcError, RpcResult,
 };
 use kaspa_rpc_service::service::RpcCoreService;
 use std::{
@@ -35,6 +39,7 @@ struct ServerInner {
     pub sockets: Mutex<HashMap<u64, Connection>>,
     pub rpc_core: Option<RpcCore>,
     pub options: Arc<Options>,
+    pub method_counters: Arc<RpcMethodCounters>,
 }
 
 #[derive(Clone)]
@@ -53,6 +58,9 @@ impl Server {
             "invalid setup: Server must exclusively get either a core service or a gRPC server address"
         );
 
+        // The counters are reported by the core service, a proxy keeping its own
+        let method_counters = core_service.as_ref().map(|service| service.method_counters()).unwrap_or_default();
+
         let rpc_core = if let Some(service) = core_service {
             // Prepare rpc service objects
             let notification_channel = NotificationChannel::default();
@@ -65,6 +73,7 @@ impl Server {
             let collector = Arc::new(WrpcServiceCollector::new(WRPC_SERVER, notification_channel.receiver(), converter));
             let subscriber = Arc::new(Subscriber::new(WRPC_SERVER, enabled_events, service.notifier(), listener_id));
             let wrpc_notifier = Arc::new(Notifier::new(WRPC_SERVER, enabled_events, vec![collector], vec![subscriber], tasks));
+            service.register_listener_metrics_source(wrpc_notifier.clone());
             Some(RpcCore { service, wrpc_notifier })
         } else {
             None
@@ -77,6 +86,7 @@ impl Server {
                 sockets: Mutex::new(HashMap::new()),
                 rpc_core,
                 options,
+                method_counters,
             }),
         }
     }
@@ -149,6 +159,9 @@ impl Server {
     }
 
     pub async fn start_notify(&self, connection: &Connection, scope: Scope) -> RpcResult<()> {
+        if !self.profile().allows_subscription(&scope) {
+            return Err(RpcError::UnavailableOnSafeListener);
+        }
         let listener_id = if let Some(listener_id) = connection.listener_id() {
             listener_id
         } else {
@@ -188,6 +201,14 @@ impl Server {
         self.inner.options.verbose
     }
 
+    pub fn profile(&self) -> RpcProfile {
+        self.inner.options.profile
+    }
+
+    pub fn method_counters(&self) -> &RpcMethodCounters {
+        &self.inner.method_counters
+    }
+
     pub async fn join(&self) -> Result<()> {
         if let Some(rpc_core) = &self.inner.rpc_core {
             // Wait for the internal notifier to stop
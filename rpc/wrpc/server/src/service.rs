@@ -5,7 +5,7 @@ use kaspa_core::{
     task::service::{AsyncService, AsyncServiceError, AsyncServiceFuture},
     trace, warn,
 };
-use kaspa_rpc_core::api::ops::RpcApiOps;
+use kaspa_rpc_core::api::{ops::RpcApiOps, profile::RpcProfile};
 use kaspa_rpc_service::service::RpcCoreService;
 use kaspa_utils::triggers::SingleTrigger;
 pub use kaspa_wrpc_core::ServerCounters;
@@ -22,11 +22,14 @@ pub struct Options {
     pub listen_address: String,
     pub grpc_proxy_address: Option<String>,
     pub verbose: bool,
+    /// The permission profile of the listener. Defaults to unsafe, leaving the restriction of unsafe methods to the
+    /// RPC core service, or to the gRPC server when proxying
+    pub profile: RpcProfile,
 }
 
 impl Default for Options {
     fn default() -> Self {
-        Options { listen_address: "127.0.0.1:17110".to_owned(), verbose: false, grpc_proxy_address: None }
+        Options { listen_address: "127.0.0.1:17110".to_owned(), verbose: false, grpc_proxy_address: None, profile: RpcProfile::Unsafe }
     }
 }
 
@@ -154,7 +157,7 @@ impl WrpcService {
         });
 
         // Spawn a task running the server
-        info!("WRPC Server starting on: {}", listen_address);
+        info!("WRPC Server starting on: {} ({} profile)", listen_address, self.options.profile);
         tokio::spawn(async move {
             let config = WebSocketConfig { max_message_size: Some(MAX_WRPC_MESSAGE_SIZE), ..Default::default() };
             let serve_result = self.server.listen(&listen_address, Some(config)).await;
@@ -23,12 +23,21 @@ use kaspa_core::{info, task::service::AsyncService, task::tick::TickService, tim
 use kaspa_database::prelude::ConnBuilder;
 use kaspa_database::{create_temp_db, load_existing_db};
 use kaspa_hashes::Hash;
-use kaspa_perf_monitor::builder::Builder;
+use kaspa_perf_monitor::{builder::Builder, counters::CountersSnapshot};
 use kaspa_utils::fd_budget;
 use simulator::network::KaspaNetworkSimulator;
-use std::{collections::VecDeque, sync::Arc, time::Duration};
+use soak::{spawn_soak_checker, SoakConfig};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 pub mod simulator;
+mod soak;
 
 /// Kaspa Network Simulator
 #[derive(Parser, Debug)]
@@ -46,6 +55,11 @@ struct Args {
     #[arg(short, long, default_value_t = 1)]
     miners: u64,
 
+    /// Seed of the mining intervals, making the DAG topology reproducible across runs with the same args (random if
+    /// not specified). See also `kaspa_consensus::dag_generator` for generating topologies without running consensus
+    #[arg(long)]
+    seed: Option<u64>,
+
     /// Target transactions per block
     #[arg(short, long, default_value_t = 200)]
     tpb: u64,
@@ -93,6 +107,33 @@ struct Args {
     /// Use testnet-11 consensus params
     #[arg(long, default_value_t = false)]
     testnet11: bool,
+
+    /// Use the hash-rate independent testing params (tiny DAA windows, K derived from --bps) instead of
+    /// devnet params adjusted to the simulation args. Useful for quickly building large DAGs
+    #[arg(long, default_value_t = false, conflicts_with = "testnet11")]
+    test_params: bool,
+
+    /// Use the high-throughput simnet params preset for the given BPS (supported values: 10, 32). Overrides --bps
+    #[arg(long, conflicts_with_all = ["testnet11", "test_params"])]
+    bps_preset: Option<u64>,
+
+    /// Audit mode: assert that the validation throughput keeps up with the simulated BPS and that the peak
+    /// resident memory remains within --audit-memory-budget (implies --perf-metrics)
+    #[arg(long, default_value_t = false, conflicts_with = "test_pruning")]
+    audit: bool,
+    /// The resident memory budget enforced by audit and soak modes (in MB)
+    #[arg(long, default_value_t = 8192)]
+    audit_memory_budget: u64,
+
+    /// Soak mode: run the simulation for the given number of (wall-clock) hours, periodically asserting consensus
+    /// invariants and that the peak resident memory remains within --audit-memory-budget (implies --perf-metrics).
+    /// On failure, a summary of the consensus state is dumped to --output-dir (or the working directory)
+    #[arg(long, conflicts_with_all = ["test_pruning", "input_dir"])]
+    soak_hours: Option<f64>,
+    /// The interval between the invariant checks of soak mode (in seconds)
+    #[arg(long, default_value_t = 300)]
+    soak_check_interval_sec: u64,
+
     /// Enable performance metrics: cpu, memory, disk io usage
     #[arg(long, default_value_t = false)]
     perf_metrics: bool,
@@ -136,9 +177,12 @@ fn main() {
 fn main_impl(mut args: Args) {
     let rt = tokio::runtime::Runtime::new().unwrap();
 
-    let stop_perf_monitor = args.perf_metrics.then(|| {
+    let peak_resident_set_size = Arc::new(AtomicU64::new(0));
+    let stop_perf_monitor = (args.perf_metrics || args.audit || args.soak_hours.is_some()).then(|| {
         let ts = Arc::new(TickService::new());
-        let cb = move |counters| {
+        let peak_resident_set_size = peak_resident_set_size.clone();
+        let cb = move |counters: CountersSnapshot| {
+            peak_resident_set_size.fetch_max(counters.resident_set_size, Ordering::Relaxed);
             trace!("metrics: {:?}", counters);
             #[cfg(feature = "heap")]
             trace!("heap stats: {:?}", dhat::HeapStats::get());
@@ -162,8 +206,16 @@ fn main_impl(mut args: Args) {
             args.miners
         );
     }
-    args.bps = if args.testnet11 { Testnet11Bps::bps() as f64 } else { args.bps };
-    let params = if args.testnet11 { TESTNET11_PARAMS } else { DEVNET_PARAMS };
+    args.bps = if args.testnet11 { Testnet11Bps::bps() as f64 } else { args.bps_preset.map_or(args.bps, |bps| bps as f64) };
+    let params = if args.testnet11 {
+        TESTNET11_PARAMS
+    } else if let Some(bps) = args.bps_preset {
+        Params::high_bps_preset(bps).unwrap_or_else(|| panic!("BPS preset {} is not supported", bps))
+    } else if args.test_params {
+        Params::for_testing(args.bps as u64)
+    } else {
+        DEVNET_PARAMS
+    };
     let mut builder = ConfigBuilder::new(params)
         .apply_args(|config| apply_args_to_consensus_params(&args, &mut config.params))
         .apply_args(|config| apply_args_to_perf_params(&args, &mut config.perf))
@@ -207,23 +259,45 @@ fn main_impl(mut args: Args) {
         ));
         (consensus, lifetime)
     } else {
-        let until = if args.target_blocks.is_none() { config.genesis.timestamp + args.sim_time * 1000 } else { u64::MAX }; // milliseconds
+        let until = if args.target_blocks.is_none() && args.soak_hours.is_none() {
+            config.genesis.timestamp + args.sim_time * 1000
+        } else {
+            u64::MAX
+        }; // milliseconds
+        let dump_dir = args.output_dir.clone().unwrap_or_else(|| ".".to_string());
         let mut sim = KaspaNetworkSimulator::new(args.delay, args.bps, args.target_blocks, config.clone(), args.output_dir);
-        let (consensus, handles, lifetime) = sim
-            .init(
-                args.miners,
-                args.tpb,
-                args.rocksdb_stats,
-                args.rocksdb_stats_period_sec,
-                args.rocksdb_files_limit,
-                args.rocksdb_mem_budget,
-            )
-            .run(until);
+        sim.init(
+            args.miners,
+            args.seed,
+            args.tpb,
+            args.rocksdb_stats,
+            args.rocksdb_stats_period_sec,
+            args.rocksdb_files_limit,
+            args.rocksdb_mem_budget,
+        );
+        let soak_checker = args.soak_hours.map(|hours| {
+            let config = SoakConfig {
+                duration: Duration::from_secs_f64(hours * 3600.0),
+                check_interval: Duration::from_secs(args.soak_check_interval_sec),
+                memory_budget_mb: args.audit_memory_budget,
+                dump_dir: dump_dir.into(),
+            };
+            spawn_soak_checker(sim.main_consensus(), config, peak_resident_set_size.clone(), sim.halt_signal())
+        });
+        let halt = sim.halt_signal();
+        let (consensus, handles, lifetime) = sim.run(until);
+        if let Some(soak_checker) = soak_checker {
+            halt.store(true, Ordering::Relaxed);
+            match soak_checker.join().unwrap() {
+                Ok(checks) => info!("[Soak] all {checks} invariant checks passed"),
+                Err(violation) => panic!("soak failed: {violation}"),
+            }
+        }
         consensus.shutdown(handles);
         (consensus, lifetime)
     };
 
-    if args.test_pruning {
+    if args.test_pruning || args.soak_hours.is_some() {
         drop(consensus);
         return;
     }
@@ -242,11 +316,14 @@ fn main_impl(mut args: Args) {
         unix_now(),
     ));
     let handles2 = consensus2.run_processors();
-    rt.block_on(validate(&consensus, &consensus2, &config, args.delay, args.bps));
+    let block_processing_rate = rt.block_on(validate(&consensus, &consensus2, &config, args.delay, args.bps));
     consensus2.shutdown(handles2);
     if let Some(stop_perf_monitor) = stop_perf_monitor {
         _ = rt.block_on(stop_perf_monitor);
     }
+    if args.audit {
+        audit(block_processing_rate, args.bps, peak_resident_set_size.load(Ordering::Relaxed), args.audit_memory_budget);
+    }
     drop(consensus);
 }
 
@@ -262,6 +339,13 @@ fn apply_args_to_consensus_params(args: &Args, params: &mut Params) {
             params.difficulty_window_size(0),
             params.past_median_time_window_size(0),
         );
+    } else if args.bps_preset.is_some() || args.test_params {
+        info!(
+            "Using preset params (GHOSTDAG K={}, DAA window size={}, Median time window size={})",
+            params.ghostdag_k,
+            params.difficulty_window_size(0),
+            params.past_median_time_window_size(0),
+        );
     } else {
         let k = u64::max(calculate_ghostdag_k(2.0 * args.delay * args.bps, 0.05), params.ghostdag_k as u64);
         let k = u64::min(k, KType::MAX as u64) as KType; // Clamp to KType::MAX
@@ -315,7 +399,29 @@ fn apply_args_to_perf_params(args: &Args, perf_params: &mut PerfParams) {
     }
 }
 
-async fn validate(src_consensus: &Consensus, dst_consensus: &Consensus, params: &Params, delay: f64, bps: f64) {
+/// Asserts that the node keeps up with the simulated block rate within the memory budget
+fn audit(block_processing_rate: f64, bps: f64, peak_resident_set_size: u64, memory_budget_mb: u64) {
+    let peak_memory_mb = peak_resident_set_size / (1024 * 1024);
+    info!(
+        "[Audit] block processing rate: {:.2} (b/s), required: {:.2} (b/s); peak resident memory: {} MB, budget: {} MB",
+        block_processing_rate, bps, peak_memory_mb, memory_budget_mb
+    );
+    assert!(
+        block_processing_rate >= bps,
+        "audit failed: block processing rate {:.2} (b/s) is lower than the simulated BPS {}",
+        block_processing_rate,
+        bps
+    );
+    assert!(
+        peak_memory_mb <= memory_budget_mb,
+        "audit failed: peak resident memory {} MB exceeds the budget of {} MB",
+        peak_memory_mb,
+        memory_budget_mb
+    );
+}
+
+/// Validates the simulated DAG in a fresh consensus and returns the block processing rate
+async fn validate(src_consensus: &Consensus, dst_consensus: &Consensus, params: &Params, delay: f64, bps: f64) -> f64 {
     let hashes = topologically_ordered_hashes(src_consensus, params.genesis.hash);
     let num_blocks = hashes.len();
     let num_txs = print_stats(src_consensus, &hashes, delay, bps, params.ghostdag_k);
@@ -346,6 +452,7 @@ async fn validate(src_consensus: &Consensus, dst_consensus: &Consensus, params:
         num_blocks as f64 / elapsed.as_secs_f64(),
         num_txs as f64 / elapsed.as_secs_f64(),
     );
+    num_blocks as f64 / elapsed.as_secs_f64()
 }
 
 fn submit_chunk(
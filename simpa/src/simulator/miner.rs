@@ -14,12 +14,13 @@ use kaspa_consensus_core::tx::{
 use kaspa_consensus_core::utxo::utxo_view::UtxoView;
 use kaspa_core::trace;
 use kaspa_utils::sim::{Environment, Process, Resumption, Suspension};
-use rand::rngs::ThreadRng;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use rand_distr::{Distribution, Exp};
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use std::cmp::max;
 use std::iter::once;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 struct OnetimeTxSelector {
@@ -63,7 +64,7 @@ pub struct Miner {
 
     // Rand
     dist: Exp<f64>, // The time interval between Poisson(lambda) events distributes ~Exp(lambda)
-    rng: ThreadRng,
+    rng: ChaCha8Rng,
 
     // Counters
     num_blocks: u64,
@@ -73,6 +74,9 @@ pub struct Miner {
     target_txs_per_block: u64,
     target_blocks: Option<u64>,
     max_cached_outpoints: usize,
+
+    // Raised externally to end the simulation
+    halt: Arc<AtomicBool>,
 }
 
 impl Miner {
@@ -84,6 +88,7 @@ impl Miner {
         pk: secp256k1::PublicKey,
         consensus: Arc<Consensus>,
         params: &Params,
+        seed: Option<u64>,
         target_txs_per_block: u64,
         target_blocks: Option<u64>,
     ) -> Self {
@@ -98,15 +103,20 @@ impl Miner {
             secret_key: sk,
             possible_unspent_outpoints: IndexSet::new(),
             dist: Exp::new(bps * hashrate).unwrap(),
-            rng: rand::thread_rng(),
+            rng: seed.map_or_else(ChaCha8Rng::from_entropy, ChaCha8Rng::seed_from_u64),
             num_blocks: 0,
             sim_time: 0,
             target_txs_per_block,
             target_blocks,
             max_cached_outpoints: 10_000,
+            halt: Default::default(),
         }
     }
 
+    pub fn with_halt_signal(self, halt: Arc<AtomicBool>) -> Self {
+        Self { halt, ..self }
+    }
+
     fn build_new_block(&mut self, timestamp: u64) -> Block {
         let txs = self.build_txs();
         let nonce = self.id;
@@ -220,6 +230,9 @@ impl Miner {
 
     fn report_progress(&mut self, env: &mut Environment<Block>) -> bool {
         self.num_blocks += 1;
+        if self.halt.load(Ordering::Relaxed) {
+            return true;
+        }
         if let Some(target_blocks) = self.target_blocks {
             if self.num_blocks > target_blocks {
                 return true; // Exit
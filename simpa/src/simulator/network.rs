@@ -1,6 +1,7 @@
 use async_channel::unbounded;
 use kaspa_consensus_notify::root::ConsensusNotificationRoot;
 use kaspa_core::time::unix_now;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::thread::JoinHandle;
 
@@ -28,6 +29,7 @@ pub struct KaspaNetworkSimulator {
     bps: f64,                   // Blocks per second
     target_blocks: Option<u64>, // Target simulation blocks
     output_dir: Option<String>, // Possible permanent output directory
+    halt: Arc<AtomicBool>,      // Ends the simulation when raised
 }
 
 impl KaspaNetworkSimulator {
@@ -39,12 +41,14 @@ impl KaspaNetworkSimulator {
             config,
             target_blocks,
             output_dir,
+            halt: Default::default(),
         }
     }
 
     pub fn init(
         &mut self,
         num_miners: u64,
+        seed: Option<u64>,
         target_txs_per_block: u64,
         rocksdb_stats: bool,
         rocksdb_stats_period_sec: Option<u32>,
@@ -88,23 +92,37 @@ impl KaspaNetworkSimulator {
             ));
             let handles = consensus.run_processors();
             let (sk, pk) = secp.generate_keypair(&mut rng);
-            let miner_process = Box::new(Miner::new(
-                i,
-                self.bps,
-                1f64 / num_miners as f64,
-                sk,
-                pk,
-                consensus.clone(),
-                &self.config,
-                target_txs_per_block,
-                self.target_blocks,
-            ));
+            let miner_process = Box::new(
+                Miner::new(
+                    i,
+                    self.bps,
+                    1f64 / num_miners as f64,
+                    sk,
+                    pk,
+                    consensus.clone(),
+                    &self.config,
+                    seed.map(|seed| seed.wrapping_add(i)),
+                    target_txs_per_block,
+                    self.target_blocks,
+                )
+                .with_halt_signal(self.halt.clone()),
+            );
             self.simulation.register(i, miner_process);
             self.consensuses.push((consensus, handles, lifetime));
         }
         self
     }
 
+    /// The consensus instance returned by [`Self::run`], which can be inspected while the simulation runs
+    pub fn main_consensus(&self) -> Arc<Consensus> {
+        self.consensuses[0].0.clone()
+    }
+
+    /// A signal which ends the simulation when raised
+    pub fn halt_signal(&self) -> Arc<AtomicBool> {
+        self.halt.clone()
+    }
+
     pub fn run(&mut self, until: u64) -> ConsensusWrapper {
         self.simulation.run(until);
         for (consensus, handles, _) in self.consensuses.drain(1..) {
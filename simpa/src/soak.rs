@@ -0,0 +1,144 @@
+use kaspa_consensus::{
+    consensus::Consensus,
+    model::stores::{headers::HeaderStoreReader, virtual_state::VirtualStateStoreReader},
+};
+use kaspa_consensus_core::{api::ConsensusApi, muhash::MuHashExtensions};
+use kaspa_core::{info, time::unix_now};
+use kaspa_hashes::Hash;
+use kaspa_muhash::MuHash;
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+pub struct SoakConfig {
+    /// The wall-clock duration of the soak, after which the simulation is halted
+    pub duration: Duration,
+    pub check_interval: Duration,
+    pub memory_budget_mb: u64,
+    /// The directory state dumps are written to on failure
+    pub dump_dir: PathBuf,
+}
+
+/// Checks the consensus invariants which must hold at any point of a simulation
+struct InvariantChecker {
+    consensus: Arc<Consensus>,
+    memory_budget_mb: u64,
+    peak_resident_set_size: Arc<AtomicU64>,
+    finality_point: Option<Hash>,
+}
+
+impl InvariantChecker {
+    fn check(&mut self) -> Result<(), String> {
+        let _session = self.consensus.acquire_session();
+
+        // The virtual UTXO set matches the virtual multiset
+        let virtual_read = self.consensus.virtual_stores.read();
+        let mut multiset = MuHash::new();
+        for (outpoint, entry) in virtual_read.utxo_set.iterator().map(|r| r.unwrap()) {
+            multiset.add_utxo(&outpoint, &entry);
+        }
+        let expected = virtual_read.state.get().unwrap().multiset.clone().finalize();
+        drop(virtual_read);
+        if multiset.finalize() != expected {
+            return Err(format!("the virtual UTXO set does not match the virtual multiset {expected}"));
+        }
+
+        // The pruning point UTXO set matches the pruning point UTXO commitment (once fully advanced to it)
+        let pruning_point = self.consensus.pruning_point();
+        let pruning_utxoset_read = self.consensus.pruning_utxoset_stores.read();
+        if pruning_utxoset_read.utxoset_position().unwrap() == pruning_point {
+            let mut multiset = MuHash::new();
+            for (outpoint, entry) in pruning_utxoset_read.utxo_set.iterator().map(|r| r.unwrap()) {
+                multiset.add_utxo(&outpoint, &entry);
+            }
+            let commitment = self.consensus.headers_store.get_header(pruning_point).unwrap().utxo_commitment;
+            if multiset.finalize() != commitment {
+                return Err(format!("the pruning point UTXO set does not match the UTXO commitment of pruning point {pruning_point}"));
+            }
+        }
+        drop(pruning_utxoset_read);
+
+        // The previously observed finality point is never reorged out of the selected chain
+        let sink = self.consensus.get_sink();
+        if let Some(finality_point) = self.finality_point {
+            if !self.consensus.is_chain_ancestor_of(finality_point, sink).unwrap_or(false) {
+                return Err(format!("finality violated: finality point {finality_point} is not in the selected chain of sink {sink}"));
+            }
+        }
+        self.finality_point = Some(self.consensus.finality_point());
+
+        let peak_memory_mb = self.peak_resident_set_size.load(Ordering::Relaxed) / (1024 * 1024);
+        if peak_memory_mb > self.memory_budget_mb {
+            return Err(format!("peak resident memory {} MB exceeds the budget of {} MB", peak_memory_mb, self.memory_budget_mb));
+        }
+        Ok(())
+    }
+
+    /// Writes a summary of the consensus state to a file in `dir`, returning its path
+    fn dump_state(&self, dir: &Path, violation: &str) -> std::io::Result<PathBuf> {
+        let mut dump = String::new();
+        let sink = self.consensus.get_sink();
+        writeln!(dump, "violation: {violation}").unwrap();
+        writeln!(dump, "sink: {sink}").unwrap();
+        if let Ok(header) = self.consensus.headers_store.get_header(sink) {
+            writeln!(dump, "sink blue score: {}", header.blue_score).unwrap();
+            writeln!(dump, "sink utxo commitment: {}", header.utxo_commitment).unwrap();
+        }
+        writeln!(dump, "virtual daa score: {}", self.consensus.get_virtual_daa_score()).unwrap();
+        writeln!(dump, "virtual parents: {:?}", self.consensus.get_virtual_parents()).unwrap();
+        writeln!(dump, "finality point: {}", self.consensus.finality_point()).unwrap();
+        writeln!(dump, "previously observed finality point: {:?}", self.finality_point).unwrap();
+        writeln!(dump, "pruning point: {}", self.consensus.pruning_point()).unwrap();
+        writeln!(dump, "peak resident memory: {} bytes", self.peak_resident_set_size.load(Ordering::Relaxed)).unwrap();
+        let path = dir.join(format!("simpa-soak-failure-{}.txt", unix_now()));
+        std::fs::write(&path, dump)?;
+        Ok(path)
+    }
+}
+
+/// Spawns a thread periodically checking the invariants of `consensus` while the simulation runs, until either the
+/// soak duration elapses or an invariant is violated, both of which raise `halt`. A final check is performed once
+/// the simulation halted. Returns the number of checks performed or the first violation found
+pub fn spawn_soak_checker(
+    consensus: Arc<Consensus>,
+    config: SoakConfig,
+    peak_resident_set_size: Arc<AtomicU64>,
+    halt: Arc<AtomicBool>,
+) -> JoinHandle<Result<u64, String>> {
+    thread::spawn(move || {
+        let mut checker =
+            InvariantChecker { consensus, memory_budget_mb: config.memory_budget_mb, peak_resident_set_size, finality_point: None };
+        let start = Instant::now();
+        let mut checks = 0;
+        loop {
+            let next_check = Instant::now() + config.check_interval;
+            while Instant::now() < next_check && !halt.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_secs(1).min(config.check_interval));
+            }
+            let halted = halt.load(Ordering::Relaxed);
+            checks += 1;
+            if let Err(violation) = checker.check() {
+                halt.store(true, Ordering::Relaxed);
+                match checker.dump_state(&config.dump_dir, &violation) {
+                    Ok(path) => info!("[Soak] state dumped to {}", path.display()),
+                    Err(err) => info!("[Soak] failed dumping state: {err}"),
+                }
+                return Err(violation);
+            }
+            info!("[Soak] invariant check #{checks} passed after {:?}", start.elapsed());
+            if halted {
+                return Ok(checks);
+            }
+            if start.elapsed() >= config.duration {
+                halt.store(true, Ordering::Relaxed);
+            }
+        }
+    })
+}
@@ -10,20 +10,23 @@ use kaspa_consensus::consensus::test_consensus::{TestConsensus, TestConsensusFac
 use kaspa_consensus::model::stores::block_transactions::{
     BlockTransactionsStore, BlockTransactionsStoreReader, DbBlockTransactionsStore,
 };
+use kaspa_consensus::model::stores::block_window_cache::BlockWindowHeap;
 use kaspa_consensus::model::stores::ghostdag::{GhostdagStoreReader, KType as GhostdagKType};
 use kaspa_consensus::model::stores::headers::HeaderStoreReader;
 use kaspa_consensus::model::stores::reachability::DbReachabilityStore;
 use kaspa_consensus::model::stores::relations::DbRelationsStore;
 use kaspa_consensus::model::stores::selected_chain::SelectedChainStoreReader;
+use kaspa_consensus::model::stores::utxo_diffs::UtxoDiffsStoreReader;
 use kaspa_consensus::params::{Params, DEVNET_PARAMS, MAINNET_PARAMS, MAX_DIFFICULTY_TARGET, MAX_DIFFICULTY_TARGET_AS_F64};
 use kaspa_consensus::pipeline::monitor::ConsensusMonitor;
 use kaspa_consensus::pipeline::ProcessingCounters;
 use kaspa_consensus::processes::reachability::tests::{DagBlock, DagBuilder, StoreValidationExtensions};
 use kaspa_consensus::processes::window::{WindowManager, WindowType};
 use kaspa_consensus_core::api::{BlockValidationFutures, ConsensusApi};
-use kaspa_consensus_core::block::Block;
+use kaspa_consensus_core::block::{Block, BlockOrigin};
 use kaspa_consensus_core::blockhash::new_unique;
 use kaspa_consensus_core::blockstatus::BlockStatus;
+use kaspa_consensus_core::coinbase::MinerData;
 use kaspa_consensus_core::constants::BLOCK_VERSION;
 use kaspa_consensus_core::errors::block::{BlockProcessResult, RuleError};
 use kaspa_consensus_core::header::Header;
@@ -31,7 +34,8 @@ use kaspa_consensus_core::network::{NetworkId, NetworkType::Mainnet};
 use kaspa_consensus_core::subnets::SubnetworkId;
 use kaspa_consensus_core::trusted::{ExternalGhostdagData, TrustedBlock};
 use kaspa_consensus_core::tx::{ScriptPublicKey, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry};
-use kaspa_consensus_core::{blockhash, hashing, BlockHashMap, BlueWorkType};
+use kaspa_consensus_core::utxo::utxo_diff::UtxoDiff;
+use kaspa_consensus_core::{blockhash, hashing, BlockHashMap, BlockHashSet, BlueWorkType};
 use kaspa_consensus_notify::root::ConsensusNotificationRoot;
 use kaspa_consensus_notify::service::NotifyService;
 use kaspa_consensusmanager::ConsensusManager;
@@ -48,7 +52,7 @@ use kaspa_core::signals::Shutdown;
 use kaspa_core::task::runtime::AsyncRuntime;
 use kaspa_core::{assert_match, info};
 use kaspa_database::create_temp_db;
-use kaspa_database::prelude::ConnBuilder;
+use kaspa_database::prelude::{ConnBuilder, DurabilityProfile};
 use kaspa_index_processor::service::IndexService;
 use kaspa_math::Uint256;
 use kaspa_muhash::MuHash;
@@ -197,6 +201,31 @@ async fn consensus_sanity_test() {
     consensus.shutdown(wait_handles);
 }
 
+#[tokio::test]
+async fn fast_params_dag_test() {
+    const WIDTH: u64 = 3;
+    const LEVELS: u64 = 334;
+    let params = Params::for_testing(10);
+    let config = ConfigBuilder::new(params).build();
+    let consensus = TestConsensus::new(&config).with_simulated_clock();
+    let wait_handles = consensus.init();
+
+    // Build a DAG of ~1000 blocks where each level merges all blocks of the previous one
+    let mut tips = vec![config.genesis.hash];
+    for level in 0..LEVELS {
+        let hashes = (1..=WIDTH).map(|i| Hash::from_u64_word(level * WIDTH + i)).collect_vec();
+        let futures = hashes.iter().map(|&hash| consensus.add_utxo_valid_block_with_parents(hash, tips.clone(), vec![])).collect_vec();
+        try_join_all(futures).await.unwrap();
+        tips = hashes;
+    }
+
+    let sink = consensus.get_sink();
+    assert!(tips.contains(&sink));
+    assert_eq!(consensus.ghostdag_store().get_blue_score(sink).unwrap(), WIDTH * (LEVELS - 1) + 1);
+
+    consensus.shutdown(wait_handles);
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct GhostdagTestDag {
     #[serde(rename = "K")]
@@ -383,6 +412,66 @@ async fn block_window_test() {
     consensus.shutdown(wait_handles);
 }
 
+#[tokio::test]
+async fn sampled_window_equivalence_test() {
+    // With a sample rate of 1 and equal window sizes, the sampled windows must be equivalent to the full windows
+    let build_config = |sampling: bool| {
+        ConfigBuilder::new(DEVNET_PARAMS)
+            .skip_proof_of_work()
+            .edit_consensus_params(|p| {
+                p.sampling_activation_daa_score = if sampling { 0 } else { u64::MAX };
+                p.legacy_difficulty_window_size = 64;
+                p.sampled_difficulty_window_size = 64;
+                p.difficulty_sample_rate = 1;
+                p.legacy_timestamp_deviation_tolerance = 16;
+                p.new_timestamp_deviation_tolerance = 16;
+                p.past_median_time_sampled_window_size = 31;
+                p.past_median_time_sample_rate = 1;
+            })
+            .build()
+    };
+    let full_config = build_config(false);
+    let sampled_config = build_config(true);
+    let full = TestConsensus::new(&full_config);
+    let sampled = TestConsensus::new(&sampled_config);
+    let (full_handles, sampled_handles) = (full.init(), sampled.init());
+    let window_hashes = |window: &BlockWindowHeap| window.blocks.iter().map(|block| block.0.hash).collect::<BlockHashSet>();
+
+    let mut tips = vec![full_config.genesis.hash];
+    let mut next_hash = 1u64;
+    for level in 0..150u64 {
+        // Alternate between levels of a single block and wider levels merging each other
+        let width = if level % 3 == 0 { 1 } else { 3 };
+        let mut level_hashes = vec![];
+        for _ in 0..width {
+            let hash = Hash::from_u64_word(next_hash);
+            next_hash += 1;
+            // Bits and timestamps are derived from the full windows and re-validated against the sampled windows
+            let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![]), vec![]);
+            let block = full.build_utxo_valid_block_with_parents(hash, tips.clone(), miner_data, vec![]).to_immutable();
+            full.validate_and_insert_block(block.clone()).virtual_state_task.await.unwrap();
+            sampled.validate_and_insert_block(block).virtual_state_task.await.unwrap();
+
+            let full_ghostdag = full.ghostdag_store().get_data(hash).unwrap();
+            let sampled_ghostdag = sampled.ghostdag_store().get_data(hash).unwrap();
+            let full_daa_window = full.window_manager().block_daa_window(&full_ghostdag).unwrap();
+            let sampled_daa_window = sampled.window_manager().block_daa_window(&sampled_ghostdag).unwrap();
+            assert_eq!(full_daa_window.daa_score, sampled_daa_window.daa_score);
+            assert_eq!(full_daa_window.mergeset_non_daa, sampled_daa_window.mergeset_non_daa);
+            assert_eq!(window_hashes(&full_daa_window.window), window_hashes(&sampled_daa_window.window));
+
+            let (_, full_window) = full.window_manager().calc_past_median_time(&full_ghostdag).unwrap();
+            let (_, sampled_window) = sampled.window_manager().calc_past_median_time(&sampled_ghostdag).unwrap();
+            assert_eq!(window_hashes(&full_window), window_hashes(&sampled_window));
+            level_hashes.push(hash);
+        }
+        tips = level_hashes;
+    }
+
+    full.shutdown(full_handles);
+    sampled.shutdown(sampled_handles);
+}
+
 #[tokio::test]
 async fn header_in_isolation_validation_test() {
     let config = Config::new(MAINNET_PARAMS);
@@ -501,6 +590,45 @@ async fn missing_parents_test() {
     consensus.shutdown(wait_handles);
 }
 
+#[tokio::test]
+async fn precheck_block_test() {
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    let block = consensus.build_block_with_parents(1.into(), vec![config.genesis.hash]);
+    assert_match!(consensus.precheck_block(&block.clone().to_immutable()), Ok(()));
+
+    let mut missing_parents_block = block.clone();
+    missing_parents_block.header.parents_by_level[0] = vec![0.into()];
+    assert_match!(consensus.precheck_block(&missing_parents_block.to_immutable()), Err(RuleError::MissingParents(_)));
+
+    let mut future_block = block;
+    future_block.header.timestamp = unix_now() + 3_600_000;
+    assert_match!(consensus.precheck_block(&future_block.to_immutable()), Err(RuleError::TimeTooFarIntoTheFuture(_, _)));
+
+    // Pre-checks do not queue the block
+    assert_eq!(consensus.get_block_status(1.into()), None);
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn block_origin_counters_test() {
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    let block = consensus.build_block_with_parents(1.into(), vec![config.genesis.hash]).to_immutable();
+    consensus.validate_and_insert_block_with_origin(block, BlockOrigin::Rpc).virtual_state_task.await.unwrap();
+    consensus.add_block_with_parents(2.into(), vec![1.into()]).await.unwrap();
+
+    let snapshot = consensus.processing_counters().snapshot();
+    assert_eq!(snapshot.blocks_submitted, 2);
+    assert_eq!(snapshot.rpc_blocks_submitted, 1);
+    assert_eq!((snapshot.relay_blocks_submitted, snapshot.ibd_blocks_submitted), (0, 0));
+
+    consensus.shutdown(wait_handles);
+}
+
 // Errors such as ErrTimeTooOld which happen after DAA and PoW validation should set the block
 // as a known invalid.
 #[tokio::test]
@@ -528,6 +656,31 @@ async fn known_invalid_test() {
     consensus.shutdown(wait_handles);
 }
 
+// Descendants of known-invalid blocks should be rejected at submission time, before entering the pipeline
+#[tokio::test]
+async fn known_invalid_ancestry_test() {
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    let mut block = consensus.build_block_with_parents(1.into(), vec![config.genesis.hash]);
+    block.header.timestamp -= 1;
+    assert_match!(
+        consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task.await,
+        Err(RuleError::TimeTooOld(_, _))
+    );
+
+    let child = Block::from_precomputed_hash(2.into(), vec![1.into()]);
+    assert_match!(consensus.validate_and_insert_block(child).block_task.await, Err(RuleError::InvalidParent(hash)) if hash == 1.into());
+    let grandchild = Block::from_precomputed_hash(3.into(), vec![2.into()]);
+    assert_match!(consensus.validate_and_insert_block(grandchild).block_task.await, Err(RuleError::InvalidParent(hash)) if hash == 2.into());
+
+    // Short-circuited blocks never reach the pipeline, hence they are not stored
+    assert_eq!(consensus.get_block_status(2.into()), None);
+    assert_eq!(consensus.get_block_status(3.into()), None);
+
+    consensus.shutdown(wait_handles);
+}
+
 #[tokio::test]
 async fn median_time_test() {
     struct Test {
@@ -788,6 +941,7 @@ impl KaspadGoParams {
             dns_seeders: &[],
             net: NetworkId { network_type: Mainnet, suffix: None },
             genesis: GENESIS,
+            genesis_allocations: &[],
             ghostdag_k: self.K,
             legacy_timestamp_deviation_tolerance: self.TimestampDeviationTolerance,
             new_timestamp_deviation_tolerance: self.TimestampDeviationTolerance,
@@ -807,6 +961,7 @@ impl KaspadGoParams {
             finality_depth,
             pruning_depth: 2 * finality_depth + 4 * self.MergeSetSizeLimit * self.K as u64 + 2 * self.K as u64 + 2,
             coinbase_payload_script_public_key_max_len: self.CoinbasePayloadScriptPublicKeyMaxLength,
+            coinbase_payload_script_public_key_max_version: MAINNET_PARAMS.coinbase_payload_script_public_key_max_version,
             max_coinbase_payload_len: self.MaxCoinbasePayloadLength,
             max_tx_inputs: MAINNET_PARAMS.max_tx_inputs,
             max_tx_outputs: MAINNET_PARAMS.max_tx_outputs,
@@ -822,6 +977,8 @@ impl KaspadGoParams {
             skip_proof_of_work: self.SkipProofOfWork,
             max_block_level: self.MaxBlockLevel,
             pruning_proof_m: self.PruningProofM,
+            enforce_script_policy: MAINNET_PARAMS.enforce_script_policy,
+            introspection_opcodes: MAINNET_PARAMS.introspection_opcodes,
         }
     }
 }
@@ -927,7 +1084,7 @@ async fn json_test(file_path: &str, concurrency: bool) {
     let (_utxoindex_db_lifetime, utxoindex_db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
     let consensus_manager = Arc::new(ConsensusManager::new(Arc::new(TestConsensusFactory::new(tc.clone()))));
     let utxoindex = UtxoIndex::new(consensus_manager.clone(), utxoindex_db).unwrap();
-    let index_service = Arc::new(IndexService::new(&notify_service.notifier(), Some(UtxoIndexProxy::new(utxoindex.clone()))));
+    let index_service = Arc::new(IndexService::new(&notify_service.notifier(), Some(UtxoIndexProxy::new(utxoindex.clone())), None));
 
     let async_runtime = Arc::new(AsyncRuntime::new(2));
     async_runtime.register(tick_service.clone());
@@ -1668,6 +1825,30 @@ async fn selected_chain_test() {
     assert!(consensus.selected_chain_store.read().get_by_index(3).is_err());
     assert_selected_chain_store_matches_virtual_chain(&consensus);
 
+    // Chain membership and chain paths are resolved through the selected chain store
+    assert!(consensus.is_chain_block(23.into()).unwrap());
+    assert!(!consensus.is_chain_block(21.into()).unwrap());
+    assert!(!consensus.is_chain_block(14.into()).unwrap());
+    let chain_path = consensus.get_virtual_chain_from_block(14.into()).unwrap();
+    assert_eq!(chain_path.removed, (8..15).rev().map(|i| i.into()).collect_vec());
+    assert_eq!(chain_path.added, vec![22.into(), 23.into()]);
+    let chain_path = consensus.get_virtual_chain_from_block(config.genesis.hash).unwrap();
+    assert!(chain_path.removed.is_empty());
+    assert_eq!(chain_path.added, vec![22.into(), 23.into()]);
+
+    // The UTXO diffs contributed by the mergeset blocks, in consensus order, compose the UTXO diff of the chain block
+    let mergeset_utxo_diffs = consensus.get_chain_block_mergeset_utxo_diffs(23.into()).unwrap();
+    assert_eq!(mergeset_utxo_diffs[0].block_hash, 22.into());
+    assert_eq!(
+        mergeset_utxo_diffs.iter().map(|x| x.block_hash).sorted().collect_vec(),
+        (15..23).map(Hash::from).sorted().collect_vec()
+    );
+    assert!(mergeset_utxo_diffs.iter().all(|x| x.is_blue));
+    let mut composed_utxo_diff = UtxoDiff::default();
+    mergeset_utxo_diffs.iter().for_each(|x| composed_utxo_diff.with_diff_in_place(&x.utxo_diff).unwrap());
+    assert_eq!(composed_utxo_diff, *consensus.utxo_diffs_store.get(23.into()).unwrap());
+    assert!(consensus.get_chain_block_mergeset_utxo_diffs(21.into()).is_err());
+
     consensus.shutdown(wait_handles);
 }
 
@@ -1711,6 +1892,7 @@ async fn staging_consensus_test() {
         counters,
         tx_script_cache_counters,
         200,
+        DurabilityProfile::Default,
     ));
     let consensus_manager = Arc::new(ConsensusManager::new(consensus_factory));
 
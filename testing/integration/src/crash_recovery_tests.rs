@@ -0,0 +1,197 @@
+//!
+//! Crash-consistency tests. Each test spawns a child test process building a chain over a permanent DB, which is
+//! killed mid-commit by a failpoint, and then verifies that a consensus reopened over the same DB recovers a
+//! consistent state and resumes processing. Last tests kill the header processor worker under the exit-on-panic hook
+//! of kaspad, and verify that it is restarted, and that its death is escalated once its restarts are exhausted while
+//! the following processors wind down.
+//!
+
+use async_channel::unbounded;
+use kaspa_consensus::config::{Config, ConfigBuilder};
+use kaspa_consensus::consensus::test_consensus::TestConsensus;
+use kaspa_consensus::model::stores::virtual_state::VirtualStateStoreReader;
+use kaspa_consensus::params::DEVNET_PARAMS;
+use kaspa_consensus::pipeline::header_processor::MAX_WORKER_RESTARTS;
+use kaspa_consensus_core::api::ConsensusApi;
+use kaspa_consensus_core::blockstatus::BlockStatus;
+use kaspa_consensus_core::muhash::MuHashExtensions;
+use kaspa_core::panic::configure_panic;
+use kaspa_database::prelude::ConnBuilder;
+use kaspa_database::utils::get_kaspa_tempdir;
+use kaspa_database::{create_permanent_db, load_existing_db};
+use kaspa_hashes::Hash;
+use kaspa_muhash::MuHash;
+use kaspa_utils::failpoint::FAILPOINTS_ENV_VAR;
+use std::process::Command;
+
+/// The DB directory of the child process. The child test returns immediately when it is not set
+const DB_DIR_ENV_VAR: &str = "KASPA_CRASH_RECOVERY_DB_DIR";
+
+/// The length of the chain built by the child, which is expected to be killed before completing it
+const CHAIN_LENGTH: u64 = 20;
+
+fn config() -> ConfigBuilder {
+    ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work()
+}
+
+/// Builds a chain over the DB found in [`DB_DIR_ENV_VAR`]. Only meant to be spawned by [`crash_and_recover`]
+#[tokio::test]
+#[ignore]
+async fn crash_recovery_child() {
+    let Ok(db_dir) = std::env::var(DB_DIR_ENV_VAR) else {
+        return;
+    };
+    let (_lifetime, db) = create_permanent_db!(db_dir, ConnBuilder::default().with_files_limit(10));
+    let config = config().build();
+    let consensus = TestConsensus::with_db(db, &config, unbounded().0);
+    let wait_handles = consensus.init();
+
+    let mut parent = config.genesis.hash;
+    for i in 1..=CHAIN_LENGTH {
+        let hash = Hash::from_u64_word(i);
+        consensus.add_utxo_valid_block_with_parents(hash, vec![parent], vec![]).await.unwrap();
+        parent = hash;
+    }
+    consensus.shutdown(wait_handles);
+}
+
+/// Runs the child process with `failpoint` configured to `actions`, and verifies the state it left behind
+async fn crash_and_recover(failpoint: &str, actions: &str) {
+    let tempdir = get_kaspa_tempdir();
+    let db_dir = tempdir.path().join("consensus");
+    let status = Command::new(std::env::current_exe().unwrap())
+        .args(["crash_recovery_tests::crash_recovery_child", "--exact", "--ignored", "--nocapture"])
+        .env(DB_DIR_ENV_VAR, &db_dir)
+        .env(FAILPOINTS_ENV_VAR, format!("{failpoint}={actions}"))
+        .status()
+        .unwrap();
+    assert!(!status.success(), "the child process was expected to be killed by failpoint {failpoint}");
+
+    let (_lifetime, db) = load_existing_db!(db_dir, ConnBuilder::default().with_files_limit(10));
+    let config: Config = config().skip_adding_genesis().build();
+    let consensus = TestConsensus::with_db(db, &config, unbounded().0);
+    let wait_handles = consensus.init();
+
+    // The selected chain is consistent down to the pruning point
+    let sink = consensus.get_sink();
+    let report = consensus.verify_chain_integrity(sink, usize::MAX).unwrap();
+    assert!(report.violations.is_empty(), "failpoint {failpoint}: {:?}", report.violations);
+
+    // The virtual UTXO set matches the virtual multiset
+    let virtual_read = consensus.virtual_stores.read();
+    let mut multiset = MuHash::new();
+    for (outpoint, entry) in virtual_read.utxo_set.iterator().map(|r| r.unwrap()) {
+        multiset.add_utxo(&outpoint, &entry);
+    }
+    assert_eq!(multiset.finalize(), virtual_read.state.get().unwrap().multiset.clone().finalize(), "failpoint {failpoint}");
+    drop(virtual_read);
+
+    // Processing resumes on top of the recovered sink. Hashes are chosen so as not to collide with blocks which
+    // might have been partially committed by the child
+    let mut parent = sink;
+    for i in 1..=5 {
+        let hash = Hash::from_u64_word(1000 + i);
+        let status = consensus.add_utxo_valid_block_with_parents(hash, vec![parent], vec![]).await.unwrap();
+        assert_eq!(status, BlockStatus::StatusUTXOValid, "failpoint {failpoint}");
+        parent = hash;
+    }
+    assert_eq!(consensus.get_sink(), parent);
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn header_commit_crash_recovery_test() {
+    crash_and_recover("header-processor-commit", "10*abort").await;
+}
+
+#[tokio::test]
+async fn body_commit_crash_recovery_test() {
+    crash_and_recover("body-processor-commit", "10*abort").await;
+}
+
+#[tokio::test]
+async fn utxo_state_commit_crash_recovery_test() {
+    crash_and_recover("virtual-processor-commit-utxo-state", "10*abort").await;
+}
+
+#[tokio::test]
+async fn virtual_state_commit_crash_recovery_test() {
+    crash_and_recover("virtual-processor-commit-virtual-state", "10*abort").await;
+}
+
+/// Builds a chain while the header processor worker dies a few times along the way. Only meant to be spawned by
+/// [`header_worker_restart_test`]
+#[tokio::test]
+#[ignore]
+async fn header_worker_restart_child() {
+    if std::env::var(FAILPOINTS_ENV_VAR).is_err() {
+        return;
+    }
+    // Any panic which is not recovered from exits the process with a failure
+    configure_panic();
+    let config = config().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    let mut parent = config.genesis.hash;
+    for i in 1..=CHAIN_LENGTH {
+        let hash = Hash::from_u64_word(i);
+        consensus.add_utxo_valid_block_with_parents(hash, vec![parent], vec![]).await.unwrap();
+        parent = hash;
+    }
+    assert_eq!(consensus.get_sink(), parent);
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn header_worker_restart_test() {
+    let status = Command::new(std::env::current_exe().unwrap())
+        .args(["crash_recovery_tests::header_worker_restart_child", "--exact", "--ignored", "--nocapture"])
+        .env(FAILPOINTS_ENV_VAR, format!("header-processor-worker={HEADER_WORKER_RUNS}*panic*{MAX_WORKER_RESTARTS}"))
+        .status()
+        .unwrap();
+    assert!(status.success(), "the header processor worker was expected to be restarted");
+}
+
+/// Builds a few blocks until the header processor worker starts dying on every run. Only meant to be spawned by
+/// [`header_worker_death_escalation_test`]
+#[tokio::test]
+#[ignore]
+async fn header_worker_death_child() {
+    if std::env::var(FAILPOINTS_ENV_VAR).is_err() {
+        return;
+    }
+    configure_panic();
+    let config = config().build();
+    let consensus = TestConsensus::new(&config);
+    let mut wait_handles = consensus.init();
+
+    // The worker loop runs once before receiving each block, so these blocks are processed before the worker dies
+    let mut parent = config.genesis.hash;
+    for i in 1..=HEADER_WORKER_RUNS {
+        let hash = Hash::from_u64_word(i);
+        consensus.add_utxo_valid_block_with_parents(hash, vec![parent], vec![]).await.unwrap();
+        parent = hash;
+    }
+
+    // The worker exhausts its restarts and escalates, while the following processors wind down gracefully
+    let header_processor = wait_handles.remove(0);
+    assert_eq!(header_processor.thread().name(), Some("header-processor"));
+    assert!(header_processor.join().is_err());
+    for handle in wait_handles {
+        handle.join().unwrap();
+    }
+}
+
+/// The number of header processor worker loop runs before the worker starts dying
+const HEADER_WORKER_RUNS: u64 = 5;
+
+#[tokio::test]
+async fn header_worker_death_escalation_test() {
+    let status = Command::new(std::env::current_exe().unwrap())
+        .args(["crash_recovery_tests::header_worker_death_child", "--exact", "--ignored", "--nocapture"])
+        .env(FAILPOINTS_ENV_VAR, format!("header-processor-worker={HEADER_WORKER_RUNS}*panic"))
+        .status()
+        .unwrap();
+    assert!(status.success(), "the header processor worker death was expected to be escalated");
+}
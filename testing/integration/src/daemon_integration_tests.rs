@@ -1,6 +1,8 @@
 use kaspa_addresses::Address;
+use kaspa_consensus_core::config::params::Params;
 use kaspa_consensusmanager::ConsensusManager;
 use kaspa_core::task::runtime::AsyncRuntime;
+use kaspa_faucet::faucet::Faucet;
 use kaspa_notify::scope::{Scope, VirtualDaaScoreChangedScope};
 use kaspa_rpc_core::{api::rpc::RpcApi, Notification};
 use kaspad_lib::args::Args;
@@ -100,6 +102,97 @@ async fn daemon_mining_test() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn daemon_block_production_test() {
+    kaspa_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut kaspad = Daemon::new_random_with_args(args, total_fd_limit);
+    let rpc_client = kaspad.start().await;
+
+    let pay_address = Address::new(kaspad.network.into(), kaspa_addresses::Version::PubKey, &[0; 32]);
+    rpc_client.start_block_production(pay_address, 20.0).await.unwrap();
+    let mut block_count = 0;
+    for _ in 0..100 {
+        block_count = rpc_client.get_block_dag_info().await.unwrap().block_count;
+        if block_count >= 5 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(block_count >= 5, "expected at least 5 produced blocks, got {block_count}");
+
+    let response = rpc_client.stop_block_production().await.unwrap();
+    assert!(response.was_producing);
+    assert!(response.blocks_produced >= 5);
+
+    // No further blocks are produced once the production has stopped and its last block has been processed
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let block_count = rpc_client.get_block_dag_info().await.unwrap().block_count;
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(rpc_client.get_block_dag_info().await.unwrap().block_count, block_count);
+    assert!(!rpc_client.stop_block_production().await.unwrap().was_producing);
+
+    rpc_client.disconnect().await.unwrap();
+    drop(rpc_client);
+    kaspad.shutdown();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn daemon_faucet_test() {
+    kaspa_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        simnet: true,
+        utxoindex: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut kaspad = Daemon::new_random_with_args(args, total_fd_limit);
+    let rpc_client = Arc::new(kaspad.start().await);
+
+    let keypair = secp256k1::KeyPair::from_seckey_slice(secp256k1::SECP256K1, &[1; 32]).unwrap();
+    let faucet = Faucet::try_new(rpc_client.clone(), keypair).await.unwrap();
+
+    // Mine until the first coinbase outputs paying to the faucet are mature
+    let coinbase_maturity = Params::from(faucet.network_id()).coinbase_maturity;
+    faucet.mine(coinbase_maturity + 10).await.unwrap();
+    assert!(faucet.balance().await.unwrap() > 0);
+
+    let recipient = Address::new(kaspad.network.into(), kaspa_addresses::Version::PubKey, &[2; 32]);
+    faucet.dispense(&recipient, 1_000_000).await.unwrap();
+
+    // Mine the transaction and a chain block accepting it
+    let miner = Address::new(kaspad.network.into(), kaspa_addresses::Version::PubKey, &[0; 32]);
+    for _ in 0..2 {
+        let template = rpc_client.get_block_template(miner.clone(), vec![]).await.unwrap();
+        rpc_client.submit_block(template.block, false).await.unwrap();
+    }
+    let mut balance = 0;
+    for _ in 0..50 {
+        balance = rpc_client.get_balance_by_address(recipient.clone()).await.unwrap();
+        if balance > 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(balance, 1_000_000);
+
+    rpc_client.disconnect().await.unwrap();
+    drop(faucet);
+    drop(rpc_client);
+    kaspad.shutdown();
+}
+
 // The following test runtime parameters are required for a graceful shutdown of the gRPC server
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn daemon_cleaning_test() {
@@ -6,6 +6,10 @@ pub mod consensus_integration_tests;
 #[cfg(test)]
 pub mod consensus_pipeline_tests;
 
+#[cfg(test)]
+#[cfg(feature = "failpoints")]
+pub mod crash_recovery_tests;
+
 #[cfg(test)]
 pub mod daemon_integration_tests;
 
@@ -7,12 +7,13 @@ use kaspa_consensus::params::SIMNET_GENESIS;
 use kaspa_consensus_core::{constants::MAX_SOMPI, subnets::SubnetworkId, tx::Transaction};
 use kaspa_core::info;
 use kaspa_grpc_core::ops::KaspadPayloadOps;
-use kaspa_hashes::Hash;
+use kaspa_hashes::{Hash, ZERO_HASH};
 use kaspa_notify::{
     connection::{ChannelConnection, ChannelType},
     scope::{
-        BlockAddedScope, FinalityConflictScope, NewBlockTemplateScope, PruningPointUtxoSetOverrideScope, Scope,
-        SinkBlueScoreChangedScope, UtxosChangedScope, VirtualChainChangedScope, VirtualDaaScoreChangedScope,
+        AcceptedTransactionsScope, BlockAddedScope, FinalityConflictScope, MempoolTransactionRemovedScope, NewBlockTemplateScope,
+        PruningPointUtxoSetOverrideScope, Scope, SinkBlueScoreChangedScope, SyncStateChangedScope, UtxosChangedScope,
+        VirtualChainChangedScope, VirtualDaaScoreChangedScope,
     },
 };
 use kaspa_rpc_core::{api::rpc::RpcApi, model::*, Notification};
@@ -48,7 +49,9 @@ async fn sanity_test() {
         enable_unsynced_mining: true,
         block_template_cache_lifetime: Some(0),
         utxoindex: true,
+        txindex: true,
         unsafe_rpc: true,
+        stale_template_daa_threshold: Some(0),
         ..Default::default()
     };
 
@@ -146,6 +149,15 @@ async fn sanity_test() {
                         .unwrap();
                     assert!(response.added_chain_block_hashes.contains(&block.header.hash));
                     assert!(response.removed_chain_block_hashes.is_empty());
+
+                    // A block mined over the now outdated template is rejected as stale, unless explicitly allowed
+                    let mut stale_block = block.clone();
+                    stale_block.header.nonce += 1;
+                    stale_block.header.finalize();
+                    let response = rpc_client.submit_block(stale_block.clone(), false).await.unwrap();
+                    assert_eq!(response.report, SubmitBlockReport::Reject(SubmitBlockRejectReason::StaleTemplate));
+                    let response = rpc_client.submit_block(stale_block, true).await.unwrap();
+                    assert_eq!(response.report, SubmitBlockReport::Success);
                 })
             }
 
@@ -197,6 +209,7 @@ async fn sanity_test() {
                     assert!(response.is_utxo_indexed);
                     assert!(response.has_message_id);
                     assert!(response.has_notify_command);
+                    assert!(!response.is_clock_drifted);
                 })
             }
 
@@ -247,8 +260,10 @@ async fn sanity_test() {
             KaspadPayloadOps::GetConnectedPeerInfo => {
                 let rpc_client = client.clone();
                 tst!(op, {
-                    let response = rpc_client.get_connected_peer_info_call(GetConnectedPeerInfoRequest {}).await.unwrap();
+                    let response =
+                        rpc_client.get_connected_peer_info_call(GetConnectedPeerInfoRequest { include_traffic: true }).await.unwrap();
                     assert!(response.peer_info.is_empty());
+                    assert!(response.peer_traffic.is_empty());
                 })
             }
 
@@ -299,6 +314,19 @@ async fn sanity_test() {
                 })
             }
 
+            KaspadPayloadOps::CheckTransaction => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // Build an erroneous transaction...
+                    let transaction = Transaction::new(0, vec![], vec![], 0, SubnetworkId::default(), 0, vec![]);
+                    let response = rpc_client.check_transaction((&transaction).into()).await.unwrap();
+                    // ...whose violated rule is reported without failing the call
+                    assert_eq!(response.transaction_id, transaction.id());
+                    assert!(response.rule_error.is_some());
+                    assert!(response.fee.is_none());
+                })
+            }
+
             KaspadPayloadOps::GetSubnetwork => {
                 let rpc_client = client.clone();
                 tst!(op, {
@@ -343,12 +371,15 @@ async fn sanity_test() {
             KaspadPayloadOps::GetHeaders => {
                 let rpc_client = client.clone();
                 tst!(op, {
-                    let response_result = rpc_client
-                        .get_headers_call(GetHeadersRequest { start_hash: SIMNET_GENESIS.hash, limit: 1, is_ascending: true })
-                        .await;
+                    let headers = rpc_client.get_headers(SIMNET_GENESIS.hash, 1, true).await.unwrap();
+                    assert_eq!(headers.len(), 1);
+                    assert_eq!(headers[0].hash, SIMNET_GENESIS.hash);
 
-                    // Err because it's currently unimplemented
-                    assert!(response_result.is_err());
+                    let dag_info = rpc_client.get_block_dag_info().await.unwrap();
+                    let headers = rpc_client.get_headers(dag_info.sink, 10, false).await.unwrap();
+                    assert_eq!(headers[0].hash, dag_info.sink);
+                    assert!(headers.len() <= 10);
+                    assert!(headers.windows(2).all(|w| w[0].blue_score > w[1].blue_score));
                 })
             }
 
@@ -454,32 +485,69 @@ async fn sanity_test() {
                 let rpc_client = client.clone();
                 tst!(op, {
                     let get_metrics_call_response = rpc_client
-                        .get_metrics_call(GetMetricsRequest { consensus_metrics: true, process_metrics: true })
+                        .get_metrics_call(GetMetricsRequest {
+                            consensus_metrics: true,
+                            process_metrics: true,
+                            lifetime_metrics: true,
+                            method_metrics: false,
+                            p2p_message_metrics: false,
+                            listener_metrics: false,
+                        })
                         .await
                         .unwrap();
                     assert!(get_metrics_call_response.process_metrics.is_some());
                     assert!(get_metrics_call_response.consensus_metrics.is_some());
+                    assert!(get_metrics_call_response.lifetime_metrics.is_some());
+                    assert!(get_metrics_call_response.method_metrics.is_empty());
 
                     let get_metrics_call_response = rpc_client
-                        .get_metrics_call(GetMetricsRequest { consensus_metrics: false, process_metrics: true })
+                        .get_metrics_call(GetMetricsRequest {
+                            consensus_metrics: false,
+                            process_metrics: true,
+                            lifetime_metrics: false,
+                            method_metrics: true,
+                            p2p_message_metrics: true,
+                            listener_metrics: false,
+                        })
                         .await
                         .unwrap();
                     assert!(get_metrics_call_response.process_metrics.is_some());
+                    // The first call above was measured
+                    assert!(get_metrics_call_response.method_metrics.iter().any(|metrics| metrics.method == "GetMetrics"));
                     assert!(get_metrics_call_response.consensus_metrics.is_none());
+                    assert!(get_metrics_call_response.lifetime_metrics.is_none());
 
                     let get_metrics_call_response = rpc_client
-                        .get_metrics_call(GetMetricsRequest { consensus_metrics: true, process_metrics: false })
+                        .get_metrics_call(GetMetricsRequest {
+                            consensus_metrics: true,
+                            process_metrics: false,
+                            lifetime_metrics: false,
+                            method_metrics: false,
+                            p2p_message_metrics: false,
+                            listener_metrics: false,
+                        })
                         .await
                         .unwrap();
                     assert!(get_metrics_call_response.process_metrics.is_none());
+                    assert!(get_metrics_call_response.method_metrics.is_empty());
+                    assert!(get_metrics_call_response.p2p_message_metrics.is_empty());
                     assert!(get_metrics_call_response.consensus_metrics.is_some());
+                    assert!(get_metrics_call_response.lifetime_metrics.is_none());
 
                     let get_metrics_call_response = rpc_client
-                        .get_metrics_call(GetMetricsRequest { consensus_metrics: false, process_metrics: false })
+                        .get_metrics_call(GetMetricsRequest {
+                            consensus_metrics: false,
+                            process_metrics: false,
+                            lifetime_metrics: true,
+                            method_metrics: false,
+                            p2p_message_metrics: false,
+                            listener_metrics: false,
+                        })
                         .await
                         .unwrap();
                     assert!(get_metrics_call_response.process_metrics.is_none());
                     assert!(get_metrics_call_response.consensus_metrics.is_none());
+                    assert!(get_metrics_call_response.lifetime_metrics.is_some());
                 })
             }
 
@@ -524,6 +592,269 @@ async fn sanity_test() {
                 })
             }
 
+            KaspadPayloadOps::GetTransaction => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // No transaction was accepted by the chain at this point, so the lookup is expected to fail
+                    let response_result =
+                        rpc_client.get_transaction_call(GetTransactionRequest { transaction_id: 0.into(), include_proof: true }).await;
+                    assert!(response_result.is_err());
+                })
+            }
+
+            KaspadPayloadOps::GetBlockValidationTrace => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // Validation tracing is disabled by default
+                    let response_result =
+                        rpc_client.get_block_validation_trace_call(GetBlockValidationTraceRequest { hash: 0.into() }).await;
+                    assert!(response_result.is_err());
+                })
+            }
+
+            KaspadPayloadOps::GetHealth => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let response = rpc_client.get_health_call(GetHealthRequest {}).await.unwrap();
+                    assert!(!response.services.is_empty());
+                    assert!(response.services.iter().any(|service| service.name == "p2p-service"));
+                })
+            }
+
+            KaspadPayloadOps::ReloadSettings => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let request = ReloadSettingsRequest {
+                        mempool_max_transactions: Some(100_000),
+                        mempool_max_orphans: Some(100),
+                        ..Default::default()
+                    };
+                    let response = rpc_client.reload_settings_call(request).await.unwrap();
+                    assert_eq!(response.mempool_max_transactions, 100_000);
+                    assert_eq!(response.mempool_max_orphans, 100);
+
+                    // Unset settings keep their current value
+                    let response = rpc_client.reload_settings_call(ReloadSettingsRequest::default()).await.unwrap();
+                    assert_eq!(response.mempool_max_transactions, 100_000);
+                    assert_eq!(response.mempool_max_orphans, 100);
+
+                    // An invalid request is rejected as a whole, leaving all settings unchanged
+                    let request = ReloadSettingsRequest {
+                        log_level: Some("info,kaspa_p2p_flows=loud".to_string()),
+                        mempool_max_orphans: Some(200),
+                        ..Default::default()
+                    };
+                    assert!(rpc_client.reload_settings_call(request).await.is_err());
+                    let request = ReloadSettingsRequest { mempool_max_transactions: Some(0), ..Default::default() };
+                    assert!(rpc_client.reload_settings_call(request).await.is_err());
+                    let response = rpc_client.reload_settings_call(ReloadSettingsRequest::default()).await.unwrap();
+                    assert_eq!(response.mempool_max_transactions, 100_000);
+                    assert_eq!(response.mempool_max_orphans, 100);
+                })
+            }
+
+            KaspadPayloadOps::GetCurrentDifficulty => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let response = rpc_client.get_current_difficulty_call(GetCurrentDifficultyRequest {}).await.unwrap();
+                    assert_ne!(response.bits, 0);
+                    let dag_info = rpc_client.get_block_dag_info().await.unwrap();
+                    assert_eq!(response.difficulty, dag_info.difficulty);
+                })
+            }
+
+            KaspadPayloadOps::GetBlockDagTimingInfo => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let dag_info = rpc_client.get_block_dag_info().await.unwrap();
+                    let response = rpc_client.get_block_dag_timing_info(dag_info.sink).await.unwrap();
+                    assert_eq!(response.bits, response.expected_bits);
+                    assert!(response.past_median_time <= response.timestamp);
+                })
+            }
+
+            KaspadPayloadOps::GetUtxoSetChunk => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let mut response = rpc_client.get_utxo_set_chunk(None).await.unwrap();
+                    let snapshot_daa_score = response.snapshot_daa_score;
+                    while let Some(cursor) = response.next_cursor.clone() {
+                        response = rpc_client.get_utxo_set_chunk(Some(cursor)).await.unwrap();
+                        assert_eq!(response.snapshot_daa_score, snapshot_daa_score);
+                    }
+                    assert!(rpc_client.get_utxo_set_chunk(Some("invalid".to_string())).await.is_err());
+                })
+            }
+
+            KaspadPayloadOps::GetStateDigests => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let response = rpc_client.get_state_digests(10).await.unwrap();
+                    assert!(response.interval > 0);
+                    assert!(response.digests.len() <= 10);
+                    assert!(response.digests.windows(2).all(|x| x[0].blue_score > x[1].blue_score));
+                    assert!(rpc_client.get_state_digests(u32::MAX).await.is_err());
+                })
+            }
+
+            KaspadPayloadOps::GetDagBlocksAround => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let dag_info = rpc_client.get_block_dag_info().await.unwrap();
+                    let response = rpc_client.get_dag_blocks_around(dag_info.sink, 2).await.unwrap();
+                    assert_eq!(response.blocks[0].hash, dag_info.sink);
+                    assert_eq!(response.blocks[0].distance, 0);
+                    assert!(response.blocks[0].is_chain_block);
+                    assert!(response.blocks.iter().all(|block| block.distance <= 2));
+                    assert!(rpc_client.get_dag_blocks_around(dag_info.sink, u32::MAX).await.is_err());
+                })
+            }
+
+            KaspadPayloadOps::GetBlockRelations => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let dag_info = rpc_client.get_block_dag_info().await.unwrap();
+                    let response = rpc_client.get_block_relations(dag_info.sink).await.unwrap();
+                    let block = rpc_client.get_block(dag_info.sink, false).await.unwrap();
+                    let verbose_data = block.verbose_data.unwrap();
+                    assert_eq!(response.selected_parent_hash, verbose_data.selected_parent_hash);
+                    assert_eq!(response.blue_score, verbose_data.blue_score);
+                    assert_eq!(response.children_hashes, verbose_data.children_hashes);
+                })
+            }
+
+            KaspadPayloadOps::GetTransactionAcceptanceProof => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // The sink accepts the coinbase transaction of its selected parent
+                    let dag_info = rpc_client.get_block_dag_info().await.unwrap();
+                    if dag_info.sink != SIMNET_GENESIS.hash {
+                        let relations = rpc_client.get_block_relations(dag_info.sink).await.unwrap();
+                        let selected_parent = rpc_client.get_block(relations.selected_parent_hash, true).await.unwrap();
+                        let coinbase_id = selected_parent.transactions[0].verbose_data.as_ref().unwrap().transaction_id;
+                        let proof = rpc_client.get_transaction_acceptance_proof(coinbase_id, Some(dag_info.sink)).await.unwrap();
+                        assert_eq!(proof.accepting_block_hash(), Some(dag_info.sink));
+                        assert_eq!(proof.verify(), Ok(dag_info.sink));
+                    }
+                    assert!(rpc_client.get_transaction_acceptance_proof(ZERO_HASH, Some(dag_info.sink)).await.is_err());
+                })
+            }
+
+            KaspadPayloadOps::InvalidateBlock => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // Blocks outside the future of the finality point cannot be invalidated
+                    assert!(rpc_client.invalidate_block(SIMNET_GENESIS.hash).await.is_err());
+                    assert!(rpc_client.invalidate_block(ZERO_HASH).await.is_err());
+                })
+            }
+
+            KaspadPayloadOps::ReconsiderBlock => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    assert!(rpc_client.reconsider_block(SIMNET_GENESIS.hash).await.is_err());
+                })
+            }
+
+            KaspadPayloadOps::GetNetworkStats => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let response = rpc_client.get_network_stats().await.unwrap();
+                    assert!(response.inter_arrival_p50 <= response.inter_arrival_max);
+                    assert!((0.0..=1.0).contains(&response.blue_ratio));
+                })
+            }
+
+            KaspadPayloadOps::GetMempoolFeeHistogram => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let buckets = rpc_client.get_mempool_fee_histogram(vec![1.0, 10.0]).await.unwrap();
+                    assert_eq!(buckets.len(), 3);
+                    assert_eq!(buckets[0].min_fee_rate, 0.0);
+                    assert!(rpc_client.get_mempool_fee_histogram(vec![10.0, 1.0]).await.is_err());
+                })
+            }
+
+            KaspadPayloadOps::GetRelayPolicy => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let response = rpc_client.get_relay_policy().await.unwrap();
+                    assert!(response.pay_to_pubkey_dust_threshold >= response.minimum_output_value);
+                })
+            }
+
+            KaspadPayloadOps::GetUtxoReturnAddress => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // No transaction was accepted by the chain at this point, so the lookup is expected to fail
+                    assert!(rpc_client.get_utxo_return_address(0.into()).await.is_err());
+                })
+            }
+
+            KaspadPayloadOps::GetSpendingTransaction => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let outpoint = RpcTransactionOutpoint::new(0.into(), 0);
+                    assert!(rpc_client.get_spending_transaction(outpoint).await.is_err());
+                })
+            }
+
+            KaspadPayloadOps::StartBlockProduction => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // Producing blocks is avoided here so as not to interfere with the other tests
+                    let pay_address = Address::new(Prefix::Simnet, Version::PubKey, &[0u8; 32]);
+                    assert!(rpc_client.start_block_production(pay_address, 0.0).await.is_err());
+                    let pay_address = Address::new(Prefix::Mainnet, Version::PubKey, &[0u8; 32]);
+                    assert!(rpc_client.start_block_production(pay_address, 1.0).await.is_err());
+                })
+            }
+
+            KaspadPayloadOps::StopBlockProduction => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let response = rpc_client.stop_block_production().await.unwrap();
+                    assert!(!response.was_producing);
+                    assert_eq!(response.blocks_produced, 0);
+                })
+            }
+
+            KaspadPayloadOps::ResetLifetimeMetrics => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    rpc_client.reset_lifetime_metrics().await.unwrap();
+                    let lifetime_metrics = rpc_client
+                        .get_metrics_call(GetMetricsRequest {
+                            process_metrics: false,
+                            consensus_metrics: false,
+                            lifetime_metrics: true,
+                            method_metrics: false,
+                            p2p_message_metrics: false,
+                            listener_metrics: false,
+                        })
+                        .await
+                        .unwrap()
+                        .lifetime_metrics
+                        .unwrap();
+                    assert_eq!(lifetime_metrics.starts, 1);
+                    assert_eq!(lifetime_metrics.recent_runs.len(), 1);
+                })
+            }
+
+            KaspadPayloadOps::GetSystemInfo => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let response = rpc_client.get_system_info().await.unwrap();
+                    assert!(!response.version.is_empty());
+                    assert!(response.cpu_cores > 0);
+                    assert!(response.total_memory > 0);
+
+                    // The system id is stable and unrelated to the p2p id
+                    assert_eq!(rpc_client.get_system_info().await.unwrap().system_id, response.system_id);
+                    assert_ne!(rpc_client.get_info().await.unwrap().p2p_id, response.system_id);
+                })
+            }
+
             KaspadPayloadOps::NotifyBlockAdded => {
                 let rpc_client = client.clone();
                 let id = listener_id;
@@ -540,6 +871,30 @@ async fn sanity_test() {
                 })
             }
 
+            KaspadPayloadOps::NotifyMempoolTransactionRemoved => {
+                let rpc_client = client.clone();
+                let id = listener_id;
+                tst!(op, {
+                    rpc_client.start_notify(id, MempoolTransactionRemovedScope {}.into()).await.unwrap();
+                })
+            }
+
+            KaspadPayloadOps::NotifySyncStateChanged => {
+                let rpc_client = client.clone();
+                let id = listener_id;
+                tst!(op, {
+                    rpc_client.start_notify(id, SyncStateChangedScope {}.into()).await.unwrap();
+                })
+            }
+
+            KaspadPayloadOps::NotifyAcceptedTransactions => {
+                let rpc_client = client.clone();
+                let id = listener_id;
+                tst!(op, {
+                    rpc_client.start_notify(id, AcceptedTransactionsScope::new(vec![]).into()).await.unwrap();
+                })
+            }
+
             KaspadPayloadOps::NotifyFinalityConflict => {
                 let rpc_client = client.clone();
                 let id = listener_id;
@@ -0,0 +1,23 @@
+//! Pinning of threads to CPU cores
+
+/// Pins the calling thread to the CPU core with index `core`. Returns whether the thread was pinned, which
+/// fails if the core is not available to the process. Pinning is only supported on Linux
+pub fn pin_current_thread(core: usize) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        if core >= libc::CPU_SETSIZE as usize {
+            return false;
+        }
+        // SAFETY: the set is a plain bit mask which is fully initialized before being passed to the syscall
+        unsafe {
+            let mut set: libc::cpu_set_t = std::mem::zeroed();
+            libc::CPU_SET(core, &mut set);
+            libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) == 0
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = core;
+        false
+    }
+}
@@ -0,0 +1,209 @@
+//! Exponential backoff with jitter for retrying connections.
+//!
+//! [`BackoffPolicy`] computes the delays, [`Backoff`] tracks the failures of a single connection and [`AddressBackoff`]
+//! tracks those of many addresses, putting an address which exhausted its attempts on cooldown. Time is given as
+//! milliseconds and randomness as a value in `[0, 1)` supplied by the caller, so that failure patterns can be simulated
+//! deterministically.
+
+use std::{collections::HashMap, hash::Hash, time::Duration};
+
+/// Parameters of an exponential backoff
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackoffPolicy {
+    /// The delay following the first failure
+    pub initial_delay: Duration,
+    /// The delay growth factor applied on each consecutive failure
+    pub multiplier: u32,
+    /// The upper bound of the delay, regardless of the number of failures
+    pub max_delay: Duration,
+    /// The fraction of the delay, between 0 and 1, which is randomly cut off in order to spread out the retries
+    /// of clients failing simultaneously
+    pub jitter: f64,
+    /// The number of consecutive failures after which no more attempts are made, `None` for retrying forever
+    pub max_attempts: Option<u32>,
+}
+
+impl BackoffPolicy {
+    pub const fn new(initial_delay: Duration, multiplier: u32, max_delay: Duration, jitter: f64, max_attempts: Option<u32>) -> Self {
+        Self { initial_delay, multiplier, max_delay, jitter, max_attempts }
+    }
+
+    /// Returns the delay following `failures` consecutive failures (at least one), before applying the jitter
+    pub fn base_delay(&self, failures: u32) -> Duration {
+        let factor = (self.multiplier as u64).saturating_pow(failures.saturating_sub(1));
+        let delay = (self.initial_delay.as_millis() as u64).saturating_mul(factor);
+        Duration::from_millis(delay).min(self.max_delay)
+    }
+
+    /// Returns the delay following `failures` consecutive failures, with the jitter applied according to `random`,
+    /// a value in `[0, 1)`
+    pub fn delay(&self, failures: u32, random: f64) -> Duration {
+        let cut = self.jitter.clamp(0.0, 1.0) * random.clamp(0.0, 1.0);
+        self.base_delay(failures).mul_f64(1.0 - cut)
+    }
+
+    /// Returns whether attempts are exhausted after `failures` consecutive failures
+    pub fn is_exhausted(&self, failures: u32) -> bool {
+        self.max_attempts.is_some_and(|max_attempts| failures >= max_attempts)
+    }
+}
+
+/// Tracks the consecutive failures of a single connection
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    policy: BackoffPolicy,
+    failures: u32,
+}
+
+impl Backoff {
+    pub fn new(policy: BackoffPolicy) -> Self {
+        Self { policy, failures: 0 }
+    }
+
+    /// Records a failed attempt and returns the delay before the next one, or `None` if attempts are exhausted
+    pub fn on_failure(&mut self, random: f64) -> Option<Duration> {
+        self.failures = self.failures.saturating_add(1);
+        (!self.policy.is_exhausted(self.failures)).then(|| self.policy.delay(self.failures, random))
+    }
+
+    /// Records a successful attempt, resetting the delays
+    pub fn on_success(&mut self) {
+        self.failures = 0;
+    }
+
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct AddressState {
+    failures: u32,
+    /// The time in milliseconds before which no attempt should be made
+    next_attempt: u64,
+}
+
+/// Tracks the consecutive failures of many addresses. An address which exhausted its attempts is put on cooldown,
+/// after which it is given a fresh series of attempts
+#[derive(Debug, Clone)]
+pub struct AddressBackoff<A> {
+    policy: BackoffPolicy,
+    cooldown: Duration,
+    states: HashMap<A, AddressState>,
+}
+
+impl<A: Hash + Eq> AddressBackoff<A> {
+    pub fn new(policy: BackoffPolicy, cooldown: Duration) -> Self {
+        Self { policy, cooldown, states: HashMap::new() }
+    }
+
+    /// Returns whether `address` may be attempted at time `now`
+    pub fn can_attempt(&self, address: &A, now: u64) -> bool {
+        self.states.get(address).map_or(true, |state| now >= state.next_attempt)
+    }
+
+    /// Records a failed attempt to `address` at time `now` and returns the time before which it should not be attempted
+    /// again, which is the end of the cooldown if its attempts are exhausted
+    pub fn on_failure(&mut self, address: A, now: u64, random: f64) -> u64 {
+        let state = self.states.entry(address).or_insert(AddressState { failures: 0, next_attempt: now });
+        state.failures = state.failures.saturating_add(1);
+        let delay = if self.policy.is_exhausted(state.failures) {
+            // A new series of attempts starts once the cooldown is over
+            state.failures = 0;
+            self.cooldown
+        } else {
+            self.policy.delay(state.failures, random)
+        };
+        state.next_attempt = now.saturating_add(delay.as_millis() as u64);
+        state.next_attempt
+    }
+
+    /// Records a successful attempt to `address`, forgetting its past failures
+    pub fn on_success(&mut self, address: &A) {
+        self.states.remove(address);
+    }
+
+    /// Forgets the addresses which may be attempted at time `now` and have no failures to account for
+    pub fn prune(&mut self, now: u64) {
+        self.states.retain(|_, state| state.failures > 0 || now < state.next_attempt);
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: BackoffPolicy = BackoffPolicy::new(Duration::from_secs(1), 2, Duration::from_secs(10), 0.5, Some(5));
+
+    #[test]
+    fn test_backoff_delays() {
+        let delays = (1..=6).map(|failures| POLICY.base_delay(failures).as_secs()).collect::<Vec<_>>();
+        assert_eq!(delays, vec![1, 2, 4, 8, 10, 10]);
+
+        // The jitter cuts off at most half of the delay
+        assert_eq!(POLICY.delay(3, 0.0), Duration::from_secs(4));
+        assert_eq!(POLICY.delay(3, 0.5), Duration::from_secs(3));
+        assert!(POLICY.delay(3, 0.999) > Duration::from_secs(2));
+
+        // Huge failure counts saturate at the maximal delay
+        assert_eq!(POLICY.base_delay(u32::MAX), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_backoff_failure_pattern() {
+        let mut backoff = Backoff::new(POLICY);
+
+        // Two failures followed by a success reset the delays
+        assert_eq!(backoff.on_failure(0.0), Some(Duration::from_secs(1)));
+        assert_eq!(backoff.on_failure(0.0), Some(Duration::from_secs(2)));
+        backoff.on_success();
+        assert_eq!(backoff.on_failure(0.0), Some(Duration::from_secs(1)));
+
+        // Consecutive failures exhaust the attempts
+        let delays = (0..4).map(|_| backoff.on_failure(0.0)).collect::<Vec<_>>();
+        assert_eq!(delays, vec![Some(Duration::from_secs(2)), Some(Duration::from_secs(4)), Some(Duration::from_secs(8)), None]);
+        assert_eq!(backoff.failures(), 5);
+
+        // Retrying forever
+        let mut backoff = Backoff::new(BackoffPolicy { max_attempts: None, ..POLICY });
+        assert!((0..100).all(|_| backoff.on_failure(0.0).is_some()));
+    }
+
+    #[test]
+    fn test_address_backoff_cooldown() {
+        let mut backoff = AddressBackoff::new(POLICY, Duration::from_secs(60));
+        let (a, b) = ("a", "b");
+
+        // Address `a` keeps failing on every attempt, as soon as allowed
+        let mut now = 0;
+        let mut attempts = vec![];
+        while attempts.len() < 7 {
+            assert!(backoff.can_attempt(&a, now));
+            attempts.push(now);
+            now = backoff.on_failure(a, now, 0.0);
+            assert!(!backoff.can_attempt(&a, now - 1));
+        }
+        // Delays of 1, 2, 4 and 8 seconds, a cooldown once the 5th attempt failed, then a fresh series of attempts
+        assert_eq!(attempts, vec![0, 1_000, 3_000, 7_000, 15_000, 75_000, 76_000]);
+
+        // Addresses are tracked independently
+        assert!(backoff.can_attempt(&b, 0));
+        backoff.on_failure(b, 0, 0.0);
+        assert!(!backoff.can_attempt(&b, 500));
+        backoff.on_success(&b);
+        assert!(backoff.can_attempt(&b, 500));
+        assert_eq!(backoff.len(), 1);
+
+        // A successful attempt forgets the failures
+        backoff.on_success(&a);
+        assert!(backoff.is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+//!
+//! Failpoints allowing tests to inject faults at named points of the code, e.g., to kill the process in the midst of
+//! a store commit and verify that a restarted node recovers a consistent state.
+//!
+//! A failpoint is declared with [`fail_point!`](crate::fail_point) and is evaluated only when this crate is built
+//! with the `failpoints` feature, otherwise the macro expands to nothing. Failpoints are configured either through
+//! [`cfg`] or through the `KASPA_FAILPOINTS` environment variable, read on first evaluation, which holds
+//! `;` separated `name=action` pairs. Supported actions are:
+//!
+//! - `off`: do nothing
+//! - `panic`: panic in the evaluating thread
+//! - `abort`: abort the process, as if it were killed
+//! - `sleep(<ms>)`: block the evaluating thread for the given duration
+//!
+//! An action may be prefixed by `<n>*` in order to trigger only from the `n+1`'th evaluation onwards, e.g.,
+//! `KASPA_FAILPOINTS="virtual-processor-commit-virtual-state=5*abort"`, and suffixed by `*<m>` in order to trigger
+//! only `m` times before turning off, e.g., `header-processor-worker=5*panic*2`.
+//!
+
+/// Evaluates the failpoint `name`, see the [module docs](crate::failpoint)
+#[cfg(feature = "failpoints")]
+#[macro_export]
+macro_rules! fail_point {
+    ($name: expr) => {
+        $crate::failpoint::eval($name)
+    };
+}
+
+/// Evaluates the failpoint `name`, see the [module docs](crate::failpoint)
+#[cfg(not(feature = "failpoints"))]
+#[macro_export]
+macro_rules! fail_point {
+    ($name: expr) => {};
+}
+
+#[cfg(feature = "failpoints")]
+pub use registry::{cfg, eval, remove, FAILPOINTS_ENV_VAR};
+
+#[cfg(feature = "failpoints")]
+mod registry {
+    use std::{
+        collections::HashMap,
+        sync::{Mutex, OnceLock},
+        time::Duration,
+    };
+
+    pub const FAILPOINTS_ENV_VAR: &str = "KASPA_FAILPOINTS";
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Action {
+        Off,
+        Panic,
+        Abort,
+        Sleep(u64),
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct FailPoint {
+        /// The number of evaluations left before the action triggers
+        skip: u64,
+        action: Action,
+        /// The number of times left for the action to trigger, if limited
+        times: Option<u64>,
+    }
+
+    impl FailPoint {
+        fn parse(actions: &str) -> Result<Self, String> {
+            let parse_count = |count: &str| count.trim().parse().map_err(|_| format!("invalid failpoint count `{count}`"));
+            let (skip, action, times) = match actions.split('*').collect::<Vec<_>>()[..] {
+                [action] => (0, action, None),
+                [skip, action] => (parse_count(skip)?, action, None),
+                [skip, action, times] => match parse_count(times)? {
+                    0 => return Err(format!("invalid failpoint count `{times}`")),
+                    times => (parse_count(skip)?, action, Some(times)),
+                },
+                _ => return Err(format!("invalid failpoint actions `{actions}`")),
+            };
+            let action = match action.trim() {
+                "off" => Action::Off,
+                "panic" => Action::Panic,
+                "abort" => Action::Abort,
+                sleep => sleep
+                    .strip_prefix("sleep(")
+                    .and_then(|sleep| sleep.strip_suffix(')'))
+                    .and_then(|millis| millis.trim().parse().ok())
+                    .map(Action::Sleep)
+                    .ok_or_else(|| format!("invalid failpoint action `{sleep}`"))?,
+            };
+            Ok(Self { skip, action, times })
+        }
+    }
+
+    fn registry() -> &'static Mutex<HashMap<String, FailPoint>> {
+        static REGISTRY: OnceLock<Mutex<HashMap<String, FailPoint>>> = OnceLock::new();
+        REGISTRY.get_or_init(|| {
+            let mut failpoints = HashMap::new();
+            if let Ok(config) = std::env::var(FAILPOINTS_ENV_VAR) {
+                for entry in config.split(';').map(str::trim).filter(|entry| !entry.is_empty()) {
+                    let (name, actions) =
+                        entry.split_once('=').unwrap_or_else(|| panic!("invalid {FAILPOINTS_ENV_VAR} entry `{entry}`"));
+                    let failpoint = FailPoint::parse(actions).unwrap_or_else(|err| panic!("{FAILPOINTS_ENV_VAR}: {err}"));
+                    failpoints.insert(name.trim().to_owned(), failpoint);
+                }
+            }
+            Mutex::new(failpoints)
+        })
+    }
+
+    /// Configures the failpoint `name` with `actions`, overriding any previous configuration
+    pub fn cfg(name: &str, actions: &str) -> Result<(), String> {
+        let failpoint = FailPoint::parse(actions)?;
+        registry().lock().unwrap().insert(name.to_owned(), failpoint);
+        Ok(())
+    }
+
+    /// Removes the configuration of the failpoint `name`, turning it off
+    pub fn remove(name: &str) {
+        registry().lock().unwrap().remove(name);
+    }
+
+    pub fn eval(name: &str) {
+        let action = {
+            let mut failpoints = registry().lock().unwrap();
+            let Some(failpoint) = failpoints.get_mut(name) else {
+                return;
+            };
+            if failpoint.skip > 0 {
+                failpoint.skip -= 1;
+                return;
+            }
+            let action = failpoint.action;
+            if let Some(times) = failpoint.times.as_mut() {
+                *times -= 1;
+                if *times == 0 {
+                    failpoints.remove(name);
+                }
+            }
+            action
+        };
+        match action {
+            Action::Off => {}
+            Action::Panic => panic!("failpoint {name} panicked"),
+            Action::Abort => {
+                log::warn!("failpoint {name} aborting the process");
+                std::process::abort()
+            }
+            Action::Sleep(millis) => std::thread::sleep(Duration::from_millis(millis)),
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_failpoints() {
+            assert_eq!(FailPoint::parse("abort"), Ok(FailPoint { skip: 0, action: Action::Abort, times: None }));
+            assert_eq!(FailPoint::parse("3*sleep(20)"), Ok(FailPoint { skip: 3, action: Action::Sleep(20), times: None }));
+            assert_eq!(FailPoint::parse("1*panic*2"), Ok(FailPoint { skip: 1, action: Action::Panic, times: Some(2) }));
+            assert!(FailPoint::parse("x*panic").is_err());
+            assert!(FailPoint::parse("1*panic*2*3").is_err());
+            assert!(FailPoint::parse("sleep(ms)").is_err());
+
+            cfg("test-failpoint", "2*panic").unwrap();
+            eval("test-failpoint");
+            eval("test-failpoint");
+            assert!(std::panic::catch_unwind(|| eval("test-failpoint")).is_err());
+            remove("test-failpoint");
+            eval("test-failpoint");
+
+            cfg("test-failpoint", "1*panic*2").unwrap();
+            eval("test-failpoint");
+            assert!(std::panic::catch_unwind(|| eval("test-failpoint")).is_err());
+            assert!(std::panic::catch_unwind(|| eval("test-failpoint")).is_err());
+            eval("test-failpoint");
+        }
+    }
+}
@@ -1,7 +1,10 @@
+pub mod affinity;
 pub mod any;
 pub mod arc;
+pub mod backoff;
 pub mod binary_heap;
 pub mod channel;
+pub mod failpoint;
 pub mod hashmap;
 pub mod hex;
 pub mod iter;
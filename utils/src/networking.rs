@@ -63,6 +63,11 @@ impl IpAddress {
             return false;
         }
 
+        // OnionCat addresses fall within the RFC 4193 range but are reachable through a Tor proxy
+        if self.is_onion() {
+            return true;
+        }
+
         match self.0 {
             IpAddr::V4(ip) => {
                 // RFC 1918 is covered by is_private
@@ -106,6 +111,81 @@ impl IpAddress {
     pub fn prefix_bucket(&self) -> PrefixBucket {
         PrefixBucket::from(self)
     }
+
+    /// Returns whether this is an OnionCat address, i.e., a Tor onion service address mapped into the
+    /// `fd87:d87e:eb43::/48` IPv6 range
+    pub fn is_onion(&self) -> bool {
+        match self.0 {
+            IpAddr::V4(_) => false,
+            IpAddr::V6(ip) => ip.octets().starts_with(&ONION_CAT_PREFIX),
+        }
+    }
+
+    /// Returns the `.onion` host name encoded by an OnionCat address, or `None` if this is not an onion address
+    pub fn onion_host(&self) -> Option<String> {
+        match self.0 {
+            IpAddr::V6(ip) if self.is_onion() => Some(format!("{}.onion", base32_encode(&ip.octets()[ONION_CAT_PREFIX.len()..]))),
+            _ => None,
+        }
+    }
+
+    /// Maps a `.onion` host name to its OnionCat address. Only v2 onion services, whose 10 byte identifier fits
+    /// the OnionCat range, can be mapped: v3 host names (56 characters) are unsupported and return `None`
+    pub fn from_onion_host(host: &str) -> Option<Self> {
+        let identifier = host.strip_suffix(".onion")?;
+        if identifier.len() != ONION_V2_HOST_LENGTH {
+            return None;
+        }
+        let mut octets = [0u8; 16];
+        octets[..ONION_CAT_PREFIX.len()].copy_from_slice(&ONION_CAT_PREFIX);
+        octets[ONION_CAT_PREFIX.len()..].copy_from_slice(&base32_decode(identifier)?);
+        Some(Self(IpAddr::V6(Ipv6Addr::from(octets))))
+    }
+}
+
+/// The OnionCat IPv6 prefix under which the 10 bytes of an onion service identifier are mapped
+const ONION_CAT_PREFIX: [u8; 6] = [0xfd, 0x87, 0xd8, 0x7e, 0xeb, 0x43];
+
+/// The length of a v2 onion host name, excluding the `.onion` suffix
+const ONION_V2_HOST_LENGTH: usize = 16;
+
+/// Lowercase RFC 4648 base32 encoding without padding, as used by onion host names
+fn base32_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+    let mut encoded = String::with_capacity((bytes.len() * 8 + 4) / 5);
+    let (mut buffer, mut bits) = (0u16, 0u32);
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    encoded
+}
+
+/// Decodes lowercase RFC 4648 base32 without padding, returning `None` on characters outside of the alphabet
+fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(encoded.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u16, 0u32);
+    for c in encoded.bytes() {
+        let value = match c {
+            b'a'..=b'z' => c - b'a',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | value as u16;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+        }
+    }
+    Some(decoded)
 }
 
 impl From<IpAddr> for IpAddress {
@@ -289,6 +369,12 @@ impl From<NetAddress> for ContextualNetAddress {
     }
 }
 
+impl From<IpAddress> for ContextualNetAddress {
+    fn from(value: IpAddress) -> Self {
+        Self::new(value, None)
+    }
+}
+
 impl FromStr for ContextualNetAddress {
     type Err = AddrParseError;
 
@@ -571,5 +657,28 @@ mod tests {
 
         // Broadcast ip
         assert!(!IpAddress::from_str("255.255.255.255").unwrap().is_publicly_routable());
+
+        // OnionCat
+        assert!(IpAddress::from_str("fd87:d87e:eb43::").unwrap().is_publicly_routable());
+        assert!(IpAddress::from_str("fd87:d87e:eb43:ffff:ffff:ffff:ffff:ffff").unwrap().is_publicly_routable());
+        assert!(!IpAddress::from_str("fd87:d87e:eb42:ffff:ffff:ffff:ffff:ffff").unwrap().is_publicly_routable());
+        assert!(!IpAddress::from_str("fd87:d87e:eb44::").unwrap().is_publicly_routable());
+    }
+
+    #[test]
+    fn test_onion_host() {
+        assert_eq!(IpAddress::from_str("123.45.67.89").unwrap().onion_host(), None);
+        assert_eq!(IpAddress::from_str("fd87:d87e:eb42::1").unwrap().onion_host(), None);
+        // The last 10 bytes are the base32 encoded onion service identifier
+        let ip = IpAddress::from_str("fd87:d87e:eb43:744:208d:5408:63e3:3b4e").unwrap();
+        assert!(ip.is_onion());
+        assert_eq!(ip.onion_host().unwrap(), "a5ccbdkubbr6go2o.onion");
+        assert_eq!(IpAddress::from_str("fd87:d87e:eb43::").unwrap().onion_host().unwrap(), "aaaaaaaaaaaaaaaa.onion");
+
+        // Host names map back to OnionCat addresses, except for v3 ones
+        assert_eq!(IpAddress::from_onion_host("a5ccbdkubbr6go2o.onion"), Some(ip));
+        assert_eq!(IpAddress::from_onion_host("a5ccbdkubbr6go2o"), None);
+        assert_eq!(IpAddress::from_onion_host("a5ccbdkubbr6go2!.onion"), None);
+        assert_eq!(IpAddress::from_onion_host("pg6mmjiyjmcrsslvykfwnntlaru7p5svn6y2ymmju6nubxndf4pscryd.onion"), None);
     }
 }
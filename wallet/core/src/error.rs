@@ -190,6 +190,9 @@ pub enum Error {
     #[error("Not allowed on a resident account")]
     ResidentAccount,
 
+    #[error("Not allowed on a watch-only account")]
+    WatchOnlyAccount,
+
     #[error("This feature is not supported by this account type")]
     AccountKindFeature,
 
@@ -205,6 +208,9 @@ pub enum Error {
     #[error("Fees::Include or Fees::Exclude are not allowed in sweep transactions")]
     GeneratorFeesInSweepTransaction,
 
+    #[error("Consolidation fees of {0} sompi exceed the fee budget of {1} sompi")]
+    ConsolidationFeeBudget(u64, u64),
+
     #[error("Change address does not match supplied network type")]
     GeneratorChangeAddressNetworkTypeMismatch,
 
@@ -220,6 +226,57 @@ pub enum Error {
     #[error(transparent)]
     MultisigCreateError(#[from] kaspa_txscript::MultisigCreateError),
 
+    #[error("Invalid multisig redeem script")]
+    MultisigInvalidRedeemScript,
+
+    #[error("The key is not one of the multisig cosigner keys")]
+    MultisigUnknownCosigner,
+
+    #[error("The sig op count of input {0} does not match the multisig script")]
+    MultisigSigOpCount(usize),
+
+    #[error("Invalid multisig signature for input {0}")]
+    MultisigInvalidSignature(usize),
+
+    #[error("Input {0} has {1} multisig signatures out of the {2} required")]
+    MultisigNotEnoughSignatures(usize, usize, usize),
+
+    #[error("Expected {0} signatures, got {1}")]
+    SignatureCount(usize, usize),
+
+    #[error("Invalid signature for input {0}")]
+    InvalidSignature(usize),
+
+    #[error("The transaction has no input {0}")]
+    InputIndex(usize),
+
+    #[error("Unsupported PSKT version {0}")]
+    PsktVersion(u16),
+
+    #[error("PSKT input {0} has no UTXO entry")]
+    PsktMissingUtxoEntry(usize),
+
+    #[error("PSKT has no input {0}")]
+    PsktInputIndex(usize),
+
+    #[error("The redeem script does not match the UTXO entry of PSKT input {0}")]
+    PsktRedeemScriptMismatch(usize),
+
+    #[error("PSKT redeem scripts can not be set once the PSKT is signed")]
+    PsktAlreadySigned,
+
+    #[error("PSKTs of different transactions can not be combined")]
+    PsktMismatch,
+
+    #[error("PSKT input {0} is neither a pay-to-pubkey nor a multisig input")]
+    PsktUnsupportedInput(usize),
+
+    #[error("PSKT input {0} is not signed")]
+    PsktMissingSignature(usize),
+
+    #[error("Invalid signature for PSKT input {0}")]
+    PsktInvalidSignature(usize),
+
     #[error(transparent)]
     TxScriptError(#[from] kaspa_txscript_errors::TxScriptError),
 }
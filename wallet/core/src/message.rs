@@ -1,3 +1,4 @@
+use kaspa_addresses::{Address, Version};
 use kaspa_hashes::{Hash, PersonalMessageSigningHash};
 use secp256k1::{Error, XOnlyPublicKey};
 
@@ -29,6 +30,15 @@ pub fn verify_message(msg: &PersonalMessage, signature: &Vec<u8>, pubkey: &XOnly
     sig.verify(&msg, pubkey)
 }
 
+/// Ok(()) if the signature matches the given message and the key of a pay-to-pubkey address, proving the ownership of the address
+/// Error if the address is not a pay-to-pubkey address, or the signature is invalid
+pub fn verify_message_with_address(msg: &PersonalMessage, signature: &Vec<u8>, address: &Address) -> Result<(), Error> {
+    if address.version != Version::PubKey {
+        return Err(Error::InvalidPublicKey);
+    }
+    verify_message(msg, signature, &XOnlyPublicKey::from_slice(&address.payload)?)
+}
+
 fn calc_personal_message_hash(msg: &PersonalMessage) -> Hash {
     let mut hasher = PersonalMessageSigningHash::new();
     hasher.write(msg);
@@ -104,6 +114,23 @@ Ut omnis magnam et accusamus earum rem impedit provident eum commodi repellat qu
         verify_message(&pm, &sign_message(&pm, &privkey).expect("sign_message failed"), &pubkey).expect("verify_message failed");
     }
 
+    #[test]
+    fn test_verify_with_address() {
+        let pm = PersonalMessage("Hello Kaspa!");
+        let privkey = [0x03; 32];
+        let keypair = secp256k1::KeyPair::from_seckey_slice(secp256k1::SECP256K1, &privkey).unwrap();
+        let pubkey = keypair.x_only_public_key().0.serialize();
+        let signature = sign_message(&pm, &privkey).expect("sign_message failed");
+
+        let address = Address::new(kaspa_addresses::Prefix::Mainnet, Version::PubKey, &pubkey);
+        verify_message_with_address(&pm, &signature, &address).expect("verify_message_with_address failed");
+        assert!(verify_message_with_address(&PersonalMessage("Hello Kaspa?"), &signature, &address).is_err());
+
+        // Only pay-to-pubkey addresses commit to a Schnorr key
+        let address = Address::new(kaspa_addresses::Prefix::Mainnet, Version::ScriptHash, &pubkey);
+        assert_eq!(verify_message_with_address(&pm, &signature, &address), Err(Error::InvalidPublicKey));
+    }
+
     #[test]
     fn test_fail_verify() {
         let pm = PersonalMessage("Not Hello Kaspa!");
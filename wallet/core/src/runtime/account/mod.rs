@@ -15,13 +15,13 @@ use crate::derivation::AddressDerivationManagerTrait;
 use crate::derivation::{gen0::*, gen1::*, AddressDerivationMeta, PubkeyDerivationManagerTrait, WalletDerivationManagerTrait};
 use crate::imports::*;
 use crate::result::Result;
-use crate::runtime::{Balance, BalanceStrings, Wallet};
+use crate::runtime::{Balance, BalanceStrings, ConsolidationSettings, Wallet};
 use crate::secret::Secret;
 use crate::storage::interface::AccessContext;
 use crate::storage::Metadata;
-use crate::storage::{self, AccessContextT, AccountData, PrvKeyData, PrvKeyDataId};
+use crate::storage::{self, AccessContextT, AccountData, Binding, PrvKeyData, PrvKeyDataId};
 use crate::tx::{Fees, Generator, GeneratorSettings, GeneratorSummary, PaymentDestination, PendingTransaction, Signer};
-use crate::utxo::{UtxoContext, UtxoContextBinding};
+use crate::utxo::{discovery::history_addresses, AddressDiscovery, Discovery, UtxoContext, UtxoContextBinding};
 use kaspa_bip32::PrivateKey;
 use kaspa_consensus_wasm::UtxoEntryReference;
 use kaspa_notify::listener::ListenerId;
@@ -70,6 +70,7 @@ pub async fn try_from_storage(
         AccountData::Keypair(keypair) => {
             Ok(Arc::new(Keypair::try_new(wallet, prv_key_data_id.unwrap(), settings, keypair, meta).await?))
         }
+        AccountData::WatchOnly(watch_only) => Ok(Arc::new(WatchOnly::try_new(wallet, settings, watch_only, meta).await?)),
         AccountData::Hardware(_hardware) => {
             todo!()
         }
@@ -321,6 +322,58 @@ pub trait Account: AnySync + Send + Sync + 'static {
         Ok((generator.summary(), ids))
     }
 
+    /// Consolidates the dust UTXOs of the account selected by `settings` into its change address.
+    /// The run is estimated first and nothing is submitted if its fees exceed the fee budget.
+    /// Returns `None` if the account has too few dust UTXOs to consolidate.
+    async fn consolidate(
+        self: Arc<Self>,
+        settings: &ConsolidationSettings,
+        wallet_secret: Secret,
+        payment_secret: Option<Secret>,
+        abortable: &Abortable,
+        notifier: Option<GenerationNotifier>,
+    ) -> Result<Option<(GeneratorSummary, Vec<kaspa_hashes::Hash>)>> {
+        let Some(utxos) = settings.select(&self.utxo_context().context().mature) else {
+            return Ok(None);
+        };
+
+        let mut generator_settings =
+            GeneratorSettings::try_new_with_account(self.clone().as_dyn_arc(), PaymentDestination::Change, Fees::None, None)?;
+        generator_settings.utxo_iterator = Box::new(utxos.clone().into_iter());
+        let generator = Generator::try_new(generator_settings, None, Some(abortable))?;
+        let mut stream = generator.stream();
+        while stream.try_next().await?.is_some() {
+            yield_executor().await;
+        }
+        let fees = generator.summary().aggregated_fees();
+        if fees > settings.fee_budget {
+            return Err(Error::ConsolidationFeeBudget(fees, settings.fee_budget));
+        }
+
+        let keydata = self.prv_key_data(wallet_secret).await?;
+        let signer = Arc::new(Signer::new(self.clone().as_dyn_arc(), keydata, payment_secret));
+        let mut generator_settings =
+            GeneratorSettings::try_new_with_account(self.clone().as_dyn_arc(), PaymentDestination::Change, Fees::None, None)?;
+        generator_settings.utxo_iterator = Box::new(utxos.into_iter());
+        let generator = Generator::try_new(generator_settings, Some(signer), Some(abortable))?;
+
+        let mut stream = generator.stream();
+        let mut ids = vec![];
+        while let Some(transaction) = stream.try_next().await? {
+            if let Some(notifier) = notifier.as_ref() {
+                notifier(&transaction);
+            }
+
+            transaction.try_sign()?;
+            transaction.log().await?;
+            let id = transaction.try_submit(&self.wallet().rpc_api()).await?;
+            ids.push(id);
+            yield_executor().await;
+        }
+
+        Ok(Some((generator.summary(), ids)))
+    }
+
     async fn send(
         self: Arc<Self>,
         destination: PaymentDestination,
@@ -522,6 +575,44 @@ pub trait DerivationCapableAccount: Account {
         Ok(())
     }
 
+    /// Runs a gap-limit discovery of the used addresses of the account and advances
+    /// the address indexes past the last used address of each chain. Addresses
+    /// found in the transaction history of the account are considered used, which
+    /// is all the discovery relies on if the node lacks the UTXO index.
+    async fn discover_addresses(
+        self: Arc<Self>,
+        gap_limit: Option<u32>,
+        batch_size: Option<u32>,
+        abortable: Option<&Abortable>,
+    ) -> Result<AddressDiscovery> {
+        let derivation = self.derivation();
+        let binding = Binding::from(self.utxo_context().binding());
+        let history =
+            history_addresses(&self.wallet().store().as_transaction_record_store()?, &binding, &self.wallet().network_id()?).await?;
+        let discovery = Discovery::new(self.wallet().rpc_api(), gap_limit, batch_size)
+            .with_history(history)
+            .discover(&derivation, abortable)
+            .await?;
+
+        let chains =
+            [(derivation.receive_address_manager(), &discovery.receive), (derivation.change_address_manager(), &discovery.change)];
+        for (address_manager, chain) in chains {
+            if let Some(last_index) = chain.last_index() {
+                if last_index + 1 > address_manager.index() {
+                    address_manager.set_index(last_index + 1)?;
+                }
+            }
+        }
+
+        if !discovery.is_empty() {
+            let metadata = self.metadata()?.expect("derivation accounts must provide metadata");
+            let store = self.wallet().store().as_account_store()?;
+            store.update_metadata(&[&metadata]).await?;
+        }
+
+        Ok(discovery)
+    }
+
     async fn new_receive_address(self: Arc<Self>) -> Result<Address> {
         let address = self.derivation().receive_address_manager().new_address()?;
         self.utxo_context().register_addresses(&[address.clone()]).await?;
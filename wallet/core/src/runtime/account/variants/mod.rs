@@ -3,9 +3,11 @@ pub mod keypair;
 pub mod legacy;
 pub mod multisig;
 pub mod resident;
+pub mod watch_only;
 
 pub use bip32::*;
 pub use keypair::*;
 pub use legacy::*;
 pub use multisig::*;
 pub use resident::*;
+pub use watch_only::*;
@@ -0,0 +1,90 @@
+use crate::derivation::AddressDerivationManager;
+use crate::imports::*;
+use crate::result::Result;
+use crate::runtime::account::Inner;
+use crate::runtime::account::{Account, AccountId, AccountKind, DerivationCapableAccount};
+use crate::runtime::Wallet;
+use crate::storage::{self, Metadata, PrvKeyDataId, Settings};
+use crate::AddressDerivationManagerTrait;
+
+/// Account derived from an imported extended public key. The account tracks the
+/// balance and the history of its addresses but holds no private key, so it can not
+/// sign transactions.
+pub struct WatchOnly {
+    inner: Arc<Inner>,
+    xpub_key: String,
+    ecdsa: bool,
+    derivation: Arc<AddressDerivationManager>,
+}
+
+impl WatchOnly {
+    pub async fn try_new(
+        wallet: &Arc<Wallet>,
+        settings: Settings,
+        data: storage::account::WatchOnly,
+        meta: Option<Arc<Metadata>>,
+    ) -> Result<Self> {
+        let id = AccountId::from_watch_only(&data);
+        let inner = Arc::new(Inner::new(wallet, id, Some(settings)));
+
+        let storage::account::WatchOnly { xpub_key, ecdsa, .. } = data;
+
+        let address_derivation_indexes = meta.and_then(|meta| meta.address_derivation_indexes()).unwrap_or_default();
+
+        let xpub_keys = Arc::new(vec![xpub_key.clone()]);
+        let derivation =
+            AddressDerivationManager::new(wallet, AccountKind::Bip32, &xpub_keys, ecdsa, 0, None, 1, address_derivation_indexes)
+                .await?;
+
+        Ok(Self { inner, xpub_key, ecdsa, derivation })
+    }
+}
+
+#[async_trait]
+impl Account for WatchOnly {
+    fn inner(&self) -> &Arc<Inner> {
+        &self.inner
+    }
+
+    fn account_kind(&self) -> AccountKind {
+        AccountKind::WatchOnly
+    }
+
+    fn prv_key_data_id(&self) -> Result<&PrvKeyDataId> {
+        Err(Error::WatchOnlyAccount)
+    }
+
+    fn as_dyn_arc(self: Arc<Self>) -> Arc<dyn Account> {
+        self
+    }
+
+    fn receive_address(&self) -> Result<Address> {
+        self.derivation.receive_address_manager().current_address()
+    }
+
+    fn change_address(&self) -> Result<Address> {
+        self.derivation.change_address_manager().current_address()
+    }
+
+    fn as_storable(&self) -> Result<storage::account::Account> {
+        let settings = self.context().settings.clone().unwrap_or_default();
+        let watch_only = storage::WatchOnly::new(self.xpub_key.clone(), self.ecdsa);
+        let account = storage::Account::new(*self.id(), None, settings, storage::AccountData::WatchOnly(watch_only));
+        Ok(account)
+    }
+
+    fn metadata(&self) -> Result<Option<Metadata>> {
+        let metadata = Metadata::new(self.inner.id, self.derivation.address_derivation_meta());
+        Ok(Some(metadata))
+    }
+
+    fn as_derivation_capable(self: Arc<Self>) -> Result<Arc<dyn DerivationCapableAccount>> {
+        Ok(self.clone())
+    }
+}
+
+impl DerivationCapableAccount for WatchOnly {
+    fn derivation(&self) -> Arc<dyn AddressDerivationManagerTrait> {
+        self.derivation.clone()
+    }
+}
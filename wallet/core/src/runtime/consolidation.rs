@@ -0,0 +1,185 @@
+//!
+//! UTXO consolidation: periodically merges the dust UTXOs of an account into
+//! a single change output, keeping the account UTXO set compact.
+//!
+
+use crate::imports::*;
+use crate::result::Result;
+use crate::runtime::Account;
+use crate::secret::Secret;
+use crate::utxo::UtxoEntryReference;
+use futures::pin_mut;
+use workflow_core::abortable::Abortable;
+
+/// Default amount (in sompi) under which a UTXO is considered dust (0.1 KAS)
+pub const DEFAULT_CONSOLIDATION_DUST_THRESHOLD: u64 = 10_000_000;
+/// Default minimum number of dust UTXOs triggering a consolidation
+pub const DEFAULT_CONSOLIDATION_MIN_UTXO_COUNT: usize = 100;
+/// Default maximum number of UTXOs consolidated in a single run
+pub const DEFAULT_CONSOLIDATION_MAX_UTXO_COUNT: usize = 1_000;
+/// Default maximum amount of fees (in sompi) spent by a single run (0.01 KAS)
+pub const DEFAULT_CONSOLIDATION_FEE_BUDGET: u64 = 1_000_000;
+/// Default period between two consolidation runs
+pub const DEFAULT_CONSOLIDATION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone)]
+pub struct ConsolidationSettings {
+    /// UTXOs holding less than this amount (in sompi) are consolidated
+    pub dust_threshold: u64,
+    /// Minimum number of dust UTXOs required for a run to consolidate anything
+    pub min_utxo_count: usize,
+    /// Maximum number of UTXOs consolidated in a single run, the smallest first
+    pub max_utxo_count: usize,
+    /// Maximum amount of fees (in sompi) a single run may spend
+    pub fee_budget: u64,
+    /// Period between two consolidation runs of a [`Consolidator`]
+    pub interval: Duration,
+}
+
+impl Default for ConsolidationSettings {
+    fn default() -> Self {
+        Self {
+            dust_threshold: DEFAULT_CONSOLIDATION_DUST_THRESHOLD,
+            min_utxo_count: DEFAULT_CONSOLIDATION_MIN_UTXO_COUNT,
+            max_utxo_count: DEFAULT_CONSOLIDATION_MAX_UTXO_COUNT,
+            fee_budget: DEFAULT_CONSOLIDATION_FEE_BUDGET,
+            interval: DEFAULT_CONSOLIDATION_INTERVAL,
+        }
+    }
+}
+
+impl ConsolidationSettings {
+    /// Selects the dust UTXOs to consolidate among `mature`, the smallest first.
+    /// Returns `None` if there are fewer dust UTXOs than `min_utxo_count`.
+    pub fn select(&self, mature: &[UtxoEntryReference]) -> Option<Vec<UtxoEntryReference>> {
+        let mut dust = mature.iter().filter(|entry| entry.amount() < self.dust_threshold).cloned().collect::<Vec<_>>();
+        if dust.is_empty() || dust.len() < self.min_utxo_count {
+            return None;
+        }
+        dust.sort_by_key(|entry| entry.amount());
+        dust.truncate(self.max_utxo_count);
+        Some(dust)
+    }
+}
+
+struct Inner {
+    account: Arc<dyn Account>,
+    settings: Mutex<ConsolidationSettings>,
+    task_ctl: DuplexChannel,
+    running: AtomicBool,
+    abortable: Abortable,
+}
+
+/// Account maintenance task running [`Account::consolidate`] periodically
+#[derive(Clone)]
+pub struct Consolidator {
+    inner: Arc<Inner>,
+}
+
+impl Consolidator {
+    pub fn new(account: Arc<dyn Account>, settings: ConsolidationSettings) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                account,
+                settings: Mutex::new(settings),
+                task_ctl: DuplexChannel::oneshot(),
+                running: AtomicBool::new(false),
+                abortable: Abortable::default(),
+            }),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.inner.running.load(Ordering::SeqCst)
+    }
+
+    pub fn settings(&self) -> ConsolidationSettings {
+        self.inner.settings.lock().unwrap().clone()
+    }
+
+    /// Replaces the consolidation settings. A change of `interval` applies once the task is restarted.
+    pub fn set_settings(&self, settings: ConsolidationSettings) {
+        *self.inner.settings.lock().unwrap() = settings;
+    }
+
+    /// Starts the consolidation task. The secrets are kept for the lifetime of the task
+    /// since every run needs to sign its transactions.
+    pub async fn start(&self, wallet_secret: Secret, payment_secret: Option<Secret>) -> Result<()> {
+        if self.is_running() {
+            panic!("Consolidator::start() called while already running");
+        }
+
+        let this = self.clone();
+        this.inner.running.store(true, Ordering::SeqCst);
+        this.inner.abortable.reset();
+        let task_ctl_receiver = self.inner.task_ctl.request.receiver.clone();
+        let task_ctl_sender = self.inner.task_ctl.response.sender.clone();
+        let period = self.settings().interval;
+
+        spawn(async move {
+            let interval = interval(period);
+            pin_mut!(interval);
+
+            loop {
+                select! {
+                    _ = task_ctl_receiver.recv().fuse() => {
+                        break;
+                    },
+
+                    _ = interval.next().fuse() => {
+                        this.run(&wallet_secret, &payment_secret).await;
+                    }
+                }
+            }
+
+            this.inner.running.store(false, Ordering::SeqCst);
+            task_ctl_sender.send(()).await.unwrap();
+        });
+        Ok(())
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        if self.is_running() {
+            self.inner.abortable.abort();
+            self.inner.task_ctl.signal(()).await.expect("Consolidator::stop() `signal` error");
+        }
+        Ok(())
+    }
+
+    async fn run(&self, wallet_secret: &Secret, payment_secret: &Option<Secret>) {
+        let account = self.inner.account.clone();
+        if !account.wallet().is_connected() || !account.wallet().is_synced() {
+            return;
+        }
+
+        let settings = self.settings();
+        let result =
+            account.clone().consolidate(&settings, wallet_secret.clone(), payment_secret.clone(), &self.inner.abortable, None);
+        match result.await {
+            Ok(Some((summary, _ids))) => log_info!("account {}: consolidation: {summary}", account.name_or_id()),
+            Ok(None) => log_trace!("account {}: nothing to consolidate", account.name_or_id()),
+            Err(err) => log_error!("account {}: consolidation error: {err}", account.name_or_id()),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consolidation_select() {
+        let settings = ConsolidationSettings { dust_threshold: 1_000, min_utxo_count: 3, max_utxo_count: 4, ..Default::default() };
+        let utxos = |amounts: &[u64]| amounts.iter().map(|amount| UtxoEntryReference::fake(*amount)).collect::<Vec<_>>();
+        let amounts = |utxos: Vec<UtxoEntryReference>| utxos.iter().map(|utxo| utxo.amount()).collect::<Vec<_>>();
+
+        // Not enough dust
+        assert!(settings.select(&utxos(&[10, 5_000, 20, 1_000])).is_none());
+        assert!(settings.select(&[]).is_none());
+
+        // The smallest dust UTXOs are selected first, up to the maximum count
+        assert_eq!(amounts(settings.select(&utxos(&[900, 10, 5_000, 30, 20, 999])).unwrap()), vec![10, 20, 30, 900]);
+        assert_eq!(amounts(settings.select(&utxos(&[300, 200, 100, 2_000])).unwrap()), vec![100, 200, 300]);
+    }
+}
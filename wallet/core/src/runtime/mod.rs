@@ -1,11 +1,13 @@
 pub mod account;
 pub mod balance;
+pub mod consolidation;
 pub mod maps;
 pub mod sync;
 pub mod wallet;
 
-pub use account::{try_from_storage, Account, AccountId, AccountKind, Bip32, Keypair, Legacy, MultiSig};
+pub use account::{try_from_storage, Account, AccountId, AccountKind, Bip32, Keypair, Legacy, MultiSig, WatchOnly};
 pub use balance::{AtomicBalance, Balance, BalanceStrings};
+pub use consolidation::{ConsolidationSettings, Consolidator};
 pub use maps::ActiveAccountMap;
 pub use sync::SyncMonitor;
 pub use wallet::{AccountCreateArgs, PrvKeyDataCreateArgs, Wallet, WalletCreateArgs};
@@ -584,6 +584,28 @@ impl Wallet {
         Ok(account)
     }
 
+    /// Creates a watch-only account deriving its addresses from the supplied extended public key
+    pub async fn import_watch_only_account(
+        self: &Arc<Wallet>,
+        wallet_secret: Secret,
+        xpub_key: String,
+        name: Option<String>,
+    ) -> Result<Arc<dyn Account>> {
+        let account_storage = self.inner.store.clone().as_account_store()?;
+        let ctx: Arc<dyn AccessContextT> = Arc::new(AccessContext::new(wallet_secret));
+
+        let watch_only = storage::WatchOnly::new(xpub_key, false);
+        let settings = storage::Settings { is_visible: false, name, title: None };
+        let account: Arc<dyn Account> = Arc::new(runtime::WatchOnly::try_new(self, settings, watch_only, None).await?);
+        let stored_account = account.as_storable()?;
+
+        account_storage.store_single(&stored_account, None).await?;
+        self.inner.store.clone().commit(&ctx).await?;
+        account.clone().start().await?;
+
+        Ok(account)
+    }
+
     pub async fn create_wallet(self: &Arc<Wallet>, args: WalletCreateArgs) -> Result<Option<String>> {
         self.reset(true).await?;
         let ctx: Arc<dyn AccessContextT> = Arc::new(AccessContext::new(args.wallet_secret.clone()));
@@ -946,6 +968,22 @@ impl Wallet {
             account.clone().initialize_private_data(wallet_secret, None, None).await?;
             self.legacy_accounts().insert(account.clone());
         }
+        if self.is_connected() {
+            // The account is stored at this point, so a failed discovery leaves its addresses to be derived from the first index
+            match account.clone().as_derivation_capable()?.discover_addresses(None, None, None).await {
+                Ok(discovery) if !discovery.has_utxo_index => log_warning!(
+                    "address discovery: the node is not running with --utxoindex, {} receive and {} change addresses in use found in the transaction history only",
+                    discovery.receive.used.len(),
+                    discovery.change.used.len()
+                ),
+                Ok(discovery) => log_info!(
+                    "address discovery: {} receive and {} change addresses in use",
+                    discovery.receive.used.len(),
+                    discovery.change.used.len()
+                ),
+                Err(err) => log_warning!("address discovery failed, the account addresses will be derived from the first index: {err}"),
+            }
+        }
         account.clone().start().await?;
         if is_legacy {
             let derivation = account.clone().as_derivation_capable()?.derivation();
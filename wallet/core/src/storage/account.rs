@@ -91,6 +91,23 @@ impl Keypair {
     }
 }
 
+const WATCH_ONLY_ACCOUNT_VERSION: u16 = 0;
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub struct WatchOnly {
+    #[serde(default)]
+    pub version: u16,
+
+    pub xpub_key: String,
+    pub ecdsa: bool,
+}
+
+impl WatchOnly {
+    pub fn new(xpub_key: String, ecdsa: bool) -> Self {
+        Self { version: WATCH_ONLY_ACCOUNT_VERSION, xpub_key, ecdsa }
+    }
+}
+
 const HARDWARE_ACCOUNT_VERSION: u16 = 0;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -116,6 +133,7 @@ pub enum AccountData {
     MultiSig(MultiSig),
     Keypair(Keypair),
     Hardware(Hardware),
+    WatchOnly(WatchOnly),
 }
 
 impl AccountData {
@@ -126,6 +144,7 @@ impl AccountData {
             AccountData::MultiSig { .. } => AccountKind::MultiSig,
             AccountData::Hardware { .. } => AccountKind::Hardware,
             AccountData::Keypair { .. } => AccountKind::Keypair,
+            AccountData::WatchOnly { .. } => AccountKind::WatchOnly,
         }
     }
 }
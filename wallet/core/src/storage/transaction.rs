@@ -164,6 +164,16 @@ impl TransactionData {
             }
         }
     }
+
+    /// UTXO entries received by the wallet, none being recorded for outgoing transactions
+    pub fn utxo_entries(&self) -> &[UtxoRecord] {
+        match self {
+            TransactionData::Reorg { utxo_entries, .. }
+            | TransactionData::Incoming { utxo_entries, .. }
+            | TransactionData::External { utxo_entries, .. } => utxo_entries,
+            TransactionData::Outgoing { .. } => &[],
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -204,6 +214,10 @@ impl TransactionRecord {
         self.transaction_data.transaction_type()
     }
 
+    pub fn transaction_data(&self) -> &TransactionData {
+        &self.transaction_data
+    }
+
     pub fn network_id(&self) -> &NetworkId {
         &self.network_id
     }
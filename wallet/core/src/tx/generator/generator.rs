@@ -57,7 +57,7 @@ use crate::imports::*;
 use crate::result::Result;
 use crate::tx::{
     mass::*, Fees, GeneratorSettings, GeneratorSummary, PaymentDestination, PendingTransaction, PendingTransactionIterator,
-    PendingTransactionStream,
+    PendingTransactionStream, SelectionContext,
 };
 use crate::utxo::{UtxoContext, UtxoEntryReference};
 use kaspa_consensus_core::constants::UNACCEPTED_DAA_SCORE;
@@ -260,6 +260,7 @@ impl Generator {
             final_transaction_priority_fee,
             final_transaction_destination,
             final_transaction_payload,
+            coin_selection,
         } = settings;
 
         let mass_calculator = MassCalculator::new(&network_type.into());
@@ -302,17 +303,6 @@ impl Generator {
         // if final_transaction_amount.is_none() && !matches!(final_transaction_priority_fee, Fees::None) {
         // }
 
-        let context = Mutex::new(Context {
-            utxo_source_iterator: utxo_iterator,
-            number_of_transactions: 0,
-            aggregated_utxos: 0,
-            aggregate_fees: 0,
-            stage: Some(Box::default()),
-            utxo_stash: VecDeque::default(),
-            final_transaction_id: None,
-            is_done: false,
-        });
-
         let standard_change_output_mass =
             mass_calculator.calc_mass_for_output(&TransactionOutput::new(0, pay_to_address_script(&change_address)));
         let signature_mass_per_input = mass_calculator.calc_signature_mass(minimum_signatures);
@@ -326,6 +316,42 @@ impl Generator {
             return Err(Error::GeneratorTransactionIsTooHeavy);
         }
 
+        let utxo_iterator = if let Some(coin_selection) = coin_selection {
+            let input = TransactionInput::new(TransactionOutpoint::new(Default::default(), 0), vec![], 0, sig_op_count);
+            let input_mass = mass_calculator.calc_mass_for_input(&input) + signature_mass_per_input;
+            let base_mass = mass_calculator.blank_transaction_mass() + final_transaction_outputs_mass + final_transaction_payload_mass;
+            let fee = |mass: u64| mass * MINIMUM_RELAY_TRANSACTION_FEE / 1000;
+
+            let target = final_transaction_amount.map(|amount| match final_transaction_priority_fee {
+                Fees::SenderPaysAll(priority_fees) => amount + fee(base_mass) + priority_fees,
+                _ => amount,
+            });
+            let selection_context = SelectionContext {
+                target,
+                input_fee: fee(input_mass),
+                change_fee: fee(standard_change_output_mass),
+                dust_threshold: STANDARD_OUTPUT_SIZE_PLUS_INPUT_SIZE_3X * MINIMUM_RELAY_TRANSACTION_FEE / 1000,
+                max_inputs: (MAXIMUM_STANDARD_TRANSACTION_MASS.saturating_sub(base_mass + standard_change_output_mass) / input_mass)
+                    as usize,
+            };
+
+            let utxos = coin_selection.select(utxo_iterator.collect(), &selection_context);
+            Box::new(utxos.into_iter())
+        } else {
+            utxo_iterator
+        };
+
+        let context = Mutex::new(Context {
+            utxo_source_iterator: utxo_iterator,
+            number_of_transactions: 0,
+            aggregated_utxos: 0,
+            aggregate_fees: 0,
+            stage: Some(Box::default()),
+            utxo_stash: VecDeque::default(),
+            final_transaction_id: None,
+            is_done: false,
+        });
+
         let inner = Inner {
             network_type,
             multiplexer,
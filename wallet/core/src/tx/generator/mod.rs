@@ -2,6 +2,7 @@
 pub mod generator;
 pub mod iterator;
 pub mod pending;
+pub mod selection;
 pub mod settings;
 pub mod signer;
 pub mod stream;
@@ -10,6 +11,7 @@ pub mod summary;
 pub use generator::*;
 pub use iterator::*;
 pub use pending::*;
+pub use selection::*;
 pub use settings::*;
 pub use signer::*;
 pub use stream::*;
@@ -1,6 +1,6 @@
 use crate::result::Result;
 use crate::rpc::DynRpcApi;
-use crate::tx::{DataKind, Generator};
+use crate::tx::{apply_signatures, export_sighashes, DataKind, Generator, SighashRequest};
 use crate::utxo::UtxoEntryReference;
 use kaspa_addresses::Address;
 use kaspa_consensus_core::network::NetworkType;
@@ -185,6 +185,19 @@ impl PendingTransaction {
         Ok(())
     }
 
+    /// Exports the sighashes of the transaction inputs for signing by an external device
+    pub fn sighashes(&self) -> Result<Vec<SighashRequest>> {
+        Ok(export_sighashes(&*self.inner.signable_tx.lock()?, self.addresses()))
+    }
+
+    /// Applies the signatures produced by an external device for the [`sighashes`](Self::sighashes) of the transaction
+    pub fn try_apply_signatures(&self, requests: &[SighashRequest], signatures: &[[u8; 64]]) -> Result<()> {
+        let mutable_tx = self.inner.signable_tx.lock()?.clone();
+        let signed_tx = apply_signatures(mutable_tx, requests, signatures)?;
+        *self.inner.signable_tx.lock().unwrap() = signed_tx;
+        Ok(())
+    }
+
     pub fn try_sign_with_keys(&self, privkeys: Vec<[u8; 32]>) -> Result<()> {
         let mutable_tx = self.inner.signable_tx.lock()?.clone();
         let signed_tx = sign_with_multiple_v2(mutable_tx, privkeys);
@@ -0,0 +1,287 @@
+//!
+//! Coin selection strategies used by the [`Generator`](super::Generator) to
+//! order the UTXO entries it consumes.
+//!
+//! The [`Generator`](super::Generator) accumulates UTXO entries in the order
+//! they are supplied until the final transaction value is reached. A
+//! [`CoinSelection`] strategy reorders the supplied entries ahead of the
+//! generation, placing the preferred entries first. All entries are retained,
+//! so the generator can still fall back on the remaining ones (for example
+//! when the preferred entries do not fit in a single transaction).
+//!
+//! All strategies are deterministic: entries of equal value are ordered
+//! by their outpoint.
+//!
+
+use crate::imports::*;
+use crate::utxo::UtxoEntryReference;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// Default number of branches explored by [`BranchAndBound`] before giving up
+pub const DEFAULT_BRANCH_AND_BOUND_MAX_TRIES: usize = 100_000;
+
+/// Values derived from the generator settings that the coin selection
+/// strategies use to evaluate the supplied UTXO entries.
+#[derive(Debug, Clone)]
+pub struct SelectionContext {
+    /// Value (in sompi) the selected inputs need to cover after deduction of
+    /// their own fees, `None` for sweep transactions.
+    pub target: Option<u64>,
+    /// Relay fee (in sompi) of a single signed input
+    pub input_fee: u64,
+    /// Relay fee (in sompi) of the change output
+    pub change_fee: u64,
+    /// Change values below this amount (in sompi) are dust and are added to the fees
+    /// instead of creating a change output
+    pub dust_threshold: u64,
+    /// Maximum number of inputs fitting in a single transaction
+    pub max_inputs: usize,
+}
+
+impl SelectionContext {
+    /// Value of the entry once the fee of spending it is deducted, `None` if the
+    /// entry does not pay for itself.
+    pub fn effective_value(&self, utxo: &UtxoEntryReference) -> Option<u64> {
+        utxo.amount().checked_sub(self.input_fee).filter(|value| *value > 0)
+    }
+}
+
+/// Strategy ordering the UTXO entries consumed by the [`Generator`](super::Generator)
+pub trait CoinSelection: Send + Sync {
+    /// Returns all `utxos` in the order in which they should be consumed.
+    fn select(&self, utxos: Vec<UtxoEntryReference>, context: &SelectionContext) -> Vec<UtxoEntryReference>;
+}
+
+fn sort_largest_first(utxos: &mut [UtxoEntryReference]) {
+    utxos.sort_by(|a, b| b.amount().cmp(&a.amount()).then_with(|| a.cmp(b)));
+}
+
+/// Consumes the largest entries first, minimizing the number of inputs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LargestFirst;
+
+impl CoinSelection for LargestFirst {
+    fn select(&self, mut utxos: Vec<UtxoEntryReference>, _context: &SelectionContext) -> Vec<UtxoEntryReference> {
+        sort_largest_first(&mut utxos);
+        utxos
+    }
+}
+
+/// Branch-and-bound search for a set of entries matching the target closely
+/// enough for the final transaction to need no change output, the leftover
+/// being a dust amount added to the fees. Among the matching sets the one
+/// wasting the least is selected. Falls back to [`LargestFirst`] if no such
+/// set is found within `max_tries` branches.
+#[derive(Debug, Clone, Copy)]
+pub struct BranchAndBound {
+    pub max_tries: usize,
+}
+
+impl Default for BranchAndBound {
+    fn default() -> Self {
+        Self { max_tries: DEFAULT_BRANCH_AND_BOUND_MAX_TRIES }
+    }
+}
+
+impl BranchAndBound {
+    /// Returns the indexes (in `values`, sorted in descending order) of the best
+    /// set with a sum in `[target, target + tolerance)`.
+    fn search(&self, values: &[u64], target: u64, tolerance: u64, max_inputs: usize) -> Option<Vec<usize>> {
+        let upper = target.saturating_add(tolerance);
+        // remaining[i] is the sum of values[i..]
+        let mut remaining = vec![0; values.len() + 1];
+        for i in (0..values.len()).rev() {
+            remaining[i] = remaining[i + 1] + values[i];
+        }
+
+        let mut best: Option<(u64, Vec<usize>)> = None;
+        let mut selected = Vec::new();
+        let mut tries = 0;
+        self.explore(values, &remaining, 0, 0, target, upper, max_inputs, &mut selected, &mut best, &mut tries);
+        best.map(|(_, selected)| selected)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn explore(
+        &self,
+        values: &[u64],
+        remaining: &[u64],
+        index: usize,
+        sum: u64,
+        target: u64,
+        upper: u64,
+        max_inputs: usize,
+        selected: &mut Vec<usize>,
+        best: &mut Option<(u64, Vec<usize>)>,
+        tries: &mut usize,
+    ) {
+        if *tries >= self.max_tries || matches!(best, Some((0, _))) {
+            return;
+        }
+        *tries += 1;
+
+        if sum >= upper {
+            return;
+        }
+        if sum >= target {
+            let waste = sum - target;
+            if best.as_ref().map(|(best_waste, _)| waste < *best_waste).unwrap_or(true) {
+                *best = Some((waste, selected.clone()));
+            }
+            return;
+        }
+        if index == values.len() || selected.len() == max_inputs || sum + remaining[index] < target {
+            return;
+        }
+
+        selected.push(index);
+        self.explore(values, remaining, index + 1, sum + values[index], target, upper, max_inputs, selected, best, tries);
+        selected.pop();
+
+        // skip the entries of equal value, excluding them leads to the same sets
+        let mut next = index + 1;
+        while next < values.len() && values[next] == values[index] {
+            next += 1;
+        }
+        self.explore(values, remaining, next, sum, target, upper, max_inputs, selected, best, tries);
+    }
+}
+
+impl CoinSelection for BranchAndBound {
+    fn select(&self, mut utxos: Vec<UtxoEntryReference>, context: &SelectionContext) -> Vec<UtxoEntryReference> {
+        sort_largest_first(&mut utxos);
+        let Some(target) = context.target else {
+            return utxos;
+        };
+
+        // entries not paying for themselves are never worth selecting
+        let candidates = utxos.iter().take_while(|utxo| context.effective_value(utxo).is_some()).count();
+        let values = utxos[..candidates].iter().filter_map(|utxo| context.effective_value(utxo)).collect::<Vec<_>>();
+
+        // a change output is created only if the leftover, after paying for it, is not dust
+        let target = target.saturating_add(context.change_fee);
+        if let Some(indexes) = self.search(&values, target, context.dust_threshold, context.max_inputs) {
+            let mut selected = vec![false; utxos.len()];
+            indexes.into_iter().for_each(|index| selected[index] = true);
+            let (head, tail): (Vec<_>, Vec<_>) = utxos.into_iter().enumerate().partition(|(index, _)| selected[*index]);
+            head.into_iter().chain(tail).map(|(_, utxo)| utxo).collect()
+        } else {
+            utxos
+        }
+    }
+}
+
+/// Avoids linking addresses of the wallet together by spending entries of a
+/// single address whenever possible. The address holding the smallest balance
+/// sufficient to cover the target is consumed first, followed by the remaining
+/// addresses in the order of their balance, the largest first.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PrivacyAware;
+
+impl CoinSelection for PrivacyAware {
+    fn select(&self, utxos: Vec<UtxoEntryReference>, context: &SelectionContext) -> Vec<UtxoEntryReference> {
+        let mut groups = BTreeMap::<Option<Address>, Vec<UtxoEntryReference>>::new();
+        for utxo in utxos {
+            groups.entry(utxo.utxo.address.clone()).or_default().push(utxo);
+        }
+
+        let mut groups = groups
+            .into_values()
+            .map(|mut group| {
+                sort_largest_first(&mut group);
+                let value = group.iter().take(context.max_inputs).filter_map(|utxo| context.effective_value(utxo)).sum::<u64>();
+                (value, group)
+            })
+            .collect::<Vec<_>>();
+        // stable sort, groups of equal value remain ordered by address
+        groups.sort_by_key(|(value, _)| Reverse(*value));
+
+        if let Some(target) = context.target {
+            let target = target.saturating_add(context.change_fee);
+            if let Some(index) = groups.iter().rposition(|(value, _)| *value >= target) {
+                let group = groups.remove(index);
+                groups.insert(0, group);
+            }
+        }
+
+        groups.into_iter().flat_map(|(_, group)| group).collect()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_addresses::{Prefix, Version};
+
+    fn context(target: Option<u64>) -> SelectionContext {
+        SelectionContext { target, input_fee: 10, change_fee: 5, dust_threshold: 20, max_inputs: 10 }
+    }
+
+    fn utxos(amounts: &[u64]) -> Vec<UtxoEntryReference> {
+        amounts.iter().map(|amount| UtxoEntryReference::fake(*amount)).collect()
+    }
+
+    fn amounts(utxos: &[UtxoEntryReference]) -> Vec<u64> {
+        utxos.iter().map(|utxo| utxo.amount()).collect()
+    }
+
+    #[test]
+    fn test_largest_first() {
+        let selected = LargestFirst.select(utxos(&[300, 1_000, 5, 700]), &context(Some(500)));
+        assert_eq!(amounts(&selected), vec![1_000, 700, 300, 5]);
+    }
+
+    #[test]
+    fn test_branch_and_bound() {
+        // effective values are 990, 690, 390, 290 and 90, no set sums in [695, 715): falls back to largest first
+        let selected = BranchAndBound::default().select(utxos(&[100, 300, 1_000, 400, 700]), &context(Some(690)));
+        assert_eq!(amounts(&selected), vec![1_000, 700, 400, 300, 100]);
+
+        // 400 + 300 + 100 => 770 in [765, 785) is preferred over 700 + 100 => 780
+        let selected = BranchAndBound::default().select(utxos(&[100, 300, 1_000, 400, 700]), &context(Some(760)));
+        assert_eq!(amounts(&selected), vec![400, 300, 100, 1_000, 700]);
+
+        // the match with the least waste is preferred: 700 => 690 in [685, 705) with a waste of 5,
+        // while 400 + 300 => 680 is below the target
+        let selected = BranchAndBound::default().select(utxos(&[400, 300, 700, 1_000]), &context(Some(680)));
+        assert_eq!(amounts(&selected), vec![700, 1_000, 400, 300]);
+
+        // entries below the input fee are never selected, sweeps are ordered largest first
+        let selected = BranchAndBound::default().select(utxos(&[5, 100]), &context(Some(85)));
+        assert_eq!(amounts(&selected), vec![100, 5]);
+        let selected = BranchAndBound::default().select(utxos(&[5, 100]), &context(None));
+        assert_eq!(amounts(&selected), vec![100, 5]);
+
+        // the search is bounded by the number of inputs fitting in a transaction
+        let selected =
+            BranchAndBound::default().select(utxos(&[110, 110, 110]), &SelectionContext { max_inputs: 2, ..context(Some(295)) });
+        assert_eq!(amounts(&selected), vec![110, 110, 110]);
+    }
+
+    #[test]
+    fn test_privacy_aware() {
+        let address = |byte: u8| Address::new(Prefix::Testnet, Version::PubKey, &[byte; 32]);
+        let (a, b, c) = (address(1), address(2), address(3));
+        let utxos = vec![
+            UtxoEntryReference::fake_with_address(500, &a),
+            UtxoEntryReference::fake_with_address(300, &b),
+            UtxoEntryReference::fake_with_address(200, &b),
+            UtxoEntryReference::fake_with_address(1_000, &c),
+            UtxoEntryReference::fake_with_address(100, &a),
+        ];
+
+        // `b` (480) is the smallest single address covering 400 + 5
+        let selected = PrivacyAware.select(utxos.clone(), &context(Some(400)));
+        assert_eq!(amounts(&selected), vec![300, 200, 1_000, 500, 100]);
+
+        // only `c` (990) covers 900 + 5
+        let selected = PrivacyAware.select(utxos.clone(), &context(Some(900)));
+        assert_eq!(amounts(&selected), vec![1_000, 500, 100, 300, 200]);
+
+        // no single address covers the target, the addresses are consumed largest first
+        let selected = PrivacyAware.select(utxos, &context(Some(2_000)));
+        assert_eq!(amounts(&selected), vec![1_000, 500, 100, 300, 200]);
+    }
+}
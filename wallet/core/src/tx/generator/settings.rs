@@ -1,6 +1,6 @@
 use crate::result::Result;
 use crate::runtime::Account;
-use crate::tx::{Fees, PaymentDestination};
+use crate::tx::{CoinSelection, Fees, PaymentDestination};
 use crate::utxo::{UtxoContext, UtxoEntryReference, UtxoIterator};
 use crate::Events;
 use kaspa_addresses::Address;
@@ -29,6 +29,8 @@ pub struct GeneratorSettings {
     pub final_transaction_destination: PaymentDestination,
     // payload
     pub final_transaction_payload: Option<Vec<u8>>,
+    // coin selection strategy ordering the utxos (consumed in the iterator order if not set)
+    pub coin_selection: Option<Arc<dyn CoinSelection>>,
 }
 
 impl GeneratorSettings {
@@ -58,6 +60,7 @@ impl GeneratorSettings {
             final_transaction_priority_fee: final_priority_fee,
             final_transaction_destination,
             final_transaction_payload,
+            coin_selection: None,
         };
 
         Ok(settings)
@@ -88,6 +91,7 @@ impl GeneratorSettings {
             final_transaction_priority_fee: final_priority_fee,
             final_transaction_destination,
             final_transaction_payload,
+            coin_selection: None,
         };
 
         Ok(settings)
@@ -117,6 +121,7 @@ impl GeneratorSettings {
             final_transaction_priority_fee: final_priority_fee,
             final_transaction_destination,
             final_transaction_payload,
+            coin_selection: None,
         };
 
         Ok(settings)
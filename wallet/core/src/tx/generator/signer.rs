@@ -3,10 +3,21 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use kaspa_addresses::Address;
+use kaspa_addresses::{Address, Version};
 use kaspa_bip32::PrivateKey;
-use kaspa_consensus_core::{sign::sign_with_multiple_v2, tx::SignableTransaction};
+use kaspa_consensus_core::{
+    hashing::{
+        sighash::{calc_schnorr_signature_hash, SigHashReusedValues},
+        sighash_type::SIG_HASH_ALL,
+    },
+    sign::sign_with_multiple_v2,
+    tx::SignableTransaction,
+};
+use kaspa_hashes::Hash;
+use kaspa_txscript::pay_to_address_script;
+use secp256k1::{schnorr::Signature, Message, XOnlyPublicKey};
 
+use crate::error::Error;
 use crate::result::Result;
 use crate::{runtime::Account, secret::Secret, storage::PrvKeyData};
 
@@ -81,3 +92,155 @@ impl SignerT for KeydataSigner {
         Ok(sign_with_multiple_v2(mutable_tx, keys_for_signing))
     }
 }
+
+// ---
+
+/// The signature hash of a transaction input, to be signed by the key of its address
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SighashRequest {
+    pub input_index: usize,
+    pub address: Address,
+    pub sighash: Hash,
+}
+
+/// A device holding the private keys, such as a hardware wallet, which signs sighashes without exposing the keys
+pub trait DeviceSignerT: Send + Sync + 'static {
+    /// Returns the Schnorr signatures of the requests, in the order of the requests
+    fn sign_sighashes(&self, requests: &[SighashRequest]) -> Result<Vec<[u8; 64]>>;
+}
+
+/// Exports the sighashes of all inputs spending from pay-to-pubkey `addresses`
+pub fn export_sighashes(transaction: &SignableTransaction, addresses: &[Address]) -> Vec<SighashRequest> {
+    let scripts = addresses
+        .iter()
+        .filter(|address| address.version == Version::PubKey)
+        .map(|address| (pay_to_address_script(address), address))
+        .collect::<Vec<_>>();
+    let mut reused_values = SigHashReusedValues::new();
+    let mut requests = vec![];
+    for (input_index, entry) in transaction.entries.iter().enumerate() {
+        let Some(entry) = entry.as_ref() else { continue };
+        if let Some((_, address)) = scripts.iter().find(|(script, _)| *script == entry.script_public_key) {
+            let sighash = calc_schnorr_signature_hash(&transaction.as_verifiable(), input_index, SIG_HASH_ALL, &mut reused_values);
+            requests.push(SighashRequest { input_index, address: (*address).clone(), sighash });
+        }
+    }
+    requests
+}
+
+/// Verifies the signatures returned for exported sighashes and applies them to the transaction
+pub fn apply_signatures(
+    mut transaction: SignableTransaction,
+    requests: &[SighashRequest],
+    signatures: &[[u8; 64]],
+) -> Result<SignableTransaction> {
+    if requests.len() != signatures.len() {
+        return Err(Error::SignatureCount(requests.len(), signatures.len()));
+    }
+    if let Some(request) = requests.iter().find(|request| request.input_index >= transaction.tx.inputs.len()) {
+        return Err(Error::InputIndex(request.input_index));
+    }
+    for (request, signature) in requests.iter().zip(signatures) {
+        let msg = Message::from_slice(request.sighash.as_bytes().as_slice())?;
+        Signature::from_slice(signature)?
+            .verify(&msg, &XOnlyPublicKey::from_slice(&request.address.payload)?)
+            .map_err(|_| Error::InvalidSignature(request.input_index))?;
+        // This represents OP_DATA_65 <SIGNATURE+SIGHASH_TYPE> (since signature length is 64 bytes and SIGHASH_TYPE is one byte)
+        transaction.tx.inputs[request.input_index].signature_script =
+            std::iter::once(65u8).chain(*signature).chain([SIG_HASH_ALL.to_u8()]).collect();
+    }
+    Ok(transaction)
+}
+
+/// A signer delegating the signing of sighashes to a [`DeviceSignerT`], so that transaction generation does not
+/// require access to the keys
+pub struct ExternalSigner {
+    device: Arc<dyn DeviceSignerT>,
+}
+
+impl ExternalSigner {
+    pub fn new(device: Arc<dyn DeviceSignerT>) -> Self {
+        Self { device }
+    }
+}
+
+impl SignerT for ExternalSigner {
+    fn try_sign(&self, mutable_tx: SignableTransaction, addresses: &[Address]) -> Result<SignableTransaction> {
+        let requests = export_sighashes(&mutable_tx, addresses);
+        let signatures = self.device.sign_sighashes(&requests)?;
+        apply_signatures(mutable_tx, &requests, &signatures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_addresses::Prefix;
+    use kaspa_consensus_core::{sign::verify, subnets::SubnetworkId, tx::*};
+    use secp256k1::{rand::thread_rng, KeyPair};
+    use std::str::FromStr;
+
+    struct TestDevice {
+        keys: Vec<KeyPair>,
+    }
+
+    impl DeviceSignerT for TestDevice {
+        fn sign_sighashes(&self, requests: &[SighashRequest]) -> Result<Vec<[u8; 64]>> {
+            Ok(requests
+                .iter()
+                .map(|request| {
+                    let key =
+                        self.keys.iter().find(|key| request.address.payload[..] == key.x_only_public_key().0.serialize()).unwrap();
+                    *key.sign_schnorr(Message::from_slice(request.sighash.as_bytes().as_slice()).unwrap()).as_ref()
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_external_signer() {
+        let keys = vec![KeyPair::new(secp256k1::SECP256K1, &mut thread_rng()), KeyPair::new(secp256k1::SECP256K1, &mut thread_rng())];
+        let addresses = keys
+            .iter()
+            .map(|key| Address::new(Prefix::Testnet, Version::PubKey, &key.x_only_public_key().0.serialize()))
+            .collect::<Vec<_>>();
+        let prev_tx_id = TransactionId::from_str("880eb9819a31821d9d2399e2f35e2433b72637e393d71ecc9b8d0250f49153c3").unwrap();
+        let inputs = (0..3)
+            .map(|index| TransactionInput {
+                previous_outpoint: TransactionOutpoint { transaction_id: prev_tx_id, index },
+                signature_script: vec![],
+                sequence: 0,
+                sig_op_count: 1,
+            })
+            .collect();
+        let output = TransactionOutput { value: 500, script_public_key: pay_to_address_script(&addresses[0]) };
+        let tx = Transaction::new(0, inputs, vec![output], 0, SubnetworkId::from_bytes([0; 20]), 0, vec![]);
+        let entries = [0, 1, 1]
+            .into_iter()
+            .map(|key| UtxoEntry {
+                amount: 200,
+                script_public_key: pay_to_address_script(&addresses[key]),
+                block_daa_score: 0,
+                is_coinbase: false,
+            })
+            .collect();
+        let tx = SignableTransaction::with_entries(tx, entries);
+
+        let signer = ExternalSigner::new(Arc::new(TestDevice { keys: keys.clone() }));
+        let signed = signer.try_sign(tx.clone(), &addresses).unwrap();
+        assert!(verify(&signed.as_verifiable()).is_ok());
+
+        // Signatures are verified against the addresses they were requested for
+        let requests = export_sighashes(&tx, &addresses[1..]);
+        assert_eq!(requests.iter().map(|request| request.input_index).collect::<Vec<_>>(), vec![1, 2]);
+        let mut signatures = TestDevice { keys }.sign_sighashes(&requests).unwrap();
+        assert!(matches!(apply_signatures(tx.clone(), &requests, &signatures[1..]), Err(Error::SignatureCount(2, 1))));
+        signatures.swap(0, 1);
+        assert!(matches!(apply_signatures(tx.clone(), &requests, &signatures), Err(Error::InvalidSignature(1))));
+
+        // Requests for inputs the transaction does not have are rejected
+        let mut requests = requests;
+        requests[1].input_index = 3;
+        assert!(matches!(apply_signatures(tx, &requests, &signatures), Err(Error::InputIndex(3))));
+    }
+}
@@ -301,6 +301,7 @@ where
         final_transaction_priority_fee: final_priority_fee,
         final_transaction_destination,
         final_transaction_payload,
+        coin_selection: None,
     };
 
     Generator::try_new(settings, None, None)
@@ -2,10 +2,14 @@ pub mod consensus;
 pub mod fees;
 pub mod generator;
 pub mod mass;
+pub mod multisig;
 pub mod payment;
+pub mod pskt;
 
 pub use consensus::*;
 pub use fees::*;
 pub use generator::*;
 pub use mass::*;
+pub use multisig::*;
 pub use payment::*;
+pub use pskt::*;
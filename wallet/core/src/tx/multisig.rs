@@ -0,0 +1,257 @@
+//!
+//! Signing of m-of-n Schnorr multisig inputs, which are locked to the pay-to-script-hash of a multisig redeem script.
+//!
+//! Cosigners sign independently of each other: a coordinator prepares the unsigned transaction with
+//! [`MultiSigScript::prepare`] and hands it out, each cosigner returns its [`PartialSignature`]s produced by
+//! [`MultiSigScript::sign_partial`], and any `required` of those are combined into the signature scripts by
+//! [`MultiSigScript::combine`].
+//!
+
+use crate::error::Error;
+use crate::result::Result;
+use borsh::{BorshDeserialize, BorshSerialize};
+use kaspa_addresses::{Address, Prefix};
+use kaspa_consensus_core::{
+    hashing::{
+        sighash::{calc_schnorr_signature_hash, SigHashReusedValues},
+        sighash_type::SIG_HASH_ALL,
+    },
+    tx::{ScriptPublicKey, SignableTransaction},
+};
+use kaspa_txscript::{
+    extract_script_pub_key_address, multisig_redeem_script,
+    opcodes::codes::{Op16, OpData32, OpData65, OpTrue},
+    pay_to_script_hash_script, pay_to_script_hash_signature_script,
+};
+use kaspa_utils::serde_bytes;
+use secp256k1::{schnorr::Signature, KeyPair, Message, XOnlyPublicKey};
+use serde::{Deserialize, Serialize};
+use std::iter::once;
+
+/// An m-of-n Schnorr multisig redeem script along with its cosigner keys
+#[derive(Clone, Debug)]
+pub struct MultiSigScript {
+    pub_keys: Vec<[u8; 32]>,
+    required: usize,
+    redeem_script: Vec<u8>,
+    script_public_key: ScriptPublicKey,
+}
+
+/// The signature of a single cosigner over a single input
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialSignature {
+    pub input_index: u32,
+    /// The x-only public key of the cosigner
+    #[serde(with = "serde_bytes")]
+    pub pub_key: [u8; 32],
+    #[serde(with = "serde_bytes")]
+    pub signature: [u8; 64],
+}
+
+impl MultiSigScript {
+    /// Creates the script requiring `required` signatures of the `pub_keys` cosigners. The order of the keys
+    /// is part of the script, hence of its address
+    pub fn try_new(pub_keys: Vec<[u8; 32]>, required: usize) -> Result<Self> {
+        let redeem_script = multisig_redeem_script(pub_keys.iter(), required)?;
+        let script_public_key = pay_to_script_hash_script(&redeem_script);
+        Ok(Self { pub_keys, required, redeem_script, script_public_key })
+    }
+
+    /// Recovers the script from a redeem script created by [`MultiSigScript::try_new`], for up to 16 required signatures
+    pub fn try_from_redeem_script(redeem_script: &[u8]) -> Result<Self> {
+        let required = match redeem_script.first() {
+            Some(&opcode) if (OpTrue..=Op16).contains(&opcode) => (opcode - OpTrue + 1) as usize,
+            _ => return Err(Error::MultisigInvalidRedeemScript),
+        };
+        let pub_keys = redeem_script[1..]
+            .chunks(33)
+            .take_while(|chunk| chunk.len() == 33 && chunk[0] == OpData32)
+            .map(|chunk| <[u8; 32]>::try_from(&chunk[1..]).unwrap())
+            .collect::<Vec<_>>();
+        // Rebuilding the script validates everything but the keys
+        let script = Self::try_new(pub_keys, required).map_err(|_| Error::MultisigInvalidRedeemScript)?;
+        if script.redeem_script != redeem_script {
+            return Err(Error::MultisigInvalidRedeemScript);
+        }
+        Ok(script)
+    }
+
+    pub fn pub_keys(&self) -> &[[u8; 32]] {
+        &self.pub_keys
+    }
+
+    pub fn required(&self) -> usize {
+        self.required
+    }
+
+    pub fn redeem_script(&self) -> &[u8] {
+        &self.redeem_script
+    }
+
+    pub fn script_public_key(&self) -> &ScriptPublicKey {
+        &self.script_public_key
+    }
+
+    pub fn address(&self, prefix: Prefix) -> Result<Address> {
+        Ok(extract_script_pub_key_address(&self.script_public_key, prefix)?)
+    }
+
+    /// Sets the sig op count of the inputs spending this script. The count is committed to by the signature
+    /// hash, so the transaction must be prepared before it is handed to the cosigners
+    pub fn prepare(&self, tx: &mut SignableTransaction) {
+        for index in self.input_indexes(tx).collect::<Vec<_>>() {
+            tx.tx.inputs[index].sig_op_count = self.pub_keys.len() as u8;
+        }
+    }
+
+    /// Signs all inputs spending this script with the key of a single cosigner
+    pub fn sign_partial(&self, tx: &SignableTransaction, keypair: &KeyPair) -> Result<Vec<PartialSignature>> {
+        let pub_key = keypair.x_only_public_key().0.serialize();
+        if !self.pub_keys.contains(&pub_key) {
+            return Err(Error::MultisigUnknownCosigner);
+        }
+        let mut reused_values = SigHashReusedValues::new();
+        self.input_indexes(tx)
+            .map(|index| {
+                let msg = self.signature_message(tx, index, &mut reused_values)?;
+                Ok(PartialSignature { input_index: index as u32, pub_key, signature: *keypair.sign_schnorr(msg).as_ref() })
+            })
+            .collect()
+    }
+
+    /// Verifies the partial signatures of the cosigners and combines `required` of them into the signature
+    /// script of every input spending this script
+    pub fn combine(&self, mut tx: SignableTransaction, signatures: &[PartialSignature]) -> Result<SignableTransaction> {
+        let mut reused_values = SigHashReusedValues::new();
+        for index in self.input_indexes(&tx).collect::<Vec<_>>() {
+            tx.tx.inputs[index].signature_script = self.signature_script(&tx, index, signatures, &mut reused_values)?;
+        }
+        Ok(tx)
+    }
+
+    /// Verifies the partial signatures of an input and combines `required` of them into its signature script
+    pub(crate) fn signature_script(
+        &self,
+        tx: &SignableTransaction,
+        index: usize,
+        signatures: &[PartialSignature],
+        reused_values: &mut SigHashReusedValues,
+    ) -> Result<Vec<u8>> {
+        let msg = self.signature_message(tx, index, reused_values)?;
+        let mut signature_script = Vec::new();
+        let mut count = 0;
+        // OpCheckMultiSig consumes the signatures in the order of their keys within the redeem script
+        for pub_key in self.pub_keys.iter() {
+            if count == self.required {
+                break;
+            }
+            let Some(partial) = signatures.iter().find(|sig| sig.input_index as usize == index && sig.pub_key == *pub_key) else {
+                continue;
+            };
+            Signature::from_slice(&partial.signature)?
+                .verify(&msg, &XOnlyPublicKey::from_slice(pub_key)?)
+                .map_err(|_| Error::MultisigInvalidSignature(index))?;
+            signature_script.extend(once(OpData65).chain(partial.signature).chain(once(SIG_HASH_ALL.to_u8())));
+            count += 1;
+        }
+        if count < self.required {
+            return Err(Error::MultisigNotEnoughSignatures(index, count, self.required));
+        }
+        Ok(pay_to_script_hash_signature_script(self.redeem_script.clone(), signature_script)?)
+    }
+
+    fn input_indexes<'a>(&'a self, tx: &'a SignableTransaction) -> impl Iterator<Item = usize> + 'a {
+        tx.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.as_ref().is_some_and(|entry| entry.script_public_key == self.script_public_key))
+            .map(|(index, _)| index)
+    }
+
+    pub(crate) fn signature_message(
+        &self,
+        tx: &SignableTransaction,
+        index: usize,
+        reused_values: &mut SigHashReusedValues,
+    ) -> Result<Message> {
+        if tx.tx.inputs[index].sig_op_count != self.pub_keys.len() as u8 {
+            return Err(Error::MultisigSigOpCount(index));
+        }
+        let sig_hash = calc_schnorr_signature_hash(&tx.as_verifiable(), index, SIG_HASH_ALL, reused_values);
+        Ok(Message::from_slice(sig_hash.as_bytes().as_slice())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::{subnets::SubnetworkId, tx::*};
+    use kaspa_txscript::{caches::Cache, TxScriptEngine};
+    use rand::thread_rng;
+    use std::str::FromStr;
+
+    fn random_keypairs(count: usize) -> Vec<KeyPair> {
+        (0..count).map(|_| KeyPair::new(secp256k1::SECP256K1, &mut thread_rng())).collect()
+    }
+
+    fn spending_transaction(script: &MultiSigScript) -> SignableTransaction {
+        let prev_tx_id = TransactionId::from_str("63020db736215f8b1105a9281f7bcbb6473d965ecc45bb2fb5da59bd35e6ff84").unwrap();
+        let inputs = (0..2)
+            .map(|index| TransactionInput {
+                previous_outpoint: TransactionOutpoint { transaction_id: prev_tx_id, index },
+                signature_script: vec![],
+                sequence: 0,
+                sig_op_count: 0,
+            })
+            .collect();
+        let output = TransactionOutput { value: 2000, script_public_key: script.script_public_key().clone() };
+        let tx = Transaction::new(0, inputs, vec![output], 0, SubnetworkId::from_bytes([0; 20]), 0, vec![]);
+        let entry =
+            UtxoEntry { amount: 1500, script_public_key: script.script_public_key().clone(), block_daa_score: 0, is_coinbase: false };
+        SignableTransaction::with_entries(tx, vec![entry.clone(), entry])
+    }
+
+    fn execute_inputs(tx: &SignableTransaction) -> bool {
+        let tx = tx.as_verifiable();
+        let cache = Cache::new(10_000);
+        let mut reused_values = SigHashReusedValues::new();
+        tx.populated_inputs().enumerate().all(|(index, (input, entry))| {
+            let mut engine = TxScriptEngine::from_transaction_input(&tx, input, index, entry, &mut reused_values, &cache).unwrap();
+            engine.execute().is_ok()
+        })
+    }
+
+    #[test]
+    fn test_multisig_partial_signing() {
+        let keypairs = random_keypairs(3);
+        let script = MultiSigScript::try_new(keypairs.iter().map(|kp| kp.x_only_public_key().0.serialize()).collect(), 2).unwrap();
+        let recovered = MultiSigScript::try_from_redeem_script(script.redeem_script()).unwrap();
+        assert_eq!((recovered.pub_keys(), recovered.required()), (script.pub_keys(), 2));
+        let truncated = &script.redeem_script()[..script.redeem_script().len() - 1];
+        assert!(matches!(MultiSigScript::try_from_redeem_script(truncated), Err(Error::MultisigInvalidRedeemScript)));
+        let mut tx = spending_transaction(&script);
+
+        // Signing before the sig op counts are prepared would commit to wrong counts
+        assert!(matches!(script.sign_partial(&tx, &keypairs[0]), Err(Error::MultisigSigOpCount(0))));
+        script.prepare(&mut tx);
+        assert!(matches!(script.sign_partial(&tx, &random_keypairs(1)[0]), Err(Error::MultisigUnknownCosigner)));
+
+        // Cosigners sign in any order, and signatures beyond the required ones are ignored
+        let third = script.sign_partial(&tx, &keypairs[2]).unwrap();
+        let first = script.sign_partial(&tx, &keypairs[0]).unwrap();
+        assert_eq!(first.len(), 2);
+        assert!(matches!(script.combine(tx.clone(), &third), Err(Error::MultisigNotEnoughSignatures(0, 1, 2))));
+
+        let signed = script.combine(tx.clone(), &[third.clone(), first.clone()].concat()).unwrap();
+        assert!(execute_inputs(&signed));
+        let second = script.sign_partial(&tx, &keypairs[1]).unwrap();
+        let signed = script.combine(tx.clone(), &[third.clone(), second, first.clone()].concat()).unwrap();
+        assert!(execute_inputs(&signed));
+
+        // A signature which does not match its key is rejected rather than combined
+        let mut forged = third;
+        forged[1].pub_key = keypairs[1].x_only_public_key().0.serialize();
+        assert!(matches!(script.combine(tx, &[first, forged].concat()), Err(Error::MultisigInvalidSignature(1))));
+    }
+}
@@ -0,0 +1,268 @@
+//!
+//! PSKT, a partially signed Kaspa transaction, is an unsigned transaction along with everything required for signing
+//! it: the UTXO entries of its inputs, the redeem scripts of its multisig inputs, and the signatures collected so far.
+//!
+//! A PSKT goes through the following roles, each of which can be performed by a different party (e.g. a watch-only
+//! wallet, a hardware wallet or the cosigners of a multisig):
+//! - creator: [`Pskt::try_new`] from a transaction with populated UTXO entries
+//! - updater: [`Pskt::set_redeem_script`] for inputs spending multisig scripts
+//! - signer: [`Pskt::sign`] with any key able to sign some of the inputs
+//! - combiner: [`Pskt::combine`] of the copies signed by different signers
+//! - finalizer: [`Pskt::finalize`] into the signed transaction
+//!
+//! PSKTs are exchanged in their JSON form, see [`Pskt::to_json`] and [`Pskt::from_json`].
+//!
+
+use crate::error::Error;
+use crate::result::Result;
+use crate::tx::{MultiSigScript, PartialSignature};
+use kaspa_consensus_core::{
+    hashing::{
+        sighash::{calc_schnorr_signature_hash, SigHashReusedValues},
+        sighash_type::SIG_HASH_ALL,
+    },
+    tx::{SignableTransaction, Transaction, UtxoEntry},
+};
+use kaspa_txscript::{opcodes::codes::OpData65, script_class::ScriptClass};
+use kaspa_utils::serde_bytes;
+use secp256k1::{schnorr::Signature, KeyPair, Message, XOnlyPublicKey};
+use serde::{Deserialize, Serialize};
+use std::iter::once;
+
+pub const PSKT_VERSION: u16 = 0;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PsktInput {
+    pub utxo_entry: UtxoEntry,
+    /// The redeem script of a pay-to-script-hash input, empty for a pay-to-pubkey input
+    #[serde(with = "serde_bytes", default, skip_serializing_if = "Vec::is_empty")]
+    pub redeem_script: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pskt {
+    pub version: u16,
+    /// The unsigned transaction
+    pub tx: Transaction,
+    pub inputs: Vec<PsktInput>,
+    pub partial_signatures: Vec<PartialSignature>,
+}
+
+impl Pskt {
+    /// Creates a PSKT from a transaction with all of its UTXO entries. Any signature scripts are discarded
+    pub fn try_new(tx: SignableTransaction) -> Result<Self> {
+        let SignableTransaction { mut tx, entries, .. } = tx;
+        let inputs = entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                Ok(PsktInput { utxo_entry: entry.ok_or(Error::PsktMissingUtxoEntry(index))?, redeem_script: vec![] })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        tx.inputs.iter_mut().for_each(|input| input.signature_script.clear());
+        tx.finalize();
+        Ok(Self { version: PSKT_VERSION, tx, inputs, partial_signatures: vec![] })
+    }
+
+    /// Sets the multisig redeem script of an input, along with the sig op count it requires. Since sig op counts
+    /// are committed to by the signatures of all inputs, redeem scripts can only be set before signing
+    pub fn set_redeem_script(&mut self, index: usize, redeem_script: Vec<u8>) -> Result<()> {
+        if !self.partial_signatures.is_empty() {
+            return Err(Error::PsktAlreadySigned);
+        }
+        let script = MultiSigScript::try_from_redeem_script(&redeem_script)?;
+        let input = self.inputs.get_mut(index).ok_or(Error::PsktInputIndex(index))?;
+        if input.utxo_entry.script_public_key != *script.script_public_key() {
+            return Err(Error::PsktRedeemScriptMismatch(index));
+        }
+        input.redeem_script = redeem_script;
+        self.tx.inputs[index].sig_op_count = script.pub_keys().len() as u8;
+        self.tx.finalize();
+        Ok(())
+    }
+
+    /// Signs all inputs which can be signed by the key, either as a cosigner of a multisig input or as the owner of
+    /// a pay-to-pubkey input. Returns the number of signed inputs
+    pub fn sign(&mut self, keypair: &KeyPair) -> Result<usize> {
+        let pub_key = keypair.x_only_public_key().0.serialize();
+        let tx = self.signable_transaction();
+        let mut reused_values = SigHashReusedValues::new();
+        let mut signed = 0;
+        for (index, input) in self.inputs.iter().enumerate() {
+            let msg = if input.redeem_script.is_empty() {
+                if p2pk_key(&input.utxo_entry) != Some(&pub_key) {
+                    continue;
+                }
+                let sig_hash = calc_schnorr_signature_hash(&tx.as_verifiable(), index, SIG_HASH_ALL, &mut reused_values);
+                Message::from_slice(sig_hash.as_bytes().as_slice())?
+            } else {
+                let script = MultiSigScript::try_from_redeem_script(&input.redeem_script)?;
+                if !script.pub_keys().contains(&pub_key) {
+                    continue;
+                }
+                script.signature_message(&tx, index, &mut reused_values)?
+            };
+            let signature = PartialSignature { input_index: index as u32, pub_key, signature: *keypair.sign_schnorr(msg).as_ref() };
+            self.partial_signatures.retain(|sig| !(sig.input_index == signature.input_index && sig.pub_key == pub_key));
+            self.partial_signatures.push(signature);
+            signed += 1;
+        }
+        Ok(signed)
+    }
+
+    /// Merges the signatures of another copy of the same PSKT
+    pub fn combine(mut self, other: Pskt) -> Result<Self> {
+        if self.tx != other.tx || self.inputs != other.inputs {
+            return Err(Error::PsktMismatch);
+        }
+        for signature in other.partial_signatures {
+            if !self.partial_signatures.iter().any(|sig| sig.input_index == signature.input_index && sig.pub_key == signature.pub_key)
+            {
+                self.partial_signatures.push(signature);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Verifies the collected signatures and builds the signature scripts of all inputs
+    pub fn finalize(&self) -> Result<SignableTransaction> {
+        let mut tx = self.signable_transaction();
+        let mut reused_values = SigHashReusedValues::new();
+        for (index, input) in self.inputs.iter().enumerate() {
+            tx.tx.inputs[index].signature_script = if input.redeem_script.is_empty() {
+                let pub_key = p2pk_key(&input.utxo_entry).ok_or(Error::PsktUnsupportedInput(index))?;
+                let partial = self
+                    .partial_signatures
+                    .iter()
+                    .find(|sig| sig.input_index as usize == index && sig.pub_key == *pub_key)
+                    .ok_or(Error::PsktMissingSignature(index))?;
+                let sig_hash = calc_schnorr_signature_hash(&tx.as_verifiable(), index, SIG_HASH_ALL, &mut reused_values);
+                let msg = Message::from_slice(sig_hash.as_bytes().as_slice())?;
+                Signature::from_slice(&partial.signature)?
+                    .verify(&msg, &XOnlyPublicKey::from_slice(pub_key)?)
+                    .map_err(|_| Error::PsktInvalidSignature(index))?;
+                once(OpData65).chain(partial.signature).chain(once(SIG_HASH_ALL.to_u8())).collect()
+            } else {
+                let script = MultiSigScript::try_from_redeem_script(&input.redeem_script)?;
+                script.signature_script(&tx, index, &self.partial_signatures, &mut reused_values)?
+            };
+        }
+        Ok(tx)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let mut pskt: Pskt = serde_json::from_str(json)?;
+        if pskt.version != PSKT_VERSION {
+            return Err(Error::PsktVersion(pskt.version));
+        }
+        if pskt.inputs.len() != pskt.tx.inputs.len() {
+            return Err(Error::PsktMismatch);
+        }
+        // The serialized transaction id is not trusted
+        pskt.tx.finalize();
+        Ok(pskt)
+    }
+
+    fn signable_transaction(&self) -> SignableTransaction {
+        SignableTransaction::with_entries(self.tx.clone(), self.inputs.iter().map(|input| input.utxo_entry.clone()).collect())
+    }
+}
+
+fn p2pk_key(entry: &UtxoEntry) -> Option<&[u8; 32]> {
+    let script = entry.script_public_key.script();
+    ScriptClass::is_pay_to_pubkey(script).then(|| script[1..33].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::{
+        subnets::SubnetworkId,
+        tx::{
+            ScriptPublicKey, ScriptVec, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput, VerifiableTransaction,
+        },
+    };
+    use kaspa_txscript::{caches::Cache, TxScriptEngine};
+    use rand::thread_rng;
+    use std::str::FromStr;
+
+    fn keypair() -> KeyPair {
+        KeyPair::new(secp256k1::SECP256K1, &mut thread_rng())
+    }
+
+    fn p2pk_script(keypair: &KeyPair) -> ScriptPublicKey {
+        let script = once(0x20).chain(keypair.x_only_public_key().0.serialize()).chain(once(0xac));
+        ScriptPublicKey::new(0, ScriptVec::from_iter(script))
+    }
+
+    #[test]
+    fn test_pskt_roles() {
+        let (cosigners, owner) = ([keypair(), keypair(), keypair()], keypair());
+        let multisig = MultiSigScript::try_new(cosigners.iter().map(|kp| kp.x_only_public_key().0.serialize()).collect(), 2).unwrap();
+
+        let prev_tx_id = TransactionId::from_str("880eb9819a31821d9d2399e2f35e2433b72637e393d71ecc9b8d0250f49153c3").unwrap();
+        let inputs = (0..2)
+            .map(|index| TransactionInput {
+                previous_outpoint: TransactionOutpoint { transaction_id: prev_tx_id, index },
+                signature_script: vec![],
+                sequence: 0,
+                sig_op_count: 1,
+            })
+            .collect();
+        let output = TransactionOutput { value: 3000, script_public_key: p2pk_script(&owner) };
+        let tx = Transaction::new(0, inputs, vec![output], 0, SubnetworkId::from_bytes([0; 20]), 0, vec![]);
+        let entries = vec![
+            UtxoEntry {
+                amount: 2000,
+                script_public_key: multisig.script_public_key().clone(),
+                block_daa_score: 0,
+                is_coinbase: false,
+            },
+            UtxoEntry { amount: 1500, script_public_key: p2pk_script(&owner), block_daa_score: 0, is_coinbase: false },
+        ];
+
+        // Creator and updater
+        let mut pskt = Pskt::try_new(SignableTransaction::with_entries(tx, entries)).unwrap();
+        assert!(matches!(pskt.set_redeem_script(1, multisig.redeem_script().to_vec()), Err(Error::PsktRedeemScriptMismatch(1))));
+        pskt.set_redeem_script(0, multisig.redeem_script().to_vec()).unwrap();
+
+        // Signers work on their own copies, which travel as JSON
+        let json = pskt.to_json().unwrap();
+        let mut copies = [Pskt::from_json(&json).unwrap(), Pskt::from_json(&json).unwrap()];
+        assert_eq!(copies[0], pskt);
+        assert_eq!(copies[0].sign(&cosigners[2]).unwrap(), 1);
+        assert_eq!(copies[1].sign(&cosigners[0]).unwrap(), 1);
+        assert_eq!(copies[1].sign(&owner).unwrap(), 1);
+        assert_eq!(pskt.clone().sign(&keypair()).unwrap(), 0);
+        assert!(matches!(copies[0].set_redeem_script(0, multisig.redeem_script().to_vec()), Err(Error::PsktAlreadySigned)));
+        assert!(matches!(copies[0].finalize(), Err(Error::MultisigNotEnoughSignatures(0, 1, 2))));
+
+        // Combiner and finalizer
+        let [first, second] = copies;
+        let combined = first.combine(Pskt::from_json(&second.to_json().unwrap()).unwrap()).unwrap();
+        assert_eq!(combined.partial_signatures.len(), 3);
+        let mut multisig_only = combined.clone();
+        multisig_only.partial_signatures.retain(|sig| sig.input_index == 0);
+        assert!(matches!(multisig_only.finalize(), Err(Error::PsktMissingSignature(1))));
+        let signed = combined.finalize().unwrap();
+
+        let signed = signed.as_verifiable();
+        let cache = Cache::new(10_000);
+        let mut reused_values = SigHashReusedValues::new();
+        for (index, (input, entry)) in signed.populated_inputs().enumerate() {
+            let mut engine = TxScriptEngine::from_transaction_input(&signed, input, index, entry, &mut reused_values, &cache).unwrap();
+            assert!(engine.execute().is_ok(), "input {index} should verify");
+        }
+
+        // A PSKT of another transaction cannot be combined
+        let mut other = pskt.clone();
+        other.tx.lock_time = 1;
+        assert!(matches!(pskt.combine(other), Err(Error::PsktMismatch)));
+    }
+}
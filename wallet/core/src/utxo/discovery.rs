@@ -0,0 +1,187 @@
+//!
+//! Gap-limit address discovery used when restoring an account: the addresses
+//! of each derivation chain are queried from the node in batches until
+//! `gap_limit` consecutive addresses following the last used one are found
+//! unused.
+//!
+//! An address is considered used if it received funds according to the
+//! transaction history of the account, if it holds UTXOs or if it is involved
+//! in a transaction of the mempool. The node is queried only if it runs
+//! with the UTXO index, otherwise the discovery relies on the transaction
+//! history alone.
+//!
+
+use crate::derivation::{AddressDerivationManagerTrait, AddressManager};
+use crate::imports::*;
+use crate::result::Result;
+use crate::storage::{Binding, TransactionRecordStore};
+use futures::future::join;
+use workflow_core::abortable::Abortable;
+
+/// Default number of consecutive unused addresses ending the discovery of a chain
+pub const DEFAULT_GAP_LIMIT: u32 = 20;
+/// Default number of addresses queried in a single RPC request
+pub const DEFAULT_DISCOVERY_BATCH_SIZE: u32 = 100;
+
+/// Used addresses of a single derivation chain
+#[derive(Debug, Clone, Default)]
+pub struct ChainDiscovery {
+    /// Used addresses with their derivation index, in the index order
+    pub used: Vec<(u32, Address)>,
+}
+
+impl ChainDiscovery {
+    /// Derivation index of the last used address
+    pub fn last_index(&self) -> Option<u32> {
+        self.used.last().map(|(index, _)| *index)
+    }
+
+    pub fn addresses(&self) -> impl Iterator<Item = &Address> {
+        self.used.iter().map(|(_, address)| address)
+    }
+}
+
+/// Used addresses of the receive and change chains of an account
+#[derive(Debug, Clone, Default)]
+pub struct AddressDiscovery {
+    pub receive: ChainDiscovery,
+    pub change: ChainDiscovery,
+    /// Whether the node was queried, which requires its UTXO index. If not,
+    /// the used addresses were discovered from the transaction history only
+    pub has_utxo_index: bool,
+}
+
+impl AddressDiscovery {
+    pub fn is_empty(&self) -> bool {
+        self.receive.used.is_empty() && self.change.used.is_empty()
+    }
+}
+
+pub struct Discovery {
+    rpc: Arc<DynRpcApi>,
+    gap_limit: u32,
+    batch_size: u32,
+    /// Addresses known to be used from the transaction history of the account
+    history: HashSet<Address>,
+}
+
+impl Discovery {
+    pub fn new(rpc: Arc<DynRpcApi>, gap_limit: Option<u32>, batch_size: Option<u32>) -> Self {
+        Self {
+            rpc,
+            gap_limit: gap_limit.unwrap_or(DEFAULT_GAP_LIMIT).max(1),
+            batch_size: batch_size.unwrap_or(DEFAULT_DISCOVERY_BATCH_SIZE).max(1),
+            history: HashSet::new(),
+        }
+    }
+
+    /// Considers used the addresses of `history`, see [`history_addresses`]
+    pub fn with_history(mut self, history: HashSet<Address>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Discovers the used addresses of both chains of `derivation`. The address
+    /// managers are not modified.
+    pub async fn discover(
+        &self,
+        derivation: &Arc<dyn AddressDerivationManagerTrait>,
+        abortable: Option<&Abortable>,
+    ) -> Result<AddressDiscovery> {
+        // Balances by addresses are only served by nodes running with the UTXO index
+        let has_utxo_index = self.rpc.get_info().await?.is_utxo_indexed;
+        let receive_address_manager = derivation.receive_address_manager();
+        let change_address_manager = derivation.change_address_manager();
+        let (receive, change) = join(
+            self.discover_chain(&receive_address_manager, has_utxo_index, abortable),
+            self.discover_chain(&change_address_manager, has_utxo_index, abortable),
+        )
+        .await;
+        Ok(AddressDiscovery { receive: receive?, change: change?, has_utxo_index })
+    }
+
+    pub async fn discover_chain(
+        &self,
+        address_manager: &Arc<AddressManager>,
+        query_node: bool,
+        abortable: Option<&Abortable>,
+    ) -> Result<ChainDiscovery> {
+        let mut discovery = ChainDiscovery::default();
+        let mut cursor: u32 = 0;
+
+        while !is_gap_reached(cursor, discovery.last_index(), self.gap_limit) {
+            if let Some(abortable) = abortable {
+                abortable.check()?;
+            }
+
+            let first = cursor;
+            cursor += self.batch_size;
+            let addresses = address_manager.get_range_with_args(first..cursor, false)?;
+
+            let mut used = HashSet::new();
+            if query_node {
+                let balances = self.rpc.get_balances_by_addresses(addresses.clone()).await?;
+                used.extend(balances.into_iter().filter(|entry| entry.balance.unwrap_or_default() > 0).map(|entry| entry.address));
+                let mempool = self.rpc.get_mempool_entries_by_addresses(addresses.clone(), true, false).await?;
+                used.extend(
+                    mempool
+                        .into_iter()
+                        .filter(|entry| !entry.sending.is_empty() || !entry.receiving.is_empty())
+                        .map(|entry| entry.address),
+                );
+            }
+
+            discovery.used.extend(
+                addresses
+                    .into_iter()
+                    .zip(first..cursor)
+                    .filter(|(address, _)| used.contains(address) || self.history.contains(address))
+                    .map(|(address, index)| (index, address)),
+            );
+            yield_executor().await;
+        }
+
+        Ok(discovery)
+    }
+}
+
+/// Returns the addresses which received funds according to the transaction
+/// history stored for `binding`. An address which received funds since spent
+/// holds no UTXOs, yet it is used and must not count towards the gap limit
+pub async fn history_addresses(
+    store: &Arc<dyn TransactionRecordStore>,
+    binding: &Binding,
+    network_id: &NetworkId,
+) -> Result<HashSet<Address>> {
+    let ids = store.transaction_id_iter(binding, network_id).await?.try_collect::<Vec<_>>().await?;
+    let ids = ids.into_iter().map(|id| *id).collect::<Vec<_>>();
+    let records = store.load_multiple(binding, network_id, &ids).await?;
+    Ok(records
+        .iter()
+        .flat_map(|record| record.transaction_data().utxo_entries())
+        .filter_map(|utxo_entry| utxo_entry.address.clone())
+        .collect())
+}
+
+/// Checks whether the addresses scanned up to `cursor` (excluded) end with at
+/// least `gap_limit` unused addresses
+fn is_gap_reached(cursor: u32, last_used_index: Option<u32>, gap_limit: u32) -> bool {
+    let first_unused = last_used_index.map(|index| index + 1).unwrap_or_default();
+    cursor.saturating_sub(first_unused) >= gap_limit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_gap_reached() {
+        assert!(!is_gap_reached(0, None, 20));
+        assert!(!is_gap_reached(19, None, 20));
+        assert!(is_gap_reached(20, None, 20));
+        assert!(!is_gap_reached(100, Some(85), 20));
+        assert!(is_gap_reached(100, Some(79), 20));
+        assert!(!is_gap_reached(100, Some(99), 1));
+        assert!(is_gap_reached(101, Some(99), 1));
+    }
+}
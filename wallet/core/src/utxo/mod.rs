@@ -1,5 +1,6 @@
 pub mod binding;
 pub mod context;
+pub mod discovery;
 pub mod iterator;
 pub mod pending;
 pub mod processor;
@@ -10,6 +11,7 @@ pub mod stream;
 
 pub use binding::UtxoContextBinding;
 pub use context::{UtxoContext, UtxoContextId};
+pub use discovery::{AddressDiscovery, ChainDiscovery, Discovery};
 pub use iterator::UtxoIterator;
 pub use pending::PendingUtxoEntryReference;
 pub use processor::UtxoProcessor;
@@ -24,13 +24,13 @@ pub fn js_sign_message(value: JsValue) -> Result<String, Error> {
     }
 }
 
-/// Verifies with a public key the signature of the given message
-/// @param {object} value - an object containing { message: String, signature: String, publicKey: String|PublicKey }
-/// @returns {bool} true if the signature can be verified with the given public key and message, false otherwise
+/// Verifies with a public key, or with the key of a pay-to-pubkey address, the signature of the given message
+/// @param {object} value - an object containing { message: String, signature: String, publicKey: String|PublicKey } or
+/// { message: String, signature: String, address: String|Address }
+/// @returns {bool} true if the signature can be verified with the given key and message, false otherwise
 #[wasm_bindgen(js_name = verifyMessage, skip_jsdoc)]
 pub fn js_verify_message(value: JsValue) -> Result<bool, Error> {
     if let Some(object) = Object::try_from(&value) {
-        let public_key = object.get::<PublicKey>("publicKey")?;
         let raw_msg = object.get_string("message")?;
         let signature = object.get_string("signature")?;
 
@@ -38,6 +38,10 @@ pub fn js_verify_message(value: JsValue) -> Result<bool, Error> {
         let mut signature_bytes = [0u8; 64];
         faster_hex::hex_decode(signature.as_bytes(), &mut signature_bytes)?;
 
+        if let Some(address) = object.try_get::<Address>("address")? {
+            return Ok(verify_message_with_address(&pm, &signature_bytes.to_vec(), &address).is_ok());
+        }
+        let public_key = object.get::<PublicKey>("publicKey")?;
         Ok(verify_message(&pm, &signature_bytes.to_vec(), &public_key.into()).is_ok())
     } else {
         Err(Error::custom("Failed to parse input"))
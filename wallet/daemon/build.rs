@@ -0,0 +1,12 @@
+fn main() {
+    let proto_file = "./proto/kaspawalletd.proto";
+
+    tonic_build::configure()
+        .build_server(true)
+        .build_client(true)
+        .compile(&[proto_file], &["./proto"])
+        .unwrap_or_else(|e| panic!("protobuf compile error: {e}"));
+
+    // recompile protobufs only if the proto file changes.
+    println!("cargo:rerun-if-changed={proto_file}");
+}
@@ -0,0 +1,117 @@
+use crate::error::{Error, Result};
+use clap::{arg, Command};
+use kaspa_consensus_core::network::NetworkId;
+use kaspa_core::kaspad_env::version;
+use kaspa_wallet_core::secret::Secret;
+use std::{ffi::OsString, net::SocketAddr, path::PathBuf, str::FromStr};
+
+/// The environment variable holding the wallet password, unless a password file is specified
+pub const PASSWORD_ENV_VAR: &str = "KASPA_WALLET_PASSWORD";
+
+pub struct Args {
+    pub network_id: NetworkId,
+    pub rpc_server: Option<String>,
+    pub listen: SocketAddr,
+    pub wallet: Option<String>,
+    pub account: Option<String>,
+    pub password_file: Option<PathBuf>,
+    pub log_level: String,
+}
+
+impl Args {
+    pub fn parse() -> Result<Self> {
+        Self::parse_from(std::env::args_os())
+    }
+
+    fn parse_from<I, T>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let m = cli().get_matches_from(args);
+        Ok(Args {
+            network_id: NetworkId::from_str(m.get_one::<String>("network").unwrap())?,
+            rpc_server: m.get_one::<String>("rpcserver").cloned(),
+            listen: m
+                .get_one::<String>("listen")
+                .unwrap()
+                .parse()
+                .map_err(|err| Error::Custom(format!("invalid listen address: {err}")))?,
+            wallet: m.get_one::<String>("wallet").cloned(),
+            account: m.get_one::<String>("account").cloned(),
+            password_file: m.get_one::<String>("password-file").map(PathBuf::from),
+            log_level: m.get_one::<String>("loglevel").cloned().unwrap(),
+        })
+    }
+
+    /// Reads the wallet password from the password file, or else from the [`PASSWORD_ENV_VAR`] environment variable
+    pub fn wallet_secret(&self) -> Result<Secret> {
+        let password = match &self.password_file {
+            Some(path) => std::fs::read_to_string(path)?,
+            None => std::env::var(PASSWORD_ENV_VAR).map_err(|_| Error::MissingPassword)?,
+        };
+        Ok(Secret::new(password.trim_end_matches(['\r', '\n']).as_bytes().to_vec()))
+    }
+}
+
+pub fn cli() -> Command {
+    Command::new("kaspawalletd")
+        .about(format!("{} (kaspawalletd) v{}", env!("CARGO_PKG_DESCRIPTION"), version()))
+        .version(env!("CARGO_PKG_VERSION"))
+        .arg(arg!(--network <NETWORK> "The network of the wallet, e.g., mainnet or testnet-10.").default_value("mainnet"))
+        .arg(arg!(--rpcserver <URL> "The wRPC (Borsh) URL of the node. Defaults to the local node of the network."))
+        .arg(arg!(--listen <ADDRESS> "Interface:port to listen for gRPC requests on.").default_value("127.0.0.1:8082"))
+        .arg(arg!(--wallet <NAME> "The name of the wallet file to open. Defaults to the wallet selected in the wallet settings."))
+        .arg(arg!(--account <ACCOUNT> "Name or id prefix of the account to operate on. Required if the wallet has several accounts."))
+        .arg(arg!(--"password-file" <PATH> "A file holding the wallet password. Defaults to the KASPA_WALLET_PASSWORD environment variable."))
+        .arg(arg!(--loglevel <LEVEL> "Logging level for all subsystems {off, error, warn, info, debug, trace}.").default_value("info"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_parse_args() {
+        let args = Args::parse_from(["kaspawalletd"]).unwrap();
+        assert_eq!(args.network_id, NetworkId::from_str("mainnet").unwrap());
+        assert_eq!(args.listen, "127.0.0.1:8082".parse().unwrap());
+        assert!(args.rpc_server.is_none() && args.wallet.is_none() && args.account.is_none() && args.password_file.is_none());
+
+        let args = Args::parse_from([
+            "kaspawalletd",
+            "--network",
+            "testnet-10",
+            "--listen",
+            "0.0.0.0:9000",
+            "--wallet",
+            "kaspa",
+            "--account",
+            "main",
+            "--password-file",
+            "password.txt",
+        ])
+        .unwrap();
+        assert_eq!(args.network_id, NetworkId::from_str("testnet-10").unwrap());
+        assert_eq!(args.listen, "0.0.0.0:9000".parse().unwrap());
+        assert_eq!(args.wallet.as_deref(), Some("kaspa"));
+        assert_eq!(args.account.as_deref(), Some("main"));
+        assert_eq!(args.password_file, Some(PathBuf::from("password.txt")));
+
+        assert!(Args::parse_from(["kaspawalletd", "--listen", "localhost"]).is_err());
+        assert!(Args::parse_from(["kaspawalletd", "--network", "moonnet"]).is_err());
+    }
+
+    #[test]
+    fn test_wallet_secret_from_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"pass word\r\n").unwrap();
+        let args = Args::parse_from(["kaspawalletd", "--password-file", file.path().to_str().unwrap()]).unwrap();
+        // Only the trailing line break is dropped
+        assert_eq!(args.wallet_secret().unwrap().as_ref(), b"pass word");
+
+        let args = Args::parse_from(["kaspawalletd", "--password-file", "/nonexistent/password.txt"]).unwrap();
+        assert!(matches!(args.wallet_secret(), Err(Error::Io(_))));
+    }
+}
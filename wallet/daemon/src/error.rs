@@ -0,0 +1,62 @@
+use kaspa_consensus_core::network::NetworkIdError;
+use thiserror::Error;
+use tonic::Status;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("{0}")]
+    Custom(String),
+
+    #[error("no wallet password, use --password-file or set the {} environment variable", crate::args::PASSWORD_ENV_VAR)]
+    MissingPassword,
+
+    #[error("the wallet has {0} accounts, select one with --account")]
+    AccountSelection(usize),
+
+    #[error("no account matches {0}")]
+    AccountNotFound(String),
+
+    #[error(transparent)]
+    Wallet(#[from] kaspa_wallet_core::error::Error),
+
+    #[error(transparent)]
+    Rpc(#[from] kaspa_wrpc_client::error::Error),
+
+    #[error(transparent)]
+    NetworkId(#[from] NetworkIdError),
+
+    #[error(transparent)]
+    Transport(#[from] tonic::transport::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl From<Error> for Status {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Wallet(kaspa_wallet_core::error::Error::Chacha20poly1305(_)) => Status::unauthenticated(err.to_string()),
+            Error::Wallet(kaspa_wallet_core::error::Error::NotConnected) => Status::unavailable(err.to_string()),
+            Error::Wallet(kaspa_wallet_core::error::Error::WatchOnlyAccount) => Status::failed_precondition(err.to_string()),
+            Error::Wallet(kaspa_wallet_core::error::Error::BIP32Error(_)) => Status::invalid_argument(err.to_string()),
+            _ => Status::internal(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::Code;
+
+    #[test]
+    fn test_status_codes() {
+        let code = |err: Error| Status::from(err).code();
+        assert_eq!(code(kaspa_wallet_core::error::Error::NotConnected.into()), Code::Unavailable);
+        assert_eq!(code(kaspa_wallet_core::error::Error::WatchOnlyAccount.into()), Code::FailedPrecondition);
+        assert_eq!(code(kaspa_wallet_core::error::Error::Custom("failure".to_string()).into()), Code::Internal);
+        assert_eq!(code(Error::AccountNotFound("main".to_string())), Code::Internal);
+    }
+}
@@ -0,0 +1,89 @@
+//!
+//! `kaspawalletd` is a wallet daemon serving a gRPC API over an account of a local wallet.
+//!
+//! The daemon is a thin layer over the wallet core: the private keys remain encrypted at rest (Argon2 and
+//! XChaCha20-Poly1305) in the wallet file and are only decrypted to sign a transaction, with the wallet password
+//! supplied in the request. Account addresses are tracked via UtxosChanged subscriptions of the node, which
+//! maintain the balance and the transaction history of the account.
+//!
+
+use args::Args;
+use error::{Error, Result};
+use kaspa_core::{info, log::init_logger};
+use kaspa_wallet_core::{runtime::Wallet, ConnectOptions, ConnectStrategy};
+use service::{KaspaWalletdServer, WalletService};
+use std::sync::Arc;
+use tokio::signal::ctrl_c;
+use tonic::transport::Server;
+
+mod args;
+mod error;
+mod service;
+
+#[tokio::main]
+async fn main() {
+    let args = match Args::parse() {
+        Ok(args) => args,
+        Err(err) => {
+            println!("{err}");
+            std::process::exit(1);
+        }
+    };
+    init_logger(None, &args.log_level);
+
+    if let Err(err) = run(args).await {
+        kaspa_core::error!("{err}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: Args) -> Result<()> {
+    let wallet_secret = args.wallet_secret()?;
+    let wallet = Arc::new(Wallet::try_new(Wallet::local_store()?, Some(args.network_id))?);
+    wallet.start().await?;
+
+    let wrpc_client = wallet.wrpc_client().ok_or(Error::Custom("the wallet has no wRPC client".to_string()))?;
+    let url = args.rpc_server.clone().unwrap_or("127.0.0.1".to_string());
+    let url = wrpc_client.parse_url_with_network_type(Some(url), args.network_id.into())?;
+    info!("Connecting to the node at {}", url.as_deref().unwrap_or_default());
+    let options = ConnectOptions { block_async_connect: false, strategy: ConnectStrategy::Retry, url, ..Default::default() };
+    wrpc_client.connect(options).await?;
+
+    wallet.load_and_activate(wallet_secret, args.wallet.clone()).await?;
+    select_account(&wallet, args.account.as_deref()).await?;
+    info!("Serving account {} of the wallet", wallet.account()?.name_with_id());
+
+    info!("gRPC server listening on {}", args.listen);
+    let served = Server::builder()
+        .add_service(KaspaWalletdServer::new(WalletService::new(wallet.clone())))
+        .serve_with_shutdown(args.listen, async {
+            ctrl_c().await.ok();
+        })
+        .await;
+
+    info!("Shutting down the wallet");
+    wallet.close().await?;
+    wallet.stop().await?;
+    Ok(served?)
+}
+
+async fn select_account(wallet: &Arc<Wallet>, account: Option<&str>) -> Result<()> {
+    match account {
+        Some(pat) => {
+            let accounts = wallet.find_accounts_by_name_or_id(pat).await?;
+            match accounts.as_slice() {
+                [account] => wallet.select(Some(account)).await?,
+                [] => return Err(Error::AccountNotFound(pat.to_string())),
+                _ => return Err(Error::AccountSelection(accounts.len())),
+            }
+        }
+        None => {
+            let count = wallet.active_accounts().len();
+            if count != 1 {
+                return Err(Error::AccountSelection(count));
+            }
+            wallet.autoselect_default_account_if_single().await?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use crate::error::Error;
+use futures::TryStreamExt;
+use kaspa_wallet_core::{
+    error::Error as WalletError,
+    runtime::{Account, AccountKind, Wallet},
+    secret::Secret,
+    storage::{transaction::TransactionData, Binding, TransactionRecord as StoredTransactionRecord},
+    tx::{PaymentOutput, PaymentOutputs},
+    Address,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use workflow_core::abortable::Abortable;
+
+tonic::include_proto!("kaspawalletd");
+
+pub use kaspa_walletd_server::KaspaWalletdServer;
+
+type Result<T> = std::result::Result<Response<T>, Status>;
+
+/// Serves the gRPC API of the daemon over the selected account of a loaded wallet
+pub struct WalletService {
+    wallet: Arc<Wallet>,
+}
+
+impl WalletService {
+    pub fn new(wallet: Arc<Wallet>) -> Self {
+        Self { wallet }
+    }
+
+    fn account(&self) -> std::result::Result<Arc<dyn Account>, Status> {
+        self.wallet.account().map_err(|err| Error::from(err).into())
+    }
+}
+
+/// Returns the value a transaction moved in or out of the account, and the fees the account paid for it
+fn value_and_fees(data: &TransactionData) -> (u64, u64) {
+    match data {
+        TransactionData::Reorg { aggregate_input_value, .. }
+        | TransactionData::Incoming { aggregate_input_value, .. }
+        | TransactionData::External { aggregate_input_value, .. } => (*aggregate_input_value, 0),
+        TransactionData::Outgoing { fees, payment_value, aggregate_output_value, change_value, .. } => {
+            // A sweep into the wallet itself has no payment value, in which case only the fees are spent
+            (payment_value.unwrap_or(aggregate_output_value.saturating_sub(*change_value)), *fees)
+        }
+    }
+}
+
+impl From<&StoredTransactionRecord> for TransactionRecord {
+    fn from(record: &StoredTransactionRecord) -> Self {
+        let (value, fees) = value_and_fees(record.transaction_data());
+        Self {
+            transaction_id: record.id().to_string(),
+            r#type: record.transaction_type().to_string(),
+            value,
+            fees,
+            block_daa_score: record.block_daa_score(),
+            unix_time: record.unixtime().unwrap_or_default(),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl kaspa_walletd_server::KaspaWalletd for WalletService {
+    async fn get_status(&self, _request: Request<GetStatusRequest>) -> Result<GetStatusResponse> {
+        let account = self.wallet.account().ok();
+        let is_watch_only = account.as_ref().is_some_and(|account| account.account_kind() == AccountKind::WatchOnly);
+        let account = account.map(|account| account.name_with_id()).unwrap_or_default();
+        Ok(Response::new(GetStatusResponse {
+            network: self.wallet.network_id().map_err(Error::from)?.to_string(),
+            account,
+            is_connected: self.wallet.is_connected(),
+            is_synced: self.wallet.is_synced(),
+            daa_score: self.wallet.current_daa_score().unwrap_or_default(),
+            is_watch_only,
+        }))
+    }
+
+    async fn get_balance(&self, _request: Request<GetBalanceRequest>) -> Result<GetBalanceResponse> {
+        let balance = self.account()?.balance().unwrap_or_default();
+        Ok(Response::new(GetBalanceResponse { mature: balance.mature, pending: balance.pending }))
+    }
+
+    async fn get_receive_address(&self, _request: Request<GetReceiveAddressRequest>) -> Result<GetReceiveAddressResponse> {
+        let address = self.account()?.receive_address().map_err(Error::from)?;
+        Ok(Response::new(GetReceiveAddressResponse { address: address.to_string() }))
+    }
+
+    async fn new_address(&self, _request: Request<NewAddressRequest>) -> Result<NewAddressResponse> {
+        let account = self.account()?.as_derivation_capable().map_err(Error::from)?;
+        let address = account.new_receive_address().await.map_err(Error::from)?;
+        Ok(Response::new(NewAddressResponse { address: address.to_string() }))
+    }
+
+    async fn send(&self, request: Request<SendRequest>) -> Result<SendResponse> {
+        let request = request.into_inner();
+        let address = Address::try_from(request.to_address.as_str()).map_err(|err| Status::invalid_argument(err.to_string()))?;
+        if request.amount == 0 {
+            return Err(Status::invalid_argument("the amount must be positive"));
+        }
+        let wallet_secret = Secret::new(request.password.into_bytes());
+        let payment_secret = (!request.payment_password.is_empty()).then(|| Secret::new(request.payment_password.into_bytes()));
+        let outputs = PaymentOutputs { outputs: vec![PaymentOutput::new(address, request.amount)] };
+
+        let abortable = Abortable::default();
+        let (summary, ids) = self
+            .account()?
+            .send(outputs.into(), request.priority_fee.into(), None, wallet_secret, payment_secret, &abortable, None)
+            .await
+            .map_err(Error::from)?;
+        Ok(Response::new(SendResponse {
+            transaction_ids: ids.iter().map(|id| id.to_string()).collect(),
+            fees: summary.aggregated_fees,
+        }))
+    }
+
+    async fn get_history(&self, request: Request<GetHistoryRequest>) -> Result<GetHistoryResponse> {
+        let limit = request.into_inner().limit as usize;
+        let account = self.account()?;
+        let binding = Binding::from(&account);
+        let network_id = self.wallet.network_id().map_err(Error::from)?;
+        let store = self.wallet.store().as_transaction_record_store().map_err(Error::from)?;
+
+        let ids = match store.transaction_id_iter(&binding, &network_id).await {
+            Ok(ids) => ids.try_collect::<Vec<_>>().await.map_err(Error::from)?,
+            Err(WalletError::NoRecordsFound) => vec![],
+            Err(err) => return Err(Error::from(err).into()),
+        };
+        // Records are kept in the order of their arrival, the most recent last
+        let skip = if limit == 0 { 0 } else { ids.len().saturating_sub(limit) };
+        let mut records = Vec::with_capacity(ids.len() - skip);
+        for id in ids.iter().skip(skip) {
+            let record = store.load_single(&binding, &network_id, id).await.map_err(Error::from)?;
+            records.push(record.as_ref().into());
+        }
+        Ok(Response::new(GetHistoryResponse { records }))
+    }
+
+    async fn import_watch_only_account(
+        &self,
+        request: Request<ImportWatchOnlyAccountRequest>,
+    ) -> Result<ImportWatchOnlyAccountResponse> {
+        let request = request.into_inner();
+        let wallet_secret = Secret::new(request.password.into_bytes());
+        let name = (!request.name.is_empty()).then_some(request.name);
+        let account = self.wallet.import_watch_only_account(wallet_secret, request.xpub, name).await.map_err(Error::from)?;
+        Ok(Response::new(ImportWatchOnlyAccountResponse {
+            account_id: account.id().to_string(),
+            receive_address: account.receive_address().map_err(Error::from)?.to_string(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::{subnets::SUBNETWORK_ID_NATIVE, tx::Transaction};
+
+    fn outgoing(payment_value: Option<u64>) -> TransactionData {
+        TransactionData::Outgoing {
+            is_final: true,
+            fees: 2_000,
+            aggregate_input_value: 100_000,
+            aggregate_output_value: 98_000,
+            transaction: Transaction::new(0, vec![], vec![], 0, SUBNETWORK_ID_NATIVE, 0, vec![]),
+            payment_value,
+            change_value: 38_000,
+        }
+    }
+
+    #[test]
+    fn test_value_and_fees() {
+        let incoming = TransactionData::Incoming { utxo_entries: vec![], aggregate_input_value: 50_000 };
+        assert_eq!(value_and_fees(&incoming), (50_000, 0));
+        let reorg = TransactionData::Reorg { utxo_entries: vec![], aggregate_input_value: 50_000 };
+        assert_eq!(value_and_fees(&reorg), (50_000, 0));
+
+        // A payment reports its payment value, a sweep the outputs left after the change
+        assert_eq!(value_and_fees(&outgoing(Some(60_000))), (60_000, 2_000));
+        assert_eq!(value_and_fees(&outgoing(None)), (60_000, 2_000));
+    }
+}